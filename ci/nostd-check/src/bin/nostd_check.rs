@@ -13,12 +13,18 @@
 //
 
 #![no_std]
+extern crate alloc;
 
+use alloc::vec::Vec;
 use core::panic::PanicInfo;
 use getrandom::{register_custom_getrandom, Error};
 use linked_list_allocator::LockedHeap;
-#[allow(unused_imports)]
-use {zenoh_buffers, zenoh_codec, zenoh_protocol};
+use zenoh_buffers::{reader::HasReader, writer::HasWriter, ZBuf};
+use zenoh_codec::{RCodec, WCodec, Zenoh080};
+use zenoh_protocol::{
+    core::{Timestamp, TimestampId, NTP64},
+    transport::{KeepAlive, TransportMessage},
+};
 
 #[panic_handler]
 fn dummy_panic_handler(_: &PanicInfo) -> ! {
@@ -32,6 +38,33 @@ fn dummy_get_rand(_: &mut [u8]) -> Result<(), Error> {
     Ok(())
 }
 
+// Make sure the wire encoders and decoders are usable without std
+fn codec() {
+    let codec = Zenoh080::new();
+
+    let msg: TransportMessage = KeepAlive::default().into();
+    let mut buffer = Vec::new();
+    let mut writer = buffer.writer();
+    codec.write(&mut writer, &msg).unwrap();
+    let mut reader = buffer.reader();
+    let _: TransportMessage = codec.read(&mut reader).unwrap();
+
+    let mut zbuf = ZBuf::empty();
+    let mut writer = zbuf.writer();
+    codec.write(&mut writer, &msg).unwrap();
+    let mut reader = zbuf.reader();
+    let _: TransportMessage = codec.read(&mut reader).unwrap();
+
+    // The timestamps are plain types without the uhlc feature
+    let timestamp = Timestamp::new(NTP64(42), TimestampId::try_from([1_u8]).unwrap());
+    let mut buffer = Vec::new();
+    let mut writer = buffer.writer();
+    codec.write(&mut writer, &timestamp).unwrap();
+    let mut reader = buffer.reader();
+    let _: Timestamp = codec.read(&mut reader).unwrap();
+}
+
 fn main() {
     register_custom_getrandom!(dummy_get_rand);
+    codec();
 }
@@ -19,6 +19,8 @@
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 //!
 //! Provide different buffer implementations used for serialization and deserialization.
+//!
+//! This crate supports `no_std` targets providing an allocator when its default `std` feature is disabled.
 #![cfg_attr(not(feature = "std"), no_std)]
 extern crate alloc;
 
@@ -27,10 +29,12 @@ mod slice;
 pub mod vec;
 mod zbuf;
 mod zslice;
+mod zstr;
 
 pub use bbuf::*;
 pub use zbuf::*;
 pub use zslice::*;
+pub use zstr::*;
 
 // SAFETY: this crate operates on eventually initialized slices for read and write. Because of that, internal buffers
 //         implementation keeps track of various slices indexes. Boundaries checks are performed by individual
@@ -21,15 +21,21 @@ use crate::{
 };
 use alloc::{sync::Arc, vec::Vec};
 use core::{cmp, iter, mem, num::NonZeroUsize, ops::RangeBounds, ptr};
+#[cfg(feature = "single-or-vec")]
 use zenoh_collections::SingleOrVec;
 
 fn get_mut_unchecked<T>(arc: &mut Arc<T>) -> &mut T {
     unsafe { &mut (*(Arc::as_ptr(arc) as *mut T)) }
 }
 
+#[cfg(feature = "single-or-vec")]
+type ZSlices = SingleOrVec<ZSlice>;
+#[cfg(not(feature = "single-or-vec"))]
+type ZSlices = Vec<ZSlice>;
+
 #[derive(Debug, Clone, Default, Eq)]
 pub struct ZBuf {
-    slices: SingleOrVec<ZSlice>,
+    slices: ZSlices,
 }
 
 impl ZBuf {
@@ -43,11 +49,11 @@ impl ZBuf {
     }
 
     pub fn zslices(&self) -> impl Iterator<Item = &ZSlice> + '_ {
-        self.slices.as_ref().iter()
+        self.slices.as_slice().iter()
     }
 
     pub fn zslices_mut(&mut self) -> impl Iterator<Item = &mut ZSlice> + '_ {
-        self.slices.as_mut().iter_mut()
+        self.slices.as_mut_slice().iter_mut()
     }
 
     pub fn push_zslice(&mut self, zslice: ZSlice) {
@@ -79,7 +85,7 @@ impl ZBuf {
         let mut start_idx_in_start_slice = 0;
         let mut end_slice_idx = 0;
         let mut end_idx_in_end_slice = 0;
-        for (i, slice) in self.slices.as_mut().iter_mut().enumerate() {
+        for (i, slice) in self.slices.as_mut_slice().iter_mut().enumerate() {
             if slice.len() > start {
                 start_slice_idx = i;
                 start_idx_in_start_slice = start;
@@ -92,10 +98,10 @@ impl ZBuf {
             start -= slice.len();
             end -= slice.len();
         }
-        let start_slice = &mut self.slices.as_mut()[start_slice_idx];
+        let start_slice = &mut self.slices.as_mut_slice()[start_slice_idx];
         start_slice.end = start_slice.start + start_idx_in_start_slice;
         let drain_start = start_slice_idx + (start_slice.start < start_slice.end) as usize;
-        let end_slice = &mut self.slices.as_mut()[end_slice_idx];
+        let end_slice = &mut self.slices.as_mut_slice()[end_slice_idx];
         end_slice.start += end_idx_in_end_slice;
         let drain_end = end_slice_idx + (end_slice.start >= end_slice.end) as usize;
         self.slices.drain(drain_start..drain_end);
@@ -106,7 +112,7 @@ impl ZBuf {
         }
         let old_at = at;
         let mut slice_index = usize::MAX;
-        for (i, slice) in self.slices.as_ref().iter().enumerate() {
+        for (i, slice) in self.slices.as_slice().iter().enumerate() {
             if at < slice.len() {
                 slice_index = i;
                 break;
@@ -121,7 +127,7 @@ impl ZBuf {
             }
         }
         if at != 0 {
-            let split = &self.slices.as_ref()[slice_index];
+            let split = &self.slices.as_slice()[slice_index];
             let (l, r) = (
                 split.subslice(0, at).unwrap(),
                 split.subslice(at, split.len()).unwrap(),
@@ -141,7 +147,7 @@ impl Buffer for ZBuf {
     #[inline(always)]
     fn len(&self) -> usize {
         self.slices
-            .as_ref()
+            .as_slice()
             .iter()
             .fold(0, |len, slice| len + slice.len())
     }
@@ -152,7 +158,7 @@ impl SplitBuffer for ZBuf {
     type Slices<'a> = iter::Map<core::slice::Iter<'a, ZSlice>, fn(&'a ZSlice) -> &'a [u8]>;
 
     fn slices(&self) -> Self::Slices<'_> {
-        self.slices.as_ref().iter().map(ZSlice::as_slice)
+        self.slices.as_slice().iter().map(ZSlice::as_slice)
     }
 }
 
@@ -287,7 +293,7 @@ impl<'a> Reader for ZBufReader<'a> {
 
     fn remaining(&self) -> usize {
         // SAFETY: self.cursor.slice validity is ensured by the reader
-        let s = crate::unsafe_slice!(self.inner.slices.as_ref(), self.cursor.slice..);
+        let s = crate::unsafe_slice!(self.inner.slices.as_slice(), self.cursor.slice..);
         s.iter().fold(0, |acc, it| acc + it.len()) - self.cursor.byte
     }
 
@@ -410,8 +416,10 @@ impl Iterator for ZBufSliceIterator<'_, '_> {
         }
 
         // SAFETY: self.reader.cursor.slice is ensured by the reader.
-        let slice =
-            crate::unsafe_slice!(self.reader.inner.slices.as_ref(), self.reader.cursor.slice);
+        let slice = crate::unsafe_slice!(
+            self.reader.inner.slices.as_slice(),
+            self.reader.cursor.slice
+        );
         let start = self.reader.cursor.byte;
         // SAFETY: self.reader.cursor.byte is ensured by the reader.
         let current = crate::unsafe_slice!(slice, start..);
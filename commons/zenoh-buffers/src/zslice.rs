@@ -32,6 +32,11 @@ pub trait ZSliceBuffer: Send + Sync + fmt::Debug {
     fn as_slice(&self) -> &[u8];
     fn as_mut_slice(&mut self) -> &mut [u8];
     fn as_any(&self) -> &dyn Any;
+    /// Whether the content of the buffer may change while it is shared, e.g. shared memory
+    /// written by other processes.
+    fn is_volatile(&self) -> bool {
+        false
+    }
 }
 
 impl ZSliceBuffer for Vec<u8> {
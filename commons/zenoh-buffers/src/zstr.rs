@@ -0,0 +1,224 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::ZSlice;
+use alloc::{string::String, vec::Vec};
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    str::{self, Utf8Error},
+};
+
+/*************************************/
+/*               ZSTR                */
+/*************************************/
+/// A UTF-8 string backed by a [`ZSlice`].
+///
+/// When decoded from a [`ZSlice`] reader, a [`ZStr`] points directly into the
+/// receive buffer instead of owning a copy of its bytes. The underlying buffer
+/// is kept alive by reference counting as long as the [`ZStr`] exists.
+///
+/// Only the query parameters are decoded as [`ZStr`]. Key expression and encoding
+/// suffixes are still copied: they are short and are kept by the routing tables
+/// well beyond the lifetime of the receive buffer.
+#[derive(Clone)]
+pub struct ZStr(ZSlice);
+
+impl ZStr {
+    /// Build a [`ZStr`] from a [`ZSlice`], validating that its content is UTF-8.
+    ///
+    /// The content of a [volatile](crate::ZSliceBuffer::is_volatile) buffer, e.g. shared memory,
+    /// is copied before being validated, since it could be altered afterwards.
+    pub fn new(slice: ZSlice) -> Result<Self, Utf8Error> {
+        let slice = if slice.buf.is_volatile() {
+            slice.as_slice().to_vec().into()
+        } else {
+            slice
+        };
+        str::from_utf8(slice.as_slice())?;
+        Ok(Self(slice))
+    }
+
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the content is validated at construction and is not volatile, nor can it be
+        //         mutated while shared by the ZStr.
+        unsafe { str::from_utf8_unchecked(self.0.as_slice()) }
+    }
+
+    pub fn as_zslice(&self) -> &ZSlice {
+        &self.0
+    }
+
+    pub fn into_zslice(self) -> ZSlice {
+        self.0
+    }
+
+    #[cfg(feature = "test")]
+    pub fn rand(len: usize) -> Self {
+        use rand::{distributions::Alphanumeric, Rng};
+
+        let rng = rand::thread_rng();
+        rng.sample_iter(Alphanumeric)
+            .take(len)
+            .map(char::from)
+            .collect::<String>()
+            .into()
+    }
+}
+
+impl Default for ZStr {
+    fn default() -> Self {
+        Self(Vec::new().into())
+    }
+}
+
+impl Deref for ZStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ZStr {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl Borrow<str> for ZStr {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl PartialEq for ZStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ZStr {}
+
+impl PartialEq<str> for ZStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ZStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for ZStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Display for ZStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for ZStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+// From impls
+impl From<String> for ZStr {
+    fn from(s: String) -> Self {
+        Self(Vec::from(s).into())
+    }
+}
+
+impl From<&str> for ZStr {
+    fn from(s: &str) -> Self {
+        Self(s.as_bytes().to_vec().into())
+    }
+}
+
+impl From<ZStr> for String {
+    fn from(s: ZStr) -> Self {
+        s.as_str().into()
+    }
+}
+
+impl From<ZStr> for ZSlice {
+    fn from(s: ZStr) -> Self {
+        s.0
+    }
+}
+
+impl TryFrom<ZSlice> for ZStr {
+    type Error = Utf8Error;
+
+    fn try_from(slice: ZSlice) -> Result<Self, Self::Error> {
+        Self::new(slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZSliceBuffer;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn zstr() {
+        let zslice: ZSlice = b"zenoh/zstr".to_vec().into();
+        let zstr = ZStr::new(zslice.clone()).unwrap();
+        assert_eq!(zstr, "zenoh/zstr");
+        assert_eq!(zstr.as_ptr(), zslice.as_slice().as_ptr());
+
+        let invalid: ZSlice = [0xff, 0xfe].to_vec().into();
+        assert!(ZStr::new(invalid).is_err());
+    }
+
+    #[test]
+    fn zstr_volatile() {
+        #[derive(Debug)]
+        struct Volatile(Vec<u8>);
+
+        impl ZSliceBuffer for Volatile {
+            fn as_slice(&self) -> &[u8] {
+                &self.0
+            }
+            fn as_mut_slice(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+            fn as_any(&self) -> &dyn core::any::Any {
+                self
+            }
+            fn is_volatile(&self) -> bool {
+                true
+            }
+        }
+
+        // The content of a volatile buffer is copied rather than referenced
+        let zslice: ZSlice = Arc::new(Volatile(b"zenoh/zstr".to_vec())).into();
+        let zstr = ZStr::new(zslice.clone()).unwrap();
+        assert_eq!(zstr, "zenoh/zstr");
+        assert_ne!(zstr.as_ptr(), zslice.as_slice().as_ptr());
+
+        let invalid: ZSlice = Arc::new(Volatile([0xff, 0xfe].to_vec())).into();
+        assert!(ZStr::new(invalid).is_err());
+    }
+}
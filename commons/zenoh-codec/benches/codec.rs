@@ -89,9 +89,13 @@ fn criterion_benchmark(c: &mut Criterion) {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 8]),
         }),
@@ -135,9 +139,13 @@ fn criterion_benchmark(c: &mut Criterion) {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 8]),
         }),
@@ -176,9 +184,13 @@ fn criterion_benchmark(c: &mut Criterion) {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 8]),
         }),
@@ -217,9 +229,13 @@ fn criterion_benchmark(c: &mut Criterion) {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 1_000_000]),
         }),
@@ -245,9 +261,13 @@ fn criterion_benchmark(c: &mut Criterion) {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 1_000_000]),
         }),
@@ -284,9 +304,13 @@ fn criterion_benchmark(c: &mut Criterion) {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::from(vec![0u8; 1_000_000]),
         }),
@@ -48,6 +48,22 @@ where
     read_inner(&mut *reader, s, header)
 }
 
+/// Reads an unknown extension of a zenoh message, accepting the mandatory user-defined
+/// extensions: they are understood by the applications, not by the infrastructure.
+#[cold]
+#[inline(never)]
+pub fn read_user<R>(reader: &mut R, s: &str, header: u8) -> Result<(ZExtUnknown, bool), DidntRead>
+where
+    R: Reader,
+{
+    if iext::is_user(header) {
+        let codec = Zenoh080Header::new(header);
+        codec.read(&mut *reader)
+    } else {
+        read_inner(&mut *reader, s, header)
+    }
+}
+
 fn skip_inner<R>(reader: &mut R, s: &str, header: u8) -> Result<bool, DidntRead>
 where
     R: Reader,
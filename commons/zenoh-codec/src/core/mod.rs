@@ -22,6 +22,7 @@ mod zbuf;
 mod zenohid;
 mod zint;
 mod zslice;
+mod zstr;
 
 use crate::{LCodec, RCodec, WCodec, Zenoh080, Zenoh080Bounded};
 use alloc::{string::String, vec::Vec};
@@ -17,7 +17,7 @@ use zenoh_buffers::{
     reader::{DidntRead, Reader},
     writer::{DidntWrite, Writer},
 };
-use zenoh_protocol::core::{Timestamp, ZenohId};
+use zenoh_protocol::core::{Timestamp, TimestampId, ZenohId, NTP64};
 
 impl LCodec<&Timestamp> for Zenoh080 {
     fn w_len(self, x: &Timestamp) -> usize {
@@ -48,14 +48,14 @@ where
     fn read(self, reader: &mut R) -> Result<Timestamp, Self::Error> {
         let time: u64 = self.read(&mut *reader)?;
         let size: usize = self.read(&mut *reader)?;
-        if size > (uhlc::ID::MAX_SIZE) {
+        if size > (TimestampId::MAX_SIZE) {
             return Err(DidntRead);
         }
         let mut id = [0_u8; ZenohId::MAX_SIZE];
         reader.read_exact(&mut id[..size])?;
 
-        let time = uhlc::NTP64(time);
-        let id = uhlc::ID::try_from(&id[..size]).map_err(|_| DidntRead)?;
+        let time = NTP64(time);
+        let id = TimestampId::try_from(&id[..size]).map_err(|_| DidntRead)?;
         Ok(Timestamp::new(time, id))
     }
 }
@@ -0,0 +1,89 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::{LCodec, RCodec, WCodec, Zenoh080, Zenoh080Bounded};
+use zenoh_buffers::{
+    reader::{DidntRead, Reader},
+    writer::{DidntWrite, Writer},
+    ZStr,
+};
+
+// ZStr - Bounded
+macro_rules! zstr_impl {
+    ($bound:ty) => {
+        impl<W> WCodec<&ZStr, &mut W> for Zenoh080Bounded<$bound>
+        where
+            W: Writer,
+        {
+            type Output = Result<(), DidntWrite>;
+
+            fn write(self, writer: &mut W, x: &ZStr) -> Self::Output {
+                self.write(&mut *writer, x.as_str())
+            }
+        }
+
+        impl<R> RCodec<ZStr, &mut R> for Zenoh080Bounded<$bound>
+        where
+            R: Reader,
+        {
+            type Error = DidntRead;
+
+            fn read(self, reader: &mut R) -> Result<ZStr, Self::Error> {
+                let len: usize = self.read(&mut *reader)?;
+                if len == 0 {
+                    return Ok(ZStr::default());
+                }
+                // Borrow the bytes from the reader whenever possible instead of copying them
+                let zslice = reader.read_zslice(len)?;
+                ZStr::new(zslice).map_err(|_| DidntRead)
+            }
+        }
+    };
+}
+
+zstr_impl!(u8);
+zstr_impl!(u16);
+zstr_impl!(u32);
+zstr_impl!(u64);
+zstr_impl!(usize);
+
+// ZStr
+impl LCodec<&ZStr> for Zenoh080 {
+    fn w_len(self, x: &ZStr) -> usize {
+        self.w_len(x.as_str())
+    }
+}
+
+impl<W> WCodec<&ZStr, &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: &ZStr) -> Self::Output {
+        let zodec = Zenoh080Bounded::<usize>::new();
+        zodec.write(&mut *writer, x)
+    }
+}
+
+impl<R> RCodec<ZStr, &mut R> for Zenoh080
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<ZStr, Self::Error> {
+        let zodec = Zenoh080Bounded::<usize>::new();
+        zodec.read(&mut *reader)
+    }
+}
@@ -17,6 +17,8 @@
 //! This crate is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
+//!
+//! This crate supports `no_std` targets providing an allocator when its default `std` feature is disabled.
 #![cfg_attr(not(feature = "std"), no_std)]
 extern crate alloc;
 
@@ -26,7 +26,7 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::{
     common::{imsg, ZExtZ64, ZExtZBufHeader},
-    core::{Reliability, ZenohId},
+    core::{Reliability, Timestamp, ZenohId},
     network::{ext::EntityIdType, *},
 };
 
@@ -45,6 +45,7 @@ where
             NetworkBody::Request(b) => self.write(&mut *writer, b),
             NetworkBody::Response(b) => self.write(&mut *writer, b),
             NetworkBody::ResponseFinal(b) => self.write(&mut *writer, b),
+            NetworkBody::RequestCancel(b) => self.write(&mut *writer, b),
             NetworkBody::Declare(b) => self.write(&mut *writer, b),
             NetworkBody::OAM(b) => self.write(&mut *writer, b),
         }
@@ -89,6 +90,7 @@ where
             id::REQUEST => NetworkBody::Request(self.read(&mut *reader)?),
             id::RESPONSE => NetworkBody::Response(self.read(&mut *reader)?),
             id::RESPONSE_FINAL => NetworkBody::ResponseFinal(self.read(&mut *reader)?),
+            id::REQUEST_CANCEL => NetworkBody::RequestCancel(self.read(&mut *reader)?),
             id::DECLARE => NetworkBody::Declare(self.read(&mut *reader)?),
             id::OAM => NetworkBody::OAM(self.read(&mut *reader)?),
             _ => return Err(DidntRead),
@@ -173,7 +175,7 @@ where
 
     fn read(self, reader: &mut R) -> Result<(ext::TimestampType<{ ID }>, bool), Self::Error> {
         let (_, more): (ZExtZBufHeader<{ ID }>, bool) = self.read(&mut *reader)?;
-        let timestamp: uhlc::Timestamp = self.codec.read(&mut *reader)?;
+        let timestamp: Timestamp = self.codec.read(&mut *reader)?;
         Ok((ext::TimestampType { timestamp }, more))
     }
 }
@@ -24,7 +24,7 @@ use zenoh_protocol::{
     network::{
         id,
         request::{ext, flag},
-        Mapping, Request, RequestId,
+        Mapping, Request, RequestCancel, RequestId,
     },
     zenoh::RequestBody,
 };
@@ -72,6 +72,45 @@ where
     }
 }
 
+// Selection
+impl<W> WCodec<(&ext::SelectionType, bool), &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: (&ext::SelectionType, bool)) -> Self::Output {
+        let (x, more) = x;
+
+        let v = match x {
+            ext::SelectionType::Nearest => 0,
+            ext::SelectionType::RoundRobin => 1,
+            ext::SelectionType::Ranked => 2,
+        };
+        let ext = ext::Selection::new(v);
+        self.write(&mut *writer, (&ext, more))
+    }
+}
+
+impl<R> RCodec<(Option<ext::SelectionType>, bool), &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<(Option<ext::SelectionType>, bool), Self::Error> {
+        let (ext, more): (ext::Selection, bool) = self.read(&mut *reader)?;
+        // An unknown selection falls back to the one configured on the routers
+        let s = match ext.value {
+            0 => Some(ext::SelectionType::Nearest),
+            1 => Some(ext::SelectionType::RoundRobin),
+            2 => Some(ext::SelectionType::Ranked),
+            _ => None,
+        };
+        Ok((s, more))
+    }
+}
+
 impl<W> WCodec<&Request, &mut W> for Zenoh080
 where
     W: Writer,
@@ -88,6 +127,7 @@ where
             ext_target,
             ext_budget,
             ext_timeout,
+            ext_selection,
             payload,
         } = x;
 
@@ -98,6 +138,7 @@ where
             + ((ext_target != &ext::TargetType::default()) as u8)
             + (ext_budget.is_some() as u8)
             + (ext_timeout.is_some() as u8)
+            + (ext_selection.is_some() as u8)
             + ((ext_nodeid != &ext::NodeIdType::default()) as u8);
         if n_exts != 0 {
             header |= flag::Z;
@@ -137,6 +178,10 @@ where
             let e = ext::Timeout::new(to.as_millis() as u64);
             self.write(&mut *writer, (&e, n_exts != 0))?;
         }
+        if let Some(s) = ext_selection.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (s, n_exts != 0))?;
+        }
         if ext_nodeid != &ext::NodeIdType::default() {
             n_exts -= 1;
             self.write(&mut *writer, (*ext_nodeid, n_exts != 0))?;
@@ -191,6 +236,7 @@ where
         let mut ext_target = ext::TargetType::default();
         let mut ext_limit = None;
         let mut ext_timeout = None;
+        let mut ext_selection = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -227,6 +273,11 @@ where
                     ext_timeout = Some(ext::TimeoutType::from_millis(to.value));
                     has_ext = ext;
                 }
+                ext::Selection::ID => {
+                    let (s, ext): (Option<ext::SelectionType>, bool) = eodec.read(&mut *reader)?;
+                    ext_selection = s;
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "Request", ext)?;
                 }
@@ -246,6 +297,108 @@ where
             ext_target,
             ext_budget: ext_limit,
             ext_timeout,
+            ext_selection,
+        })
+    }
+}
+
+// RequestCancel
+impl<W> WCodec<&RequestCancel, &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: &RequestCancel) -> Self::Output {
+        let RequestCancel {
+            rid,
+            ext_qos,
+            ext_tstamp,
+        } = x;
+
+        // Header
+        let mut header = id::REQUEST_CANCEL;
+        let mut n_exts =
+            ((ext_qos != &ext::QoSType::default()) as u8) + (ext_tstamp.is_some() as u8);
+        if n_exts != 0 {
+            header |= flag::Z;
+        }
+        self.write(&mut *writer, header)?;
+
+        // Body
+        self.write(&mut *writer, rid)?;
+
+        // Extensions
+        if ext_qos != &ext::QoSType::default() {
+            n_exts -= 1;
+            self.write(&mut *writer, (*ext_qos, n_exts != 0))?;
+        }
+        if let Some(ts) = ext_tstamp.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (ts, n_exts != 0))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R> RCodec<RequestCancel, &mut R> for Zenoh080
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<RequestCancel, Self::Error> {
+        let header: u8 = self.read(&mut *reader)?;
+        let codec = Zenoh080Header::new(header);
+        codec.read(reader)
+    }
+}
+
+impl<R> RCodec<RequestCancel, &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<RequestCancel, Self::Error> {
+        if imsg::mid(self.header) != id::REQUEST_CANCEL {
+            return Err(DidntRead);
+        }
+
+        // Body
+        let bodec = Zenoh080Bounded::<RequestId>::new();
+        let rid: RequestId = bodec.read(&mut *reader)?;
+
+        // Extensions
+        let mut ext_qos = ext::QoSType::default();
+        let mut ext_tstamp = None;
+
+        let mut has_ext = imsg::has_flag(self.header, flag::Z);
+        while has_ext {
+            let ext: u8 = self.codec.read(&mut *reader)?;
+            let eodec = Zenoh080Header::new(ext);
+            match iext::eid(ext) {
+                ext::QoS::ID => {
+                    let (q, ext): (ext::QoSType, bool) = eodec.read(&mut *reader)?;
+                    ext_qos = q;
+                    has_ext = ext;
+                }
+                ext::Timestamp::ID => {
+                    let (t, ext): (ext::TimestampType, bool) = eodec.read(&mut *reader)?;
+                    ext_tstamp = Some(t);
+                    has_ext = ext;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "RequestCancel", ext)?;
+                }
+            }
+        }
+
+        Ok(RequestCancel {
+            rid,
+            ext_qos,
+            ext_tstamp,
         })
     }
 }
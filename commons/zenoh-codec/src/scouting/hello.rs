@@ -11,17 +11,17 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::{RCodec, WCodec, Zenoh080, Zenoh080Header, Zenoh080Length};
+use crate::{common::extension, RCodec, WCodec, Zenoh080, Zenoh080Header, Zenoh080Length};
 use alloc::{vec, vec::Vec};
 use zenoh_buffers::{
     reader::{DidntRead, Reader},
     writer::{DidntWrite, Writer},
 };
 use zenoh_protocol::{
-    common::{imsg, ZExtUnknown},
+    common::{iext, imsg},
     core::{Locator, WhatAmI, ZenohId},
     scouting::{
-        hello::{flag, Hello},
+        hello::{ext, flag, Hello},
         id,
     },
 };
@@ -38,6 +38,7 @@ where
             whatami,
             zid,
             locators,
+            ext_metadata,
         } = x;
 
         // Header
@@ -45,6 +46,9 @@ where
         if !locators.is_empty() {
             header |= flag::L;
         }
+        if ext_metadata.is_some() {
+            header |= flag::Z;
+        }
         self.write(&mut *writer, header)?;
 
         // Body
@@ -67,6 +71,11 @@ where
             self.write(&mut *writer, locators.as_slice())?;
         }
 
+        // Extensions
+        if let Some(metadata) = ext_metadata.as_ref() {
+            self.write(&mut *writer, (metadata, false))?;
+        }
+
         Ok(())
     }
 }
@@ -116,10 +125,22 @@ where
         };
 
         // Extensions
-        let mut has_extensions = imsg::has_flag(self.header, flag::Z);
-        while has_extensions {
-            let (_, more): (ZExtUnknown, bool) = self.codec.read(&mut *reader)?;
-            has_extensions = more;
+        let mut ext_metadata = None;
+
+        let mut has_ext = imsg::has_flag(self.header, flag::Z);
+        while has_ext {
+            let ext: u8 = self.codec.read(&mut *reader)?;
+            let eodec = Zenoh080Header::new(ext);
+            match iext::eid(ext) {
+                ext::Metadata::ID => {
+                    let (m, ext): (ext::Metadata, bool) = eodec.read(&mut *reader)?;
+                    ext_metadata = Some(m);
+                    has_ext = ext;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "Hello", ext)?;
+                }
+            }
         }
 
         Ok(Hello {
@@ -127,6 +148,7 @@ where
             zid,
             whatami,
             locators,
+            ext_metadata,
         })
     }
 }
@@ -38,6 +38,7 @@ where
             more,
             sn,
             ext_qos,
+            ext_total,
         } = x;
 
         // Header
@@ -48,7 +49,8 @@ where
         if *more {
             header |= flag::M;
         }
-        if ext_qos != &ext::QoSType::default() {
+        let mut n_exts = (ext_qos != &ext::QoSType::default()) as u8 + ext_total.is_some() as u8;
+        if n_exts != 0 {
             header |= flag::Z;
         }
         self.write(&mut *writer, header)?;
@@ -58,7 +60,12 @@ where
 
         // Extensions
         if ext_qos != &ext::QoSType::default() {
-            self.write(&mut *writer, (*ext_qos, false))?;
+            n_exts -= 1;
+            self.write(&mut *writer, (*ext_qos, n_exts != 0))?;
+        }
+        if let Some(total) = ext_total.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (total, n_exts != 0))?;
         }
 
         Ok(())
@@ -98,6 +105,7 @@ where
 
         // Extensions
         let mut ext_qos = ext::QoSType::default();
+        let mut ext_total = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -109,6 +117,11 @@ where
                     ext_qos = q;
                     has_ext = ext;
                 }
+                ext::TotalSize::ID => {
+                    let (t, ext): (ext::TotalSize, bool) = eodec.read(&mut *reader)?;
+                    ext_total = Some(t);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "Fragment", ext)?;
                 }
@@ -120,6 +133,7 @@ where
             more,
             sn,
             ext_qos,
+            ext_total,
         })
     }
 }
@@ -138,6 +152,7 @@ where
             sn,
             payload,
             ext_qos,
+            ext_total,
         } = x;
 
         // Header
@@ -146,6 +161,7 @@ where
             more: *more,
             sn: *sn,
             ext_qos: *ext_qos,
+            ext_total: *ext_total,
         };
         self.write(&mut *writer, &header)?;
 
@@ -184,6 +200,7 @@ where
             more: header.more,
             sn: header.sn,
             ext_qos: header.ext_qos,
+            ext_total: header.ext_total,
             payload,
         })
     }
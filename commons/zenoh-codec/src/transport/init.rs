@@ -49,6 +49,9 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_version,
+            ext_checksum,
         } = x;
 
         // Header
@@ -61,7 +64,10 @@ where
             + (ext_auth.is_some() as u8)
             + (ext_mlink.is_some() as u8)
             + (ext_lowlatency.is_some() as u8)
-            + (ext_compression.is_some() as u8);
+            + (ext_compression.is_some() as u8)
+            + (ext_compression_algorithms.is_some() as u8)
+            + (ext_version.is_some() as u8)
+            + (ext_checksum.is_some() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -111,6 +117,18 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (compression, n_exts != 0))?;
         }
+        if let Some(algorithms) = ext_compression_algorithms.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (algorithms, n_exts != 0))?;
+        }
+        if let Some(version) = ext_version.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (version, n_exts != 0))?;
+        }
+        if let Some(checksum) = ext_checksum.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (checksum, n_exts != 0))?;
+        }
 
         Ok(())
     }
@@ -170,6 +188,9 @@ where
         let mut ext_mlink = None;
         let mut ext_lowlatency = None;
         let mut ext_compression = None;
+        let mut ext_compression_algorithms = None;
+        let mut ext_version = None;
+        let mut ext_checksum = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -206,6 +227,21 @@ where
                     ext_compression = Some(q);
                     has_ext = ext;
                 }
+                ext::CompressionAlgorithms::ID => {
+                    let (a, ext): (ext::CompressionAlgorithms, bool) = eodec.read(&mut *reader)?;
+                    ext_compression_algorithms = Some(a);
+                    has_ext = ext;
+                }
+                ext::Version::ID => {
+                    let (v, ext): (ext::Version, bool) = eodec.read(&mut *reader)?;
+                    ext_version = Some(v);
+                    has_ext = ext;
+                }
+                ext::Checksum::ID => {
+                    let (c, ext): (ext::Checksum, bool) = eodec.read(&mut *reader)?;
+                    ext_checksum = Some(c);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "InitSyn", ext)?;
                 }
@@ -224,6 +260,9 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_version,
+            ext_checksum,
         })
     }
 }
@@ -249,6 +288,8 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_checksum,
         } = x;
 
         // Header
@@ -261,7 +302,9 @@ where
             + (ext_auth.is_some() as u8)
             + (ext_mlink.is_some() as u8)
             + (ext_lowlatency.is_some() as u8)
-            + (ext_compression.is_some() as u8);
+            + (ext_compression.is_some() as u8)
+            + (ext_compression_algorithms.is_some() as u8)
+            + (ext_checksum.is_some() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -314,6 +357,14 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (compression, n_exts != 0))?;
         }
+        if let Some(algorithms) = ext_compression_algorithms.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (algorithms, n_exts != 0))?;
+        }
+        if let Some(checksum) = ext_checksum.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (checksum, n_exts != 0))?;
+        }
 
         Ok(())
     }
@@ -376,6 +427,8 @@ where
         let mut ext_mlink = None;
         let mut ext_lowlatency = None;
         let mut ext_compression = None;
+        let mut ext_compression_algorithms = None;
+        let mut ext_checksum = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -412,6 +465,16 @@ where
                     ext_compression = Some(q);
                     has_ext = ext;
                 }
+                ext::CompressionAlgorithms::ID => {
+                    let (a, ext): (ext::CompressionAlgorithms, bool) = eodec.read(&mut *reader)?;
+                    ext_compression_algorithms = Some(a);
+                    has_ext = ext;
+                }
+                ext::Checksum::ID => {
+                    let (c, ext): (ext::Checksum, bool) = eodec.read(&mut *reader)?;
+                    ext_checksum = Some(c);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "InitAck", ext)?;
                 }
@@ -431,6 +494,8 @@ where
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_checksum,
         })
     }
 }
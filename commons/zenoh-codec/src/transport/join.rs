@@ -148,6 +148,7 @@ where
             next_sn,
             ext_qos,
             ext_shm,
+            ext_version,
         } = x;
 
         // Header
@@ -158,7 +159,8 @@ where
         if resolution != &Resolution::default() || batch_size != &batch_size::MULTICAST {
             header |= flag::S;
         }
-        let mut n_exts = (ext_qos.is_some() as u8) + (ext_shm.is_some() as u8);
+        let mut n_exts =
+            (ext_qos.is_some() as u8) + (ext_shm.is_some() as u8) + (ext_version.is_some() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -199,6 +201,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (shm, n_exts != 0))?;
         }
+        if let Some(version) = ext_version.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (version, n_exts != 0))?;
+        }
 
         Ok(())
     }
@@ -262,6 +268,7 @@ where
         // Extensions
         let mut ext_qos = None;
         let mut ext_shm = None;
+        let mut ext_version = None;
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
         while has_ext {
@@ -278,6 +285,11 @@ where
                     ext_shm = Some(s);
                     has_ext = ext;
                 }
+                ext::Version::ID => {
+                    let (v, ext): (ext::Version, bool) = eodec.read(&mut *reader)?;
+                    ext_version = Some(v);
+                    has_ext = ext;
+                }
                 _ => {
                     has_ext = extension::skip(reader, "Join", ext)?;
                 }
@@ -294,6 +306,7 @@ where
             next_sn,
             ext_qos,
             ext_shm,
+            ext_version,
         })
     }
 }
@@ -17,10 +17,10 @@ use zenoh_buffers::{
     writer::{DidntWrite, Writer},
 };
 use zenoh_protocol::{
-    common::imsg,
+    common::{iext, imsg},
     transport::{
         id,
-        keepalive::{flag, KeepAlive},
+        keepalive::{ext, flag, KeepAlive},
     },
 };
 
@@ -31,11 +31,29 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &KeepAlive) -> Self::Output {
-        let KeepAlive = x;
+        let KeepAlive {
+            ext_probe,
+            ext_echo,
+        } = x;
 
         // Header
-        let header = id::KEEP_ALIVE;
+        let mut header = id::KEEP_ALIVE;
+        let mut n_exts = (ext_probe.is_some() as u8) + (ext_echo.is_some() as u8);
+        if n_exts != 0 {
+            header |= flag::Z;
+        }
         self.write(&mut *writer, header)?;
+
+        // Extensions
+        if let Some(probe) = ext_probe.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (probe, n_exts != 0))?;
+        }
+        if let Some(echo) = ext_echo.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (echo, n_exts != 0))?;
+        }
+
         Ok(())
     }
 }
@@ -65,11 +83,33 @@ where
         }
 
         // Extensions
-        let has_ext = imsg::has_flag(self.header, flag::Z);
-        if has_ext {
-            extension::skip_all(reader, "Unknown KeepAlive ext")?;
+        let mut ext_probe = None;
+        let mut ext_echo = None;
+
+        let mut has_ext = imsg::has_flag(self.header, flag::Z);
+        while has_ext {
+            let ext: u8 = self.codec.read(&mut *reader)?;
+            let eodec = Zenoh080Header::new(ext);
+            match iext::eid(ext) {
+                ext::Probe::ID => {
+                    let (p, ext): (ext::Probe, bool) = eodec.read(&mut *reader)?;
+                    ext_probe = Some(p);
+                    has_ext = ext;
+                }
+                ext::Echo::ID => {
+                    let (e, ext): (ext::Echo, bool) = eodec.read(&mut *reader)?;
+                    ext_echo = Some(e);
+                    has_ext = ext;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "KeepAlive", ext)?;
+                }
+            }
         }
 
-        Ok(KeepAlive)
+        Ok(KeepAlive {
+            ext_probe,
+            ext_echo,
+        })
     }
 }
@@ -19,6 +19,7 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::{
     common::{iext, imsg},
+    core::Timestamp,
     zenoh::{
         ack::{ext, flag, Ack},
         id,
@@ -93,7 +94,7 @@ where
         }
 
         // Body
-        let mut timestamp: Option<uhlc::Timestamp> = None;
+        let mut timestamp: Option<Timestamp> = None;
         if imsg::has_flag(self.header, flag::T) {
             timestamp = Some(self.codec.read(&mut *reader)?);
         }
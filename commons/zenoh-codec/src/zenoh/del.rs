@@ -19,6 +19,7 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::{
     common::{iext, imsg},
+    core::Timestamp,
     zenoh::{
         del::{ext, flag, Del},
         id,
@@ -35,7 +36,10 @@ where
         let Del {
             timestamp,
             ext_sinfo,
+            ext_coherence,
             ext_attachment,
+            ext_body,
+            ext_trace,
             ext_unknown,
         } = x;
 
@@ -45,7 +49,10 @@ where
             header |= flag::T;
         }
         let mut n_exts = (ext_sinfo.is_some()) as u8
+            + (ext_coherence.is_some()) as u8
             + (ext_attachment.is_some()) as u8
+            + (ext_body.is_some()) as u8
+            + (ext_trace.is_some() as u8)
             + (ext_unknown.len() as u8);
         if n_exts != 0 {
             header |= flag::Z;
@@ -62,10 +69,22 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (sinfo, n_exts != 0))?;
         }
+        if let Some(coherence) = ext_coherence.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (coherence, n_exts != 0))?;
+        }
         if let Some(att) = ext_attachment.as_ref() {
             n_exts -= 1;
             self.write(&mut *writer, (att, n_exts != 0))?;
         }
+        if let Some(body) = ext_body.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (body, n_exts != 0))?;
+        }
+        if let Some(trace) = ext_trace.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (trace, n_exts != 0))?;
+        }
         for u in ext_unknown.iter() {
             n_exts -= 1;
             self.write(&mut *writer, (u, n_exts != 0))?;
@@ -100,14 +119,17 @@ where
         }
 
         // Body
-        let mut timestamp: Option<uhlc::Timestamp> = None;
+        let mut timestamp: Option<Timestamp> = None;
         if imsg::has_flag(self.header, flag::T) {
             timestamp = Some(self.codec.read(&mut *reader)?);
         }
 
         // Extensions
         let mut ext_sinfo: Option<ext::SourceInfoType> = None;
+        let mut ext_coherence: Option<ext::CoherenceType> = None;
         let mut ext_attachment: Option<ext::AttachmentType> = None;
+        let mut ext_body: Option<ext::DelBodyType> = None;
+        let mut ext_trace: Option<ext::TraceIdType> = None;
         let mut ext_unknown = Vec::new();
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
@@ -120,13 +142,28 @@ where
                     ext_sinfo = Some(s);
                     has_ext = ext;
                 }
+                ext::Coherence::ID => {
+                    let (c, ext): (ext::CoherenceType, bool) = eodec.read(&mut *reader)?;
+                    ext_coherence = Some(c);
+                    has_ext = ext;
+                }
                 ext::Attachment::ID => {
                     let (a, ext): (ext::AttachmentType, bool) = eodec.read(&mut *reader)?;
                     ext_attachment = Some(a);
                     has_ext = ext;
                 }
+                ext::DelBodyType::SID | ext::DelBodyType::VID => {
+                    let (b, ext): (ext::DelBodyType, bool) = eodec.read(&mut *reader)?;
+                    ext_body = Some(b);
+                    has_ext = ext;
+                }
+                ext::TraceId::ID => {
+                    let (t, ext): (ext::TraceIdType, bool) = eodec.read(&mut *reader)?;
+                    ext_trace = Some(t);
+                    has_ext = ext;
+                }
                 _ => {
-                    let (u, ext) = extension::read(reader, "Del", ext)?;
+                    let (u, ext) = extension::read_user(reader, "Del", ext)?;
                     ext_unknown.push(u);
                     has_ext = ext;
                 }
@@ -136,7 +173,10 @@ where
         Ok(Del {
             timestamp,
             ext_sinfo,
+            ext_coherence,
             ext_attachment,
+            ext_body,
+            ext_trace,
             ext_unknown,
         })
     }
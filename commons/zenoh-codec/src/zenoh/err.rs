@@ -19,6 +19,7 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::{
     common::{iext, imsg},
+    core::Timestamp,
     zenoh::{
         err::{ext, flag, Err},
         id,
@@ -38,6 +39,7 @@ where
             timestamp,
             ext_sinfo,
             ext_body,
+            ext_trace,
             ext_unknown,
         } = x;
 
@@ -49,8 +51,10 @@ where
         if *is_infrastructure {
             header |= flag::I;
         }
-        let mut n_exts =
-            (ext_sinfo.is_some() as u8) + (ext_body.is_some() as u8) + (ext_unknown.len() as u8);
+        let mut n_exts = (ext_sinfo.is_some() as u8)
+            + (ext_body.is_some() as u8)
+            + (ext_trace.is_some() as u8)
+            + (ext_unknown.len() as u8);
         if n_exts != 0 {
             header |= flag::Z;
         }
@@ -71,6 +75,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (body, n_exts != 0))?;
         }
+        if let Some(trace) = ext_trace.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (trace, n_exts != 0))?;
+        }
         for u in ext_unknown.iter() {
             n_exts -= 1;
             self.write(&mut *writer, (u, n_exts != 0))?;
@@ -107,7 +115,7 @@ where
         // Body
         let code: u16 = self.codec.read(&mut *reader)?;
         let is_infrastructure = imsg::has_flag(self.header, flag::I);
-        let mut timestamp: Option<uhlc::Timestamp> = None;
+        let mut timestamp: Option<Timestamp> = None;
         if imsg::has_flag(self.header, flag::T) {
             timestamp = Some(self.codec.read(&mut *reader)?);
         }
@@ -115,6 +123,7 @@ where
         // Extensions
         let mut ext_sinfo: Option<ext::SourceInfoType> = None;
         let mut ext_body: Option<ext::ErrBodyType> = None;
+        let mut ext_trace: Option<ext::TraceIdType> = None;
         let mut ext_unknown = Vec::new();
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
@@ -132,6 +141,11 @@ where
                     ext_body = Some(s);
                     has_ext = ext;
                 }
+                ext::TraceId::ID => {
+                    let (t, ext): (ext::TraceIdType, bool) = eodec.read(&mut *reader)?;
+                    ext_trace = Some(t);
+                    has_ext = ext;
+                }
                 _ => {
                     let (u, ext) = extension::read(reader, "Err", ext)?;
                     ext_unknown.push(u);
@@ -146,6 +160,7 @@ where
             timestamp,
             ext_sinfo,
             ext_body,
+            ext_trace,
             ext_unknown,
         })
     }
@@ -206,6 +206,84 @@ where
     }
 }
 
+// Extension: Coherence
+impl<const ID: u8> LCodec<&ext::CoherenceType<{ ID }>> for Zenoh080 {
+    fn w_len(self, x: &ext::CoherenceType<{ ID }>) -> usize {
+        let ext::CoherenceType {
+            zid,
+            set_id,
+            index,
+            last: _,
+        } = x;
+
+        1 + self.w_len(zid) + self.w_len(*set_id) + self.w_len(*index)
+    }
+}
+
+impl<W, const ID: u8> WCodec<(&ext::CoherenceType<{ ID }>, bool), &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: (&ext::CoherenceType<{ ID }>, bool)) -> Self::Output {
+        let (x, more) = x;
+        let ext::CoherenceType {
+            zid,
+            set_id,
+            index,
+            last,
+        } = x;
+
+        let header: ZExtZBufHeader<{ ID }> = ZExtZBufHeader::new(self.w_len(x));
+        self.write(&mut *writer, (&header, more))?;
+
+        let mut flags: u8 = (zid.size() as u8 - 1) << 4;
+        if *last {
+            flags |= 1;
+        }
+        self.write(&mut *writer, flags)?;
+
+        let lodec = Zenoh080Length::new(zid.size());
+        lodec.write(&mut *writer, zid)?;
+
+        self.write(&mut *writer, set_id)?;
+        self.write(&mut *writer, index)?;
+        Ok(())
+    }
+}
+
+impl<R, const ID: u8> RCodec<(ext::CoherenceType<{ ID }>, bool), &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<(ext::CoherenceType<{ ID }>, bool), Self::Error> {
+        let (_, more): (ZExtZBufHeader<{ ID }>, bool) = self.read(&mut *reader)?;
+
+        let flags: u8 = self.codec.read(&mut *reader)?;
+        let length = 1 + ((flags >> 4) as usize);
+        let last = flags & 1 != 0;
+
+        let lodec = Zenoh080Length::new(length);
+        let zid: ZenohId = lodec.read(&mut *reader)?;
+
+        let set_id: u32 = self.codec.read(&mut *reader)?;
+        let index: u32 = self.codec.read(&mut *reader)?;
+
+        Ok((
+            ext::CoherenceType {
+                zid,
+                set_id,
+                index,
+                last,
+            },
+            more,
+        ))
+    }
+}
+
 // Extension: Shm
 #[cfg(feature = "shared-memory")]
 impl<W, const ID: u8> WCodec<(&ext::ShmType<{ ID }>, bool), &mut W> for Zenoh080
@@ -415,3 +493,40 @@ where
         Ok((ext::AttachmentType { buffer }, more))
     }
 }
+
+// Extension: TraceId
+impl<W, const ID: u8> WCodec<(&ext::TraceIdType<{ ID }>, bool), &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, writer: &mut W, x: (&ext::TraceIdType<{ ID }>, bool)) -> Self::Output {
+        let (x, more) = x;
+        let ext::TraceIdType { id } = x;
+
+        let header: ZExtZBufHeader<{ ID }> = ZExtZBufHeader::new(id.len());
+        self.write(&mut *writer, (&header, more))?;
+        writer.write_exact(id)?;
+
+        Ok(())
+    }
+}
+
+impl<R, const ID: u8> RCodec<(ext::TraceIdType<{ ID }>, bool), &mut R> for Zenoh080Header
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, reader: &mut R) -> Result<(ext::TraceIdType<{ ID }>, bool), Self::Error> {
+        let (h, more): (ZExtZBufHeader<{ ID }>, bool) = self.read(&mut *reader)?;
+        let mut id = [0u8; 16];
+        if h.len != id.len() {
+            return Err(DidntRead);
+        }
+        reader.read_exact(&mut id)?;
+
+        Ok((ext::TraceIdType { id }, more))
+    }
+}
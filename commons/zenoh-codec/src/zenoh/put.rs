@@ -24,7 +24,7 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::{
     common::{iext, imsg},
-    core::Encoding,
+    core::{Encoding, Timestamp},
     zenoh::{
         id,
         put::{ext, flag, Put},
@@ -42,9 +42,13 @@ where
             timestamp,
             encoding,
             ext_sinfo,
+            ext_coherence,
+            ext_ttl,
+            ext_latency_budget,
             ext_attachment,
             #[cfg(feature = "shared-memory")]
             ext_shm,
+            ext_trace,
             ext_unknown,
             payload,
         }: &Put = x;
@@ -58,7 +62,11 @@ where
             header |= flag::E;
         }
         let mut n_exts = (ext_sinfo.is_some()) as u8
+            + (ext_coherence.is_some()) as u8
+            + (ext_ttl.is_some()) as u8
+            + (ext_latency_budget.is_some()) as u8
             + (ext_attachment.is_some()) as u8
+            + (ext_trace.is_some() as u8)
             + (ext_unknown.len() as u8);
         #[cfg(feature = "shared-memory")]
         {
@@ -87,10 +95,28 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (eshm, n_exts != 0))?;
         }
+        if let Some(coherence) = ext_coherence.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (coherence, n_exts != 0))?;
+        }
+        if let Some(ttl) = ext_ttl.as_ref() {
+            n_exts -= 1;
+            let e = ext::Ttl::new(ttl.as_millis() as u64);
+            self.write(&mut *writer, (&e, n_exts != 0))?;
+        }
+        if let Some(budget) = ext_latency_budget.as_ref() {
+            n_exts -= 1;
+            let e = ext::LatencyBudget::new(budget.as_micros() as u64);
+            self.write(&mut *writer, (&e, n_exts != 0))?;
+        }
         if let Some(att) = ext_attachment.as_ref() {
             n_exts -= 1;
             self.write(&mut *writer, (att, n_exts != 0))?;
         }
+        if let Some(trace) = ext_trace.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (trace, n_exts != 0))?;
+        }
         for u in ext_unknown.iter() {
             n_exts -= 1;
             self.write(&mut *writer, (u, n_exts != 0))?;
@@ -138,7 +164,7 @@ where
         }
 
         // Body
-        let mut timestamp: Option<uhlc::Timestamp> = None;
+        let mut timestamp: Option<Timestamp> = None;
         if imsg::has_flag(self.header, flag::T) {
             timestamp = Some(self.codec.read(&mut *reader)?);
         }
@@ -150,9 +176,13 @@ where
 
         // Extensions
         let mut ext_sinfo: Option<ext::SourceInfoType> = None;
+        let mut ext_coherence: Option<ext::CoherenceType> = None;
+        let mut ext_ttl: Option<ext::TtlType> = None;
+        let mut ext_latency_budget: Option<ext::LatencyBudgetType> = None;
         #[cfg(feature = "shared-memory")]
         let mut ext_shm: Option<ext::ShmType> = None;
         let mut ext_attachment: Option<ext::AttachmentType> = None;
+        let mut ext_trace: Option<ext::TraceIdType> = None;
         let mut ext_unknown = Vec::new();
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
@@ -171,13 +201,33 @@ where
                     ext_shm = Some(s);
                     has_ext = ext;
                 }
+                ext::Coherence::ID => {
+                    let (c, ext): (ext::CoherenceType, bool) = eodec.read(&mut *reader)?;
+                    ext_coherence = Some(c);
+                    has_ext = ext;
+                }
+                ext::Ttl::ID => {
+                    let (t, ext): (ext::Ttl, bool) = eodec.read(&mut *reader)?;
+                    ext_ttl = Some(ext::TtlType::from_millis(t.value));
+                    has_ext = ext;
+                }
+                ext::LatencyBudget::ID => {
+                    let (b, ext): (ext::LatencyBudget, bool) = eodec.read(&mut *reader)?;
+                    ext_latency_budget = Some(ext::LatencyBudgetType::from_micros(b.value));
+                    has_ext = ext;
+                }
                 ext::Attachment::ID => {
                     let (a, ext): (ext::AttachmentType, bool) = eodec.read(&mut *reader)?;
                     ext_attachment = Some(a);
                     has_ext = ext;
                 }
+                ext::TraceId::ID => {
+                    let (t, ext): (ext::TraceIdType, bool) = eodec.read(&mut *reader)?;
+                    ext_trace = Some(t);
+                    has_ext = ext;
+                }
                 _ => {
-                    let (u, ext) = extension::read(reader, "Put", ext)?;
+                    let (u, ext) = extension::read_user(reader, "Put", ext)?;
                     ext_unknown.push(u);
                     has_ext = ext;
                 }
@@ -203,9 +253,13 @@ where
             timestamp,
             encoding,
             ext_sinfo,
+            ext_coherence,
+            ext_ttl,
+            ext_latency_budget,
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_trace,
             ext_unknown,
             payload,
         })
@@ -12,10 +12,11 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::{common::extension, RCodec, WCodec, Zenoh080, Zenoh080Header};
-use alloc::{string::String, vec::Vec};
+use alloc::vec::Vec;
 use zenoh_buffers::{
     reader::{DidntRead, Reader},
     writer::{DidntWrite, Writer},
+    ZStr,
 };
 
 use zenoh_protocol::{
@@ -80,6 +81,7 @@ where
             ext_consolidation,
             ext_body,
             ext_attachment,
+            ext_trace,
             ext_unknown,
         } = x;
 
@@ -92,6 +94,7 @@ where
             + ((ext_consolidation != &ext::ConsolidationType::default()) as u8)
             + (ext_body.is_some() as u8)
             + (ext_attachment.is_some() as u8)
+            + (ext_trace.is_some() as u8)
             + (ext_unknown.len() as u8);
         if n_exts != 0 {
             header |= flag::Z;
@@ -120,6 +123,10 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (att, n_exts != 0))?;
         }
+        if let Some(trace) = ext_trace.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (trace, n_exts != 0))?;
+        }
         for u in ext_unknown.iter() {
             n_exts -= 1;
             self.write(&mut *writer, (u, n_exts != 0))?;
@@ -154,7 +161,7 @@ where
         }
 
         // Body
-        let mut parameters = String::new();
+        let mut parameters = ZStr::default();
         if imsg::has_flag(self.header, flag::P) {
             parameters = self.codec.read(&mut *reader)?;
         }
@@ -164,6 +171,7 @@ where
         let mut ext_consolidation = ext::ConsolidationType::default();
         let mut ext_body: Option<ext::QueryBodyType> = None;
         let mut ext_attachment: Option<ext::AttachmentType> = None;
+        let mut ext_trace: Option<ext::TraceIdType> = None;
         let mut ext_unknown = Vec::new();
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
@@ -191,8 +199,13 @@ where
                     ext_attachment = Some(a);
                     has_ext = ext;
                 }
+                ext::TraceId::ID => {
+                    let (t, ext): (ext::TraceIdType, bool) = eodec.read(&mut *reader)?;
+                    ext_trace = Some(t);
+                    has_ext = ext;
+                }
                 _ => {
-                    let (u, ext) = extension::read(reader, "Query", ext)?;
+                    let (u, ext) = extension::read_user(reader, "Query", ext)?;
                     ext_unknown.push(u);
                     has_ext = ext;
                 }
@@ -205,6 +218,7 @@ where
             ext_consolidation,
             ext_body,
             ext_attachment,
+            ext_trace,
             ext_unknown,
         })
     }
@@ -24,7 +24,7 @@ use zenoh_buffers::{
 };
 use zenoh_protocol::{
     common::{iext, imsg},
-    core::Encoding,
+    core::{Encoding, Timestamp},
     zenoh::{
         id,
         reply::{ext, flag, Reply},
@@ -46,6 +46,8 @@ where
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_delete,
+            ext_trace,
             ext_unknown,
             payload,
         } = x;
@@ -61,6 +63,8 @@ where
         let mut n_exts = (ext_sinfo.is_some()) as u8
             + ((ext_consolidation != &ext::ConsolidationType::default()) as u8)
             + (ext_attachment.is_some()) as u8
+            + (ext_delete.is_some()) as u8
+            + (ext_trace.is_some() as u8)
             + (ext_unknown.len() as u8);
         #[cfg(feature = "shared-memory")]
         {
@@ -97,6 +101,14 @@ where
             n_exts -= 1;
             self.write(&mut *writer, (att, n_exts != 0))?;
         }
+        if let Some(del) = ext_delete.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (del, n_exts != 0))?;
+        }
+        if let Some(trace) = ext_trace.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (trace, n_exts != 0))?;
+        }
         for u in ext_unknown.iter() {
             n_exts -= 1;
             self.write(&mut *writer, (u, n_exts != 0))?;
@@ -144,7 +156,7 @@ where
         }
 
         // Body
-        let mut timestamp: Option<uhlc::Timestamp> = None;
+        let mut timestamp: Option<Timestamp> = None;
         if imsg::has_flag(self.header, flag::T) {
             timestamp = Some(self.codec.read(&mut *reader)?);
         }
@@ -160,6 +172,8 @@ where
         #[cfg(feature = "shared-memory")]
         let mut ext_shm: Option<ext::ShmType> = None;
         let mut ext_attachment: Option<ext::AttachmentType> = None;
+        let mut ext_delete: Option<ext::Delete> = None;
+        let mut ext_trace: Option<ext::TraceIdType> = None;
         let mut ext_unknown = Vec::new();
 
         let mut has_ext = imsg::has_flag(self.header, flag::Z);
@@ -188,6 +202,16 @@ where
                     ext_attachment = Some(a);
                     has_ext = ext;
                 }
+                ext::Delete::ID => {
+                    let (d, ext): (ext::Delete, bool) = eodec.read(&mut *reader)?;
+                    ext_delete = Some(d);
+                    has_ext = ext;
+                }
+                ext::TraceId::ID => {
+                    let (t, ext): (ext::TraceIdType, bool) = eodec.read(&mut *reader)?;
+                    ext_trace = Some(t);
+                    has_ext = ext;
+                }
                 _ => {
                     let (u, ext) = extension::read(reader, "Reply", ext)?;
                     ext_unknown.push(u);
@@ -219,6 +243,8 @@ where
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_delete,
+            ext_trace,
             ext_unknown,
             payload,
         })
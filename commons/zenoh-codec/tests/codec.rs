@@ -19,7 +19,7 @@ use std::convert::TryFrom;
 use zenoh_buffers::{
     reader::{HasReader, Reader},
     writer::HasWriter,
-    BBuf, ZBuf, ZSlice,
+    BBuf, ZBuf, ZSlice, ZStr,
 };
 use zenoh_codec::*;
 use zenoh_protocol::{
@@ -271,6 +271,35 @@ fn codec_zslice_bounded() {
     assert_eq!(zslice, r_res.unwrap());
 }
 
+#[test]
+fn codec_zstr() {
+    run!(ZStr, ZStr::rand(thread_rng().gen_range(0..16)));
+}
+
+#[test]
+fn codec_zstr_bounded() {
+    use crate::Zenoh080Bounded;
+
+    let zstr = ZStr::rand(1 + u8::MAX as usize);
+
+    let zodec = Zenoh080Bounded::<u8>::new();
+    let codec = Zenoh080::new();
+
+    let mut buff = vec![];
+
+    let mut writer = buff.writer();
+    assert!(zodec.write(&mut writer, &zstr).is_err());
+    let mut writer = buff.writer();
+    codec.write(&mut writer, &zstr).unwrap();
+
+    let mut reader = buff.reader();
+    let r_res: Result<ZStr, _> = zodec.read(&mut reader);
+    assert!(r_res.is_err());
+    let mut reader = buff.reader();
+    let r_res: Result<ZStr, _> = codec.read(&mut reader);
+    assert_eq!(zstr, r_res.unwrap());
+}
+
 #[test]
 fn codec_zbuf() {
     run!(
@@ -311,8 +340,8 @@ fn codec_locator() {
 #[test]
 fn codec_timestamp() {
     run!(Timestamp, {
-        let time = uhlc::NTP64(thread_rng().gen());
-        let id = uhlc::ID::try_from(ZenohId::rand().to_le_bytes()).unwrap();
+        let time = NTP64(thread_rng().gen());
+        let id = TimestampId::try_from(ZenohId::rand().to_le_bytes()).unwrap();
         Timestamp::new(time, id)
     });
 }
@@ -546,6 +575,11 @@ fn codec_response_final() {
     run!(ResponseFinal, ResponseFinal::rand());
 }
 
+#[test]
+fn codec_request_cancel() {
+    run!(RequestCancel, RequestCancel::rand());
+}
+
 #[test]
 fn codec_network_oam() {
     run!(network::Oam, network::Oam::rand());
@@ -562,6 +596,30 @@ fn codec_put() {
     run!(zenoh::Put, zenoh::Put::rand());
 }
 
+#[test]
+fn codec_put_user_ext() {
+    let codec = Zenoh080::new();
+    let mut buff = vec![];
+
+    // Mandatory user-defined extensions are decoded...
+    let mut x = zenoh::Put::rand();
+    x.ext_unknown = vec![ZExtUnknown::new(iext::USER_ID_MIN, true, ZExtBody::rand())];
+    codec.write(&mut buff.writer(), &x).unwrap();
+    let y: zenoh::Put = codec.read(&mut buff.reader()).unwrap();
+    assert_eq!(x, y);
+
+    // ...while the other unknown mandatory extensions are not
+    buff.clear();
+    x.ext_unknown = vec![ZExtUnknown::new(
+        iext::USER_ID_MIN - 1,
+        true,
+        ZExtBody::rand(),
+    )];
+    codec.write(&mut buff.writer(), &x).unwrap();
+    let y: Result<zenoh::Put, _> = codec.read(&mut buff.reader());
+    assert!(y.is_err());
+}
+
 #[test]
 fn codec_del() {
     run!(zenoh::Del, zenoh::Del::rand());
@@ -572,6 +630,43 @@ fn codec_query() {
     run!(zenoh::Query, zenoh::Query::rand());
 }
 
+#[test]
+fn codec_zero_copy() {
+    let codec = Zenoh080::new();
+
+    let contains = |zslice: &ZSlice, s: &[u8]| {
+        let range = zslice.as_slice().as_ptr_range();
+        s.is_empty() || (range.contains(&s.as_ptr()) && s.as_ptr_range().end <= range.end)
+    };
+
+    // Payloads and parameters decoded from a ZSlice must borrow from it
+    for _ in 0..NUM_ITER {
+        let x = zenoh::Put::rand();
+        let mut buffer = vec![];
+        let mut writer = buffer.writer();
+        codec.write(&mut writer, &x).unwrap();
+
+        let zslice = ZSlice::from(buffer);
+        let mut cursor = zslice.clone();
+        let mut reader = cursor.reader();
+        let y: zenoh::Put = codec.read(&mut reader).unwrap();
+        assert_eq!(x, y);
+        assert!(y.payload.zslices().all(|s| contains(&zslice, s)));
+
+        let x = zenoh::Query::rand();
+        let mut buffer = vec![];
+        let mut writer = buffer.writer();
+        codec.write(&mut writer, &x).unwrap();
+
+        let zslice = ZSlice::from(buffer);
+        let mut cursor = zslice.clone();
+        let mut reader = cursor.reader();
+        let y: zenoh::Query = codec.read(&mut reader).unwrap();
+        assert_eq!(x, y);
+        assert!(contains(&zslice, y.parameters.as_bytes()));
+    }
+}
+
 #[test]
 fn codec_reply() {
     run!(zenoh::Reply, zenoh::Reply::rand());
@@ -0,0 +1,129 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Fixed wire encodings shared with other zenoh implementations (e.g. zenoh-pico).
+//!
+//! Each line of `vectors.txt` is `<name> <hex>`. Every message below must encode to
+//! exactly the bytes of its vector and decode back to itself. A failure here means
+//! the wire format changed and the vectors must be updated on every side.
+use std::collections::HashMap;
+use zenoh_buffers::{
+    reader::{HasReader, Reader},
+    writer::HasWriter,
+    ZBuf,
+};
+use zenoh_codec::*;
+use zenoh_protocol::{
+    core::{Encoding, WhatAmIMatcher, ZenohId},
+    network::{push, NetworkMessage, Push},
+    scouting::{Scout, ScoutingMessage},
+    transport::{close, Close, KeepAlive, TransportMessage},
+    zenoh::{PushBody, Put},
+};
+
+fn vectors() -> HashMap<&'static str, Vec<u8>> {
+    include_str!("vectors.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let (name, hex) = l.split_once(' ').unwrap();
+            let bytes = (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect();
+            (name, bytes)
+        })
+        .collect()
+}
+
+macro_rules! check {
+    ($vectors:expr, $name:literal, $type:ty, $msg:expr) => {
+        let codec = Zenoh080::new();
+        let x: $type = $msg;
+        let expected = $vectors
+            .get($name)
+            .unwrap_or_else(|| panic!("missing vector {}", $name));
+
+        let mut buff = vec![];
+        let mut writer = buff.writer();
+        codec.write(&mut writer, &x).unwrap();
+        assert_eq!(&buff, expected, "encoding of {}", $name);
+
+        let mut reader = expected.reader();
+        let y: $type = codec.read(&mut reader).unwrap();
+        assert_eq!(x, y, "decoding of {}", $name);
+        assert!(!reader.can_read());
+    };
+}
+
+#[test]
+fn codec_vectors() {
+    let vectors = vectors();
+    let zid = ZenohId::try_from([0x01, 0x02, 0x03, 0x04].as_slice()).unwrap();
+
+    check!(
+        vectors,
+        "scout",
+        ScoutingMessage,
+        Scout {
+            version: zenoh_protocol::VERSION,
+            what: WhatAmIMatcher::empty().router().peer(),
+            zid: Some(zid),
+        }
+        .into()
+    );
+    check!(
+        vectors,
+        "keep_alive",
+        TransportMessage,
+        KeepAlive::default().into()
+    );
+    check!(
+        vectors,
+        "close",
+        TransportMessage,
+        Close {
+            reason: close::reason::INVALID,
+            session: true,
+        }
+        .into()
+    );
+    check!(
+        vectors,
+        "push_put",
+        NetworkMessage,
+        Push {
+            wire_expr: "demo/example".into(),
+            ext_qos: push::ext::QoSType::default(),
+            ext_tstamp: None,
+            ext_nodeid: push::ext::NodeIdType::default(),
+            payload: PushBody::Put(Put {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                ext_coherence: None,
+                ext_ttl: None,
+                ext_latency_budget: None,
+                ext_attachment: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_trace: None,
+                ext_unknown: vec![],
+                payload: ZBuf::from(b"hello".to_vec()),
+            }),
+        }
+        .into()
+    );
+}
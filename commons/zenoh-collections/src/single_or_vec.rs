@@ -156,6 +156,15 @@ impl<T> SingleOrVec<T> {
             SingleOrVecInner::Vec(v) => v.last_mut(),
         }
     }
+
+    pub fn as_slice(&self) -> &[T] {
+        self.0.as_ref()
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.0.as_mut()
+    }
+
     pub fn drain<Range: RangeBounds<usize>>(&mut self, range: Range) -> Drain<T> {
         match &mut self.0 {
             this @ SingleOrVecInner::Single(_) if range.contains(&0) => Drain {
@@ -19,6 +19,7 @@ use crate::{
     },
     Config,
 };
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use zenoh_core::zparse_default;
 use zenoh_protocol::core::WhatAmI;
@@ -33,6 +34,10 @@ pub struct ConnectionRetryModeDependentConf {
     pub period_max_ms: Option<ModeDependentValue<i64>>,
     // increase factor for the next timeout until next try
     pub period_increase_factor: Option<ModeDependentValue<f64>>,
+    // fraction of the timeout by which it is randomly shortened or lengthened
+    pub period_jitter: Option<ModeDependentValue<f64>>,
+    // maximum number of tries, a negative value means no limit
+    pub max_attempts: Option<ModeDependentValue<i64>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -41,6 +46,8 @@ pub struct ConnectionRetryConf {
     pub period_init_ms: i64,
     pub period_max_ms: i64,
     pub period_increase_factor: f64,
+    pub period_jitter: f64,
+    pub max_attempts: i64,
 }
 
 impl ConnectionRetryConf {
@@ -64,6 +71,14 @@ impl ConnectionRetryConf {
                 .period_increase_factor
                 .get(whatami)
                 .unwrap_or(default_retry.period_increase_factor.get(whatami).unwrap()),
+            period_jitter: *retry
+                .period_jitter
+                .get(whatami)
+                .unwrap_or(default_retry.period_jitter.get(whatami).unwrap()),
+            max_attempts: *retry
+                .max_attempts
+                .get(whatami)
+                .unwrap_or(default_retry.max_attempts.get(whatami).unwrap()),
         }
     }
 
@@ -79,6 +94,7 @@ impl ConnectionRetryConf {
 pub struct ConnectionRetryPeriod {
     conf: ConnectionRetryConf,
     delay: i64,
+    retries: i64,
 }
 
 impl ConnectionRetryPeriod {
@@ -86,9 +102,15 @@ impl ConnectionRetryPeriod {
         ConnectionRetryPeriod {
             conf: conf.clone(),
             delay: conf.period_init_ms,
+            retries: 0,
         }
     }
 
+    // returns true if the try that just failed was the last one allowed
+    pub fn is_exhausted(&self) -> bool {
+        self.conf.max_attempts >= 0 && self.retries + 1 >= self.conf.max_attempts
+    }
+
     pub fn duration(&self) -> std::time::Duration {
         if self.conf.period_init_ms < 0 {
             return std::time::Duration::MAX;
@@ -102,7 +124,13 @@ impl ConnectionRetryPeriod {
     }
 
     pub fn next_duration(&mut self) -> std::time::Duration {
-        let res = self.duration();
+        let mut res = self.duration();
+        self.retries += 1;
+
+        let jitter = self.conf.period_jitter.clamp(0.0, 1.0);
+        if jitter > 0.0 && res != std::time::Duration::MAX {
+            res = res.mul_f64(1.0 + jitter * rand::thread_rng().gen_range(-1.0..=1.0));
+        }
 
         self.delay = (self.delay as f64 * self.conf.period_increase_factor) as i64;
         if self.conf.period_max_ms > 0 && self.delay > self.conf.period_max_ms {
@@ -195,6 +223,12 @@ pub fn get_retry_config(
         if let Some(val) = config.get("retry_period_increase_factor") {
             res.period_increase_factor = zparse_default!(val, res.period_increase_factor);
         }
+        if let Some(val) = config.get("retry_period_jitter") {
+            res.period_jitter = zparse_default!(val, res.period_jitter);
+        }
+        if let Some(val) = config.get("retry_max_attempts") {
+            res.max_attempts = zparse_default!(val, res.max_attempts);
+        }
     }
     res
 }
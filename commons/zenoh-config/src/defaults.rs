@@ -71,6 +71,9 @@ pub mod scouting {
             mode_accessor!(crate::WhatAmIMatcher);
         }
     }
+    pub mod nat_traversal {
+        pub const enabled: bool = false;
+    }
 }
 
 #[allow(non_upper_case_globals)]
@@ -94,10 +97,19 @@ pub const queries_default_timeout: u64 = 10000;
 pub mod routing {
     pub mod router {
         pub const peers_failover_brokering: bool = true;
+        pub mod cluster {
+            pub const enabled: bool = false;
+        }
     }
     pub mod peer {
         pub const mode: &str = "peer_to_peer";
     }
+    pub mod interests {
+        pub const enabled: bool = false;
+    }
+    pub mod query {
+        pub const selection: crate::QueryableSelectionConf = crate::QueryableSelectionConf::Nearest;
+    }
 }
 
 impl Default for TransportUnicastConf {
@@ -107,13 +119,37 @@ impl Default for TransportUnicastConf {
             accept_pending: 100,
             max_sessions: 1_000,
             max_links: 1,
+            multilink: MultilinkConf::default(),
             lowlatency: false,
+            checksum: false,
             qos: QoSUnicastConf::default(),
             compression: CompressionUnicastConf::default(),
         }
     }
 }
 
+#[allow(clippy::derivable_impls)]
+impl Default for MultilinkConf {
+    fn default() -> Self {
+        Self {
+            policy: MultilinkPolicyConf::default(),
+            links: vec![],
+            health: MultilinkHealthConf::default(),
+        }
+    }
+}
+
+impl Default for MultilinkHealthConf {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            probe_interval: 500,
+            max_lost_probes: 3,
+            max_rtt: None,
+        }
+    }
+}
+
 impl Default for TransportMulticastConf {
     fn default() -> Self {
         Self {
@@ -141,7 +177,11 @@ impl Default for QoSMulticastConf {
 #[allow(clippy::derivable_impls)]
 impl Default for CompressionUnicastConf {
     fn default() -> Self {
-        Self { enabled: false }
+        Self {
+            enabled: false,
+            algorithm: CompressionAlgorithmConf::default(),
+            threshold: 0,
+        }
     }
 }
 
@@ -162,6 +202,7 @@ impl Default for LinkTxConf {
             keep_alive: 4,
             batch_size: BatchSize::MAX,
             queue: QueueConf::default(),
+            dscp: DscpConf::default(),
             threads: num,
         }
     }
@@ -173,6 +214,17 @@ impl Default for QueueConf {
             size: QueueSizeConf::default(),
             congestion_control: CongestionControlConf::default(),
             backoff: 100,
+            batching: BatchingConf::default(),
+            pacing: PacingConf::default(),
+        }
+    }
+}
+
+impl Default for BatchingConf {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_latency: 1000,
         }
     }
 }
@@ -197,10 +249,33 @@ impl Default for QueueSizeConf {
     }
 }
 
+impl DscpConf {
+    pub const MAX: u8 = 63;
+}
+
+// Classes of service of RFC 4594
+impl Default for DscpConf {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            control: 48,
+            real_time: 46,
+            interactive_high: 34,
+            interactive_low: 26,
+            data_high: 18,
+            data: 0,
+            data_low: 10,
+            background: 8,
+        }
+    }
+}
+
 impl Default for CongestionControlConf {
     fn default() -> Self {
         Self {
             wait_before_drop: 1000,
+            drop_first_queue_size: 16,
+            adaptive_wait_before_drop: 100000,
         }
     }
 }
@@ -210,6 +285,7 @@ impl Default for LinkRxConf {
         Self {
             buffer_size: BatchSize::MAX as usize,
             max_message_size: 2_usize.pow(30),
+            max_peer_reassembly_size: 2_usize.pow(31),
         }
     }
 }
@@ -227,6 +303,7 @@ impl Default for AclConfig {
         Self {
             enabled: false,
             default_permission: Permission::Deny,
+            deny_overrides: false,
             rules: None,
         }
     }
@@ -255,6 +332,8 @@ impl Default for ConnectionRetryModeDependentConf {
             period_init_ms: Some(ModeDependentValue::Unique(1000)),
             period_max_ms: Some(ModeDependentValue::Unique(4000)),
             period_increase_factor: Some(ModeDependentValue::Unique(2.)),
+            period_jitter: Some(ModeDependentValue::Unique(0.)),
+            max_attempts: Some(ModeDependentValue::Unique(-1)),
         }
     }
 }
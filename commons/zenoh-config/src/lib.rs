@@ -38,7 +38,7 @@ pub use zenoh_protocol::core::{
     whatami, EndPoint, Locator, Priority, WhatAmI, WhatAmIMatcher, WhatAmIMatcherVisitor, ZenohId,
 };
 use zenoh_protocol::{
-    core::{key_expr::OwnedKeyExpr, Bits},
+    core::{key_expr::OwnedKeyExpr, Bits, CongestionControl},
     transport::{BatchSize, TransportSn},
 };
 use zenoh_result::{bail, zerror, ZResult};
@@ -100,15 +100,187 @@ pub struct DownsamplingItemConf {
     pub flow: InterceptorFlow,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BandwidthQuotaRuleConf {
+    /// The key expression whose matching publications share the quota.
+    pub key_expr: OwnedKeyExpr,
+    /// The maximum number of payload bytes sent per second.
+    pub bytes_per_sec: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BandwidthQuotaItemConf {
+    /// A list of interfaces on which the quotas will be enforced.
+    /// Quotas will be enforced on all interfaces if the parameter is None
+    pub interfaces: Option<Vec<String>>,
+    /// A list of quota rules, the first rule matching a key expression applies.
+    pub rules: Vec<BandwidthQuotaRuleConf>,
+}
+
+/// How a router or peer selects the queryable receiving a query that targets the best matching one.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryableSelectionConf {
+    /// The nearest complete queryable.
+    #[default]
+    Nearest,
+    /// Each complete queryable in turn.
+    RoundRobin,
+    /// The complete queryable reached through the best ranked node of `routing.query.ranking`.
+    Ranked,
+}
+
+/// How a transport with several links spreads the messages over them.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MultilinkPolicyConf {
+    /// All the messages take the active link, the others are kept in standby until it fails.
+    #[default]
+    ActiveStandby,
+    /// The priorities are spread over the links in turn.
+    RoundRobin,
+    /// The messages take the link with the lowest latency.
+    LowestLatency,
+    /// The messages take the link their priority is pinned to, if any.
+    Priority,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithmConf {
+    /// Fast compression with a moderate ratio.
+    #[default]
+    Lz4,
+    /// Slower compression with a higher ratio, better suited to bandwidth-constrained links.
+    Zstd,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MultilinkLinkConf {
+    /// The link interfaces matching this entry, any interface if None.
+    pub interfaces: Option<Vec<String>>,
+    /// The link protocols matching this entry, any protocol if None.
+    pub protocols: Option<Vec<String>>,
+    /// The priorities pinned to the matching links by the `priority` policy.
+    #[serde(default)]
+    pub priorities: Vec<PriorityConf>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityConf {
+    Control,
+    RealTime,
+    InteractiveHigh,
+    InteractiveLow,
+    DataHigh,
+    Data,
+    DataLow,
+    Background,
+}
+
+impl From<PriorityConf> for Priority {
+    fn from(priority: PriorityConf) -> Self {
+        match priority {
+            PriorityConf::Control => Priority::Control,
+            PriorityConf::RealTime => Priority::RealTime,
+            PriorityConf::InteractiveHigh => Priority::InteractiveHigh,
+            PriorityConf::InteractiveLow => Priority::InteractiveLow,
+            PriorityConf::DataHigh => Priority::DataHigh,
+            PriorityConf::Data => Priority::Data,
+            PriorityConf::DataLow => Priority::DataLow,
+            PriorityConf::Background => Priority::Background,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PriorityRemappingRuleConf {
+    /// The priority of the incoming messages to remap.
+    pub from: PriorityConf,
+    /// The priority these messages are given instead.
+    pub to: PriorityConf,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PriorityRemappingItemConf {
+    /// A list of interfaces on which the incoming messages will be remapped.
+    /// Remapping will be applied for all interfaces if the parameter is None
+    pub interfaces: Option<Vec<String>>,
+    /// A list of remapping rules, applied before clamping.
+    #[serde(default)]
+    pub rules: Vec<PriorityRemappingRuleConf>,
+    /// The highest priority allowed for incoming messages, higher priorities are lowered to it.
+    pub max: Option<PriorityConf>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionControlModeConf {
+    Drop,
+    Block,
+    DropFirst,
+    Adaptive,
+}
+
+impl From<CongestionControlModeConf> for CongestionControl {
+    fn from(mode: CongestionControlModeConf) -> Self {
+        match mode {
+            CongestionControlModeConf::Drop => CongestionControl::Drop,
+            CongestionControlModeConf::Block => CongestionControl::Block,
+            CongestionControlModeConf::DropFirst => CongestionControl::DropFirst,
+            CongestionControlModeConf::Adaptive => CongestionControl::Adaptive,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct QosOverwriteConf {
+    /// The priority given to the matching messages.
+    pub priority: Option<PriorityConf>,
+    /// The congestion control given to the matching messages.
+    pub congestion_control: Option<CongestionControlModeConf>,
+    /// The express flag given to the matching messages.
+    pub express: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct QosOverwriteItemConf {
+    /// A list of interfaces on which the QoS of the messages will be overwritten.
+    /// Overwriting will be applied for all interfaces if the parameter is None
+    pub interfaces: Option<Vec<String>>,
+    /// A list of key expressions whose matching messages will be overwritten.
+    pub key_exprs: Vec<OwnedKeyExpr>,
+    /// The QoS given to the matching messages, the unset fields are kept as is.
+    pub overwrite: QosOverwriteConf,
+    /// QoS overwrite flow direction: egress, ingress
+    pub flow: InterceptorFlow,
+}
+
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub struct AclConfigRules {
     pub interfaces: Option<Vec<String>>,
+    pub cert_common_names: Option<Vec<String>>,
+    pub cert_subject_alt_names: Option<Vec<String>>,
+    pub usernames: Option<Vec<String>>,
+    pub token_claims: Option<Vec<String>>,
+    pub zids: Option<Vec<ZenohId>>,
     pub key_exprs: Vec<String>,
     pub actions: Vec<Action>,
     pub flows: Option<Vec<InterceptorFlow>>,
     pub permission: Permission,
 }
 
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct AdminSpaceOperatorConf {
+    /// The name of the operator, sent along with its signed admin space writes.
+    pub id: String,
+    /// The Ed25519 public key of the operator, base64 encoded.
+    pub public_key: String,
+}
+
 #[derive(Clone, Serialize, Debug, Deserialize)]
 pub struct PolicyRule {
     pub subject: Subject,
@@ -123,6 +295,11 @@ pub struct PolicyRule {
 #[serde(rename_all = "snake_case")]
 pub enum Subject {
     Interface(String),
+    CertCommonName(String),
+    CertSubjectAltName(String),
+    Username(String),
+    TokenClaim(String),
+    Zid(ZenohId),
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
@@ -252,9 +429,10 @@ validated_struct::validator! {
                 enabled: Option<bool>,
                 /// The socket which should be used for multicast scouting. `zenohd` will use `224.0.0.224:7446` by default if none is provided.
                 address: Option<SocketAddr>,
-                /// The network interface which should be used for multicast scouting. `zenohd` will automatically select an interface if none is provided.
+                /// The network interfaces which should be used for multicast scouting, as a comma separated list of names or addresses.
+                /// `zenohd` will automatically select an interface if none is provided.
                 interface: Option<String>,
-                /// The time-to-live on multicast scouting packets. (default: 1)
+                /// The time-to-live on multicast scouting packets, or their hop limit on IPv6. (default: 1)
                 pub ttl: Option<u32>,
                 /// Which type of Zenoh instances to automatically establish sessions with upon discovery through UDP multicast.
                 #[serde(deserialize_with = "treat_error_as_none")]
@@ -277,6 +455,21 @@ validated_struct::validator! {
                 #[serde(deserialize_with = "treat_error_as_none")]
                 autoconnect: Option<ModeDependentValue<WhatAmIMatcher>>,
             },
+            /// The NAT traversal configuration. A router reachable by the peers, the rendezvous,
+            /// coordinates the UDP hole punching between the peers discovered through gossip
+            /// that can not connect to each other directly. The peers keep communicating through
+            /// the router if the hole punching fails.
+            pub nat_traversal: #[derive(Default)]
+            NatTraversalConf {
+                /// Whether NAT traversal is enabled or not (default `false`). Routers act as rendezvous
+                /// on their UDP listeners, peers register their UDP listeners with their rendezvous.
+                enabled: Option<bool>,
+                /// In peer mode, the UDP endpoint of the rendezvous router.
+                rendezvous: Option<EndPoint>,
+                /// The secret shared by the rendezvous and its peers, authenticating their NAT
+                /// traversal messages. Required when NAT traversal is enabled.
+                secret: Option<String>,
+            },
         },
 
         /// Configuration of data messages timestamps management.
@@ -304,6 +497,26 @@ validated_struct::validator! {
                 /// connected to each other.
                 /// The failover brokering only works if gossip discovery is enabled.
                 peers_failover_brokering: Option<bool>,
+                /// The cluster configuration of routers.
+                /// Routers of a same cluster share their declarations through the routers
+                /// linkstate network, and retain the last publications on the same key
+                /// expressions, so that clients can connect to any of them interchangeably.
+                pub cluster: #[derive(Default)]
+                RouterClusterConf {
+                    /// Whether this router is part of a cluster (default `false`).
+                    enabled: Option<bool>,
+                    /// The name of the cluster, reported in the admin space.
+                    name: Option<String>,
+                    /// The Zenoh IDs of all the routers of the cluster (including this one).
+                    members: Vec<ZenohId>,
+                    /// The minimum number of reachable members (including this one) for this router
+                    /// to consider it is part of the active partition of the cluster.
+                    /// If left unset, a strict majority of the members is required.
+                    quorum: Option<usize>,
+                    /// The key expressions on which the last publication of each key is retained
+                    /// by all the members and replied to the queries, as long as the quorum is reached.
+                    retained: Vec<OwnedKeyExpr>,
+                },
             },
             /// The routing strategy to use in peers and it's configuration.
             pub peer: #[derive(Default)]
@@ -311,6 +524,31 @@ validated_struct::validator! {
                 /// The routing strategy to use in peers. ("peer_to_peer" or "linkstate").
                 mode: Option<String>,
             },
+            /// The interest-based propagation of the declarations.
+            pub interests: #[derive(Default)]
+            InterestsRoutingConf {
+                /// When enabled on a router, the subscribers and queryables are only declared
+                /// to its clients for the key expressions they declared an interest in,
+                /// instead of all of them (default `false`).
+                /// When enabled on a client, its publishers declare an interest in their key
+                /// expression, and the data and queries for the key expressions without
+                /// interest are sent to its routers.
+                /// The clients which declare no interest, e.g. with interests disabled, still
+                /// receive all the declarations, so that routers and clients can enable it
+                /// in any order.
+                enabled: Option<bool>,
+            },
+            /// The routing of the queries.
+            pub query: #[derive(Default)]
+            QueryRoutingConf {
+                /// The selection of the queryable receiving a query that targets the best
+                /// matching queryable ("nearest", "round_robin" or "ranked").
+                selection: Option<QueryableSelectionConf>,
+                /// The Zenoh IDs of the nodes to forward the queries to, from the most preferred,
+                /// used by the "ranked" selection. The queryables reached through other nodes
+                /// are only selected if none of them is reachable through the listed nodes.
+                ranking: Vec<ZenohId>,
+            },
         },
 
         /// The declarations aggregation strategy.
@@ -332,10 +570,34 @@ validated_struct::validator! {
                 max_sessions: usize,
                 /// Maximum number of unicast incoming links per transport session (default: 1)
                 max_links: usize,
+                pub multilink: MultilinkConf {
+                    /// How the messages are spread over the links of a transport session with several links:
+                    /// "active_standby", "round_robin", "lowest_latency" or "priority" (default "active_standby").
+                    policy: MultilinkPolicyConf,
+                    /// The links in order of preference: the active link of the "active_standby" and "priority"
+                    /// policies is the first one matching an entry, and the "priority" policy pins the priorities
+                    /// of an entry to its first matching link. Links matching no entry come last.
+                    links: Vec<MultilinkLinkConf>,
+                    pub health: MultilinkHealthConf {
+                        /// Whether the links are probed to migrate the messages off the degraded ones
+                        /// before their lease expires (default `false`).
+                        enabled: bool,
+                        /// Interval in milliseconds between two probes of a link (default: 500).
+                        probe_interval: u64,
+                        /// Number of consecutive unanswered probes after which a link is degraded (default: 3).
+                        max_lost_probes: u32,
+                        /// Round trip time in milliseconds above which a link is degraded (default: none).
+                        max_rtt: Option<u64>,
+                    },
+                },
                 /// Enables the LowLatency transport (default `false`).
                 /// This option does not make LowLatency transport mandatory, the actual implementation of transport
                 /// used will depend on Establish procedure and other party's settings
                 lowlatency: bool,
+                /// Enables a CRC32C checksum on the batches sent on the links (default `false`).
+                /// The checksum is used only if the other party enables it as well. Batches whose
+                /// checksum does not match are dropped instead of closing the link.
+                checksum: bool,
                 pub qos: QoSUnicastConf {
                     /// Whether QoS is enabled or not.
                     /// If set to `false`, the QoS will be disabled. (default `true`).
@@ -345,6 +607,10 @@ validated_struct::validator! {
                     /// You must compile zenoh with "transport_compression" feature to be able to enable compression.
                     /// When enabled is true, batches will be sent compressed. (default `false`).
                     enabled: bool,
+                    /// The algorithm used to compress the batches, if supported by the remote node. (default `lz4`).
+                    algorithm: CompressionAlgorithmConf,
+                    /// The minimum size in bytes of a batch to be compressed. (default `0`).
+                    threshold: usize,
                 },
             },
             pub multicast: TransportMulticastConf {
@@ -371,6 +637,7 @@ validated_struct::validator! {
                 pub tx: LinkTxConf {
                     /// The resolution in bits to be used for the message sequence numbers.
                     /// When establishing a session with another Zenoh instance, the lowest value of the two instances will be used.
+                    /// It can be overridden for the links opened to an endpoint with its `sn_resolution` parameter.
                     /// Accepted values: 8bit, 16bit, 32bit, 64bit.
                     sequence_number_resolution: Bits where (sequence_number_resolution_validator),
                     /// Link lease duration in milliseconds (default: 10000)
@@ -378,6 +645,7 @@ validated_struct::validator! {
                     /// Number fo keep-alive messages in a link lease duration (default: 4)
                     keep_alive: usize,
                     /// Zenoh's MTU equivalent (default: 2^16-1)
+                    /// It can be overridden for the links opened to an endpoint with its `batch_size` parameter.
                     batch_size: BatchSize,
                     pub queue: QueueConf {
                         /// The size of each priority queue indicates the number of batches a given queue can contain.
@@ -398,14 +666,61 @@ validated_struct::validator! {
                         /// Congestion occurs when the queue is empty (no available batch).
                         /// Using CongestionControl::Block the caller is blocked until a batch is available and re-insterted into the queue.
                         /// Using CongestionControl::Drop the message might be dropped, depending on conditions configured here.
+                        /// Using CongestionControl::DropFirst the message is kept aside, dropping the oldest messages kept aside if needed.
+                        /// Using CongestionControl::Adaptive the caller is blocked, until the congestion lasts too long and the messages start being dropped.
                         pub congestion_control: CongestionControlConf {
                             /// The maximum time in microseconds to wait for an available batch before dropping the message if still no batch is available.
                             pub wait_before_drop: u64,
+                            /// The maximum number of messages using CongestionControl::DropFirst kept aside while no batch is available.
+                            pub drop_first_queue_size: usize,
+                            /// The maximum time in microseconds to wait for an available batch before dropping a message using CongestionControl::Adaptive.
+                            /// Once a message was dropped, the next ones wait at most `wait_before_drop` until the congestion is resolved.
+                            pub adaptive_wait_before_drop: u64,
                         },
                         /// The initial exponential backoff time in nanoseconds to allow the batching to eventually progress.
                         /// Higher values lead to a more aggressive batching but it will introduce additional latency.
                         backoff: u64,
+                        /// The batches are sent as soon as they are full, or once they held messages for the latency budget.
+                        /// In between, the batching waits for more messages only while the throughput observed on the batch
+                        /// is high enough to fill it up within the budget, otherwise the batch is sent right away.
+                        pub batching: BatchingConf {
+                            /// Whether the adaptive batching is enabled. If disabled, the batches are sent as soon as
+                            /// no new message is being added to them.
+                            enabled: bool,
+                            /// The maximum time in microseconds a message waits in a batch before being sent.
+                            max_latency: u64,
+                        },
+                        /// The rate in bytes per second at which the fragments of the messages too large for a batch
+                        /// are sent, for each priority. Pacing large messages keeps them from introducing latency
+                        /// spikes for the messages of the other priorities sharing the link.
+                        /// The fragments are sent as fast as possible for the priorities without a rate (default).
+                        pub pacing: #[derive(Default)]
+                        PacingConf {
+                            control: Option<u64> where (pacing_validator),
+                            real_time: Option<u64> where (pacing_validator),
+                            interactive_high: Option<u64> where (pacing_validator),
+                            interactive_low: Option<u64> where (pacing_validator),
+                            data_high: Option<u64> where (pacing_validator),
+                            data: Option<u64> where (pacing_validator),
+                            data_low: Option<u64> where (pacing_validator),
+                            background: Option<u64> where (pacing_validator),
+                        },
                     },
+                    /// The DSCP of the IP packets sent on the TCP and UDP links for each priority,
+                    /// so that the network equipment can honor the priorities. The DSCP is a 6-bit value.
+                    /// If qos is false, then the DSCP of the DATA priority is used for all the packets.
+                    pub dscp: DscpConf {
+                        /// Whether the IP packets are marked or not (default `false`).
+                        enabled: bool,
+                        control: u8,
+                        real_time: u8,
+                        interactive_high: u8,
+                        interactive_low: u8,
+                        data_high: u8,
+                        data: u8,
+                        data_low: u8,
+                        background: u8,
+                    } where (dscp_validator),
                     // Number of threads used for TX
                     threads: usize,
                 },
@@ -419,6 +734,31 @@ validated_struct::validator! {
                     /// Maximum size of the defragmentation buffer at receiver end (default: 1GiB).
                     /// Fragmented messages that are larger than the configured size will be dropped.
                     max_message_size: usize,
+                    /// Maximum memory in bytes used to reassemble the fragmented messages received from
+                    /// a same peer (default: 2GiB). A fragmented message that would exceed it is dropped.
+                    max_peer_reassembly_size: usize,
+                },
+                /// The socket options of the TCP links. The unset options keep the system defaults.
+                pub tcp: #[derive(Default)]
+                TcpConf {
+                    /// The idle time in seconds before the first keepalive probe is sent.
+                    /// Keepalive is enabled if any of the keepalive options is set.
+                    keepalive_time: Option<u64>,
+                    /// The time in seconds between two keepalive probes.
+                    keepalive_interval: Option<u64>,
+                    /// The number of unanswered keepalive probes before the connection is dropped.
+                    keepalive_probes: Option<u32>,
+                    /// The time in milliseconds transmitted data may stay unacknowledged before
+                    /// the connection is dropped (Linux only).
+                    user_timeout: Option<u64>,
+                    /// Whether Nagle's algorithm is disabled (default `true`).
+                    nodelay: Option<bool>,
+                    /// The size in bytes of the socket send buffer.
+                    so_sndbuf: Option<u32>,
+                    /// The size in bytes of the socket receive buffer.
+                    so_rcvbuf: Option<u32>,
+                    /// The network interface the sockets are bound to.
+                    iface: Option<String>,
                 },
                 pub tls: #[derive(Default)]
                 TLSConf {
@@ -429,6 +769,16 @@ validated_struct::validator! {
                     client_private_key: Option<String>,
                     client_certificate: Option<String>,
                     server_name_verification: Option<bool>,
+                    /// Path to the certificate revocation lists used to check the revocation status of peer certificates.
+                    crl: Option<String>,
+                    /// Path to the DER-encoded OCSP response stapled to the TLS server certificate.
+                    server_ocsp_response: Option<String>,
+                    /// Interval in milliseconds at which listeners check their certificate files for changes (0 disables reloading).
+                    reload_interval: Option<u64>,
+                    /// URI of the TLS server private key held by a registered key provider (e.g. a PKCS#11 token or a TPM).
+                    server_private_key_uri: Option<String>,
+                    /// URI of the TLS client private key held by a registered key provider (e.g. a PKCS#11 token or a TPM).
+                    client_private_key_uri: Option<String>,
                     // Skip serializing field because they contain secrets
                     #[serde(skip_serializing)]
                     root_ca_certificate_base64: Option<SecretValue>,
@@ -462,7 +812,12 @@ validated_struct::validator! {
                     user: Option<String>,
                     password: Option<String>,
                     /// The path to a file containing the user password dictionary, a file containing `<user>:<password>`
+                    /// entries where the password is either in plaintext or an argon2 (PHC string) or bcrypt salted hash.
+                    /// The file is reloaded when it is modified.
                     dictionary_file: Option<String>,
+                    /// Whether to send the password itself instead of a challenge response to remotes storing
+                    /// salted password hashes. It is only ever sent over encrypted links (TLS, QUIC, ...). (default `false`).
+                    cleartext: Option<bool>,
                 } where (user_conf_validator),
                 pub pubkey: #[derive(Default)]
                 PubKeyConf {
@@ -473,9 +828,44 @@ validated_struct::validator! {
                     key_size: Option<usize>,
                     known_keys_file: Option<String>,
                 },
+                /// The configuration of the authentication with signed tokens (JWT).
+                /// Clients present a token, routers verify its signature and its claims.
+                pub token: #[derive(Default)]
+                TokenConf {
+                    /// The token presented when opening a session, only sent over encrypted links.
+                    token: Option<String>,
+                    /// The path to a file containing the token presented when opening a session.
+                    token_file: Option<String>,
+                    /// The shared secret used to verify the tokens signed with HS256.
+                    secret: Option<String>,
+                    /// The path to the PEM RSA public key used to verify the tokens signed with RS256.
+                    public_key_file: Option<String>,
+                    /// The expected `iss` claim of the tokens, if any.
+                    issuer: Option<String>,
+                    /// The expected `aud` claim of the tokens, if any.
+                    audience: Option<String>,
+                } where (token_conf_validator),
             },
 
         },
+        /// Configuration of the global memory budget.
+        pub memory: #[derive(Default)]
+        MemoryConf {
+            /// The maximum amount of memory in bytes that zenoh may use for its buffers, handler queues,
+            /// pending queries and routing tables. When approaching this budget, pending queries are refused
+            /// first, then the handler queues of new subscribers, queryables and queries, then the reassembly
+            /// of fragmented messages, and finally the buffers of new links. The routing tables are accounted
+            /// but never refused. No budget is enforced if unset.
+            budget: Option<usize>,
+            /// Soft limits in bytes of each of the accounted subsystems. Unset limits default to the budget.
+            pub limits: #[derive(Default)]
+            MemoryLimitsConf {
+                pending_queries: Option<usize>,
+                handler_queues: Option<usize>,
+                defragmentation: Option<usize>,
+                rx_buffers: Option<usize>,
+            },
+        },
         /// Configuration of the admin space.
         pub adminspace: #[derive(Default)]
         /// <div class="stab unstable">
@@ -496,6 +886,10 @@ validated_struct::validator! {
                 /// Whether the admin space accepts config changes at runtime (false by default).
                 #[serde(default = "set_false")]
                 pub write: bool,
+                /// The operators allowed to write to the admin space. If set, the writes are only
+                /// accepted when signed by one of them, see `zenoh::adminspace::AdminOperator`.
+                #[serde(default)]
+                pub operators: Option<Vec<AdminSpaceOperatorConf>>,
             },
 
         },
@@ -503,10 +897,30 @@ validated_struct::validator! {
         /// Configuration of the downsampling.
         downsampling: Vec<DownsamplingItemConf>,
 
+        /// Configuration of the bandwidth quotas of outgoing publications.
+        bandwidth_quota: Vec<BandwidthQuotaItemConf>,
+
+        /// Configuration of the priority remapping of incoming messages.
+        priority_remapping: Vec<PriorityRemappingItemConf>,
+
+        /// Configuration of the QoS overwrite of the data messages matching key expressions.
+        qos_overwrite: Vec<QosOverwriteItemConf>,
+
+        /// Configuration of the latency budget enforcement on incoming data messages.
+        pub latency_budget: #[derive(Default)]
+        LatencyBudgetConf {
+            /// Whether the data messages received after their latency budget elapsed are dropped (false by default).
+            enabled: Option<bool>,
+        },
+
         ///Configuration of the access control (ACL)
         pub access_control: AclConfig {
             pub enabled: bool,
             pub default_permission: Permission,
+            /// Whether a deny from any subject of a remote prevails over an allow from another one
+            /// (false by default: an allow from any subject prevails).
+            #[serde(default = "set_false")]
+            pub deny_overrides: bool,
             pub rules: Option<Vec<AclConfigRules>>
         },
 
@@ -530,6 +944,7 @@ impl Default for PermissionsConf {
         PermissionsConf {
             read: true,
             write: false,
+            operators: None,
         }
     }
 }
@@ -971,10 +1386,45 @@ fn queue_size_validator(q: &QueueSizeConf) -> bool {
         && check(background)
 }
 
+fn pacing_validator(rate: &Option<u64>) -> bool {
+    *rate != Some(0)
+}
+
+fn dscp_validator(d: &DscpConf) -> bool {
+    fn check(dscp: &u8) -> bool {
+        *dscp <= DscpConf::MAX
+    }
+
+    let DscpConf {
+        enabled: _,
+        control,
+        real_time,
+        interactive_high,
+        interactive_low,
+        data_high,
+        data,
+        data_low,
+        background,
+    } = d;
+    check(control)
+        && check(real_time)
+        && check(interactive_high)
+        && check(interactive_low)
+        && check(data_high)
+        && check(data)
+        && check(data_low)
+        && check(background)
+}
+
 fn user_conf_validator(u: &UsrPwdConf) -> bool {
     (u.password().is_none() && u.user().is_none()) || (u.password().is_some() && u.user().is_some())
 }
 
+fn token_conf_validator(t: &TokenConf) -> bool {
+    (t.token().is_none() || t.token_file().is_none())
+        && (t.secret().is_none() || t.public_key_file().is_none())
+}
+
 /// This part of the configuration is highly dynamic (any [`serde_json::Value`] may be put in there), but should follow this scheme:
 /// ```javascript
 /// plugins: {
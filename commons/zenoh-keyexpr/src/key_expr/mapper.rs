@@ -0,0 +1,400 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! # Rewriting Key Expressions
+//! Bridges between zenoh and other protocols (or between zenoh domains) commonly need to translate
+//! key expressions from one key space to another, and back. [`KeyMapper`] compiles a list of
+//! [`KeyMapRule`]s for that purpose, which then rewrite key expressions chunk by chunk.
+//!
+//! ## The rule syntax
+//! A rule is written `<from> -> <to>`, where both sides are `/`-separated lists of chunks of one of the following kinds:
+//! - verbatim chunks, such as `dds` or `@ros2`, which must be non-wild key expression chunks;
+//! - capture chunks, written `<id>`, which capture exactly one chunk of the rewritten key expression;
+//! - at most one `**` chunk, which captures any number of (non-verbatim) chunks.
+//!
+//! Both sides must contain the same captures (and `**` if any), each exactly once, which makes every rule reversible:
+//! [`KeyMapper::map`] rewrites key expressions from `<from>` to `<to>`, and [`KeyMapper::unmap`] from `<to>` to `<from>`.
+//!
+//! Wildcards of the rewritten key expression are preserved whenever they are captured. A wild chunk facing a verbatim chunk
+//! of a rule is restricted to that chunk, while a `**` facing anything else than the `**` of a rule prevents the mapping.
+//! The results are canonized.
+//!
+//! ```
+//! # use zenoh_keyexpr::{keyexpr, mapper::KeyMapper};
+//! let mapper = KeyMapper::new(["dds/<topic>/** -> ros/<topic>/**"]).unwrap();
+//! let ke = keyexpr::new("dds/chatter/a/b").unwrap();
+//! assert_eq!(mapper.map(ke).unwrap().as_str(), "ros/chatter/a/b");
+//! let ke = keyexpr::new("ros/*/**").unwrap();
+//! assert_eq!(mapper.unmap(ke).unwrap().as_str(), "dds/*/**");
+//! ```
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    convert::TryFrom,
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use zenoh_result::{bail, Error, ZResult};
+
+use super::{keyexpr, OwnedKeyExpr, DOUBLE_WILD};
+
+const ARROW: &str = "->";
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Chunk {
+    Verbatim(OwnedKeyExpr),
+    Capture(String),
+    Rest,
+}
+
+impl Display for Chunk {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Chunk::Verbatim(ke) => write!(f, "{ke}"),
+            Chunk::Capture(id) => write!(f, "<{id}>"),
+            Chunk::Rest => write!(f, "**"),
+        }
+    }
+}
+
+/// One side of a [`KeyMapRule`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Pattern {
+    chunks: Vec<Chunk>,
+}
+
+impl Pattern {
+    fn new(s: &str) -> ZResult<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            bail!("Empty key mapping pattern")
+        }
+        let mut chunks = Vec::new();
+        for chunk in s.split('/') {
+            let chunk = if let Some(id) = chunk.strip_prefix('<').and_then(|c| c.strip_suffix('>'))
+            {
+                if id.is_empty() || !id.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    bail!("Invalid capture `{chunk}` in key mapping pattern `{s}`")
+                }
+                if chunks.contains(&Chunk::Capture(id.to_string())) {
+                    bail!("Capture `{chunk}` appears more than once in key mapping pattern `{s}`")
+                }
+                Chunk::Capture(id.to_string())
+            } else if chunk.as_bytes() == DOUBLE_WILD {
+                if chunks.contains(&Chunk::Rest) {
+                    bail!("`**` appears more than once in key mapping pattern `{s}`")
+                }
+                Chunk::Rest
+            } else {
+                let ke = match keyexpr::new(chunk) {
+                    Ok(ke) => ke,
+                    Err(e) => bail!("Invalid key mapping pattern `{s}`: {e}"),
+                };
+                if ke.is_wild() {
+                    bail!("Wild chunk `{chunk}` in key mapping pattern `{s}`: use `<id>` or `**` to capture chunks")
+                }
+                Chunk::Verbatim(ke.into())
+            };
+            chunks.push(chunk);
+        }
+        Ok(Pattern { chunks })
+    }
+
+    fn has_same_captures(&self, other: &Self) -> bool {
+        let is_capture = |c: &&Chunk| !matches!(c, Chunk::Verbatim(_));
+        self.chunks.iter().filter(is_capture).count()
+            == other.chunks.iter().filter(is_capture).count()
+            && self
+                .chunks
+                .iter()
+                .filter(is_capture)
+                .all(|c| other.chunks.contains(c))
+    }
+
+    /// Matches `ke` against this pattern, returning the chunks it captured.
+    fn captures<'a>(&'a self, ke: &'a keyexpr) -> Option<Captures<'a>> {
+        fn is_verbatim(chunk: &keyexpr) -> bool {
+            chunk.as_bytes().first() == Some(&b'@')
+        }
+        fn is_double_wild(chunk: &keyexpr) -> bool {
+            chunk.as_bytes() == DOUBLE_WILD
+        }
+        fn capture<'a>(
+            pattern: &'a Chunk,
+            chunk: &'a keyexpr,
+            captures: &mut Captures<'a>,
+        ) -> bool {
+            match pattern {
+                Chunk::Verbatim(verbatim) => chunk.intersects(verbatim),
+                Chunk::Capture(id) => {
+                    captures.chunks.push((id.as_str(), chunk));
+                    !is_double_wild(chunk) && !is_verbatim(chunk)
+                }
+                Chunk::Rest => unreachable!(),
+            }
+        }
+
+        let input: Vec<&keyexpr> = ke.chunks().collect();
+        let mut captures = Captures {
+            chunks: Vec::new(),
+            rest: Vec::new(),
+        };
+        match self.chunks.iter().position(|c| *c == Chunk::Rest) {
+            None => {
+                if input.len() != self.chunks.len() {
+                    return None;
+                }
+                for (pattern, chunk) in self.chunks.iter().zip(input) {
+                    if !capture(pattern, chunk, &mut captures) {
+                        return None;
+                    }
+                }
+            }
+            Some(rest) => {
+                let (prefix, suffix) = (&self.chunks[..rest], &self.chunks[rest + 1..]);
+                if input.len() < prefix.len() + suffix.len() {
+                    return None;
+                }
+                let rest_end = input.len() - suffix.len();
+                for (pattern, chunk) in prefix.iter().zip(&input[..rest]) {
+                    if !capture(pattern, chunk, &mut captures) {
+                        return None;
+                    }
+                }
+                for (pattern, chunk) in suffix.iter().zip(&input[rest_end..]) {
+                    if !capture(pattern, chunk, &mut captures) {
+                        return None;
+                    }
+                }
+                captures.rest = input[rest..rest_end].to_vec();
+                if captures.rest.iter().any(|chunk| is_verbatim(chunk)) {
+                    return None;
+                }
+            }
+        }
+        Some(captures)
+    }
+
+    /// Builds the key expression described by this pattern, using `captures` to fill its captures.
+    fn build(&self, captures: &Captures) -> Option<OwnedKeyExpr> {
+        let mut result = String::new();
+        let mut push = |chunk: &str| {
+            if !result.is_empty() {
+                result.push('/');
+            }
+            result.push_str(chunk);
+        };
+        for chunk in &self.chunks {
+            match chunk {
+                Chunk::Verbatim(verbatim) => push(verbatim),
+                Chunk::Capture(id) => push(captures.get(id)?),
+                Chunk::Rest => captures.rest.iter().for_each(|chunk| push(chunk)),
+            }
+        }
+        OwnedKeyExpr::autocanonize(result).ok()
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{chunk}")?;
+        }
+        Ok(())
+    }
+}
+
+struct Captures<'a> {
+    chunks: Vec<(&'a str, &'a keyexpr)>,
+    rest: Vec<&'a keyexpr>,
+}
+
+impl<'a> Captures<'a> {
+    fn get(&self, id: &str) -> Option<&'a keyexpr> {
+        self.chunks
+            .iter()
+            .find_map(|(capture, chunk)| (*capture == id).then_some(*chunk))
+    }
+}
+
+/// A reversible key expression rewriting rule, written `<from> -> <to>`.
+///
+/// See the [module level documentation](self) for the rule syntax.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct KeyMapRule {
+    from: Pattern,
+    to: Pattern,
+}
+
+impl KeyMapRule {
+    /// Constructs a rule rewriting key expressions matching `from` into `to`.
+    pub fn new(from: &str, to: &str) -> ZResult<Self> {
+        let (from, to) = (Pattern::new(from)?, Pattern::new(to)?);
+        if !from.has_same_captures(&to) {
+            bail!("Key mapping patterns `{from}` and `{to}` don't have the same captures")
+        }
+        Ok(KeyMapRule { from, to })
+    }
+
+    /// Rewrites `ke` from this rule's `from` pattern to its `to` pattern, if `ke` matches the former.
+    pub fn map(&self, ke: &keyexpr) -> Option<OwnedKeyExpr> {
+        self.to.build(&self.from.captures(ke)?)
+    }
+
+    /// Rewrites `ke` from this rule's `to` pattern to its `from` pattern, if `ke` matches the former.
+    pub fn unmap(&self, ke: &keyexpr) -> Option<OwnedKeyExpr> {
+        self.from.build(&self.to.captures(ke)?)
+    }
+}
+
+impl TryFrom<&str> for KeyMapRule {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.split_once(ARROW) {
+            Some((from, to)) => KeyMapRule::new(from, to),
+            None => bail!("Key mapping rule `{value}` must be of the form `<from> {ARROW} <to>`"),
+        }
+    }
+}
+
+impl FromStr for KeyMapRule {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyMapRule::try_from(s)
+    }
+}
+
+impl Display for KeyMapRule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} {ARROW} {}", self.from, self.to)
+    }
+}
+
+impl Debug for KeyMapRule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+/// Rewrites key expressions according to a list of [`KeyMapRule`]s, in both directions.
+///
+/// Rules are tried in order, the first one matching a key expression is used to rewrite it.
+///
+/// See the [module level documentation](self) for more details.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyMapper {
+    rules: Vec<KeyMapRule>,
+}
+
+impl KeyMapper {
+    /// Compiles a [`KeyMapper`] from rules written `<from> -> <to>`.
+    pub fn new<I, S>(rules: I) -> ZResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let rules = rules
+            .into_iter()
+            .map(|rule| KeyMapRule::try_from(rule.as_ref()))
+            .collect::<ZResult<_>>()?;
+        Ok(KeyMapper { rules })
+    }
+
+    /// Appends a rule to this mapper, with the lowest priority.
+    pub fn push(&mut self, rule: KeyMapRule) {
+        self.rules.push(rule);
+    }
+
+    /// The rules of this mapper, in order of priority.
+    pub fn rules(&self) -> &[KeyMapRule] {
+        &self.rules
+    }
+
+    /// Rewrites `ke` with the first rule whose `from` pattern it matches.
+    pub fn map(&self, ke: &keyexpr) -> Option<OwnedKeyExpr> {
+        self.rules.iter().find_map(|rule| rule.map(ke))
+    }
+
+    /// Rewrites `ke` back with the first rule whose `to` pattern it matches.
+    pub fn unmap(&self, ke: &keyexpr) -> Option<OwnedKeyExpr> {
+        self.rules.iter().find_map(|rule| rule.unmap(ke))
+    }
+}
+
+impl FromIterator<KeyMapRule> for KeyMapper {
+    fn from_iter<T: IntoIterator<Item = KeyMapRule>>(iter: T) -> Self {
+        KeyMapper {
+            rules: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[test]
+fn key_mapper() {
+    let mapper = KeyMapper::new([
+        "dds/<topic>/** -> ros/<topic>/**",
+        "a/<x>/b/<y> -> c/<y>/<x>",
+        "@local/** -> remote/**",
+    ])
+    .unwrap();
+    let map = |ke: &str| {
+        mapper
+            .map(keyexpr::new(ke).unwrap())
+            .map(|ke| ke.to_string())
+    };
+    let unmap = |ke: &str| {
+        mapper
+            .unmap(keyexpr::new(ke).unwrap())
+            .map(|ke| ke.to_string())
+    };
+
+    assert_eq!(map("dds/chatter").as_deref(), Some("ros/chatter"));
+    assert_eq!(map("dds/chatter/a/b").as_deref(), Some("ros/chatter/a/b"));
+    assert_eq!(map("dds/*/**").as_deref(), Some("ros/*/**"));
+    assert_eq!(map("dds/chat$*/a/**").as_deref(), Some("ros/chat$*/a/**"));
+    assert_eq!(map("*/chatter").as_deref(), Some("ros/chatter"));
+    assert_eq!(map("dds/**"), None);
+    assert_eq!(map("dds"), None);
+    assert_eq!(map("dds/@ros"), None);
+    assert_eq!(unmap("ros/chatter/a/b").as_deref(), Some("dds/chatter/a/b"));
+
+    assert_eq!(map("a/1/b/2").as_deref(), Some("c/2/1"));
+    assert_eq!(unmap("c/2/1").as_deref(), Some("a/1/b/2"));
+    assert_eq!(map("a/1/b/2/3"), None);
+
+    assert_eq!(map("@local/x/y").as_deref(), Some("remote/x/y"));
+    assert_eq!(map("@local").as_deref(), Some("remote"));
+    assert_eq!(unmap("remote/**").as_deref(), Some("@local/**"));
+    assert_eq!(unmap("remote/@x"), None);
+
+    assert!(KeyMapRule::try_from("a/<x> -> b").is_err());
+    assert!(KeyMapRule::try_from("a/<x> -> b/<y>").is_err());
+    assert!(KeyMapRule::try_from("a/** -> b").is_err());
+    assert!(KeyMapRule::try_from("a/<x>/<x> -> b/<x>/<x>").is_err());
+    assert!(KeyMapRule::try_from("a/* -> b/*").is_err());
+    assert!(KeyMapRule::try_from("a/b").is_err());
+    assert_eq!(
+        KeyMapRule::try_from(" dds/<topic>/**  ->  ros/<topic>/** ")
+            .unwrap()
+            .to_string(),
+        "dds/<topic>/** -> ros/<topic>/**"
+    );
+}
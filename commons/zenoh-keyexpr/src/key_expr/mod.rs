@@ -43,5 +43,7 @@ pub mod fuzzer;
 
 pub mod format;
 
+pub mod mapper;
+
 #[cfg(test)]
 mod tests;
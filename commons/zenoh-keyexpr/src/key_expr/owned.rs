@@ -26,7 +26,7 @@ use core::{
 /// A [`Arc<str>`] newtype that is statically known to be a valid key expression.
 ///
 /// See [`keyexpr`](super::borrowed::keyexpr).
-#[derive(Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize)]
 #[cfg_attr(feature = "std", derive(schemars::JsonSchema))]
 #[serde(try_from = "String")]
 pub struct OwnedKeyExpr(pub(crate) Arc<str>);
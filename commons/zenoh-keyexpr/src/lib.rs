@@ -34,6 +34,7 @@
 //! the Key Expression semantics with high performance.
 //!
 //! Enter [KeTrees](keyexpr_tree). These are data-structures specially built to store KE-value pairs in a manner that supports the set-semantics of KEs.
+//! They are behind the `keyexpr-tree` feature, so that the crates which only need the Key Expressions, such as the protocol, do without their collections.
 //!
 //! # Building and parsing Key Expressions
 //! A common issue in REST API is the association of meaning to sections of the URL, and respecting that API in a convenient manner.
@@ -42,6 +43,10 @@
 //!
 //! [`kedefine`] also allows you to define formats at compile time, allowing a more performant, but more importantly safer and more convenient use of said formats,
 //! as the [`keformat`] and [`kewrite`] macros will be able to tell you if you're attempting to set fields of the format that do not exist.
+//!
+//! # Rewriting Key Expressions
+//! Bridges commonly need to translate key expressions between key spaces, in both directions.
+//! [`KeyMapper`](mapper::KeyMapper) compiles rewriting rules such as `dds/<topic>/** -> ros/<topic>/**` for that purpose.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 extern crate alloc;
@@ -49,4 +54,5 @@ extern crate alloc;
 pub mod key_expr;
 
 pub use key_expr::*;
+#[cfg(feature = "keyexpr-tree")]
 pub mod keyexpr_tree;
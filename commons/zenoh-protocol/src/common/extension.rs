@@ -66,6 +66,13 @@ pub mod iext {
     pub const ENC_MASK: u8 = 0b11 << 5;
     pub const FLAG_Z: u8 = 1 << 7;
 
+    /// The first ID reserved to user-defined extensions of the zenoh messages (i.e. Put, Del
+    /// and Query). IDs from `USER_ID_MIN` to `ID_MASK` are never assigned to official extensions.
+    pub const USER_ID_MIN: u8 = 0x0c;
+    /// The protocol version that introduced the user-defined extensions. Older versions fail to
+    /// decode the messages carrying mandatory ones.
+    pub const USER_VERSION: u8 = 0x09;
+
     pub const fn eid(header: u8) -> u8 {
         header & !FLAG_Z
     }
@@ -74,6 +81,10 @@ pub mod iext {
         header & ID_MASK
     }
 
+    pub const fn is_user(header: u8) -> bool {
+        mid(header) >= USER_ID_MIN
+    }
+
     pub(super) const fn id(id: u8, mandatory: bool, encoding: u8) -> u8 {
         let mut id = id & ID_MASK;
         if mandatory {
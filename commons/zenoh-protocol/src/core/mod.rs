@@ -12,24 +12,33 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use alloc::{
-    boxed::Box,
-    format,
-    string::{String, ToString},
-    vec::Vec,
-};
+use alloc::{boxed::Box, string::ToString, vec::Vec};
 use core::{
     convert::{From, TryFrom, TryInto},
     fmt,
     hash::Hash,
     str::FromStr,
 };
+#[cfg(feature = "uhlc")]
+use uhlc::ParseIDError;
+#[cfg(feature = "uhlc")]
 pub use uhlc::{Timestamp, NTP64};
 use zenoh_keyexpr::OwnedKeyExpr;
 use zenoh_result::{bail, zerror};
 
-/// The unique Id of the [`HLC`](uhlc::HLC) that generated the concerned [`Timestamp`].
+#[cfg(not(feature = "uhlc"))]
+mod timestamp;
+#[cfg(not(feature = "uhlc"))]
+use timestamp::ParseIDError;
+#[cfg(not(feature = "uhlc"))]
+pub use timestamp::{Timestamp, NTP64};
+
+/// The unique Id of the HLC that generated the concerned [`Timestamp`].
+#[cfg(feature = "uhlc")]
 pub type TimestampId = uhlc::ID;
+/// The unique Id of the HLC that generated the concerned [`Timestamp`].
+#[cfg(not(feature = "uhlc"))]
+pub type TimestampId = timestamp::ID;
 
 /// Constants and helpers for zenoh `whatami` flags.
 pub mod whatami;
@@ -94,7 +103,7 @@ impl TryFrom<u64> for SampleKind {
 /// The global unique id of a zenoh peer.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
-pub struct ZenohId(uhlc::ID);
+pub struct ZenohId(TimestampId);
 
 impl ZenohId {
     pub const MAX_SIZE: usize = 16;
@@ -105,12 +114,12 @@ impl ZenohId {
     }
 
     #[inline]
-    pub fn to_le_bytes(&self) -> [u8; uhlc::ID::MAX_SIZE] {
+    pub fn to_le_bytes(&self) -> [u8; TimestampId::MAX_SIZE] {
         self.0.to_le_bytes()
     }
 
     pub fn rand() -> ZenohId {
-        ZenohId(uhlc::ID::rand())
+        ZenohId(TimestampId::rand())
     }
 
     pub fn into_keyexpr(self) -> OwnedKeyExpr {
@@ -133,6 +142,7 @@ impl std::error::Error for SizeError {}
 #[cfg(not(feature = "std"))]
 impl zenoh_result::IError for SizeError {}
 
+#[cfg(feature = "uhlc")]
 impl From<uhlc::SizeError> for SizeError {
     fn from(val: uhlc::SizeError) -> Self {
         Self(val.0)
@@ -145,7 +155,7 @@ impl fmt::Display for SizeError {
         write!(
             f,
             "Maximum ID size ({} bytes) exceeded: {}",
-            uhlc::ID::MAX_SIZE,
+            TimestampId::MAX_SIZE,
             self.0
         )
     }
@@ -208,9 +218,9 @@ impl FromStr for ZenohId {
                 s
             );
         }
-        let u: uhlc::ID = s
+        let u: TimestampId = s
             .parse()
-            .map_err(|e: uhlc::ParseIDError| zerror!("Invalid id: {} - {}", s, e.cause))?;
+            .map_err(|e: ParseIDError| zerror!("Invalid id: {} - {}", s, e.cause))?;
         Ok(ZenohId(u))
     }
 }
@@ -228,7 +238,7 @@ impl fmt::Display for ZenohId {
 }
 
 // A PeerID can be converted into a Timestamp's ID
-impl From<&ZenohId> for uhlc::ID {
+impl From<&ZenohId> for TimestampId {
     fn from(zid: &ZenohId) -> Self {
         zid.0
     }
@@ -250,6 +260,7 @@ impl From<&ZenohId> for OwnedKeyExpr {
     }
 }
 
+#[cfg(feature = "serde")]
 impl serde::Serialize for ZenohId {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -259,6 +270,7 @@ impl serde::Serialize for ZenohId {
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for ZenohId {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -270,7 +282,10 @@ impl<'de> serde::Deserialize<'de> for ZenohId {
             type Value = ZenohId;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str(&format!("An hex string of 1-{} bytes", ZenohId::MAX_SIZE))
+                formatter.write_str(&alloc::format!(
+                    "An hex string of 1-{} bytes",
+                    ZenohId::MAX_SIZE
+                ))
             }
 
             fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -287,7 +302,7 @@ impl<'de> serde::Deserialize<'de> for ZenohId {
                 self.visit_str(v)
             }
 
-            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            fn visit_string<E>(self, v: alloc::string::String) -> Result<Self::Value, E>
             where
                 E: serde::de::Error,
             {
@@ -345,14 +360,56 @@ impl TryFrom<u8> for Priority {
     }
 }
 
+/// The reliability of the channel used to deliver messages.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Reliability {
+    /// Messages may be lost, e.g. when sent over an unreliable link.
     #[default]
     BestEffort,
+    /// Messages are delivered reliably when using a reliable link.
     Reliable,
 }
 
+impl fmt::Display for Reliability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reliability::BestEffort => f.write_str("best_effort"),
+            Reliability::Reliable => f.write_str("reliable"),
+        }
+    }
+}
+
+impl FromStr for Reliability {
+    type Err = zenoh_result::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "best_effort" => Ok(Reliability::BestEffort),
+            "reliable" => Ok(Reliability::Reliable),
+            unknown => bail!(
+                "{} is not a valid reliability value. Admitted values are: 'best_effort', 'reliable'.",
+                unknown
+            ),
+        }
+    }
+}
+
+impl TryFrom<u8> for Reliability {
+    type Error = zenoh_result::Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(Reliability::BestEffort),
+            1 => Ok(Reliability::Reliable),
+            unknown => bail!(
+                "{} is not a valid reliability value. Admitted values are: [0-1].",
+                unknown
+            ),
+        }
+    }
+}
+
 impl Reliability {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
@@ -378,9 +435,67 @@ pub struct Channel {
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
 pub enum CongestionControl {
+    /// When transmitting a message in a node with a full queue, the node may drop the message.
     #[default]
     Drop = 0,
+    /// When transmitting a message in a node with a full queue, the node will wait for queue to
+    /// progress.
     Block = 1,
+    /// When transmitting a message in a node with a full queue, the node keeps the message aside
+    /// and drops the oldest messages kept aside first, so that the most recent data gets through.
+    ///
+    /// Nodes that don't support it handle it as [`CongestionControl::Drop`].
+    DropFirst = 2,
+    /// When transmitting a message in a node with a full queue, the node will wait for the queue
+    /// to progress, but switches to dropping the messages if the congestion is sustained.
+    ///
+    /// Nodes that don't support it handle it as [`CongestionControl::Block`].
+    Adaptive = 3,
+}
+
+impl fmt::Display for CongestionControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CongestionControl::Drop => f.write_str("drop"),
+            CongestionControl::Block => f.write_str("block"),
+            CongestionControl::DropFirst => f.write_str("drop_first"),
+            CongestionControl::Adaptive => f.write_str("adaptive"),
+        }
+    }
+}
+
+impl FromStr for CongestionControl {
+    type Err = zenoh_result::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop" => Ok(CongestionControl::Drop),
+            "block" => Ok(CongestionControl::Block),
+            "drop_first" => Ok(CongestionControl::DropFirst),
+            "adaptive" => Ok(CongestionControl::Adaptive),
+            unknown => bail!(
+                "{} is not a valid congestion control value. Admitted values are: 'drop', 'block', 'drop_first', 'adaptive'.",
+                unknown
+            ),
+        }
+    }
+}
+
+impl TryFrom<u8> for CongestionControl {
+    type Error = zenoh_result::Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(CongestionControl::Drop),
+            1 => Ok(CongestionControl::Block),
+            2 => Ok(CongestionControl::DropFirst),
+            3 => Ok(CongestionControl::Adaptive),
+            unknown => bail!(
+                "{} is not a valid congestion control value. Admitted values are: [0-3].",
+                unknown
+            ),
+        }
+    }
 }
 
 /// The subscription mode.
@@ -431,3 +546,15 @@ pub enum QueryTarget {
     #[cfg(feature = "complete_n")]
     Complete(u64),
 }
+
+/// How the queryable receiving a query that targets the [`QueryTarget::BestMatching`] queryable
+/// is selected among the complete ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryableSelection {
+    /// The nearest complete queryable.
+    Nearest,
+    /// Each complete queryable in turn.
+    RoundRobin,
+    /// The complete queryable reached through the best ranked node of the routers ranking.
+    Ranked,
+}
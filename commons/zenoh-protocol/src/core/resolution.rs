@@ -12,7 +12,6 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::{network::RequestId, transport::TransportSn};
-use alloc::string::String;
 use core::{fmt, str::FromStr};
 use zenoh_result::{bail, ZError};
 
@@ -162,6 +161,7 @@ impl From<u8> for Resolution {
 }
 
 // Serde
+#[cfg(feature = "serde")]
 impl serde::Serialize for Bits {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -171,7 +171,9 @@ impl serde::Serialize for Bits {
     }
 }
 
+#[cfg(feature = "serde")]
 pub struct BitsVisitor;
+#[cfg(feature = "serde")]
 impl<'de> serde::de::Visitor<'de> for BitsVisitor {
     type Value = Bits;
 
@@ -202,7 +204,7 @@ impl<'de> serde::de::Visitor<'de> for BitsVisitor {
         self.visit_str(v)
     }
 
-    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    fn visit_string<E>(self, v: alloc::string::String) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
@@ -210,6 +212,7 @@ impl<'de> serde::de::Visitor<'de> for BitsVisitor {
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for Bits {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
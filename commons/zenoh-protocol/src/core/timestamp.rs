@@ -0,0 +1,235 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The timestamp types of the messages when the `uhlc` feature is disabled.
+//!
+//! They mirror the subset of the `uhlc` API used by the protocol and the codec, and have the
+//! same wire representation, but come without the clock that generates the timestamps.
+use super::SizeError;
+use core::{
+    convert::TryFrom,
+    fmt,
+    num::{NonZeroU128, ParseIntError},
+    str::FromStr,
+};
+
+/// A 64-bit NTP time: the seconds since the epoch in the 32 upper bits, the fraction of the
+/// second in the 32 lower bits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NTP64(pub u64);
+
+impl NTP64 {
+    #[inline]
+    pub const fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for NTP64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The non-zero identifier of a clock, of 1 to 16 bytes in little-endian order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ID(NonZeroU128);
+
+impl ID {
+    pub const MAX_SIZE: usize = 16;
+
+    /// The number of significant bytes of the identifier.
+    #[inline]
+    pub fn size(&self) -> usize {
+        Self::MAX_SIZE - (self.0.leading_zeros() as usize / 8)
+    }
+
+    #[inline]
+    pub fn to_le_bytes(&self) -> [u8; Self::MAX_SIZE] {
+        self.0.get().to_le_bytes()
+    }
+
+    pub fn rand() -> ID {
+        use rand::{rngs::OsRng, Rng};
+
+        loop {
+            if let Some(id) = NonZeroU128::new(OsRng.gen()) {
+                return ID(id);
+            }
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for ID {
+    type Error = SizeError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        let size = slice.len();
+        if size > Self::MAX_SIZE {
+            return Err(SizeError(size));
+        }
+        let mut bytes = [0_u8; Self::MAX_SIZE];
+        bytes[..size].copy_from_slice(slice);
+        // An identifier made of zeros only is invalid, as in uhlc
+        NonZeroU128::new(u128::from_le_bytes(bytes))
+            .map(ID)
+            .ok_or(SizeError(0))
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8; N]> for ID {
+    type Error = SizeError;
+
+    fn try_from(bytes: &[u8; N]) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+impl<const N: usize> TryFrom<[u8; N]> for ID {
+    type Error = SizeError;
+
+    fn try_from(bytes: [u8; N]) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseIDError {
+    pub cause: &'static str,
+}
+
+impl From<ParseIntError> for ParseIDError {
+    fn from(_: ParseIntError) -> Self {
+        ParseIDError {
+            cause: "Invalid hexadecimal string",
+        }
+    }
+}
+
+impl FromStr for ID {
+    type Err = ParseIDError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseIDError {
+                cause: "Empty strings are not valid",
+            });
+        }
+        if s.len() > 2 * Self::MAX_SIZE {
+            return Err(ParseIDError {
+                cause: "The string is longer than 32 characters",
+            });
+        }
+        let id = u128::from_str_radix(s, 16)?;
+        NonZeroU128::new(id).map(ID).ok_or(ParseIDError {
+            cause: "An identifier can not be zero",
+        })
+    }
+}
+
+impl fmt::Display for ID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}", self.0.get())
+    }
+}
+
+impl fmt::Debug for ID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A time associated with the identifier of the clock that generated it, ordered by time first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    time: NTP64,
+    id: ID,
+}
+
+impl Timestamp {
+    #[inline]
+    pub fn new(time: NTP64, id: ID) -> Timestamp {
+        Timestamp { time, id }
+    }
+
+    #[inline]
+    pub fn get_time(&self) -> &NTP64 {
+        &self.time
+    }
+
+    #[inline]
+    pub fn get_id(&self) -> &ID {
+        &self.id
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.time, self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id_bytes() {
+        let id = ID::try_from([0x12_u8, 0x34, 0x00]).unwrap();
+        assert_eq!(id.size(), 2);
+        assert_eq!(&id.to_le_bytes()[..id.size()], &[0x12, 0x34]);
+        assert_eq!(ID::try_from(&id.to_le_bytes()).unwrap(), id);
+
+        let max = ID::try_from([0xff_u8; ID::MAX_SIZE]).unwrap();
+        assert_eq!(max.size(), ID::MAX_SIZE);
+        assert!(ID::try_from([0xff_u8; ID::MAX_SIZE + 1]).is_err());
+        assert!(ID::try_from([0_u8; 4]).is_err());
+        assert!(ID::try_from(&[0_u8; 0][..]).is_err());
+
+        for _ in 0..64 {
+            let id = ID::rand();
+            assert_eq!(ID::try_from(&id.to_le_bytes()[..id.size()]).unwrap(), id);
+        }
+    }
+
+    #[test]
+    fn id_str() {
+        let id = ID::try_from([0x12_u8, 0x34]).unwrap();
+        assert_eq!(id.to_string(), "3412");
+        assert_eq!("3412".parse::<ID>().unwrap(), id);
+        assert_eq!(
+            "0123456789abcdef0123456789abcdef"
+                .parse::<ID>()
+                .unwrap()
+                .size(),
+            ID::MAX_SIZE
+        );
+        for invalid in ["", "0", "00", "xyz", "0123456789abcdef0123456789abcdef0"] {
+            assert!(invalid.parse::<ID>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn timestamp_order() {
+        let a = ID::try_from([1_u8]).unwrap();
+        let b = ID::try_from([2_u8]).unwrap();
+        let t1 = Timestamp::new(NTP64(1), b);
+        let t2 = Timestamp::new(NTP64(2), a);
+        let t3 = Timestamp::new(NTP64(2), b);
+        assert!(t1 < t2 && t2 < t3);
+        assert_eq!(t3.get_time().as_u64(), 2);
+        assert_eq!(t3.get_id(), &b);
+        assert_eq!(t3.to_string(), "2/02");
+    }
+}
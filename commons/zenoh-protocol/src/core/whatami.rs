@@ -11,7 +11,6 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use alloc::string::String;
 use const_format::formatcp;
 use core::{convert::TryFrom, fmt, num::NonZeroU8, ops::BitOr, str::FromStr};
 use zenoh_result::{bail, ZError};
@@ -258,6 +257,7 @@ impl From<WhatAmI> for WhatAmIMatcher {
 }
 
 // Serde
+#[cfg(feature = "serde")]
 impl serde::Serialize for WhatAmI {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -267,8 +267,10 @@ impl serde::Serialize for WhatAmI {
     }
 }
 
+#[cfg(feature = "serde")]
 pub struct WhatAmIVisitor;
 
+#[cfg(feature = "serde")]
 impl<'de> serde::de::Visitor<'de> for WhatAmIVisitor {
     type Value = WhatAmI;
 
@@ -295,7 +297,7 @@ impl<'de> serde::de::Visitor<'de> for WhatAmIVisitor {
     {
         self.visit_str(v)
     }
-    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    fn visit_string<E>(self, v: alloc::string::String) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
@@ -303,6 +305,7 @@ impl<'de> serde::de::Visitor<'de> for WhatAmIVisitor {
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for WhatAmI {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -312,6 +315,7 @@ impl<'de> serde::Deserialize<'de> for WhatAmI {
     }
 }
 
+#[cfg(feature = "serde")]
 impl serde::Serialize for WhatAmIMatcher {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -321,7 +325,9 @@ impl serde::Serialize for WhatAmIMatcher {
     }
 }
 
+#[cfg(feature = "serde")]
 pub struct WhatAmIMatcherVisitor;
+#[cfg(feature = "serde")]
 impl<'de> serde::de::Visitor<'de> for WhatAmIMatcherVisitor {
     type Value = WhatAmIMatcher;
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -357,7 +363,7 @@ impl<'de> serde::de::Visitor<'de> for WhatAmIMatcherVisitor {
     {
         self.visit_str(v)
     }
-    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    fn visit_string<E>(self, v: alloc::string::String) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
@@ -365,6 +371,7 @@ impl<'de> serde::de::Visitor<'de> for WhatAmIMatcherVisitor {
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for WhatAmIMatcher {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -17,6 +17,18 @@
 //! This crate is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
+//!
+//! Built with `default-features = false`, the message types only depend on `alloc`, e.g. to
+//! embed them in host-side encoders for zenoh-pico devices. The default features are:
+//! - `std`: the standard library support;
+//! - `serde`: the serde implementations of the core types;
+//! - `single-or-vec`: the payloads made of a single slice keep it inline rather than growing a
+//!   vector, at the cost of depending on `zenoh-collections`;
+//! - `uhlc`: the timestamps are the ones of the `uhlc` clocks rather than plain types with the
+//!   same wire representation.
+//!
+//! The collections of the key expression trees are not needed either, they are behind the
+//! `keyexpr-tree` feature of `zenoh-keyexpr`.
 #![cfg_attr(not(feature = "std"), no_std)]
 extern crate alloc;
 
@@ -28,7 +40,9 @@ pub mod transport;
 pub mod zenoh;
 
 // Zenoh version
-pub const VERSION: u8 = 0x08;
+pub const VERSION: u8 = 0x09;
+// The oldest Zenoh version this implementation is able to interoperate with
+pub const VERSION_MIN: u8 = 0x08;
 
 // Zenoh protocol uses the following conventions for message definition and representation.
 //
@@ -766,7 +766,7 @@ pub mod interest {
     }
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct Interest(u8);
 
     impl Interest {
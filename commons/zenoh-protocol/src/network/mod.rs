@@ -26,7 +26,7 @@ pub use declare::{
 };
 pub use oam::Oam;
 pub use push::Push;
-pub use request::{AtomicRequestId, Request, RequestId};
+pub use request::{AtomicRequestId, Request, RequestCancel, RequestId};
 pub use response::{Response, ResponseFinal};
 
 use crate::core::{CongestionControl, Priority};
@@ -40,6 +40,7 @@ pub mod id {
     pub const REQUEST: u8 = 0x1c;
     pub const RESPONSE: u8 = 0x1b;
     pub const RESPONSE_FINAL: u8 = 0x1a;
+    pub const REQUEST_CANCEL: u8 = 0x19;
 }
 
 #[repr(u8)]
@@ -71,6 +72,7 @@ pub enum NetworkBody {
     Request(Request),
     Response(Response),
     ResponseFinal(ResponseFinal),
+    RequestCancel(RequestCancel),
     Declare(Declare),
     OAM(Oam),
 }
@@ -89,13 +91,14 @@ impl NetworkMessage {
 
         let mut rng = rand::thread_rng();
 
-        let body = match rng.gen_range(0..6) {
+        let body = match rng.gen_range(0..7) {
             0 => NetworkBody::Push(Push::rand()),
             1 => NetworkBody::Request(Request::rand()),
             2 => NetworkBody::Response(Response::rand()),
             3 => NetworkBody::ResponseFinal(ResponseFinal::rand()),
             4 => NetworkBody::Declare(Declare::rand()),
             5 => NetworkBody::OAM(Oam::rand()),
+            6 => NetworkBody::RequestCancel(RequestCancel::rand()),
             _ => unreachable!(),
         };
 
@@ -114,16 +117,71 @@ impl NetworkMessage {
             return true;
         }
 
-        let cc = match &self.body {
+        matches!(
+            self.congestion_control(),
+            CongestionControl::Drop | CongestionControl::DropFirst
+        )
+    }
+
+    #[inline]
+    pub fn congestion_control(&self) -> CongestionControl {
+        match &self.body {
             NetworkBody::Declare(msg) => msg.ext_qos.get_congestion_control(),
             NetworkBody::Push(msg) => msg.ext_qos.get_congestion_control(),
             NetworkBody::Request(msg) => msg.ext_qos.get_congestion_control(),
             NetworkBody::Response(msg) => msg.ext_qos.get_congestion_control(),
             NetworkBody::ResponseFinal(msg) => msg.ext_qos.get_congestion_control(),
+            NetworkBody::RequestCancel(msg) => msg.ext_qos.get_congestion_control(),
             NetworkBody::OAM(msg) => msg.ext_qos.get_congestion_control(),
+        }
+    }
+
+    #[inline]
+    pub fn is_express(&self) -> bool {
+        match &self.body {
+            NetworkBody::Declare(msg) => msg.ext_qos.is_express(),
+            NetworkBody::Push(msg) => msg.ext_qos.is_express(),
+            NetworkBody::Request(msg) => msg.ext_qos.is_express(),
+            NetworkBody::Response(msg) => msg.ext_qos.is_express(),
+            NetworkBody::ResponseFinal(msg) => msg.ext_qos.is_express(),
+            NetworkBody::RequestCancel(msg) => msg.ext_qos.is_express(),
+            NetworkBody::OAM(msg) => msg.ext_qos.is_express(),
+        }
+    }
+
+    /// The oldest protocol version able to decode this message.
+    #[inline]
+    pub fn min_version(&self) -> u8 {
+        use crate::{
+            common::{iext, ZExtUnknown},
+            zenoh::{reply, PushBody, RequestBody, ResponseBody},
         };
 
-        cc == CongestionControl::Drop
+        let user = |exts: &[ZExtUnknown]| {
+            if exts.iter().any(ZExtUnknown::is_mandatory) {
+                iext::USER_VERSION
+            } else {
+                crate::VERSION_MIN
+            }
+        };
+        match &self.body {
+            NetworkBody::Push(Push { payload, .. }) => match payload {
+                PushBody::Put(put) => user(&put.ext_unknown),
+                PushBody::Del(del) => user(&del.ext_unknown),
+            },
+            NetworkBody::Request(Request { payload, .. }) => match payload {
+                RequestBody::Query(query) => user(&query.ext_unknown),
+                RequestBody::Put(put) => user(&put.ext_unknown),
+                RequestBody::Del(del) => user(&del.ext_unknown),
+                RequestBody::Pull(_) => crate::VERSION_MIN,
+            },
+            NetworkBody::Response(Response {
+                payload: ResponseBody::Reply(reply),
+                ..
+            }) if reply.ext_delete.is_some() => reply::ext::DELETE_VERSION,
+            NetworkBody::RequestCancel(_) => RequestCancel::VERSION,
+            _ => crate::VERSION_MIN,
+        }
     }
 
     #[inline]
@@ -134,6 +192,7 @@ impl NetworkMessage {
             NetworkBody::Request(msg) => msg.ext_qos.get_priority(),
             NetworkBody::Response(msg) => msg.ext_qos.get_priority(),
             NetworkBody::ResponseFinal(msg) => msg.ext_qos.get_priority(),
+            NetworkBody::RequestCancel(msg) => msg.ext_qos.get_priority(),
             NetworkBody::OAM(msg) => msg.ext_qos.get_priority(),
         }
     }
@@ -148,6 +207,7 @@ impl fmt::Display for NetworkMessage {
             Request(_) => write!(f, "Request"),
             Response(_) => write!(f, "Response"),
             ResponseFinal(_) => write!(f, "ResponseFinal"),
+            RequestCancel(_) => write!(f, "RequestCancel"),
             Declare(_) => write!(f, "Declare"),
         }
     }
@@ -194,6 +254,12 @@ impl From<ResponseFinal> for NetworkMessage {
     }
 }
 
+impl From<RequestCancel> for NetworkMessage {
+    fn from(cancel: RequestCancel) -> Self {
+        NetworkBody::RequestCancel(cancel).into()
+    }
+}
+
 // Extensions
 pub mod ext {
     use crate::{
@@ -207,13 +273,15 @@ pub mod ext {
     /// +-+-+-+-+-+-+-+-+
     /// |Z|0_1|    ID   |
     /// +-+-+-+---------+
-    /// %0|rsv|E|D|prio %
+    /// %0|r|F|E|D|prio %
     /// +---------------+
     ///
     /// - prio: Priority class
     /// - D:    Don't drop. Don't drop the message for congestion control.
     /// - E:    Express. Don't batch this message.
-    /// - rsv:  Reserved
+    /// - F:    Flexible. Drop the oldest messages first if D==0,
+    ///         drop the messages under sustained congestion if D==1.
+    /// - r:    Reserved
     /// ```
     #[repr(transparent)]
     #[derive(Clone, Copy, PartialEq, Eq)]
@@ -225,6 +293,7 @@ pub mod ext {
         const P_MASK: u8 = 0b00000111;
         const D_FLAG: u8 = 0b00001000;
         const E_FLAG: u8 = 0b00010000;
+        const F_FLAG: u8 = 0b00100000;
 
         pub const fn new(
             priority: Priority,
@@ -232,9 +301,12 @@ pub mod ext {
             is_express: bool,
         ) -> Self {
             let mut inner = priority as u8;
-            if let CongestionControl::Block = congestion_control {
+            if let CongestionControl::Block | CongestionControl::Adaptive = congestion_control {
                 inner |= Self::D_FLAG;
             }
+            if let CongestionControl::DropFirst | CongestionControl::Adaptive = congestion_control {
+                inner |= Self::F_FLAG;
+            }
             if is_express {
                 inner |= Self::E_FLAG;
             }
@@ -242,7 +314,7 @@ pub mod ext {
         }
 
         pub fn set_priority(&mut self, priority: Priority) {
-            self.inner = imsg::set_flag(self.inner, priority as u8);
+            self.inner = (self.inner & !Self::P_MASK) | priority as u8;
         }
 
         pub const fn get_priority(&self) -> Priority {
@@ -250,16 +322,31 @@ pub mod ext {
         }
 
         pub fn set_congestion_control(&mut self, cctrl: CongestionControl) {
-            match cctrl {
-                CongestionControl::Block => self.inner = imsg::set_flag(self.inner, Self::D_FLAG),
-                CongestionControl::Drop => self.inner = imsg::unset_flag(self.inner, Self::D_FLAG),
-            }
+            let (d, f) = match cctrl {
+                CongestionControl::Drop => (false, false),
+                CongestionControl::Block => (true, false),
+                CongestionControl::DropFirst => (false, true),
+                CongestionControl::Adaptive => (true, true),
+            };
+            self.inner = match d {
+                true => imsg::set_flag(self.inner, Self::D_FLAG),
+                false => imsg::unset_flag(self.inner, Self::D_FLAG),
+            };
+            self.inner = match f {
+                true => imsg::set_flag(self.inner, Self::F_FLAG),
+                false => imsg::unset_flag(self.inner, Self::F_FLAG),
+            };
         }
 
         pub const fn get_congestion_control(&self) -> CongestionControl {
-            match imsg::has_flag(self.inner, Self::D_FLAG) {
-                true => CongestionControl::Block,
-                false => CongestionControl::Drop,
+            match (
+                imsg::has_flag(self.inner, Self::D_FLAG),
+                imsg::has_flag(self.inner, Self::F_FLAG),
+            ) {
+                (false, false) => CongestionControl::Drop,
+                (true, false) => CongestionControl::Block,
+                (false, true) => CongestionControl::DropFirst,
+                (true, true) => CongestionControl::Adaptive,
             }
         }
 
@@ -348,7 +435,7 @@ pub mod ext {
     /// ```
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct TimestampType<const ID: u8> {
-        pub timestamp: uhlc::Timestamp,
+        pub timestamp: crate::core::Timestamp,
     }
 
     impl<const ID: u8> TimestampType<{ ID }> {
@@ -357,9 +444,9 @@ pub mod ext {
             use rand::Rng;
             let mut rng = rand::thread_rng();
 
-            let time = uhlc::NTP64(rng.gen());
-            let id = uhlc::ID::try_from(ZenohId::rand().to_le_bytes()).unwrap();
-            let timestamp = uhlc::Timestamp::new(time, id);
+            let time = crate::core::NTP64(rng.gen());
+            let id = crate::core::TimestampId::try_from(ZenohId::rand().to_le_bytes()).unwrap();
+            let timestamp = crate::core::Timestamp::new(time, id);
             Self { timestamp }
         }
     }
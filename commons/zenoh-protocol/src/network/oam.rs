@@ -25,6 +25,11 @@ pub mod id {
     use super::OamId;
 
     pub const OAM_LINKSTATE: OamId = 0x0001;
+    /// The samples dropped by the interceptors of a node, notified to the next hops of their
+    /// route. The body is a ZBuf carrying the reason of the drops (u8), their number (z64),
+    /// the routing context the samples would have carried (z64) and the key expression they
+    /// were published on (string).
+    pub const OAM_DROP_NOTIFICATION: OamId = 0x0002;
 }
 
 /// ```text
@@ -60,13 +60,14 @@ pub struct Request {
     pub ext_target: ext::TargetType,
     pub ext_budget: Option<ext::BudgetType>,
     pub ext_timeout: Option<ext::TimeoutType>,
+    pub ext_selection: Option<ext::SelectionType>,
     pub payload: RequestBody,
 }
 
 pub mod ext {
     use crate::{
         common::{ZExtZ64, ZExtZBuf},
-        core::QueryTarget,
+        core::{QueryTarget, QueryableSelection},
         zextz64, zextzbuf,
     };
     use core::{num::NonZeroU32, time::Duration};
@@ -115,6 +116,33 @@ pub mod ext {
     // The timeout of the request
     pub type Timeout = zextz64!(0x6, false);
     pub type TimeoutType = Duration;
+
+    pub type Selection = zextz64!(0x7, false);
+    /// - Selection (0x07)
+    ///  7 6 5 4 3 2 1 0
+    /// +-+-+-+-+-+-+-+-+
+    /// %   selection   %
+    /// +---------------+
+    ///
+    /// How the routers select the queryable receiving a query targeting the best matching one,
+    /// overriding their configured selection.
+    pub type SelectionType = QueryableSelection;
+
+    impl SelectionType {
+        #[cfg(feature = "test")]
+        pub fn rand() -> Self {
+            use rand::prelude::*;
+            let mut rng = rand::thread_rng();
+
+            *[
+                SelectionType::Nearest,
+                SelectionType::RoundRobin,
+                SelectionType::Ranked,
+            ]
+            .choose(&mut rng)
+            .unwrap()
+        }
+    }
 }
 
 impl Request {
@@ -142,6 +170,7 @@ impl Request {
         } else {
             None
         };
+        let ext_selection = rng.gen_bool(0.5).then(ext::SelectionType::rand);
 
         Self {
             wire_expr,
@@ -153,6 +182,58 @@ impl Request {
             ext_target,
             ext_budget,
             ext_timeout,
+            ext_selection,
+        }
+    }
+}
+
+/// # RequestCancel message
+///
+/// Sent towards the queryables to cancel a request: no more responses are expected for it.
+/// It is only sent to the nodes speaking protocol version [`RequestCancel::VERSION`] or newer.
+///
+/// ```text
+/// Flags:
+/// - X: Reserved
+/// - X: Reserved
+/// - Z: Extension      if Z==1 then at least one extension is present
+///
+///  7 6 5 4 3 2 1 0
+/// +-+-+-+-+-+-+-+-+
+/// |Z|X|X| ReqCncl |
+/// +-+-+-+---------+
+/// ~ request_id:z32~  (*)
+/// +---------------+
+/// ~   [req_exts]  ~  if Z==1
+/// +---------------+
+///
+/// (*) The resolution of the request id is negotiated during the session establishment.
+///     This implementation limits the resolution to 32bit.
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestCancel {
+    pub rid: RequestId,
+    pub ext_qos: ext::QoSType,
+    pub ext_tstamp: Option<ext::TimestampType>,
+}
+
+impl RequestCancel {
+    /// The protocol version that introduced the RequestCancel message.
+    pub const VERSION: u8 = 0x09;
+
+    #[cfg(feature = "test")]
+    pub fn rand() -> Self {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let rid: RequestId = rng.gen();
+        let ext_qos = ext::QoSType::rand();
+        let ext_tstamp = rng.gen_bool(0.5).then(ext::TimestampType::rand);
+
+        Self {
+            rid,
+            ext_qos,
+            ext_tstamp,
         }
     }
 }
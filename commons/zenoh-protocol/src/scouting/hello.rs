@@ -83,6 +83,8 @@ use core::fmt;
 /// +---------------+
 /// ~   <utf8;z8>   ~ if Flag(L)==1 -- List of locators
 /// +---------------+
+/// ~   [HelloExts] ~ if Flag(Z)==1
+/// +---------------+
 ///
 /// (*) WhatAmI. It indicates the role of the zenoh node sending the HELLO message.
 ///    The valid WhatAmI values are:
@@ -104,6 +106,16 @@ pub struct Hello {
     pub whatami: WhatAmI,
     pub zid: ZenohId,
     pub locators: Vec<Locator>,
+    pub ext_metadata: Option<ext::Metadata>,
+}
+
+// Extensions
+pub mod ext {
+    use crate::{common::ZExtZBuf, zextzbuf};
+
+    /// # Metadata extension
+    /// The metadata of the zenoh node, encoded as JSON
+    pub type Metadata = zextzbuf!(0x1, false);
 }
 
 impl fmt::Display for Hello {
@@ -131,11 +143,13 @@ impl Hello {
         } else {
             vec![]
         };
+        let ext_metadata = rng.gen_bool(0.5).then_some(ext::Metadata::rand());
         Self {
             version,
             zid,
             whatami,
             locators,
+            ext_metadata,
         }
     }
 }
@@ -25,7 +25,7 @@ use zenoh_buffers::ZSlice;
 ///
 /// ```text
 ///     A                   B
-///     |  FRAGMENT(MORE)   |
+///     |  FRAGMENT(MORE)   |  <- carries the total size of the message
 ///     |------------------>|
 ///     |  FRAGMENT(MORE)   |
 ///     |------------------>|
@@ -61,6 +61,11 @@ use zenoh_buffers::ZSlice;
 ///       the boundary of the serialized messages. The length is encoded as little-endian.
 ///       In any case, the length of a message must not exceed 65535 bytes.
 ///
+/// The first fragment of a message announces the total size of the message with the
+/// [`ext::TotalSize`] extension. It allows the receiver to refuse upfront a message exceeding
+/// its reassembly limits and to discard the stale fragments of a message whose transmission
+/// was interrupted.
+///
 pub mod flag {
     pub const R: u8 = 1 << 5; // 0x20 Reliable      if R==1 then the frame is reliable
     pub const M: u8 = 1 << 6; // 0x40 More          if M==1 then another fragment will follow
@@ -74,6 +79,7 @@ pub struct Fragment {
     pub sn: TransportSn,
     pub payload: ZSlice,
     pub ext_qos: ext::QoSType,
+    pub ext_total: Option<ext::TotalSize>,
 }
 
 // Extensions
@@ -82,6 +88,10 @@ pub mod ext {
 
     pub type QoS = zextz64!(0x1, true);
     pub type QoSType = crate::transport::ext::QoSType<{ QoS::ID }>;
+
+    /// # TotalSize extension
+    /// The total size in bytes of the fragmented message, carried by its first fragment
+    pub type TotalSize = zextz64!(0x2, false);
 }
 
 impl Fragment {
@@ -96,6 +106,7 @@ impl Fragment {
         let sn: TransportSn = rng.gen();
         let payload = ZSlice::rand(rng.gen_range(8..128));
         let ext_qos = ext::QoSType::rand();
+        let ext_total = rng.gen_bool(0.5).then(ext::TotalSize::rand);
 
         Fragment {
             reliability,
@@ -103,6 +114,7 @@ impl Fragment {
             more,
             payload,
             ext_qos,
+            ext_total,
         }
     }
 }
@@ -114,6 +126,7 @@ pub struct FragmentHeader {
     pub more: bool,
     pub sn: TransportSn,
     pub ext_qos: ext::QoSType,
+    pub ext_total: Option<ext::TotalSize>,
 }
 
 impl FragmentHeader {
@@ -127,12 +140,14 @@ impl FragmentHeader {
         let more = rng.gen_bool(0.5);
         let sn: TransportSn = rng.gen();
         let ext_qos = ext::QoSType::rand();
+        let ext_total = rng.gen_bool(0.5).then(ext::TotalSize::rand);
 
         FragmentHeader {
             reliability,
             more,
             sn,
             ext_qos,
+            ext_total,
         }
     }
 }
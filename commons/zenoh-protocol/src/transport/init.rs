@@ -50,7 +50,7 @@ use zenoh_buffers::ZSlice;
 /// +-+-+-+-+-+-+-+-+
 /// |Z|S|A|   INIT  |
 /// +-+-+-+---------+
-/// |    version    |
+/// |    version    | (!)
 /// +---------------+
 /// |zid_len|x|x|wai| (#)(*)
 /// +-------+-+-+---+
@@ -68,6 +68,10 @@ use zenoh_buffers::ZSlice;
 ///
 /// If A==1 and S==0 then size parameters are (ie. S flag) are accepted.
 ///
+/// (!) Version. In an InitSyn it is the oldest protocol version the sender is able to speak, the newest
+///     one being announced by the Version extension when it differs. In an InitAck it is the version
+///     selected by the sender for the transport, which is the newest one supported by both nodes.
+///
 /// (*) WhatAmI. It indicates the role of the zenoh node sending the INIT message.
 ///    The valid WhatAmI values are:
 ///    - 0b00: Router
@@ -119,13 +123,16 @@ pub struct InitSyn {
     pub ext_mlink: Option<ext::MultiLink>,
     pub ext_lowlatency: Option<ext::LowLatency>,
     pub ext_compression: Option<ext::Compression>,
+    pub ext_compression_algorithms: Option<ext::CompressionAlgorithms>,
+    pub ext_version: Option<ext::Version>,
+    pub ext_checksum: Option<ext::Checksum>,
 }
 
 // Extensions
 pub mod ext {
     use crate::{
-        common::{ZExtUnit, ZExtZBuf},
-        zextunit, zextzbuf,
+        common::{ZExtUnit, ZExtZ64, ZExtZBuf},
+        zextunit, zextz64, zextzbuf,
     };
 
     /// # QoS extension
@@ -151,12 +158,29 @@ pub mod ext {
     /// # Compression extension
     /// Used to negotiate the use of compression on the link
     pub type Compression = zextunit!(0x6, false);
+
+    /// # Version extension
+    /// Used to announce the newest protocol version the sender is able to speak.
+    /// It is not mandatory so that nodes speaking an older version only see the version field.
+    pub type Version = zextz64!(0x7, false);
+
+    /// # Checksum extension
+    /// Used to negotiate a CRC32C checksum appended to every batch sent on the link.
+    pub type Checksum = zextunit!(0x8, false);
+
+    /// # Compression algorithms extension
+    /// Sent along with the Compression extension to announce the bitmask of the compression
+    /// algorithms the sender is able to decompress:
+    /// - 0b01: LZ4
+    /// - 0b10: Zstd
+    /// A node sending the Compression extension alone is only able to decompress LZ4.
+    pub type CompressionAlgorithms = zextz64!(0x9, false);
 }
 
 impl InitSyn {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        use crate::common::{ZExtUnit, ZExtZBuf};
+        use crate::common::{ZExtUnit, ZExtZ64, ZExtZBuf};
         use rand::Rng;
 
         let mut rng = rand::thread_rng();
@@ -172,6 +196,9 @@ impl InitSyn {
         let ext_mlink = rng.gen_bool(0.5).then_some(ZExtZBuf::rand());
         let ext_lowlatency = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
         let ext_compression = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
+        let ext_compression_algorithms = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
+        let ext_version = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
+        let ext_checksum = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
 
         Self {
             version,
@@ -185,6 +212,9 @@ impl InitSyn {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_version,
+            ext_checksum,
         }
     }
 }
@@ -203,12 +233,14 @@ pub struct InitAck {
     pub ext_mlink: Option<ext::MultiLink>,
     pub ext_lowlatency: Option<ext::LowLatency>,
     pub ext_compression: Option<ext::Compression>,
+    pub ext_compression_algorithms: Option<ext::CompressionAlgorithms>,
+    pub ext_checksum: Option<ext::Checksum>,
 }
 
 impl InitAck {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        use crate::common::{ZExtUnit, ZExtZBuf};
+        use crate::common::{ZExtUnit, ZExtZ64, ZExtZBuf};
         use rand::Rng;
 
         let mut rng = rand::thread_rng();
@@ -229,6 +261,8 @@ impl InitAck {
         let ext_mlink = rng.gen_bool(0.5).then_some(ZExtZBuf::rand());
         let ext_lowlatency = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
         let ext_compression = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
+        let ext_compression_algorithms = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
+        let ext_checksum = rng.gen_bool(0.5).then_some(ZExtUnit::rand());
 
         Self {
             version,
@@ -243,6 +277,8 @@ impl InitAck {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_checksum,
         }
     }
 }
@@ -46,7 +46,7 @@ use core::time::Duration;
 /// +-+-+-+-+-+-+-+-+
 /// |Z|S|T|   JOIN  |
 /// +-+-+-+---------+
-/// |    version    |
+/// |    version    | (!)
 /// +---------------+
 /// |zid_len|x|x|wai| (#)(*)
 /// +-------+-+-+---+
@@ -66,6 +66,9 @@ use core::time::Duration;
 ///
 /// If A==1 and S==0 then size parameters are (ie. S flag) are accepted.
 ///
+/// (!) Version. It is the oldest protocol version the sender is able to speak, the newest one
+///     being announced by the Version extension when it differs.
+///
 /// (*) WhatAmI. It indicates the role of the zenoh node sending the JOIN message.
 ///    The valid WhatAmI values are:
 ///    - 0b00: Router
@@ -111,12 +114,13 @@ pub struct Join {
     pub next_sn: PrioritySn,
     pub ext_qos: Option<ext::QoSType>,
     pub ext_shm: Option<ext::Shm>,
+    pub ext_version: Option<ext::Version>,
 }
 
 // Extensions
 pub mod ext {
     use super::{Priority, PrioritySn};
-    use crate::{common::ZExtZBuf, zextzbuf};
+    use crate::{common::ZExtZBuf, zextz64, zextzbuf};
     use alloc::boxed::Box;
 
     /// # QoS extension
@@ -127,12 +131,17 @@ pub mod ext {
     /// # Shm extension
     /// Used to advertise shared memory capabilities
     pub type Shm = zextzbuf!(0x2, true);
+
+    /// # Version extension
+    /// Used to announce the newest protocol version the sender is able to speak.
+    /// It is not mandatory so that nodes speaking an older version only see the version field.
+    pub type Version = zextz64!(0x3, false);
 }
 
 impl Join {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        use crate::common::ZExtZBuf;
+        use crate::common::{ZExtZ64, ZExtZBuf};
         use rand::Rng;
 
         let mut rng = rand::thread_rng();
@@ -152,6 +161,7 @@ impl Join {
             .gen_bool(0.5)
             .then_some(Box::new([PrioritySn::rand(); Priority::NUM]));
         let ext_shm = rng.gen_bool(0.5).then_some(ZExtZBuf::rand());
+        let ext_version = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
 
         Self {
             version,
@@ -163,6 +173,7 @@ impl Join {
             next_sn,
             ext_qos,
             ext_shm,
+            ext_version,
         }
     }
 }
@@ -47,6 +47,20 @@
 /// |                   |
 /// ```
 ///
+/// A [`KeepAlive`] message MAY carry a probe, which the receiver SHOULD echo back on the same link
+/// in a [`KeepAlive`] message as soon as possible. This allows the sender of the probe to measure
+/// the round trip time and the loss of the link:
+///
+/// ```text
+/// A                   B
+/// |  KEEP ALIVE(P=n)  |
+/// |------------------>|
+/// |                   |
+/// |  KEEP ALIVE(E=n)  |
+/// |<------------------|
+/// |                   |
+/// ```
+///
 /// NOTE: In order to consider eventual packet loss, transmission latency and jitter, the time
 ///       interval between two subsequent [`KeepAlive`] messages SHOULD be set to one fourth of
 ///       the lease time. This is in-line with the ITU-T G.8013/Y.1731 specification on continous
@@ -81,12 +95,38 @@ pub mod flag {
     pub const Z: u8 = 1 << 7; // 0x80 Extensions    if Z==1 then an extension will follow
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct KeepAlive;
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KeepAlive {
+    pub ext_probe: Option<ext::Probe>,
+    pub ext_echo: Option<ext::Echo>,
+}
+
+pub mod ext {
+    use crate::{common::ZExtZ64, zextz64};
+
+    /// # Probe extension
+    /// Asks the receiver to echo the probe value back on the same link
+    pub type Probe = zextz64!(0x1, false);
+
+    /// # Echo extension
+    /// Echoes back the value of a probe received on the same link
+    pub type Echo = zextz64!(0x2, false);
+}
 
 impl KeepAlive {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        Self
+        use crate::common::ZExtZ64;
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+
+        let ext_probe = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
+        let ext_echo = rng.gen_bool(0.5).then_some(ZExtZ64::rand());
+
+        Self {
+            ext_probe,
+            ext_echo,
+        }
     }
 }
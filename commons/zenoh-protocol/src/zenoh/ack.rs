@@ -11,9 +11,8 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::common::ZExtUnknown;
+use crate::{common::ZExtUnknown, core::Timestamp};
 use alloc::vec::Vec;
-use uhlc::Timestamp;
 
 /// # Ack message
 ///
@@ -62,8 +61,8 @@ impl Ack {
         let mut rng = rand::thread_rng();
 
         let timestamp = rng.gen_bool(0.5).then_some({
-            let time = uhlc::NTP64(rng.gen());
-            let id = uhlc::ID::try_from(ZenohId::rand().to_le_bytes()).unwrap();
+            let time = crate::core::NTP64(rng.gen());
+            let id = crate::core::TimestampId::try_from(ZenohId::rand().to_le_bytes()).unwrap();
             Timestamp::new(time, id)
         });
         let ext_sinfo = rng.gen_bool(0.5).then_some(ext::SourceInfoType::rand());
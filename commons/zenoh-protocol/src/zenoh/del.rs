@@ -11,9 +11,8 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::common::ZExtUnknown;
+use crate::{common::ZExtUnknown, core::Timestamp};
 use alloc::vec::Vec;
-use uhlc::Timestamp;
 
 /// # Put message
 ///
@@ -42,7 +41,10 @@ pub mod flag {
 pub struct Del {
     pub timestamp: Option<Timestamp>,
     pub ext_sinfo: Option<ext::SourceInfoType>,
+    pub ext_coherence: Option<ext::CoherenceType>,
     pub ext_attachment: Option<ext::AttachmentType>,
+    pub ext_body: Option<ext::DelBodyType>,
+    pub ext_trace: Option<ext::TraceIdType>,
     pub ext_unknown: Vec<ZExtUnknown>,
 }
 
@@ -57,6 +59,22 @@ pub mod ext {
     /// # User attachment
     pub type Attachment = zextzbuf!(0x2, false);
     pub type AttachmentType = crate::zenoh::ext::AttachmentType<{ Attachment::ID }>;
+
+    /// # Coherence extension
+    /// Used to mark the data as part of a coherent set of changes
+    pub type Coherence = zextzbuf!(0x3, false);
+    pub type CoherenceType = crate::zenoh::ext::CoherenceType<{ Coherence::ID }>;
+
+    /// # DelBody extension
+    /// Used to carry an encoded payload along with the deletion, e.g. tombstone metadata.
+    /// With the "shared-memory" feature, a payload in shared memory is flagged by the
+    /// 0x05 Shm extension nested in this one.
+    pub type DelBodyType = crate::zenoh::ext::ValueType<{ ZExtZBuf::<0x04>::id(false) }, 0x05>;
+
+    /// # TraceId extension
+    /// Used to correlate the messages of a same exchange across the hops of the infrastructure
+    pub type TraceId = zextzbuf!(0x6, false);
+    pub type TraceIdType = crate::zenoh::ext::TraceIdType<{ TraceId::ID }>;
 }
 
 impl Del {
@@ -67,24 +85,27 @@ impl Del {
         let mut rng = rand::thread_rng();
 
         let timestamp = rng.gen_bool(0.5).then_some({
-            let time = uhlc::NTP64(rng.gen());
-            let id = uhlc::ID::try_from(ZenohId::rand().to_le_bytes()).unwrap();
+            let time = crate::core::NTP64(rng.gen());
+            let id = crate::core::TimestampId::try_from(ZenohId::rand().to_le_bytes()).unwrap();
             Timestamp::new(time, id)
         });
         let ext_sinfo = rng.gen_bool(0.5).then_some(ext::SourceInfoType::rand());
         let ext_attachment = rng.gen_bool(0.5).then_some(ext::AttachmentType::rand());
+        let ext_coherence = rng.gen_bool(0.5).then_some(ext::CoherenceType::rand());
+        let ext_body = rng.gen_bool(0.5).then_some(ext::DelBodyType::rand());
+        let ext_trace = rng.gen_bool(0.5).then_some(ext::TraceIdType::rand());
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
-            ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::Attachment::ID) + 1,
-                false,
-            ));
+            ext_unknown.push(ZExtUnknown::rand2(iext::mid(ext::TraceId::ID) + 1, false));
         }
 
         Self {
             timestamp,
             ext_sinfo,
+            ext_coherence,
             ext_attachment,
+            ext_body,
+            ext_trace,
             ext_unknown,
         }
     }
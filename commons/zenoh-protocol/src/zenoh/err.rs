@@ -11,9 +11,8 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::common::ZExtUnknown;
+use crate::{common::ZExtUnknown, core::Timestamp};
 use alloc::vec::Vec;
-use uhlc::Timestamp;
 
 /// # Err message
 ///
@@ -40,6 +39,29 @@ pub mod flag {
     pub const Z: u8 = 1 << 7; // 0x80 Extensions        if Z==1 then an extension will follow
 }
 
+/// The registry of the well-known values of the `code` field.
+/// Any other value is application-defined.
+pub mod code {
+    pub const UNSPECIFIED: u16 = 0x00;
+    pub const NOT_FOUND: u16 = 0x01;
+    pub const UNAUTHORIZED: u16 = 0x02;
+    pub const INVALID_REQUEST: u16 = 0x03;
+    pub const TIMEOUT: u16 = 0x04;
+    pub const INTERNAL: u16 = 0x05;
+}
+
+pub fn code_to_str(code: u16) -> &'static str {
+    match code {
+        code::UNSPECIFIED => "UNSPECIFIED",
+        code::NOT_FOUND => "NOT_FOUND",
+        code::UNAUTHORIZED => "UNAUTHORIZED",
+        code::INVALID_REQUEST => "INVALID_REQUEST",
+        code::TIMEOUT => "TIMEOUT",
+        code::INTERNAL => "INTERNAL",
+        _ => "UNKNOWN",
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Err {
     pub code: u16,
@@ -47,6 +69,7 @@ pub struct Err {
     pub timestamp: Option<Timestamp>,
     pub ext_sinfo: Option<ext::SourceInfoType>,
     pub ext_body: Option<ext::ErrBodyType>,
+    pub ext_trace: Option<ext::TraceIdType>,
     pub ext_unknown: Vec<ZExtUnknown>,
 }
 
@@ -63,6 +86,11 @@ pub mod ext {
     /// Shared Memory extension is automatically defined by ValueType extension if
     /// #[cfg(feature = "shared-memory")] is defined.
     pub type ErrBodyType = crate::zenoh::ext::ValueType<{ ZExtZBuf::<0x02>::id(false) }, 0x03>;
+
+    /// # TraceId extension
+    /// Used to correlate the messages of a same exchange across the hops of the infrastructure
+    pub type TraceId = zextzbuf!(0x4, false);
+    pub type TraceIdType = crate::zenoh::ext::TraceIdType<{ TraceId::ID }>;
 }
 
 impl Err {
@@ -75,18 +103,16 @@ impl Err {
         let code: u16 = rng.gen();
         let is_infrastructure = rng.gen_bool(0.5);
         let timestamp = rng.gen_bool(0.5).then_some({
-            let time = uhlc::NTP64(rng.gen());
-            let id = uhlc::ID::try_from(ZenohId::rand().to_le_bytes()).unwrap();
+            let time = crate::core::NTP64(rng.gen());
+            let id = crate::core::TimestampId::try_from(ZenohId::rand().to_le_bytes()).unwrap();
             Timestamp::new(time, id)
         });
         let ext_sinfo = rng.gen_bool(0.5).then_some(ext::SourceInfoType::rand());
         let ext_body = rng.gen_bool(0.5).then_some(ext::ErrBodyType::rand());
+        let ext_trace = rng.gen_bool(0.5).then_some(ext::TraceIdType::rand());
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
-            ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::ErrBodyType::SID) + 1,
-                false,
-            ));
+            ext_unknown.push(ZExtUnknown::rand2(iext::mid(ext::TraceId::ID) + 1, false));
         }
 
         Self {
@@ -95,6 +121,7 @@ impl Err {
             timestamp,
             ext_sinfo,
             ext_body,
+            ext_trace,
             ext_unknown,
         }
     }
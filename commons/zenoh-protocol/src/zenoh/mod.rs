@@ -201,6 +201,43 @@ pub mod ext {
         }
     }
 
+    ///  7 6 5 4 3 2 1 0
+    /// +-+-+-+-+-+-+-+-+
+    /// |zid_len|X|X|X|L|  -- L: the sample is the last one of the coherent set
+    /// +-------+-+-+---+
+    /// ~      zid      ~
+    /// +---------------+
+    /// %    set_id     %  -- Counter decided by the Zenoh Node
+    /// +---------------+
+    /// %     index     %  -- Index of the sample in the coherent set
+    /// +---------------+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct CoherenceType<const ID: u8> {
+        pub zid: ZenohId,
+        pub set_id: u32,
+        pub index: u32,
+        pub last: bool,
+    }
+
+    impl<const ID: u8> CoherenceType<{ ID }> {
+        #[cfg(feature = "test")]
+        pub fn rand() -> Self {
+            use rand::Rng;
+            let mut rng = rand::thread_rng();
+
+            let zid = ZenohId::rand();
+            let set_id: u32 = rng.gen();
+            let index: u32 = rng.gen();
+            let last: bool = rng.gen_bool(0.5);
+            Self {
+                zid,
+                set_id,
+                index,
+                last,
+            }
+        }
+    }
+
     ///  7 6 5 4 3 2 1 0
     /// +-+-+-+-+-+-+-+-+
     /// +-+-+-+-+-+-+-+-+
@@ -290,4 +327,40 @@ pub mod ext {
             }
         }
     }
+
+    /// ```text
+    /// 7 6 5 4 3 2 1 0
+    /// +-+-+-+-+-+-+-+-+
+    /// ~  id: <u8;16>  ~
+    /// +---------------+
+    /// ```
+    /// The trace ID is forwarded untouched by the infrastructure, from a publication to its
+    /// deliveries and from a query to its replies.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct TraceIdType<const ID: u8> {
+        pub id: [u8; 16],
+    }
+
+    impl<const ID: u8> TraceIdType<{ ID }> {
+        pub const fn new(id: [u8; 16]) -> Self {
+            Self { id }
+        }
+
+        #[cfg(feature = "test")]
+        pub fn rand() -> Self {
+            use rand::Rng;
+            let mut rng = rand::thread_rng();
+
+            Self { id: rng.gen() }
+        }
+    }
+
+    impl<const ID: u8> core::fmt::Display for TraceIdType<{ ID }> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            for b in self.id {
+                write!(f, "{b:02x}")?;
+            }
+            Ok(())
+        }
+    }
 }
@@ -11,9 +11,11 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::{common::ZExtUnknown, core::Encoding};
+use crate::{
+    common::ZExtUnknown,
+    core::{Encoding, Timestamp},
+};
 use alloc::vec::Vec;
-use uhlc::Timestamp;
 use zenoh_buffers::ZBuf;
 
 /// # Put message
@@ -48,7 +50,11 @@ pub struct Put {
     pub timestamp: Option<Timestamp>,
     pub encoding: Encoding,
     pub ext_sinfo: Option<ext::SourceInfoType>,
+    pub ext_coherence: Option<ext::CoherenceType>,
+    pub ext_ttl: Option<ext::TtlType>,
+    pub ext_latency_budget: Option<ext::LatencyBudgetType>,
     pub ext_attachment: Option<ext::AttachmentType>,
+    pub ext_trace: Option<ext::TraceIdType>,
     #[cfg(feature = "shared-memory")]
     pub ext_shm: Option<ext::ShmType>,
     pub ext_unknown: Vec<ZExtUnknown>,
@@ -58,7 +64,11 @@ pub struct Put {
 pub mod ext {
     #[cfg(feature = "shared-memory")]
     use crate::{common::ZExtUnit, zextunit};
-    use crate::{common::ZExtZBuf, zextzbuf};
+    use crate::{
+        common::{ZExtZ64, ZExtZBuf},
+        zextz64, zextzbuf,
+    };
+    use core::time::Duration;
 
     /// # SourceInfo extension
     /// Used to carry additional information about the source of data
@@ -75,6 +85,27 @@ pub mod ext {
     /// # User attachment
     pub type Attachment = zextzbuf!(0x3, false);
     pub type AttachmentType = crate::zenoh::ext::AttachmentType<{ Attachment::ID }>;
+
+    /// # Coherence extension
+    /// Used to mark the data as part of a coherent set of changes
+    pub type Coherence = zextzbuf!(0x4, false);
+    pub type CoherenceType = crate::zenoh::ext::CoherenceType<{ Coherence::ID }>;
+
+    /// # Time-to-live extension
+    /// Used to indicate for how long the data is valid after its timestamp
+    pub type Ttl = zextz64!(0x5, false);
+    pub type TtlType = Duration;
+
+    /// # Latency budget extension
+    /// Used to indicate the maximum delay after its timestamp for the data to be delivered,
+    /// in microseconds
+    pub type LatencyBudget = zextz64!(0x6, false);
+    pub type LatencyBudgetType = Duration;
+
+    /// # TraceId extension
+    /// Used to correlate the messages of a same exchange across the hops of the infrastructure
+    pub type TraceId = zextzbuf!(0x7, false);
+    pub type TraceIdType = crate::zenoh::ext::TraceIdType<{ TraceId::ID }>;
 }
 
 impl Put {
@@ -85,8 +116,8 @@ impl Put {
         let mut rng = rand::thread_rng();
 
         let timestamp = rng.gen_bool(0.5).then_some({
-            let time = uhlc::NTP64(rng.gen());
-            let id = uhlc::ID::try_from(ZenohId::rand().to_le_bytes()).unwrap();
+            let time = crate::core::NTP64(rng.gen());
+            let id = crate::core::TimestampId::try_from(ZenohId::rand().to_le_bytes()).unwrap();
             Timestamp::new(time, id)
         });
         let encoding = Encoding::rand();
@@ -94,12 +125,17 @@ impl Put {
         #[cfg(feature = "shared-memory")]
         let ext_shm = rng.gen_bool(0.5).then_some(ext::ShmType::rand());
         let ext_attachment = rng.gen_bool(0.5).then_some(ext::AttachmentType::rand());
+        let ext_coherence = rng.gen_bool(0.5).then_some(ext::CoherenceType::rand());
+        let ext_ttl = rng
+            .gen_bool(0.5)
+            .then_some(ext::TtlType::from_millis(rng.gen()));
+        let ext_latency_budget = rng
+            .gen_bool(0.5)
+            .then_some(ext::LatencyBudgetType::from_micros(rng.gen()));
+        let ext_trace = rng.gen_bool(0.5).then_some(ext::TraceIdType::rand());
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
-            ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::Attachment::ID) + 1,
-                false,
-            ));
+            ext_unknown.push(ZExtUnknown::rand2(iext::mid(ext::TraceId::ID) + 1, false));
         }
         let payload = ZBuf::rand(rng.gen_range(1..=64));
 
@@ -107,9 +143,13 @@ impl Put {
             timestamp,
             encoding,
             ext_sinfo,
+            ext_coherence,
+            ext_ttl,
+            ext_latency_budget,
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_trace,
             ext_unknown,
             payload,
         }
@@ -12,7 +12,8 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::{common::ZExtUnknown, core::ConsolidationMode};
-use alloc::{string::String, vec::Vec};
+use alloc::vec::Vec;
+use zenoh_buffers::ZStr;
 
 /// The kind of consolidation.
 #[repr(u8)]
@@ -90,11 +91,12 @@ pub mod flag {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Query {
-    pub parameters: String,
+    pub parameters: ZStr,
     pub ext_sinfo: Option<ext::SourceInfoType>,
     pub ext_consolidation: Consolidation,
     pub ext_body: Option<ext::QueryBodyType>,
     pub ext_attachment: Option<ext::AttachmentType>,
+    pub ext_trace: Option<ext::TraceIdType>,
     pub ext_unknown: Vec<ZExtUnknown>,
 }
 
@@ -122,37 +124,36 @@ pub mod ext {
     /// # User attachment
     pub type Attachment = zextzbuf!(0x5, false);
     pub type AttachmentType = crate::zenoh::ext::AttachmentType<{ Attachment::ID }>;
+
+    /// # TraceId extension
+    /// Used to correlate the messages of a same exchange across the hops of the infrastructure
+    pub type TraceId = zextzbuf!(0x6, false);
+    pub type TraceIdType = crate::zenoh::ext::TraceIdType<{ TraceId::ID }>;
 }
 
 impl Query {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
         use crate::common::iext;
-        use rand::{
-            distributions::{Alphanumeric, DistString},
-            Rng,
-        };
+        use rand::Rng;
         let mut rng = rand::thread_rng();
 
         const MIN: usize = 2;
         const MAX: usize = 16;
 
-        let parameters: String = if rng.gen_bool(0.5) {
-            let len = rng.gen_range(MIN..MAX);
-            Alphanumeric.sample_string(&mut rng, len)
+        let parameters = if rng.gen_bool(0.5) {
+            ZStr::rand(rng.gen_range(MIN..MAX))
         } else {
-            String::new()
+            ZStr::default()
         };
         let ext_sinfo = rng.gen_bool(0.5).then_some(ext::SourceInfoType::rand());
         let ext_consolidation = Consolidation::rand();
         let ext_body = rng.gen_bool(0.5).then_some(ext::QueryBodyType::rand());
         let ext_attachment = rng.gen_bool(0.5).then_some(ext::AttachmentType::rand());
+        let ext_trace = rng.gen_bool(0.5).then_some(ext::TraceIdType::rand());
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
-            ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::Attachment::ID) + 1,
-                false,
-            ));
+            ext_unknown.push(ZExtUnknown::rand2(iext::mid(ext::TraceId::ID) + 1, false));
         }
 
         Self {
@@ -161,6 +162,7 @@ impl Query {
             ext_consolidation,
             ext_body,
             ext_attachment,
+            ext_trace,
             ext_unknown,
         }
     }
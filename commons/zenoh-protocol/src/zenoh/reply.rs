@@ -11,9 +11,11 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::{common::ZExtUnknown, core::Encoding};
+use crate::{
+    common::ZExtUnknown,
+    core::{Encoding, Timestamp},
+};
 use alloc::vec::Vec;
-use uhlc::Timestamp;
 use zenoh_buffers::ZBuf;
 
 /// # Reply message
@@ -52,16 +54,16 @@ pub struct Reply {
     #[cfg(feature = "shared-memory")]
     pub ext_shm: Option<ext::ShmType>,
     pub ext_attachment: Option<ext::AttachmentType>,
+    pub ext_delete: Option<ext::Delete>,
+    pub ext_trace: Option<ext::TraceIdType>,
     pub ext_unknown: Vec<ZExtUnknown>,
     pub payload: ZBuf,
 }
 
 pub mod ext {
-    #[cfg(feature = "shared-memory")]
-    use crate::{common::ZExtUnit, zextunit};
     use crate::{
-        common::{ZExtZ64, ZExtZBuf},
-        zextz64, zextzbuf,
+        common::{ZExtUnit, ZExtZ64, ZExtZBuf},
+        zextunit, zextz64, zextzbuf,
     };
 
     /// # SourceInfo extension
@@ -83,6 +85,18 @@ pub mod ext {
     /// # User attachment
     pub type Attachment = zextzbuf!(0x4, false);
     pub type AttachmentType = crate::zenoh::ext::AttachmentType<{ Attachment::ID }>;
+
+    /// # Delete extension
+    /// Used to reply with a deletion rather than a value. It is mandatory since ignoring it
+    /// would turn the deletion into a put.
+    pub type Delete = zextunit!(0x5, true);
+    /// The protocol version that introduced the Delete extension.
+    pub const DELETE_VERSION: u8 = 0x09;
+
+    /// # TraceId extension
+    /// Used to correlate the messages of a same exchange across the hops of the infrastructure
+    pub type TraceId = zextzbuf!(0x6, false);
+    pub type TraceIdType = crate::zenoh::ext::TraceIdType<{ TraceId::ID }>;
 }
 
 impl Reply {
@@ -93,8 +107,8 @@ impl Reply {
         let mut rng = rand::thread_rng();
 
         let timestamp = rng.gen_bool(0.5).then_some({
-            let time = uhlc::NTP64(rng.gen());
-            let id = uhlc::ID::try_from(ZenohId::rand().to_le_bytes()).unwrap();
+            let time = crate::core::NTP64(rng.gen());
+            let id = crate::core::TimestampId::try_from(ZenohId::rand().to_le_bytes()).unwrap();
             Timestamp::new(time, id)
         });
         let encoding = Encoding::rand();
@@ -103,12 +117,11 @@ impl Reply {
         #[cfg(feature = "shared-memory")]
         let ext_shm = rng.gen_bool(0.5).then_some(ext::ShmType::rand());
         let ext_attachment = rng.gen_bool(0.5).then_some(ext::AttachmentType::rand());
+        let ext_delete = rng.gen_bool(0.5).then_some(ext::Delete::rand());
+        let ext_trace = rng.gen_bool(0.5).then_some(ext::TraceIdType::rand());
         let mut ext_unknown = Vec::new();
         for _ in 0..rng.gen_range(0..4) {
-            ext_unknown.push(ZExtUnknown::rand2(
-                iext::mid(ext::Attachment::ID) + 1,
-                false,
-            ));
+            ext_unknown.push(ZExtUnknown::rand2(iext::mid(ext::TraceId::ID) + 1, false));
         }
         let payload = ZBuf::rand(rng.gen_range(1..=64));
 
@@ -120,6 +133,8 @@ impl Reply {
             #[cfg(feature = "shared-memory")]
             ext_shm,
             ext_attachment,
+            ext_delete,
+            ext_trace,
             ext_unknown,
             payload,
         }
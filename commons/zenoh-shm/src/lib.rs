@@ -11,20 +11,26 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use shared_memory::{Shmem, ShmemConf, ShmemError};
 use std::{
     any::Any,
-    cmp,
-    collections::{binary_heap::BinaryHeap, HashMap},
+    collections::HashMap,
     fmt, mem,
-    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+    sync::{
+        atomic::{AtomicPtr, AtomicUsize, Ordering},
+        Arc,
+    },
 };
 use zenoh_buffers::ZSliceBuffer;
 use zenoh_result::{zerror, ShmError, ZResult};
 
+mod policy;
+mod provider;
+
+pub use policy::*;
+pub use provider::*;
+
 const MIN_FREE_CHUNK_SIZE: usize = 1_024;
 const ACCOUNTED_OVERHEAD: usize = 4_096;
-const ZENOH_SHM_PREFIX: &str = "zenoh_shm_zid";
 
 // Chunk header
 type ChunkHeaderType = AtomicUsize;
@@ -37,31 +43,6 @@ fn align_addr_at(addr: usize, align: usize) -> usize {
     }
 }
 
-#[derive(Eq, Copy, Clone, Debug)]
-struct Chunk {
-    base_addr: *mut u8,
-    offset: usize,
-    size: usize,
-}
-
-impl Ord for Chunk {
-    fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.size.cmp(&other.size)
-    }
-}
-
-impl PartialOrd for Chunk {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl PartialEq for Chunk {
-    fn eq(&self, other: &Self) -> bool {
-        self.size == other.size
-    }
-}
-
 /// Informations about a [`SharedMemoryBuf`].
 ///
 /// This that can be serialized and can be used to retrieve the [`SharedMemoryBuf`] in a remote process.
@@ -193,21 +174,25 @@ impl Clone for SharedMemoryBuf {
 /*       SHARED MEMORY READER        */
 /*************************************/
 pub struct SharedMemoryReader {
-    segments: HashMap<String, Shmem>,
+    provider: Arc<dyn ShmProvider>,
+    segments: HashMap<String, Box<dyn ShmSegment>>,
 }
 
-unsafe impl Send for SharedMemoryReader {}
-unsafe impl Sync for SharedMemoryReader {}
-
 impl SharedMemoryReader {
     pub fn new() -> Self {
+        Self::with_provider(Arc::new(DefaultShmProvider))
+    }
+
+    /// Creates a reader opening the shared memory segments through the given provider.
+    pub fn with_provider(provider: Arc<dyn ShmProvider>) -> Self {
         Self {
+            provider,
             segments: HashMap::new(),
         }
     }
 
     pub fn connect_map_to_shm(&mut self, info: &SharedMemoryBufInfo) -> ZResult<()> {
-        match ShmemConf::new().flink(&info.shm_manager).open() {
+        match self.provider.open(&info.shm_manager) {
             Ok(shm) => {
                 self.segments.insert(info.shm_manager.clone(), shm);
                 Ok(())
@@ -272,6 +257,53 @@ impl fmt::Debug for SharedMemoryReader {
     }
 }
 
+/// A snapshot of the state of a [`SharedMemoryManager`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShmStats {
+    /// The number of bytes managed, overhead included.
+    pub size: usize,
+    /// The number of bytes in the free chunks.
+    pub available: usize,
+    /// The size of the largest free chunk, which bounds the size of the next allocation.
+    pub largest_free_chunk: usize,
+    /// The number of free chunks.
+    pub free_chunks: usize,
+    /// The number of allocated chunks not garbage collected yet.
+    pub busy_chunks: usize,
+    /// The number of successful allocations.
+    pub allocations: u64,
+    /// The number of failed allocations.
+    pub alloc_failures: u64,
+    /// The number of garbage collections.
+    pub gc_runs: u64,
+    /// The number of defragmentations.
+    pub defrag_runs: u64,
+}
+
+impl ShmStats {
+    /// The share of the available memory that is not part of the largest free chunk.
+    ///
+    /// It ranges from `0.0`, when all the available memory is contiguous, to almost `1.0`
+    /// when it is scattered in many small chunks.
+    pub fn fragmentation(&self) -> f64 {
+        if self.available == 0 {
+            0.0
+        } else {
+            1.0 - self.largest_free_chunk as f64 / self.available as f64
+        }
+    }
+}
+
+#[derive(Default)]
+struct ShmCounters {
+    allocations: u64,
+    alloc_failures: u64,
+    gc_runs: u64,
+    defrag_runs: u64,
+}
+
+type AllocFailureHook = Box<dyn FnMut(usize, &ShmStats) + Send>;
+
 /// A shared memory segment manager.
 ///
 /// Allows to access a shared memory segment and reserve some parts of this segment for writting.
@@ -279,61 +311,50 @@ pub struct SharedMemoryManager {
     segment_path: String,
     size: usize,
     available: usize,
-    own_segment: Shmem,
-    free_list: BinaryHeap<Chunk>,
-    busy_list: Vec<Chunk>,
+    own_segment: Box<dyn ShmSegment>,
+    free_list: Vec<ShmChunk>,
+    busy_list: Vec<ShmChunk>,
     alignment: usize,
+    policy: Box<dyn ShmAllocPolicy>,
+    reclaim: ShmReclaimPolicy,
+    alloc_failure_hook: Option<AllocFailureHook>,
+    counters: ShmCounters,
 }
 
-unsafe impl Send for SharedMemoryManager {}
-
 impl SharedMemoryManager {
     /// Creates a new SharedMemoryManager managing allocations of a region of the
     /// given size.
     pub fn make(id: String, size: usize) -> ZResult<SharedMemoryManager> {
-        let mut temp_dir = std::env::temp_dir();
-        let file_name: String = format!("{ZENOH_SHM_PREFIX}_{id}");
-        temp_dir.push(file_name);
-        let path: String = temp_dir
-            .to_str()
-            .ok_or_else(|| ShmError(zerror!("Unable to parse tmp directory: {:?}", temp_dir)))?
-            .to_string();
-        tracing::trace!("Creating file at: {}", path);
+        Self::make_with_provider(id, size, &DefaultShmProvider)
+    }
+
+    /// Creates a new SharedMemoryManager managing allocations of a region of the
+    /// given size, created through the given provider.
+    pub fn make_with_provider(
+        id: String,
+        size: usize,
+        provider: &dyn ShmProvider,
+    ) -> ZResult<SharedMemoryManager> {
         let real_size = size + ACCOUNTED_OVERHEAD;
-        let shmem = match ShmemConf::new()
-            .size(real_size)
-            .flink(path.clone())
-            .create()
-        {
-            Ok(m) => m,
-            Err(ShmemError::LinkExists) => {
-                return Err(ShmError(zerror!(
-                    "Unable to open SharedMemoryManager: SharedMemory already exists"
-                ))
-                .into())
-            }
-            Err(e) => {
-                return Err(ShmError(zerror!("Unable to open SharedMemoryManager: {}", e)).into())
-            }
-        };
-        let base_ptr = shmem.as_ptr();
+        let (path, segment) = provider.create(&id, real_size)?;
 
-        let mut free_list = BinaryHeap::new();
-        let chunk = Chunk {
-            base_addr: base_ptr,
+        let free_list = vec![ShmChunk {
             offset: 0,
             size: real_size,
-        };
-        free_list.push(chunk);
+        }];
         let busy_list = vec![];
         let shm = SharedMemoryManager {
             segment_path: path,
             size,
             available: real_size,
-            own_segment: shmem,
+            own_segment: segment,
             free_list,
             busy_list,
             alignment: mem::align_of::<ChunkHeaderType>(),
+            policy: Box::new(LargestFirst),
+            reclaim: ShmReclaimPolicy::default(),
+            alloc_failure_hook: None,
+            counters: ShmCounters::default(),
         };
         tracing::trace!(
             "Created SharedMemoryManager for {:?}",
@@ -342,24 +363,79 @@ impl SharedMemoryManager {
         Ok(shm)
     }
 
-    fn free_chunk_map_to_shmbuf(&self, chunk: &Chunk) -> SharedMemoryBuf {
+    /// Sets the policy selecting the free chunk each allocation is carved from.
+    ///
+    /// By default, the [`LargestFirst`] policy is used.
+    pub fn with_alloc_policy<P>(mut self, policy: P) -> Self
+    where
+        P: ShmAllocPolicy + 'static,
+    {
+        self.policy = Box::new(policy);
+        self
+    }
+
+    /// Sets the policy automatically garbage collecting and defragmenting the memory.
+    pub fn with_reclaim_policy(mut self, reclaim: ShmReclaimPolicy) -> Self {
+        self.reclaim = reclaim;
+        self
+    }
+
+    /// Sets a hook called with the requested length and the [`ShmStats`] of the manager
+    /// whenever an allocation fails.
+    pub fn with_alloc_failure_hook<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(usize, &ShmStats) + Send + 'static,
+    {
+        self.alloc_failure_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Returns a snapshot of the usage and fragmentation of the managed memory.
+    pub fn stats(&self) -> ShmStats {
+        ShmStats {
+            size: self.size + ACCOUNTED_OVERHEAD,
+            available: self.available,
+            largest_free_chunk: self.free_list.iter().map(|c| c.size).max().unwrap_or(0),
+            free_chunks: self.free_list.len(),
+            busy_chunks: self.busy_list.len(),
+            allocations: self.counters.allocations,
+            alloc_failures: self.counters.alloc_failures,
+            gc_runs: self.counters.gc_runs,
+            defrag_runs: self.counters.defrag_runs,
+        }
+    }
+
+    fn chunk_addr(&self, chunk: &ShmChunk) -> *mut u8 {
+        // SAFETY: the chunks are always within the bounds of the own segment.
+        unsafe { self.own_segment.as_ptr().add(chunk.offset) }
+    }
+
+    fn free_chunk_map_to_shmbuf(&self, chunk: &ShmChunk) -> SharedMemoryBuf {
         let info = SharedMemoryBufInfo {
             offset: chunk.offset,
             length: chunk.size,
             shm_manager: self.segment_path.clone(),
             kind: 0,
         };
-        let rc = chunk.base_addr as *mut ChunkHeaderType;
+        let base_addr = self.chunk_addr(chunk);
+        let rc = base_addr as *mut ChunkHeaderType;
         unsafe { (*rc).store(1, Ordering::SeqCst) };
         let rc_ptr = AtomicPtr::<ChunkHeaderType>::new(rc);
         SharedMemoryBuf {
             rc_ptr,
-            buf: AtomicPtr::<u8>::new(unsafe { chunk.base_addr.add(CHUNK_HEADER_SIZE) }),
+            buf: AtomicPtr::<u8>::new(unsafe { base_addr.add(CHUNK_HEADER_SIZE) }),
             len: chunk.size - CHUNK_HEADER_SIZE,
             info,
         }
     }
 
+    fn select_free_chunk(&self, required_len: usize) -> Option<usize> {
+        if self.available < required_len {
+            return None;
+        }
+        self.policy.select(&self.free_list, required_len)
+    }
+
     pub fn alloc(&mut self, len: usize) -> ZResult<SharedMemoryBuf> {
         tracing::trace!("SharedMemoryManager::alloc({})", len);
         // Always allocate a size that will keep the proper alignment requirements
@@ -367,114 +443,126 @@ impl SharedMemoryManager {
         if self.available < required_len {
             self.garbage_collect();
         }
-        if self.available >= required_len {
-            // The strategy taken is the same for some Unix System V implementations -- as described in the
-            // famous Bach's book --  in essence keep an ordered list of free slot and always look for the
-            // biggest as that will give the biggest left-over.
-            match self.free_list.pop() {
-                Some(mut chunk) if chunk.size >= required_len => {
-                    self.available -= required_len;
-                    tracing::trace!("Allocator selected Chunk ({:?})", &chunk);
-                    if chunk.size - required_len >= MIN_FREE_CHUNK_SIZE {
-                        let free_chunk = Chunk {
-                            base_addr: unsafe { chunk.base_addr.add(required_len) },
-                            offset: chunk.offset + required_len,
-                            size: chunk.size - required_len,
-                        };
-                        tracing::trace!(
-                            "The allocation will leave a Free Chunk: {:?}",
-                            &free_chunk
-                        );
-                        self.free_list.push(free_chunk);
-                    }
+        let mut index = self.select_free_chunk(required_len);
+        if index.is_none() && self.reclaim.reclaim_on_failure {
+            tracing::trace!("SharedMemoryManager::alloc({}) reclaiming memory", len);
+            self.garbage_collect();
+            self.defragment();
+            index = self.select_free_chunk(required_len);
+        }
+
+        match index {
+            Some(index) => {
+                let mut chunk = self.free_list.swap_remove(index);
+                tracing::trace!("Allocator selected Chunk ({:?})", &chunk);
+                if chunk.size - required_len >= MIN_FREE_CHUNK_SIZE {
+                    let free_chunk = ShmChunk {
+                        offset: chunk.offset + required_len,
+                        size: chunk.size - required_len,
+                    };
+                    tracing::trace!("The allocation will leave a Free Chunk: {:?}", &free_chunk);
+                    self.free_list.push(free_chunk);
                     chunk.size = required_len;
-                    let shm_buf = self.free_chunk_map_to_shmbuf(&chunk);
-                    tracing::trace!("The allocated Chunk is ({:?})", &chunk);
-                    tracing::trace!("Allocated Shared Memory Buffer: {:?}", &shm_buf);
-                    self.busy_list.push(chunk);
-                    Ok(shm_buf)
                 }
-                Some(c) => {
-                    self.free_list.push(c);
-                    let e = zerror!("SharedMemoryManager::alloc({}) cannot find any available chunk\nSharedMemoryManager::free_list = {:?}", len, self.free_list);
-                    Err(e.into())
+                // A left-over too small to be a free chunk stays part of the busy chunk,
+                // so that it is given back along with the buffer by the garbage collection.
+                self.available -= chunk.size;
+                let shm_buf = self.free_chunk_map_to_shmbuf(&ShmChunk {
+                    offset: chunk.offset,
+                    size: required_len,
+                });
+                tracing::trace!("The allocated Chunk is ({:?})", &chunk);
+                tracing::trace!("Allocated Shared Memory Buffer: {:?}", &shm_buf);
+                self.busy_list.push(chunk);
+                self.counters.allocations += 1;
+
+                if self.available < self.reclaim.gc_watermark {
+                    self.garbage_collect();
+                    if self.stats().fragmentation() > self.reclaim.defrag_watermark {
+                        self.defragment();
+                    }
                 }
-                None => {
+                Ok(shm_buf)
+            }
+            None => {
+                self.counters.alloc_failures += 1;
+                let stats = self.stats();
+                if let Some(hook) = self.alloc_failure_hook.as_mut() {
+                    hook(len, &stats);
+                }
+                if self.available < required_len {
+                    let e = zerror!( "SharedMemoryManager does not have sufficient free memory to allocate {} bytes, try de-fragmenting!", len);
+                    tracing::warn!("{}", e);
+                    Err(e.into())
+                } else {
                     let e = zerror!("SharedMemoryManager::alloc({}) cannot find any available chunk\nSharedMemoryManager::free_list = {:?}", len, self.free_list);
                     tracing::trace!("{}", e);
                     Err(e.into())
                 }
             }
-        } else {
-            let e = zerror!( "SharedMemoryManager does not have sufficient free memory to allocate {} bytes, try de-fragmenting!", len);
-            tracing::warn!("{}", e);
-            Err(e.into())
         }
     }
 
-    fn is_free_chunk(chunk: &Chunk) -> bool {
-        let rc_ptr = chunk.base_addr as *mut ChunkHeaderType;
+    fn is_free_chunk(&self, chunk: &ShmChunk) -> bool {
+        let rc_ptr = self.chunk_addr(chunk) as *mut ChunkHeaderType;
         let rc = unsafe { (*rc_ptr).load(Ordering::SeqCst) };
         rc == 0
     }
 
-    fn try_merge_adjacent_chunks(a: &Chunk, b: &Chunk) -> Option<Chunk> {
-        let end_addr = unsafe { a.base_addr.add(a.size) };
-        if end_addr == b.base_addr {
-            Some(Chunk {
-                base_addr: a.base_addr,
-                size: a.size + b.size,
+    fn try_merge_adjacent_chunks(a: &ShmChunk, b: &ShmChunk) -> Option<ShmChunk> {
+        if a.offset + a.size == b.offset {
+            Some(ShmChunk {
                 offset: a.offset,
+                size: a.size + b.size,
             })
         } else {
             None
         }
     }
-    // Returns the amount of memory that it was able to de-fragment
+
+    /// Merges the adjacent free chunks.
+    ///
+    /// Returns the amount of free memory merged into a preceding chunk.
     pub fn defragment(&mut self) -> usize {
-        if self.free_list.len() > 1 {
-            let mut fbs: Vec<Chunk> = self.free_list.drain().collect();
-            fbs.sort_by(|x, y| x.offset.partial_cmp(&y.offset).unwrap());
-            let mut current = fbs.remove(0);
-            let mut defrag_mem = 0;
-            let mut i = 0;
-            let n = fbs.len();
-            for chunk in fbs.iter() {
-                i += 1;
-                let next = *chunk;
-                match SharedMemoryManager::try_merge_adjacent_chunks(&current, &next) {
-                    Some(c) => {
-                        current = c;
-                        defrag_mem += current.size;
-                        if i == n {
-                            self.free_list.push(current)
-                        }
-                    }
-                    None => {
-                        self.free_list.push(current);
-                        if i == n {
-                            self.free_list.push(next);
-                        } else {
-                            current = next;
-                        }
-                    }
+        self.counters.defrag_runs += 1;
+        if self.free_list.len() < 2 {
+            return 0;
+        }
+
+        let mut fbs: Vec<ShmChunk> = self.free_list.drain(..).collect();
+        fbs.sort_by_key(|c| c.offset);
+        let mut defrag_mem = 0;
+        for next in fbs {
+            let merged = self
+                .free_list
+                .last()
+                .and_then(|current| SharedMemoryManager::try_merge_adjacent_chunks(current, &next));
+            match merged {
+                Some(c) => {
+                    defrag_mem += next.size;
+                    *self.free_list.last_mut().unwrap() = c;
                 }
+                None => self.free_list.push(next),
             }
-            defrag_mem
-        } else {
-            0
         }
+        tracing::trace!(
+            "De-fragmented {} bytes, {} free chunks left",
+            defrag_mem,
+            self.free_list.len()
+        );
+        defrag_mem
     }
 
+    /// Gives back the memory of the buffers no longer referenced by any process.
+    ///
     /// Returns the amount of memory freed
     pub fn garbage_collect(&mut self) -> usize {
         tracing::trace!("Running Garbage Collector");
+        self.counters.gc_runs += 1;
 
         let mut freed = 0;
-        let (free, busy) = self
-            .busy_list
-            .iter()
-            .partition(|&c| SharedMemoryManager::is_free_chunk(c));
+        let (free, busy): (Vec<ShmChunk>, Vec<ShmChunk>) =
+            self.busy_list.iter().partition(|&c| self.is_free_chunk(c));
         self.busy_list = busy;
 
         for f in free {
@@ -523,4 +611,57 @@ impl ZSliceBuffer for SharedMemoryBuf {
     fn as_any(&self) -> &dyn Any {
         self
     }
+    fn is_volatile(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    fn fragmented_manager(id: &str, reclaim: ShmReclaimPolicy) -> SharedMemoryManager {
+        let id = format!("{id}_{}", std::process::id());
+        let mut shm = SharedMemoryManager::make(id, 4_096)
+            .unwrap()
+            .with_reclaim_policy(reclaim);
+        // Fill the whole segment with small buffers and release them right away
+        let bufs: Vec<SharedMemoryBuf> = (0..8).map(|_| shm.alloc(1_000).unwrap()).collect();
+        assert!(shm.alloc(1_000).is_err());
+        drop(bufs);
+        shm
+    }
+
+    #[test]
+    fn shm_reclaim() {
+        let reclaim = ShmReclaimPolicy {
+            reclaim_on_failure: false,
+            ..Default::default()
+        };
+        let failures = Arc::new(AtomicU64::new(0));
+        let c_failures = failures.clone();
+        let mut shm = fragmented_manager("test_shm_reclaim_manual", reclaim)
+            .with_alloc_failure_hook(move |_, _| {
+                c_failures.fetch_add(1, Ordering::SeqCst);
+            });
+
+        // The memory is available but scattered in small chunks
+        assert!(shm.alloc(2_000).is_err());
+        assert_eq!(failures.load(Ordering::SeqCst), 1);
+        let stats = shm.stats();
+        assert_eq!(stats.available, stats.size);
+        assert!(stats.fragmentation() > 0.5);
+
+        assert!(shm.defragment() > 0);
+        let stats = shm.stats();
+        assert_eq!(stats.free_chunks, 1);
+        assert_eq!(stats.fragmentation(), 0.0);
+        assert!(shm.alloc(2_000).is_ok());
+
+        // The default policy reclaims the memory on allocation failures
+        let mut shm = fragmented_manager("test_shm_reclaim_auto", ShmReclaimPolicy::default());
+        assert!(shm.alloc(2_000).is_ok());
+        assert!(shm.stats().defrag_runs > 0);
+    }
 }
@@ -0,0 +1,132 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+/// A contiguous region of a shared memory segment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShmChunk {
+    /// The index of the beginning of the chunk in the segment.
+    pub offset: usize,
+    /// The size of the chunk in bytes.
+    pub size: usize,
+}
+
+/// The policy selecting the free chunk an allocation is carved from.
+pub trait ShmAllocPolicy: Send {
+    /// Returns the index in `free` of a chunk of at least `len` bytes, if any.
+    fn select(&self, free: &[ShmChunk], len: usize) -> Option<usize>;
+}
+
+/// Select the largest free chunk, as that gives the largest left-over.
+///
+/// This is the strategy of some Unix System V implementations, as described in the famous
+/// Bach's book, and the default one of the [`SharedMemoryManager`](crate::SharedMemoryManager).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LargestFirst;
+
+impl ShmAllocPolicy for LargestFirst {
+    fn select(&self, free: &[ShmChunk], len: usize) -> Option<usize> {
+        free.iter()
+            .enumerate()
+            .max_by_key(|(_, c)| c.size)
+            .filter(|(_, c)| c.size >= len)
+            .map(|(i, _)| i)
+    }
+}
+
+/// Select the smallest free chunk the allocation fits in, which keeps the large chunks
+/// available for the large allocations.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BestFit;
+
+impl ShmAllocPolicy for BestFit {
+    fn select(&self, free: &[ShmChunk], len: usize) -> Option<usize> {
+        free.iter()
+            .enumerate()
+            .filter(|(_, c)| c.size >= len)
+            .min_by_key(|(_, c)| c.size)
+            .map(|(i, _)| i)
+    }
+}
+
+/// Select the free chunk with the lowest offset the allocation fits in, which packs the
+/// allocations at the beginning of the segment.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstFit;
+
+impl ShmAllocPolicy for FirstFit {
+    fn select(&self, free: &[ShmChunk], len: usize) -> Option<usize> {
+        free.iter()
+            .enumerate()
+            .filter(|(_, c)| c.size >= len)
+            .min_by_key(|(_, c)| c.offset)
+            .map(|(i, _)| i)
+    }
+}
+
+/// The policy automatically reclaiming the memory of a
+/// [`SharedMemoryManager`](crate::SharedMemoryManager).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShmReclaimPolicy {
+    /// Garbage collect the released buffers after an allocation leaving less than this
+    /// amount of available bytes. A value of `0` disables it.
+    pub gc_watermark: usize,
+    /// Defragment the free chunks after a garbage collection leaving a fragmentation ratio,
+    /// as given by [`ShmStats::fragmentation`](crate::ShmStats::fragmentation), above this value.
+    pub defrag_watermark: f64,
+    /// Garbage collect, defragment and retry once when no free chunk can hold an allocation.
+    pub reclaim_on_failure: bool,
+}
+
+impl Default for ShmReclaimPolicy {
+    fn default() -> Self {
+        Self {
+            gc_watermark: 0,
+            defrag_watermark: 0.5,
+            reclaim_on_failure: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alloc_policies() {
+        let free = [
+            ShmChunk {
+                offset: 4_096,
+                size: 2_048,
+            },
+            ShmChunk {
+                offset: 0,
+                size: 1_024,
+            },
+            ShmChunk {
+                offset: 8_192,
+                size: 8_192,
+            },
+        ];
+
+        assert_eq!(LargestFirst.select(&free, 512), Some(2));
+        assert_eq!(BestFit.select(&free, 512), Some(1));
+        assert_eq!(BestFit.select(&free, 1_500), Some(0));
+        assert_eq!(FirstFit.select(&free, 512), Some(1));
+        assert_eq!(FirstFit.select(&free, 4_096), Some(2));
+
+        assert_eq!(LargestFirst.select(&free, 16_384), None);
+        assert_eq!(BestFit.select(&free, 16_384), None);
+        assert_eq!(FirstFit.select(&[], 1), None);
+    }
+}
@@ -0,0 +1,284 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use shared_memory::{Shmem, ShmemConf, ShmemError};
+use std::{
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+    sync::Once,
+};
+use zenoh_result::{zerror, ShmError, ZResult};
+
+const ZENOH_SHM_PREFIX: &str = "zenoh_shm_zid";
+const ZENOH_SHM_LOCK_EXTENSION: &str = "lock";
+
+/// A shared memory segment mapped in the address space of the process.
+pub trait ShmSegment: Send + Sync {
+    /// The address the segment is mapped at.
+    fn as_ptr(&self) -> *mut u8;
+
+    /// The size of the segment in bytes.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A backend creating and opening the shared memory segments.
+///
+/// A [`SharedMemoryManager`](crate::SharedMemoryManager) allocates its buffers in a segment
+/// created by a provider, and a [`SharedMemoryReader`](crate::SharedMemoryReader) maps the
+/// segments of the other processes through a provider. Hence, the processes exchanging
+/// shared memory buffers need to use compatible providers.
+pub trait ShmProvider: Send + Sync {
+    /// Creates a segment of at least `size` bytes for the given `id`.
+    ///
+    /// It returns the segment along with the identifier the other processes open it with.
+    fn create(&self, id: &str, size: usize) -> ZResult<(String, Box<dyn ShmSegment>)>;
+
+    /// Opens the segment created by another process with the given identifier.
+    fn open(&self, id: &str) -> ZResult<Box<dyn ShmSegment>>;
+}
+
+/// The [`ShmProvider`] of the platform the process runs on.
+#[cfg(unix)]
+pub type DefaultShmProvider = PosixShmProvider;
+/// The [`ShmProvider`] of the platform the process runs on.
+#[cfg(windows)]
+pub type DefaultShmProvider = WindowsShmProvider;
+
+/// The default [`ShmProvider`] on unix platforms, backed by POSIX shared memory.
+///
+/// The segments are identified by a link file created in the temporary directory.
+#[cfg(unix)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PosixShmProvider;
+
+#[cfg(unix)]
+impl ShmProvider for PosixShmProvider {
+    fn create(&self, id: &str, size: usize) -> ZResult<(String, Box<dyn ShmSegment>)> {
+        create_linked(ShmemConf::new(), id, size)
+    }
+
+    fn open(&self, id: &str) -> ZResult<Box<dyn ShmSegment>> {
+        open_linked(id)
+    }
+}
+
+/// The default [`ShmProvider`] on Windows, backed by named file mappings.
+///
+/// The segments are identified by a link file created in the temporary directory, which
+/// holds the name of the file mapping.
+#[cfg(windows)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WindowsShmProvider;
+
+#[cfg(windows)]
+impl ShmProvider for WindowsShmProvider {
+    fn create(&self, id: &str, size: usize) -> ZResult<(String, Box<dyn ShmSegment>)> {
+        // Name the mapping after the link file, so that it can be told apart in the
+        // session namespace of the kernel objects.
+        let os_id = format!("{ZENOH_SHM_PREFIX}_{}_{id}", std::process::id());
+        create_linked(ShmemConf::new().os_id(os_id), id, size)
+    }
+
+    fn open(&self, id: &str) -> ZResult<Box<dyn ShmSegment>> {
+        open_linked(id)
+    }
+}
+
+fn create_linked(conf: ShmemConf, id: &str, size: usize) -> ZResult<(String, Box<dyn ShmSegment>)> {
+    static CLEANUP: Once = Once::new();
+    CLEANUP.call_once(cleanup_stale_segments);
+
+    let mut temp_dir = std::env::temp_dir();
+    let file_name: String = format!("{ZENOH_SHM_PREFIX}_{}_{id}", std::process::id());
+    temp_dir.push(file_name);
+    let path: String = temp_dir
+        .to_str()
+        .ok_or_else(|| ShmError(zerror!("Unable to parse tmp directory: {:?}", temp_dir)))?
+        .to_string();
+    // The lock is taken before the link file exists, so that a segment is never seen without
+    // its owner holding the lock
+    let lock = OwnerLock::acquire(&temp_dir).ok_or_else(|| {
+        ShmError(zerror!(
+            "Unable to open SharedMemoryManager: unable to lock {}",
+            path
+        ))
+    })?;
+    tracing::trace!("Creating file at: {}", path);
+    let shmem = match conf.size(size).flink(path.clone()).create() {
+        Ok(m) => m,
+        Err(ShmemError::LinkExists) => {
+            return Err(ShmError(zerror!(
+                "Unable to open SharedMemoryManager: SharedMemory already exists"
+            ))
+            .into())
+        }
+        Err(e) => {
+            return Err(ShmError(zerror!("Unable to open SharedMemoryManager: {}", e)).into())
+        }
+    };
+    Ok((path, Box::new(LinkedShmSegment(shmem, Some(lock)))))
+}
+
+fn open_linked(id: &str) -> ZResult<Box<dyn ShmSegment>> {
+    let shmem = ShmemConf::new()
+        .flink(id)
+        .open()
+        .map_err(|e| ShmError(zerror!("{:?}", e)))?;
+    Ok(Box::new(LinkedShmSegment(shmem, None)))
+}
+
+/// An exclusive lock on a file next to the link file of a segment, held by the process that
+/// created the segment for as long as the segment exists.
+///
+/// The system releases the lock when the process terminates, whatever the PID namespace the
+/// process runs in and even if its PID gets reused.
+struct OwnerLock {
+    file: Option<File>,
+    path: PathBuf,
+}
+
+impl OwnerLock {
+    fn path(link: &Path) -> PathBuf {
+        let mut path = link.as_os_str().to_owned();
+        path.push(".");
+        path.push(ZENOH_SHM_LOCK_EXTENSION);
+        path.into()
+    }
+
+    /// Takes the lock of the segment linked at the given path, if no process holds it.
+    fn acquire(link: &Path) -> Option<OwnerLock> {
+        let path = Self::path(link);
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create(true);
+        // The file can't be opened again as long as the owner keeps it open
+        #[cfg(windows)]
+        std::os::windows::fs::OpenOptionsExt::share_mode(&mut options, 0);
+        let file = options.open(&path).ok()?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            // SAFETY: the file descriptor is valid as long as the file is open.
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+                return None;
+            }
+        }
+        Some(OwnerLock {
+            file: Some(file),
+            path,
+        })
+    }
+}
+
+impl Drop for OwnerLock {
+    fn drop(&mut self) {
+        // The file is closed before being removed, as required on Windows
+        drop(self.file.take());
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Removes the segments left behind by the processes that terminated abnormally.
+///
+/// A process exiting normally removes its segments when dropping its managers. Otherwise,
+/// the link files of its segments are found in the temporary directory, next to lock files
+/// that no process holds anymore. The link files without a lock file are left untouched.
+fn cleanup_stale_segments() {
+    let temp_dir = std::env::temp_dir();
+    let Ok(entries) = std::fs::read_dir(&temp_dir) else {
+        return;
+    };
+    let prefix = format!("{ZENOH_SHM_PREFIX}_");
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_link = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|n| n.starts_with(&prefix))
+            && path.extension().and_then(|e| e.to_str()) != Some(ZENOH_SHM_LOCK_EXTENSION);
+        if !is_link || !OwnerLock::path(&path).exists() {
+            continue;
+        }
+        // The lock is released once the segment is removed
+        if let Some(_lock) = OwnerLock::acquire(&path) {
+            remove_stale_segment(path);
+        }
+    }
+}
+
+fn remove_stale_segment(path: PathBuf) {
+    tracing::debug!("Removing stale shared memory segment: {}", path.display());
+    // Taking the ownership of the segment makes it unlinked on drop, if it still exists
+    if let Ok(mut shmem) = ShmemConf::new().flink(&path).open() {
+        shmem.set_owner(true);
+    }
+    let _ = std::fs::remove_file(&path);
+}
+
+// The segment is unlinked before its lock is released
+struct LinkedShmSegment(Shmem, Option<OwnerLock>);
+
+// SAFETY: the mapping is only accessed through raw pointers whose
+//         concurrent use is synchronized by the chunk reference counts.
+unsafe impl Send for LinkedShmSegment {}
+unsafe impl Sync for LinkedShmSegment {}
+
+impl ShmSegment for LinkedShmSegment {
+    fn as_ptr(&self) -> *mut u8 {
+        self.0.as_ptr()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shm_stale_segments() {
+        // A segment whose owner terminated without removing its lock file
+        let mut stale = std::env::temp_dir();
+        stale.push(format!("{ZENOH_SHM_PREFIX}_{}_test_stale", u32::MAX));
+        std::fs::write(&stale, b"").unwrap();
+        std::fs::write(OwnerLock::path(&stale), b"").unwrap();
+
+        // A segment without lock file, whose owner is unknown
+        let mut unknown = std::env::temp_dir();
+        unknown.push(format!("{ZENOH_SHM_PREFIX}_{}_test_unknown", u32::MAX));
+        std::fs::write(&unknown, b"").unwrap();
+
+        let (link, segment) = DefaultShmProvider.create("test_live", 1_024).unwrap();
+        assert!(segment.len() >= 1_024);
+
+        cleanup_stale_segments();
+        assert!(!stale.exists());
+        assert!(!OwnerLock::path(&stale).exists());
+        assert!(unknown.exists());
+        assert!(Path::new(&link).exists());
+        assert!(DefaultShmProvider.open(&link).is_ok());
+
+        // The lock of a live segment can't be taken, and is removed along with the segment
+        assert!(OwnerLock::acquire(Path::new(&link)).is_none());
+        drop(segment);
+        assert!(!Path::new(&link).exists());
+        assert!(!OwnerLock::path(Path::new(&link)).exists());
+
+        std::fs::remove_file(&unknown).unwrap();
+    }
+}
@@ -31,6 +31,9 @@ pub use lifo_queue::*;
 pub mod object_pool;
 pub use object_pool::*;
 
+pub mod zslice_pool;
+pub use zslice_pool::*;
+
 pub mod mvar;
 pub use mvar::*;
 
@@ -0,0 +1,232 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    any::Any,
+    cell::UnsafeCell,
+    fmt,
+    sync::{
+        atomic::{fence, AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use zenoh_buffers::{ZSlice, ZSliceBuffer};
+
+/// Provides a pool of pre-allocated buffers that are shared as [`ZSlice`]s.
+///
+/// A buffer is handed out again once all the [`ZSlice`]s pointing into it have been dropped.
+/// Contrary to a [`RecyclingObjectPool`](crate::RecyclingObjectPool), the reference counter
+/// shared by those [`ZSlice`]s is allocated along with the buffer once and for all, so that
+/// taking a buffer and sharing it does not allocate as long as the pool is not exhausted.
+pub struct ZSlicePool {
+    slots: Box<[Arc<ZSliceSlot>]>,
+    next: AtomicUsize,
+    size: usize,
+}
+
+impl ZSlicePool {
+    /// Creates a pool of `num` buffers of `size` bytes each.
+    pub fn new(num: usize, size: usize) -> ZSlicePool {
+        ZSlicePool {
+            slots: (0..num).map(|_| Arc::new(ZSliceSlot::new(size))).collect(),
+            next: AtomicUsize::new(0),
+            size,
+        }
+    }
+
+    /// The number of buffers of the pool.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// The size of the buffers of the pool.
+    pub fn buffer_size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns a buffer that is neither taken nor shared anymore, if any.
+    pub fn try_take(&self) -> Option<PooledBuffer> {
+        let num = self.slots.len();
+        let first = self.next.load(Ordering::Relaxed);
+        for i in 0..num {
+            let idx = (first + i) % num;
+            let slot = &self.slots[idx];
+            if slot
+                .taken
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                continue;
+            }
+            // The pool holds the only reference once all the slices have been dropped
+            if Arc::strong_count(slot) == 1 {
+                // Synchronize with the release of the last slice, as Arc::get_mut() does
+                fence(Ordering::Acquire);
+                self.next.store((idx + 1) % num, Ordering::Relaxed);
+                return Some(PooledBuffer {
+                    slot: slot.clone(),
+                    pooled: true,
+                });
+            }
+            slot.taken.store(false, Ordering::Release);
+        }
+        None
+    }
+
+    /// Returns a buffer of the pool or, if the pool is exhausted, a newly allocated one
+    /// that is not returned to the pool.
+    pub fn take(&self) -> PooledBuffer {
+        self.try_take().unwrap_or_else(|| PooledBuffer {
+            slot: Arc::new(ZSliceSlot::new(self.size)),
+            pooled: false,
+        })
+    }
+}
+
+impl fmt::Debug for ZSlicePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZSlicePool")
+            .field("len", &self.len())
+            .field("buffer_size", &self.size)
+            .finish()
+    }
+}
+
+/// A buffer taken from a [`ZSlicePool`], exclusively owned until it is shared as a [`ZSlice`].
+pub struct PooledBuffer {
+    slot: Arc<ZSliceSlot>,
+    pooled: bool,
+}
+
+impl PooledBuffer {
+    pub fn as_slice(&self) -> &[u8] {
+        self.slot.as_slice()
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: the buffer has been taken while no slice was pointing into it and no
+        //         slice can be created before the PooledBuffer is consumed.
+        unsafe { &mut *self.slot.buffer.get() }
+    }
+
+    /// Returns whether the buffer is returned to its pool once it is no longer used.
+    pub fn is_pooled(&self) -> bool {
+        self.pooled
+    }
+
+    /// Shares the `start..end` range of the buffer as a [`ZSlice`] without allocating.
+    pub fn into_zslice(self, start: usize, end: usize) -> Option<ZSlice> {
+        ZSlice::make(self.slot.clone(), start, end).ok()
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        // The slices created from the buffer keep it from being taken again until dropped
+        self.slot.taken.store(false, Ordering::Release);
+    }
+}
+
+impl fmt::Debug for PooledBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledBuffer")
+            .field("len", &self.as_slice().len())
+            .field("pooled", &self.pooled)
+            .finish()
+    }
+}
+
+struct ZSliceSlot {
+    taken: AtomicBool,
+    buffer: UnsafeCell<Box<[u8]>>,
+}
+
+impl ZSliceSlot {
+    fn new(size: usize) -> Self {
+        Self {
+            taken: AtomicBool::new(false),
+            buffer: UnsafeCell::new(vec![0_u8; size].into_boxed_slice()),
+        }
+    }
+}
+
+// SAFETY: the buffer is only mutated through the PooledBuffer holding the slot,
+//         while the slot is neither taken by another PooledBuffer nor shared by a ZSlice.
+unsafe impl Sync for ZSliceSlot {}
+
+impl ZSliceBuffer for ZSliceSlot {
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: see the Sync implementation.
+        unsafe { &*self.buffer.get() }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.buffer.get_mut()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl fmt::Debug for ZSliceSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZSliceSlot")
+            .field("len", &self.as_slice().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zslice_pool() {
+        let pool = ZSlicePool::new(2, 16);
+
+        let mut a = pool.take();
+        assert!(a.is_pooled());
+        a.as_mut_slice()[..4].copy_from_slice(&[1, 2, 3, 4]);
+        let za = a.into_zslice(0, 4).unwrap();
+        let zb = pool.take().into_zslice(0, 16).unwrap();
+
+        // Both buffers are shared, the pool is exhausted
+        assert!(pool.try_take().is_none());
+        let c = pool.take();
+        assert!(!c.is_pooled());
+        drop(c);
+
+        // A sub-slice keeps the buffer from being reused
+        let sub = za.subslice(1, 3).unwrap();
+        drop(za);
+        assert!(pool.try_take().is_none());
+        assert_eq!(sub.as_slice(), &[2, 3]);
+        drop(sub);
+
+        // The buffer is handed out again once all its slices are dropped
+        let a = pool.try_take().unwrap();
+        assert_eq!(&a.as_slice()[..4], &[1, 2, 3, 4]);
+        assert!(pool.try_take().is_none());
+        drop(zb);
+        assert!(pool.try_take().is_some());
+
+        // A buffer that is taken but never shared is returned too
+        drop(a);
+        assert!(pool.try_take().is_some());
+    }
+}
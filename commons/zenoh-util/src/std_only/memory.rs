@@ -0,0 +1,316 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// The subsystems whose memory is accounted by a [`MemoryBudget`].
+///
+/// The variants are listed in degradation order: when the overall usage approaches the budget,
+/// reservations are refused to the first subsystems before the last ones. The routing tables
+/// are never refused, their usage is accounted so that the other subsystems degrade first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemorySubsystem {
+    /// The state kept for the queries waiting for their replies, including the replies
+    /// kept for their consolidation.
+    /// Refused once the overall usage exceeds 80% of the budget.
+    PendingQueries,
+    /// The queues of the subscriber, queryable and query handlers.
+    /// Refused once the overall usage exceeds 85% of the budget.
+    HandlerQueues,
+    /// The buffers reassembling fragmented messages.
+    /// Refused once the overall usage exceeds 90% of the budget.
+    Defragmentation,
+    /// The buffers receiving batches from the links.
+    /// Refused once the overall usage would exceed the budget.
+    RxBuffers,
+    /// The resources of the routing tables.
+    /// Never refused, see [`MemoryBudget::reserve`].
+    RoutingTables,
+}
+
+impl MemorySubsystem {
+    pub const ALL: [MemorySubsystem; 5] = [
+        MemorySubsystem::PendingQueries,
+        MemorySubsystem::HandlerQueues,
+        MemorySubsystem::Defragmentation,
+        MemorySubsystem::RxBuffers,
+        MemorySubsystem::RoutingTables,
+    ];
+
+    /// The share of the budget, in percent, this subsystem may draw from.
+    const fn watermark(self) -> usize {
+        match self {
+            MemorySubsystem::PendingQueries => 80,
+            MemorySubsystem::HandlerQueues => 85,
+            MemorySubsystem::Defragmentation => 90,
+            MemorySubsystem::RxBuffers | MemorySubsystem::RoutingTables => 100,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            MemorySubsystem::PendingQueries => "pending_queries",
+            MemorySubsystem::HandlerQueues => "handler_queues",
+            MemorySubsystem::Defragmentation => "defragmentation",
+            MemorySubsystem::RxBuffers => "rx_buffers",
+            MemorySubsystem::RoutingTables => "routing_tables",
+        }
+    }
+}
+
+impl fmt::Display for MemorySubsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A global memory budget shared by the subsystems of a zenoh instance.
+///
+/// Each subsystem reserves memory with [`MemoryBudget::try_reserve`] before allocating it and the
+/// reservation is released when the returned [`MemoryReservation`] is dropped. A reservation is
+/// refused when it would make its subsystem exceed its own soft limit, or the overall usage exceed
+/// the share of the budget granted to the subsystem (see [`MemorySubsystem`]).
+///
+/// An unlimited budget only accounts the memory usage and never refuses a reservation.
+///
+/// Only the memory reserved by the accounted subsystems is limited: the budget does not bound
+/// the overall memory usage of the process (e.g. the memory of the applications callbacks).
+pub struct MemoryBudget {
+    total: Option<usize>,
+    limits: [Option<usize>; MemorySubsystem::ALL.len()],
+    used: [AtomicUsize; MemorySubsystem::ALL.len()],
+    total_used: AtomicUsize,
+}
+
+impl MemoryBudget {
+    /// Creates a budget that never refuses a reservation.
+    pub fn unlimited() -> Self {
+        Self {
+            total: None,
+            limits: [None; MemorySubsystem::ALL.len()],
+            used: Default::default(),
+            total_used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a budget of `total` bytes.
+    pub fn new(total: usize) -> Self {
+        Self {
+            total: Some(total),
+            ..Self::unlimited()
+        }
+    }
+
+    /// Sets the soft limit of `subsystem` in bytes.
+    pub fn limit(mut self, subsystem: MemorySubsystem, limit: usize) -> Self {
+        self.limits[subsystem as usize] = Some(limit);
+        self
+    }
+
+    /// The total budget in bytes, `None` if unlimited.
+    pub fn total(&self) -> Option<usize> {
+        self.total
+    }
+
+    /// The number of bytes currently reserved by all subsystems.
+    pub fn total_used(&self) -> usize {
+        self.total_used.load(Ordering::Relaxed)
+    }
+
+    /// The number of bytes currently reserved by `subsystem`.
+    pub fn used(&self, subsystem: MemorySubsystem) -> usize {
+        self.used[subsystem as usize].load(Ordering::Relaxed)
+    }
+
+    /// Reserves `size` bytes for `subsystem`, or returns `None` if the budget doesn't allow it.
+    pub fn try_reserve(
+        self: &Arc<Self>,
+        subsystem: MemorySubsystem,
+        size: usize,
+    ) -> Option<MemoryReservation> {
+        self.acquire(subsystem, size).then(|| MemoryReservation {
+            budget: self.clone(),
+            subsystem,
+            size,
+        })
+    }
+
+    /// Reserves `size` bytes for `subsystem` even if the budget doesn't allow it.
+    ///
+    /// It accounts the memory of the subsystems that can't refuse an allocation, reducing the
+    /// share of the budget left to the others.
+    pub fn reserve(self: &Arc<Self>, subsystem: MemorySubsystem, size: usize) -> MemoryReservation {
+        self.used[subsystem as usize].fetch_add(size, Ordering::AcqRel);
+        self.total_used.fetch_add(size, Ordering::AcqRel);
+        MemoryReservation {
+            budget: self.clone(),
+            subsystem,
+            size,
+        }
+    }
+
+    fn acquire(&self, subsystem: MemorySubsystem, size: usize) -> bool {
+        let cap = |max: Option<usize>| max.unwrap_or(usize::MAX);
+        let total = cap(self
+            .total
+            .map(|t| (t as u128 * subsystem.watermark() as u128 / 100) as usize));
+        let limit = cap(self.limits[subsystem as usize]);
+
+        let used = &self.used[subsystem as usize];
+        if used
+            .fetch_update(Ordering::AcqRel, Ordering::Relaxed, |u| {
+                u.checked_add(size).filter(|u| *u <= limit)
+            })
+            .is_err()
+        {
+            return false;
+        }
+        if self
+            .total_used
+            .fetch_update(Ordering::AcqRel, Ordering::Relaxed, |u| {
+                u.checked_add(size).filter(|u| *u <= total)
+            })
+            .is_err()
+        {
+            used.fetch_sub(size, Ordering::AcqRel);
+            return false;
+        }
+        true
+    }
+
+    fn release(&self, subsystem: MemorySubsystem, size: usize) {
+        self.used[subsystem as usize].fetch_sub(size, Ordering::AcqRel);
+        self.total_used.fetch_sub(size, Ordering::AcqRel);
+    }
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl fmt::Debug for MemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("MemoryBudget");
+        s.field("total", &self.total)
+            .field("total_used", &self.total_used());
+        for subsystem in MemorySubsystem::ALL {
+            s.field(subsystem.as_str(), &self.used(subsystem));
+        }
+        s.finish()
+    }
+}
+
+/// Memory reserved in a [`MemoryBudget`], released when dropped.
+#[derive(Debug)]
+pub struct MemoryReservation {
+    budget: Arc<MemoryBudget>,
+    subsystem: MemorySubsystem,
+    size: usize,
+}
+
+impl MemoryReservation {
+    /// The number of reserved bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Reserves `additional` more bytes. Returns `false` and leaves the reservation unchanged
+    /// if the budget doesn't allow it.
+    pub fn try_grow(&mut self, additional: usize) -> bool {
+        let granted = self.budget.acquire(self.subsystem, additional);
+        if granted {
+            self.size += additional;
+        }
+        granted
+    }
+
+    /// Releases all the reserved bytes, keeping the reservation for later growth.
+    pub fn clear(&mut self) {
+        self.budget.release(self.subsystem, self.size);
+        self.size = 0;
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_budget() {
+        let budget = Arc::new(MemoryBudget::new(1000).limit(MemorySubsystem::RxBuffers, 500));
+
+        // Soft limit of the subsystem
+        let rx = budget.try_reserve(MemorySubsystem::RxBuffers, 400).unwrap();
+        assert!(budget
+            .try_reserve(MemorySubsystem::RxBuffers, 200)
+            .is_none());
+
+        // Degradation order: queries are refused first
+        let mut defrag = budget
+            .try_reserve(MemorySubsystem::Defragmentation, 300)
+            .unwrap();
+        assert_eq!(budget.total_used(), 700);
+        assert!(budget
+            .try_reserve(MemorySubsystem::PendingQueries, 150)
+            .is_none());
+        assert!(defrag.try_grow(150));
+        assert!(!defrag.try_grow(100));
+        assert_eq!(budget.used(MemorySubsystem::Defragmentation), 450);
+
+        defrag.clear();
+        assert_eq!(budget.total_used(), 400);
+        let queries = budget
+            .try_reserve(MemorySubsystem::PendingQueries, 150)
+            .unwrap();
+        assert_eq!(queries.size(), 150);
+
+        // The routing tables are accounted beyond the budget, refusing the other subsystems
+        let tables = budget.reserve(MemorySubsystem::RoutingTables, 1000);
+        assert_eq!(budget.total_used(), 1550);
+        assert!(budget
+            .try_reserve(MemorySubsystem::HandlerQueues, 1)
+            .is_none());
+        assert!(budget.try_reserve(MemorySubsystem::RxBuffers, 1).is_none());
+        drop(tables);
+        let queues = budget
+            .try_reserve(MemorySubsystem::HandlerQueues, 100)
+            .unwrap();
+
+        drop((rx, defrag, queries, queues));
+        assert_eq!(budget.total_used(), 0);
+        for subsystem in MemorySubsystem::ALL {
+            assert_eq!(budget.used(subsystem), 0);
+        }
+
+        // An unlimited budget only accounts
+        let budget = Arc::new(MemoryBudget::unlimited());
+        let _r = budget
+            .try_reserve(MemorySubsystem::RxBuffers, usize::MAX / 2)
+            .unwrap();
+        assert_eq!(budget.total_used(), usize::MAX / 2);
+    }
+}
@@ -7,6 +7,8 @@ pub mod timer;
 pub use timer::*;
 pub mod log;
 pub use log::*;
+pub mod memory;
+pub use memory::*;
 
 /// The "ZENOH_HOME" environement variable name
 pub const ZENOH_HOME_ENV_VAR: &str = "ZENOH_HOME";
@@ -11,7 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use std::net::{IpAddr, Ipv6Addr};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 use tokio::net::{TcpSocket, UdpSocket};
 use zenoh_core::zconfigurable;
 #[cfg(unix)]
@@ -141,6 +141,32 @@ pub fn get_multicast_interfaces() -> Vec<IpAddr> {
     }
 }
 
+/// Get the network interfaces to bind the UDP sending port to when not specified by user, for an
+/// IPv6 multicast group: an IPv6 address of each of the non-loopback multicast interfaces.
+pub fn get_ipv6_multicast_interfaces() -> Vec<IpAddr> {
+    #[cfg(unix)]
+    {
+        pnet_datalink::interfaces()
+            .iter()
+            .filter(|iface| {
+                iface.is_up() && iface.is_running() && iface.is_multicast() && !iface.is_loopback()
+            })
+            .filter_map(|iface| {
+                iface
+                    .ips
+                    .iter()
+                    .map(|ipnet| ipnet.ip())
+                    .find(|ip| ip.is_ipv6() && !ip.is_multicast())
+            })
+            .collect()
+    }
+    #[cfg(windows)]
+    {
+        // On windows, bind to [::], the system will select the default interface
+        vec![IpAddr::V6(Ipv6Addr::UNSPECIFIED)]
+    }
+}
+
 pub fn get_local_addresses(interface: Option<&str>) -> ZResult<Vec<IpAddr>> {
     #[cfg(unix)]
     {
@@ -446,3 +472,177 @@ pub fn set_bind_to_device_udp_socket(socket: &UdpSocket, iface: &str) -> ZResult
     tracing::warn!("Binding the socket {socket:?} to the interface {iface} is not supported on macOS and Windows");
     Ok(())
 }
+
+/// Set the DSCP of the IP packets sent on a socket bound to the given local address.
+pub fn set_dscp<S>(socket: &S, addr: &SocketAddr, dscp: u8) -> ZResult<()>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    let socket = socket2::SockRef::from(socket);
+    // The DSCP is made of the 6 most significant bits of the ToS/Traffic Class field
+    let tos = u32::from(dscp) << 2;
+    match addr {
+        SocketAddr::V4(_) => socket.set_tos(tos)?,
+        #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+        SocketAddr::V6(_) => socket.set_tclass_v6(tos)?,
+        #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+        SocketAddr::V6(_) => {
+            bail!("Setting the DSCP of IPv6 sockets is not supported on this platform")
+        }
+    }
+    Ok(())
+}
+
+/// Forbid the fragmentation of the IP packets sent on a socket bound to the given local address,
+/// so that the MTU of its path gets discovered (RFC 1191 and RFC 8201).
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_path_mtu_discovery<S>(socket: &S, addr: &SocketAddr) -> ZResult<()>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    use std::os::fd::AsRawFd;
+
+    let socket = socket2::SockRef::from(socket);
+    let (level, name, value) = match addr {
+        SocketAddr::V4(_) => (
+            libc::IPPROTO_IP,
+            libc::IP_MTU_DISCOVER,
+            libc::IP_PMTUDISC_DO,
+        ),
+        SocketAddr::V6(_) => (
+            libc::IPPROTO_IPV6,
+            libc::IPV6_MTU_DISCOVER,
+            libc::IPV6_PMTUDISC_DO,
+        ),
+    };
+    let res = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            level,
+            name,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if res < 0 {
+        bail!("{}", std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn set_path_mtu_discovery<S>(_socket: &S, _addr: &SocketAddr) -> ZResult<()>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    bail!("Path MTU discovery is not supported on this platform")
+}
+
+/// Get the MTU, in bytes of IP packet, of the path of a socket connected to the given remote
+/// address.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn get_path_mtu<S>(socket: &S, addr: &SocketAddr) -> ZResult<u32>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    use std::os::fd::AsRawFd;
+
+    let socket = socket2::SockRef::from(socket);
+    let (level, name) = match addr {
+        SocketAddr::V4(_) => (libc::IPPROTO_IP, libc::IP_MTU),
+        SocketAddr::V6(_) => (libc::IPPROTO_IPV6, libc::IPV6_MTU),
+    };
+    let mut mtu: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let res = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            level,
+            name,
+            &mut mtu as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if res < 0 {
+        bail!("{}", std::io::Error::last_os_error());
+    }
+    Ok(mtu as u32)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn get_path_mtu<S>(_socket: &S, _addr: &SocketAddr) -> ZResult<u32>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    bail!("Path MTU discovery is not supported on this platform")
+}
+
+/// Get the round trip time estimated by the OS on a TCP socket, and the number of segments it
+/// retransmitted.
+#[cfg(target_os = "linux")]
+pub fn get_tcp_info<S>(socket: &S) -> ZResult<(std::time::Duration, u64)>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    use std::os::fd::AsRawFd;
+
+    let socket = socket2::SockRef::from(socket);
+    let mut info: libc::tcp_info = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::tcp_info>() as libc::socklen_t;
+    let res = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_TCP,
+            libc::TCP_INFO,
+            &mut info as *mut libc::tcp_info as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if res < 0 {
+        bail!("{}", std::io::Error::last_os_error());
+    }
+    Ok((
+        std::time::Duration::from_micros(info.tcpi_rtt.into()),
+        info.tcpi_total_retrans.into(),
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_tcp_info<S>(_socket: &S) -> ZResult<(std::time::Duration, u64)>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    bail!("Getting the TCP information of a socket is not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dscp() {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        set_dscp(&socket, &addr, 46).unwrap();
+        assert_eq!(socket2::SockRef::from(&socket).tos().unwrap(), 46 << 2);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn path_mtu() {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        socket.connect(addr).unwrap();
+        set_path_mtu_discovery(&socket, &addr).unwrap();
+        assert!(get_path_mtu(&socket, &addr).unwrap() >= 576);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn tcp_info() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let socket = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (_, retransmissions) = get_tcp_info(&socket).unwrap();
+        assert_eq!(retransmissions, 0);
+    }
+}
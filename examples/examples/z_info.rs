@@ -36,6 +36,17 @@ async fn main() {
         "peers zid: {:?}",
         info.peers_zid().res().await.collect::<Vec<ZenohId>>()
     );
+    for connection in info.connections().res().await {
+        let links: Vec<String> = connection
+            .links
+            .iter()
+            .map(|link| format!("{} => {}", link.src, link.dst))
+            .collect();
+        println!(
+            "connection: {} ({}) established {:?} via {:?}",
+            connection.zid, connection.whatami, connection.established, links
+        );
+    }
 }
 
 #[derive(clap::Parser, Clone, PartialEq, Eq, Hash, Debug)]
@@ -23,6 +23,8 @@ mod listener;
 mod multicast;
 pub mod tls;
 mod unicast;
+#[cfg(target_os = "linux")]
+mod uring;
 
 use alloc::{borrow::ToOwned, boxed::Box, string::String, vec, vec::Vec};
 use async_trait::async_trait;
@@ -31,6 +33,8 @@ pub use listener::*;
 pub use multicast::*;
 use serde::Serialize;
 pub use unicast::*;
+#[cfg(target_os = "linux")]
+pub use uring::UringStream;
 use zenoh_protocol::core::Locator;
 use zenoh_result::ZResult;
 
@@ -39,6 +43,7 @@ use zenoh_result::ZResult;
 /*************************************/
 
 pub const BIND_INTERFACE: &str = "iface";
+pub const IO_URING: &str = "io_uring";
 
 #[derive(Clone, Debug, Serialize, Hash, PartialEq, Eq)]
 pub struct Link {
@@ -49,6 +54,8 @@ pub struct Link {
     pub is_reliable: bool,
     pub is_streamed: bool,
     pub interfaces: Vec<String>,
+    pub cert_common_name: Option<String>,
+    pub cert_subject_alt_names: Vec<String>,
 }
 
 #[async_trait]
@@ -77,6 +84,8 @@ impl From<&LinkUnicast> for Link {
             is_reliable: link.is_reliable(),
             is_streamed: link.is_streamed(),
             interfaces: link.get_interface_names(),
+            cert_common_name: link.get_cert_common_name(),
+            cert_subject_alt_names: link.get_cert_subject_alt_names(),
         }
     }
 }
@@ -97,6 +106,8 @@ impl From<&LinkMulticast> for Link {
             is_reliable: link.is_reliable(),
             is_streamed: false,
             interfaces: vec![],
+            cert_common_name: None,
+            cert_subject_alt_names: vec![],
         }
     }
 }
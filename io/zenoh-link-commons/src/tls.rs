@@ -1,71 +1,75 @@
-use alloc::vec::Vec;
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use core::fmt;
 use rustls::{
     client::{
-        danger::{ServerCertVerified, ServerCertVerifier},
-        verify_server_cert_signed_by_trust_anchor,
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        ResolvesClientCert, WebPkiServerVerifier,
     },
-    crypto::{verify_tls12_signature, verify_tls13_signature},
     pki_types::{CertificateDer, ServerName, UnixTime},
-    server::ParsedCertificate,
-    RootCertStore,
+    server::{ClientHello, ResolvesServerCert},
+    sign::{CertifiedKey, Signer, SigningKey},
+    CertificateError, DigitallySignedStruct,
 };
-use webpki::ALL_VERIFICATION_ALGS;
+pub use rustls::{SignatureAlgorithm, SignatureScheme};
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+use x509_parser::{
+    der_parser::asn1_rs::{Any, Class, Tag},
+    prelude::{FromDer, GeneralName, X509Certificate},
+};
+use zenoh_result::{zerror, ZResult};
 
 impl ServerCertVerifier for WebPkiVerifierAnyServerName {
     /// Will verify the certificate is valid in the following ways:
     /// - Signed by a  trusted `RootCertStore` CA
     /// - Not Expired
+    /// - Not revoked, if certificate revocation lists were provided to the inner verifier
     fn verify_server_cert(
         &self,
         end_entity: &CertificateDer<'_>,
         intermediates: &[CertificateDer<'_>],
-        _server_name: &ServerName<'_>,
-        _ocsp_response: &[u8],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
         now: UnixTime,
     ) -> Result<ServerCertVerified, rustls::Error> {
-        let cert = ParsedCertificate::try_from(end_entity)?;
-        verify_server_cert_signed_by_trust_anchor(
-            &cert,
-            &self.roots,
+        // The server name is verified last, once the chain of trust and the revocation
+        // status have been checked: a name mismatch is the only error to disregard.
+        match self.inner.verify_server_cert(
+            end_entity,
             intermediates,
+            server_name,
+            ocsp_response,
             now,
-            ALL_VERIFICATION_ALGS,
-        )?;
-        Ok(ServerCertVerified::assertion())
+        ) {
+            Err(rustls::Error::InvalidCertificate(CertificateError::NotValidForName)) => {
+                Ok(ServerCertVerified::assertion())
+            }
+            res => res,
+        }
     }
 
     fn verify_tls12_signature(
         &self,
         message: &[u8],
         cert: &CertificateDer<'_>,
-        dss: &rustls::DigitallySignedStruct,
-    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
-        verify_tls12_signature(
-            message,
-            cert,
-            dss,
-            &rustls::crypto::ring::default_provider().signature_verification_algorithms,
-        )
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
     }
 
     fn verify_tls13_signature(
         &self,
         message: &[u8],
         cert: &CertificateDer<'_>,
-        dss: &rustls::DigitallySignedStruct,
-    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
-        verify_tls13_signature(
-            message,
-            cert,
-            dss,
-            &rustls::crypto::ring::default_provider().signature_verification_algorithms,
-        )
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
     }
 
-    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
-        rustls::crypto::ring::default_provider()
-            .signature_verification_algorithms
-            .supported_schemes()
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
     }
 }
 
@@ -73,15 +77,439 @@ impl ServerCertVerifier for WebPkiVerifierAnyServerName {
 /// see the trait impl for more information.
 #[derive(Debug)]
 pub struct WebPkiVerifierAnyServerName {
-    roots: RootCertStore,
+    inner: Arc<WebPkiServerVerifier>,
 }
 
 #[allow(unreachable_pub)]
 impl WebPkiVerifierAnyServerName {
     /// Constructs a new `WebPkiVerifierAnyServerName`.
     ///
-    /// `roots` is the set of trust anchors to trust for issuing server certs.
-    pub fn new(roots: RootCertStore) -> Self {
-        Self { roots }
+    /// `inner` is the verifier of the chain of trust and of the revocation status of server certs.
+    pub fn new(inner: Arc<WebPkiServerVerifier>) -> Self {
+        Self { inner }
+    }
+}
+
+// Decodes the value of an attribute of a name, of one of the string types allowed in names
+fn attribute_string(value: &Any) -> Option<String> {
+    if value.class() != Class::Universal {
+        return None;
+    }
+    let content = value.data;
+    match value.tag() {
+        Tag::Utf8String | Tag::PrintableString | Tag::Ia5String | Tag::VisibleString => {
+            core::str::from_utf8(content).ok().map(|s| s.into())
+        }
+        // Decoded as Latin-1, as done by most implementations
+        Tag::T61String => Some(content.iter().map(|&b| b as char).collect()),
+        // UCS-2, i.e. UTF-16 without the surrogate pairs, big endian
+        Tag::BmpString if content.len() % 2 == 0 => char::decode_utf16(
+            content
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]])),
+        )
+        .collect::<Result<_, _>>()
+        .ok(),
+        // UCS-4, big endian
+        Tag::UniversalString if content.len() % 4 == 0 => content
+            .chunks_exact(4)
+            .map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Returns the common name (CN) of the subject of a DER-encoded X.509 certificate.
+///
+/// Since the common name is used as an identity, a subject with several common names has none.
+pub fn get_cert_common_name(cert: &[u8]) -> Option<String> {
+    let (_, cert) = X509Certificate::from_der(cert).ok()?;
+    let mut common_names = cert.subject().iter_common_name();
+    let common_name = common_names.next()?;
+    if common_names.next().is_some() {
+        return None;
+    }
+    attribute_string(common_name.attr_value())
+}
+
+/// Returns the subject alternative names (SAN) of a DER-encoded X.509 certificate.
+///
+/// The DNS names, email addresses and URIs are returned as is, the IP addresses in their usual
+/// textual representation. The other kinds of names are ignored.
+pub fn get_cert_subject_alt_names(cert: &[u8]) -> Vec<String> {
+    let Ok((_, cert)) = X509Certificate::from_der(cert) else {
+        return Vec::new();
+    };
+    let Ok(Some(san)) = cert.subject_alternative_name() else {
+        return Vec::new();
+    };
+    san.value
+        .general_names
+        .iter()
+        .filter_map(|name| match name {
+            GeneralName::DNSName(name) | GeneralName::RFC822Name(name) | GeneralName::URI(name) => {
+                Some((*name).into())
+            }
+            GeneralName::IPAddress(ip) => {
+                if let Ok(ip) = <[u8; 4]>::try_from(*ip) {
+                    Some(std::net::Ipv4Addr::from(ip).to_string())
+                } else if let Ok(ip) = <[u8; 16]>::try_from(*ip) {
+                    Some(std::net::Ipv6Addr::from(ip).to_string())
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// A private key held by a device which performs the signatures itself, e.g. a PKCS#11 token or
+/// a TPM, so that the key material is never loaded in memory.
+pub trait PrivateKey: Send + Sync + fmt::Debug {
+    /// The algorithm of the key.
+    fn algorithm(&self) -> SignatureAlgorithm;
+
+    /// The signature schemes supported by the key, by order of preference.
+    fn schemes(&self) -> Vec<SignatureScheme>;
+
+    /// Signs `message` with `scheme`, one of the schemes returned by [`PrivateKey::schemes`].
+    fn sign(&self, scheme: SignatureScheme, message: &[u8]) -> ZResult<Vec<u8>>;
+}
+
+/// Loads the private keys designated by the URIs of a given scheme, e.g. `pkcs11` or `tpm`.
+pub trait KeyProvider: Send + Sync {
+    /// Loads the private key designated by `uri`, scheme included.
+    fn load(&self, uri: &str) -> ZResult<Arc<dyn PrivateKey>>;
+}
+
+fn key_providers() -> &'static RwLock<HashMap<String, Arc<dyn KeyProvider>>> {
+    static KEY_PROVIDERS: OnceLock<RwLock<HashMap<String, Arc<dyn KeyProvider>>>> = OnceLock::new();
+    KEY_PROVIDERS.get_or_init(Default::default)
+}
+
+/// Registers the provider of the private keys whose URI starts with `scheme:`.
+///
+/// Providers are registered by the application before opening the session: the keys are loaded
+/// when the TLS and QUIC links configured with a `*_private_key_uri` are created.
+pub fn register_key_provider(scheme: &str, provider: Arc<dyn KeyProvider>) {
+    let mut providers = key_providers().write().unwrap_or_else(|e| e.into_inner());
+    providers.insert(scheme.into(), provider);
+}
+
+/// Loads the private key designated by `uri` with the provider registered for its scheme.
+///
+/// The `;` of the URI, e.g. between the attributes of a PKCS#11 URI, separate the parameters of
+/// an endpoint: they are percent-encoded as `%3B` in the configuration and decoded here.
+pub fn load_private_key(uri: &str) -> ZResult<Arc<dyn PrivateKey>> {
+    let uri = uri.replace("%3B", ";").replace("%3b", ";");
+    let (scheme, _) = uri
+        .split_once(':')
+        .ok_or_else(|| zerror!("Invalid private key URI: {}", uri))?;
+    let provider = key_providers()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(scheme)
+        .cloned()
+        .ok_or_else(|| zerror!("No key provider registered for '{}'", scheme))?;
+    provider.load(&uri)
+}
+
+/// [`SigningKey`] delegating the signatures to a [`PrivateKey`].
+#[derive(Debug)]
+pub struct ProvidedSigningKey(pub Arc<dyn PrivateKey>);
+
+impl SigningKey for ProvidedSigningKey {
+    fn choose_scheme(&self, offered: &[SignatureScheme]) -> Option<Box<dyn Signer>> {
+        self.0
+            .schemes()
+            .into_iter()
+            .find(|scheme| offered.contains(scheme))
+            .map(|scheme| {
+                Box::new(ProvidedSigner {
+                    key: self.0.clone(),
+                    scheme,
+                }) as Box<dyn Signer>
+            })
+    }
+
+    fn algorithm(&self) -> SignatureAlgorithm {
+        self.0.algorithm()
+    }
+}
+
+#[derive(Debug)]
+struct ProvidedSigner {
+    key: Arc<dyn PrivateKey>,
+    scheme: SignatureScheme,
+}
+
+impl Signer for ProvidedSigner {
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, rustls::Error> {
+        self.key
+            .sign(self.scheme, message)
+            .map_err(|e| rustls::Error::General(e.to_string()))
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        self.scheme
+    }
+}
+
+/// Resolver always presenting the same certified key, for the servers and the clients whose
+/// private key is held by a [`KeyProvider`].
+#[derive(Debug)]
+pub struct SingleCertResolver(pub Arc<CertifiedKey>);
+
+impl ResolvesServerCert for SingleCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.0.clone())
+    }
+}
+
+impl ResolvesClientCert for SingleCertResolver {
+    fn resolve(
+        &self,
+        _root_hint_subjects: &[&[u8]],
+        _sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(self.0.clone())
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DER_SEQUENCE: u8 = 0x30;
+    const DER_SET: u8 = 0x31;
+
+    const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+    const OID_ORGANIZATION: &[u8] = &[0x55, 0x04, 0x0a];
+    const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+    const OID_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+
+    // Encodes a DER element, with a long form length when it doesn't fit in 7 bits
+    fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut buffer = vec![tag];
+        if content.len() < 0x80 {
+            buffer.push(content.len() as u8);
+        } else {
+            let len = content.len().to_be_bytes();
+            let len = &len[len.iter().position(|&b| b != 0).unwrap()..];
+            buffer.push(0x80 | len.len() as u8);
+            buffer.extend_from_slice(len);
+        }
+        buffer.extend_from_slice(content);
+        buffer
+    }
+
+    fn seq(elements: &[Vec<u8>]) -> Vec<u8> {
+        der(DER_SEQUENCE, &elements.concat())
+    }
+
+    // A name made of one relative distinguished name per attribute
+    fn name(attributes: &[(&[u8], u8, &[u8])]) -> Vec<u8> {
+        let rdns: Vec<Vec<u8>> = attributes
+            .iter()
+            .map(|(oid, tag, value)| der(DER_SET, &seq(&[der(0x06, oid), der(*tag, value)])))
+            .collect();
+        seq(&rdns)
+    }
+
+    fn extension(oid: &[u8], critical: bool, value: &[u8]) -> Vec<u8> {
+        let mut fields = vec![der(0x06, oid)];
+        if critical {
+            fields.push(der(0x01, &[0xff]));
+        }
+        fields.push(der(0x04, value));
+        seq(&fields)
+    }
+
+    fn cert(version: bool, subject: Vec<u8>, extensions: Option<Vec<Vec<u8>>>) -> Vec<u8> {
+        let algorithm = seq(&[der(0x06, &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02])]);
+        let mut tbs = vec![];
+        if version {
+            tbs.push(der(0xa0, &der(0x02, &[0x02])));
+        }
+        tbs.push(der(0x02, &[0x01, 0x23]));
+        tbs.push(algorithm.clone());
+        tbs.push(name(&[(OID_COMMON_NAME, 0x0c, b"Issuer")]));
+        tbs.push(seq(&[
+            der(0x17, b"240101000000Z"),
+            der(0x17, b"340101000000Z"),
+        ]));
+        tbs.push(subject);
+        tbs.push(seq(&[algorithm.clone(), der(0x03, &[0x00; 65])]));
+        if let Some(extensions) = extensions {
+            tbs.push(der(0xa3, &seq(&extensions)));
+        }
+        seq(&[seq(&tbs), algorithm, der(0x03, &[0x00; 72])])
+    }
+
+    #[test]
+    fn cert_common_name() {
+        let subject = name(&[
+            (OID_ORGANIZATION, 0x0c, b"ZettaScale"),
+            (OID_COMMON_NAME, 0x0c, "zénoh".as_bytes()),
+        ]);
+        let c = cert(true, subject, None);
+        assert_eq!(get_cert_common_name(&c).as_deref(), Some("zénoh"));
+
+        // Without the optional version
+        let subject = name(&[(OID_COMMON_NAME, 0x0c, b"v1")]);
+        assert_eq!(
+            get_cert_common_name(&cert(false, subject, None)).as_deref(),
+            Some("v1")
+        );
+    }
+
+    #[test]
+    fn cert_common_name_long() {
+        // The lengths of the name and of the certificate are encoded on one and two bytes
+        let cn = "z".repeat(200);
+        let c = cert(true, name(&[(OID_COMMON_NAME, 0x0c, cn.as_bytes())]), None);
+        assert_eq!(
+            &c[..4],
+            &[
+                DER_SEQUENCE,
+                0x82,
+                ((c.len() - 4) >> 8) as u8,
+                (c.len() - 4) as u8
+            ]
+        );
+        assert_eq!(get_cert_common_name(&c), Some(cn));
+    }
+
+    #[test]
+    fn cert_common_name_string_types() {
+        let cn = |tag, value: &[u8]| {
+            get_cert_common_name(&cert(true, name(&[(OID_COMMON_NAME, tag, value)]), None))
+        };
+        // PrintableString, IA5String and VisibleString
+        assert_eq!(cn(0x13, b"printable").as_deref(), Some("printable"));
+        assert_eq!(cn(0x16, b"ia5").as_deref(), Some("ia5"));
+        assert_eq!(cn(0x1a, b"visible").as_deref(), Some("visible"));
+        // TeletexString, as Latin-1
+        assert_eq!(cn(0x14, b"z\xe9noh").as_deref(), Some("zénoh"));
+        // BMPString
+        let bmp: Vec<u8> = "zénoh€".encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(cn(0x1e, &bmp).as_deref(), Some("zénoh€"));
+        assert_eq!(cn(0x1e, &bmp[1..]), None);
+        // UniversalString
+        let universal: Vec<u8> = "zénoh🦀"
+            .chars()
+            .flat_map(|c| (c as u32).to_be_bytes())
+            .collect();
+        assert_eq!(cn(0x1c, &universal).as_deref(), Some("zénoh🦀"));
+        // Invalid UTF-8 and unknown string types
+        assert_eq!(cn(0x0c, &[0xff, 0xfe]), None);
+        assert_eq!(cn(0x04, b"octets"), None);
+    }
+
+    #[test]
+    fn cert_common_name_ambiguous() {
+        // Several common names, in one or several relative distinguished names
+        let subject = name(&[
+            (OID_COMMON_NAME, 0x0c, b"zenoh"),
+            (OID_COMMON_NAME, 0x0c, b"admin"),
+        ]);
+        assert_eq!(get_cert_common_name(&cert(true, subject, None)), None);
+        let rdn = der(
+            DER_SET,
+            &[
+                seq(&[der(0x06, OID_COMMON_NAME), der(0x0c, b"zenoh")]),
+                seq(&[der(0x06, OID_COMMON_NAME), der(0x0c, b"admin")]),
+            ]
+            .concat(),
+        );
+        assert_eq!(get_cert_common_name(&cert(true, seq(&[rdn]), None)), None);
+    }
+
+    #[test]
+    fn cert_common_name_oid() {
+        // The common name OID as the content of an element which isn't an OID
+        let attribute = seq(&[der(0x04, OID_COMMON_NAME), der(0x0c, b"admin")]);
+        let subject = seq(&[der(DER_SET, &attribute)]);
+        assert_eq!(get_cert_common_name(&cert(true, subject, None)), None);
+
+        // An OID starting with the common name OID
+        let subject = name(&[(&[0x55, 0x04, 0x03, 0x01], 0x0c, b"admin")]);
+        assert_eq!(get_cert_common_name(&cert(true, subject, None)), None);
+
+        // A common name of a context-specific type
+        let subject = name(&[(OID_COMMON_NAME, 0x8c, b"admin")]);
+        assert_eq!(get_cert_common_name(&cert(true, subject, None)), None);
+    }
+
+    #[test]
+    fn cert_missing_names() {
+        let subject = name(&[(OID_ORGANIZATION, 0x0c, b"ZettaScale")]);
+        let c = cert(true, subject.clone(), None);
+        assert_eq!(get_cert_common_name(&c), None);
+        assert!(get_cert_subject_alt_names(&c).is_empty());
+
+        // Extensions without the subject alternative names
+        let key_usage = extension(OID_KEY_USAGE, true, &der(0x03, &[0x05, 0xa0]));
+        let c = cert(true, subject, Some(vec![key_usage]));
+        assert!(get_cert_subject_alt_names(&c).is_empty());
+    }
+
+    #[test]
+    fn cert_subject_alt_names() {
+        let general_names = seq(&[
+            der(0x82, b"zenoh.io"),
+            der(0x87, &[127, 0, 0, 1]),
+            der(0x87, &std::net::Ipv6Addr::LOCALHOST.octets()),
+            der(0x81, b"zenoh@zettascale.tech"),
+            der(0x86, b"https://zenoh.io"),
+            // Registered ids and IP addresses of an invalid length are ignored
+            der(0x88, &[0x2a, 0x03]),
+            der(0x87, &[10, 0, 0]),
+        ]);
+        let key_usage = extension(OID_KEY_USAGE, true, &der(0x03, &[0x05, 0xa0]));
+        for critical in [false, true] {
+            let san = extension(OID_SUBJECT_ALT_NAME, critical, &general_names);
+            let c = cert(
+                true,
+                name(&[(OID_COMMON_NAME, 0x0c, b"zenoh")]),
+                Some(vec![key_usage.clone(), san]),
+            );
+            assert_eq!(
+                get_cert_subject_alt_names(&c),
+                vec![
+                    "zenoh.io",
+                    "127.0.0.1",
+                    "::1",
+                    "zenoh@zettascale.tech",
+                    "https://zenoh.io"
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn cert_malformed() {
+        assert_eq!(get_cert_common_name(&[]), None);
+        assert!(get_cert_subject_alt_names(&[]).is_empty());
+        assert_eq!(get_cert_common_name(b"not a certificate"), None);
+
+        // No truncation of a valid certificate is mistaken for one
+        let san = extension(OID_SUBJECT_ALT_NAME, false, &seq(&[der(0x82, b"zenoh.io")]));
+        let c = cert(
+            true,
+            name(&[(OID_COMMON_NAME, 0x0c, b"zenoh")]),
+            Some(vec![san]),
+        );
+        assert_eq!(get_cert_common_name(&c).as_deref(), Some("zenoh"));
+        assert_eq!(get_cert_subject_alt_names(&c), vec!["zenoh.io"]);
+        for len in 0..c.len() {
+            assert_eq!(get_cert_common_name(&c[..len]), None);
+            assert!(get_cert_subject_alt_names(&c[..len]).is_empty());
+        }
     }
 }
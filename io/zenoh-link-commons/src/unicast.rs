@@ -13,12 +13,18 @@
 //
 use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use async_trait::async_trait;
+use core::time::Duration;
 use core::{
     fmt,
     hash::{Hash, Hasher},
     ops::Deref,
 };
-use std::net::SocketAddr;
+use std::{
+    io::{self, IoSlice},
+    net::SocketAddr,
+};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use zenoh_buffers::ZBuf;
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::ZResult;
 
@@ -49,9 +55,45 @@ pub trait LinkUnicastTrait: Send + Sync {
     fn get_interface_names(&self) -> Vec<String>;
     async fn write(&self, buffer: &[u8]) -> ZResult<usize>;
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()>;
+    /// Write all the given buffers, in order, as a single sequence of bytes. The links not
+    /// supporting vectored I/O coalesce the buffers in a contiguous one first.
+    async fn write_vectored_all(&self, buffers: &[&[u8]]) -> ZResult<()> {
+        self.write_all(&buffers.concat()).await
+    }
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize>;
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()>;
     async fn close(&self) -> ZResult<()>;
+    /// Whether the link encrypts the exchanged data and authenticates the remote end, e.g. with TLS.
+    fn is_secure(&self) -> bool {
+        false
+    }
+    /// Set the DSCP of the IP packets sent on the link, ignored by the links not supporting it.
+    fn set_dscp(&self, _dscp: u8) -> ZResult<()> {
+        Ok(())
+    }
+    /// Get the statistics the OS maintains for the link, none for the links not supporting it.
+    fn get_os_stats(&self) -> LinkOsStats {
+        LinkOsStats::default()
+    }
+    /// Get the common name of the certificate the remote end authenticated with, none for the
+    /// links not supporting certificates.
+    fn get_cert_common_name(&self) -> Option<String> {
+        None
+    }
+    /// Get the subject alternative names of the certificate the remote end authenticated with,
+    /// none for the links not supporting certificates.
+    fn get_cert_subject_alt_names(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// The statistics the OS maintains for a link, e.g. the ones of a TCP connection.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LinkOsStats {
+    /// The round trip time estimated by the OS.
+    pub rtt: Option<Duration>,
+    /// The number of segments retransmitted by the OS.
+    pub retransmissions: Option<u64>,
 }
 
 impl Deref for LinkUnicast {
@@ -63,6 +105,20 @@ impl Deref for LinkUnicast {
     }
 }
 
+impl LinkUnicast {
+    /// Write all the slices of a [`ZBuf`] on the link without coalescing them, when the link
+    /// supports vectored I/O.
+    ///
+    /// This is used by the low-latency transport, which writes the serialized messages as is.
+    /// The universal transport writes its batches with [`LinkUnicastTrait::write_vectored_all`]
+    /// as well: the large payloads are referenced by the batches instead of being copied, and
+    /// are written alongside the batch buffer and its checksum trailer.
+    pub async fn write_zbuf_all(&self, zbuf: &ZBuf) -> ZResult<()> {
+        let buffers: Vec<&[u8]> = zbuf.zslices().map(|s| s.as_slice()).collect();
+        self.write_vectored_all(&buffers).await
+    }
+}
+
 impl Eq for LinkUnicast {}
 
 impl PartialEq for LinkUnicast {
@@ -102,6 +158,53 @@ impl From<Arc<dyn LinkUnicastTrait>> for LinkUnicast {
     }
 }
 
+// The maximum number of buffers in a single vectored write, i.e. the IOV_MAX of Linux
+const MAX_IO_SLICES: usize = 1_024;
+
+/// Write all the given buffers on a stream, in order, with vectored writes.
+pub async fn write_all_vectored<W>(writer: &mut W, buffers: &[&[u8]]) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    // The position of the first byte not written yet
+    let (mut index, mut offset) = (0, 0);
+    let mut slices = Vec::with_capacity(buffers.len().min(MAX_IO_SLICES));
+    loop {
+        while index < buffers.len() && offset == buffers[index].len() {
+            index += 1;
+            offset = 0;
+        }
+        if index == buffers.len() {
+            return Ok(());
+        }
+
+        slices.clear();
+        slices.push(IoSlice::new(&buffers[index][offset..]));
+        slices.extend(
+            buffers[index + 1..]
+                .iter()
+                .take(MAX_IO_SLICES - 1)
+                .map(|b| IoSlice::new(b)),
+        );
+        let mut n = writer.write_vectored(&slices).await?;
+        if n == 0 {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+
+        while n > 0 {
+            let remaining = buffers[index].len() - offset;
+            if n < remaining {
+                offset += n;
+                n = 0;
+            } else {
+                n -= remaining;
+                index += 1;
+                offset = 0;
+            }
+        }
+    }
+}
+
 pub fn get_ip_interface_names(addr: &SocketAddr) -> Vec<String> {
     match zenoh_util::net::get_interface_names_by_addr(addr.ip()) {
         Ok(interfaces) => {
@@ -114,3 +217,96 @@ pub fn get_ip_interface_names(addr: &SocketAddr) -> Vec<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    // A writer accepting at most `chunk` bytes per write, possibly ending in the middle
+    // of a buffer, as a socket with a full send buffer does
+    struct PartialWriter {
+        chunk: usize,
+        written: Vec<u8>,
+        calls: usize,
+    }
+
+    impl AsyncWrite for PartialWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.poll_write_vectored(cx, &[IoSlice::new(buf)])
+        }
+
+        fn poll_write_vectored(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> Poll<io::Result<usize>> {
+            assert!(bufs.len() <= MAX_IO_SLICES);
+            self.calls += 1;
+            let mut n = 0;
+            for buf in bufs {
+                let len = buf.len().min(self.chunk - n);
+                self.written.extend_from_slice(&buf[..len]);
+                n += len;
+                if n == self.chunk {
+                    break;
+                }
+            }
+            Poll::Ready(Ok(n))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            true
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn write_all_vectored_partial_writes() {
+        let buffers: Vec<Vec<u8>> = (0..8_u8)
+            .map(|i| vec![i; (i as usize * 3) % 7])
+            .chain((0..2 * MAX_IO_SLICES).map(|i| vec![i as u8]))
+            .collect();
+        let buffers: Vec<&[u8]> = buffers.iter().map(|b| b.as_slice()).collect();
+        let expected = buffers.concat();
+
+        for chunk in [1, 2, 5, 64, expected.len()] {
+            let mut writer = PartialWriter {
+                chunk,
+                written: vec![],
+                calls: 0,
+            };
+            futures::executor::block_on(write_all_vectored(&mut writer, &buffers)).unwrap();
+            assert_eq!(writer.written, expected);
+            assert!(writer.calls >= expected.len() / chunk);
+        }
+    }
+
+    #[test]
+    fn write_all_vectored_write_zero() {
+        let mut writer = PartialWriter {
+            chunk: 0,
+            written: vec![],
+            calls: 0,
+        };
+        let res =
+            futures::executor::block_on(write_all_vectored(&mut writer, &[b"zenoh".as_slice()]));
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::WriteZero);
+        // Nothing to write is not an error
+        futures::executor::block_on(write_all_vectored(&mut writer, &[&[], &[]])).unwrap();
+    }
+}
@@ -0,0 +1,799 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! An io_uring backend for the stream-oriented links on Linux.
+//!
+//! A single ring is shared by all the links of the process: the operations are submitted by the
+//! tasks reading or writing on the links, while their completions are reaped by a dedicated thread.
+//! The buffers handed to the kernel are owned by the ring until the operation completes, so that
+//! dropping a pending read or write is safe: the operation is then cancelled and its buffer is
+//! released once the kernel is done with it. The vectored writes send the buffers of the caller
+//! without copying them, so dropping a pending one waits for the kernel to be done with them.
+use libc::{c_long, c_uint, c_void};
+use std::future::Future;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::pin::Pin;
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, Thread};
+use zenoh_core::zlock;
+
+// The number of submission queue entries of the ring
+const RING_ENTRIES: u32 = 256;
+
+// The io_uring interface of Linux (include/uapi/linux/io_uring.h)
+const IORING_OFF_SQ_RING: libc::off_t = 0;
+const IORING_OFF_SQES: libc::off_t = 0x1000_0000;
+const IORING_FEAT_SINGLE_MMAP: u32 = 1 << 0;
+const IORING_FEAT_NODROP: u32 = 1 << 1;
+const IORING_FEAT_FAST_POLL: u32 = 1 << 5;
+const IORING_ENTER_GETEVENTS: c_uint = 1 << 0;
+const IORING_OP_SENDMSG: u8 = 9;
+const IORING_OP_ASYNC_CANCEL: u8 = 14;
+const IORING_OP_SEND: u8 = 26;
+const IORING_OP_RECV: u8 = 27;
+
+// The user data of the cancellation requests, whose completions are ignored
+const CANCEL_USER_DATA: u64 = u64::MAX;
+
+// The maximum number of buffers of a vectored write (UIO_MAXIOV)
+const MAX_IOVECS: usize = 1024;
+
+#[repr(C)]
+#[derive(Default)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+#[repr(C)]
+#[derive(Clone, Default)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    op_flags: u32,
+    user_data: u64,
+    buf_index: u16,
+    personality: u16,
+    splice_fd_in: i32,
+    addr3: u64,
+    pad: u64,
+}
+
+#[repr(C)]
+struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+fn cvt(res: c_long) -> io::Result<c_long> {
+    if res < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(res)
+    }
+}
+
+struct Mmap {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl Mmap {
+    fn new(fd: RawFd, len: usize, offset: libc::off_t) -> io::Result<Mmap> {
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd,
+                offset,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mmap {
+            ptr: ptr as *mut u8,
+            len,
+        })
+    }
+
+    fn at<T>(&self, offset: u32) -> *mut T {
+        unsafe { self.ptr.add(offset as usize) as *mut T }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut c_void, self.len);
+        }
+    }
+}
+
+enum OpState {
+    // The operation is in flight, the waker is the one of the last poll
+    Pending(Option<Waker>),
+    // The operation completed with the given result
+    Completed(i32),
+    // The operation was dropped while in flight: its buffer is kept until the kernel releases it
+    Cancelled { _buf: Vec<u8> },
+    // The operation was dropped while in flight and borrows the memory of the given thread,
+    // which waits for its completion
+    Abandoned(Thread),
+}
+
+struct State {
+    ops: Vec<Option<OpState>>,
+    free: Vec<usize>,
+}
+
+impl State {
+    fn insert(&mut self) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.ops[index] = Some(OpState::Pending(None));
+                index
+            }
+            None => {
+                self.ops.push(Some(OpState::Pending(None)));
+                self.ops.len() - 1
+            }
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.ops[index] = None;
+        self.free.push(index);
+    }
+}
+
+struct Uring {
+    fd: OwnedFd,
+    // The shared ring memory: the submission and completion queues, then the submission entries
+    _ring: Mmap,
+    sqes: Mmap,
+    sq_head: *const AtomicU32,
+    sq_tail: *const AtomicU32,
+    sq_mask: u32,
+    sq_entries: u32,
+    cq_head: *const AtomicU32,
+    cq_tail: *const AtomicU32,
+    cq_mask: u32,
+    cqes: *const Cqe,
+    // The submission queue is only accessed while holding the state lock
+    state: Mutex<State>,
+}
+
+// The raw pointers point to the ring memory, which lives as long as the ring
+unsafe impl Send for Uring {}
+unsafe impl Sync for Uring {}
+
+impl Uring {
+    fn new() -> io::Result<Uring> {
+        let mut params = Params::default();
+        let fd = cvt(unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_setup,
+                RING_ENTRIES as c_uint,
+                &mut params as *mut Params,
+            )
+        })?;
+        let fd = unsafe { OwnedFd::from_raw_fd(fd as RawFd) };
+
+        // Socket reads and writes are only efficient with the internal polling of the ring
+        let required = IORING_FEAT_SINGLE_MMAP | IORING_FEAT_NODROP | IORING_FEAT_FAST_POLL;
+        if params.features & required != required {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the kernel lacks the required io_uring features",
+            ));
+        }
+
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * 4;
+        let cq_len =
+            params.cq_off.cqes as usize + params.cq_entries as usize * mem::size_of::<Cqe>();
+        let ring = Mmap::new(fd.as_raw_fd(), sq_len.max(cq_len), IORING_OFF_SQ_RING)?;
+        let sqes = Mmap::new(
+            fd.as_raw_fd(),
+            params.sq_entries as usize * mem::size_of::<Sqe>(),
+            IORING_OFF_SQES,
+        )?;
+
+        // Map the submission queue slots to the submission entries once for all
+        let array: *mut u32 = ring.at(params.sq_off.array);
+        for i in 0..params.sq_entries {
+            unsafe { array.add(i as usize).write(i) };
+        }
+
+        Ok(Uring {
+            sq_head: ring.at(params.sq_off.head),
+            sq_tail: ring.at(params.sq_off.tail),
+            sq_mask: unsafe { *ring.at::<u32>(params.sq_off.ring_mask) },
+            sq_entries: params.sq_entries,
+            cq_head: ring.at(params.cq_off.head),
+            cq_tail: ring.at(params.cq_off.tail),
+            cq_mask: unsafe { *ring.at::<u32>(params.cq_off.ring_mask) },
+            cqes: ring.at(params.cq_off.cqes),
+            fd,
+            _ring: ring,
+            sqes,
+            state: Mutex::new(State {
+                ops: vec![],
+                free: vec![],
+            }),
+        })
+    }
+
+    fn get() -> Option<&'static Uring> {
+        static URING: OnceLock<Option<Uring>> = OnceLock::new();
+        URING
+            .get_or_init(|| {
+                let uring = match Uring::new() {
+                    Ok(uring) => uring,
+                    Err(e) => {
+                        tracing::warn!("io_uring is not available, falling back to epoll: {}", e);
+                        return None;
+                    }
+                };
+                if let Err(e) = std::thread::Builder::new()
+                    .name("zenoh-io-uring".to_string())
+                    .spawn(|| Uring::get().unwrap().drive())
+                {
+                    tracing::warn!(
+                        "Unable to start the io_uring thread, falling back to epoll: {}",
+                        e
+                    );
+                    return None;
+                }
+                Some(uring)
+            })
+            .as_ref()
+    }
+
+    fn enter(&self, to_submit: u32, min_complete: u32, flags: c_uint) -> io::Result<()> {
+        cvt(unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_enter,
+                self.fd.as_raw_fd(),
+                to_submit as c_uint,
+                min_complete as c_uint,
+                flags,
+                ptr::null::<c_void>(),
+                0usize,
+            )
+        })?;
+        Ok(())
+    }
+
+    // Push an entry in the submission queue, returning false if the queue is full
+    fn push(&self, _state: &mut State, sqe: &Sqe) -> bool {
+        unsafe {
+            let head = (*self.sq_head).load(Ordering::Acquire);
+            let tail = (*self.sq_tail).load(Ordering::Relaxed);
+            if tail.wrapping_sub(head) == self.sq_entries {
+                return false;
+            }
+            let slot: *mut Sqe = self.sqes.at(0);
+            slot.add((tail & self.sq_mask) as usize).write(sqe.clone());
+            (*self.sq_tail).store(tail.wrapping_add(1), Ordering::Release);
+        }
+        true
+    }
+
+    fn submit(&self) {
+        // The entries left in the queue upon error are submitted by the next call to enter
+        if let Err(e) = self.enter(self.sq_entries, 0, 0) {
+            tracing::trace!("io_uring submission error: {}", e);
+        }
+    }
+
+    // Push an entry in the submission queue, retrying until the kernel consumed some entries
+    // if it is full
+    fn push_blocking(&self, mut state: MutexGuard<'_, State>, sqe: &Sqe) {
+        while !self.push(&mut state, sqe) {
+            drop(state);
+            self.submit();
+            thread::yield_now();
+            state = zlock!(self.state);
+        }
+        drop(state);
+        self.submit();
+    }
+
+    async fn op(
+        &'static self,
+        mut sqe: Sqe,
+        buf: Vec<u8>,
+        msg: Option<Box<Msg>>,
+    ) -> (io::Result<usize>, Vec<u8>) {
+        let index = loop {
+            {
+                let mut state = zlock!(self.state);
+                let index = state.insert();
+                sqe.user_data = index as u64;
+                if self.push(&mut state, &sqe) {
+                    break index;
+                }
+                // The queue is full, retry once the kernel consumed some entries
+                state.remove(index);
+            }
+            self.submit();
+            tokio::task::yield_now().await;
+        };
+        self.submit();
+        Op {
+            uring: self,
+            index,
+            buf: Some(buf),
+            msg,
+        }
+        .await
+    }
+
+    // Sends the given message, whose buffers are borrowed from the caller until it completes
+    async fn sendmsg(&'static self, fd: RawFd, msg: Box<Msg>) -> io::Result<usize> {
+        let sqe = Sqe {
+            opcode: IORING_OP_SENDMSG,
+            fd,
+            addr: &msg.hdr as *const libc::msghdr as u64,
+            len: 1,
+            op_flags: libc::MSG_NOSIGNAL as u32,
+            ..Default::default()
+        };
+        self.op(sqe, vec![], Some(msg)).await.0
+    }
+
+    fn drive(&self) {
+        loop {
+            if let Err(e) = self.enter(self.sq_entries, 1, IORING_ENTER_GETEVENTS) {
+                if e.kind() != io::ErrorKind::Interrupted {
+                    tracing::trace!("io_uring completion error: {}", e);
+                }
+            }
+
+            let mut wakers = vec![];
+            let mut threads = vec![];
+            {
+                let mut state = zlock!(self.state);
+                unsafe {
+                    let mut head = (*self.cq_head).load(Ordering::Relaxed);
+                    let tail = (*self.cq_tail).load(Ordering::Acquire);
+                    while head != tail {
+                        let cqe = &*self.cqes.add((head & self.cq_mask) as usize);
+                        head = head.wrapping_add(1);
+                        if cqe.user_data == CANCEL_USER_DATA {
+                            continue;
+                        }
+                        let index = cqe.user_data as usize;
+                        match state.ops[index].replace(OpState::Completed(cqe.res)) {
+                            Some(OpState::Pending(waker)) => wakers.extend(waker),
+                            // The waiting thread releases the operation
+                            Some(OpState::Abandoned(thread)) => threads.push(thread),
+                            _ => state.remove(index),
+                        }
+                    }
+                    (*self.cq_head).store(head, Ordering::Release);
+                }
+            }
+            for waker in wakers {
+                waker.wake();
+            }
+            for thread in threads {
+                thread.unpark();
+            }
+        }
+    }
+}
+
+// The header and the buffers of a vectored write, read by the kernel until the write completes
+struct Msg {
+    hdr: libc::msghdr,
+    _iovecs: Vec<libc::iovec>,
+}
+
+// The pointers point to the iovecs owned by the message and to the buffers of the caller of the
+// write, which waits for the kernel to be done with them
+unsafe impl Send for Msg {}
+unsafe impl Sync for Msg {}
+
+impl Msg {
+    fn new(buffers: &[&[u8]]) -> Box<Msg> {
+        let mut iovecs: Vec<libc::iovec> = buffers
+            .iter()
+            .map(|b| libc::iovec {
+                iov_base: b.as_ptr() as *mut c_void,
+                iov_len: b.len(),
+            })
+            .collect();
+        let mut hdr: libc::msghdr = unsafe { mem::zeroed() };
+        hdr.msg_iov = iovecs.as_mut_ptr();
+        hdr.msg_iovlen = iovecs.len() as _;
+        Box::new(Msg {
+            hdr,
+            _iovecs: iovecs,
+        })
+    }
+}
+
+struct Op {
+    uring: &'static Uring,
+    index: usize,
+    buf: Option<Vec<u8>>,
+    // The vectored write of the operation, if any
+    msg: Option<Box<Msg>>,
+}
+
+impl Future for Op {
+    type Output = (io::Result<usize>, Vec<u8>);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = zlock!(self.uring.state);
+        match state.ops[self.index].as_mut() {
+            Some(OpState::Completed(res)) => {
+                let res = *res;
+                state.remove(self.index);
+                drop(state);
+                let res = if res < 0 {
+                    Err(io::Error::from_raw_os_error(-res))
+                } else {
+                    Ok(res as usize)
+                };
+                Poll::Ready((res, self.buf.take().unwrap()))
+            }
+            Some(OpState::Pending(waker)) => {
+                if !waker.as_ref().is_some_and(|w| w.will_wake(cx.waker())) {
+                    *waker = Some(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Drop for Op {
+    fn drop(&mut self) {
+        let Some(buf) = self.buf.take() else {
+            return;
+        };
+        let mut state = zlock!(self.uring.state);
+        if let Some(OpState::Completed(_)) = state.ops[self.index] {
+            state.remove(self.index);
+            return;
+        }
+        let sqe = Sqe {
+            opcode: IORING_OP_ASYNC_CANCEL,
+            addr: self.index as u64,
+            user_data: CANCEL_USER_DATA,
+            ..Default::default()
+        };
+        if self.msg.is_none() {
+            state.ops[self.index] = Some(OpState::Cancelled { _buf: buf });
+            // A full queue simply delays the cancellation until the peer sends or closes
+            if self.uring.push(&mut state, &sqe) {
+                drop(state);
+                self.uring.submit();
+            }
+            return;
+        }
+
+        // The buffers of the caller must outlive the operation: wait for the kernel to release
+        // them, which it does as soon as the cancellation is submitted
+        state.ops[self.index] = Some(OpState::Abandoned(thread::current()));
+        self.uring.push_blocking(state, &sqe);
+        loop {
+            let mut state = zlock!(self.uring.state);
+            if let Some(OpState::Completed(_)) = state.ops[self.index] {
+                state.remove(self.index);
+                return;
+            }
+            drop(state);
+            thread::park();
+        }
+    }
+}
+
+/// A stream socket whose reads and writes go through io_uring.
+///
+/// The socket is switched to blocking mode and must not be used for I/O by anything else,
+/// while its ownership is kept by the caller.
+pub struct UringStream {
+    uring: &'static Uring,
+    fd: RawFd,
+    // The buffers handed to the kernel, reused across the operations
+    rbuf: Mutex<Vec<u8>>,
+    wbuf: Mutex<Vec<u8>>,
+}
+
+impl UringStream {
+    /// Returns `None` if io_uring is not supported on this host.
+    pub fn new(fd: RawFd) -> io::Result<Option<UringStream>> {
+        let Some(uring) = Uring::get() else {
+            return Ok(None);
+        };
+
+        // Blocking sockets let the ring wait for readiness instead of failing with EAGAIN
+        let flags = cvt(unsafe { libc::fcntl(fd, libc::F_GETFL) } as c_long)?;
+        cvt(unsafe { libc::fcntl(fd, libc::F_SETFL, flags as i32 & !libc::O_NONBLOCK) } as c_long)?;
+
+        Ok(Some(UringStream {
+            uring,
+            fd,
+            rbuf: Mutex::new(vec![]),
+            wbuf: Mutex::new(vec![]),
+        }))
+    }
+
+    pub async fn read(&self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut buf = mem::take(&mut *zlock!(self.rbuf));
+        if buf.len() < buffer.len() {
+            buf.resize(buffer.len(), 0);
+        }
+        let sqe = Sqe {
+            opcode: IORING_OP_RECV,
+            fd: self.fd,
+            addr: buf.as_mut_ptr() as u64,
+            len: buffer.len() as u32,
+            ..Default::default()
+        };
+        let (res, buf) = self.uring.op(sqe, buf, None).await;
+        if let Ok(n) = res {
+            buffer[..n].copy_from_slice(&buf[..n]);
+        }
+        *zlock!(self.rbuf) = buf;
+        res
+    }
+
+    pub async fn read_exact(&self, buffer: &mut [u8]) -> io::Result<()> {
+        let mut read = 0;
+        while read < buffer.len() {
+            match self.read(&mut buffer[read..]).await {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn write(&self, buffer: &[u8]) -> io::Result<usize> {
+        let mut buf = mem::take(&mut *zlock!(self.wbuf));
+        if buf.len() < buffer.len() {
+            buf.resize(buffer.len(), 0);
+        }
+        buf[..buffer.len()].copy_from_slice(buffer);
+        let sqe = Sqe {
+            opcode: IORING_OP_SEND,
+            fd: self.fd,
+            addr: buf.as_ptr() as u64,
+            len: buffer.len() as u32,
+            op_flags: libc::MSG_NOSIGNAL as u32,
+            ..Default::default()
+        };
+        let (res, buf) = self.uring.op(sqe, buf, None).await;
+        *zlock!(self.wbuf) = buf;
+        res
+    }
+
+    pub async fn write_all(&self, buffer: &[u8]) -> io::Result<()> {
+        let mut written = 0;
+        while written < buffer.len() {
+            match self.write(&buffer[written..]).await {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => written += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the given buffers at once, without copying them, returning the number of bytes
+    /// written.
+    pub async fn write_vectored(&self, buffers: &[&[u8]]) -> io::Result<usize> {
+        // sendmsg is the writev of the sockets, which doesn't raise SIGPIPE with MSG_NOSIGNAL
+        let buffers = &buffers[..buffers.len().min(MAX_IOVECS)];
+        self.uring.sendmsg(self.fd, Msg::new(buffers)).await
+    }
+
+    pub async fn write_vectored_all(&self, buffers: &[&[u8]]) -> io::Result<()> {
+        // The buffers left to write, the first one possibly partially written
+        let mut buffers: Vec<&[u8]> = buffers.iter().copied().filter(|b| !b.is_empty()).collect();
+        let mut start = 0;
+        while start < buffers.len() {
+            match self.write_vectored(&buffers[start..]).await {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(mut n) => {
+                    while n > 0 {
+                        let len = buffers[start].len();
+                        if n < len {
+                            buffers[start] = &buffers[start][n..];
+                            break;
+                        }
+                        n -= len;
+                        start += 1;
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        os::{fd::AsRawFd, unix::net::UnixStream},
+        time::Duration,
+    };
+
+    // The streams borrow the file descriptors of the returned sockets
+    fn pair() -> Option<((UnixStream, UringStream), (UnixStream, UringStream))> {
+        let (a, b) = UnixStream::pair().unwrap();
+        let ua = UringStream::new(a.as_raw_fd()).unwrap()?;
+        let ub = UringStream::new(b.as_raw_fd()).unwrap()?;
+        Some(((a, ua), (b, ub)))
+    }
+
+    #[test]
+    fn state_reuses_indexes() {
+        let mut state = State {
+            ops: vec![],
+            free: vec![],
+        };
+        assert_eq!(state.insert(), 0);
+        assert_eq!(state.insert(), 1);
+        state.remove(0);
+        assert!(state.ops[0].is_none());
+        assert_eq!(state.insert(), 0);
+        assert_eq!(state.insert(), 2);
+        assert_eq!(state.ops.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn uring_read_write() {
+        let Some(((_a, a), (_b, b))) = pair() else {
+            return;
+        };
+        a.write_all(b"zenoh").await.unwrap();
+        let mut buf = [0; 5];
+        b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"zenoh");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn uring_write_vectored_all() {
+        let Some(((_a, a), (_b, b))) = pair() else {
+            return;
+        };
+        // Larger than the socket buffer, so that the writes are partial
+        let buffers: Vec<Vec<u8>> = (0..16_usize)
+            .map(|i| (0..i * 65_537).map(|j| (i + j) as u8).collect())
+            .collect();
+        let buffers: Vec<&[u8]> = buffers.iter().map(|b| b.as_slice()).collect();
+        let expected = buffers.concat();
+
+        let reader = async {
+            let mut received = vec![0; expected.len()];
+            b.read_exact(&mut received).await.unwrap();
+            received
+        };
+        let (res, received) = tokio::join!(a.write_vectored_all(&buffers), reader);
+        res.unwrap();
+        assert!(received == expected);
+    }
+
+    #[tokio::test]
+    async fn uring_read_eof() {
+        let Some(((_a, a), (b, ub))) = pair() else {
+            return;
+        };
+        a.write_all(b"zen").await.unwrap();
+        drop(ub);
+        drop(b);
+        let mut buf = [0; 5];
+        let err = a.read_exact(&mut buf).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[tokio::test]
+    async fn uring_cancel_read() {
+        let Some(((_a, a), (_b, b))) = pair() else {
+            return;
+        };
+        let mut buf = [0; 5];
+        let res = tokio::time::timeout(Duration::from_millis(100), b.read(&mut buf)).await;
+        assert!(res.is_err());
+
+        // The cancelled read must not consume the data sent afterwards
+        a.write_all(b"zenoh").await.unwrap();
+        b.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"zenoh");
+    }
+
+    #[tokio::test]
+    async fn uring_cancel_write_vectored() {
+        let Some(((_a, a), (_b, b))) = pair() else {
+            return;
+        };
+        // Nobody reads, so the write blocks once the socket buffer is full
+        let data = vec![0xAA; 16 * 1024 * 1024];
+        let res = tokio::time::timeout(
+            Duration::from_millis(100),
+            a.write_vectored_all(&[&data, &data]),
+        )
+        .await;
+        assert!(res.is_err());
+        drop(data);
+
+        // The stream remains usable in the other direction
+        b.write_all(b"zenoh").await.unwrap();
+        let mut buf = [0; 5];
+        a.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"zenoh");
+    }
+}
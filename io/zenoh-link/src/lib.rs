@@ -24,13 +24,16 @@ use zenoh_result::{bail, ZResult};
 #[cfg(feature = "transport_tcp")]
 pub use zenoh_link_tcp as tcp;
 #[cfg(feature = "transport_tcp")]
-use zenoh_link_tcp::{LinkManagerUnicastTcp, TcpLocatorInspector, TCP_LOCATOR_PREFIX};
+use zenoh_link_tcp::{
+    LinkManagerUnicastTcp, TcpConfigurator, TcpLocatorInspector, TCP_LOCATOR_PREFIX,
+};
 
 #[cfg(feature = "transport_udp")]
 pub use zenoh_link_udp as udp;
 #[cfg(feature = "transport_udp")]
 use zenoh_link_udp::{
-    LinkManagerMulticastUdp, LinkManagerUnicastUdp, UdpLocatorInspector, UDP_LOCATOR_PREFIX,
+    LinkManagerMulticastUdp, LinkManagerUnicastUdp, UdpLocatorInspector, UdpsConfigurator,
+    UdpsLocatorInspector, UDPS_LOCATOR_PREFIX, UDP_LOCATOR_PREFIX,
 };
 
 #[cfg(feature = "transport_tls")]
@@ -50,7 +53,10 @@ use zenoh_link_quic::{
 #[cfg(feature = "transport_ws")]
 pub use zenoh_link_ws as ws;
 #[cfg(feature = "transport_ws")]
-use zenoh_link_ws::{LinkManagerUnicastWs, WsLocatorInspector, WS_LOCATOR_PREFIX};
+use zenoh_link_ws::{
+    LinkManagerUnicastWs, WsLocatorInspector, WssConfigurator, WssLocatorInspector,
+    WSS_LOCATOR_PREFIX, WS_LOCATOR_PREFIX,
+};
 
 #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
 pub use zenoh_link_unixsock_stream as unixsock_stream;
@@ -77,6 +83,19 @@ pub use zenoh_link_vsock as vsock;
 #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
 use zenoh_link_vsock::{LinkManagerUnicastVsock, VsockLocatorInspector, VSOCK_LOCATOR_PREFIX};
 
+#[cfg(all(feature = "transport_ble", target_os = "linux"))]
+pub use zenoh_link_ble as ble;
+#[cfg(all(feature = "transport_ble", target_os = "linux"))]
+use zenoh_link_ble::{BleLocatorInspector, LinkManagerUnicastBle, BLE_LOCATOR_PREFIX};
+
+#[cfg(feature = "transport_webtransport")]
+pub use zenoh_link_webtransport as webtransport;
+#[cfg(feature = "transport_webtransport")]
+use zenoh_link_webtransport::{
+    LinkManagerUnicastWebTransport, WebTransportConfigurator, WebTransportLocatorInspector,
+    WEBTRANSPORT_LOCATOR_PREFIX,
+};
+
 pub use zenoh_link_commons::*;
 pub use zenoh_protocol::core::{EndPoint, Locator};
 
@@ -89,8 +108,12 @@ pub const PROTOCOLS: &[&str] = &[
     tls::TLS_LOCATOR_PREFIX,
     #[cfg(feature = "transport_udp")]
     udp::UDP_LOCATOR_PREFIX,
+    #[cfg(feature = "transport_udp")]
+    udp::UDPS_LOCATOR_PREFIX,
     #[cfg(feature = "transport_ws")]
     ws::WS_LOCATOR_PREFIX,
+    #[cfg(feature = "transport_ws")]
+    ws::WSS_LOCATOR_PREFIX,
     #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
     unixsock_stream::UNIXSOCKSTREAM_LOCATOR_PREFIX,
     #[cfg(feature = "transport_serial")]
@@ -99,6 +122,10 @@ pub const PROTOCOLS: &[&str] = &[
     unixpipe::UNIXPIPE_LOCATOR_PREFIX,
     #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
     vsock::VSOCK_LOCATOR_PREFIX,
+    #[cfg(all(feature = "transport_ble", target_os = "linux"))]
+    ble::BLE_LOCATOR_PREFIX,
+    #[cfg(feature = "transport_webtransport")]
+    webtransport::WEBTRANSPORT_LOCATOR_PREFIX,
 ];
 
 #[derive(Default, Clone)]
@@ -111,8 +138,12 @@ pub struct LocatorInspector {
     tls_inspector: TlsLocatorInspector,
     #[cfg(feature = "transport_udp")]
     udp_inspector: UdpLocatorInspector,
+    #[cfg(feature = "transport_udp")]
+    udps_inspector: UdpsLocatorInspector,
     #[cfg(feature = "transport_ws")]
     ws_inspector: WsLocatorInspector,
+    #[cfg(feature = "transport_ws")]
+    wss_inspector: WssLocatorInspector,
     #[cfg(all(feature = "transport_unixsock-stream", target_family = "unix"))]
     unixsock_stream_inspector: UnixSockStreamLocatorInspector,
     #[cfg(feature = "transport_serial")]
@@ -121,6 +152,10 @@ pub struct LocatorInspector {
     unixpipe_inspector: UnixPipeLocatorInspector,
     #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
     vsock_inspector: VsockLocatorInspector,
+    #[cfg(all(feature = "transport_ble", target_os = "linux"))]
+    ble_inspector: BleLocatorInspector,
+    #[cfg(feature = "transport_webtransport")]
+    webtransport_inspector: WebTransportLocatorInspector,
 }
 impl LocatorInspector {
     pub async fn is_multicast(&self, locator: &Locator) -> ZResult<bool> {
@@ -132,6 +167,8 @@ impl LocatorInspector {
             TCP_LOCATOR_PREFIX => self.tcp_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_udp")]
             UDP_LOCATOR_PREFIX => self.udp_inspector.is_multicast(locator).await,
+            #[cfg(feature = "transport_udp")]
+            UDPS_LOCATOR_PREFIX => self.udps_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_tls")]
             TLS_LOCATOR_PREFIX => self.tls_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_quic")]
@@ -142,24 +179,38 @@ impl LocatorInspector {
             }
             #[cfg(feature = "transport_ws")]
             WS_LOCATOR_PREFIX => self.ws_inspector.is_multicast(locator).await,
+            #[cfg(feature = "transport_ws")]
+            WSS_LOCATOR_PREFIX => self.wss_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_serial")]
             SERIAL_LOCATOR_PREFIX => self.serial_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_unixpipe")]
             UNIXPIPE_LOCATOR_PREFIX => self.unixpipe_inspector.is_multicast(locator).await,
             #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
             VSOCK_LOCATOR_PREFIX => self.vsock_inspector.is_multicast(locator).await,
+            #[cfg(all(feature = "transport_ble", target_os = "linux"))]
+            BLE_LOCATOR_PREFIX => self.ble_inspector.is_multicast(locator).await,
+            #[cfg(feature = "transport_webtransport")]
+            WEBTRANSPORT_LOCATOR_PREFIX => self.webtransport_inspector.is_multicast(locator).await,
             _ => bail!("Unsupported protocol: {}.", protocol),
         }
     }
 }
 #[derive(Default)]
 pub struct LinkConfigurator {
+    #[cfg(feature = "transport_tcp")]
+    tcp_inspector: TcpConfigurator,
     #[cfg(feature = "transport_quic")]
     quic_inspector: QuicConfigurator,
     #[cfg(feature = "transport_tls")]
     tls_inspector: TlsConfigurator,
     #[cfg(feature = "transport_unixpipe")]
     unixpipe_inspector: UnixPipeConfigurator,
+    #[cfg(feature = "transport_ws")]
+    wss_inspector: WssConfigurator,
+    #[cfg(feature = "transport_udp")]
+    udps_inspector: UdpsConfigurator,
+    #[cfg(feature = "transport_webtransport")]
+    webtransport_inspector: WebTransportConfigurator,
 }
 
 impl LinkConfigurator {
@@ -181,6 +232,13 @@ impl LinkConfigurator {
                 errors.insert(proto, e);
             }
         };
+        #[cfg(feature = "transport_tcp")]
+        {
+            insert_config(
+                TCP_LOCATOR_PREFIX.into(),
+                self.tcp_inspector.inspect_config(config),
+            );
+        }
         #[cfg(feature = "transport_quic")]
         {
             insert_config(
@@ -202,6 +260,27 @@ impl LinkConfigurator {
                 self.unixpipe_inspector.inspect_config(config),
             );
         }
+        #[cfg(feature = "transport_ws")]
+        {
+            insert_config(
+                WSS_LOCATOR_PREFIX.into(),
+                self.wss_inspector.inspect_config(config),
+            );
+        }
+        #[cfg(feature = "transport_udp")]
+        {
+            insert_config(
+                UDPS_LOCATOR_PREFIX.into(),
+                self.udps_inspector.inspect_config(config),
+            );
+        }
+        #[cfg(feature = "transport_webtransport")]
+        {
+            insert_config(
+                WEBTRANSPORT_LOCATOR_PREFIX.into(),
+                self.webtransport_inspector.inspect_config(config),
+            );
+        }
         (configs, errors)
     }
 }
@@ -219,6 +298,10 @@ impl LinkManagerBuilderUnicast {
             TCP_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastTcp::new(_manager))),
             #[cfg(feature = "transport_udp")]
             UDP_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastUdp::new(_manager))),
+            #[cfg(feature = "transport_udp")]
+            UDPS_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastUdp::new_secure(
+                _manager,
+            ))),
             #[cfg(feature = "transport_tls")]
             TLS_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastTls::new(_manager))),
             #[cfg(feature = "transport_quic")]
@@ -229,6 +312,10 @@ impl LinkManagerBuilderUnicast {
             )),
             #[cfg(feature = "transport_ws")]
             WS_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastWs::new(_manager))),
+            #[cfg(feature = "transport_ws")]
+            WSS_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastWs::new_secure(
+                _manager,
+            ))),
             #[cfg(feature = "transport_serial")]
             SERIAL_LOCATOR_PREFIX => {
                 Ok(std::sync::Arc::new(LinkManagerUnicastSerial::new(_manager)))
@@ -239,6 +326,12 @@ impl LinkManagerBuilderUnicast {
             }
             #[cfg(all(feature = "transport_vsock", target_os = "linux"))]
             VSOCK_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastVsock::new(_manager))),
+            #[cfg(all(feature = "transport_ble", target_os = "linux"))]
+            BLE_LOCATOR_PREFIX => Ok(std::sync::Arc::new(LinkManagerUnicastBle::new(_manager))),
+            #[cfg(feature = "transport_webtransport")]
+            WEBTRANSPORT_LOCATOR_PREFIX => Ok(std::sync::Arc::new(
+                LinkManagerUnicastWebTransport::new(_manager),
+            )),
             _ => bail!("Unicast not supported for {} protocol", protocol),
         }
     }
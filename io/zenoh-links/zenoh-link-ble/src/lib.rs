@@ -0,0 +1,82 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This crate is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+//!
+//! Implements an experimental link over Bluetooth Low Energy L2CAP connection-oriented
+//! channels (LE CoC), using the Bluetooth sockets of Linux (BlueZ).
+//!
+//! The endpoints are of the form `ble/<bdaddr>:<psm>`, e.g. `ble/00:1A:7D:DA:71:13:128`,
+//! where the listeners may use the `00:00:00:00:00:00` address to accept the channels on
+//! any adapter. The links are reliable and message-oriented, each batch being sent in
+//! one L2CAP SDU, so they are best used with the low-latency transport.
+use async_trait::async_trait;
+use zenoh_core::zconfigurable;
+use zenoh_link_commons::LocatorInspector;
+use zenoh_protocol::core::Locator;
+use zenoh_result::ZResult;
+
+#[cfg(target_os = "linux")]
+mod socket;
+#[cfg(target_os = "linux")]
+mod unicast;
+#[cfg(target_os = "linux")]
+pub use unicast::*;
+
+pub const BLE_LOCATOR_PREFIX: &str = "ble";
+
+#[derive(Default, Clone, Copy)]
+pub struct BleLocatorInspector;
+#[async_trait]
+impl LocatorInspector for BleLocatorInspector {
+    fn protocol(&self) -> &str {
+        BLE_LOCATOR_PREFIX
+    }
+
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
+zconfigurable! {
+    // Default receive MTU (L2CAP SDU) in bytes, advertised to the peer when
+    // establishing the channel. The MTU of a link is the minimum of the receive
+    // MTU and of the send MTU advertised by the peer.
+    static ref BLE_DEFAULT_MTU: u16 = 4096;
+    // Amount of time in microseconds to throttle the accept loop upon an error.
+    // Default set to 100 ms.
+    static ref BLE_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+}
+
+pub mod config {
+    /// The type of the remote address of the links opened on an endpoint:
+    /// `public` (the default) or `random`.
+    pub const BLE_ADDR_TYPE: &str = "addr_type";
+    pub const BLE_ADDR_TYPE_PUBLIC: &str = "public";
+    pub const BLE_ADDR_TYPE_RANDOM: &str = "random";
+
+    /// The receive MTU advertised to the peer, in bytes, of at least 23.
+    pub const BLE_MTU: &str = "mtu";
+
+    /// The security level required on the channels: `low` (the default), `medium`
+    /// (encryption) or `high` (encryption with an authenticated key).
+    pub const BLE_SECURITY: &str = "security";
+    pub const BLE_SECURITY_LOW: &str = "low";
+    pub const BLE_SECURITY_MEDIUM: &str = "medium";
+    pub const BLE_SECURITY_HIGH: &str = "high";
+}
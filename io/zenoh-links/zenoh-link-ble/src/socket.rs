@@ -0,0 +1,394 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! A non-blocking L2CAP socket of the Linux Bluetooth stack, driven by tokio.
+use libc::{c_int, c_void, socklen_t};
+use std::fmt;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use tokio::io::unix::AsyncFd;
+
+// The Bluetooth socket constants of Linux (include/net/bluetooth/bluetooth.h)
+const BTPROTO_L2CAP: c_int = 0;
+const SOL_BLUETOOTH: c_int = 274;
+const BT_SECURITY: c_int = 4;
+const BT_SNDMTU: c_int = 12;
+const BT_RCVMTU: c_int = 13;
+
+pub(crate) const BDADDR_LE_PUBLIC: u8 = 0x01;
+pub(crate) const BDADDR_LE_RANDOM: u8 = 0x02;
+
+pub(crate) const BT_SECURITY_LOW: u8 = 1;
+pub(crate) const BT_SECURITY_MEDIUM: u8 = 2;
+pub(crate) const BT_SECURITY_HIGH: u8 = 3;
+
+// The minimum MTU of the LE credit-based channels (Core Specification, Vol 3, Part A, 4.22)
+pub(crate) const L2CAP_LE_MIN_MTU: u16 = 23;
+
+const LISTEN_BACKLOG: c_int = 16;
+
+// struct sockaddr_l2 (include/net/bluetooth/l2cap.h)
+#[repr(C)]
+#[derive(Default)]
+struct SockaddrL2 {
+    l2_family: libc::sa_family_t,
+    l2_psm: u16,
+    // The address in little-endian order
+    l2_bdaddr: [u8; 6],
+    l2_cid: u16,
+    l2_bdaddr_type: u8,
+}
+
+// struct bt_security (include/net/bluetooth/bluetooth.h)
+#[repr(C)]
+struct BtSecurity {
+    level: u8,
+    key_size: u8,
+}
+
+/// The address of an L2CAP channel over LE.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BleAddr {
+    /// The Bluetooth device address, in the display order.
+    pub bdaddr: [u8; 6],
+    pub addr_type: u8,
+    pub psm: u16,
+}
+
+impl BleAddr {
+    fn to_raw(self) -> SockaddrL2 {
+        let mut l2_bdaddr = self.bdaddr;
+        l2_bdaddr.reverse();
+        SockaddrL2 {
+            l2_family: libc::AF_BLUETOOTH as libc::sa_family_t,
+            l2_psm: self.psm.to_le(),
+            l2_bdaddr,
+            l2_cid: 0,
+            l2_bdaddr_type: self.addr_type,
+        }
+    }
+
+    fn from_raw(raw: &SockaddrL2) -> BleAddr {
+        let mut bdaddr = raw.l2_bdaddr;
+        bdaddr.reverse();
+        BleAddr {
+            bdaddr,
+            addr_type: raw.l2_bdaddr_type,
+            psm: u16::from_le(raw.l2_psm),
+        }
+    }
+}
+
+impl fmt::Display for BleAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.bdaddr;
+        write!(
+            f,
+            "{a:02X}:{b:02X}:{c:02X}:{d:02X}:{e:02X}:{g:02X}:{}",
+            self.psm
+        )
+    }
+}
+
+impl fmt::Debug for BleAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+fn cvt(res: c_int) -> io::Result<c_int> {
+    if res < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(res)
+    }
+}
+
+fn cvt_size(res: isize) -> io::Result<usize> {
+    if res < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(res as usize)
+    }
+}
+
+/// An L2CAP socket in sequential packet mode: each read or write is one SDU.
+pub(crate) struct L2capSocket {
+    fd: AsyncFd<OwnedFd>,
+}
+
+impl L2capSocket {
+    pub(crate) fn new() -> io::Result<L2capSocket> {
+        let fd = cvt(unsafe {
+            libc::socket(
+                libc::AF_BLUETOOTH,
+                libc::SOCK_SEQPACKET | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                BTPROTO_L2CAP,
+            )
+        })?;
+        Self::from_raw_fd(fd)
+    }
+
+    /// Returns a pair of connected local sockets with the same sequential packet semantics.
+    #[cfg(test)]
+    pub(crate) fn pair() -> io::Result<(L2capSocket, L2capSocket)> {
+        let mut fds = [0; 2];
+        cvt(unsafe {
+            libc::socketpair(
+                libc::AF_UNIX,
+                libc::SOCK_SEQPACKET | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                0,
+                fds.as_mut_ptr(),
+            )
+        })?;
+        Ok((Self::from_raw_fd(fds[0])?, Self::from_raw_fd(fds[1])?))
+    }
+
+    fn from_raw_fd(fd: RawFd) -> io::Result<L2capSocket> {
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        Ok(L2capSocket {
+            fd: AsyncFd::new(fd)?,
+        })
+    }
+
+    fn raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+
+    fn set_option<T>(&self, level: c_int, name: c_int, value: &T) -> io::Result<()> {
+        cvt(unsafe {
+            libc::setsockopt(
+                self.raw_fd(),
+                level,
+                name,
+                value as *const T as *const c_void,
+                mem::size_of::<T>() as socklen_t,
+            )
+        })?;
+        Ok(())
+    }
+
+    fn get_option<T: Default>(&self, level: c_int, name: c_int) -> io::Result<T> {
+        let mut value = T::default();
+        let mut len = mem::size_of::<T>() as socklen_t;
+        cvt(unsafe {
+            libc::getsockopt(
+                self.raw_fd(),
+                level,
+                name,
+                &mut value as *mut T as *mut c_void,
+                &mut len,
+            )
+        })?;
+        Ok(value)
+    }
+
+    /// Sets the MTU advertised to the peer, before the channel is established.
+    pub(crate) fn set_recv_mtu(&self, mtu: u16) -> io::Result<()> {
+        self.set_option(SOL_BLUETOOTH, BT_RCVMTU, &mtu)
+    }
+
+    pub(crate) fn recv_mtu(&self) -> io::Result<u16> {
+        self.get_option(SOL_BLUETOOTH, BT_RCVMTU)
+    }
+
+    /// Returns the MTU advertised by the peer, once the channel is established.
+    pub(crate) fn send_mtu(&self) -> io::Result<u16> {
+        self.get_option(SOL_BLUETOOTH, BT_SNDMTU)
+    }
+
+    pub(crate) fn set_security(&self, level: u8) -> io::Result<()> {
+        let security = BtSecurity { level, key_size: 0 };
+        self.set_option(SOL_BLUETOOTH, BT_SECURITY, &security)
+    }
+
+    pub(crate) fn bind(&self, addr: BleAddr) -> io::Result<()> {
+        let raw = addr.to_raw();
+        cvt(unsafe {
+            libc::bind(
+                self.raw_fd(),
+                &raw as *const SockaddrL2 as *const libc::sockaddr,
+                mem::size_of::<SockaddrL2>() as socklen_t,
+            )
+        })?;
+        Ok(())
+    }
+
+    pub(crate) fn listen(&self) -> io::Result<()> {
+        cvt(unsafe { libc::listen(self.raw_fd(), LISTEN_BACKLOG) })?;
+        Ok(())
+    }
+
+    pub(crate) async fn connect(&self, addr: BleAddr) -> io::Result<()> {
+        let raw = addr.to_raw();
+        let res = cvt(unsafe {
+            libc::connect(
+                self.raw_fd(),
+                &raw as *const SockaddrL2 as *const libc::sockaddr,
+                mem::size_of::<SockaddrL2>() as socklen_t,
+            )
+        });
+        match res {
+            Ok(_) => return Ok(()),
+            Err(e) if e.raw_os_error() == Some(libc::EINPROGRESS) => {}
+            Err(e) => return Err(e),
+        }
+        // The socket becomes writable once the channel is established or failed
+        let _guard = self.fd.writable().await?;
+        let error: c_int = self.get_option(libc::SOL_SOCKET, libc::SO_ERROR)?;
+        if error != 0 {
+            return Err(io::Error::from_raw_os_error(error));
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn accept(&self) -> io::Result<(L2capSocket, BleAddr)> {
+        loop {
+            let mut guard = self.fd.readable().await?;
+            let res = guard.try_io(|fd| {
+                let mut raw = SockaddrL2::default();
+                let mut len = mem::size_of::<SockaddrL2>() as socklen_t;
+                let fd = cvt(unsafe {
+                    libc::accept4(
+                        fd.as_raw_fd(),
+                        &mut raw as *mut SockaddrL2 as *mut libc::sockaddr,
+                        &mut len,
+                        libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                    )
+                })?;
+                Ok((fd, raw))
+            });
+            if let Ok(res) = res {
+                let (fd, raw) = res?;
+                return Ok((Self::from_raw_fd(fd)?, BleAddr::from_raw(&raw)));
+            }
+        }
+    }
+
+    pub(crate) fn local_addr(&self) -> io::Result<BleAddr> {
+        let mut raw = SockaddrL2::default();
+        let mut len = mem::size_of::<SockaddrL2>() as socklen_t;
+        cvt(unsafe {
+            libc::getsockname(
+                self.raw_fd(),
+                &mut raw as *mut SockaddrL2 as *mut libc::sockaddr,
+                &mut len,
+            )
+        })?;
+        Ok(BleAddr::from_raw(&raw))
+    }
+
+    pub(crate) fn peer_addr(&self) -> io::Result<BleAddr> {
+        let mut raw = SockaddrL2::default();
+        let mut len = mem::size_of::<SockaddrL2>() as socklen_t;
+        cvt(unsafe {
+            libc::getpeername(
+                self.raw_fd(),
+                &mut raw as *mut SockaddrL2 as *mut libc::sockaddr,
+                &mut len,
+            )
+        })?;
+        Ok(BleAddr::from_raw(&raw))
+    }
+
+    pub(crate) async fn send(&self, buffer: &[u8]) -> io::Result<usize> {
+        loop {
+            let mut guard = self.fd.writable().await?;
+            let res = guard.try_io(|fd| {
+                cvt_size(unsafe {
+                    libc::send(
+                        fd.as_raw_fd(),
+                        buffer.as_ptr() as *const c_void,
+                        buffer.len(),
+                        libc::MSG_NOSIGNAL,
+                    )
+                })
+            });
+            if let Ok(res) = res {
+                return res;
+            }
+        }
+    }
+
+    pub(crate) async fn recv(&self, buffer: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let mut guard = self.fd.readable().await?;
+            let res = guard.try_io(|fd| {
+                cvt_size(unsafe {
+                    libc::recv(
+                        fd.as_raw_fd(),
+                        buffer.as_mut_ptr() as *mut c_void,
+                        buffer.len(),
+                        0,
+                    )
+                })
+            });
+            if let Ok(res) = res {
+                return res;
+            }
+        }
+    }
+
+    pub(crate) fn shutdown(&self) -> io::Result<()> {
+        cvt(unsafe { libc::shutdown(self.raw_fd(), libc::SHUT_RDWR) })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: BleAddr = BleAddr {
+        bdaddr: [0x00, 0x1A, 0x7D, 0xDA, 0x71, 0x13],
+        addr_type: BDADDR_LE_RANDOM,
+        psm: 0x0080,
+    };
+
+    #[test]
+    fn ble_addr_raw() {
+        let raw = ADDR.to_raw();
+        assert_eq!(raw.l2_family, libc::AF_BLUETOOTH as libc::sa_family_t);
+        // The kernel expects the device address and the PSM in little-endian order
+        assert_eq!(raw.l2_bdaddr, [0x13, 0x71, 0xDA, 0x7D, 0x1A, 0x00]);
+        assert_eq!(u16::from_le(raw.l2_psm), 0x0080);
+        assert_eq!(raw.l2_cid, 0);
+        assert_eq!(raw.l2_bdaddr_type, BDADDR_LE_RANDOM);
+        assert_eq!(BleAddr::from_raw(&raw), ADDR);
+    }
+
+    #[test]
+    fn ble_addr_display() {
+        assert_eq!(ADDR.to_string(), "00:1A:7D:DA:71:13:128");
+        assert_eq!(format!("{ADDR:?}"), "00:1A:7D:DA:71:13:128");
+    }
+
+    #[tokio::test]
+    async fn socket_sdu_boundaries() {
+        let (a, b) = L2capSocket::pair().unwrap();
+        assert_eq!(a.send(b"zenoh").await.unwrap(), 5);
+        assert_eq!(a.send(&[0xAA; 1024]).await.unwrap(), 1024);
+
+        // Each read returns exactly one SDU, however large the buffer
+        let mut buffer = [0; 4096];
+        assert_eq!(b.recv(&mut buffer).await.unwrap(), 5);
+        assert_eq!(&buffer[..5], b"zenoh");
+        assert_eq!(b.recv(&mut buffer).await.unwrap(), 1024);
+        assert!(buffer[..1024].iter().all(|&x| x == 0xAA));
+
+        a.shutdown().unwrap();
+        assert_eq!(b.recv(&mut buffer).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,601 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock as AsyncRwLock;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use zenoh_core::{zasyncread, zasyncwrite};
+use zenoh_link_commons::{
+    LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
+};
+use zenoh_protocol::core::endpoint::{Address, Config};
+use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
+
+use super::config::*;
+pub use super::socket::BleAddr;
+use super::socket::{
+    L2capSocket, BDADDR_LE_PUBLIC, BDADDR_LE_RANDOM, BT_SECURITY_HIGH, BT_SECURITY_LOW,
+    BT_SECURITY_MEDIUM, L2CAP_LE_MIN_MTU,
+};
+use super::{BLE_ACCEPT_THROTTLE_TIME, BLE_DEFAULT_MTU, BLE_LOCATOR_PREFIX};
+
+/// Parses a `<bdaddr>:<psm>` address, where the PSM is given in decimal or in hexadecimal
+/// with a `0x` prefix.
+pub fn get_ble_addr(address: Address<'_>, addr_type: u8) -> ZResult<BleAddr> {
+    let Some((bdaddr, psm)) = address.as_str().rsplit_once(':') else {
+        bail!("Incorrect ble address: {:?}", address);
+    };
+
+    let bytes = bdaddr
+        .split(':')
+        .map(|b| u8::from_str_radix(b, 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| zerror!("Incorrect ble device address: {:?}", bdaddr))?;
+    let bdaddr: [u8; 6] = bytes
+        .try_into()
+        .map_err(|_| zerror!("Incorrect ble device address: {:?}", bdaddr))?;
+
+    let psm = match psm.strip_prefix("0x") {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => psm.parse::<u16>(),
+    }
+    .map_err(|_| zerror!("Incorrect ble psm: {:?}", psm))?;
+
+    Ok(BleAddr {
+        bdaddr,
+        addr_type,
+        psm,
+    })
+}
+
+struct BleConfig {
+    addr_type: u8,
+    mtu: u16,
+    security: Option<u8>,
+}
+
+impl BleConfig {
+    fn new(config: Config<'_>) -> ZResult<BleConfig> {
+        let addr_type = match config.get(BLE_ADDR_TYPE) {
+            None | Some(BLE_ADDR_TYPE_PUBLIC) => BDADDR_LE_PUBLIC,
+            Some(BLE_ADDR_TYPE_RANDOM) => BDADDR_LE_RANDOM,
+            Some(s) => bail!("Unknown ble address type: {}", s),
+        };
+        let mtu = match config.get(BLE_MTU) {
+            Some(s) => s
+                .parse::<u16>()
+                .map_err(|_| zerror!("Unknown ble mtu: {}", s))?,
+            None => *BLE_DEFAULT_MTU,
+        };
+        if mtu < L2CAP_LE_MIN_MTU {
+            bail!(
+                "Invalid ble mtu: {} is below the minimum of {}",
+                mtu,
+                L2CAP_LE_MIN_MTU
+            );
+        }
+        let security = match config.get(BLE_SECURITY) {
+            None => None,
+            Some(BLE_SECURITY_LOW) => Some(BT_SECURITY_LOW),
+            Some(BLE_SECURITY_MEDIUM) => Some(BT_SECURITY_MEDIUM),
+            Some(BLE_SECURITY_HIGH) => Some(BT_SECURITY_HIGH),
+            Some(s) => bail!("Unknown ble security level: {}", s),
+        };
+        Ok(BleConfig {
+            addr_type,
+            mtu,
+            security,
+        })
+    }
+
+    fn apply(&self, socket: &L2capSocket) -> ZResult<()> {
+        socket
+            .set_recv_mtu(self.mtu)
+            .map_err(|e| zerror!("Can not set the ble mtu to {}: {}", self.mtu, e))?;
+        if let Some(level) = self.security {
+            socket
+                .set_security(level)
+                .map_err(|e| zerror!("Can not set the ble security level: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+pub struct LinkUnicastBle {
+    // The underlying L2CAP socket
+    socket: L2capSocket,
+    // The source address of this link (address used on the local adapter)
+    src_addr: BleAddr,
+    src_locator: Locator,
+    // The destination address of this link (address used on the remote device)
+    dst_addr: BleAddr,
+    dst_locator: Locator,
+    // The MTU negotiated for the channel
+    mtu: u16,
+}
+
+impl LinkUnicastBle {
+    fn new(socket: L2capSocket, src_addr: BleAddr, dst_addr: BleAddr) -> ZResult<LinkUnicastBle> {
+        // The SDUs are bounded by the MTU advertised by each side of the channel
+        let send_mtu = socket
+            .send_mtu()
+            .map_err(|e| zerror!("Can not get the ble send mtu: {}", e))?;
+        let recv_mtu = socket
+            .recv_mtu()
+            .map_err(|e| zerror!("Can not get the ble receive mtu: {}", e))?;
+        Self::with_mtus(socket, src_addr, dst_addr, send_mtu, recv_mtu)
+    }
+
+    fn with_mtus(
+        socket: L2capSocket,
+        src_addr: BleAddr,
+        dst_addr: BleAddr,
+        send_mtu: u16,
+        recv_mtu: u16,
+    ) -> ZResult<LinkUnicastBle> {
+        Ok(LinkUnicastBle {
+            socket,
+            src_addr,
+            src_locator: Locator::new(BLE_LOCATOR_PREFIX, src_addr.to_string(), "")?,
+            dst_addr,
+            dst_locator: Locator::new(BLE_LOCATOR_PREFIX, dst_addr.to_string(), "")?,
+            mtu: send_mtu.min(recv_mtu),
+        })
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastBle {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing ble link: {}", self);
+        self.socket.shutdown().map_err(|e| {
+            let e = zerror!("ble link shutdown {}: {:?}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        self.socket.send(buffer).await.map_err(|e| {
+            let e = zerror!("Write error on ble link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        let n = self.write(buffer).await?;
+        if n != buffer.len() {
+            let e = zerror!(
+                "Write error on ble link {}: sent {} out of {} bytes",
+                self,
+                n,
+                buffer.len()
+            );
+            tracing::trace!("{}", e);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let n = self.socket.recv(buffer).await.map_err(|e| {
+            let e = zerror!("Read error on ble link {}: {}", self, e);
+            tracing::trace!("{}", e);
+            e
+        })?;
+        if n == 0 {
+            let e = zerror!("Read error on ble link {}: channel closed", self);
+            tracing::trace!("{}", e);
+            return Err(e.into());
+        }
+        Ok(n)
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let n = self.read(buffer).await?;
+        if n != buffer.len() {
+            let e = zerror!(
+                "Read error on ble link {}: received {} out of {} bytes",
+                self,
+                n,
+                buffer.len()
+            );
+            tracing::trace!("{}", e);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        self.mtu
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        vec!["ble".to_string()]
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for LinkUnicastBle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.src_addr, self.dst_addr)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastBle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ble")
+            .field("src", &self.src_addr)
+            .field("dst", &self.dst_addr)
+            .field("mtu", &self.mtu)
+            .finish()
+    }
+}
+
+struct ListenerUnicastBle {
+    endpoint: EndPoint,
+    token: CancellationToken,
+    handle: JoinHandle<ZResult<()>>,
+}
+
+impl ListenerUnicastBle {
+    fn new(endpoint: EndPoint, token: CancellationToken, handle: JoinHandle<ZResult<()>>) -> Self {
+        Self {
+            endpoint,
+            token,
+            handle,
+        }
+    }
+
+    async fn stop(&self) {
+        self.token.cancel();
+    }
+}
+
+pub struct LinkManagerUnicastBle {
+    manager: NewLinkChannelSender,
+    listeners: Arc<AsyncRwLock<HashMap<BleAddr, ListenerUnicastBle>>>,
+}
+
+impl LinkManagerUnicastBle {
+    pub fn new(manager: NewLinkChannelSender) -> Self {
+        Self {
+            manager,
+            listeners: Arc::new(AsyncRwLock::new(HashMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastBle {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let config = BleConfig::new(endpoint.config())?;
+        let addr = get_ble_addr(endpoint.address(), config.addr_type)?;
+
+        let socket = L2capSocket::new()
+            .map_err(|e| zerror!("Can not create a new ble link bound to {}: {}", endpoint, e))?;
+        // Bind on any local adapter with a dynamically allocated PSM
+        let any = BleAddr {
+            bdaddr: [0; 6],
+            addr_type: BDADDR_LE_PUBLIC,
+            psm: 0,
+        };
+        socket
+            .bind(any)
+            .map_err(|e| zerror!("Can not create a new ble link bound to {}: {}", endpoint, e))?;
+        config.apply(&socket)?;
+        socket
+            .connect(addr)
+            .await
+            .map_err(|e| zerror!("Can not create a new ble link bound to {}: {}", endpoint, e))?;
+
+        let local_addr = socket.local_addr()?;
+        let peer_addr = socket.peer_addr()?;
+        let link = Arc::new(LinkUnicastBle::new(socket, local_addr, peer_addr)?);
+        Ok(LinkUnicast(link))
+    }
+
+    async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
+        let config = BleConfig::new(endpoint.config())?;
+        let addr = get_ble_addr(endpoint.address(), BDADDR_LE_PUBLIC)?;
+
+        let socket = L2capSocket::new().map_err(|e| {
+            zerror!(
+                "Can not create a new ble listener bound to {}: {}",
+                endpoint,
+                e
+            )
+        })?;
+        socket.bind(addr).map_err(|e| {
+            zerror!(
+                "Can not create a new ble listener bound to {}: {}",
+                endpoint,
+                e
+            )
+        })?;
+        // The accepted channels inherit the options of the listening socket
+        config.apply(&socket)?;
+        socket.listen().map_err(|e| {
+            zerror!(
+                "Can not create a new ble listener bound to {}: {}",
+                endpoint,
+                e
+            )
+        })?;
+
+        let local_addr = socket.local_addr()?;
+        // Update the endpoint locator address
+        endpoint = EndPoint::new(
+            endpoint.protocol(),
+            format!("{local_addr}"),
+            endpoint.metadata(),
+            endpoint.config(),
+        )?;
+        let token = CancellationToken::new();
+        let c_token = token.clone();
+
+        let c_manager = self.manager.clone();
+
+        let locator = endpoint.to_locator();
+
+        let mut listeners = zasyncwrite!(self.listeners);
+        let c_listeners = self.listeners.clone();
+        let c_addr = addr;
+        let task = async move {
+            // Wait for the accept loop to terminate
+            let res = accept_task(socket, c_token, c_manager).await;
+            zasyncwrite!(c_listeners).remove(&c_addr);
+            res
+        };
+        let handle = zenoh_runtime::ZRuntime::Acceptor.spawn(task);
+
+        let listener = ListenerUnicastBle::new(endpoint, token, handle);
+        // Update the list of active listeners on the manager
+        listeners.insert(addr, listener);
+        Ok(locator)
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let addr = get_ble_addr(endpoint.address(), BDADDR_LE_PUBLIC)?;
+
+        let listener = zasyncwrite!(self.listeners).remove(&addr).ok_or_else(|| {
+            zerror!(
+                "Can not delete the listener because it has not been found: {}",
+                addr
+            )
+        })?;
+
+        // Send the stop signal
+        listener.stop().await;
+        listener.handle.await?
+    }
+
+    async fn get_listeners(&self) -> Vec<EndPoint> {
+        zasyncread!(self.listeners)
+            .values()
+            .map(|x| x.endpoint.clone())
+            .collect()
+    }
+
+    async fn get_locators(&self) -> Vec<Locator> {
+        zasyncread!(self.listeners)
+            .values()
+            .map(|x| x.endpoint.to_locator())
+            .collect()
+    }
+}
+
+async fn accept_task(
+    socket: L2capSocket,
+    token: CancellationToken,
+    manager: NewLinkChannelSender,
+) -> ZResult<()> {
+    async fn accept(socket: &L2capSocket) -> ZResult<(LinkUnicastBle, BleAddr)> {
+        let (stream, dst_addr) = socket.accept().await.map_err(|e| zerror!(e))?;
+        let src_addr = stream.local_addr().map_err(|e| zerror!(e))?;
+        let link = LinkUnicastBle::new(stream, src_addr, dst_addr)?;
+        Ok((link, dst_addr))
+    }
+
+    let src_addr = socket.local_addr().map_err(|e| {
+        let e = zerror!("Can not accept ble connections: {}", e);
+        tracing::warn!("{}", e);
+        e
+    })?;
+
+    tracing::trace!("Ready to accept ble connections on: {:?}", src_addr);
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            res = accept(&socket) => {
+                match res {
+                    Ok((link, dst_addr)) => {
+                        tracing::debug!("Accepted ble connection on {:?}: {:?}", src_addr, dst_addr);
+                        // Communicate the new link to the initial transport manager
+                        if let Err(e) = manager.send_async(LinkUnicast(Arc::new(link))).await {
+                            tracing::error!("{}-{}: {}", file!(), line!(), e)
+                        }
+                    },
+                    Err(e) => {
+                        tracing::warn!("{}. Hint: increase the system open file limit.", e);
+                        // Throttle the accept loop upon an error
+                        // NOTE: This might be due to various factors. However, the most common case is that
+                        //       the process has reached the maximum number of open files in the system. On
+                        //       Linux systems this limit can be changed by using the "ulimit" command line
+                        //       tool. In case of systemd-based systems, this can be changed by using the
+                        //       "sysctl" command line tool.
+                        tokio::time::sleep(Duration::from_micros(*BLE_ACCEPT_THROTTLE_TIME)).await;
+                    }
+
+                }
+            }
+        };
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn addr(address: &str, addr_type: u8) -> ZResult<BleAddr> {
+        let endpoint = EndPoint::from_str(&format!("{BLE_LOCATOR_PREFIX}/{address}"))?;
+        get_ble_addr(endpoint.address(), addr_type)
+    }
+
+    fn config(endpoint: &str) -> ZResult<BleConfig> {
+        BleConfig::new(EndPoint::from_str(endpoint)?.config())
+    }
+
+    fn pair(send_mtu: u16, recv_mtu: u16) -> (LinkUnicastBle, LinkUnicastBle) {
+        let src_addr = addr("00:1A:7D:DA:71:13:128", BDADDR_LE_PUBLIC);
+        let dst_addr = addr("00:1A:7D:DA:71:14:0x81", BDADDR_LE_PUBLIC);
+        let (src_addr, dst_addr) = (src_addr.unwrap(), dst_addr.unwrap());
+        let (a, b) = L2capSocket::pair().unwrap();
+        (
+            LinkUnicastBle::with_mtus(a, src_addr, dst_addr, send_mtu, recv_mtu).unwrap(),
+            LinkUnicastBle::with_mtus(b, dst_addr, src_addr, recv_mtu, send_mtu).unwrap(),
+        )
+    }
+
+    #[test]
+    fn ble_addr_parse() {
+        let expected = addr("00:1A:7D:DA:71:13:128", BDADDR_LE_RANDOM).unwrap();
+        assert_eq!(expected.bdaddr, [0x00, 0x1A, 0x7D, 0xDA, 0x71, 0x13]);
+        assert_eq!(expected.addr_type, BDADDR_LE_RANDOM);
+        assert_eq!(expected.psm, 128);
+        // The PSM may be given in hexadecimal, the locators display it in decimal
+        let hex = addr("00:1a:7d:da:71:13:0x80", BDADDR_LE_RANDOM);
+        assert_eq!(hex.unwrap(), expected);
+        let display = addr(&expected.to_string(), BDADDR_LE_RANDOM);
+        assert_eq!(display.unwrap(), expected);
+
+        for invalid in [
+            "",
+            "128",
+            "00:1A:7D:DA:71:128",
+            "00:1A:7D:DA:71:13:0D:128",
+            "00:1A:7D:DA:71:G3:128",
+            "00:1A:7D:DA:71:13:",
+            "00:1A:7D:DA:71:13:65536",
+            "00:1A:7D:DA:71:13:0x",
+        ] {
+            assert!(addr(invalid, BDADDR_LE_PUBLIC).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn ble_config() {
+        let default = config("ble/00:1A:7D:DA:71:13:128").unwrap();
+        assert_eq!(default.addr_type, BDADDR_LE_PUBLIC);
+        assert_eq!(default.mtu, *BLE_DEFAULT_MTU);
+        assert_eq!(default.security, None);
+
+        let custom =
+            config("ble/00:1A:7D:DA:71:13:128#addr_type=random;mtu=512;security=high").unwrap();
+        assert_eq!(custom.addr_type, BDADDR_LE_RANDOM);
+        assert_eq!(custom.mtu, 512);
+        assert_eq!(custom.security, Some(BT_SECURITY_HIGH));
+
+        // The MTU of the LE channels is at least 23 bytes
+        assert_eq!(
+            config("ble/00:1A:7D:DA:71:13:128#mtu=23").unwrap().mtu,
+            L2CAP_LE_MIN_MTU
+        );
+        for invalid in [
+            "addr_type=static",
+            "mtu=22",
+            "mtu=0",
+            "mtu=65536",
+            "mtu=-1",
+            "security=none",
+        ] {
+            let endpoint = format!("ble/00:1A:7D:DA:71:13:128#{invalid}");
+            assert!(config(&endpoint).is_err(), "{invalid}");
+        }
+    }
+
+    #[tokio::test]
+    async fn link_mtu() {
+        // The SDUs are bounded by the smallest of the MTUs advertised by both ends
+        let (a, b) = pair(512, 4096);
+        assert_eq!(a.get_mtu(), 512);
+        assert_eq!(b.get_mtu(), 512);
+        let (a, _b) = pair(u16::MAX, L2CAP_LE_MIN_MTU);
+        assert_eq!(a.get_mtu(), L2CAP_LE_MIN_MTU);
+
+        assert!(!a.is_streamed());
+        assert!(a.is_reliable());
+        assert_eq!(a.get_src().to_string(), "ble/00:1A:7D:DA:71:13:128");
+        assert_eq!(a.get_dst().to_string(), "ble/00:1A:7D:DA:71:14:129");
+    }
+
+    #[tokio::test]
+    async fn link_framing() {
+        let (a, b) = pair(4096, 4096);
+        let batches: Vec<Vec<u8>> = (1..=8_u8).map(|i| vec![i; i as usize * 100]).collect();
+        for batch in batches.iter() {
+            a.write_all(batch).await.unwrap();
+        }
+
+        // Each read returns one batch, without the length prefix of the streamed links
+        let mut buffer = vec![0; b.get_mtu() as usize];
+        for batch in batches.iter() {
+            let n = b.read(&mut buffer).await.unwrap();
+            assert_eq!(&buffer[..n], batch.as_slice());
+        }
+
+        // An exact read of a batch of another size fails instead of mixing two batches
+        a.write_all(b"zenoh").await.unwrap();
+        a.write_all(b"zenoh").await.unwrap();
+        b.read_exact(&mut buffer[..5]).await.unwrap();
+        assert_eq!(&buffer[..5], b"zenoh");
+        assert!(b.read_exact(&mut buffer[..10]).await.is_err());
+
+        // The closing of the channel is an error rather than an empty batch
+        a.close().await.unwrap();
+        assert!(b.read(&mut buffer).await.is_err());
+    }
+}
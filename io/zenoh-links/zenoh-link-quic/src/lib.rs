@@ -28,7 +28,9 @@ mod unicast;
 mod utils;
 mod verify;
 pub use unicast::*;
-pub use utils::TlsConfigurator as QuicConfigurator;
+pub use utils::{
+    get_quic_addr, TlsClientConfig, TlsConfigurator as QuicConfigurator, TlsServerConfig,
+};
 
 // Default ALPN protocol
 pub const ALPN_QUIC_HTTP: &[&[u8]] = &[b"hq-29"];
@@ -67,6 +69,8 @@ zconfigurable! {
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref QUIC_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Maximum number of TLS sessions cached for resumption by a QUIC link manager.
+    static ref QUIC_SESSION_CACHE_SIZE: usize = 256;
 }
 
 pub mod config {
@@ -77,6 +81,8 @@ pub mod config {
     pub const TLS_SERVER_PRIVATE_KEY_FILE: &str = "server_private_key_file";
     pub const TLS_SERVER_PRIVATE_KEY_RAW: &str = "server_private_key_raw";
     pub const TLS_SERVER_PRIVATE_KEY_BASE64: &str = "server_private_key_base64";
+    /// URI of a private key loaded by a registered [`zenoh_link_commons::tls::KeyProvider`].
+    pub const TLS_SERVER_PRIVATE_KEY_URI: &str = "server_private_key_uri";
 
     pub const TLS_SERVER_CERTIFICATE_FILE: &str = "server_certificate_file";
     pub const TLS_SERVER_CERTIFICATE_RAW: &str = "server_certificate_raw";
@@ -85,6 +91,7 @@ pub mod config {
     pub const TLS_CLIENT_PRIVATE_KEY_FILE: &str = "client_private_key_file";
     pub const TLS_CLIENT_PRIVATE_KEY_RAW: &str = "client_private_key_raw";
     pub const TLS_CLIENT_PRIVATE_KEY_BASE64: &str = "client_private_key_base64";
+    pub const TLS_CLIENT_PRIVATE_KEY_URI: &str = "client_private_key_uri";
 
     pub const TLS_CLIENT_CERTIFICATE_FILE: &str = "client_certificate_file";
     pub const TLS_CLIENT_CERTIFICATE_RAW: &str = "client_certificate_raw";
@@ -94,4 +101,24 @@ pub mod config {
 
     pub const TLS_SERVER_NAME_VERIFICATION: &str = "server_name_verification";
     pub const TLS_SERVER_NAME_VERIFICATION_DEFAULT: &str = "true";
+
+    pub const TLS_CRL_FILE: &str = "crl_file";
+    pub const TLS_CRL_RAW: &str = "crl_raw";
+    pub const TLS_CRL_BASE64: &str = "crl_base64";
+
+    /// Interval in milliseconds at which listeners check their certificate files for changes.
+    /// A value of 0 disables the reloading of the files.
+    pub const TLS_RELOAD_INTERVAL: &str = "reload_interval";
+    pub const TLS_RELOAD_INTERVAL_DEFAULT: &str = "10000";
+
+    /// Resume TLS sessions with session tickets instead of performing a full handshake.
+    pub const QUIC_RESUMPTION: &str = "resumption";
+    pub const QUIC_RESUMPTION_DEFAULT: &str = "true";
+
+    /// Send the first bytes of a resumed connection as 0-RTT data.
+    ///
+    /// 0-RTT data may be replayed by an attacker: disable it where that is a concern.
+    /// Only the opening message of the zenoh transport handshake is sent this way.
+    pub const QUIC_ZERO_RTT: &str = "zero_rtt";
+    pub const QUIC_ZERO_RTT_DEFAULT: &str = "true";
 }
@@ -14,25 +14,35 @@
 
 use crate::{
     config::*,
-    utils::{get_quic_addr, TlsClientConfig, TlsServerConfig},
+    utils::{get_quic_addr, get_tls_server_files_mtime, TlsClientConfig, TlsServerConfig},
     ALPN_QUIC_HTTP, QUIC_ACCEPT_THROTTLE_TIME, QUIC_DEFAULT_MTU, QUIC_LOCATOR_PREFIX,
+    QUIC_SESSION_CACHE_SIZE,
 };
 use async_trait::async_trait;
+use rustls::client::{ClientSessionMemoryCache, ClientSessionStore, Resumption};
+use rustls::server::NoServerSessionStorage;
 use std::fmt;
 use std::net::IpAddr;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex as AsyncMutex;
 use tokio_util::sync::CancellationToken;
 use zenoh_core::zasynclock;
 use zenoh_link_commons::{
-    get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
+    get_ip_interface_names, tls, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
     ListenersUnicastIP, NewLinkChannelSender,
 };
-use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_protocol::core::{Config, EndPoint, Locator};
 use zenoh_result::{bail, zerror, ZResult};
 
+// The data written on a stream opened in 0-RTT, until the server accepts or rejects it
+struct EarlyData {
+    accepted: quinn::ZeroRttAccepted,
+    written: Vec<u8>,
+}
+
 pub struct LinkUnicastQuic {
     connection: quinn::Connection,
     src_addr: SocketAddr,
@@ -40,6 +50,9 @@ pub struct LinkUnicastQuic {
     dst_locator: Locator,
     send: AsyncMutex<quinn::SendStream>,
     recv: AsyncMutex<quinn::RecvStream>,
+    early: AsyncMutex<Option<EarlyData>>,
+    // Whether the 0-RTT data, if any, has been accepted or rejected
+    settled: AtomicBool,
 }
 
 impl LinkUnicastQuic {
@@ -49,6 +62,7 @@ impl LinkUnicastQuic {
         dst_locator: Locator,
         send: quinn::SendStream,
         recv: quinn::RecvStream,
+        accepted: Option<quinn::ZeroRttAccepted>,
     ) -> LinkUnicastQuic {
         // Build the Quic object
         LinkUnicastQuic {
@@ -58,8 +72,98 @@ impl LinkUnicastQuic {
             dst_locator,
             send: AsyncMutex::new(send),
             recv: AsyncMutex::new(recv),
+            settled: AtomicBool::new(accepted.is_none()),
+            early: AsyncMutex::new(accepted.map(|accepted| EarlyData {
+                accepted,
+                written: vec![],
+            })),
+        }
+    }
+
+    // Waits for the server to accept or reject the 0-RTT data, if any. The streams opened in
+    // 0-RTT are discarded upon rejection, a new stream is then opened and the data written so
+    // far is written again on it.
+    async fn settle(&self, early: &mut Option<EarlyData>) -> ZResult<()> {
+        let Some(EarlyData { accepted, written }) = early.take() else {
+            return Ok(());
+        };
+        let res = if accepted.await {
+            Ok(())
+        } else {
+            self.reopen(&written).await
+        };
+        self.settled.store(true, Ordering::Release);
+        res
+    }
+
+    async fn reopen(&self, written: &[u8]) -> ZResult<()> {
+        tracing::debug!(
+            "0-RTT data rejected on QUIC link {}, reopening its stream",
+            self
+        );
+        let (mut send, recv) = self
+            .connection
+            .open_bi()
+            .await
+            .map_err(|e| zerror!("Can not reopen the stream of QUIC link {}: {}", self, e))?;
+        send.write_all(written)
+            .await
+            .map_err(|e| zerror!("Write error on QUIC link {}: {}", self, e))?;
+        *zasynclock!(self.send) = send;
+        *zasynclock!(self.recv) = recv;
+        Ok(())
+    }
+
+    async fn write_stream(&self, buffer: &[u8], all: bool) -> Result<usize, quinn::WriteError> {
+        let mut guard = zasynclock!(self.send);
+        if all {
+            guard.write_all(buffer).await.map(|_| buffer.len())
+        } else {
+            guard.write(buffer).await
         }
     }
+
+    // Writes on the stream, keeping a copy of the data until the 0-RTT data is accepted
+    async fn write_early(&self, buffer: &[u8], all: bool) -> ZResult<usize> {
+        if self.settled.load(Ordering::Acquire) {
+            return self.write_stream(buffer, all).await.map_err(|e| {
+                tracing::trace!("Write error on QUIC link {}: {}", self, e);
+                zerror!(e).into()
+            });
+        }
+        let mut early = zasynclock!(self.early);
+        let res = self.write_stream(buffer, all).await;
+        match res {
+            Ok(n) => {
+                if let Some(early) = early.as_mut() {
+                    early.written.extend_from_slice(&buffer[..n]);
+                }
+                Ok(n)
+            }
+            Err(quinn::WriteError::ZeroRttRejected) if early.is_some() => {
+                // The whole buffer is written on the reopened stream
+                if let Some(early) = early.as_mut() {
+                    early.written.extend_from_slice(buffer);
+                }
+                self.settle(&mut early).await?;
+                Ok(buffer.len())
+            }
+            Err(e) => {
+                tracing::trace!("Write error on QUIC link {}: {}", self, e);
+                Err(zerror!(e).into())
+            }
+        }
+    }
+
+    fn peer_certificate(&self) -> Option<rustls::Certificate> {
+        // The identity is only known once the handshake is complete, e.g. not yet on 0-RTT links
+        self.connection
+            .peer_identity()?
+            .downcast::<Vec<rustls::Certificate>>()
+            .ok()?
+            .first()
+            .cloned()
+    }
 }
 
 #[async_trait]
@@ -76,22 +180,18 @@ impl LinkUnicastTrait for LinkUnicastQuic {
     }
 
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
-        let mut guard = zasynclock!(self.send);
-        guard.write(buffer).await.map_err(|e| {
-            tracing::trace!("Write error on QUIC link {}: {}", self, e);
-            zerror!(e).into()
-        })
+        self.write_early(buffer, false).await
     }
 
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
-        let mut guard = zasynclock!(self.send);
-        guard.write_all(buffer).await.map_err(|e| {
-            tracing::trace!("Write error on QUIC link {}: {}", self, e);
-            zerror!(e).into()
-        })
+        self.write_early(buffer, true).await.map(|_| ())
     }
 
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        // The answer to the 0-RTT data is read on the reopened stream if it is rejected
+        if !self.settled.load(Ordering::Acquire) {
+            self.settle(&mut *zasynclock!(self.early)).await?;
+        }
         let mut guard = zasynclock!(self.recv);
         guard
             .read(buffer)
@@ -113,6 +213,9 @@ impl LinkUnicastTrait for LinkUnicastQuic {
     }
 
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        if !self.settled.load(Ordering::Acquire) {
+            self.settle(&mut *zasynclock!(self.early)).await?;
+        }
         let mut guard = zasynclock!(self.recv);
         guard.read_exact(buffer).await.map_err(|e| {
             let e = zerror!("Read error on QUIC link {}: {}", self, e);
@@ -141,6 +244,17 @@ impl LinkUnicastTrait for LinkUnicastQuic {
         get_ip_interface_names(&self.src_addr)
     }
 
+    fn get_cert_common_name(&self) -> Option<String> {
+        self.peer_certificate()
+            .and_then(|cert| tls::get_cert_common_name(&cert.0))
+    }
+
+    fn get_cert_subject_alt_names(&self) -> Vec<String> {
+        self.peer_certificate()
+            .map(|cert| tls::get_cert_subject_alt_names(&cert.0))
+            .unwrap_or_default()
+    }
+
     #[inline(always)]
     fn is_reliable(&self) -> bool {
         true
@@ -150,6 +264,11 @@ impl LinkUnicastTrait for LinkUnicastQuic {
     fn is_streamed(&self) -> bool {
         true
     }
+
+    #[inline(always)]
+    fn is_secure(&self) -> bool {
+        true
+    }
 }
 
 impl Drop for LinkUnicastQuic {
@@ -182,6 +301,9 @@ impl fmt::Debug for LinkUnicastQuic {
 pub struct LinkManagerUnicastQuic {
     manager: NewLinkChannelSender,
     listeners: ListenersUnicastIP,
+    // Session tickets are shared by all the links of the manager so that
+    // reconnections to a known server can be resumed.
+    sessions: Arc<dyn ClientSessionStore>,
 }
 
 impl LinkManagerUnicastQuic {
@@ -189,10 +311,18 @@ impl LinkManagerUnicastQuic {
         Self {
             manager,
             listeners: ListenersUnicastIP::new(),
+            sessions: Arc::new(ClientSessionMemoryCache::new(*QUIC_SESSION_CACHE_SIZE)),
         }
     }
 }
 
+fn get_bool_config(epconf: &Config<'_>, key: &str, default: &str) -> ZResult<bool> {
+    let value = epconf.get(key).unwrap_or(default);
+    value
+        .parse()
+        .map_err(|_| zerror!("Invalid QUIC {} argument: {}", key, value).into())
+}
+
 #[async_trait]
 impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
@@ -215,6 +345,10 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             tracing::warn!("Skipping name verification of servers");
         }
 
+        let resumption = get_bool_config(&epconf, QUIC_RESUMPTION, QUIC_RESUMPTION_DEFAULT)?;
+        let zero_rtt =
+            resumption && get_bool_config(&epconf, QUIC_ZERO_RTT, QUIC_ZERO_RTT_DEFAULT)?;
+
         // Initialize the QUIC connection
         let mut client_crypto = TlsClientConfig::new(&epconf)
             .await
@@ -222,6 +356,12 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
 
         client_crypto.client_config.alpn_protocols =
             ALPN_QUIC_HTTP.iter().map(|&x| x.into()).collect();
+        client_crypto.client_config.resumption = if resumption {
+            Resumption::store(self.sessions.clone())
+        } else {
+            Resumption::disabled()
+        };
+        client_crypto.client_config.enable_early_data = zero_rtt;
 
         let ip_addr: IpAddr = if addr.is_ipv4() {
             Ipv4Addr::UNSPECIFIED.into()
@@ -238,12 +378,31 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             .local_addr()
             .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?;
 
-        let quic_conn = quic_endpoint
+        let connecting = quic_endpoint
             .connect(addr, host)
-            .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?
-            .await
             .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?;
 
+        // A resumed connection does not wait for the handshake to complete: the stream is
+        // opened right away and its first bytes are sent as 0-RTT data. Otherwise, or when
+        // no session ticket is available for this server, wait for the full handshake.
+        let early = if zero_rtt {
+            connecting.into_0rtt()
+        } else {
+            Err(connecting)
+        };
+        let (quic_conn, accepted) = match early {
+            Ok((quic_conn, accepted)) => {
+                tracing::trace!("Resuming QUIC connection to {} with 0-RTT", addr);
+                (quic_conn, Some(accepted))
+            }
+            Err(connecting) => (
+                connecting.await.map_err(|e| {
+                    zerror!("Can not create a new QUIC link bound to {}: {}", host, e)
+                })?,
+                None,
+            ),
+        };
+
         let (send, recv) = quic_conn
             .open_bi()
             .await
@@ -255,6 +414,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             endpoint.into(),
             send,
             recv,
+            accepted,
         ));
 
         Ok(LinkUnicast(link))
@@ -271,22 +431,15 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
         let addr = get_quic_addr(&epaddr).await?;
 
         // Server config
-        let mut server_crypto = TlsServerConfig::new(&epconf)
+        let server_config = server_config(&epconf)
             .await
             .map_err(|e| zerror!("Cannot create a new QUIC listener on {addr}: {e}"))?;
-        server_crypto.server_config.alpn_protocols =
-            ALPN_QUIC_HTTP.iter().map(|&x| x.into()).collect();
-        let mut server_config =
-            quinn::ServerConfig::with_crypto(Arc::new(server_crypto.server_config));
-
-        // We do not accept unidireactional streams.
-        Arc::get_mut(&mut server_config.transport)
-            .unwrap()
-            .max_concurrent_uni_streams(0_u8.into());
-        // For the time being we only allow one bidirectional stream
-        Arc::get_mut(&mut server_config.transport)
-            .unwrap()
-            .max_concurrent_bidi_streams(1_u8.into());
+
+        let reload_interval: u64 = epconf
+            .get(TLS_RELOAD_INTERVAL)
+            .unwrap_or(TLS_RELOAD_INTERVAL_DEFAULT)
+            .parse()
+            .map_err(|_| zerror!("Unknown reload interval argument on {addr}"))?;
 
         // Initialize the Endpoint
         let quic_endpoint = quinn::Endpoint::server(server_config, addr)
@@ -310,7 +463,18 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
 
         let c_manager = self.manager.clone();
 
-        let task = async move { accept_task(quic_endpoint, c_token, c_manager).await };
+        let c_endpoint = endpoint.clone();
+
+        let task = async move {
+            accept_task(
+                quic_endpoint,
+                c_endpoint,
+                reload_interval,
+                c_token,
+                c_manager,
+            )
+            .await
+        };
 
         // Initialize the QuicAcceptor
         let locator = endpoint.to_locator();
@@ -337,8 +501,37 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
     }
 }
 
+/// Builds the QUIC server configuration of a listener from its endpoint configuration.
+async fn server_config(epconf: &Config<'_>) -> ZResult<quinn::ServerConfig> {
+    let mut server_crypto = TlsServerConfig::new(epconf).await?;
+    server_crypto.server_config.alpn_protocols = ALPN_QUIC_HTTP.iter().map(|&x| x.into()).collect();
+    let resumption = get_bool_config(epconf, QUIC_RESUMPTION, QUIC_RESUMPTION_DEFAULT)?;
+    let zero_rtt = resumption && get_bool_config(epconf, QUIC_ZERO_RTT, QUIC_ZERO_RTT_DEFAULT)?;
+    if !resumption {
+        server_crypto.server_config.session_storage = Arc::new(NoServerSessionStorage {});
+        server_crypto.server_config.send_tls13_tickets = 0;
+    }
+    // QUIC requires the early data size to be either 0 or 0xffffffff (RFC 9001, Section 4.6.1)
+    if zero_rtt {
+        server_crypto.server_config.max_early_data_size = u32::MAX;
+    }
+    let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(server_crypto.server_config));
+
+    // We do not accept unidireactional streams.
+    Arc::get_mut(&mut server_config.transport)
+        .unwrap()
+        .max_concurrent_uni_streams(0_u8.into());
+    // For the time being we only allow one bidirectional stream
+    Arc::get_mut(&mut server_config.transport)
+        .unwrap()
+        .max_concurrent_bidi_streams(1_u8.into());
+    Ok(server_config)
+}
+
 async fn accept_task(
-    endpoint: quinn::Endpoint,
+    quic_endpoint: quinn::Endpoint,
+    endpoint: EndPoint,
+    reload_interval: u64,
     token: CancellationToken,
     manager: NewLinkChannelSender,
 ) -> ZResult<()> {
@@ -356,10 +549,16 @@ async fn accept_task(
         Ok(conn)
     }
 
-    let src_addr = endpoint
+    let src_addr = quic_endpoint
         .local_addr()
         .map_err(|e| zerror!("Can not accept QUIC connections: {}", e))?;
 
+    // The certificate files are polled for changes: new connections are accepted with the
+    // reloaded configuration while the established ones keep using the previous one.
+    let mut mtime = get_tls_server_files_mtime(&endpoint.config()).await;
+    let mut reload = tokio::time::interval(Duration::from_millis(reload_interval.max(1)));
+    reload.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
     // The accept future
     tracing::trace!("Ready to accept QUIC connections on: {:?}", src_addr);
 
@@ -367,7 +566,24 @@ async fn accept_task(
         tokio::select! {
             _ = token.cancelled() => break,
 
-            res = accept(endpoint.accept()) => {
+            _ = reload.tick(), if reload_interval > 0 => {
+                let modified = get_tls_server_files_mtime(&endpoint.config()).await;
+                if modified <= mtime {
+                    continue;
+                }
+                mtime = modified;
+                match server_config(&endpoint.config()).await {
+                    Ok(config) => {
+                        tracing::info!("Reloaded TLS configuration of listener {}", src_addr);
+                        quic_endpoint.set_server_config(Some(config));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Can not reload TLS configuration of listener {}: {}", src_addr, e);
+                    }
+                }
+            }
+
+            res = accept(quic_endpoint.accept()) => {
                 match res {
                     Ok(quic_conn) => {
                         // Get the bideractional streams. Note that we don't allow unidirectional streams.
@@ -388,6 +604,7 @@ async fn accept_task(
                             Locator::new(QUIC_LOCATOR_PREFIX, dst_addr.to_string(), "")?,
                             send,
                             recv,
+                            None,
                         ));
 
                         // Communicate the new link to the initial transport manager
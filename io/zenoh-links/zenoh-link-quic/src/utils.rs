@@ -12,19 +12,29 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::config::*;
-use crate::verify::WebPkiVerifierAnyServerName;
+use crate::verify::{RevocationVerifier, WebPkiVerifierAnyServerName};
 use rustls::OwnedTrustAnchor;
 use rustls::{
-    server::AllowAnyAuthenticatedClient, version::TLS13, Certificate, ClientConfig, PrivateKey,
-    RootCertStore, ServerConfig,
+    client::{ResolvesClientCert, ServerCertVerifier, WebPkiVerifier},
+    server::{
+        AllowAnyAuthenticatedClient, ClientHello, ResolvesServerCert, UnparsedCertRevocationList,
+    },
+    sign::{CertifiedKey, Signer, SigningKey},
+    version::TLS13,
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, SignatureAlgorithm,
+    SignatureScheme,
 };
 use rustls_pki_types::{CertificateDer, TrustAnchor};
 use secrecy::ExposeSecret;
-use zenoh_link_commons::ConfigurationInspector;
+use zenoh_link_commons::{
+    tls::{self, load_private_key},
+    ConfigurationInspector,
+};
 // use rustls_pki_types::{CertificateDer, PrivateKeyDer, TrustAnchor};
 use std::fs::File;
 use std::io;
 use std::net::SocketAddr;
+use std::time::SystemTime;
 use std::{
     io::{BufReader, Cursor},
     sync::Arc,
@@ -138,6 +148,32 @@ impl ConfigurationInspector<ZenohConfig> for TlsConfigurator {
             };
         }
 
+        if let Some(crl) = c.crl() {
+            ps.push((TLS_CRL_FILE, crl));
+        }
+
+        let reload_interval = c.reload_interval().map(|i| i.to_string());
+        if let Some(reload_interval) = reload_interval.as_deref() {
+            ps.push((TLS_RELOAD_INTERVAL, reload_interval));
+        }
+
+        // The ';' of the key URIs would be taken for the separator of the endpoint parameters
+        let server_private_key_uri = c
+            .server_private_key_uri()
+            .as_ref()
+            .map(|uri| uri.replace(';', "%3B"));
+        if let Some(server_private_key_uri) = server_private_key_uri.as_deref() {
+            ps.push((TLS_SERVER_PRIVATE_KEY_URI, server_private_key_uri));
+        }
+
+        let client_private_key_uri = c
+            .client_private_key_uri()
+            .as_ref()
+            .map(|uri| uri.replace(';', "%3B"));
+        if let Some(client_private_key_uri) = client_private_key_uri.as_deref() {
+            ps.push((TLS_CLIENT_PRIVATE_KEY_URI, client_private_key_uri));
+        }
+
         let mut s = String::new();
         endpoint::Parameters::extend(ps.drain(..), &mut s);
 
@@ -145,8 +181,8 @@ impl ConfigurationInspector<ZenohConfig> for TlsConfigurator {
     }
 }
 
-pub(crate) struct TlsServerConfig {
-    pub(crate) server_config: ServerConfig,
+pub struct TlsServerConfig {
+    pub server_config: ServerConfig,
 }
 
 impl TlsServerConfig {
@@ -157,7 +193,6 @@ impl TlsServerConfig {
                 .map_err(|_| zerror!("Unknown client auth argument: {}", s))?,
             None => false,
         };
-        let tls_server_private_key = TlsServerConfig::load_tls_private_key(config).await?;
         let tls_server_certificate = TlsServerConfig::load_tls_certificate(config).await?;
 
         let certs: Vec<Certificate> =
@@ -167,35 +202,8 @@ impl TlsServerConfig {
                 .map(Certificate)
                 .collect();
 
-        let mut keys: Vec<PrivateKey> =
-            rustls_pemfile::rsa_private_keys(&mut Cursor::new(&tls_server_private_key))
-                .map_err(|err| zerror!("Error processing server key: {err}."))?
-                .into_iter()
-                .map(PrivateKey)
-                .collect();
-
-        if keys.is_empty() {
-            keys = rustls_pemfile::pkcs8_private_keys(&mut Cursor::new(&tls_server_private_key))
-                .map_err(|err| zerror!("Error processing server key: {err}."))?
-                .into_iter()
-                .map(PrivateKey)
-                .collect();
-        }
-
-        if keys.is_empty() {
-            keys = rustls_pemfile::ec_private_keys(&mut Cursor::new(&tls_server_private_key))
-                .map_err(|err| zerror!("Error processing server key: {err}."))?
-                .into_iter()
-                .map(PrivateKey)
-                .collect();
-        }
-
-        if keys.is_empty() {
-            bail!("No private key found for TLS server.");
-        }
-
-        let sc = if tls_server_client_auth {
-            let root_cert_store = load_trust_anchors(config)?.map_or_else(
+        let builder = if tls_server_client_auth {
+            let trust_anchors = load_trust_anchors(config)?.map_or_else(
                 || {
                     Err(zerror!(
                         "Missing root certificates while client authentication is enabled."
@@ -203,19 +211,35 @@ impl TlsServerConfig {
                 },
                 Ok,
             )?;
-            let client_auth = AllowAnyAuthenticatedClient::new(root_cert_store);
+            // Certificates whose revocation status is unknown are accepted
+            let client_auth = AllowAnyAuthenticatedClient::new(root_cert_store(&trust_anchors))
+                .with_crls(
+                    load_tls_crls(config)
+                        .await?
+                        .into_iter()
+                        .map(UnparsedCertRevocationList),
+                )
+                .map_err(|e| zerror!("Invalid TLS CRL: {:?}", e))?;
             ServerConfig::builder()
                 .with_safe_default_cipher_suites()
                 .with_safe_default_kx_groups()
                 .with_protocol_versions(&[&TLS13])?
                 .with_client_cert_verifier(Arc::new(client_auth))
-                .with_single_cert(certs, keys.remove(0))
-                .map_err(|e| zerror!(e))?
         } else {
             ServerConfig::builder()
                 .with_safe_defaults()
                 .with_no_client_auth()
-                .with_single_cert(certs, keys.remove(0))
+        };
+
+        let sc = if let Some(uri) = config.get(TLS_SERVER_PRIVATE_KEY_URI) {
+            let certified_key =
+                CertifiedKey::new(certs, Arc::new(ProvidedSigningKey(load_private_key(uri)?)));
+            builder.with_cert_resolver(Arc::new(SingleCertResolver(Arc::new(certified_key))))
+        } else {
+            let tls_server_private_key = TlsServerConfig::load_tls_private_key(config).await?;
+            let key = parse_private_key(&tls_server_private_key, "server")?;
+            builder
+                .with_single_cert(certs, key)
                 .map_err(|e| zerror!(e))?
         };
         Ok(TlsServerConfig { server_config: sc })
@@ -242,8 +266,8 @@ impl TlsServerConfig {
     }
 }
 
-pub(crate) struct TlsClientConfig {
-    pub(crate) client_config: ClientConfig,
+pub struct TlsClientConfig {
+    pub client_config: ClientConfig,
 }
 
 impl TlsClientConfig {
@@ -270,28 +294,30 @@ impl TlsClientConfig {
 
         // Allows mixed user-generated CA and webPKI CA
         tracing::debug!("Loading default Web PKI certificates.");
-        let mut root_cert_store = RootCertStore {
-            roots: webpki_roots::TLS_SERVER_ROOTS
-                .iter()
-                .map(|ta| ta.to_owned())
-                .map(|ta| {
-                    OwnedTrustAnchor::from_subject_spki_name_constraints(
-                        ta.subject.to_vec(),
-                        ta.subject_public_key_info.to_vec(),
-                        ta.name_constraints.map(|nc| nc.to_vec()),
-                    )
-                })
-                .collect(),
-        };
+        let mut trust_anchors: Vec<TrustAnchor<'static>> = webpki_roots::TLS_SERVER_ROOTS.to_vec();
 
-        if let Some(custom_root_cert) = load_trust_anchors(config)? {
+        if let Some(custom_trust_anchors) = load_trust_anchors(config)? {
             tracing::debug!("Loading user-generated certificates.");
-            root_cert_store.roots.extend(custom_root_cert.roots);
+            trust_anchors.extend(custom_trust_anchors);
         }
 
+        let verifier: Arc<dyn ServerCertVerifier> = if tls_server_name_verification {
+            Arc::new(WebPkiVerifier::new(root_cert_store(&trust_anchors), None))
+        } else {
+            Arc::new(WebPkiVerifierAnyServerName::new(root_cert_store(
+                &trust_anchors,
+            )))
+        };
+        // rustls 0.21 does not check the revocation status of the server certificates
+        let crls = load_tls_crls(config).await?;
+        let verifier: Arc<dyn ServerCertVerifier> = if crls.is_empty() {
+            verifier
+        } else {
+            Arc::new(RevocationVerifier::new(verifier, trust_anchors, crls)?)
+        };
+
         let cc = if tls_client_server_auth {
             tracing::debug!("Loading client authentication key and certificate...");
-            let tls_client_private_key = TlsClientConfig::load_tls_private_key(config).await?;
             let tls_client_certificate = TlsClientConfig::load_tls_certificate(config).await?;
 
             let certs: Vec<Certificate> =
@@ -301,68 +327,33 @@ impl TlsClientConfig {
                     .map(Certificate)
                     .collect();
 
-            let mut keys: Vec<PrivateKey> =
-                rustls_pemfile::rsa_private_keys(&mut Cursor::new(&tls_client_private_key))
-                    .map_err(|err| zerror!("Error processing client key: {err}."))?
-                    .into_iter()
-                    .map(PrivateKey)
-                    .collect();
-
-            if keys.is_empty() {
-                keys =
-                    rustls_pemfile::pkcs8_private_keys(&mut Cursor::new(&tls_client_private_key))
-                        .map_err(|err| zerror!("Error processing client key: {err}."))?
-                        .into_iter()
-                        .map(PrivateKey)
-                        .collect();
-            }
-
-            if keys.is_empty() {
-                keys = rustls_pemfile::ec_private_keys(&mut Cursor::new(&tls_client_private_key))
-                    .map_err(|err| zerror!("Error processing client key: {err}."))?
-                    .into_iter()
-                    .map(PrivateKey)
-                    .collect();
-            }
-
-            if keys.is_empty() {
-                bail!("No private key found for TLS client.");
-            }
-
             let builder = ClientConfig::builder()
                 .with_safe_default_cipher_suites()
                 .with_safe_default_kx_groups()
                 .with_protocol_versions(&[&TLS13])?;
 
-            if tls_server_name_verification {
-                builder
-                    .with_root_certificates(root_cert_store)
-                    .with_client_auth_cert(certs, keys.remove(0))
-            } else {
-                builder
-                    .with_custom_certificate_verifier(Arc::new(WebPkiVerifierAnyServerName::new(
-                        root_cert_store,
-                    )))
-                    .with_client_auth_cert(certs, keys.remove(0))
-            }
-            .map_err(|e| zerror!("Bad certificate/key: {}", e))?
+            let key: Arc<dyn SigningKey> = match config.get(TLS_CLIENT_PRIVATE_KEY_URI) {
+                Some(uri) => Arc::new(ProvidedSigningKey(load_private_key(uri)?)),
+                None => {
+                    let tls_client_private_key =
+                        TlsClientConfig::load_tls_private_key(config).await?;
+                    let key = parse_private_key(&tls_client_private_key, "client")?;
+                    rustls::sign::any_supported_type(&key)
+                        .map_err(|e| zerror!("Bad certificate/key: {}", e))?
+                }
+            };
+            let resolver = Arc::new(SingleCertResolver(Arc::new(CertifiedKey::new(certs, key))));
+
+            builder
+                .with_custom_certificate_verifier(verifier)
+                .with_client_cert_resolver(resolver)
         } else {
-            let builder = ClientConfig::builder()
+            ClientConfig::builder()
                 .with_safe_default_cipher_suites()
                 .with_safe_default_kx_groups()
-                .with_protocol_versions(&[&TLS13])?;
-
-            if tls_server_name_verification {
-                builder
-                    .with_root_certificates(root_cert_store)
-                    .with_no_client_auth()
-            } else {
-                builder
-                    .with_custom_certificate_verifier(Arc::new(WebPkiVerifierAnyServerName::new(
-                        root_cert_store,
-                    )))
-                    .with_no_client_auth()
-            }
+                .with_protocol_versions(&[&TLS13])?
+                .with_custom_certificate_verifier(verifier)
+                .with_no_client_auth()
         };
         Ok(TlsClientConfig { client_config: cc })
     }
@@ -388,34 +379,135 @@ impl TlsClientConfig {
     }
 }
 
-fn process_pem(pem: &mut dyn io::BufRead) -> ZResult<Vec<OwnedTrustAnchor>> {
+fn parse_private_key(pem: &[u8], side: &str) -> ZResult<PrivateKey> {
+    let mut keys: Vec<PrivateKey> = rustls_pemfile::rsa_private_keys(&mut Cursor::new(pem))
+        .map_err(|err| zerror!("Error processing {side} key: {err}."))?
+        .into_iter()
+        .map(PrivateKey)
+        .collect();
+
+    if keys.is_empty() {
+        keys = rustls_pemfile::pkcs8_private_keys(&mut Cursor::new(pem))
+            .map_err(|err| zerror!("Error processing {side} key: {err}."))?
+            .into_iter()
+            .map(PrivateKey)
+            .collect();
+    }
+
+    if keys.is_empty() {
+        keys = rustls_pemfile::ec_private_keys(&mut Cursor::new(pem))
+            .map_err(|err| zerror!("Error processing {side} key: {err}."))?
+            .into_iter()
+            .map(PrivateKey)
+            .collect();
+    }
+
+    if keys.is_empty() {
+        bail!("No private key found for TLS {side}.");
+    }
+    Ok(keys.remove(0))
+}
+
+/// [`SigningKey`] delegating the signatures to the private key of a registered key provider.
+///
+/// quinn still depends on rustls 0.21: the schemes and algorithms of the rustls 0.22 based
+/// [`zenoh_link_commons::tls::PrivateKey`] are converted through their code points.
+struct ProvidedSigningKey(Arc<dyn tls::PrivateKey>);
+
+impl SigningKey for ProvidedSigningKey {
+    fn choose_scheme(&self, offered: &[SignatureScheme]) -> Option<Box<dyn Signer>> {
+        self.0.schemes().into_iter().find_map(|provided| {
+            let scheme = SignatureScheme::from(provided.get_u16());
+            offered.contains(&scheme).then(|| {
+                Box::new(ProvidedSigner {
+                    key: self.0.clone(),
+                    provided,
+                    scheme,
+                }) as Box<dyn Signer>
+            })
+        })
+    }
+
+    fn algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::from(self.0.algorithm().get_u8())
+    }
+}
+
+struct ProvidedSigner {
+    key: Arc<dyn tls::PrivateKey>,
+    provided: tls::SignatureScheme,
+    scheme: SignatureScheme,
+}
+
+impl Signer for ProvidedSigner {
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, rustls::Error> {
+        self.key
+            .sign(self.provided, message)
+            .map_err(|e| rustls::Error::General(e.to_string()))
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        self.scheme
+    }
+}
+
+struct SingleCertResolver(Arc<CertifiedKey>);
+
+impl ResolvesServerCert for SingleCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.0.clone())
+    }
+}
+
+impl ResolvesClientCert for SingleCertResolver {
+    fn resolve(
+        &self,
+        _acceptable_issuers: &[&[u8]],
+        _sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(self.0.clone())
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+fn process_pem(pem: &mut dyn io::BufRead) -> ZResult<Vec<TrustAnchor<'static>>> {
     let certs: Vec<CertificateDer> = rustls_pemfile::certs(pem)
         .map_err(|err| zerror!("Error processing PEM certificates: {err}."))?
         .into_iter()
         .map(CertificateDer::from)
         .collect();
 
-    let trust_anchors: Vec<OwnedTrustAnchor> = certs
+    let trust_anchors: Vec<TrustAnchor<'static>> = certs
         .into_iter()
         .map(|cert| {
             anchor_from_trusted_cert(&cert)
                 .map_err(|err| zerror!("Error processing trust anchor: {err}."))
                 .map(|trust_anchor| trust_anchor.to_owned())
         })
-        .collect::<Result<Vec<TrustAnchor>, ZError>>()?
-        .into_iter()
-        .map(|ta| {
-            OwnedTrustAnchor::from_subject_spki_name_constraints(
-                ta.subject.to_vec(),
-                ta.subject_public_key_info.to_vec(),
-                ta.name_constraints.map(|nc| nc.to_vec()),
-            )
-        })
-        .collect();
+        .collect::<Result<Vec<TrustAnchor>, ZError>>()?;
 
     Ok(trust_anchors)
 }
 
+/// Converts the trust anchors to the rustls 0.21 [`RootCertStore`] quinn depends on.
+fn root_cert_store(trust_anchors: &[TrustAnchor<'_>]) -> RootCertStore {
+    RootCertStore {
+        roots: trust_anchors
+            .iter()
+            .map(|ta| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject.to_vec(),
+                    ta.subject_public_key_info.to_vec(),
+                    ta.name_constraints.as_ref().map(|nc| nc.to_vec()),
+                )
+            })
+            .collect(),
+    }
+}
+
 async fn load_tls_key(
     config: &Config<'_>,
     tls_private_key_raw_config_key: &str,
@@ -467,32 +559,66 @@ async fn load_tls_certificate(
     Err(zerror!("Missing tls certificates.").into())
 }
 
-fn load_trust_anchors(config: &Config<'_>) -> ZResult<Option<RootCertStore>> {
-    let mut root_cert_store = RootCertStore::empty();
+fn load_trust_anchors(config: &Config<'_>) -> ZResult<Option<Vec<TrustAnchor<'static>>>> {
     if let Some(value) = config.get(TLS_ROOT_CA_CERTIFICATE_RAW) {
         let mut pem = BufReader::new(value.as_bytes());
-        let trust_anchors = process_pem(&mut pem)?;
-        root_cert_store.roots.extend(trust_anchors);
-        return Ok(Some(root_cert_store));
+        return process_pem(&mut pem).map(Some);
     }
 
     if let Some(b64_certificate) = config.get(TLS_ROOT_CA_CERTIFICATE_BASE64) {
         let certificate_pem = base64_decode(b64_certificate)?;
         let mut pem = BufReader::new(certificate_pem.as_slice());
-        let trust_anchors = process_pem(&mut pem)?;
-        root_cert_store.roots.extend(trust_anchors);
-        return Ok(Some(root_cert_store));
+        return process_pem(&mut pem).map(Some);
     }
 
     if let Some(filename) = config.get(TLS_ROOT_CA_CERTIFICATE_FILE) {
         let mut pem = BufReader::new(File::open(filename)?);
-        let trust_anchors = process_pem(&mut pem)?;
-        root_cert_store.roots.extend(trust_anchors);
-        return Ok(Some(root_cert_store));
+        return process_pem(&mut pem).map(Some);
     }
     Ok(None)
 }
 
+async fn load_tls_crls(config: &Config<'_>) -> ZResult<Vec<Vec<u8>>> {
+    let pem = if let Some(value) = config.get(TLS_CRL_RAW) {
+        value.as_bytes().to_vec()
+    } else if let Some(b64_crl) = config.get(TLS_CRL_BASE64) {
+        base64_decode(b64_crl)?
+    } else if let Some(value) = config.get(TLS_CRL_FILE) {
+        tokio::fs::read(value)
+            .await
+            .map_err(|e| zerror!("Invalid TLS CRL file: {}", e))?
+    } else {
+        return Ok(vec![]);
+    };
+
+    let crls = rustls_pemfile::crls(&mut Cursor::new(&pem))
+        .map_err(|err| zerror!("Error processing CRLs: {err}."))?;
+    if crls.is_empty() {
+        bail!("No CRL found in the TLS CRL configuration.");
+    }
+    Ok(crls)
+}
+
+/// Returns the most recent modification time of the files a QUIC listener is configured with.
+pub async fn get_tls_server_files_mtime(config: &Config<'_>) -> Option<SystemTime> {
+    let mut mtime = None;
+    for key in [
+        TLS_SERVER_PRIVATE_KEY_FILE,
+        TLS_SERVER_CERTIFICATE_FILE,
+        TLS_ROOT_CA_CERTIFICATE_FILE,
+        TLS_CRL_FILE,
+    ] {
+        if let Some(path) = config.get(key) {
+            let modified = tokio::fs::metadata(path)
+                .await
+                .and_then(|m| m.modified())
+                .ok();
+            mtime = mtime.max(modified);
+        }
+    }
+    mtime
+}
+
 pub async fn get_quic_addr(address: &Address<'_>) -> ZResult<SocketAddr> {
     match tokio::net::lookup_host(address.as_str()).await?.next() {
         Some(addr) => Ok(addr),
@@ -1,10 +1,17 @@
 use rustls::client::verify_server_cert_signed_by_trust_anchor;
 use rustls::server::ParsedCertificate;
-use std::time::SystemTime;
+use rustls_pki_types::{CertificateDer, TrustAnchor, UnixTime};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio_rustls::rustls::{
     client::{ServerCertVerified, ServerCertVerifier},
-    Certificate, RootCertStore, ServerName,
+    Certificate, CertificateError, RootCertStore, ServerName,
 };
+use webpki::{
+    CertRevocationList, EndEntityCert, KeyUsage, OwnedCertRevocationList, RevocationOptionsBuilder,
+    UnknownStatusPolicy,
+};
+use zenoh_result::{zerror, ZResult};
 
 impl ServerCertVerifier for WebPkiVerifierAnyServerName {
     /// Will verify the certificate is valid in the following ways:
@@ -40,3 +47,89 @@ impl WebPkiVerifierAnyServerName {
         Self { roots }
     }
 }
+
+/// `ServerCertVerifier` that checks the revocation status of the server certificates against
+/// certificate revocation lists, once they have been verified by the `inner` verifier.
+///
+/// Certificates whose revocation status is unknown are accepted.
+pub struct RevocationVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    roots: Vec<TrustAnchor<'static>>,
+    crls: Vec<CertRevocationList<'static>>,
+}
+
+impl RevocationVerifier {
+    /// Constructs a new `RevocationVerifier`.
+    ///
+    /// `roots` are the trust anchors of `inner` and `crls` the DER-encoded revocation lists.
+    pub fn new(
+        inner: Arc<dyn ServerCertVerifier>,
+        roots: Vec<TrustAnchor<'static>>,
+        crls: Vec<Vec<u8>>,
+    ) -> ZResult<Self> {
+        let crls = crls
+            .iter()
+            .map(|crl| {
+                OwnedCertRevocationList::from_der(crl)
+                    .map(CertRevocationList::from)
+                    .map_err(|e| zerror!("Invalid TLS CRL: {:?}", e).into())
+            })
+            .collect::<ZResult<_>>()?;
+        Ok(Self { inner, roots, crls })
+    }
+}
+
+impl ServerCertVerifier for RevocationVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        )?;
+
+        let end_entity = CertificateDer::from(end_entity.0.as_slice());
+        let cert = EndEntityCert::try_from(&end_entity).map_err(pki_error)?;
+        let intermediates = intermediates
+            .iter()
+            .map(|c| CertificateDer::from(c.0.as_slice()))
+            .collect::<Vec<_>>();
+        let crls = self.crls.iter().collect::<Vec<_>>();
+        let revocation = RevocationOptionsBuilder::new(&crls)
+            .map_err(|_| tokio_rustls::rustls::Error::General("No TLS CRL".into()))?
+            .with_status_policy(UnknownStatusPolicy::Allow)
+            .build();
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| tokio_rustls::rustls::Error::FailedToGetCurrentTime)?;
+        cert.verify_for_usage(
+            webpki::ALL_VERIFICATION_ALGS,
+            &self.roots,
+            &intermediates,
+            UnixTime::since_unix_epoch(now),
+            KeyUsage::server_auth(),
+            Some(revocation),
+        )
+        .map_err(pki_error)?;
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn pki_error(error: webpki::Error) -> tokio_rustls::rustls::Error {
+    match error {
+        webpki::Error::CertRevoked => {
+            tokio_rustls::rustls::Error::InvalidCertificate(CertificateError::Revoked)
+        }
+        e => tokio_rustls::rustls::Error::InvalidCertificate(CertificateError::Other(Arc::new(e))),
+    }
+}
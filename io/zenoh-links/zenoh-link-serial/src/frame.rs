@@ -0,0 +1,633 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The framing of the serial link.
+//!
+//! A frame is the content of a write followed by its CRC, if any, byte-stuffed with either COBS
+//! or SLIP and surrounded by delimiters. A corrupted frame is thus detected and skipped up to the
+//! next delimiter, without losing the synchronization with the sender.
+//!
+//! When XON/XOFF flow control is in use, the XON and XOFF bytes are additionally escaped on the
+//! line, so that they are never mistaken for flow control by the serial drivers.
+//!
+//! The format of the frames is not negotiated: both ends of a link must be configured with the
+//! same framing, CRC, flow control and retransmissions, enabled or not, since the frames only
+//! carry a retransmission header when they are. This format also breaks the compatibility with
+//! the peers framing with z-serial, i.e. running a version of Zenoh prior to its introduction.
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+use zenoh_result::{bail, Error as ZError};
+
+const COBS_DELIMITER: u8 = 0x00;
+
+const SLIP_END: u8 = 0xC0;
+const SLIP_ESC: u8 = 0xDB;
+const SLIP_ESC_END: u8 = 0xDC;
+const SLIP_ESC_ESC: u8 = 0xDD;
+
+const XON: u8 = 0x11;
+const XOFF: u8 = 0x13;
+const XESC: u8 = 0x7D;
+const XESC_MASK: u8 = 0x20;
+
+const CRC16: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_IBM_SDLC);
+const CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framing {
+    Cobs,
+    Slip,
+}
+
+impl Framing {
+    fn delimiter(&self) -> u8 {
+        match self {
+            Framing::Cobs => COBS_DELIMITER,
+            Framing::Slip => SLIP_END,
+        }
+    }
+
+    // The worst case size of a stuffed frame of the given size, delimiters included
+    fn max_stuffed_len(&self, len: usize) -> usize {
+        match self {
+            Framing::Cobs => len + len / 254 + 3,
+            Framing::Slip => 2 * len + 2,
+        }
+    }
+}
+
+impl FromStr for Framing {
+    type Err = ZError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cobs" => Ok(Framing::Cobs),
+            "slip" => Ok(Framing::Slip),
+            _ => bail!("Unknown serial framing: {}. Supported: cobs, slip.", s),
+        }
+    }
+}
+
+impl fmt::Display for Framing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Framing::Cobs => write!(f, "cobs"),
+            Framing::Slip => write!(f, "slip"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Crc {
+    None,
+    Crc16,
+    Crc32,
+}
+
+impl Crc {
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Crc::None => 0,
+            Crc::Crc16 => 2,
+            Crc::Crc32 => 4,
+        }
+    }
+
+    fn append(&self, frame: &mut Vec<u8>) {
+        match self {
+            Crc::None => {}
+            Crc::Crc16 => {
+                let crc = CRC16.checksum(frame);
+                frame.extend_from_slice(&crc.to_le_bytes());
+            }
+            Crc::Crc32 => {
+                let crc = CRC32.checksum(frame);
+                frame.extend_from_slice(&crc.to_le_bytes());
+            }
+        }
+    }
+
+    // Checks and removes the CRC at the end of the frame
+    fn check(&self, frame: &mut Vec<u8>) -> bool {
+        let Some(len) = frame.len().checked_sub(self.len()) else {
+            return false;
+        };
+        let (content, crc) = frame.split_at(len);
+        let valid = match self {
+            Crc::None => true,
+            Crc::Crc16 => CRC16.checksum(content).to_le_bytes() == crc,
+            Crc::Crc32 => CRC32.checksum(content).to_le_bytes() == crc,
+        };
+        frame.truncate(len);
+        valid
+    }
+}
+
+impl FromStr for Crc {
+    type Err = ZError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Crc::None),
+            "crc16" => Ok(Crc::Crc16),
+            "crc32" => Ok(Crc::Crc32),
+            _ => bail!("Unknown serial CRC: {}. Supported: none, crc16, crc32.", s),
+        }
+    }
+}
+
+impl fmt::Display for Crc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crc::None => write!(f, "none"),
+            Crc::Crc16 => write!(f, "crc16"),
+            Crc::Crc32 => write!(f, "crc32"),
+        }
+    }
+}
+
+fn cobs_encode(input: &[u8], out: &mut Vec<u8>) {
+    let mut code_idx = out.len();
+    let mut code = 1u8;
+    out.push(0);
+    for &b in input {
+        if b != 0 {
+            out.push(b);
+            code += 1;
+        }
+        if b == 0 || code == 0xFF {
+            out[code_idx] = code;
+            code_idx = out.len();
+            code = 1;
+            out.push(0);
+        }
+    }
+    out[code_idx] = code;
+}
+
+fn cobs_decode(input: &[u8], out: &mut Vec<u8>) -> bool {
+    let mut i = 0;
+    while i < input.len() {
+        let code = input[i] as usize;
+        if code == 0 || i + code > input.len() {
+            return false;
+        }
+        out.extend_from_slice(&input[i + 1..i + code]);
+        i += code;
+        if code < 0xFF && i < input.len() {
+            out.push(0);
+        }
+    }
+    true
+}
+
+fn slip_encode(input: &[u8], out: &mut Vec<u8>) {
+    for &b in input {
+        match b {
+            SLIP_END => out.extend_from_slice(&[SLIP_ESC, SLIP_ESC_END]),
+            SLIP_ESC => out.extend_from_slice(&[SLIP_ESC, SLIP_ESC_ESC]),
+            _ => out.push(b),
+        }
+    }
+}
+
+pub(crate) struct Encoder {
+    framing: Framing,
+    crc: Crc,
+    xonxoff: bool,
+    content: Vec<u8>,
+    stuffed: Vec<u8>,
+}
+
+impl Encoder {
+    pub(crate) fn new(framing: Framing, crc: Crc, xonxoff: bool) -> Self {
+        Self {
+            framing,
+            crc,
+            xonxoff,
+            content: vec![],
+            stuffed: vec![],
+        }
+    }
+
+    /// Returns the bytes to write on the line for a frame made of the given parts.
+    pub(crate) fn encode(&mut self, parts: &[&[u8]]) -> &[u8] {
+        self.content.clear();
+        for part in parts {
+            self.content.extend_from_slice(part);
+        }
+        self.crc.append(&mut self.content);
+
+        let delimiter = self.framing.delimiter();
+        self.stuffed.clear();
+        self.stuffed
+            .reserve(self.framing.max_stuffed_len(self.content.len()));
+        // The leading delimiter terminates any garbage received by the peer before this frame
+        self.stuffed.push(delimiter);
+        match self.framing {
+            Framing::Cobs => cobs_encode(&self.content, &mut self.stuffed),
+            Framing::Slip => slip_encode(&self.content, &mut self.stuffed),
+        }
+        self.stuffed.push(delimiter);
+
+        if !self.xonxoff {
+            return &self.stuffed;
+        }
+        self.content.clear();
+        for &b in &self.stuffed {
+            match b {
+                XON | XOFF | XESC => self.content.extend_from_slice(&[XESC, b ^ XESC_MASK]),
+                _ => self.content.push(b),
+            }
+        }
+        &self.content
+    }
+}
+
+pub(crate) enum Decoded {
+    // More bytes are needed to complete the frame
+    Incomplete,
+    // The frame is corrupted and has been dropped
+    Invalid,
+    // The frame is complete, its content is available
+    Frame,
+}
+
+pub(crate) struct Decoder {
+    framing: Framing,
+    crc: Crc,
+    xonxoff: bool,
+    max_len: usize,
+    // The bytes of the frame being received: still stuffed with COBS, unstuffed with SLIP
+    pending: Vec<u8>,
+    slip_escaped: bool,
+    xonxoff_escaped: bool,
+    invalid: bool,
+    // The content of the last decoded frame
+    frame: Vec<u8>,
+}
+
+impl Decoder {
+    pub(crate) fn new(framing: Framing, crc: Crc, xonxoff: bool, max_len: usize) -> Self {
+        Self {
+            framing,
+            crc,
+            xonxoff,
+            max_len: framing.max_stuffed_len(max_len + crc.len()),
+            pending: vec![],
+            slip_escaped: false,
+            xonxoff_escaped: false,
+            invalid: false,
+            frame: vec![],
+        }
+    }
+
+    /// The content of the last decoded frame.
+    pub(crate) fn frame(&self) -> &[u8] {
+        &self.frame
+    }
+
+    /// Decodes the input up to the end of a frame, returning the number of bytes consumed.
+    pub(crate) fn decode(&mut self, input: &[u8]) -> (usize, Decoded) {
+        let delimiter = self.framing.delimiter();
+        for (i, &b) in input.iter().enumerate() {
+            // A delimiter is never escaped, so that a corrupted escape does not hide it
+            let b = if self.xonxoff_escaped {
+                self.xonxoff_escaped = false;
+                if b == delimiter {
+                    self.invalid = true;
+                    b
+                } else {
+                    b ^ XESC_MASK
+                }
+            } else if self.xonxoff && b == XESC {
+                self.xonxoff_escaped = true;
+                continue;
+            } else {
+                b
+            };
+
+            if b == delimiter {
+                // Consecutive delimiters delimit empty frames, which are skipped
+                if self.pending.is_empty() && !self.invalid {
+                    continue;
+                }
+                return (i + 1, self.finish());
+            }
+            if self.invalid {
+                continue;
+            }
+            if self.pending.len() >= self.max_len {
+                self.invalid = true;
+                continue;
+            }
+            match self.framing {
+                Framing::Cobs => self.pending.push(b),
+                Framing::Slip if self.slip_escaped => {
+                    self.slip_escaped = false;
+                    match b {
+                        SLIP_ESC_END => self.pending.push(SLIP_END),
+                        SLIP_ESC_ESC => self.pending.push(SLIP_ESC),
+                        _ => self.invalid = true,
+                    }
+                }
+                Framing::Slip if b == SLIP_ESC => self.slip_escaped = true,
+                Framing::Slip => self.pending.push(b),
+            }
+        }
+        (input.len(), Decoded::Incomplete)
+    }
+
+    fn finish(&mut self) -> Decoded {
+        let mut valid = !self.invalid && !self.slip_escaped;
+        self.frame.clear();
+        if valid {
+            valid = match self.framing {
+                Framing::Cobs => cobs_decode(&self.pending, &mut self.frame),
+                Framing::Slip => {
+                    mem::swap(&mut self.pending, &mut self.frame);
+                    true
+                }
+            } && self.crc.check(&mut self.frame);
+        }
+        self.pending.clear();
+        self.slip_escaped = false;
+        self.invalid = false;
+        if valid {
+            Decoded::Frame
+        } else {
+            self.frame.clear();
+            Decoded::Invalid
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAMINGS: [Framing; 2] = [Framing::Cobs, Framing::Slip];
+    const CRCS: [Crc; 3] = [Crc::None, Crc::Crc16, Crc::Crc32];
+
+    // Decodes the whole input, returning the frames and None for each invalid frame
+    fn decode_all(decoder: &mut Decoder, mut input: &[u8]) -> Vec<Option<Vec<u8>>> {
+        let mut frames = vec![];
+        while !input.is_empty() {
+            let (n, decoded) = decoder.decode(input);
+            input = &input[n..];
+            match decoded {
+                Decoded::Frame => frames.push(Some(decoder.frame().to_vec())),
+                Decoded::Invalid => frames.push(None),
+                Decoded::Incomplete => {}
+            }
+        }
+        frames
+    }
+
+    fn payloads() -> Vec<Vec<u8>> {
+        vec![
+            vec![0x00],
+            vec![0x01],
+            vec![SLIP_END, SLIP_ESC, SLIP_ESC_END, SLIP_ESC_ESC],
+            vec![XON, XOFF, XESC, XESC ^ XESC_MASK],
+            (0..=255).collect(),
+            (0..1_500).map(|i| (i % 7) as u8).collect(),
+            vec![0xFF; 254],
+            vec![0x00; 300],
+        ]
+    }
+
+    #[test]
+    fn cobs() {
+        // The examples of the COBS paper
+        for (decoded, encoded) in [
+            (&[0x00][..], &[0x01, 0x01][..]),
+            (&[0x00, 0x00], &[0x01, 0x01, 0x01]),
+            (&[0x11, 0x22, 0x00, 0x33], &[0x03, 0x11, 0x22, 0x02, 0x33]),
+            (&[0x11, 0x22, 0x33, 0x44], &[0x05, 0x11, 0x22, 0x33, 0x44]),
+            (&[0x11, 0x00, 0x00, 0x00], &[0x02, 0x11, 0x01, 0x01, 0x01]),
+        ] {
+            let mut out = vec![];
+            cobs_encode(decoded, &mut out);
+            assert_eq!(out, encoded);
+            let mut out = vec![];
+            assert!(cobs_decode(encoded, &mut out));
+            assert_eq!(out, decoded);
+        }
+        // Blocks of 254 non-zero bytes are not followed by a zero
+        let decoded: Vec<u8> = (1..=254).collect();
+        let mut encoded = vec![];
+        cobs_encode(&decoded, &mut encoded);
+        assert_eq!(encoded[0], 0xFF);
+        assert!(!encoded.contains(&COBS_DELIMITER));
+        let mut out = vec![];
+        assert!(cobs_decode(&encoded, &mut out));
+        assert_eq!(out, decoded);
+
+        // A code pointing past the end, or a zero code, is invalid
+        assert!(!cobs_decode(&[0x05, 0x11, 0x22], &mut vec![]));
+        assert!(!cobs_decode(&[0x02, 0x11, 0x00, 0x22], &mut vec![]));
+    }
+
+    #[test]
+    fn slip() {
+        let mut out = vec![];
+        slip_encode(&[0x01, SLIP_END, 0x02, SLIP_ESC, 0x03], &mut out);
+        assert_eq!(
+            out,
+            [
+                0x01,
+                SLIP_ESC,
+                SLIP_ESC_END,
+                0x02,
+                SLIP_ESC,
+                SLIP_ESC_ESC,
+                0x03
+            ]
+        );
+    }
+
+    #[test]
+    fn crc() {
+        // The check values of the CRC catalogue
+        for (crc, check) in [
+            (Crc::Crc16, &0x906Eu16.to_le_bytes()[..]),
+            (Crc::Crc32, &0xCBF43926u32.to_le_bytes()[..]),
+        ] {
+            let mut frame = b"123456789".to_vec();
+            crc.append(&mut frame);
+            assert_eq!(&frame[9..], check);
+            assert!(crc.check(&mut frame));
+            assert_eq!(frame, b"123456789");
+        }
+
+        let mut frame = b"123456789".to_vec();
+        Crc::None.append(&mut frame);
+        assert_eq!(frame, b"123456789");
+        assert!(Crc::None.check(&mut frame));
+
+        // Any single bit error is detected
+        for crc in [Crc::Crc16, Crc::Crc32] {
+            let mut frame = b"123456789".to_vec();
+            crc.append(&mut frame);
+            for i in 0..frame.len() * 8 {
+                let mut corrupted = frame.clone();
+                corrupted[i / 8] ^= 1 << (i % 8);
+                assert!(!crc.check(&mut corrupted));
+            }
+            // Frames shorter than the CRC are invalid
+            assert!(!crc.check(&mut vec![0x00]));
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        for framing in FRAMINGS {
+            for crc in CRCS {
+                for xonxoff in [false, true] {
+                    let mut encoder = Encoder::new(framing, crc, xonxoff);
+                    let mut decoder = Decoder::new(framing, crc, xonxoff, 1_500);
+                    for payload in payloads() {
+                        // The frame parts are concatenated
+                        let (head, tail) = payload.split_at(payload.len() / 2);
+                        let bytes = encoder.encode(&[head, tail]).to_vec();
+                        assert_eq!(bytes.first(), Some(&framing.delimiter()));
+                        assert_eq!(bytes.last(), Some(&framing.delimiter()));
+                        let inner = &bytes[1..bytes.len() - 1];
+                        assert!(!inner.contains(&framing.delimiter()));
+                        if xonxoff {
+                            assert!(!bytes.contains(&XON) && !bytes.contains(&XOFF));
+                        }
+
+                        assert_eq!(decode_all(&mut decoder, &bytes), [Some(payload.clone())]);
+
+                        // Byte by byte
+                        for (i, b) in bytes.iter().enumerate() {
+                            let (n, decoded) = decoder.decode(&[*b]);
+                            assert_eq!(n, 1);
+                            if i == bytes.len() - 1 {
+                                assert!(matches!(decoded, Decoded::Frame));
+                                assert_eq!(decoder.frame(), payload);
+                            } else {
+                                assert!(matches!(decoded, Decoded::Incomplete));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn consecutive_frames() {
+        for framing in FRAMINGS {
+            let mut encoder = Encoder::new(framing, Crc::Crc32, false);
+            let mut decoder = Decoder::new(framing, Crc::Crc32, false, 1_500);
+            let mut bytes = vec![];
+            for payload in payloads() {
+                bytes.extend_from_slice(encoder.encode(&[&payload]));
+            }
+            let frames: Vec<_> = payloads().into_iter().map(Some).collect();
+            assert_eq!(decode_all(&mut decoder, &bytes), frames);
+        }
+    }
+
+    #[test]
+    fn corrupted_frames() {
+        for framing in FRAMINGS {
+            for crc in [Crc::Crc16, Crc::Crc32] {
+                let mut encoder = Encoder::new(framing, crc, false);
+                let mut decoder = Decoder::new(framing, crc, false, 1_500);
+                let first = encoder.encode(&[b"first"]).to_vec();
+                let second = encoder.encode(&[b"second"]).to_vec();
+
+                // A corrupted frame is dropped without losing the following one
+                for i in 1..first.len() - 1 {
+                    let mut bytes = first.clone();
+                    bytes[i] ^= 0x04;
+                    bytes.extend_from_slice(&second);
+                    let frames = decode_all(&mut decoder, &bytes);
+                    assert_eq!(frames.last(), Some(&Some(b"second".to_vec())));
+                    assert!(!frames.contains(&Some(b"first".to_vec())));
+                }
+
+                // A truncated frame is dropped at the delimiter of the next one
+                let mut bytes = first[..first.len() / 2].to_vec();
+                bytes.extend_from_slice(&second);
+                assert_eq!(
+                    decode_all(&mut decoder, &bytes),
+                    [None, Some(b"second".to_vec())]
+                );
+
+                // The garbage received before a frame is dropped
+                let mut bytes = vec![0x42; 10];
+                bytes.extend_from_slice(&second);
+                assert_eq!(
+                    decode_all(&mut decoder, &bytes),
+                    [None, Some(b"second".to_vec())]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_frames() {
+        for framing in FRAMINGS {
+            let mut encoder = Encoder::new(framing, Crc::Crc32, false);
+            let mut decoder = Decoder::new(framing, Crc::Crc32, false, 16);
+            let mut bytes = encoder.encode(&[&[0x42; 1_000]]).to_vec();
+            bytes.extend_from_slice(encoder.encode(&[&[0x42; 16]]));
+            assert_eq!(
+                decode_all(&mut decoder, &bytes),
+                [None, Some(vec![0x42; 16])]
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_escapes() {
+        // An escape followed by a delimiter invalidates the frame
+        let mut decoder = Decoder::new(Framing::Slip, Crc::None, false, 1_500);
+        assert_eq!(
+            decode_all(&mut decoder, &[SLIP_END, 0x01, SLIP_ESC, SLIP_END]),
+            [None]
+        );
+        let mut decoder = Decoder::new(Framing::Slip, Crc::None, false, 1_500);
+        assert_eq!(
+            decode_all(&mut decoder, &[SLIP_END, SLIP_ESC, 0x01, 0x02, SLIP_END]),
+            [None]
+        );
+        let mut decoder = Decoder::new(Framing::Cobs, Crc::None, true, 1_500);
+        assert_eq!(
+            decode_all(
+                &mut decoder,
+                &[0x00, 0x02, 0x01, XESC, 0x00, 0x02, 0x01, 0x00]
+            ),
+            [None, Some(vec![0x01])]
+        );
+    }
+
+    #[test]
+    fn parse() {
+        for framing in FRAMINGS {
+            assert_eq!(framing.to_string().parse::<Framing>().unwrap(), framing);
+        }
+        for crc in CRCS {
+            assert_eq!(crc.to_string().parse::<Crc>().unwrap(), crc);
+        }
+        assert!("hdlc".parse::<Framing>().is_err());
+        assert!("crc8".parse::<Crc>().is_err());
+    }
+}
@@ -17,33 +17,51 @@
 //! This crate is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
+mod frame;
 mod unicast;
 
 use async_trait::async_trait;
+pub use frame::{Crc, Framing};
 use std::str::FromStr;
+pub use tokio_serial::FlowControl;
 pub use unicast::*;
 use zenoh_core::zconfigurable;
 use zenoh_link_commons::LocatorInspector;
 use zenoh_protocol::core::{endpoint::Address, EndPoint, Locator};
-use zenoh_result::ZResult;
+use zenoh_result::{bail, zerror, ZResult};
 
 // Maximum MTU (Serial PDU) in bytes.
-const SERIAL_MAX_MTU: u16 = z_serial::MAX_MTU as u16;
+const SERIAL_MAX_MTU: u16 = 1_500;
 
 const DEFAULT_BAUDRATE: u32 = 9_600;
 
 const DEFAULT_EXCLUSIVE: bool = true;
 
+const DEFAULT_FRAMING: Framing = Framing::Cobs;
+
+const DEFAULT_CRC: Crc = Crc::Crc32;
+
+// No retransmissions by default, i.e. the link is best effort
+const DEFAULT_WINDOW: u16 = 0;
+
+const DEFAULT_FLOW_CONTROL: FlowControl = FlowControl::None;
+
 pub const SERIAL_LOCATOR_PREFIX: &str = "serial";
 
 const SERIAL_MTU_LIMIT: u16 = SERIAL_MAX_MTU;
 
+// Maximum number of unacknowledged frames, so that the sequence numbers never wrap within a window
+const SERIAL_MAX_WINDOW: u16 = 1_024;
+
 zconfigurable! {
     // Default MTU (UDP PDU) in bytes.
     static ref SERIAL_DEFAULT_MTU: u16 = SERIAL_MTU_LIMIT;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref SERIAL_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds to wait for an acknowledgment before retransmitting,
+    // on top of the time needed to send a full retransmission window at the port baud rate.
+    static ref SERIAL_RETRANSMIT_TIME: u64 = 100;
 }
 
 #[derive(Default, Clone, Copy)]
@@ -75,6 +93,48 @@ pub fn get_exclusive(endpoint: &EndPoint) -> bool {
     }
 }
 
+pub fn get_framing(endpoint: &EndPoint) -> ZResult<Framing> {
+    match endpoint.config().get(config::PORT_FRAMING_RAW) {
+        Some(framing) => framing.parse(),
+        None => Ok(DEFAULT_FRAMING),
+    }
+}
+
+pub fn get_crc(endpoint: &EndPoint) -> ZResult<Crc> {
+    match endpoint.config().get(config::PORT_CRC_RAW) {
+        Some(crc) => crc.parse(),
+        None => Ok(DEFAULT_CRC),
+    }
+}
+
+pub fn get_window(endpoint: &EndPoint) -> ZResult<u16> {
+    match endpoint.config().get(config::PORT_WINDOW_RAW) {
+        Some(window) => match u16::from_str(window) {
+            Ok(window) if window <= SERIAL_MAX_WINDOW => Ok(window),
+            _ => bail!(
+                "Invalid serial retransmission window: {}. It must be at most {}.",
+                window,
+                SERIAL_MAX_WINDOW
+            ),
+        },
+        None => Ok(DEFAULT_WINDOW),
+    }
+}
+
+pub fn get_flow_control(endpoint: &EndPoint) -> ZResult<FlowControl> {
+    match endpoint.config().get(config::PORT_FLOW_CONTROL_RAW) {
+        Some("none") => Ok(FlowControl::None),
+        Some("xonxoff") => Ok(FlowControl::Software),
+        Some("rtscts") => Ok(FlowControl::Hardware),
+        Some(flow_control) => Err(zerror!(
+            "Unknown serial flow control: {}. Supported: none, xonxoff, rtscts.",
+            flow_control
+        )
+        .into()),
+        None => Ok(DEFAULT_FLOW_CONTROL),
+    }
+}
+
 pub fn get_unix_path_as_string(address: Address<'_>) -> String {
     address.as_str().to_owned()
 }
@@ -82,4 +142,14 @@ pub fn get_unix_path_as_string(address: Address<'_>) -> String {
 pub mod config {
     pub const PORT_BAUD_RATE_RAW: &str = "baudrate";
     pub const PORT_EXCLUSIVE_RAW: &str = "exclusive";
+    /// The byte-stuffing of the frames: "cobs" (default) or "slip".
+    pub const PORT_FRAMING_RAW: &str = "framing";
+    /// The checksum of the frames: "none", "crc16" or "crc32" (default).
+    pub const PORT_CRC_RAW: &str = "crc";
+    /// The number of frames that can be sent without being acknowledged.
+    /// The default 0 disables the acknowledgments and retransmissions.
+    /// It is not negotiated: the retransmissions must be enabled or disabled on both ends.
+    pub const PORT_WINDOW_RAW: &str = "window";
+    /// The flow control of the port: "none" (default), "xonxoff" or "rtscts".
+    pub const PORT_FLOW_CONTROL_RAW: &str = "flow_control";
 }
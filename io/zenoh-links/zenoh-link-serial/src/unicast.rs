@@ -14,43 +14,183 @@
 
 use async_trait::async_trait;
 use std::cell::UnsafeCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::path::Path;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc,
+    Arc, Mutex,
 };
-use std::time::Duration;
-use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{Mutex as AsyncMutex, Notify, RwLock as AsyncRwLock};
 use tokio::task::JoinHandle;
+use tokio_serial::{ClearBuffer, SerialPort, SerialPortBuilderExt, SerialStream};
 use tokio_util::sync::CancellationToken;
-use zenoh_core::{zasynclock, zasyncread, zasyncwrite};
+use zenoh_core::{zasynclock, zasyncread, zasyncwrite, zlock};
 use zenoh_link_commons::{
     ConstructibleLinkManagerUnicast, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
     NewLinkChannelSender,
 };
 use zenoh_protocol::core::{EndPoint, Locator};
-use zenoh_result::{zerror, ZResult};
+use zenoh_result::{bail, zerror, ZResult};
 
-use z_serial::ZSerial;
-
-use crate::get_exclusive;
+use crate::frame::{Crc, Decoded, Decoder, Encoder, Framing};
+use crate::{get_crc, get_exclusive, get_flow_control, get_framing, get_window, FlowControl};
 
 use super::{
     get_baud_rate, get_unix_path_as_string, SERIAL_ACCEPT_THROTTLE_TIME, SERIAL_DEFAULT_MTU,
-    SERIAL_LOCATOR_PREFIX,
+    SERIAL_LOCATOR_PREFIX, SERIAL_RETRANSMIT_TIME,
 };
 
+// The frame header when retransmissions are enabled: the frame kind and a sequence number
+const FRAME_DATA: u8 = 0x01;
+const FRAME_ACK: u8 = 0x02;
+const FRAME_HEADER_LEN: usize = 3;
+
+const READ_BUFFER_SIZE: usize = 4_096;
+
+struct SerialConfig {
+    baud_rate: u32,
+    exclusive: bool,
+    framing: Framing,
+    crc: Crc,
+    window: u16,
+    flow_control: FlowControl,
+}
+
+impl SerialConfig {
+    fn new(endpoint: &EndPoint) -> ZResult<Self> {
+        Ok(Self {
+            baud_rate: get_baud_rate(endpoint),
+            exclusive: get_exclusive(endpoint),
+            framing: get_framing(endpoint)?,
+            crc: get_crc(endpoint)?,
+            window: get_window(endpoint)?,
+            flow_control: get_flow_control(endpoint)?,
+        })
+    }
+
+    fn open(&self, path: &str) -> ZResult<SerialStream> {
+        #[allow(unused_mut)]
+        let mut port = tokio_serial::new(path, self.baud_rate)
+            .flow_control(self.flow_control)
+            .open_native_async()
+            .map_err(|e| zerror!("{}", e))?;
+        #[cfg(unix)]
+        port.set_exclusive(self.exclusive)
+            .map_err(|e| zerror!("{}", e))?;
+        Ok(port)
+    }
+
+    // The time to wait for an acknowledgment: the time to send the frame and its acknowledgment,
+    // plus a frame of the peer possibly in between, at 10 bits per byte on the line.
+    fn retransmit_timeout(&self) -> Duration {
+        let frame_len = *SERIAL_DEFAULT_MTU as u64 + (FRAME_HEADER_LEN + self.crc.len()) as u64;
+        Duration::from_millis(*SERIAL_RETRANSMIT_TIME)
+            + Duration::from_micros(3 * frame_len * 10 * 1_000_000 / self.baud_rate as u64)
+    }
+}
+
+impl fmt::Display for SerialConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "baudrate {}, exclusive {}, framing {}, crc {}, window {} and flow control {:?}",
+            self.baud_rate, self.exclusive, self.framing, self.crc, self.window, self.flow_control
+        )
+    }
+}
+
+struct ReadState {
+    decoder: Decoder,
+    // The bytes read from the port and not decoded yet
+    buffer: Box<[u8]>,
+    start: usize,
+    end: usize,
+}
+
+// A go-back-N retransmission scheme, with cumulative acknowledgments
+struct Arq {
+    window: usize,
+    timeout: Duration,
+    state: Mutex<ArqState>,
+    acked: Notify,
+}
+
+struct ArqState {
+    // The sequence number of the next frame to send
+    next_seq: u16,
+    // The frames sent and not acknowledged yet, as written on the line
+    unacked: VecDeque<(u16, Vec<u8>)>,
+    // The last time the oldest unacknowledged frame was (re)sent or some frames were acknowledged
+    timer: Instant,
+    // The sequence number of the next frame to receive
+    expected: u16,
+}
+
+impl ArqState {
+    fn new() -> Self {
+        Self {
+            next_seq: 0,
+            unacked: VecDeque::new(),
+            timer: Instant::now(),
+            expected: 0,
+        }
+    }
+
+    // Assigns the next sequence number to a frame
+    fn assign_seq(&mut self) -> u16 {
+        let seq = self.next_seq;
+        self.next_seq = seq.wrapping_add(1);
+        seq
+    }
+
+    // Keeps the sent frame with the given sequence number until it is acknowledged
+    fn push(&mut self, seq: u16, frame: Vec<u8>) {
+        if self.unacked.is_empty() {
+            self.timer = Instant::now();
+        }
+        self.unacked.push_back((seq, frame));
+    }
+
+    // Drops the frames acknowledged by the given acknowledgment, i.e. the sequence number of the
+    // next frame expected by the peer, returning whether some were. The stale or out of window
+    // acknowledgments acknowledge nothing.
+    fn acknowledge(&mut self, ack: u16) -> bool {
+        let Some(&(base, _)) = self.unacked.front() else {
+            return false;
+        };
+        let acked = ack.wrapping_sub(base) as usize;
+        if acked == 0 || acked > self.unacked.len() {
+            return false;
+        }
+        self.unacked.drain(..acked);
+        self.timer = Instant::now();
+        true
+    }
+
+    // Accepts the received frame with the given sequence number if it is the expected one,
+    // the out of order ones being dropped until they are retransmitted in order
+    fn receive(&mut self, seq: u16) -> bool {
+        let accepted = seq == self.expected;
+        if accepted {
+            self.expected = seq.wrapping_add(1);
+        }
+        accepted
+    }
+}
+
 struct LinkUnicastSerial {
-    // The underlying serial port as returned by ZSerial (tokio-serial)
-    // NOTE: ZSerial requires &mut for read and write operations. This means
+    // The underlying serial port as returned by tokio-serial
+    // NOTE: SerialStream requires &mut for read and write operations. This means
     //       that concurrent reads and writes are not possible. To achieve that,
     //       we use an UnsafeCell for interior mutability. Using an UnsafeCell
     //       is safe in our case since the transmission and reception logic
     //       already ensures that no concurrent reads or writes can happen on
     //       the same stream: there is only one task at the time that writes on
     //       the stream and only one task at the time that reads from the stream.
-    port: UnsafeCell<ZSerial>,
+    port: UnsafeCell<SerialStream>,
     // The serial port path
     src_locator: Locator,
     // The serial destination path (random UUIDv4)
@@ -58,8 +198,10 @@ struct LinkUnicastSerial {
     // A flag that tells if the link is connected or not
     is_connected: Arc<AtomicBool>,
     // Locks for reading and writing ends of the serial.
-    write_lock: AsyncMutex<()>,
-    read_lock: AsyncMutex<()>,
+    write_lock: AsyncMutex<Encoder>,
+    read_lock: AsyncMutex<ReadState>,
+    // The retransmission state, if enabled
+    arq: Option<Arq>,
 }
 
 unsafe impl Send for LinkUnicastSerial {}
@@ -67,26 +209,64 @@ unsafe impl Sync for LinkUnicastSerial {}
 
 impl LinkUnicastSerial {
     fn new(
-        port: UnsafeCell<ZSerial>,
+        port: UnsafeCell<SerialStream>,
         src_path: &str,
         dst_path: &str,
         is_connected: Arc<AtomicBool>,
-    ) -> Self {
-        Self {
+        config: &SerialConfig,
+    ) -> Arc<Self> {
+        let xonxoff = config.flow_control == FlowControl::Software;
+        let mut max_len = *SERIAL_DEFAULT_MTU as usize;
+        let arq = (config.window > 0).then(|| {
+            max_len += FRAME_HEADER_LEN;
+            Arq {
+                window: config.window as usize,
+                timeout: config.retransmit_timeout(),
+                state: Mutex::new(ArqState::new()),
+                acked: Notify::new(),
+            }
+        });
+
+        let link = Arc::new(Self {
             port,
             src_locator: Locator::new(SERIAL_LOCATOR_PREFIX, src_path, "").unwrap(),
             dst_locator: Locator::new(SERIAL_LOCATOR_PREFIX, dst_path, "").unwrap(),
             is_connected,
-            write_lock: AsyncMutex::new(()),
-            read_lock: AsyncMutex::new(()),
+            write_lock: AsyncMutex::new(Encoder::new(config.framing, config.crc, xonxoff)),
+            read_lock: AsyncMutex::new(ReadState {
+                decoder: Decoder::new(config.framing, config.crc, xonxoff, max_len),
+                buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+                start: 0,
+                end: 0,
+            }),
+            arq,
+        });
+
+        if let Some(arq) = link.arq.as_ref() {
+            // Retransmit the frames whose acknowledgment is overdue while there is nothing to write
+            let timeout = arq.timeout;
+            let c_link = Arc::downgrade(&link);
+            zenoh_runtime::ZRuntime::TX.spawn(async move {
+                loop {
+                    tokio::time::sleep(timeout / 2).await;
+                    let Some(link) = c_link.upgrade() else {
+                        break;
+                    };
+                    if let Err(e) = link.retransmit_overdue().await {
+                        tracing::debug!("{}", e);
+                    }
+                }
+            });
         }
+
+        link
     }
 
     // NOTE: It is safe to suppress Clippy warning since no concurrent reads
     //       or concurrent writes will ever happen. The write_lock and read_lock
     //       are respectively acquired in any read and write operation.
     #[allow(clippy::mut_from_ref)]
-    fn get_port_mut(&self) -> &mut ZSerial {
+    fn get_port_mut(&self) -> &mut SerialStream {
         unsafe { &mut *self.port.get() }
     }
 
@@ -107,6 +287,120 @@ impl LinkUnicastSerial {
         }
         false
     }
+
+    async fn write_frame(&self, parts: &[&[u8]]) -> ZResult<()> {
+        let mut encoder = zasynclock!(self.write_lock);
+        let bytes = encoder.encode(parts);
+        self.get_port_mut().write_all(bytes).await.map_err(|e| {
+            let e = zerror!("Unable to write on Serial link {}: {}", self, e);
+            tracing::error!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn write_data(&self, arq: &Arq, buffer: &[u8]) -> ZResult<()> {
+        // Wait for the retransmission window to have some room
+        loop {
+            let acked = arq.acked.notified();
+            if zlock!(arq.state).unacked.len() < arq.window {
+                break;
+            }
+            if tokio::time::timeout(arq.timeout, acked).await.is_err() {
+                self.retransmit(arq).await?;
+            }
+        }
+
+        let mut encoder = zasynclock!(self.write_lock);
+        let seq = zlock!(arq.state).assign_seq();
+        let bytes = encoder.encode(&[&[FRAME_DATA], &seq.to_le_bytes(), buffer]);
+        zlock!(arq.state).push(seq, bytes.to_vec());
+        self.get_port_mut().write_all(bytes).await.map_err(|e| {
+            let e = zerror!("Unable to write on Serial link {}: {}", self, e);
+            tracing::error!("{}", e);
+            e.into()
+        })
+    }
+
+    async fn retransmit(&self, arq: &Arq) -> ZResult<()> {
+        let _guard = zasynclock!(self.write_lock);
+        let frames: Vec<Vec<u8>> = {
+            let mut state = zlock!(arq.state);
+            state.timer = Instant::now();
+            state.unacked.iter().map(|(_, f)| f.clone()).collect()
+        };
+        tracing::trace!(
+            "Retransmitting {} frames on Serial link {}",
+            frames.len(),
+            self
+        );
+        for frame in frames {
+            self.get_port_mut()
+                .write_all(&frame)
+                .await
+                .map_err(|e| zerror!("Unable to retransmit on Serial link {}: {}", self, e))?;
+        }
+        Ok(())
+    }
+
+    async fn retransmit_overdue(&self) -> ZResult<()> {
+        let Some(arq) = self.arq.as_ref() else {
+            return Ok(());
+        };
+        let overdue = {
+            let state = zlock!(arq.state);
+            !state.unacked.is_empty() && state.timer.elapsed() >= arq.timeout
+        };
+        if overdue && self.is_connected.load(Ordering::Acquire) {
+            self.retransmit(arq).await?;
+        }
+        Ok(())
+    }
+
+    fn acknowledge(&self, arq: &Arq, ack: u16) {
+        if zlock!(arq.state).acknowledge(ack) {
+            arq.acked.notify_one();
+        }
+    }
+
+    async fn read_frame(&self, state: &mut ReadState) -> ZResult<()> {
+        loop {
+            if state.start == state.end {
+                let n = self
+                    .get_port_mut()
+                    .read(&mut state.buffer)
+                    .await
+                    .map_err(|e| zerror!("Read error on Serial link {}: {}", self, e))?;
+                if n == 0 {
+                    bail!("Serial link {} has been closed", self);
+                }
+                state.start = 0;
+                state.end = n;
+            }
+            let (n, decoded) = state.decoder.decode(&state.buffer[state.start..state.end]);
+            state.start += n;
+            match decoded {
+                Decoded::Frame => return Ok(()),
+                Decoded::Invalid => {
+                    tracing::debug!("Dropped a corrupted frame on Serial link {}", self)
+                }
+                Decoded::Incomplete => {}
+            }
+        }
+    }
+}
+
+fn copy_payload(link: &LinkUnicastSerial, payload: &[u8], buffer: &mut [u8]) -> ZResult<usize> {
+    let len = payload.len();
+    if len > buffer.len() {
+        bail!(
+            "Read error on Serial link {}: frame of {} bytes larger than the buffer of {} bytes",
+            link,
+            len,
+            buffer.len()
+        );
+    }
+    buffer[..len].copy_from_slice(payload);
+    Ok(len)
 }
 
 #[async_trait]
@@ -114,7 +408,7 @@ impl LinkUnicastTrait for LinkUnicastSerial {
     async fn close(&self) -> ZResult<()> {
         tracing::trace!("Closing Serial link: {}", self);
         let _guard = zasynclock!(self.write_lock);
-        self.get_port_mut().clear().map_err(|e| {
+        self.get_port_mut().clear(ClearBuffer::All).map_err(|e| {
             let e = zerror!("Unable to close Serial link {}: {}", self, e);
             tracing::error!("{}", e);
             e
@@ -124,12 +418,18 @@ impl LinkUnicastTrait for LinkUnicastSerial {
     }
 
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
-        let _guard = zasynclock!(self.write_lock);
-        self.get_port_mut().write(buffer).await.map_err(|e| {
-            let e = zerror!("Unable to write on Serial link {}: {}", self, e);
-            tracing::error!("{}", e);
-            e
-        })?;
+        if buffer.len() > *SERIAL_DEFAULT_MTU as usize {
+            bail!(
+                "Unable to write on Serial link {}: {} bytes exceed the MTU of {} bytes",
+                self,
+                buffer.len(),
+                *SERIAL_DEFAULT_MTU
+            );
+        }
+        match self.arq.as_ref() {
+            Some(arq) => self.write_data(arq, buffer).await?,
+            None => self.write_frame(&[buffer]).await?,
+        }
         Ok(buffer.len())
     }
 
@@ -142,17 +442,33 @@ impl LinkUnicastTrait for LinkUnicastSerial {
     }
 
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let mut state = zasynclock!(self.read_lock);
         loop {
-            let _guard = zasynclock!(self.read_lock);
-            match self.get_port_mut().read_msg(buffer).await {
-                Ok(read) => return Ok(read),
-                Err(e) => {
-                    let e = zerror!("Read error on Serial link {}: {}", self, e);
-                    tracing::error!("{}", e);
-                    drop(_guard);
-                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
-                    continue;
+            self.read_frame(&mut state).await?;
+            let frame = state.decoder.frame();
+            let Some(arq) = self.arq.as_ref() else {
+                return copy_payload(self, frame, buffer);
+            };
+            if frame.len() < FRAME_HEADER_LEN {
+                continue;
+            }
+            let seq = u16::from_le_bytes([frame[1], frame[2]]);
+            match frame[0] {
+                FRAME_ACK => self.acknowledge(arq, seq),
+                FRAME_DATA => {
+                    let (accepted, expected) = {
+                        let mut arq_state = zlock!(arq.state);
+                        (arq_state.receive(seq), arq_state.expected)
+                    };
+                    // Out of order frames are dropped and answered with the last acknowledgment,
+                    // which also covers the retransmissions caused by a lost acknowledgment
+                    self.write_frame(&[&[FRAME_ACK], &expected.to_le_bytes()])
+                        .await?;
+                    if accepted {
+                        return copy_payload(self, &frame[FRAME_HEADER_LEN..], buffer);
+                    }
                 }
+                kind => tracing::debug!("Unknown frame kind {} on Serial link {}", kind, self),
             }
         }
     }
@@ -185,8 +501,13 @@ impl LinkUnicastTrait for LinkUnicastSerial {
     fn get_interface_names(&self) -> Vec<String> {
         // For POSIX systems, the interface name refers to the file name without the path
         // e.g. for serial port "/dev/ttyUSB0" interface name will be "ttyUSB0"
-        match z_serial::get_available_port_names() {
-            Ok(interfaces) => {
+        match tokio_serial::available_ports() {
+            Ok(ports) => {
+                let interfaces: Vec<String> = ports
+                    .iter()
+                    .filter_map(|p| Path::new(&p.port_name).file_name()?.to_str())
+                    .map(|name| name.to_owned())
+                    .collect();
                 tracing::trace!("get_interface_names for serial: {:?}", interfaces);
                 interfaces
             }
@@ -199,7 +520,8 @@ impl LinkUnicastTrait for LinkUnicastSerial {
 
     #[inline(always)]
     fn is_reliable(&self) -> bool {
-        false
+        // The retransmissions make the link reliable
+        self.arq.is_some()
     }
 
     #[inline(always)]
@@ -270,10 +592,9 @@ impl ConstructibleLinkManagerUnicast<()> for LinkManagerUnicastSerial {
 impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
         let path = get_unix_path_as_string(endpoint.address());
-        let baud_rate = get_baud_rate(&endpoint);
-        let exclusive = get_exclusive(&endpoint);
-        tracing::trace!("Opening Serial Link on device {path:?}, with baudrate {baud_rate} and exclusive set as {exclusive}");
-        let port = ZSerial::new(path.clone(), baud_rate, exclusive).map_err(|e| {
+        let config = SerialConfig::new(&endpoint)?;
+        tracing::trace!("Opening Serial Link on device {path:?}, with {config}");
+        let port = config.open(&path).map_err(|e| {
             let e = zerror!(
                 "Can not create a new Serial link bound to {:?}: {}",
                 path,
@@ -284,22 +605,22 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
         })?;
 
         // Create Serial link
-        let link = Arc::new(LinkUnicastSerial::new(
+        let link = LinkUnicastSerial::new(
             UnsafeCell::new(port),
             &path,
             &path,
             Arc::new(AtomicBool::new(true)),
-        ));
+            &config,
+        );
 
         Ok(LinkUnicast(link))
     }
 
     async fn new_listener(&self, endpoint: EndPoint) -> ZResult<Locator> {
         let path = get_unix_path_as_string(endpoint.address());
-        let baud_rate = get_baud_rate(&endpoint);
-        let exclusive = get_exclusive(&endpoint);
-        tracing::trace!("Creating Serial listener on device {path:?}, with baudrate {baud_rate} and exclusive set as {exclusive}");
-        let port = ZSerial::new(path.clone(), baud_rate, exclusive).map_err(|e| {
+        let config = SerialConfig::new(&endpoint)?;
+        tracing::trace!("Creating Serial listener on device {path:?}, with {config}");
+        let port = config.open(&path).map_err(|e| {
             let e = zerror!(
                 "Can not create a new Serial link bound to {:?}: {}",
                 path,
@@ -312,12 +633,13 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
         // Creating the link
         let is_connected = Arc::new(AtomicBool::new(false));
         let dst_path = format!("{}", uuid::Uuid::new_v4());
-        let link = Arc::new(LinkUnicastSerial::new(
+        let link = LinkUnicastSerial::new(
             UnsafeCell::new(port),
             &path,
             &dst_path,
             is_connected.clone(),
-        ));
+            &config,
+        );
 
         // Spawn the accept loop for the listener
         let token = CancellationToken::new();
@@ -433,3 +755,200 @@ async fn accept_read_task(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arq_state(next_seq: u16, frames: usize) -> ArqState {
+        let mut state = ArqState::new();
+        state.next_seq = next_seq;
+        for _ in 0..frames {
+            let seq = state.assign_seq();
+            state.push(seq, seq.to_le_bytes().to_vec());
+        }
+        state
+    }
+
+    fn unacked(state: &ArqState) -> Vec<u16> {
+        state.unacked.iter().map(|(seq, _)| *seq).collect()
+    }
+
+    #[test]
+    fn arq_acknowledge() {
+        let mut state = arq_state(0, 4);
+        assert_eq!(unacked(&state), [0, 1, 2, 3]);
+        // Nothing is acknowledged by a stale or an out of window acknowledgment
+        assert!(!state.acknowledge(0));
+        assert!(!state.acknowledge(5));
+        assert!(!state.acknowledge(u16::MAX));
+        assert_eq!(unacked(&state), [0, 1, 2, 3]);
+        // The acknowledgments are cumulative
+        assert!(state.acknowledge(2));
+        assert_eq!(unacked(&state), [2, 3]);
+        assert!(!state.acknowledge(2));
+        assert!(!state.acknowledge(1));
+        assert!(state.acknowledge(4));
+        assert!(state.unacked.is_empty());
+        assert!(!state.acknowledge(4));
+    }
+
+    #[test]
+    fn arq_acknowledge_wrapping() {
+        let mut state = arq_state(u16::MAX - 1, 4);
+        assert_eq!(unacked(&state), [u16::MAX - 1, u16::MAX, 0, 1]);
+        assert!(!state.acknowledge(u16::MAX - 1));
+        assert!(state.acknowledge(1));
+        assert_eq!(unacked(&state), [1]);
+        assert!(!state.acknowledge(u16::MAX));
+        assert!(state.acknowledge(2));
+        assert!(state.unacked.is_empty());
+    }
+
+    #[test]
+    fn arq_receive() {
+        let mut state = ArqState::new();
+        assert!(state.receive(0));
+        // Duplicated and out of order frames are dropped
+        assert!(!state.receive(0));
+        assert!(!state.receive(2));
+        assert_eq!(state.expected, 1);
+        assert!(state.receive(1));
+        assert!(state.receive(2));
+        assert_eq!(state.expected, 3);
+
+        state.expected = u16::MAX;
+        assert!(state.receive(u16::MAX));
+        assert_eq!(state.expected, 0);
+        assert!(state.receive(0));
+    }
+
+    #[cfg(unix)]
+    mod link {
+        use super::super::*;
+
+        const TIMEOUT: Duration = Duration::from_secs(10);
+
+        fn config(framing: Framing, crc: Crc, window: u16) -> SerialConfig {
+            SerialConfig {
+                baud_rate: 115_200,
+                exclusive: false,
+                framing,
+                crc,
+                window,
+                flow_control: FlowControl::None,
+            }
+        }
+
+        // Two links on the ends of a pseudo terminal
+        fn pair(config: &SerialConfig) -> (Arc<LinkUnicastSerial>, Arc<LinkUnicastSerial>) {
+            let (a, b) = SerialStream::pair().unwrap();
+            let link = |port, path| {
+                let is_connected = Arc::new(AtomicBool::new(true));
+                LinkUnicastSerial::new(UnsafeCell::new(port), path, path, is_connected, config)
+            };
+            (link(a, "a"), link(b, "b"))
+        }
+
+        async fn read(link: &LinkUnicastSerial) -> Vec<u8> {
+            let mut buffer = vec![0; *SERIAL_DEFAULT_MTU as usize];
+            let n = tokio::time::timeout(TIMEOUT, link.read(&mut buffer))
+                .await
+                .unwrap()
+                .unwrap();
+            buffer.truncate(n);
+            buffer
+        }
+
+        fn payload(len: usize) -> Vec<u8> {
+            (0..len).map(|i| (i % 256) as u8).collect()
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn link_roundtrip() {
+            for framing in [Framing::Cobs, Framing::Slip] {
+                for crc in [Crc::None, Crc::Crc16, Crc::Crc32] {
+                    for window in [0, 4] {
+                        let (a, b) = pair(&config(framing, crc, window));
+                        assert_eq!(a.is_reliable(), window > 0);
+                        for len in [1, 100, *SERIAL_DEFAULT_MTU as usize] {
+                            a.write_all(&payload(len)).await.unwrap();
+                            assert_eq!(read(&b).await, payload(len));
+                            b.write_all(&payload(len)).await.unwrap();
+                            assert_eq!(read(&a).await, payload(len));
+                        }
+                    }
+                }
+            }
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn link_garbage() {
+            let (a, b) = pair(&config(Framing::Cobs, Crc::Crc32, 0));
+            // The garbage on the line is dropped up to the next frame
+            a.get_port_mut().write_all(&[0x42; 64]).await.unwrap();
+            a.write_all(b"zenoh").await.unwrap();
+            assert_eq!(read(&b).await, b"zenoh");
+
+            // A corrupted frame is dropped
+            let mut encoder = Encoder::new(Framing::Cobs, Crc::Crc32, false);
+            let mut frame = encoder.encode(&[b"corrupted"]).to_vec();
+            frame[4] ^= 0x01;
+            a.get_port_mut().write_all(&frame).await.unwrap();
+            a.write_all(b"zenoh").await.unwrap();
+            assert_eq!(read(&b).await, b"zenoh");
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn link_retransmission() {
+            let (a, b) = pair(&config(Framing::Cobs, Crc::Crc32, 4));
+            // Process the acknowledgments received by a
+            let c_a = a.clone();
+            let acks = tokio::spawn(async move { read(&c_a).await });
+
+            // The first frame is lost, the second one is received out of order
+            a.write_all(b"lost").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            b.get_port_mut().clear(ClearBuffer::Input).unwrap();
+            a.write_all(b"out of order").await.unwrap();
+
+            // Both are retransmitted in order
+            assert_eq!(read(&b).await, b"lost");
+            assert_eq!(read(&b).await, b"out of order");
+            let arq = a.arq.as_ref().unwrap();
+            tokio::time::timeout(TIMEOUT, async {
+                while !zlock!(arq.state).unacked.is_empty() {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .unwrap();
+            acks.abort();
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn link_window() {
+            let (a, b) = pair(&config(Framing::Slip, Crc::Crc16, 2));
+            let c_a = a.clone();
+            let acks = tokio::spawn(async move { read(&c_a).await });
+
+            // The writes block once the window is full, until the frames are acknowledged
+            a.write_all(b"0").await.unwrap();
+            a.write_all(b"1").await.unwrap();
+            let c_a = a.clone();
+            let blocked = tokio::spawn(async move { c_a.write_all(b"2").await });
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            assert!(!blocked.is_finished());
+
+            assert_eq!(read(&b).await, b"0");
+            assert_eq!(read(&b).await, b"1");
+            tokio::time::timeout(TIMEOUT, blocked)
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(read(&b).await, b"2");
+            acks.abort();
+        }
+    }
+}
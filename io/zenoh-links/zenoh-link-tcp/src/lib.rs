@@ -25,7 +25,9 @@ use zenoh_protocol::core::{endpoint::Address, Locator};
 use zenoh_result::{zerror, ZResult};
 
 mod unicast;
+mod utils;
 pub use unicast::*;
+pub use utils::{TcpConfigurator, TcpSocketConfig};
 
 // Default MTU (TCP PDU) in bytes.
 // NOTE: Since TCP is a byte-stream oriented transport, theoretically it has
@@ -63,6 +65,24 @@ zconfigurable! {
     static ref TCP_ACCEPT_THROTTLE_TIME: u64 = 100_000;
 }
 
+pub mod config {
+    /// The idle time in seconds before the first keepalive probe is sent.
+    pub const TCP_KEEPALIVE_TIME: &str = "keepalive_time";
+    /// The time in seconds between two keepalive probes.
+    pub const TCP_KEEPALIVE_INTERVAL: &str = "keepalive_interval";
+    /// The number of unanswered keepalive probes before the connection is dropped.
+    pub const TCP_KEEPALIVE_PROBES: &str = "keepalive_probes";
+    /// The time in milliseconds transmitted data may stay unacknowledged before the connection
+    /// is dropped (Linux only).
+    pub const TCP_USER_TIMEOUT: &str = "user_timeout";
+    /// Whether Nagle's algorithm is disabled (default `true`).
+    pub const TCP_NODELAY: &str = "nodelay";
+    /// The size in bytes of the socket send buffer.
+    pub const TCP_SO_SNDBUF: &str = "so_sndbuf";
+    /// The size in bytes of the socket receive buffer.
+    pub const TCP_SO_RCVBUF: &str = "so_rcvbuf";
+}
+
 pub async fn get_tcp_addrs(address: Address<'_>) -> ZResult<impl Iterator<Item = SocketAddr>> {
     let iter = tokio::net::lookup_host(address.as_str().to_string())
         .await
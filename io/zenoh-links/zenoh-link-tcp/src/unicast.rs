@@ -16,26 +16,33 @@ use std::cell::UnsafeCell;
 use std::convert::TryInto;
 use std::fmt;
 use std::net::SocketAddr;
+#[cfg(target_os = "linux")]
+use std::os::fd::AsRawFd;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio_util::sync::CancellationToken;
+#[cfg(target_os = "linux")]
+use zenoh_link_commons::UringStream;
 use zenoh_link_commons::{
-    get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
-    ListenersUnicastIP, NewLinkChannelSender, BIND_INTERFACE,
+    get_ip_interface_names, write_all_vectored, LinkManagerUnicastTrait, LinkOsStats, LinkUnicast,
+    LinkUnicastTrait, ListenersUnicastIP, NewLinkChannelSender, IO_URING,
 };
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{bail, zerror, Error as ZError, ZResult};
 
 use super::{
-    get_tcp_addrs, TCP_ACCEPT_THROTTLE_TIME, TCP_DEFAULT_MTU, TCP_LINGER_TIMEOUT,
+    get_tcp_addrs, TcpSocketConfig, TCP_ACCEPT_THROTTLE_TIME, TCP_DEFAULT_MTU, TCP_LINGER_TIMEOUT,
     TCP_LOCATOR_PREFIX,
 };
-use tokio::net::{TcpListener, TcpSocket, TcpStream};
+use tokio::net::{TcpListener, TcpStream};
 
 pub struct LinkUnicastTcp {
     // The underlying socket as returned from the tokio library
     socket: UnsafeCell<TcpStream>,
+    // The io_uring backend of the socket, if enabled
+    #[cfg(target_os = "linux")]
+    uring: Option<UringStream>,
     // The source socket address of this link (address used on the local host)
     src_addr: SocketAddr,
     src_locator: Locator,
@@ -47,11 +54,17 @@ pub struct LinkUnicastTcp {
 unsafe impl Sync for LinkUnicastTcp {}
 
 impl LinkUnicastTcp {
-    fn new(socket: TcpStream, src_addr: SocketAddr, dst_addr: SocketAddr) -> LinkUnicastTcp {
-        // Set the TCP nodelay option
-        if let Err(err) = socket.set_nodelay(true) {
+    fn new(
+        socket: TcpStream,
+        src_addr: SocketAddr,
+        dst_addr: SocketAddr,
+        config: &TcpSocketConfig,
+        io_uring: bool,
+    ) -> LinkUnicastTcp {
+        // Set the TCP nodelay, keepalive and user timeout options
+        if let Err(err) = config.set_stream_options(&socket) {
             tracing::warn!(
-                "Unable to set NODEALY option on TCP link {} => {}: {}",
+                "Unable to set the socket options on TCP link {} => {}: {}",
                 src_addr,
                 dst_addr,
                 err
@@ -70,9 +83,38 @@ impl LinkUnicastTcp {
             );
         }
 
+        // Switch the socket to the io_uring backend if requested
+        #[cfg(target_os = "linux")]
+        let uring = if io_uring {
+            match UringStream::new(socket.as_raw_fd()) {
+                Ok(uring) => uring,
+                Err(err) => {
+                    tracing::warn!(
+                        "Unable to use io_uring on TCP link {} => {}: {}",
+                        src_addr,
+                        dst_addr,
+                        err
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "linux"))]
+        if io_uring {
+            tracing::warn!(
+                "io_uring is only supported on Linux, ignoring it on TCP link {} => {}",
+                src_addr,
+                dst_addr
+            );
+        }
+
         // Build the Tcp object
         LinkUnicastTcp {
             socket: UnsafeCell::new(socket),
+            #[cfg(target_os = "linux")]
+            uring,
             src_addr,
             src_locator: Locator::new(TCP_LOCATOR_PREFIX, src_addr.to_string(), "").unwrap(),
             dst_addr,
@@ -98,6 +140,14 @@ impl LinkUnicastTrait for LinkUnicastTcp {
     }
 
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.write(buffer).await.map_err(|e| {
+                let e = zerror!("Write error on TCP link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         self.get_mut_socket().write(buffer).await.map_err(|e| {
             let e = zerror!("Write error on TCP link {}: {}", self, e);
             tracing::trace!("{}", e);
@@ -106,6 +156,14 @@ impl LinkUnicastTrait for LinkUnicastTcp {
     }
 
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.write_all(buffer).await.map_err(|e| {
+                let e = zerror!("Write error on TCP link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         self.get_mut_socket().write_all(buffer).await.map_err(|e| {
             let e = zerror!("Write error on TCP link {}: {}", self, e);
             tracing::trace!("{}", e);
@@ -113,7 +171,33 @@ impl LinkUnicastTrait for LinkUnicastTcp {
         })
     }
 
+    async fn write_vectored_all(&self, buffers: &[&[u8]]) -> ZResult<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.write_vectored_all(buffers).await.map_err(|e| {
+                let e = zerror!("Write error on TCP link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
+        write_all_vectored(self.get_mut_socket(), buffers)
+            .await
+            .map_err(|e| {
+                let e = zerror!("Write error on TCP link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            })
+    }
+
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.read(buffer).await.map_err(|e| {
+                let e = zerror!("Read error on TCP link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         self.get_mut_socket().read(buffer).await.map_err(|e| {
             let e = zerror!("Read error on TCP link {}: {}", self, e);
             tracing::trace!("{}", e);
@@ -122,6 +206,14 @@ impl LinkUnicastTrait for LinkUnicastTcp {
     }
 
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.read_exact(buffer).await.map_err(|e| {
+                let e = zerror!("Read error on TCP link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         let _ = self
             .get_mut_socket()
             .read_exact(buffer)
@@ -134,6 +226,24 @@ impl LinkUnicastTrait for LinkUnicastTcp {
         Ok(())
     }
 
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        zenoh_util::net::set_dscp(self.get_mut_socket(), &self.src_addr, dscp)
+            .map_err(|e| zerror!("Can not set the DSCP of TCP link {}: {}", self, e).into())
+    }
+
+    fn get_os_stats(&self) -> LinkOsStats {
+        match zenoh_util::net::get_tcp_info(self.get_mut_socket()) {
+            Ok((rtt, retransmissions)) => LinkOsStats {
+                rtt: Some(rtt),
+                retransmissions: Some(retransmissions),
+            },
+            Err(e) => {
+                tracing::trace!("Can not get the TCP information of link {}: {}", self, e);
+                LinkOsStats::default()
+            }
+        }
+    }
+
     #[inline(always)]
     fn get_src(&self) -> &Locator {
         &self.src_locator
@@ -193,6 +303,21 @@ impl fmt::Debug for LinkUnicastTcp {
     }
 }
 
+fn get_io_uring(endpoint: &EndPoint) -> ZResult<bool> {
+    match endpoint.config().get(IO_URING) {
+        Some(value) => value.parse().map_err(|_| {
+            zerror!(
+                "Invalid {} value on TCP endpoint {}: {}",
+                IO_URING,
+                endpoint,
+                value
+            )
+            .into()
+        }),
+        None => Ok(false),
+    }
+}
+
 pub struct LinkManagerUnicastTcp {
     manager: NewLinkChannelSender,
     listeners: ListenersUnicastIP,
@@ -211,16 +336,9 @@ impl LinkManagerUnicastTcp {
     async fn new_link_inner(
         &self,
         dst_addr: &SocketAddr,
-        iface: Option<&str>,
+        config: &TcpSocketConfig,
     ) -> ZResult<(TcpStream, SocketAddr, SocketAddr)> {
-        let socket = match dst_addr {
-            SocketAddr::V4(_) => TcpSocket::new_v4(),
-            SocketAddr::V6(_) => TcpSocket::new_v6(),
-        }?;
-
-        if let Some(iface) = iface {
-            zenoh_util::net::set_bind_to_device_tcp_socket(&socket, iface)?;
-        }
+        let socket = config.new_socket(dst_addr)?;
 
         // Build a TcpStream from TcpSocket
         // https://docs.rs/tokio/latest/tokio/net/struct.TcpSocket.html
@@ -243,16 +361,9 @@ impl LinkManagerUnicastTcp {
     async fn new_listener_inner(
         &self,
         addr: &SocketAddr,
-        iface: Option<&str>,
+        config: &TcpSocketConfig,
     ) -> ZResult<(TcpListener, SocketAddr)> {
-        let socket = match addr {
-            SocketAddr::V4(_) => TcpSocket::new_v4(),
-            SocketAddr::V6(_) => TcpSocket::new_v6(),
-        }?;
-
-        if let Some(iface) = iface {
-            zenoh_util::net::set_bind_to_device_tcp_socket(&socket, iface)?;
-        }
+        let socket = config.new_socket(addr)?;
 
         // Build a TcpListener from TcpSocket
         // https://docs.rs/tokio/latest/tokio/net/struct.TcpSocket.html
@@ -275,14 +386,16 @@ impl LinkManagerUnicastTcp {
 impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
         let dst_addrs = get_tcp_addrs(endpoint.address()).await?;
-        let config = endpoint.config();
-        let iface = config.get(BIND_INTERFACE);
+        let config = TcpSocketConfig::new(&endpoint.config())?;
+        let io_uring = get_io_uring(&endpoint)?;
 
         let mut errs: Vec<ZError> = vec![];
         for da in dst_addrs {
-            match self.new_link_inner(&da, iface).await {
+            match self.new_link_inner(&da, &config).await {
                 Ok((stream, src_addr, dst_addr)) => {
-                    let link = Arc::new(LinkUnicastTcp::new(stream, src_addr, dst_addr));
+                    let link = Arc::new(LinkUnicastTcp::new(
+                        stream, src_addr, dst_addr, &config, io_uring,
+                    ));
                     return Ok(LinkUnicast(link));
                 }
                 Err(e) => {
@@ -304,12 +417,12 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
 
     async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
         let addrs = get_tcp_addrs(endpoint.address()).await?;
-        let config = endpoint.config();
-        let iface = config.get(BIND_INTERFACE);
+        let config = TcpSocketConfig::new(&endpoint.config())?;
+        let io_uring = get_io_uring(&endpoint)?;
 
         let mut errs: Vec<ZError> = vec![];
         for da in addrs {
-            match self.new_listener_inner(&da, iface).await {
+            match self.new_listener_inner(&da, &config).await {
                 Ok((socket, local_addr)) => {
                     // Update the endpoint locator address
                     endpoint = EndPoint::new(
@@ -323,7 +436,10 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
                     let c_token = token.clone();
 
                     let c_manager = self.manager.clone();
-                    let task = async move { accept_task(socket, c_token, c_manager).await };
+                    let c_config = config.clone();
+                    let task = async move {
+                        accept_task(socket, c_token, c_manager, c_config, io_uring).await
+                    };
 
                     let locator = endpoint.to_locator();
                     self.listeners
@@ -390,6 +506,8 @@ async fn accept_task(
     socket: TcpListener,
     token: CancellationToken,
     manager: NewLinkChannelSender,
+    config: TcpSocketConfig,
+    io_uring: bool,
 ) -> ZResult<()> {
     async fn accept(socket: &TcpListener) -> ZResult<(TcpStream, SocketAddr)> {
         let res = socket.accept().await.map_err(|e| zerror!(e))?;
@@ -411,7 +529,7 @@ async fn accept_task(
                     Ok((stream, dst_addr)) => {
                         tracing::debug!("Accepted TCP connection on {:?}: {:?}", src_addr, dst_addr);
                         // Create the new link object
-                        let link = Arc::new(LinkUnicastTcp::new(stream, src_addr, dst_addr));
+                        let link = Arc::new(LinkUnicastTcp::new(stream, src_addr, dst_addr, &config, io_uring));
 
                         // Communicate the new link to the initial transport manager
                         if let Err(e) = manager.send_async(LinkUnicast(link)).await {
@@ -0,0 +1,242 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::config::*;
+use socket2::{SockRef, TcpKeepalive};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::net::{TcpSocket, TcpStream};
+use zenoh_config::Config as ZenohConfig;
+use zenoh_link_commons::{ConfigurationInspector, BIND_INTERFACE};
+use zenoh_protocol::core::endpoint::{Config, Parameters};
+use zenoh_result::{zerror, ZResult};
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct TcpConfigurator;
+
+impl ConfigurationInspector<ZenohConfig> for TcpConfigurator {
+    fn inspect_config(&self, config: &ZenohConfig) -> ZResult<String> {
+        let mut ps: Vec<(&str, String)> = vec![];
+
+        let c = config.transport().link().tcp();
+        if let Some(v) = c.keepalive_time() {
+            ps.push((TCP_KEEPALIVE_TIME, v.to_string()));
+        }
+        if let Some(v) = c.keepalive_interval() {
+            ps.push((TCP_KEEPALIVE_INTERVAL, v.to_string()));
+        }
+        if let Some(v) = c.keepalive_probes() {
+            ps.push((TCP_KEEPALIVE_PROBES, v.to_string()));
+        }
+        if let Some(v) = c.user_timeout() {
+            ps.push((TCP_USER_TIMEOUT, v.to_string()));
+        }
+        if let Some(v) = c.nodelay() {
+            ps.push((TCP_NODELAY, v.to_string()));
+        }
+        if let Some(v) = c.so_sndbuf() {
+            ps.push((TCP_SO_SNDBUF, v.to_string()));
+        }
+        if let Some(v) = c.so_rcvbuf() {
+            ps.push((TCP_SO_RCVBUF, v.to_string()));
+        }
+        if let Some(v) = c.iface() {
+            ps.push((BIND_INTERFACE, v.clone()));
+        }
+
+        let mut s = String::new();
+        Parameters::extend(ps.iter().map(|(k, v)| (*k, v.as_str())), &mut s);
+
+        Ok(s)
+    }
+}
+
+/// The socket options of a TCP link, as given in the configuration of its endpoint.
+#[derive(Clone, Debug)]
+pub struct TcpSocketConfig {
+    keepalive_time: Option<Duration>,
+    keepalive_interval: Option<Duration>,
+    keepalive_probes: Option<u32>,
+    user_timeout: Option<Duration>,
+    nodelay: bool,
+    so_sndbuf: Option<u32>,
+    so_rcvbuf: Option<u32>,
+    iface: Option<String>,
+}
+
+impl TcpSocketConfig {
+    pub fn new(config: &Config) -> ZResult<Self> {
+        Ok(Self {
+            keepalive_time: parse(config, TCP_KEEPALIVE_TIME)?.map(Duration::from_secs),
+            keepalive_interval: parse(config, TCP_KEEPALIVE_INTERVAL)?.map(Duration::from_secs),
+            keepalive_probes: parse(config, TCP_KEEPALIVE_PROBES)?,
+            user_timeout: parse(config, TCP_USER_TIMEOUT)?.map(Duration::from_millis),
+            nodelay: parse(config, TCP_NODELAY)?.unwrap_or(true),
+            so_sndbuf: parse(config, TCP_SO_SNDBUF)?,
+            so_rcvbuf: parse(config, TCP_SO_RCVBUF)?,
+            iface: config.get(BIND_INTERFACE).map(|s| s.to_string()),
+        })
+    }
+
+    pub fn iface(&self) -> Option<&str> {
+        self.iface.as_deref()
+    }
+
+    /// Creates a socket for the given address, with the options that must be set before
+    /// connecting or listening: the buffer sizes, which bound the TCP window scaling, and the
+    /// interface to bind to.
+    pub fn new_socket(&self, addr: &SocketAddr) -> ZResult<TcpSocket> {
+        let socket = match addr {
+            SocketAddr::V4(_) => TcpSocket::new_v4(),
+            SocketAddr::V6(_) => TcpSocket::new_v6(),
+        }?;
+
+        if let Some(iface) = self.iface() {
+            zenoh_util::net::set_bind_to_device_tcp_socket(&socket, iface)?;
+        }
+        if let Some(size) = self.so_sndbuf {
+            socket
+                .set_send_buffer_size(size)
+                .map_err(|e| zerror!("Unable to set {} to {}: {}", TCP_SO_SNDBUF, size, e))?;
+        }
+        if let Some(size) = self.so_rcvbuf {
+            socket
+                .set_recv_buffer_size(size)
+                .map_err(|e| zerror!("Unable to set {} to {}: {}", TCP_SO_RCVBUF, size, e))?;
+        }
+
+        Ok(socket)
+    }
+
+    /// Sets the options of a connected socket.
+    pub fn set_stream_options(&self, stream: &TcpStream) -> ZResult<()> {
+        stream
+            .set_nodelay(self.nodelay)
+            .map_err(|e| zerror!("Unable to set {}: {}", TCP_NODELAY, e))?;
+
+        let socket = SockRef::from(stream);
+        if self.keepalive_time.is_some()
+            || self.keepalive_interval.is_some()
+            || self.keepalive_probes.is_some()
+        {
+            // The unset parameters keep the system defaults
+            let mut keepalive = TcpKeepalive::new();
+            if let Some(time) = self.keepalive_time {
+                keepalive = keepalive.with_time(time);
+            }
+            if let Some(interval) = self.keepalive_interval {
+                #[cfg(any(
+                    target_os = "android",
+                    target_os = "dragonfly",
+                    target_os = "freebsd",
+                    target_os = "fuchsia",
+                    target_os = "illumos",
+                    target_os = "ios",
+                    target_os = "linux",
+                    target_os = "macos",
+                    target_os = "netbsd",
+                    target_os = "tvos",
+                    target_os = "watchos",
+                    target_os = "windows",
+                ))]
+                {
+                    keepalive = keepalive.with_interval(interval);
+                }
+                #[cfg(not(any(
+                    target_os = "android",
+                    target_os = "dragonfly",
+                    target_os = "freebsd",
+                    target_os = "fuchsia",
+                    target_os = "illumos",
+                    target_os = "ios",
+                    target_os = "linux",
+                    target_os = "macos",
+                    target_os = "netbsd",
+                    target_os = "tvos",
+                    target_os = "watchos",
+                    target_os = "windows",
+                )))]
+                tracing::warn!(
+                    "{} is not supported on this platform, ignoring {:?}",
+                    TCP_KEEPALIVE_INTERVAL,
+                    interval
+                );
+            }
+            if let Some(probes) = self.keepalive_probes {
+                #[cfg(any(
+                    target_os = "android",
+                    target_os = "dragonfly",
+                    target_os = "freebsd",
+                    target_os = "fuchsia",
+                    target_os = "illumos",
+                    target_os = "ios",
+                    target_os = "linux",
+                    target_os = "macos",
+                    target_os = "netbsd",
+                    target_os = "tvos",
+                    target_os = "watchos",
+                ))]
+                {
+                    keepalive = keepalive.with_retries(probes);
+                }
+                #[cfg(not(any(
+                    target_os = "android",
+                    target_os = "dragonfly",
+                    target_os = "freebsd",
+                    target_os = "fuchsia",
+                    target_os = "illumos",
+                    target_os = "ios",
+                    target_os = "linux",
+                    target_os = "macos",
+                    target_os = "netbsd",
+                    target_os = "tvos",
+                    target_os = "watchos",
+                )))]
+                tracing::warn!(
+                    "{} is not supported on this platform, ignoring {}",
+                    TCP_KEEPALIVE_PROBES,
+                    probes
+                );
+            }
+            socket
+                .set_tcp_keepalive(&keepalive)
+                .map_err(|e| zerror!("Unable to set the keepalive: {}", e))?;
+        }
+
+        if let Some(timeout) = self.user_timeout {
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            socket
+                .set_tcp_user_timeout(Some(timeout))
+                .map_err(|e| zerror!("Unable to set {}: {}", TCP_USER_TIMEOUT, e))?;
+            #[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+            tracing::warn!(
+                "{} is not supported on this platform, ignoring {:?}",
+                TCP_USER_TIMEOUT,
+                timeout
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn parse<T: FromStr>(config: &Config, key: &str) -> ZResult<Option<T>> {
+    match config.get(key) {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| zerror!("Invalid {} value on TCP endpoint: {}", key, value).into()),
+        None => Ok(None),
+    }
+}
@@ -26,7 +26,7 @@ use zenoh_result::ZResult;
 mod unicast;
 mod utils;
 pub use unicast::*;
-pub use utils::TlsConfigurator;
+pub use utils::{get_tls_server_name, TlsClientConfig, TlsConfigurator, TlsServerConfig};
 
 // Default MTU (TLS PDU) in bytes.
 // NOTE: Since TLS is a byte-stream oriented transport, theoretically it has
@@ -71,6 +71,8 @@ pub mod config {
     pub const TLS_SERVER_PRIVATE_KEY_FILE: &str = "server_private_key_file";
     pub const TLS_SERVER_PRIVATE_KEY_RAW: &str = "server_private_key_raw";
     pub const TLS_SERVER_PRIVATE_KEY_BASE_64: &str = "server_private_key_base64";
+    /// URI of a private key loaded by a registered [`zenoh_link_commons::tls::KeyProvider`].
+    pub const TLS_SERVER_PRIVATE_KEY_URI: &str = "server_private_key_uri";
 
     pub const TLS_SERVER_CERTIFICATE_FILE: &str = "server_certificate_file";
     pub const TLS_SERVER_CERTIFICATE_RAW: &str = "server_certificate_raw";
@@ -79,6 +81,7 @@ pub mod config {
     pub const TLS_CLIENT_PRIVATE_KEY_FILE: &str = "client_private_key_file";
     pub const TLS_CLIENT_PRIVATE_KEY_RAW: &str = "client_private_key_raw";
     pub const TLS_CLIENT_PRIVATE_KEY_BASE64: &str = "client_private_key_base64";
+    pub const TLS_CLIENT_PRIVATE_KEY_URI: &str = "client_private_key_uri";
 
     pub const TLS_CLIENT_CERTIFICATE_FILE: &str = "client_certificate_file";
     pub const TLS_CLIENT_CERTIFICATE_RAW: &str = "client_certificate_raw";
@@ -87,4 +90,16 @@ pub mod config {
     pub const TLS_CLIENT_AUTH: &str = "client_auth";
 
     pub const TLS_SERVER_NAME_VERIFICATION: &str = "server_name_verification";
+
+    pub const TLS_CRL_FILE: &str = "crl_file";
+    pub const TLS_CRL_RAW: &str = "crl_raw";
+    pub const TLS_CRL_BASE64: &str = "crl_base64";
+
+    pub const TLS_SERVER_OCSP_RESPONSE_FILE: &str = "server_ocsp_response_file";
+    pub const TLS_SERVER_OCSP_RESPONSE_BASE64: &str = "server_ocsp_response_base64";
+
+    /// Interval in milliseconds at which listeners check their certificate files for changes.
+    /// A value of 0 disables the reloading of the files.
+    pub const TLS_RELOAD_INTERVAL: &str = "reload_interval";
+    pub const TLS_RELOAD_INTERVAL_DEFAULT: &str = "10000";
 }
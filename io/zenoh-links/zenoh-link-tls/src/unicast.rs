@@ -12,7 +12,11 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::{
-    utils::{get_tls_addr, get_tls_host, get_tls_server_name, TlsClientConfig, TlsServerConfig},
+    config::*,
+    utils::{
+        get_tls_addr, get_tls_host, get_tls_server_files_mtime, get_tls_server_name,
+        TlsClientConfig, TlsServerConfig,
+    },
     TLS_ACCEPT_THROTTLE_TIME, TLS_DEFAULT_MTU, TLS_LINGER_TIMEOUT, TLS_LOCATOR_PREFIX,
 };
 
@@ -30,8 +34,8 @@ use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
 use tokio_util::sync::CancellationToken;
 use zenoh_core::zasynclock;
 use zenoh_link_commons::{
-    get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
-    ListenersUnicastIP, NewLinkChannelSender,
+    get_ip_interface_names, tls, write_all_vectored, LinkManagerUnicastTrait, LinkUnicast,
+    LinkUnicastTrait, ListenersUnicastIP, NewLinkChannelSender,
 };
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{zerror, ZResult};
@@ -52,6 +56,9 @@ pub struct LinkUnicastTls {
     // The destination socket address of this link (address used on the local host)
     dst_addr: SocketAddr,
     dst_locator: Locator,
+    // The common name and the subject alternative names of the certificate of the remote end, if any
+    cert_common_name: Option<String>,
+    cert_subject_alt_names: Vec<String>,
     // Make sure there are no concurrent read or writes
     write_mtx: AsyncMutex<()>,
     read_mtx: AsyncMutex<()>,
@@ -66,7 +73,12 @@ impl LinkUnicastTls {
         src_addr: SocketAddr,
         dst_addr: SocketAddr,
     ) -> LinkUnicastTls {
-        let (tcp_stream, _) = socket.get_ref();
+        let (tcp_stream, tls_conn) = socket.get_ref();
+        let cert = tls_conn.peer_certificates().and_then(|certs| certs.first());
+        let cert_common_name = cert.and_then(|cert| tls::get_cert_common_name(cert));
+        let cert_subject_alt_names = cert
+            .map(|cert| tls::get_cert_subject_alt_names(cert))
+            .unwrap_or_default();
         // Set the TLS nodelay option
         if let Err(err) = tcp_stream.set_nodelay(true) {
             tracing::warn!(
@@ -96,6 +108,8 @@ impl LinkUnicastTls {
             src_locator: Locator::new(TLS_LOCATOR_PREFIX, src_addr.to_string(), "").unwrap(),
             dst_addr,
             dst_locator: Locator::new(TLS_LOCATOR_PREFIX, dst_addr.to_string(), "").unwrap(),
+            cert_common_name,
+            cert_subject_alt_names,
             write_mtx: AsyncMutex::new(()),
             read_mtx: AsyncMutex::new(()),
         }
@@ -142,6 +156,16 @@ impl LinkUnicastTrait for LinkUnicastTls {
         })
     }
 
+    async fn write_vectored_all(&self, buffers: &[&[u8]]) -> ZResult<()> {
+        let _guard = zasynclock!(self.write_mtx);
+        write_all_vectored(self.get_sock_mut(), buffers)
+            .await
+            .map_err(|e| {
+                tracing::trace!("Write error on TLS link {}: {}", self, e);
+                zerror!(e).into()
+            })
+    }
+
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
         let _guard = zasynclock!(self.read_mtx);
         self.get_sock_mut().read(buffer).await.map_err(|e| {
@@ -179,6 +203,15 @@ impl LinkUnicastTrait for LinkUnicastTls {
         get_ip_interface_names(&self.src_addr)
     }
 
+    #[inline(always)]
+    fn get_cert_common_name(&self) -> Option<String> {
+        self.cert_common_name.clone()
+    }
+
+    fn get_cert_subject_alt_names(&self) -> Vec<String> {
+        self.cert_subject_alt_names.clone()
+    }
+
     #[inline(always)]
     fn is_reliable(&self) -> bool {
         true
@@ -188,6 +221,11 @@ impl LinkUnicastTrait for LinkUnicastTls {
     fn is_streamed(&self) -> bool {
         true
     }
+
+    #[inline(always)]
+    fn is_secure(&self) -> bool {
+        true
+    }
 }
 
 impl Drop for LinkUnicastTls {
@@ -310,13 +348,30 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
             .map_err(|e| zerror!("Can not create a new TLS listener on {}: {}", addr, e))?;
         let local_port = local_addr.port();
 
+        let reload_interval: u64 = epconf
+            .get(TLS_RELOAD_INTERVAL)
+            .unwrap_or(TLS_RELOAD_INTERVAL_DEFAULT)
+            .parse()
+            .map_err(|_| zerror!("Unknown reload interval argument on {addr}"))?;
+
         // Initialize the TlsAcceptor
         let acceptor = TlsAcceptor::from(Arc::new(tls_server_config.server_config));
         let token = self.listeners.token.child_token();
         let c_token = token.clone();
         let c_manager = self.manager.clone();
-
-        let task = async move { accept_task(socket, acceptor, c_token, c_manager).await };
+        let c_endpoint = endpoint.clone();
+
+        let task = async move {
+            accept_task(
+                socket,
+                acceptor,
+                c_endpoint,
+                reload_interval,
+                c_token,
+                c_manager,
+            )
+            .await
+        };
 
         // Update the endpoint locator address
         let locator = Locator::new(
@@ -349,7 +404,9 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
 
 async fn accept_task(
     socket: TcpListener,
-    acceptor: TlsAcceptor,
+    mut acceptor: TlsAcceptor,
+    endpoint: EndPoint,
+    reload_interval: u64,
     token: CancellationToken,
     manager: NewLinkChannelSender,
 ) -> ZResult<()> {
@@ -364,11 +421,34 @@ async fn accept_task(
         e
     })?;
 
+    // The certificate files are polled for changes: new connections are accepted with the
+    // reloaded configuration while the established ones keep using the previous one.
+    let mut mtime = get_tls_server_files_mtime(&endpoint.config()).await;
+    let mut reload = tokio::time::interval(Duration::from_millis(reload_interval.max(1)));
+    reload.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
     tracing::trace!("Ready to accept TLS connections on: {:?}", src_addr);
     loop {
         tokio::select! {
             _ = token.cancelled() => break,
 
+            _ = reload.tick(), if reload_interval > 0 => {
+                let modified = get_tls_server_files_mtime(&endpoint.config()).await;
+                if modified <= mtime {
+                    continue;
+                }
+                mtime = modified;
+                match TlsServerConfig::new(&endpoint.config()).await {
+                    Ok(config) => {
+                        tracing::info!("Reloaded TLS configuration of listener {}", src_addr);
+                        acceptor = TlsAcceptor::from(Arc::new(config.server_config));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Can not reload TLS configuration of listener {}: {}", src_addr, e);
+                    }
+                }
+            }
+
             res = accept(&socket) => {
                 match res {
                     Ok((tcp_stream, dst_addr)) => {
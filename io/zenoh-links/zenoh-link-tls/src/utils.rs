@@ -13,8 +13,10 @@
 //
 use crate::config::*;
 use rustls::{
-    pki_types::{CertificateDer, PrivateKeyDer, TrustAnchor},
+    client::WebPkiServerVerifier,
+    pki_types::{CertificateDer, CertificateRevocationListDer, PrivateKeyDer, TrustAnchor},
     server::WebPkiClientVerifier,
+    sign::CertifiedKey,
     version::TLS13,
     ClientConfig, RootCertStore, ServerConfig,
 };
@@ -22,6 +24,7 @@ use rustls_pki_types::ServerName;
 use secrecy::ExposeSecret;
 use std::fs::File;
 use std::io;
+use std::time::SystemTime;
 use std::{convert::TryFrom, net::SocketAddr};
 use std::{
     io::{BufReader, Cursor},
@@ -29,7 +32,10 @@ use std::{
 };
 use webpki::anchor_from_trusted_cert;
 use zenoh_config::Config as ZenohConfig;
-use zenoh_link_commons::{tls::WebPkiVerifierAnyServerName, ConfigurationInspector};
+use zenoh_link_commons::{
+    tls::{load_private_key, ProvidedSigningKey, SingleCertResolver, WebPkiVerifierAnyServerName},
+    ConfigurationInspector,
+};
 use zenoh_protocol::core::endpoint::Config;
 use zenoh_protocol::core::endpoint::{self, Address};
 use zenoh_result::{bail, zerror, ZError, ZResult};
@@ -137,6 +143,36 @@ impl ConfigurationInspector<ZenohConfig> for TlsConfigurator {
             };
         }
 
+        if let Some(crl) = c.crl() {
+            ps.push((TLS_CRL_FILE, crl));
+        }
+
+        if let Some(server_ocsp_response) = c.server_ocsp_response() {
+            ps.push((TLS_SERVER_OCSP_RESPONSE_FILE, server_ocsp_response));
+        }
+
+        let reload_interval = c.reload_interval().map(|i| i.to_string());
+        if let Some(reload_interval) = reload_interval.as_deref() {
+            ps.push((TLS_RELOAD_INTERVAL, reload_interval));
+        }
+
+        // The ';' of the key URIs would be taken for the separator of the endpoint parameters
+        let server_private_key_uri = c
+            .server_private_key_uri()
+            .as_ref()
+            .map(|uri| uri.replace(';', "%3B"));
+        if let Some(server_private_key_uri) = server_private_key_uri.as_deref() {
+            ps.push((TLS_SERVER_PRIVATE_KEY_URI, server_private_key_uri));
+        }
+
+        let client_private_key_uri = c
+            .client_private_key_uri()
+            .as_ref()
+            .map(|uri| uri.replace(';', "%3B"));
+        if let Some(client_private_key_uri) = client_private_key_uri.as_deref() {
+            ps.push((TLS_CLIENT_PRIVATE_KEY_URI, client_private_key_uri));
+        }
+
         let mut s = String::new();
         endpoint::Parameters::extend(ps.drain(..), &mut s);
 
@@ -144,8 +180,8 @@ impl ConfigurationInspector<ZenohConfig> for TlsConfigurator {
     }
 }
 
-pub(crate) struct TlsServerConfig {
-    pub(crate) server_config: ServerConfig,
+pub struct TlsServerConfig {
+    pub server_config: ServerConfig,
 }
 
 impl TlsServerConfig {
@@ -156,7 +192,6 @@ impl TlsServerConfig {
                 .map_err(|_| zerror!("Unknown client auth argument: {}", s))?,
             None => false,
         };
-        let tls_server_private_key = TlsServerConfig::load_tls_private_key(config).await?;
         let tls_server_certificate = TlsServerConfig::load_tls_certificate(config).await?;
 
         let certs: Vec<CertificateDer> =
@@ -164,31 +199,11 @@ impl TlsServerConfig {
                 .collect::<Result<_, _>>()
                 .map_err(|err| zerror!("Error processing server certificate: {err}."))?;
 
-        let mut keys: Vec<PrivateKeyDer> =
-            rustls_pemfile::rsa_private_keys(&mut Cursor::new(&tls_server_private_key))
-                .map(|x| x.map(PrivateKeyDer::from))
-                .collect::<Result<_, _>>()
-                .map_err(|err| zerror!("Error processing server key: {err}."))?;
-
-        if keys.is_empty() {
-            keys = rustls_pemfile::pkcs8_private_keys(&mut Cursor::new(&tls_server_private_key))
-                .map(|x| x.map(PrivateKeyDer::from))
-                .collect::<Result<_, _>>()
-                .map_err(|err| zerror!("Error processing server key: {err}."))?;
-        }
+        // The OCSP response is stapled to the certificate as is, it is up to the
+        // certificate owner to renew it before it expires.
+        let ocsp = load_tls_ocsp_response(config).await?;
 
-        if keys.is_empty() {
-            keys = rustls_pemfile::ec_private_keys(&mut Cursor::new(&tls_server_private_key))
-                .map(|x| x.map(PrivateKeyDer::from))
-                .collect::<Result<_, _>>()
-                .map_err(|err| zerror!("Error processing server key: {err}."))?;
-        }
-
-        if keys.is_empty() {
-            bail!("No private key found for TLS server.");
-        }
-
-        let sc = if tls_server_client_auth {
+        let builder = if tls_server_client_auth {
             let root_cert_store = load_trust_anchors(config)?.map_or_else(
                 || {
                     Err(zerror!(
@@ -197,15 +212,28 @@ impl TlsServerConfig {
                 },
                 Ok,
             )?;
-            let client_auth = WebPkiClientVerifier::builder(root_cert_store.into()).build()?;
+            let client_auth = WebPkiClientVerifier::builder(root_cert_store.into())
+                .with_crls(load_tls_crls(config).await?)
+                .allow_unknown_revocation_status()
+                .build()?;
             ServerConfig::builder_with_protocol_versions(&[&TLS13])
                 .with_client_cert_verifier(client_auth)
-                .with_single_cert(certs, keys.remove(0))
-                .map_err(|e| zerror!(e))?
         } else {
-            ServerConfig::builder()
-                .with_no_client_auth()
-                .with_single_cert(certs, keys.remove(0))
+            ServerConfig::builder().with_no_client_auth()
+        };
+
+        let sc = if let Some(uri) = config.get(TLS_SERVER_PRIVATE_KEY_URI) {
+            let mut certified_key =
+                CertifiedKey::new(certs, Arc::new(ProvidedSigningKey(load_private_key(uri)?)));
+            if !ocsp.is_empty() {
+                certified_key.ocsp = Some(ocsp);
+            }
+            builder.with_cert_resolver(Arc::new(SingleCertResolver(Arc::new(certified_key))))
+        } else {
+            let tls_server_private_key = TlsServerConfig::load_tls_private_key(config).await?;
+            let key = parse_private_key(&tls_server_private_key, "server")?;
+            builder
+                .with_single_cert_with_ocsp(certs, key, ocsp)
                 .map_err(|e| zerror!(e))?
         };
         Ok(TlsServerConfig { server_config: sc })
@@ -232,8 +260,8 @@ impl TlsServerConfig {
     }
 }
 
-pub(crate) struct TlsClientConfig {
-    pub(crate) client_config: ClientConfig,
+pub struct TlsClientConfig {
+    pub client_config: ClientConfig,
 }
 
 impl TlsClientConfig {
@@ -269,9 +297,16 @@ impl TlsClientConfig {
             root_cert_store.extend(custom_root_cert.roots);
         }
 
+        // Certificates whose revocation status is unknown are accepted: the CRLs are
+        // typically issued by the user-generated CA, not by the Web PKI ones.
+        let verifier = WebPkiServerVerifier::builder(Arc::new(root_cert_store))
+            .with_crls(load_tls_crls(config).await?)
+            .allow_unknown_revocation_status()
+            .build()
+            .map_err(|e| zerror!("Invalid TLS certificate verifier: {e}."))?;
+
         let cc = if tls_client_server_auth {
             tracing::debug!("Loading client authentication key and certificate...");
-            let tls_client_private_key = TlsClientConfig::load_tls_private_key(config).await?;
             let tls_client_certificate = TlsClientConfig::load_tls_certificate(config).await?;
 
             let certs: Vec<CertificateDer> =
@@ -279,57 +314,39 @@ impl TlsClientConfig {
                     .collect::<Result<_, _>>()
                     .map_err(|err| zerror!("Error processing client certificate: {err}."))?;
 
-            let mut keys: Vec<PrivateKeyDer> =
-                rustls_pemfile::rsa_private_keys(&mut Cursor::new(&tls_client_private_key))
-                    .map(|x| x.map(PrivateKeyDer::from))
-                    .collect::<Result<_, _>>()
-                    .map_err(|err| zerror!("Error processing client key: {err}."))?;
-
-            if keys.is_empty() {
-                keys =
-                    rustls_pemfile::pkcs8_private_keys(&mut Cursor::new(&tls_client_private_key))
-                        .map(|x| x.map(PrivateKeyDer::from))
-                        .collect::<Result<_, _>>()
-                        .map_err(|err| zerror!("Error processing client key: {err}."))?;
-            }
-
-            if keys.is_empty() {
-                keys = rustls_pemfile::ec_private_keys(&mut Cursor::new(&tls_client_private_key))
-                    .map(|x| x.map(PrivateKeyDer::from))
-                    .collect::<Result<_, _>>()
-                    .map_err(|err| zerror!("Error processing client key: {err}."))?;
-            }
-
-            if keys.is_empty() {
-                bail!("No private key found for TLS client.");
-            }
-
             let builder = ClientConfig::builder_with_protocol_versions(&[&TLS13]);
-
-            if tls_server_name_verification {
-                builder
-                    .with_root_certificates(root_cert_store)
-                    .with_client_auth_cert(certs, keys.remove(0))
+            let builder = if tls_server_name_verification {
+                builder.with_webpki_verifier(verifier)
             } else {
                 builder
                     .dangerous()
                     .with_custom_certificate_verifier(Arc::new(WebPkiVerifierAnyServerName::new(
-                        root_cert_store,
+                        verifier,
                     )))
-                    .with_client_auth_cert(certs, keys.remove(0))
+            };
+
+            if let Some(uri) = config.get(TLS_CLIENT_PRIVATE_KEY_URI) {
+                let certified_key =
+                    CertifiedKey::new(certs, Arc::new(ProvidedSigningKey(load_private_key(uri)?)));
+                builder.with_client_cert_resolver(Arc::new(SingleCertResolver(Arc::new(
+                    certified_key,
+                ))))
+            } else {
+                let tls_client_private_key = TlsClientConfig::load_tls_private_key(config).await?;
+                let key = parse_private_key(&tls_client_private_key, "client")?;
+                builder
+                    .with_client_auth_cert(certs, key)
+                    .map_err(|e| zerror!("Bad certificate/key: {}", e))?
             }
-            .map_err(|e| zerror!("Bad certificate/key: {}", e))?
         } else {
             let builder = ClientConfig::builder();
             if tls_server_name_verification {
-                builder
-                    .with_root_certificates(root_cert_store)
-                    .with_no_client_auth()
+                builder.with_webpki_verifier(verifier).with_no_client_auth()
             } else {
                 builder
                     .dangerous()
                     .with_custom_certificate_verifier(Arc::new(WebPkiVerifierAnyServerName::new(
-                        root_cert_store,
+                        verifier,
                     )))
                     .with_no_client_auth()
             }
@@ -358,6 +375,32 @@ impl TlsClientConfig {
     }
 }
 
+fn parse_private_key(pem: &[u8], side: &str) -> ZResult<PrivateKeyDer<'static>> {
+    let mut keys: Vec<PrivateKeyDer> = rustls_pemfile::rsa_private_keys(&mut Cursor::new(pem))
+        .map(|x| x.map(PrivateKeyDer::from))
+        .collect::<Result<_, _>>()
+        .map_err(|err| zerror!("Error processing {side} key: {err}."))?;
+
+    if keys.is_empty() {
+        keys = rustls_pemfile::pkcs8_private_keys(&mut Cursor::new(pem))
+            .map(|x| x.map(PrivateKeyDer::from))
+            .collect::<Result<_, _>>()
+            .map_err(|err| zerror!("Error processing {side} key: {err}."))?;
+    }
+
+    if keys.is_empty() {
+        keys = rustls_pemfile::ec_private_keys(&mut Cursor::new(pem))
+            .map(|x| x.map(PrivateKeyDer::from))
+            .collect::<Result<_, _>>()
+            .map_err(|err| zerror!("Error processing {side} key: {err}."))?;
+    }
+
+    if keys.is_empty() {
+        bail!("No private key found for TLS {side}.");
+    }
+    Ok(keys.remove(0))
+}
+
 fn process_pem(pem: &mut dyn io::BufRead) -> ZResult<Vec<TrustAnchor<'static>>> {
     let certs: Vec<CertificateDer> = rustls_pemfile::certs(pem)
         .map(|result| result.map_err(|err| zerror!("Error processing PEM certificates: {err}.")))
@@ -452,6 +495,62 @@ fn load_trust_anchors(config: &Config<'_>) -> ZResult<Option<RootCertStore>> {
     Ok(None)
 }
 
+async fn load_tls_crls(config: &Config<'_>) -> ZResult<Vec<CertificateRevocationListDer<'static>>> {
+    let pem = if let Some(value) = config.get(TLS_CRL_RAW) {
+        value.as_bytes().to_vec()
+    } else if let Some(b64_crl) = config.get(TLS_CRL_BASE64) {
+        base64_decode(b64_crl)?
+    } else if let Some(value) = config.get(TLS_CRL_FILE) {
+        tokio::fs::read(value)
+            .await
+            .map_err(|e| zerror!("Invalid TLS CRL file: {}", e))?
+    } else {
+        return Ok(vec![]);
+    };
+
+    let crls: Vec<CertificateRevocationListDer> = rustls_pemfile::crls(&mut Cursor::new(&pem))
+        .collect::<Result<_, _>>()
+        .map_err(|err| zerror!("Error processing CRLs: {err}."))?;
+    if crls.is_empty() {
+        bail!("No CRL found in the TLS CRL configuration.");
+    }
+    Ok(crls)
+}
+
+async fn load_tls_ocsp_response(config: &Config<'_>) -> ZResult<Vec<u8>> {
+    if let Some(b64_response) = config.get(TLS_SERVER_OCSP_RESPONSE_BASE64) {
+        return base64_decode(b64_response);
+    }
+
+    if let Some(value) = config.get(TLS_SERVER_OCSP_RESPONSE_FILE) {
+        return Ok(tokio::fs::read(value)
+            .await
+            .map_err(|e| zerror!("Invalid TLS OCSP response file: {}", e))?);
+    }
+    Ok(vec![])
+}
+
+/// Returns the most recent modification time of the files a TLS listener is configured with.
+pub async fn get_tls_server_files_mtime(config: &Config<'_>) -> Option<SystemTime> {
+    let mut mtime = None;
+    for key in [
+        TLS_SERVER_PRIVATE_KEY_FILE,
+        TLS_SERVER_CERTIFICATE_FILE,
+        TLS_ROOT_CA_CERTIFICATE_FILE,
+        TLS_CRL_FILE,
+        TLS_SERVER_OCSP_RESPONSE_FILE,
+    ] {
+        if let Some(path) = config.get(key) {
+            let modified = tokio::fs::metadata(path)
+                .await
+                .and_then(|m| m.modified())
+                .ok();
+            mtime = mtime.max(modified);
+        }
+    }
+    mtime
+}
+
 pub fn base64_decode(data: &str) -> ZResult<Vec<u8>> {
     use base64::engine::general_purpose;
     use base64::Engine;
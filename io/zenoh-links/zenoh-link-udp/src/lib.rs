@@ -18,6 +18,8 @@
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 mod multicast;
+mod nat;
+mod secure;
 mod unicast;
 
 use async_trait::async_trait;
@@ -26,7 +28,8 @@ use std::net::SocketAddr;
 pub use unicast::*;
 use zenoh_core::zconfigurable;
 use zenoh_link_commons::LocatorInspector;
-use zenoh_protocol::core::{endpoint::Address, Locator};
+pub use zenoh_link_tls::TlsConfigurator as UdpsConfigurator;
+use zenoh_protocol::core::{endpoint::Address, EndPoint, Locator};
 use zenoh_result::{zerror, ZResult};
 
 // NOTE: In case of using UDP in high-throughput scenarios, it is recommended to set the
@@ -49,6 +52,7 @@ use zenoh_result::{zerror, ZResult};
 const UDP_MAX_MTU: u16 = u16::MAX - 8 - 40;
 
 pub const UDP_LOCATOR_PREFIX: &str = "udp";
+pub const UDPS_LOCATOR_PREFIX: &str = "udps";
 
 #[cfg(any(target_os = "linux", target_os = "windows"))]
 // Linux default value of a maximum datagram size is set to UDP MAX MTU.
@@ -67,6 +71,25 @@ zconfigurable! {
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref UDP_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds to complete a UDPS handshake.
+    // Default set to 10 s.
+    static ref UDPS_HANDSHAKE_TIMEOUT: u64 = 10_000;
+    // Amount of time in milliseconds before retransmitting a flight of the UDPS
+    // handshake, doubled upon each retransmission.
+    // Default set to 500 ms.
+    static ref UDPS_RETRANSMIT_TIME: u64 = 500;
+    // Maximum number of UDPS handshakes in progress at once on a listener.
+    static ref UDPS_MAX_HANDSHAKES: usize = 64;
+    // Amount of time in milliseconds between two registrations of a UDP listener with its NAT
+    // traversal rendezvous, which also keep the NAT mapping of the listener alive.
+    // Default set to 10 s.
+    static ref UDP_NAT_KEEPALIVE_TIME: u64 = 10_000;
+    // Amount of time in milliseconds to punch a hole toward a node behind a NAT.
+    // Default set to 5 s.
+    static ref UDP_NAT_PUNCH_TIMEOUT: u64 = 5_000;
+    // Amount of time in milliseconds between two retransmissions of the NAT traversal messages.
+    // Default set to 250 ms.
+    static ref UDP_NAT_RETRANSMIT_TIME: u64 = 250;
 }
 
 #[derive(Default, Clone, Copy)]
@@ -85,9 +108,47 @@ impl LocatorInspector for UdpLocatorInspector {
     }
 }
 
+#[derive(Default, Clone, Copy)]
+pub struct UdpsLocatorInspector;
+#[async_trait]
+impl LocatorInspector for UdpsLocatorInspector {
+    fn protocol(&self) -> &str {
+        UDPS_LOCATOR_PREFIX
+    }
+
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
 pub mod config {
+    // The UDPS links use the configuration of the TLS links
+    pub use zenoh_link_tls::config::*;
+
     pub const UDP_MULTICAST_IFACE: &str = "iface";
     pub const UDP_MULTICAST_JOIN: &str = "join";
+    /// The time-to-live of the IPv4 datagrams, or the hop limit of the IPv6 ones, sent to a UDP
+    /// multicast group (default `1`). The IPv6 multicast scope is the one of the group address.
+    pub const UDP_MULTICAST_TTL: &str = "ttl";
+
+    /// The UDP address of the NAT traversal rendezvous a UDP listener registers with.
+    pub const UDP_NAT_RENDEZVOUS: &str = "nat_rendezvous";
+    /// The id a UDP listener registers under with its NAT traversal rendezvous.
+    pub const UDP_NAT_ID: &str = "nat_id";
+    /// Whether a UDP listener acts as a NAT traversal rendezvous (default `false`).
+    pub const UDP_NAT_SERVER: &str = "nat_server";
+    /// The secret shared by a NAT traversal rendezvous and the UDP listeners registering with it,
+    /// required to authenticate their messages. Since the messages carry the time they were sent at,
+    /// the clocks of the nodes must not drift apart from the one of their rendezvous by more than
+    /// a few registration periods.
+    pub const UDP_NAT_SECRET: &str = "nat_secret";
+    /// The id of the node to open a link to through its NAT, the address of the endpoint being
+    /// the one of the rendezvous both nodes are registered with.
+    pub const UDP_NAT_TARGET: &str = "nat_target";
+    /// Whether a UDP link discovers the MTU of its path instead of using the default one
+    /// (default `false`). The datagrams of the link are then never fragmented: a link failing to
+    /// send a datagram larger than a shrunk path MTU gets closed and re-established with the new one.
+    pub const UDP_PMTUD: &str = "pmtud";
 }
 
 pub async fn get_udp_addrs(address: Address<'_>) -> ZResult<impl Iterator<Item = SocketAddr>> {
@@ -97,6 +158,75 @@ pub async fn get_udp_addrs(address: Address<'_>) -> ZResult<impl Iterator<Item =
     Ok(iter)
 }
 
+pub(crate) fn get_pmtud_config(endpoint: &EndPoint) -> ZResult<bool> {
+    match endpoint.config().get(config::UDP_PMTUD) {
+        Some(s) => s.parse().map_err(|_| {
+            zerror!(
+                "Invalid {} value on endpoint {}: {}",
+                config::UDP_PMTUD,
+                endpoint,
+                s
+            )
+            .into()
+        }),
+        None => Ok(false),
+    }
+}
+
+fn path_mtu<S>(socket: &S, dst_addr: &SocketAddr) -> ZResult<u16>
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    let mtu = zenoh_util::net::get_path_mtu(socket, dst_addr)?;
+    // Substract the size of the IPv4/IPv6 header and of the UDP header
+    let header = if dst_addr.is_ipv4() { 20 } else { 40 } + 8;
+    let mtu = u16::try_from(mtu.saturating_sub(header)).unwrap_or(u16::MAX);
+    Ok(mtu.min(*UDP_DEFAULT_MTU))
+}
+
+fn path_mtu_or_default(res: ZResult<u16>, src_addr: &SocketAddr, dst_addr: &SocketAddr) -> u16 {
+    match res {
+        Ok(mtu) => {
+            tracing::debug!(
+                "Discovered a path MTU of {} from {} to {}",
+                mtu,
+                src_addr,
+                dst_addr
+            );
+            mtu
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Can not discover the path MTU from {} to {}, using the default MTU: {}",
+                src_addr,
+                dst_addr,
+                e
+            );
+            *UDP_DEFAULT_MTU
+        }
+    }
+}
+
+/// Get the MTU (UDP PDU) of the path of a socket connected to a remote address, bounded by the
+/// default MTU which is also the fallback upon failure.
+pub(crate) fn get_path_mtu<S>(socket: &S, src_addr: &SocketAddr, dst_addr: &SocketAddr) -> u16
+where
+    for<'s> socket2::SockRef<'s>: From<&'s S>,
+{
+    path_mtu_or_default(path_mtu(socket, dst_addr), src_addr, dst_addr)
+}
+
+/// Discover the MTU (UDP PDU) of the path from a local address to a remote one like
+/// [`get_path_mtu`], for sockets which are not connected to the remote address, e.g. the ones of
+/// listeners and multicast links, by connecting a dedicated socket to it.
+pub(crate) fn discover_path_mtu(src_addr: &SocketAddr, dst_addr: &SocketAddr) -> u16 {
+    let res = std::net::UdpSocket::bind(SocketAddr::new(src_addr.ip(), 0))
+        .and_then(|socket| socket.connect(dst_addr).map(|_| socket))
+        .map_err(|e| zerror!("{}", e).into())
+        .and_then(|socket| path_mtu(&socket, dst_addr));
+    path_mtu_or_default(res, src_addr, dst_addr)
+}
+
 pub(crate) fn socket_addr_to_udp_locator(addr: &SocketAddr) -> Locator {
     Locator::new(UDP_LOCATOR_PREFIX, addr.to_string(), "").unwrap()
 }
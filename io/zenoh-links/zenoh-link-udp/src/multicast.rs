@@ -12,7 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::{config::*, UDP_DEFAULT_MTU};
-use crate::{get_udp_addrs, socket_addr_to_udp_locator};
+use crate::{discover_path_mtu, get_pmtud_config, get_udp_addrs, socket_addr_to_udp_locator};
 use async_trait::async_trait;
 use socket2::{Domain, Protocol, Socket, Type};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
@@ -34,6 +34,8 @@ pub struct LinkMulticastUdp {
     multicast_locator: Locator,
     // The multicast UDP socket used for read operations
     mcast_sock: UdpSocket,
+    // The MTU of this link, possibly discovered on its path
+    mtu: u16,
 }
 
 impl LinkMulticastUdp {
@@ -42,6 +44,7 @@ impl LinkMulticastUdp {
         unicast_socket: UdpSocket,
         multicast_addr: SocketAddr,
         mcast_sock: UdpSocket,
+        mtu: u16,
     ) -> LinkMulticastUdp {
         LinkMulticastUdp {
             unicast_locator: socket_addr_to_udp_locator(&unicast_addr),
@@ -50,6 +53,7 @@ impl LinkMulticastUdp {
             unicast_socket,
             multicast_addr,
             mcast_sock,
+            mtu,
         }
     }
 }
@@ -120,7 +124,7 @@ impl LinkMulticastTrait for LinkMulticastUdp {
 
     #[inline(always)]
     fn get_mtu(&self) -> u16 {
-        *UDP_DEFAULT_MTU
+        self.mtu
     }
 
     #[inline(always)]
@@ -208,6 +212,28 @@ impl LinkManagerMulticastUdp {
             }
         };
 
+        // The index of the IPv6 interface, 0 letting the system select one
+        let iface_index = match local_addr {
+            IpAddr::V6(addr) if !addr.is_unspecified() => {
+                zenoh_util::net::get_index_of_interface(local_addr)
+                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?
+            }
+            _ => 0,
+        };
+
+        let ttl = match config.get(UDP_MULTICAST_TTL) {
+            Some(ttl) => ttl.parse().map_err(|e| {
+                zerror!(
+                    "{}: invalid {} {}: {}",
+                    mcast_addr,
+                    UDP_MULTICAST_TTL,
+                    ttl,
+                    e
+                )
+            })?,
+            None => 1,
+        };
+
         // Establish a unicast UDP socket
         let ucast_sock = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))
             .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
@@ -216,13 +242,18 @@ impl LinkManagerMulticastUdp {
                 ucast_sock
                     .set_multicast_if_v4(addr)
                     .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                ucast_sock
+                    .set_multicast_ttl_v4(ttl)
+                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+            }
+            IpAddr::V6(_) => {
+                ucast_sock
+                    .set_multicast_if_v6(iface_index)
+                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                ucast_sock
+                    .set_multicast_hops_v6(ttl)
+                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
             }
-            IpAddr::V6(_) => match zenoh_util::net::get_index_of_interface(local_addr) {
-                Ok(idx) => ucast_sock
-                    .set_multicast_if_v6(idx)
-                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?,
-                Err(e) => bail!("{}: {}", mcast_addr, e),
-            },
         }
 
         ucast_sock
@@ -280,13 +311,13 @@ impl LinkManagerMulticastUdp {
             IpAddr::V6(dst_ip6) => {
                 // Join default multicast group
                 mcast_sock
-                    .join_multicast_v6(&dst_ip6, 0)
+                    .join_multicast_v6(&dst_ip6, iface_index)
                     .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
                 // Join any additional multicast group
                 for g in join {
                     let g: Ipv6Addr = g.parse().map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
                     mcast_sock
-                        .join_multicast_v6(&g, 0)
+                        .join_multicast_v6(&g, iface_index)
                         .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
                 }
             }
@@ -315,13 +346,29 @@ impl LinkManagerMulticastTrait for LinkManagerMulticastUdp {
             .await?
             .filter(|a| a.ip().is_multicast())
             .collect::<Vec<SocketAddr>>();
+        let pmtud = get_pmtud_config(endpoint)?;
 
         let mut errs: Vec<ZError> = vec![];
         for maddr in mcast_addrs {
             match self.new_link_inner(&maddr, endpoint.config()).await {
                 Ok((mcast_sock, ucast_sock, ucast_addr)) => {
+                    let mtu = if pmtud {
+                        // Forbid the fragmentation of the datagrams sent to the group
+                        if let Err(e) =
+                            zenoh_util::net::set_path_mtu_discovery(&ucast_sock, &ucast_addr)
+                        {
+                            tracing::warn!(
+                                "Can not forbid the fragmentation on UDP link {}: {}",
+                                ucast_addr,
+                                e
+                            );
+                        }
+                        discover_path_mtu(&ucast_addr, &maddr)
+                    } else {
+                        *UDP_DEFAULT_MTU
+                    };
                     let link = Arc::new(LinkMulticastUdp::new(
-                        ucast_addr, ucast_sock, maddr, mcast_sock,
+                        ucast_addr, ucast_sock, maddr, mcast_sock, mtu,
                     ));
 
                     return Ok(LinkMulticast(link));
@@ -0,0 +1,590 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! UDP hole punching.
+//!
+//! A node behind a NAT registers its UDP listener with a rendezvous, i.e. the UDP listener of a
+//! node reachable by everyone, which thus learns the public address of the listener. To open a
+//! link toward another registered node, a node asks the rendezvous to introduce them: the
+//! rendezvous sends to each of them the public address of the other, to which both of them send
+//! punch packets from their listener. Once the node opening the link receives a punch packet, the
+//! NATs on both sides let the traffic through and the link is opened over the listener socket.
+//!
+//! The NAT traversal messages start with a magic that can not start a Zenoh transport message nor
+//! a UDPS record, so that they are told apart from the traffic of the links of the listener.
+//!
+//! The registrations, the introduction requests and the introductions are authenticated with a
+//! secret shared by the rendezvous and its nodes: they carry a nonce, i.e. the time they were sent
+//! at, and the HMAC of their content. The rendezvous only accepts a nonce of a node greater than
+//! the previous one, and the nodes and the rendezvous only accept the nonces no older than the
+//! registrations, so that a message can not be replayed to hijack the registration of a node.
+use super::{
+    socket_addr_to_udp_locator, UDP_NAT_KEEPALIVE_TIME, UDP_NAT_PUNCH_TIMEOUT,
+    UDP_NAT_RETRANSMIT_TIME,
+};
+use crate::unicast::LinkHashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::sync::Notify;
+use zenoh_core::zlock;
+use zenoh_result::{bail, zerror, ZResult};
+
+const MAGIC: &[u8] = b"ZNAT";
+
+const REGISTER: u8 = 0x01;
+const REGISTERED: u8 = 0x02;
+const CONNECT: u8 = 0x03;
+const INTRODUCE: u8 = 0x04;
+const UNKNOWN: u8 = 0x05;
+const PUNCH: u8 = 0x06;
+
+// The length of the nonce and of the HMAC of the authenticated messages
+const NONCE_LEN: usize = 8;
+const TAG_LEN: usize = 32;
+
+// The maximum number of introduction requests handled per second from the same address, and the
+// maximum number of addresses tracked for it, the requests from other addresses being dropped
+const MAX_CONNECTS_PER_SEC: usize = 16;
+const MAX_CONNECT_SOURCES: usize = 1024;
+
+pub(crate) type NatListeners = Arc<Mutex<Vec<Weak<NatListener>>>>;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Message {
+    // A node registers its listener under its id (node => rendezvous)
+    Register {
+        id: String,
+        nonce: u64,
+    },
+    // The public address of the registered listener (rendezvous => node)
+    Registered {
+        addr: SocketAddr,
+    },
+    // A node asks to be introduced to a registered node (node => rendezvous)
+    Connect {
+        id: String,
+        target: String,
+        nonce: u64,
+    },
+    // The public address of a node to punch a hole to (rendezvous => nodes)
+    Introduce {
+        id: String,
+        addr: SocketAddr,
+        nonce: u64,
+    },
+    // The node to introduce is not registered (rendezvous => node)
+    Unknown {
+        id: String,
+    },
+    // A packet opening the NAT of the sender toward the receiver (node => node)
+    Punch,
+}
+
+impl Message {
+    /// Encodes a NAT traversal message, authenticated with the given secret if it needs to be.
+    pub(crate) fn encode(&self, secret: &[u8]) -> ZResult<Vec<u8>> {
+        fn field(buffer: &mut Vec<u8>, s: &str) {
+            // The ids and addresses are much shorter than 255 bytes
+            let s = &s.as_bytes()[..s.len().min(u8::MAX as usize)];
+            buffer.push(s.len() as u8);
+            buffer.extend_from_slice(s);
+        }
+
+        let mut buffer = MAGIC.to_vec();
+        match self {
+            Message::Register { id, .. } => {
+                buffer.push(REGISTER);
+                field(&mut buffer, id);
+            }
+            Message::Registered { addr } => {
+                buffer.push(REGISTERED);
+                field(&mut buffer, &addr.to_string());
+            }
+            Message::Connect { id, target, .. } => {
+                buffer.push(CONNECT);
+                field(&mut buffer, id);
+                field(&mut buffer, target);
+            }
+            Message::Introduce { id, addr, .. } => {
+                buffer.push(INTRODUCE);
+                field(&mut buffer, id);
+                field(&mut buffer, &addr.to_string());
+            }
+            Message::Unknown { id } => {
+                buffer.push(UNKNOWN);
+                field(&mut buffer, id);
+            }
+            Message::Punch => buffer.push(PUNCH),
+        }
+        if let Some(nonce) = self.nonce() {
+            buffer.extend_from_slice(&nonce.to_be_bytes());
+            let tag = zenoh_crypto::hmac::sign(secret, &buffer)?;
+            buffer.extend_from_slice(&tag);
+        }
+        Ok(buffer)
+    }
+
+    /// Decodes a NAT traversal message, returns `None` if the packet is not one, and an error if
+    /// it is malformed or its authentication with the given secret fails.
+    pub(crate) fn decode(packet: &[u8], secret: &[u8]) -> Option<ZResult<Self>> {
+        let buffer = packet.strip_prefix(MAGIC)?;
+        Some(Self::decode_message(packet, buffer, secret))
+    }
+
+    fn decode_message(packet: &[u8], mut buffer: &[u8], secret: &[u8]) -> ZResult<Self> {
+        fn field(buffer: &mut &[u8]) -> Option<String> {
+            let (&len, rest) = buffer.split_first()?;
+            let len = len as usize;
+            if rest.len() < len {
+                return None;
+            }
+            let (s, rest) = rest.split_at(len);
+            *buffer = rest;
+            String::from_utf8(s.to_vec()).ok()
+        }
+
+        fn nonce(buffer: &mut &[u8]) -> Option<u64> {
+            if buffer.len() < NONCE_LEN {
+                return None;
+            }
+            let (nonce, rest) = buffer.split_at(NONCE_LEN);
+            *buffer = rest;
+            Some(u64::from_be_bytes(nonce.try_into().ok()?))
+        }
+
+        let malformed = || zerror!("Malformed NAT traversal message");
+        let (&kind, rest) = buffer.split_first().ok_or_else(malformed)?;
+        buffer = rest;
+        let msg = match kind {
+            REGISTER => Message::Register {
+                id: field(&mut buffer).ok_or_else(malformed)?,
+                nonce: nonce(&mut buffer).ok_or_else(malformed)?,
+            },
+            REGISTERED => Message::Registered {
+                addr: field(&mut buffer)
+                    .and_then(|a| a.parse().ok())
+                    .ok_or_else(malformed)?,
+            },
+            CONNECT => Message::Connect {
+                id: field(&mut buffer).ok_or_else(malformed)?,
+                target: field(&mut buffer).ok_or_else(malformed)?,
+                nonce: nonce(&mut buffer).ok_or_else(malformed)?,
+            },
+            INTRODUCE => Message::Introduce {
+                id: field(&mut buffer).ok_or_else(malformed)?,
+                addr: field(&mut buffer)
+                    .and_then(|a| a.parse().ok())
+                    .ok_or_else(malformed)?,
+                nonce: nonce(&mut buffer).ok_or_else(malformed)?,
+            },
+            UNKNOWN => Message::Unknown {
+                id: field(&mut buffer).ok_or_else(malformed)?,
+            },
+            PUNCH => Message::Punch,
+            _ => bail!("Unknown NAT traversal message kind: {:#x}", kind),
+        };
+        // The HMAC covers the whole message but itself
+        if msg.nonce().is_some() {
+            let signed = &packet[..packet.len() - buffer.len()];
+            if buffer.len() != TAG_LEN || !zenoh_crypto::hmac::verify(secret, signed, buffer) {
+                bail!("Unauthenticated NAT traversal message");
+            }
+        }
+        Ok(msg)
+    }
+
+    fn nonce(&self) -> Option<u64> {
+        match self {
+            Message::Register { nonce, .. }
+            | Message::Connect { nonce, .. }
+            | Message::Introduce { nonce, .. } => Some(*nonce),
+            _ => None,
+        }
+    }
+}
+
+/// The time elapsed since the UNIX epoch in milliseconds.
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// The time during which a registration and the nonce of an authenticated message are valid:
+/// a few keepalive periods, the registrations being renewed upon each of them.
+fn validity() -> Duration {
+    Duration::from_millis(3 * *UDP_NAT_KEEPALIVE_TIME)
+}
+
+/// Whether a nonce is no older than the registrations, and not much ahead of the local clock.
+fn is_fresh(nonce: u64) -> bool {
+    let validity = validity().as_millis() as u64;
+    now_millis().abs_diff(nonce) < validity
+}
+
+/// The NAT traversal configuration of a UDP listener.
+pub(crate) struct NatConfig {
+    // The address of the rendezvous to register with, and the id to register under
+    pub(crate) rendezvous: Option<(SocketAddr, String)>,
+    // Whether the listener is a rendezvous
+    pub(crate) server: bool,
+    // The secret shared with the rendezvous or the registered nodes
+    pub(crate) secret: Vec<u8>,
+}
+
+struct Registration {
+    // The public address of the registered listener
+    addr: SocketAddr,
+    // When the registration was last renewed
+    time: Instant,
+    // The nonce of the last message of the registered node
+    nonce: u64,
+}
+
+#[derive(Default)]
+struct NatState {
+    // The public addresses of the nodes introduced by the rendezvous, by id
+    introduced: HashMap<String, SocketAddr>,
+    // The addresses of the introduced nodes a hole is being punched toward, and since when
+    punching: HashMap<SocketAddr, Instant>,
+    // The addresses of the introduced nodes punch packets were received from
+    punched: HashSet<SocketAddr>,
+    // The registered listeners, by id
+    registered: HashMap<String, Registration>,
+    // The number of introduction requests received per address in the current second, and when
+    // the second started
+    connects: HashMap<SocketAddr, (Instant, usize)>,
+}
+
+impl NatState {
+    /// Registers the listener of a node at the given address, returns `false` if the nonce of the
+    /// message is not greater than the one of the previous message of the node or is not fresh.
+    fn register(&mut self, id: String, addr: SocketAddr, nonce: u64) -> bool {
+        // The registrations not renewed for a few keepalive periods have expired
+        let validity = validity();
+        self.registered.retain(|_, r| r.time.elapsed() < validity);
+        if !is_fresh(nonce) || self.registered.get(&id).is_some_and(|r| nonce <= r.nonce) {
+            return false;
+        }
+        let registration = Registration {
+            addr,
+            time: Instant::now(),
+            nonce,
+        };
+        self.registered.insert(id, registration);
+        true
+    }
+
+    /// Counts an introduction request from the given address, returns `false` if the address
+    /// exceeded its rate.
+    fn connect(&mut self, addr: SocketAddr) -> bool {
+        let period = Duration::from_secs(1);
+        self.connects.retain(|_, (t, _)| t.elapsed() < period);
+        if let Some((_, count)) = self.connects.get_mut(&addr) {
+            *count += 1;
+            return *count <= MAX_CONNECTS_PER_SEC;
+        }
+        if self.connects.len() >= MAX_CONNECT_SOURCES {
+            return false;
+        }
+        self.connects.insert(addr, (Instant::now(), 1));
+        true
+    }
+
+    /// Starts punching a hole toward an introduced node, returns `false` if a hole is already
+    /// being punched toward it.
+    fn punch(&mut self, addr: SocketAddr) -> bool {
+        self.expire_punches();
+        match self.punching.entry(addr) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(e) => {
+                e.insert(Instant::now());
+                true
+            }
+        }
+    }
+
+    /// Records a punch packet received from the given address, returns `false` if no hole is
+    /// being punched toward it, i.e. if it has not been introduced.
+    fn punched(&mut self, addr: SocketAddr) -> bool {
+        self.expire_punches();
+        if !self.punching.contains_key(&addr) {
+            return false;
+        }
+        self.punched.insert(addr);
+        true
+    }
+
+    fn expire_punches(&mut self) {
+        let timeout = Duration::from_millis(*UDP_NAT_PUNCH_TIMEOUT);
+        let punching = &mut self.punching;
+        punching.retain(|_, t| t.elapsed() < timeout);
+        self.punched.retain(|addr| punching.contains_key(addr));
+    }
+}
+
+pub(crate) struct NatListener {
+    config: NatConfig,
+    socket: Arc<UdpSocket>,
+    links: LinkHashMap,
+    local_addr: SocketAddr,
+    state: Mutex<NatState>,
+    // Notified upon each introduction and punch packet
+    event: Notify,
+    // The nonce of the last authenticated message sent
+    nonce: AtomicU64,
+}
+
+impl NatListener {
+    pub(crate) fn new(
+        config: NatConfig,
+        socket: Arc<UdpSocket>,
+        links: LinkHashMap,
+        local_addr: SocketAddr,
+    ) -> Self {
+        Self {
+            config,
+            socket,
+            links,
+            local_addr,
+            state: Mutex::new(NatState::default()),
+            event: Notify::new(),
+            nonce: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn socket(&self) -> &Arc<UdpSocket> {
+        &self.socket
+    }
+
+    pub(crate) fn links(&self) -> &LinkHashMap {
+        &self.links
+    }
+
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub(crate) fn rendezvous(&self) -> Option<SocketAddr> {
+        self.config.rendezvous.as_ref().map(|(addr, _)| *addr)
+    }
+
+    pub(crate) fn secret(&self) -> &[u8] {
+        &self.config.secret
+    }
+
+    /// Returns a nonce for an authenticated message, greater than the previous one.
+    fn nonce(&self) -> u64 {
+        let now = now_millis();
+        let previous = self
+            .nonce
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(now.max(n + 1))
+            });
+        now.max(previous.unwrap_or_default() + 1)
+    }
+
+    async fn send(&self, msg: &Message, dst_addr: SocketAddr) {
+        let buffer = match msg.encode(self.secret()) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                tracing::debug!("Unable to encode a NAT traversal message: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = self.socket.send_to(&buffer, dst_addr).await {
+            tracing::debug!(
+                "Unable to send a NAT traversal message from {} to {}: {}",
+                self.local_addr,
+                dst_addr,
+                e
+            );
+        }
+    }
+
+    /// Registers the listener with its rendezvous, if any.
+    pub(crate) async fn register(&self) {
+        if let Some((rendezvous, id)) = self.config.rendezvous.as_ref() {
+            let msg = Message::Register {
+                id: id.clone(),
+                nonce: self.nonce(),
+            };
+            self.send(&msg, *rendezvous).await;
+        }
+    }
+
+    /// Handles a NAT traversal message received from the given address.
+    pub(crate) async fn handle(self: &Arc<Self>, msg: Message, src_addr: SocketAddr) {
+        tracing::trace!(
+            "Received NAT traversal message on {} from {}: {:?}",
+            self.local_addr,
+            src_addr,
+            msg
+        );
+        match msg {
+            Message::Register { id, nonce } if self.config.server => {
+                if !zlock!(self.state).register(id.clone(), src_addr, nonce) {
+                    tracing::debug!("Ignored replayed registration of {} from {}", id, src_addr);
+                    return;
+                }
+                self.send(&Message::Registered { addr: src_addr }, src_addr)
+                    .await;
+            }
+            Message::Connect { id, target, nonce } if self.config.server => {
+                let target_addr = {
+                    let mut state = zlock!(self.state);
+                    if !state.connect(src_addr) {
+                        tracing::trace!("Too many introduction requests from {}", src_addr);
+                        return;
+                    }
+                    if !state.register(id.clone(), src_addr, nonce) {
+                        tracing::debug!("Ignored replayed introduction request from {}", src_addr);
+                        return;
+                    }
+                    state.registered.get(&target).map(|r| r.addr)
+                };
+                match target_addr {
+                    Some(target_addr) => {
+                        let msg = Message::Introduce {
+                            id: target,
+                            addr: target_addr,
+                            nonce: self.nonce(),
+                        };
+                        self.send(&msg, src_addr).await;
+                        let msg = Message::Introduce {
+                            id,
+                            addr: src_addr,
+                            nonce: self.nonce(),
+                        };
+                        self.send(&msg, target_addr).await;
+                    }
+                    None => self.send(&Message::Unknown { id: target }, src_addr).await,
+                }
+            }
+            Message::Registered { addr } => {
+                tracing::debug!(
+                    "UDP listener {} is reachable at {} from its rendezvous {}",
+                    self.local_addr,
+                    addr,
+                    src_addr
+                );
+            }
+            Message::Introduce { id, addr, nonce }
+                if self.rendezvous() == Some(src_addr) && is_fresh(nonce) =>
+            {
+                let is_new = {
+                    let mut state = zlock!(self.state);
+                    state.introduced.insert(id, addr);
+                    state.punch(addr)
+                };
+                self.event.notify_waiters();
+                // Open the NAT toward the introduced node until it opens a link, the repeated
+                // introductions of a same node sharing the same punching task
+                if is_new {
+                    let c_self = self.clone();
+                    zenoh_runtime::ZRuntime::Acceptor
+                        .spawn(async move { c_self.punch(addr).await });
+                }
+            }
+            Message::Unknown { id } => {
+                tracing::debug!("Node {} is not registered with rendezvous {}", id, src_addr);
+            }
+            Message::Punch => {
+                // Punch packets are not authenticated, only the ones of introduced nodes count
+                if !zlock!(self.state).punched(src_addr) {
+                    tracing::trace!("Ignored punch packet from {}", src_addr);
+                    return;
+                }
+                self.event.notify_waiters();
+            }
+            msg => tracing::debug!(
+                "Ignored NAT traversal message on {} from {}: {:?}",
+                self.local_addr,
+                src_addr,
+                msg
+            ),
+        }
+    }
+
+    async fn punch(&self, dst_addr: SocketAddr) {
+        let start = Instant::now();
+        let timeout = Duration::from_millis(*UDP_NAT_PUNCH_TIMEOUT);
+        let period = Duration::from_millis(*UDP_NAT_RETRANSMIT_TIME);
+        while start.elapsed() < timeout
+            && !zlock!(self.links).contains_key(&(self.local_addr, dst_addr))
+        {
+            self.send(&Message::Punch, dst_addr).await;
+            tokio::time::sleep(period).await;
+        }
+        // Let a later introduction of the node punch the hole again
+        zlock!(self.state).punching.remove(&dst_addr);
+    }
+
+    /// Asks the rendezvous to introduce the node with the given id and waits for a hole to be
+    /// punched toward it, returns its public address.
+    pub(crate) async fn traverse(&self, target: &str) -> ZResult<SocketAddr> {
+        let Some((rendezvous, id)) = self.config.rendezvous.as_ref() else {
+            bail!("UDP listener {} has no rendezvous", self.local_addr);
+        };
+        let timeout = Duration::from_millis(*UDP_NAT_PUNCH_TIMEOUT);
+        let period = Duration::from_millis(*UDP_NAT_RETRANSMIT_TIME);
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        // Wait for the introduction, asking for it again periodically since it may be lost
+        let addr = loop {
+            let event = self.event.notified();
+            if let Some(addr) = zlock!(self.state).introduced.remove(target) {
+                break addr;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                bail!(
+                    "Node {} has not been introduced by rendezvous {}",
+                    target,
+                    socket_addr_to_udp_locator(rendezvous)
+                );
+            }
+            let msg = Message::Connect {
+                id: id.clone(),
+                target: target.to_string(),
+                nonce: self.nonce(),
+            };
+            self.send(&msg, *rendezvous).await;
+            let _ = tokio::time::timeout(period, event).await;
+        };
+
+        // Wait for a punch packet from the introduced node, meaning that the NATs let the traffic
+        // through in both directions
+        loop {
+            let event = self.event.notified();
+            if zlock!(self.state).punched.remove(&addr) {
+                return Ok(addr);
+            }
+            if tokio::time::timeout_at(deadline, event).await.is_err() {
+                return Err(zerror!(
+                    "Unable to punch a hole from {} to node {} at {}",
+                    self.local_addr,
+                    target,
+                    addr
+                )
+                .into());
+            }
+        }
+    }
+}
@@ -0,0 +1,828 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Secured UDP unicast links (`udps`).
+//!
+//! ⚠️ This is a Zenoh-specific record protocol, it is **not** DTLS and does not interoperate with
+//! any DTLS 1.2 or 1.3 implementation. It has not been through a security review yet, so it should
+//! not be relied upon where a reviewed protocol is required (e.g. `tls` or `quic`).
+//!
+//! The handshake is a TLS 1.3 handshake driven by the QUIC interface of rustls: the handshake
+//! messages are carried in records protected by the keys of their TLS encryption level and
+//! retransmitted by flights. The keys of the first flight are derived from a fixed secret, so
+//! they only obfuscate it, as the initial keys of QUIC. Once the handshake is done, each batch is
+//! carried in one record protected by the TLS 1.3 traffic keys, with a sliding window of 64
+//! packet numbers against replays.
+//!
+//! Every record starts with a header of 9 bytes, used as the additional data of the AEAD:
+//!
+//! ```text
+//!  0 1 2 3 4 5 6 7
+//! +-+-+-+-+-+-+-+-+
+//! |     kind      |
+//! +---------------+
+//! ~ packet number ~  (u64, big endian)
+//! +---------------+
+//! ~   payload     ~  (encrypted, followed by the tag)
+//! +---------------+
+//! ```
+//!
+//! The payload of the handshake records is the offset of their data in the handshake stream of
+//! the encryption level (u32, big endian), followed by the data. The handshake is complete on the
+//! client side once it receives the empty `DONE` record of the server.
+//!
+//! Before keeping any state for a new session, a listener answers its first flight with a `RETRY`
+//! record carrying a cookie, a timestamp authenticated with a key of the listener and bound to the
+//! address of the client, as the cookies of DTLS. The client then resends its first flight with
+//! each `INITIAL` record prefixed by a `COOKIE` record echoing it:
+//!
+//! ```text
+//!  0 1 2 3 4 5 6 7
+//! +-+-+-+-+-+-+-+-+
+//! | RETRY/COOKIE  |
+//! +---------------+
+//! ~   timestamp   ~  (u64, big endian, in milliseconds since the UNIX epoch)
+//! +---------------+
+//! ~      tag      ~  (HMAC-SHA3-256 of the timestamp and of the address of the client)
+//! +---------------+
+//! ```
+//!
+//! A spoofed source never receives the cookie, so it can neither open sessions nor have the
+//! listener send more than a `RETRY` record, smaller than the datagram it answers. At most
+//! `UDPS_MAX_HANDSHAKES` handshakes run at once on a listener, the other first flights being
+//! dropped until one of them completes.
+use super::unicast::LinkUnicastUdp;
+use super::{
+    UDPS_HANDSHAKE_TIMEOUT, UDPS_LOCATOR_PREFIX, UDPS_MAX_HANDSHAKES, UDPS_RETRANSMIT_TIME,
+    UDP_MAX_MTU,
+};
+use async_trait::async_trait;
+use rustls::crypto::ring::cipher_suite::TLS13_AES_128_GCM_SHA256;
+use rustls::crypto::SecureRandom;
+use rustls::pki_types::ServerName;
+use rustls::quic::{self, KeyChange, Keys, PacketKey, Version};
+use rustls::{ClientConfig, ServerConfig, Side};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex as AsyncMutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use zenoh_core::{zasynclock, zlock};
+use zenoh_link_commons::LinkUnicastTrait;
+use zenoh_protocol::core::Locator;
+use zenoh_result::{bail, zerror, ZResult};
+
+const KIND_INITIAL: u8 = 0x01;
+const KIND_HANDSHAKE: u8 = 0x02;
+const KIND_DATA: u8 = 0x03;
+const KIND_DONE: u8 = 0x04;
+const KIND_RETRY: u8 = 0x05;
+const KIND_COOKIE: u8 = 0x06;
+
+const HEADER_LEN: usize = 9;
+const OFFSET_LEN: usize = 4;
+// All the AEADs of TLS 1.3 have 16-byte tags
+const TAG_LEN: usize = 16;
+/// The overhead of a data record.
+pub(crate) const UDPS_OVERHEAD: usize = HEADER_LEN + TAG_LEN;
+
+// The handshake data is split in records small enough not to be fragmented by IP
+const HANDSHAKE_CHUNK: usize = 1_152;
+// The records received before the keys to open them
+const MAX_DEFERRED: usize = 16;
+// The initial keys only obfuscate the first handshake messages, as in QUIC
+const INITIAL_SECRET: &[u8] = b"zenoh-udps";
+
+const COOKIE_KEY_LEN: usize = 32;
+const COOKIE_TIME_LEN: usize = 8;
+const COOKIE_TAG_LEN: usize = 32;
+const COOKIE_LEN: usize = COOKIE_TIME_LEN + COOKIE_TAG_LEN;
+// The length of both the RETRY record and the COOKIE record prefixing an INITIAL one
+const COOKIE_RECORD_LEN: usize = 1 + COOKIE_LEN;
+
+fn is_initial(datagram: &[u8]) -> bool {
+    datagram.len() > HEADER_LEN && datagram[0] == KIND_INITIAL
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// What a UDPS listener does with a datagram from a source without a link.
+pub(crate) enum Admission {
+    /// Accept a new session from the source, the permit being held during its handshake.
+    Accept(OwnedSemaphorePermit),
+    /// Send the given `RETRY` record to the source.
+    Retry(Vec<u8>),
+    /// Drop the datagram.
+    Drop,
+}
+
+/// Guards a UDPS listener against the datagrams with spoofed sources, see the module doc.
+pub(crate) struct Gatekeeper {
+    key: [u8; COOKIE_KEY_LEN],
+    handshakes: Arc<Semaphore>,
+}
+
+impl Gatekeeper {
+    pub(crate) fn new() -> ZResult<Self> {
+        let mut key = [0u8; COOKIE_KEY_LEN];
+        rustls::crypto::ring::default_provider()
+            .secure_random
+            .fill(&mut key)
+            .map_err(|e| zerror!("Can not generate the UDPS cookie key: {:?}", e))?;
+        Ok(Self {
+            key,
+            handshakes: Arc::new(Semaphore::new(*UDPS_MAX_HANDSHAKES)),
+        })
+    }
+
+    fn cookie_data(time: &[u8], addr: &SocketAddr) -> Vec<u8> {
+        let mut data = time.to_vec();
+        match addr.ip() {
+            IpAddr::V4(ip) => data.extend_from_slice(&ip.octets()),
+            IpAddr::V6(ip) => data.extend_from_slice(&ip.octets()),
+        }
+        data.extend_from_slice(&addr.port().to_be_bytes());
+        data
+    }
+
+    fn retry(&self, addr: &SocketAddr) -> ZResult<Vec<u8>> {
+        let time = now_millis().to_be_bytes();
+        let tag = zenoh_crypto::hmac::sign(&self.key, &Self::cookie_data(&time, addr))?;
+        let mut record = Vec::with_capacity(COOKIE_RECORD_LEN);
+        record.push(KIND_RETRY);
+        record.extend_from_slice(&time);
+        record.extend_from_slice(&tag);
+        Ok(record)
+    }
+
+    fn check(&self, cookie: &[u8], addr: &SocketAddr) -> bool {
+        let (time, tag) = cookie.split_at(COOKIE_TIME_LEN);
+        let mut millis = [0u8; COOKIE_TIME_LEN];
+        millis.copy_from_slice(time);
+        // The cookie must have been issued during the last handshake timeout
+        let age = now_millis().saturating_sub(u64::from_be_bytes(millis));
+        age <= *UDPS_HANDSHAKE_TIMEOUT
+            && zenoh_crypto::hmac::verify(&self.key, &Self::cookie_data(time, addr), tag)
+    }
+
+    /// Decides what to do with a `datagram` received from `addr`, for which there is no link.
+    pub(crate) fn admit(&self, datagram: &[u8], addr: &SocketAddr) -> Admission {
+        if datagram.len() > COOKIE_RECORD_LEN && datagram[0] == KIND_COOKIE {
+            let (cookie, record) = datagram[1..].split_at(COOKIE_LEN);
+            if !is_initial(record) {
+                return Admission::Drop;
+            }
+            if self.check(cookie, addr) {
+                return match self.handshakes.clone().try_acquire_owned() {
+                    Ok(permit) => Admission::Accept(permit),
+                    Err(_) => {
+                        tracing::debug!(
+                            "Too many UDPS handshakes in progress, dropping the one of {}",
+                            addr
+                        );
+                        Admission::Drop
+                    }
+                };
+            }
+            // The cookie has expired, or was not issued by this listener: issue a new one
+            tracing::trace!("Invalid UDPS cookie from {}", addr);
+        } else if !is_initial(datagram) {
+            return Admission::Drop;
+        }
+        // Never answer with more bytes than received
+        if datagram.len() >= COOKIE_RECORD_LEN {
+            return match self.retry(addr) {
+                Ok(record) => Admission::Retry(record),
+                Err(e) => {
+                    tracing::debug!("Can not issue a UDPS cookie to {}: {}", addr, e);
+                    Admission::Drop
+                }
+            };
+        }
+        Admission::Drop
+    }
+}
+
+fn header(kind: u8, pn: u64) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[0] = kind;
+    header[1..].copy_from_slice(&pn.to_be_bytes());
+    header
+}
+
+fn seal(key: &dyn PacketKey, kind: u8, pn: u64, payload: &[&[u8]]) -> ZResult<Vec<u8>> {
+    let len = payload.iter().map(|p| p.len()).sum::<usize>();
+    let mut record = Vec::with_capacity(HEADER_LEN + len + TAG_LEN);
+    record.extend_from_slice(&header(kind, pn));
+    for p in payload {
+        record.extend_from_slice(p);
+    }
+    let (header, payload) = record.split_at_mut(HEADER_LEN);
+    let tag = key
+        .encrypt_in_place(pn, header, payload)
+        .map_err(|e| zerror!("UDPS encryption error: {}", e))?;
+    record.extend_from_slice(tag.as_ref());
+    Ok(record)
+}
+
+fn open<'a>(key: &dyn PacketKey, record: &'a mut [u8]) -> Option<(u64, &'a [u8])> {
+    if record.len() < HEADER_LEN + TAG_LEN {
+        return None;
+    }
+    let (header, payload) = record.split_at_mut(HEADER_LEN);
+    let mut pn = [0u8; 8];
+    pn.copy_from_slice(&header[1..]);
+    let pn = u64::from_be_bytes(pn);
+    key.decrypt_in_place(pn, header, payload)
+        .ok()
+        .map(|plain| (pn, plain))
+}
+
+/// A sliding window of the last 64 packet numbers received.
+#[derive(Default)]
+struct ReplayWindow {
+    next: u64,
+    seen: u64,
+}
+
+impl ReplayWindow {
+    fn check(&self, pn: u64) -> bool {
+        if pn >= self.next {
+            return true;
+        }
+        let age = self.next - 1 - pn;
+        age < 64 && self.seen & (1 << age) == 0
+    }
+
+    fn update(&mut self, pn: u64) {
+        if pn >= self.next {
+            let shift = pn + 1 - self.next;
+            self.seen = if shift >= 64 { 0 } else { self.seen << shift };
+            self.seen |= 1;
+            self.next = pn + 1;
+        } else {
+            self.seen |= 1 << (self.next - 1 - pn);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Space {
+    keys: Option<Keys>,
+    send_pn: u64,
+    send_offset: u64,
+    recv_offset: u64,
+    pending: BTreeMap<u64, Vec<u8>>,
+    // The contiguous handshake data not forming a whole message yet
+    partial: Vec<u8>,
+}
+
+enum Received {
+    Nothing,
+    Progress,
+    Retransmission,
+    Retry,
+    Done(u64),
+}
+
+struct Handshake {
+    conn: quic::Connection,
+    side: Side,
+    // The Initial and Handshake encryption levels
+    spaces: [Space; 2],
+    write_level: usize,
+    one_rtt: Option<Keys>,
+    // The records of the last flight, resent until the peer answers
+    flight: Vec<Vec<u8>>,
+    deferred: Vec<Vec<u8>>,
+    // The cookie of the server, echoed with the INITIAL records of the client
+    cookie: Option<Vec<u8>>,
+}
+
+impl Handshake {
+    fn new(conn: quic::Connection, side: Side) -> ZResult<Self> {
+        let suite = TLS13_AES_128_GCM_SHA256
+            .tls13()
+            .ok_or_else(|| zerror!("UDPS requires a TLS 1.3 cipher suite"))?;
+        let alg = suite
+            .quic
+            .ok_or_else(|| zerror!("UDPS requires a cipher suite supporting QUIC"))?;
+        let initial = Keys::initial(Version::V1, suite, alg, INITIAL_SECRET, side);
+        let mut spaces: [Space; 2] = Default::default();
+        spaces[0].keys = Some(initial);
+        Ok(Self {
+            conn,
+            side,
+            spaces,
+            write_level: 0,
+            one_rtt: None,
+            flight: vec![],
+            deferred: vec![],
+            cookie: None,
+        })
+    }
+
+    fn is_complete(&self) -> bool {
+        self.one_rtt.is_some() && !self.conn.is_handshaking()
+    }
+
+    fn seal_handshake(&mut self, data: &[u8]) -> ZResult<Vec<Vec<u8>>> {
+        let level = self.write_level;
+        let kind = if level == 0 {
+            KIND_INITIAL
+        } else {
+            KIND_HANDSHAKE
+        };
+        let space = &mut self.spaces[level];
+        let key = match space.keys.as_ref() {
+            Some(keys) => keys.local.packet.as_ref(),
+            None => bail!("Missing the UDPS keys of the handshake"),
+        };
+        let mut records = vec![];
+        for chunk in data.chunks(HANDSHAKE_CHUNK) {
+            let offset = u32::try_from(space.send_offset)
+                .map_err(|_| zerror!("UDPS handshake too large"))?;
+            records.push(seal(
+                key,
+                kind,
+                space.send_pn,
+                &[&offset.to_be_bytes(), chunk],
+            )?);
+            space.send_pn += 1;
+            space.send_offset += chunk.len() as u64;
+        }
+        Ok(records)
+    }
+
+    /// Collects the handshake messages rustls has to send, in records.
+    fn write(&mut self) -> ZResult<Vec<Vec<u8>>> {
+        let mut records = vec![];
+        loop {
+            let mut data = vec![];
+            let change = self.conn.write_hs(&mut data);
+            if !data.is_empty() {
+                if self.write_level > 1 {
+                    // Post-handshake messages (e.g. session tickets) are not used
+                    tracing::trace!(
+                        "Discarding {} bytes of UDPS post-handshake data",
+                        data.len()
+                    );
+                } else {
+                    records.extend(self.seal_handshake(&data)?);
+                }
+            }
+            match change {
+                Some(KeyChange::Handshake { keys }) => {
+                    self.spaces[1].keys = Some(keys);
+                    self.write_level = 1;
+                }
+                Some(KeyChange::OneRtt { keys, .. }) => {
+                    self.one_rtt = Some(keys);
+                    self.write_level = 2;
+                }
+                None if data.is_empty() => break,
+                None => {}
+            }
+        }
+        Ok(records)
+    }
+
+    /// Processes a record received during the handshake.
+    fn read(&mut self, record: &mut [u8]) -> ZResult<Received> {
+        let record = match record.first() {
+            Some(&KIND_COOKIE) if self.side == Side::Server && record.len() > COOKIE_RECORD_LEN => {
+                // The cookie has already been checked by the listener
+                &mut record[COOKIE_RECORD_LEN..]
+            }
+            _ => record,
+        };
+        let level = match record.first() {
+            Some(&KIND_RETRY) if self.side == Side::Client => {
+                // Only the server's first answer may be a RETRY
+                if record.len() != COOKIE_RECORD_LEN || self.spaces[0].recv_offset > 0 {
+                    return Ok(Received::Nothing);
+                }
+                // The first cookie is echoed right away, the next ones with the retransmissions
+                let first = self.cookie.replace(record[1..].to_vec()).is_none();
+                return Ok(if first {
+                    Received::Retry
+                } else {
+                    Received::Retransmission
+                });
+            }
+            Some(&KIND_INITIAL) => 0,
+            Some(&KIND_HANDSHAKE) => 1,
+            Some(&KIND_DONE) if self.side == Side::Client => {
+                let Some(keys) = self.one_rtt.as_ref() else {
+                    self.defer(record);
+                    return Ok(Received::Nothing);
+                };
+                return Ok(match open(keys.remote.packet.as_ref(), record) {
+                    Some((pn, _)) => Received::Done(pn),
+                    None => Received::Nothing,
+                });
+            }
+            _ => return Ok(Received::Nothing),
+        };
+
+        let space = &mut self.spaces[level];
+        let Some(keys) = space.keys.as_ref() else {
+            self.defer(record);
+            return Ok(Received::Nothing);
+        };
+        let Some((_, plain)) = open(keys.remote.packet.as_ref(), record) else {
+            return Ok(Received::Nothing);
+        };
+        if plain.len() < OFFSET_LEN {
+            return Ok(Received::Nothing);
+        }
+        let mut offset = [0u8; OFFSET_LEN];
+        offset.copy_from_slice(&plain[..OFFSET_LEN]);
+        let offset = u32::from_be_bytes(offset) as u64;
+        let data = &plain[OFFSET_LEN..];
+
+        if offset + data.len() as u64 <= space.recv_offset {
+            // The peer has not received our last flight
+            return Ok(Received::Retransmission);
+        }
+        space.pending.entry(offset).or_insert_with(|| data.to_vec());
+
+        // Reassemble the contiguous handshake data
+        while let Some(entry) = space.pending.first_entry() {
+            let start = *entry.key();
+            if start > space.recv_offset {
+                break;
+            }
+            let data = entry.remove();
+            let end = start + data.len() as u64;
+            if end <= space.recv_offset {
+                continue;
+            }
+            let skip = (space.recv_offset - start) as usize;
+            space.partial.extend_from_slice(&data[skip..]);
+            space.recv_offset = end;
+        }
+
+        // Pass the whole handshake messages to rustls, i.e. a 4-byte header (type and
+        // 24-bit length) followed by the body, since rustls does not join them in QUIC mode
+        let mut progress = false;
+        while space.partial.len() >= 4 {
+            let len = u32::from_be_bytes([0, space.partial[1], space.partial[2], space.partial[3]]);
+            let end = 4 + len as usize;
+            if space.partial.len() < end {
+                break;
+            }
+            self.conn.read_hs(&space.partial[..end]).map_err(|e| {
+                zerror!(
+                    "UDPS handshake error: {} (alert: {:?})",
+                    e,
+                    self.conn.alert()
+                )
+            })?;
+            space.partial.drain(..end);
+            progress = true;
+        }
+        Ok(if progress {
+            Received::Progress
+        } else {
+            Received::Nothing
+        })
+    }
+
+    fn defer(&mut self, record: &[u8]) {
+        if self.deferred.len() < MAX_DEFERRED {
+            self.deferred.push(record.to_vec());
+        }
+    }
+
+    async fn send_flight(&self, link: &LinkUnicastUdp) -> ZResult<()> {
+        for record in self.flight.iter() {
+            match self.cookie.as_ref() {
+                Some(cookie) if record[0] == KIND_INITIAL => {
+                    let mut datagram = Vec::with_capacity(COOKIE_RECORD_LEN + record.len());
+                    datagram.push(KIND_COOKIE);
+                    datagram.extend_from_slice(cookie);
+                    datagram.extend_from_slice(record);
+                    link.write_all(&datagram).await?;
+                }
+                _ => link.write_all(record).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the handshake until its completion, returning the `DONE` record on the server side
+    /// and its packet number on the client side.
+    async fn run(&mut self, link: &LinkUnicastUdp) -> ZResult<(Option<Vec<u8>>, Option<u64>)> {
+        let rto = Duration::from_millis(*UDPS_RETRANSMIT_TIME);
+        let mut timeout = rto;
+        let mut next = Instant::now() + timeout;
+        let mut last_resend = Instant::now();
+        let mut buffer = zenoh_buffers::vec::uninit(UDP_MAX_MTU as usize);
+
+        // The client sends the first flight
+        let records = self.write()?;
+        if !records.is_empty() {
+            self.flight = records;
+            self.send_flight(link).await?;
+        }
+
+        loop {
+            let n = tokio::select! {
+                res = link.read(&mut buffer) => res?,
+                _ = tokio::time::sleep_until(next) => {
+                    // Resend the last flight with an exponential backoff
+                    tracing::trace!("Retransmitting the UDPS handshake flight on {}", link);
+                    self.send_flight(link).await?;
+                    timeout *= 2;
+                    next = Instant::now() + timeout;
+                    continue;
+                }
+            };
+
+            let mut inputs = vec![buffer[..n].to_vec()];
+            let mut progress = false;
+            while let Some(mut record) = inputs.pop() {
+                match self.read(&mut record)? {
+                    Received::Nothing => {}
+                    Received::Progress => progress = true,
+                    Received::Retransmission => {
+                        if last_resend.elapsed() > rto / 4 {
+                            last_resend = Instant::now();
+                            self.send_flight(link).await?;
+                        }
+                    }
+                    Received::Retry => {
+                        last_resend = Instant::now();
+                        self.send_flight(link).await?;
+                        timeout = rto;
+                        next = Instant::now() + timeout;
+                    }
+                    Received::Done(pn) => return Ok((None, Some(pn))),
+                }
+                if progress {
+                    let records = self.write()?;
+                    if !records.is_empty() {
+                        self.flight = records;
+                        self.send_flight(link).await?;
+                        timeout = rto;
+                        next = Instant::now() + timeout;
+                    }
+                    // New keys may open the records received too early
+                    inputs.append(&mut mem::take(&mut self.deferred));
+                    progress = false;
+                }
+            }
+
+            if self.side == Side::Server && self.is_complete() {
+                // Acknowledge the end of the handshake to the client
+                let keys = self.one_rtt.as_ref().unwrap();
+                let done = seal(keys.local.packet.as_ref(), KIND_DONE, 0, &[])?;
+                link.write_all(&done).await?;
+                return Ok((Some(done), None));
+            }
+        }
+    }
+}
+
+async fn handshake(
+    link: LinkUnicastUdp,
+    conn: quic::Connection,
+    side: Side,
+) -> ZResult<LinkUnicastUdps> {
+    let mut hs = Handshake::new(conn, side)?;
+    let res = match tokio::time::timeout(
+        Duration::from_millis(*UDPS_HANDSHAKE_TIMEOUT),
+        hs.run(&link),
+    )
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => Err(zerror!("UDPS handshake on {} timed out", link).into()),
+    };
+    let (done, done_pn) = match res {
+        Ok(res) => res,
+        Err(e) => {
+            let _ = link.close().await;
+            return Err(e);
+        }
+    };
+
+    let keys = hs
+        .one_rtt
+        .take()
+        .ok_or_else(|| zerror!("Missing the UDPS traffic keys on {}", link))?;
+    let mut replay = ReplayWindow::default();
+    if let Some(pn) = done_pn {
+        replay.update(pn);
+    }
+    // The server has used the first packet number for the DONE record
+    let local_pn = if done.is_some() { 1 } else { 0 };
+    Ok(LinkUnicastUdps::new(link, keys, local_pn, replay, done))
+}
+
+/// Opens a UDPS session over a UDP link, as a client.
+pub(crate) async fn connect(
+    link: LinkUnicastUdp,
+    config: Arc<ClientConfig>,
+    server_name: ServerName<'static>,
+) -> ZResult<LinkUnicastUdps> {
+    let conn = quic::ClientConnection::new(config, Version::V1, server_name, vec![])
+        .map_err(|e| zerror!("Can not start the UDPS handshake on {}: {}", link, e))?;
+    handshake(link, conn.into(), Side::Client).await
+}
+
+/// Accepts a UDPS session over a UDP link, as a server.
+pub(crate) async fn accept(
+    link: LinkUnicastUdp,
+    config: Arc<ServerConfig>,
+) -> ZResult<LinkUnicastUdps> {
+    let conn = quic::ServerConnection::new(config, Version::V1, vec![])
+        .map_err(|e| zerror!("Can not start the UDPS handshake on {}: {}", link, e))?;
+    handshake(link, conn.into(), Side::Server).await
+}
+
+pub struct LinkUnicastUdps {
+    // The underlying UDP link
+    inner: LinkUnicastUdp,
+    src_locator: Locator,
+    dst_locator: Locator,
+    // Protects the outgoing records
+    local: Box<dyn PacketKey>,
+    local_pn: AtomicU64,
+    // Opens the incoming records
+    remote: Box<dyn PacketKey>,
+    replay: Mutex<ReplayWindow>,
+    // The record ending the handshake, resent if the peer retransmits its last flight
+    done: Option<Vec<u8>>,
+    rbuf: AsyncMutex<Vec<u8>>,
+}
+
+impl LinkUnicastUdps {
+    fn new(
+        inner: LinkUnicastUdp,
+        keys: Keys,
+        local_pn: u64,
+        replay: ReplayWindow,
+        done: Option<Vec<u8>>,
+    ) -> Self {
+        Self {
+            src_locator: Locator::new(UDPS_LOCATOR_PREFIX, inner.src_addr.to_string(), "").unwrap(),
+            dst_locator: Locator::new(UDPS_LOCATOR_PREFIX, inner.dst_addr.to_string(), "").unwrap(),
+            inner,
+            local: keys.local.packet,
+            local_pn: AtomicU64::new(local_pn),
+            remote: keys.remote.packet,
+            replay: Mutex::new(replay),
+            done,
+            rbuf: AsyncMutex::new(zenoh_buffers::vec::uninit(UDP_MAX_MTU as usize)),
+        }
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastUdps {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing UDPS link: {}", self);
+        self.inner.close().await
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        let pn = self.local_pn.fetch_add(1, Ordering::Relaxed);
+        let record = seal(self.local.as_ref(), KIND_DATA, pn, &[buffer])?;
+        self.inner.write_all(&record).await?;
+        Ok(buffer.len())
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        let _ = self.write(buffer).await?;
+        Ok(())
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let mut rbuf = zasynclock!(self.rbuf);
+        loop {
+            let n = self.inner.read(&mut rbuf).await?;
+            let record = &mut rbuf[..n];
+            match record.first() {
+                Some(&KIND_DATA) => {}
+                Some(&KIND_HANDSHAKE) => {
+                    // The client has not received the end of the handshake
+                    if let Some(done) = self.done.as_ref() {
+                        self.inner.write_all(done).await?;
+                    }
+                    continue;
+                }
+                _ => continue,
+            }
+
+            if record.len() < HEADER_LEN {
+                continue;
+            }
+            let mut pn = [0u8; 8];
+            pn.copy_from_slice(&record[1..HEADER_LEN]);
+            if !zlock!(self.replay).check(u64::from_be_bytes(pn)) {
+                tracing::trace!("Dropping a replayed UDPS record on {}", self);
+                continue;
+            }
+            let Some((pn, plain)) = open(self.remote.as_ref(), record) else {
+                tracing::trace!("Dropping a UDPS record failing authentication on {}", self);
+                continue;
+            };
+            zlock!(self.replay).update(pn);
+
+            if plain.len() > buffer.len() {
+                bail!(
+                    "UDPS record of {} bytes larger than the read buffer on {}",
+                    plain.len(),
+                    self
+                );
+            }
+            buffer[..plain.len()].copy_from_slice(plain);
+            return Ok(plain.len());
+        }
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let n = self.read(buffer).await?;
+        if n != buffer.len() {
+            bail!(
+                "Read error on UDPS link {}: received {} out of {} bytes",
+                self,
+                n,
+                buffer.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        self.inner.set_dscp(dscp)
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        self.inner.get_mtu() - UDPS_OVERHEAD as u16
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        self.inner.get_interface_names()
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for LinkUnicastUdps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.inner.src_addr, self.inner.dst_addr)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastUdps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Udps")
+            .field("src", &self.inner.src_addr)
+            .field("dst", &self.inner.dst_addr)
+            .finish()
+    }
+}
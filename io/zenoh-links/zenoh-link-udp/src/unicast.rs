@@ -12,10 +12,15 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::{
-    get_udp_addrs, socket_addr_to_udp_locator, UDP_ACCEPT_THROTTLE_TIME, UDP_DEFAULT_MTU,
-    UDP_MAX_MTU,
+    config::{UDP_NAT_ID, UDP_NAT_RENDEZVOUS, UDP_NAT_SECRET, UDP_NAT_SERVER, UDP_NAT_TARGET},
+    discover_path_mtu, get_path_mtu, get_pmtud_config, get_udp_addrs,
+    nat::{Message as NatMessage, NatConfig, NatListener, NatListeners},
+    secure::{self, Admission, Gatekeeper},
+    socket_addr_to_udp_locator, UDP_ACCEPT_THROTTLE_TIME, UDP_DEFAULT_MTU, UDP_MAX_MTU,
+    UDP_NAT_KEEPALIVE_TIME,
 };
 use async_trait::async_trait;
+use rustls::ServerConfig;
 use std::collections::HashMap;
 use std::fmt;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
@@ -29,11 +34,13 @@ use zenoh_link_commons::{
     get_ip_interface_names, ConstructibleLinkManagerUnicast, LinkManagerUnicastTrait, LinkUnicast,
     LinkUnicastTrait, ListenersUnicastIP, NewLinkChannelSender, BIND_INTERFACE,
 };
+use zenoh_link_tls::{get_tls_server_name, TlsClientConfig, TlsServerConfig};
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{bail, zerror, Error as ZError, ZResult};
 use zenoh_sync::Mvar;
 
-type LinkHashMap = Arc<Mutex<HashMap<(SocketAddr, SocketAddr), Weak<LinkUnicastUdpUnconnected>>>>;
+pub(crate) type LinkHashMap =
+    Arc<Mutex<HashMap<(SocketAddr, SocketAddr), Weak<LinkUnicastUdpUnconnected>>>>;
 type LinkInput = (Vec<u8>, usize);
 type LinkLeftOver = (Vec<u8>, usize, usize);
 
@@ -61,7 +68,7 @@ impl LinkUnicastUdpConnected {
     }
 }
 
-struct LinkUnicastUdpUnconnected {
+pub(crate) struct LinkUnicastUdpUnconnected {
     socket: Weak<UdpSocket>,
     links: LinkHashMap,
     input: Mvar<LinkInput>,
@@ -121,13 +128,15 @@ enum LinkUnicastUdpVariant {
 
 pub struct LinkUnicastUdp {
     // The source socket address of this link (address used on the local host)
-    src_addr: SocketAddr,
+    pub(crate) src_addr: SocketAddr,
     src_locator: Locator,
     // The destination socket address of this link (address used on the remote host)
-    dst_addr: SocketAddr,
+    pub(crate) dst_addr: SocketAddr,
     dst_locator: Locator,
     // The UDP socket is connected to the peer
     variant: LinkUnicastUdpVariant,
+    // The MTU of this link, possibly discovered on its path
+    mtu: u16,
 }
 
 impl LinkUnicastUdp {
@@ -135,6 +144,7 @@ impl LinkUnicastUdp {
         src_addr: SocketAddr,
         dst_addr: SocketAddr,
         variant: LinkUnicastUdpVariant,
+        mtu: u16,
     ) -> LinkUnicastUdp {
         LinkUnicastUdp {
             src_locator: socket_addr_to_udp_locator(&src_addr),
@@ -142,6 +152,7 @@ impl LinkUnicastUdp {
             src_addr,
             dst_addr,
             variant,
+            mtu,
         }
     }
 }
@@ -189,6 +200,17 @@ impl LinkUnicastTrait for LinkUnicastUdp {
         Ok(())
     }
 
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        match &self.variant {
+            LinkUnicastUdpVariant::Connected(link) => {
+                zenoh_util::net::set_dscp(link.socket.as_ref(), &self.src_addr, dscp)
+                    .map_err(|e| zerror!("Can not set the DSCP of UDP link {}: {}", self, e).into())
+            }
+            // The socket of the listener is shared by all its links
+            LinkUnicastUdpVariant::Unconnected(_) => Ok(()),
+        }
+    }
+
     #[inline(always)]
     fn get_src(&self) -> &Locator {
         &self.src_locator
@@ -201,7 +223,7 @@ impl LinkUnicastTrait for LinkUnicastUdp {
 
     #[inline(always)]
     fn get_mtu(&self) -> u16 {
-        *UDP_DEFAULT_MTU
+        self.mtu
     }
 
     #[inline(always)]
@@ -239,6 +261,9 @@ impl fmt::Debug for LinkUnicastUdp {
 pub struct LinkManagerUnicastUdp {
     manager: NewLinkChannelSender,
     listeners: ListenersUnicastIP,
+    // The listeners taking part in NAT traversal
+    nat_listeners: NatListeners,
+    secure: bool,
 }
 
 impl LinkManagerUnicastUdp {
@@ -246,6 +271,26 @@ impl LinkManagerUnicastUdp {
         Self {
             manager,
             listeners: ListenersUnicastIP::new(),
+            nat_listeners: NatListeners::default(),
+            secure: false,
+        }
+    }
+
+    /// Creates a manager of secured UDP links (`udps`), see the `secure` module.
+    pub fn new_secure(manager: NewLinkChannelSender) -> Self {
+        Self {
+            manager,
+            listeners: ListenersUnicastIP::new(),
+            nat_listeners: NatListeners::default(),
+            secure: true,
+        }
+    }
+
+    fn protocol(&self) -> &str {
+        if self.secure {
+            "UDPS"
+        } else {
+            "UDP"
         }
     }
 }
@@ -260,7 +305,8 @@ impl LinkManagerUnicastUdp {
         &self,
         dst_addr: &SocketAddr,
         iface: Option<&str>,
-    ) -> ZResult<(UdpSocket, SocketAddr, SocketAddr)> {
+        pmtud: bool,
+    ) -> ZResult<(UdpSocket, SocketAddr, SocketAddr, u16)> {
         // Establish a UDP socket
         let socket = UdpSocket::bind(SocketAddr::new(
             if dst_addr.is_ipv4() {
@@ -301,13 +347,28 @@ impl LinkManagerUnicastUdp {
             e
         })?;
 
-        Ok((socket, src_addr, dst_addr))
+        let mtu = if pmtud {
+            // Forbid the fragmentation of the datagrams so that the path MTU gets discovered
+            if let Err(e) = zenoh_util::net::set_path_mtu_discovery(&socket, &src_addr) {
+                tracing::warn!(
+                    "Can not forbid the fragmentation on UDP link {}: {}",
+                    src_addr,
+                    e
+                );
+            }
+            get_path_mtu(&socket, &src_addr, &dst_addr)
+        } else {
+            *UDP_DEFAULT_MTU
+        };
+
+        Ok((socket, src_addr, dst_addr, mtu))
     }
 
     async fn new_listener_inner(
         &self,
         addr: &SocketAddr,
         iface: Option<&str>,
+        pmtud: bool,
     ) -> ZResult<(UdpSocket, SocketAddr)> {
         // Bind the UDP socket
         let socket = UdpSocket::bind(addr).await.map_err(|e| {
@@ -320,6 +381,17 @@ impl LinkManagerUnicastUdp {
             zenoh_util::net::set_bind_to_device_udp_socket(&socket, iface)?;
         }
 
+        if pmtud {
+            // Forbid the fragmentation of the datagrams sent to any peer of the listener
+            if let Err(e) = zenoh_util::net::set_path_mtu_discovery(&socket, addr) {
+                tracing::warn!(
+                    "Can not forbid the fragmentation on UDP listener {}: {}",
+                    addr,
+                    e
+                );
+            }
+        }
+
         let local_addr = socket.local_addr().map_err(|e| {
             let e = zerror!("Can not create a new UDP listener on {}: {}", addr, e);
             tracing::warn!("{}", e);
@@ -328,6 +400,114 @@ impl LinkManagerUnicastUdp {
 
         Ok((socket, local_addr))
     }
+
+    async fn get_nat_config(
+        &self,
+        endpoint: &EndPoint,
+        addr: &SocketAddr,
+    ) -> ZResult<Option<NatConfig>> {
+        let config = endpoint.config();
+        let server = match config.get(UDP_NAT_SERVER) {
+            Some(s) => s.parse().map_err(|_| {
+                zerror!(
+                    "Invalid {} value on {} endpoint {}: {}",
+                    UDP_NAT_SERVER,
+                    self.protocol(),
+                    endpoint,
+                    s
+                )
+            })?,
+            None => false,
+        };
+        let rendezvous = match (config.get(UDP_NAT_RENDEZVOUS), config.get(UDP_NAT_ID)) {
+            (Some(rendezvous), Some(id)) => {
+                // The rendezvous is reached from the listener socket, thus with the same IP version
+                let rendezvous_addr = tokio::net::lookup_host(rendezvous)
+                    .await
+                    .map_err(|e| {
+                        zerror!(
+                            "Invalid {} value: {}: {}",
+                            UDP_NAT_RENDEZVOUS,
+                            rendezvous,
+                            e
+                        )
+                    })?
+                    .find(|a| a.is_ipv4() == addr.is_ipv4())
+                    .ok_or_else(|| {
+                        zerror!(
+                            "{} {} is not reachable from UDP listener {}",
+                            UDP_NAT_RENDEZVOUS,
+                            rendezvous,
+                            addr
+                        )
+                    })?;
+                Some((rendezvous_addr, id.to_string()))
+            }
+            (None, None) => None,
+            _ => bail!(
+                "{} and {} must be set together on {} endpoint {}",
+                UDP_NAT_RENDEZVOUS,
+                UDP_NAT_ID,
+                self.protocol(),
+                endpoint
+            ),
+        };
+        if !server && rendezvous.is_none() {
+            return Ok(None);
+        }
+        let secret = config.get(UDP_NAT_SECRET).ok_or_else(|| {
+            zerror!(
+                "{} must be set for NAT traversal on {} endpoint {}",
+                UDP_NAT_SECRET,
+                self.protocol(),
+                endpoint
+            )
+        })?;
+        Ok(Some(NatConfig {
+            rendezvous,
+            server,
+            secret: secret.as_bytes().to_vec(),
+        }))
+    }
+
+    async fn new_link_nat(
+        &self,
+        endpoint: &EndPoint,
+        target: &str,
+    ) -> ZResult<(SocketAddr, SocketAddr, Arc<LinkUnicastUdpUnconnected>)> {
+        let rendezvous: Vec<SocketAddr> = get_udp_addrs(endpoint.address()).await?.collect();
+        let listener = zlock!(self.nat_listeners)
+            .iter()
+            .filter_map(|l| l.upgrade())
+            .find(|l| l.rendezvous().is_some_and(|r| rendezvous.contains(&r)))
+            .ok_or_else(|| {
+                zerror!(
+                    "Can not create a new {} link to {}: no listener is registered with rendezvous {}",
+                    self.protocol(),
+                    target,
+                    endpoint.address()
+                )
+            })?;
+
+        let dst_addr = listener.traverse(target).await?;
+        let src_addr = listener.local_addr();
+        tracing::debug!(
+            "Punched a hole from {} to node {} at {}",
+            src_addr,
+            target,
+            dst_addr
+        );
+
+        // The link shares the listener socket, whose NAT mapping has been opened toward the node
+        let unconnected = Arc::new(LinkUnicastUdpUnconnected {
+            socket: Arc::downgrade(listener.socket()),
+            links: listener.links().clone(),
+            input: Mvar::new(),
+            leftover: AsyncMutex::new(None),
+        });
+        zlock!(listener.links()).insert((src_addr, dst_addr), Arc::downgrade(&unconnected));
+        Ok((src_addr, dst_addr, unconnected))
+    }
 }
 
 #[async_trait]
@@ -338,21 +518,64 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
             .filter(|a| !a.ip().is_multicast());
         let config = endpoint.config();
         let iface = config.get(BIND_INTERFACE);
+        let pmtud = get_pmtud_config(&endpoint)?;
+
+        let tls = if self.secure {
+            let client_config = TlsClientConfig::new(&config)
+                .await
+                .map_err(|e| zerror!("Cannot create a new UDPS link to {}: {}", endpoint, e))?;
+            let epaddr = endpoint.address();
+            let server_name = get_tls_server_name(&epaddr)?.to_owned();
+            Some((Arc::new(client_config.client_config), server_name))
+        } else {
+            None
+        };
+
+        if let Some(target) = config.get(UDP_NAT_TARGET) {
+            let (src_addr, dst_addr, unconnected) = self.new_link_nat(&endpoint, target).await?;
+            let mtu = if pmtud {
+                discover_path_mtu(&src_addr, &dst_addr)
+            } else {
+                *UDP_DEFAULT_MTU
+            };
+            let link = LinkUnicastUdp::new(
+                src_addr,
+                dst_addr,
+                LinkUnicastUdpVariant::Unconnected(unconnected),
+                mtu,
+            );
+            return match tls {
+                Some((client_config, server_name)) => {
+                    let link = secure::connect(link, client_config, server_name).await?;
+                    Ok(LinkUnicast(Arc::new(link)))
+                }
+                None => Ok(LinkUnicast(Arc::new(link))),
+            };
+        }
 
         let mut errs: Vec<ZError> = vec![];
         for da in dst_addrs {
-            match self.new_link_inner(&da, iface).await {
-                Ok((socket, src_addr, dst_addr)) => {
+            match self.new_link_inner(&da, iface, pmtud).await {
+                Ok((socket, src_addr, dst_addr, mtu)) => {
                     // Create UDP link
-                    let link = Arc::new(LinkUnicastUdp::new(
+                    let link = LinkUnicastUdp::new(
                         src_addr,
                         dst_addr,
                         LinkUnicastUdpVariant::Connected(LinkUnicastUdpConnected {
                             socket: Arc::new(socket),
                         }),
-                    ));
-
-                    return Ok(LinkUnicast(link));
+                        mtu,
+                    );
+
+                    match tls.clone() {
+                        Some((client_config, server_name)) => {
+                            match secure::connect(link, client_config, server_name).await {
+                                Ok(link) => return Ok(LinkUnicast(Arc::new(link))),
+                                Err(e) => errs.push(e),
+                            }
+                        }
+                        None => return Ok(LinkUnicast(Arc::new(link))),
+                    }
                 }
                 Err(e) => {
                     errs.push(e);
@@ -365,7 +588,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
         }
 
         bail!(
-            "Can not create a new UDP link bound to {}: {:?}",
+            "Can not create a new {} link bound to {}: {:?}",
+            self.protocol(),
             endpoint,
             errs
         )
@@ -377,11 +601,32 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
             .filter(|a| !a.ip().is_multicast());
         let config = endpoint.config();
         let iface = config.get(BIND_INTERFACE);
+        let pmtud = get_pmtud_config(&endpoint)?;
+
+        let tls = if self.secure {
+            let mut server_config = TlsServerConfig::new(&config)
+                .await
+                .map_err(|e| zerror!("Cannot create a new UDPS listener on {}: {}", endpoint, e))?
+                .server_config;
+            // The session tickets are not carried by the UDPS handshake
+            server_config.send_tls13_tickets = 0;
+            Some(Arc::new(server_config))
+        } else {
+            None
+        };
 
         let mut errs: Vec<ZError> = vec![];
         for da in addrs {
-            match self.new_listener_inner(&da, iface).await {
+            match self.new_listener_inner(&da, iface, pmtud).await {
                 Ok((socket, local_addr)) => {
+                    let nat = match self.get_nat_config(&endpoint, &local_addr).await {
+                        Ok(nat) => nat,
+                        Err(e) => {
+                            errs.push(e);
+                            continue;
+                        }
+                    };
+
                     // Update the endpoint locator address
                     endpoint = EndPoint::new(
                         endpoint.protocol(),
@@ -393,8 +638,21 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
                     let token = self.listeners.token.child_token();
                     let c_token = token.clone();
                     let c_manager = self.manager.clone();
-
-                    let task = async move { accept_read_task(socket, c_token, c_manager).await };
+                    let c_tls = tls.clone();
+                    let c_nat_listeners = self.nat_listeners.clone();
+
+                    let task = async move {
+                        accept_read_task(
+                            socket,
+                            c_token,
+                            c_manager,
+                            c_tls,
+                            nat,
+                            c_nat_listeners,
+                            pmtud,
+                        )
+                        .await
+                    };
 
                     let locator = endpoint.to_locator();
                     self.listeners
@@ -414,7 +672,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
         }
 
         bail!(
-            "Can not create a new UDP listener bound to {}: {:?}",
+            "Can not create a new {} listener bound to {}: {:?}",
+            self.protocol(),
             endpoint,
             errs
         )
@@ -463,9 +722,14 @@ async fn accept_read_task(
     socket: UdpSocket,
     token: CancellationToken,
     manager: NewLinkChannelSender,
+    tls: Option<Arc<ServerConfig>>,
+    nat: Option<NatConfig>,
+    nat_listeners: NatListeners,
+    pmtud: bool,
 ) -> ZResult<()> {
     let socket = Arc::new(socket);
     let links: LinkHashMap = Arc::new(Mutex::new(HashMap::new()));
+    let gatekeeper = tls.as_ref().map(|_| Gatekeeper::new()).transpose()?;
 
     macro_rules! zaddlink {
         ($src:expr, $dst:expr, $link:expr) => {
@@ -496,6 +760,21 @@ async fn accept_read_task(
         e
     })?;
 
+    let nat = nat.map(|config| {
+        let nat = Arc::new(NatListener::new(
+            config,
+            socket.clone(),
+            links.clone(),
+            src_addr,
+        ));
+        let mut nat_listeners = zlock!(nat_listeners);
+        nat_listeners.retain(|l| l.strong_count() > 0);
+        nat_listeners.push(Arc::downgrade(&nat));
+        nat
+    });
+    // Register periodically with the NAT traversal rendezvous to keep the NAT mapping alive
+    let mut nat_keepalive = tokio::time::interval(Duration::from_millis(*UDP_NAT_KEEPALIVE_TIME));
+
     tracing::trace!("Ready to accept UDP connections on: {:?}", src_addr);
 
     loop {
@@ -505,14 +784,46 @@ async fn accept_read_task(
         tokio::select! {
             _ = token.cancelled() => break,
 
+            _ = nat_keepalive.tick(), if nat.is_some() => {
+                if let Some(nat) = nat.as_ref() {
+                    nat.register().await;
+                }
+            }
+
             res = receive(socket.clone(), &mut buff) => {
                 match res {
                     Ok((n, dst_addr)) => {
+                        if let Some(nat) = nat.as_ref() {
+                            if let Some(msg) = NatMessage::decode(&buff[..n], nat.secret()) {
+                                match msg {
+                                    Ok(msg) => nat.handle(msg, dst_addr).await,
+                                    Err(e) => tracing::debug!(
+                                        "Invalid NAT traversal message on {} from {}: {}",
+                                        src_addr,
+                                        dst_addr,
+                                        e
+                                    ),
+                                }
+                                continue;
+                            }
+                        }
                         let link = loop {
                             let res = zgetlink!(src_addr, dst_addr);
                             match res {
                                 Some(link) => break link.upgrade(),
                                 None => {
+                                    // Only the first flight of a UDPS handshake echoing a cookie opens a new link
+                                    let permit = match gatekeeper.as_ref().map(|g| g.admit(&buff[..n], &dst_addr)) {
+                                        None => None,
+                                        Some(Admission::Accept(permit)) => Some(permit),
+                                        Some(Admission::Retry(record)) => {
+                                            if let Err(e) = socket.send_to(&record, dst_addr).await {
+                                                tracing::debug!("Can not send a UDPS cookie to {}: {}", dst_addr, e);
+                                            }
+                                            break None;
+                                        }
+                                        Some(Admission::Drop) => break None,
+                                    };
                                     // A new peers has sent data to this socket
                                     tracing::debug!("Accepted UDP connection on {}: {}", src_addr, dst_addr);
                                     let unconnected = Arc::new(LinkUnicastUdpUnconnected {
@@ -523,14 +834,40 @@ async fn accept_read_task(
                                     });
                                     zaddlink!(src_addr, dst_addr, Arc::downgrade(&unconnected));
                                     // Create the new link object
-                                    let link = Arc::new(LinkUnicastUdp::new(
+                                    let mtu = if pmtud {
+                                        discover_path_mtu(&src_addr, &dst_addr)
+                                    } else {
+                                        *UDP_DEFAULT_MTU
+                                    };
+                                    let link = LinkUnicastUdp::new(
                                         src_addr,
                                         dst_addr,
                                         LinkUnicastUdpVariant::Unconnected(unconnected),
-                                    ));
-                                    // Add the new link to the set of connected peers
-                                    if let Err(e) = manager.send_async(LinkUnicast(link)).await {
-                                        tracing::error!("{}-{}: {}", file!(), line!(), e)
+                                        mtu,
+                                    );
+                                    match tls.clone() {
+                                        Some(server_config) => {
+                                            // Complete the UDPS handshake before handing the link over
+                                            let c_manager = manager.clone();
+                                            zenoh_runtime::ZRuntime::Acceptor.spawn(async move {
+                                                // Release the handshake slot once the handshake is over
+                                                let _permit = permit;
+                                                match secure::accept(link, server_config).await {
+                                                    Ok(link) => {
+                                                        if let Err(e) = c_manager.send_async(LinkUnicast(Arc::new(link))).await {
+                                                            tracing::error!("{}-{}: {}", file!(), line!(), e)
+                                                        }
+                                                    }
+                                                    Err(e) => tracing::debug!("{}", e),
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            // Add the new link to the set of connected peers
+                                            if let Err(e) = manager.send_async(LinkUnicast(Arc::new(link))).await {
+                                                tracing::error!("{}-{}: {}", file!(), line!(), e)
+                                            }
+                                        }
                                     }
                                 }
                             }
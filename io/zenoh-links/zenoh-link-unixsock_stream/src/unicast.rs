@@ -17,6 +17,8 @@ use std::cell::UnsafeCell;
 use std::collections::HashMap;
 use std::fmt;
 use std::fs::remove_file;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::AsRawFd;
 use std::os::unix::io::RawFd;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -28,8 +30,11 @@ use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 use zenoh_core::{zasyncread, zasyncwrite};
+#[cfg(target_os = "linux")]
+use zenoh_link_commons::UringStream;
 use zenoh_link_commons::{
-    LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
+    write_all_vectored, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
+    NewLinkChannelSender, IO_URING,
 };
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{zerror, ZResult};
@@ -39,6 +44,9 @@ use super::{get_unix_path_as_string, UNIXSOCKSTREAM_DEFAULT_MTU, UNIXSOCKSTREAM_
 pub struct LinkUnicastUnixSocketStream {
     // The underlying socket as returned from the tokio library
     socket: UnsafeCell<UnixStream>,
+    // The io_uring backend of the socket, if enabled
+    #[cfg(target_os = "linux")]
+    uring: Option<UringStream>,
     // The Unix domain socket source path
     src_locator: Locator,
     // The Unix domain socker destination path (random UUIDv4)
@@ -48,9 +56,43 @@ pub struct LinkUnicastUnixSocketStream {
 unsafe impl Sync for LinkUnicastUnixSocketStream {}
 
 impl LinkUnicastUnixSocketStream {
-    fn new(socket: UnixStream, src_path: &str, dst_path: &str) -> LinkUnicastUnixSocketStream {
+    fn new(
+        socket: UnixStream,
+        src_path: &str,
+        dst_path: &str,
+        io_uring: bool,
+    ) -> LinkUnicastUnixSocketStream {
+        // Switch the socket to the io_uring backend if requested
+        #[cfg(target_os = "linux")]
+        let uring = if io_uring {
+            match UringStream::new(socket.as_raw_fd()) {
+                Ok(uring) => uring,
+                Err(err) => {
+                    tracing::warn!(
+                        "Unable to use io_uring on UnixSocketStream link {} => {}: {}",
+                        src_path,
+                        dst_path,
+                        err
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        #[cfg(not(target_os = "linux"))]
+        if io_uring {
+            tracing::warn!(
+                "io_uring is only supported on Linux, ignoring it on UnixSocketStream link {} => {}",
+                src_path,
+                dst_path
+            );
+        }
+
         LinkUnicastUnixSocketStream {
             socket: UnsafeCell::new(socket),
+            #[cfg(target_os = "linux")]
+            uring,
             src_locator: Locator::new(UNIXSOCKSTREAM_LOCATOR_PREFIX, src_path, "").unwrap(),
             dst_locator: Locator::new(UNIXSOCKSTREAM_LOCATOR_PREFIX, dst_path, "").unwrap(),
         }
@@ -73,6 +115,14 @@ impl LinkUnicastTrait for LinkUnicastUnixSocketStream {
     }
 
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.write(buffer).await.map_err(|e| {
+                let e = zerror!("Write error on UnixSocketStream link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         self.get_mut_socket().write(buffer).await.map_err(|e| {
             let e = zerror!("Write error on UnixSocketStream link {}: {}", self, e);
             tracing::trace!("{}", e);
@@ -81,6 +131,14 @@ impl LinkUnicastTrait for LinkUnicastUnixSocketStream {
     }
 
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.write_all(buffer).await.map_err(|e| {
+                let e = zerror!("Write error on UnixSocketStream link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         self.get_mut_socket().write_all(buffer).await.map_err(|e| {
             let e = zerror!("Write error on UnixSocketStream link {}: {}", self, e);
             tracing::trace!("{}", e);
@@ -88,7 +146,33 @@ impl LinkUnicastTrait for LinkUnicastUnixSocketStream {
         })
     }
 
+    async fn write_vectored_all(&self, buffers: &[&[u8]]) -> ZResult<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.write_vectored_all(buffers).await.map_err(|e| {
+                let e = zerror!("Write error on UnixSocketStream link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
+        write_all_vectored(self.get_mut_socket(), buffers)
+            .await
+            .map_err(|e| {
+                let e = zerror!("Write error on UnixSocketStream link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            })
+    }
+
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.read(buffer).await.map_err(|e| {
+                let e = zerror!("Read error on UnixSocketStream link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         self.get_mut_socket().read(buffer).await.map_err(|e| {
             let e = zerror!("Read error on UnixSocketStream link {}: {}", self, e);
             tracing::trace!("{}", e);
@@ -97,6 +181,14 @@ impl LinkUnicastTrait for LinkUnicastUnixSocketStream {
     }
 
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(uring) = self.uring.as_ref() {
+            return uring.read_exact(buffer).await.map_err(|e| {
+                let e = zerror!("Read error on UnixSocketStream link {}: {}", self, e);
+                tracing::trace!("{}", e);
+                e.into()
+            });
+        }
         self.get_mut_socket()
             .read_exact(buffer)
             .await
@@ -195,6 +287,21 @@ impl ListenerUnixSocketStream {
     }
 }
 
+fn get_io_uring(endpoint: &EndPoint) -> ZResult<bool> {
+    match endpoint.config().get(IO_URING) {
+        Some(value) => value.parse().map_err(|_| {
+            zerror!(
+                "Invalid {} value on UnixSocketStream endpoint {}: {}",
+                IO_URING,
+                endpoint,
+                value
+            )
+            .into()
+        }),
+        None => Ok(false),
+    }
+}
+
 pub struct LinkManagerUnicastUnixSocketStream {
     manager: NewLinkChannelSender,
     listeners: Arc<AsyncRwLock<HashMap<String, ListenerUnixSocketStream>>>,
@@ -213,6 +320,7 @@ impl LinkManagerUnicastUnixSocketStream {
 impl LinkManagerUnicastTrait for LinkManagerUnicastUnixSocketStream {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
         let path = get_unix_path_as_string(endpoint.address());
+        let io_uring = get_io_uring(&endpoint)?;
 
         // Create the UnixSocketStream connection
         let stream = UnixStream::connect(&path).await.map_err(|e| {
@@ -270,6 +378,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUnixSocketStream {
             stream,
             local_path_str,
             remote_path_str,
+            io_uring,
         ));
 
         Ok(LinkUnicast(link))
@@ -277,6 +386,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUnixSocketStream {
 
     async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
         let path = get_unix_path_as_string(endpoint.address());
+        let io_uring = get_io_uring(&endpoint)?;
 
         // Because of the lack of SO_REUSEADDR we have to check if the
         // file is still there and if it is not used by another process.
@@ -387,7 +497,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUnixSocketStream {
 
         let task = async move {
             // Wait for the accept loop to terminate
-            let res = accept_task(socket, c_token, c_manager).await;
+            let res = accept_task(socket, c_token, c_manager, io_uring).await;
             zasyncwrite!(c_listeners).remove(&c_path);
             res
         };
@@ -449,6 +559,7 @@ async fn accept_task(
     socket: UnixListener,
     token: CancellationToken,
     manager: NewLinkChannelSender,
+    io_uring: bool,
 ) -> ZResult<()> {
     async fn accept(socket: &UnixListener) -> ZResult<UnixStream> {
         let (stream, _) = socket.accept().await.map_err(|e| zerror!(e))?;
@@ -498,7 +609,7 @@ async fn accept_task(
 
                         // Create the new link object
                         let link = Arc::new(LinkUnicastUnixSocketStream::new(
-                            stream, src_path, &dst_path,
+                            stream, src_path, &dst_path, io_uring,
                         ));
 
                         // Communicate the new link to the initial transport manager
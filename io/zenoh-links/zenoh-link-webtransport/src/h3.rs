@@ -0,0 +1,795 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! A minimal implementation of WebTransport over HTTP/3 (draft-ietf-webtrans-http3), enough
+//! to establish one WebTransport session per QUIC connection: the HTTP/3 control streams,
+//! the extended CONNECT handshake with a QPACK codec that doesn't use the dynamic table, and
+//! the framing of the WebTransport streams and datagrams.
+use quinn::{Connection, RecvStream, SendStream, VarInt};
+use std::collections::HashMap;
+use tokio::sync::oneshot;
+use zenoh_result::{bail, zerror, ZResult};
+
+// HTTP/3 unidirectional stream types (RFC 9114)
+const H3_STREAM_TYPE_CONTROL: u64 = 0x00;
+
+// HTTP/3 frame types (RFC 9114)
+const H3_FRAME_HEADERS: u64 = 0x01;
+const H3_FRAME_SETTINGS: u64 = 0x04;
+
+// HTTP/3 settings
+const SETTINGS_ENABLE_CONNECT_PROTOCOL: u64 = 0x08;
+const SETTINGS_H3_DATAGRAM: u64 = 0x33;
+const SETTINGS_H3_DATAGRAM_DRAFT04: u64 = 0xff_d277;
+const SETTINGS_ENABLE_WEBTRANSPORT: u64 = 0x2b60_3742;
+const SETTINGS_WEBTRANSPORT_MAX_SESSIONS: u64 = 0xc671_706a;
+
+// The signal starting the WebTransport bidirectional streams
+const WEBTRANSPORT_STREAM: u64 = 0x41;
+
+// The maximum length of the HTTP/3 frames read during the handshake
+const H3_MAX_FRAME_LEN: u64 = 16 * 1024;
+
+/*************************************/
+/*              VARINT               */
+/*************************************/
+fn encode_varint(buf: &mut Vec<u8>, value: u64) {
+    if value < 1 << 6 {
+        buf.push(value as u8);
+    } else if value < 1 << 14 {
+        buf.extend_from_slice(&(value as u16 | 0x4000).to_be_bytes());
+    } else if value < 1 << 30 {
+        buf.extend_from_slice(&(value as u32 | 0x8000_0000).to_be_bytes());
+    } else {
+        buf.extend_from_slice(&(value | 0xc000_0000_0000_0000).to_be_bytes());
+    }
+}
+
+fn decode_varint(buf: &[u8]) -> Option<(u64, usize)> {
+    let first = *buf.first()?;
+    let len = 1 << (first >> 6);
+    let bytes = buf.get(1..len)?;
+    let value = bytes
+        .iter()
+        .fold(u64::from(first & 0x3f), |v, b| (v << 8) | u64::from(*b));
+    Some((value, len))
+}
+
+async fn read_varint(recv: &mut RecvStream) -> ZResult<u64> {
+    let mut buf = [0u8; 8];
+    recv.read_exact(&mut buf[..1])
+        .await
+        .map_err(|e| zerror!("{}", e))?;
+    let len = 1 << (buf[0] >> 6);
+    recv.read_exact(&mut buf[1..len])
+        .await
+        .map_err(|e| zerror!("{}", e))?;
+    decode_varint(&buf[..len])
+        .map(|(value, _)| value)
+        .ok_or_else(|| zerror!("Invalid QUIC variable-length integer").into())
+}
+
+/*************************************/
+/*              FRAMES               */
+/*************************************/
+fn encode_frame(buf: &mut Vec<u8>, frame_type: u64, payload: &[u8]) {
+    encode_varint(buf, frame_type);
+    encode_varint(buf, payload.len() as u64);
+    buf.extend_from_slice(payload);
+}
+
+async fn read_frame(recv: &mut RecvStream) -> ZResult<(u64, Vec<u8>)> {
+    let frame_type = read_varint(recv).await?;
+    let len = read_varint(recv).await?;
+    if len > H3_MAX_FRAME_LEN {
+        bail!("HTTP/3 frame too large: {} bytes", len);
+    }
+    let mut payload = vec![0u8; len as usize];
+    recv.read_exact(&mut payload)
+        .await
+        .map_err(|e| zerror!("{}", e))?;
+    Ok((frame_type, payload))
+}
+
+/*************************************/
+/*          CONTROL STREAMS          */
+/*************************************/
+pub(crate) type Settings = HashMap<u64, u64>;
+
+pub(crate) fn supports_webtransport(settings: &Settings) -> bool {
+    settings.get(&SETTINGS_ENABLE_WEBTRANSPORT) == Some(&1)
+        || settings
+            .get(&SETTINGS_WEBTRANSPORT_MAX_SESSIONS)
+            .is_some_and(|n| *n > 0)
+}
+
+/// Opens the HTTP/3 control stream and sends the settings enabling WebTransport on it.
+/// Since closing it is a connection error, the stream must be kept for the whole lifetime
+/// of the connection.
+pub(crate) async fn open_control_stream(connection: &Connection) -> ZResult<SendStream> {
+    let mut settings = vec![];
+    for (id, value) in [
+        (SETTINGS_ENABLE_CONNECT_PROTOCOL, 1),
+        (SETTINGS_H3_DATAGRAM, 1),
+        (SETTINGS_H3_DATAGRAM_DRAFT04, 1),
+        (SETTINGS_ENABLE_WEBTRANSPORT, 1),
+        (SETTINGS_WEBTRANSPORT_MAX_SESSIONS, 1),
+    ] {
+        encode_varint(&mut settings, id);
+        encode_varint(&mut settings, value);
+    }
+    let mut buf = vec![];
+    encode_varint(&mut buf, H3_STREAM_TYPE_CONTROL);
+    encode_frame(&mut buf, H3_FRAME_SETTINGS, &settings);
+
+    let mut send = connection.open_uni().await.map_err(|e| zerror!("{}", e))?;
+    send.write_all(&buf).await.map_err(|e| zerror!("{}", e))?;
+    Ok(send)
+}
+
+async fn read_settings(recv: &mut RecvStream) -> ZResult<Settings> {
+    let (frame_type, payload) = read_frame(recv).await?;
+    if frame_type != H3_FRAME_SETTINGS {
+        bail!("HTTP/3 control stream doesn't start with a SETTINGS frame");
+    }
+    let mut settings = Settings::new();
+    let mut pos = 0;
+    while pos < payload.len() {
+        let (id, n) = decode_varint(&payload[pos..]).ok_or("Invalid HTTP/3 SETTINGS frame")?;
+        pos += n;
+        let (value, n) = decode_varint(&payload[pos..]).ok_or("Invalid HTTP/3 SETTINGS frame")?;
+        pos += n;
+        settings.insert(id, value);
+    }
+    Ok(settings)
+}
+
+/// Accepts the unidirectional streams opened by the peer (its control stream, QPACK
+/// streams, ...) for the whole lifetime of the connection, returning the settings sent
+/// on its control stream.
+pub(crate) fn spawn_uni_streams_task(
+    connection: Connection,
+) -> oneshot::Receiver<ZResult<Settings>> {
+    let (tx, rx) = oneshot::channel();
+    zenoh_runtime::ZRuntime::RX.spawn(async move {
+        let mut tx = Some(tx);
+        while let Ok(mut recv) = connection.accept_uni().await {
+            let Ok(stream_type) = read_varint(&mut recv).await else {
+                continue;
+            };
+            if stream_type == H3_STREAM_TYPE_CONTROL {
+                if let Some(tx) = tx.take() {
+                    let _ = tx.send(read_settings(&mut recv).await);
+                }
+            }
+            // Stopping these streams would be a connection error: drain them until closed
+            zenoh_runtime::ZRuntime::RX.spawn(async move {
+                while let Ok(Some(_)) = recv.read_chunk(usize::MAX, true).await {}
+            });
+        }
+    });
+    rx
+}
+
+/*************************************/
+/*               QPACK               */
+/*************************************/
+pub(crate) type Fields = Vec<(String, String)>;
+
+// The entries of the QPACK static table (RFC 9204, Appendix A) needed by the handshake
+const QPACK_STATIC_TABLE: &[(u64, &str, &str)] = &[
+    (0, ":authority", ""),
+    (1, ":path", "/"),
+    (15, ":method", "CONNECT"),
+    (16, ":method", "DELETE"),
+    (17, ":method", "GET"),
+    (18, ":method", "HEAD"),
+    (19, ":method", "OPTIONS"),
+    (20, ":method", "POST"),
+    (21, ":method", "PUT"),
+    (22, ":scheme", "http"),
+    (23, ":scheme", "https"),
+    (24, ":status", "103"),
+    (25, ":status", "200"),
+    (26, ":status", "304"),
+    (27, ":status", "404"),
+    (28, ":status", "503"),
+    (63, ":status", "100"),
+    (64, ":status", "204"),
+    (65, ":status", "206"),
+    (66, ":status", "302"),
+    (67, ":status", "400"),
+    (68, ":status", "403"),
+    (69, ":status", "421"),
+    (70, ":status", "425"),
+    (71, ":status", "500"),
+];
+
+fn static_entry(index: u64) -> Option<(&'static str, &'static str)> {
+    QPACK_STATIC_TABLE
+        .iter()
+        .find(|(i, _, _)| *i == index)
+        .map(|(_, name, value)| (*name, *value))
+}
+
+// Encodes an integer on a prefix of the given number of bits (RFC 7541, Section 5.1)
+fn encode_prefixed_int(buf: &mut Vec<u8>, flags: u8, bits: u8, value: u64) {
+    let max = (1u64 << bits) - 1;
+    if value < max {
+        buf.push(flags | value as u8);
+        return;
+    }
+    buf.push(flags | max as u8);
+    let mut value = value - max;
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn decode_prefixed_int(buf: &[u8], pos: &mut usize, bits: u8) -> ZResult<u64> {
+    let max = (1u64 << bits) - 1;
+    let first = *buf.get(*pos).ok_or("Truncated QPACK integer")?;
+    *pos += 1;
+    let mut value = u64::from(first) & max;
+    if value < max {
+        return Ok(value);
+    }
+    let mut shift = 0;
+    loop {
+        let byte = *buf.get(*pos).ok_or("Truncated QPACK integer")?;
+        *pos += 1;
+        // Reject the continuation bytes whose bits don't fit in 64 bits, and the sums that
+        // overflow
+        let bits = u64::from(byte & 0x7f);
+        if shift >= u64::BITS || bits.leading_zeros() < shift {
+            bail!("QPACK integer overflow");
+        }
+        value = value
+            .checked_add(bits << shift)
+            .ok_or("QPACK integer overflow")?;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+fn encode_string(buf: &mut Vec<u8>, flags: u8, bits: u8, value: &str) {
+    // Strings are never Huffman-encoded
+    encode_prefixed_int(buf, flags, bits, value.len() as u64);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn decode_string(buf: &[u8], pos: &mut usize, bits: u8) -> ZResult<String> {
+    let huffman = buf.get(*pos).is_some_and(|b| b & (1 << bits) != 0);
+    let len = decode_prefixed_int(buf, pos, bits)? as usize;
+    let bytes = buf
+        .get(*pos..pos.saturating_add(len))
+        .ok_or("Truncated QPACK string")?;
+    *pos += len;
+    let bytes = if huffman {
+        huffman_decode(bytes)?
+    } else {
+        bytes.to_vec()
+    };
+    String::from_utf8(bytes).map_err(|e| zerror!("Invalid QPACK string: {}", e).into())
+}
+
+fn encode_field_section(fields: &[(&str, &str)]) -> Vec<u8> {
+    // Required Insert Count and Base are 0 since the dynamic table isn't used
+    let mut buf = vec![0, 0];
+    for (name, value) in fields {
+        if let Some((index, _, _)) = QPACK_STATIC_TABLE
+            .iter()
+            .find(|(_, n, v)| n == name && v == value)
+        {
+            // Indexed field line, static table
+            encode_prefixed_int(&mut buf, 0b1100_0000, 6, *index);
+        } else if let Some((index, _, _)) = QPACK_STATIC_TABLE.iter().find(|(_, n, _)| n == name) {
+            // Literal field line with name reference, static table
+            encode_prefixed_int(&mut buf, 0b0101_0000, 4, *index);
+            encode_string(&mut buf, 0, 7, value);
+        } else {
+            // Literal field line with literal name
+            encode_string(&mut buf, 0b0010_0000, 3, name);
+            encode_string(&mut buf, 0, 7, value);
+        }
+    }
+    buf
+}
+
+fn decode_field_section(buf: &[u8]) -> ZResult<Fields> {
+    let mut pos = 0;
+    if decode_prefixed_int(buf, &mut pos, 8)? != 0 {
+        bail!("QPACK dynamic table is not supported");
+    }
+    decode_prefixed_int(buf, &mut pos, 7)?;
+
+    let mut fields = Fields::new();
+    while let Some(byte) = buf.get(pos) {
+        if byte & 0b1000_0000 != 0 {
+            // Indexed field line
+            let is_static = byte & 0b0100_0000 != 0;
+            let index = decode_prefixed_int(buf, &mut pos, 6)?;
+            if !is_static {
+                bail!("QPACK dynamic table is not supported");
+            }
+            if let Some((name, value)) = static_entry(index) {
+                fields.push((name.to_string(), value.to_string()));
+            }
+        } else if byte & 0b0100_0000 != 0 {
+            // Literal field line with name reference
+            let is_static = byte & 0b0001_0000 != 0;
+            let index = decode_prefixed_int(buf, &mut pos, 4)?;
+            let value = decode_string(buf, &mut pos, 7)?;
+            if !is_static {
+                bail!("QPACK dynamic table is not supported");
+            }
+            if let Some((name, _)) = static_entry(index) {
+                fields.push((name.to_string(), value));
+            }
+        } else if byte & 0b0010_0000 != 0 {
+            // Literal field line with literal name
+            let name = decode_string(buf, &mut pos, 3)?;
+            let value = decode_string(buf, &mut pos, 7)?;
+            fields.push((name, value));
+        } else {
+            bail!("QPACK dynamic table is not supported");
+        }
+    }
+    Ok(fields)
+}
+
+fn get_field<'a>(fields: &'a Fields, name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/*************************************/
+/*              HUFFMAN              */
+/*************************************/
+// The lengths of the codes of the HPACK Huffman code (RFC 7541, Appendix B) for each
+// symbol, the last one being EOS. The code is canonical, so it is given by these lengths.
+const HUFFMAN_CODE_LENGTHS: [u8; 257] = [
+    13, 23, 28, 28, 28, 28, 28, 28, 28, 24, 30, 28, 28, 30, 28, 28, 28, 28, 28, 28, 28, 28, 30, 28,
+    28, 28, 28, 28, 28, 28, 28, 28, 6, 10, 10, 12, 13, 6, 8, 11, 10, 10, 8, 11, 8, 6, 6, 6, 5, 5,
+    5, 6, 6, 6, 6, 6, 6, 6, 7, 8, 15, 6, 12, 10, 13, 6, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+    7, 7, 7, 7, 7, 7, 7, 7, 8, 7, 8, 13, 19, 13, 14, 6, 15, 5, 6, 5, 6, 5, 6, 6, 6, 5, 7, 7, 6, 6,
+    6, 5, 6, 7, 6, 5, 5, 6, 7, 7, 7, 7, 7, 15, 11, 14, 13, 28, 20, 22, 20, 20, 22, 22, 22, 23, 22,
+    23, 23, 23, 23, 23, 24, 23, 24, 24, 22, 23, 24, 23, 23, 23, 23, 21, 22, 23, 22, 23, 23, 24, 22,
+    21, 20, 22, 22, 23, 23, 21, 23, 22, 22, 24, 21, 22, 23, 23, 21, 21, 22, 21, 23, 22, 23, 23, 20,
+    22, 22, 22, 23, 22, 22, 23, 26, 26, 20, 19, 22, 23, 22, 25, 26, 26, 26, 27, 27, 26, 24, 25, 19,
+    21, 26, 27, 27, 26, 27, 24, 21, 21, 26, 26, 28, 27, 27, 27, 20, 24, 20, 21, 22, 21, 21, 23, 22,
+    22, 25, 25, 24, 24, 26, 23, 26, 27, 26, 26, 27, 27, 27, 27, 27, 28, 27, 27, 27, 27, 27, 26, 30,
+];
+
+const HUFFMAN_MAX_CODE_LENGTH: usize = 30;
+
+struct HuffmanTable {
+    // the number of codes of each length
+    count: [u32; HUFFMAN_MAX_CODE_LENGTH + 1],
+    // the first code of each length
+    first: [u32; HUFFMAN_MAX_CODE_LENGTH + 1],
+    // the position in symbols of the first code of each length
+    offset: [usize; HUFFMAN_MAX_CODE_LENGTH + 1],
+    // the symbols ordered by code
+    symbols: [u16; 257],
+}
+
+const HUFFMAN_TABLE: HuffmanTable = {
+    let mut count = [0u32; HUFFMAN_MAX_CODE_LENGTH + 1];
+    let mut i = 0;
+    while i < HUFFMAN_CODE_LENGTHS.len() {
+        count[HUFFMAN_CODE_LENGTHS[i] as usize] += 1;
+        i += 1;
+    }
+    let mut first = [0u32; HUFFMAN_MAX_CODE_LENGTH + 1];
+    let mut offset = [0usize; HUFFMAN_MAX_CODE_LENGTH + 1];
+    let (mut code, mut position, mut len) = (0u32, 0usize, 1);
+    while len <= HUFFMAN_MAX_CODE_LENGTH {
+        first[len] = code;
+        offset[len] = position;
+        code = (code + count[len]) << 1;
+        position += count[len] as usize;
+        len += 1;
+    }
+    let mut symbols = [0u16; 257];
+    let mut next = offset;
+    let mut symbol = 0;
+    while symbol < HUFFMAN_CODE_LENGTHS.len() {
+        let len = HUFFMAN_CODE_LENGTHS[symbol] as usize;
+        symbols[next[len]] = symbol as u16;
+        next[len] += 1;
+        symbol += 1;
+    }
+    HuffmanTable {
+        count,
+        first,
+        offset,
+        symbols,
+    }
+};
+
+fn huffman_decode(bytes: &[u8]) -> ZResult<Vec<u8>> {
+    const EOS: u16 = 256;
+
+    let table = &HUFFMAN_TABLE;
+    let mut decoded = Vec::with_capacity(bytes.len() * 8 / 5);
+    let (mut code, mut len) = (0u32, 0usize);
+    for byte in bytes {
+        for bit in (0..8).rev() {
+            code = (code << 1) | u32::from((byte >> bit) & 1);
+            len += 1;
+            if code >= table.first[len] && code - table.first[len] < table.count[len] {
+                let symbol = table.symbols[table.offset[len] + (code - table.first[len]) as usize];
+                if symbol == EOS {
+                    bail!("Invalid Huffman string: EOS symbol");
+                }
+                decoded.push(symbol as u8);
+                (code, len) = (0, 0);
+            } else if len == HUFFMAN_MAX_CODE_LENGTH {
+                bail!("Invalid Huffman string: unknown code");
+            }
+        }
+    }
+    // The padding is made of the most significant bits of EOS, i.e. at most 7 bits set to 1
+    if len > 7 || code != (1 << len) - 1 {
+        bail!("Invalid Huffman string: invalid padding");
+    }
+    Ok(decoded)
+}
+
+/*************************************/
+/*             HANDSHAKE             */
+/*************************************/
+async fn send_headers(send: &mut SendStream, fields: &[(&str, &str)]) -> ZResult<()> {
+    let mut buf = vec![];
+    encode_frame(&mut buf, H3_FRAME_HEADERS, &encode_field_section(fields));
+    send.write_all(&buf).await.map_err(|e| zerror!("{}", e))?;
+    Ok(())
+}
+
+async fn read_headers(recv: &mut RecvStream) -> ZResult<Fields> {
+    loop {
+        let (frame_type, payload) = read_frame(recv).await?;
+        // Frames of unknown types must be ignored
+        if frame_type == H3_FRAME_HEADERS {
+            return decode_field_section(&payload);
+        }
+    }
+}
+
+/// Requests a WebTransport session with an extended CONNECT on the given request stream,
+/// the id of this stream being the id of the session.
+pub(crate) async fn connect(
+    send: &mut SendStream,
+    recv: &mut RecvStream,
+    authority: &str,
+    path: &str,
+) -> ZResult<u64> {
+    send_headers(
+        send,
+        &[
+            (":method", "CONNECT"),
+            (":protocol", "webtransport"),
+            (":scheme", "https"),
+            (":authority", authority),
+            (":path", path),
+            ("sec-webtransport-http3-draft02", "1"),
+        ],
+    )
+    .await?;
+    let fields = read_headers(recv).await?;
+    match get_field(&fields, ":status") {
+        Some(status) if status.starts_with('2') => Ok(VarInt::from(send.id()).into_inner()),
+        Some(status) => bail!("WebTransport session refused with status {}", status),
+        None => bail!("Invalid WebTransport session response: {:?}", fields),
+    }
+}
+
+/// Accepts the WebTransport session requested with an extended CONNECT on the given
+/// request stream, returning the id of the session and the requested path.
+pub(crate) async fn accept(send: &mut SendStream, recv: &mut RecvStream) -> ZResult<(u64, String)> {
+    let fields = read_headers(recv).await?;
+    if get_field(&fields, ":method") != Some("CONNECT")
+        || get_field(&fields, ":protocol") != Some("webtransport")
+    {
+        let _ = send_headers(send, &[(":status", "400")]).await;
+        bail!("Invalid WebTransport session request: {:?}", fields);
+    }
+    send_headers(
+        send,
+        &[
+            (":status", "200"),
+            ("sec-webtransport-http3-draft", "draft02"),
+        ],
+    )
+    .await?;
+    let path = get_field(&fields, ":path").unwrap_or("/").to_string();
+    Ok((VarInt::from(send.id()).into_inner(), path))
+}
+
+/*************************************/
+/*        STREAMS & DATAGRAMS        */
+/*************************************/
+/// Opens a bidirectional stream of the given WebTransport session.
+pub(crate) async fn open_bi(
+    connection: &Connection,
+    session_id: u64,
+) -> ZResult<(SendStream, RecvStream)> {
+    let (mut send, recv) = connection.open_bi().await.map_err(|e| zerror!("{}", e))?;
+    let mut buf = vec![];
+    encode_varint(&mut buf, WEBTRANSPORT_STREAM);
+    encode_varint(&mut buf, session_id);
+    send.write_all(&buf).await.map_err(|e| zerror!("{}", e))?;
+    Ok((send, recv))
+}
+
+/// Accepts a bidirectional stream of the given WebTransport session, ignoring the others.
+pub(crate) async fn accept_bi(
+    connection: &Connection,
+    session_id: u64,
+) -> ZResult<(SendStream, RecvStream)> {
+    loop {
+        let (send, mut recv) = connection.accept_bi().await.map_err(|e| zerror!("{}", e))?;
+        let signal = read_varint(&mut recv).await?;
+        let id = read_varint(&mut recv).await?;
+        if signal == WEBTRANSPORT_STREAM && id == session_id {
+            return Ok((send, recv));
+        }
+        tracing::debug!(
+            "Ignoring bidirectional stream which isn't part of the WebTransport session"
+        );
+    }
+}
+
+/// Returns the header of the datagrams of the given WebTransport session.
+pub(crate) fn datagram_header(session_id: u64) -> Vec<u8> {
+    // The datagrams are prefixed by the quarter stream id of the session
+    let mut buf = vec![];
+    encode_varint(&mut buf, session_id / 4);
+    buf
+}
+
+/// Returns the payload of the given datagram if it belongs to the given WebTransport session.
+pub(crate) fn datagram_payload(datagram: &[u8], session_id: u64) -> Option<&[u8]> {
+    let (quarter_id, len) = decode_varint(datagram)?;
+    (quarter_id == session_id / 4).then(|| &datagram[len..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn varint() {
+        // RFC 9000, Appendix A.1
+        for (value, encoded) in [
+            (37, "25"),
+            (15_293, "7bbd"),
+            (494_878_333, "9d7f3e7d"),
+            (151_288_809_941_952_652, "c2197c5eff14e88c"),
+        ] {
+            let mut buf = vec![];
+            encode_varint(&mut buf, value);
+            assert_eq!(buf, hex(encoded));
+            assert_eq!(decode_varint(&buf), Some((value, buf.len())));
+        }
+        for value in [
+            0,
+            63,
+            64,
+            16_383,
+            16_384,
+            (1 << 30) - 1,
+            1 << 30,
+            (1 << 62) - 1,
+        ] {
+            let mut buf = vec![];
+            encode_varint(&mut buf, value);
+            assert_eq!(decode_varint(&buf), Some((value, buf.len())));
+            assert_eq!(decode_varint(&buf[..buf.len() - 1]), None);
+        }
+    }
+
+    #[test]
+    fn frame() {
+        let mut buf = vec![];
+        encode_frame(&mut buf, H3_FRAME_SETTINGS, &[0x08, 0x01]);
+        assert_eq!(buf, [0x04, 0x02, 0x08, 0x01]);
+    }
+
+    #[test]
+    fn prefixed_int() {
+        // RFC 7541, Appendix C.1
+        for (value, bits, encoded) in [(10, 5, "0a"), (1337, 5, "1f9a0a"), (42, 8, "2a")] {
+            let mut buf = vec![];
+            encode_prefixed_int(&mut buf, 0, bits, value);
+            assert_eq!(buf, hex(encoded));
+            let mut pos = 0;
+            assert_eq!(decode_prefixed_int(&buf, &mut pos, bits).unwrap(), value);
+            assert_eq!(pos, buf.len());
+        }
+        // The flags are not part of the integer
+        let mut pos = 0;
+        assert_eq!(decode_prefixed_int(&[0xea], &mut pos, 5).unwrap(), 10);
+
+        for value in [0, 30, 31, 127, 128, u64::from(u32::MAX), u64::MAX] {
+            let mut buf = vec![];
+            encode_prefixed_int(&mut buf, 0b1110_0000, 5, value);
+            let mut pos = 0;
+            assert_eq!(decode_prefixed_int(&buf, &mut pos, 5).unwrap(), value);
+            assert_eq!(pos, buf.len());
+        }
+    }
+
+    #[test]
+    fn prefixed_int_invalid() {
+        // Truncated
+        assert!(decode_prefixed_int(&[], &mut 0, 5).is_err());
+        assert!(decode_prefixed_int(&[0x1f], &mut 0, 5).is_err());
+        assert!(decode_prefixed_int(&[0x1f, 0x9a], &mut 0, 5).is_err());
+        // Too many continuation bytes
+        let mut buf = vec![0xff];
+        buf.extend([0x80; 10]);
+        buf.push(0x01);
+        assert!(decode_prefixed_int(&buf, &mut 0, 8).is_err());
+        // Bits beyond 64 bits
+        let mut buf = vec![0xff];
+        buf.extend([0xff; 9]);
+        buf.push(0x7f);
+        assert!(decode_prefixed_int(&buf, &mut 0, 8).is_err());
+        // Sum beyond 64 bits
+        let mut buf = vec![];
+        encode_prefixed_int(&mut buf, 0, 8, u64::MAX);
+        buf[1] += 1;
+        assert!(decode_prefixed_int(&buf, &mut 0, 8).is_err());
+    }
+
+    #[test]
+    fn huffman() {
+        // RFC 7541, Appendices C.4 and C.6
+        for (decoded, encoded) in [
+            ("www.example.com", "f1e3c2e5f23a6ba0ab90f4ff"),
+            ("no-cache", "a8eb10649cbf"),
+            ("custom-key", "25a849e95ba97d7f"),
+            ("custom-value", "25a849e95bb8e8b4bf"),
+            ("302", "6402"),
+            ("private", "aec3771a4b"),
+            (
+                "Mon, 21 Oct 2013 20:13:21 GMT",
+                "d07abe941054d444a8200595040b8166e082a62d1bff",
+            ),
+            (
+                "https://www.example.com",
+                "9d29ad171863c78f0b97c8e9ae82ae43d3",
+            ),
+            ("", ""),
+        ] {
+            assert_eq!(huffman_decode(&hex(encoded)).unwrap(), decoded.as_bytes());
+        }
+    }
+
+    #[test]
+    fn huffman_invalid() {
+        // '0' padded with 0s instead of the most significant bits of EOS
+        assert!(huffman_decode(&[0x00]).is_err());
+        // Padding longer than 7 bits
+        assert!(huffman_decode(&hex("f1e3c2e5f23a6ba0ab90f4ffff")).is_err());
+        // EOS
+        assert!(huffman_decode(&hex("fffffffc")).is_err());
+    }
+
+    #[test]
+    fn field_section() {
+        let fields = [
+            (":method", "CONNECT"),
+            (":protocol", "webtransport"),
+            (":scheme", "https"),
+            (":authority", "localhost:7447"),
+            (":path", "/zenoh"),
+            ("sec-webtransport-http3-draft02", "1"),
+        ];
+        let buf = encode_field_section(&fields);
+        // No dynamic table, the static entries are indexed
+        assert_eq!(buf[..3], [0x00, 0x00, 0b1100_0000 | 15]);
+        let decoded = decode_field_section(&buf).unwrap();
+        assert_eq!(
+            decoded,
+            fields
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect::<Fields>()
+        );
+        assert_eq!(get_field(&decoded, ":path"), Some("/zenoh"));
+        assert_eq!(get_field(&decoded, ":status"), None);
+
+        // RFC 9204, Appendix B.1
+        let decoded = decode_field_section(&hex("0000510b2f696e6465782e68746d6c")).unwrap();
+        assert_eq!(decoded, [(":path".to_string(), "/index.html".to_string())]);
+
+        // Huffman-encoded value of a literal with name reference
+        let mut buf = hex("000050");
+        buf.push(0x80 | 12);
+        buf.extend(hex("f1e3c2e5f23a6ba0ab90f4ff"));
+        let decoded = decode_field_section(&buf).unwrap();
+        assert_eq!(
+            decoded,
+            [(":authority".to_string(), "www.example.com".to_string())]
+        );
+
+        // Huffman-encoded name and value of a literal with literal name
+        let mut buf = hex("0000");
+        encode_prefixed_int(&mut buf, 0b0010_1000, 3, 8);
+        buf.extend(hex("25a849e95ba97d7f"));
+        encode_prefixed_int(&mut buf, 0b1000_0000, 7, 6);
+        buf.extend(hex("a8eb10649cbf"));
+        let decoded = decode_field_section(&buf).unwrap();
+        assert_eq!(
+            decoded,
+            [("custom-key".to_string(), "no-cache".to_string())]
+        );
+    }
+
+    #[test]
+    fn field_section_invalid() {
+        // Required Insert Count other than 0
+        assert!(decode_field_section(&hex("0100")).is_err());
+        // Indexed field line, dynamic table
+        assert!(decode_field_section(&hex("000080")).is_err());
+        // Literal field line with name reference, dynamic table
+        assert!(decode_field_section(&hex("00004000")).is_err());
+        // Indexed field line with post-base index
+        assert!(decode_field_section(&hex("000010")).is_err());
+        // Truncated string
+        assert!(decode_field_section(&hex("0000510b2f69")).is_err());
+        // Invalid UTF-8
+        assert!(decode_field_section(&hex("00005101ff")).is_err());
+    }
+
+    #[test]
+    fn settings() {
+        let mut settings = Settings::new();
+        assert!(!supports_webtransport(&settings));
+        settings.insert(SETTINGS_ENABLE_WEBTRANSPORT, 1);
+        assert!(supports_webtransport(&settings));
+        let mut settings = Settings::new();
+        settings.insert(SETTINGS_WEBTRANSPORT_MAX_SESSIONS, 0);
+        assert!(!supports_webtransport(&settings));
+        settings.insert(SETTINGS_WEBTRANSPORT_MAX_SESSIONS, 16);
+        assert!(supports_webtransport(&settings));
+    }
+
+    #[test]
+    fn datagram() {
+        let session_id = 4 * 100;
+        let mut datagram = datagram_header(session_id);
+        assert_eq!(datagram, [0x40, 100]);
+        datagram.extend_from_slice(b"zenoh");
+        assert_eq!(datagram_payload(&datagram, session_id), Some(&b"zenoh"[..]));
+        assert_eq!(datagram_payload(&datagram, 0), None);
+        assert_eq!(datagram_payload(&[0x40], session_id), None);
+    }
+}
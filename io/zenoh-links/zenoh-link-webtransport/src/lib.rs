@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This crate is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+use async_trait::async_trait;
+
+use zenoh_core::zconfigurable;
+use zenoh_link_commons::LocatorInspector;
+use zenoh_protocol::core::Locator;
+use zenoh_result::ZResult;
+
+mod h3;
+mod unicast;
+pub use unicast::*;
+pub use zenoh_link_quic::QuicConfigurator as WebTransportConfigurator;
+
+// ALPN protocol of HTTP/3
+pub const ALPN_H3: &[&[u8]] = &[b"h3"];
+
+// Default MTU (WebTransport stream) in bytes.
+// NOTE: Since WebTransport streams are byte-stream oriented, theoretically they
+//       have no limit regarding the MTU. However, given the batching strategy
+//       adopted in Zenoh and the usage of 16 bits in Zenoh to encode the
+//       payload length in byte-streamed, the MTU is constrained to
+//       2^16 - 1 bytes (i.e., 65535).
+//       In datagram mode, the MTU is given by the largest QUIC datagram the
+//       connection can carry.
+const WEBTRANSPORT_MAX_MTU: u16 = u16::MAX;
+pub const WEBTRANSPORT_LOCATOR_PREFIX: &str = "webtransport";
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct WebTransportLocatorInspector;
+
+#[async_trait]
+impl LocatorInspector for WebTransportLocatorInspector {
+    fn protocol(&self) -> &str {
+        WEBTRANSPORT_LOCATOR_PREFIX
+    }
+
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
+zconfigurable! {
+    // Default MTU (WebTransport stream) in bytes.
+    static ref WEBTRANSPORT_DEFAULT_MTU: u16 = WEBTRANSPORT_MAX_MTU;
+    // Amount of time in microseconds to throttle the accept loop upon an error.
+    // Default set to 100 ms.
+    static ref WEBTRANSPORT_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds to establish a WebTransport session over
+    // an established QUIC connection.
+    // Default set to 10 s.
+    static ref WEBTRANSPORT_HANDSHAKE_TIMEOUT: u64 = 10_000;
+}
+
+pub mod config {
+    pub use zenoh_link_quic::config::*;
+
+    /// The mode of the links opened on an endpoint: `stream` (the default) carries the
+    /// messages on a WebTransport bidirectional stream and is reliable, `datagram` carries
+    /// them in WebTransport datagrams and is best-effort.
+    pub const WEBTRANSPORT_MODE: &str = "mode";
+    pub const WEBTRANSPORT_MODE_STREAM: &str = "stream";
+    pub const WEBTRANSPORT_MODE_DATAGRAM: &str = "datagram";
+    pub const WEBTRANSPORT_MODE_DEFAULT: &str = WEBTRANSPORT_MODE_STREAM;
+
+    /// The path of the WebTransport session requested by the links opened on an endpoint.
+    /// Listeners accept sessions on any path.
+    pub const WEBTRANSPORT_PATH: &str = "path";
+    pub const WEBTRANSPORT_PATH_DEFAULT: &str = "/";
+}
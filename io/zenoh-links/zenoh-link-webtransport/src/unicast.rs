@@ -0,0 +1,641 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use crate::{
+    config::*, h3, ALPN_H3, WEBTRANSPORT_ACCEPT_THROTTLE_TIME, WEBTRANSPORT_DEFAULT_MTU,
+    WEBTRANSPORT_HANDSHAKE_TIMEOUT, WEBTRANSPORT_LOCATOR_PREFIX,
+};
+use async_trait::async_trait;
+use std::fmt;
+use std::net::IpAddr;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio_util::sync::CancellationToken;
+use zenoh_core::{zasynclock, zlock};
+use zenoh_link_commons::{
+    get_ip_interface_names, LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait,
+    ListenersUnicastIP, NewLinkChannelSender,
+};
+use zenoh_link_quic::{get_quic_addr, TlsClientConfig, TlsServerConfig};
+use zenoh_protocol::core::{EndPoint, Locator};
+use zenoh_result::{bail, zerror, ZResult};
+
+// An established WebTransport session
+struct Session {
+    connection: quinn::Connection,
+    id: u64,
+    // Closing the HTTP/3 control stream or the request stream of the session would
+    // terminate the session, they are kept for the whole lifetime of the link
+    control: quinn::SendStream,
+    request: (quinn::SendStream, quinn::RecvStream),
+}
+
+enum LinkIo {
+    // The messages are carried on a WebTransport bidirectional stream
+    Stream {
+        send: AsyncMutex<quinn::SendStream>,
+        recv: AsyncMutex<quinn::RecvStream>,
+    },
+    // The messages are carried in WebTransport datagrams
+    Datagram {
+        header: Vec<u8>,
+        // The first datagram received by a listener, before the link was created
+        pending: Mutex<Option<Vec<u8>>>,
+        mtu: u16,
+    },
+}
+
+pub struct LinkUnicastWebTransport {
+    connection: quinn::Connection,
+    session_id: u64,
+    src_addr: SocketAddr,
+    src_locator: Locator,
+    dst_locator: Locator,
+    io: LinkIo,
+    _control: quinn::SendStream,
+    _request: (quinn::SendStream, quinn::RecvStream),
+}
+
+impl LinkUnicastWebTransport {
+    fn new(session: Session, src_addr: SocketAddr, dst_locator: Locator, io: LinkIo) -> Self {
+        // Build the WebTransport object
+        LinkUnicastWebTransport {
+            connection: session.connection,
+            session_id: session.id,
+            src_addr,
+            src_locator: Locator::new(WEBTRANSPORT_LOCATOR_PREFIX, src_addr.to_string(), "")
+                .unwrap(),
+            dst_locator,
+            io,
+            _control: session.control,
+            _request: session.request,
+        }
+    }
+
+    fn stream(send: quinn::SendStream, recv: quinn::RecvStream) -> LinkIo {
+        LinkIo::Stream {
+            send: AsyncMutex::new(send),
+            recv: AsyncMutex::new(recv),
+        }
+    }
+
+    fn datagram(session: &Session, pending: Option<Vec<u8>>) -> ZResult<LinkIo> {
+        let header = h3::datagram_header(session.id);
+        let max_size = session
+            .connection
+            .max_datagram_size()
+            .ok_or("WebTransport datagrams are not supported by the peer")?;
+        let mtu = max_size.saturating_sub(header.len()).min(u16::MAX as usize) as u16;
+        Ok(LinkIo::Datagram {
+            header,
+            pending: Mutex::new(pending),
+            mtu,
+        })
+    }
+
+    fn send_datagram(&self, header: &[u8], mtu: u16, buffer: &[u8]) -> ZResult<()> {
+        if buffer.len() > mtu as usize {
+            bail!(
+                "Write error on WebTransport link {}: {} bytes exceed the MTU of {} bytes",
+                self,
+                buffer.len(),
+                mtu
+            );
+        }
+        let mut datagram = Vec::with_capacity(header.len() + buffer.len());
+        datagram.extend_from_slice(header);
+        datagram.extend_from_slice(buffer);
+        self.connection.send_datagram(datagram.into()).map_err(|e| {
+            tracing::trace!("Write error on WebTransport link {}: {}", self, e);
+            zerror!(e).into()
+        })
+    }
+
+    async fn recv_datagram(
+        &self,
+        pending: &Mutex<Option<Vec<u8>>>,
+        buffer: &mut [u8],
+    ) -> ZResult<usize> {
+        let copy = |payload: &[u8], buffer: &mut [u8]| {
+            let len = payload.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&payload[..len]);
+            len
+        };
+        if let Some(payload) = zlock!(pending).take() {
+            return Ok(copy(&payload, buffer));
+        }
+        loop {
+            let datagram = self.connection.read_datagram().await.map_err(|e| {
+                let e = zerror!("Read error on WebTransport link {}: {}", self, e);
+                tracing::trace!("{}", &e);
+                e
+            })?;
+            if let Some(payload) = h3::datagram_payload(&datagram, self.session_id) {
+                return Ok(copy(payload, buffer));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastWebTransport {
+    async fn close(&self) -> ZResult<()> {
+        tracing::trace!("Closing WebTransport link: {}", self);
+        if let LinkIo::Stream { send, .. } = &self.io {
+            // Flush the WebTransport stream
+            let mut guard = zasynclock!(send);
+            if let Err(e) = guard.finish().await {
+                tracing::trace!("Error closing WebTransport stream {}: {}", self, e);
+            }
+        }
+        self.connection.close(quinn::VarInt::from_u32(0), &[0]);
+        Ok(())
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        match &self.io {
+            LinkIo::Stream { send, .. } => {
+                let mut guard = zasynclock!(send);
+                guard.write(buffer).await.map_err(|e| {
+                    tracing::trace!("Write error on WebTransport link {}: {}", self, e);
+                    zerror!(e).into()
+                })
+            }
+            LinkIo::Datagram { header, mtu, .. } => {
+                self.send_datagram(header, *mtu, buffer)?;
+                Ok(buffer.len())
+            }
+        }
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        match &self.io {
+            LinkIo::Stream { send, .. } => {
+                let mut guard = zasynclock!(send);
+                guard.write_all(buffer).await.map_err(|e| {
+                    tracing::trace!("Write error on WebTransport link {}: {}", self, e);
+                    zerror!(e).into()
+                })
+            }
+            LinkIo::Datagram { header, mtu, .. } => self.send_datagram(header, *mtu, buffer),
+        }
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        match &self.io {
+            LinkIo::Stream { recv, .. } => {
+                let mut guard = zasynclock!(recv);
+                guard
+                    .read(buffer)
+                    .await
+                    .map_err(|e| {
+                        let e = zerror!("Read error on WebTransport link {}: {}", self, e);
+                        tracing::trace!("{}", &e);
+                        e
+                    })?
+                    .ok_or_else(|| {
+                        let e = zerror!(
+                            "Read error on WebTransport link {}: stream {} has been closed",
+                            self,
+                            guard.id()
+                        );
+                        tracing::trace!("{}", &e);
+                        e.into()
+                    })
+            }
+            LinkIo::Datagram { pending, .. } => self.recv_datagram(pending, buffer).await,
+        }
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        match &self.io {
+            LinkIo::Stream { recv, .. } => {
+                let mut guard = zasynclock!(recv);
+                guard.read_exact(buffer).await.map_err(|e| {
+                    let e = zerror!("Read error on WebTransport link {}: {}", self, e);
+                    tracing::trace!("{}", &e);
+                    e.into()
+                })
+            }
+            LinkIo::Datagram { pending, .. } => {
+                let len = self.recv_datagram(pending, buffer).await?;
+                if len != buffer.len() {
+                    bail!(
+                        "Read error on WebTransport link {}: expected {} bytes, received {}",
+                        self,
+                        buffer.len(),
+                        len
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        match &self.io {
+            LinkIo::Stream { .. } => *WEBTRANSPORT_DEFAULT_MTU,
+            LinkIo::Datagram { mtu, .. } => *mtu,
+        }
+    }
+
+    #[inline(always)]
+    fn get_interface_names(&self) -> Vec<String> {
+        get_ip_interface_names(&self.src_addr)
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        matches!(self.io, LinkIo::Stream { .. })
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        matches!(self.io, LinkIo::Stream { .. })
+    }
+
+    #[inline(always)]
+    fn is_secure(&self) -> bool {
+        true
+    }
+}
+
+impl Drop for LinkUnicastWebTransport {
+    fn drop(&mut self) {
+        self.connection.close(quinn::VarInt::from_u32(0), &[0]);
+    }
+}
+
+impl fmt::Display for LinkUnicastWebTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} => {}",
+            self.src_addr,
+            self.connection.remote_address()
+        )?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastWebTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebTransport")
+            .field("src", &self.src_addr)
+            .field("dst", &self.connection.remote_address())
+            .field("reliable", &self.is_reliable())
+            .finish()
+    }
+}
+
+async fn with_handshake_timeout<T>(
+    future: impl std::future::Future<Output = ZResult<T>>,
+) -> ZResult<T> {
+    tokio::time::timeout(
+        Duration::from_millis(*WEBTRANSPORT_HANDSHAKE_TIMEOUT),
+        future,
+    )
+    .await
+    .map_err(|_| zerror!("WebTransport session handshake timed out"))?
+}
+
+async fn connect_session(
+    connection: quinn::Connection,
+    authority: &str,
+    path: &str,
+) -> ZResult<Session> {
+    let settings = h3::spawn_uni_streams_task(connection.clone());
+    let control = h3::open_control_stream(&connection).await?;
+    // The server must enable WebTransport before the session is requested
+    let settings = settings
+        .await
+        .map_err(|_| zerror!("Connection closed before receiving the HTTP/3 settings"))??;
+    if !h3::supports_webtransport(&settings) {
+        bail!("WebTransport is not supported by the server");
+    }
+    let (mut send, mut recv) = connection.open_bi().await.map_err(|e| zerror!("{}", e))?;
+    let id = h3::connect(&mut send, &mut recv, authority, path).await?;
+    Ok(Session {
+        connection,
+        id,
+        control,
+        request: (send, recv),
+    })
+}
+
+async fn accept_session(connection: quinn::Connection) -> ZResult<(Session, LinkIo)> {
+    h3::spawn_uni_streams_task(connection.clone());
+    let control = h3::open_control_stream(&connection).await?;
+    let (mut send, mut recv) = connection.accept_bi().await.map_err(|e| zerror!("{}", e))?;
+    let (id, path) = h3::accept(&mut send, &mut recv).await?;
+    tracing::trace!("Accepted WebTransport session {} on path {}", id, path);
+    let session = Session {
+        connection,
+        id,
+        control,
+        request: (send, recv),
+    };
+
+    // The mode of the link is given by the first message of the client, opening a
+    // bidirectional stream or sending a datagram
+    let first_datagram = async {
+        loop {
+            let datagram = session
+                .connection
+                .read_datagram()
+                .await
+                .map_err(|e| zerror!("{}", e))?;
+            if let Some(payload) = h3::datagram_payload(&datagram, session.id) {
+                return ZResult::Ok(payload.to_vec());
+            }
+        }
+    };
+    let io = tokio::select! {
+        res = h3::accept_bi(&session.connection, session.id) => {
+            let (send, recv) = res?;
+            LinkUnicastWebTransport::stream(send, recv)
+        }
+        res = first_datagram => LinkUnicastWebTransport::datagram(&session, Some(res?))?,
+    };
+    Ok((session, io))
+}
+
+pub struct LinkManagerUnicastWebTransport {
+    manager: NewLinkChannelSender,
+    listeners: ListenersUnicastIP,
+}
+
+impl LinkManagerUnicastWebTransport {
+    pub fn new(manager: NewLinkChannelSender) -> Self {
+        Self {
+            manager,
+            listeners: ListenersUnicastIP::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastWebTransport {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let epaddr = endpoint.address();
+        let host = epaddr
+            .as_str()
+            .split(':')
+            .next()
+            .ok_or("Endpoints must be of the form webtransport/<address>:<port>")?;
+        let epconf = endpoint.config();
+
+        let addr = get_quic_addr(&epaddr).await?;
+
+        let datagram = match epconf
+            .get(WEBTRANSPORT_MODE)
+            .unwrap_or(WEBTRANSPORT_MODE_DEFAULT)
+        {
+            WEBTRANSPORT_MODE_STREAM => false,
+            WEBTRANSPORT_MODE_DATAGRAM => true,
+            mode => bail!("Unknown WebTransport mode on {}: {}", endpoint, mode),
+        };
+        let path = epconf
+            .get(WEBTRANSPORT_PATH)
+            .unwrap_or(WEBTRANSPORT_PATH_DEFAULT);
+
+        let server_name_verification: bool = epconf
+            .get(TLS_SERVER_NAME_VERIFICATION)
+            .unwrap_or(TLS_SERVER_NAME_VERIFICATION_DEFAULT)
+            .parse()?;
+
+        if !server_name_verification {
+            tracing::warn!("Skipping name verification of servers");
+        }
+
+        // Initialize the QUIC connection
+        let mut client_crypto = TlsClientConfig::new(&epconf)
+            .await
+            .map_err(|e| zerror!("Cannot create a new WebTransport client on {addr}: {e}"))?;
+
+        client_crypto.client_config.alpn_protocols = ALPN_H3.iter().map(|&x| x.into()).collect();
+
+        let ip_addr: IpAddr = if addr.is_ipv4() {
+            Ipv4Addr::UNSPECIFIED.into()
+        } else {
+            Ipv6Addr::UNSPECIFIED.into()
+        };
+        let mut quic_endpoint = quinn::Endpoint::client(SocketAddr::new(ip_addr, 0))
+            .map_err(|e| zerror!("Can not create a new WebTransport link bound to {host}: {e}"))?;
+        quic_endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            client_crypto.client_config,
+        )));
+
+        let src_addr = quic_endpoint
+            .local_addr()
+            .map_err(|e| zerror!("Can not create a new WebTransport link bound to {host}: {e}"))?;
+
+        let quic_conn = quic_endpoint
+            .connect(addr, host)
+            .map_err(|e| zerror!("Can not create a new WebTransport link bound to {host}: {e}"))?
+            .await
+            .map_err(|e| zerror!("Can not create a new WebTransport link bound to {host}: {e}"))?;
+
+        // Establish the WebTransport session
+        let session = with_handshake_timeout(async {
+            let session = connect_session(quic_conn, epaddr.as_str(), path).await?;
+            let io = if datagram {
+                LinkUnicastWebTransport::datagram(&session, None)?
+            } else {
+                let (send, recv) = h3::open_bi(&session.connection, session.id).await?;
+                LinkUnicastWebTransport::stream(send, recv)
+            };
+            Ok((session, io))
+        })
+        .await
+        .map_err(|e| zerror!("Can not create a new WebTransport link bound to {host}: {e}"))?;
+
+        let (session, io) = session;
+        let link = Arc::new(LinkUnicastWebTransport::new(
+            session,
+            src_addr,
+            endpoint.into(),
+            io,
+        ));
+
+        Ok(LinkUnicast(link))
+    }
+
+    async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
+        let epaddr = endpoint.address();
+        let epconf = endpoint.config();
+
+        if epconf.is_empty() {
+            bail!("No WebTransport configuration provided");
+        };
+
+        let addr = get_quic_addr(&epaddr).await?;
+
+        // Server config
+        let mut server_crypto = TlsServerConfig::new(&epconf)
+            .await
+            .map_err(|e| zerror!("Cannot create a new WebTransport listener on {addr}: {e}"))?;
+        server_crypto.server_config.alpn_protocols = ALPN_H3.iter().map(|&x| x.into()).collect();
+        let mut server_config =
+            quinn::ServerConfig::with_crypto(Arc::new(server_crypto.server_config));
+
+        // The clients open the HTTP/3 control and QPACK unidirectional streams
+        Arc::get_mut(&mut server_config.transport)
+            .unwrap()
+            .max_concurrent_uni_streams(8_u8.into());
+        // The clients open the request stream of the session and, in stream mode, the
+        // WebTransport stream of the link
+        Arc::get_mut(&mut server_config.transport)
+            .unwrap()
+            .max_concurrent_bidi_streams(2_u8.into());
+
+        // Initialize the Endpoint
+        let quic_endpoint = quinn::Endpoint::server(server_config, addr)
+            .map_err(|e| zerror!("Can not create a new WebTransport listener on {addr}: {e}"))?;
+
+        let local_addr = quic_endpoint
+            .local_addr()
+            .map_err(|e| zerror!("Can not create a new WebTransport listener on {addr}: {e}"))?;
+
+        // Update the endpoint locator address
+        endpoint = EndPoint::new(
+            endpoint.protocol(),
+            local_addr.to_string(),
+            endpoint.metadata(),
+            endpoint.config(),
+        )?;
+
+        // Spawn the accept loop for the listener
+        let token = self.listeners.token.child_token();
+        let c_token = token.clone();
+
+        let c_manager = self.manager.clone();
+
+        let task = async move { accept_task(quic_endpoint, c_token, c_manager).await };
+
+        let locator = endpoint.to_locator();
+
+        self.listeners
+            .add_listener(endpoint, local_addr, task, token)
+            .await?;
+
+        Ok(locator)
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let epaddr = endpoint.address();
+        let addr = get_quic_addr(&epaddr).await?;
+        self.listeners.del_listener(addr).await
+    }
+
+    async fn get_listeners(&self) -> Vec<EndPoint> {
+        self.listeners.get_endpoints()
+    }
+
+    async fn get_locators(&self) -> Vec<Locator> {
+        self.listeners.get_locators()
+    }
+}
+
+async fn accept_task(
+    endpoint: quinn::Endpoint,
+    token: CancellationToken,
+    manager: NewLinkChannelSender,
+) -> ZResult<()> {
+    async fn accept(acceptor: quinn::Accept<'_>) -> ZResult<quinn::Connection> {
+        let qc = acceptor
+            .await
+            .ok_or_else(|| zerror!("Can not accept WebTransport connections: acceptor closed"))?;
+
+        let conn = qc.await.map_err(|e| {
+            let e = zerror!("WebTransport acceptor failed: {:?}", e);
+            tracing::warn!("{}", e);
+            e
+        })?;
+
+        Ok(conn)
+    }
+
+    let src_addr = endpoint
+        .local_addr()
+        .map_err(|e| zerror!("Can not accept WebTransport connections: {}", e))?;
+
+    // The accept future
+    tracing::trace!(
+        "Ready to accept WebTransport connections on: {:?}",
+        src_addr
+    );
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+
+            res = accept(endpoint.accept()) => {
+                match res {
+                    Ok(quic_conn) => {
+                        // Establish the WebTransport session without blocking the accept loop
+                        let c_manager = manager.clone();
+                        zenoh_runtime::ZRuntime::Acceptor.spawn(async move {
+                            let dst_addr = quic_conn.remote_address();
+                            let (session, io) = match with_handshake_timeout(accept_session(quic_conn)).await {
+                                Ok(res) => res,
+                                Err(e) => {
+                                    tracing::warn!("Can not accept WebTransport session from {}: {}", dst_addr, e);
+                                    return;
+                                }
+                            };
+                            tracing::debug!("Accepted WebTransport connection on {:?}: {:?}", src_addr, dst_addr);
+                            // Create the new link object
+                            let link = Arc::new(LinkUnicastWebTransport::new(
+                                session,
+                                src_addr,
+                                Locator::new(WEBTRANSPORT_LOCATOR_PREFIX, dst_addr.to_string(), "").unwrap(),
+                                io,
+                            ));
+
+                            // Communicate the new link to the initial transport manager
+                            if let Err(e) = c_manager.send_async(LinkUnicast(link)).await {
+                                tracing::error!("{}-{}: {}", file!(), line!(), e)
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        tracing::warn!("{} Hint: increase the system open file limit.", e);
+                        // Throttle the accept loop upon an error
+                        // NOTE: This might be due to various factors. However, the most common case is that
+                        //       the process has reached the maximum number of open files in the system. On
+                        //       Linux systems this limit can be changed by using the "ulimit" command line
+                        //       tool. In case of systemd-based systems, this can be changed by using the
+                        //       "sysctl" command line tool.
+                        tokio::time::sleep(Duration::from_micros(*WEBTRANSPORT_ACCEPT_THROTTLE_TIME)).await;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
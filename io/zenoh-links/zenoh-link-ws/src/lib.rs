@@ -26,6 +26,7 @@ use zenoh_protocol::core::{endpoint::Address, Locator};
 use zenoh_result::{bail, ZResult};
 mod unicast;
 pub use unicast::*;
+pub use zenoh_link_tls::{config, TlsConfigurator as WssConfigurator};
 
 // Default MTU (WSS PDU) in bytes.
 // NOTE: Since TCP is a byte-stream oriented transport, theoretically it has
@@ -36,6 +37,7 @@ pub use unicast::*;
 const WS_MAX_MTU: u16 = u16::MAX;
 
 pub const WS_LOCATOR_PREFIX: &str = "ws";
+pub const WSS_LOCATOR_PREFIX: &str = "wss";
 
 #[derive(Default, Clone, Copy)]
 pub struct WsLocatorInspector;
@@ -49,6 +51,18 @@ impl LocatorInspector for WsLocatorInspector {
     }
 }
 
+#[derive(Default, Clone, Copy)]
+pub struct WssLocatorInspector;
+#[async_trait]
+impl LocatorInspector for WssLocatorInspector {
+    fn protocol(&self) -> &str {
+        WSS_LOCATOR_PREFIX
+    }
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
 zconfigurable! {
     // Default MTU (TCP PDU) in bytes.
     static ref WS_DEFAULT_MTU: u16 = WS_MAX_MTU;
@@ -64,12 +78,8 @@ pub async fn get_ws_addr(address: Address<'_>) -> ZResult<SocketAddr> {
     }
 }
 
-pub async fn get_ws_url(address: Address<'_>) -> ZResult<Url> {
-    match Url::parse(&format!(
-        "{}://{}",
-        WS_LOCATOR_PREFIX,
-        get_ws_addr(address).await?
-    )) {
+pub async fn get_ws_url(protocol: &str, address: Address<'_>) -> ZResult<Url> {
+    match Url::parse(&format!("{}://{}", protocol, get_ws_addr(address).await?)) {
         Ok(url) => Ok(url),
         Err(e) => bail!(
             "Couldn't resolve WebSocket locator address: {}: {}",
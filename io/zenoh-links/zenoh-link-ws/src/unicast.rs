@@ -22,27 +22,51 @@ use std::fmt;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
 use tokio::task::JoinHandle;
-use tokio_tungstenite::accept_async;
+use tokio_rustls::{TlsAcceptor, TlsConnector};
 use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::{accept_async, client_async};
 use tokio_util::sync::CancellationToken;
 use zenoh_core::{zasynclock, zasyncread, zasyncwrite};
 use zenoh_link_commons::{
     LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
 };
+use zenoh_link_tls::{get_tls_server_name, TlsClientConfig, TlsServerConfig};
 use zenoh_protocol::core::{EndPoint, Locator};
 use zenoh_result::{bail, zerror, ZResult};
 
-use super::{get_ws_addr, get_ws_url, TCP_ACCEPT_THROTTLE_TIME, WS_DEFAULT_MTU, WS_LOCATOR_PREFIX};
+use super::{
+    get_ws_addr, get_ws_url, TCP_ACCEPT_THROTTLE_TIME, WSS_LOCATOR_PREFIX, WS_DEFAULT_MTU,
+    WS_LOCATOR_PREFIX,
+};
+
+// The stream carrying the WebSocket messages: a TCP stream, wrapped in a TLS stream for wss.
+trait WsIo: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> WsIo for T {}
+
+type WsStream = WebSocketStream<Box<dyn WsIo>>;
+
+fn set_nodelay(stream: &TcpStream, src_addr: &SocketAddr, dst_addr: &SocketAddr) {
+    // Set the TCP nodelay option
+    if let Err(err) = stream.set_nodelay(true) {
+        tracing::warn!(
+            "Unable to set NODEALY option on TCP link {} => {}: {}",
+            src_addr,
+            dst_addr,
+            err
+        );
+    }
+}
 
 pub struct LinkUnicastWs {
     // The inbound message stream as returned from the futures_util::stream::StreamExt::split method
-    recv: AsyncMutex<SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
+    recv: AsyncMutex<SplitStream<WsStream>>,
     // // The outbound message stream as returned from the futures_util::stream::StreamExt::split method
-    send: AsyncMutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>,
+    send: AsyncMutex<SplitSink<WsStream, Message>>,
     // The source socket address of this link (address used on the local host)
     src_addr: SocketAddr,
     src_locator: Locator,
@@ -55,20 +79,11 @@ pub struct LinkUnicastWs {
 
 impl LinkUnicastWs {
     fn new(
-        socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        socket: WsStream,
         src_addr: SocketAddr,
         dst_addr: SocketAddr,
+        protocol: &str,
     ) -> LinkUnicastWs {
-        // Set the TCP nodelay option
-        if let Err(err) = get_stream(&socket).set_nodelay(true) {
-            tracing::warn!(
-                "Unable to set NODEALY option on TCP link {} => {}: {}",
-                src_addr,
-                dst_addr,
-                err
-            );
-        }
-
         let (send, recv) = socket.split();
         let send = AsyncMutex::new(send);
         let recv = AsyncMutex::new(recv);
@@ -79,9 +94,9 @@ impl LinkUnicastWs {
             recv,
             send,
             src_addr,
-            src_locator: Locator::new(WS_LOCATOR_PREFIX, src_addr.to_string(), "").unwrap(),
+            src_locator: Locator::new(protocol, src_addr.to_string(), "").unwrap(),
             dst_addr,
-            dst_locator: Locator::new(WS_LOCATOR_PREFIX, dst_addr.to_string(), "").unwrap(),
+            dst_locator: Locator::new(protocol, dst_addr.to_string(), "").unwrap(),
             leftovers: AsyncMutex::new(None),
         }
     }
@@ -220,6 +235,11 @@ impl LinkUnicastTrait for LinkUnicastWs {
     fn is_streamed(&self) -> bool {
         false
     }
+
+    #[inline(always)]
+    fn is_secure(&self) -> bool {
+        self.src_locator.protocol().as_str() == WSS_LOCATOR_PREFIX
+    }
 }
 
 impl Drop for LinkUnicastWs {
@@ -280,6 +300,7 @@ impl ListenerUnicastWs {
 pub struct LinkManagerUnicastWs {
     manager: NewLinkChannelSender,
     listeners: Arc<AsyncRwLock<HashMap<SocketAddr, ListenerUnicastWs>>>,
+    secure: bool,
 }
 
 impl LinkManagerUnicastWs {
@@ -287,6 +308,23 @@ impl LinkManagerUnicastWs {
         Self {
             manager,
             listeners: Arc::new(AsyncRwLock::new(HashMap::new())),
+            secure: false,
+        }
+    }
+
+    /// Creates a manager of WebSocket links secured by TLS (`wss`).
+    pub fn new_secure(manager: NewLinkChannelSender) -> Self {
+        Self {
+            secure: true,
+            ..Self::new(manager)
+        }
+    }
+
+    fn protocol(&self) -> &'static str {
+        if self.secure {
+            WSS_LOCATOR_PREFIX
+        } else {
+            WS_LOCATOR_PREFIX
         }
     }
 }
@@ -294,9 +332,10 @@ impl LinkManagerUnicastWs {
 #[async_trait]
 impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
-        let dst_url = get_ws_url(endpoint.address()).await?;
+        let epaddr = endpoint.address();
+        let dst_url = get_ws_url(self.protocol(), epaddr).await?;
 
-        let (stream, _) = tokio_tungstenite::connect_async(&dst_url)
+        let tcp_stream = TcpStream::connect(get_ws_addr(epaddr).await?)
             .await
             .map_err(|e| {
                 zerror!(
@@ -306,7 +345,15 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                 )
             })?;
 
-        let src_addr = get_stream(&stream).local_addr().map_err(|e| {
+        let src_addr = tcp_stream.local_addr().map_err(|e| {
+            zerror!(
+                "Can not create a new WebSocket link bound to {}: {}",
+                dst_url,
+                e
+            )
+        })?;
+
+        let dst_addr = tcp_stream.peer_addr().map_err(|e| {
             zerror!(
                 "Can not create a new WebSocket link bound to {}: {}",
                 dst_url,
@@ -314,7 +361,30 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
             )
         })?;
 
-        let dst_addr = get_stream(&stream).peer_addr().map_err(|e| {
+        set_nodelay(&tcp_stream, &src_addr, &dst_addr);
+
+        let io: Box<dyn WsIo> = if self.secure {
+            let client_config = TlsClientConfig::new(&endpoint.config())
+                .await
+                .map_err(|e| zerror!("Cannot create a new WebSocket link to {endpoint}: {e}"))?;
+            let connector = TlsConnector::from(Arc::new(client_config.client_config));
+            let server_name = get_tls_server_name(&epaddr)?;
+            let tls_stream = connector
+                .connect(server_name.to_owned(), tcp_stream)
+                .await
+                .map_err(|e| {
+                    zerror!(
+                        "Can not create a new WebSocket link bound to {}: {}",
+                        dst_url,
+                        e
+                    )
+                })?;
+            Box::new(tls_stream)
+        } else {
+            Box::new(tcp_stream)
+        };
+
+        let (stream, _) = client_async(dst_url.as_str(), io).await.map_err(|e| {
             zerror!(
                 "Can not create a new WebSocket link bound to {}: {}",
                 dst_url,
@@ -322,7 +392,12 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
             )
         })?;
 
-        let link = Arc::new(LinkUnicastWs::new(stream, src_addr, dst_addr));
+        let link = Arc::new(LinkUnicastWs::new(
+            stream,
+            src_addr,
+            dst_addr,
+            self.protocol(),
+        ));
 
         Ok(LinkUnicast(link))
     }
@@ -330,6 +405,16 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
     async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
         let addr = get_ws_addr(endpoint.address()).await?;
 
+        // Initialize the TlsAcceptor for wss
+        let acceptor = if self.secure {
+            let server_config = TlsServerConfig::new(&endpoint.config())
+                .await
+                .map_err(|e| zerror!("Cannot create a new WebSocket listener on {addr}. {e}"))?;
+            Some(TlsAcceptor::from(Arc::new(server_config.server_config)))
+        } else {
+            None
+        };
+
         // Bind the TCP socket
         let socket = TcpListener::bind(addr).await.map_err(|e| {
             zerror!(
@@ -361,10 +446,11 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
         let c_manager = self.manager.clone();
         let c_listeners = self.listeners.clone();
         let c_addr = local_addr;
+        let protocol = self.protocol();
 
         let task = async move {
             // Wait for the accept loop to terminate
-            let res = accept_task(socket, c_token, c_manager).await;
+            let res = accept_task(socket, acceptor, protocol, c_token, c_manager).await;
             zasyncwrite!(c_listeners).remove(&c_addr);
             res
         };
@@ -417,7 +503,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                         for ipaddr in ipaddrs {
                             if !ipaddr.is_loopback() && !ipaddr.is_multicast() && ipaddr.is_ipv4() {
                                 let l = Locator::new(
-                                    WS_LOCATOR_PREFIX,
+                                    self.protocol(),
                                     SocketAddr::new(ipaddr, key.port()).to_string(),
                                     value.endpoint.metadata(),
                                 )
@@ -434,7 +520,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                         for ipaddr in ipaddrs {
                             if !ipaddr.is_loopback() && !ipaddr.is_multicast() && ipaddr.is_ipv6() {
                                 let l = Locator::new(
-                                    WS_LOCATOR_PREFIX,
+                                    self.protocol(),
                                     SocketAddr::new(ipaddr, key.port()).to_string(),
                                     value.endpoint.metadata(),
                                 )
@@ -457,6 +543,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
 
 async fn accept_task(
     socket: TcpListener,
+    acceptor: Option<TlsAcceptor>,
+    protocol: &'static str,
     token: CancellationToken,
     manager: NewLinkChannelSender,
 ) -> ZResult<()> {
@@ -504,15 +592,28 @@ async fn accept_task(
             dst_addr
         );
 
-        let stream = accept_async(MaybeTlsStream::Plain(stream))
-            .await
-            .map_err(|e| {
-                let e = zerror!("Error when creating the WebSocket session: {}", e);
-                tracing::trace!("{}", e);
-                e
-            })?;
+        set_nodelay(&stream, &src_addr, &dst_addr);
+
+        let io: Box<dyn WsIo> = match &acceptor {
+            Some(acceptor) => match acceptor.accept(stream).await {
+                Ok(tls_stream) => Box::new(tls_stream),
+                Err(e) => {
+                    tracing::warn!("Can not accept TLS (WebSocket) connection: {}", e);
+                    continue;
+                }
+            },
+            None => Box::new(stream),
+        };
+
+        let stream = match accept_async(io).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::warn!("Error when creating the WebSocket session: {}", e);
+                continue;
+            }
+        };
         // Create the new link object
-        let link = Arc::new(LinkUnicastWs::new(stream, src_addr, dst_addr));
+        let link = Arc::new(LinkUnicastWs::new(stream, src_addr, dst_addr, protocol));
 
         // Communicate the new link to the initial transport manager
         if let Err(e) = manager.send_async(LinkUnicast(link)).await {
@@ -522,14 +623,3 @@ async fn accept_task(
 
     Ok(())
 }
-
-fn get_stream(ws_stream: &WebSocketStream<MaybeTlsStream<TcpStream>>) -> &TcpStream {
-    match ws_stream.get_ref() {
-        MaybeTlsStream::Plain(s) => s,
-        // This two are available only if the TLS features are enabled for
-        // tokio_tungstenite.
-        // MaybeTlsStream::NativeTls(s) => s.get_ref().get_ref(),
-        // MaybeTlsStream::Rustls(s) => s.get_ref().0
-        _ => panic!(),
-    }
-}
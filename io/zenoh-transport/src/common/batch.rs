@@ -11,27 +11,36 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use std::num::NonZeroUsize;
+use std::{fmt, num::NonZeroUsize, sync::Arc};
 use zenoh_buffers::{
     buffer::Buffer,
     reader::{DidntRead, HasReader},
-    writer::{DidntWrite, HasWriter, Writer},
-    BBuf, ZBufReader, ZSlice, ZSliceBuffer,
+    writer::{BacktrackableWriter, DidntWrite, HasWriter, Writer},
+    BBuf, ZBufReader, ZSlice,
 };
 use zenoh_codec::{
     transport::batch::{BatchError, Zenoh080Batch},
     RCodec, WCodec,
 };
+#[cfg(feature = "transport_compression")]
+use zenoh_protocol::common::imsg;
 use zenoh_protocol::{
     network::NetworkMessage,
     transport::{fragment::FragmentHeader, frame::FrameHeader, BatchSize, TransportMessage},
 };
-use zenoh_result::{zerror, ZResult};
-#[cfg(feature = "transport_compression")]
-use {std::sync::Arc, zenoh_protocol::common::imsg};
+use zenoh_result::{bail, zerror, ZResult};
+use zenoh_sync::PooledBuffer;
 
 const L_LEN: usize = (BatchSize::BITS / 8) as usize;
 const H_LEN: usize = BatchHeader::SIZE;
+const C_LEN: usize = (u32::BITS / 8) as usize;
+
+// The CRC32C (Castagnoli) algorithm used to checksum the batches
+const CHECKSUM: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+
+// The size of the slices referenced by the batches instead of being copied, below which
+// copying them is cheaper than adding them to the vectored write of the batch
+const MIN_REFERENCED_SLICE_LEN: usize = 4_096;
 
 // Split the inner buffer into (length, header, payload) inmutable slices
 macro_rules! zsplit {
@@ -76,6 +85,55 @@ macro_rules! zsplit_mut {
     }};
 }
 
+// Compression algorithm
+#[cfg(feature = "transport_compression")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    #[default]
+    Lz4,
+    Zstd,
+}
+
+#[cfg(feature = "transport_compression")]
+impl CompressionAlgorithm {
+    /// The bitmask of the algorithms this node is able to decompress.
+    pub const SUPPORTED: u64 = Self::Lz4.mask() | Self::Zstd.mask();
+
+    pub const fn mask(&self) -> u64 {
+        match self {
+            Self::Lz4 => 1, // 1 << 0
+            Self::Zstd => 1 << 1,
+        }
+    }
+
+    // The upper bound of the size of a compressed input of the given length
+    fn max_output_size(&self, len: usize) -> usize {
+        match self {
+            Self::Lz4 => lz4_flex::block::get_maximum_output_size(len),
+            Self::Zstd => zstd::zstd_safe::compress_bound(len),
+        }
+    }
+}
+
+#[cfg(feature = "transport_compression")]
+impl From<zenoh_config::CompressionAlgorithmConf> for CompressionAlgorithm {
+    fn from(conf: zenoh_config::CompressionAlgorithmConf) -> Self {
+        match conf {
+            zenoh_config::CompressionAlgorithmConf::Lz4 => Self::Lz4,
+            zenoh_config::CompressionAlgorithmConf::Zstd => Self::Zstd,
+        }
+    }
+}
+
+#[cfg(feature = "transport_compression")]
+thread_local! {
+    // Zstd contexts are expensive to create, hence they are lazily created once per thread
+    static ZSTD_COMPRESSOR: std::cell::RefCell<Option<zstd::bulk::Compressor<'static>>> =
+        std::cell::RefCell::new(zstd::bulk::Compressor::new(zstd::DEFAULT_COMPRESSION_LEVEL).ok());
+    static ZSTD_DECOMPRESSOR: std::cell::RefCell<Option<zstd::bulk::Decompressor<'static>>> =
+        std::cell::RefCell::new(zstd::bulk::Decompressor::new().ok());
+}
+
 // Batch config
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct BatchConfig {
@@ -83,6 +141,14 @@ pub struct BatchConfig {
     pub is_streamed: bool,
     #[cfg(feature = "transport_compression")]
     pub is_compression: bool,
+    // The algorithm used to compress the batches
+    #[cfg(feature = "transport_compression")]
+    pub compression_algorithm: CompressionAlgorithm,
+    // The batches whose payload is smaller than this size are not compressed
+    #[cfg(feature = "transport_compression")]
+    pub compression_threshold: usize,
+    // A checksum of the batch is appended at its end, after compression if any
+    pub is_checksum: bool,
 }
 
 impl Default for BatchConfig {
@@ -92,6 +158,11 @@ impl Default for BatchConfig {
             is_streamed: false,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: 0,
+            is_checksum: false,
         }
     }
 }
@@ -115,8 +186,11 @@ impl BatchConfig {
         }
         #[cfg(feature = "transport_compression")]
         {
-            self.is_compression
-                .then_some(BatchHeader::new(BatchHeader::COMPRESSION))
+            let header = match self.compression_algorithm {
+                CompressionAlgorithm::Lz4 => BatchHeader::COMPRESSION,
+                CompressionAlgorithm::Zstd => BatchHeader::COMPRESSION | BatchHeader::ZSTD,
+            };
+            self.is_compression.then_some(BatchHeader::new(header))
         }
     }
 
@@ -127,6 +201,27 @@ impl BatchConfig {
         }
         len
     }
+
+    /// The size of the support buffer required to finalize a batch, if compression is enabled.
+    pub fn max_support_buffer_size(&self) -> Option<usize> {
+        #[cfg(feature = "transport_compression")]
+        if self.is_compression {
+            let len = self
+                .compression_algorithm
+                .max_output_size(self.max_buffer_size());
+            return Some(len + self.trailer_len());
+        }
+        None
+    }
+
+    // The number of bytes appended at the end of a batch
+    const fn trailer_len(&self) -> usize {
+        if self.is_checksum {
+            C_LEN
+        } else {
+            0
+        }
+    }
 }
 
 // Batch header
@@ -138,6 +233,8 @@ impl BatchHeader {
     const SIZE: usize = 1;
     #[cfg(feature = "transport_compression")]
     const COMPRESSION: u8 = 1; // 1 << 0
+    #[cfg(feature = "transport_compression")]
+    const ZSTD: u8 = 1 << 1; // 1 << 1, the batch is compressed with Zstd instead of LZ4
 
     #[cfg(feature = "transport_compression")]
     const fn new(h: u8) -> Self {
@@ -155,6 +252,17 @@ impl BatchHeader {
     pub fn is_compression(&self) -> bool {
         imsg::has_flag(self.as_u8(), Self::COMPRESSION)
     }
+
+    /// The algorithm the batch has been compressed with.
+    #[cfg(feature = "transport_compression")]
+    #[inline(always)]
+    pub fn compression_algorithm(&self) -> CompressionAlgorithm {
+        if imsg::has_flag(self.as_u8(), Self::ZSTD) {
+            CompressionAlgorithm::Zstd
+        } else {
+            CompressionAlgorithm::Lz4
+        }
+    }
 }
 
 // WRITE BATCH
@@ -176,6 +284,8 @@ impl WBatchStats {
 pub enum Finalize {
     Batch,
     Buffer,
+    /// The batch, to be followed by the given trailer on the link.
+    Trailer([u8; C_LEN]),
 }
 
 /// Write Batch
@@ -197,14 +307,21 @@ pub enum Finalize {
 ///
 /// | Keep Alive | Frame Reliable<Zenoh Message, Zenoh Message> | Frame Best Effort<Zenoh Message Fragment> |
 ///
+/// Unless the batch is compressed, the large payloads (e.g. shared memory or large publications)
+/// are not copied in the buffer: the batch references their slices, which are written along with
+/// the buffer with vectored I/O (see [`WBatch::as_slices`]).
 #[derive(Clone, Debug)]
 pub struct WBatch {
     // The buffer to perform the batching on
     pub buffer: BBuf,
+    // The slices referenced by the batch instead of being copied in the buffer
+    slices: WBatchSlices,
     // The batch codec
     pub codec: Zenoh080Batch,
     // It contains 1 byte as additional header, e.g. to signal the batch is compressed
     pub config: BatchConfig,
+    // Whether the batch holds a fragment of a message whose next fragments are yet to be sent
+    pub more_fragments: bool,
     // Statistics related to this batch
     #[cfg(feature = "stats")]
     pub stats: WBatchStats,
@@ -213,9 +330,12 @@ pub struct WBatch {
 impl WBatch {
     pub fn new(config: BatchConfig) -> Self {
         let mut batch = Self {
-            buffer: BBuf::with_capacity(config.max_buffer_size()),
+            // Leave room for the trailer, it is appended when finalizing the batch
+            buffer: BBuf::with_capacity(config.max_buffer_size() - config.trailer_len()),
+            slices: WBatchSlices::default(),
             codec: Zenoh080Batch::new(),
             config,
+            more_fragments: false,
             #[cfg(feature = "stats")]
             stats: WBatchStats::default(),
         };
@@ -236,14 +356,16 @@ impl WBatch {
     #[inline(always)]
     pub fn len(&self) -> BatchSize {
         let (_l, _h, p) = Self::split(self.buffer.as_slice(), &self.config);
-        p.len() as BatchSize
+        (p.len() + self.slices.len) as BatchSize
     }
 
     /// Clear the [`WBatch`][WBatch] memory buffer and related internal state.
     #[inline(always)]
     pub fn clear(&mut self) {
         self.buffer.clear();
+        self.slices.clear();
         self.codec.clear();
+        self.more_fragments = false;
         #[cfg(feature = "stats")]
         {
             self.stats.clear();
@@ -251,12 +373,39 @@ impl WBatch {
         Self::init(&mut self.buffer, &self.config);
     }
 
-    /// Get a `&[u8]` to access the internal memory buffer, usually for transmitting it on the network.
+    /// Get a `&[u8]` to access the internal memory buffer. It is the whole batch only if it
+    /// references no slice, see [`WBatch::as_slices`].
     #[inline(always)]
     pub fn as_slice(&self) -> &[u8] {
         self.buffer.as_slice()
     }
 
+    /// Get the bytes of the batch, i.e. its internal memory buffer interleaved with the slices
+    /// it references, usually for transmitting them on the network with vectored I/O.
+    pub fn as_slices(&self) -> Vec<&[u8]> {
+        let buffer = self.buffer.as_slice();
+        let mut slices = Vec::with_capacity(2 * self.slices.slices.len() + 1);
+        let mut start = 0;
+        for (offset, slice) in self.slices.slices.iter() {
+            slices.push(&buffer[start..*offset]);
+            slices.push(slice.as_slice());
+            start = *offset;
+        }
+        slices.push(&buffer[start..]);
+        slices
+    }
+
+    // The writer serializing on the batch, the slices are only referenced if the batch is not
+    // compressed as the compression needs a contiguous buffer
+    fn writer(&mut self) -> (WBatchWriter<'_>, &mut Zenoh080Batch) {
+        let writer = WBatchWriter {
+            buffer: &mut self.buffer,
+            slices: &mut self.slices,
+            reference: !self.config.has_header(),
+        };
+        (writer, &mut self.codec)
+    }
+
     fn init(buffer: &mut BBuf, config: &BatchConfig) {
         let mut writer = buffer.writer();
         if config.is_streamed {
@@ -296,47 +445,104 @@ impl WBatch {
             }
         }
 
+        if self.config.is_checksum {
+            res = self.checksum(buffer.as_deref_mut(), res)?;
+        }
+
         if self.config.is_streamed {
-            let buff = match res {
-                Finalize::Batch => self.buffer.as_mut_slice(),
-                Finalize::Buffer => buffer
-                    .as_mut()
-                    .ok_or_else(|| zerror!("Support buffer not provided"))?
-                    .as_mut_slice(),
+            let (buff, slices_len, trailer_len) = match &res {
+                Finalize::Batch => (self.buffer.as_mut_slice(), self.slices.len, 0),
+                Finalize::Buffer => (
+                    buffer
+                        .as_mut()
+                        .ok_or_else(|| zerror!("Support buffer not provided"))?
+                        .as_mut_slice(),
+                    0,
+                    0,
+                ),
+                Finalize::Trailer(trailer) => {
+                    (self.buffer.as_mut_slice(), self.slices.len, trailer.len())
+                }
             };
             let (length, header, payload) = Self::split_mut(buff, &self.config);
-            let len: BatchSize = (header.len() as BatchSize) + (payload.len() as BatchSize);
+            let len: BatchSize = (header.len() as BatchSize)
+                + (payload.len() as BatchSize)
+                + (slices_len as BatchSize)
+                + (trailer_len as BatchSize);
             length.copy_from_slice(&len.to_le_bytes());
         }
 
         Ok(res)
     }
 
+    // Append the checksum of the header and the payload to the finalized batch. The batch buffer
+    // has no room for it, hence the checksum of an uncompressed batch is returned as a trailer
+    // to be written right after the batch on the link.
+    fn checksum(&mut self, support: Option<&mut BBuf>, res: Finalize) -> ZResult<Finalize> {
+        if let Finalize::Buffer = res {
+            let support = support.ok_or_else(|| zerror!("Support buffer not provided"))?;
+            let (length, _header, _payload) = Self::split(support.as_slice(), &self.config);
+            let crc = CHECKSUM.checksum(&support.as_slice()[length.len()..]);
+            support
+                .writer()
+                .write_exact(&crc.to_le_bytes())
+                .map_err(|_| zerror!("Checksum error"))?;
+            return Ok(Finalize::Buffer);
+        }
+
+        let (length, _header, _payload) = Self::split(self.buffer.as_slice(), &self.config);
+        let mut digest = CHECKSUM.digest();
+        for (i, slice) in self.as_slices().into_iter().enumerate() {
+            digest.update(if i == 0 {
+                &slice[length.len()..]
+            } else {
+                slice
+            });
+        }
+        Ok(Finalize::Trailer(digest.finalize().to_le_bytes()))
+    }
+
     #[cfg(feature = "transport_compression")]
     fn compress(&mut self, support: &mut BBuf) -> ZResult<Finalize> {
         // Write the initial bytes for the batch
         support.clear();
         Self::init(support, &self.config);
 
-        // Compress the actual content
+        // Compress the actual content, unless it is too small to be worth it
         let (_length, _header, payload) = Self::split(self.buffer.as_slice(), &self.config);
-        let mut writer = support.writer();
-        writer
-            .with_slot(writer.remaining(), |b| {
-                lz4_flex::block::compress_into(payload, b).unwrap_or(0)
-            })
-            .map_err(|_| zerror!("Compression error"))?;
-
-        // Verify wether the resulting compressed data is smaller than the initial input
-        if support.len() < self.buffer.len() {
-            Ok(Finalize::Buffer)
-        } else {
-            // Keep the original uncompressed buffer and unset the compression flag from the header
-            let (_l, h, _p) = Self::split_mut(self.buffer.as_mut_slice(), &self.config);
-            let h = h.first_mut().ok_or_else(|| zerror!("Empty BatchHeader"))?;
-            *h &= !BatchHeader::COMPRESSION;
-            Ok(Finalize::Batch)
+        if payload.len() >= self.config.compression_threshold {
+            let mut writer = support.writer();
+            writer
+                .with_slot(writer.remaining(), |b| {
+                    match self.config.compression_algorithm {
+                        CompressionAlgorithm::Lz4 => {
+                            lz4_flex::block::compress_into(payload, b).unwrap_or(0)
+                        }
+                        CompressionAlgorithm::Zstd => Self::zstd_compress(payload, b).unwrap_or(0),
+                    }
+                })
+                .map_err(|_| zerror!("Compression error"))?;
+
+            // Verify wether the resulting compressed data is smaller than the initial input
+            if support.len() < self.buffer.len() {
+                return Ok(Finalize::Buffer);
+            }
         }
+
+        // Keep the original uncompressed buffer and unset the compression flags from the header
+        let (_l, h, _p) = Self::split_mut(self.buffer.as_mut_slice(), &self.config);
+        let h = h.first_mut().ok_or_else(|| zerror!("Empty BatchHeader"))?;
+        *h &= !(BatchHeader::COMPRESSION | BatchHeader::ZSTD);
+        Ok(Finalize::Batch)
+    }
+
+    #[cfg(feature = "transport_compression")]
+    fn zstd_compress(payload: &[u8], into: &mut [u8]) -> Option<usize> {
+        ZSTD_COMPRESSOR.with(|c| {
+            c.borrow_mut()
+                .as_mut()
+                .and_then(|c| c.compress_to_buffer(payload, into).ok())
+        })
     }
 }
 
@@ -350,8 +556,8 @@ impl Encode<&TransportMessage> for &mut WBatch {
     type Output = Result<(), DidntWrite>;
 
     fn encode(self, x: &TransportMessage) -> Self::Output {
-        let mut writer = self.buffer.writer();
-        self.codec.write(&mut writer, x)
+        let (mut writer, codec) = self.writer();
+        codec.write(&mut writer, x)
     }
 }
 
@@ -359,8 +565,8 @@ impl Encode<&NetworkMessage> for &mut WBatch {
     type Output = Result<(), BatchError>;
 
     fn encode(self, x: &NetworkMessage) -> Self::Output {
-        let mut writer = self.buffer.writer();
-        self.codec.write(&mut writer, x)
+        let (mut writer, codec) = self.writer();
+        codec.write(&mut writer, x)
     }
 }
 
@@ -368,8 +574,8 @@ impl Encode<(&NetworkMessage, &FrameHeader)> for &mut WBatch {
     type Output = Result<(), BatchError>;
 
     fn encode(self, x: (&NetworkMessage, &FrameHeader)) -> Self::Output {
-        let mut writer = self.buffer.writer();
-        self.codec.write(&mut writer, x)
+        let (mut writer, codec) = self.writer();
+        codec.write(&mut writer, x)
     }
 }
 
@@ -377,8 +583,134 @@ impl Encode<(&mut ZBufReader<'_>, &mut FragmentHeader)> for &mut WBatch {
     type Output = Result<NonZeroUsize, DidntWrite>;
 
     fn encode(self, x: (&mut ZBufReader<'_>, &mut FragmentHeader)) -> Self::Output {
-        let mut writer = self.buffer.writer();
-        self.codec.write(&mut writer, x)
+        let (mut writer, codec) = self.writer();
+        codec.write(&mut writer, x)
+    }
+}
+
+// The slices referenced by a batch instead of being copied in its buffer
+#[derive(Clone, Debug, Default)]
+struct WBatchSlices {
+    // The slices, along with the offset in the buffer they are inserted at
+    slices: Vec<(usize, ZSlice)>,
+    // The total length of the slices
+    len: usize,
+}
+
+impl WBatchSlices {
+    fn clear(&mut self) {
+        self.slices.clear();
+        self.len = 0;
+    }
+}
+
+// The writer of a batch, bounded by the capacity of its buffer minus the length of the slices
+// it references
+struct WBatchWriter<'a> {
+    buffer: &'a mut BBuf,
+    slices: &'a mut WBatchSlices,
+    reference: bool,
+}
+
+impl Writer for WBatchWriter<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Result<NonZeroUsize, DidntWrite> {
+        let len = bytes.len().min(self.remaining());
+        if len == 0 {
+            return Err(DidntWrite);
+        }
+        let mut writer = &mut *self.buffer;
+        writer.write(&bytes[..len])
+    }
+
+    fn write_exact(&mut self, bytes: &[u8]) -> Result<(), DidntWrite> {
+        if bytes.len() > self.remaining() {
+            return Err(DidntWrite);
+        }
+        let mut writer = &mut *self.buffer;
+        writer.write_exact(bytes)
+    }
+
+    fn remaining(&self) -> usize {
+        (self.buffer.capacity() - self.buffer.len()).saturating_sub(self.slices.len)
+    }
+
+    fn write_zslice(&mut self, slice: &ZSlice) -> Result<(), DidntWrite> {
+        if !self.reference || slice.len() < MIN_REFERENCED_SLICE_LEN {
+            return self.write_exact(slice.as_slice());
+        }
+        if slice.len() > self.remaining() {
+            return Err(DidntWrite);
+        }
+        self.slices.slices.push((self.buffer.len(), slice.clone()));
+        self.slices.len += slice.len();
+        Ok(())
+    }
+
+    fn with_slot<F>(&mut self, len: usize, f: F) -> Result<NonZeroUsize, DidntWrite>
+    where
+        F: FnOnce(&mut [u8]) -> usize,
+    {
+        if len > self.remaining() {
+            return Err(DidntWrite);
+        }
+        let mut writer = &mut *self.buffer;
+        writer.with_slot(len, f)
+    }
+}
+
+impl BacktrackableWriter for WBatchWriter<'_> {
+    type Mark = (usize, usize, usize);
+
+    fn mark(&mut self) -> Self::Mark {
+        (self.buffer.len(), self.slices.slices.len(), self.slices.len)
+    }
+
+    fn rewind(&mut self, mark: Self::Mark) -> bool {
+        let mut writer = &mut *self.buffer;
+        writer.rewind(mark.0);
+        self.slices.slices.truncate(mark.1);
+        self.slices.len = mark.2;
+        true
+    }
+}
+
+/// The error returned when the checksum of a received batch does not match its content.
+#[derive(Debug)]
+pub struct CorruptedBatch;
+
+impl fmt::Display for CorruptedBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Batch checksum mismatch")
+    }
+}
+
+impl std::error::Error for CorruptedBatch {}
+
+/// A buffer a batch is read or decompressed into, then shared as a [`ZSlice`].
+pub trait RBatchBuffer {
+    fn as_mut_slice(&mut self) -> &mut [u8];
+
+    fn into_zslice(self, start: usize, end: usize) -> Option<ZSlice>;
+}
+
+impl RBatchBuffer for Box<[u8]> {
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+
+    fn into_zslice(self, start: usize, end: usize) -> Option<ZSlice> {
+        ZSlice::make(Arc::new(self), start, end).ok()
+    }
+}
+
+// The pooled buffers carry their reference counter, sharing them does not allocate
+impl RBatchBuffer for PooledBuffer {
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        PooledBuffer::as_mut_slice(self)
+    }
+
+    fn into_zslice(self, start: usize, end: usize) -> Option<ZSlice> {
+        PooledBuffer::into_zslice(self, start, end)
     }
 }
 
@@ -423,11 +755,26 @@ impl RBatch {
     pub fn initialize<C, T>(&mut self, #[allow(unused_variables)] buff: C) -> ZResult<()>
     where
         C: Fn() -> T + Copy,
-        T: ZSliceBuffer + 'static,
+        T: RBatchBuffer,
     {
         #[allow(unused_variables)]
         let (l, h, p) = Self::split(self.buffer.as_slice(), &self.config);
 
+        // Verify and strip the checksum trailer, if any
+        let c_len = self.config.trailer_len();
+        if p.len() < c_len {
+            bail!("Invalid batch length");
+        }
+        #[allow(unused_variables)]
+        let (p, c) = p.split_at(p.len() - c_len);
+        if self.config.is_checksum {
+            let crc = u32::from_le_bytes(c.try_into().map_err(|_| zerror!("Invalid checksum"))?);
+            let end = self.buffer.len() - c_len;
+            if CHECKSUM.checksum(&self.buffer.as_slice()[l.len()..end]) != crc {
+                return Err(CorruptedBatch.into());
+            }
+        }
+
         #[cfg(feature = "transport_compression")]
         {
             if self.config.has_header() {
@@ -437,7 +784,7 @@ impl RBatch {
                 let header = BatchHeader::new(b);
 
                 if header.is_compression() {
-                    let zslice = self.decompress(p, buff)?;
+                    let zslice = self.decompress(p, header.compression_algorithm(), buff)?;
                     self.buffer = zslice;
                     return Ok(());
                 }
@@ -446,22 +793,37 @@ impl RBatch {
 
         self.buffer = self
             .buffer
-            .subslice(l.len() + h.len(), self.buffer.len())
+            .subslice(l.len() + h.len(), self.buffer.len() - c_len)
             .ok_or_else(|| zerror!("Invalid batch length"))?;
 
         Ok(())
     }
 
     #[cfg(feature = "transport_compression")]
-    fn decompress<T>(&self, payload: &[u8], mut buff: impl FnMut() -> T) -> ZResult<ZSlice>
+    fn decompress<T>(
+        &self,
+        payload: &[u8],
+        algorithm: CompressionAlgorithm,
+        mut buff: impl FnMut() -> T,
+    ) -> ZResult<ZSlice>
     where
-        T: ZSliceBuffer + 'static,
+        T: RBatchBuffer,
     {
         let mut into = (buff)();
-        let n = lz4_flex::block::decompress_into(payload, into.as_mut_slice())
-            .map_err(|_| zerror!("Decompression error"))?;
-        let zslice = ZSlice::make(Arc::new(into), 0, n)
-            .map_err(|_| zerror!("Invalid decompression buffer length"))?;
+        let n = match algorithm {
+            CompressionAlgorithm::Lz4 => {
+                lz4_flex::block::decompress_into(payload, into.as_mut_slice()).ok()
+            }
+            CompressionAlgorithm::Zstd => ZSTD_DECOMPRESSOR.with(|d| {
+                d.borrow_mut()
+                    .as_mut()
+                    .and_then(|d| d.decompress_to_buffer(payload, into.as_mut_slice()).ok())
+            }),
+        }
+        .ok_or_else(|| zerror!("Decompression error"))?;
+        let zslice = into
+            .into_zslice(0, n)
+            .ok_or_else(|| zerror!("Invalid decompression buffer length"))?;
         Ok(zslice)
     }
 }
@@ -500,13 +862,12 @@ mod tests {
     use super::*;
     use rand::Rng;
     use zenoh_buffers::ZBuf;
-    use zenoh_core::zcondfeat;
     use zenoh_protocol::{
         core::{CongestionControl, Encoding, Priority, Reliability, WireExpr},
         network::{ext, Push},
         transport::{
             frame::{self, FrameHeader},
-            Fragment, KeepAlive, TransportMessage,
+            Fragment, Frame, KeepAlive, TransportBody, TransportMessage,
         },
         zenoh::{PushBody, Put},
     };
@@ -527,27 +888,33 @@ mod tests {
                     is_streamed: rng.gen_bool(0.5),
                     #[cfg(feature = "transport_compression")]
                     is_compression: rng.gen_bool(0.5),
+                    #[cfg(feature = "transport_compression")]
+                    compression_algorithm: if rng.gen_bool(0.5) {
+                        CompressionAlgorithm::Lz4
+                    } else {
+                        CompressionAlgorithm::Zstd
+                    },
+                    #[cfg(feature = "transport_compression")]
+                    compression_threshold: rng.gen_range(0..512),
+                    is_checksum: rng.gen_bool(0.5),
                 };
                 let mut wbatch = WBatch::new(config);
                 wbatch.encode(&msg_in).unwrap();
                 println!("Encoded WBatch: {:?}", wbatch);
 
-                let mut buffer = zcondfeat!(
-                    "transport_compression",
-                    config.is_compression.then_some(BBuf::with_capacity(
-                        lz4_flex::block::get_maximum_output_size(wbatch.as_slice().len()),
-                    )),
-                    None
-                );
+                let mut buffer = config.max_support_buffer_size().map(BBuf::with_capacity);
 
                 let res = wbatch.finalize(buffer.as_mut()).unwrap();
                 let bytes = match res {
-                    Finalize::Batch => wbatch.as_slice(),
-                    Finalize::Buffer => buffer.as_mut().unwrap().as_slice(),
+                    Finalize::Batch => wbatch.as_slices().concat(),
+                    Finalize::Buffer => buffer.as_mut().unwrap().as_slice().to_vec(),
+                    Finalize::Trailer(trailer) => {
+                        [wbatch.as_slices().concat(), trailer.to_vec()].concat()
+                    }
                 };
                 println!("Finalized WBatch: {:02x?}", bytes);
 
-                let mut rbatch = RBatch::new(config, bytes.to_vec().into_boxed_slice());
+                let mut rbatch = RBatch::new(config, bytes.into_boxed_slice());
                 println!("Decoded RBatch: {:?}", rbatch);
                 rbatch
                     .initialize(|| {
@@ -561,6 +928,132 @@ mod tests {
         }
     }
 
+    #[test]
+    fn checksum_batch() {
+        for is_streamed in [false, true] {
+            let config = BatchConfig {
+                mtu: BatchSize::MAX,
+                is_streamed,
+                is_checksum: true,
+                ..Default::default()
+            };
+            let buff = || zenoh_buffers::vec::uninit(config.mtu as usize).into_boxed_slice();
+
+            let msg_in: TransportMessage = KeepAlive::default().into();
+            let mut wbatch = WBatch::new(config);
+            wbatch.encode(&msg_in).unwrap();
+
+            // The checksum of an uncompressed batch does not need a support buffer
+            assert!(config.max_support_buffer_size().is_none());
+            let bytes = match wbatch.finalize(None).unwrap() {
+                Finalize::Trailer(trailer) => [wbatch.as_slice(), &trailer].concat(),
+                res => panic!("Unexpected finalization: {res:?}"),
+            };
+
+            // An intact batch is decoded
+            let mut rbatch = RBatch::new(config, bytes.clone().into_boxed_slice());
+            rbatch.initialize(buff).unwrap();
+            let msg_out: TransportMessage = rbatch.decode().unwrap();
+            assert_eq!(msg_in, msg_out);
+
+            // A batch with a flipped bit is reported as corrupted
+            let mut corrupted = bytes;
+            let i = corrupted.len() - C_LEN - 1;
+            corrupted[i] ^= 0x01;
+            let mut rbatch = RBatch::new(config, corrupted.into_boxed_slice());
+            let e = rbatch.initialize(buff).unwrap_err();
+            assert!(e.is::<CorruptedBatch>());
+        }
+    }
+
+    #[test]
+    fn zero_copy_batch() {
+        for (is_streamed, is_checksum) in
+            [(false, false), (true, false), (false, true), (true, true)]
+        {
+            let config = BatchConfig {
+                mtu: BatchSize::MAX,
+                is_streamed,
+                is_checksum,
+                ..Default::default()
+            };
+            let buff = || zenoh_buffers::vec::uninit(config.mtu as usize).into_boxed_slice();
+
+            let payload: ZSlice = vec![42u8; 2 * MIN_REFERENCED_SLICE_LEN].into();
+            let nmsg: NetworkMessage = Push {
+                wire_expr: WireExpr::empty(),
+                ext_qos: ext::QoSType::new(Priority::default(), CongestionControl::Block, false),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                payload: PushBody::Put(Put {
+                    timestamp: None,
+                    encoding: Encoding::default(),
+                    ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_attachment: None,
+                    ext_trace: None,
+                    ext_unknown: vec![],
+                    payload: ZBuf::from(payload.clone()),
+                }),
+            }
+            .into();
+            let frame = FrameHeader {
+                reliability: Reliability::Reliable,
+                sn: 0,
+                ext_qos: frame::ext::QoSType::default(),
+            };
+
+            let mut wbatch = WBatch::new(config);
+            wbatch.encode((&nmsg, &frame)).unwrap();
+            wbatch.encode(&nmsg).unwrap();
+
+            // The payloads are referenced by the batch, not copied in its buffer
+            let slices = wbatch.as_slices();
+            assert_eq!(slices.len(), 5);
+            assert_eq!(slices[1].as_ptr(), payload.as_slice().as_ptr());
+            assert_eq!(slices[3].as_ptr(), payload.as_slice().as_ptr());
+            assert!(wbatch.as_slice().len() < MIN_REFERENCED_SLICE_LEN);
+            let (length, header, _payload) = WBatch::split(wbatch.as_slice(), &config);
+            assert_eq!(
+                wbatch.len() as usize,
+                slices.iter().map(|s| s.len()).sum::<usize>() - length.len() - header.len()
+            );
+
+            // A rewound write drops the slices it referenced
+            let mut full = WBatch::new(BatchConfig {
+                mtu: (3 * MIN_REFERENCED_SLICE_LEN) as BatchSize,
+                ..config
+            });
+            full.encode((&nmsg, &frame)).unwrap();
+            let len = full.len();
+            assert!(full.encode(&nmsg).is_err());
+            assert_eq!(full.len(), len);
+            assert_eq!(full.as_slices().len(), 3);
+
+            let bytes = match wbatch.finalize(None).unwrap() {
+                Finalize::Batch => wbatch.as_slices().concat(),
+                Finalize::Trailer(trailer) => {
+                    [wbatch.as_slices().concat(), trailer.to_vec()].concat()
+                }
+                res => panic!("Unexpected finalization: {res:?}"),
+            };
+
+            let mut rbatch = RBatch::new(config, bytes.into_boxed_slice());
+            rbatch.initialize(buff).unwrap();
+            let msg_out: TransportMessage = rbatch.decode().unwrap();
+            match msg_out.body {
+                TransportBody::Frame(Frame { payload, .. }) => {
+                    assert_eq!(payload, vec![nmsg.clone(), nmsg.clone()])
+                }
+                body => panic!("Unexpected message: {body:?}"),
+            }
+        }
+    }
+
     #[test]
     fn serialization_batch() {
         let config = BatchConfig {
@@ -568,10 +1061,15 @@ mod tests {
             is_streamed: false,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: 0,
+            is_checksum: false,
         };
         let mut batch = WBatch::new(config);
 
-        let tmsg: TransportMessage = KeepAlive.into();
+        let tmsg: TransportMessage = KeepAlive::default().into();
         let nmsg: NetworkMessage = Push {
             wire_expr: WireExpr::empty(),
             ext_qos: ext::QoSType::new(Priority::default(), CongestionControl::Block, false),
@@ -581,9 +1079,13 @@ mod tests {
                 timestamp: None,
                 encoding: Encoding::default(),
                 ext_sinfo: None,
+                ext_coherence: None,
+                ext_ttl: None,
+                ext_latency_budget: None,
                 #[cfg(feature = "shared-memory")]
                 ext_shm: None,
                 ext_attachment: None,
+                ext_trace: None,
                 ext_unknown: vec![],
                 payload: ZBuf::from(vec![0u8; 8]),
             }),
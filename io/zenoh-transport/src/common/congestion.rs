@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use zenoh_core::zlock;
+use zenoh_protocol::core::Priority;
+
+/// A callback notified when the transmission queues of a priority start (`true`) or
+/// stop (`false`) dropping the messages using [`CongestionControl::Adaptive`](zenoh_protocol::core::CongestionControl::Adaptive).
+pub type CongestionCallback = Arc<dyn Fn(Priority, bool) + Send + Sync>;
+
+/// Tracks the transmission queues of a [`TransportManager`](crate::TransportManager)
+/// dropping the messages using [`CongestionControl::Adaptive`](zenoh_protocol::core::CongestionControl::Adaptive)
+/// because of a sustained congestion.
+#[derive(Default)]
+pub struct CongestionState {
+    dropping: [AtomicUsize; Priority::NUM],
+    next_id: AtomicUsize,
+    listeners: Mutex<HashMap<usize, CongestionCallback>>,
+}
+
+impl CongestionState {
+    /// Returns `true` if at least one queue of the given priority is dropping messages.
+    pub fn is_dropping(&self, priority: Priority) -> bool {
+        self.dropping[priority as usize].load(Ordering::Acquire) > 0
+    }
+
+    /// Registers a callback, returning its id.
+    pub fn add_listener(&self, callback: CongestionCallback) -> usize {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        zlock!(self.listeners).insert(id, callback);
+        id
+    }
+
+    /// Unregisters the callback with the given id.
+    pub fn remove_listener(&self, id: usize) {
+        zlock!(self.listeners).remove(&id);
+    }
+
+    pub(crate) fn start_dropping(&self, priority: Priority) {
+        if self.dropping[priority as usize].fetch_add(1, Ordering::AcqRel) == 0 {
+            tracing::debug!(
+                "Adaptive congestion control dropping messages for {:?}",
+                priority
+            );
+            self.notify(priority, true);
+        }
+    }
+
+    pub(crate) fn stop_dropping(&self, priority: Priority) {
+        if self.dropping[priority as usize].fetch_sub(1, Ordering::AcqRel) == 1 {
+            tracing::debug!(
+                "Adaptive congestion control blocking messages for {:?}",
+                priority
+            );
+            self.notify(priority, false);
+        }
+    }
+
+    fn notify(&self, priority: Priority, dropping: bool) {
+        let listeners: Vec<CongestionCallback> = zlock!(self.listeners).values().cloned().collect();
+        for listener in listeners {
+            listener(priority, dropping);
+        }
+    }
+}
+
+impl fmt::Debug for CongestionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CongestionState")
+            .field("dropping", &self.dropping)
+            .finish()
+    }
+}
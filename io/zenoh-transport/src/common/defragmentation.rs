@@ -12,6 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::seq_num::SeqNum;
+use std::sync::Arc;
 use zenoh_buffers::{buffer::Buffer, reader::HasReader, ZBuf, ZSlice};
 use zenoh_codec::{RCodec, Zenoh080Reliability};
 use zenoh_protocol::{
@@ -19,7 +20,17 @@ use zenoh_protocol::{
     network::NetworkMessage,
     transport::TransportSn,
 };
-use zenoh_result::{bail, ZResult};
+use zenoh_result::{bail, zerror, ZResult};
+use zenoh_util::{MemoryBudget, MemoryReservation, MemorySubsystem};
+
+/// The progress of the reassembly of a fragmented message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct DefragProgress {
+    /// The number of bytes received so far.
+    pub(crate) received: usize,
+    /// The total size in bytes of the message, if announced by its first fragment.
+    pub(crate) total: Option<usize>,
+}
 
 #[derive(Debug)]
 pub(crate) struct DefragBuffer {
@@ -28,6 +39,13 @@ pub(crate) struct DefragBuffer {
     buffer: ZBuf,
     capacity: usize,
     len: usize,
+    // The total size of the message being reassembled, if announced by its first fragment
+    total: Option<usize>,
+    // Whether the fragments of the current message are discarded until its last one
+    discarding: bool,
+    reservation: MemoryReservation,
+    // The memory reserved in the reassembly budget shared by the buffers of the same peer
+    peer_reservation: MemoryReservation,
 }
 
 impl DefragBuffer {
@@ -35,26 +53,48 @@ impl DefragBuffer {
         reliability: Reliability,
         resolution: Bits,
         capacity: usize,
+        memory_budget: &Arc<MemoryBudget>,
+        peer_budget: &Arc<MemoryBudget>,
     ) -> ZResult<DefragBuffer> {
+        let reservation = memory_budget
+            .try_reserve(MemorySubsystem::Defragmentation, 0)
+            .ok_or_else(|| zerror!("Memory budget exceeded"))?;
+        let peer_reservation = peer_budget
+            .try_reserve(MemorySubsystem::Defragmentation, 0)
+            .ok_or_else(|| zerror!("Peer memory budget exceeded"))?;
         let db = DefragBuffer {
             reliability,
             sn: SeqNum::make(0, resolution)?,
             buffer: ZBuf::empty(),
             capacity,
             len: 0,
+            total: None,
+            discarding: false,
+            reservation,
+            peer_reservation,
         };
         Ok(db)
     }
 
     #[inline(always)]
     pub(crate) fn is_empty(&self) -> bool {
-        self.buffer.is_empty()
+        self.buffer.is_empty() && !self.discarding
+    }
+
+    /// Whether the fragments of the current message are discarded because it exceeded the limits.
+    #[inline(always)]
+    pub(crate) fn is_discarding(&self) -> bool {
+        self.discarding
     }
 
     #[inline(always)]
     pub(crate) fn clear(&mut self) {
         self.buffer.clear();
         self.len = 0;
+        self.total = None;
+        self.discarding = false;
+        self.reservation.clear();
+        self.peer_reservation.clear();
     }
 
     #[inline(always)]
@@ -62,35 +102,183 @@ impl DefragBuffer {
         self.sn.set(sn)
     }
 
+    /// The progress of the reassembly of the current message, if any.
+    pub(crate) fn progress(&self) -> Option<DefragProgress> {
+        (!self.buffer.is_empty() || self.total.is_some()).then_some(DefragProgress {
+            received: self.len,
+            total: self.total,
+        })
+    }
+
+    /// Starts the reassembly of a message of `total` bytes from its first fragment `sn`,
+    /// discarding the incomplete message being reassembled if any.
+    ///
+    /// The memory of the whole message is reserved upfront. If the message exceeds the limits,
+    /// its fragments are discarded until its last one and an error is returned.
+    pub(crate) fn begin(&mut self, sn: TransportSn, total: usize) -> ZResult<()> {
+        self.clear();
+        self.sync(sn)?;
+        self.total = Some(total);
+
+        if total > self.capacity {
+            self.abort();
+            bail!(
+                "Fragmented message of {} bytes exceeds the maximum message size: {}.",
+                total,
+                self.capacity
+            )
+        }
+        if !self.peer_reservation.try_grow(total) {
+            self.abort();
+            bail!(
+                "Fragmented message of {} bytes refused: peer reassembly limit exceeded.",
+                total
+            )
+        }
+        if !self.reservation.try_grow(total) {
+            self.abort();
+            bail!(
+                "Fragmented message of {} bytes refused: memory budget exceeded.",
+                total
+            )
+        }
+        Ok(())
+    }
+
+    // Releases the memory of the current message and discards its next fragments.
+    fn abort(&mut self) {
+        let total = self.total;
+        self.clear();
+        self.total = total;
+        self.discarding = true;
+    }
+
     pub(crate) fn push(&mut self, sn: TransportSn, zslice: ZSlice) -> ZResult<()> {
         if sn != self.sn.get() {
             self.clear();
             bail!("Expected SN {}, received {}", self.sn.get(), sn)
         }
+        self.sn.increment();
+        if self.discarding {
+            return Ok(());
+        }
 
         let new_len = self.len + zslice.len();
-        if new_len > self.capacity {
-            self.clear();
-            bail!(
-                "Defragmentation buffer full: {} bytes. Capacity: {}.",
-                new_len,
-                self.capacity
-            )
+        match self.total {
+            // The memory of the whole message has been reserved upfront
+            Some(total) => {
+                if new_len > total {
+                    self.abort();
+                    bail!(
+                        "Fragmented message exceeds its announced size: {} bytes. Announced: {}.",
+                        new_len,
+                        total
+                    )
+                }
+            }
+            None => {
+                if new_len > self.capacity {
+                    self.abort();
+                    bail!(
+                        "Defragmentation buffer full: {} bytes. Capacity: {}.",
+                        new_len,
+                        self.capacity
+                    )
+                }
+                if !self.peer_reservation.try_grow(zslice.len()) {
+                    self.abort();
+                    bail!(
+                        "Defragmentation buffer refused {} bytes: peer reassembly limit exceeded.",
+                        new_len
+                    )
+                }
+                if !self.reservation.try_grow(zslice.len()) {
+                    self.abort();
+                    bail!(
+                        "Defragmentation buffer refused {} bytes: memory budget exceeded.",
+                        new_len
+                    )
+                }
+            }
         }
 
-        self.sn.increment();
         self.buffer.push_zslice(zslice);
         self.len = new_len;
 
         Ok(())
     }
 
+    /// Decodes the reassembled message once its last fragment has been pushed.
+    ///
+    /// Returns `Ok(None)` if the fragments of the message were discarded.
     #[inline(always)]
-    pub(crate) fn defragment(&mut self) -> Option<NetworkMessage> {
+    pub(crate) fn defragment(&mut self) -> ZResult<Option<NetworkMessage>> {
+        if self.discarding {
+            self.clear();
+            return Ok(None);
+        }
         let mut reader = self.buffer.reader();
         let rcodec = Zenoh080Reliability::new(self.reliability);
-        let res: Option<NetworkMessage> = rcodec.read(&mut reader).ok();
+        let res: Result<NetworkMessage, _> = rcodec.read(&mut reader);
         self.clear();
-        res
+        res.map(Some)
+            .map_err(|_| zerror!("Defragmentation error").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(capacity: usize, peer_budget: &Arc<MemoryBudget>) -> DefragBuffer {
+        let memory_budget = Arc::new(MemoryBudget::unlimited());
+        DefragBuffer::make(
+            Reliability::Reliable,
+            Bits::U32,
+            capacity,
+            &memory_budget,
+            peer_budget,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn defrag_limits() {
+        let peer_budget =
+            Arc::new(MemoryBudget::unlimited().limit(MemorySubsystem::Defragmentation, 1_024));
+        let mut b1 = buffer(1_024, &peer_budget);
+        let mut b2 = buffer(1_024, &peer_budget);
+
+        // The memory of the whole message is reserved upfront
+        b1.begin(0, 800).unwrap();
+        b1.push(0, vec![0u8; 400].into()).unwrap();
+        assert_eq!(
+            b1.progress(),
+            Some(DefragProgress {
+                received: 400,
+                total: Some(800)
+            })
+        );
+        assert_eq!(peer_budget.used(MemorySubsystem::Defragmentation), 800);
+
+        // The peer limit is shared by the buffers of the peer
+        assert!(b2.begin(0, 400).is_err());
+        assert!(b2.is_discarding());
+        b2.push(0, vec![0u8; 400].into()).unwrap();
+        assert!(b2.defragment().unwrap().is_none());
+        assert!(b2.is_empty());
+
+        // A message larger than the capacity is discarded until its last fragment
+        b1.clear();
+        assert!(b1.begin(0, 2_048).is_err());
+        b1.push(0, vec![0u8; 1_024].into()).unwrap();
+        b1.push(1, vec![0u8; 1_024].into()).unwrap();
+        assert!(b1.defragment().unwrap().is_none());
+        assert_eq!(peer_budget.used(MemorySubsystem::Defragmentation), 0);
+
+        // A message can not exceed its announced size
+        b1.begin(2, 100).unwrap();
+        assert!(b1.push(2, vec![0u8; 200].into()).is_err());
+        assert!(b1.is_discarding());
     }
 }
@@ -15,14 +15,18 @@ use crate::common::batch::BatchConfig;
 //
 use super::{
     batch::{Encode, WBatch},
+    congestion::CongestionState,
     priority::{TransportChannelTx, TransportPriorityTx},
 };
 use flume::{bounded, Receiver, Sender};
 use ringbuffer_spsc::{RingBuffer, RingBufferReader, RingBufferWriter};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
 use std::{
-    sync::atomic::{AtomicBool, AtomicU16, Ordering},
+    sync::atomic::{AtomicBool, AtomicU16, AtomicUsize, Ordering},
     time::Instant,
 };
 use zenoh_buffers::{
@@ -33,12 +37,12 @@ use zenoh_buffers::{
 use zenoh_codec::{transport::batch::BatchError, WCodec, Zenoh080};
 use zenoh_config::QueueSizeConf;
 use zenoh_core::zlock;
-use zenoh_protocol::core::Reliability;
+use zenoh_protocol::core::{CongestionControl, Reliability};
 use zenoh_protocol::network::NetworkMessage;
 use zenoh_protocol::{
     core::Priority,
     transport::{
-        fragment::FragmentHeader,
+        fragment::{self, FragmentHeader},
         frame::{self, FrameHeader},
         BatchSize, TransportMessage,
     },
@@ -50,16 +54,71 @@ type NanoSeconds = u32;
 
 const RBLEN: usize = QueueSizeConf::MAX;
 const TSLOT: NanoSeconds = 100;
+// The time after which the consumer retries to serialize the messages kept aside
+// when the stage in was busy
+const KEPT_ASIDE_RETRY: NanoSeconds = 1_000_000;
+
+thread_local! {
+    // The pipelines holding back their current batch until the end of the batch scope
+    // of this thread, with the index of the held priority
+    static BATCH_SCOPE: RefCell<Option<Vec<(TransmissionPipelineProducer, usize)>>> =
+        RefCell::new(None);
+}
+
+/// Runs `f`, holding back the current batches the non-express messages it pushes on this thread
+/// are serialized on, and flushing them once it returns.
+///
+/// The messages pushed by `f` are thus carried by as few batches as possible: only the batches
+/// that are full are sent before the end of the scope. Nested scopes are part of the outermost one.
+pub fn batch_scope<R>(f: impl FnOnce() -> R) -> R {
+    let is_nested = BATCH_SCOPE.with(|scope| {
+        let mut scope = scope.borrow_mut();
+        let is_nested = scope.is_some();
+        scope.get_or_insert_with(Vec::new);
+        is_nested
+    });
+    if is_nested {
+        return f();
+    }
+
+    // Flush the held batches even if `f` panics
+    struct Release;
+    impl Drop for Release {
+        fn drop(&mut self) {
+            let held = BATCH_SCOPE
+                .with(|scope| scope.borrow_mut().take())
+                .unwrap_or_default();
+            for (pipeline, idx) in held {
+                pipeline.release(idx);
+            }
+        }
+    }
+    let _release = Release;
+    f()
+}
+
+// The number of batches taken out of a refill ring buffer, and given back to it.
+// The batches of a priority are given back in the order they were taken out.
+#[derive(Default)]
+struct BatchCounters {
+    pulled: AtomicUsize,
+    refilled: AtomicUsize,
+}
 
 // Inner structure to reuse serialization batches
 struct StageInRefill {
     n_ref_r: Receiver<()>,
     s_ref_r: RingBufferReader<WBatch, RBLEN>,
+    counters: Arc<BatchCounters>,
 }
 
 impl StageInRefill {
     fn pull(&mut self) -> Option<WBatch> {
-        self.s_ref_r.pull()
+        let batch = self.s_ref_r.pull();
+        if batch.is_some() {
+            self.counters.pulled.fetch_add(1, Ordering::AcqRel);
+        }
+        batch
     }
 
     fn wait(&self) -> bool {
@@ -124,6 +183,15 @@ struct StageIn {
     s_out: StageInOut,
     mutex: StageInMutex,
     fragbuf: ZBuf,
+    // The priority of the messages serialized on this stage
+    priority: Priority,
+    // The messages using CongestionControl::DropFirst waiting for an available batch
+    kept_aside: VecDeque<NetworkMessage>,
+    has_kept_aside: Arc<AtomicBool>,
+    // Whether the messages using CongestionControl::Adaptive are being dropped
+    adaptive_dropping: bool,
+    // The number of batch scopes holding back the current batch
+    held: Arc<AtomicUsize>,
 }
 
 impl StageIn {
@@ -132,6 +200,51 @@ impl StageIn {
         msg: &mut NetworkMessage,
         priority: Priority,
         deadline_before_drop: Option<Instant>,
+    ) -> bool {
+        self.push_network_message_inner(msg, priority, deadline_before_drop, true)
+    }
+
+    // Keep a message using CongestionControl::DropFirst aside until a batch is available,
+    // dropping the oldest message kept aside if there are too many of them.
+    fn keep_aside(&mut self, msg: NetworkMessage, max: usize) {
+        if self.kept_aside.len() >= max {
+            if self.kept_aside.pop_front().is_some() {
+                tracing::trace!(
+                    "Dropping the oldest message kept aside for {:?}",
+                    self.priority
+                );
+            }
+            if max == 0 {
+                return;
+            }
+        }
+        self.kept_aside.push_back(msg);
+        self.has_kept_aside.store(true, Ordering::Release);
+    }
+
+    // Serialize the messages kept aside in the available batches, without waiting.
+    // Messages that need to be fragmented are only serialized if `fragment` is true.
+    fn flush_kept_aside(&mut self, fragment: bool) {
+        while let Some(mut msg) = self.kept_aside.pop_front() {
+            if !self.push_network_message_inner(
+                &mut msg,
+                self.priority,
+                Some(Instant::now()),
+                fragment,
+            ) {
+                self.kept_aside.push_front(msg);
+                return;
+            }
+        }
+        self.has_kept_aside.store(false, Ordering::Release);
+    }
+
+    fn push_network_message_inner(
+        &mut self,
+        msg: &mut NetworkMessage,
+        priority: Priority,
+        deadline_before_drop: Option<Instant>,
+        fragment: bool,
     ) -> bool {
         // Lock the current serialization batch.
         let mut c_guard = self.mutex.current();
@@ -179,10 +292,15 @@ impl StageIn {
 
         macro_rules! zretok {
             ($batch:expr) => {{
-                let bytes = $batch.len();
-                *c_guard = Some($batch);
-                drop(c_guard);
-                self.s_out.notify(bytes);
+                if msg.is_express() {
+                    // Move out the batch right away instead of waiting for more messages
+                    self.s_out.move_batch($batch);
+                } else {
+                    let bytes = $batch.len();
+                    *c_guard = Some($batch);
+                    drop(c_guard);
+                    self.s_out.notify(bytes);
+                }
                 return true;
             }};
         }
@@ -230,6 +348,11 @@ impl StageIn {
         // too large for the current batch size: we need to fragment.
         // Reinsert the current batch for fragmentation.
         *c_guard = Some(batch);
+        if !fragment {
+            // Restore the sequence number and give the message back
+            tch.sn.set(sn).unwrap();
+            return false;
+        }
 
         // Take the expandable buffer and serialize the totality of the message
         self.fragbuf.clear();
@@ -238,12 +361,13 @@ impl StageIn {
         let codec = Zenoh080::new();
         codec.write(&mut writer, &*msg).unwrap();
 
-        // Fragment the whole message
+        // Fragment the whole message, announcing its total size in the first fragment
         let mut fragment = FragmentHeader {
             reliability: frame.reliability,
             more: true,
             sn,
             ext_qos: frame.ext_qos,
+            ext_total: Some(fragment::ext::TotalSize::new(self.fragbuf.len() as u64)),
         };
         let mut reader = self.fragbuf.reader();
         while reader.can_read() {
@@ -256,6 +380,9 @@ impl StageIn {
                 Ok(_) => {
                     // Update the SN
                     fragment.sn = tch.sn.get();
+                    fragment.ext_total = None;
+                    // Let the stage out pace the next fragments, if any
+                    batch.more_fragments = reader.can_read();
                     // Move the serialization batch into the OUT pipeline
                     self.s_out.move_batch(batch);
                 }
@@ -279,6 +406,18 @@ impl StageIn {
         true
     }
 
+    // Move out the current batch, if it is not empty
+    fn flush_current(&mut self) {
+        let mut c_guard = self.mutex.current();
+        if let Some(batch) = c_guard.take() {
+            if batch.is_empty() {
+                *c_guard = Some(batch);
+            } else {
+                self.s_out.move_batch(batch);
+            }
+        }
+    }
+
     #[inline]
     fn push_transport_message(&mut self, msg: TransportMessage) -> bool {
         // Lock the current serialization batch.
@@ -344,6 +483,40 @@ enum Pull {
     Backoff(NanoSeconds),
 }
 
+// Inner structure to adapt the batching to the observed throughput within a latency budget
+#[derive(Clone)]
+struct Batching {
+    max_latency: Duration,
+    capacity: BatchSize,
+    // When the bytes of the current batch have been first observed
+    since: Option<Instant>,
+}
+
+impl Batching {
+    fn new(max_latency: Duration, capacity: BatchSize) -> Self {
+        Self {
+            max_latency,
+            capacity,
+            since: None,
+        }
+    }
+
+    // Returns the remaining latency budget if the current batch is expected to fill up within it,
+    // in which case it is worth waiting for more messages, None if the batch should be sent now.
+    fn wait(&mut self, bytes: BatchSize) -> Option<Duration> {
+        if bytes == 0 {
+            return None;
+        }
+        let now = Instant::now();
+        let since = *self.since.get_or_insert(now);
+        let elapsed = now.duration_since(since);
+        let remaining = self.max_latency.checked_sub(elapsed)?;
+        // The time to fill up the batch at the throughput observed since its first bytes
+        let fill = elapsed.mul_f64(self.capacity.saturating_sub(bytes) as f64 / bytes as f64);
+        (fill < remaining).then_some(remaining)
+    }
+}
+
 // Inner structure to keep track and signal backoff operations
 #[derive(Clone)]
 struct Backoff {
@@ -351,15 +524,17 @@ struct Backoff {
     last_bytes: BatchSize,
     bytes: Arc<AtomicU16>,
     backoff: Arc<AtomicBool>,
+    batching: Option<Batching>,
 }
 
 impl Backoff {
-    fn new(bytes: Arc<AtomicU16>, backoff: Arc<AtomicBool>) -> Self {
+    fn new(bytes: Arc<AtomicU16>, backoff: Arc<AtomicBool>, batching: Option<Batching>) -> Self {
         Self {
             retry_time: 0,
             last_bytes: 0,
             bytes,
             backoff,
+            batching,
         }
     }
 
@@ -386,6 +561,9 @@ impl Backoff {
     fn stop(&mut self) {
         self.retry_time = 0;
         self.backoff.store(false, Ordering::Relaxed);
+        if let Some(batching) = self.batching.as_mut() {
+            batching.since = None;
+        }
     }
 }
 
@@ -394,6 +572,7 @@ struct StageOutIn {
     s_out_r: RingBufferReader<WBatch, RBLEN>,
     current: Arc<Mutex<Option<WBatch>>>,
     backoff: Backoff,
+    held: Arc<AtomicUsize>,
 }
 
 impl StageOutIn {
@@ -411,10 +590,28 @@ impl StageOutIn {
         let new_bytes = self.backoff.bytes.load(Ordering::Relaxed);
         let old_bytes = self.backoff.last_bytes;
         self.backoff.last_bytes = new_bytes;
+        // The current batch is flushed at the end of the batch scopes holding it back
+        let is_held = self.held.load(Ordering::Acquire) > 0;
+
+        // Without adaptive batching, the batch is sent as soon as no new bytes have been written on it
+        let wait = match self.backoff.batching.as_mut() {
+            Some(batching) => batching.wait(new_bytes),
+            None => (new_bytes != old_bytes).then_some(Duration::MAX),
+        };
 
         match new_bytes.cmp(&old_bytes) {
-            std::cmp::Ordering::Equal => {
-                // No new bytes have been written on the batch, try to pull
+            std::cmp::Ordering::Less => {
+                // There should be a new batch in Stage OUT
+                if let Some(batch) = self.s_out_r.pull() {
+                    self.backoff.stop();
+                    return Pull::Some(batch);
+                }
+                // Go to backoff
+            }
+            std::cmp::Ordering::Equal | std::cmp::Ordering::Greater
+                if wait.is_none() && !is_held =>
+            {
+                // The batch is not expected to fill up in time, try to pull
                 if let Ok(mut g) = self.current.try_lock() {
                     // First try to pull from stage OUT
                     if let Some(batch) = self.s_out_r.pull() {
@@ -436,46 +633,87 @@ impl StageOutIn {
                 }
                 // Go to backoff
             }
-            std::cmp::Ordering::Less => {
-                // There should be a new batch in Stage OUT
-                if let Some(batch) = self.s_out_r.pull() {
-                    self.backoff.stop();
-                    return Pull::Some(batch);
-                }
-                // Go to backoff
-            }
-            std::cmp::Ordering::Greater => {
+            std::cmp::Ordering::Equal | std::cmp::Ordering::Greater => {
                 // Go to backoff
             }
         }
 
-        // Do backoff
+        // Do backoff, without exceeding the latency budget
         self.backoff.next();
-        Pull::Backoff(self.backoff.retry_time)
+        let budget = wait.map_or(self.backoff.retry_time, |w| {
+            w.as_nanos().min(NanoSeconds::MAX as u128) as NanoSeconds
+        });
+        Pull::Backoff(self.backoff.retry_time.min(budget))
     }
 }
 
 struct StageOutRefill {
     n_ref_w: Sender<()>,
     s_ref_w: RingBufferWriter<WBatch, RBLEN>,
+    counters: Arc<BatchCounters>,
 }
 
 impl StageOutRefill {
     fn refill(&mut self, batch: WBatch) {
         assert!(self.s_ref_w.push(batch).is_none());
+        self.counters.refilled.fetch_add(1, Ordering::AcqRel);
         let _ = self.n_ref_w.try_send(());
     }
 }
 
+// Inner structure to spread the fragments of a large message over time, leaving room on the
+// link for the other priorities
+struct Pacing {
+    // The rate in bytes per second at which the fragments are pulled
+    rate: u64,
+    // The time before which the next batch can not be pulled
+    next: Option<Instant>,
+}
+
+impl Pacing {
+    fn new(rate: u64) -> Self {
+        Self { rate, next: None }
+    }
+
+    // Returns the time left before the next batch can be pulled, if any
+    fn wait(&mut self) -> Option<Duration> {
+        let wait = self.next?.checked_duration_since(Instant::now());
+        if wait.is_none() {
+            self.next = None;
+        }
+        wait
+    }
+
+    fn pulled(&mut self, batch: &WBatch) {
+        if batch.more_fragments {
+            let delay = Duration::from_secs_f64(batch.len() as f64 / self.rate as f64);
+            self.next = Some(Instant::now() + delay);
+        }
+    }
+}
+
 struct StageOut {
     s_in: StageOutIn,
     s_ref: StageOutRefill,
+    has_kept_aside: Arc<AtomicBool>,
+    pacing: Option<Pacing>,
 }
 
 impl StageOut {
     #[inline]
     fn try_pull(&mut self) -> Pull {
-        self.s_in.try_pull()
+        let Some(pacing) = self.pacing.as_mut() else {
+            return self.s_in.try_pull();
+        };
+        // Delay the next fragment, the other priorities are pulled in the meantime
+        if let Some(wait) = pacing.wait() {
+            return Pull::Backoff(wait.as_nanos().min(NanoSeconds::MAX as u128) as NanoSeconds);
+        }
+        let pull = self.s_in.try_pull();
+        if let Pull::Some(batch) = &pull {
+            pacing.pulled(batch);
+        }
+        pull
     }
 
     #[inline]
@@ -502,7 +740,12 @@ pub(crate) struct TransmissionPipelineConf {
     pub(crate) batch: BatchConfig,
     pub(crate) queue_size: [usize; Priority::NUM],
     pub(crate) wait_before_drop: Duration,
+    pub(crate) drop_first_queue_size: usize,
+    pub(crate) adaptive_wait_before_drop: Duration,
     pub(crate) backoff: Duration,
+    pub(crate) pacing: [Option<u64>; Priority::NUM],
+    // The maximum latency budget of the batches, if adaptive batching is enabled
+    pub(crate) batching: Option<Duration>,
 }
 
 // A 2-stage transmission pipeline
@@ -512,9 +755,11 @@ impl TransmissionPipeline {
     pub(crate) fn make(
         config: TransmissionPipelineConf,
         priority: &[TransportPriorityTx],
+        congestion: &Arc<CongestionState>,
     ) -> (TransmissionPipelineProducer, TransmissionPipelineConsumer) {
         let mut stage_in = vec![];
         let mut stage_out = vec![];
+        let mut counters = vec![];
 
         let default_queue_size = [config.queue_size[Priority::default() as usize]];
         let size_iter = if priority.len() == 1 {
@@ -541,6 +786,8 @@ impl TransmissionPipeline {
             // Create the channel for notifying that new batches are in the refill ring buffer
             // This is a SPSC channel
             let (n_ref_w, n_ref_r) = bounded(1);
+            let batch_counters = Arc::new(BatchCounters::default());
+            counters.push(batch_counters.clone());
 
             // Create the refill ring buffer
             // This is a SPSC ring buffer
@@ -548,9 +795,15 @@ impl TransmissionPipeline {
             let current = Arc::new(Mutex::new(None));
             let bytes = Arc::new(AtomicU16::new(0));
             let backoff = Arc::new(AtomicBool::new(false));
+            let has_kept_aside = Arc::new(AtomicBool::new(false));
+            let held = Arc::new(AtomicUsize::new(0));
 
             stage_in.push(Mutex::new(StageIn {
-                s_ref: StageInRefill { n_ref_r, s_ref_r },
+                s_ref: StageInRefill {
+                    n_ref_r,
+                    s_ref_r,
+                    counters: batch_counters.clone(),
+                },
                 s_out: StageInOut {
                     n_out_w: n_out_w.clone(),
                     s_out_w,
@@ -562,6 +815,14 @@ impl TransmissionPipeline {
                     priority: priority[prio].clone(),
                 },
                 fragbuf: ZBuf::empty(),
+                priority: match priority.len() {
+                    1 => Priority::default(),
+                    _ => Priority::try_from(prio as u8).unwrap(),
+                },
+                kept_aside: VecDeque::new(),
+                has_kept_aside: has_kept_aside.clone(),
+                adaptive_dropping: false,
+                held: held.clone(),
             }));
 
             // The stage out for this priority
@@ -569,34 +830,65 @@ impl TransmissionPipeline {
                 s_in: StageOutIn {
                     s_out_r,
                     current,
-                    backoff: Backoff::new(bytes, backoff),
+                    backoff: Backoff::new(
+                        bytes,
+                        backoff,
+                        config
+                            .batching
+                            .map(|max_latency| Batching::new(max_latency, config.batch.mtu)),
+                    ),
+                    held,
+                },
+                s_ref: StageOutRefill {
+                    n_ref_w,
+                    s_ref_w,
+                    counters: batch_counters,
                 },
-                s_ref: StageOutRefill { n_ref_w, s_ref_w },
+                has_kept_aside,
+                pacing: match priority.len() {
+                    1 => config.pacing[Priority::default() as usize],
+                    _ => config.pacing[prio],
+                }
+                .map(Pacing::new),
             });
         }
 
         let active = Arc::new(AtomicBool::new(true));
+        let stage_in: Arc<[Mutex<StageIn>]> = stage_in.into_boxed_slice().into();
         let producer = TransmissionPipelineProducer {
-            stage_in: stage_in.into_boxed_slice().into(),
+            stage_in: stage_in.clone(),
             active: active.clone(),
+            counters: counters.into_boxed_slice().into(),
             wait_before_drop: config.wait_before_drop,
+            drop_first_queue_size: config.drop_first_queue_size,
+            adaptive_wait_before_drop: config.adaptive_wait_before_drop,
+            congestion: congestion.clone(),
         };
         let consumer = TransmissionPipelineConsumer {
             stage_out: stage_out.into_boxed_slice(),
+            stage_in,
             n_out_r,
             active,
+            batch: config.batch,
         };
 
         (producer, consumer)
     }
 }
 
+/// The batches taken out of the refill ring buffers of a pipeline at some point in time.
+pub(crate) struct FlushPoint(Vec<usize>);
+
 #[derive(Clone)]
 pub(crate) struct TransmissionPipelineProducer {
     // Each priority queue has its own Mutex
     stage_in: Arc<[Mutex<StageIn>]>,
     active: Arc<AtomicBool>,
+    counters: Arc<[Arc<BatchCounters>]>,
     wait_before_drop: Duration,
+    drop_first_queue_size: usize,
+    adaptive_wait_before_drop: Duration,
+    congestion: Arc<CongestionState>,
 }
 
 impl TransmissionPipelineProducer {
@@ -609,15 +901,96 @@ impl TransmissionPipelineProducer {
         } else {
             (0, Priority::default())
         };
-        // If message is droppable, compute a deadline after which the sample could be dropped
-        let deadline_before_drop = if msg.is_droppable() {
-            Some(Instant::now() + self.wait_before_drop)
+        let congestion_control = if msg.is_reliable() {
+            msg.congestion_control()
         } else {
-            None
+            CongestionControl::Drop
         };
         // Lock the channel. We are the only one that will be writing on it.
         let mut queue = zlock!(self.stage_in[idx]);
-        queue.push_network_message(&mut msg, priority, deadline_before_drop)
+        self.hold(&queue, idx);
+        if !queue.kept_aside.is_empty() {
+            queue.flush_kept_aside(true);
+        }
+        match congestion_control {
+            CongestionControl::Block => queue.push_network_message(&mut msg, priority, None),
+            // If message is droppable, compute a deadline after which the sample could be dropped
+            CongestionControl::Drop => queue.push_network_message(
+                &mut msg,
+                priority,
+                Some(Instant::now() + self.wait_before_drop),
+            ),
+            CongestionControl::DropFirst => {
+                // Don't overtake the messages already kept aside
+                if queue.kept_aside.is_empty()
+                    && queue.push_network_message(
+                        &mut msg,
+                        priority,
+                        Some(Instant::now() + self.wait_before_drop),
+                    )
+                {
+                    return true;
+                }
+                queue.keep_aside(msg, self.drop_first_queue_size);
+                true
+            }
+            CongestionControl::Adaptive => {
+                let dropping = queue.adaptive_dropping;
+                let wait = if dropping {
+                    self.wait_before_drop
+                } else {
+                    self.adaptive_wait_before_drop
+                };
+                let res =
+                    queue.push_network_message(&mut msg, priority, Some(Instant::now() + wait));
+                // Start dropping when a message was dropped, stop when a message went through
+                if res == dropping {
+                    queue.adaptive_dropping = !dropping;
+                    drop(queue);
+                    self.notify_adaptive(idx, !dropping);
+                }
+                res
+            }
+        }
+    }
+
+    // Hold back the current batch of a priority if this thread is in a batch scope
+    fn hold(&self, queue: &StageIn, idx: usize) {
+        BATCH_SCOPE.with(|scope| {
+            if let Some(held) = scope.borrow_mut().as_mut() {
+                if !held
+                    .iter()
+                    .any(|(p, i)| *i == idx && Arc::ptr_eq(&p.stage_in, &self.stage_in))
+                {
+                    queue.held.fetch_add(1, Ordering::AcqRel);
+                    held.push((self.clone(), idx));
+                }
+            }
+        });
+    }
+
+    // Release the current batch of a priority held back by a batch scope, flushing it
+    // if no other batch scope holds it back
+    fn release(&self, idx: usize) {
+        let mut queue = zlock!(self.stage_in[idx]);
+        if queue.held.fetch_sub(1, Ordering::AcqRel) == 1 {
+            queue.flush_current();
+        }
+    }
+
+    fn notify_adaptive(&self, idx: usize, dropping: bool) {
+        let notify = |priority| match dropping {
+            true => self.congestion.start_dropping(priority),
+            false => self.congestion.stop_dropping(priority),
+        };
+        if self.stage_in.len() > 1 {
+            notify(Priority::try_from(idx as u8).unwrap());
+        } else {
+            // The queue is shared by all the priorities
+            for p in Priority::MAX as u8..=Priority::MIN as u8 {
+                notify(Priority::try_from(p).unwrap());
+            }
+        }
     }
 
     #[inline]
@@ -633,6 +1006,28 @@ impl TransmissionPipelineProducer {
         queue.push_transport_message(msg)
     }
 
+    /// Returns the point to wait for with [`is_flushed`](Self::is_flushed) for the messages
+    /// pushed so far to be written on the link, but not the ones pushed afterwards.
+    pub(crate) fn flush_point(&self) -> FlushPoint {
+        FlushPoint(
+            self.counters
+                .iter()
+                .map(|c| c.pulled.load(Ordering::Acquire))
+                .collect(),
+        )
+    }
+
+    /// Returns `true` when all the messages pushed before the given point have been pulled out
+    /// of the pipeline and written on the link, or when the pipeline has been disabled.
+    pub(crate) fn is_flushed(&self, point: &FlushPoint) -> bool {
+        !self.active.load(Ordering::Relaxed)
+            || self
+                .counters
+                .iter()
+                .zip(point.0.iter())
+                .all(|(c, pulled)| c.refilled.load(Ordering::Acquire) >= *pulled)
+    }
+
     pub(crate) fn disable(&self) {
         self.active.store(false, Ordering::Relaxed);
 
@@ -642,8 +1037,17 @@ impl TransmissionPipelineProducer {
             self.stage_in.iter().map(|x| zlock!(x)).collect();
 
         // Unblock waiting pullers
-        for ig in in_guards.iter_mut() {
+        let mut dropping = vec![];
+        for (idx, ig) in in_guards.iter_mut().enumerate() {
             ig.s_out.notify(BatchSize::MAX);
+            if ig.adaptive_dropping {
+                ig.adaptive_dropping = false;
+                dropping.push(idx);
+            }
+        }
+        drop(in_guards);
+        for idx in dropping {
+            self.notify_adaptive(idx, false);
         }
     }
 }
@@ -651,8 +1055,10 @@ impl TransmissionPipelineProducer {
 pub(crate) struct TransmissionPipelineConsumer {
     // A single Mutex for all the priority queues
     stage_out: Box<[StageOut]>,
+    stage_in: Arc<[Mutex<StageIn>]>,
     n_out_r: Receiver<()>,
     active: Arc<AtomicBool>,
+    batch: BatchConfig,
 }
 
 impl TransmissionPipelineConsumer {
@@ -661,6 +1067,15 @@ impl TransmissionPipelineConsumer {
             // Calculate the backoff maximum
             let mut bo = NanoSeconds::MAX;
             for (prio, queue) in self.stage_out.iter_mut().enumerate() {
+                // Serialize the messages kept aside as soon as batches are available,
+                // without waiting for the producer to push another message
+                if queue.has_kept_aside.load(Ordering::Acquire) {
+                    match self.stage_in[prio].try_lock() {
+                        // Don't fragment messages here: it could require waiting for more batches
+                        Ok(mut stage_in) => stage_in.flush_kept_aside(false),
+                        Err(_) => bo = bo.min(KEPT_ASIDE_RETRY),
+                    }
+                }
                 match queue.try_pull() {
                     Pull::Some(batch) => {
                         return Some((batch, prio));
@@ -684,6 +1099,16 @@ impl TransmissionPipelineConsumer {
 
     pub(crate) fn refill(&mut self, batch: WBatch, priority: usize) {
         self.stage_out[priority].refill(batch);
+        // Serialize the messages kept aside waiting for this batch, unless the stage in is busy
+        if self.stage_out[priority]
+            .has_kept_aside
+            .load(Ordering::Acquire)
+        {
+            if let Ok(mut queue) = self.stage_in[priority].try_lock() {
+                // Don't fragment messages here: it could require waiting for more batches
+                queue.flush_kept_aside(false);
+            }
+        }
     }
 
     pub(crate) fn drain(&mut self) -> Vec<(WBatch, usize)> {
@@ -692,6 +1117,37 @@ impl TransmissionPipelineConsumer {
 
         // Acquire all the locks, in_guard first, out_guard later
         // Use the same locking order as in disable to avoid deadlocks
+        let mut in_guards: Vec<MutexGuard<'_, StageIn>> =
+            self.stage_in.iter().map(|x| zlock!(x)).collect();
+
+        // Serialize the messages kept aside instead of dropping them, in new batches
+        // once the available ones are full
+        for (prio, s_out) in self.stage_out.iter_mut().enumerate() {
+            let queue = &mut in_guards[prio];
+            while !queue.kept_aside.is_empty() {
+                queue.flush_kept_aside(false);
+                if queue.kept_aside.is_empty() {
+                    break;
+                }
+                let current = s_out.s_in.current.clone();
+                let mut current = zlock!(current);
+                if current.as_ref().is_some_and(|batch| batch.is_empty()) {
+                    // The message doesn't fit in an empty batch and fragmenting it
+                    // could require waiting for more batches
+                    queue.kept_aside.pop_front();
+                    tracing::trace!(
+                        "Dropping a message kept aside for {:?} that needs fragmentation",
+                        queue.priority
+                    );
+                    continue;
+                }
+                for b in s_out.drain(&mut current) {
+                    batches.push((b, prio));
+                }
+                let _ = s_out.s_ref.s_ref_w.push(WBatch::new(self.batch));
+            }
+        }
+
         let locks = self
             .stage_out
             .iter()
@@ -706,6 +1162,7 @@ impl TransmissionPipelineConsumer {
                 batches.push((b, prio));
             }
         }
+        drop(in_guards);
 
         batches
     }
@@ -714,6 +1171,8 @@ impl TransmissionPipelineConsumer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "transport_compression")]
+    use crate::common::batch::CompressionAlgorithm;
     use std::{
         convert::TryFrom,
         sync::{
@@ -731,7 +1190,7 @@ mod tests {
     use zenoh_codec::{RCodec, Zenoh080};
     use zenoh_protocol::{
         core::{Bits, CongestionControl, Encoding, Priority},
-        network::{ext, Push},
+        network::{ext, NetworkBody, Push},
         transport::{BatchSize, Fragment, Frame, TransportBody, TransportSn},
         zenoh::{PushBody, Put},
     };
@@ -746,10 +1205,19 @@ mod tests {
             is_streamed: true,
             #[cfg(feature = "transport_compression")]
             is_compression: true,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: 0,
+            is_checksum: false,
         },
         queue_size: [1; Priority::NUM],
         wait_before_drop: Duration::from_millis(1),
+        drop_first_queue_size: 2,
+        adaptive_wait_before_drop: Duration::from_millis(100),
         backoff: Duration::from_micros(1),
+        pacing: [None; Priority::NUM],
+        batching: None,
     };
 
     const CONFIG_NOT_STREAMED: TransmissionPipelineConf = TransmissionPipelineConf {
@@ -758,10 +1226,19 @@ mod tests {
             is_streamed: false,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: 0,
+            is_checksum: false,
         },
         queue_size: [1; Priority::NUM],
         wait_before_drop: Duration::from_millis(1),
+        drop_first_queue_size: 2,
+        adaptive_wait_before_drop: Duration::from_millis(100),
         backoff: Duration::from_micros(1),
+        pacing: [None; Priority::NUM],
+        batching: None,
     };
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
@@ -780,9 +1257,13 @@ mod tests {
                     timestamp: None,
                     encoding: Encoding::default(),
                     ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
                     #[cfg(feature = "shared-memory")]
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_trace: None,
                     ext_unknown: vec![],
                     payload,
                 }),
@@ -811,10 +1292,11 @@ mod tests {
                 let (batch, priority) = queue.pull().await.unwrap();
                 batches += 1;
                 bytes += batch.len() as usize;
-                // Create a ZBuf for deserialization starting from the batch
-                let bytes = batch.as_slice();
+                // Create a ZBuf for deserialization starting from the batch, along with the
+                // payloads it references
+                let bytes = batch.as_slices().concat();
                 // Deserialize the messages
-                let mut reader = bytes.reader();
+                let mut reader = bytes.as_slice().reader();
                 let codec = Zenoh080::new();
 
                 loop {
@@ -868,8 +1350,11 @@ mod tests {
             // Compute the number of messages to send
             let num_msg = max_msgs.min(bytes / ps);
 
-            let (producer, consumer) =
-                TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice());
+            let (producer, consumer) = TransmissionPipeline::make(
+                CONFIG_NOT_STREAMED,
+                priorities.as_slice(),
+                &Arc::default(),
+            );
 
             let t_c = task::spawn(async move {
                 consume(consumer, num_msg).await;
@@ -908,9 +1393,13 @@ mod tests {
                     timestamp: None,
                     encoding: Encoding::default(),
                     ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
                     #[cfg(feature = "shared-memory")]
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_trace: None,
                     ext_unknown: vec![],
                     payload,
                 }),
@@ -938,7 +1427,7 @@ mod tests {
         let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
         let priorities = vec![tct];
         let (producer, mut consumer) =
-            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice());
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), &Arc::default());
 
         let counter = Arc::new(AtomicUsize::new(0));
 
@@ -987,6 +1476,481 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_express() -> ZResult<()> {
+        fn message(is_express: bool) -> NetworkMessage {
+            Push {
+                wire_expr: "test".into(),
+                ext_qos: ext::QoSType::new(Priority::Data, CongestionControl::Block, is_express),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                payload: PushBody::Put(Put {
+                    timestamp: None,
+                    encoding: Encoding::default(),
+                    ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_attachment: None,
+                    ext_trace: None,
+                    ext_unknown: vec![],
+                    payload: ZBuf::from(vec![0_u8; 8]),
+                }),
+            }
+            .into()
+        }
+
+        async fn pull_msgs(queue: &mut TransmissionPipelineConsumer) -> usize {
+            let (batch, priority) = timeout(TIMEOUT, queue.pull()).await.unwrap().unwrap();
+            let mut reader = batch.as_slice().reader();
+            let codec = Zenoh080::new();
+            let mut msgs = 0;
+            loop {
+                let res: Result<TransportMessage, DidntRead> = codec.read(&mut reader);
+                match res {
+                    Ok(TransportMessage {
+                        body: TransportBody::Frame(Frame { payload, .. }),
+                        ..
+                    }) => msgs += payload.len(),
+                    Ok(_) => msgs += 1,
+                    Err(_) => break,
+                }
+            }
+            queue.refill(batch, priority);
+            msgs
+        }
+
+        let config = TransmissionPipelineConf {
+            queue_size: [2; Priority::NUM],
+            ..CONFIG_NOT_STREAMED
+        };
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(config, priorities.as_slice(), &Arc::default());
+
+        // Regular messages are batched together
+        assert!(producer.push_network_message(message(false)));
+        assert!(producer.push_network_message(message(false)));
+        assert_eq!(pull_msgs(&mut consumer).await, 2);
+
+        // Express messages are moved out of the pipeline one by one
+        assert!(producer.push_network_message(message(true)));
+        assert!(producer.push_network_message(message(true)));
+        assert_eq!(pull_msgs(&mut consumer).await, 1);
+        assert_eq!(pull_msgs(&mut consumer).await, 1);
+
+        Ok(())
+    }
+
+    fn congestion_message(id: u8, congestion_control: CongestionControl) -> NetworkMessage {
+        Push {
+            wire_expr: "test".into(),
+            // Express messages are moved out of the pipeline in a batch of their own
+            ext_qos: ext::QoSType::new(Priority::Data, congestion_control, true),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            payload: PushBody::Put(Put {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                ext_coherence: None,
+                ext_ttl: None,
+                ext_latency_budget: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_attachment: None,
+                ext_trace: None,
+                ext_unknown: vec![],
+                payload: ZBuf::from(vec![id; 8]),
+            }),
+        }
+        .into()
+    }
+
+    async fn pull_ids(queue: &mut TransmissionPipelineConsumer) -> Vec<u8> {
+        let (batch, priority) = timeout(TIMEOUT, queue.pull()).await.unwrap().unwrap();
+        let ids = batch_ids(&batch);
+        queue.refill(batch, priority);
+        ids
+    }
+
+    fn batch_ids(batch: &WBatch) -> Vec<u8> {
+        let mut reader = batch.as_slice().reader();
+        let codec = Zenoh080::new();
+        let mut ids = vec![];
+        while let Ok(TransportMessage {
+            body: TransportBody::Frame(Frame { payload, .. }),
+            ..
+        }) = codec.read(&mut reader)
+        {
+            for msg in payload {
+                if let NetworkBody::Push(Push {
+                    payload: PushBody::Put(put),
+                    ..
+                }) = msg.body
+                {
+                    ids.push(put.payload.zslices().next().unwrap()[0]);
+                }
+            }
+        }
+        ids
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_drop_first() -> ZResult<()> {
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), &Arc::default());
+
+        // The only batch is taken by the first message
+        assert!(producer.push_network_message(congestion_message(0, CongestionControl::DropFirst)));
+        // The next messages are kept aside, the oldest one being dropped
+        for id in 1..4 {
+            assert!(
+                producer.push_network_message(congestion_message(id, CongestionControl::DropFirst))
+            );
+        }
+        // The messages kept aside are sent as soon as batches are available
+        assert_eq!(pull_ids(&mut consumer).await, [0]);
+        assert_eq!(pull_ids(&mut consumer).await, [2]);
+        assert_eq!(pull_ids(&mut consumer).await, [3]);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_drop_first_quiet() -> ZResult<()> {
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), &Arc::default());
+
+        assert!(producer.push_network_message(congestion_message(0, CongestionControl::DropFirst)));
+        assert!(producer.push_network_message(congestion_message(1, CongestionControl::DropFirst)));
+
+        // The stage in is busy when the batch is given back...
+        let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await.unwrap().unwrap();
+        assert_eq!(batch_ids(&batch), [0]);
+        let stage_in = zlock!(producer.stage_in[0]);
+        consumer.refill(batch, priority);
+        drop(stage_in);
+        // ...but the message kept aside is sent without any other message being pushed
+        assert_eq!(pull_ids(&mut consumer).await, [1]);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_drop_first_drain() -> ZResult<()> {
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), &Arc::default());
+
+        // The only batch is taken by the first message, the next ones are kept aside
+        for id in 0..3 {
+            assert!(
+                producer.push_network_message(congestion_message(id, CongestionControl::DropFirst))
+            );
+        }
+        // The messages kept aside are drained along with the serialized ones
+        producer.disable();
+        let ids: Vec<Vec<u8>> = consumer
+            .drain()
+            .iter()
+            .map(|(batch, _)| batch_ids(batch))
+            .collect();
+        assert_eq!(ids, [[0], [1], [2]]);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_adaptive() -> ZResult<()> {
+        let congestion = Arc::new(CongestionState::default());
+        let events = Arc::new(Mutex::new(vec![]));
+        let c_events = events.clone();
+        congestion.add_listener(Arc::new(move |priority, dropping| {
+            if priority == Priority::Data {
+                zlock!(c_events).push(dropping);
+            }
+        }));
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), &congestion);
+
+        // The only batch is taken by the first message
+        assert!(producer.push_network_message(congestion_message(0, CongestionControl::Adaptive)));
+        // The next message blocks until the adaptive deadline, then is dropped
+        let start = Instant::now();
+        assert!(!producer.push_network_message(congestion_message(1, CongestionControl::Adaptive)));
+        assert!(start.elapsed() >= CONFIG_NOT_STREAMED.adaptive_wait_before_drop);
+        assert!(congestion.is_dropping(Priority::Data));
+        assert_eq!(*zlock!(events), [true]);
+        // While dropping, messages are dropped after the regular deadline
+        let start = Instant::now();
+        assert!(!producer.push_network_message(congestion_message(2, CongestionControl::Adaptive)));
+        assert!(start.elapsed() < CONFIG_NOT_STREAMED.adaptive_wait_before_drop);
+
+        // Once the congestion is resolved, messages are blocking again
+        assert_eq!(pull_ids(&mut consumer).await, [0]);
+        assert!(producer.push_network_message(congestion_message(3, CongestionControl::Adaptive)));
+        assert!(!congestion.is_dropping(Priority::Data));
+        assert_eq!(*zlock!(events), [true, false]);
+        assert_eq!(pull_ids(&mut consumer).await, [3]);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_pacing() -> ZResult<()> {
+        const MTU: BatchSize = 1_024;
+        const RATE: u64 = 10_000;
+
+        let mut pacing = [None; Priority::NUM];
+        pacing[Priority::Data as usize] = Some(RATE);
+        let config = TransmissionPipelineConf {
+            batch: BatchConfig {
+                mtu: MTU,
+                ..CONFIG_NOT_STREAMED.batch
+            },
+            queue_size: [16; Priority::NUM],
+            pacing,
+            ..CONFIG_NOT_STREAMED
+        };
+        let priorities = (0..Priority::NUM)
+            .map(|_| TransportPriorityTx::make(Bits::from(TransportSn::MAX)))
+            .collect::<ZResult<Vec<_>>>()?;
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(config, priorities.as_slice(), &Arc::default());
+
+        // The producer of a large message is not paced
+        let mut message = congestion_message(0, CongestionControl::Block);
+        if let NetworkBody::Push(Push {
+            payload: PushBody::Put(put),
+            ..
+        }) = &mut message.body
+        {
+            put.payload = ZBuf::from(vec![0_u8; 10 * MTU as usize]);
+        }
+        let expected = Duration::from_secs_f64((9 * MTU as u64) as f64 / RATE as f64);
+        let start = Instant::now();
+        assert!(producer.push_network_message(message));
+        assert!(start.elapsed() < expected);
+
+        // The consumer spreads the fragments over time at the pacing rate
+        let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await.unwrap().unwrap();
+        assert_eq!(priority, Priority::Data as usize);
+        assert!(batch.more_fragments);
+        let mut paced = batch.len() as u64;
+        consumer.refill(batch, priority);
+
+        // The other priorities are pulled while the next fragment is delayed
+        let mut message = congestion_message(1, CongestionControl::Block);
+        if let NetworkBody::Push(Push { ext_qos, .. }) = &mut message.body {
+            ext_qos.set_priority(Priority::Background);
+        }
+        assert!(producer.push_network_message(message));
+        let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await.unwrap().unwrap();
+        assert_eq!(priority, Priority::Background as usize);
+        assert_eq!(batch_ids(&batch), [1]);
+        consumer.refill(batch, priority);
+
+        // All the fragments but the last one are paced
+        loop {
+            let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await.unwrap().unwrap();
+            assert_eq!(priority, Priority::Data as usize);
+            let more = batch.more_fragments;
+            if more {
+                paced += batch.len() as u64;
+            }
+            consumer.refill(batch, priority);
+            if !more {
+                break;
+            }
+        }
+        assert!(start.elapsed() >= Duration::from_secs_f64(paced as f64 / RATE as f64));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_flush() -> ZResult<()> {
+        let message: NetworkMessage = Push {
+            wire_expr: "test".into(),
+            ext_qos: ext::QoSType::new(Priority::Data, CongestionControl::Block, false),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            payload: PushBody::Put(Put {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                ext_coherence: None,
+                ext_ttl: None,
+                ext_latency_budget: None,
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_attachment: None,
+                ext_trace: None,
+                ext_unknown: vec![],
+                payload: ZBuf::from(vec![0_u8; 8]),
+            }),
+        }
+        .into();
+
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), &Arc::default());
+        assert!(producer.is_flushed(&producer.flush_point()));
+
+        // The pipeline is flushed once the batch has been given back...
+        assert!(producer.push_network_message(message.clone()));
+        let point = producer.flush_point();
+        assert!(!producer.is_flushed(&point));
+        let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await.unwrap().unwrap();
+        assert!(!producer.is_flushed(&point));
+        consumer.refill(batch, priority);
+        assert!(producer.is_flushed(&point));
+
+        // ...whatever the messages pushed afterwards.
+        assert!(producer.push_network_message(message.clone()));
+        let point = producer.flush_point();
+        let (batch, priority) = timeout(TIMEOUT, consumer.pull()).await.unwrap().unwrap();
+        assert!(producer.push_network_message(message));
+        consumer.refill(batch, priority);
+        assert!(producer.is_flushed(&point));
+        assert!(!producer.is_flushed(&producer.flush_point()));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_batching() -> ZResult<()> {
+        const MTU: BatchSize = 1_024;
+        const MAX_LATENCY: Duration = Duration::from_millis(20);
+
+        let message = |id: u8| -> NetworkMessage {
+            Push {
+                wire_expr: "test".into(),
+                ext_qos: ext::QoSType::new(Priority::Data, CongestionControl::Block, false),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                payload: PushBody::Put(Put {
+                    timestamp: None,
+                    encoding: Encoding::default(),
+                    ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_attachment: None,
+                    ext_trace: None,
+                    ext_unknown: vec![],
+                    payload: ZBuf::from(vec![id; 64]),
+                }),
+            }
+            .into()
+        };
+
+        let config = TransmissionPipelineConf {
+            batch: BatchConfig {
+                mtu: MTU,
+                ..CONFIG_NOT_STREAMED.batch
+            },
+            queue_size: [2; Priority::NUM],
+            batching: Some(MAX_LATENCY),
+            ..CONFIG_NOT_STREAMED
+        };
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(config, priorities.as_slice(), &Arc::default());
+
+        // The messages pushed while the batch is held back are sent together, in order
+        for id in 0..4 {
+            assert!(producer.push_network_message(message(id)));
+        }
+        assert_eq!(pull_ids(&mut consumer).await, [0, 1, 2, 3]);
+
+        // A full batch is sent first, the next messages are carried by the next batch
+        let ids: Vec<u8> = (0..20).collect();
+        for id in ids.iter() {
+            assert!(producer.push_network_message(message(*id)));
+        }
+        let first = pull_ids(&mut consumer).await;
+        let second = pull_ids(&mut consumer).await;
+        assert!(first.len() > 1 && second.len() > 1);
+        assert_eq!([first, second].concat(), ids);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn tx_pipeline_batch_scope() -> ZResult<()> {
+        let message = |id: u8| -> NetworkMessage {
+            let mut message = congestion_message(id, CongestionControl::Block);
+            if let NetworkBody::Push(push) = &mut message.body {
+                push.ext_qos.set_is_express(false);
+            }
+            message
+        };
+
+        let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX))?;
+        let priorities = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(CONFIG_NOT_STREAMED, priorities.as_slice(), &Arc::default());
+
+        batch_scope(|| {
+            for id in 0..4 {
+                assert!(producer.push_network_message(message(id)));
+                // The current batch is not sent while the scope holds it back
+                for _ in 0..2 {
+                    assert!(matches!(consumer.stage_out[0].try_pull(), Pull::Backoff(_)));
+                }
+            }
+            // Nested scopes don't flush the batch
+            batch_scope(|| assert!(producer.push_network_message(message(4))));
+            assert!(matches!(consumer.stage_out[0].try_pull(), Pull::Backoff(_)));
+        });
+
+        // The messages are flushed together, in a single batch, at the end of the scope
+        assert_eq!(pull_ids(&mut consumer).await, [0, 1, 2, 3, 4]);
+        for _ in 0..2 {
+            assert!(!matches!(consumer.stage_out[0].try_pull(), Pull::Some(_)));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn tx_pipeline_batching_budget() {
+        const CAPACITY: BatchSize = 1_024;
+        const MAX_LATENCY: Duration = Duration::from_secs(1);
+
+        let batching = |elapsed: Duration| Batching {
+            max_latency: MAX_LATENCY,
+            capacity: CAPACITY,
+            since: Some(Instant::now() - elapsed),
+        };
+
+        // An empty batch is never waited for
+        assert!(batching(Duration::ZERO).wait(0).is_none());
+        // A batch filling up fast enough is waited for, within the latency budget
+        let wait = batching(MAX_LATENCY / 2).wait(CAPACITY / 4 * 3).unwrap();
+        assert!(wait <= MAX_LATENCY / 2);
+        // A batch filling up too slowly is sent right away
+        assert!(batching(MAX_LATENCY / 2).wait(CAPACITY / 8).is_none());
+        // So is a batch whose latency budget is exhausted
+        assert!(batching(MAX_LATENCY * 2).wait(CAPACITY - 1).is_none());
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     #[ignore]
     async fn tx_pipeline_thr() {
@@ -994,7 +1958,7 @@ mod tests {
         let tct = TransportPriorityTx::make(Bits::from(TransportSn::MAX)).unwrap();
         let priorities = vec![tct];
         let (producer, mut consumer) =
-            TransmissionPipeline::make(CONFIG_STREAMED, priorities.as_slice());
+            TransmissionPipeline::make(CONFIG_STREAMED, priorities.as_slice(), &Arc::default());
         let count = Arc::new(AtomicUsize::new(0));
         let size = Arc::new(AtomicUsize::new(0));
 
@@ -1025,9 +1989,13 @@ mod tests {
                             timestamp: None,
                             encoding: Encoding::default(),
                             ext_sinfo: None,
+                            ext_coherence: None,
+                            ext_ttl: None,
+                            ext_latency_budget: None,
                             #[cfg(feature = "shared-memory")]
                             ext_shm: None,
                             ext_attachment: None,
+                            ext_trace: None,
                             ext_unknown: vec![],
                             payload,
                         }),
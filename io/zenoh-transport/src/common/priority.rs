@@ -20,6 +20,7 @@ use zenoh_protocol::{
     transport::{PrioritySn, TransportSn},
 };
 use zenoh_result::ZResult;
+use zenoh_util::MemoryBudget;
 
 #[derive(Debug)]
 pub(crate) struct TransportChannelTx {
@@ -39,9 +40,14 @@ impl TransportChannelTx {
     }
 }
 
+/// Number of sequence numbers preceding the most recent one that are checked for replays.
+const REPLAY_WINDOW: TransportSn = u64::BITS;
+
 #[derive(Debug)]
 pub(crate) struct TransportChannelRx {
     pub(crate) sn: SeqNum,
+    // Bit i is set if the SN preceding `sn` by i has been received
+    window: u64,
     pub(crate) defrag: DefragBuffer,
 }
 
@@ -50,13 +56,48 @@ impl TransportChannelRx {
         reliability: Reliability,
         resolution: Bits,
         defrag_buff_size: usize,
+        memory_budget: &Arc<MemoryBudget>,
+        peer_budget: &Arc<MemoryBudget>,
     ) -> ZResult<TransportChannelRx> {
         let sn = SeqNum::make(0, resolution)?;
-        let defrag = DefragBuffer::make(reliability, resolution, defrag_buff_size)?;
-        let tch = TransportChannelRx { sn, defrag };
+        let defrag = DefragBuffer::make(
+            reliability,
+            resolution,
+            defrag_buff_size,
+            memory_budget,
+            peer_budget,
+        )?;
+        let tch = TransportChannelRx {
+            sn,
+            window: u64::MAX,
+            defrag,
+        };
         Ok(tch)
     }
 
+    /// Checks that a best-effort SN has not been received yet, and records it.
+    ///
+    /// Datagrams may be reordered: the SNs preceding the most recent one are accepted as long as
+    /// they are within the anti-replay window. The older ones are rejected as if replayed.
+    pub(crate) fn accept(&mut self, sn: TransportSn) -> ZResult<bool> {
+        let gap = self.sn.gap(sn)?;
+        if self.sn.roll(sn)? {
+            self.window = if gap < REPLAY_WINDOW {
+                (self.window << gap) | 1
+            } else {
+                1
+            };
+            return Ok(true);
+        }
+
+        let age = gap.wrapping_neg() & self.sn.resolution();
+        if age >= REPLAY_WINDOW || self.window & (1 << age) != 0 {
+            return Ok(false);
+        }
+        self.window |= 1 << age;
+        Ok(true)
+    }
+
     pub(crate) fn sync(&mut self, sn: TransportSn) -> ZResult<()> {
         // Set the sequence number in the state as it had received a message with sn - 1
         let sn = if sn == 0 {
@@ -66,6 +107,8 @@ impl TransportChannelRx {
         };
 
         self.sn.set(sn)?;
+        // Nothing sent before the initial SN is accepted
+        self.window = u64::MAX;
         self.defrag.sync(sn)
     }
 }
@@ -100,9 +143,26 @@ pub(crate) struct TransportPriorityRx {
 }
 
 impl TransportPriorityRx {
-    pub(crate) fn make(resolution: Bits, defrag_buff_size: usize) -> ZResult<TransportPriorityRx> {
-        let rch = TransportChannelRx::make(Reliability::Reliable, resolution, defrag_buff_size)?;
-        let bch = TransportChannelRx::make(Reliability::BestEffort, resolution, defrag_buff_size)?;
+    pub(crate) fn make(
+        resolution: Bits,
+        defrag_buff_size: usize,
+        memory_budget: &Arc<MemoryBudget>,
+        peer_budget: &Arc<MemoryBudget>,
+    ) -> ZResult<TransportPriorityRx> {
+        let rch = TransportChannelRx::make(
+            Reliability::Reliable,
+            resolution,
+            defrag_buff_size,
+            memory_budget,
+            peer_budget,
+        )?;
+        let bch = TransportChannelRx::make(
+            Reliability::BestEffort,
+            resolution,
+            defrag_buff_size,
+            memory_budget,
+            peer_budget,
+        )?;
         let ctr = TransportPriorityRx {
             reliable: Arc::new(Mutex::new(rch)),
             best_effort: Arc::new(Mutex::new(bch)),
@@ -115,3 +175,72 @@ impl TransportPriorityRx {
         zlock!(self.best_effort).sync(sn.best_effort)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(resolution: Bits) -> TransportChannelRx {
+        let memory_budget = Arc::new(MemoryBudget::unlimited());
+        let mut c = TransportChannelRx::make(
+            Reliability::BestEffort,
+            resolution,
+            1_024,
+            &memory_budget,
+            &memory_budget,
+        )
+        .unwrap();
+        c.sync(0).unwrap();
+        c
+    }
+
+    #[test]
+    fn channel_rx_replay() {
+        let mut c = channel(Bits::U32);
+
+        // In order
+        assert!(c.accept(0).unwrap());
+        assert!(c.accept(1).unwrap());
+        assert!(!c.accept(1).unwrap());
+
+        // Reordered within the window
+        assert!(c.accept(5).unwrap());
+        assert!(c.accept(3).unwrap());
+        assert!(c.accept(2).unwrap());
+        assert!(!c.accept(3).unwrap());
+        assert!(!c.accept(5).unwrap());
+        assert!(c.accept(4).unwrap());
+        assert_eq!(c.sn.get(), 5);
+
+        // Older than the window
+        assert!(c.accept(5 + REPLAY_WINDOW).unwrap());
+        assert!(!c.accept(5).unwrap());
+        assert!(c.accept(6).unwrap());
+        assert!(!c.accept(6).unwrap());
+
+        // The window is reset by a large gap
+        assert!(c.accept(1_000).unwrap());
+        assert!(c.accept(999).unwrap());
+        assert!(!c.accept(1_000).unwrap());
+    }
+
+    #[test]
+    fn channel_rx_replay_rollover() {
+        let mut c = channel(Bits::U8);
+        let mask = c.sn.resolution();
+
+        // Nothing preceding the initial SN is accepted
+        c.sync(10).unwrap();
+        assert!(!c.accept(9).unwrap());
+        assert!(!c.accept(mask).unwrap());
+
+        c.sync(mask - 1).unwrap();
+        assert!(c.accept(mask).unwrap());
+        assert!(c.accept(1).unwrap());
+        assert!(c.accept(mask - 1).unwrap());
+        assert!(c.accept(0).unwrap());
+        assert!(!c.accept(mask).unwrap());
+        assert!(!c.accept(0).unwrap());
+        assert_eq!(c.sn.get(), 1);
+    }
+}
@@ -153,7 +153,6 @@ impl SeqNum {
     }
 
     /// Computes the modulo gap between two sequence numbers.
-    #[cfg(test)] // @TODO: remove #[cfg(test)] once reliability is implemented
     pub(crate) fn gap(&self, value: TransportSn) -> ZResult<TransportSn> {
         if (value & !self.mask) != 0 {
             bail!("The sequence number value must be smaller than the resolution");
@@ -204,6 +204,10 @@ stats_struct! {
         # TYPE "counter"
         pub tx_z_put_pl_bytes DiscriminatedStats,
 
+        # HELP "Counter of payload bytes in zenoh put messages dropped because they exceeded a bandwidth quota."
+        # TYPE "counter"
+        pub tx_z_put_throttled_bytes,
+
         # HELP "Counter of sent zenoh del messages."
         # TYPE "counter"
         pub tx_z_del_msgs DiscriminatedStats,
@@ -228,10 +232,18 @@ stats_struct! {
         # TYPE "counter"
         pub rx_bytes,
 
+        # HELP "Counter of received batches dropped because their checksum did not match."
+        # TYPE "counter"
+        pub rx_corrupted,
+
         # HELP "Counter of received transport messages."
         # TYPE "counter"
         pub rx_t_msgs,
 
+        # HELP "Counter of received best-effort transport messages dropped because their sequence number was replayed."
+        # TYPE "counter"
+        pub rx_t_replayed,
+
         # HELP "Counter of received network messages."
         # TYPE "counter"
         pub rx_n_msgs,
@@ -248,6 +260,10 @@ stats_struct! {
         # TYPE "counter"
         pub rx_z_put_pl_bytes DiscriminatedStats,
 
+        # HELP "Counter of received zenoh put messages dropped because their latency budget elapsed."
+        # TYPE "counter"
+        pub rx_z_put_late,
+
         # HELP "Counter of received zenoh del messages."
         # TYPE "counter"
         pub rx_z_del_msgs DiscriminatedStats,
@@ -22,12 +22,22 @@ pub mod manager;
 pub mod multicast;
 pub mod unicast;
 
+pub use common::pipeline::batch_scope;
 #[cfg(feature = "stats")]
 pub use common::stats;
 
 #[cfg(feature = "shared-memory")]
 mod shm;
 
+/// The endpoint configuration parameters overriding the transport configuration
+/// on the links opened to an endpoint, e.g. `udp/192.168.1.1:7447#sn_resolution=16bit;batch_size=1024`.
+pub mod config {
+    /// The resolution in bits of the frame sequence numbers: `8bit`, `16bit` or `32bit`.
+    pub const SN_RESOLUTION: &str = "sn_resolution";
+    /// The batch size in bytes.
+    pub const BATCH_SIZE: &str = "batch_size";
+}
+
 use crate::{multicast::TransportMulticast, unicast::TransportUnicast};
 pub use manager::*;
 use serde::Serialize;
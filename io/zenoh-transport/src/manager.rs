@@ -15,6 +15,7 @@ use super::unicast::manager::{
     TransportManagerBuilderUnicast, TransportManagerConfigUnicast, TransportManagerStateUnicast,
 };
 use super::TransportEventHandler;
+use crate::common::congestion::CongestionState;
 use crate::multicast::manager::{
     TransportManagerBuilderMulticast, TransportManagerConfigMulticast,
     TransportManagerStateMulticast,
@@ -24,16 +25,21 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex as AsyncMutex;
-use zenoh_config::{Config, LinkRxConf, QueueConf, QueueSizeConf};
+use zenoh_config::{
+    BatchingConf, Config, DscpConf, LinkRxConf, PacingConf, QueueConf, QueueSizeConf,
+};
 use zenoh_crypto::{BlockCipher, PseudoRng};
 use zenoh_link::NewLinkChannelSender;
 use zenoh_protocol::{
-    core::{EndPoint, Field, Locator, Priority, Resolution, WhatAmI, ZenohId},
-    transport::BatchSize,
-    VERSION,
+    core::{Bits, EndPoint, Field, Locator, Priority, Resolution, WhatAmI, ZenohId},
+    transport::{BatchSize, TransportSn},
+    VERSION, VERSION_MIN,
 };
-use zenoh_result::{bail, ZResult};
+use zenoh_result::{bail, zerror, ZResult};
+#[cfg(feature = "shared-memory")]
+use zenoh_shm::ShmProvider;
 use zenoh_task::TaskController;
+use zenoh_util::{MemoryBudget, MemorySubsystem};
 
 /// # Examples
 /// ```
@@ -90,15 +96,24 @@ use zenoh_task::TaskController;
 
 pub struct TransportManagerConfig {
     pub version: u8,
+    pub version_min: u8,
     pub zid: ZenohId,
     pub whatami: WhatAmI,
     pub resolution: Resolution,
     pub batch_size: u16,
     pub wait_before_drop: Duration,
+    pub drop_first_queue_size: usize,
+    pub adaptive_wait_before_drop: Duration,
+    pub congestion: Arc<CongestionState>,
     pub queue_size: [usize; Priority::NUM],
     pub queue_backoff: Duration,
+    pub batching: Option<Duration>,
+    pub pacing: [Option<u64>; Priority::NUM],
+    pub dscp: Option<[u8; Priority::NUM]>,
     pub defrag_buff_size: usize,
+    pub defrag_peer_buff_size: usize,
     pub link_rx_buffer_size: usize,
+    pub memory_budget: Arc<MemoryBudget>,
     pub unicast: TransportManagerConfigUnicast,
     pub multicast: TransportManagerConfigMulticast,
     pub endpoints: HashMap<String, String>, // (protocol, config)
@@ -107,6 +122,16 @@ pub struct TransportManagerConfig {
     pub protocols: Vec<String>,
 }
 
+impl TransportManagerConfig {
+    /// A budget limiting the memory used to reassemble the fragmented messages of a peer.
+    pub(crate) fn peer_defrag_budget(&self) -> Arc<MemoryBudget> {
+        Arc::new(
+            MemoryBudget::unlimited()
+                .limit(MemorySubsystem::Defragmentation, self.defrag_peer_buff_size),
+        )
+    }
+}
+
 pub struct TransportManagerState {
     pub unicast: TransportManagerStateUnicast,
     pub multicast: TransportManagerStateMulticast,
@@ -119,15 +144,23 @@ pub struct TransportManagerParams {
 
 pub struct TransportManagerBuilder {
     version: u8,
+    version_min: u8,
     zid: ZenohId,
     whatami: WhatAmI,
     resolution: Resolution,
     batch_size: u16,
     wait_before_drop: Duration,
+    drop_first_queue_size: usize,
+    adaptive_wait_before_drop: Duration,
     queue_size: QueueSizeConf,
     queue_backoff: Duration,
+    batching: BatchingConf,
+    pacing: PacingConf,
+    dscp: DscpConf,
     defrag_buff_size: usize,
+    defrag_peer_buff_size: usize,
     link_rx_buffer_size: usize,
+    memory_budget: Arc<MemoryBudget>,
     unicast: TransportManagerBuilderUnicast,
     multicast: TransportManagerBuilderMulticast,
     endpoints: HashMap<String, String>, // (protocol, config)
@@ -136,6 +169,14 @@ pub struct TransportManagerBuilder {
 }
 
 impl TransportManagerBuilder {
+    /// The range of protocol versions the manager is able to speak, the newest one being
+    /// selected during the establishment of unicast transports.
+    pub fn version(mut self, version_min: u8, version: u8) -> Self {
+        self.version_min = version_min;
+        self.version = version;
+        self
+    }
+
     pub fn zid(mut self, zid: ZenohId) -> Self {
         self.zid = zid;
         self
@@ -161,6 +202,16 @@ impl TransportManagerBuilder {
         self
     }
 
+    pub fn drop_first_queue_size(mut self, drop_first_queue_size: usize) -> Self {
+        self.drop_first_queue_size = drop_first_queue_size;
+        self
+    }
+
+    pub fn adaptive_wait_before_drop(mut self, adaptive_wait_before_drop: Duration) -> Self {
+        self.adaptive_wait_before_drop = adaptive_wait_before_drop;
+        self
+    }
+
     pub fn queue_size(mut self, queue_size: QueueSizeConf) -> Self {
         self.queue_size = queue_size;
         self
@@ -171,16 +222,41 @@ impl TransportManagerBuilder {
         self
     }
 
+    pub fn batching(mut self, batching: BatchingConf) -> Self {
+        self.batching = batching;
+        self
+    }
+
+    pub fn pacing(mut self, pacing: PacingConf) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    pub fn dscp(mut self, dscp: DscpConf) -> Self {
+        self.dscp = dscp;
+        self
+    }
+
     pub fn defrag_buff_size(mut self, defrag_buff_size: usize) -> Self {
         self.defrag_buff_size = defrag_buff_size;
         self
     }
 
+    pub fn defrag_peer_buff_size(mut self, defrag_peer_buff_size: usize) -> Self {
+        self.defrag_peer_buff_size = defrag_peer_buff_size;
+        self
+    }
+
     pub fn link_rx_buffer_size(mut self, link_rx_buffer_size: usize) -> Self {
         self.link_rx_buffer_size = link_rx_buffer_size;
         self
     }
 
+    pub fn memory_budget(mut self, memory_budget: Arc<MemoryBudget>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
     pub fn endpoints(mut self, endpoints: HashMap<String, String>) -> Self {
         self.endpoints = endpoints;
         self
@@ -196,6 +272,15 @@ impl TransportManagerBuilder {
         self
     }
 
+    /// Sets the [`ShmProvider`] the unicast and multicast transports create and open
+    /// their shared memory segments with.
+    #[cfg(feature = "shared-memory")]
+    pub fn shm_provider(mut self, shm_provider: Arc<dyn ShmProvider>) -> Self {
+        self.unicast = self.unicast.shm_provider(shm_provider.clone());
+        self.multicast = self.multicast.shm_provider(shm_provider);
+        self
+    }
+
     pub fn tx_threads(mut self, num: usize) -> Self {
         self.tx_threads = num;
         self
@@ -218,12 +303,56 @@ impl TransportManagerBuilder {
         self = self.resolution(resolution);
         self = self.batch_size(*link.tx().batch_size());
         self = self.defrag_buff_size(*link.rx().max_message_size());
+        self = self.defrag_peer_buff_size(*link.rx().max_peer_reassembly_size());
         self = self.link_rx_buffer_size(*link.rx().buffer_size());
+
+        let memory = config.memory();
+        let mut memory_budget = match memory.budget() {
+            Some(budget) => MemoryBudget::new(*budget),
+            None => MemoryBudget::unlimited(),
+        };
+        for (subsystem, limit) in [
+            (
+                MemorySubsystem::PendingQueries,
+                memory.limits().pending_queries(),
+            ),
+            (
+                MemorySubsystem::HandlerQueues,
+                memory.limits().handler_queues(),
+            ),
+            (
+                MemorySubsystem::Defragmentation,
+                memory.limits().defragmentation(),
+            ),
+            (MemorySubsystem::RxBuffers, memory.limits().rx_buffers()),
+        ] {
+            if let Some(limit) = limit {
+                memory_budget = memory_budget.limit(subsystem, *limit);
+            }
+        }
+        self = self.memory_budget(Arc::new(memory_budget));
         self = self.wait_before_drop(Duration::from_micros(
             *link.tx().queue().congestion_control().wait_before_drop(),
         ));
+        self = self.drop_first_queue_size(
+            *link
+                .tx()
+                .queue()
+                .congestion_control()
+                .drop_first_queue_size(),
+        );
+        self = self.adaptive_wait_before_drop(Duration::from_micros(
+            *link
+                .tx()
+                .queue()
+                .congestion_control()
+                .adaptive_wait_before_drop(),
+        ));
         self = self.queue_size(link.tx().queue().size().clone());
         self = self.queue_backoff(Duration::from_nanos(*link.tx().queue().backoff()));
+        self = self.batching(link.tx().queue().batching().clone());
+        self = self.pacing(link.tx().queue().pacing().clone());
+        self = self.dscp(link.tx().dscp().clone());
         self = self.tx_threads(*link.tx().threads());
         self = self.protocols(link.protocols().clone());
 
@@ -264,17 +393,50 @@ impl TransportManagerBuilder {
         queue_size[Priority::DataLow as usize] = *self.queue_size.data_low();
         queue_size[Priority::Background as usize] = *self.queue_size.background();
 
+        let mut pacing = [None; Priority::NUM];
+        pacing[Priority::Control as usize] = *self.pacing.control();
+        pacing[Priority::RealTime as usize] = *self.pacing.real_time();
+        pacing[Priority::InteractiveHigh as usize] = *self.pacing.interactive_high();
+        pacing[Priority::InteractiveLow as usize] = *self.pacing.interactive_low();
+        pacing[Priority::DataHigh as usize] = *self.pacing.data_high();
+        pacing[Priority::Data as usize] = *self.pacing.data();
+        pacing[Priority::DataLow as usize] = *self.pacing.data_low();
+        pacing[Priority::Background as usize] = *self.pacing.background();
+
+        let dscp = self.dscp.enabled().then(|| {
+            let mut dscp = [0; Priority::NUM];
+            dscp[Priority::Control as usize] = *self.dscp.control();
+            dscp[Priority::RealTime as usize] = *self.dscp.real_time();
+            dscp[Priority::InteractiveHigh as usize] = *self.dscp.interactive_high();
+            dscp[Priority::InteractiveLow as usize] = *self.dscp.interactive_low();
+            dscp[Priority::DataHigh as usize] = *self.dscp.data_high();
+            dscp[Priority::Data as usize] = *self.dscp.data();
+            dscp[Priority::DataLow as usize] = *self.dscp.data_low();
+            dscp[Priority::Background as usize] = *self.dscp.background();
+            dscp
+        });
+
         let config = TransportManagerConfig {
             version: self.version,
+            version_min: self.version_min,
             zid: self.zid,
             whatami: self.whatami,
             resolution: self.resolution,
             batch_size: self.batch_size,
             wait_before_drop: self.wait_before_drop,
+            drop_first_queue_size: self.drop_first_queue_size,
+            adaptive_wait_before_drop: self.adaptive_wait_before_drop,
+            congestion: Arc::new(CongestionState::default()),
             queue_size,
             queue_backoff: self.queue_backoff,
+            batching: (*self.batching.enabled())
+                .then_some(Duration::from_micros(*self.batching.max_latency())),
+            pacing,
+            dscp,
             defrag_buff_size: self.defrag_buff_size,
+            defrag_peer_buff_size: self.defrag_peer_buff_size,
             link_rx_buffer_size: self.link_rx_buffer_size,
+            memory_budget: self.memory_budget,
             unicast: unicast.config,
             multicast: multicast.config,
             endpoints: self.endpoints,
@@ -305,17 +467,27 @@ impl Default for TransportManagerBuilder {
         let queue = QueueConf::default();
         let backoff = *queue.backoff();
         let wait_before_drop = *queue.congestion_control().wait_before_drop();
+        let drop_first_queue_size = *queue.congestion_control().drop_first_queue_size();
+        let adaptive_wait_before_drop = *queue.congestion_control().adaptive_wait_before_drop();
         Self {
             version: VERSION,
+            version_min: VERSION_MIN,
             zid: ZenohId::rand(),
             whatami: zenoh_config::defaults::mode,
             resolution: Resolution::default(),
             batch_size: BatchSize::MAX,
             wait_before_drop: Duration::from_micros(wait_before_drop),
+            drop_first_queue_size,
+            adaptive_wait_before_drop: Duration::from_micros(adaptive_wait_before_drop),
             queue_size: queue.size,
             queue_backoff: Duration::from_nanos(backoff),
+            batching: queue.batching,
+            pacing: queue.pacing,
+            dscp: DscpConf::default(),
             defrag_buff_size: *link_rx.max_message_size(),
+            defrag_peer_buff_size: *link_rx.max_peer_reassembly_size(),
             link_rx_buffer_size: *link_rx.buffer_size(),
+            memory_budget: Arc::new(MemoryBudget::unlimited()),
             endpoints: HashMap::new(),
             unicast: TransportManagerBuilderUnicast::default(),
             multicast: TransportManagerBuilderMulticast::default(),
@@ -402,6 +574,40 @@ impl TransportManager {
             .await;
     }
 
+    /// The SN resolution and batch size to propose on the links opened to `endpoint`:
+    /// the configured ones, unless overridden by the [`config`](crate::config) parameters of the endpoint.
+    pub(crate) fn get_endpoint_params(
+        &self,
+        endpoint: &EndPoint,
+    ) -> ZResult<(Resolution, BatchSize)> {
+        let mut resolution = self.config.resolution;
+        if let Some(s) = endpoint.config().get(crate::config::SN_RESOLUTION) {
+            let bits: Bits = s.parse()?;
+            if bits > Bits::from(TransportSn::MAX) {
+                bail!(
+                    "Invalid {} on {}: {} exceeds {}",
+                    crate::config::SN_RESOLUTION,
+                    endpoint,
+                    bits,
+                    Bits::from(TransportSn::MAX)
+                );
+            }
+            resolution.set(Field::FrameSN, bits);
+        }
+        let mut batch_size = self.config.batch_size;
+        if let Some(s) = endpoint.config().get(crate::config::BATCH_SIZE) {
+            batch_size = s.parse().map_err(|_| {
+                zerror!(
+                    "Invalid {} on {}: {}",
+                    crate::config::BATCH_SIZE,
+                    endpoint,
+                    s
+                )
+            })?;
+        }
+        Ok((resolution, batch_size))
+    }
+
     /*************************************/
     /*              LISTENER             */
     /*************************************/
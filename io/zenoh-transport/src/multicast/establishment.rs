@@ -25,20 +25,22 @@ use std::sync::Arc;
 use zenoh_core::zasynclock;
 use zenoh_link::LinkMulticast;
 use zenoh_protocol::{
-    core::{Field, Priority},
-    transport::PrioritySn,
+    core::{Field, Priority, Resolution},
+    transport::{batch_size, BatchSize, PrioritySn},
 };
 use zenoh_result::{bail, ZResult};
 
 pub(crate) async fn open_link(
     manager: &TransportManager,
     link: LinkMulticast,
+    resolution: Resolution,
+    batch_size: BatchSize,
 ) -> ZResult<TransportMulticast> {
     // Create and configure the multicast transport
     let mut prng = zasynclock!(manager.prng);
 
     // Generate initial SNs
-    let sn_resolution = manager.config.resolution.get(Field::FrameSN);
+    let sn_resolution = resolution.get(Field::FrameSN);
     let max = seq_num::get_mask(sn_resolution);
     macro_rules! zgen_prioritysn {
         () => {
@@ -61,6 +63,9 @@ pub(crate) async fn open_link(
 
     // Create the transport
     let locator = link.get_dst().to_owned();
+    // For cross-system compatibility reasons we set the default minimal
+    // batch size to 8192 bytes unless explicitly configured smaller.
+    let batch_size = batch_size.min(link.get_mtu()).min(batch_size::MULTICAST);
     let config = TransportLinkMulticastConfig {
         batch: BatchConfig {
             mtu: link.get_mtu(),
@@ -73,7 +78,8 @@ pub(crate) async fn open_link(
 
     let config = TransportConfigMulticast {
         link,
-        sn_resolution,
+        resolution,
+        batch_size,
         initial_sns,
         #[cfg(feature = "shared-memory")]
         is_shm: manager.config.multicast.is_shm,
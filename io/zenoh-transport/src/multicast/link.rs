@@ -15,7 +15,7 @@
 use crate::stats::TransportStats;
 use crate::{
     common::{
-        batch::{BatchConfig, Encode, Finalize, RBatch, WBatch},
+        batch::{BatchConfig, Encode, Finalize, RBatch, RBatchBuffer, WBatch},
         pipeline::{
             TransmissionPipeline, TransmissionPipelineConf, TransmissionPipelineConsumer,
             TransmissionPipelineProducer,
@@ -31,15 +31,15 @@ use std::{
     time::{Duration, Instant},
 };
 use tokio::task::JoinHandle;
-use zenoh_buffers::{BBuf, ZSlice, ZSliceBuffer};
-use zenoh_core::{zcondfeat, zlock};
+use zenoh_buffers::BBuf;
+use zenoh_core::zlock;
 use zenoh_link::{Link, LinkMulticast, Locator};
 use zenoh_protocol::{
     core::{Bits, Priority, Resolution, WhatAmI, ZenohId},
-    transport::{BatchSize, Close, Join, PrioritySn, TransportMessage, TransportSn},
+    transport::{join, BatchSize, Close, Join, PrioritySn, TransportMessage, TransportSn},
 };
 use zenoh_result::{zerror, ZResult};
-use zenoh_sync::{RecyclingObject, RecyclingObjectPool, Signal};
+use zenoh_sync::{Signal, ZSlicePool};
 
 /****************************/
 /* TRANSPORT MULTICAST LINK */
@@ -65,18 +65,11 @@ impl TransportLinkMulticast {
     pub(crate) fn tx(&self) -> TransportLinkMulticastTx {
         TransportLinkMulticastTx {
             inner: self.clone(),
-            buffer: zcondfeat!(
-                "transport_compression",
-                self.config
-                    .batch
-                    .is_compression
-                    .then_some(BBuf::with_capacity(
-                        lz4_flex::block::get_maximum_output_size(
-                            self.config.batch.max_buffer_size()
-                        ),
-                    )),
-                None
-            ),
+            buffer: self
+                .config
+                .batch
+                .max_support_buffer_size()
+                .map(BBuf::with_capacity),
         }
     }
 
@@ -151,17 +144,24 @@ impl TransportLinkMulticastTx {
             .finalize(self.buffer.as_mut())
             .map_err(|_| zerror!("{ERR}{self}"))?;
 
-        let bytes = match res {
-            Finalize::Batch => batch.as_slice(),
-            Finalize::Buffer => self
+        let mut slices = match &res {
+            Finalize::Batch | Finalize::Trailer(_) => batch.as_slices(),
+            Finalize::Buffer => vec![self
                 .buffer
                 .as_ref()
                 .ok_or_else(|| zerror!("Invalid buffer finalization"))?
-                .as_slice(),
+                .as_slice()],
         };
+        if let Finalize::Trailer(trailer) = &res {
+            slices.push(trailer);
+        }
 
-        // Send the message on the link
-        self.inner.link.write_all(bytes).await?;
+        // Send the message on the link, a datagram can not be written in several parts
+        if let [bytes] = slices[..] {
+            self.inner.link.write_all(bytes).await?;
+        } else {
+            self.inner.link.write_all(&slices.concat()).await?;
+        }
 
         Ok(())
     }
@@ -205,13 +205,13 @@ impl TransportLinkMulticastRx {
     pub async fn recv_batch<C, T>(&self, buff: C) -> ZResult<(RBatch, Locator)>
     where
         C: Fn() -> T + Copy,
-        T: ZSliceBuffer + 'static,
+        T: RBatchBuffer,
     {
         const ERR: &str = "Read error from link: ";
 
         let mut into = (buff)();
         let (n, locator) = self.inner.link.read(into.as_mut_slice()).await?;
-        let buffer = ZSlice::make(Arc::new(into), 0, n).map_err(|_| zerror!("Error"))?;
+        let buffer = into.into_zslice(0, n).ok_or_else(|| zerror!("Error"))?;
         let mut batch = RBatch::new(self.inner.config.batch, buffer);
         batch.initialize(buff).map_err(|_| zerror!("{ERR}{self}"))?;
         Ok((batch, locator.into_owned()))
@@ -249,11 +249,13 @@ impl fmt::Debug for TransportLinkMulticastRx {
 /**************************************/
 pub(super) struct TransportLinkMulticastConfigUniversal {
     pub(super) version: u8,
+    pub(super) version_min: u8,
     pub(super) zid: ZenohId,
     pub(super) whatami: WhatAmI,
     pub(super) lease: Duration,
     pub(super) join_interval: Duration,
     pub(super) sn_resolution: Bits,
+    pub(super) resolution: Resolution,
     pub(super) batch_size: BatchSize,
 }
 
@@ -321,10 +323,18 @@ impl TransportLinkMulticastUniversal {
                 batch: self.link.config.batch,
                 queue_size: self.transport.manager.config.queue_size,
                 wait_before_drop: self.transport.manager.config.wait_before_drop,
+                drop_first_queue_size: self.transport.manager.config.drop_first_queue_size,
+                adaptive_wait_before_drop: self.transport.manager.config.adaptive_wait_before_drop,
                 backoff: self.transport.manager.config.queue_backoff,
+                pacing: self.transport.manager.config.pacing,
+                batching: self.transport.manager.config.batching,
             };
             // The pipeline
-            let (producer, consumer) = TransmissionPipeline::make(tpc, &priority_tx);
+            let (producer, consumer) = TransmissionPipeline::make(
+                tpc,
+                &priority_tx,
+                &self.transport.manager.config.congestion,
+            );
             self.pipeline = Some(producer);
 
             // Spawn the TX task
@@ -495,16 +505,20 @@ async fn tx_task(
                 } else {
                     (next_sns[0], None)
                 };
+                // Older nodes only join with the same version, so the oldest version is
+                // advertised and the newest one is announced in an extension
                 let message: TransportMessage = Join {
-                    version: config.version,
+                    version: config.version_min,
                     whatami: config.whatami,
                     zid: config.zid,
-                    resolution: Resolution::default(),
+                    resolution: config.resolution,
                     batch_size: config.batch_size,
                     lease: config.lease,
                     next_sn,
                     ext_qos,
                     ext_shm: None,
+                    ext_version: (config.version != config.version_min)
+                        .then(|| join::ext::Version::new(config.version as u64)),
                 }
                 .into();
 
@@ -532,18 +546,11 @@ async fn rx_task(
     rx_buffer_size: usize,
     batch_size: BatchSize,
 ) -> ZResult<()> {
-    async fn read<T, F>(
+    async fn read(
         link: &mut TransportLinkMulticastRx,
-        pool: &RecyclingObjectPool<T, F>,
-    ) -> ZResult<(RBatch, Locator)>
-    where
-        T: ZSliceBuffer + 'static,
-        F: Fn() -> T,
-        RecyclingObject<T>: ZSliceBuffer,
-    {
-        let (rbatch, locator) = link
-            .recv_batch(|| pool.try_take().unwrap_or_else(|| pool.alloc()))
-            .await?;
+        pool: &ZSlicePool,
+    ) -> ZResult<(RBatch, Locator)> {
+        let (rbatch, locator) = link.recv_batch(|| pool.take()).await?;
         Ok((rbatch, locator))
     }
 
@@ -554,7 +561,7 @@ async fn rx_task(
         n += 1;
     }
 
-    let pool = RecyclingObjectPool::new(n, || vec![0_u8; mtu].into_boxed_slice());
+    let pool = ZSlicePool::new(n, mtu);
     loop {
         tokio::select! {
             _ = signal.wait() => break,
@@ -29,6 +29,8 @@ use zenoh_link::*;
 use zenoh_protocol::core::ZenohId;
 use zenoh_protocol::{core::endpoint, transport::close};
 use zenoh_result::{bail, zerror, ZResult};
+#[cfg(feature = "shared-memory")]
+use zenoh_shm::{DefaultShmProvider, ShmProvider};
 
 pub struct TransportManagerConfigMulticast {
     pub lease: Duration,
@@ -50,6 +52,8 @@ pub struct TransportManagerBuilderMulticast {
     is_qos: bool,
     #[cfg(feature = "shared-memory")]
     is_shm: bool,
+    #[cfg(feature = "shared-memory")]
+    shm_provider: Arc<dyn ShmProvider>,
     #[cfg(feature = "transport_compression")]
     is_compression: bool,
 }
@@ -101,6 +105,12 @@ impl TransportManagerBuilderMulticast {
         self
     }
 
+    #[cfg(feature = "shared-memory")]
+    pub fn shm_provider(mut self, shm_provider: Arc<dyn ShmProvider>) -> Self {
+        self.shm_provider = shm_provider;
+        self
+    }
+
     #[cfg(feature = "transport_compression")]
     pub fn compression(mut self, is_compression: bool) -> Self {
         self.is_compression = is_compression;
@@ -142,7 +152,7 @@ impl TransportManagerBuilderMulticast {
             protocols: Arc::new(Mutex::new(HashMap::new())),
             transports: Arc::new(Mutex::new(HashMap::new())),
             #[cfg(feature = "shared-memory")]
-            shm: Arc::new(SharedMemoryMulticast::make()?),
+            shm: Arc::new(SharedMemoryMulticast::make(self.shm_provider)?),
         };
 
         let params = TransportManagerParamsMulticast { config, state };
@@ -167,6 +177,8 @@ impl Default for TransportManagerBuilderMulticast {
             is_qos: false,
             #[cfg(feature = "shared-memory")]
             is_shm: *shm.enabled(),
+            #[cfg(feature = "shared-memory")]
+            shm_provider: Arc::new(DefaultShmProvider),
             #[cfg(feature = "transport_compression")]
             is_compression: *compression.enabled(),
         };
@@ -264,9 +276,11 @@ impl TransportManager {
                 .extend(endpoint::Parameters::iter(config))?;
         }
 
+        let (resolution, batch_size) = self.get_endpoint_params(&endpoint)?;
+
         // Open the link
         let link = manager.new_link(&endpoint).await?;
-        super::establishment::open_link(self, link).await
+        super::establishment::open_link(self, link, resolution, batch_size).await
     }
 
     pub async fn get_transport_multicast(&self, zid: &ZenohId) -> Option<TransportMulticast> {
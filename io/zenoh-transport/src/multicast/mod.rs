@@ -29,15 +29,15 @@ pub use manager::{
 };
 use std::{
     fmt::{self, Write},
-    sync::{Arc, Weak},
+    sync::{atomic::Ordering, Arc, Weak},
 };
 use transport::TransportMulticastInner;
 use zenoh_core::{zcondfeat, zread};
 use zenoh_link::Link;
 use zenoh_protocol::{
-    core::Bits,
+    core::{Bits, Resolution},
     network::NetworkMessage,
-    transport::{close, PrioritySn},
+    transport::{close, BatchSize, PrioritySn},
 };
 use zenoh_result::{zerror, ZResult};
 
@@ -46,7 +46,8 @@ use zenoh_result::{zerror, ZResult};
 /*************************************/
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct TransportConfigMulticast {
-    pub(crate) sn_resolution: Bits,
+    pub(crate) resolution: Resolution,
+    pub(crate) batch_size: BatchSize,
     pub(crate) initial_sns: Box<[PrioritySn]>,
     pub(crate) link: TransportLinkMulticast,
     #[cfg(feature = "shared-memory")]
@@ -70,6 +71,13 @@ impl TransportMulticast {
         Ok(transport.get_sn_resolution())
     }
 
+    /// The batch size used on the link of the transport, which the peers joining it must use too.
+    #[inline(always)]
+    pub fn get_batch_size(&self) -> ZResult<BatchSize> {
+        let transport = self.get_transport()?;
+        Ok(transport.get_batch_size())
+    }
+
     #[cfg(feature = "shared-memory")]
     #[inline(always)]
     pub fn is_shm(&self) -> ZResult<bool> {
@@ -110,9 +118,16 @@ impl TransportMulticast {
         }
     }
 
+    /// Schedule a message for transmission. Messages that some peers of the group are not
+    /// able to decode, because of the protocol version they speak, are silently dropped.
     #[inline(always)]
     pub fn schedule(&self, message: NetworkMessage) -> ZResult<()> {
         let transport = self.get_transport()?;
+        let version = transport.version.load(Ordering::Acquire);
+        if message.min_version() > version {
+            tracing::trace!("Dropping {} unsupported by version {}", message, version);
+            return Ok(());
+        }
         transport.schedule(message);
         Ok(())
     }
@@ -161,6 +176,7 @@ impl fmt::Debug for TransportMulticast {
 
                 f.debug_struct("Transport Multicast")
                     .field("sn_resolution", &transport.get_sn_resolution())
+                    .field("batch_size", &transport.get_batch_size())
                     .field("is_qos", &transport.is_qos())
                     .field("is_shm", &is_shm)
                     .field("peers", &peers)
@@ -55,7 +55,7 @@ impl TransportMulticastInner {
         peer: &TransportMulticastPeer,
     ) -> ZResult<()> {
         // Check if parameters are ok
-        if join.version != peer.version
+        if self.peer_version(&join) != peer.version
             || join.zid != peer.zid
             || join.whatami != peer.whatami
             || join.resolution != peer.resolution
@@ -89,24 +89,26 @@ impl TransportMulticastInner {
             return Ok(());
         }
 
-        if join.version != self.manager.config.version {
+        if !(self.manager.config.version_min..=self.manager.config.version).contains(&join.version)
+        {
             tracing::debug!(
-                "Ingoring Join on {} from peer: {}. Unsupported version: {}. Expected: {}.",
+                "Ingoring Join on {} from peer: {}. Unsupported version: {}. Expected: {}..={}.",
                 locator,
                 join.zid,
                 join.version,
+                self.manager.config.version_min,
                 self.manager.config.version,
             );
             return Ok(());
         }
 
-        if join.resolution != self.manager.config.resolution {
+        if join.resolution != self.resolution {
             tracing::debug!(
                 "Ingoring Join on {} from peer: {}. Unsupported SN resolution: {:?}. Expected: {:?}.",
                 locator,
                 join.zid,
                 join.resolution,
-                self.manager.config.resolution,
+                self.resolution,
             );
             return Ok(());
         }
@@ -161,7 +163,9 @@ impl TransportMulticastInner {
             Reliability::BestEffort => zlock!(c.best_effort),
         };
 
-        self.verify_sn(sn, &mut guard)?;
+        if !self.verify_sn(reliability, sn, &mut guard)? {
+            return Ok(());
+        }
 
         for msg in payload.drain(..) {
             self.trigger_callback(msg, peer)?;
@@ -175,6 +179,7 @@ impl TransportMulticastInner {
             more,
             sn,
             ext_qos,
+            ext_total,
             payload,
         } = fragment;
 
@@ -197,22 +202,67 @@ impl TransportMulticastInner {
             Reliability::BestEffort => zlock!(c.best_effort),
         };
 
-        self.verify_sn(sn, &mut guard)?;
+        if !self.verify_sn(reliability, sn, &mut guard)? {
+            return Ok(());
+        }
+        // Reordered fragments can not be defragmented
+        if reliability == Reliability::BestEffort && guard.sn.get() != sn {
+            tracing::debug!(
+                "Transport: {}. Reordered fragment dropped: {}. Expected: {}.",
+                self.manager.config.zid,
+                sn,
+                guard.defrag.sn.get()
+            );
+            guard.defrag.clear();
+            return Ok(());
+        }
 
-        if guard.defrag.is_empty() {
+        // The first fragment of a message announces its total size
+        if let Some(total) = ext_total {
+            if !guard.defrag.is_empty() {
+                tracing::debug!(
+                    "Transport: {}. Peer: {}. Incomplete fragmented message discarded: {:?}.",
+                    self.manager.config.zid,
+                    peer.zid,
+                    guard.defrag.progress()
+                );
+            }
+            if let Err(e) = guard.defrag.begin(sn, total.value as usize) {
+                tracing::warn!(
+                    "Transport: {}. Peer: {}. Fragmented message aborted: {}",
+                    self.manager.config.zid,
+                    peer.zid,
+                    e
+                );
+            }
+        } else if guard.defrag.is_empty() {
             let _ = guard.defrag.sync(sn);
         }
-        guard.defrag.push(sn, payload)?;
+        if let Err(e) = guard.defrag.push(sn, payload) {
+            if !guard.defrag.is_discarding() {
+                return Err(e);
+            }
+            tracing::warn!(
+                "Transport: {}. Peer: {}. Fragmented message aborted: {}",
+                self.manager.config.zid,
+                peer.zid,
+                e
+            );
+        }
         if !more {
             // When shared-memory feature is disabled, msg does not need to be mutable
-            let msg = guard.defrag.defragment().ok_or_else(|| {
+            let msg = guard.defrag.defragment().map_err(|e| {
                 zerror!(
-                    "Transport: {}. Peer: {}. Priority: {:?}. Defragmentation error.",
+                    "Transport: {}. Peer: {}. Priority: {:?}. {}.",
                     self.manager.config.zid,
                     peer.zid,
-                    priority
+                    priority,
+                    e
                 )
             })?;
+            let Some(msg) = msg else {
+                return Ok(());
+            };
             return self.trigger_callback(msg, peer);
         }
 
@@ -221,9 +271,27 @@ impl TransportMulticastInner {
 
     fn verify_sn(
         &self,
+        reliability: Reliability,
         sn: TransportSn,
         guard: &mut MutexGuard<'_, TransportChannelRx>,
-    ) -> ZResult<()> {
+    ) -> ZResult<bool> {
+        // Best-effort messages are typically sent over datagram links, where the anti-replay
+        // window both tolerates reordering and prevents captured datagrams from being re-injected
+        if reliability == Reliability::BestEffort {
+            let accepted = guard.accept(sn)?;
+            if !accepted {
+                tracing::debug!(
+                    "Transport: {}. Frame with replayed SN dropped: {}. Latest: {}.",
+                    self.manager.config.zid,
+                    sn,
+                    guard.sn.get()
+                );
+                #[cfg(feature = "stats")]
+                self.stats.inc_rx_t_replayed(1);
+            }
+            return Ok(accepted);
+        }
+
         let precedes = guard.sn.precedes(sn)?;
         if !precedes {
             tracing::debug!(
@@ -237,14 +305,14 @@ impl TransportMulticastInner {
                 guard.defrag.clear();
             }
             // Keep reading
-            return Ok(());
+            return Ok(true);
         }
 
         // Set will always return OK because we have already checked
         // with precedes() that the sn has the right resolution
         let _ = guard.sn.set(sn);
 
-        Ok(())
+        Ok(true)
     }
 
     pub(super) fn read_messages(
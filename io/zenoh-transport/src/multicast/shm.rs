@@ -12,10 +12,11 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use rand::{Rng, SeedableRng};
+use std::sync::Arc;
 use tokio::sync::RwLock;
 use zenoh_crypto::PseudoRng;
 use zenoh_result::ZResult;
-use zenoh_shm::{SharedMemoryManager, SharedMemoryReader};
+use zenoh_shm::{SharedMemoryManager, SharedMemoryReader, ShmProvider};
 
 pub(crate) type Challenge = u64;
 const NAME: &str = "zshm_mcast";
@@ -28,16 +29,20 @@ pub(crate) struct SharedMemoryMulticast {
 unsafe impl Sync for SharedMemoryMulticast {}
 
 impl SharedMemoryMulticast {
-    pub fn make() -> ZResult<SharedMemoryMulticast> {
+    pub fn make(provider: Arc<dyn ShmProvider>) -> ZResult<SharedMemoryMulticast> {
         let mut prng = PseudoRng::from_entropy();
         let nonce = prng.gen::<Challenge>();
         let size = std::mem::size_of::<Challenge>();
 
-        let mut _manager = SharedMemoryManager::make(format!("{NAME}.{nonce}"), size)?;
+        let mut _manager = SharedMemoryManager::make_with_provider(
+            format!("{NAME}.{nonce}"),
+            size,
+            provider.as_ref(),
+        )?;
 
         let shmauth = SharedMemoryMulticast {
             _manager,
-            reader: RwLock::new(SharedMemoryReader::new()),
+            reader: RwLock::new(SharedMemoryReader::with_provider(provider)),
         };
         Ok(shmauth)
     }
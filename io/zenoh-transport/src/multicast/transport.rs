@@ -24,7 +24,7 @@ use crate::{
 use std::{
     collections::HashMap,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU8, Ordering},
         Arc, RwLock,
     },
     time::Duration,
@@ -33,7 +33,7 @@ use tokio_util::sync::CancellationToken;
 use zenoh_core::{zcondfeat, zread, zwrite};
 use zenoh_link::{Link, Locator};
 use zenoh_protocol::core::Resolution;
-use zenoh_protocol::transport::{batch_size, Close, TransportMessage};
+use zenoh_protocol::transport::{BatchSize, Close, TransportMessage};
 use zenoh_protocol::{
     core::{Bits, Field, Priority, WhatAmI, ZenohId},
     transport::{close, Join},
@@ -77,8 +77,13 @@ pub(crate) struct TransportMulticastInner {
     pub(super) priority_tx: Arc<[TransportPriorityTx]>,
     // Remote peers
     pub(super) peers: Arc<RwLock<HashMap<Locator, TransportMulticastPeer>>>,
+    // The newest protocol version spoken by all the peers
+    pub(super) version: Arc<AtomicU8>,
     // The multicast locator - Convenience for logging
     pub(super) locator: Locator,
+    // The SN resolution and batch size used on the link
+    pub(super) resolution: Resolution,
+    pub(super) batch_size: BatchSize,
     // The multicast link
     pub(super) link: Arc<RwLock<Option<TransportLinkMulticastUniversal>>>,
     // The callback
@@ -98,7 +103,7 @@ impl TransportMulticastInner {
         let mut priority_tx = vec![];
         if (config.initial_sns.len() != 1) != (config.initial_sns.len() != Priority::NUM) {
             for sn in config.initial_sns.iter() {
-                let tct = TransportPriorityTx::make(config.sn_resolution)?;
+                let tct = TransportPriorityTx::make(config.resolution.get(Field::FrameSN))?;
                 tct.sync(*sn)?;
                 priority_tx.push(tct);
             }
@@ -109,11 +114,15 @@ impl TransportMulticastInner {
         #[cfg(feature = "stats")]
         let stats = Arc::new(TransportStats::new(Some(manager.get_stats().clone())));
 
+        let version = Arc::new(AtomicU8::new(manager.config.version));
         let ti = TransportMulticastInner {
             manager,
             priority_tx: priority_tx.into_boxed_slice().into(),
             peers: Arc::new(RwLock::new(HashMap::new())),
+            version,
             locator: config.link.link.get_dst().to_owned(),
+            resolution: config.resolution,
+            batch_size: config.batch_size,
             link: Arc::new(RwLock::new(None)),
             callback: Arc::new(RwLock::new(None)),
             task_controller: TaskController::default(),
@@ -138,7 +147,11 @@ impl TransportMulticastInner {
     /*            ACCESSORS              */
     /*************************************/
     pub(crate) fn get_sn_resolution(&self) -> Bits {
-        self.manager.config.resolution.get(Field::FrameSN)
+        self.resolution.get(Field::FrameSN)
+    }
+
+    pub(crate) fn get_batch_size(&self) -> BatchSize {
+        self.batch_size
     }
 
     pub(crate) fn is_qos(&self) -> bool {
@@ -226,22 +239,16 @@ impl TransportMulticastInner {
         let mut guard = zwrite!(self.link);
         match guard.as_mut() {
             Some(l) => {
-                // For cross-system compatibility reasons we set the default minimal
-                // batch size to 8192 bytes unless explicitly configured smaller.
-                let batch_size = self
-                    .manager
-                    .config
-                    .batch_size
-                    .min(l.link.link.get_mtu())
-                    .min(batch_size::MULTICAST);
                 let config = TransportLinkMulticastConfigUniversal {
                     version: self.manager.config.version,
+                    version_min: self.manager.config.version_min,
                     zid: self.manager.config.zid,
                     whatami: self.manager.config.whatami,
                     lease: self.manager.config.multicast.lease,
                     join_interval: self.manager.config.multicast.join_interval,
-                    sn_resolution: self.manager.config.resolution.get(Field::FrameSN),
-                    batch_size,
+                    sn_resolution: self.resolution.get(Field::FrameSN),
+                    resolution: self.resolution,
+                    batch_size: self.batch_size,
                 };
                 l.start_tx(config, self.priority_tx.clone());
                 Ok(())
@@ -277,15 +284,7 @@ impl TransportMulticastInner {
         let mut guard = zwrite!(self.link);
         match guard.as_mut() {
             Some(l) => {
-                // For cross-system compatibility reasons we set the default minimal
-                // batch size to 8192 bytes unless explicitly configured smaller.
-                let batch_size = self
-                    .manager
-                    .config
-                    .batch_size
-                    .min(l.link.link.get_mtu())
-                    .min(batch_size::MULTICAST);
-                l.start_rx(batch_size);
+                l.start_rx(self.batch_size);
                 Ok(())
             }
             None => {
@@ -344,11 +343,15 @@ impl TransportMulticastInner {
         }
         .into_boxed_slice();
 
+        // The fragmented messages of the peer are reassembled within a shared limit
+        let peer_budget = self.manager.config.peer_defrag_budget();
         let mut priority_rx = Vec::with_capacity(next_sns.len());
         for sn in next_sns.iter() {
             let tprx = TransportPriorityRx::make(
                 join.resolution.get(Field::FrameSN),
                 self.manager.config.defrag_buff_size,
+                &self.manager.config.memory_budget,
+                &peer_budget,
             )?;
             tprx.sync(*sn)?;
             priority_rx.push(tprx);
@@ -397,7 +400,7 @@ impl TransportMulticastInner {
         // TODO(yuyuan): Integrate the above async task into TransportMulticastPeer
         // Store the new peer
         let peer = TransportMulticastPeer {
-            version: join.version,
+            version: self.peer_version(&join),
             locator: locator.clone(),
             zid: peer.zid,
             whatami: peer.whatami,
@@ -408,14 +411,36 @@ impl TransportMulticastInner {
             priority_rx,
             handler,
         };
-        zwrite!(self.peers).insert(locator.clone(), peer);
+        let mut guard = zwrite!(self.peers);
+        guard.insert(locator.clone(), peer);
+        self.update_version(&guard);
 
         Ok(())
     }
 
+    /// Returns the newest protocol version spoken by both this node and the sender of `join`.
+    pub(super) fn peer_version(&self, join: &Join) -> u8 {
+        // Nodes speaking an older version only advertise a single version through the version field
+        let version = join
+            .ext_version
+            .as_ref()
+            .map_or(join.version, |v| u8::try_from(v.value).unwrap_or(u8::MAX));
+        version.min(self.manager.config.version)
+    }
+
+    fn update_version(&self, peers: &HashMap<Locator, TransportMulticastPeer>) {
+        let version = peers
+            .values()
+            .map(|p| p.version)
+            .min()
+            .unwrap_or(self.manager.config.version);
+        self.version.store(version, Ordering::Release);
+    }
+
     pub(super) fn del_peer(&self, locator: &Locator, reason: u8) -> ZResult<()> {
         let mut guard = zwrite!(self.peers);
         if let Some(peer) = guard.remove(locator) {
+            self.update_version(&guard);
             tracing::debug!(
                 "Peer {}/{}/{} has left multicast {} with reason: {}",
                 peer.zid,
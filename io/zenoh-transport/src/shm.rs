@@ -168,7 +168,10 @@ pub fn map_zmsg_to_shminfo(msg: &mut NetworkMessage) -> ZResult<bool> {
             ResponseBody::Err(b) => b.map_to_shminfo(),
             ResponseBody::Ack(_) => Ok(false),
         },
-        NetworkBody::ResponseFinal(_) | NetworkBody::Declare(_) | NetworkBody::OAM(_) => Ok(false),
+        NetworkBody::ResponseFinal(_)
+        | NetworkBody::RequestCancel(_)
+        | NetworkBody::Declare(_)
+        | NetworkBody::OAM(_) => Ok(false),
     }
 }
 
@@ -223,7 +226,10 @@ pub fn map_zmsg_to_shmbuf(
             ResponseBody::Reply(b) => b.map_to_shmbuf(shmr),
             ResponseBody::Ack(_) => Ok(false),
         },
-        NetworkBody::ResponseFinal(_) | NetworkBody::Declare(_) | NetworkBody::OAM(_) => Ok(false),
+        NetworkBody::ResponseFinal(_)
+        | NetworkBody::RequestCancel(_)
+        | NetworkBody::Declare(_)
+        | NetworkBody::OAM(_) => Ok(false),
     }
 }
 
@@ -11,12 +11,14 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+#[cfg(feature = "transport_compression")]
+use crate::common::batch::CompressionAlgorithm;
 #[cfg(feature = "shared-memory")]
 use crate::unicast::shared_memory_unicast::Challenge;
 use crate::{
     common::batch::BatchConfig,
     unicast::{
-        establishment::{compute_sn, ext, AcceptFsm, Cookie, Zenoh080Cookie},
+        establishment::{compute_sn, ext, negotiate_version, AcceptFsm, Cookie, Zenoh080Cookie},
         link::{
             LinkUnicastWithOpenAck, TransportLinkUnicast, TransportLinkUnicastConfig,
             TransportLinkUnicastDirection,
@@ -47,6 +49,7 @@ use zenoh_result::ZResult;
 pub(super) type AcceptError = (zenoh_result::Error, Option<u8>);
 
 struct StateTransport {
+    version: u8,
     batch_size: BatchSize,
     resolution: Resolution,
     ext_qos: ext::qos::StateAccept,
@@ -55,6 +58,7 @@ struct StateTransport {
     #[cfg(feature = "shared-memory")]
     ext_shm: ext::shm::StateAccept,
     ext_lowlatency: ext::lowlatency::StateAccept,
+    ext_checksum: ext::checksum::StateAccept,
 }
 
 #[cfg(any(feature = "transport_auth", feature = "transport_compression"))]
@@ -74,6 +78,7 @@ struct State {
 // InitSyn
 struct RecvInitSynIn {
     mine_version: u8,
+    mine_version_min: u8,
 }
 struct RecvInitSynOut {
     other_zid: ZenohId,
@@ -84,7 +89,6 @@ struct RecvInitSynOut {
 
 // InitAck
 struct SendInitAckIn {
-    mine_version: u8,
     mine_zid: ZenohId,
     mine_whatami: WhatAmI,
     other_zid: ZenohId,
@@ -132,6 +136,7 @@ struct AcceptLink<'a> {
     ext_lowlatency: ext::lowlatency::LowLatencyFsm<'a>,
     #[cfg(feature = "transport_compression")]
     ext_compression: ext::compression::CompressionFsm<'a>,
+    ext_checksum: ext::checksum::ChecksumFsm<'a>,
 }
 
 #[async_trait]
@@ -164,15 +169,29 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             }
         };
 
-        // Check if the version is supported
-        if init_syn.version != input.mine_version {
-            let e = zerror!(
-                "Rejecting InitSyn on {} because of unsupported Zenoh version from peer: {}",
-                self.link,
-                init_syn.zid
-            );
-            return Err((e.into(), Some(close::reason::INVALID)));
-        }
+        // Select the newest version supported by both nodes. Nodes speaking an older version
+        // only advertise a single version through the version field.
+        let other_version = init_syn.ext_version.map_or(init_syn.version, |v| {
+            u8::try_from(v.value).unwrap_or(u8::MAX)
+        });
+        state.transport.version = match negotiate_version(
+            (input.mine_version_min, input.mine_version),
+            (init_syn.version, other_version),
+        ) {
+            Some(version) => version,
+            None => {
+                let e = zerror!(
+                    "Rejecting InitSyn on {} because of unsupported Zenoh version from peer: {}. Supported: {:#04x}..={:#04x}. Peer: {:#04x}..={:#04x}.",
+                    self.link,
+                    init_syn.zid,
+                    input.mine_version_min,
+                    input.mine_version,
+                    init_syn.version,
+                    other_version,
+                );
+                return Err((e.into(), Some(close::reason::INVALID)));
+            }
+        };
 
         // Compute the minimum SN resolution
         state.transport.resolution = {
@@ -235,7 +254,19 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
         // Extension Compression
         #[cfg(feature = "transport_compression")]
         self.ext_compression
-            .recv_init_syn((&mut state.link.ext_compression, init_syn.ext_compression))
+            .recv_init_syn((
+                &mut state.link.ext_compression,
+                (
+                    init_syn.ext_compression,
+                    init_syn.ext_compression_algorithms,
+                ),
+            ))
+            .await
+            .map_err(|e| (e, Some(close::reason::GENERIC)))?;
+
+        // Extension Checksum
+        self.ext_checksum
+            .recv_init_syn((&mut state.transport.ext_checksum, init_syn.ext_checksum))
             .await
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
@@ -302,18 +333,26 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
         // Extension MultiLink
-        let ext_compression = zcondfeat!(
+        let (ext_compression, ext_compression_algorithms) = zcondfeat!(
             "transport_compression",
             self.ext_compression
                 .send_init_ack(&state.link.ext_compression)
                 .await
                 .map_err(|e| (e, Some(close::reason::GENERIC)))?,
-            None
+            (None, None)
         );
 
+        // Extension Checksum
+        let ext_checksum = self
+            .ext_checksum
+            .send_init_ack(&state.transport.ext_checksum)
+            .await
+            .map_err(|e| (e, Some(close::reason::GENERIC)))?;
+
         // Create the cookie
         let cookie_nonce: u64 = zasynclock!(self.prng).gen();
         let cookie = Cookie {
+            version: state.transport.version,
             zid: input.other_zid,
             whatami: input.other_whatami,
             resolution: state.transport.resolution,
@@ -329,6 +368,7 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             ext_lowlatency: state.transport.ext_lowlatency,
             #[cfg(feature = "transport_compression")]
             ext_compression: state.link.ext_compression,
+            ext_checksum: state.transport.ext_checksum,
         };
 
         let mut encrypted = vec![];
@@ -348,7 +388,7 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
 
         // Send the message on the link
         let message: TransportMessage = InitAck {
-            version: input.mine_version,
+            version: state.transport.version,
             whatami: input.mine_whatami,
             zid: input.mine_zid,
             resolution: state.transport.resolution,
@@ -360,6 +400,8 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_checksum,
         }
         .into();
 
@@ -436,6 +478,7 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
         // Rebuild the state from the cookie
         let mut state = State {
             transport: StateTransport {
+                version: cookie.version,
                 batch_size: cookie.batch_size,
                 resolution: cookie.resolution,
                 ext_qos: cookie.ext_qos,
@@ -444,6 +487,7 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
                 #[cfg(feature = "shared-memory")]
                 ext_shm: cookie.ext_shm,
                 ext_lowlatency: cookie.ext_lowlatency,
+                ext_checksum: cookie.ext_checksum,
             },
             #[cfg(any(feature = "transport_auth", feature = "transport_compression"))]
             link: StateLink {
@@ -596,6 +640,11 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: false,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: 0,
+            is_checksum: false,
         },
     };
     let mut link = TransportLinkUnicast::new(link, config);
@@ -613,6 +662,7 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
         ext_lowlatency: ext::lowlatency::LowLatencyFsm::new(),
         #[cfg(feature = "transport_compression")]
         ext_compression: ext::compression::CompressionFsm::new(),
+        ext_checksum: ext::checksum::ChecksumFsm::new(),
     };
 
     // Init handshake
@@ -632,6 +682,7 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
     let iack_out = {
         let mut state = State {
             transport: StateTransport {
+                version: manager.config.version,
                 batch_size: manager.config.batch_size.min(batch_size::UNICAST).min(mtu),
                 resolution: manager.config.resolution,
                 ext_qos: ext::qos::StateAccept::new(manager.config.unicast.is_qos),
@@ -646,6 +697,7 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
                 ext_lowlatency: ext::lowlatency::StateAccept::new(
                     manager.config.unicast.is_lowlatency,
                 ),
+                ext_checksum: ext::checksum::StateAccept::new(manager.config.unicast.is_checksum),
             },
             #[cfg(any(feature = "transport_auth", feature = "transport_compression"))]
             link: StateLink {
@@ -654,10 +706,11 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
                     .state
                     .unicast
                     .authenticator
-                    .accept(&mut *zasynclock!(manager.prng)),
+                    .accept(&mut *zasynclock!(manager.prng), fsm.link.link.is_secure()),
                 #[cfg(feature = "transport_compression")]
                 ext_compression: ext::compression::StateAccept::new(
                     manager.config.unicast.is_compression,
+                    manager.config.unicast.compression_algorithm,
                 ),
             },
         };
@@ -667,11 +720,11 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
         // from the Cookie received in the OpenSyn.
         let isyn_in = RecvInitSynIn {
             mine_version: manager.config.version,
+            mine_version_min: manager.config.version_min,
         };
         let isyn_out = step!(fsm.recv_init_syn((&mut state, isyn_in)).await);
 
         let iack_in = SendInitAckIn {
-            mine_version: manager.config.version,
             mine_zid: manager.config.zid,
             mine_whatami: manager.config.whatami,
             other_zid: isyn_out.other_zid,
@@ -698,6 +751,7 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
 
     // Initialize the transport
     let config = TransportConfigUnicast {
+        version: state.transport.version,
         zid: osyn_out.other_zid,
         whatami: osyn_out.other_whatami,
         sn_resolution: state.transport.resolution.get(Field::FrameSN),
@@ -708,6 +762,9 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
         #[cfg(feature = "shared-memory")]
         is_shm: state.transport.ext_shm.is_shm(),
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
+        username: zcondfeat!("transport_auth", state.link.ext_auth.username(), None),
+        #[cfg(feature = "auth_token")]
+        token_claims: state.link.ext_auth.token_claims(),
     };
 
     let a_config = TransportLinkUnicastConfig {
@@ -717,6 +774,11 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: state.link.ext_compression.is_compression(),
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: state.link.ext_compression.algorithm(),
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: manager.config.unicast.compression_threshold,
+            is_checksum: state.transport.ext_checksum.is_checksum(),
         },
     };
     let a_link = link.reconfigure(a_config);
@@ -24,6 +24,7 @@ use zenoh_protocol::core::{Resolution, WhatAmI, ZenohId};
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct Cookie {
+    pub(crate) version: u8,
     pub(crate) zid: ZenohId,
     pub(crate) whatami: WhatAmI,
     pub(crate) resolution: Resolution,
@@ -40,6 +41,7 @@ pub(crate) struct Cookie {
     pub(crate) ext_lowlatency: ext::lowlatency::StateAccept,
     #[cfg(feature = "transport_compression")]
     pub(crate) ext_compression: ext::compression::StateAccept,
+    pub(crate) ext_checksum: ext::checksum::StateAccept,
 }
 
 impl<W> WCodec<&Cookie, &mut W> for Zenoh080
@@ -49,6 +51,7 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &Cookie) -> Self::Output {
+        self.write(&mut *writer, x.version)?;
         self.write(&mut *writer, &x.zid)?;
         let wai: u8 = x.whatami.into();
         self.write(&mut *writer, wai)?;
@@ -66,6 +69,7 @@ where
         self.write(&mut *writer, &x.ext_lowlatency)?;
         #[cfg(feature = "transport_compression")]
         self.write(&mut *writer, &x.ext_compression)?;
+        self.write(&mut *writer, &x.ext_checksum)?;
 
         Ok(())
     }
@@ -78,6 +82,7 @@ where
     type Error = DidntRead;
 
     fn read(self, reader: &mut R) -> Result<Cookie, Self::Error> {
+        let version: u8 = self.read(&mut *reader)?;
         let zid: ZenohId = self.read(&mut *reader)?;
         let wai: u8 = self.read(&mut *reader)?;
         let whatami = WhatAmI::try_from(wai).map_err(|_| DidntRead)?;
@@ -96,8 +101,10 @@ where
         let ext_lowlatency: ext::lowlatency::StateAccept = self.read(&mut *reader)?;
         #[cfg(feature = "transport_compression")]
         let ext_compression: ext::compression::StateAccept = self.read(&mut *reader)?;
+        let ext_checksum: ext::checksum::StateAccept = self.read(&mut *reader)?;
 
         let cookie = Cookie {
+            version,
             zid,
             whatami,
             resolution,
@@ -113,6 +120,7 @@ where
             ext_lowlatency,
             #[cfg(feature = "transport_compression")]
             ext_compression,
+            ext_checksum,
         };
 
         Ok(cookie)
@@ -169,6 +177,7 @@ impl Cookie {
         let mut rng = rand::thread_rng();
 
         Self {
+            version: rng.gen(),
             zid: ZenohId::default(),
             whatami: WhatAmI::rand(),
             resolution: Resolution::rand(),
@@ -184,6 +193,7 @@ impl Cookie {
             ext_lowlatency: ext::lowlatency::StateAccept::rand(),
             #[cfg(feature = "transport_compression")]
             ext_compression: ext::compression::StateAccept::rand(),
+            ext_checksum: ext::checksum::StateAccept::rand(),
         }
     }
 }
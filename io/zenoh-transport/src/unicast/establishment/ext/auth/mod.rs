@@ -13,6 +13,8 @@
 //
 #[cfg(feature = "auth_pubkey")]
 pub(crate) mod pubkey;
+#[cfg(feature = "auth_token")]
+pub(crate) mod token;
 #[cfg(feature = "auth_usrpwd")]
 pub(crate) mod usrpwd;
 
@@ -23,6 +25,8 @@ pub use pubkey::*;
 use rand::{CryptoRng, Rng};
 use std::convert::TryInto;
 use std::marker::PhantomData;
+#[cfg(feature = "auth_token")]
+pub use token::*;
 use tokio::sync::{Mutex, RwLock};
 #[cfg(feature = "auth_usrpwd")]
 pub use usrpwd::*;
@@ -46,6 +50,10 @@ pub(crate) mod id {
     pub(crate) const PUBKEY: u8 = 0x1;
     #[cfg(feature = "auth_usrpwd")]
     pub(crate) const USRPWD: u8 = 0x2;
+    #[cfg(feature = "auth_token")]
+    pub(crate) const TOKEN: u8 = 0x3;
+    #[cfg(feature = "auth_usrpwd")]
+    pub(crate) const USRPWD_CLEARTEXT: u8 = 0x4;
 }
 
 #[derive(Debug, Default)]
@@ -54,6 +62,8 @@ pub struct Auth {
     pubkey: Option<RwLock<AuthPubKey>>,
     #[cfg(feature = "auth_usrpwd")]
     usrpwd: Option<RwLock<AuthUsrPwd>>,
+    #[cfg(feature = "auth_token")]
+    token: Option<RwLock<AuthToken>>,
 }
 
 impl Auth {
@@ -67,10 +77,16 @@ impl Auth {
             usrpwd: AuthUsrPwd::from_config(auth.usrpwd())
                 .await?
                 .map(RwLock::new),
+            #[cfg(feature = "auth_token")]
+            token: AuthToken::from_config(auth.token()).await?.map(RwLock::new),
         })
     }
 
-    pub(crate) fn open<R>(&self, #[allow(unused)] prng: &mut R) -> StateOpen
+    pub(crate) fn open<R>(
+        &self,
+        #[allow(unused)] prng: &mut R,
+        #[allow(unused)] is_secure: bool,
+    ) -> StateOpen
     where
         R: Rng + CryptoRng,
     {
@@ -81,11 +97,20 @@ impl Auth {
             usrpwd: self
                 .usrpwd
                 .is_some()
-                .then_some(usrpwd::StateOpen::new(prng)),
+                .then_some(usrpwd::StateOpen::new(prng, is_secure)),
+            #[cfg(feature = "auth_token")]
+            token: self
+                .token
+                .is_some()
+                .then_some(token::StateOpen::new(is_secure)),
         }
     }
 
-    pub(crate) fn accept<R>(&self, #[allow(unused)] prng: &mut R) -> StateAccept
+    pub(crate) fn accept<R>(
+        &self,
+        #[allow(unused)] prng: &mut R,
+        #[allow(unused)] is_secure: bool,
+    ) -> StateAccept
     where
         R: Rng + CryptoRng,
     {
@@ -96,7 +121,12 @@ impl Auth {
             usrpwd: self
                 .usrpwd
                 .is_some()
-                .then_some(usrpwd::StateAccept::new(prng)),
+                .then_some(usrpwd::StateAccept::new(prng, is_secure)),
+            #[cfg(feature = "auth_token")]
+            token: self
+                .token
+                .is_some()
+                .then_some(token::StateAccept::new(is_secure)),
         }
     }
 
@@ -106,6 +136,8 @@ impl Auth {
             pubkey: self.pubkey.as_ref().map(|x| AuthPubKeyFsm::new(x, prng)),
             #[cfg(feature = "auth_usrpwd")]
             usrpwd: self.usrpwd.as_ref().map(AuthUsrPwdFsm::new),
+            #[cfg(feature = "auth_token")]
+            token: self.token.as_ref().map(AuthTokenFsm::new),
             _a: PhantomData,
         }
     }
@@ -119,6 +151,8 @@ impl Auth {
             pubkey: None,
             #[cfg(feature = "auth_usrpwd")]
             usrpwd: None,
+            #[cfg(feature = "auth_token")]
+            token: None,
         }
     }
 
@@ -141,6 +175,16 @@ impl Auth {
     pub fn get_usrpwd(&self) -> Option<&RwLock<AuthUsrPwd>> {
         self.usrpwd.as_ref()
     }
+
+    #[cfg(feature = "auth_token")]
+    pub fn set_token(&mut self, token: Option<AuthToken>) {
+        self.token = token.map(RwLock::new);
+    }
+
+    #[cfg(feature = "auth_token")]
+    pub fn get_token(&self) -> Option<&RwLock<AuthToken>> {
+        self.token.as_ref()
+    }
 }
 
 pub(crate) struct AuthFsm<'a> {
@@ -148,6 +192,8 @@ pub(crate) struct AuthFsm<'a> {
     pubkey: Option<AuthPubKeyFsm<'a>>,
     #[cfg(feature = "auth_usrpwd")]
     usrpwd: Option<AuthUsrPwdFsm<'a>>,
+    #[cfg(feature = "auth_token")]
+    token: Option<AuthTokenFsm<'a>>,
     _a: PhantomData<&'a ()>, // Required only when all auth features are disabled
 }
 
@@ -157,6 +203,8 @@ pub(crate) struct StateOpen {
     pubkey: Option<pubkey::StateOpen>,
     #[cfg(feature = "auth_usrpwd")]
     usrpwd: Option<usrpwd::StateOpen>,
+    #[cfg(feature = "auth_token")]
+    token: Option<token::StateOpen>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -165,9 +213,26 @@ pub(crate) struct StateAccept {
     pubkey: Option<pubkey::StateAccept>,
     #[cfg(feature = "auth_usrpwd")]
     usrpwd: Option<usrpwd::StateAccept>,
+    #[cfg(feature = "auth_token")]
+    token: Option<token::StateAccept>,
 }
 
 impl StateAccept {
+    /// The user authenticated by the usrpwd extension, if any.
+    pub(crate) fn username(&self) -> Option<String> {
+        #[cfg(feature = "auth_usrpwd")]
+        if let Some(user) = self.usrpwd.as_ref().and_then(|x| x.user()) {
+            return Some(String::from_utf8_lossy(user).into_owned());
+        }
+        None
+    }
+
+    /// The claims of the token verified by the token extension, if any.
+    #[cfg(feature = "auth_token")]
+    pub(crate) fn token_claims(&self) -> Option<Claims> {
+        self.token.as_ref().and_then(|x| x.claims()).cloned()
+    }
+
     #[cfg(test)]
     pub(crate) fn rand() -> Self {
         let mut rng = rand::thread_rng();
@@ -176,6 +241,8 @@ impl StateAccept {
             pubkey: rng.gen_bool(0.5).then_some(pubkey::StateAccept::rand()),
             #[cfg(feature = "auth_usrpwd")]
             usrpwd: rng.gen_bool(0.5).then_some(usrpwd::StateAccept::rand()),
+            #[cfg(feature = "auth_token")]
+            token: rng.gen_bool(0.5).then_some(token::StateAccept::rand()),
         }
     }
 }
@@ -210,6 +277,15 @@ where
             }
         }
 
+        #[cfg(feature = "auth_token")]
+        {
+            if let Some(token) = x.token.as_ref() {
+                self.write(&mut wbuf, id::TOKEN)?;
+                self.write(&mut wbuf, token)?;
+                count += 1;
+            }
+        }
+
         self.write(&mut *writer, count)?;
         if !buff.is_empty() {
             let mut rbuf = buff.reader();
@@ -233,6 +309,8 @@ where
         let mut pubkey: Option<pubkey::StateAccept> = None;
         #[cfg(feature = "auth_usrpwd")]
         let mut usrpwd: Option<usrpwd::StateAccept> = None;
+        #[cfg(feature = "auth_token")]
+        let mut token: Option<token::StateAccept> = None;
 
         while count > 0 {
             let e: u8 = self.read(&mut *reader)?;
@@ -245,6 +323,10 @@ where
                 id::USRPWD => {
                     usrpwd = Some(self.read(&mut *reader)?);
                 }
+                #[cfg(feature = "auth_token")]
+                id::TOKEN => {
+                    token = Some(self.read(&mut *reader)?);
+                }
                 _ => return Err(DidntRead),
             }
 
@@ -256,6 +338,8 @@ where
             pubkey,
             #[cfg(feature = "auth_usrpwd")]
             usrpwd,
+            #[cfg(feature = "auth_token")]
+            token,
         };
         Ok(state)
     }
@@ -315,13 +399,30 @@ impl<'a> OpenFsm for &'a AuthFsm<'a> {
         #[cfg(feature = "auth_usrpwd")]
         {
             match (self.usrpwd.as_ref(), state.usrpwd.as_ref()) {
+                (Some(e), Some(s)) => {
+                    let (x, c) = e.send_init_syn(s).await?;
+                    if let Some(x) = x {
+                        exts.push(x.into())
+                    }
+                    if let Some(c) = c {
+                        exts.push(c.into())
+                    }
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid UsrPwd configuration."),
+            }
+        }
+
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_ref()) {
                 (Some(e), Some(s)) => {
                     if let Some(e) = e.send_init_syn(s).await?.take() {
                         exts.push(e.into())
                     }
                 }
                 (None, None) => {}
-                _ => bail!("{S} Invalid UsrPwd configuration."),
+                _ => bail!("{S} Invalid Token configuration."),
             }
         }
 
@@ -370,13 +471,27 @@ impl<'a> OpenFsm for &'a AuthFsm<'a> {
             match (self.usrpwd.as_ref(), state.usrpwd.as_mut()) {
                 (Some(e), Some(s)) => {
                     let x = ztake!(exts, id::USRPWD);
-                    e.recv_init_ack((s, ztryinto!(x, S))).await?;
+                    let c = ztake!(exts, id::USRPWD_CLEARTEXT);
+                    e.recv_init_ack((s, ztryinto!(x, S), ztryinto!(c, S)))
+                        .await?;
                 }
                 (None, None) => {}
                 _ => bail!("{S} Invalid UsrPwd configuration."),
             }
         }
 
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_mut()) {
+                (Some(e), Some(s)) => {
+                    let x = ztake!(exts, id::TOKEN);
+                    e.recv_init_ack((s, ztryinto!(x, S))).await?;
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid Token configuration."),
+            }
+        }
+
         Ok(())
     }
 
@@ -416,6 +531,19 @@ impl<'a> OpenFsm for &'a AuthFsm<'a> {
             }
         }
 
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_ref()) {
+                (Some(e), Some(s)) => {
+                    if let Some(e) = e.send_open_syn(s).await?.take() {
+                        exts.push(e.into())
+                    }
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid Token configuration."),
+            }
+        }
+
         let codec = Zenoh080::new();
         let mut buff = vec![];
         let mut writer = buff.writer();
@@ -468,6 +596,18 @@ impl<'a> OpenFsm for &'a AuthFsm<'a> {
             }
         }
 
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_mut()) {
+                (Some(e), Some(s)) => {
+                    let x = ztake!(exts, id::TOKEN);
+                    e.recv_open_ack((s, ztryinto!(x, S))).await?;
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid Token configuration."),
+            }
+        }
+
         Ok(())
     }
 }
@@ -513,13 +653,27 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
             match (self.usrpwd.as_ref(), state.usrpwd.as_mut()) {
                 (Some(e), Some(s)) => {
                     let x = ztake!(exts, id::USRPWD);
-                    e.recv_init_syn((s, ztryinto!(x, S))).await?;
+                    let c = ztake!(exts, id::USRPWD_CLEARTEXT);
+                    e.recv_init_syn((s, ztryinto!(x, S), ztryinto!(c, S)))
+                        .await?;
                 }
                 (None, None) => {}
                 _ => bail!("{S} Invalid UsrPwd configuration."),
             }
         }
 
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_mut()) {
+                (Some(e), Some(s)) => {
+                    let x = ztake!(exts, id::TOKEN);
+                    e.recv_init_syn((s, ztryinto!(x, S))).await?;
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid Token configuration."),
+            }
+        }
+
         Ok(())
     }
 
@@ -549,13 +703,30 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
         #[cfg(feature = "auth_usrpwd")]
         {
             match (self.usrpwd.as_ref(), state.usrpwd.as_ref()) {
+                (Some(e), Some(s)) => {
+                    let (x, c) = e.send_init_ack(s).await?;
+                    if let Some(x) = x {
+                        exts.push(x.into())
+                    }
+                    if let Some(c) = c {
+                        exts.push(c.into())
+                    }
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid UsrPwd configuration."),
+            }
+        }
+
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_ref()) {
                 (Some(e), Some(s)) => {
                     if let Some(e) = e.send_init_ack(s).await?.take() {
                         exts.push(e.into())
                     }
                 }
                 (None, None) => {}
-                _ => bail!("{S} Invalid UsrPwd configuration."),
+                _ => bail!("{S} Invalid Token configuration."),
             }
         }
 
@@ -611,6 +782,18 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
             }
         }
 
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_mut()) {
+                (Some(e), Some(s)) => {
+                    let x = ztake!(exts, id::TOKEN);
+                    e.recv_open_syn((s, ztryinto!(x, S))).await?;
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid Token configuration."),
+            }
+        }
+
         Ok(())
     }
 
@@ -650,6 +833,19 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
             }
         }
 
+        #[cfg(feature = "auth_token")]
+        {
+            match (self.token.as_ref(), state.token.as_ref()) {
+                (Some(e), Some(s)) => {
+                    if let Some(e) = e.send_open_ack(s).await?.take() {
+                        exts.push(e.into())
+                    }
+                }
+                (None, None) => {}
+                _ => bail!("{S} Invalid Token configuration."),
+            }
+        }
+
         let codec = Zenoh080::new();
         let mut buff = vec![];
         let mut writer = buff.writer();
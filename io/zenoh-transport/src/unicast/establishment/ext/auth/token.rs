@@ -0,0 +1,537 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::unicast::establishment::{ext::auth::id, AcceptFsm, OpenFsm};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::{hmac, signature};
+use rsa::{
+    pkcs1::{DecodeRsaPublicKey, EncodeRsaPublicKey},
+    pkcs8::DecodePublicKey,
+    RsaPublicKey,
+};
+use serde_json::{Map, Value};
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::RwLock;
+use zenoh_buffers::{
+    reader::{DidntRead, HasReader, Reader},
+    writer::{DidntWrite, Writer},
+};
+use zenoh_codec::{RCodec, WCodec, Zenoh080};
+use zenoh_config::TokenConf;
+use zenoh_core::{bail, zasyncread, zerror, Error as ZError, Result as ZResult};
+use zenoh_protocol::common::{ZExtUnit, ZExtZBuf};
+
+mod ext {
+    use super::{id::TOKEN, ZExtUnit, ZExtZBuf};
+    use zenoh_protocol::{zextunit, zextzbuf};
+
+    pub(super) type InitSyn = zextunit!(TOKEN, false);
+    pub(super) type InitAck = zextunit!(TOKEN, false);
+    pub(super) type OpenSyn = zextzbuf!(TOKEN, false);
+    pub(super) type OpenAck = zextunit!(TOKEN, false);
+}
+
+// Authenticator
+pub type Claims = Map<String, Value>;
+
+enum TokenKey {
+    // HS256
+    Secret(hmac::Key),
+    // RS256, the PKCS#1 DER encoding of the public key
+    PublicKey(Vec<u8>),
+}
+
+pub struct AuthToken {
+    token: Option<String>,
+    key: Option<TokenKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl AuthToken {
+    pub fn new(token: Option<String>) -> Self {
+        Self {
+            token,
+            key: None,
+            issuer: None,
+            audience: None,
+        }
+    }
+
+    /// Verify the received tokens with a shared secret (HS256).
+    pub fn set_secret(&mut self, secret: &[u8]) {
+        self.key = Some(TokenKey::Secret(hmac::Key::new(hmac::HMAC_SHA256, secret)));
+    }
+
+    /// Verify the received tokens with a PEM RSA public key (RS256).
+    pub fn set_public_key_pem(&mut self, pem: &str) -> ZResult<()> {
+        let key = RsaPublicKey::from_public_key_pem(pem)
+            .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+            .map_err(|e| zerror!("Invalid RSA public key: {}.", e))?;
+        let der = key
+            .to_pkcs1_der()
+            .map_err(|e| zerror!("Invalid RSA public key: {}.", e))?;
+        self.key = Some(TokenKey::PublicKey(der.as_bytes().to_vec()));
+        Ok(())
+    }
+
+    pub fn set_issuer(&mut self, issuer: Option<String>) {
+        self.issuer = issuer;
+    }
+
+    pub fn set_audience(&mut self, audience: Option<String>) {
+        self.audience = audience;
+    }
+
+    pub async fn from_config(config: &TokenConf) -> ZResult<Option<Self>> {
+        const S: &str = "Token extension - From config.";
+
+        let mut token = config.token().as_ref().map(|t| t.trim().to_owned());
+        if let Some(path) = config.token_file() {
+            let content = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| zerror!("{S} Invalid token file: {}.", e))?;
+            token = Some(content.trim().to_owned());
+        }
+        if token.as_ref().is_some_and(|t| t.is_empty()) {
+            bail!("{S} Empty token.");
+        }
+
+        let mut auth = Self::new(token);
+        if let Some(secret) = config.secret() {
+            if secret.is_empty() {
+                bail!("{S} Empty secret.");
+            }
+            auth.set_secret(secret.as_bytes());
+        }
+        if let Some(path) = config.public_key_file() {
+            let pem = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| zerror!("{S} Invalid public key file: {}.", e))?;
+            auth.set_public_key_pem(&pem)
+                .map_err(|e| zerror!("{S} {}", e))?;
+        }
+        auth.set_issuer(config.issuer().clone());
+        auth.set_audience(config.audience().clone());
+
+        if auth.token.is_some() || auth.key.is_some() {
+            tracing::debug!("{S} Token authentication is enabled.");
+            Ok(Some(auth))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Verify the signature and the claims of a token, returning its claims if it is valid.
+    pub fn verify(&self, token: &str) -> ZResult<Claims> {
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| zerror!("No key to verify the token."))?;
+
+        let mut parts = token.split('.');
+        let (header, payload, sig) = match (parts.next(), parts.next(), parts.next(), parts.next())
+        {
+            (Some(h), Some(p), Some(s), None) => (h, p, s),
+            _ => bail!("Malformed token."),
+        };
+        let decode = |part: &str| {
+            URL_SAFE_NO_PAD
+                .decode(part.trim_end_matches('='))
+                .map_err(|_| zerror!("Malformed token."))
+        };
+
+        let header: Claims =
+            serde_json::from_slice(&decode(header)?).map_err(|_| zerror!("Malformed header."))?;
+        let signed = &token.as_bytes()[..token.len() - sig.len() - 1];
+        let sig = decode(sig)?;
+        match (header.get("alg").and_then(Value::as_str), key) {
+            (Some("HS256"), TokenKey::Secret(key)) => {
+                hmac::verify(key, signed, &sig).map_err(|_| zerror!("Invalid signature."))?
+            }
+            (Some("RS256"), TokenKey::PublicKey(key)) => {
+                signature::UnparsedPublicKey::new(&signature::RSA_PKCS1_2048_8192_SHA256, key)
+                    .verify(signed, &sig)
+                    .map_err(|_| zerror!("Invalid signature."))?
+            }
+            (alg, _) => bail!("Unsupported algorithm: {:?}.", alg),
+        }
+
+        let claims: Claims =
+            serde_json::from_slice(&decode(payload)?).map_err(|_| zerror!("Malformed claims."))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| zerror!("{}", e))?
+            .as_secs();
+        if let Some(exp) = claims.get("exp") {
+            if exp.as_u64().map_or(true, |exp| now >= exp) {
+                bail!("Expired token.");
+            }
+        }
+        if let Some(nbf) = claims.get("nbf") {
+            if nbf.as_u64().map_or(true, |nbf| now < nbf) {
+                bail!("Token not yet valid.");
+            }
+        }
+        if let Some(issuer) = self.issuer.as_ref() {
+            if claims.get("iss").and_then(Value::as_str) != Some(issuer.as_str()) {
+                bail!("Invalid issuer.");
+            }
+        }
+        if let Some(audience) = self.audience.as_ref() {
+            let valid = match claims.get("aud") {
+                Some(Value::String(aud)) => aud == audience,
+                Some(Value::Array(auds)) => auds.iter().any(|aud| aud.as_str() == Some(audience)),
+                _ => false,
+            };
+            if !valid {
+                bail!("Invalid audience.");
+            }
+        }
+
+        Ok(claims)
+    }
+}
+
+impl fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let token = if self.token.is_some() { "***" } else { "" };
+        let key = match self.key {
+            Some(TokenKey::Secret(_)) => "HS256",
+            Some(TokenKey::PublicKey(_)) => "RS256",
+            None => "",
+        };
+        write!(
+            f,
+            "Token: '{}', Key: '{}', Issuer: {:?}, Audience: {:?}",
+            token, key, self.issuer, self.audience
+        )
+    }
+}
+
+// OpenFsm / AcceptFsm
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct StateOpen {
+    is_secure: bool,
+}
+
+impl StateOpen {
+    pub(crate) const fn new(is_secure: bool) -> Self {
+        Self { is_secure }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct StateAccept {
+    // Whether the link is encrypted and the claims of the verified token,
+    // they are not part of the cookie
+    is_secure: bool,
+    claims: Option<Claims>,
+}
+
+impl StateAccept {
+    pub(crate) const fn new(is_secure: bool) -> Self {
+        Self {
+            is_secure,
+            claims: None,
+        }
+    }
+
+    pub(crate) fn claims(&self) -> Option<&Claims> {
+        self.claims.as_ref()
+    }
+
+    #[cfg(all(test, feature = "test"))]
+    pub(crate) fn rand() -> Self {
+        Self::new(false)
+    }
+}
+
+// Codec
+impl<W> WCodec<&StateAccept, &mut W> for Zenoh080
+where
+    W: Writer,
+{
+    type Output = Result<(), DidntWrite>;
+
+    fn write(self, _writer: &mut W, _x: &StateAccept) -> Self::Output {
+        Ok(())
+    }
+}
+
+impl<R> RCodec<StateAccept, &mut R> for Zenoh080
+where
+    R: Reader,
+{
+    type Error = DidntRead;
+
+    fn read(self, _reader: &mut R) -> Result<StateAccept, Self::Error> {
+        Ok(StateAccept::new(false))
+    }
+}
+
+pub(crate) struct AuthTokenFsm<'a> {
+    inner: &'a RwLock<AuthToken>,
+}
+
+impl<'a> AuthTokenFsm<'a> {
+    pub(super) const fn new(inner: &'a RwLock<AuthToken>) -> Self {
+        Self { inner }
+    }
+}
+
+/*************************************/
+/*             InitSyn               */
+/*************************************/
+//  7 6 5 4 3 2 1 0
+// +-+-+-+-+-+-+-+-+
+// +---------------+
+//
+// ZExtUnit
+//
+// The token is a bearer credential, it is only ever sent and accepted over encrypted links.
+
+/*************************************/
+/*             InitAck               */
+/*************************************/
+//  7 6 5 4 3 2 1 0
+// +-+-+-+-+-+-+-+-+
+// +---------------+
+//
+// ZExtUnit
+
+/*************************************/
+/*             OpenSyn               */
+/*************************************/
+//  7 6 5 4 3 2 1 0
+// +-+-+-+-+-+-+-+-+
+// ~     token     ~
+// +---------------+
+//
+// ZExtZBuf
+
+/*************************************/
+/*             OpenAck               */
+/*************************************/
+//  7 6 5 4 3 2 1 0
+// +-+-+-+-+-+-+-+-+
+// +---------------+
+//
+// ZExtUnit
+
+#[async_trait]
+impl<'a> OpenFsm for &'a AuthTokenFsm<'a> {
+    type Error = ZError;
+
+    type SendInitSynIn = &'a StateOpen;
+    type SendInitSynOut = Option<ext::InitSyn>;
+    async fn send_init_syn(
+        self,
+        state: Self::SendInitSynIn,
+    ) -> Result<Self::SendInitSynOut, Self::Error> {
+        const S: &str = "Token extension - Send InitSyn.";
+
+        if zasyncread!(self.inner).token.is_none() {
+            return Ok(None);
+        }
+        if !state.is_secure {
+            tracing::warn!("{S} The token is not sent over an unencrypted link.");
+            return Ok(None);
+        }
+        Ok(Some(ZExtUnit::new()))
+    }
+
+    type RecvInitAckIn = (&'a mut StateOpen, Option<ext::InitAck>);
+    type RecvInitAckOut = ();
+    async fn recv_init_ack(
+        self,
+        _input: Self::RecvInitAckIn,
+    ) -> Result<Self::RecvInitAckOut, Self::Error> {
+        Ok(())
+    }
+
+    type SendOpenSynIn = &'a StateOpen;
+    type SendOpenSynOut = Option<ext::OpenSyn>;
+    async fn send_open_syn(
+        self,
+        state: Self::SendOpenSynIn,
+    ) -> Result<Self::SendOpenSynOut, Self::Error> {
+        // Never send the token over an unencrypted link
+        if !state.is_secure {
+            return Ok(None);
+        }
+        // If a token is not configured, don't continue the TOKEN authentication
+        let output = zasyncread!(self.inner)
+            .token
+            .as_ref()
+            .map(|token| ZExtZBuf::new(token.as_bytes().to_vec().into()));
+        Ok(output)
+    }
+
+    type RecvOpenAckIn = (&'a mut StateOpen, Option<ext::OpenAck>);
+    type RecvOpenAckOut = ();
+    async fn recv_open_ack(
+        self,
+        _input: Self::RecvOpenAckIn,
+    ) -> Result<Self::RecvOpenAckOut, Self::Error> {
+        // The remote may not verify tokens, hence the OpenAck extension is optional
+        Ok(())
+    }
+}
+
+/*************************************/
+/*            ACCEPT                 */
+/*************************************/
+#[async_trait]
+impl<'a> AcceptFsm for &'a AuthTokenFsm<'a> {
+    type Error = ZError;
+
+    type RecvInitSynIn = (&'a mut StateAccept, Option<ext::InitSyn>);
+    type RecvInitSynOut = ();
+    async fn recv_init_syn(
+        self,
+        input: Self::RecvInitSynIn,
+    ) -> Result<Self::RecvInitSynOut, Self::Error> {
+        const S: &str = "Token extension - Recv InitSyn.";
+
+        let (state, ext_token) = input;
+        if zasyncread!(self.inner).key.is_some() {
+            if !state.is_secure {
+                bail!("{S} Tokens are only accepted over encrypted links.");
+            }
+            if ext_token.is_none() {
+                bail!("{S} Expected extension.");
+            }
+        }
+
+        Ok(())
+    }
+
+    type SendInitAckIn = &'a StateAccept;
+    type SendInitAckOut = Option<ext::InitAck>;
+    async fn send_init_ack(
+        self,
+        _input: Self::SendInitAckIn,
+    ) -> Result<Self::SendInitAckOut, Self::Error> {
+        Ok(None)
+    }
+
+    type RecvOpenSynIn = (&'a mut StateAccept, Option<ext::OpenSyn>);
+    type RecvOpenSynOut = ();
+    async fn recv_open_syn(
+        self,
+        input: Self::RecvOpenSynIn,
+    ) -> Result<Self::RecvOpenSynOut, Self::Error> {
+        const S: &str = "Token extension - Recv OpenSyn.";
+
+        let r_inner = zasyncread!(self.inner);
+        // If no key is configured, the received tokens are not verified
+        if r_inner.key.is_none() {
+            return Ok(());
+        }
+
+        let (state, mut ext_token) = input;
+        let ext_token = ext_token
+            .take()
+            .ok_or_else(|| zerror!("{S} Expected extension."))?;
+
+        let mut reader = ext_token.value.reader();
+        let mut token = vec![0u8; reader.remaining()];
+        reader
+            .read_exact(&mut token)
+            .map_err(|_| zerror!("{S} Decoding error."))?;
+        let token = String::from_utf8(token).map_err(|_| zerror!("{S} Decoding error."))?;
+
+        let claims = r_inner
+            .verify(&token)
+            .map_err(|e| zerror!("{S} Invalid token: {}", e))?;
+        state.claims = Some(claims);
+
+        Ok(())
+    }
+
+    type SendOpenAckIn = &'a StateAccept;
+    type SendOpenAckOut = Option<ext::OpenAck>;
+    async fn send_open_ack(
+        self,
+        state: Self::SendOpenAckIn,
+    ) -> Result<Self::SendOpenAckOut, Self::Error> {
+        Ok(state.claims.is_some().then_some(ZExtUnit::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn authenticator_token_verify() {
+        use super::AuthToken;
+
+        // {"alg":"HS256","typ":"JWT"}
+        const HEADER: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9";
+        fn sign(payload: &str) -> String {
+            use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+            use ring::hmac;
+
+            let payload = URL_SAFE_NO_PAD.encode(payload);
+            let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+            let sig = hmac::sign(&key, format!("{HEADER}.{payload}").as_bytes());
+            format!(
+                "{HEADER}.{payload}.{}",
+                URL_SAFE_NO_PAD.encode(sig.as_ref())
+            )
+        }
+
+        let mut auth = AuthToken::new(None);
+        auth.set_secret(b"secret");
+        auth.set_issuer(Some("idp".to_owned()));
+        auth.set_audience(Some("zenoh".to_owned()));
+
+        // Valid token
+        let claims = auth
+            .verify(&sign(r#"{"sub":"usr1","iss":"idp","aud":["zenoh"]}"#))
+            .unwrap();
+        assert_eq!(claims.get("sub").and_then(|s| s.as_str()), Some("usr1"));
+        // Tampered token
+        let token = sign(r#"{"sub":"usr1","iss":"idp","aud":"zenoh"}"#);
+        let (_, sig) = token.rsplit_once('.').unwrap();
+        let forged = sign(r#"{"sub":"usr2","iss":"idp","aud":"zenoh"}"#);
+        let (signed, _) = forged.rsplit_once('.').unwrap();
+        assert!(auth.verify(&format!("{signed}.{sig}")).is_err());
+        // Wrong issuer and audience
+        assert!(auth
+            .verify(&sign(r#"{"sub":"usr1","iss":"other","aud":"zenoh"}"#))
+            .is_err());
+        assert!(auth
+            .verify(&sign(r#"{"sub":"usr1","iss":"idp","aud":"other"}"#))
+            .is_err());
+        // Expired and not yet valid tokens
+        assert!(auth
+            .verify(&sign(r#"{"sub":"usr1","iss":"idp","aud":"zenoh","exp":1}"#))
+            .is_err());
+        assert!(auth
+            .verify(&sign(
+                r#"{"sub":"usr1","iss":"idp","aud":"zenoh","nbf":99999999999}"#
+            ))
+            .is_err());
+        // Unsigned token
+        assert!(auth
+            .verify("eyJhbGciOiJub25lIn0.eyJzdWIiOiJ1c3IxIn0.")
+            .is_err());
+        // Malformed token
+        assert!(auth.verify("usr1").is_err());
+    }
+}
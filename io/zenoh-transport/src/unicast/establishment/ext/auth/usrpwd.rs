@@ -12,9 +12,15 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::unicast::establishment::{ext::auth::id, AcceptFsm, OpenFsm};
+use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use async_trait::async_trait;
 use rand::{CryptoRng, Rng};
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    time::SystemTime,
+};
 use tokio::sync::RwLock;
 use zenoh_buffers::{
     reader::{DidntRead, HasReader, Reader},
@@ -22,27 +28,106 @@ use zenoh_buffers::{
 };
 use zenoh_codec::{RCodec, WCodec, Zenoh080};
 use zenoh_config::UsrPwdConf;
-use zenoh_core::{bail, zasyncread, zerror, Error as ZError, Result as ZResult};
+use zenoh_core::{bail, zasyncread, zasyncwrite, zerror, Error as ZError, Result as ZResult};
 use zenoh_crypto::hmac;
 use zenoh_protocol::common::{ZExtUnit, ZExtZ64, ZExtZBuf};
 
 mod ext {
-    use super::{id::USRPWD, ZExtUnit, ZExtZ64, ZExtZBuf};
+    use super::{
+        id::{USRPWD, USRPWD_CLEARTEXT},
+        ZExtUnit, ZExtZ64, ZExtZBuf,
+    };
     use zenoh_protocol::{zextunit, zextz64, zextzbuf};
 
     pub(super) type InitSyn = zextunit!(USRPWD, false);
     pub(super) type InitAck = zextz64!(USRPWD, false);
     pub(super) type OpenSyn = zextzbuf!(USRPWD, false);
     pub(super) type OpenAck = zextunit!(USRPWD, false);
+    pub(super) type Cleartext = zextunit!(USRPWD_CLEARTEXT, false);
 }
 
 // Authenticator
 type User = Vec<u8>;
 type Password = Vec<u8>;
 
+const USRPWD_KEY: &[u8] = b"zenoh-usrpwd";
+
+/// The secret stored for a user: either the plaintext password or a salted hash of it
+/// in PHC (argon2) or modular crypt (bcrypt) format.
+#[derive(Clone, PartialEq, Eq)]
+enum Secret {
+    Plain(Password),
+    Argon2(String),
+    Bcrypt(String),
+}
+
+impl Secret {
+    fn is_hash(&self) -> bool {
+        !matches!(self, Self::Plain(_))
+    }
+
+    fn verify(&self, password: &[u8]) -> bool {
+        match self {
+            // Compare the HMACs of the passwords so that the comparison is in constant time
+            Self::Plain(p) => hmac::sign(USRPWD_KEY, password)
+                .map(|h| hmac::verify(USRPWD_KEY, p, &h))
+                .unwrap_or(false),
+            Self::Argon2(h) => PasswordHash::new(h)
+                .map(|h| Argon2::default().verify_password(password, &h).is_ok())
+                .unwrap_or(false),
+            Self::Bcrypt(h) => bcrypt::verify(password, h).unwrap_or(false),
+        }
+    }
+}
+
+impl FromStr for Secret {
+    type Err = ZError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("$argon2") {
+            let hash = PasswordHash::new(s).map_err(|e| zerror!("invalid argon2 hash: {e}"))?;
+            if hash.salt.is_none() || hash.hash.is_none() {
+                bail!("invalid argon2 hash: missing salt or hash");
+            }
+            Ok(Self::Argon2(s.to_owned()))
+        } else if ["$2a$", "$2b$", "$2x$", "$2y$"]
+            .iter()
+            .any(|p| s.starts_with(p))
+        {
+            bcrypt::HashParts::from_str(s).map_err(|e| zerror!("invalid bcrypt hash: {e}"))?;
+            Ok(Self::Bcrypt(s.to_owned()))
+        } else {
+            Ok(Self::Plain(s.as_bytes().to_owned()))
+        }
+    }
+}
+
+struct Dictionary {
+    path: String,
+    // The modification time and the length of the file when it was last read
+    version: Option<(SystemTime, u64)>,
+    users: HashSet<User>,
+}
+
+impl Dictionary {
+    async fn version(path: &str) -> Option<(SystemTime, u64)> {
+        let metadata = tokio::fs::metadata(path).await.ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    // Whether the file has been modified since it was last read, a file that can not be
+    // accessed anymore is not considered modified so that its users are kept
+    async fn is_modified(&self) -> bool {
+        let version = Self::version(&self.path).await;
+        version.is_some() && version != self.version
+    }
+}
+
 pub struct AuthUsrPwd {
-    lookup: HashMap<User, Password>,
+    lookup: HashMap<User, Secret>,
     credentials: Option<(User, Password)>,
+    cleartext: bool,
+    dictionary: Option<Dictionary>,
 }
 
 impl AuthUsrPwd {
@@ -50,11 +135,29 @@ impl AuthUsrPwd {
         Self {
             lookup: HashMap::new(),
             credentials,
+            cleartext: false,
+            dictionary: None,
         }
     }
 
+    /// Allow sending the password itself instead of a challenge response to remotes storing
+    /// salted password hashes. It is only ever done on encrypted links (e.g. TLS or QUIC).
+    pub fn set_cleartext(&mut self, cleartext: bool) {
+        self.cleartext = cleartext;
+    }
+
     pub async fn add_user(&mut self, user: User, password: Password) -> ZResult<()> {
-        self.lookup.insert(user, password);
+        self.lookup.insert(user, Secret::Plain(password));
+        Ok(())
+    }
+
+    /// Add a user whose password is stored as an argon2 (PHC string) or bcrypt hash.
+    pub async fn add_user_hashed(&mut self, user: User, hash: &str) -> ZResult<()> {
+        let secret = Secret::from_str(hash)?;
+        if !secret.is_hash() {
+            bail!("Unsupported password hash format.");
+        }
+        self.lookup.insert(user, secret);
         Ok(())
     }
 
@@ -63,39 +166,88 @@ impl AuthUsrPwd {
         Ok(())
     }
 
+    /// Reload the user-password dictionary file if it has been modified since it was last read.
+    ///
+    /// The users defined by the previous version of the file are replaced by the ones it
+    /// currently defines, users added with [`AuthUsrPwd::add_user`] are left untouched.
+    /// Returns `true` if the dictionary has been reloaded.
+    pub async fn reload(&mut self) -> ZResult<bool> {
+        let Some(dictionary) = self.dictionary.as_mut() else {
+            return Ok(false);
+        };
+        let version = Dictionary::version(&dictionary.path).await;
+        if version.is_none() || version == dictionary.version {
+            return Ok(false);
+        }
+
+        let lookup = Self::read_dictionary(&dictionary.path).await?;
+        for user in dictionary.users.drain() {
+            self.lookup.remove(&user);
+        }
+        dictionary.users = lookup.keys().cloned().collect();
+        dictionary.version = version;
+        self.lookup.extend(lookup);
+        Ok(true)
+    }
+
+    async fn is_dictionary_modified(&self) -> bool {
+        match self.dictionary.as_ref() {
+            Some(dictionary) => dictionary.is_modified().await,
+            None => false,
+        }
+    }
+
+    async fn read_dictionary(path: &str) -> ZResult<HashMap<User, Secret>> {
+        const S: &str = "UsrPwd extension - Read dictionary.";
+
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| zerror!("{S} Invalid user-password dictionary file: {}.", e))?;
+
+        // Populate the user-password dictionary
+        // The config file is expected to be in the form of:
+        //      usr1:pwd1
+        //      usr2:$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>
+        //      usr3:$2b$12$<salt><hash>
+        // I.e.: one <user>:<password> entry per line, where the password is either in plaintext
+        // or an argon2 or bcrypt salted hash
+        let mut lookup: HashMap<User, Secret> = HashMap::new();
+        for l in content.lines() {
+            let line = l.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let idx = line.find(':').ok_or_else(|| {
+                zerror!("{S} Invalid user-password dictionary file: invalid format.")
+            })?;
+            let user = line[..idx].trim().as_bytes().to_owned();
+            if user.is_empty() {
+                bail!("{S} Invalid user-password dictionary file: empty user.")
+            }
+            let password = line[idx + 1..].trim();
+            if password.is_empty() {
+                bail!("{S} Invalid user-password dictionary file: empty password.")
+            }
+            let secret = Secret::from_str(password)
+                .map_err(|e| zerror!("{S} Invalid user-password dictionary file: {e}."))?;
+            lookup.insert(user, secret);
+        }
+        Ok(lookup)
+    }
+
     pub async fn from_config(config: &UsrPwdConf) -> ZResult<Option<Self>> {
         const S: &str = "UsrPwd extension - From config.";
 
-        let mut lookup: HashMap<User, Password> = HashMap::new();
+        let mut lookup: HashMap<User, Secret> = HashMap::new();
+        let mut dictionary: Option<Dictionary> = None;
         if let Some(dict) = config.dictionary_file() {
-            let content = tokio::fs::read_to_string(dict)
-                .await
-                .map_err(|e| zerror!("{S} Invalid user-password dictionary file: {}.", e))?;
-
-            // Populate the user-password dictionary
-            // The config file is expected to be in the form of:
-            //      usr1:pwd1
-            //      usr2:pwd2
-            //      usr3:pwd3
-            // I.e.: one <user>:<password> entry per line
-            for l in content.lines() {
-                let line = l.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                let idx = line.find(':').ok_or_else(|| {
-                    zerror!("{S} Invalid user-password dictionary file: invalid format.")
-                })?;
-                let user = line[..idx].trim().as_bytes().to_owned();
-                if user.is_empty() {
-                    bail!("{S} Invalid user-password dictionary file: empty user.")
-                }
-                let password = line[idx + 1..].trim().as_bytes().to_owned();
-                if password.is_empty() {
-                    bail!("{S} Invalid user-password dictionary file: empty password.")
-                }
-                lookup.insert(user, password);
-            }
+            let version = Dictionary::version(dict).await;
+            lookup = Self::read_dictionary(dict).await?;
+            dictionary = Some(Dictionary {
+                path: dict.clone(),
+                version,
+                users: lookup.keys().cloned().collect(),
+            });
             tracing::debug!("{S} User-password dictionary has been configured.");
         }
 
@@ -112,11 +264,17 @@ impl AuthUsrPwd {
             Ok(Some(Self {
                 lookup,
                 credentials,
+                cleartext: config.cleartext().unwrap_or(false),
+                dictionary,
             }))
         } else {
             Ok(None)
         }
     }
+
+    fn has_hashes(&self) -> bool {
+        self.lookup.values().any(Secret::is_hash)
+    }
 }
 
 impl fmt::Debug for AuthUsrPwd {
@@ -144,34 +302,56 @@ impl fmt::Debug for AuthUsrPwd {
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct StateOpen {
     nonce: u64,
+    is_secure: bool,
+    // Whether the password itself is sent to the remote instead of its HMAC
+    cleartext: bool,
 }
 
 impl StateOpen {
-    pub(crate) fn new<R>(prng: &mut R) -> Self
+    pub(crate) fn new<R>(prng: &mut R, is_secure: bool) -> Self
     where
         R: Rng + CryptoRng,
     {
-        Self { nonce: prng.gen() }
+        Self {
+            nonce: prng.gen(),
+            is_secure,
+            cleartext: false,
+        }
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct StateAccept {
     nonce: u64,
+    cleartext: bool,
+    // Whether the link is encrypted and the authenticated user, they are not part of the cookie
+    is_secure: bool,
+    user: Option<User>,
 }
 
 impl StateAccept {
-    pub(crate) fn new<R>(prng: &mut R) -> Self
+    pub(crate) fn new<R>(prng: &mut R, is_secure: bool) -> Self
     where
         R: Rng + CryptoRng,
     {
-        Self { nonce: prng.gen() }
+        Self {
+            nonce: prng.gen(),
+            cleartext: false,
+            is_secure,
+            user: None,
+        }
+    }
+
+    pub(crate) fn user(&self) -> Option<&User> {
+        self.user.as_ref()
     }
 
     #[cfg(all(test, feature = "test"))]
     pub(crate) fn rand() -> Self {
         let mut rng = rand::thread_rng();
-        Self::new(&mut rng)
+        let mut state = Self::new(&mut rng, false);
+        state.cleartext = rng.gen_bool(0.5);
+        state
     }
 }
 
@@ -183,7 +363,9 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &StateAccept) -> Self::Output {
-        self.write(&mut *writer, x.nonce)
+        self.write(&mut *writer, x.nonce)?;
+        self.write(&mut *writer, u8::from(x.cleartext))?;
+        Ok(())
     }
 }
 
@@ -195,7 +377,13 @@ where
 
     fn read(self, reader: &mut R) -> Result<StateAccept, Self::Error> {
         let nonce: u64 = self.read(&mut *reader)?;
-        Ok(StateAccept { nonce })
+        let cleartext: u8 = self.read(&mut *reader)?;
+        Ok(StateAccept {
+            nonce,
+            cleartext: cleartext != 0,
+            is_secure: false,
+            user: None,
+        })
     }
 }
 
@@ -217,6 +405,9 @@ impl<'a> AuthUsrPwdFsm<'a> {
 /// +---------------+
 ///
 /// ZExtUnit
+///
+/// It is followed by a Cleartext extension if the remote accepts to send its password itself
+/// to nodes storing salted password hashes, which it only does over encrypted links.
 
 /*************************************/
 /*             InitAck               */
@@ -227,6 +418,19 @@ impl<'a> AuthUsrPwdFsm<'a> {
 /// +---------------+
 ///
 /// ZExtZ64
+///
+/// It is followed by a Cleartext extension if the OpenSyn has to carry the password itself
+/// instead of its HMAC, i.e. if the remote stores salted password hashes, the link is
+/// encrypted and the Cleartext extension was present in the InitSyn.
+
+/*************************************/
+/*            Cleartext              */
+/*************************************/
+///  7 6 5 4 3 2 1 0
+/// +-+-+-+-+-+-+-+-+
+/// +---------------+
+///
+/// ZExtUnit
 
 /*************************************/
 /*             OpenSyn               */
@@ -235,13 +439,16 @@ impl<'a> AuthUsrPwdFsm<'a> {
 /// +-+-+-+-+-+-+-+-+
 /// ~     user      ~
 /// +---------------+
-/// ~     hash      ~
+/// ~     proof     ~
 /// +---------------+
 ///
 /// ZExtZBuf
+///
+/// The proof is the HMAC of the password using the nonce as key, or the password itself if
+/// the Cleartext extension was present in the InitAck.
 struct OpenSyn {
     user: Vec<u8>,
-    hmac: Vec<u8>,
+    proof: Vec<u8>,
 }
 
 impl<W> WCodec<&OpenSyn, &mut W> for Zenoh080
@@ -252,7 +459,7 @@ where
 
     fn write(self, writer: &mut W, x: &OpenSyn) -> Self::Output {
         self.write(&mut *writer, x.user.as_slice())?;
-        self.write(&mut *writer, x.hmac.as_slice())?;
+        self.write(&mut *writer, x.proof.as_slice())?;
         Ok(())
     }
 }
@@ -265,8 +472,8 @@ where
 
     fn read(self, reader: &mut R) -> Result<OpenSyn, Self::Error> {
         let user: Vec<u8> = self.read(&mut *reader)?;
-        let hmac: Vec<u8> = self.read(&mut *reader)?;
-        Ok(OpenSyn { user, hmac })
+        let proof: Vec<u8> = self.read(&mut *reader)?;
+        Ok(OpenSyn { user, proof })
     }
 }
 
@@ -284,19 +491,24 @@ impl<'a> OpenFsm for &'a AuthUsrPwdFsm<'a> {
     type Error = ZError;
 
     type SendInitSynIn = &'a StateOpen;
-    type SendInitSynOut = Option<ext::InitSyn>;
+    type SendInitSynOut = (Option<ext::InitSyn>, Option<ext::Cleartext>);
     async fn send_init_syn(
         self,
-        _input: Self::SendInitSynIn,
+        state: Self::SendInitSynIn,
     ) -> Result<Self::SendInitSynOut, Self::Error> {
-        let output = zasyncread!(self.inner)
-            .credentials
-            .is_some()
-            .then_some(ZExtUnit::new());
-        Ok(output)
+        let r_inner = zasyncread!(self.inner);
+        if r_inner.credentials.is_none() {
+            return Ok((None, None));
+        }
+        let cleartext = r_inner.cleartext && state.is_secure;
+        Ok((Some(ZExtUnit::new()), cleartext.then_some(ZExtUnit::new())))
     }
 
-    type RecvInitAckIn = (&'a mut StateOpen, Option<ext::InitAck>);
+    type RecvInitAckIn = (
+        &'a mut StateOpen,
+        Option<ext::InitAck>,
+        Option<ext::Cleartext>,
+    );
     type RecvInitAckOut = ();
     async fn recv_init_ack(
         self,
@@ -304,16 +516,25 @@ impl<'a> OpenFsm for &'a AuthUsrPwdFsm<'a> {
     ) -> Result<Self::RecvInitAckOut, Self::Error> {
         const S: &str = "UsrPwd extension - Recv InitSyn.";
 
-        if zasyncread!(self.inner).credentials.is_none() {
+        let r_inner = zasyncread!(self.inner);
+        if r_inner.credentials.is_none() {
             return Ok(());
         };
 
-        let (state, mut ext_userwpd) = input;
+        let (state, mut ext_userwpd, ext_cleartext) = input;
         let ext_usrpwd = ext_userwpd
             .take()
             .ok_or_else(|| zerror!("{S} Decoding error."))?;
         state.nonce = ext_usrpwd.value;
 
+        // Never send the password itself unless it was agreed to do so over an encrypted link
+        if ext_cleartext.is_some() {
+            if !(r_inner.cleartext && state.is_secure) {
+                bail!("{S} Unexpected cleartext password request.");
+            }
+            state.cleartext = true;
+        }
+
         Ok(())
     }
 
@@ -332,13 +553,18 @@ impl<'a> OpenFsm for &'a AuthUsrPwdFsm<'a> {
             None => return Ok(None),
         };
 
-        // Create the HMAC of the password using the nonce received as a key (it's a challenge)
-        let key = state.nonce.to_le_bytes();
-        let hmac = hmac::sign(&key, password).map_err(|_| zerror!("{S} Encoding error."))?;
+        let proof = if state.cleartext {
+            // The remote only knows the hash of the password, it needs the password to verify it
+            password.clone()
+        } else {
+            // Create the HMAC of the password using the nonce received as a key (it's a challenge)
+            let key = state.nonce.to_le_bytes();
+            hmac::sign(&key, password).map_err(|_| zerror!("{S} Encoding error."))?
+        };
         // Create the OpenSyn extension
         let open_syn = OpenSyn {
             user: user.to_vec(),
-            hmac,
+            proof,
         };
         drop(r_inner);
 
@@ -377,7 +603,11 @@ impl<'a> OpenFsm for &'a AuthUsrPwdFsm<'a> {
 impl<'a> AcceptFsm for &'a AuthUsrPwdFsm<'a> {
     type Error = ZError;
 
-    type RecvInitSynIn = (&'a mut StateAccept, Option<ext::InitSyn>);
+    type RecvInitSynIn = (
+        &'a mut StateAccept,
+        Option<ext::InitSyn>,
+        Option<ext::Cleartext>,
+    );
     type RecvInitSynOut = ();
     async fn recv_init_syn(
         self,
@@ -385,21 +615,38 @@ impl<'a> AcceptFsm for &'a AuthUsrPwdFsm<'a> {
     ) -> Result<Self::RecvInitSynOut, Self::Error> {
         const S: &str = "UsrPwd extension - Recv InitSyn.";
 
-        let (_, ext_usrpwd) = input;
+        let (state, ext_usrpwd, ext_cleartext) = input;
         if ext_usrpwd.is_none() {
             bail!("{S} Expected extension.");
         }
 
+        // Pick up any change of the dictionary file before challenging the remote, the write lock
+        // is only taken when the file has been modified
+        if zasyncread!(self.inner).is_dictionary_modified().await {
+            match zasyncwrite!(self.inner).reload().await {
+                Ok(true) => tracing::debug!("{S} User-password dictionary has been reloaded."),
+                Ok(false) => {}
+                Err(e) => tracing::warn!("{S} Failed to reload the user-password dictionary: {e}"),
+            }
+        }
+        // Hashed passwords can only be verified with the password itself, only ask for it if the
+        // remote agreed to send it and the link is encrypted
+        state.cleartext =
+            ext_cleartext.is_some() && state.is_secure && zasyncread!(self.inner).has_hashes();
+
         Ok(())
     }
 
     type SendInitAckIn = &'a StateAccept;
-    type SendInitAckOut = Option<ext::InitAck>;
+    type SendInitAckOut = (Option<ext::InitAck>, Option<ext::Cleartext>);
     async fn send_init_ack(
         self,
         state: Self::SendInitAckIn,
     ) -> Result<Self::SendInitAckOut, Self::Error> {
-        Ok(Some(ZExtZ64::new(state.nonce)))
+        Ok((
+            Some(ZExtZ64::new(state.nonce)),
+            state.cleartext.then_some(ZExtUnit::new()),
+        ))
     }
 
     type RecvOpenSynIn = (&'a mut StateAccept, Option<ext::OpenSyn>);
@@ -421,18 +668,35 @@ impl<'a> AcceptFsm for &'a AuthUsrPwdFsm<'a> {
             .read(&mut reader)
             .map_err(|_| zerror!("{S} Decoding error."))?;
 
-        let r_inner = zasyncread!(self.inner);
-        let pwd = r_inner
+        let secret = zasyncread!(self.inner)
             .lookup
             .get(&open_syn.user)
+            .cloned()
             .ok_or_else(|| zerror!("{S} Invalid user."))?;
 
-        // Create the HMAC of the password using the nonce received as challenge
-        let key = state.nonce.to_le_bytes();
-        let hmac = hmac::sign(&key, pwd).map_err(|_| zerror!("{S} Encoding error."))?;
-        if hmac != open_syn.hmac {
+        let valid = if state.cleartext {
+            // Hash verification is purposely expensive, don't stall the runtime with it
+            let password = open_syn.proof;
+            tokio::task::spawn_blocking(move || secret.verify(&password))
+                .await
+                .map_err(|e| zerror!("{S} {e}"))?
+        } else {
+            // Check the HMAC of the password using the nonce sent as challenge
+            let pwd = match secret {
+                Secret::Plain(pwd) => pwd,
+                _ => bail!(
+                    "{S} The password of user '{}' is hashed: it can only be verified if the remote \
+                    enables cleartext and connects over an encrypted link.",
+                    String::from_utf8_lossy(&open_syn.user)
+                ),
+            };
+            let key = state.nonce.to_le_bytes();
+            hmac::verify(&key, &pwd, &open_syn.proof)
+        };
+        if !valid {
             bail!("{S} Invalid password.");
         }
+        state.user = Some(open_syn.user);
 
         Ok(())
     }
@@ -504,4 +768,67 @@ mod tests {
 
         inner().await;
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn authenticator_usrpwd_hashed() {
+        use super::AuthUsrPwd;
+        use argon2::{
+            password_hash::{PasswordHasher, SaltString},
+            Argon2,
+        };
+        use std::io::Write;
+        use zenoh_config::UsrPwdConf;
+
+        let f1 = "zenoh-test-auth-usrpwd-hashed.txt";
+        let salt = SaltString::encode_b64(b"zenoh-test-salt").unwrap();
+        let argon2 = Argon2::default()
+            .hash_password(b"pwd1", &salt)
+            .unwrap()
+            .to_string();
+        let bcrypt = bcrypt::hash("pwd2", 4).unwrap();
+
+        let mut config = UsrPwdConf::default();
+        config.set_dictionary_file(Some(f1.to_owned())).unwrap();
+
+        let write = |content: &str| {
+            let mut f = std::fs::File::create(f1).unwrap();
+            writeln!(f, "{content}").unwrap();
+        };
+
+        // Valid hashes
+        write(&format!("usr1:{argon2}\nusr2:{bcrypt}\nusr3:pwd3"));
+        let mut auth = AuthUsrPwd::from_config(&config).await.unwrap().unwrap();
+        assert!(auth.has_hashes());
+        assert!(auth.lookup[b"usr1".as_slice()].verify(b"pwd1"));
+        assert!(!auth.lookup[b"usr1".as_slice()].verify(b"pwd2"));
+        assert!(auth.lookup[b"usr2".as_slice()].verify(b"pwd2"));
+        assert!(!auth.lookup[b"usr2".as_slice()].verify(b"pwd1"));
+        assert!(auth.lookup[b"usr3".as_slice()].verify(b"pwd3"));
+
+        // Nothing changed
+        assert!(!auth.reload().await.unwrap());
+
+        // Reload on change, users added at runtime are kept
+        auth.add_user(b"usr4".to_vec(), b"pwd4".to_vec())
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        assert!(!auth.is_dictionary_modified().await);
+        write("usr3:pwd3");
+        assert!(auth.is_dictionary_modified().await);
+        assert!(auth.reload().await.unwrap());
+        assert!(!auth.is_dictionary_modified().await);
+        assert!(!auth.has_hashes());
+        assert!(!auth.lookup.contains_key(b"usr1".as_slice()));
+        assert!(auth.lookup.contains_key(b"usr3".as_slice()));
+        assert!(auth.lookup.contains_key(b"usr4".as_slice()));
+
+        // Invalid hashes
+        write("usr1:$argon2id$invalid");
+        assert!(AuthUsrPwd::from_config(&config).await.is_err());
+        write("usr1:$2b$invalid");
+        assert!(AuthUsrPwd::from_config(&config).await.is_err());
+
+        let _ = std::fs::remove_file(f1);
+    }
 }
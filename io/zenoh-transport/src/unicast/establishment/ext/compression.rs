@@ -11,7 +11,10 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::unicast::establishment::{AcceptFsm, OpenFsm};
+use crate::{
+    common::batch::CompressionAlgorithm,
+    unicast::establishment::{AcceptFsm, OpenFsm},
+};
 use async_trait::async_trait;
 use core::marker::PhantomData;
 use zenoh_buffers::{
@@ -33,22 +36,61 @@ impl<'a> CompressionFsm<'a> {
     }
 }
 
+// The extensions announcing compression and the supported algorithms
+type InitExts = (
+    Option<init::ext::Compression>,
+    Option<init::ext::CompressionAlgorithms>,
+);
+
+fn init_exts(is_compression: bool) -> InitExts {
+    if is_compression {
+        (
+            Some(init::ext::Compression::new()),
+            Some(init::ext::CompressionAlgorithms::new(
+                CompressionAlgorithm::SUPPORTED,
+            )),
+        )
+    } else {
+        (None, None)
+    }
+}
+
+// Pick the configured algorithm if the other side is able to decompress it, LZ4 otherwise.
+// Older nodes don't announce their algorithms and only decompress LZ4.
+fn negotiate(
+    algorithm: CompressionAlgorithm,
+    other: Option<init::ext::CompressionAlgorithms>,
+) -> CompressionAlgorithm {
+    match other {
+        Some(other) if other.value & algorithm.mask() != 0 => algorithm,
+        _ => CompressionAlgorithm::Lz4,
+    }
+}
+
 /*************************************/
 /*              OPEN                 */
 /*************************************/
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct StateOpen {
     is_compression: bool,
+    algorithm: CompressionAlgorithm,
 }
 
 impl StateOpen {
-    pub(crate) const fn new(is_compression: bool) -> Self {
-        Self { is_compression }
+    pub(crate) const fn new(is_compression: bool, algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            is_compression,
+            algorithm,
+        }
     }
 
     pub(crate) const fn is_compression(&self) -> bool {
         self.is_compression
     }
+
+    pub(crate) const fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
 }
 
 #[async_trait]
@@ -56,25 +98,25 @@ impl<'a> OpenFsm for &'a CompressionFsm<'a> {
     type Error = ZError;
 
     type SendInitSynIn = &'a StateOpen;
-    type SendInitSynOut = Option<init::ext::Compression>;
+    type SendInitSynOut = InitExts;
     async fn send_init_syn(
         self,
         state: Self::SendInitSynIn,
     ) -> Result<Self::SendInitSynOut, Self::Error> {
-        let output = state
-            .is_compression
-            .then_some(init::ext::Compression::new());
-        Ok(output)
+        Ok(init_exts(state.is_compression))
     }
 
-    type RecvInitAckIn = (&'a mut StateOpen, Option<init::ext::Compression>);
+    type RecvInitAckIn = (&'a mut StateOpen, InitExts);
     type RecvInitAckOut = ();
     async fn recv_init_ack(
         self,
         input: Self::RecvInitAckIn,
     ) -> Result<Self::RecvInitAckOut, Self::Error> {
-        let (state, other_ext) = input;
-        state.is_compression &= other_ext.is_some();
+        let (state, (other_ext, other_algorithms)) = input;
+        match other_ext {
+            Some(_) => state.algorithm = negotiate(state.algorithm, other_algorithms),
+            None => state.is_compression = false,
+        }
         Ok(())
     }
 
@@ -103,22 +145,35 @@ impl<'a> OpenFsm for &'a CompressionFsm<'a> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) struct StateAccept {
     is_compression: bool,
+    algorithm: CompressionAlgorithm,
 }
 
 impl StateAccept {
-    pub(crate) const fn new(is_compression: bool) -> Self {
-        Self { is_compression }
+    pub(crate) const fn new(is_compression: bool, algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            is_compression,
+            algorithm,
+        }
     }
 
     pub(crate) const fn is_compression(&self) -> bool {
         self.is_compression
     }
 
+    pub(crate) const fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+
     #[cfg(test)]
     pub(crate) fn rand() -> Self {
         use rand::Rng;
         let mut rng = rand::thread_rng();
-        Self::new(rng.gen_bool(0.5))
+        let algorithm = if rng.gen_bool(0.5) {
+            CompressionAlgorithm::Lz4
+        } else {
+            CompressionAlgorithm::Zstd
+        };
+        Self::new(rng.gen_bool(0.5), algorithm)
     }
 }
 
@@ -132,6 +187,11 @@ where
     fn write(self, writer: &mut W, x: &StateAccept) -> Self::Output {
         let is_compression = u8::from(x.is_compression);
         self.write(&mut *writer, is_compression)?;
+        let algorithm: u8 = match x.algorithm {
+            CompressionAlgorithm::Lz4 => 0,
+            CompressionAlgorithm::Zstd => 1,
+        };
+        self.write(&mut *writer, algorithm)?;
         Ok(())
     }
 }
@@ -145,7 +205,16 @@ where
     fn read(self, reader: &mut R) -> Result<StateAccept, Self::Error> {
         let is_compression: u8 = self.read(&mut *reader)?;
         let is_compression = is_compression == 1;
-        Ok(StateAccept { is_compression })
+        let algorithm: u8 = self.read(&mut *reader)?;
+        let algorithm = match algorithm {
+            0 => CompressionAlgorithm::Lz4,
+            1 => CompressionAlgorithm::Zstd,
+            _ => return Err(DidntRead),
+        };
+        Ok(StateAccept {
+            is_compression,
+            algorithm,
+        })
     }
 }
 
@@ -153,27 +222,27 @@ where
 impl<'a> AcceptFsm for &'a CompressionFsm<'a> {
     type Error = ZError;
 
-    type RecvInitSynIn = (&'a mut StateAccept, Option<init::ext::Compression>);
+    type RecvInitSynIn = (&'a mut StateAccept, InitExts);
     type RecvInitSynOut = ();
     async fn recv_init_syn(
         self,
         input: Self::RecvInitSynIn,
     ) -> Result<Self::RecvInitSynOut, Self::Error> {
-        let (state, other_ext) = input;
-        state.is_compression &= other_ext.is_some();
+        let (state, (other_ext, other_algorithms)) = input;
+        match other_ext {
+            Some(_) => state.algorithm = negotiate(state.algorithm, other_algorithms),
+            None => state.is_compression = false,
+        }
         Ok(())
     }
 
     type SendInitAckIn = &'a StateAccept;
-    type SendInitAckOut = Option<init::ext::Compression>;
+    type SendInitAckOut = InitExts;
     async fn send_init_ack(
         self,
         state: Self::SendInitAckIn,
     ) -> Result<Self::SendInitAckOut, Self::Error> {
-        let output = state
-            .is_compression
-            .then_some(init::ext::Compression::new());
-        Ok(output)
+        Ok(init_exts(state.is_compression))
     }
 
     type RecvOpenSynIn = (&'a mut StateAccept, Option<open::ext::Compression>);
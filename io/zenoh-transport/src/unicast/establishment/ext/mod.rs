@@ -13,6 +13,7 @@
 //
 #[cfg(feature = "transport_auth")]
 pub mod auth;
+pub(crate) mod checksum;
 #[cfg(feature = "transport_compression")]
 pub(crate) mod compression;
 pub(crate) mod lowlatency;
@@ -100,6 +100,13 @@ pub trait AcceptFsm {
 /*************************************/
 /*           FUNCTIONS               */
 /*************************************/
+/// Select the newest protocol version supported by both ends of a link, given the inclusive ranges
+/// of versions supported by each of them.
+pub(super) fn negotiate_version(mine: (u8, u8), other: (u8, u8)) -> Option<u8> {
+    let version = mine.1.min(other.1);
+    (version >= mine.0 && version >= other.0).then_some(version)
+}
+
 pub(super) fn compute_sn(zid1: ZenohId, zid2: ZenohId, resolution: Resolution) -> TransportSn {
     // Create a random yet deterministic initial_sn.
     // In case of multilink it's important that the same initial_sn is used for every connection attempt.
@@ -11,12 +11,14 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+#[cfg(feature = "transport_compression")]
+use crate::common::batch::CompressionAlgorithm;
 #[cfg(feature = "shared-memory")]
 use crate::unicast::shared_memory_unicast::Challenge;
 use crate::{
     common::batch::BatchConfig,
     unicast::{
-        establishment::{compute_sn, ext, OpenFsm},
+        establishment::{compute_sn, ext, negotiate_version, OpenFsm},
         link::{
             LinkUnicastWithOpenAck, TransportLinkUnicast, TransportLinkUnicastConfig,
             TransportLinkUnicastDirection,
@@ -35,8 +37,8 @@ use zenoh_link::LinkUnicast;
 use zenoh_protocol::{
     core::{Field, Resolution, WhatAmI, ZenohId},
     transport::{
-        batch_size, close, BatchSize, Close, InitSyn, OpenSyn, TransportBody, TransportMessage,
-        TransportSn,
+        batch_size, close, init, BatchSize, Close, InitSyn, OpenSyn, TransportBody,
+        TransportMessage, TransportSn,
     },
 };
 use zenoh_result::ZResult;
@@ -44,6 +46,8 @@ use zenoh_result::ZResult;
 type OpenError = (zenoh_result::Error, Option<u8>);
 
 struct StateTransport {
+    version: u8,
+    version_min: u8,
     batch_size: BatchSize,
     resolution: Resolution,
     ext_qos: ext::qos::StateOpen,
@@ -52,6 +56,7 @@ struct StateTransport {
     #[cfg(feature = "shared-memory")]
     ext_shm: ext::shm::StateOpen,
     ext_lowlatency: ext::lowlatency::StateOpen,
+    ext_checksum: ext::checksum::StateOpen,
 }
 
 #[cfg(any(feature = "transport_auth", feature = "transport_compression"))]
@@ -70,7 +75,6 @@ struct State {
 
 // InitSyn
 struct SendInitSynIn {
-    mine_version: u8,
     mine_zid: ZenohId,
     mine_whatami: WhatAmI,
 }
@@ -116,6 +120,7 @@ struct OpenLink<'a> {
     ext_lowlatency: ext::lowlatency::LowLatencyFsm<'a>,
     #[cfg(feature = "transport_compression")]
     ext_compression: ext::compression::CompressionFsm<'a>,
+    ext_checksum: ext::checksum::ChecksumFsm<'a>,
 }
 
 #[async_trait]
@@ -175,17 +180,29 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
         // Extension Compression
-        let ext_compression = zcondfeat!(
+        let (ext_compression, ext_compression_algorithms) = zcondfeat!(
             "transport_compression",
             self.ext_compression
                 .send_init_syn(&state.link.ext_compression)
                 .await
                 .map_err(|e| (e, Some(close::reason::GENERIC)))?,
-            None
+            (None, None)
         );
 
+        // Extension Checksum
+        let ext_checksum = self
+            .ext_checksum
+            .send_init_syn(&state.transport.ext_checksum)
+            .await
+            .map_err(|e| (e, Some(close::reason::GENERIC)))?;
+
+        // Advertise the oldest supported version in the version field so that nodes speaking
+        // an older version accept the InitSyn, and the newest one in the Version extension.
+        let ext_version = (state.transport.version != state.transport.version_min)
+            .then(|| init::ext::Version::new(state.transport.version as u64));
+
         let msg: TransportMessage = InitSyn {
-            version: input.mine_version,
+            version: state.transport.version_min,
             whatami: input.mine_whatami,
             zid: input.mine_zid,
             batch_size: state.transport.batch_size,
@@ -196,6 +213,9 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
             ext_mlink,
             ext_lowlatency,
             ext_compression,
+            ext_compression_algorithms,
+            ext_version,
+            ext_checksum,
         }
         .into();
 
@@ -245,6 +265,25 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
             }
         };
 
+        // Check the version selected by the other node
+        state.transport.version = match negotiate_version(
+            (state.transport.version_min, state.transport.version),
+            (init_ack.version, init_ack.version),
+        ) {
+            Some(version) => version,
+            None => {
+                let e = zerror!(
+                    "Invalid Zenoh version selected on {}: {:#04x}. Supported: {:#04x}..={:#04x}.",
+                    link,
+                    init_ack.version,
+                    state.transport.version_min,
+                    state.transport.version,
+                );
+                tracing::error!("{}", e);
+                return Err((e.into(), Some(close::reason::INVALID)));
+            }
+        };
+
         // Compute the minimum SN resolution
         state.transport.resolution = {
             let mut res = Resolution::default();
@@ -324,7 +363,19 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
         // Extension Compression
         #[cfg(feature = "transport_compression")]
         self.ext_compression
-            .recv_init_ack((&mut state.link.ext_compression, init_ack.ext_compression))
+            .recv_init_ack((
+                &mut state.link.ext_compression,
+                (
+                    init_ack.ext_compression,
+                    init_ack.ext_compression_algorithms,
+                ),
+            ))
+            .await
+            .map_err(|e| (e, Some(close::reason::GENERIC)))?;
+
+        // Extension Checksum
+        self.ext_checksum
+            .recv_init_ack((&mut state.transport.ext_checksum, init_ack.ext_checksum))
             .await
             .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
@@ -514,6 +565,8 @@ impl<'a, 'b: 'a> OpenFsm for &'a mut OpenLink<'b> {
 pub(crate) async fn open_link(
     link: LinkUnicast,
     manager: &TransportManager,
+    resolution: Resolution,
+    batch_size: BatchSize,
 ) -> ZResult<TransportUnicast> {
     let is_streamed = link.is_streamed();
     let config = TransportLinkUnicastConfig {
@@ -523,6 +576,11 @@ pub(crate) async fn open_link(
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: false, // Perform the exchange Init/Open exchange with no compression
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: CompressionAlgorithm::Lz4,
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: 0,
+            is_checksum: false, // Perform the exchange Init/Open exchange with no checksum
         },
     };
     let mut link = TransportLinkUnicast::new(link, config);
@@ -537,16 +595,17 @@ pub(crate) async fn open_link(
         ext_lowlatency: ext::lowlatency::LowLatencyFsm::new(),
         #[cfg(feature = "transport_compression")]
         ext_compression: ext::compression::CompressionFsm::new(),
+        ext_checksum: ext::checksum::ChecksumFsm::new(),
     };
 
     let mut state = State {
         transport: StateTransport {
-            batch_size: manager
-                .config
-                .batch_size
+            version: manager.config.version,
+            version_min: manager.config.version_min,
+            batch_size: batch_size
                 .min(batch_size::UNICAST)
                 .min(link.config.batch.mtu),
-            resolution: manager.config.resolution,
+            resolution,
             ext_qos: ext::qos::StateOpen::new(manager.config.unicast.is_qos),
             #[cfg(feature = "transport_multilink")]
             ext_mlink: manager
@@ -558,6 +617,7 @@ pub(crate) async fn open_link(
             ext_shm: ext::shm::StateOpen::new(manager.config.unicast.is_shm),
 
             ext_lowlatency: ext::lowlatency::StateOpen::new(manager.config.unicast.is_lowlatency),
+            ext_checksum: ext::checksum::StateOpen::new(manager.config.unicast.is_checksum),
         },
         #[cfg(any(feature = "transport_auth", feature = "transport_compression"))]
         link: StateLink {
@@ -566,10 +626,11 @@ pub(crate) async fn open_link(
                 .state
                 .unicast
                 .authenticator
-                .open(&mut *zasynclock!(manager.prng)),
+                .open(&mut *zasynclock!(manager.prng), link.link.is_secure()),
             #[cfg(feature = "transport_compression")]
             ext_compression: ext::compression::StateOpen::new(
                 manager.config.unicast.is_compression,
+                manager.config.unicast.compression_algorithm,
             ),
         },
     };
@@ -588,7 +649,6 @@ pub(crate) async fn open_link(
     }
 
     let isyn_in = SendInitSynIn {
-        mine_version: manager.config.version,
         mine_zid: manager.config.zid,
         mine_whatami: manager.config.whatami,
     };
@@ -611,6 +671,7 @@ pub(crate) async fn open_link(
 
     // Initialize the transport
     let config = TransportConfigUnicast {
+        version: state.transport.version,
         zid: iack_out.other_zid,
         whatami: iack_out.other_whatami,
         sn_resolution: state.transport.resolution.get(Field::FrameSN),
@@ -621,6 +682,9 @@ pub(crate) async fn open_link(
         #[cfg(feature = "shared-memory")]
         is_shm: state.transport.ext_shm.is_shm(),
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
+        username: None,
+        #[cfg(feature = "auth_token")]
+        token_claims: None,
     };
 
     let o_config = TransportLinkUnicastConfig {
@@ -630,6 +694,11 @@ pub(crate) async fn open_link(
             is_streamed,
             #[cfg(feature = "transport_compression")]
             is_compression: state.link.ext_compression.is_compression(),
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: state.link.ext_compression.algorithm(),
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: manager.config.unicast.compression_threshold,
+            is_checksum: state.transport.ext_checksum.is_checksum(),
         },
     };
     let o_link = link.reconfigure(o_config);
@@ -11,11 +11,11 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::common::batch::{BatchConfig, Decode, Encode, Finalize, RBatch, WBatch};
+use crate::common::batch::{
+    BatchConfig, CorruptedBatch, Decode, Encode, Finalize, RBatch, RBatchBuffer, WBatch,
+};
 use std::fmt;
-use std::sync::Arc;
-use zenoh_buffers::{BBuf, ZSlice, ZSliceBuffer};
-use zenoh_core::zcondfeat;
+use zenoh_buffers::BBuf;
 use zenoh_link::{Link, LinkUnicast};
 use zenoh_protocol::transport::{BatchSize, Close, OpenAck, TransportMessage};
 use zenoh_result::{zerror, ZResult};
@@ -60,18 +60,11 @@ impl TransportLinkUnicast {
     pub(crate) fn tx(&self) -> TransportLinkUnicastTx {
         TransportLinkUnicastTx {
             inner: self.clone(),
-            buffer: zcondfeat!(
-                "transport_compression",
-                self.config
-                    .batch
-                    .is_compression
-                    .then_some(BBuf::with_capacity(
-                        lz4_flex::block::get_maximum_output_size(
-                            self.config.batch.max_buffer_size()
-                        ),
-                    )),
-                None
-            ),
+            buffer: self
+                .config
+                .batch
+                .max_support_buffer_size()
+                .map(BBuf::with_capacity),
         }
     }
 
@@ -146,7 +139,8 @@ pub(crate) struct TransportLinkUnicastTx {
 }
 
 impl TransportLinkUnicastTx {
-    pub(crate) async fn send_batch(&mut self, batch: &mut WBatch) -> ZResult<()> {
+    // Returns the number of bytes actually written on the link
+    pub(crate) async fn send_batch(&mut self, batch: &mut WBatch) -> ZResult<usize> {
         const ERR: &str = "Write error on link: ";
 
         // tracing::trace!("WBatch: {:?}", batch);
@@ -155,21 +149,31 @@ impl TransportLinkUnicastTx {
             .finalize(self.buffer.as_mut())
             .map_err(|_| zerror!("{ERR}{self}"))?;
 
-        let bytes = match res {
-            Finalize::Batch => batch.as_slice(),
-            Finalize::Buffer => self
+        let slices: Vec<&[u8]> = match &res {
+            Finalize::Batch => batch.as_slices(),
+            Finalize::Buffer => vec![self
                 .buffer
                 .as_ref()
                 .ok_or_else(|| zerror!("Invalid buffer finalization"))?
-                .as_slice(),
+                .as_slice()],
+            Finalize::Trailer(trailer) => {
+                let mut slices = batch.as_slices();
+                slices.push(trailer);
+                slices
+            }
         };
 
-        // tracing::trace!("WBytes: {:02x?}", bytes);
+        // tracing::trace!("WBytes: {:02x?}", slices);
 
-        // Send the message on the link
-        self.inner.link.write_all(bytes).await?;
+        // Send the message on the link, along with the slices it references and its trailer
+        // without copying them together
+        if let [bytes] = slices[..] {
+            self.inner.link.write_all(bytes).await?;
+        } else {
+            self.inner.link.write_vectored_all(&slices).await?;
+        }
 
-        Ok(())
+        Ok(slices.iter().map(|s| s.len()).sum())
     }
 
     pub(crate) async fn send(&mut self, msg: &TransportMessage) -> ZResult<usize> {
@@ -209,7 +213,7 @@ impl TransportLinkUnicastRx {
     pub async fn recv_batch<C, T>(&mut self, buff: C) -> ZResult<RBatch>
     where
         C: Fn() -> T + Copy,
-        T: ZSliceBuffer + 'static,
+        T: RBatchBuffer,
     {
         const ERR: &str = "Read error from link: ";
 
@@ -234,12 +238,18 @@ impl TransportLinkUnicastRx {
 
         // tracing::trace!("RBytes: {:02x?}", &into.as_slice()[0..end]);
 
-        let buffer = ZSlice::make(Arc::new(into), 0, end)
-            .map_err(|_| zerror!("{ERR}{self}. ZSlice index(es) out of bounds"))?;
+        let buffer = into
+            .into_zslice(0, end)
+            .ok_or_else(|| zerror!("{ERR}{self}. ZSlice index(es) out of bounds"))?;
         let mut batch = RBatch::new(self.batch, buffer);
-        batch
-            .initialize(buff)
-            .map_err(|e| zerror!("{ERR}{self}. {e}."))?;
+        batch.initialize(buff).map_err(|e| {
+            // Let the caller tell a corrupted batch, that can be dropped, from a broken link
+            if e.is::<CorruptedBatch>() {
+                e
+            } else {
+                zerror!("{ERR}{self}. {e}.").into()
+            }
+        })?;
 
         // tracing::trace!("RBatch: {:?}", batch);
 
@@ -288,21 +298,17 @@ impl MaybeOpenAck {
 
     pub(crate) async fn send_open_ack(mut self) -> ZResult<()> {
         if let Some(msg) = self.open_ack {
-            zcondfeat!(
-                "transport_compression",
-                {
-                    // !!! Workaround !!! as the state of the link is set with compression once the OpenSyn is received.
-                    // Here we are disabling the compression just to send the OpenAck (that is not supposed to be compressed).
-                    // Then then we re-enable it, in case it was enabled, after the OpenAck has been sent.
-                    let compression = self.link.inner.config.batch.is_compression;
-                    self.link.inner.config.batch.is_compression = false;
-                    self.link.send(&msg.into()).await?;
-                    self.link.inner.config.batch.is_compression = compression;
-                },
-                {
-                    self.link.send(&msg.into()).await?;
-                }
-            )
+            // !!! Workaround !!! as the state of the link is set with compression and checksum once the OpenSyn is received.
+            // Here we are disabling them just to send the OpenAck (that is not supposed to be compressed nor checksummed).
+            // Then we restore them, in case they were enabled, after the OpenAck has been sent.
+            let batch = self.link.inner.config.batch;
+            #[cfg(feature = "transport_compression")]
+            {
+                self.link.inner.config.batch.is_compression = false;
+            }
+            self.link.inner.config.batch.is_checksum = false;
+            self.link.send(&msg.into()).await?;
+            self.link.inner.config.batch = batch;
         }
         Ok(())
     }
@@ -20,7 +20,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio_util::sync::CancellationToken;
-use zenoh_buffers::{writer::HasWriter, ZSlice};
+use zenoh_buffers::{buffer::Buffer, writer::HasWriter, ZBuf};
 use zenoh_codec::*;
 use zenoh_core::{zasyncread, zasyncwrite};
 use zenoh_link::LinkUnicast;
@@ -28,39 +28,29 @@ use zenoh_protocol::transport::TransportMessageLowLatency;
 use zenoh_protocol::transport::{KeepAlive, TransportBodyLowLatency};
 use zenoh_result::{zerror, ZResult};
 use zenoh_runtime::ZRuntime;
+use zenoh_util::MemorySubsystem;
 
 pub(crate) async fn send_with_link(
     link: &LinkUnicast,
     msg: TransportMessageLowLatency,
     #[cfg(feature = "stats")] stats: &Arc<TransportStats>,
 ) -> ZResult<()> {
-    let len;
     let codec = Zenoh080::new();
+    // The payload of the message is referenced by the serialized message, not copied
+    let mut zbuf = ZBuf::empty();
+    let mut writer = zbuf.writer();
+    codec
+        .write(&mut writer, &msg)
+        .map_err(|_| zerror!("Error serializing message {:?}", msg))?;
+
+    let len = zbuf.len() as u32;
     if link.is_streamed() {
-        let mut buffer = vec![0, 0, 0, 0];
-        let mut writer = buffer.writer();
-        codec
-            .write(&mut writer, &msg)
-            .map_err(|_| zerror!("Error serializing message {:?}", msg))?;
-
-        len = (buffer.len() - 4) as u32;
         let le = len.to_le_bytes();
-
-        buffer[0..4].copy_from_slice(&le);
-
-        link.write_all(&buffer).await?;
+        let mut buffers: Vec<&[u8]> = vec![&le];
+        buffers.extend(zbuf.zslices().map(|s| s.as_slice()));
+        link.write_vectored_all(&buffers).await?;
     } else {
-        let mut buffer = vec![];
-        let mut writer = buffer.writer();
-        codec
-            .write(&mut writer, &msg)
-            .map_err(|_| zerror!("Error serializing message {:?}", msg))?;
-
-        #[cfg(feature = "stats")]
-        {
-            len = buffer.len() as u32;
-        }
-        link.write_all(&buffer).await?;
+        link.write_zbuf_all(&zbuf).await?;
     }
     tracing::trace!("Sent: {:?}", msg);
 
@@ -152,7 +142,7 @@ impl TransportUnicastLowlatency {
             let is_streamed = link_rx.link.is_streamed();
 
             // The pool of buffers
-            let pool = {
+            let (pool, _reservation) = {
                 let mtu = if is_streamed {
                     link_rx.batch.mtu as usize
                 } else {
@@ -162,16 +152,22 @@ impl TransportUnicastLowlatency {
                 if rx_buffer_size % mtu != 0 {
                     n += 1;
                 }
-                zenoh_sync::RecyclingObjectPool::new(n, move || vec![0_u8; mtu].into_boxed_slice())
+                let reservation = c_transport
+                    .manager
+                    .config
+                    .memory_budget
+                    .try_reserve(MemorySubsystem::RxBuffers, n * mtu)
+                    .ok_or_else(|| zerror!("{}: memory budget exceeded by RX buffers", link_rx))?;
+                (zenoh_sync::ZSlicePool::new(n, mtu), reservation)
             };
 
             loop {
                 // Retrieve one buffer
-                let mut buffer = pool.try_take().unwrap_or_else(|| pool.alloc());
+                let mut buffer = pool.take();
 
                 tokio::select! {
                     // Async read from the underlying link
-                    res = tokio::time::timeout(lease, read_with_link(&link_rx, &mut buffer, is_streamed)) => {
+                    res = tokio::time::timeout(lease, read_with_link(&link_rx, buffer.as_mut_slice(), is_streamed)) => {
                         let bytes = res.map_err(|_| zerror!("{}: expired after {} milliseconds", link_rx, lease.as_millis()))??;
 
                         #[cfg(feature = "stats")] {
@@ -180,7 +176,7 @@ impl TransportUnicastLowlatency {
                         }
 
                         // Deserialize all the messages from the current ZBuf
-                        let zslice = ZSlice::make(Arc::new(buffer), 0, bytes).unwrap();
+                        let zslice = buffer.into_zslice(0, bytes).unwrap();
                         c_transport.read_messages(zslice, &link_rx.link).await?;
                     }
 
@@ -230,7 +226,7 @@ async fn keepalive_task(
         tokio::select! {
             _ = interval.tick() => {
                 let keepailve = TransportMessageLowLatency {
-                    body: TransportBodyLowLatency::KeepAlive(KeepAlive),
+                    body: TransportBodyLowLatency::KeepAlive(KeepAlive::default()),
                 };
 
                 let guard = zasyncwrite!(link);
@@ -17,13 +17,13 @@ use crate::{
     unicast::{
         link::{LinkUnicastWithOpenAck, TransportLinkUnicast},
         transport_unicast_inner::{AddLinkResult, TransportUnicastTrait},
-        TransportConfigUnicast,
+        TransportConfigUnicast, TransportLinkMetrics, TransportReassembly,
     },
     TransportManager, TransportPeerEventHandler,
 };
 use async_trait::async_trait;
 use std::sync::{Arc, RwLock as SyncRwLock};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard, RwLock};
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::TaskTracker;
@@ -32,7 +32,7 @@ use zenoh_link::Link;
 use zenoh_protocol::network::NetworkMessage;
 use zenoh_protocol::transport::TransportBodyLowLatency;
 use zenoh_protocol::transport::TransportMessageLowLatency;
-use zenoh_protocol::transport::{Close, TransportSn};
+use zenoh_protocol::transport::{BatchSize, Close, TransportSn};
 use zenoh_protocol::{
     core::{WhatAmI, ZenohId},
     transport::close,
@@ -54,6 +54,8 @@ pub(crate) struct TransportUnicastLowlatency {
     pub(super) callback: Arc<SyncRwLock<Option<Arc<dyn TransportPeerEventHandler>>>>,
     // Mutex for notification
     alive: Arc<AsyncMutex<bool>>,
+    // The time the transport was established
+    established: SystemTime,
     // Transport statistics
     #[cfg(feature = "stats")]
     pub(super) stats: Arc<TransportStats>,
@@ -76,6 +78,7 @@ impl TransportUnicastLowlatency {
             link: Arc::new(RwLock::new(None)),
             callback: Arc::new(SyncRwLock::new(None)),
             alive: Arc::new(AsyncMutex::new(false)),
+            established: SystemTime::now(),
             #[cfg(feature = "stats")]
             stats,
             token: CancellationToken::new(),
@@ -183,6 +186,23 @@ impl TransportUnicastTrait for TransportUnicastLowlatency {
         vec![]
     }
 
+    fn get_link_metrics(&self) -> Vec<TransportLinkMetrics> {
+        // The lowlatency transport has a single link and does not collect its metrics
+        vec![]
+    }
+
+    fn get_batch_size(&self) -> Option<BatchSize> {
+        let handle = tokio::runtime::Handle::current();
+        let guard =
+            tokio::task::block_in_place(|| handle.block_on(async { zasyncread!(self.link) }));
+        guard.as_ref().map(|l| l.config.batch.mtu)
+    }
+
+    fn get_reassemblies(&self) -> Vec<TransportReassembly> {
+        // The lowlatency transport does not fragment messages
+        vec![]
+    }
+
     fn get_zid(&self) -> ZenohId {
         self.config.zid
     }
@@ -191,6 +211,10 @@ impl TransportUnicastTrait for TransportUnicastLowlatency {
         self.config.whatami
     }
 
+    fn get_established(&self) -> SystemTime {
+        self.established
+    }
+
     #[cfg(feature = "shared-memory")]
     fn is_shm(&self) -> bool {
         self.config.is_shm
@@ -220,6 +244,10 @@ impl TransportUnicastTrait for TransportUnicastLowlatency {
         self.internal_schedule(msg)
     }
 
+    async fn flush(&self) {
+        // Messages are written on the link when scheduled
+    }
+
     /*************************************/
     /*               LINK                */
     /*************************************/
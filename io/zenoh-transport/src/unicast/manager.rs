@@ -14,6 +14,8 @@
 #[cfg(feature = "shared-memory")]
 use super::shared_memory_unicast::SharedMemoryUnicast;
 use super::{link::LinkUnicastWithOpenAck, transport_unicast_inner::InitTransportResult};
+#[cfg(feature = "transport_compression")]
+use crate::common::batch::CompressionAlgorithm;
 #[cfg(feature = "transport_auth")]
 use crate::unicast::establishment::ext::auth::Auth;
 #[cfg(feature = "transport_multilink")]
@@ -40,7 +42,7 @@ use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
 use zenoh_config::CompressionUnicastConf;
 #[cfg(feature = "shared-memory")]
 use zenoh_config::SharedMemoryConf;
-use zenoh_config::{Config, LinkTxConf, QoSUnicastConf, TransportUnicastConf};
+use zenoh_config::{Config, LinkTxConf, MultilinkConf, QoSUnicastConf, TransportUnicastConf};
 use zenoh_core::{zasynclock, zcondfeat};
 use zenoh_crypto::PseudoRng;
 use zenoh_link::*;
@@ -49,6 +51,8 @@ use zenoh_protocol::{
     transport::{close, TransportSn},
 };
 use zenoh_result::{bail, zerror, ZResult};
+#[cfg(feature = "shared-memory")]
+use zenoh_shm::{DefaultShmProvider, ShmProvider};
 
 /*************************************/
 /*         TRANSPORT CONFIG          */
@@ -61,12 +65,18 @@ pub struct TransportManagerConfigUnicast {
     pub max_sessions: usize,
     pub is_qos: bool,
     pub is_lowlatency: bool,
+    pub is_checksum: bool,
     #[cfg(feature = "transport_multilink")]
     pub max_links: usize,
+    pub multilink: MultilinkConf,
     #[cfg(feature = "shared-memory")]
     pub is_shm: bool,
     #[cfg(feature = "transport_compression")]
     pub is_compression: bool,
+    #[cfg(feature = "transport_compression")]
+    pub compression_algorithm: CompressionAlgorithm,
+    #[cfg(feature = "transport_compression")]
+    pub compression_threshold: usize,
 }
 
 pub struct TransportManagerStateUnicast {
@@ -106,13 +116,21 @@ pub struct TransportManagerBuilderUnicast {
     pub(super) is_qos: bool,
     #[cfg(feature = "transport_multilink")]
     pub(super) max_links: usize,
+    pub(super) multilink: MultilinkConf,
     #[cfg(feature = "shared-memory")]
     pub(super) is_shm: bool,
+    #[cfg(feature = "shared-memory")]
+    pub(super) shm_provider: Arc<dyn ShmProvider>,
     #[cfg(feature = "transport_auth")]
     pub(super) authenticator: Auth,
     pub(super) is_lowlatency: bool,
+    pub(super) is_checksum: bool,
     #[cfg(feature = "transport_compression")]
     pub(super) is_compression: bool,
+    #[cfg(feature = "transport_compression")]
+    pub(super) compression_algorithm: CompressionAlgorithm,
+    #[cfg(feature = "transport_compression")]
+    pub(super) compression_threshold: usize,
 }
 
 impl TransportManagerBuilderUnicast {
@@ -151,12 +169,22 @@ impl TransportManagerBuilderUnicast {
         self
     }
 
+    pub fn checksum(mut self, is_checksum: bool) -> Self {
+        self.is_checksum = is_checksum;
+        self
+    }
+
     #[cfg(feature = "transport_multilink")]
     pub fn max_links(mut self, max_links: usize) -> Self {
         self.max_links = max_links;
         self
     }
 
+    pub fn multilink(mut self, multilink: MultilinkConf) -> Self {
+        self.multilink = multilink;
+        self
+    }
+
     #[cfg(feature = "transport_auth")]
     pub fn authenticator(mut self, authenticator: Auth) -> Self {
         self.authenticator = authenticator;
@@ -169,12 +197,30 @@ impl TransportManagerBuilderUnicast {
         self
     }
 
+    #[cfg(feature = "shared-memory")]
+    pub fn shm_provider(mut self, shm_provider: Arc<dyn ShmProvider>) -> Self {
+        self.shm_provider = shm_provider;
+        self
+    }
+
     #[cfg(feature = "transport_compression")]
     pub fn compression(mut self, is_compression: bool) -> Self {
         self.is_compression = is_compression;
         self
     }
 
+    #[cfg(feature = "transport_compression")]
+    pub fn compression_algorithm(mut self, compression_algorithm: CompressionAlgorithm) -> Self {
+        self.compression_algorithm = compression_algorithm;
+        self
+    }
+
+    #[cfg(feature = "transport_compression")]
+    pub fn compression_threshold(mut self, compression_threshold: usize) -> Self {
+        self.compression_threshold = compression_threshold;
+        self
+    }
+
     pub async fn from_config(mut self, config: &Config) -> ZResult<TransportManagerBuilderUnicast> {
         self = self.lease(Duration::from_millis(
             *config.transport().link().tx().lease(),
@@ -187,6 +233,8 @@ impl TransportManagerBuilderUnicast {
         self = self.max_sessions(*config.transport().unicast().max_sessions());
         self = self.qos(*config.transport().unicast().qos().enabled());
         self = self.lowlatency(*config.transport().unicast().lowlatency());
+        self = self.checksum(*config.transport().unicast().checksum());
+        self = self.multilink(config.transport().unicast().multilink().clone());
 
         #[cfg(feature = "transport_multilink")]
         {
@@ -202,7 +250,10 @@ impl TransportManagerBuilderUnicast {
         }
         #[cfg(feature = "transport_compression")]
         {
-            self = self.compression(*config.transport().unicast().compression().enabled());
+            let compression = config.transport().unicast().compression();
+            self = self.compression(*compression.enabled());
+            self = self.compression_algorithm((*compression.algorithm()).into());
+            self = self.compression_threshold(*compression.threshold());
         }
 
         Ok(self)
@@ -215,6 +266,9 @@ impl TransportManagerBuilderUnicast {
         if self.is_qos && self.is_lowlatency {
             bail!("'qos' and 'lowlatency' options are incompatible");
         }
+        if self.is_checksum && self.is_lowlatency {
+            bail!("'checksum' and 'lowlatency' options are incompatible");
+        }
 
         let config = TransportManagerConfigUnicast {
             lease: self.lease,
@@ -225,11 +279,17 @@ impl TransportManagerBuilderUnicast {
             is_qos: self.is_qos,
             #[cfg(feature = "transport_multilink")]
             max_links: self.max_links,
+            multilink: self.multilink,
             #[cfg(feature = "shared-memory")]
             is_shm: self.is_shm,
             is_lowlatency: self.is_lowlatency,
+            is_checksum: self.is_checksum,
             #[cfg(feature = "transport_compression")]
             is_compression: self.is_compression,
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: self.compression_algorithm,
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: self.compression_threshold,
         };
 
         let state = TransportManagerStateUnicast {
@@ -239,7 +299,7 @@ impl TransportManagerBuilderUnicast {
             #[cfg(feature = "transport_multilink")]
             multilink: Arc::new(MultiLink::make(prng)?),
             #[cfg(feature = "shared-memory")]
-            shm: Arc::new(SharedMemoryUnicast::make()?),
+            shm: Arc::new(SharedMemoryUnicast::make(self.shm_provider)?),
             #[cfg(feature = "transport_auth")]
             authenticator: Arc::new(self.authenticator),
         };
@@ -269,13 +329,21 @@ impl Default for TransportManagerBuilderUnicast {
             is_qos: *qos.enabled(),
             #[cfg(feature = "transport_multilink")]
             max_links: *transport.max_links(),
+            multilink: transport.multilink().clone(),
             #[cfg(feature = "shared-memory")]
             is_shm: *shm.enabled(),
+            #[cfg(feature = "shared-memory")]
+            shm_provider: Arc::new(DefaultShmProvider),
             #[cfg(feature = "transport_auth")]
             authenticator: Auth::default(),
             is_lowlatency: *transport.lowlatency(),
+            is_checksum: *transport.checksum(),
             #[cfg(feature = "transport_compression")]
             is_compression: *compression.enabled(),
+            #[cfg(feature = "transport_compression")]
+            compression_algorithm: (*compression.algorithm()).into(),
+            #[cfg(feature = "transport_compression")]
+            compression_threshold: *compression.threshold(),
         }
     }
 }
@@ -590,9 +658,10 @@ impl TransportManager {
             "shared-memory",
             {
                 tracing::debug!(
-            "New transport opened between {} and {} - whatami: {}, sn resolution: {:?}, initial sn: {:?}, qos: {}, shm: {}, multilink: {}, lowlatency: {}",
+            "New transport opened between {} and {} - version: {:#04x}, whatami: {}, sn resolution: {:?}, initial sn: {:?}, qos: {}, shm: {}, multilink: {}, lowlatency: {}",
             self.config.zid,
             config.zid,
+            config.version,
             config.whatami,
             config.sn_resolution,
             config.tx_initial_sn,
@@ -604,9 +673,10 @@ impl TransportManager {
             },
             {
                 tracing::debug!(
-            "New transport opened between {} and {} - whatami: {}, sn resolution: {:?}, initial sn: {:?}, qos: {}, multilink: {}, lowlatency: {}",
+            "New transport opened between {} and {} - version: {:#04x}, whatami: {}, sn resolution: {:?}, initial sn: {:?}, qos: {}, multilink: {}, lowlatency: {}",
             self.config.zid,
             config.zid,
+            config.version,
             config.whatami,
             config.sn_resolution,
             config.tx_initial_sn,
@@ -695,10 +765,12 @@ impl TransportManager {
                 .extend(endpoint::Parameters::iter(config))?;
         };
 
+        let (resolution, batch_size) = self.get_endpoint_params(&endpoint)?;
+
         // Create a new link associated by calling the Link Manager
         let link = manager.new_link(endpoint).await?;
         // Open the link
-        super::establishment::open::open_link(link, self).await
+        super::establishment::open::open_link(link, self, resolution, batch_size).await
     }
 
     pub async fn get_transport_unicast(&self, peer: &ZenohId) -> Option<TransportUnicast> {
@@ -714,6 +786,19 @@ impl TransportManager {
             .collect()
     }
 
+    /// Waits until the messages scheduled so far on the unicast transports have been written
+    /// on their links. The messages scheduled afterwards, for example by the other sessions
+    /// sharing the runtime, are not waited for.
+    /// Returns `false` if they could not be flushed within `timeout`.
+    pub async fn flush_unicast(&self, timeout: Duration) -> bool {
+        let transports = zasynclock!(self.state.unicast.transports)
+            .values()
+            .cloned()
+            .collect::<Vec<Arc<dyn TransportUnicastTrait>>>();
+        let flushes = futures::future::join_all(transports.iter().map(|t| t.flush()));
+        tokio::time::timeout(timeout, flushes).await.is_ok()
+    }
+
     pub(super) async fn del_transport_unicast(&self, peer: &ZenohId) -> ZResult<()> {
         zasynclock!(self.state.unicast.transports)
             .remove(peer)
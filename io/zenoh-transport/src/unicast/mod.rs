@@ -27,17 +27,20 @@ pub(crate) mod shared_memory_unicast;
 use self::transport_unicast_inner::TransportUnicastTrait;
 
 use super::{TransportPeer, TransportPeerEventHandler};
+#[cfg(feature = "auth_token")]
+use establishment::ext::auth::Claims;
 #[cfg(feature = "transport_multilink")]
 use establishment::ext::auth::ZPublicKey;
 pub use manager::*;
 use std::fmt;
 use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime};
 use zenoh_core::zcondfeat;
 use zenoh_link::Link;
 use zenoh_protocol::network::NetworkMessage;
 use zenoh_protocol::{
-    core::{Bits, WhatAmI, ZenohId},
-    transport::{close, TransportSn},
+    core::{Bits, Priority, Reliability, WhatAmI, ZenohId},
+    transport::{close, BatchSize, TransportSn},
 };
 use zenoh_result::{zerror, ZResult};
 
@@ -46,6 +49,7 @@ use zenoh_result::{zerror, ZResult};
 /*************************************/
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct TransportConfigUnicast {
+    pub(crate) version: u8,
     pub(crate) zid: ZenohId,
     pub(crate) whatami: WhatAmI,
     pub(crate) sn_resolution: Bits,
@@ -56,6 +60,57 @@ pub(crate) struct TransportConfigUnicast {
     #[cfg(feature = "shared-memory")]
     pub(crate) is_shm: bool,
     pub(crate) is_lowlatency: bool,
+    pub(crate) username: Option<String>,
+    #[cfg(feature = "auth_token")]
+    pub(crate) token_claims: Option<Claims>,
+}
+
+/// The runtime metrics of a link of a [`TransportUnicast`].
+#[derive(Clone, Debug)]
+pub struct TransportLinkMetrics {
+    pub link: Link,
+    /// The number of network messages scheduled on the link.
+    pub tx_msgs: u64,
+    /// The number of network messages dropped by the link because of congestion.
+    pub tx_dropped: u64,
+    /// The number of bytes written on the link.
+    pub tx_bytes: u64,
+    /// The number of network messages received on the link.
+    pub rx_msgs: u64,
+    /// The number of bytes read from the link.
+    pub rx_bytes: u64,
+    /// The number of batches dropped because their checksum did not match, if checksum is
+    /// enabled on the link.
+    pub rx_corrupted: u64,
+    /// The smoothed time taken to write a batch on the link, if any has been written yet.
+    pub latency: Option<Duration>,
+    /// The smoothed round trip time of the link probes, if any has been echoed yet.
+    pub rtt: Option<Duration>,
+    /// The round trip time estimated by the OS, if it maintains one for the link.
+    pub os_rtt: Option<Duration>,
+    /// The ratio of the last link probes that have not been echoed.
+    pub loss: f64,
+    /// The number of segments retransmitted by the OS on the link, if it reports them.
+    pub retransmissions: Option<u64>,
+    /// Whether the link is degraded, in which case it takes messages only if all the links are.
+    pub degraded: bool,
+    /// The average ratio between the size of the batches written on the link and their
+    /// capacity, if any batch has been written yet.
+    pub batch_fill_ratio: Option<f64>,
+    /// The ratio between the size of the batches and the bytes written on the link,
+    /// if compression is enabled on it and any batch has been written yet.
+    pub compression_ratio: Option<f64>,
+}
+
+/// The progress of the reassembly of a fragmented message received on a [`TransportUnicast`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransportReassembly {
+    pub priority: Priority,
+    pub reliability: Reliability,
+    /// The number of bytes received so far.
+    pub received: usize,
+    /// The total size in bytes of the message, if announced by its first fragment.
+    pub total: Option<usize>,
 }
 
 /// [`TransportUnicast`] is the transport handler returned
@@ -115,9 +170,74 @@ impl TransportUnicast {
         Ok(transport.get_links())
     }
 
+    /// The runtime metrics of the links of the transport.
+    /// They are only collected by the universal transport, the lowlatency one returns none.
+    #[inline(always)]
+    pub fn get_link_metrics(&self) -> ZResult<Vec<TransportLinkMetrics>> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_link_metrics())
+    }
+
+    /// The fragmented messages being reassembled on the transport.
+    /// Only the universal transport fragments messages, the lowlatency one returns none.
+    #[inline(always)]
+    pub fn get_reassemblies(&self) -> ZResult<Vec<TransportReassembly>> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_reassemblies())
+    }
+
+    /// The protocol version negotiated on the transport.
+    #[inline(always)]
+    pub fn get_version(&self) -> ZResult<u8> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_config().version)
+    }
+
+    /// The resolution of the frame sequence numbers negotiated on the transport.
+    #[inline(always)]
+    pub fn get_sn_resolution(&self) -> ZResult<Bits> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_config().sn_resolution)
+    }
+
+    /// The smallest batch size negotiated on the links of the transport, if it has any.
+    #[inline(always)]
+    pub fn get_batch_size(&self) -> ZResult<Option<BatchSize>> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_batch_size())
+    }
+
+    /// The user authenticated on the transport, if any.
+    #[inline(always)]
+    pub fn get_username(&self) -> ZResult<Option<String>> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_config().username.clone())
+    }
+
+    /// The claims of the token the remote authenticated with, if any.
+    #[cfg(feature = "auth_token")]
+    #[inline(always)]
+    pub fn get_token_claims(&self) -> ZResult<Option<Claims>> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_config().token_claims.clone())
+    }
+
+    #[inline(always)]
+    pub fn get_established(&self) -> ZResult<SystemTime> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_established())
+    }
+
+    /// Schedule a message for transmission. Messages the peer is not able to decode
+    /// with the negotiated protocol version are silently dropped.
     #[inline(always)]
     pub fn schedule(&self, message: NetworkMessage) -> ZResult<()> {
         let transport = self.get_inner()?;
+        let version = transport.get_config().version;
+        if message.min_version() > version {
+            tracing::trace!("Dropping {} unsupported by version {}", message, version);
+            return Ok(());
+        }
         transport.schedule(message)
     }
 
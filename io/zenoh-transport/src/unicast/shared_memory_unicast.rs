@@ -12,11 +12,12 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use rand::{Rng, SeedableRng};
+use std::sync::Arc;
 use tokio::sync::RwLock;
 use zenoh_core::zerror;
 use zenoh_crypto::PseudoRng;
 use zenoh_result::ZResult;
-use zenoh_shm::{SharedMemoryBuf, SharedMemoryManager, SharedMemoryReader};
+use zenoh_shm::{SharedMemoryBuf, SharedMemoryManager, SharedMemoryReader, ShmProvider};
 
 pub(crate) type Challenge = u64;
 const NAME: &str = "zshm";
@@ -35,13 +36,17 @@ pub(crate) struct SharedMemoryUnicast {
 unsafe impl Sync for SharedMemoryUnicast {}
 
 impl SharedMemoryUnicast {
-    pub fn make() -> ZResult<SharedMemoryUnicast> {
+    pub fn make(provider: Arc<dyn ShmProvider>) -> ZResult<SharedMemoryUnicast> {
         // Create a challenge for session establishment
         let mut prng = PseudoRng::from_entropy();
         let nonce = prng.gen::<Challenge>();
         let size = std::mem::size_of::<Challenge>();
 
-        let mut _manager = SharedMemoryManager::make(format!("{NAME}.{nonce}"), size)?;
+        let mut _manager = SharedMemoryManager::make_with_provider(
+            format!("{NAME}.{nonce}"),
+            size,
+            provider.as_ref(),
+        )?;
 
         let mut challenge = _manager.alloc(size).map_err(|e| zerror!("{e}"))?;
         let slice = unsafe { challenge.as_mut_slice() };
@@ -50,7 +55,7 @@ impl SharedMemoryUnicast {
         let shmauth = SharedMemoryUnicast {
             challenge,
             _manager,
-            reader: RwLock::new(SharedMemoryReader::new()),
+            reader: RwLock::new(SharedMemoryReader::with_provider(provider)),
         };
         Ok(shmauth)
     }
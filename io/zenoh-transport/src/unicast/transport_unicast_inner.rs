@@ -13,17 +13,24 @@
 //
 
 use crate::{
-    unicast::{link::TransportLinkUnicast, TransportConfigUnicast},
+    unicast::{
+        link::TransportLinkUnicast, TransportConfigUnicast, TransportLinkMetrics,
+        TransportReassembly,
+    },
     TransportPeerEventHandler,
 };
 use async_trait::async_trait;
-use std::{fmt::DebugStruct, sync::Arc, time::Duration};
+use std::{
+    fmt::DebugStruct,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 use tokio::sync::MutexGuard as AsyncMutexGuard;
 use zenoh_link::Link;
 use zenoh_protocol::{
     core::{WhatAmI, ZenohId},
     network::NetworkMessage,
-    transport::TransportSn,
+    transport::{BatchSize, TransportSn},
 };
 use zenoh_result::ZResult;
 
@@ -55,6 +62,10 @@ pub(crate) trait TransportUnicastTrait: Send + Sync {
     fn get_whatami(&self) -> WhatAmI;
     fn get_callback(&self) -> Option<Arc<dyn TransportPeerEventHandler>>;
     fn get_links(&self) -> Vec<Link>;
+    fn get_link_metrics(&self) -> Vec<TransportLinkMetrics>;
+    fn get_batch_size(&self) -> Option<BatchSize>;
+    fn get_reassemblies(&self) -> Vec<TransportReassembly>;
+    fn get_established(&self) -> SystemTime;
     #[cfg(feature = "shared-memory")]
     fn is_shm(&self) -> bool;
     fn is_qos(&self) -> bool;
@@ -76,6 +87,9 @@ pub(crate) trait TransportUnicastTrait: Send + Sync {
     /*                TX                 */
     /*************************************/
     fn schedule(&self, msg: NetworkMessage) -> ZResult<()>;
+    /// Waits until the messages scheduled so far have been written on the links, whatever
+    /// the messages scheduled afterwards.
+    async fn flush(&self);
 
     /*************************************/
     /*            TERMINATION            */
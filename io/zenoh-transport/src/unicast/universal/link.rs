@@ -12,25 +12,247 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::transport::TransportUnicastUniversal;
+#[cfg(feature = "stats")]
+use crate::common::stats::TransportStats;
 use crate::{
     common::{
-        batch::{BatchConfig, RBatch},
+        batch::{BatchConfig, CorruptedBatch, RBatch},
         pipeline::{
             TransmissionPipeline, TransmissionPipelineConf, TransmissionPipelineConsumer,
             TransmissionPipelineProducer,
         },
         priority::TransportPriorityTx,
     },
-    unicast::link::{TransportLinkUnicast, TransportLinkUnicastRx, TransportLinkUnicastTx},
+    unicast::{
+        link::{TransportLinkUnicast, TransportLinkUnicastRx, TransportLinkUnicastTx},
+        TransportLinkMetrics,
+    },
 };
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
-use zenoh_buffers::ZSliceBuffer;
-use zenoh_protocol::transport::{KeepAlive, TransportMessage};
+use zenoh_core::{zcondfeat, zlock};
+use zenoh_protocol::core::Priority;
+use zenoh_protocol::transport::{keepalive, KeepAlive, TransportMessage};
 use zenoh_result::{zerror, ZResult};
-use zenoh_sync::{RecyclingObject, RecyclingObjectPool};
-#[cfg(feature = "stats")]
-use {crate::common::stats::TransportStats, std::sync::Arc};
+use zenoh_sync::ZSlicePool;
+use zenoh_util::MemorySubsystem;
+
+// The latency of a link on which no batch has been written yet
+const NO_LATENCY: u64 = u64::MAX;
+// The number of last probes over which the loss of a link is estimated
+const PROBE_WINDOW: u32 = u32::BITS;
+
+// The probing of a link and the thresholds beyond which it is degraded
+struct LinkHealth {
+    probe_interval: Duration,
+    max_lost_probes: u32,
+    max_rtt: Option<Duration>,
+}
+
+#[derive(Default)]
+struct Probes {
+    // The sequence number of the last probe
+    sn: u64,
+    // The time the last probe has been sent, until it is echoed
+    sent: Option<Instant>,
+    // The outcome of the last probes, a set bit for a lost one
+    lost: u32,
+    count: u32,
+    consecutive_lost: u32,
+}
+
+pub(super) struct LinkMetrics {
+    tx_msgs: AtomicU64,
+    tx_dropped: AtomicU64,
+    tx_bytes: AtomicU64,
+    rx_msgs: AtomicU64,
+    rx_bytes: AtomicU64,
+    // The number of batches dropped because of a checksum mismatch
+    rx_corrupted: AtomicU64,
+    // The number of batches written and their cumulated size, before compression
+    tx_batches: AtomicU64,
+    tx_batch_bytes: AtomicU64,
+    batch_size: u16,
+    // The size of the batches before and after compression
+    tx_uncompressed_bytes: AtomicU64,
+    tx_compressed_bytes: AtomicU64,
+    // The smoothed time taken to write a batch, in microseconds
+    latency: AtomicU64,
+    // The smoothed round trip time of the probes, in microseconds
+    rtt: AtomicU64,
+    health: Option<LinkHealth>,
+    probes: Mutex<Probes>,
+    degraded: AtomicBool,
+    // Whether the link is the one selected by the lowest latency policy
+    pub(super) selected: AtomicBool,
+}
+
+impl LinkMetrics {
+    fn new(health: Option<LinkHealth>, batch_size: u16) -> Self {
+        Self {
+            tx_msgs: AtomicU64::new(0),
+            tx_dropped: AtomicU64::new(0),
+            tx_bytes: AtomicU64::new(0),
+            rx_msgs: AtomicU64::new(0),
+            rx_bytes: AtomicU64::new(0),
+            rx_corrupted: AtomicU64::new(0),
+            tx_batches: AtomicU64::new(0),
+            tx_batch_bytes: AtomicU64::new(0),
+            batch_size,
+            tx_uncompressed_bytes: AtomicU64::new(0),
+            tx_compressed_bytes: AtomicU64::new(0),
+            latency: AtomicU64::new(NO_LATENCY),
+            rtt: AtomicU64::new(NO_LATENCY),
+            health,
+            probes: Mutex::new(Probes::default()),
+            degraded: AtomicBool::new(false),
+            selected: AtomicBool::new(false),
+        }
+    }
+
+    pub(super) fn inc_tx_msgs(&self) {
+        self.tx_msgs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn inc_tx_dropped(&self) {
+        self.tx_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn inc_tx_bytes(&self, n: usize) {
+        self.tx_bytes.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn inc_rx_msgs(&self, n: usize) {
+        self.rx_msgs.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    fn inc_rx_bytes(&self, n: usize) {
+        self.rx_bytes.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    fn inc_rx_corrupted(&self) {
+        self.rx_corrupted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn inc_tx_batch(&self, len: usize) {
+        self.tx_batches.fetch_add(1, Ordering::Relaxed);
+        self.tx_batch_bytes.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    fn batch_fill_ratio(&self) -> Option<f64> {
+        match self.tx_batches.load(Ordering::Relaxed) {
+            0 => None,
+            batches => Some(
+                self.tx_batch_bytes.load(Ordering::Relaxed) as f64
+                    / (batches as f64 * self.batch_size as f64),
+            ),
+        }
+    }
+
+    fn inc_tx_compression(&self, uncompressed: usize, compressed: usize) {
+        self.tx_uncompressed_bytes
+            .fetch_add(uncompressed as u64, Ordering::Relaxed);
+        self.tx_compressed_bytes
+            .fetch_add(compressed as u64, Ordering::Relaxed);
+    }
+
+    fn compression_ratio(&self) -> Option<f64> {
+        match self.tx_compressed_bytes.load(Ordering::Relaxed) {
+            0 => None,
+            compressed => {
+                Some(self.tx_uncompressed_bytes.load(Ordering::Relaxed) as f64 / compressed as f64)
+            }
+        }
+    }
+
+    // Only the TX task of the link updates the latency, so the load and store do not race
+    fn update_latency(&self, sample: Duration) {
+        let sample = sample.as_micros().min(NO_LATENCY as u128 - 1) as u64;
+        let latency = match self.latency.load(Ordering::Relaxed) {
+            NO_LATENCY => sample,
+            latency => (7 * latency + sample) / 8,
+        };
+        self.latency.store(latency, Ordering::Relaxed);
+    }
+
+    pub(super) fn latency(&self) -> Option<Duration> {
+        match self.latency.load(Ordering::Relaxed) {
+            NO_LATENCY => None,
+            latency => Some(Duration::from_micros(latency)),
+        }
+    }
+
+    pub(super) fn rtt(&self) -> Option<Duration> {
+        match self.rtt.load(Ordering::Relaxed) {
+            NO_LATENCY => None,
+            rtt => Some(Duration::from_micros(rtt)),
+        }
+    }
+
+    fn loss(&self) -> f64 {
+        let probes = zlock!(self.probes);
+        if probes.count == 0 {
+            0.0
+        } else {
+            probes.lost.count_ones() as f64 / probes.count as f64
+        }
+    }
+
+    pub(super) fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    // Records the outcome of the last probe
+    fn record_probe(&self, probes: &mut Probes, lost: bool) {
+        probes.lost = probes.lost << 1 | lost as u32;
+        probes.count = (probes.count + 1).min(PROBE_WINDOW);
+        if lost {
+            probes.consecutive_lost += 1;
+        } else {
+            probes.consecutive_lost = 0;
+        }
+
+        if let Some(health) = self.health.as_ref() {
+            let degraded = probes.consecutive_lost >= health.max_lost_probes
+                || health
+                    .max_rtt
+                    .zip(self.rtt())
+                    .is_some_and(|(max, rtt)| rtt > max);
+            self.degraded.store(degraded, Ordering::Relaxed);
+        }
+    }
+
+    // Returns the sequence number of a new probe, the previous one is lost if not echoed yet
+    fn next_probe(&self) -> u64 {
+        let mut probes = zlock!(self.probes);
+        if probes.sent.is_some() {
+            self.record_probe(&mut probes, true);
+        }
+        probes.sn = probes.sn.wrapping_add(1);
+        probes.sent = Some(Instant::now());
+        probes.sn
+    }
+
+    pub(super) fn echo(&self, sn: u64) {
+        let mut probes = zlock!(self.probes);
+        // Late echoes of the probes already considered lost are ignored
+        if probes.sn != sn {
+            return;
+        }
+        let Some(sent) = probes.sent.take() else {
+            return;
+        };
+        let sample = sent.elapsed().as_micros().min(NO_LATENCY as u128 - 1) as u64;
+        let rtt = match self.rtt.load(Ordering::Relaxed) {
+            NO_LATENCY => sample,
+            rtt => (7 * rtt + sample) / 8,
+        };
+        self.rtt.store(rtt, Ordering::Relaxed);
+        self.record_probe(&mut probes, false);
+    }
+}
 
 #[derive(Clone)]
 pub(super) struct TransportLinkUnicastUniversal {
@@ -38,6 +260,10 @@ pub(super) struct TransportLinkUnicastUniversal {
     pub(super) link: TransportLinkUnicast,
     // The transmission pipeline
     pub(super) pipeline: TransmissionPipelineProducer,
+    // The runtime metrics
+    pub(super) metrics: Arc<LinkMetrics>,
+    // The indexes of the multilink configuration entries matching the link
+    pub(super) entries: Arc<[usize]>,
     // The task handling substruct
     tracker: TaskTracker,
     token: CancellationToken,
@@ -57,18 +283,60 @@ impl TransportLinkUnicastUniversal {
                 is_streamed: link.link.is_streamed(),
                 #[cfg(feature = "transport_compression")]
                 is_compression: link.config.batch.is_compression,
+                #[cfg(feature = "transport_compression")]
+                compression_algorithm: link.config.batch.compression_algorithm,
+                #[cfg(feature = "transport_compression")]
+                compression_threshold: link.config.batch.compression_threshold,
+                is_checksum: link.config.batch.is_checksum,
             },
             queue_size: transport.manager.config.queue_size,
             wait_before_drop: transport.manager.config.wait_before_drop,
+            drop_first_queue_size: transport.manager.config.drop_first_queue_size,
+            adaptive_wait_before_drop: transport.manager.config.adaptive_wait_before_drop,
             backoff: transport.manager.config.queue_backoff,
+            pacing: transport.manager.config.pacing,
+            batching: transport.manager.config.batching,
         };
 
         // The pipeline
-        let (producer, consumer) = TransmissionPipeline::make(config, priority_tx);
-
+        let (producer, consumer) =
+            TransmissionPipeline::make(config, priority_tx, &transport.manager.config.congestion);
+
+        let multilink = transport.manager.config.unicast.multilink.health();
+        let health = multilink.enabled().then(|| LinkHealth {
+            probe_interval: Duration::from_millis((*multilink.probe_interval()).max(1)),
+            max_lost_probes: *multilink.max_lost_probes(),
+            max_rtt: multilink.max_rtt().map(Duration::from_millis),
+        });
+
+        // The link properties do not change, the matching entries are computed once
+        let interfaces = link.link.get_interface_names();
+        let protocol = link.link.get_dst().protocol();
+        let entries = transport
+            .manager
+            .config
+            .unicast
+            .multilink
+            .links()
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| {
+                e.interfaces
+                    .as_ref()
+                    .map_or(true, |is| is.iter().any(|i| interfaces.contains(i)))
+                    && e.protocols
+                        .as_ref()
+                        .map_or(true, |ps| ps.iter().any(|p| p == protocol.as_str()))
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let metrics = Arc::new(LinkMetrics::new(health, link.config.batch.mtu));
         let result = Self {
             link,
             pipeline: producer,
+            metrics,
+            entries,
             tracker: TaskTracker::new(),
             token: CancellationToken::new(),
         };
@@ -82,15 +350,26 @@ impl TransportLinkUnicastUniversal {
         consumer: TransmissionPipelineConsumer,
         keep_alive: Duration,
     ) {
+        // Without QoS all the batches are sent with the DSCP of the default priority
+        let dscp = transport.manager.config.dscp.map(|dscp| {
+            if transport.config.is_qos {
+                dscp
+            } else {
+                [dscp[Priority::default() as usize]; Priority::NUM]
+            }
+        });
         // Spawn the TX task
         let mut tx = self.link.tx();
         let token = self.token.clone();
+        let metrics = self.metrics.clone();
         let task = async move {
             let res = tx_task(
                 consumer,
                 &mut tx,
                 keep_alive,
+                dscp,
                 token,
+                metrics,
                 #[cfg(feature = "stats")]
                 transport.stats.clone(),
             )
@@ -112,6 +391,7 @@ impl TransportLinkUnicastUniversal {
     pub(super) fn start_rx(&mut self, transport: TransportUnicastUniversal, lease: Duration) {
         let mut rx = self.link.rx();
         let token = self.token.clone();
+        let metrics = self.metrics.clone();
         let task = async move {
             // Start the consume task
             let res = rx_task(
@@ -120,6 +400,7 @@ impl TransportLinkUnicastUniversal {
                 lease,
                 transport.manager.config.link_rx_buffer_size,
                 token,
+                metrics,
             )
             .await;
 
@@ -145,6 +426,27 @@ impl TransportLinkUnicastUniversal {
         self.tracker.spawn_on(task, &zenoh_runtime::ZRuntime::RX);
     }
 
+    pub(super) fn metrics(&self) -> TransportLinkMetrics {
+        let os_stats = self.link.link.get_os_stats();
+        TransportLinkMetrics {
+            link: self.link.link(),
+            tx_msgs: self.metrics.tx_msgs.load(Ordering::Relaxed),
+            tx_dropped: self.metrics.tx_dropped.load(Ordering::Relaxed),
+            tx_bytes: self.metrics.tx_bytes.load(Ordering::Relaxed),
+            rx_msgs: self.metrics.rx_msgs.load(Ordering::Relaxed),
+            rx_bytes: self.metrics.rx_bytes.load(Ordering::Relaxed),
+            rx_corrupted: self.metrics.rx_corrupted.load(Ordering::Relaxed),
+            latency: self.metrics.latency(),
+            rtt: self.metrics.rtt(),
+            os_rtt: os_stats.rtt,
+            loss: self.metrics.loss(),
+            retransmissions: os_stats.retransmissions,
+            degraded: self.metrics.is_degraded(),
+            batch_fill_ratio: self.metrics.batch_fill_ratio(),
+            compression_ratio: self.metrics.compression_ratio(),
+        }
+    }
+
     pub(super) async fn close(self) -> ZResult<()> {
         tracing::trace!("{}: closing", self.link);
 
@@ -164,16 +466,47 @@ async fn tx_task(
     mut pipeline: TransmissionPipelineConsumer,
     link: &mut TransportLinkUnicastTx,
     keep_alive: Duration,
+    dscp: Option<[u8; Priority::NUM]>,
     token: CancellationToken,
+    metrics: Arc<LinkMetrics>,
     #[cfg(feature = "stats")] stats: Arc<TransportStats>,
 ) -> ZResult<()> {
     let mut interval =
         tokio::time::interval_at(tokio::time::Instant::now() + keep_alive, keep_alive);
+    // The probes are only sent when the health checks are enabled
+    let is_probing = metrics.health.is_some();
+    let period = metrics
+        .health
+        .as_ref()
+        .map_or(keep_alive, |h| h.probe_interval);
+    let mut probe_interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    let mut current_dscp = None;
+    // The compression ratio is only measured on the links negotiating compression
+    let is_compression = zcondfeat!(
+        "transport_compression",
+        link.inner.config.batch.is_compression,
+        false
+    );
     loop {
         tokio::select! {
             res = pipeline.pull() => {
                 if let Some((mut batch, priority)) = res {
-                    link.send_batch(&mut batch).await?;
+                    if let Some(dscp) = dscp.map(|dscp| dscp[priority]) {
+                        if current_dscp != Some(dscp) {
+                            if let Err(e) = link.inner.link.set_dscp(dscp) {
+                                tracing::warn!("{}", e);
+                            }
+                            current_dscp = Some(dscp);
+                        }
+                    }
+                    let start = Instant::now();
+                    let n = link.send_batch(&mut batch).await?;
+                    metrics.update_latency(start.elapsed());
+                    metrics.inc_tx_bytes(n);
+                    metrics.inc_tx_batch(batch.len() as usize);
+                    if is_compression {
+                        metrics.inc_tx_compression(batch.len() as usize, n);
+                    }
 
                     #[cfg(feature = "stats")]
                     {
@@ -189,10 +522,13 @@ async fn tx_task(
             }
 
             _ = interval.tick() => {
-                let message: TransportMessage = KeepAlive.into();
+                let message: TransportMessage = KeepAlive::default().into();
 
-                #[allow(unused_variables)] // Used when stats feature is enabled
+                // The keep alives also measure the latency of the links not taking messages
+                let start = Instant::now();
                 let n = link.send(&message).await?;
+                metrics.update_latency(start.elapsed());
+                metrics.inc_tx_bytes(n);
 
                 #[cfg(feature = "stats")]
                 {
@@ -201,6 +537,34 @@ async fn tx_task(
                 }
             }
 
+            _ = probe_interval.tick(), if is_probing => {
+                let was_degraded = metrics.is_degraded();
+                let message: TransportMessage = KeepAlive {
+                    ext_probe: Some(keepalive::ext::Probe::new(metrics.next_probe())),
+                    ext_echo: None,
+                }
+                .into();
+                let n = link.send(&message).await?;
+                metrics.inc_tx_bytes(n);
+
+                #[cfg(feature = "stats")]
+                {
+                    stats.inc_tx_t_msgs(1);
+                    stats.inc_tx_bytes(n);
+                }
+
+                match (was_degraded, metrics.is_degraded()) {
+                    (false, true) => tracing::warn!(
+                        "{}: link degraded (rtt: {:?}, loss: {:.2}), migrating its messages",
+                        link,
+                        metrics.rtt(),
+                        metrics.loss()
+                    ),
+                    (true, false) => tracing::info!("{}: link recovered", link),
+                    _ => {}
+                }
+            }
+
             _ = token.cancelled() => break
         }
     }
@@ -208,9 +572,11 @@ async fn tx_task(
     // Drain the transmission pipeline and write remaining bytes on the wire
     let mut batches = pipeline.drain();
     for (mut b, _) in batches.drain(..) {
-        tokio::time::timeout(keep_alive, link.send_batch(&mut b))
+        let n = tokio::time::timeout(keep_alive, link.send_batch(&mut b))
             .await
             .map_err(|_| zerror!("{}: flush failed after {} ms", link, keep_alive.as_millis()))??;
+        metrics.inc_tx_bytes(n);
+        metrics.inc_tx_batch(b.len() as usize);
 
         #[cfg(feature = "stats")]
         {
@@ -228,19 +594,10 @@ async fn rx_task(
     lease: Duration,
     rx_buffer_size: usize,
     token: CancellationToken,
+    metrics: Arc<LinkMetrics>,
 ) -> ZResult<()> {
-    async fn read<T, F>(
-        link: &mut TransportLinkUnicastRx,
-        pool: &RecyclingObjectPool<T, F>,
-    ) -> ZResult<RBatch>
-    where
-        T: ZSliceBuffer + 'static,
-        F: Fn() -> T,
-        RecyclingObject<T>: ZSliceBuffer,
-    {
-        let batch = link
-            .recv_batch(|| pool.try_take().unwrap_or_else(|| pool.alloc()))
-            .await?;
+    async fn read(link: &mut TransportLinkUnicastRx, pool: &ZSlicePool) -> ZResult<RBatch> {
+        let batch = link.recv_batch(|| pool.take()).await?;
         Ok(batch)
     }
 
@@ -251,19 +608,39 @@ async fn rx_task(
         n += 1;
     }
 
-    let pool = RecyclingObjectPool::new(n, || vec![0_u8; mtu].into_boxed_slice());
+    let _reservation = transport
+        .manager
+        .config
+        .memory_budget
+        .try_reserve(MemorySubsystem::RxBuffers, n * mtu)
+        .ok_or_else(|| zerror!("{}: memory budget exceeded by RX buffers", link))?;
+    let pool = ZSlicePool::new(n, mtu);
     let l = (&link.link).into();
 
     loop {
         tokio::select! {
             batch = tokio::time::timeout(lease, read(link, &pool)) => {
-                let batch = batch.map_err(|_| zerror!("{}: expired after {} milliseconds", link, lease.as_millis()))??;
+                let batch = match batch.map_err(|_| zerror!("{}: expired after {} milliseconds", link, lease.as_millis()))? {
+                    Ok(batch) => batch,
+                    // Drop the corrupted batches, the lost messages are handled as on any lossy link
+                    Err(e) if e.is::<CorruptedBatch>() => {
+                        tracing::debug!("{}: dropping corrupted batch", link);
+                        metrics.inc_rx_corrupted();
+                        #[cfg(feature = "stats")]
+                        {
+                            transport.stats.inc_rx_corrupted(1);
+                        }
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
+                metrics.inc_rx_bytes(2 + batch.len()); // Account for the batch len encoding (16 bits)
                 #[cfg(feature = "stats")]
                 {
 
                     transport.stats.inc_rx_bytes(2 + batch.len()); // Account for the batch len encoding (16 bits)
                 }
-                transport.read_messages(batch, &l)?;
+                transport.read_messages(batch, &l, &metrics)?;
             }
 
             _ = token.cancelled() => break
@@ -11,7 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::transport::TransportUnicastUniversal;
+use super::{link::LinkMetrics, transport::TransportUnicastUniversal};
 use crate::{
     common::{
         batch::{Decode, RBatch},
@@ -26,7 +26,9 @@ use zenoh_link::Link;
 use zenoh_protocol::{
     core::{Priority, Reliability},
     network::NetworkMessage,
-    transport::{Close, Fragment, Frame, KeepAlive, TransportBody, TransportMessage, TransportSn},
+    transport::{
+        keepalive, Close, Fragment, Frame, KeepAlive, TransportBody, TransportMessage, TransportSn,
+    },
 };
 use zenoh_result::{bail, zerror, ZResult};
 
@@ -92,7 +94,9 @@ impl TransportUnicastUniversal {
             Reliability::BestEffort => zlock!(c.best_effort),
         };
 
-        self.verify_sn(sn, &mut guard)?;
+        if !self.verify_sn(reliability, sn, &mut guard)? {
+            return Ok(());
+        }
 
         let callback = zread!(self.callback).clone();
         if let Some(callback) = callback.as_ref() {
@@ -115,6 +119,7 @@ impl TransportUnicastUniversal {
             more,
             sn,
             ext_qos: qos,
+            ext_total,
             payload,
         } = fragment;
 
@@ -135,18 +140,64 @@ impl TransportUnicastUniversal {
             Reliability::BestEffort => zlock!(c.best_effort),
         };
 
-        self.verify_sn(sn, &mut guard)?;
+        if !self.verify_sn(reliability, sn, &mut guard)? {
+            return Ok(());
+        }
+        // Reordered fragments can not be defragmented
+        if reliability == Reliability::BestEffort && guard.sn.get() != sn {
+            tracing::debug!(
+                "Transport: {}. Reordered fragment dropped: {}. Expected: {}.",
+                self.config.zid,
+                sn,
+                guard.defrag.sn.get()
+            );
+            guard.defrag.clear();
+            return Ok(());
+        }
 
-        if guard.defrag.is_empty() {
+        // The first fragment of a message announces its total size
+        if let Some(total) = ext_total {
+            if !guard.defrag.is_empty() {
+                tracing::debug!(
+                    "Transport: {}. Incomplete fragmented message discarded: {:?}.",
+                    self.config.zid,
+                    guard.defrag.progress()
+                );
+            }
+            if let Err(e) = guard.defrag.begin(sn, total.value as usize) {
+                tracing::warn!(
+                    "Transport: {}. Fragmented message aborted: {}",
+                    self.config.zid,
+                    e
+                );
+            }
+        } else if guard.defrag.is_empty() {
             let _ = guard.defrag.sync(sn);
         }
-        guard.defrag.push(sn, payload)?;
+        if let Err(e) = guard.defrag.push(sn, payload) {
+            if !guard.defrag.is_discarding() {
+                return Err(e);
+            }
+            tracing::warn!(
+                "Transport: {}. Fragmented message aborted: {}",
+                self.config.zid,
+                e
+            );
+        }
+        tracing::trace!(
+            "Transport: {}. Reassembly progress: {:?}.",
+            self.config.zid,
+            guard.defrag.progress()
+        );
         if !more {
             // When shared-memory feature is disabled, msg does not need to be mutable
             let msg = guard
                 .defrag
                 .defragment()
-                .ok_or_else(|| zerror!("Transport: {}. Defragmentation error.", self.config.zid))?;
+                .map_err(|e| zerror!("Transport: {}. {}.", self.config.zid, e))?;
+            let Some(msg) = msg else {
+                return Ok(());
+            };
 
             let callback = zread!(self.callback).clone();
             if let Some(callback) = callback.as_ref() {
@@ -165,9 +216,27 @@ impl TransportUnicastUniversal {
 
     fn verify_sn(
         &self,
+        reliability: Reliability,
         sn: TransportSn,
         guard: &mut MutexGuard<'_, TransportChannelRx>,
-    ) -> ZResult<()> {
+    ) -> ZResult<bool> {
+        // Best-effort messages are typically sent over datagram links, where the anti-replay
+        // window both tolerates reordering and prevents captured datagrams from being re-injected
+        if reliability == Reliability::BestEffort {
+            let accepted = guard.accept(sn)?;
+            if !accepted {
+                tracing::debug!(
+                    "Transport: {}. Frame with replayed SN dropped: {}. Latest: {}.",
+                    self.config.zid,
+                    sn,
+                    guard.sn.get()
+                );
+                #[cfg(feature = "stats")]
+                self.stats.inc_rx_t_replayed(1);
+            }
+            return Ok(accepted);
+        }
+
         let precedes = guard.sn.roll(sn)?;
         if !precedes {
             tracing::debug!(
@@ -181,13 +250,49 @@ impl TransportUnicastUniversal {
                 guard.defrag.clear();
             }
             // Keep reading
-            return Ok(());
+            return Ok(true);
         }
 
-        Ok(())
+        Ok(true)
+    }
+
+    fn handle_keepalive(
+        &self,
+        link: &Link,
+        ext_probe: Option<keepalive::ext::Probe>,
+        ext_echo: Option<keepalive::ext::Echo>,
+    ) {
+        if ext_probe.is_none() && ext_echo.is_none() {
+            return;
+        }
+        let Some((pipeline, metrics)) = zread!(self.links)
+            .iter()
+            .find(|tl| tl.link == *link)
+            .map(|tl| (tl.pipeline.clone(), tl.metrics.clone()))
+        else {
+            return;
+        };
+
+        // Echo the probe on the link it has been received from
+        if let Some(probe) = ext_probe {
+            let msg: TransportMessage = KeepAlive {
+                ext_probe: None,
+                ext_echo: Some(keepalive::ext::Echo::new(probe.value)),
+            }
+            .into();
+            pipeline.push_transport_message(msg, Priority::Control);
+        }
+        if let Some(echo) = ext_echo {
+            metrics.echo(echo.value);
+        }
     }
 
-    pub(super) fn read_messages(&self, mut batch: RBatch, link: &Link) -> ZResult<()> {
+    pub(super) fn read_messages(
+        &self,
+        mut batch: RBatch,
+        link: &Link,
+        metrics: &LinkMetrics,
+    ) -> ZResult<()> {
         while !batch.is_empty() {
             let msg: TransportMessage = batch
                 .decode()
@@ -201,12 +306,24 @@ impl TransportUnicastUniversal {
             }
 
             match msg.body {
-                TransportBody::Frame(msg) => self.handle_frame(msg)?,
-                TransportBody::Fragment(fragment) => self.handle_fragment(fragment)?,
+                TransportBody::Frame(msg) => {
+                    metrics.inc_rx_msgs(msg.payload.len());
+                    self.handle_frame(msg)?
+                }
+                TransportBody::Fragment(fragment) => {
+                    // A fragmented message is received along with its last fragment
+                    if !fragment.more {
+                        metrics.inc_rx_msgs(1);
+                    }
+                    self.handle_fragment(fragment)?
+                }
                 TransportBody::Close(Close { reason, session }) => {
                     self.handle_close(link, reason, session)?
                 }
-                TransportBody::KeepAlive(KeepAlive { .. }) => {}
+                TransportBody::KeepAlive(KeepAlive {
+                    ext_probe,
+                    ext_echo,
+                }) => self.handle_keepalive(link, ext_probe, ext_echo),
                 _ => {
                     tracing::debug!(
                         "Transport: {}. Message handling not implemented: {:?}",
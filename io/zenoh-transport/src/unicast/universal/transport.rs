@@ -19,21 +19,21 @@ use crate::{
         link::{LinkUnicastWithOpenAck, TransportLinkUnicastDirection},
         transport_unicast_inner::{AddLinkResult, TransportUnicastTrait},
         universal::link::TransportLinkUnicastUniversal,
-        TransportConfigUnicast,
+        TransportConfigUnicast, TransportLinkMetrics, TransportReassembly,
     },
     TransportManager, TransportPeerEventHandler,
 };
 use async_trait::async_trait;
 use std::fmt::DebugStruct;
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
-use zenoh_core::{zasynclock, zcondfeat, zread, zwrite};
+use zenoh_core::{zasynclock, zcondfeat, zlock, zread, zwrite};
 use zenoh_link::Link;
 use zenoh_protocol::{
-    core::{Priority, WhatAmI, ZenohId},
+    core::{Priority, Reliability, WhatAmI, ZenohId},
     network::NetworkMessage,
-    transport::{close, Close, PrioritySn, TransportMessage, TransportSn},
+    transport::{close, BatchSize, Close, PrioritySn, TransportMessage, TransportSn},
 };
 use zenoh_result::{bail, zerror, ZResult};
 
@@ -65,6 +65,8 @@ pub(crate) struct TransportUnicastUniversal {
     add_link_lock: Arc<AsyncMutex<()>>,
     // Mutex for notification
     pub(super) alive: Arc<AsyncMutex<bool>>,
+    // The time the transport was established
+    established: SystemTime,
     // Transport statistics
     #[cfg(feature = "stats")]
     pub(super) stats: Arc<TransportStats>,
@@ -83,10 +85,14 @@ impl TransportUnicastUniversal {
             priority_tx.push(TransportPriorityTx::make(config.sn_resolution)?);
         }
 
+        // The fragmented messages of the peer are reassembled within a shared limit
+        let peer_budget = manager.config.peer_defrag_budget();
         for _ in 0..Priority::NUM {
             priority_rx.push(TransportPriorityRx::make(
                 config.sn_resolution,
                 manager.config.defrag_buff_size,
+                &manager.config.memory_budget,
+                &peer_budget,
             )?);
         }
 
@@ -110,6 +116,7 @@ impl TransportUnicastUniversal {
             add_link_lock: Arc::new(AsyncMutex::new(())),
             callback: Arc::new(RwLock::new(None)),
             alive: Arc::new(AsyncMutex::new(false)),
+            established: SystemTime::now(),
             #[cfg(feature = "stats")]
             stats,
         });
@@ -324,6 +331,10 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
         self.config.whatami
     }
 
+    fn get_established(&self) -> SystemTime {
+        self.established
+    }
+
     #[cfg(feature = "shared-memory")]
     fn is_shm(&self) -> bool {
         self.config.is_shm
@@ -377,6 +388,40 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
         zread!(self.links).iter().map(|l| l.link.link()).collect()
     }
 
+    fn get_link_metrics(&self) -> Vec<TransportLinkMetrics> {
+        zread!(self.links).iter().map(|l| l.metrics()).collect()
+    }
+
+    fn get_batch_size(&self) -> Option<BatchSize> {
+        zread!(self.links)
+            .iter()
+            .map(|l| l.link.config.batch.mtu)
+            .min()
+    }
+
+    fn get_reassemblies(&self) -> Vec<TransportReassembly> {
+        let mut reassemblies = vec![];
+        for (prio, c) in self.priority_rx.iter().enumerate() {
+            let Ok(priority) = Priority::try_from(prio as u8) else {
+                continue;
+            };
+            for (reliability, channel) in [
+                (Reliability::Reliable, &c.reliable),
+                (Reliability::BestEffort, &c.best_effort),
+            ] {
+                if let Some(progress) = zlock!(channel).defrag.progress() {
+                    reassemblies.push(TransportReassembly {
+                        priority,
+                        reliability,
+                        received: progress.received,
+                        total: progress.total,
+                    });
+                }
+            }
+        }
+        reassemblies
+    }
+
     /*************************************/
     /*                TX                 */
     /*************************************/
@@ -387,6 +432,16 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
         }
     }
 
+    async fn flush(&self) {
+        let points = zread!(self.links)
+            .iter()
+            .map(|l| (l.pipeline.clone(), l.pipeline.flush_point()))
+            .collect::<Vec<_>>();
+        while !points.iter().all(|(p, point)| p.is_flushed(point)) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
     fn add_debug_fields<'a, 'b: 'a, 'c>(
         &self,
         s: &'c mut DebugStruct<'a, 'b>,
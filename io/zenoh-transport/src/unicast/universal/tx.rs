@@ -11,48 +11,162 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::transport::TransportUnicastUniversal;
+use super::{
+    link::{LinkMetrics, TransportLinkUnicastUniversal},
+    transport::TransportUnicastUniversal,
+};
+use std::{sync::atomic::Ordering, time::Duration};
+use zenoh_config::MultilinkPolicyConf;
 use zenoh_core::zread;
-use zenoh_protocol::network::NetworkMessage;
+use zenoh_protocol::{core::Priority, network::NetworkMessage};
+
+// A link whose latency is not lower than the selected one by more than this ratio does not
+// replace it, to avoid reordering the messages by switching links back and forth
+const LATENCY_HYSTERESIS: (u32, u32) = (4, 5);
+
+// The most preferred link, the first established one among the equally preferred ones
+fn active_link(links: &[TransportLinkUnicastUniversal], candidates: &[usize]) -> Option<usize> {
+    candidates
+        .iter()
+        .copied()
+        .min_by_key(|&i| links[i].entries.first().copied().unwrap_or(usize::MAX))
+}
+
+// The link with the lowest latency, switching only to a significantly faster one
+fn lowest_latency_link(
+    links: &[TransportLinkUnicastUniversal],
+    candidates: &[usize],
+) -> Option<usize> {
+    let selected = candidates
+        .iter()
+        .copied()
+        .find(|&i| links[i].metrics.selected.load(Ordering::Relaxed));
+    // The round trip time of the probes measures the links end to end, while the time taken to
+    // write on a link only measures the local socket: the latter only ranks the links when none
+    // of them is probed
+    let latency: fn(&LinkMetrics) -> Option<Duration> =
+        if candidates.iter().any(|&i| links[i].metrics.rtt().is_some()) {
+            LinkMetrics::rtt
+        } else {
+            LinkMetrics::latency
+        };
+    // The links on which nothing has been written yet are measured by their keep alives
+    let lowest = candidates
+        .iter()
+        .copied()
+        .filter_map(|i| latency(&links[i].metrics).map(|l| (i, l)))
+        .min_by_key(|(_, l)| *l);
+
+    let index = match (selected, lowest) {
+        (Some(s), Some((l, latency))) if s != l => {
+            let (num, den) = LATENCY_HYSTERESIS;
+            match latency(&links[s].metrics) {
+                Some(current) if latency >= current * num / den => s,
+                _ => l,
+            }
+        }
+        (Some(s), _) => s,
+        (None, Some((l, _))) => l,
+        (None, None) => return active_link(links, candidates),
+    };
+    if selected != Some(index) {
+        if let Some(s) = selected {
+            links[s].metrics.selected.store(false, Ordering::Relaxed);
+        }
+        links[index].metrics.selected.store(true, Ordering::Relaxed);
+    }
+    Some(index)
+}
 
 impl TransportUnicastUniversal {
-    fn schedule_on_link(&self, msg: NetworkMessage) -> bool {
-        macro_rules! zpush {
-            ($guard:expr, $pipeline:expr, $msg:expr) => {
-                // Drop the guard before the push_zenoh_message since
-                // the link could be congested and this operation could
-                // block for fairly long time
-                let pl = $pipeline.clone();
-                drop($guard);
-                tracing::trace!("Scheduled: {:?}", $msg);
-                return pl.push_network_message($msg);
-            };
+    fn pinned_link(
+        &self,
+        links: &[TransportLinkUnicastUniversal],
+        candidates: &[usize],
+        priority: Priority,
+    ) -> Option<usize> {
+        self.manager
+            .config
+            .unicast
+            .multilink
+            .links()
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.priorities.iter().any(|&p| Priority::from(p) == priority))
+            .find_map(|(e, _)| {
+                candidates
+                    .iter()
+                    .copied()
+                    .find(|&i| links[i].entries.contains(&e))
+            })
+    }
+
+    fn select_link(
+        &self,
+        links: &[TransportLinkUnicastUniversal],
+        msg: &NetworkMessage,
+    ) -> Option<usize> {
+        if links.len() < 2 {
+            return links.first().map(|_| 0);
         }
 
-        let guard = zread!(self.links);
         // First try to find the best match between msg and link reliability
-        if let Some(pl) = guard.iter().find_map(|tl| {
-            if msg.is_reliable() == tl.link.link.is_reliable() {
-                Some(&tl.pipeline)
-            } else {
-                None
-            }
-        }) {
-            zpush!(guard, pl, msg);
+        let mut candidates: Vec<usize> = (0..links.len())
+            .filter(|&i| msg.is_reliable() == links[i].link.link.is_reliable())
+            .collect();
+        // No best match found, take any available link
+        if candidates.is_empty() {
+            candidates = (0..links.len()).collect();
+        }
+        // Fail over from the degraded links, unless all of them are
+        if candidates.iter().any(|&i| !links[i].metrics.is_degraded()) {
+            candidates.retain(|&i| !links[i].metrics.is_degraded());
         }
 
-        // No best match found, take the first available link
-        if let Some(pl) = guard.iter().map(|tl| &tl.pipeline).next() {
-            zpush!(guard, pl, msg);
+        // Without QoS all the messages share the sequence numbers of the default priority,
+        // they must then take the same link to not be reordered
+        let priority = if self.config.is_qos {
+            msg.priority()
+        } else {
+            Priority::default()
+        };
+        match self.manager.config.unicast.multilink.policy() {
+            MultilinkPolicyConf::ActiveStandby => active_link(links, &candidates),
+            MultilinkPolicyConf::RoundRobin => {
+                Some(candidates[priority as usize % candidates.len()])
+            }
+            MultilinkPolicyConf::LowestLatency => lowest_latency_link(links, &candidates),
+            MultilinkPolicyConf::Priority => self
+                .pinned_link(links, &candidates, priority)
+                .or_else(|| active_link(links, &candidates)),
         }
+    }
 
-        // No Link found
-        tracing::trace!(
-            "Message dropped because the transport has no links: {}",
-            msg
-        );
+    fn schedule_on_link(&self, msg: NetworkMessage) -> bool {
+        let guard = zread!(self.links);
+        let Some(index) = self.select_link(&guard, &msg) else {
+            // No Link found
+            tracing::trace!(
+                "Message dropped because the transport has no links: {}",
+                msg
+            );
+            return false;
+        };
 
-        false
+        // Drop the guard before the push_zenoh_message since
+        // the link could be congested and this operation could
+        // block for fairly long time
+        let pl = guard[index].pipeline.clone();
+        let metrics = guard[index].metrics.clone();
+        drop(guard);
+        tracing::trace!("Scheduled: {:?}", msg);
+        let res = pl.push_network_message(msg);
+        if res {
+            metrics.inc_tx_msgs();
+        } else {
+            metrics.inc_tx_dropped();
+        }
+        res
     }
 
     #[allow(unused_mut)] // When feature "shared-memory" is not enabled
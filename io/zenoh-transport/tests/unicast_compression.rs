@@ -37,6 +37,7 @@ mod tests {
     };
     use zenoh_result::ZResult;
     use zenoh_transport::{
+        common::batch::CompressionAlgorithm,
         multicast::TransportMulticast,
         unicast::{test_helpers::make_transport_manager_builder, TransportUnicast},
         TransportEventHandler, TransportManager, TransportMulticastEventHandler, TransportPeer,
@@ -160,6 +161,7 @@ mod tests {
         client_endpoints: &[EndPoint],
         server_endpoints: &[EndPoint],
         lowlatency_transport: bool,
+        algorithm: CompressionAlgorithm,
     ) -> (
         TransportManager,
         Arc<SHRouter>,
@@ -179,7 +181,8 @@ mod tests {
             false,
             lowlatency_transport,
         )
-        .compression(true);
+        .compression(true)
+        .compression_algorithm(algorithm);
         let router_manager = TransportManager::builder()
             .zid(router_id)
             .whatami(WhatAmI::Router)
@@ -201,7 +204,8 @@ mod tests {
             false,
             lowlatency_transport,
         )
-        .compression(true);
+        .compression(true)
+        .compression_algorithm(algorithm);
         let client_manager = TransportManager::builder()
             .whatami(WhatAmI::Client)
             .zid(client_id)
@@ -297,9 +301,13 @@ mod tests {
                 timestamp: None,
                 encoding: Encoding::default(),
                 ext_sinfo: None,
+                ext_coherence: None,
+                ext_ttl: None,
+                ext_latency_budget: None,
                 #[cfg(feature = "shared-memory")]
                 ext_shm: None,
                 ext_attachment: None,
+                ext_trace: None,
                 ext_unknown: vec![],
             }
             .into(),
@@ -336,15 +344,22 @@ mod tests {
         channel: Channel,
         msg_size: usize,
         lowlatency_transport: bool,
+        algorithm: CompressionAlgorithm,
     ) {
         println!(
-            "\n>>> Running test for:  {:?}, {:?}, {:?}, {}",
-            client_endpoints, server_endpoints, channel, msg_size
+            "\n>>> Running test for:  {:?}, {:?}, {:?}, {}, {:?}",
+            client_endpoints, server_endpoints, channel, msg_size, algorithm
         );
 
         #[allow(unused_variables)] // Used when stats feature is enabled
         let (router_manager, router_handler, client_manager, client_transport) =
-            open_transport_unicast(client_endpoints, server_endpoints, lowlatency_transport).await;
+            open_transport_unicast(
+                client_endpoints,
+                server_endpoints,
+                lowlatency_transport,
+                algorithm,
+            )
+            .await;
 
         test_transport(
             router_handler.clone(),
@@ -354,6 +369,22 @@ mod tests {
         )
         .await;
 
+        // The zero-filled payloads are highly compressible
+        if !lowlatency_transport {
+            let metrics = client_transport.get_link_metrics().unwrap();
+            for m in metrics.iter() {
+                println!(
+                    "\tCompression ratio on {}: {:?}",
+                    m.link, m.compression_ratio
+                );
+            }
+            assert!(metrics.iter().any(|m| m.compression_ratio.is_some()));
+            assert!(metrics
+                .iter()
+                .filter_map(|m| m.compression_ratio)
+                .all(|r| r > 1.0));
+        }
+
         #[cfg(feature = "stats")]
         {
             let c_stats = client_transport.get_stats().unwrap().report();
@@ -383,6 +414,7 @@ mod tests {
         channel: &[Channel],
         msg_size: &[usize],
         lowlatency_transport: bool,
+        algorithm: CompressionAlgorithm,
     ) {
         for ch in channel.iter() {
             for ms in msg_size.iter() {
@@ -392,6 +424,7 @@ mod tests {
                     *ch,
                     *ms,
                     lowlatency_transport,
+                    algorithm,
                 )
                 .await;
             }
@@ -403,8 +436,17 @@ mod tests {
         server_endpoints: &[EndPoint],
         channel: &[Channel],
         msg_size: &[usize],
+        algorithm: CompressionAlgorithm,
     ) {
-        run_internal(client_endpoints, server_endpoints, channel, msg_size, false).await;
+        run_internal(
+            client_endpoints,
+            server_endpoints,
+            channel,
+            msg_size,
+            false,
+            algorithm,
+        )
+        .await;
     }
 
     async fn run_with_lowlatency_transport(
@@ -417,7 +459,15 @@ mod tests {
             println!("LowLatency transport doesn't support more than one link, so this test would produce MAX_LINKS error!");
             panic!();
         }
-        run_internal(client_endpoints, server_endpoints, channel, msg_size, true).await;
+        run_internal(
+            client_endpoints,
+            server_endpoints,
+            channel,
+            msg_size,
+            true,
+            CompressionAlgorithm::Lz4,
+        )
+        .await;
     }
 
     #[cfg(feature = "transport_tcp")]
@@ -442,7 +492,40 @@ mod tests {
             },
         ];
         // Run
-        run_with_universal_transport(&endpoints, &endpoints, &channel, &MSG_SIZE_ALL).await;
+        run_with_universal_transport(
+            &endpoints,
+            &endpoints,
+            &channel,
+            &MSG_SIZE_ALL,
+            CompressionAlgorithm::Lz4,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "transport_tcp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn transport_unicast_compression_zstd_tcp_only() {
+        zenoh_util::try_init_log_from_env();
+
+        // Define the locators
+        let endpoints: Vec<EndPoint> = vec![
+            format!("tcp/127.0.0.1:{}", 19002).parse().unwrap(),
+            format!("tcp/[::1]:{}", 19003).parse().unwrap(),
+        ];
+        // Define the reliability and congestion control
+        let channel = [Channel {
+            priority: Priority::default(),
+            reliability: Reliability::Reliable,
+        }];
+        // Run
+        run_with_universal_transport(
+            &endpoints,
+            &endpoints,
+            &channel,
+            &MSG_SIZE_ALL,
+            CompressionAlgorithm::Zstd,
+        )
+        .await;
     }
 
     #[cfg(feature = "transport_tcp")]
@@ -489,7 +572,14 @@ mod tests {
             },
         ];
         // Run
-        run_with_universal_transport(&endpoints, &endpoints, &channel, &MSG_SIZE_NOFRAG).await;
+        run_with_universal_transport(
+            &endpoints,
+            &endpoints,
+            &channel,
+            &MSG_SIZE_NOFRAG,
+            CompressionAlgorithm::Lz4,
+        )
+        .await;
     }
 
     #[cfg(feature = "transport_udp")]
@@ -80,9 +80,13 @@ async fn run(endpoint: &EndPoint, channel: Channel, msg_size: usize) {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
             ext_attachment: None,
+            ext_trace: None,
             ext_unknown: vec![],
         }
         .into(),
@@ -94,12 +98,18 @@ async fn run(endpoint: &EndPoint, channel: Channel, msg_size: usize) {
     );
     client_transport.schedule(message.clone()).unwrap();
 
-    // Wait that the client transport has been closed
-    ztimeout!(async {
-        while client_transport.get_zid().is_ok() {
-            tokio::time::sleep(SLEEP).await;
-        }
-    });
+    // The router drops the message upfront and keeps the transport open
+    tokio::time::sleep(SLEEP).await;
+    assert!(client_transport.get_zid().is_ok());
+    assert_eq!(router_manager.get_transports_unicast().await.len(), 1);
+    let router_transport = router_manager
+        .get_transport_unicast(&client_id)
+        .await
+        .unwrap();
+    assert!(router_transport.get_reassemblies().unwrap().is_empty());
+
+    // Close the transport
+    ztimeout!(client_transport.close()).unwrap();
 
     // Wait on the router manager that the transport has been closed
     ztimeout!(async {
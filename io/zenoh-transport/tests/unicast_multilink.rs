@@ -14,14 +14,26 @@
 #[cfg(feature = "transport_multilink")]
 mod tests {
     use std::{convert::TryFrom, sync::Arc, time::Duration};
+    use zenoh_config::{MultilinkConf, MultilinkPolicyConf};
     use zenoh_core::ztimeout;
     use zenoh_link::EndPoint;
-    use zenoh_protocol::core::{WhatAmI, ZenohId};
+    use zenoh_protocol::{
+        core::{CongestionControl, Encoding, Priority, WhatAmI, ZenohId},
+        network::{
+            push::{
+                ext::{NodeIdType, QoSType},
+                Push,
+            },
+            NetworkMessage,
+        },
+        zenoh::Put,
+    };
     use zenoh_result::ZResult;
     use zenoh_transport::{
-        multicast::TransportMulticast, unicast::TransportUnicast, DummyTransportPeerEventHandler,
-        TransportEventHandler, TransportManager, TransportMulticastEventHandler, TransportPeer,
-        TransportPeerEventHandler,
+        multicast::TransportMulticast,
+        unicast::{TransportLinkMetrics, TransportUnicast},
+        DummyTransportPeerEventHandler, TransportEventHandler, TransportManager,
+        TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
     };
 
     const TIMEOUT: Duration = Duration::from_secs(60);
@@ -474,6 +486,190 @@ mod tests {
         tokio::time::sleep(SLEEP).await;
     }
 
+    async fn multilink_policy(
+        endpoint: &EndPoint,
+        multilink: MultilinkConf,
+    ) -> Vec<TransportLinkMetrics> {
+        const MSG_COUNT: usize = 100;
+
+        /* [ROUTER] */
+        let unicast = TransportManager::config_unicast().max_links(2);
+        let router_manager = TransportManager::builder()
+            .whatami(WhatAmI::Router)
+            .zid(ZenohId::try_from([1]).unwrap())
+            .unicast(unicast)
+            .build(Arc::new(SHRouterOpenClose))
+            .unwrap();
+
+        /* [CLIENT] */
+        let health = *multilink.health().enabled();
+        let unicast = TransportManager::config_unicast()
+            .max_links(2)
+            .multilink(multilink);
+        let client_manager = TransportManager::builder()
+            .whatami(WhatAmI::Client)
+            .zid(ZenohId::try_from([2]).unwrap())
+            .unicast(unicast)
+            .build(Arc::new(SHClientOpenClose::new()))
+            .unwrap();
+
+        // Open a transport with two links
+        let _ = ztimeout!(router_manager.add_listener(endpoint.clone())).unwrap();
+        let _ = ztimeout!(client_manager.open_transport_unicast(endpoint.clone())).unwrap();
+        let transport = ztimeout!(client_manager.open_transport_unicast(endpoint.clone())).unwrap();
+        assert_eq!(transport.get_links().unwrap().len(), 2);
+
+        // Wait for the links to be probed
+        if health {
+            ztimeout!(async {
+                while !transport
+                    .get_link_metrics()
+                    .unwrap()
+                    .iter()
+                    .all(|m| m.rtt.is_some())
+                {
+                    tokio::time::sleep(SLEEP).await;
+                }
+            });
+        }
+
+        // Send messages of two priorities
+        for p in [Priority::Data, Priority::DataLow] {
+            let message: NetworkMessage = Push {
+                wire_expr: "test".into(),
+                ext_qos: QoSType::new(p, CongestionControl::Block, false),
+                ext_tstamp: None,
+                ext_nodeid: NodeIdType::default(),
+                payload: Put {
+                    payload: vec![0u8; 8].into(),
+                    timestamp: None,
+                    encoding: Encoding::default(),
+                    ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_attachment: None,
+                    ext_trace: None,
+                    ext_unknown: vec![],
+                }
+                .into(),
+            }
+            .into();
+            for _ in 0..MSG_COUNT {
+                transport.schedule(message.clone()).unwrap();
+            }
+        }
+
+        // Wait for the messages to be written on the links
+        ztimeout!(async {
+            while !transport
+                .get_link_metrics()
+                .unwrap()
+                .iter()
+                .all(|m| m.tx_msgs == 0 || m.batch_fill_ratio.is_some())
+            {
+                tokio::time::sleep(SLEEP).await;
+            }
+        });
+        let metrics = transport.get_link_metrics().unwrap();
+        println!("Link metrics: {metrics:?}");
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(
+            metrics.iter().map(|m| m.tx_msgs).sum::<u64>(),
+            2 * MSG_COUNT as u64
+        );
+        assert!(metrics.iter().all(|m| m.tx_dropped == 0));
+        assert!(metrics
+            .iter()
+            .filter(|m| m.tx_msgs > 0)
+            .all(|m| m.batch_fill_ratio.is_some_and(|r| r > 0.0 && r <= 1.0)));
+
+        // Wait for the messages to be received on the router links
+        let router_transport = router_manager
+            .get_transport_unicast(&client_manager.config.zid)
+            .await
+            .unwrap();
+        ztimeout!(async {
+            while router_transport
+                .get_link_metrics()
+                .unwrap()
+                .iter()
+                .map(|m| m.rx_msgs)
+                .sum::<u64>()
+                < 2 * MSG_COUNT as u64
+            {
+                tokio::time::sleep(SLEEP).await;
+            }
+        });
+
+        ztimeout!(client_manager.close());
+        ztimeout!(router_manager.close());
+
+        // Wait a little bit
+        tokio::time::sleep(SLEEP).await;
+
+        metrics
+    }
+
+    #[cfg(feature = "transport_tcp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn multilink_tcp_active_standby() {
+        zenoh_util::try_init_log_from_env();
+
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 18001).parse().unwrap();
+        let mut multilink = MultilinkConf::default();
+        multilink
+            .set_policy(MultilinkPolicyConf::ActiveStandby)
+            .unwrap();
+        let metrics = multilink_policy(&endpoint, multilink).await;
+        // All the messages take the first link
+        assert_eq!(metrics[0].tx_msgs, 200);
+        assert_eq!(metrics[1].tx_msgs, 0);
+    }
+
+    #[cfg(feature = "transport_tcp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn multilink_tcp_round_robin() {
+        zenoh_util::try_init_log_from_env();
+
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 18002).parse().unwrap();
+        let mut multilink = MultilinkConf::default();
+        multilink
+            .set_policy(MultilinkPolicyConf::RoundRobin)
+            .unwrap();
+        let metrics = multilink_policy(&endpoint, multilink).await;
+        // Each priority takes its own link
+        assert_eq!(metrics[0].tx_msgs, 100);
+        assert_eq!(metrics[1].tx_msgs, 100);
+    }
+
+    #[cfg(feature = "transport_tcp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn multilink_tcp_health() {
+        zenoh_util::try_init_log_from_env();
+
+        // Healthy links
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 18003).parse().unwrap();
+        let mut multilink = MultilinkConf::default();
+        multilink.health.set_enabled(true).unwrap();
+        multilink.health.set_probe_interval(50).unwrap();
+        let metrics = multilink_policy(&endpoint, multilink).await;
+        assert!(metrics.iter().all(|m| m.rtt.is_some() && !m.degraded));
+        assert_eq!(metrics[0].tx_msgs, 200);
+
+        // All the links are degraded, they keep taking the messages
+        let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 18004).parse().unwrap();
+        let mut multilink = MultilinkConf::default();
+        multilink.health.set_enabled(true).unwrap();
+        multilink.health.set_probe_interval(50).unwrap();
+        multilink.health.set_max_rtt(Some(0)).unwrap();
+        let metrics = multilink_policy(&endpoint, multilink).await;
+        assert!(metrics.iter().all(|m| m.degraded));
+        assert_eq!(metrics[0].tx_msgs, 200);
+    }
+
     #[cfg(feature = "transport_tcp")]
     #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
     async fn multilink_tcp_only() {
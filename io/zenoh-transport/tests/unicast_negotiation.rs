@@ -0,0 +1,358 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    any::Any,
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use zenoh_buffers::ZBuf;
+use zenoh_core::ztimeout;
+use zenoh_link::Link;
+use zenoh_protocol::{
+    common::{iext, ZExtBody, ZExtUnit, ZExtUnknown},
+    core::{Bits, Encoding, EndPoint, ZenohId},
+    network::{
+        push::ext::{NodeIdType, QoSType},
+        NetworkMessage, Push, RequestCancel, Response, ResponseFinal,
+    },
+    zenoh::{Put, Reply},
+    VERSION, VERSION_MIN,
+};
+use zenoh_result::ZResult;
+use zenoh_transport::{
+    multicast::TransportMulticast, unicast::TransportUnicast, TransportEventHandler,
+    TransportManager, TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+// Transport Handler
+struct SH;
+
+impl TransportEventHandler for SH {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(SC))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+struct SC;
+
+impl TransportPeerEventHandler for SC {
+    fn handle_message(&self, _message: NetworkMessage) -> ZResult<()> {
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+async fn run(listen: &EndPoint, connect: &EndPoint) {
+    let router_id = ZenohId::try_from([1]).unwrap();
+    let client_id = ZenohId::try_from([2]).unwrap();
+
+    let router_manager = TransportManager::builder()
+        .zid(router_id)
+        .build(Arc::new(SH))
+        .unwrap();
+    let client_manager = TransportManager::builder()
+        .zid(client_id)
+        .build(Arc::new(SH))
+        .unwrap();
+
+    let _ = ztimeout!(router_manager.add_listener_unicast(listen.clone())).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The parameters proposed by the client endpoint are lower than the router ones
+    let transport = ztimeout!(client_manager.open_transport_unicast(connect.clone())).unwrap();
+    assert_eq!(transport.get_sn_resolution().unwrap(), Bits::U16);
+    assert_eq!(transport.get_batch_size().unwrap(), Some(1_024));
+    tokio::time::sleep(SLEEP).await;
+
+    let transport = ztimeout!(router_manager.get_transport_unicast(&client_id)).unwrap();
+    assert_eq!(transport.get_sn_resolution().unwrap(), Bits::U16);
+    assert_eq!(transport.get_batch_size().unwrap(), Some(1_024));
+
+    // The invalid parameters are rejected
+    let mut invalid = connect.clone();
+    invalid
+        .config_mut()
+        .insert("sn_resolution", "64bit")
+        .unwrap();
+    assert!(ztimeout!(client_manager.open_transport_unicast(invalid)).is_err());
+
+    ztimeout!(client_manager.close());
+    ztimeout!(router_manager.close());
+    tokio::time::sleep(SLEEP).await;
+}
+
+#[cfg(feature = "transport_tcp")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transport_unicast_negotiation_tcp() {
+    zenoh_util::try_init_log_from_env();
+
+    let listen: EndPoint = format!("tcp/127.0.0.1:{}", 17100).parse().unwrap();
+    let connect: EndPoint = format!(
+        "tcp/127.0.0.1:{}#sn_resolution=16bit;batch_size=1024",
+        17100
+    )
+    .parse()
+    .unwrap();
+    run(&listen, &connect).await;
+}
+
+async fn run_version(endpoint: &EndPoint) {
+    let router_id = ZenohId::try_from([1]).unwrap();
+    let client_id = ZenohId::try_from([2]).unwrap();
+
+    let router_manager = TransportManager::builder()
+        .zid(router_id)
+        .build(Arc::new(SH))
+        .unwrap();
+    let _ = ztimeout!(router_manager.add_listener_unicast(endpoint.clone())).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // (client versions, negotiated version)
+    let cases = [
+        ((VERSION_MIN, VERSION), Some(VERSION)),
+        // A node speaking only the previous version
+        ((VERSION_MIN, VERSION_MIN), Some(VERSION_MIN)),
+        // A node speaking only a future version
+        ((VERSION + 1, VERSION + 1), None),
+    ];
+    for ((version_min, version), expected) in cases {
+        let client_manager = TransportManager::builder()
+            .zid(client_id)
+            .version(version_min, version)
+            .build(Arc::new(SH))
+            .unwrap();
+
+        let res = ztimeout!(client_manager.open_transport_unicast(endpoint.clone()));
+        match expected {
+            Some(expected) => {
+                let transport = res.unwrap();
+                assert_eq!(transport.get_version().unwrap(), expected);
+                tokio::time::sleep(SLEEP).await;
+
+                let transport =
+                    ztimeout!(router_manager.get_transport_unicast(&client_id)).unwrap();
+                assert_eq!(transport.get_version().unwrap(), expected);
+            }
+            None => assert!(res.is_err()),
+        }
+
+        ztimeout!(client_manager.close());
+        tokio::time::sleep(SLEEP).await;
+    }
+
+    ztimeout!(router_manager.close());
+    tokio::time::sleep(SLEEP).await;
+}
+
+#[cfg(feature = "transport_tcp")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transport_unicast_negotiation_version_tcp() {
+    zenoh_util::try_init_log_from_env();
+
+    let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 17101).parse().unwrap();
+    run_version(&endpoint).await;
+}
+
+// Transport Handler recording the messages it receives
+#[derive(Default)]
+struct SHRecord {
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+impl TransportEventHandler for SHRecord {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(SCRecord {
+            received: self.received.clone(),
+        }))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+struct SCRecord {
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+impl TransportPeerEventHandler for SCRecord {
+    fn handle_message(&self, message: NetworkMessage) -> ZResult<()> {
+        self.received.lock().unwrap().push(message.to_string());
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+async fn run_version_gating(endpoint: &EndPoint) {
+    let router_id = ZenohId::try_from([1]).unwrap();
+    let client_id = ZenohId::try_from([2]).unwrap();
+
+    let router_manager = TransportManager::builder()
+        .zid(router_id)
+        .build(Arc::new(SH))
+        .unwrap();
+    let _ = ztimeout!(router_manager.add_listener_unicast(endpoint.clone())).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // (client versions, whether the client receives the messages introduced by VERSION)
+    let cases = [
+        ((VERSION_MIN, VERSION), true),
+        ((VERSION_MIN, VERSION_MIN), false),
+    ];
+    for ((version_min, version), expected) in cases {
+        let handler = SHRecord::default();
+        let received = handler.received.clone();
+        let client_manager = TransportManager::builder()
+            .zid(client_id)
+            .version(version_min, version)
+            .build(Arc::new(handler))
+            .unwrap();
+        let _ = ztimeout!(client_manager.open_transport_unicast(endpoint.clone())).unwrap();
+        tokio::time::sleep(SLEEP).await;
+
+        let transport = ztimeout!(router_manager.get_transport_unicast(&client_id)).unwrap();
+        let cancel: NetworkMessage = RequestCancel {
+            rid: 1,
+            ext_qos: Default::default(),
+            ext_tstamp: None,
+        }
+        .into();
+        let end: NetworkMessage = ResponseFinal {
+            rid: 1,
+            ext_qos: Default::default(),
+            ext_tstamp: None,
+        }
+        .into();
+        let push = |mandatory: bool| -> NetworkMessage {
+            Push {
+                wire_expr: "test".into(),
+                ext_qos: QoSType::default(),
+                ext_tstamp: None,
+                ext_nodeid: NodeIdType::default(),
+                payload: Put {
+                    payload: vec![0u8; 8].into(),
+                    timestamp: None,
+                    encoding: Encoding::default(),
+                    ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_attachment: None,
+                    ext_trace: None,
+                    ext_unknown: vec![ZExtUnknown::new(
+                        iext::USER_ID_MIN,
+                        mandatory,
+                        ZExtBody::Unit,
+                    )],
+                }
+                .into(),
+            }
+            .into()
+        };
+        let reply_del: NetworkMessage = Response {
+            rid: 1,
+            wire_expr: "test".into(),
+            payload: Reply {
+                timestamp: None,
+                encoding: Encoding::default(),
+                ext_sinfo: None,
+                ext_consolidation: Default::default(),
+                #[cfg(feature = "shared-memory")]
+                ext_shm: None,
+                ext_attachment: None,
+                ext_delete: Some(ZExtUnit::new()),
+                ext_trace: None,
+                ext_unknown: vec![],
+                payload: ZBuf::empty(),
+            }
+            .into(),
+            ext_qos: Default::default(),
+            ext_tstamp: None,
+            ext_respid: None,
+        }
+        .into();
+        transport.schedule(push(true)).unwrap();
+        transport.schedule(push(false)).unwrap();
+        transport.schedule(reply_del).unwrap();
+        transport.schedule(cancel).unwrap();
+        transport.schedule(end).unwrap();
+        tokio::time::sleep(SLEEP).await;
+
+        // A client speaking the previous version doesn't receive the RequestCancel, the replies
+        // with deletions and the messages with mandatory user extensions, it would fail to decode
+        // them, and keeps the transport open
+        let expected_msgs = if expected {
+            vec!["Push", "Push", "Response", "RequestCancel", "ResponseFinal"]
+        } else {
+            vec!["Push", "ResponseFinal"]
+        };
+        assert_eq!(*received.lock().unwrap(), expected_msgs);
+        assert!(ztimeout!(router_manager.get_transport_unicast(&client_id)).is_some());
+
+        ztimeout!(client_manager.close());
+        tokio::time::sleep(SLEEP).await;
+    }
+
+    ztimeout!(router_manager.close());
+    tokio::time::sleep(SLEEP).await;
+}
+
+#[cfg(feature = "transport_tcp")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transport_unicast_negotiation_version_gating_tcp() {
+    zenoh_util::try_init_log_from_env();
+
+    let endpoint: EndPoint = format!("tcp/127.0.0.1:{}", 17102).parse().unwrap();
+    run_version_gating(&endpoint).await;
+}
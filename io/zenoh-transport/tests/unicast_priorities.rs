@@ -295,9 +295,13 @@ async fn single_run(router_handler: Arc<SHRouter>, client_transport: TransportUn
                     timestamp: None,
                     encoding: Encoding::default(),
                     ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
                     #[cfg(feature = "shared-memory")]
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_trace: None,
                     ext_unknown: vec![],
                 }
                 .into(),
@@ -269,8 +269,12 @@ mod tests {
                     timestamp: None,
                     encoding: Encoding::default(),
                     ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_trace: None,
                     ext_unknown: vec![],
                 }
                 .into(),
@@ -317,8 +321,12 @@ mod tests {
                     timestamp: None,
                     encoding: Encoding::default(),
                     ext_sinfo: None,
+                    ext_coherence: None,
+                    ext_ttl: None,
+                    ext_latency_budget: None,
                     ext_shm: None,
                     ext_attachment: None,
+                    ext_trace: None,
                     ext_unknown: vec![],
                 }
                 .into(),
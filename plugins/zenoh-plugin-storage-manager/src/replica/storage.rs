@@ -56,9 +56,10 @@ pub struct StorageService {
     complete: bool,
     name: String,
     strip_prefix: Option<OwnedKeyExpr>,
-    storage: Mutex<Box<dyn zenoh_backend_traits::Storage>>,
+    storage: Arc<Mutex<Box<dyn zenoh_backend_traits::Storage>>>,
     capability: Capability,
     tombstones: Arc<RwLock<KeBoxTree<Timestamp, NonWild, KeyedSetProvider>>>,
+    expirations: Arc<RwLock<KeBoxTree<Timestamp, NonWild, KeyedSetProvider>>>,
     wildcard_updates: Arc<RwLock<KeBoxTree<Update, UnknownWildness, KeyedSetProvider>>>,
     in_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
     out_interceptor: Option<Arc<dyn Fn(Sample) -> Sample + Send + Sync>>,
@@ -81,9 +82,10 @@ impl StorageService {
             complete: config.complete,
             name: name.to_string(),
             strip_prefix: config.strip_prefix,
-            storage: Mutex::new(store_intercept.storage),
+            storage: Arc::new(Mutex::new(store_intercept.storage)),
             capability: store_intercept.capability,
             tombstones: Arc::new(RwLock::new(KeBoxTree::default())),
+            expirations: Arc::new(RwLock::new(KeBoxTree::default())),
             wildcard_updates: Arc::new(RwLock::new(KeBoxTree::default())),
             in_interceptor: store_intercept.in_interceptor,
             out_interceptor: store_intercept.out_interceptor,
@@ -136,6 +138,9 @@ impl StorageService {
                 config: gc_config,
                 tombstones: self.tombstones.clone(),
                 wildcard_updates: self.wildcard_updates.clone(),
+                expirations: self.expirations.clone(),
+                storage: self.storage.clone(),
+                strip_prefix: self.strip_prefix.clone(),
             },
         );
         t.add_async(gc).await;
@@ -270,6 +275,11 @@ impl StorageService {
             sample
         };
 
+        if sample.is_expired() {
+            tracing::trace!("[STORAGE] Ignoring expired sample: {}", sample);
+            return;
+        }
+
         // if wildcard, update wildcard_updates
         if sample.key_expr.is_wild() {
             self.register_wildcard_update(sample.clone()).await;
@@ -317,6 +327,7 @@ impl StorageService {
                             Sample::new(KeyExpr::from(k.clone()), sample.value.clone())
                                 .with_timestamp(sample.timestamp.unwrap());
                         sample_to_store.kind = sample.kind;
+                        sample_to_store.ttl = sample.ttl;
                         sample_to_store
                     }
                 };
@@ -348,6 +359,10 @@ impl StorageService {
                     Err("sample kind not implemented".into())
                 };
                 drop(storage);
+                if result.is_ok() {
+                    self.update_expiration(&k, sample_to_store.expiration())
+                        .await;
+                }
                 if self.replication.is_some()
                     && result.is_ok()
                     && !matches!(result.unwrap(), StorageInsertionResult::Outdated)
@@ -388,6 +403,24 @@ impl StorageService {
         }
     }
 
+    async fn update_expiration(&self, key_expr: &OwnedKeyExpr, expiration: Option<Timestamp>) {
+        let mut expirations = self.expirations.write().await;
+        match expiration {
+            Some(expiration) => {
+                expirations.insert(key_expr, expiration);
+            }
+            None => {
+                expirations.remove(key_expr);
+            }
+        }
+    }
+
+    async fn is_expired(&self, key_expr: &OwnedKeyExpr) -> bool {
+        let expirations = self.expirations.read().await;
+        let now = NTP64::from(SystemTime::now().duration_since(UNIX_EPOCH).unwrap());
+        matches!(expirations.weight_at(key_expr), Some(expiration) if *expiration.get_time() < now)
+    }
+
     async fn register_wildcard_update(&self, sample: Sample) {
         // @TODO: change into a better store that does incremental writes
         let key = sample.clone().key_expr;
@@ -504,6 +537,9 @@ impl StorageService {
             let matching_keys = self.get_matching_keys(q.key_expr()).await;
             let mut storage = self.storage.lock().await;
             for key in matching_keys {
+                if self.is_expired(&key).await {
+                    continue;
+                }
                 let stripped_key = match self.strip_prefix(&key.clone().into()) {
                     Ok(k) => k,
                     Err(e) => {
@@ -539,6 +575,9 @@ impl StorageService {
             }
             drop(storage);
         } else {
+            if self.is_expired(&q.key_expr().clone().into()).await {
+                return;
+            }
             let stripped_key = match self.strip_prefix(q.key_expr()) {
                 Ok(k) => k,
                 Err(e) => {
@@ -602,7 +641,14 @@ impl StorageService {
     }
 
     fn strip_prefix(&self, key_expr: &KeyExpr<'_>) -> ZResult<Option<OwnedKeyExpr>> {
-        let key = match &self.strip_prefix {
+        StorageService::get_stripped(&self.strip_prefix, key_expr)
+    }
+
+    pub fn get_stripped(
+        strip_prefix: &Option<OwnedKeyExpr>,
+        key_expr: &KeyExpr<'_>,
+    ) -> ZResult<Option<OwnedKeyExpr>> {
+        let key = match strip_prefix {
             Some(prefix) => {
                 if key_expr.as_str().eq(prefix.as_str()) {
                     ""
@@ -704,6 +750,9 @@ struct GarbageCollectionEvent {
     config: GarbageCollectionConfig,
     tombstones: Arc<RwLock<KeBoxTree<Timestamp, NonWild, KeyedSetProvider>>>,
     wildcard_updates: Arc<RwLock<KeBoxTree<Update, UnknownWildness, KeyedSetProvider>>>,
+    expirations: Arc<RwLock<KeBoxTree<Timestamp, NonWild, KeyedSetProvider>>>,
+    storage: Arc<Mutex<Box<dyn zenoh_backend_traits::Storage>>>,
+    strip_prefix: Option<OwnedKeyExpr>,
 }
 
 #[async_trait]
@@ -740,6 +789,34 @@ impl Timed for GarbageCollectionEvent {
             wildcard_updates.remove(&k);
         }
 
+        // Delete the data that outlived its ttl
+        let now = NTP64::from(SystemTime::now().duration_since(UNIX_EPOCH).unwrap());
+        let mut expirations = self.expirations.write().await;
+        let mut expired = Vec::new();
+        for (k, expiration) in expirations.key_value_pairs() {
+            if expiration.get_time() < &now {
+                expired.push((k, *expiration));
+            }
+        }
+        if !expired.is_empty() {
+            let mut storage = self.storage.lock().await;
+            for (k, expiration) in expired {
+                expirations.remove(&k);
+                let stripped_key =
+                    match StorageService::get_stripped(&self.strip_prefix, &k.clone().into()) {
+                        Ok(stripped) => stripped,
+                        Err(e) => {
+                            tracing::error!("{}", e);
+                            continue;
+                        }
+                    };
+                tracing::trace!("Deleting expired key {}", k);
+                if let Err(e) = storage.delete(stripped_key, expiration).await {
+                    tracing::warn!("Error deleting expired key {}: {}", k, e);
+                }
+            }
+        }
+
         tracing::trace!("End garbage collection of obsolete data-infos");
     }
 }
@@ -40,6 +40,9 @@ pub trait LoadedPlugin<StartArgs, Instance>: PluginStatus {
 pub trait StartedPlugin<StartArgs, Instance>: PluginStatus {
     fn as_status(&self) -> &dyn PluginStatus;
     fn stop(&mut self);
+    fn pause(&mut self) -> ZResult<()>;
+    fn resume(&mut self) -> ZResult<()>;
+    fn reconfigure(&mut self) -> ZResult<()>;
     fn instance(&self) -> &Instance;
     fn instance_mut(&mut self) -> &mut Instance;
 }
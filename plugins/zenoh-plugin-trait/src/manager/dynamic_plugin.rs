@@ -112,6 +112,7 @@ pub struct DynamicPlugin<StartArgs, Instance> {
     source: DynamicPluginSource,
     starter: Option<DynamicPluginStarter<StartArgs, Instance>>,
     instance: Option<Instance>,
+    paused: bool,
 }
 
 impl<StartArgs, Instance> DynamicPlugin<StartArgs, Instance> {
@@ -124,6 +125,7 @@ impl<StartArgs, Instance> DynamicPlugin<StartArgs, Instance> {
             source,
             starter: None,
             instance: None,
+            paused: false,
         }
     }
 }
@@ -155,7 +157,11 @@ impl<StartArgs: PluginStartArgs, Instance: PluginInstance> PluginStatus
     fn state(&self) -> PluginState {
         if self.starter.is_some() {
             if self.instance.is_some() {
-                PluginState::Started
+                if self.paused {
+                    PluginState::Paused
+                } else {
+                    PluginState::Started
+                }
             } else {
                 PluginState::Loaded
             }
@@ -256,6 +262,28 @@ impl<StartArgs: PluginStartArgs, Instance: PluginInstance> StartedPlugin<StartAr
         tracing::debug!("Plugin `{}` stopped", self.name);
         self.report.clear();
         self.instance = None;
+        self.paused = false;
+    }
+    fn pause(&mut self) -> ZResult<()> {
+        if !self.paused {
+            self.instance_mut().pause()?;
+            tracing::debug!("Plugin `{}` paused", self.name);
+            self.paused = true;
+        }
+        Ok(())
+    }
+    fn resume(&mut self) -> ZResult<()> {
+        if self.paused {
+            self.instance_mut().resume()?;
+            tracing::debug!("Plugin `{}` resumed", self.name);
+            self.paused = false;
+        }
+        Ok(())
+    }
+    fn reconfigure(&mut self) -> ZResult<()> {
+        self.instance_mut().reconfigure()?;
+        tracing::debug!("Plugin `{}` reconfigured", self.name);
+        Ok(())
     }
     fn instance(&self) -> &Instance {
         self.instance.as_ref().unwrap()
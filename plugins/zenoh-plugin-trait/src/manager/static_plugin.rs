@@ -20,6 +20,7 @@ where
     P: Plugin<StartArgs = StartArgs, Instance = Instance>,
 {
     instance: Option<Instance>,
+    paused: bool,
     required: bool,
     phantom: PhantomData<P>,
     id: String,
@@ -32,6 +33,7 @@ where
     pub fn new(id: String, required: bool) -> Self {
         Self {
             instance: None,
+            paused: false,
             required,
             phantom: PhantomData,
             id,
@@ -61,9 +63,11 @@ where
         "__static_lib__"
     }
     fn state(&self) -> PluginState {
-        self.instance
-            .as_ref()
-            .map_or(PluginState::Loaded, |_| PluginState::Started)
+        match self.instance {
+            Some(_) if self.paused => PluginState::Paused,
+            Some(_) => PluginState::Started,
+            None => PluginState::Loaded,
+        }
     }
     fn report(&self) -> PluginReport {
         if let Some(instance) = &self.instance {
@@ -140,6 +144,28 @@ where
     fn stop(&mut self) {
         tracing::debug!("Plugin `{}` stopped", self.name());
         self.instance = None;
+        self.paused = false;
+    }
+    fn pause(&mut self) -> ZResult<()> {
+        if !self.paused {
+            self.instance_mut().pause()?;
+            tracing::debug!("Plugin `{}` paused", self.name());
+            self.paused = true;
+        }
+        Ok(())
+    }
+    fn resume(&mut self) -> ZResult<()> {
+        if self.paused {
+            self.instance_mut().resume()?;
+            tracing::debug!("Plugin `{}` resumed", self.name());
+            self.paused = false;
+        }
+        Ok(())
+    }
+    fn reconfigure(&mut self) -> ZResult<()> {
+        self.instance_mut().reconfigure()?;
+        tracing::debug!("Plugin `{}` reconfigured", self.name());
+        Ok(())
     }
     fn instance(&self) -> &Instance {
         self.instance.as_ref().unwrap()
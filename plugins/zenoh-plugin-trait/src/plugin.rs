@@ -15,17 +15,19 @@ use crate::StructVersion;
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, ops::BitOrAssign};
 use zenoh_keyexpr::keyexpr;
-use zenoh_result::ZResult;
+use zenoh_result::{bail, ZResult};
 
 /// The plugin can be in one of these states:
 /// - Declared: the plugin is declared in the configuration file, but not loaded yet or failed to load
 /// - Loaded: the plugin is loaded, but not started yet or failed to start
 /// - Started: the plugin is started and running
+/// - Paused: the plugin is started but its activity is suspended until it's resumed
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
 pub enum PluginState {
     Declared,
     Loaded,
     Started,
+    Paused,
 }
 
 /// The severity level of a plugin report messages
@@ -161,6 +163,18 @@ pub trait PluginControl {
     fn plugins_status(&self, _names: &keyexpr) -> Vec<PluginStatusRec> {
         Vec::new()
     }
+    /// Suspends the plugin's activity without stopping it. Not supported by default.
+    fn pause(&mut self) -> ZResult<()> {
+        bail!("Pausing is not supported by this plugin")
+    }
+    /// Resumes the plugin's activity after a [`pause`](PluginControl::pause). Not supported by default.
+    fn resume(&mut self) -> ZResult<()> {
+        bail!("Resuming is not supported by this plugin")
+    }
+    /// Asks the plugin to reload its configuration without being restarted. Not supported by default.
+    fn reconfigure(&mut self) -> ZResult<()> {
+        bail!("Reconfiguration is not supported by this plugin")
+    }
 }
 
 pub trait PluginStartArgs: StructVersion {}
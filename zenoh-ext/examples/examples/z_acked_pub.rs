@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use clap::Parser;
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::config::Config;
+use zenoh::prelude::r#async::*;
+use zenoh_ext::acknowledged::*;
+
+#[tokio::main]
+async fn main() {
+    zenoh_util::try_init_log_from_env();
+    let args = Args::parse();
+    let z = Arc::new(zenoh::open(Config::default()).res().await.unwrap());
+    let config = AckedPublisherConfig::new(args.key)
+        .unwrap()
+        .retransmit_period(Duration::from_millis(args.retransmit_period))
+        .expiration(Duration::from_secs(args.expiration));
+
+    let publisher = AckedPublisher::start(z.clone(), config).await.unwrap();
+    let events = publisher.subscribe();
+    tokio::spawn(async move {
+        while let Ok(evt) = events.recv_async().await {
+            println!(">> {evt:?}");
+        }
+    });
+
+    println!(
+        "Publishing on {} with acknowledgements",
+        publisher.key_expr()
+    );
+    for idx in 0u64.. {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let value = format!("[{idx:4}] {}", args.value);
+        match publisher.put(value).await {
+            Ok(sn) => println!(
+                "Published {sn} ({} pending, receivers: {:?})",
+                publisher.pending(),
+                publisher.receivers()
+            ),
+            Err(e) => println!("Failed to publish: {e}"),
+        }
+    }
+}
+
+#[derive(Parser, Clone, PartialEq, Eq, Hash, Debug)]
+struct Args {
+    #[arg(short, long, default_value = "demo/example/zenoh-rs-acked")]
+    /// The key expression to publish onto.
+    key: String,
+    #[arg(short, long, default_value = "Acknowledged publication from Rust!")]
+    /// The value to publish.
+    value: String,
+    #[arg(short, long, default_value = "1000")]
+    /// The delay before the first retransmission, in milliseconds.
+    retransmit_period: u64,
+    #[arg(short, long, default_value = "60")]
+    /// The delay after which publications are no longer retransmitted, in seconds.
+    expiration: u64,
+}
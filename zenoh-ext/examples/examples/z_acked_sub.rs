@@ -0,0 +1,59 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use clap::Parser;
+use std::sync::Arc;
+use zenoh::config::Config;
+use zenoh::prelude::r#async::*;
+use zenoh_ext::acknowledged::*;
+
+#[tokio::main]
+async fn main() {
+    zenoh_util::try_init_log_from_env();
+    let args = Args::parse();
+    let z = Arc::new(zenoh::open(Config::default()).res().await.unwrap());
+    let config = AckedSubscriberConfig::new(args.name, args.key).unwrap();
+
+    let subscriber = AckedSubscriber::start(z.clone(), config).await.unwrap();
+    println!(
+        "Receiving on {} as {}",
+        subscriber.key_expr(),
+        subscriber.name()
+    );
+    for idx in 0u64.. {
+        let sample = subscriber.recv_async().await.unwrap();
+        println!(
+            ">> [Subscriber] Received {:?} ('{}': '{}')",
+            sample.sn(),
+            sample.key_expr.as_str(),
+            sample.value
+        );
+        // Simulate processing failures by not acknowledging some of the samples
+        if args.skip == 0 || idx % args.skip != 0 {
+            sample.ack().await.unwrap();
+        }
+    }
+}
+
+#[derive(Parser, Clone, PartialEq, Eq, Hash, Debug)]
+struct Args {
+    #[arg(short, long, default_value = "receiver")]
+    /// The name of the receiver, to be kept across restarts.
+    name: String,
+    #[arg(short, long, default_value = "demo/example/**")]
+    /// The key expression to subscribe to.
+    key: String,
+    #[arg(short, long, default_value = "0")]
+    /// Do not acknowledge one sample out of `skip` (0 to acknowledge all the samples).
+    skip: u64,
+}
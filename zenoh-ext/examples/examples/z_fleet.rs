@@ -0,0 +1,41 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use futures::StreamExt;
+use std::sync::Arc;
+use zenoh::config::Config;
+use zenoh::prelude::r#async::*;
+use zenoh_ext::heartbeat::*;
+
+#[tokio::main]
+async fn main() {
+    zenoh_util::try_init_log_from_env();
+    let z = Arc::new(zenoh::open(Config::default()).res().await.unwrap());
+
+    let monitor = FleetMonitor::start(z.clone()).await.unwrap();
+    let rx = monitor.subscribe().await;
+    let mut stream = rx.stream();
+    while let Some(evt) = stream.next().await {
+        println!(">>> {:?}", &evt);
+        println!(">> Fleet <<");
+        for node in monitor.fleet().await {
+            println!(
+                "\t{} (stale: {}, uptime: {:?}) {:?}",
+                node.status.name,
+                node.stale,
+                node.status.uptime(),
+                node.status.fields
+            );
+        }
+    }
+}
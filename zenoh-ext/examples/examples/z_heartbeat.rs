@@ -0,0 +1,48 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use clap::Parser;
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::config::Config;
+use zenoh::prelude::r#async::*;
+use zenoh_ext::heartbeat::*;
+
+#[tokio::main]
+async fn main() {
+    zenoh_util::try_init_log_from_env();
+    let args = Args::parse();
+    let z = Arc::new(zenoh::open(Config::default()).res().await.unwrap());
+    let name = args.name.unwrap_or_else(|| z.zid().to_string());
+    let config = HeartbeatConfig::new(name)
+        .unwrap()
+        .period(Duration::from_millis(args.period))
+        .version(env!("CARGO_PKG_VERSION"));
+
+    let heartbeat = Heartbeat::start(z.clone(), config).await.unwrap();
+    println!("Publishing heartbeats for {}", heartbeat.name());
+    for counter in 0u64.. {
+        heartbeat.set_field("counter", counter).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+#[derive(Parser, Clone, PartialEq, Eq, Hash, Debug)]
+struct Args {
+    #[arg(short, long)]
+    /// The name of the node (default: the zenoh id).
+    name: Option<String>,
+    #[arg(short, long, default_value = "1000")]
+    /// The heartbeat period in milliseconds.
+    period: u64,
+}
@@ -23,7 +23,7 @@ async fn main() {
     // Initiate logging
     zenoh_util::try_init_log_from_env();
 
-    let (config, key_expr, value, history, prefix, complete) = parse_args();
+    let (config, key_expr, value, history, prefix, complete, persistence) = parse_args();
 
     println!("Opening session...");
     let session = zenoh::open(config).res().await.unwrap();
@@ -36,6 +36,9 @@ async fn main() {
     if let Some(prefix) = prefix {
         publication_cache_builder = publication_cache_builder.queryable_prefix(prefix);
     }
+    if let Some(path) = persistence {
+        publication_cache_builder = publication_cache_builder.persistence(path);
+    }
     let _publication_cache = publication_cache_builder.res().await.unwrap();
 
     println!("Press CTRL-C to quit...");
@@ -64,6 +67,9 @@ struct Args {
     #[arg(short = 'x', long)]
     /// An optional queryable prefix.
     prefix: Option<String>,
+    #[arg(short = 'P', long)]
+    /// An optional file where to persist the cached publications across restarts.
+    persistence: Option<String>,
     #[command(flatten)]
     common: CommonArgs,
 }
@@ -75,6 +81,7 @@ fn parse_args() -> (
     usize,
     Option<String>,
     bool,
+    Option<String>,
 ) {
     let args = Args::parse();
     let mut config: Config = args.common.into();
@@ -89,5 +96,6 @@ fn parse_args() -> (
         args.history,
         args.prefix,
         args.complete,
+        args.persistence,
     )
 }
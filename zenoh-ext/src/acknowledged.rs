@@ -0,0 +1,682 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To publish data with end-to-end acknowledgements and at-least-once delivery.
+//!
+//! An [`AckedPublisher`] tags each of its publications with a sequence number and keeps
+//! them until all the interested [`AckedSubscriber`]s acknowledged them. Publications are
+//! retransmitted with an exponential backoff until they are acknowledged or expire.
+//!
+//! An [`AckedSubscriber`] is identified by a stable name, and declares a liveliness token on
+//! `@ack/rcv/<name>/<key_expr>` so that the publishers know which receivers they must wait for.
+//! Each received [`AckedSample`] is acknowledged by the application once it is processed: if
+//! the subscriber crashes before, the publication is delivered again to the subscriber
+//! restarted with the same name. Acknowledgements are published on `@ack/pub/<publisher id>/<name>`.
+
+use flume::{Receiver, Sender};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryInto;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh::liveliness::LivelinessToken;
+use zenoh::prelude::r#async::*;
+use zenoh::publication::Publisher;
+use zenoh::sample::Attachment;
+use zenoh::subscriber::FlumeSubscriber;
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+use zenoh_result::{bail, zerror};
+use zenoh_task::TaskController;
+
+/// The key expression prefix under which receivers and acknowledgements are declared.
+pub const ACK_PREFIX: &str = "@ack";
+const RECEIVERS_PREFIX: &str = "rcv";
+const ACKS_PREFIX: &str = "pub";
+const ATTACHMENT_PUBLISHER: &str = "ack.pub";
+const ATTACHMENT_SN: &str = "ack.sn";
+const ATTACHMENT_LOW: &str = "ack.low";
+const DEFAULT_MAX_PENDING: usize = 1024;
+const DEFAULT_RETRANSMIT_PERIOD: Duration = Duration::from_secs(1);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+const DEFAULT_EXPIRATION: Duration = Duration::from_secs(60);
+const RETRANSMIT_TICK: Duration = Duration::from_millis(50);
+
+static PUBLISHER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// The configuration of an [`AckedPublisher`].
+#[derive(Debug, Clone)]
+pub struct AckedPublisherConfig {
+    key_expr: OwnedKeyExpr,
+    max_pending: usize,
+    retransmit_period: Duration,
+    max_backoff: Duration,
+    expiration: Duration,
+}
+
+impl AckedPublisherConfig {
+    pub fn new<T>(key_expr: T) -> ZResult<AckedPublisherConfig>
+    where
+        T: TryInto<OwnedKeyExpr> + Send,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "Acknowledged publications are not allowed on wildcard key expressions: {}",
+                key_expr
+            );
+        }
+        Ok(AckedPublisherConfig {
+            key_expr,
+            max_pending: DEFAULT_MAX_PENDING,
+            retransmit_period: DEFAULT_RETRANSMIT_PERIOD,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            expiration: DEFAULT_EXPIRATION,
+        })
+    }
+
+    /// The maximum number of publications waiting for acknowledgements.
+    pub fn max_pending(mut self, n: usize) -> Self {
+        self.max_pending = n;
+        self
+    }
+
+    /// The delay before the first retransmission of a publication.
+    pub fn retransmit_period(mut self, d: Duration) -> Self {
+        self.retransmit_period = d;
+        self
+    }
+
+    /// The maximum delay between two retransmissions of a publication.
+    pub fn max_backoff(mut self, d: Duration) -> Self {
+        self.max_backoff = d;
+        self
+    }
+
+    /// The delay after which a publication is no longer retransmitted.
+    pub fn expiration(mut self, d: Duration) -> Self {
+        self.expiration = d;
+        self
+    }
+}
+
+/// Events exposed to the user to be informed of the delivery of the publications.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeliveryEvent {
+    /// The publication was acknowledged by all the receivers known when it was published.
+    Acked { sn: u64 },
+    /// The publication expired before being acknowledged by all the receivers.
+    Expired { sn: u64, unacked: Vec<String> },
+}
+
+struct PendingPublication {
+    value: Value,
+    waiting: HashSet<String>,
+    next_retry: Instant,
+    backoff: Duration,
+    expires: Instant,
+}
+
+#[derive(Default)]
+struct PublisherInner {
+    next_sn: u64,
+    pending: BTreeMap<u64, PendingPublication>,
+    // The liveliness tokens of the receivers, with their names
+    receivers: HashMap<OwnedKeyExpr, String>,
+}
+
+impl PublisherInner {
+    // The publications below this sequence number are no longer retransmitted
+    fn low(&self) -> u64 {
+        self.pending.keys().next().copied().unwrap_or(self.next_sn)
+    }
+}
+
+struct AckedPublisherState {
+    id: String,
+    key_expr: OwnedKeyExpr,
+    max_pending: usize,
+    retransmit_period: Duration,
+    max_backoff: Duration,
+    expiration: Duration,
+    publisher: Publisher<'static>,
+    inner: Mutex<PublisherInner>,
+    user_events_tx: Mutex<Option<Sender<DeliveryEvent>>>,
+}
+
+impl AckedPublisherState {
+    fn notify(&self, evt: DeliveryEvent) {
+        if let Some(tx) = &*zlock!(self.user_events_tx) {
+            let _ = tx.send(evt);
+        }
+    }
+
+    async fn send(&self, sn: u64, low: u64, value: Value) -> ZResult<()> {
+        let mut attachment = Attachment::new();
+        attachment.insert(ATTACHMENT_PUBLISHER, &self.id);
+        attachment.insert(ATTACHMENT_SN, &sn.to_string());
+        attachment.insert(ATTACHMENT_LOW, &low.to_string());
+        self.publisher
+            .put(value)
+            .with_attachment(attachment)
+            .res()
+            .await
+    }
+}
+
+fn receiver_name(token: &keyexpr) -> Option<&str> {
+    token
+        .as_str()
+        .strip_prefix(ACK_PREFIX)?
+        .strip_prefix('/')?
+        .strip_prefix(RECEIVERS_PREFIX)?
+        .strip_prefix('/')?
+        .split('/')
+        .next()
+}
+
+async fn retransmit_task(state: Arc<AckedPublisherState>) {
+    loop {
+        tokio::time::sleep(RETRANSMIT_TICK).await;
+        let now = Instant::now();
+        let mut events = vec![];
+        let mut retransmissions = vec![];
+        let low = {
+            let mut inner = zlock!(state.inner);
+            let expired: Vec<u64> = inner
+                .pending
+                .iter()
+                .filter(|(_, p)| p.expires <= now)
+                .map(|(sn, _)| *sn)
+                .collect();
+            for sn in expired {
+                if let Some(p) = inner.pending.remove(&sn) {
+                    let mut unacked: Vec<String> = p.waiting.into_iter().collect();
+                    unacked.sort();
+                    events.push(DeliveryEvent::Expired { sn, unacked });
+                }
+            }
+            for (sn, p) in inner.pending.iter_mut() {
+                if p.next_retry <= now {
+                    retransmissions.push((*sn, p.value.clone()));
+                    p.next_retry = now + p.backoff;
+                    p.backoff = (p.backoff * 2).min(state.max_backoff);
+                }
+            }
+            inner.low()
+        };
+        for (sn, value) in retransmissions {
+            tracing::trace!("Retransmitting publication {} on {}", sn, &state.key_expr);
+            if let Err(e) = state.send(sn, low, value).await {
+                tracing::warn!(
+                    "Failed to retransmit publication {} on {}: {}",
+                    sn,
+                    &state.key_expr,
+                    e
+                );
+            }
+        }
+        for evt in events {
+            tracing::debug!("Publication expired on {}: {:?}", &state.key_expr, evt);
+            state.notify(evt);
+        }
+    }
+}
+
+async fn acks_handler(state: Arc<AckedPublisherState>, sub: FlumeSubscriber<'static>) {
+    while let Ok(s) = sub.recv_async().await {
+        let Some(name) = s.key_expr.as_str().rsplit('/').next() else {
+            continue;
+        };
+        let payload = s.value.payload.contiguous();
+        let Some(sn) = std::str::from_utf8(&payload)
+            .ok()
+            .and_then(|sn| sn.parse::<u64>().ok())
+        else {
+            tracing::warn!("Received an invalid acknowledgement from {}", name);
+            continue;
+        };
+        let acked = {
+            let mut inner = zlock!(state.inner);
+            let Some(p) = inner.pending.get_mut(&sn) else {
+                continue;
+            };
+            p.waiting.remove(name);
+            p.waiting.is_empty() && inner.pending.remove(&sn).is_some()
+        };
+        if acked {
+            tracing::trace!("Publication {} acknowledged on {}", sn, &state.key_expr);
+            state.notify(DeliveryEvent::Acked { sn });
+        }
+    }
+}
+
+async fn receivers_handler(state: Arc<AckedPublisherState>, sub: FlumeSubscriber<'static>) {
+    while let Ok(s) = sub.recv_async().await {
+        let Some(name) = receiver_name(&s.key_expr) else {
+            continue;
+        };
+        let mut inner = zlock!(state.inner);
+        match s.kind {
+            SampleKind::Put => {
+                tracing::debug!("Receiver joined {}: {}", &state.key_expr, name);
+                inner
+                    .receivers
+                    .insert(s.key_expr.clone().into(), name.to_string());
+            }
+            SampleKind::Delete => {
+                tracing::debug!("Receiver left {}: {}", &state.key_expr, name);
+                inner.receivers.remove(s.key_expr.as_keyexpr());
+            }
+        }
+    }
+}
+
+/// Publishes data on a key expression until it is acknowledged by the interested
+/// [`AckedSubscriber`]s.
+///
+/// Each publication waits for the acknowledgements of the receivers that were known when it
+/// was published. Receivers that leave are still waited for, until the publication expires,
+/// so that they get it once restarted. Publications made while no receiver is known are
+/// considered acknowledged right away.
+pub struct AckedPublisher {
+    state: Arc<AckedPublisherState>,
+    task_controller: TaskController,
+}
+
+impl Drop for AckedPublisher {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl AckedPublisher {
+    pub async fn start(z: Arc<Session>, with: AckedPublisherConfig) -> ZResult<AckedPublisher> {
+        if with.max_pending == 0 {
+            bail!("The maximum number of pending publications must be greater than zero");
+        }
+        if with.retransmit_period.is_zero() {
+            bail!("Retransmission period must be greater than zero");
+        }
+        let id = format!(
+            "{}_{}",
+            z.zid(),
+            PUBLISHER_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let publisher = z.declare_publisher(with.key_expr.clone()).res().await?;
+        let acks_sub = z
+            .declare_subscriber(format!("{ACK_PREFIX}/{ACKS_PREFIX}/{id}/*"))
+            .res()
+            .await?;
+        let receivers_ke = format!("{ACK_PREFIX}/{RECEIVERS_PREFIX}/*/{}", with.key_expr);
+        let receivers_sub = z
+            .liveliness()
+            .declare_subscriber(&receivers_ke)
+            .res()
+            .await?;
+
+        let mut receivers = HashMap::new();
+        let replies = z.liveliness().get(&receivers_ke).res().await?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.sample {
+                if let Some(name) = receiver_name(&sample.key_expr) {
+                    receivers.insert(sample.key_expr.clone().into(), name.to_string());
+                }
+            }
+        }
+
+        let state = Arc::new(AckedPublisherState {
+            id,
+            key_expr: with.key_expr,
+            max_pending: with.max_pending,
+            retransmit_period: with.retransmit_period,
+            max_backoff: with.max_backoff.max(with.retransmit_period),
+            expiration: with.expiration,
+            publisher,
+            inner: Mutex::new(PublisherInner {
+                receivers,
+                ..Default::default()
+            }),
+            user_events_tx: Mutex::new(Default::default()),
+        });
+
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(acks_handler(state.clone(), acks_sub));
+        task_controller.spawn_abortable(receivers_handler(state.clone(), receivers_sub));
+        task_controller.spawn_abortable(retransmit_task(state.clone()));
+        Ok(AckedPublisher {
+            state,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of this publisher.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.state.key_expr
+    }
+
+    /// Publishes a value, returning its sequence number.
+    ///
+    /// Fails if the maximum number of publications waiting for acknowledgements is reached.
+    pub async fn put<IntoValue>(&self, value: IntoValue) -> ZResult<u64>
+    where
+        IntoValue: Into<Value>,
+    {
+        let value = value.into();
+        let (sn, low, acked) = {
+            let mut inner = zlock!(self.state.inner);
+            if inner.pending.len() >= self.state.max_pending {
+                bail!(
+                    "Too many publications waiting for acknowledgements on {}: {}",
+                    &self.state.key_expr,
+                    self.state.max_pending
+                );
+            }
+            let sn = inner.next_sn;
+            inner.next_sn += 1;
+            let waiting: HashSet<String> = inner.receivers.values().cloned().collect();
+            let acked = waiting.is_empty();
+            if !acked {
+                let now = Instant::now();
+                inner.pending.insert(
+                    sn,
+                    PendingPublication {
+                        value: value.clone(),
+                        waiting,
+                        next_retry: now + self.state.retransmit_period,
+                        backoff: (self.state.retransmit_period * 2).min(self.state.max_backoff),
+                        expires: now + self.state.expiration,
+                    },
+                );
+            }
+            (sn, inner.low(), acked)
+        };
+        self.state.send(sn, low, value).await?;
+        if acked {
+            self.state.notify(DeliveryEvent::Acked { sn });
+        }
+        Ok(sn)
+    }
+
+    /// Returns the number of publications waiting for acknowledgements.
+    pub fn pending(&self) -> usize {
+        zlock!(self.state.inner).pending.len()
+    }
+
+    /// Returns the names of the currently known receivers.
+    pub fn receivers(&self) -> Vec<String> {
+        let mut receivers: Vec<String> = zlock!(self.state.inner)
+            .receivers
+            .values()
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        receivers.sort();
+        receivers
+    }
+
+    /// Returns a receivers that will allow to receive notifications for delivery events.
+    /// Notice that there can be a single subscription at the time, each call to subscribe
+    /// will cancel the previous subscription.
+    pub fn subscribe(&self) -> Receiver<DeliveryEvent> {
+        let (tx, rx) = flume::unbounded();
+        *zlock!(self.state.user_events_tx) = Some(tx);
+        rx
+    }
+}
+
+/// The configuration of an [`AckedSubscriber`].
+#[derive(Debug, Clone)]
+pub struct AckedSubscriberConfig {
+    name: OwnedKeyExpr,
+    key_expr: OwnedKeyExpr,
+}
+
+impl AckedSubscriberConfig {
+    pub fn new<N, K>(name: N, key_expr: K) -> ZResult<AckedSubscriberConfig>
+    where
+        N: TryInto<OwnedKeyExpr> + Send,
+        <N as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+        K: TryInto<OwnedKeyExpr> + Send,
+        <K as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let name: OwnedKeyExpr = name.try_into().map_err(|e| e.into())?;
+        if name.is_wild() || name.contains('/') {
+            bail!(
+                "Receiver name must be a single chunk without wildcards: {}",
+                name
+            );
+        }
+        let key_expr = key_expr.try_into().map_err(|e| e.into())?;
+        Ok(AckedSubscriberConfig { name, key_expr })
+    }
+}
+
+// For each publisher, the sequence numbers delivered to the application,
+// and whether they were acknowledged
+type Delivered = HashMap<String, BTreeMap<u64, bool>>;
+
+struct AckInfo {
+    key_expr: OwnedKeyExpr,
+    publisher: String,
+    sn: u64,
+    session: Arc<Session>,
+    delivered: Arc<Mutex<Delivered>>,
+}
+
+/// A [`Sample`] received by an [`AckedSubscriber`].
+///
+/// The sample must be acknowledged with [`AckedSample::ack`] once processed. If it is dropped
+/// without being acknowledged, it will be delivered again on the next retransmission.
+pub struct AckedSample {
+    sample: Sample,
+    ack: Option<AckInfo>,
+}
+
+impl AckedSample {
+    /// Returns the sequence number of the sample, if it was published by an [`AckedPublisher`].
+    pub fn sn(&self) -> Option<u64> {
+        self.ack.as_ref().map(|ack| ack.sn)
+    }
+
+    /// Acknowledges the sample to its publisher.
+    pub async fn ack(mut self) -> ZResult<()> {
+        let Some(ack) = self.ack.as_ref() else {
+            return Ok(());
+        };
+        // On failure, the sample is dropped unacknowledged and will be delivered again
+        ack.session
+            .put(&ack.key_expr, ack.sn.to_string())
+            .res()
+            .await?;
+        let Some(ack) = self.ack.take() else {
+            return Ok(());
+        };
+        if let Some(acked) = zlock!(ack.delivered)
+            .get_mut(&ack.publisher)
+            .and_then(|sns| sns.get_mut(&ack.sn))
+        {
+            *acked = true;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for AckedSample {
+    type Target = Sample;
+
+    fn deref(&self) -> &Sample {
+        &self.sample
+    }
+}
+
+impl Drop for AckedSample {
+    fn drop(&mut self) {
+        if let Some(ack) = self.ack.take() {
+            if let Some(sns) = zlock!(ack.delivered).get_mut(&ack.publisher) {
+                sns.remove(&ack.sn);
+            }
+        }
+    }
+}
+
+fn attachment_value<T: std::str::FromStr>(attachment: &Attachment, key: &str) -> Option<T> {
+    std::str::from_utf8(&attachment.get(&key)?)
+        .ok()?
+        .parse()
+        .ok()
+}
+
+async fn samples_handler(
+    z: Arc<Session>,
+    name: OwnedKeyExpr,
+    delivered: Arc<Mutex<Delivered>>,
+    sub: FlumeSubscriber<'static>,
+    tx: Sender<AckedSample>,
+) {
+    while let Ok(sample) = sub.recv_async().await {
+        let info = sample.attachment.as_ref().and_then(|a| {
+            Some((
+                attachment_value::<String>(a, ATTACHMENT_PUBLISHER)?,
+                attachment_value::<u64>(a, ATTACHMENT_SN)?,
+                attachment_value::<u64>(a, ATTACHMENT_LOW)?,
+            ))
+        });
+        let Some((publisher, sn, low)) = info else {
+            let _ = tx.send(AckedSample { sample, ack: None });
+            continue;
+        };
+        let key_expr = match OwnedKeyExpr::try_from(format!(
+            "{ACK_PREFIX}/{ACKS_PREFIX}/{publisher}/{name}"
+        )) {
+            Ok(ke) if !publisher.contains('/') && !ke.is_wild() => ke,
+            _ => {
+                tracing::warn!("Received a sample from an invalid publisher: {}", publisher);
+                continue;
+            }
+        };
+        let previous = {
+            let mut delivered = zlock!(delivered);
+            let sns = delivered.entry(publisher.clone()).or_default();
+            *sns = sns.split_off(&low);
+            let previous = sns.get(&sn).copied();
+            if previous.is_none() {
+                sns.insert(sn, false);
+            }
+            previous
+        };
+        match previous {
+            None => {
+                let ack = AckInfo {
+                    key_expr,
+                    publisher,
+                    sn,
+                    session: z.clone(),
+                    delivered: delivered.clone(),
+                };
+                let _ = tx.send(AckedSample {
+                    sample,
+                    ack: Some(ack),
+                });
+            }
+            // The acknowledgement was lost, send it again
+            Some(true) => {
+                if let Err(e) = z.put(&key_expr, sn.to_string()).res().await {
+                    tracing::warn!("Failed to acknowledge publication {}: {}", sn, e);
+                }
+            }
+            // Still being processed by the application
+            Some(false) => {}
+        }
+    }
+}
+
+/// Receives the publications of [`AckedPublisher`]s, to be acknowledged once processed.
+///
+/// Publications are deduplicated: a retransmission of a publication that is being processed or
+/// that was already acknowledged is not delivered again.
+pub struct AckedSubscriber {
+    name: OwnedKeyExpr,
+    key_expr: OwnedKeyExpr,
+    receiver: Receiver<AckedSample>,
+    task_controller: TaskController,
+    _token: LivelinessToken<'static>,
+}
+
+impl Drop for AckedSubscriber {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl AckedSubscriber {
+    pub async fn start(z: Arc<Session>, with: AckedSubscriberConfig) -> ZResult<AckedSubscriber> {
+        let sub = z.declare_subscriber(with.key_expr.clone()).res().await?;
+        let token = z
+            .liveliness()
+            .declare_token(format!(
+                "{ACK_PREFIX}/{RECEIVERS_PREFIX}/{}/{}",
+                with.name, with.key_expr
+            ))
+            .res()
+            .await?;
+        let (tx, receiver) = flume::unbounded();
+
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(samples_handler(
+            z,
+            with.name.clone(),
+            Arc::new(Mutex::new(Default::default())),
+            sub,
+            tx,
+        ));
+        Ok(AckedSubscriber {
+            name: with.name,
+            key_expr: with.key_expr,
+            receiver,
+            task_controller,
+            _token: token,
+        })
+    }
+
+    /// Returns the name of this receiver.
+    pub fn name(&self) -> &keyexpr {
+        &self.name
+    }
+
+    /// Returns the key expression of this subscriber.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+
+    /// Receives the next sample.
+    pub async fn recv_async(&self) -> ZResult<AckedSample> {
+        self.receiver
+            .recv_async()
+            .await
+            .map_err(|e| zerror!("{}", e).into())
+    }
+
+    /// Receives the next sample, blocking the current thread.
+    pub fn recv(&self) -> ZResult<AckedSample> {
+        self.receiver.recv().map_err(|e| zerror!("{}", e).into())
+    }
+}
@@ -0,0 +1,531 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To transfer large payloads as chunks fetched with queries.
+//!
+//! A [`BlobServer`] splits a blob (e.g. a firmware image) in chunks and answers the queries on
+//! `<key_expr>/manifest` with the [`BlobManifest`] describing them, and on
+//! `<key_expr>/chunk/<index>` with the chunks themselves.
+//!
+//! A [`BlobFetcher`] fetches the chunks in parallel into a `<path>.part` file, checking each
+//! of them against the SHA3-256 digest of the manifest and retrying the failed ones. The file is
+//! renamed to `<path>` once complete. If the transfer is interrupted, fetching the same blob
+//! to the same path again resumes it: the chunks already present in `<path>.part` with the
+//! expected digest are not fetched again.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use std::sync::Arc;
+//! use zenoh::prelude::r#async::*;
+//! use zenoh_ext::blob::*;
+//!
+//! let session = Arc::new(zenoh::open(config::peer()).res().await.unwrap());
+//! let _server = BlobServer::serve_file(
+//!     session.clone(),
+//!     BlobServerConfig::new("firmware/v2").unwrap(),
+//!     "/tmp/firmware-v2.bin",
+//! )
+//! .await
+//! .unwrap();
+//!
+//! let manifest = BlobFetcher::fetch(
+//!     session,
+//!     BlobFetcherConfig::new("firmware/v2").unwrap().parallelism(8),
+//!     "/tmp/firmware-v2.download",
+//! )
+//! .await
+//! .unwrap();
+//! println!("Fetched {} bytes", manifest.size);
+//! # }
+//! ```
+
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::convert::TryInto;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::query::ErrorCode;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+use zenoh_result::{bail, zerror};
+use zenoh_task::TaskController;
+
+const MANIFEST_POSTFIX: &str = "manifest";
+const CHUNK_POSTFIX: &str = "chunk";
+const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+const DEFAULT_PARALLELISM: usize = 4;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_RETRIES: usize = 5;
+const DEFAULT_RETRY_PERIOD: Duration = Duration::from_secs(1);
+
+/// The SHA3-256 digest of a chunk.
+pub type ChunkDigest = [u8; 32];
+
+fn digest(data: &[u8]) -> ChunkDigest {
+    Sha3_256::digest(data).into()
+}
+
+/// The description of a blob served by a [`BlobServer`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlobManifest {
+    /// The size of the blob in bytes.
+    pub size: u64,
+    /// The size of the chunks in bytes, the last one being possibly shorter.
+    pub chunk_size: u32,
+    /// The digests of the chunks.
+    pub chunks: Vec<ChunkDigest>,
+}
+
+impl BlobManifest {
+    /// Returns the offset and the length of the chunk with the given index.
+    fn chunk_range(&self, index: usize) -> (u64, usize) {
+        let offset = index as u64 * self.chunk_size as u64;
+        let len = (self.size - offset).min(self.chunk_size as u64);
+        (offset, len as usize)
+    }
+}
+
+enum BlobSource {
+    Bytes(Vec<u8>),
+    File(Mutex<File>),
+}
+
+impl BlobSource {
+    fn len(&self) -> ZResult<u64> {
+        match self {
+            BlobSource::Bytes(data) => Ok(data.len() as u64),
+            BlobSource::File(file) => Ok(zlock!(file).metadata()?.len()),
+        }
+    }
+
+    fn read(&self, offset: u64, len: usize) -> ZResult<Vec<u8>> {
+        match self {
+            BlobSource::Bytes(data) => Ok(data[offset as usize..offset as usize + len].to_vec()),
+            BlobSource::File(file) => read_at(&mut zlock!(file), offset, len),
+        }
+    }
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> ZResult<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// The configuration of a [`BlobServer`].
+#[derive(Debug, Clone)]
+pub struct BlobServerConfig {
+    key_expr: OwnedKeyExpr,
+    chunk_size: u32,
+}
+
+impl BlobServerConfig {
+    pub fn new<T>(key_expr: T) -> ZResult<BlobServerConfig>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "Blob key expression is not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        Ok(BlobServerConfig {
+            key_expr,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        })
+    }
+
+    /// Change the size of the chunks in bytes.
+    pub fn chunk_size(mut self, size: u32) -> Self {
+        self.chunk_size = size;
+        self
+    }
+}
+
+struct BlobServerState {
+    key_expr: OwnedKeyExpr,
+    manifest: BlobManifest,
+    source: BlobSource,
+}
+
+impl BlobServerState {
+    fn answer(&self, query: &Query) -> ZResult<Option<Vec<u8>>> {
+        let Some(resource) = query
+            .key_expr()
+            .as_str()
+            .strip_prefix(self.key_expr.as_str())
+            .and_then(|s| s.strip_prefix('/'))
+        else {
+            return Ok(None);
+        };
+        if resource == MANIFEST_POSTFIX {
+            return Ok(Some(bincode::serialize(&self.manifest)?));
+        }
+        let Some(index) = resource
+            .strip_prefix(CHUNK_POSTFIX)
+            .and_then(|s| s.strip_prefix('/'))
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|i| *i < self.manifest.chunks.len())
+        else {
+            return Ok(None);
+        };
+        let (offset, len) = self.manifest.chunk_range(index);
+        self.source.read(offset, len).map(Some)
+    }
+}
+
+async fn queries_handler(
+    state: Arc<BlobServerState>,
+    queryable: Queryable<'static, flume::Receiver<Query>>,
+) {
+    while let Ok(query) = queryable.recv_async().await {
+        // wildcard queries are not answered, not to send the whole blob at once
+        if query.key_expr().is_wild() {
+            continue;
+        }
+        let res = match state.answer(&query) {
+            Ok(Some(buf)) => {
+                query
+                    .reply(Ok(Sample::new(query.key_expr().clone(), buf)))
+                    .res()
+                    .await
+            }
+            Ok(None) => {
+                query
+                    .reply_err(ErrorCode::NotFound, "No such blob resource")
+                    .res()
+                    .await
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read blob {}: {}", &state.key_expr, e);
+                query
+                    .reply_err(ErrorCode::Internal, e.to_string())
+                    .res()
+                    .await
+            }
+        };
+        if let Err(e) = res {
+            tracing::warn!("Error replying to query on {}: {}", query.key_expr(), e);
+        }
+    }
+}
+
+/// A server of a blob, split in chunks.
+pub struct BlobServer {
+    state: Arc<BlobServerState>,
+    task_controller: TaskController,
+}
+
+impl Drop for BlobServer {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl BlobServer {
+    /// Serves the given bytes.
+    pub async fn serve(
+        z: Arc<Session>,
+        with: BlobServerConfig,
+        data: Vec<u8>,
+    ) -> ZResult<BlobServer> {
+        Self::start(z, with, BlobSource::Bytes(data)).await
+    }
+
+    /// Serves the content of the file at the given path, read on demand.
+    ///
+    /// The file must not be modified while served.
+    pub async fn serve_file<P>(
+        z: Arc<Session>,
+        with: BlobServerConfig,
+        path: P,
+    ) -> ZResult<BlobServer>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path.as_ref())
+            .map_err(|e| zerror!("Failed to open blob file {:?}: {}", path.as_ref(), e))?;
+        Self::start(z, with, BlobSource::File(Mutex::new(file))).await
+    }
+
+    async fn start(
+        z: Arc<Session>,
+        with: BlobServerConfig,
+        source: BlobSource,
+    ) -> ZResult<BlobServer> {
+        if with.chunk_size == 0 {
+            bail!("Chunk size must be greater than zero");
+        }
+        let size = source.len()?;
+        let mut manifest = BlobManifest {
+            size,
+            chunk_size: with.chunk_size,
+            chunks: vec![],
+        };
+        let chunk_size = with.chunk_size as u64;
+        let count = ((size + chunk_size - 1) / chunk_size) as usize;
+        for index in 0..count {
+            let (offset, len) = manifest.chunk_range(index);
+            manifest.chunks.push(digest(&source.read(offset, len)?));
+        }
+        tracing::debug!(
+            "Serving blob {} of {} bytes in {} chunks",
+            &with.key_expr,
+            size,
+            count
+        );
+
+        let queryable = z
+            .declare_queryable(format!("{}/**", with.key_expr))
+            .res()
+            .await?;
+        let state = Arc::new(BlobServerState {
+            key_expr: with.key_expr,
+            manifest,
+            source,
+        });
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(queries_handler(state.clone(), queryable));
+        Ok(BlobServer {
+            state,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of the served blob.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.state.key_expr
+    }
+
+    /// Returns the manifest of the served blob.
+    pub fn manifest(&self) -> &BlobManifest {
+        &self.state.manifest
+    }
+}
+
+/// The configuration of a [`BlobFetcher`].
+#[derive(Debug, Clone)]
+pub struct BlobFetcherConfig {
+    key_expr: OwnedKeyExpr,
+    parallelism: usize,
+    timeout: Duration,
+    retries: usize,
+    retry_period: Duration,
+}
+
+impl BlobFetcherConfig {
+    pub fn new<T>(key_expr: T) -> ZResult<BlobFetcherConfig>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "Blob key expression is not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        Ok(BlobFetcherConfig {
+            key_expr,
+            parallelism: DEFAULT_PARALLELISM,
+            timeout: DEFAULT_TIMEOUT,
+            retries: DEFAULT_RETRIES,
+            retry_period: DEFAULT_RETRY_PERIOD,
+        })
+    }
+
+    /// Change the maximum number of chunks fetched concurrently.
+    pub fn parallelism(mut self, n: usize) -> Self {
+        self.parallelism = n;
+        self
+    }
+
+    /// Change the timeout of the queries.
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = d;
+        self
+    }
+
+    /// Change the number of times a failed query is retried before giving up.
+    pub fn retries(mut self, n: usize) -> Self {
+        self.retries = n;
+        self
+    }
+
+    /// Change the delay before retrying a failed query, doubled at each retry.
+    pub fn retry_period(mut self, d: Duration) -> Self {
+        self.retry_period = d;
+        self
+    }
+}
+
+/// A fetcher of the blobs served by [`BlobServer`]s.
+pub struct BlobFetcher;
+
+impl BlobFetcher {
+    /// Fetches a blob into the file at the given path, returning its manifest.
+    ///
+    /// On failure, the chunks fetched so far are kept in `<path>.part`, so that fetching the
+    /// same blob to the same path again resumes the transfer.
+    pub async fn fetch<P>(
+        z: Arc<Session>,
+        with: BlobFetcherConfig,
+        path: P,
+    ) -> ZResult<BlobManifest>
+    where
+        P: AsRef<Path>,
+    {
+        if with.parallelism == 0 {
+            bail!("Parallelism must be greater than zero");
+        }
+        let manifest_ke = format!("{}/{MANIFEST_POSTFIX}", with.key_expr);
+        let buf = query_with_retries(&z, &with, &manifest_ke).await?;
+        let manifest: BlobManifest = bincode::deserialize(&buf)
+            .map_err(|e| zerror!("Invalid manifest for blob {}: {}", &with.key_expr, e))?;
+
+        let mut part_path = path.as_ref().as_os_str().to_owned();
+        part_path.push(".part");
+        let part_path = PathBuf::from(part_path);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&part_path)
+            .map_err(|e| zerror!("Failed to open {:?}: {}", &part_path, e))?;
+
+        // resume the transfer, skipping the chunks already fetched
+        let resumed = file.metadata()?.len() == manifest.size;
+        if !resumed {
+            file.set_len(manifest.size)?;
+        }
+        let mut missing = vec![];
+        for (index, expected) in manifest.chunks.iter().enumerate() {
+            let (offset, len) = manifest.chunk_range(index);
+            if !resumed || digest(&read_at(&mut file, offset, len)?) != *expected {
+                missing.push(index);
+            }
+        }
+        tracing::debug!(
+            "Fetching {} of the {} chunks of blob {}",
+            missing.len(),
+            manifest.chunks.len(),
+            &with.key_expr
+        );
+
+        let file = Mutex::new(file);
+        stream::iter(missing)
+            .map(|index| fetch_chunk(&z, &with, &manifest, &file, index))
+            .buffer_unordered(with.parallelism)
+            .try_collect::<()>()
+            .await?;
+
+        let file = file.into_inner().map_err(|e| zerror!("{}", e))?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&part_path, path.as_ref())?;
+        Ok(manifest)
+    }
+}
+
+async fn query_with_retries(
+    z: &Session,
+    with: &BlobFetcherConfig,
+    key_expr: &str,
+) -> ZResult<Vec<u8>> {
+    let mut period = with.retry_period;
+    let mut attempt = 0;
+    loop {
+        let res = match z.get(key_expr).timeout(with.timeout).first().res().await {
+            Ok(Some(reply)) => reply
+                .into_result()
+                .map(|sample| sample.value.payload.contiguous().into_owned())
+                .map_err(|e| zerror!("{}", e).into()),
+            Ok(None) => Err(zerror!("No reply").into()),
+            Err(e) => Err(e),
+        };
+        match res {
+            Ok(buf) => return Ok(buf),
+            Err(e) if attempt >= with.retries => {
+                bail!(
+                    "Failed to fetch {} after {} attempts: {}",
+                    key_expr,
+                    attempt + 1,
+                    e
+                )
+            }
+            Err(e) => {
+                tracing::debug!(
+                    "Failed to fetch {}, retrying in {:?}: {}",
+                    key_expr,
+                    period,
+                    e
+                );
+                tokio::time::sleep(period).await;
+                period *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+async fn fetch_chunk(
+    z: &Session,
+    with: &BlobFetcherConfig,
+    manifest: &BlobManifest,
+    file: &Mutex<File>,
+    index: usize,
+) -> ZResult<()> {
+    let chunk_ke = format!("{}/{CHUNK_POSTFIX}/{index}", with.key_expr);
+    let mut attempt = 0;
+    loop {
+        let buf = query_with_retries(z, with, &chunk_ke).await?;
+        if digest(&buf) == manifest.chunks[index] {
+            let (offset, _) = manifest.chunk_range(index);
+            let mut file = zlock!(file);
+            file.seek(SeekFrom::Start(offset))?;
+            file.write_all(&buf)?;
+            return Ok(());
+        }
+        if attempt >= with.retries {
+            bail!(
+                "Chunk {} of blob {} failed the integrity check",
+                index,
+                &with.key_expr
+            );
+        }
+        tracing::debug!(
+            "Chunk {} of blob {} failed the integrity check, retrying",
+            index,
+            &with.key_expr
+        );
+        attempt += 1;
+    }
+}
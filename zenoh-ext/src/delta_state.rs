@@ -0,0 +1,491 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To distribute a state as periodic snapshots and deltas.
+//!
+//! A [`StatePublisher`] publishes each update of its state as a delta on
+//! `@state/<key_expr>/delta`, and periodically the full state as a snapshot on
+//! `@state/<key_expr>/snapshot`, where it also answers the queries for the current snapshot.
+//! Both carry the version of the state, which is the number of deltas applied since the
+//! publisher started.
+//!
+//! A [`StateSubscriber`] reconstructs the state from a snapshot and the following deltas.
+//! When it detects a gap in the versions of the deltas, or that the publisher restarted, it
+//! queries a snapshot, and applies the deltas received in the meantime on top of it.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use serde::{Deserialize, Serialize};
+//! use std::sync::Arc;
+//! use zenoh::prelude::r#async::*;
+//! use zenoh_ext::delta_state::*;
+//!
+//! #[derive(Clone, Default, Serialize, Deserialize)]
+//! struct Pose {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! impl DeltaState for Pose {
+//!     type Delta = (f64, f64);
+//!
+//!     fn apply(&mut self, (dx, dy): &Self::Delta) {
+//!         self.x += dx;
+//!         self.y += dy;
+//!     }
+//! }
+//!
+//! let session = Arc::new(zenoh::open(config::peer()).res().await.unwrap());
+//! let config = StateConfig::new("robot/pose").unwrap();
+//! let publisher = StatePublisher::start(session.clone(), config.clone(), Pose::default())
+//!     .await
+//!     .unwrap();
+//! let mut subscriber = StateSubscriber::<Pose>::start(session, config).await.unwrap();
+//! publisher.update((1.0, 0.5)).await.unwrap();
+//! subscriber.changed().await.unwrap();
+//! let pose = subscriber.state().unwrap();
+//! println!("x: {}, y: {}", pose.x, pose.y);
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use zenoh::prelude::r#async::*;
+use zenoh::query::ConsolidationMode;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+use zenoh_result::{bail, zerror};
+use zenoh_task::TaskController;
+
+/// The key expression prefix under which the states are distributed.
+pub const STATE_PREFIX: &str = "@state";
+const SNAPSHOT_SUFFIX: &str = "snapshot";
+const DELTA_SUFFIX: &str = "delta";
+const DEFAULT_SNAPSHOT_PERIOD: Duration = Duration::from_secs(10);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static PUBLISHER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A state which can be updated by applying deltas.
+pub trait DeltaState: Clone + Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// The type of the updates of the state.
+    type Delta: Serialize + DeserializeOwned + Send + Sync + 'static;
+
+    /// Applies the given delta to the state.
+    fn apply(&mut self, delta: &Self::Delta);
+}
+
+/// The configuration of a [`StatePublisher`] or a [`StateSubscriber`].
+#[derive(Debug, Clone)]
+pub struct StateConfig {
+    key_expr: OwnedKeyExpr,
+    snapshot_period: Duration,
+    timeout: Duration,
+}
+
+impl StateConfig {
+    pub fn new<T>(key_expr: T) -> ZResult<StateConfig>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "State key expression is not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        Ok(StateConfig {
+            key_expr,
+            snapshot_period: DEFAULT_SNAPSHOT_PERIOD,
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Change the period at which the publisher publishes a snapshot of its state.
+    pub fn snapshot_period(mut self, period: Duration) -> Self {
+        self.snapshot_period = period;
+        self
+    }
+
+    /// Change the timeout of the snapshot queries of the subscriber.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+enum StateMessage<S, D> {
+    Snapshot {
+        publisher: String,
+        version: u64,
+        state: S,
+    },
+    Delta {
+        publisher: String,
+        version: u64,
+        delta: D,
+    },
+}
+
+type Message<S> = StateMessage<S, <S as DeltaState>::Delta>;
+
+struct PublisherState<S> {
+    version: u64,
+    state: S,
+}
+
+fn snapshot<S: DeltaState>(id: &str, state: &Mutex<PublisherState<S>>) -> ZResult<Vec<u8>> {
+    let state = zlock!(state);
+    Ok(bincode::serialize(&Message::<S>::Snapshot {
+        publisher: id.to_string(),
+        version: state.version,
+        state: state.state.clone(),
+    })?)
+}
+
+fn reply_snapshot<S: DeltaState>(id: &str, state: &Mutex<PublisherState<S>>, query: Query) {
+    use zenoh::prelude::sync::SyncResolve;
+
+    match snapshot(id, state) {
+        Ok(buf) => {
+            let sample = Sample::new(query.key_expr().clone(), buf);
+            if let Err(e) = query.reply(Ok(sample)).res_sync() {
+                tracing::warn!("Error replying to query: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to serialize snapshot: {}", e),
+    }
+}
+
+/// A publisher of a state as periodic snapshots and deltas.
+pub struct StatePublisher<S: DeltaState> {
+    session: Arc<Session>,
+    key_expr: OwnedKeyExpr,
+    id: Arc<str>,
+    state: Arc<Mutex<PublisherState<S>>>,
+    _queryable: Queryable<'static, ()>,
+    task_controller: TaskController,
+}
+
+impl<S: DeltaState> Drop for StatePublisher<S> {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl<S: DeltaState> StatePublisher<S> {
+    /// Starts publishing the given initial state.
+    pub async fn start(z: Arc<Session>, with: StateConfig, state: S) -> ZResult<StatePublisher<S>> {
+        let key_expr = with.key_expr;
+        let id: Arc<str> = format!(
+            "{}_{}",
+            z.zid(),
+            PUBLISHER_COUNTER.fetch_add(1, Ordering::Relaxed)
+        )
+        .into();
+        let state = Arc::new(Mutex::new(PublisherState { version: 0, state }));
+        let snapshot_ke = format!("{STATE_PREFIX}/{key_expr}/{SNAPSHOT_SUFFIX}");
+
+        let quer_id = id.clone();
+        let quer_state = state.clone();
+        let queryable = z
+            .declare_queryable(&snapshot_ke)
+            .callback(move |query| reply_snapshot(&quer_id, &quer_state, query))
+            .res()
+            .await?;
+
+        let task_controller = TaskController::default();
+        let task_session = z.clone();
+        let task_id = id.clone();
+        let task_state = state.clone();
+        let period = with.snapshot_period;
+        task_controller.spawn_abortable(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let res = match snapshot(&task_id, &task_state) {
+                    Ok(buf) => task_session.put(&snapshot_ke, buf).res().await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = res {
+                    tracing::warn!("Failed to publish snapshot on {}: {}", &snapshot_ke, e);
+                }
+            }
+        });
+
+        tracing::debug!("Started StatePublisher on {} with id {}", &key_expr, &id);
+        Ok(StatePublisher {
+            session: z,
+            key_expr,
+            id,
+            state,
+            _queryable: queryable,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of this publisher.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> S {
+        zlock!(self.state).state.clone()
+    }
+
+    /// Returns the current version of the state.
+    pub fn version(&self) -> u64 {
+        zlock!(self.state).version
+    }
+
+    /// Applies the given delta to the state and publishes it.
+    pub async fn update(&self, delta: S::Delta) -> ZResult<()> {
+        let buf = {
+            let mut state = zlock!(self.state);
+            state.state.apply(&delta);
+            state.version += 1;
+            bincode::serialize(&Message::<S>::Delta {
+                publisher: self.id.to_string(),
+                version: state.version,
+                delta,
+            })?
+        };
+        self.session
+            .put(
+                format!("{STATE_PREFIX}/{}/{DELTA_SUFFIX}", &self.key_expr),
+                buf,
+            )
+            .res()
+            .await
+    }
+}
+
+struct Reconstruction<S: DeltaState> {
+    key_expr: OwnedKeyExpr,
+    // the publisher and version of the current state
+    current: Option<(String, u64)>,
+    // the deltas received ahead of the current state
+    pending: BTreeMap<u64, S::Delta>,
+    pending_publisher: Option<String>,
+    tx: watch::Sender<Option<(u64, S)>>,
+}
+
+impl<S: DeltaState> Reconstruction<S> {
+    fn on_snapshot(&mut self, publisher: String, version: u64, state: S) {
+        if let Some((current_publisher, current_version)) = &self.current {
+            if *current_publisher == publisher && *current_version >= version {
+                return;
+            }
+        }
+        tracing::debug!(
+            "StateSubscriber on {}: received snapshot {} from {}",
+            &self.key_expr,
+            version,
+            &publisher
+        );
+        let mut state = state;
+        let mut version = version;
+        if self.pending_publisher.as_ref() == Some(&publisher) {
+            self.pending = self.pending.split_off(&(version + 1));
+            while let Some(delta) = self.pending.remove(&(version + 1)) {
+                state.apply(&delta);
+                version += 1;
+            }
+        } else {
+            self.pending.clear();
+            self.pending_publisher = None;
+        }
+        self.current = Some((publisher, version));
+        self.tx.send_replace(Some((version, state)));
+    }
+
+    /// Applies the given delta, returning `false` if a snapshot is needed to apply it.
+    fn on_delta(&mut self, publisher: String, version: u64, delta: S::Delta) -> bool {
+        match &mut self.current {
+            Some((current_publisher, current_version)) if *current_publisher == publisher => {
+                if version <= *current_version {
+                    return true;
+                }
+                if version == *current_version + 1 {
+                    *current_version = version;
+                    self.tx.send_modify(|state| {
+                        if let Some((v, state)) = state {
+                            state.apply(&delta);
+                            *v = version;
+                        }
+                    });
+                    return true;
+                }
+                tracing::debug!(
+                    "StateSubscriber on {}: missed deltas {}..{} from {}",
+                    &self.key_expr,
+                    *current_version + 1,
+                    version,
+                    &publisher
+                );
+            }
+            _ => (),
+        }
+        if self.pending_publisher.as_ref() != Some(&publisher) {
+            self.pending.clear();
+            self.pending_publisher = Some(publisher);
+        }
+        self.pending.insert(version, delta);
+        false
+    }
+}
+
+/// A subscriber reconstructing a state published by a [`StatePublisher`].
+pub struct StateSubscriber<S: DeltaState> {
+    key_expr: OwnedKeyExpr,
+    rx: watch::Receiver<Option<(u64, S)>>,
+    task_controller: TaskController,
+}
+
+impl<S: DeltaState> Drop for StateSubscriber<S> {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+async fn query_snapshot<S: DeltaState>(
+    z: &Session,
+    snapshot_ke: &str,
+    timeout: Duration,
+    reconstruction: &mut Reconstruction<S>,
+) {
+    let replies = match z
+        .get(snapshot_ke)
+        .consolidation(ConsolidationMode::None)
+        .timeout(timeout)
+        .res()
+        .await
+    {
+        Ok(replies) => replies,
+        Err(e) => {
+            tracing::warn!("Failed to query snapshot on {}: {}", snapshot_ke, e);
+            return;
+        }
+    };
+    while let Ok(reply) = replies.recv_async().await {
+        match reply.sample {
+            Ok(sample) => match decode::<S>(&sample) {
+                Ok(StateMessage::Snapshot {
+                    publisher,
+                    version,
+                    state,
+                }) => reconstruction.on_snapshot(publisher, version, state),
+                Ok(_) => (),
+                Err(e) => tracing::warn!("Received an invalid snapshot: {}", e),
+            },
+            Err(e) => tracing::debug!("Received an error reply to snapshot query: {}", e),
+        }
+    }
+}
+
+fn decode<S: DeltaState>(sample: &Sample) -> ZResult<Message<S>> {
+    bincode::deserialize(&sample.value.payload.contiguous())
+        .map_err(|e| zerror!("Failed to decode state message: {}", e).into())
+}
+
+impl<S: DeltaState> StateSubscriber<S> {
+    pub async fn start(z: Arc<Session>, with: StateConfig) -> ZResult<StateSubscriber<S>> {
+        let key_expr = with.key_expr;
+        let snapshot_ke = format!("{STATE_PREFIX}/{key_expr}/{SNAPSHOT_SUFFIX}");
+        let sub = z
+            .declare_subscriber(format!("{STATE_PREFIX}/{key_expr}/*"))
+            .res()
+            .await?;
+        let (tx, rx) = watch::channel(None);
+        let mut reconstruction = Reconstruction::<S> {
+            key_expr: key_expr.clone(),
+            current: None,
+            pending: BTreeMap::new(),
+            pending_publisher: None,
+            tx,
+        };
+        query_snapshot(&z, &snapshot_ke, with.timeout, &mut reconstruction).await;
+
+        let task_controller = TaskController::default();
+        let timeout = with.timeout;
+        task_controller.spawn_abortable(async move {
+            while let Ok(sample) = sub.recv_async().await {
+                match decode::<S>(&sample) {
+                    Ok(StateMessage::Snapshot {
+                        publisher,
+                        version,
+                        state,
+                    }) => reconstruction.on_snapshot(publisher, version, state),
+                    Ok(StateMessage::Delta {
+                        publisher,
+                        version,
+                        delta,
+                    }) => {
+                        if !reconstruction.on_delta(publisher, version, delta) {
+                            query_snapshot(&z, &snapshot_ke, timeout, &mut reconstruction).await;
+                        }
+                    }
+                    Err(e) => tracing::warn!("{}", e),
+                }
+            }
+        });
+
+        tracing::debug!("Started StateSubscriber on {}", &key_expr);
+        Ok(StateSubscriber {
+            key_expr,
+            rx,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of this subscriber.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+
+    /// Returns the reconstructed state, or `None` if no snapshot was received yet.
+    pub fn state(&self) -> Option<S> {
+        self.rx.borrow().as_ref().map(|(_, state)| state.clone())
+    }
+
+    /// Returns the version of the reconstructed state, or `None` if no snapshot was received yet.
+    pub fn version(&self) -> Option<u64> {
+        self.rx.borrow().as_ref().map(|(version, _)| *version)
+    }
+
+    /// Waits for the reconstructed state to change.
+    pub async fn changed(&mut self) -> ZResult<()> {
+        self.rx
+            .changed()
+            .await
+            .map_err(|_| zerror!("StateSubscriber on {} stopped", &self.key_expr).into())
+    }
+}
@@ -0,0 +1,659 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To encrypt publications end-to-end, so that the routers only forward ciphertext.
+//!
+//! An [`EncryptedPublisher`] encrypts the payload of its publications with AES-256-GCM, using
+//! the [`EncryptionKey`] that its [`KeyProvider`] returns for its key expression. The identifier
+//! of the key is sent in the `e2e.kid` attachment. The key expression, the kind, the timestamp,
+//! the encoding and the attachments of the publications are authenticated along with the payload,
+//! so that a ciphertext can't be replayed on another key or altered, but they are not encrypted.
+//! Deletions carry no payload: they are only authenticated, with a tag sent in the `e2e.tag`
+//! attachment.
+//! An [`EncryptedSubscriber`] only delivers the publications and deletions it can authenticate
+//! with the keys of its own [`KeyProvider`].
+//!
+//! A [`StaticKeyProvider`] holds keys distributed out-of-band, each one scoped to a key
+//! expression. Adding a new key on a scope rotates the key used by the publishers, while the
+//! subscribers can still decrypt the publications made with the previous ones.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use std::sync::Arc;
+//! use zenoh::prelude::r#async::*;
+//! use zenoh_ext::encryption::*;
+//!
+//! let session = Arc::new(zenoh::open(config::peer()).res().await.unwrap());
+//! let provider = StaticKeyProvider::new()
+//!     .with_key("sensors/**", EncryptionKey::new("k1", &[0x42; 32]).unwrap())
+//!     .unwrap();
+//! let config = EncryptionConfig::new("sensors/temperature", Arc::new(provider)).unwrap();
+//! let publisher = EncryptedPublisher::start(session, config).await.unwrap();
+//! publisher.put("21.5").await.unwrap();
+//! # }
+//! ```
+
+use flume::{Receiver, Sender};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::convert::TryInto;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::r#async::*;
+use zenoh::publication::Publisher;
+use zenoh::sample::Attachment;
+use zenoh::subscriber::FlumeSubscriber;
+use zenoh::time::{Timestamp, NTP64};
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+use zenoh_result::{bail, zerror};
+use zenoh_task::TaskController;
+
+/// The attachment carrying the identifier of the key of an encrypted publication.
+pub const ATTACHMENT_KEY_ID: &str = "e2e.kid";
+/// The attachment carrying the authentication tag of an encrypted deletion.
+pub const ATTACHMENT_TAG: &str = "e2e.tag";
+
+/// A symmetric AES-256-GCM key, with an identifier telling the receivers which key to use.
+pub struct EncryptionKey {
+    id: String,
+    key: LessSafeKey,
+}
+
+impl EncryptionKey {
+    /// Creates a key from its identifier and its 32 bytes secret.
+    pub fn new<S>(id: S, secret: &[u8]) -> ZResult<EncryptionKey>
+    where
+        S: Into<String>,
+    {
+        let id = id.into();
+        if id.is_empty() {
+            bail!("The identifier of an encryption key must not be empty");
+        }
+        let key = UnboundKey::new(&AES_256_GCM, secret).map_err(|_| {
+            zerror!(
+                "An encryption key must be {} bytes long",
+                AES_256_GCM.key_len()
+            )
+        })?;
+        Ok(EncryptionKey {
+            id,
+            key: LessSafeKey::new(key),
+        })
+    }
+
+    /// Returns the identifier of this key.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    // The ciphertext is prefixed with the random nonce used to encrypt it
+    fn seal(&self, key_expr: &keyexpr, aad: &[u8], plaintext: &[u8]) -> ZResult<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| zerror!("Failed to generate a nonce"))?;
+        let mut in_out = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut in_out,
+            )
+            .map_err(|_| zerror!("Failed to encrypt a publication on {}", key_expr))?;
+        let mut ciphertext = nonce.to_vec();
+        ciphertext.append(&mut in_out);
+        Ok(ciphertext)
+    }
+
+    fn open(&self, key_expr: &keyexpr, aad: &[u8], ciphertext: &[u8]) -> ZResult<Vec<u8>> {
+        if ciphertext.len() < NONCE_LEN {
+            bail!("Truncated ciphertext on {}", key_expr);
+        }
+        let (nonce, ciphertext) = ciphertext.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| zerror!("Invalid nonce on {}", key_expr))?;
+        let mut in_out = ciphertext.to_vec();
+        let len = self
+            .key
+            .open_in_place(nonce, Aad::from(aad), &mut in_out)
+            .map_err(|_| zerror!("Failed to decrypt a publication on {}", key_expr))?
+            .len();
+        in_out.truncate(len);
+        Ok(in_out)
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionKey")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The data authenticated along with the payload of a publication, each field being prefixed
+/// with its length. Deletions have no encoding, and their tag is not part of their own attachment.
+fn associated_data(
+    key_expr: &keyexpr,
+    kind: SampleKind,
+    timestamp: Option<&Timestamp>,
+    encoding: Option<&Encoding>,
+    attachment: &Attachment,
+) -> Vec<u8> {
+    fn push(aad: &mut Vec<u8>, field: &[u8]) {
+        aad.extend_from_slice(&(field.len() as u64).to_le_bytes());
+        aad.extend_from_slice(field);
+    }
+
+    let mut aad = vec![];
+    push(&mut aad, key_expr.as_bytes());
+    push(&mut aad, kind.to_string().as_bytes());
+    push(
+        &mut aad,
+        timestamp
+            .map(|t| t.to_string())
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+    push(
+        &mut aad,
+        encoding
+            .map(|e| e.to_string())
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+    for (key, value) in attachment {
+        if key.as_slice() != ATTACHMENT_TAG.as_bytes() {
+            push(&mut aad, key.as_slice());
+            push(&mut aad, value.as_slice());
+        }
+    }
+    aad
+}
+
+/// Provides the keys to encrypt and decrypt the publications on a key expression.
+pub trait KeyProvider: Send + Sync {
+    /// Returns the key to encrypt the publications on a key expression, if any.
+    fn encryption_key(&self, key_expr: &keyexpr) -> Option<Arc<EncryptionKey>>;
+
+    /// Returns the key with the given identifier to decrypt the publications on a key
+    /// expression, if it is authorized.
+    fn decryption_key(&self, key_expr: &keyexpr, id: &str) -> Option<Arc<EncryptionKey>>;
+}
+
+/// A [`KeyProvider`] holding keys distributed out-of-band, each one scoped to a key expression.
+#[derive(Debug, Default)]
+pub struct StaticKeyProvider {
+    keys: Vec<(OwnedKeyExpr, Arc<EncryptionKey>)>,
+}
+
+impl StaticKeyProvider {
+    pub fn new() -> StaticKeyProvider {
+        StaticKeyProvider::default()
+    }
+
+    /// Adds a key for the key expressions included in a scope.
+    ///
+    /// The last key added on a scope is the one used to encrypt the publications.
+    pub fn with_key<T>(mut self, scope: T, key: EncryptionKey) -> ZResult<StaticKeyProvider>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let scope = scope.try_into().map_err(|e| e.into())?;
+        if self.keys.iter().any(|(_, k)| k.id == key.id) {
+            bail!("Duplicated encryption key identifier: {}", key.id);
+        }
+        self.keys.push((scope, Arc::new(key)));
+        Ok(self)
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn encryption_key(&self, key_expr: &keyexpr) -> Option<Arc<EncryptionKey>> {
+        self.keys
+            .iter()
+            .rev()
+            .find(|(scope, _)| scope.includes(key_expr))
+            .map(|(_, key)| key.clone())
+    }
+
+    fn decryption_key(&self, key_expr: &keyexpr, id: &str) -> Option<Arc<EncryptionKey>> {
+        self.keys
+            .iter()
+            .find(|(scope, key)| key.id == id && scope.includes(key_expr))
+            .map(|(_, key)| key.clone())
+    }
+}
+
+/// The configuration of an [`EncryptedPublisher`] or of an [`EncryptedSubscriber`].
+#[derive(Clone)]
+pub struct EncryptionConfig {
+    key_expr: OwnedKeyExpr,
+    provider: Arc<dyn KeyProvider>,
+    accept_plaintext: bool,
+}
+
+impl EncryptionConfig {
+    pub fn new<T>(key_expr: T, provider: Arc<dyn KeyProvider>) -> ZResult<EncryptionConfig>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        Ok(EncryptionConfig {
+            key_expr: key_expr.try_into().map_err(|e| e.into())?,
+            provider,
+            accept_plaintext: false,
+        })
+    }
+
+    /// Let an [`EncryptedSubscriber`] deliver the publications that are not encrypted
+    /// (false by default).
+    pub fn accept_plaintext(mut self, enabled: bool) -> Self {
+        self.accept_plaintext = enabled;
+        self
+    }
+}
+
+impl fmt::Debug for EncryptionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionConfig")
+            .field("key_expr", &self.key_expr)
+            .field("accept_plaintext", &self.accept_plaintext)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Publishes values encrypted with the key of its [`KeyProvider`].
+pub struct EncryptedPublisher {
+    session: Arc<Session>,
+    key_expr: OwnedKeyExpr,
+    provider: Arc<dyn KeyProvider>,
+    publisher: Publisher<'static>,
+    last_timestamp: Mutex<Option<Timestamp>>,
+}
+
+impl EncryptedPublisher {
+    pub async fn start(z: Arc<Session>, with: EncryptionConfig) -> ZResult<EncryptedPublisher> {
+        if with.key_expr.is_wild() {
+            bail!(
+                "Encrypted publications are not allowed on wildcard key expressions: {}",
+                with.key_expr
+            );
+        }
+        if with.provider.encryption_key(&with.key_expr).is_none() {
+            bail!("No encryption key for {}", with.key_expr);
+        }
+        let publisher = z.declare_publisher(with.key_expr.clone()).res().await?;
+        Ok(EncryptedPublisher {
+            session: z,
+            key_expr: with.key_expr,
+            provider: with.provider,
+            publisher,
+            last_timestamp: Mutex::new(None),
+        })
+    }
+
+    /// Returns the key expression of this publisher.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+
+    // The timestamp is authenticated, so it is set by the publisher rather than by the routers
+    fn new_timestamp(&self) -> Timestamp {
+        if let Some(hlc) = self.session.hlc() {
+            return hlc.new_timestamp();
+        }
+        // ensure the timestamps of this publisher are strictly increasing
+        let mut last = zlock!(self.last_timestamp);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let mut time = NTP64::from(now);
+        if let Some(last) = last.as_ref() {
+            if time <= *last.get_time() {
+                time = NTP64(last.get_time().as_u64() + 1);
+            }
+        }
+        let timestamp = Timestamp::new(time, (&self.session.zid()).into());
+        *last = Some(timestamp);
+        timestamp
+    }
+
+    fn encryption_key(&self) -> ZResult<Arc<EncryptionKey>> {
+        self.provider
+            .encryption_key(&self.key_expr)
+            .ok_or_else(|| zerror!("No encryption key for {}", self.key_expr).into())
+    }
+
+    /// Encrypts and publishes a value.
+    pub async fn put<IntoValue>(&self, value: IntoValue) -> ZResult<()>
+    where
+        IntoValue: Into<Value>,
+    {
+        let timestamp = self.new_timestamp();
+        let (value, attachment) = encrypt(
+            &self.encryption_key()?,
+            &self.key_expr,
+            SampleKind::Put,
+            value.into(),
+            &timestamp,
+        )?;
+        self.publisher
+            .put(value)
+            .timestamp(timestamp)
+            .with_attachment(attachment)
+            .res()
+            .await
+    }
+
+    /// Publishes an authenticated deletion.
+    pub async fn delete(&self) -> ZResult<()> {
+        let timestamp = self.new_timestamp();
+        let (_, attachment) = encrypt(
+            &self.encryption_key()?,
+            &self.key_expr,
+            SampleKind::Delete,
+            Value::empty(),
+            &timestamp,
+        )?;
+        self.publisher
+            .delete()
+            .timestamp(timestamp)
+            .with_attachment(attachment)
+            .res()
+            .await
+    }
+}
+
+/// Returns the encrypted value of a publication and its attachment.
+fn encrypt(
+    key: &EncryptionKey,
+    key_expr: &keyexpr,
+    kind: SampleKind,
+    value: Value,
+    timestamp: &Timestamp,
+) -> ZResult<(Value, Attachment)> {
+    let mut attachment = Attachment::new();
+    attachment.insert(ATTACHMENT_KEY_ID, key.id());
+    match kind {
+        SampleKind::Put => {
+            let aad = associated_data(
+                key_expr,
+                kind,
+                Some(timestamp),
+                Some(&value.encoding),
+                &attachment,
+            );
+            let ciphertext = key.seal(key_expr, &aad, &value.payload.contiguous())?;
+            Ok((
+                Value::new(ciphertext.into()).encoding(value.encoding),
+                attachment,
+            ))
+        }
+        SampleKind::Delete => {
+            let aad = associated_data(key_expr, kind, Some(timestamp), None, &attachment);
+            let tag = key.seal(key_expr, &aad, &[])?;
+            attachment.insert(ATTACHMENT_TAG, &tag);
+            Ok((Value::empty(), attachment))
+        }
+    }
+}
+
+fn decrypt(sample: Sample, provider: &dyn KeyProvider, accept_plaintext: bool) -> Option<Sample> {
+    let id = sample
+        .attachment
+        .as_ref()
+        .and_then(|a| a.get(&ATTACHMENT_KEY_ID))
+        .and_then(|id| String::from_utf8(id.to_vec()).ok());
+    let Some(id) = id else {
+        if !accept_plaintext {
+            tracing::debug!("Dropping a plaintext publication on {}", sample.key_expr);
+            return None;
+        }
+        return Some(sample);
+    };
+    let Some(key) = provider.decryption_key(&sample.key_expr, &id) else {
+        tracing::debug!(
+            "Dropping a publication on {} encrypted with an unknown key: {}",
+            sample.key_expr,
+            id
+        );
+        return None;
+    };
+    let attachment = sample.attachment.clone().unwrap_or_default();
+    let aad = associated_data(
+        &sample.key_expr,
+        sample.kind,
+        sample.timestamp.as_ref(),
+        (sample.kind == SampleKind::Put).then_some(&sample.value.encoding),
+        &attachment,
+    );
+    // A deletion is authenticated by its tag, which encrypts an empty payload
+    let ciphertext = match sample.kind {
+        SampleKind::Put => sample.value.payload.contiguous().into_owned(),
+        SampleKind::Delete => match attachment.get(&ATTACHMENT_TAG) {
+            Some(tag) => tag.to_vec(),
+            None => {
+                tracing::debug!(
+                    "Dropping an unauthenticated deletion on {}",
+                    sample.key_expr
+                );
+                return None;
+            }
+        },
+    };
+    match key.open(&sample.key_expr, &aad, &ciphertext) {
+        Ok(plaintext) => {
+            let mut sample = sample;
+            if sample.kind == SampleKind::Put {
+                sample.value.payload = plaintext.into();
+            }
+            Some(sample)
+        }
+        Err(e) => {
+            tracing::warn!("{}", e);
+            None
+        }
+    }
+}
+
+async fn samples_handler(
+    provider: Arc<dyn KeyProvider>,
+    accept_plaintext: bool,
+    sub: FlumeSubscriber<'static>,
+    tx: Sender<Sample>,
+) {
+    while let Ok(sample) = sub.recv_async().await {
+        if let Some(sample) = decrypt(sample, provider.as_ref(), accept_plaintext) {
+            let _ = tx.send(sample);
+        }
+    }
+}
+
+/// Receives the publications of [`EncryptedPublisher`]s that can be decrypted with the keys of
+/// its [`KeyProvider`].
+pub struct EncryptedSubscriber {
+    key_expr: OwnedKeyExpr,
+    receiver: Receiver<Sample>,
+    task_controller: TaskController,
+}
+
+impl Drop for EncryptedSubscriber {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl EncryptedSubscriber {
+    pub async fn start(z: Arc<Session>, with: EncryptionConfig) -> ZResult<EncryptedSubscriber> {
+        let sub = z.declare_subscriber(with.key_expr.clone()).res().await?;
+        let (tx, receiver) = flume::unbounded();
+
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(samples_handler(
+            with.provider,
+            with.accept_plaintext,
+            sub,
+            tx,
+        ));
+        Ok(EncryptedSubscriber {
+            key_expr: with.key_expr,
+            receiver,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of this subscriber.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+
+    /// Receives the next decrypted sample.
+    pub async fn recv_async(&self) -> ZResult<Sample> {
+        self.receiver
+            .recv_async()
+            .await
+            .map_err(|e| zerror!("{}", e).into())
+    }
+
+    /// Receives the next decrypted sample, blocking the current thread.
+    pub fn recv(&self) -> ZResult<Sample> {
+        self.receiver.recv().map_err(|e| zerror!("{}", e).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zenoh::time::TimestampId;
+
+    const KEY_EXPR: &str = "test/encryption";
+
+    fn provider(id: &str, secret: u8) -> StaticKeyProvider {
+        StaticKeyProvider::new()
+            .with_key("test/**", EncryptionKey::new(id, &[secret; 32]).unwrap())
+            .unwrap()
+    }
+
+    fn timestamp(time: u64) -> Timestamp {
+        Timestamp::new(NTP64(time), TimestampId::try_from([1]).unwrap())
+    }
+
+    fn encrypted(provider: &StaticKeyProvider, kind: SampleKind) -> Sample {
+        let key_expr = KeyExpr::try_from(KEY_EXPR).unwrap();
+        let key = provider.encryption_key(&key_expr).unwrap();
+        let value = Value::from("21.5").encoding(KnownEncoding::TextPlain.into());
+        let (value, attachment) = encrypt(&key, &key_expr, kind, value, &timestamp(1)).unwrap();
+        let mut sample = Sample::new(key_expr, value)
+            .with_timestamp(timestamp(1))
+            .with_attachment(attachment);
+        sample.kind = kind;
+        sample
+    }
+
+    fn payload(sample: &Sample) -> Vec<u8> {
+        sample.value.payload.contiguous().into_owned()
+    }
+
+    #[test]
+    fn round_trip() {
+        let provider = provider("k1", 0x42);
+
+        let put = encrypted(&provider, SampleKind::Put);
+        assert_ne!(payload(&put), b"21.5");
+        let put = decrypt(put, &provider, false).unwrap();
+        assert_eq!(payload(&put), b"21.5");
+        assert_eq!(put.value.encoding, KnownEncoding::TextPlain.into());
+
+        let delete = encrypted(&provider, SampleKind::Delete);
+        let delete = decrypt(delete, &provider, false).unwrap();
+        assert_eq!(delete.kind, SampleKind::Delete);
+    }
+
+    #[test]
+    fn tampered() {
+        let provider = provider("k1", 0x42);
+        let tamper = |kind, f: &dyn Fn(&mut Sample)| {
+            let mut sample = encrypted(&provider, kind);
+            f(&mut sample);
+            decrypt(sample, &provider, true)
+        };
+
+        for kind in [SampleKind::Put, SampleKind::Delete] {
+            // Replayed on another key expression of the scope of the key
+            assert!(tamper(kind, &|s| {
+                s.key_expr = KeyExpr::try_from("test/other").unwrap();
+            })
+            .is_none());
+            assert!(tamper(kind, &|s| s.timestamp = Some(timestamp(2))).is_none());
+            assert!(tamper(kind, &|s| s.timestamp = None).is_none());
+            assert!(tamper(kind, &|s| {
+                s.attachment.as_mut().unwrap().insert("user", "value");
+            })
+            .is_none());
+        }
+        assert!(tamper(SampleKind::Put, &|s| {
+            let mut ciphertext = payload(s);
+            ciphertext[NONCE_LEN] ^= 1;
+            s.value.payload = ciphertext.into();
+        })
+        .is_none());
+        assert!(tamper(SampleKind::Put, &|s| {
+            s.value.encoding = KnownEncoding::AppJson.into();
+        })
+        .is_none());
+        // A put can't be turned into a deletion, nor the other way around
+        assert!(tamper(SampleKind::Put, &|s| s.kind = SampleKind::Delete).is_none());
+        assert!(tamper(SampleKind::Delete, &|s| s.kind = SampleKind::Put).is_none());
+        // A deletion without its tag is not authenticated
+        assert!(tamper(SampleKind::Delete, &|s| {
+            let mut attachment = Attachment::new();
+            attachment.insert(ATTACHMENT_KEY_ID, "k1");
+            s.attachment = Some(attachment);
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn wrong_key() {
+        let publisher = provider("k1", 0x42);
+        for kind in [SampleKind::Put, SampleKind::Delete] {
+            // Same identifier, another secret
+            let sample = encrypted(&publisher, kind);
+            assert!(decrypt(sample, &provider("k1", 0x43), true).is_none());
+            // Unknown identifier
+            let sample = encrypted(&publisher, kind);
+            assert!(decrypt(sample, &provider("k2", 0x42), true).is_none());
+        }
+    }
+
+    #[test]
+    fn plaintext() {
+        let provider = provider("k1", 0x42);
+        let put = Sample::new(KeyExpr::try_from(KEY_EXPR).unwrap(), "21.5");
+        let mut delete = put.clone();
+        delete.kind = SampleKind::Delete;
+
+        assert!(decrypt(put.clone(), &provider, false).is_none());
+        assert!(decrypt(delete.clone(), &provider, false).is_none());
+        assert_eq!(payload(&decrypt(put, &provider, true).unwrap()), b"21.5");
+        assert!(decrypt(delete, &provider, true).is_some());
+    }
+}
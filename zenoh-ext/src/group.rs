@@ -24,6 +24,7 @@ use std::ops::Add;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
+use zenoh::liveliness::LivelinessToken;
 use zenoh::prelude::r#async::*;
 use zenoh::publication::Publisher;
 use zenoh::query::ConsolidationMode;
@@ -158,11 +159,13 @@ struct GroupState {
     members: Mutex<HashMap<OwnedKeyExpr, (Member, Instant)>>,
     group_publisher: Publisher<'static>,
     user_events_tx: Mutex<Option<Sender<GroupEvent>>>,
+    leader: Mutex<OwnedKeyExpr>,
     cond: Condition,
 }
 
 pub struct Group {
     state: Arc<GroupState>,
+    _token: LivelinessToken<'static>,
     task_controller: TaskController,
 }
 
@@ -173,6 +176,33 @@ impl Drop for Group {
     }
 }
 
+/// Elects the member with the greatest id as the leader of the group, returning the event
+/// to notify if the leader changed.
+async fn elect_leader(
+    state: &GroupState,
+    ms: &HashMap<OwnedKeyExpr, (Member, Instant)>,
+) -> Option<GroupEvent> {
+    let mid = ms
+        .keys()
+        .chain(std::iter::once(&state.local_member.mid))
+        .max_by(|a, b| a.as_str().cmp(b.as_str()))
+        .unwrap()
+        .clone();
+    let mut leader = state.leader.lock().await;
+    if *leader == mid {
+        return None;
+    }
+    tracing::debug!("New leader for group {}: {}", &state.gid, &mid);
+    *leader = mid.clone();
+    Some(GroupEvent::NewLeader(NewLeaderEvent { mid }))
+}
+
+fn notify_new_leader(u_evt: &Option<Sender<GroupEvent>>, evt: Option<GroupEvent>) {
+    if let (Some(tx), Some(evt)) = (u_evt, evt) {
+        tx.send(evt).unwrap()
+    }
+}
+
 async fn keep_alive_task(state: Arc<GroupState>) {
     let mid = state.local_member.mid.clone();
     let evt = GroupNetEvent::KeepAlive(KeepAliveEvent { mid });
@@ -205,6 +235,7 @@ async fn watchdog_task(s: Arc<GroupState>, period: Duration) {
         }
         if !expired_members.is_empty() {
             tracing::debug!("Other members list: {:?}", ms.keys());
+            let new_leader = elect_leader(&s, &ms).await;
             drop(ms);
             let u_evt = &*s.user_events_tx.lock().await;
             for e in expired_members {
@@ -213,6 +244,7 @@ async fn watchdog_task(s: Arc<GroupState>, period: Duration) {
                         .unwrap()
                 }
             }
+            notify_new_leader(u_evt, new_leader);
         }
     }
 }
@@ -253,23 +285,30 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                     let mut ms = state.members.lock().await;
                     ms.insert(je.member.mid.clone(), (je.member.clone(), alive_till));
                     tracing::debug!("Other members list: {:?}", ms.keys());
+                    let new_leader = elect_leader(&state, &ms).await;
                     state.cond.notify_all();
                     drop(ms);
                     let u_evt = &*state.user_events_tx.lock().await;
                     if let Some(tx) = u_evt {
                         tx.send(GroupEvent::Join(je)).unwrap()
                     }
+                    notify_new_leader(u_evt, new_leader);
                 }
                 GroupNetEvent::Leave(le) => {
                     tracing::debug!("Member leave: {:?}", &le.mid);
                     let mut ms = state.members.lock().await;
-                    ms.remove(&le.mid);
+                    if ms.remove(&le.mid).is_none() {
+                        // already removed on the withdrawal of its liveliness token
+                        continue;
+                    }
                     tracing::debug!("Other members list: {:?}", ms.keys());
+                    let new_leader = elect_leader(&state, &ms).await;
                     drop(ms);
                     let u_evt = &*state.user_events_tx.lock().await;
                     if let Some(tx) = u_evt {
                         tx.send(GroupEvent::Leave(le)).unwrap()
                     }
+                    notify_new_leader(u_evt, new_leader);
                 }
                 GroupNetEvent::KeepAlive(kae) => {
                     tracing::debug!(
@@ -339,7 +378,10 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                                         }
                                     }
                                 }
+                                let new_leader = elect_leader(&state, &mm).await;
                                 state.cond.notify_all();
+                                drop(mm);
+                                notify_new_leader(&*state.user_events_tx.lock().await, new_leader);
                             }
                         }
                     } else {
@@ -354,6 +396,43 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
     }
 }
 
+async fn liveliness_handler(z: Arc<Session>, state: Arc<GroupState>) {
+    let prefix = format!("{}/{}/", GROUP_PREFIX, &state.gid);
+    let sub = z
+        .liveliness()
+        .declare_subscriber(format!("{prefix}**"))
+        .res()
+        .await
+        .unwrap();
+    while let Ok(s) = sub.recv_async().await {
+        if s.kind != SampleKind::Delete {
+            continue;
+        }
+        // the liveliness token of a member is withdrawn when it leaves or its session closes
+        let Some(mid) = s
+            .key_expr
+            .as_str()
+            .strip_prefix(&prefix)
+            .and_then(|mid| OwnedKeyExpr::try_from(mid).ok())
+        else {
+            continue;
+        };
+        let mut ms = state.members.lock().await;
+        if ms.remove(&mid).is_none() {
+            continue;
+        }
+        tracing::debug!("Member liveliness lost: {}", &mid);
+        tracing::debug!("Other members list: {:?}", ms.keys());
+        let new_leader = elect_leader(&state, &ms).await;
+        drop(ms);
+        let u_evt = &*state.user_events_tx.lock().await;
+        if let Some(tx) = u_evt {
+            tx.send(GroupEvent::Leave(LeaveEvent { mid })).unwrap()
+        }
+        notify_new_leader(u_evt, new_leader);
+    }
+}
+
 impl Group {
     pub async fn join<T>(z: Arc<Session>, group: T, with: Member) -> ZResult<Group>
     where
@@ -378,6 +457,7 @@ impl Group {
             members: Mutex::new(Default::default()),
             group_publisher: publisher,
             user_events_tx: Mutex::new(Default::default()),
+            leader: Mutex::new(with.mid.clone()),
             cond: Condition::new(),
         });
         let is_auto_liveliness = matches!(with.liveliness, MemberLiveliness::Auto);
@@ -388,6 +468,16 @@ impl Group {
         let buf = bincode::serialize(&join_evt).unwrap();
         let _ = state.group_publisher.put(buf).res().await;
 
+        // withdrawn on drop, allowing the other members to detect the departure before the lease expires
+        let token = z
+            .liveliness()
+            .declare_token(format!(
+                "{GROUP_PREFIX}/{}/{}",
+                &state.gid, &state.local_member.mid
+            ))
+            .res()
+            .await?;
+
         let task_controller = TaskController::default();
         // If the liveliness is manual it is the user who has to assert it.
         if is_auto_liveliness {
@@ -395,13 +485,24 @@ impl Group {
         }
         task_controller.spawn_abortable(net_event_handler(z.clone(), state.clone()));
         task_controller.spawn_abortable(query_handler(z.clone(), state.clone()));
+        task_controller.spawn_abortable(liveliness_handler(z.clone(), state.clone()));
         task_controller.spawn_abortable(watchdog_task(state.clone(), Duration::from_secs(1)));
         Ok(Group {
             state,
+            _token: token,
             task_controller,
         })
     }
 
+    /// Leaves the group, announcing the departure of the local member to the others.
+    pub async fn leave(self) -> ZResult<()> {
+        let mid = self.state.local_member.mid.clone();
+        tracing::debug!("Sending Leave Message for local member: {}", &mid);
+        let leave_evt = GroupNetEvent::Leave(LeaveEvent { mid });
+        let buf = bincode::serialize(&leave_evt).unwrap();
+        self.state.group_publisher.put(buf).res().await
+    }
+
     /// Returns a receivers that will allow to receive notifications for group events.
     /// Notice that there can be a single subscription at the time, each call to subscribe
     /// will cancel the previous subscription.
@@ -480,4 +581,12 @@ impl Group {
         }
         leader
     }
+
+    /// Returns `true` if the local member is the leader of this group.
+    ///
+    /// A [`GroupEvent::NewLeader`] is notified each time the leader changes,
+    /// e.g. when the previous one left the group or its lease expired.
+    pub async fn is_leader(&self) -> bool {
+        *self.state.leader.lock().await == self.state.local_member.mid
+    }
 }
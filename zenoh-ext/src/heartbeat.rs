@@ -0,0 +1,479 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To publish application-level heartbeats and monitor a fleet of nodes.
+//!
+//! A [`Heartbeat`] periodically publishes the [`NodeStatus`] of a node as JSON under
+//! `@health/<name>`. A [`FleetMonitor`] subscribes to `@health/**` and tracks the
+//! status of all the nodes, detecting the ones that stopped sending heartbeats.
+//!
+//! A [`Heartbeat`] also declares a liveliness token on `@health/<name>`, so that the
+//! [`FleetMonitor`]s learn immediately that a node left when its session goes away,
+//! instead of waiting for the node to miss its heartbeats.
+
+use flume::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryInto;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use zenoh::liveliness::LivelinessToken;
+use zenoh::prelude::r#async::*;
+use zenoh::publication::Publisher;
+use zenoh::subscriber::FlumeSubscriber;
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_result::{bail, zerror};
+use zenoh_task::TaskController;
+
+/// The key expression prefix under which heartbeats are published.
+pub const HEALTH_PREFIX: &str = "@health";
+const DEFAULT_PERIOD: Duration = Duration::from_secs(1);
+const DEFAULT_PRIORITY: Priority = Priority::DataHigh;
+const DEFAULT_MISSED_HEARTBEATS: u32 = 3;
+const WATCHDOG_PERIOD: Duration = Duration::from_millis(100);
+
+/// The status of a node, as published in each heartbeat.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NodeStatus {
+    /// The name of the node.
+    pub name: String,
+    /// The zenoh id of the session publishing the heartbeats.
+    pub zid: String,
+    /// The version of the application, if any.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The time elapsed since the heartbeat was started, in milliseconds.
+    pub uptime_ms: u64,
+    /// The heartbeat period, in milliseconds.
+    pub period_ms: u64,
+    /// Application-specific fields.
+    #[serde(default)]
+    pub fields: BTreeMap<String, serde_json::Value>,
+}
+
+impl NodeStatus {
+    /// Returns the time elapsed since the heartbeat was started.
+    pub fn uptime(&self) -> Duration {
+        Duration::from_millis(self.uptime_ms)
+    }
+
+    /// Returns the heartbeat period.
+    pub fn period(&self) -> Duration {
+        Duration::from_millis(self.period_ms)
+    }
+}
+
+/// The configuration of a [`Heartbeat`].
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    name: OwnedKeyExpr,
+    period: Duration,
+    version: Option<String>,
+    fields: BTreeMap<String, serde_json::Value>,
+    priority: Priority,
+}
+
+impl HeartbeatConfig {
+    pub fn new<T>(name: T) -> ZResult<HeartbeatConfig>
+    where
+        T: TryInto<OwnedKeyExpr> + Send,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let name: OwnedKeyExpr = name.try_into().map_err(|e| e.into())?;
+        if name.is_wild() {
+            bail!("Node name is not allowed to contain wildcards: {}", name);
+        }
+        Ok(HeartbeatConfig {
+            name,
+            period: DEFAULT_PERIOD,
+            version: None,
+            fields: BTreeMap::new(),
+            priority: DEFAULT_PRIORITY,
+        })
+    }
+
+    pub fn period(mut self, d: Duration) -> Self {
+        self.period = d;
+        self
+    }
+
+    pub fn version<T>(mut self, v: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.version = Some(v.into());
+        self
+    }
+
+    pub fn field<K, V>(mut self, k: K, v: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<serde_json::Value>,
+    {
+        self.fields.insert(k.into(), v.into());
+        self
+    }
+
+    pub fn priority(mut self, p: Priority) -> Self {
+        self.priority = p;
+        self
+    }
+}
+
+struct HeartbeatState {
+    name: OwnedKeyExpr,
+    zid: String,
+    version: Option<String>,
+    period: Duration,
+    start: Instant,
+    fields: Mutex<BTreeMap<String, serde_json::Value>>,
+    publisher: Publisher<'static>,
+}
+
+impl HeartbeatState {
+    async fn status(&self) -> NodeStatus {
+        NodeStatus {
+            name: self.name.to_string(),
+            zid: self.zid.clone(),
+            version: self.version.clone(),
+            uptime_ms: self.start.elapsed().as_millis() as u64,
+            period_ms: self.period.as_millis() as u64,
+            fields: self.fields.lock().await.clone(),
+        }
+    }
+
+    async fn beat(&self) -> ZResult<()> {
+        let status = self.status().await;
+        let buf = serde_json::to_vec(&status).map_err(|e| zerror!("{}", e))?;
+        tracing::trace!("Sending heartbeat for: {}", &self.name);
+        self.publisher
+            .put(Value::from(buf).encoding(Encoding::APP_JSON))
+            .res()
+            .await
+    }
+}
+
+async fn heartbeat_task(state: Arc<HeartbeatState>) {
+    loop {
+        tokio::time::sleep(state.period).await;
+        if let Err(e) = state.beat().await {
+            tracing::warn!("Failed to send heartbeat for {}: {}", &state.name, e);
+        }
+    }
+}
+
+/// Periodically publishes the [`NodeStatus`] of a node under `@health/<name>`.
+///
+/// A delete is published for `@health/<name>` when the [`Heartbeat`] is dropped.
+/// The liveliness token declared on `@health/<name>` is undeclared at the same time,
+/// or when the session is lost.
+pub struct Heartbeat {
+    state: Arc<HeartbeatState>,
+    task_controller: TaskController,
+    _token: LivelinessToken<'static>,
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+        let _ = zenoh_core::SyncResolve::res_sync(self.state.publisher.delete());
+    }
+}
+
+impl Heartbeat {
+    pub async fn start(z: Arc<Session>, with: HeartbeatConfig) -> ZResult<Heartbeat> {
+        if with.period.is_zero() {
+            bail!("Heartbeat period must be greater than zero");
+        }
+        let publisher = z
+            .declare_publisher(format!("{HEALTH_PREFIX}/{}", with.name))
+            .priority(with.priority)
+            .res()
+            .await?;
+        let token = z
+            .liveliness()
+            .declare_token(format!("{HEALTH_PREFIX}/{}", with.name))
+            .res()
+            .await?;
+        let state = Arc::new(HeartbeatState {
+            name: with.name,
+            zid: z.zid().to_string(),
+            version: with.version,
+            period: with.period,
+            start: Instant::now(),
+            fields: Mutex::new(with.fields),
+            publisher,
+        });
+        state.beat().await?;
+
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(heartbeat_task(state.clone()));
+        Ok(Heartbeat {
+            state,
+            task_controller,
+            _token: token,
+        })
+    }
+
+    /// Returns the name of this node.
+    pub fn name(&self) -> &keyexpr {
+        &self.state.name
+    }
+
+    /// Returns the status that will be published in the next heartbeat.
+    pub async fn status(&self) -> NodeStatus {
+        self.state.status().await
+    }
+
+    /// Sets an application-specific field, published starting with the next heartbeat.
+    pub async fn set_field<K, V>(&self, k: K, v: V)
+    where
+        K: Into<String>,
+        V: Into<serde_json::Value>,
+    {
+        self.state.fields.lock().await.insert(k.into(), v.into());
+    }
+
+    /// Removes an application-specific field, returning its previous value if any.
+    pub async fn remove_field(&self, k: &str) -> Option<serde_json::Value> {
+        self.state.fields.lock().await.remove(k)
+    }
+
+    /// Immediately publishes a heartbeat, without waiting for the next period.
+    pub async fn beat(&self) -> ZResult<()> {
+        self.state.beat().await
+    }
+}
+
+/// The health of a node tracked by a [`FleetMonitor`].
+#[derive(Clone, Debug)]
+pub struct NodeHealth {
+    /// The last status received from the node.
+    pub status: NodeStatus,
+    /// The instant at which the last heartbeat was received.
+    pub last_seen: Instant,
+    /// Whether the node missed too many heartbeats.
+    pub stale: bool,
+}
+
+/// Events exposed to the user to be informed of relevant
+/// changes in the fleet.
+#[derive(Clone, Debug)]
+pub enum FleetEvent {
+    /// A heartbeat was received from a new node.
+    Joined(NodeStatus),
+    /// A node missed too many heartbeats.
+    Stale(String),
+    /// A heartbeat was received again from a stale node.
+    Recovered(NodeStatus),
+    /// A node stopped its heartbeat, or its session was lost.
+    Left(String),
+}
+
+struct FleetState {
+    nodes: Mutex<HashMap<String, NodeHealth>>,
+    missed_heartbeats: u32,
+    user_events_tx: Mutex<Option<Sender<FleetEvent>>>,
+}
+
+impl FleetState {
+    async fn notify(&self, evt: FleetEvent) {
+        if let Some(tx) = &*self.user_events_tx.lock().await {
+            let _ = tx.send(evt);
+        }
+    }
+}
+
+async fn watchdog_task(state: Arc<FleetState>) {
+    loop {
+        tokio::time::sleep(WATCHDOG_PERIOD).await;
+        let mut nodes = state.nodes.lock().await;
+        let mut stale_nodes = vec![];
+        for (name, node) in nodes.iter_mut() {
+            if !node.stale
+                && node.last_seen.elapsed() > node.status.period() * state.missed_heartbeats
+            {
+                tracing::debug!("Node is stale: {}", name);
+                node.stale = true;
+                stale_nodes.push(name.clone());
+            }
+        }
+        drop(nodes);
+        for name in stale_nodes {
+            state.notify(FleetEvent::Stale(name)).await;
+        }
+    }
+}
+
+async fn heartbeat_handler(state: Arc<FleetState>, sub: FlumeSubscriber<'static>) {
+    while let Ok(s) = sub.recv_async().await {
+        let Some(name) = s
+            .key_expr
+            .as_str()
+            .strip_prefix(HEALTH_PREFIX)
+            .and_then(|n| n.strip_prefix('/'))
+        else {
+            continue;
+        };
+        match s.kind {
+            SampleKind::Put => {
+                let status =
+                    match serde_json::from_slice::<NodeStatus>(&s.value.payload.contiguous()) {
+                        Ok(status) => status,
+                        Err(e) => {
+                            tracing::warn!("Failed decoding heartbeat of {}: {}", name, e);
+                            continue;
+                        }
+                    };
+                let health = NodeHealth {
+                    status: status.clone(),
+                    last_seen: Instant::now(),
+                    stale: false,
+                };
+                let previous = state.nodes.lock().await.insert(name.to_string(), health);
+                match previous {
+                    None => {
+                        tracing::debug!("Node joined: {}", name);
+                        state.notify(FleetEvent::Joined(status)).await;
+                    }
+                    Some(previous) if previous.stale => {
+                        tracing::debug!("Node recovered: {}", name);
+                        state.notify(FleetEvent::Recovered(status)).await;
+                    }
+                    Some(_) => {}
+                }
+            }
+            SampleKind::Delete => {
+                if state.nodes.lock().await.remove(name).is_some() {
+                    tracing::debug!("Node left: {}", name);
+                    state.notify(FleetEvent::Left(name.to_string())).await;
+                }
+            }
+        }
+    }
+}
+
+async fn liveliness_handler(state: Arc<FleetState>, sub: FlumeSubscriber<'static>) {
+    while let Ok(s) = sub.recv_async().await {
+        if s.kind != SampleKind::Delete {
+            continue;
+        }
+        let Some(name) = s
+            .key_expr
+            .as_str()
+            .strip_prefix(HEALTH_PREFIX)
+            .and_then(|n| n.strip_prefix('/'))
+        else {
+            continue;
+        };
+        if state.nodes.lock().await.remove(name).is_some() {
+            tracing::debug!("Node lost: {}", name);
+            state.notify(FleetEvent::Left(name.to_string())).await;
+        }
+    }
+}
+
+/// Tracks the nodes publishing heartbeats under `@health/**`.
+///
+/// A node is considered stale when no heartbeat was received from it for
+/// a given number of its heartbeat periods. Nodes are only discovered when
+/// they publish their next heartbeat, but they are considered gone as soon as
+/// their liveliness token disappears.
+pub struct FleetMonitor {
+    state: Arc<FleetState>,
+    task_controller: TaskController,
+}
+
+impl Drop for FleetMonitor {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl FleetMonitor {
+    /// Starts monitoring the fleet, considering nodes as stale after 3 missed heartbeats.
+    pub async fn start(z: Arc<Session>) -> ZResult<FleetMonitor> {
+        Self::start_with_tolerance(z, DEFAULT_MISSED_HEARTBEATS).await
+    }
+
+    /// Starts monitoring the fleet, considering nodes as stale after
+    /// `missed_heartbeats` missed heartbeats.
+    pub async fn start_with_tolerance(
+        z: Arc<Session>,
+        missed_heartbeats: u32,
+    ) -> ZResult<FleetMonitor> {
+        if missed_heartbeats == 0 {
+            bail!("The number of missed heartbeats must be greater than zero");
+        }
+        let sub = z
+            .declare_subscriber(format!("{HEALTH_PREFIX}/**"))
+            .res()
+            .await?;
+        let liveliness_sub = z
+            .liveliness()
+            .declare_subscriber(format!("{HEALTH_PREFIX}/**"))
+            .res()
+            .await?;
+        let state = Arc::new(FleetState {
+            nodes: Mutex::new(Default::default()),
+            missed_heartbeats,
+            user_events_tx: Mutex::new(Default::default()),
+        });
+
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(heartbeat_handler(state.clone(), sub));
+        task_controller.spawn_abortable(liveliness_handler(state.clone(), liveliness_sub));
+        task_controller.spawn_abortable(watchdog_task(state.clone()));
+        Ok(FleetMonitor {
+            state,
+            task_controller,
+        })
+    }
+
+    /// Returns a receivers that will allow to receive notifications for fleet events.
+    /// Notice that there can be a single subscription at the time, each call to subscribe
+    /// will cancel the previous subscription.
+    pub async fn subscribe(&self) -> Receiver<FleetEvent> {
+        let (tx, rx) = flume::unbounded();
+        *self.state.user_events_tx.lock().await = Some(tx);
+        rx
+    }
+
+    /// Returns the health of all the known nodes, stale ones included.
+    pub async fn fleet(&self) -> Vec<NodeHealth> {
+        self.state.nodes.lock().await.values().cloned().collect()
+    }
+
+    /// Returns the health of the node with the given name, if known.
+    pub async fn node(&self, name: &str) -> Option<NodeHealth> {
+        self.state.nodes.lock().await.get(name).cloned()
+    }
+
+    /// Returns the health of the nodes that missed too many heartbeats.
+    pub async fn stale_nodes(&self) -> Vec<NodeHealth> {
+        self.state
+            .nodes
+            .lock()
+            .await
+            .values()
+            .filter(|n| n.stale)
+            .cloned()
+            .collect()
+    }
+}
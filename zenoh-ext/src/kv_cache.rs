@@ -0,0 +1,322 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To maintain the latest value of each key of a key space.
+//!
+//! A [`KvCache`] subscribes to a key expression and keeps in memory the last sample received
+//! on each key, removing it on a deletion. Its entries are evicted when their time-to-live
+//! expires, or in least recently used order when the cache exceeds its capacity. It serves
+//! local reads synchronously and, optionally, answers the queries on its key expression.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use zenoh::prelude::r#async::*;
+//! use zenoh_ext::kv_cache::{KvCache, KvCacheConfig};
+//!
+//! let session = Arc::new(zenoh::open(config::peer()).res().await.unwrap());
+//! let config = KvCacheConfig::new("sensors/**")
+//!     .unwrap()
+//!     .capacity(1000)
+//!     .ttl(Duration::from_secs(60))
+//!     .queryable(true);
+//! let cache = KvCache::start(session, config).await.unwrap();
+//! if let Some(sample) = cache.get(zenoh::ke!("sensors/temperature")) {
+//!     println!("Temperature: {}", sample.value);
+//! }
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh::prelude::r#async::*;
+use zenoh::query::ErrorCode;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::subscriber::Subscriber;
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+
+/// The configuration of a [`KvCache`].
+#[derive(Debug, Clone)]
+pub struct KvCacheConfig {
+    key_expr: OwnedKeyExpr,
+    capacity: Option<usize>,
+    ttl: Option<Duration>,
+    queryable: bool,
+}
+
+impl KvCacheConfig {
+    pub fn new<T>(key_expr: T) -> ZResult<KvCacheConfig>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        Ok(KvCacheConfig {
+            key_expr: key_expr.try_into().map_err(|e| e.into())?,
+            capacity: None,
+            ttl: None,
+            queryable: false,
+        })
+    }
+
+    /// Change the maximum number of keys kept in the cache (unlimited by default).
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Change the time after which an entry is evicted, counted from its reception
+    /// (unlimited by default).
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Answer the queries on the key expression of the cache with its entries.
+    pub fn queryable(mut self, enabled: bool) -> Self {
+        self.queryable = enabled;
+        self
+    }
+}
+
+struct Entry {
+    sample: Sample,
+    received_at: Instant,
+    last_used: u64,
+}
+
+struct CacheState {
+    capacity: Option<usize>,
+    ttl: Option<Duration>,
+    entries: HashMap<OwnedKeyExpr, Entry>,
+    // the keys of the entries ordered from the least to the most recently used
+    lru: BTreeMap<u64, OwnedKeyExpr>,
+    clock: u64,
+}
+
+impl CacheState {
+    fn is_expired(&self, entry: &Entry) -> bool {
+        entry.sample.is_expired()
+            || self
+                .ttl
+                .is_some_and(|ttl| entry.received_at.elapsed() > ttl)
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn insert(&mut self, sample: Sample) {
+        let key_expr: OwnedKeyExpr = sample.key_expr.clone().into();
+        if let Some(entry) = self.entries.remove(&key_expr) {
+            self.lru.remove(&entry.last_used);
+        }
+        if sample.kind == SampleKind::Delete {
+            return;
+        }
+        let last_used = self.tick();
+        self.lru.insert(last_used, key_expr.clone());
+        self.entries.insert(
+            key_expr,
+            Entry {
+                sample,
+                received_at: Instant::now(),
+                last_used,
+            },
+        );
+        if let Some(capacity) = self.capacity {
+            while self.entries.len() > capacity {
+                let Some((_, key_expr)) = self.lru.pop_first() else {
+                    break;
+                };
+                tracing::trace!("Evicting least recently used {} from KvCache", key_expr);
+                self.entries.remove(&key_expr);
+            }
+        }
+    }
+
+    fn get(&mut self, key_expr: &keyexpr) -> Option<Sample> {
+        let entry = self.entries.get(key_expr)?;
+        let last_used = entry.last_used;
+        if self.is_expired(entry) {
+            self.entries.remove(key_expr);
+            self.lru.remove(&last_used);
+            return None;
+        }
+        let now = self.tick();
+        let entry = self.entries.get_mut(key_expr)?;
+        entry.last_used = now;
+        let sample = entry.sample.clone();
+        if let Some(key_expr) = self.lru.remove(&last_used) {
+            self.lru.insert(now, key_expr);
+        }
+        Some(sample)
+    }
+
+    fn purge_expired(&mut self) {
+        let expired: Vec<(OwnedKeyExpr, u64)> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| self.is_expired(entry))
+            .map(|(key_expr, entry)| (key_expr.clone(), entry.last_used))
+            .collect();
+        for (key_expr, last_used) in expired {
+            self.entries.remove(&key_expr);
+            self.lru.remove(&last_used);
+        }
+    }
+
+    fn get_all(&mut self, key_expr: &keyexpr) -> Vec<Sample> {
+        self.purge_expired();
+        self.entries
+            .iter()
+            .filter(|(k, _)| key_expr.intersects(k))
+            .map(|(_, entry)| entry.sample.clone())
+            .collect()
+    }
+}
+
+fn reply_query(state: &Mutex<CacheState>, query: Query) {
+    use zenoh::prelude::sync::SyncResolve;
+
+    let filter = match query.sample_filter() {
+        Ok(filter) => filter,
+        Err(e) => {
+            if let Err(e) = query
+                .reply_err(ErrorCode::InvalidRequest, e.to_string())
+                .res_sync()
+            {
+                tracing::warn!("Error replying to query: {}", e);
+            }
+            return;
+        }
+    };
+    let samples = {
+        let mut state = zlock!(state);
+        let key_expr = query.key_expr().as_keyexpr();
+        if key_expr.is_wild() {
+            state.get_all(key_expr)
+        } else {
+            state.get(key_expr).into_iter().collect()
+        }
+    };
+    for sample in samples {
+        if !filter.matches(&sample) {
+            continue;
+        }
+        if let Err(e) = query.reply(Ok(sample)).res_sync() {
+            tracing::warn!("Error replying to query: {}", e);
+        }
+    }
+}
+
+/// A cache of the latest value of each key of a key space, with LRU and TTL eviction.
+///
+/// The cache stops being updated when dropped.
+pub struct KvCache {
+    key_expr: OwnedKeyExpr,
+    state: Arc<Mutex<CacheState>>,
+    _subscriber: Subscriber<'static, ()>,
+    _queryable: Option<Queryable<'static, ()>>,
+}
+
+impl KvCache {
+    pub async fn start(z: Arc<Session>, with: KvCacheConfig) -> ZResult<KvCache> {
+        let state = Arc::new(Mutex::new(CacheState {
+            capacity: with.capacity,
+            ttl: with.ttl,
+            entries: HashMap::new(),
+            lru: BTreeMap::new(),
+            clock: 0,
+        }));
+
+        let sub_state = state.clone();
+        let subscriber = z
+            .declare_subscriber(&with.key_expr)
+            .callback(move |sample| zlock!(sub_state).insert(sample))
+            .res()
+            .await?;
+
+        let queryable = if with.queryable {
+            let quer_state = state.clone();
+            Some(
+                z.declare_queryable(&with.key_expr)
+                    .callback(move |query| reply_query(&quer_state, query))
+                    .res()
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        tracing::debug!("Started KvCache on {}", &with.key_expr);
+        Ok(KvCache {
+            key_expr: with.key_expr,
+            state,
+            _subscriber: subscriber,
+            _queryable: queryable,
+        })
+    }
+
+    /// Returns the key expression of this cache.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+
+    /// Returns the latest sample received on the given key, if it wasn't evicted.
+    pub fn get(&self, key_expr: &keyexpr) -> Option<Sample> {
+        zlock!(self.state).get(key_expr)
+    }
+
+    /// Returns the latest samples received on the keys intersecting the given key expression.
+    ///
+    /// Contrary to [`get`](Self::get), this doesn't refresh the recency of the entries.
+    pub fn get_all(&self, key_expr: &keyexpr) -> Vec<Sample> {
+        zlock!(self.state).get_all(key_expr)
+    }
+
+    /// Returns the keys of the cache.
+    pub fn keys(&self) -> Vec<OwnedKeyExpr> {
+        let mut state = zlock!(self.state);
+        state.purge_expired();
+        state.entries.keys().cloned().collect()
+    }
+
+    /// Returns the number of keys in the cache.
+    pub fn len(&self) -> usize {
+        let mut state = zlock!(self.state);
+        state.purge_expired();
+        state.entries.len()
+    }
+
+    /// Returns `true` if the cache has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the entries of the cache.
+    pub fn clear(&self) {
+        let mut state = zlock!(self.state);
+        state.entries.clear();
+        state.lru.clear();
+    }
+}
@@ -11,14 +11,24 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+pub mod acknowledged;
+pub mod blob;
+pub mod delta_state;
+pub mod encryption;
 pub mod group;
+pub mod heartbeat;
+pub mod kv_cache;
+pub mod lock;
 mod publication_cache;
 mod querying_subscriber;
+pub mod replicated_store;
+pub mod rpc;
 mod session_ext;
 mod subscriber_ext;
+pub mod time_series;
 pub use publication_cache::{PublicationCache, PublicationCacheBuilder};
 pub use querying_subscriber::{
-    FetchingSubscriber, FetchingSubscriberBuilder, QueryingSubscriberBuilder,
+    FetchingSubscriber, FetchingSubscriberBuilder, MergePolicy, QueryingSubscriberBuilder,
 };
 pub use session_ext::SessionExt;
 pub use subscriber_ext::SubscriberBuilderExt;
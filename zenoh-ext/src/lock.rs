@@ -0,0 +1,341 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To coordinate an exclusive access with a best-effort distributed lock.
+//!
+//! The holder of a [`DistributedLock`] declares a liveliness token on
+//! `@lock/<key_expr>/<fencing token>/<expiration>/<holder id>`, which is withdrawn when the lock
+//! is released, when its lease expires without being [renewed](DistributedLock::renew) or when
+//! the holder's session is lost. A token whose expiration (in milliseconds since the UNIX epoch)
+//! passed is ignored by the other contenders, in case its holder is unresponsive.
+//!
+//! The lock is best-effort: two contenders trying to acquire it at the same time may both
+//! succeed if the network doesn't propagate their tokens to each other in time. Each holder gets
+//! a fencing token, which the guarded resource should use to reject the requests of stale
+//! holders. Fencing tokens are timestamps of the Hybrid Logical Clock of the session, updated
+//! first with the fencing tokens of the holders seen by the contender: whatever the
+//! synchronization of the clocks, they are greater than the ones of the previous holders which
+//! were seen or causally precede the acquisition. The session must have timestamping enabled.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use zenoh::config::ModeDependentValue::Unique;
+//! use zenoh::prelude::r#async::*;
+//! use zenoh_ext::lock::DistributedLock;
+//!
+//! let mut config = config::peer();
+//! config.timestamping.set_enabled(Some(Unique(true))).unwrap();
+//! let session = Arc::new(zenoh::open(config).res().await.unwrap());
+//! if let Some(lock) = DistributedLock::try_lock(session, "robot/arm", Duration::from_secs(10))
+//!     .await
+//!     .unwrap()
+//! {
+//!     println!("Moving the arm with fencing token {}", lock.fencing_token());
+//!     lock.unlock().await.unwrap();
+//! }
+//! # }
+//! ```
+
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zenoh::liveliness::LivelinessToken;
+use zenoh::prelude::r#async::*;
+use zenoh::time::{Timestamp, NTP64};
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+use zenoh_result::bail;
+use zenoh_task::TaskController;
+
+/// The key expression prefix under which locks are declared.
+pub const LOCK_PREFIX: &str = "@lock";
+
+static HOLDER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn unix_time(time: SystemTime) -> Duration {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+/// A holder of a [`DistributedLock`], as seen by the other contenders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockHolder {
+    /// The id of the holder.
+    pub id: String,
+    /// The fencing token of the holder.
+    pub fencing_token: u64,
+    /// The time at which the lease of the holder expires, unless renewed.
+    pub expiration: SystemTime,
+}
+
+impl LockHolder {
+    fn token_key_expr(&self, key_expr: &keyexpr) -> String {
+        format!(
+            "{LOCK_PREFIX}/{key_expr}/{}/{}/{}",
+            self.fencing_token,
+            unix_time(self.expiration).as_millis(),
+            self.id
+        )
+    }
+
+    fn from_token_key_expr(token: &keyexpr, key_expr: &keyexpr) -> Option<LockHolder> {
+        let mut chunks = token
+            .as_str()
+            .strip_prefix(LOCK_PREFIX)?
+            .strip_prefix('/')?
+            .strip_prefix(key_expr.as_str())?
+            .strip_prefix('/')?
+            .splitn(3, '/');
+        let fencing_token = chunks.next()?.parse().ok()?;
+        let expiration = UNIX_EPOCH + Duration::from_millis(chunks.next()?.parse().ok()?);
+        let id = chunks.next()?.to_string();
+        Some(LockHolder {
+            id,
+            fencing_token,
+            expiration,
+        })
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expiration < SystemTime::now()
+    }
+}
+
+/// Lists the holders of the lock on the given key expression, expired ones included.
+async fn holders(z: &Session, key_expr: &keyexpr) -> ZResult<Vec<LockHolder>> {
+    let mut holders = vec![];
+    let replies = z
+        .liveliness()
+        .get(format!("{LOCK_PREFIX}/{key_expr}/*/*/*"))
+        .res()
+        .await?;
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.sample {
+            if let Some(holder) = LockHolder::from_token_key_expr(&sample.key_expr, key_expr) {
+                holders.push(holder);
+            }
+        }
+    }
+    Ok(holders)
+}
+
+struct LockInner {
+    holder: LockHolder,
+    expires_at: Instant,
+    token: Option<LivelinessToken<'static>>,
+}
+
+struct LockState {
+    session: Arc<Session>,
+    key_expr: OwnedKeyExpr,
+    lease: Duration,
+    inner: Mutex<LockInner>,
+}
+
+async fn lease_task(state: Arc<LockState>) {
+    loop {
+        let expires_at = zlock!(state.inner).expires_at;
+        tokio::time::sleep_until(expires_at.into()).await;
+        let mut inner = zlock!(state.inner);
+        if inner.expires_at <= Instant::now() {
+            tracing::warn!("Lease of the lock on {} expired", &state.key_expr);
+            inner.token = None;
+            return;
+        }
+    }
+}
+
+/// A best-effort distributed lock, released when dropped.
+pub struct DistributedLock {
+    state: Arc<LockState>,
+    task_controller: TaskController,
+}
+
+impl Drop for DistributedLock {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl DistributedLock {
+    /// Tries to acquire the lock on the given key expression for the given lease,
+    /// returning `None` if it is held by another contender.
+    pub async fn try_lock<T>(
+        z: Arc<Session>,
+        key_expr: T,
+        lease: Duration,
+    ) -> ZResult<Option<DistributedLock>>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "Lock key expression is not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        if lease.is_zero() {
+            bail!("Lock lease must be greater than zero");
+        }
+
+        let Some(hlc) = z.hlc() else {
+            bail!(
+                "Failed requirement for DistributedLock on {}: \
+                     the Session is not configured with timestamping enabled",
+                key_expr
+            )
+        };
+
+        let previous = holders(&z, &key_expr).await?;
+        if let Some(holder) = previous.iter().find(|h| !h.is_expired()) {
+            tracing::debug!("Lock on {} is held by {}", &key_expr, &holder.id);
+            return Ok(None);
+        }
+        // fencing tokens are HLC timestamps, made greater than the ones of the previous holders
+        for h in &previous {
+            let timestamp = Timestamp::new(NTP64(h.fencing_token), (&z.zid()).into());
+            if let Err(e) = hlc.update_with_timestamp(&timestamp) {
+                tracing::debug!("Fencing token of {} ahead of the HLC: {}", &h.id, e);
+            }
+        }
+        // the HLC rejects the timestamps too far in the future: remain greater than them anyway
+        let fencing_token = previous
+            .iter()
+            .map(|h| h.fencing_token + 1)
+            .fold(hlc.new_timestamp().get_time().as_u64(), u64::max);
+        let holder = LockHolder {
+            id: format!(
+                "{}_{}",
+                z.zid(),
+                HOLDER_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ),
+            fencing_token,
+            expiration: SystemTime::now() + lease,
+        };
+        let token = z
+            .liveliness()
+            .declare_token(holder.token_key_expr(&key_expr))
+            .res()
+            .await?;
+
+        // contenders which declared their token concurrently yield to the smallest fencing token
+        let contenders = holders(&z, &key_expr).await?;
+        if contenders.iter().any(|h| {
+            !h.is_expired() && (h.fencing_token, &h.id) < (holder.fencing_token, &holder.id)
+        }) {
+            tracing::debug!("Lost the race for the lock on {}", &key_expr);
+            token.undeclare().res().await?;
+            return Ok(None);
+        }
+
+        tracing::debug!(
+            "Acquired the lock on {} with fencing token {}",
+            &key_expr,
+            fencing_token
+        );
+        let state = Arc::new(LockState {
+            session: z,
+            key_expr,
+            lease,
+            inner: Mutex::new(LockInner {
+                holder,
+                expires_at: Instant::now() + lease,
+                token: Some(token),
+            }),
+        });
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(lease_task(state.clone()));
+        Ok(Some(DistributedLock {
+            state,
+            task_controller,
+        }))
+    }
+
+    /// Returns the current holder of the lock on the given key expression, if any.
+    pub async fn holder<T>(z: &Session, key_expr: T) -> ZResult<Option<LockHolder>>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        Ok(holders(z, &key_expr)
+            .await?
+            .into_iter()
+            .filter(|h| !h.is_expired())
+            .min_by_key(|h| h.fencing_token))
+    }
+
+    /// Returns the key expression of this lock.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.state.key_expr
+    }
+
+    /// Returns the fencing token of this lock.
+    pub fn fencing_token(&self) -> u64 {
+        zlock!(self.state.inner).holder.fencing_token
+    }
+
+    /// Returns `true` if this lock is still held, i.e. its lease didn't expire.
+    pub fn is_held(&self) -> bool {
+        zlock!(self.state.inner).token.is_some()
+    }
+
+    /// Extends the lease of this lock, failing if it already expired.
+    pub async fn renew(&self) -> ZResult<()> {
+        let holder = {
+            let inner = zlock!(self.state.inner);
+            if inner.token.is_none() {
+                bail!("Lease of the lock on {} expired", &self.state.key_expr);
+            }
+            LockHolder {
+                expiration: SystemTime::now() + self.state.lease,
+                ..inner.holder.clone()
+            }
+        };
+        let token = self
+            .state
+            .session
+            .liveliness()
+            .declare_token(holder.token_key_expr(&self.state.key_expr))
+            .res()
+            .await?;
+        let mut inner = zlock!(self.state.inner);
+        if inner.token.is_none() {
+            bail!("Lease of the lock on {} expired", &self.state.key_expr);
+        }
+        inner.holder = holder;
+        inner.expires_at = Instant::now() + self.state.lease;
+        // the previous token is undeclared when dropped
+        inner.token = Some(token);
+        Ok(())
+    }
+
+    /// Releases this lock.
+    pub async fn unlock(self) -> ZResult<()> {
+        let token = zlock!(self.state.inner).token.take();
+        if let Some(token) = token {
+            token.undeclare().res().await?;
+        }
+        Ok(())
+    }
+}
@@ -11,16 +11,22 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::fs::{File, OpenOptions};
 use std::future::Ready;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::PathBuf;
 use std::time::Duration;
 use zenoh::prelude::r#async::*;
+use zenoh::query::ErrorCode;
 use zenoh::queryable::{Query, Queryable};
 use zenoh::subscriber::FlumeSubscriber;
+use zenoh::time::{Timestamp, TimestampId, NTP64};
 use zenoh::SessionRef;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
-use zenoh_result::{bail, ZResult};
+use zenoh_result::{bail, zerror, ZResult};
 use zenoh_task::TerminatableTask;
 use zenoh_util::core::ResolveFuture;
 
@@ -34,6 +40,7 @@ pub struct PublicationCacheBuilder<'a, 'b, 'c> {
     complete: Option<bool>,
     history: usize,
     resources_limit: Option<usize>,
+    persistence: Option<PathBuf>,
 }
 
 impl<'a, 'b, 'c> PublicationCacheBuilder<'a, 'b, 'c> {
@@ -49,6 +56,7 @@ impl<'a, 'b, 'c> PublicationCacheBuilder<'a, 'b, 'c> {
             complete: None,
             history: 1,
             resources_limit: None,
+            persistence: None,
         }
     }
 
@@ -88,6 +96,20 @@ impl<'a, 'b, 'c> PublicationCacheBuilder<'a, 'b, 'c> {
         self.resources_limit = Some(limit);
         self
     }
+
+    /// Back the cache with an append-only log file at the given path, so that the cached
+    /// publications survive a restart of the process.
+    ///
+    /// The log is replayed when the [`PublicationCache`] is created, keeping only the samples
+    /// that fit in its `history` and `resources_limit`, and is periodically compacted.
+    /// Writes are not synced to disk: the last publications may be lost on a power failure.
+    pub fn persistence<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.persistence = Some(path.into());
+        self
+    }
 }
 
 impl<'a> Resolvable for PublicationCacheBuilder<'a, '_, '_> {
@@ -128,10 +150,11 @@ impl<'a> PublicationCache<'a> {
                 Some(Err(e)) => bail!("Invalid key expression for queryable_prefix: {}", e),
             };
         tracing::debug!(
-            "Create PublicationCache on {} with history={} resource_limit={:?}",
+            "Create PublicationCache on {} with history={} resource_limit={:?} persistence={:?}",
             &key_expr,
             conf.history,
-            conf.resources_limit
+            conf.resources_limit,
+            conf.persistence
         );
 
         if conf.session.hlc().is_none() {
@@ -165,6 +188,29 @@ impl<'a> PublicationCache<'a> {
         let pub_key_expr = key_expr.into_owned();
         let resources_limit = conf.resources_limit;
         let history = conf.history;
+        let limit = resources_limit.unwrap_or(usize::MAX);
+
+        let mut cache: HashMap<OwnedKeyExpr, VecDeque<Sample>> =
+            HashMap::with_capacity(resources_limit.unwrap_or(32));
+        // restore the publications persisted by a previous PublicationCache
+        let mut log = match conf.persistence {
+            Some(path) => {
+                let (mut log, samples) = PersistenceLog::open(path)?;
+                for sample in samples {
+                    if sample.is_expired() || !pub_key_expr.intersects(&sample.key_expr) {
+                        continue;
+                    }
+                    if cache_sample(&mut cache, &queryable_prefix, sample, history, limit).is_none()
+                    {
+                        tracing::warn!("PublicationCache on {}: resource_limit exceeded - can't restore persisted publication for a new resource",
+                            pub_key_expr);
+                    }
+                }
+                log.compact(cache.values().flatten())?;
+                Some(log)
+            }
+            None => None,
+        };
 
         // TODO(yuyuan): use CancellationToken to manage it
         let token = TerminatableTask::create_cancellation_token();
@@ -172,32 +218,32 @@ impl<'a> PublicationCache<'a> {
         let task = TerminatableTask::spawn(
             zenoh_runtime::ZRuntime::Application,
             async move {
-                let mut cache: HashMap<OwnedKeyExpr, VecDeque<Sample>> =
-                    HashMap::with_capacity(resources_limit.unwrap_or(32));
-                let limit = resources_limit.unwrap_or(usize::MAX);
                 loop {
                     tokio::select! {
                         // on publication received by the local subscriber, store it
                         sample = sub_recv.recv_async() => {
-                            if let Ok(sample) = sample {
-                                let queryable_key_expr: KeyExpr<'_> = if let Some(prefix) = &queryable_prefix {
-                                    prefix.join(&sample.key_expr).unwrap().into()
-                                } else {
-                                    sample.key_expr.clone()
-                                };
-
-                                if let Some(queue) = cache.get_mut(queryable_key_expr.as_keyexpr()) {
-                                    if queue.len() >= history {
-                                        queue.pop_front();
+                            if let Ok(mut sample) = sample {
+                                // expire samples with a ttl relatively to their reception if not timestamped
+                                if sample.ttl.is_some() {
+                                    sample.ensure_timestamp();
+                                }
+                                match cache_sample(&mut cache, &queryable_prefix, sample, history, limit) {
+                                    Some(sample) => {
+                                        if let Some(log) = &mut log {
+                                            if let Err(e) = log.append(sample) {
+                                                tracing::warn!("PublicationCache on {}: failed to persist publication: {}", pub_key_expr, e);
+                                            }
+                                        }
+                                    }
+                                    None => tracing::error!("PublicationCache on {}: resource_limit exceeded - can't cache publication for a new resource",
+                                        pub_key_expr),
+                                }
+                                if let Some(log) = &mut log {
+                                    if log.needs_compaction() {
+                                        if let Err(e) = log.compact(cache.values().flatten()) {
+                                            tracing::warn!("PublicationCache on {}: failed to compact persistence log: {}", pub_key_expr, e);
+                                        }
                                     }
-                                    queue.push_back(sample);
-                                } else if cache.len() >= limit {
-                                    tracing::error!("PublicationCache on {}: resource_limit exceeded - can't cache publication for a new resource",
-                                    pub_key_expr);
-                                } else {
-                                    let mut queue: VecDeque<Sample> = VecDeque::new();
-                                    queue.push_back(sample);
-                                    cache.insert(queryable_key_expr.into(), queue);
                                 }
                             }
                         },
@@ -205,13 +251,23 @@ impl<'a> PublicationCache<'a> {
                         // on query, reply with cach content
                         query = quer_recv.recv_async() => {
                             if let Ok(query) = query {
+                                let filter = match query.sample_filter() {
+                                    Ok(filter) => filter,
+                                    Err(e) => {
+                                        if let Err(e) = query.reply_err(ErrorCode::InvalidRequest, e.to_string()).res_async().await {
+                                            tracing::warn!("Error replying to query: {}", e);
+                                        }
+                                        continue;
+                                    }
+                                };
                                 if !query.selector().key_expr.as_str().contains('*') {
                                     if let Some(queue) = cache.get(query.selector().key_expr.as_keyexpr()) {
                                         for sample in queue {
-                                            if let (Ok(Some(time_range)), Some(timestamp)) = (query.selector().time_range(), sample.timestamp) {
-                                                if !time_range.contains(timestamp.get_time().to_system_time()){
-                                                    continue;
-                                                }
+                                            if sample.is_expired() {
+                                                continue;
+                                            }
+                                            if !filter.matches(sample) {
+                                                continue;
                                             }
                                             if let Err(e) = query.reply(Ok(sample.clone())).res_async().await {
                                                 tracing::warn!("Error replying to query: {}", e);
@@ -222,10 +278,11 @@ impl<'a> PublicationCache<'a> {
                                     for (key_expr, queue) in cache.iter() {
                                         if query.selector().key_expr.intersects(unsafe{ keyexpr::from_str_unchecked(key_expr) }) {
                                             for sample in queue {
-                                                if let (Ok(Some(time_range)), Some(timestamp)) = (query.selector().time_range(), sample.timestamp) {
-                                                    if !time_range.contains(timestamp.get_time().to_system_time()){
-                                                        continue;
-                                                    }
+                                                if sample.is_expired() {
+                                                    continue;
+                                                }
+                                                if !filter.matches(sample) {
+                                                    continue;
                                                 }
                                                 if let Err(e) = query.reply(Ok(sample.clone())).res_async().await {
                                                     tracing::warn!("Error replying to query: {}", e);
@@ -270,3 +327,199 @@ impl<'a> PublicationCache<'a> {
         self.local_sub.key_expr()
     }
 }
+
+/// Stores a sample in the cache, returning a reference to it or `None` if the resources limit
+/// prevented it.
+fn cache_sample<'c>(
+    cache: &'c mut HashMap<OwnedKeyExpr, VecDeque<Sample>>,
+    queryable_prefix: &Option<OwnedKeyExpr>,
+    sample: Sample,
+    history: usize,
+    limit: usize,
+) -> Option<&'c Sample> {
+    let queryable_key_expr: OwnedKeyExpr = if let Some(prefix) = queryable_prefix {
+        prefix.join(&sample.key_expr).unwrap()
+    } else {
+        sample.key_expr.clone().into()
+    };
+
+    if !cache.contains_key(&queryable_key_expr) && cache.len() >= limit {
+        return None;
+    }
+    let queue = cache.entry(queryable_key_expr).or_default();
+    queue.retain(|sample| !sample.is_expired());
+    if queue.len() >= history {
+        queue.pop_front();
+    }
+    queue.push_back(sample);
+    queue.back()
+}
+
+/// The minimum number of records in a [`PersistenceLog`] before it gets compacted.
+const PERSISTENCE_COMPACTION_THRESHOLD: usize = 1024;
+
+/// A [`Sample`] as stored in a [`PersistenceLog`].
+#[derive(Serialize, Deserialize)]
+struct PersistedSample {
+    key_expr: String,
+    payload: Vec<u8>,
+    encoding: String,
+    kind: u8,
+    timestamp: Option<(u64, Vec<u8>)>,
+    ttl: Option<Duration>,
+}
+
+impl From<&Sample> for PersistedSample {
+    fn from(sample: &Sample) -> Self {
+        PersistedSample {
+            key_expr: sample.key_expr.to_string(),
+            payload: sample.value.payload.contiguous().into_owned(),
+            encoding: sample.value.encoding.to_string(),
+            kind: sample.kind as u8,
+            timestamp: sample.timestamp.as_ref().map(|ts| {
+                let id = ts.get_id();
+                (
+                    ts.get_time().as_u64(),
+                    id.to_le_bytes()[..id.size()].to_vec(),
+                )
+            }),
+            ttl: sample.ttl,
+        }
+    }
+}
+
+impl TryFrom<PersistedSample> for Sample {
+    type Error = zenoh_result::Error;
+
+    fn try_from(persisted: PersistedSample) -> ZResult<Self> {
+        let key_expr = KeyExpr::try_from(persisted.key_expr)?;
+        let value = Value::from(persisted.payload).encoding(Encoding::from(persisted.encoding));
+        let mut sample = Sample::new(key_expr, value);
+        sample.kind = SampleKind::try_from(persisted.kind as u64)
+            .map_err(|k| zerror!("Invalid sample kind: {}", k))?;
+        if let Some((time, id)) = persisted.timestamp {
+            let id = TimestampId::try_from(id.as_slice())
+                .map_err(|e| zerror!("Invalid timestamp id: {:?}", e))?;
+            sample.timestamp = Some(Timestamp::new(NTP64(time), id));
+        }
+        sample.ttl = persisted.ttl;
+        Ok(sample)
+    }
+}
+
+/// An append-only log of the samples stored by a [`PublicationCache`].
+///
+/// Each record is a bincode-serialized [`PersistedSample`] prefixed by its length as a
+/// little-endian `u32`.
+struct PersistenceLog {
+    path: PathBuf,
+    file: File,
+    records: usize,
+    compaction_at: usize,
+}
+
+impl PersistenceLog {
+    /// Opens the log at the given path, creating it if needed, and returns the samples it contains.
+    fn open(path: PathBuf) -> ZResult<(Self, Vec<Sample>)> {
+        let mut samples = vec![];
+        match File::open(&path) {
+            Ok(file) => {
+                let mut reader = BufReader::new(file);
+                let mut len = [0u8; 4];
+                loop {
+                    match reader.read_exact(&mut len) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                        Err(e) => bail!("Failed to read persistence log {:?}: {}", path, e),
+                    }
+                    let mut buf = vec![0u8; u32::from_le_bytes(len) as usize];
+                    // a truncated record is the trace of an interrupted write, ignore it
+                    if reader.read_exact(&mut buf).is_err() {
+                        tracing::warn!("Ignoring truncated record in persistence log {:?}", path);
+                        break;
+                    }
+                    match bincode::deserialize::<PersistedSample>(&buf)
+                        .map_err(|e| zerror!("{}", e).into())
+                        .and_then(<Sample as TryFrom<PersistedSample>>::try_from)
+                    {
+                        Ok(sample) => samples.push(sample),
+                        Err(e) => {
+                            tracing::warn!(
+                                "Ignoring invalid record in persistence log {:?}: {}",
+                                path,
+                                e
+                            )
+                        }
+                    }
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => bail!("Failed to open persistence log {:?}: {}", path, e),
+        }
+        let file = Self::open_append(&path)?;
+        let log = PersistenceLog {
+            path,
+            file,
+            records: samples.len(),
+            compaction_at: PERSISTENCE_COMPACTION_THRESHOLD,
+        };
+        Ok((log, samples))
+    }
+
+    fn open_append(path: &PathBuf) -> ZResult<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| zerror!("Failed to open persistence log {:?}: {}", path, e).into())
+    }
+
+    fn write_record<W: Write>(writer: &mut W, sample: &Sample) -> ZResult<()> {
+        let buf =
+            bincode::serialize(&PersistedSample::from(sample)).map_err(|e| zerror!("{}", e))?;
+        let mut record = Vec::with_capacity(4 + buf.len());
+        record.extend_from_slice(&(buf.len() as u32).to_le_bytes());
+        record.extend_from_slice(&buf);
+        writer.write_all(&record)?;
+        Ok(())
+    }
+
+    /// Appends a sample to the log.
+    fn append(&mut self, sample: &Sample) -> ZResult<()> {
+        Self::write_record(&mut self.file, sample)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Returns `true` if the log grew enough since its last compaction to be compacted again.
+    fn needs_compaction(&self) -> bool {
+        self.records >= self.compaction_at
+    }
+
+    /// Atomically rewrites the log with the given samples only, skipping the expired ones.
+    fn compact<'s, I>(&mut self, samples: I) -> ZResult<()>
+    where
+        I: Iterator<Item = &'s Sample>,
+    {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        let mut records = 0;
+        for sample in samples.filter(|s| !s.is_expired()) {
+            Self::write_record(&mut writer, sample)?;
+            records += 1;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| zerror!("{}", e))?
+            .sync_all()?;
+        std::fs::rename(&tmp_path, &self.path)?;
+
+        self.file = Self::open_append(&self.path)?;
+        self.records = records;
+        self.compaction_at = (2 * records).max(PERSISTENCE_COMPACTION_THRESHOLD);
+        Ok(())
+    }
+}
@@ -20,6 +20,7 @@ use std::time::Duration;
 use zenoh::handlers::{locked, DefaultHandler};
 use zenoh::prelude::r#async::*;
 use zenoh::query::{QueryConsolidation, QueryTarget, ReplyKeyExpr};
+use zenoh::sample::{EntityId, SourceSn};
 use zenoh::subscriber::{Reliability, Subscriber};
 use zenoh::time::Timestamp;
 use zenoh::Result as ZResult;
@@ -39,6 +40,7 @@ pub struct QueryingSubscriberBuilder<'a, 'b, KeySpace, Handler> {
     pub(crate) query_consolidation: QueryConsolidation,
     pub(crate) query_accept_replies: ReplyKeyExpr,
     pub(crate) query_timeout: Duration,
+    pub(crate) merge_policy: MergePolicy,
     pub(crate) handler: Handler,
 }
 
@@ -63,6 +65,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             query_consolidation,
             query_accept_replies,
             query_timeout,
+            merge_policy,
             handler: _,
         } = self;
         QueryingSubscriberBuilder {
@@ -76,6 +79,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             query_consolidation,
             query_accept_replies,
             query_timeout,
+            merge_policy,
             handler: callback,
         }
     }
@@ -116,6 +120,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             query_consolidation,
             query_accept_replies,
             query_timeout,
+            merge_policy,
             handler: _,
         } = self;
         QueryingSubscriberBuilder {
@@ -129,6 +134,7 @@ impl<'a, 'b, KeySpace> QueryingSubscriberBuilder<'a, 'b, KeySpace, DefaultHandle
             query_consolidation,
             query_accept_replies,
             query_timeout,
+            merge_policy,
             handler,
         }
     }
@@ -208,6 +214,14 @@ impl<'a, 'b, KeySpace, Handler> QueryingSubscriberBuilder<'a, 'b, KeySpace, Hand
         self.query_timeout = query_timeout;
         self
     }
+
+    /// Change the policy used to merge the query replies with the publications
+    /// received while querying.
+    #[inline]
+    pub fn merge_policy(mut self, merge_policy: MergePolicy) -> Self {
+        self.merge_policy = merge_policy;
+        self
+    }
 }
 
 impl<'a, KeySpace, Handler> Resolvable for QueryingSubscriberBuilder<'a, '_, KeySpace, Handler>
@@ -260,6 +274,7 @@ where
                     .timeout(query_timeout)
                     .res_sync(),
             },
+            merge_policy: self.merge_policy,
             handler: self.handler,
             phantom: std::marker::PhantomData,
         }
@@ -280,58 +295,97 @@ where
     }
 }
 
-// Collects samples in their Timestamp order, if any,
-// and ignores repeating samples with duplicate timestamps.
-// Samples without Timestamps are kept in a separate Vector,
-// and are considered as older than any sample with Timestamp.
+/// The policy used by a [`FetchingSubscriber`] to order and deduplicate the fetched samples
+/// and the publications received while fetching.
+///
+/// Samples which can't be ordered by the policy are delivered first, in their reception order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Order the samples by [`Timestamp`], ignoring the samples with a duplicate timestamp.
+    #[default]
+    Timestamp,
+    /// Order the samples of each publisher by [`SourceSn`](zenoh::sample::SourceSn), ignoring
+    /// the samples with a duplicate sequence number.
+    ///
+    /// Only the samples carrying a [`SourceInfo`](zenoh::sample::SourceInfo) can be ordered:
+    /// this is typically the case of the samples replayed by a storage.
+    SourceSn,
+}
+
+// Collects samples in the order given by the MergePolicy,
+// and ignores repeating samples with duplicate ordering keys.
+// Samples which can't be ordered are kept in a separate Vector,
+// and are considered as older than any ordered sample.
 struct MergeQueue {
-    untimestamped: VecDeque<Sample>,
+    policy: MergePolicy,
+    unordered: VecDeque<Sample>,
     timstamped: BTreeMap<Timestamp, Sample>,
+    sequenced: BTreeMap<(ZenohId, EntityId, SourceSn), Sample>,
 }
 
 impl MergeQueue {
-    fn new() -> Self {
+    fn new(policy: MergePolicy) -> Self {
         MergeQueue {
-            untimestamped: VecDeque::new(),
+            policy,
+            unordered: VecDeque::new(),
             timstamped: BTreeMap::new(),
+            sequenced: BTreeMap::new(),
         }
     }
 
     fn len(&self) -> usize {
-        self.untimestamped.len() + self.timstamped.len()
+        self.unordered.len() + self.timstamped.len() + self.sequenced.len()
     }
 
     fn push(&mut self, sample: Sample) {
-        if let Some(ts) = sample.timestamp {
-            self.timstamped.entry(ts).or_insert(sample);
-        } else {
-            self.untimestamped.push_back(sample);
+        match self.policy {
+            MergePolicy::Timestamp => {
+                if let Some(ts) = sample.timestamp {
+                    self.timstamped.entry(ts).or_insert(sample);
+                    return;
+                }
+            }
+            MergePolicy::SourceSn => {
+                let info = &sample.source_info;
+                if let (Some(id), Some(sn)) = (info.source_id, info.source_sn) {
+                    self.sequenced
+                        .entry((id, info.source_eid, sn))
+                        .or_insert(sample);
+                    return;
+                }
+            }
         }
+        self.unordered.push_back(sample);
     }
 
     fn drain(&mut self) -> MergeQueueValues {
         let mut vec = VecDeque::new();
-        let mut queue = BTreeMap::new();
-        swap(&mut self.untimestamped, &mut vec);
-        swap(&mut self.timstamped, &mut queue);
+        let mut timstamped = BTreeMap::new();
+        let mut sequenced = BTreeMap::new();
+        swap(&mut self.unordered, &mut vec);
+        swap(&mut self.timstamped, &mut timstamped);
+        swap(&mut self.sequenced, &mut sequenced);
         MergeQueueValues {
-            untimestamped: vec,
-            timstamped: queue.into_values(),
+            unordered: vec,
+            timstamped: timstamped.into_values(),
+            sequenced: sequenced.into_values(),
         }
     }
 }
 
 struct MergeQueueValues {
-    untimestamped: VecDeque<Sample>,
+    unordered: VecDeque<Sample>,
     timstamped: btree_map::IntoValues<Timestamp, Sample>,
+    sequenced: btree_map::IntoValues<(ZenohId, EntityId, SourceSn), Sample>,
 }
 
 impl Iterator for MergeQueueValues {
     type Item = Sample;
     fn next(&mut self) -> Option<Self::Item> {
-        self.untimestamped
+        self.unordered
             .pop_front()
             .or_else(|| self.timstamped.next())
+            .or_else(|| self.sequenced.next())
     }
 }
 
@@ -359,6 +413,7 @@ pub struct FetchingSubscriberBuilder<
     pub(crate) reliability: Reliability,
     pub(crate) origin: Locality,
     pub(crate) fetch: Fetch,
+    pub(crate) merge_policy: MergePolicy,
     pub(crate) handler: Handler,
     pub(crate) phantom: std::marker::PhantomData<TryIntoSample>,
 }
@@ -385,10 +440,19 @@ where
             reliability: self.reliability,
             origin: self.origin,
             fetch: self.fetch,
+            merge_policy: self.merge_policy,
             handler: self.handler,
             phantom: std::marker::PhantomData,
         }
     }
+
+    /// Change the policy used to merge the fetched samples with the publications
+    /// received while fetching.
+    #[inline]
+    pub fn merge_policy(mut self, merge_policy: MergePolicy) -> Self {
+        self.merge_policy = merge_policy;
+        self
+    }
 }
 
 impl<
@@ -418,6 +482,7 @@ where
             reliability,
             origin,
             fetch,
+            merge_policy,
             handler: _,
             phantom,
         } = self;
@@ -428,6 +493,7 @@ where
             reliability,
             origin,
             fetch,
+            merge_policy,
             handler: callback,
             phantom,
         }
@@ -472,6 +538,7 @@ where
             reliability,
             origin,
             fetch,
+            merge_policy,
             handler: _,
             phantom,
         } = self;
@@ -482,6 +549,7 @@ where
             reliability,
             origin,
             fetch,
+            merge_policy,
             handler,
             phantom,
         }
@@ -655,7 +723,7 @@ impl<'a, Receiver> FetchingSubscriber<'a, Receiver> {
     {
         let state = Arc::new(Mutex::new(InnerState {
             pending_fetches: 0,
-            merge_queue: MergeQueue::new(),
+            merge_queue: MergeQueue::new(conf.merge_policy),
         }));
         let (callback, receiver) = conf.handler.into_cb_receiver_pair();
 
@@ -0,0 +1,477 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To share an eventually consistent key-value state between applications.
+//!
+//! Each replica of a [`ReplicatedStore`] publishes its updates on
+//! `@replica/<key_expr>/update`, the concurrent updates of a key being resolved by keeping the
+//! one with the greatest timestamp (deletions are kept as tombstones for this purpose).
+//! Since updates may be missed, each replica periodically publishes a digest of its state on
+//! `@replica/<key_expr>/digest/<replica id>`, and aligns with the replicas whose digest differs
+//! by fetching their entries from `@replica/<key_expr>/entries/<replica id>`.
+//!
+//! The updates are timestamped by the HLC of the session if it has one, or with the system time
+//! otherwise, so the clocks of the replicas should be synchronized.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use std::sync::Arc;
+//! use zenoh::prelude::r#async::*;
+//! use zenoh_ext::replicated_store::{ReplicatedStore, ReplicatedStoreConfig};
+//!
+//! let session = Arc::new(zenoh::open(config::peer()).res().await.unwrap());
+//! let config = ReplicatedStoreConfig::new("fleet/config").unwrap();
+//! let store = ReplicatedStore::start(session, config).await.unwrap();
+//! store.put("robot/1/speed", "0.5").await.unwrap();
+//! for (key, value) in store.snapshot() {
+//!     println!("{key}: {value}");
+//! }
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::r#async::*;
+use zenoh::query::ConsolidationMode;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::subscriber::Subscriber;
+use zenoh::time::{Timestamp, TimestampId, NTP64};
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+use zenoh_result::{bail, zerror};
+use zenoh_task::TaskController;
+
+/// The key expression prefix under which the replicas communicate.
+pub const REPLICATION_PREFIX: &str = "@replica";
+const UPDATE_SUFFIX: &str = "update";
+const DIGEST_PREFIX: &str = "digest";
+const ENTRIES_PREFIX: &str = "entries";
+const DEFAULT_DIGEST_PERIOD: Duration = Duration::from_secs(5);
+
+static REPLICA_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+type StateDigest = [u8; 32];
+
+/// The configuration of a [`ReplicatedStore`].
+#[derive(Debug, Clone)]
+pub struct ReplicatedStoreConfig {
+    key_expr: OwnedKeyExpr,
+    digest_period: Duration,
+}
+
+impl ReplicatedStoreConfig {
+    pub fn new<T>(key_expr: T) -> ZResult<ReplicatedStoreConfig>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "ReplicatedStore key expression is not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        Ok(ReplicatedStoreConfig {
+            key_expr,
+            digest_period: DEFAULT_DIGEST_PERIOD,
+        })
+    }
+
+    /// Change the period at which the replica publishes the digest of its state.
+    pub fn digest_period(mut self, period: Duration) -> Self {
+        self.digest_period = period;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    timestamp: Timestamp,
+    // None for a deletion
+    value: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WireEntry {
+    key_expr: String,
+    time: u64,
+    id: Vec<u8>,
+    value: Option<(Vec<u8>, String)>,
+}
+
+impl WireEntry {
+    fn new(key_expr: &keyexpr, entry: &Entry) -> Self {
+        let id = entry.timestamp.get_id();
+        WireEntry {
+            key_expr: key_expr.to_string(),
+            time: entry.timestamp.get_time().as_u64(),
+            id: id.to_le_bytes()[..id.size()].to_vec(),
+            value: entry.value.as_ref().map(|value| {
+                (
+                    value.payload.contiguous().into_owned(),
+                    value.encoding.to_string(),
+                )
+            }),
+        }
+    }
+
+    fn into_entry(self) -> ZResult<(OwnedKeyExpr, Entry)> {
+        let key_expr = OwnedKeyExpr::try_from(self.key_expr)?;
+        let id = TimestampId::try_from(self.id.as_slice())
+            .map_err(|e| zerror!("Invalid timestamp id: {:?}", e))?;
+        let value = self
+            .value
+            .map(|(payload, encoding)| Value::from(payload).encoding(Encoding::from(encoding)));
+        Ok((
+            key_expr,
+            Entry {
+                timestamp: Timestamp::new(NTP64(self.time), id),
+                value,
+            },
+        ))
+    }
+}
+
+struct StoreState {
+    key_expr: OwnedKeyExpr,
+    entries: Mutex<BTreeMap<OwnedKeyExpr, Entry>>,
+}
+
+impl StoreState {
+    /// Keeps the given entry if it is more recent than the current one, returning `true` if so.
+    fn merge(&self, key_expr: OwnedKeyExpr, entry: Entry) -> bool {
+        let mut entries = zlock!(self.entries);
+        match entries.get(&key_expr) {
+            Some(current) if current.timestamp >= entry.timestamp => false,
+            _ => {
+                tracing::trace!(
+                    "ReplicatedStore {}: updating {} at {}",
+                    &self.key_expr,
+                    key_expr,
+                    entry.timestamp
+                );
+                entries.insert(key_expr, entry);
+                true
+            }
+        }
+    }
+
+    fn merge_wire(&self, buf: &[u8]) {
+        match bincode::deserialize::<Vec<WireEntry>>(buf) {
+            Ok(wire_entries) => {
+                for wire_entry in wire_entries {
+                    match wire_entry.into_entry() {
+                        Ok((key_expr, entry)) => {
+                            self.merge(key_expr, entry);
+                        }
+                        Err(e) => tracing::warn!(
+                            "ReplicatedStore {}: received an invalid entry: {}",
+                            &self.key_expr,
+                            e
+                        ),
+                    }
+                }
+            }
+            Err(e) => tracing::warn!(
+                "ReplicatedStore {}: received invalid entries: {}",
+                &self.key_expr,
+                e
+            ),
+        }
+    }
+
+    fn wire_entries(&self) -> Vec<WireEntry> {
+        zlock!(self.entries)
+            .iter()
+            .map(|(key_expr, entry)| WireEntry::new(key_expr, entry))
+            .collect()
+    }
+
+    fn digest(&self) -> StateDigest {
+        let mut hasher = Sha3_256::new();
+        for (key_expr, entry) in zlock!(self.entries).iter() {
+            hasher.update(key_expr.as_str().as_bytes());
+            hasher.update([0]);
+            hasher.update(entry.timestamp.get_time().as_u64().to_le_bytes());
+            hasher.update(entry.timestamp.get_id().to_le_bytes());
+            hasher.update([entry.value.is_some() as u8]);
+        }
+        hasher.finalize().into()
+    }
+}
+
+fn reply_entries(state: &StoreState, entries_ke: &KeyExpr<'static>, query: Query) {
+    use zenoh::prelude::sync::SyncResolve;
+
+    match bincode::serialize(&state.wire_entries()) {
+        Ok(buf) => {
+            let sample = Sample::new(entries_ke.clone(), buf);
+            if let Err(e) = query.reply(Ok(sample)).res_sync() {
+                tracing::warn!("Error replying to query: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!(
+            "ReplicatedStore {}: failed to serialize entries: {}",
+            &state.key_expr,
+            e
+        ),
+    }
+}
+
+async fn align(z: &Session, state: &StoreState, selector: String) {
+    let replies = match z
+        .get(selector)
+        .consolidation(ConsolidationMode::None)
+        .res()
+        .await
+    {
+        Ok(replies) => replies,
+        Err(e) => {
+            tracing::warn!(
+                "ReplicatedStore {}: failed to fetch entries: {}",
+                &state.key_expr,
+                e
+            );
+            return;
+        }
+    };
+    while let Ok(reply) = replies.recv_async().await {
+        match reply.sample {
+            Ok(sample) => state.merge_wire(&sample.value.payload.contiguous()),
+            Err(e) => tracing::debug!(
+                "ReplicatedStore {}: received an error reply: {}",
+                &state.key_expr,
+                e
+            ),
+        }
+    }
+}
+
+/// A key-value store replicated between the applications starting it on the same key expression.
+///
+/// The replica stops being updated when dropped.
+pub struct ReplicatedStore {
+    session: Arc<Session>,
+    id: String,
+    state: Arc<StoreState>,
+    last_timestamp: Mutex<Option<Timestamp>>,
+    _update_sub: Subscriber<'static, ()>,
+    _entries_queryable: Queryable<'static, ()>,
+    task_controller: TaskController,
+}
+
+impl Drop for ReplicatedStore {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+impl ReplicatedStore {
+    pub async fn start(z: Arc<Session>, with: ReplicatedStoreConfig) -> ZResult<ReplicatedStore> {
+        let key_expr = with.key_expr;
+        let id = format!(
+            "{}_{}",
+            z.zid(),
+            REPLICA_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let state = Arc::new(StoreState {
+            key_expr: key_expr.clone(),
+            entries: Mutex::new(BTreeMap::new()),
+        });
+
+        let update_state = state.clone();
+        let update_sub = z
+            .declare_subscriber(format!("{REPLICATION_PREFIX}/{key_expr}/{UPDATE_SUFFIX}"))
+            .callback(move |sample| update_state.merge_wire(&sample.value.payload.contiguous()))
+            .res()
+            .await?;
+
+        let entries_state = state.clone();
+        let entries_ke = KeyExpr::try_from(format!(
+            "{REPLICATION_PREFIX}/{key_expr}/{ENTRIES_PREFIX}/{id}"
+        ))?;
+        let entries_queryable = z
+            .declare_queryable(entries_ke.clone())
+            .callback(move |query| reply_entries(&entries_state, &entries_ke, query))
+            .res()
+            .await?;
+
+        let (digest_tx, digest_rx) = flume::unbounded::<Sample>();
+        let digest_sub = z
+            .declare_subscriber(format!("{REPLICATION_PREFIX}/{key_expr}/{DIGEST_PREFIX}/*"))
+            .callback(move |sample| {
+                let _ = digest_tx.send(sample);
+            })
+            .res()
+            .await?;
+
+        // align with the existing replicas
+        align(
+            &z,
+            &state,
+            format!("{REPLICATION_PREFIX}/{key_expr}/{ENTRIES_PREFIX}/*"),
+        )
+        .await;
+
+        let task_controller = TaskController::default();
+        let digest_ke = format!("{REPLICATION_PREFIX}/{key_expr}/{DIGEST_PREFIX}/{id}");
+        let task_session = z.clone();
+        let task_state = state.clone();
+        let digest_period = with.digest_period;
+        task_controller.spawn_abortable(async move {
+            let _digest_sub = digest_sub;
+            let mut interval = tokio::time::interval(digest_period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        let digest = task_state.digest();
+                        if let Err(e) = task_session.put(&digest_ke, digest.to_vec()).res().await {
+                            tracing::warn!("ReplicatedStore {}: failed to publish digest: {}", &task_state.key_expr, e);
+                        }
+                    }
+                    sample = digest_rx.recv_async() => {
+                        let Ok(sample) = sample else { return };
+                        if sample.key_expr.as_str() == digest_ke {
+                            continue;
+                        }
+                        if *sample.value.payload.contiguous() == task_state.digest() {
+                            continue;
+                        }
+                        let Some(replica) = sample.key_expr.as_str().rsplit('/').next() else {
+                            continue;
+                        };
+                        tracing::debug!("ReplicatedStore {}: aligning with replica {}", &task_state.key_expr, replica);
+                        align(
+                            &task_session,
+                            &task_state,
+                            format!("{REPLICATION_PREFIX}/{}/{ENTRIES_PREFIX}/{replica}", &task_state.key_expr),
+                        )
+                        .await;
+                    }
+                }
+            }
+        });
+
+        tracing::debug!("Started ReplicatedStore {} with id {}", &key_expr, &id);
+        Ok(ReplicatedStore {
+            session: z,
+            id,
+            state,
+            last_timestamp: Mutex::new(None),
+            _update_sub: update_sub,
+            _entries_queryable: entries_queryable,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of this store.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.state.key_expr
+    }
+
+    /// Returns the id of this replica, unique among the replicas of the same store.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn new_timestamp(&self) -> Timestamp {
+        if let Some(hlc) = self.session.hlc() {
+            return hlc.new_timestamp();
+        }
+        // ensure the timestamps of this replica are strictly increasing
+        let mut last = zlock!(self.last_timestamp);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let mut time = NTP64::from(now);
+        if let Some(last) = last.as_ref() {
+            if time <= *last.get_time() {
+                time = NTP64(last.get_time().as_u64() + 1);
+            }
+        }
+        let timestamp = Timestamp::new(time, (&self.session.zid()).into());
+        *last = Some(timestamp);
+        timestamp
+    }
+
+    async fn update(&self, key_expr: OwnedKeyExpr, value: Option<Value>) -> ZResult<()> {
+        if key_expr.is_wild() {
+            bail!(
+                "ReplicatedStore keys are not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        let entry = Entry {
+            timestamp: self.new_timestamp(),
+            value,
+        };
+        let buf = bincode::serialize(&vec![WireEntry::new(&key_expr, &entry)])?;
+        self.state.merge(key_expr, entry);
+        self.session
+            .put(
+                format!(
+                    "{REPLICATION_PREFIX}/{}/{UPDATE_SUFFIX}",
+                    &self.state.key_expr
+                ),
+                buf,
+            )
+            .res()
+            .await
+    }
+
+    /// Sets the value of the given key in all the replicas.
+    pub async fn put<K, V>(&self, key_expr: K, value: V) -> ZResult<()>
+    where
+        K: TryInto<OwnedKeyExpr>,
+        <K as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+        V: Into<Value>,
+    {
+        let key_expr = key_expr.try_into().map_err(|e| e.into())?;
+        self.update(key_expr, Some(value.into())).await
+    }
+
+    /// Removes the given key from all the replicas.
+    pub async fn delete<K>(&self, key_expr: K) -> ZResult<()>
+    where
+        K: TryInto<OwnedKeyExpr>,
+        <K as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr = key_expr.try_into().map_err(|e| e.into())?;
+        self.update(key_expr, None).await
+    }
+
+    /// Returns the value of the given key in this replica.
+    pub fn get(&self, key_expr: &keyexpr) -> Option<Value> {
+        zlock!(self.state.entries)
+            .get(key_expr)
+            .and_then(|entry| entry.value.clone())
+    }
+
+    /// Returns the merged view of the store in this replica.
+    pub fn snapshot(&self) -> BTreeMap<OwnedKeyExpr, Value> {
+        zlock!(self.state.entries)
+            .iter()
+            .filter_map(|(key_expr, entry)| Some((key_expr.clone(), entry.value.clone()?)))
+            .collect()
+    }
+}
@@ -0,0 +1,422 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To call typed remote procedures over queries.
+//!
+//! A [`Service`] serves the requests made on a key expression with an async handler, and a
+//! [`Client`] calls it. Requests and responses are serialized in JSON, and the errors returned
+//! by the handler are sent back as error replies carrying their [`ErrorCode`].
+//!
+//! Each [`Service`] declares a queryable on `@rpc/any/<key_expr>`, which a [`Client`] queries
+//! to reach the nearest server, and another one on `@rpc/srv/<server id>/<key_expr>` along with
+//! a liveliness token, which a [`Client`] configured with
+//! [`load_balancing`](ClientConfig::load_balancing) uses to send its requests to each server
+//! in turn.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use std::sync::Arc;
+//! use zenoh::prelude::r#async::*;
+//! use zenoh::query::{ErrorCode, ReplyError};
+//! use zenoh_ext::rpc::*;
+//!
+//! let session = Arc::new(zenoh::open(config::peer()).res().await.unwrap());
+//! let _service = Service::serve(session.clone(), "math/div", |req: Request<(i64, i64)>| async move {
+//!     let (a, b) = req.payload;
+//!     if b == 0 {
+//!         return Err(ReplyError {
+//!             code: ErrorCode::InvalidRequest,
+//!             value: "division by zero".into(),
+//!         });
+//!     }
+//!     Ok(a / b)
+//! })
+//! .await
+//! .unwrap();
+//!
+//! let client = Client::start(session, ClientConfig::new("math/div").unwrap())
+//!     .await
+//!     .unwrap();
+//! let quotient: i64 = client.call(&(7, 2)).await.unwrap();
+//! # }
+//! ```
+
+use futures::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::liveliness::LivelinessToken;
+use zenoh::prelude::r#async::*;
+use zenoh::query::{
+    ConsolidationMode, ErrorCode, QueryStatus, QueryTarget, ReplyError, ReplyEvent,
+};
+use zenoh::queryable::{Query, Queryable};
+use zenoh::subscriber::FlumeSubscriber;
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_core::zlock;
+use zenoh_result::bail;
+use zenoh_task::TaskController;
+
+/// The key expression prefix under which services are declared.
+pub const RPC_PREFIX: &str = "@rpc";
+const ANY_SERVER_PREFIX: &str = "any";
+const SERVERS_PREFIX: &str = "srv";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static SERVER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A request received by a [`Service`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct Request<T> {
+    /// The deserialized payload of the request.
+    pub payload: T,
+}
+
+fn encode<T: Serialize>(payload: &T) -> Result<Value, serde_json::Error> {
+    serde_json::to_vec(payload).map(|buf| Value::from(buf).encoding(Encoding::APP_JSON))
+}
+
+fn decode<T: DeserializeOwned>(value: &Value) -> Result<T, serde_json::Error> {
+    serde_json::from_slice(&value.payload.contiguous())
+}
+
+fn reply_error<E: ToString>(code: ErrorCode, e: E) -> ReplyError {
+    ReplyError {
+        code,
+        value: e.to_string().into(),
+    }
+}
+
+/// Returns the id of the server which declared the given liveliness token.
+fn server_id<'a>(key_expr: &'a keyexpr, service: &keyexpr) -> Option<&'a str> {
+    key_expr
+        .as_str()
+        .strip_prefix(RPC_PREFIX)?
+        .strip_prefix('/')?
+        .strip_prefix(SERVERS_PREFIX)?
+        .strip_prefix('/')?
+        .strip_suffix(service.as_str())?
+        .strip_suffix('/')
+}
+
+/// A server answering the requests made on a key expression.
+///
+/// The requests are handled concurrently. The service stops serving them when dropped.
+pub struct Service {
+    id: String,
+    key_expr: OwnedKeyExpr,
+    _any_queryable: Queryable<'static, ()>,
+    _queryable: Queryable<'static, ()>,
+    _token: LivelinessToken<'static>,
+    task_controller: TaskController,
+}
+
+impl Drop for Service {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+async fn handle_request<Req, Resp, F, Fut>(query: Query, handler: &F)
+where
+    Req: DeserializeOwned,
+    Resp: Serialize,
+    F: Fn(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Resp, ReplyError>>,
+{
+    let result = match query.value().map(decode::<Req>) {
+        // failing to serialize its own response is an error of the service, not of the client
+        Some(Ok(payload)) => handler(Request { payload })
+            .await
+            .and_then(|r| encode(&r).map_err(|e| reply_error(ErrorCode::Internal, e))),
+        Some(Err(e)) => Err(reply_error(ErrorCode::InvalidRequest, e)),
+        None => Err(reply_error(
+            ErrorCode::InvalidRequest,
+            "Missing request payload",
+        )),
+    };
+    let res = match result {
+        Ok(value) => {
+            let sample = Sample::new(query.key_expr().clone(), value);
+            query.reply(Ok(sample)).res().await
+        }
+        Err(e) => query.reply_err(e.code, e.value).res().await,
+    };
+    if let Err(e) = res {
+        tracing::warn!("Error replying to request on {}: {}", query.key_expr(), e);
+    }
+}
+
+impl Service {
+    /// Starts serving the requests made on the given key expression with the given handler.
+    pub async fn serve<T, Req, Resp, F, Fut>(
+        z: Arc<Session>,
+        key_expr: T,
+        handler: F,
+    ) -> ZResult<Service>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+        Req: DeserializeOwned + Send + 'static,
+        Resp: Serialize + Send + 'static,
+        F: Fn(Request<Req>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Resp, ReplyError>> + Send + 'static,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "Service key expression is not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        let id = format!(
+            "{}_{}",
+            z.zid(),
+            SERVER_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let server_ke = format!("{RPC_PREFIX}/{SERVERS_PREFIX}/{id}/{key_expr}");
+
+        let (tx, rx) = flume::unbounded::<Query>();
+        let any_tx = tx.clone();
+        let any_queryable = z
+            .declare_queryable(format!("{RPC_PREFIX}/{ANY_SERVER_PREFIX}/{key_expr}"))
+            .complete(true)
+            .callback(move |query| {
+                let _ = any_tx.send(query);
+            })
+            .res()
+            .await?;
+        let queryable = z
+            .declare_queryable(&server_ke)
+            .complete(true)
+            .callback(move |query| {
+                let _ = tx.send(query);
+            })
+            .res()
+            .await?;
+        let token = z.liveliness().declare_token(&server_ke).res().await?;
+
+        let task_controller = TaskController::default();
+        task_controller.spawn_abortable(async move {
+            rx.into_stream()
+                .for_each_concurrent(None, |query| handle_request(query, &handler))
+                .await
+        });
+        tracing::debug!("Started service {} with id {}", &key_expr, &id);
+        Ok(Service {
+            id,
+            key_expr,
+            _any_queryable: any_queryable,
+            _queryable: queryable,
+            _token: token,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of this service.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.key_expr
+    }
+
+    /// Returns the id of this server, unique among the servers of the same service.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// The configuration of a [`Client`].
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    key_expr: OwnedKeyExpr,
+    timeout: Duration,
+    load_balancing: bool,
+}
+
+impl ClientConfig {
+    pub fn new<T>(key_expr: T) -> ZResult<ClientConfig>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let key_expr: OwnedKeyExpr = key_expr.try_into().map_err(|e| e.into())?;
+        if key_expr.is_wild() {
+            bail!(
+                "Service key expression is not allowed to contain wildcards: {}",
+                key_expr
+            );
+        }
+        Ok(ClientConfig {
+            key_expr,
+            timeout: DEFAULT_TIMEOUT,
+            load_balancing: false,
+        })
+    }
+
+    /// Change the timeout of the calls.
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = d;
+        self
+    }
+
+    /// Send the calls to each server of the service in turn, instead of the nearest one.
+    pub fn load_balancing(mut self, enabled: bool) -> Self {
+        self.load_balancing = enabled;
+        self
+    }
+}
+
+struct ClientState {
+    session: Arc<Session>,
+    key_expr: OwnedKeyExpr,
+    timeout: Duration,
+    servers: Mutex<BTreeSet<String>>,
+    next: AtomicUsize,
+}
+
+/// A client calling the servers of a [`Service`].
+pub struct Client {
+    state: Arc<ClientState>,
+    load_balancing: bool,
+    task_controller: TaskController,
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        // cancel background tasks
+        self.task_controller.terminate_all(Duration::from_secs(10));
+    }
+}
+
+async fn servers_handler(state: Arc<ClientState>, sub: FlumeSubscriber<'static>) {
+    while let Ok(s) = sub.recv_async().await {
+        let Some(id) = server_id(&s.key_expr, &state.key_expr) else {
+            continue;
+        };
+        let mut servers = zlock!(state.servers);
+        match s.kind {
+            SampleKind::Put => {
+                tracing::debug!("New server {} for service {}", id, &state.key_expr);
+                servers.insert(id.to_string());
+            }
+            SampleKind::Delete => {
+                tracing::debug!("Server {} left service {}", id, &state.key_expr);
+                servers.remove(id);
+            }
+        }
+    }
+}
+
+impl Client {
+    pub async fn start(z: Arc<Session>, with: ClientConfig) -> ZResult<Client> {
+        let state = Arc::new(ClientState {
+            session: z.clone(),
+            key_expr: with.key_expr,
+            timeout: with.timeout,
+            servers: Mutex::new(BTreeSet::new()),
+            next: AtomicUsize::new(0),
+        });
+        let task_controller = TaskController::default();
+        if with.load_balancing {
+            let servers_ke = format!("{RPC_PREFIX}/{SERVERS_PREFIX}/*/{}", state.key_expr);
+            let servers_sub = z.liveliness().declare_subscriber(&servers_ke).res().await?;
+            let replies = z.liveliness().get(&servers_ke).res().await?;
+            while let Ok(reply) = replies.recv_async().await {
+                if let Ok(sample) = reply.sample {
+                    if let Some(id) = server_id(&sample.key_expr, &state.key_expr) {
+                        zlock!(state.servers).insert(id.to_string());
+                    }
+                }
+            }
+            task_controller.spawn_abortable(servers_handler(state.clone(), servers_sub));
+        }
+        Ok(Client {
+            state,
+            load_balancing: with.load_balancing,
+            task_controller,
+        })
+    }
+
+    /// Returns the key expression of the called service.
+    pub fn key_expr(&self) -> &keyexpr {
+        &self.state.key_expr
+    }
+
+    /// Returns the ids of the known servers of the service.
+    ///
+    /// The servers are only tracked with [`load_balancing`](ClientConfig::load_balancing).
+    pub fn servers(&self) -> Vec<String> {
+        zlock!(self.state.servers).iter().cloned().collect()
+    }
+
+    fn next_server(&self) -> Option<String> {
+        let servers = zlock!(self.state.servers);
+        if servers.is_empty() {
+            return None;
+        }
+        let next = self.state.next.fetch_add(1, Ordering::Relaxed) % servers.len();
+        servers.iter().nth(next).cloned()
+    }
+
+    /// Calls the service with the given request, returning its response.
+    ///
+    /// Fails with [`ErrorCode::NotFound`] if no server answered, with [`ErrorCode::Timeout`] if
+    /// the call timed out, or with the error returned by the server's handler.
+    pub async fn call<Req, Resp>(&self, request: &Req) -> Result<Resp, ReplyError>
+    where
+        Req: Serialize,
+        Resp: DeserializeOwned,
+    {
+        let key_expr = &self.state.key_expr;
+        let selector = match self.load_balancing.then(|| self.next_server()).flatten() {
+            Some(id) => format!("{RPC_PREFIX}/{SERVERS_PREFIX}/{id}/{key_expr}"),
+            None => format!("{RPC_PREFIX}/{ANY_SERVER_PREFIX}/{key_expr}"),
+        };
+        let mut replies = self
+            .state
+            .session
+            .get(selector)
+            .with_value(encode(request).map_err(|e| reply_error(ErrorCode::InvalidRequest, e))?)
+            .target(QueryTarget::BestMatching)
+            .consolidation(ConsolidationMode::None)
+            .timeout(self.state.timeout)
+            .stream()
+            .res()
+            .await
+            .map_err(|e| reply_error(ErrorCode::Internal, e))?;
+        match replies.next().await {
+            Some(ReplyEvent::Reply(reply)) => {
+                let sample = reply.into_result()?;
+                decode(&sample.value).map_err(|e| reply_error(ErrorCode::Internal, e))
+            }
+            Some(ReplyEvent::Done(QueryStatus::Timeout)) => Err(reply_error(
+                ErrorCode::Timeout,
+                format!("Call to service {} timed out", key_expr),
+            )),
+            _ => Err(reply_error(
+                ErrorCode::NotFound,
+                format!("No server answered the call to service {}", key_expr),
+            )),
+        }
+    }
+}
@@ -24,7 +24,9 @@ use zenoh::{
     subscriber::{PushMode, Reliability, Subscriber, SubscriberBuilder},
 };
 
-use crate::{querying_subscriber::QueryingSubscriberBuilder, FetchingSubscriberBuilder};
+use crate::{
+    querying_subscriber::QueryingSubscriberBuilder, FetchingSubscriberBuilder, MergePolicy,
+};
 
 /// Allows writing `subscriber.forward(receiver)` instead of `subscriber.stream().map(Ok).forward(publisher)`
 pub trait SubscriberForward<'a, S> {
@@ -182,6 +184,7 @@ impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
             reliability: self.reliability,
             origin: self.origin,
             fetch,
+            merge_policy: MergePolicy::default(),
             handler: self.handler,
             phantom: std::marker::PhantomData,
         }
@@ -232,6 +235,7 @@ impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
             query_consolidation: QueryConsolidation::from(zenoh::query::ConsolidationMode::None),
             query_accept_replies: ReplyKeyExpr::default(),
             query_timeout: Duration::from_secs(10),
+            merge_policy: MergePolicy::default(),
             handler: self.handler,
         }
     }
@@ -298,6 +302,7 @@ impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
             reliability: Reliability::default(),
             origin: Locality::default(),
             fetch,
+            merge_policy: MergePolicy::default(),
             handler: self.handler,
             phantom: std::marker::PhantomData,
         }
@@ -347,6 +352,7 @@ impl<'a, 'b, Handler> SubscriberBuilderExt<'a, 'b, Handler>
             query_consolidation: QueryConsolidation::default(),
             query_accept_replies: ReplyKeyExpr::MatchingQuery,
             query_timeout: Duration::from_secs(10),
+            merge_policy: MergePolicy::default(),
             handler: self.handler,
         }
     }
@@ -0,0 +1,245 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! To query the samples stored on a key expression within a time range.
+//!
+//! A [`TimeRangeQuery`] builds the `_time` parameter of the selector from typed bounds (any
+//! type convertible into a [`SystemTime`], such as `time::OffsetDateTime`) and returns the
+//! replies ordered by timestamp, without duplicates. Since the queryables may ignore the time
+//! range, the samples without timestamp or outside of the range are discarded.
+//!
+//! Large result sets can be fetched [page by page](TimeRangeQuery::pages), each page being
+//! the result of a query on a time window of the range.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use futures::prelude::*;
+//! use std::time::{Duration, SystemTime};
+//! use zenoh::prelude::r#async::*;
+//! use zenoh_ext::time_series::TimeRangeQuery;
+//!
+//! let session = zenoh::open(config::peer()).res().await.unwrap();
+//! let query = TimeRangeQuery::new("sensors/temperature")
+//!     .unwrap()
+//!     .start(SystemTime::now() - Duration::from_secs(24 * 3600))
+//!     .page(Duration::from_secs(3600));
+//! let mut pages = query.pages(&session);
+//! while let Some(page) = pages.next().await {
+//!     for sample in page.unwrap() {
+//!         println!("{}: {}", sample.timestamp.unwrap(), sample.value);
+//!     }
+//! }
+//! # }
+//! ```
+
+use futures::prelude::*;
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::time::{Duration, SystemTime};
+use zenoh::prelude::r#async::*;
+use zenoh::query::{ConsolidationMode, QueryTarget};
+use zenoh::selector::{TimeBound, TimeRange};
+use zenoh::Error as ZError;
+use zenoh::Result as ZResult;
+use zenoh::Session;
+use zenoh_result::zerror;
+
+/// Sorts the given samples by timestamp, the samples without timestamp coming last.
+pub fn sort_by_timestamp(samples: &mut [Sample]) {
+    samples.sort_by(|a, b| match (&a.timestamp, &b.timestamp) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+}
+
+/// A query of the samples stored on a key expression within a time range.
+#[derive(Debug, Clone)]
+pub struct TimeRangeQuery {
+    key_expr: OwnedKeyExpr,
+    start: Option<SystemTime>,
+    end: Option<SystemTime>,
+    page: Option<Duration>,
+    target: QueryTarget,
+    timeout: Option<Duration>,
+}
+
+impl TimeRangeQuery {
+    pub fn new<T>(key_expr: T) -> ZResult<TimeRangeQuery>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        Ok(TimeRangeQuery {
+            key_expr: key_expr.try_into().map_err(|e| e.into())?,
+            start: None,
+            end: None,
+            page: None,
+            target: QueryTarget::default(),
+            timeout: None,
+        })
+    }
+
+    /// Change the inclusive start of the time range (unbounded by default).
+    pub fn start<T: Into<SystemTime>>(mut self, start: T) -> Self {
+        self.start = Some(start.into());
+        self
+    }
+
+    /// Change the exclusive end of the time range (unbounded by default).
+    pub fn end<T: Into<SystemTime>>(mut self, end: T) -> Self {
+        self.end = Some(end.into());
+        self
+    }
+
+    /// Change the duration of the time window queried for each page.
+    ///
+    /// The time range must have a start to be paginated.
+    pub fn page(mut self, page: Duration) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Change the target of the queries.
+    pub fn target(mut self, target: QueryTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Change the timeout of each query.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the selector of the samples within the whole time range.
+    pub fn selector(&self) -> Selector<'static> {
+        self.window_selector(self.start, self.end)
+    }
+
+    fn window_selector(
+        &self,
+        start: Option<SystemTime>,
+        end: Option<SystemTime>,
+    ) -> Selector<'static> {
+        let mut selector: Selector<'static> = KeyExpr::from(self.key_expr.clone()).into();
+        selector.with_time_range(TimeRange(
+            start.map_or(TimeBound::Unbounded, |t| TimeBound::Inclusive(t.into())),
+            end.map_or(TimeBound::Unbounded, |t| TimeBound::Exclusive(t.into())),
+        ));
+        selector
+    }
+
+    async fn query_window(
+        &self,
+        z: &Session,
+        start: Option<SystemTime>,
+        end: Option<SystemTime>,
+    ) -> ZResult<Vec<Sample>> {
+        let range = TimeRange::<SystemTime>(
+            start.map_or(TimeBound::Unbounded, TimeBound::Inclusive),
+            end.map_or(TimeBound::Unbounded, TimeBound::Exclusive),
+        );
+        let mut get = z
+            .get(self.window_selector(start, end))
+            .target(self.target)
+            .consolidation(ConsolidationMode::None);
+        if let Some(timeout) = self.timeout {
+            get = get.timeout(timeout);
+        }
+        let replies = get.res().await?;
+
+        let mut seen = HashSet::new();
+        let mut samples = vec![];
+        while let Ok(reply) = replies.recv_async().await {
+            match reply.sample {
+                Ok(sample) => {
+                    let Some(timestamp) = sample.timestamp else {
+                        continue;
+                    };
+                    if !range.contains(timestamp.get_time().to_system_time()) {
+                        continue;
+                    }
+                    // several queryables may store the same sample
+                    if seen.insert((sample.key_expr.as_str().to_string(), timestamp)) {
+                        samples.push(sample);
+                    }
+                }
+                Err(e) => tracing::debug!(
+                    "Received an error reply to time range query on {}: {}",
+                    &self.key_expr,
+                    e
+                ),
+            }
+        }
+        sort_by_timestamp(&mut samples);
+        Ok(samples)
+    }
+
+    /// Returns the samples within the whole time range, ordered by timestamp.
+    pub async fn fetch(&self, z: &Session) -> ZResult<Vec<Sample>> {
+        match self.page {
+            Some(_) => {
+                let mut samples = vec![];
+                let mut pages = self.pages(z);
+                while let Some(page) = pages.next().await {
+                    samples.extend(page?);
+                }
+                Ok(samples)
+            }
+            None => self.query_window(z, self.start, self.end).await,
+        }
+    }
+
+    /// Returns the samples within the time range page by page, in chronological order.
+    ///
+    /// Each page queries a time window of the configured [page](Self::page) duration, up to the
+    /// end of the time range or, if unbounded, to the time at which the pagination started.
+    pub fn pages<'a>(
+        &'a self,
+        z: &'a Session,
+    ) -> impl Stream<Item = ZResult<Vec<Sample>>> + Unpin + 'a {
+        let windows = match (self.start, self.page) {
+            (Some(start), Some(page)) if !page.is_zero() => {
+                let end = self.end.unwrap_or_else(SystemTime::now);
+                Ok((start, page, end))
+            }
+            (None, _) => Err(zerror!(
+                "Time range query on {} must have a start to be paginated",
+                &self.key_expr
+            )),
+            (_, _) => Err(zerror!(
+                "Time range query on {} must have a non-zero page duration to be paginated",
+                &self.key_expr
+            )),
+        };
+        Box::pin(stream::unfold(Some(windows), move |state| async move {
+            match state? {
+                Ok((start, page, end)) => {
+                    if start >= end {
+                        return None;
+                    }
+                    let window_end = start.checked_add(page).map_or(end, |t| t.min(end));
+                    let samples = self.query_window(z, Some(start), Some(window_end)).await;
+                    let next = samples.is_ok().then_some(Ok((window_end, page, end)));
+                    Some((samples, next))
+                }
+                Err(e) => Some((Err(e.into()), None)),
+            }
+        }))
+    }
+}
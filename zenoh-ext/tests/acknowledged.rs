@@ -0,0 +1,179 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+mod common;
+
+use common::{open_session, SLEEP, TIMEOUT};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::acknowledged::{
+    AckedPublisher, AckedPublisherConfig, AckedSubscriber, AckedSubscriberConfig, DeliveryEvent,
+};
+
+const KEY_EXPR: &str = "test/acknowledged";
+
+async fn wait_receivers(publisher: &AckedPublisher, receivers: &[&str]) {
+    ztimeout!(async {
+        while publisher.receivers() != receivers {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn acknowledged_ack() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38490"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38490"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let publisher = AckedPublisher::start(session1, AckedPublisherConfig::new(KEY_EXPR).unwrap())
+        .await
+        .unwrap();
+    let events = publisher.subscribe();
+    let subscriber = AckedSubscriber::start(
+        session2,
+        AckedSubscriberConfig::new("rx1", KEY_EXPR).unwrap(),
+    )
+    .await
+    .unwrap();
+    wait_receivers(&publisher, &["rx1"]).await;
+
+    let sn = publisher.put("data").await.unwrap();
+    assert_eq!(publisher.pending(), 1);
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.sn(), Some(sn));
+    assert_eq!(String::try_from(&sample.value).unwrap(), "data");
+    sample.ack().await.unwrap();
+
+    assert_eq!(
+        ztimeout!(events.recv_async()).unwrap(),
+        DeliveryEvent::Acked { sn }
+    );
+    assert_eq!(publisher.pending(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn acknowledged_no_receivers() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session(&[], &[]).await;
+
+    let with = AckedPublisherConfig::new(KEY_EXPR).unwrap().max_pending(1);
+    let publisher = AckedPublisher::start(session, with).await.unwrap();
+    let events = publisher.subscribe();
+
+    // Without receivers to wait for, the publications are acknowledged at once
+    for _ in 0..2 {
+        let sn = publisher.put("data").await.unwrap();
+        assert_eq!(
+            ztimeout!(events.recv_async()).unwrap(),
+            DeliveryEvent::Acked { sn }
+        );
+    }
+    assert_eq!(publisher.pending(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn acknowledged_max_pending() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38491"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38491"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let config = AckedPublisherConfig::new(KEY_EXPR).unwrap().max_pending(1);
+    let publisher = AckedPublisher::start(session1, config).await.unwrap();
+    let _subscriber = AckedSubscriber::start(
+        session2,
+        AckedSubscriberConfig::new("rx1", KEY_EXPR).unwrap(),
+    )
+    .await
+    .unwrap();
+    wait_receivers(&publisher, &["rx1"]).await;
+
+    publisher.put("data").await.unwrap();
+    assert!(publisher.put("data").await.is_err());
+    assert_eq!(publisher.pending(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn acknowledged_retransmission() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38492"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38492"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let config = AckedPublisherConfig::new(KEY_EXPR)
+        .unwrap()
+        .retransmit_period(Duration::from_millis(100));
+    let publisher = AckedPublisher::start(session1, config).await.unwrap();
+    let events = publisher.subscribe();
+    let subscriber = AckedSubscriber::start(
+        session2,
+        AckedSubscriberConfig::new("rx1", KEY_EXPR).unwrap(),
+    )
+    .await
+    .unwrap();
+    wait_receivers(&publisher, &["rx1"]).await;
+
+    // A sample dropped without being acknowledged is delivered again
+    let sn = publisher.put("data").await.unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.sn(), Some(sn));
+    drop(sample);
+
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(sample.sn(), Some(sn));
+    assert_eq!(String::try_from(&sample.value).unwrap(), "data");
+    sample.ack().await.unwrap();
+
+    assert_eq!(
+        ztimeout!(events.recv_async()).unwrap(),
+        DeliveryEvent::Acked { sn }
+    );
+    assert_eq!(publisher.pending(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn acknowledged_expiration() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38493"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38493"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let config = AckedPublisherConfig::new(KEY_EXPR)
+        .unwrap()
+        .retransmit_period(Duration::from_millis(100))
+        .expiration(SLEEP);
+    let publisher = AckedPublisher::start(session1, config).await.unwrap();
+    let events = publisher.subscribe();
+    let subscriber = AckedSubscriber::start(
+        session2,
+        AckedSubscriberConfig::new("rx1", KEY_EXPR).unwrap(),
+    )
+    .await
+    .unwrap();
+    wait_receivers(&publisher, &["rx1"]).await;
+
+    // A sample held without being acknowledged is not delivered again, and expires
+    let sn = publisher.put("data").await.unwrap();
+    let _sample = ztimeout!(subscriber.recv_async()).unwrap();
+    assert_eq!(
+        ztimeout!(events.recv_async()).unwrap(),
+        DeliveryEvent::Expired {
+            sn,
+            unacked: vec!["rx1".to_string()]
+        }
+    );
+    assert_eq!(publisher.pending(), 0);
+}
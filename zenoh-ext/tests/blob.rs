@@ -0,0 +1,169 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+mod common;
+
+use common::{open_session, SLEEP, TIMEOUT};
+use sha3::{Digest, Sha3_256};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::sync::SyncResolve;
+use zenoh::queryable::Queryable;
+use zenoh_core::ztimeout;
+use zenoh_ext::blob::{BlobFetcher, BlobFetcherConfig, BlobManifest, BlobServer, BlobServerConfig};
+
+const CHUNK_SIZE: usize = 1024;
+
+fn blob() -> Vec<u8> {
+    (0..10 * CHUNK_SIZE + CHUNK_SIZE / 2)
+        .map(|i| (i % 251) as u8)
+        .collect()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("zenoh-ext-blob-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(part_path(&path));
+    path
+}
+
+fn part_path(path: &PathBuf) -> PathBuf {
+    let mut part_path = path.as_os_str().to_owned();
+    part_path.push(".part");
+    PathBuf::from(part_path)
+}
+
+fn manifest(data: &[u8]) -> BlobManifest {
+    BlobManifest {
+        size: data.len() as u64,
+        chunk_size: CHUNK_SIZE as u32,
+        chunks: data
+            .chunks(CHUNK_SIZE)
+            .map(|chunk| Sha3_256::digest(chunk).into())
+            .collect(),
+    }
+}
+
+/// Serves the given blob like a [`BlobServer`], corrupting its chunks if `corrupt` is set,
+/// and returns the indexes of the queried chunks.
+async fn serve_fake(
+    session: &Arc<Session>,
+    key_expr: &str,
+    data: Vec<u8>,
+    corrupt: bool,
+) -> (Queryable<'static, ()>, Arc<Mutex<Vec<usize>>>) {
+    let manifest = bincode::serialize(&manifest(&data)).unwrap();
+    let queried = Arc::new(Mutex::new(vec![]));
+    let queried2 = queried.clone();
+    let prefix = format!("{key_expr}/");
+    let queryable = session
+        .declare_queryable(format!("{key_expr}/**"))
+        .callback(move |query| {
+            let resource = query.key_expr().as_str().strip_prefix(&prefix).unwrap();
+            let buf = match resource.strip_prefix("chunk/") {
+                None => manifest.clone(),
+                Some(index) => {
+                    let index: usize = index.parse().unwrap();
+                    queried2.lock().unwrap().push(index);
+                    let mut chunk = data.chunks(CHUNK_SIZE).nth(index).unwrap().to_vec();
+                    if corrupt {
+                        chunk[0] ^= 0xff;
+                    }
+                    chunk
+                }
+            };
+            let sample = Sample::new(query.key_expr().clone(), buf);
+            query.reply(Ok(sample)).res_sync().unwrap();
+        })
+        .res()
+        .await
+        .unwrap();
+    (queryable, queried)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn blob_transfer() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38502"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38502"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let source = temp_path("source");
+    let path = temp_path("transfer");
+    std::fs::write(&source, blob()).unwrap();
+
+    let with = BlobServerConfig::new("test/blob/transfer")
+        .unwrap()
+        .chunk_size(CHUNK_SIZE as u32);
+    let server = BlobServer::serve_file(session1, with, &source)
+        .await
+        .unwrap();
+    assert_eq!(server.manifest(), &manifest(&blob()));
+    tokio::time::sleep(SLEEP).await;
+
+    let with = BlobFetcherConfig::new("test/blob/transfer").unwrap();
+    let fetched = ztimeout!(BlobFetcher::fetch(session2, with, &path)).unwrap();
+    assert_eq!(&fetched, server.manifest());
+    assert_eq!(std::fs::read(&path).unwrap(), blob());
+    assert!(!part_path(&path).exists());
+
+    let _ = std::fs::remove_file(&source);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn blob_resume() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38503"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38503"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let path = temp_path("resume");
+
+    // A transfer interrupted with all the chunks fetched but the second one...
+    let mut part = blob();
+    part[CHUNK_SIZE..2 * CHUNK_SIZE].fill(0);
+    std::fs::write(part_path(&path), part).unwrap();
+
+    // ...is resumed by fetching only the second one.
+    let (_queryable, queried) = serve_fake(&session1, "test/blob/resume", blob(), false).await;
+    tokio::time::sleep(SLEEP).await;
+    let with = BlobFetcherConfig::new("test/blob/resume").unwrap();
+    ztimeout!(BlobFetcher::fetch(session2, with, &path)).unwrap();
+    assert_eq!(*queried.lock().unwrap(), [1]);
+    assert_eq!(std::fs::read(&path).unwrap(), blob());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn blob_integrity() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38504"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38504"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let path = temp_path("integrity");
+
+    // The corrupted chunks are fetched again, up to the number of retries, then rejected
+    let (_queryable, queried) = serve_fake(&session1, "test/blob/integrity", blob(), true).await;
+    tokio::time::sleep(SLEEP).await;
+    let with = BlobFetcherConfig::new("test/blob/integrity")
+        .unwrap()
+        .parallelism(1)
+        .retries(1);
+    assert!(ztimeout!(BlobFetcher::fetch(session2, with, &path)).is_err());
+    assert_eq!(*queried.lock().unwrap(), [0, 0]);
+    assert!(!path.exists());
+    assert!(part_path(&path).exists());
+
+    let _ = std::fs::remove_file(part_path(&path));
+}
@@ -0,0 +1,50 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+pub const SLEEP: Duration = Duration::from_millis(500);
+
+/// Opens a peer session listening on `listen` and connected to `connect`, without scouting.
+pub async fn open_session(listen: &[&str], connect: &[&str]) -> Arc<Session> {
+    open_session_with(listen, connect, |_| ()).await
+}
+
+/// Same as [`open_session`], with the configuration amended by `f`.
+pub async fn open_session_with(
+    listen: &[&str],
+    connect: &[&str],
+    f: impl FnOnce(&mut Config),
+) -> Arc<Session> {
+    let mut config = config::peer();
+    config.listen.endpoints = listen
+        .iter()
+        .map(|e| e.parse().unwrap())
+        .collect::<Vec<_>>();
+    config.connect.endpoints = connect
+        .iter()
+        .map(|e| e.parse().unwrap())
+        .collect::<Vec<_>>();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    f(&mut config);
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
@@ -0,0 +1,124 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+mod common;
+
+use common::{open_session, SLEEP, TIMEOUT};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::delta_state::{DeltaState, StateConfig, StatePublisher, StateSubscriber};
+
+const KEY_EXPR: &str = "test/delta_state";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Counter(i64);
+
+impl DeltaState for Counter {
+    type Delta = i64;
+
+    fn apply(&mut self, delta: &i64) {
+        self.0 += delta;
+    }
+}
+
+/// Waits for the subscriber to reconstruct the given state.
+async fn wait_state(subscriber: &StateSubscriber<Counter>, state: i64) {
+    ztimeout!(async {
+        while subscriber.state() != Some(Counter(state)) {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn delta_state_snapshot_and_deltas() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38508"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38508"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let with = StateConfig::new(KEY_EXPR).unwrap();
+
+    let publisher = StatePublisher::start(session1, with.clone(), Counter(10))
+        .await
+        .unwrap();
+    publisher.update(1).await.unwrap();
+    publisher.update(1).await.unwrap();
+    assert_eq!(publisher.version(), 2);
+    tokio::time::sleep(SLEEP).await;
+
+    // A new subscriber queries the snapshot of the state...
+    let mut subscriber = StateSubscriber::<Counter>::start(session2, with)
+        .await
+        .unwrap();
+    assert_eq!(subscriber.state(), Some(Counter(12)));
+    assert_eq!(subscriber.version(), Some(2));
+
+    // ...and applies the following deltas.
+    publisher.update(5).await.unwrap();
+    ztimeout!(subscriber.changed()).unwrap();
+    assert_eq!(subscriber.state(), Some(Counter(17)));
+    assert_eq!(subscriber.version(), Some(3));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn delta_state_publisher_restart() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38509"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38509"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let with = StateConfig::new(KEY_EXPR).unwrap();
+
+    let subscriber = StateSubscriber::<Counter>::start(session2, with.clone())
+        .await
+        .unwrap();
+    assert_eq!(subscriber.state(), None);
+
+    // A delta received without snapshot makes the subscriber query it...
+    let publisher = StatePublisher::start(session1.clone(), with.clone(), Counter(10))
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    publisher.update(1).await.unwrap();
+    wait_state(&subscriber, 11).await;
+
+    // ...as well as a delta of a restarted publisher.
+    drop(publisher);
+    let publisher = StatePublisher::start(session1, with, Counter(100))
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    publisher.update(1).await.unwrap();
+    wait_state(&subscriber, 101).await;
+    assert_eq!(subscriber.version(), Some(1));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn delta_state_periodic_snapshot() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38510"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38510"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let with = StateConfig::new(KEY_EXPR).unwrap().snapshot_period(SLEEP);
+
+    // The subscriber started before the publisher receives its periodic snapshots
+    let subscriber = StateSubscriber::<Counter>::start(session2, with.clone())
+        .await
+        .unwrap();
+    let _publisher = StatePublisher::start(session1, with, Counter(10))
+        .await
+        .unwrap();
+    wait_state(&subscriber, 10).await;
+    assert_eq!(subscriber.version(), Some(0));
+}
@@ -0,0 +1,93 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::convert::TryFrom;
+use zenoh::prelude::r#async::*;
+use zenoh::sample::SourceInfo;
+use zenoh::time::{Timestamp, TimestampId, NTP64};
+use zenoh_ext::{FetchingSubscriber, MergePolicy, SubscriberBuilderExt};
+
+const KEY_EXPR: &str = "test/fetching";
+
+async fn open_session() -> Session {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().await.unwrap()
+}
+
+fn sample(value: &str) -> Sample {
+    Sample::new(KeyExpr::try_from(KEY_EXPR).unwrap(), value)
+}
+
+fn timestamp(time: u64) -> Timestamp {
+    Timestamp::new(NTP64(time), TimestampId::try_from([1]).unwrap())
+}
+
+fn received(subscriber: &FetchingSubscriber<'_, flume::Receiver<Sample>>) -> Vec<String> {
+    subscriber
+        .try_iter()
+        .map(|sample| String::try_from(&sample.value).unwrap())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fetching_merge_timestamp() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+
+    // The fetched samples are ordered by timestamp, after the ones without timestamp
+    let subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .fetching(|cb| {
+            for (time, value) in [(3, "3"), (1, "1"), (2, "2"), (1, "duplicate")] {
+                cb(sample(value).with_timestamp(timestamp(time)));
+            }
+            cb(sample("unordered"));
+            Ok(())
+        })
+        .merge_policy(MergePolicy::Timestamp)
+        .res()
+        .await
+        .unwrap();
+    assert_eq!(received(&subscriber), ["unordered", "1", "2", "3"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn fetching_merge_source_sn() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let source_id = session.zid();
+
+    // The fetched samples are ordered by source sequence number, whatever their timestamp
+    let subscriber = session
+        .declare_subscriber(KEY_EXPR)
+        .fetching(|cb| {
+            for (sn, value) in [(3, "3"), (1, "1"), (2, "2"), (1, "duplicate")] {
+                let source_info = SourceInfo {
+                    source_id: Some(source_id),
+                    source_sn: Some(sn),
+                    source_eid: 0,
+                };
+                cb(sample(value)
+                    .with_timestamp(timestamp(10 - sn))
+                    .with_source_info(source_info));
+            }
+            cb(sample("unordered").with_timestamp(timestamp(10)));
+            Ok(())
+        })
+        .merge_policy(MergePolicy::SourceSn)
+        .res()
+        .await
+        .unwrap();
+    assert_eq!(received(&subscriber), ["unordered", "1", "2", "3"]);
+}
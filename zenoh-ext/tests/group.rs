@@ -0,0 +1,103 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+mod common;
+
+use common::{open_session, SLEEP, TIMEOUT};
+use flume::Receiver;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::group::{Group, GroupEvent, Member};
+
+const GROUP: &str = "test_group";
+
+/// Waits for an event accepted by `f`, skipping the others.
+async fn wait_event(events: &Receiver<GroupEvent>, f: impl Fn(&GroupEvent) -> bool) {
+    ztimeout!(async {
+        loop {
+            if f(&events.recv_async().await.unwrap()) {
+                return;
+            }
+        }
+    });
+}
+
+fn is_new_leader(event: &GroupEvent, mid: &str) -> bool {
+    matches!(event, GroupEvent::NewLeader(e) if e.mid.as_str() == mid)
+}
+
+fn is_leave(event: &GroupEvent, mid: &str) -> bool {
+    matches!(event, GroupEvent::Leave(e) if e.mid.as_str() == mid)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn group_leader_departure() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38497"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38497"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let group_a = Group::join(session1, GROUP, Member::new("a").unwrap())
+        .await
+        .unwrap();
+    let events = group_a.subscribe().await;
+    assert!(group_a.is_leader().await);
+
+    // The member with the greatest id is the leader...
+    let group_b = Group::join(session2, GROUP, Member::new("b").unwrap())
+        .await
+        .unwrap();
+    wait_event(
+        &events,
+        |e| matches!(e, GroupEvent::Join(e) if e.member.id().as_str() == "b"),
+    )
+    .await;
+    wait_event(&events, |e| is_new_leader(e, "b")).await;
+    assert!(group_a.wait_for_view_size(2, TIMEOUT).await);
+    assert!(group_b.wait_for_view_size(2, TIMEOUT).await);
+    assert!(!group_a.is_leader().await);
+    assert!(group_b.is_leader().await);
+
+    // ...until its departure is detected by the withdrawal of its liveliness token,
+    // long before the expiration of its lease.
+    drop(group_b);
+    wait_event(&events, |e| is_leave(e, "b")).await;
+    wait_event(&events, |e| is_new_leader(e, "a")).await;
+    assert_eq!(group_a.size().await, 1);
+    assert!(group_a.is_leader().await);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn group_leave() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38498"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38498"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let group_a = Group::join(session1, GROUP, Member::new("a").unwrap())
+        .await
+        .unwrap();
+    let events = group_a.subscribe().await;
+    let group_b = Group::join(session2, GROUP, Member::new("b").unwrap())
+        .await
+        .unwrap();
+    assert!(group_a.wait_for_view_size(2, TIMEOUT).await);
+
+    // A member leaving explicitly is notified once
+    group_b.leave().await.unwrap();
+    wait_event(&events, |e| is_leave(e, "b")).await;
+    wait_event(&events, |e| is_new_leader(e, "a")).await;
+    tokio::time::sleep(SLEEP).await;
+    assert!(events.try_iter().all(|e| !is_leave(&e, "b")));
+    assert_eq!(group_a.size().await, 1);
+}
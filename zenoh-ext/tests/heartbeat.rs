@@ -0,0 +1,139 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+mod common;
+
+use common::{open_session, SLEEP, TIMEOUT};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::ztimeout;
+use zenoh_ext::heartbeat::{
+    FleetEvent, FleetMonitor, Heartbeat, HeartbeatConfig, NodeStatus, HEALTH_PREFIX,
+};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn heartbeat_status() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38511"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38511"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let monitor = FleetMonitor::start(session1).await.unwrap();
+    let events = monitor.subscribe().await;
+    tokio::time::sleep(SLEEP).await;
+
+    // A node joins the fleet with its first heartbeat...
+    let with = HeartbeatConfig::new("node1")
+        .unwrap()
+        .period(Duration::from_millis(200))
+        .version("1.0")
+        .field("battery", 80);
+    let heartbeat = Heartbeat::start(session2.clone(), with).await.unwrap();
+    let FleetEvent::Joined(status) = ztimeout!(events.recv_async()).unwrap() else {
+        panic!("Expected the node to join");
+    };
+    assert_eq!(status.name, "node1");
+    assert_eq!(status.zid, session2.zid().to_string());
+    assert_eq!(status.version.as_deref(), Some("1.0"));
+    assert_eq!(status.period(), Duration::from_millis(200));
+    assert_eq!(status.fields["battery"], 80);
+
+    // ...then publishes its updated status...
+    heartbeat.set_field("battery", 70).await;
+    heartbeat.beat().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    let node = monitor.node("node1").await.unwrap();
+    assert!(!node.stale);
+    assert_eq!(node.status.fields["battery"], 70);
+    assert!(node.status.uptime() > Duration::ZERO);
+    assert!(monitor.stale_nodes().await.is_empty());
+
+    // ...until it leaves.
+    drop(heartbeat);
+    assert!(matches!(
+        ztimeout!(events.recv_async()).unwrap(),
+        FleetEvent::Left(name) if name == "node1"
+    ));
+    tokio::time::sleep(SLEEP).await;
+    assert!(events.try_recv().is_err());
+    assert!(monitor.fleet().await.is_empty());
+}
+
+async fn beat(session: &Session, key_expr: &str, status: &NodeStatus) {
+    session
+        .put(key_expr, serde_json::to_vec(status).unwrap())
+        .res()
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn heartbeat_liveness_transitions() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38512"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38512"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let monitor = FleetMonitor::start_with_tolerance(session1, 2)
+        .await
+        .unwrap();
+    let events = monitor.subscribe().await;
+    tokio::time::sleep(SLEEP).await;
+
+    // A node publishing its heartbeats by itself, to be able to miss them
+    let status = NodeStatus {
+        name: "node2".to_string(),
+        zid: session2.zid().to_string(),
+        version: None,
+        uptime_ms: 0,
+        period_ms: 100,
+        fields: BTreeMap::new(),
+    };
+    let key_expr = format!("{HEALTH_PREFIX}/node2");
+    beat(&session2, &key_expr, &status).await;
+    assert!(matches!(
+        ztimeout!(events.recv_async()).unwrap(),
+        FleetEvent::Joined(s) if s == status
+    ));
+
+    // The node is stale after missing 2 heartbeats...
+    assert!(matches!(
+        ztimeout!(events.recv_async()).unwrap(),
+        FleetEvent::Stale(name) if name == "node2"
+    ));
+    assert_eq!(monitor.stale_nodes().await.len(), 1);
+    assert!(monitor.node("node2").await.unwrap().stale);
+
+    // ...recovers with its next heartbeat...
+    beat(&session2, &key_expr, &status).await;
+    assert!(matches!(
+        ztimeout!(events.recv_async()).unwrap(),
+        FleetEvent::Recovered(s) if s == status
+    ));
+    assert!(!monitor.node("node2").await.unwrap().stale);
+
+    // ...and leaves when deleting its status.
+    session2.delete(&key_expr).res().await.unwrap();
+    let event = ztimeout!(async {
+        loop {
+            // it is stale again by then
+            match events.recv_async().await.unwrap() {
+                FleetEvent::Stale(_) => continue,
+                event => break event,
+            }
+        }
+    });
+    assert!(matches!(event, FleetEvent::Left(name) if name == "node2"));
+    assert!(monitor.node("node2").await.is_none());
+}
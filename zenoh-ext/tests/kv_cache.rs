@@ -0,0 +1,143 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_ext::kv_cache::{KvCache, KvCacheConfig};
+
+const SLEEP: Duration = Duration::from_millis(500);
+
+const KEY_EXPR: &str = "test/kv_cache/**";
+
+async fn open_session() -> Arc<Session> {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().await.unwrap().into_arc()
+}
+
+async fn put(session: &Session, key: &str, value: &str) {
+    session
+        .put(format!("test/kv_cache/{key}"), value)
+        .res()
+        .await
+        .unwrap();
+}
+
+fn get(cache: &KvCache, key: &str) -> Option<String> {
+    let key_expr = OwnedKeyExpr::try_from(format!("test/kv_cache/{key}")).unwrap();
+    cache
+        .get(&key_expr)
+        .map(|sample| String::try_from(&sample.value).unwrap())
+}
+
+fn keys(cache: &KvCache) -> Vec<String> {
+    let mut keys: Vec<String> = cache.keys().iter().map(|k| k.to_string()).collect();
+    keys.sort();
+    keys
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn kv_cache_last_value() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let cache = KvCache::start(session.clone(), KvCacheConfig::new(KEY_EXPR).unwrap())
+        .await
+        .unwrap();
+
+    // The last value of each key is kept...
+    put(&session, "a", "1").await;
+    put(&session, "a", "2").await;
+    put(&session, "b", "1").await;
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(get(&cache, "a").as_deref(), Some("2"));
+    assert_eq!(get(&cache, "b").as_deref(), Some("1"));
+    assert_eq!(get(&cache, "c"), None);
+
+    // ...until it is deleted.
+    session.delete("test/kv_cache/b").res().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(get(&cache, "b"), None);
+    assert_eq!(keys(&cache), ["test/kv_cache/a"]);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn kv_cache_lru_eviction() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let with = KvCacheConfig::new(KEY_EXPR).unwrap().capacity(2);
+    let cache = KvCache::start(session.clone(), with).await.unwrap();
+
+    put(&session, "a", "1").await;
+    put(&session, "b", "1").await;
+    tokio::time::sleep(SLEEP).await;
+
+    // Reading "a" makes "b" the least recently used key, evicted for "c"
+    assert!(get(&cache, "a").is_some());
+    put(&session, "c", "1").await;
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(keys(&cache), ["test/kv_cache/a", "test/kv_cache/c"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn kv_cache_ttl_eviction() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let with = KvCacheConfig::new(KEY_EXPR).unwrap().ttl(2 * SLEEP);
+    let cache = KvCache::start(session.clone(), with).await.unwrap();
+
+    put(&session, "a", "1").await;
+    tokio::time::sleep(SLEEP).await;
+    put(&session, "b", "1").await;
+    tokio::time::sleep(SLEEP).await;
+
+    // "a" expired, but not "b" received later
+    tokio::time::sleep(SLEEP / 2).await;
+    assert_eq!(get(&cache, "a"), None);
+    assert_eq!(keys(&cache), ["test/kv_cache/b"]);
+    tokio::time::sleep(SLEEP).await;
+    assert!(cache.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn kv_cache_queryable() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let with = KvCacheConfig::new(KEY_EXPR).unwrap().queryable(true);
+    let _cache = KvCache::start(session.clone(), with).await.unwrap();
+
+    put(&session, "a", "1").await;
+    put(&session, "b", "2").await;
+    tokio::time::sleep(SLEEP).await;
+
+    let replies = session.get(KEY_EXPR).res().await.unwrap();
+    let mut values = vec![];
+    while let Ok(reply) = replies.recv_async().await {
+        let sample = reply.sample.unwrap();
+        values.push((
+            sample.key_expr.to_string(),
+            String::try_from(&sample.value).unwrap(),
+        ));
+    }
+    values.sort();
+    assert_eq!(
+        values,
+        [
+            ("test/kv_cache/a".to_string(), "1".to_string()),
+            ("test/kv_cache/b".to_string(), "2".to_string())
+        ]
+    );
+}
@@ -0,0 +1,145 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+mod common;
+
+use common::{open_session, open_session_with, SLEEP};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::config::ModeDependentValue;
+use zenoh::prelude::r#async::*;
+use zenoh::time::NTP64;
+use zenoh_ext::lock::{DistributedLock, LOCK_PREFIX};
+
+const KEY_EXPR: &str = "test/lock";
+const LEASE: Duration = Duration::from_secs(10);
+
+async fn open_timestamped_session(listen: &[&str], connect: &[&str]) -> Arc<Session> {
+    open_session_with(listen, connect, |config| {
+        config
+            .timestamping
+            .set_enabled(Some(ModeDependentValue::Unique(true)))
+            .unwrap();
+    })
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn lock_exclusive() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_timestamped_session(&["tcp/127.0.0.1:38494"], &[]).await;
+    let session2 = open_timestamped_session(&[], &["tcp/127.0.0.1:38494"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let lock1 = DistributedLock::try_lock(session1, KEY_EXPR, LEASE)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(lock1.is_held());
+    tokio::time::sleep(SLEEP).await;
+
+    // The lock is held by the first contender...
+    assert!(DistributedLock::try_lock(session2.clone(), KEY_EXPR, LEASE)
+        .await
+        .unwrap()
+        .is_none());
+    let holder = DistributedLock::holder(&session2, KEY_EXPR)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(holder.fencing_token, lock1.fencing_token());
+
+    // ...until it releases it, with a greater fencing token for the next holder.
+    let fencing_token1 = lock1.fencing_token();
+    lock1.unlock().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert!(DistributedLock::holder(&session2, KEY_EXPR)
+        .await
+        .unwrap()
+        .is_none());
+    let lock2 = DistributedLock::try_lock(session2, KEY_EXPR, LEASE)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(lock2.fencing_token() > fencing_token1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn lock_lease() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_timestamped_session(&["tcp/127.0.0.1:38495"], &[]).await;
+    let session2 = open_timestamped_session(&[], &["tcp/127.0.0.1:38495"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    // A renewed lease is kept...
+    let lock1 = DistributedLock::try_lock(session1, KEY_EXPR, 2 * SLEEP)
+        .await
+        .unwrap()
+        .unwrap();
+    for _ in 0..4 {
+        tokio::time::sleep(SLEEP / 2).await;
+        lock1.renew().await.unwrap();
+    }
+    assert!(lock1.is_held());
+    tokio::time::sleep(SLEEP).await;
+    assert!(DistributedLock::try_lock(session2.clone(), KEY_EXPR, LEASE)
+        .await
+        .unwrap()
+        .is_none());
+
+    // ...and an expired one is lost, for the other contenders to acquire the lock.
+    tokio::time::sleep(4 * SLEEP).await;
+    assert!(!lock1.is_held());
+    assert!(lock1.renew().await.is_err());
+    let lock2 = DistributedLock::try_lock(session2, KEY_EXPR, LEASE)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(lock2.fencing_token() > lock1.fencing_token());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn lock_fencing_token_ahead() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_timestamped_session(&["tcp/127.0.0.1:38496"], &[]).await;
+    let session2 = open_timestamped_session(&[], &["tcp/127.0.0.1:38496"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    // An expired holder with a clock an hour ahead...
+    let ahead = session1.hlc().unwrap().new_timestamp().get_time().as_u64()
+        + NTP64::from(Duration::from_secs(3600)).as_u64();
+    let _token = session1
+        .liveliness()
+        .declare_token(format!("{LOCK_PREFIX}/{KEY_EXPR}/{ahead}/0/skewed"))
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // ...doesn't hold the lock, but its fencing token is still exceeded by the next holder.
+    let lock = DistributedLock::try_lock(session2, KEY_EXPR, LEASE)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(lock.fencing_token() > ahead);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn lock_without_timestamping() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session(&[], &[]).await;
+
+    assert!(DistributedLock::try_lock(session, KEY_EXPR, LEASE)
+        .await
+        .is_err());
+}
@@ -0,0 +1,149 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::config::ModeDependentValue;
+use zenoh::prelude::r#async::*;
+use zenoh_ext::SessionExt;
+
+const SLEEP: Duration = Duration::from_millis(500);
+
+const KEY_EXPR: &str = "test/pub_cache/**";
+
+async fn open_session() -> Arc<Session> {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .timestamping
+        .set_enabled(Some(ModeDependentValue::Unique(true)))
+        .unwrap();
+    zenoh::open(config).res().await.unwrap().into_arc()
+}
+
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "zenoh-ext-pub-cache-{}-{}.log",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+async fn get_cached(session: &Session) -> Vec<(String, String)> {
+    let replies = session.get(KEY_EXPR).res().await.unwrap();
+    let mut values = vec![];
+    while let Ok(reply) = replies.recv_async().await {
+        let sample = reply.sample.unwrap();
+        values.push((
+            sample.key_expr.to_string(),
+            String::try_from(&sample.value).unwrap(),
+        ));
+    }
+    values.sort();
+    values
+}
+
+async fn restore(session: &Arc<Session>, path: &Path, history: usize) -> Vec<(String, String)> {
+    let cache = session
+        .declare_publication_cache(KEY_EXPR)
+        .history(history)
+        .persistence(path)
+        .res()
+        .await
+        .unwrap();
+    let values = get_cached(session).await;
+    cache.close().res().await.unwrap();
+    values
+}
+
+fn values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+    values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn pub_cache_persistence() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let path = log_path("persistence");
+
+    let cache = session
+        .declare_publication_cache(KEY_EXPR)
+        .history(2)
+        .persistence(&path)
+        .res()
+        .await
+        .unwrap();
+    for (key_expr, value) in [("a", "1"), ("a", "2"), ("a", "3"), ("b", "1")] {
+        session
+            .put(format!("test/pub_cache/{key_expr}"), value)
+            .res()
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(SLEEP).await;
+    let cached = values(&[
+        ("test/pub_cache/a", "2"),
+        ("test/pub_cache/a", "3"),
+        ("test/pub_cache/b", "1"),
+    ]);
+    assert_eq!(get_cached(&session).await, cached);
+    cache.close().res().await.unwrap();
+
+    // The publications are restored by the next cache, within its history
+    assert_eq!(restore(&session, &path, 2).await, cached);
+    assert_eq!(
+        restore(&session, &path, 1).await,
+        values(&[("test/pub_cache/a", "3"), ("test/pub_cache/b", "1")])
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn pub_cache_persistence_truncated() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let path = log_path("truncated");
+
+    let cache = session
+        .declare_publication_cache(KEY_EXPR)
+        .persistence(&path)
+        .res()
+        .await
+        .unwrap();
+    session.put("test/pub_cache/a", "1").res().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    cache.close().res().await.unwrap();
+
+    // A record interrupted while being written is ignored
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    file.write_all(&64u32.to_le_bytes()).unwrap();
+    file.write_all(&[0; 8]).unwrap();
+    drop(file);
+    assert_eq!(
+        restore(&session, &path, 1).await,
+        values(&[("test/pub_cache/a", "1")])
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
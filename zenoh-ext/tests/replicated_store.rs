@@ -0,0 +1,146 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::r#async::*;
+use zenoh::time::NTP64;
+use zenoh_core::ztimeout;
+use zenoh_ext::replicated_store::{ReplicatedStore, ReplicatedStoreConfig, REPLICATION_PREFIX};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const SLEEP: Duration = Duration::from_millis(500);
+
+const KEY_EXPR: &str = "test/replicated";
+
+async fn open_session(listen: &[&str], connect: &[&str]) -> Arc<Session> {
+    let mut config = config::peer();
+    config.listen.endpoints = listen
+        .iter()
+        .map(|e| e.parse().unwrap())
+        .collect::<Vec<_>>();
+    config.connect.endpoints = connect
+        .iter()
+        .map(|e| e.parse().unwrap())
+        .collect::<Vec<_>>();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
+
+async fn start(session: Arc<Session>) -> ReplicatedStore {
+    let with = ReplicatedStoreConfig::new(KEY_EXPR)
+        .unwrap()
+        .digest_period(SLEEP);
+    ReplicatedStore::start(session, with).await.unwrap()
+}
+
+fn get(store: &ReplicatedStore, key: &str) -> Option<String> {
+    store
+        .get(&OwnedKeyExpr::try_from(key).unwrap())
+        .map(|value| String::try_from(&value).unwrap())
+}
+
+/// Waits for the replica to have the given value for the given key.
+async fn wait_value(store: &ReplicatedStore, key: &str, value: Option<&str>) {
+    ztimeout!(async {
+        while get(store, key).as_deref() != value {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn replicated_updates() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38505"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38505"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    // The entries of the existing replicas are retrieved on start...
+    let store1 = start(session1).await;
+    store1.put("a", "1").await.unwrap();
+    store1.put("b", "1").await.unwrap();
+    let store2 = start(session2).await;
+    assert_eq!(store2.snapshot(), store1.snapshot());
+
+    // ...and the updates are shared by the replicas.
+    store1.put("a", "2").await.unwrap();
+    wait_value(&store2, "a", Some("2")).await;
+    store2.delete("b").await.unwrap();
+    wait_value(&store1, "b", None).await;
+    assert_eq!(store1.snapshot().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn replicated_concurrent_updates() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38506"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38506"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let store1 = start(session1).await;
+    let store2 = start(session2).await;
+
+    // The concurrent updates of a key are resolved the same way by all the replicas
+    let (res1, res2) = tokio::join!(store1.put("a", "1"), store2.put("a", "2"));
+    res1.unwrap();
+    res2.unwrap();
+    ztimeout!(async {
+        while store1.snapshot() != store2.snapshot() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+    assert!(get(&store1, "a").is_some());
+}
+
+// The entries exchanged by the replicas
+#[derive(Serialize)]
+struct WireEntry {
+    key_expr: String,
+    time: u64,
+    id: Vec<u8>,
+    value: Option<(Vec<u8>, String)>,
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn replicated_anti_entropy() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38507"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38507"]).await;
+    tokio::time::sleep(SLEEP).await;
+    let store1 = start(session1.clone()).await;
+    let store2 = start(session2).await;
+
+    // An update missed by the second replica...
+    let entry = WireEntry {
+        key_expr: "a".to_string(),
+        time: NTP64::from(SystemTime::now().duration_since(UNIX_EPOCH).unwrap()).as_u64(),
+        id: vec![1],
+        value: Some((b"missed".to_vec(), Encoding::default().to_string())),
+    };
+    session1
+        .put(
+            format!("{REPLICATION_PREFIX}/{KEY_EXPR}/update"),
+            bincode::serialize(&vec![entry]).unwrap(),
+        )
+        .allowed_destination(Locality::SessionLocal)
+        .res()
+        .await
+        .unwrap();
+    wait_value(&store1, "a", Some("missed")).await;
+
+    // ...is retrieved on the reception of the digest of the first one.
+    wait_value(&store2, "a", Some("missed")).await;
+}
@@ -0,0 +1,178 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::query::{ErrorCode, ReplyError};
+use zenoh_core::ztimeout;
+use zenoh_ext::rpc::{Client, ClientConfig, Request, Service};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const SLEEP: Duration = Duration::from_millis(500);
+
+const KEY_EXPR: &str = "test/rpc/div";
+
+async fn open_session(listen: &[&str], connect: &[&str]) -> Arc<Session> {
+    let mut config = config::peer();
+    config.listen.endpoints = listen
+        .iter()
+        .map(|e| e.parse().unwrap())
+        .collect::<Vec<_>>();
+    config.connect.endpoints = connect
+        .iter()
+        .map(|e| e.parse().unwrap())
+        .collect::<Vec<_>>();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
+
+async fn serve_div(session: Arc<Session>) -> Service {
+    Service::serve(session, KEY_EXPR, |req: Request<(i64, i64)>| async move {
+        let (a, b) = req.payload;
+        if b == 0 {
+            return Err(ReplyError {
+                code: ErrorCode::InvalidRequest,
+                value: "division by zero".into(),
+            });
+        }
+        Ok(a / b)
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn rpc_call() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38499"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38499"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let _service = serve_div(session1).await;
+    let client = Client::start(session2, ClientConfig::new(KEY_EXPR).unwrap())
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let quotient: i64 = ztimeout!(client.call(&(7, 2))).unwrap();
+    assert_eq!(quotient, 3);
+
+    // The errors of the handler are returned to the client...
+    let e = ztimeout!(client.call::<_, i64>(&(1, 0))).unwrap_err();
+    assert_eq!(e.code, ErrorCode::InvalidRequest);
+    assert_eq!(String::try_from(&e.value).unwrap(), "division by zero");
+
+    // ...as well as the requests which can't be deserialized.
+    let e = ztimeout!(client.call::<_, i64>(&"7/2")).unwrap_err();
+    assert_eq!(e.code, ErrorCode::InvalidRequest);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn rpc_internal_error() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38513"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38513"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    // A response which can't be serialized is an error of the service
+    let _service = Service::serve(session1, KEY_EXPR, |_: Request<()>| async move {
+        Ok::<_, ReplyError>(BTreeMap::from([((7, 2), 3)]))
+    })
+    .await
+    .unwrap();
+    let client = Client::start(session2, ClientConfig::new(KEY_EXPR).unwrap())
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let e = ztimeout!(client.call::<_, i64>(&())).unwrap_err();
+    assert_eq!(e.code, ErrorCode::Internal);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn rpc_not_found_and_timeout() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38500"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38500"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let with = ClientConfig::new(KEY_EXPR).unwrap().timeout(SLEEP);
+    let client = Client::start(session2, with).await.unwrap();
+    let e = ztimeout!(client.call::<_, i64>(&(7, 2))).unwrap_err();
+    assert_eq!(e.code, ErrorCode::NotFound);
+
+    let _service = Service::serve(session1, KEY_EXPR, |_: Request<(i64, i64)>| async move {
+        tokio::time::sleep(4 * SLEEP).await;
+        Ok::<i64, ReplyError>(0)
+    })
+    .await
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    let e = ztimeout!(client.call::<_, i64>(&(7, 2))).unwrap_err();
+    assert_eq!(e.code, ErrorCode::Timeout);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn rpc_load_balancing() {
+    zenoh_util::try_init_log_from_env();
+    let session1 = open_session(&["tcp/127.0.0.1:38501"], &[]).await;
+    let session2 = open_session(&[], &["tcp/127.0.0.1:38501"]).await;
+    tokio::time::sleep(SLEEP).await;
+
+    let mut services = vec![];
+    for i in 0..2usize {
+        let service = Service::serve(
+            session1.clone(),
+            KEY_EXPR,
+            move |_: Request<()>| async move { Ok::<_, ReplyError>(i) },
+        )
+        .await
+        .unwrap();
+        services.push(service);
+    }
+    let mut ids: Vec<String> = services.iter().map(|s| s.id().to_string()).collect();
+    ids.sort();
+
+    let with = ClientConfig::new(KEY_EXPR).unwrap().load_balancing(true);
+    let client = Client::start(session2, with).await.unwrap();
+    ztimeout!(async {
+        while client.servers() != ids {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+
+    // The calls are sent to each server in turn...
+    let mut answered = vec![];
+    for _ in 0..4 {
+        answered.push(ztimeout!(client.call::<_, usize>(&())).unwrap());
+    }
+    assert_ne!(answered[0], answered[1]);
+    assert_eq!(answered[..2], answered[2..]);
+
+    // ...until one of them leaves.
+    let left = services.pop().unwrap();
+    let left_id = left.id().to_string();
+    drop(left);
+    ztimeout!(async {
+        while client.servers().contains(&left_id) {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+    for _ in 0..2 {
+        assert_eq!(ztimeout!(client.call::<_, usize>(&())).unwrap(), 0);
+    }
+}
@@ -0,0 +1,127 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use futures::prelude::*;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::r#async::*;
+use zenoh::prelude::sync::SyncResolve;
+use zenoh::query::QueryTarget;
+use zenoh::queryable::Queryable;
+use zenoh::time::{Timestamp, TimestampId};
+use zenoh_ext::time_series::TimeRangeQuery;
+
+const KEY_EXPR: &str = "test/time_series";
+
+fn time(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_000_000 + secs)
+}
+
+async fn open_session() -> Arc<Session> {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().await.unwrap().into_arc()
+}
+
+/// Declares a storage replying the samples published at the given seconds whatever the time range
+/// of the queries, and a sample without timestamp, returning the selectors of the queries.
+async fn store(
+    session: &Arc<Session>,
+    secs: &'static [u64],
+) -> (Queryable<'static, ()>, Arc<Mutex<Vec<String>>>) {
+    let selectors = Arc::new(Mutex::new(vec![]));
+    let selectors2 = selectors.clone();
+    let queryable = session
+        .declare_queryable(KEY_EXPR)
+        .callback(move |query| {
+            selectors2
+                .lock()
+                .unwrap()
+                .push(query.selector().to_string());
+            for s in secs {
+                let timestamp = Timestamp::new(
+                    time(*s).duration_since(UNIX_EPOCH).unwrap().into(),
+                    TimestampId::try_from([1]).unwrap(),
+                );
+                let sample = Sample::new(KeyExpr::try_from(KEY_EXPR).unwrap(), s.to_string())
+                    .with_timestamp(timestamp);
+                query.reply(Ok(sample)).res_sync().unwrap();
+            }
+            let sample = Sample::new(KeyExpr::try_from(KEY_EXPR).unwrap(), "untimestamped");
+            query.reply(Ok(sample)).res_sync().unwrap();
+        })
+        .res()
+        .await
+        .unwrap();
+    (queryable, selectors)
+}
+
+fn values(samples: Vec<Sample>) -> Vec<String> {
+    samples
+        .iter()
+        .map(|sample| String::try_from(&sample.value).unwrap())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn time_range_fetch() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let (_storage1, selectors) = store(&session, &[4, 2, 0, 3, 1]).await;
+    let (_storage2, _) = store(&session, &[1, 2]).await;
+
+    // The samples within the range are ordered by timestamp, without duplicates
+    let query = TimeRangeQuery::new(KEY_EXPR)
+        .unwrap()
+        .start(time(1))
+        .end(time(4))
+        .target(QueryTarget::All);
+    let samples = query.fetch(&session).await.unwrap();
+    assert_eq!(values(samples), ["1", "2", "3"]);
+    assert_eq!(*selectors.lock().unwrap(), [query.selector().to_string()]);
+    assert!(query.selector().parameters().contains("_time="));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn time_range_pages() {
+    zenoh_util::try_init_log_from_env();
+    let session = open_session().await;
+    let (_storage, selectors) = store(&session, &[4, 2, 0, 3, 1]).await;
+
+    // Each page is the result of a query on a window of the range
+    let query = TimeRangeQuery::new(KEY_EXPR)
+        .unwrap()
+        .start(time(0))
+        .end(time(5))
+        .page(Duration::from_secs(2));
+    let pages: Vec<Vec<String>> = query
+        .pages(&session)
+        .map(|page| values(page.unwrap()))
+        .collect()
+        .await;
+    assert_eq!(pages, [vec!["0", "1"], vec!["2", "3"], vec!["4"]]);
+    assert_eq!(selectors.lock().unwrap().len(), 3);
+
+    let samples = query.fetch(&session).await.unwrap();
+    assert_eq!(values(samples), ["0", "1", "2", "3", "4"]);
+
+    // The time range must have a start to be paginated
+    let query = TimeRangeQuery::new(KEY_EXPR)
+        .unwrap()
+        .end(time(5))
+        .page(Duration::from_secs(2));
+    let pages: Vec<_> = query.pages(&session).collect().await;
+    assert_eq!(pages.len(), 1);
+    assert!(pages[0].is_err());
+}
@@ -13,10 +13,11 @@
 //
 use crate::{
     keyexpr,
-    prelude::sync::{KeyExpr, Locality, SampleKind},
+    prelude::sync::{KeyExpr, Locality, OwnedKeyExpr, SampleKind, Value},
     queryable::Query,
     sample::DataInfo,
-    Sample, Session, ZResult,
+    time::{new_reception_timestamp, Timestamp},
+    Sample, Session, ZResult, LONG_VERSION,
 };
 use std::{
     collections::hash_map::DefaultHasher,
@@ -40,7 +41,11 @@ macro_rules! ke_for_sure {
 
 lazy_static::lazy_static!(
     static ref KE_STARSTAR: &'static keyexpr = ke_for_sure!("**");
+    static ref KE_AT: &'static keyexpr = ke_for_sure!("@");
     static ref KE_PREFIX: &'static keyexpr = ke_for_sure!("@/session");
+    static ref KE_TIME_NOW: &'static keyexpr = ke_for_sure!("time/now");
+    static ref KE_VERSION: &'static keyexpr = ke_for_sure!("version");
+    static ref KE_UPTIME: &'static keyexpr = ke_for_sure!("uptime");
     static ref KE_TRANSPORT_UNICAST: &'static keyexpr = ke_for_sure!("transport/unicast");
     static ref KE_LINK: &'static keyexpr = ke_for_sure!("link");
 );
@@ -60,6 +65,50 @@ pub(crate) fn init(session: &Session) {
                 move |q| super::admin::on_admin_query(&session, q)
             }),
         );
+
+        let info_key = KeyExpr::from(*KE_AT / own_zid / *KE_STARSTAR)
+            .to_wire(session)
+            .to_owned();
+
+        let _info_qabl = session.declare_queryable_inner(
+            &info_key,
+            false,
+            Locality::Any,
+            Arc::new({
+                let session = session.clone();
+                move |q| super::admin::on_info_query(&session, q)
+            }),
+        );
+    }
+}
+
+pub(crate) fn on_info_query(session: &Session, query: Query) {
+    fn reply(query: &Query, key_expr: OwnedKeyExpr, value: Value, timestamp: Option<Timestamp>) {
+        let mut sample = Sample::new(key_expr, value);
+        sample.timestamp = timestamp;
+        let _ = query.reply(Ok(sample)).res_sync();
+    }
+
+    if let Ok(own_zid) = keyexpr::new(&session.zid().to_string()) {
+        let key_expr = *KE_AT / own_zid / *KE_TIME_NOW;
+        if query.key_expr().intersects(&key_expr) {
+            let now = session
+                .runtime
+                .new_timestamp()
+                .unwrap_or_else(new_reception_timestamp);
+            reply(&query, key_expr, now.to_string().into(), Some(now));
+        }
+
+        let key_expr = *KE_AT / own_zid / *KE_VERSION;
+        if query.key_expr().intersects(&key_expr) {
+            reply(&query, key_expr, LONG_VERSION.as_str().into(), None);
+        }
+
+        let key_expr = *KE_AT / own_zid / *KE_UPTIME;
+        if query.key_expr().intersects(&key_expr) {
+            let uptime = session.runtime.uptime().as_secs_f64();
+            reply(&query, key_expr, uptime.into(), None);
+        }
     }
 }
 
@@ -0,0 +1,452 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Typed access to the admin space of the zenoh nodes.
+//!
+//! The admin space of a node is exposed under `@/<whatami>/<zid>/**` when the node enables it
+//! (see the `adminspace` section of the configuration). The [`Admin`] helpers query it and
+//! deserialize the replies, so that applications don't depend on its exact key layout.
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use zenoh::prelude::r#async::*;
+//!
+//! let session = zenoh::open(config::peer()).res().await.unwrap();
+//! for router in session.admin().routers().res().await.unwrap() {
+//!     println!("{} {} {:?}", router.zid, router.version, router.locators);
+//! }
+//! # }
+//! ```
+//!
+//! The nodes listing operators in `adminspace.permissions.operators` only accept the writes to
+//! their admin space signed by one of them, see [`AdminOperator`].
+use crate::net::runtime::adminspace::{
+    operator_message, ATTACHMENT_OPERATOR, ATTACHMENT_SIGNATURE, ATTACHMENT_TIMESTAMP,
+};
+use crate::prelude::{KeyExpr, OwnedKeyExpr, Sample, SplitBuffer};
+use crate::query::ConsolidationMode;
+use crate::sample::{Attachment, AttachmentBuilder};
+use crate::{Session, SessionRef};
+use base64::{engine::general_purpose::STANDARD as b64_std_engine, Engine};
+use ring::{
+    rand::SystemRandom,
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zenoh_core::{AsyncResolve, Resolve, ResolveFuture};
+use zenoh_protocol::core::{Locator, WhatAmI, ZenohId};
+use zenoh_result::{zerror, ZResult};
+
+pub use zenoh_plugin_trait::{PluginState, PluginStatusRec};
+
+/// A zenoh node, as described by its `@/<whatami>/<zid>` admin key.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct NodeInfo {
+    pub zid: ZenohId,
+    pub whatami: WhatAmI,
+    pub version: String,
+    pub metadata: serde_json::Value,
+    /// The locators the node listens on.
+    pub locators: Vec<Locator>,
+    pub sessions: Vec<NodeSession>,
+}
+
+/// A session established by a zenoh node with one of its neighbours.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct NodeSession {
+    /// The node reporting this session.
+    pub node: ZenohId,
+    pub peer: ZenohId,
+    pub whatami: WhatAmI,
+    /// The destination locators of the links of this session.
+    pub links: Vec<Locator>,
+}
+
+/// A link of a session established by a zenoh node.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct NodeLink {
+    /// The node reporting this link.
+    pub node: ZenohId,
+    pub peer: ZenohId,
+    pub dst: Locator,
+}
+
+/// A subscription or a queryable known by a zenoh node, with the nodes it was declared by.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct RouteInfo {
+    /// The node reporting this subscription or queryable.
+    pub node: ZenohId,
+    pub key_expr: OwnedKeyExpr,
+    pub routers: Vec<ZenohId>,
+    pub peers: Vec<ZenohId>,
+    pub clients: Vec<ZenohId>,
+}
+
+/// The status of a plugin loaded by a zenoh node.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct PluginInfo {
+    /// The node running this plugin.
+    pub node: ZenohId,
+    pub status: PluginStatusRec<'static>,
+}
+
+#[derive(Deserialize)]
+struct RawNode {
+    version: String,
+    #[serde(default)]
+    metadata: serde_json::Value,
+    locators: Vec<String>,
+    sessions: Vec<RawSession>,
+}
+
+#[derive(Deserialize)]
+struct RawSession {
+    peer: String,
+    whatami: String,
+    links: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawSources {
+    routers: Vec<ZenohId>,
+    peers: Vec<ZenohId>,
+    clients: Vec<ZenohId>,
+}
+
+impl RawSession {
+    /// Returns `None` for the sessions whose peer is not known yet.
+    fn into_session(self, node: ZenohId) -> Option<NodeSession> {
+        Some(NodeSession {
+            node,
+            peer: self.peer.parse().ok()?,
+            whatami: self.whatami.parse().ok()?,
+            links: self.links.iter().filter_map(|l| l.parse().ok()).collect(),
+        })
+    }
+}
+
+/// A reply from the admin space of the node `zid`, `suffix` being the part of its key
+/// following `@/<whatami>/<zid>`.
+struct AdminReply {
+    zid: ZenohId,
+    whatami: WhatAmI,
+    suffix: Option<String>,
+    sample: Sample,
+}
+
+impl AdminReply {
+    fn parse<T: DeserializeOwned>(&self) -> ZResult<T> {
+        serde_json::from_slice(&self.sample.value.payload.contiguous()).map_err(|e| {
+            zerror!(
+                "Invalid admin space reply on {}: {}",
+                self.sample.key_expr,
+                e
+            )
+            .into()
+        })
+    }
+
+    fn into_node(self) -> ZResult<NodeInfo> {
+        let raw: RawNode = self.parse()?;
+        Ok(NodeInfo {
+            zid: self.zid,
+            whatami: self.whatami,
+            version: raw.version,
+            metadata: raw.metadata,
+            locators: raw.locators.iter().filter_map(|l| l.parse().ok()).collect(),
+            sessions: raw
+                .sessions
+                .into_iter()
+                .filter_map(|s| s.into_session(self.zid))
+                .collect(),
+        })
+    }
+
+    fn into_route(self, kind: &str) -> ZResult<RouteInfo> {
+        let raw: RawSources = self.parse()?;
+        let key_expr = self
+            .suffix
+            .as_deref()
+            .and_then(|s| s.strip_prefix(kind))
+            .and_then(|s| s.strip_prefix('/'))
+            .ok_or_else(|| zerror!("Invalid admin space key {}", self.sample.key_expr))?;
+        Ok(RouteInfo {
+            node: self.zid,
+            key_expr: OwnedKeyExpr::try_from(key_expr)?,
+            routers: raw.routers,
+            peers: raw.peers,
+            clients: raw.clients,
+        })
+    }
+}
+
+async fn admin_get(session: &Session, key_expr: &'static str) -> ZResult<Vec<AdminReply>> {
+    let replies = session
+        .get(KeyExpr::try_from(key_expr)?)
+        .consolidation(ConsolidationMode::None)
+        .res_async()
+        .await?;
+    let mut result = vec![];
+    while let Ok(reply) = replies.recv_async().await {
+        let sample = match reply.sample {
+            Ok(sample) => sample,
+            Err(e) => {
+                tracing::warn!(
+                    "Error reply from {} on {}: {}",
+                    reply.replier_id,
+                    key_expr,
+                    e
+                );
+                continue;
+            }
+        };
+        // Skip the keys outside of the nodes admin spaces, e.g. `@/session/**`
+        let mut chunks = sample.key_expr.as_str().splitn(4, '/').skip(1);
+        let (Some(Ok(whatami)), Some(Ok(zid))) = (
+            chunks.next().map(str::parse::<WhatAmI>),
+            chunks.next().map(str::parse::<ZenohId>),
+        ) else {
+            continue;
+        };
+        let suffix = chunks.next().map(ToString::to_string);
+        result.push(AdminReply {
+            zid,
+            whatami,
+            suffix,
+            sample,
+        });
+    }
+    Ok(result)
+}
+
+/// A structure with functions to query the admin space of the zenoh nodes.
+///
+/// Only the nodes that enabled their admin space and granted the read permission reply.
+/// A reply that doesn't match the expected layout resolves to an error.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let admin = session.admin();
+/// for sub in admin.subscriptions().res().await.unwrap() {
+///     println!("{} knows {}", sub.node, sub.key_expr);
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub struct Admin<'a> {
+    pub(crate) session: SessionRef<'a>,
+}
+
+#[zenoh_macros::unstable]
+impl<'a> Admin<'a> {
+    /// The nodes reachable from this session, from `@/*/*`.
+    pub fn nodes(&self) -> impl Resolve<ZResult<Vec<NodeInfo>>> + 'a {
+        let session = self.session.clone();
+        ResolveFuture::new(async move {
+            admin_get(&session, "@/*/*")
+                .await?
+                .into_iter()
+                .map(AdminReply::into_node)
+                .collect()
+        })
+    }
+
+    /// The routers reachable from this session, from `@/router/*`.
+    pub fn routers(&self) -> impl Resolve<ZResult<Vec<NodeInfo>>> + 'a {
+        let session = self.session.clone();
+        ResolveFuture::new(async move {
+            admin_get(&session, "@/router/*")
+                .await?
+                .into_iter()
+                .map(AdminReply::into_node)
+                .collect()
+        })
+    }
+
+    /// The sessions established by the nodes reachable from this session.
+    pub fn sessions(&self) -> impl Resolve<ZResult<Vec<NodeSession>>> + 'a {
+        let nodes = self.nodes();
+        ResolveFuture::new(async move {
+            Ok(nodes
+                .res_async()
+                .await?
+                .into_iter()
+                .flat_map(|n| n.sessions)
+                .collect())
+        })
+    }
+
+    /// The links of the sessions established by the nodes reachable from this session.
+    pub fn links(&self) -> impl Resolve<ZResult<Vec<NodeLink>>> + 'a {
+        let sessions = self.sessions();
+        ResolveFuture::new(async move {
+            Ok(sessions
+                .res_async()
+                .await?
+                .into_iter()
+                .flat_map(|s| {
+                    s.links.into_iter().map(move |dst| NodeLink {
+                        node: s.node,
+                        peer: s.peer,
+                        dst,
+                    })
+                })
+                .collect())
+        })
+    }
+
+    /// The subscriptions known by the nodes reachable from this session,
+    /// from `@/*/*/subscriber/**`.
+    pub fn subscriptions(&self) -> impl Resolve<ZResult<Vec<RouteInfo>>> + 'a {
+        let session = self.session.clone();
+        ResolveFuture::new(async move {
+            admin_get(&session, "@/*/*/subscriber/**")
+                .await?
+                .into_iter()
+                .map(|r| r.into_route("subscriber"))
+                .collect()
+        })
+    }
+
+    /// The queryables known by the nodes reachable from this session,
+    /// from `@/*/*/queryable/**`.
+    pub fn queryables(&self) -> impl Resolve<ZResult<Vec<RouteInfo>>> + 'a {
+        let session = self.session.clone();
+        ResolveFuture::new(async move {
+            admin_get(&session, "@/*/*/queryable/**")
+                .await?
+                .into_iter()
+                .map(|r| r.into_route("queryable"))
+                .collect()
+        })
+    }
+
+    /// The status of the plugins of the nodes reachable from this session,
+    /// from `@/*/*/plugins/**`.
+    pub fn plugins(&self) -> impl Resolve<ZResult<Vec<PluginInfo>>> + 'a {
+        let session = self.session.clone();
+        ResolveFuture::new(async move {
+            admin_get(&session, "@/*/*/plugins/**")
+                .await?
+                .into_iter()
+                .map(|r| {
+                    Ok(PluginInfo {
+                        node: r.zid,
+                        status: r.parse()?,
+                    })
+                })
+                .collect()
+        })
+    }
+}
+
+/// An operator allowed to write to the admin space of the nodes listing its id and public key
+/// in `adminspace.permissions.operators`.
+///
+/// The writes are signed with the Ed25519 key of the operator, the signature being sent in the
+/// attachment of the put or delete. A signature is only valid for a short period of time and
+/// can't be replayed.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::adminspace::AdminOperator;
+/// use zenoh::prelude::r#async::*;
+///
+/// let pkcs8 = std::fs::read("operator.pk8").unwrap();
+/// let operator = AdminOperator::from_pkcs8("alice", &pkcs8).unwrap();
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let key_expr = KeyExpr::try_from("@/router/<zid>/config/plugins/rest/http_port").unwrap();
+/// let value = "8001";
+/// session
+///     .put(&key_expr, value)
+///     .with_attachment(operator.sign_put(&key_expr, value.as_bytes()))
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub struct AdminOperator {
+    id: String,
+    key_pair: Ed25519KeyPair,
+}
+
+#[zenoh_macros::unstable]
+impl AdminOperator {
+    /// Creates an operator from its Ed25519 key pair in PKCS#8 DER format.
+    pub fn from_pkcs8(id: impl Into<String>, pkcs8: &[u8]) -> ZResult<Self> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pkcs8)
+            .map_err(|e| zerror!("Invalid Ed25519 key pair: {}", e))?;
+        Ok(Self {
+            id: id.into(),
+            key_pair,
+        })
+    }
+
+    /// Generates a new Ed25519 key pair in PKCS#8 DER format.
+    pub fn generate_pkcs8() -> ZResult<Vec<u8>> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|e| zerror!("Failed to generate an Ed25519 key pair: {}", e))?;
+        Ok(pkcs8.as_ref().to_vec())
+    }
+
+    /// The id of the operator.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The base64 encoded public key of the operator, as expected in `adminspace.permissions.operators`.
+    pub fn public_key(&self) -> String {
+        b64_std_engine.encode(self.key_pair.public_key().as_ref())
+    }
+
+    /// The attachment signing a put of `payload` on `key_expr`.
+    pub fn sign_put(&self, key_expr: &KeyExpr, payload: &[u8]) -> Attachment {
+        self.sign(key_expr, Some(payload))
+    }
+
+    /// The attachment signing a delete of `key_expr`.
+    pub fn sign_delete(&self, key_expr: &KeyExpr) -> Attachment {
+        self.sign(key_expr, None)
+    }
+
+    fn sign(&self, key_expr: &KeyExpr, payload: Option<&[u8]>) -> Attachment {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let message = operator_message(key_expr.as_str(), payload, timestamp);
+        let mut attachment = AttachmentBuilder::new();
+        attachment.insert(ATTACHMENT_OPERATOR, self.id.as_bytes());
+        attachment.insert(ATTACHMENT_TIMESTAMP, &timestamp.to_le_bytes());
+        attachment.insert(ATTACHMENT_SIGNATURE, self.key_pair.sign(&message).as_ref());
+        attachment.build()
+    }
+}
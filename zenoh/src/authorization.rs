@@ -0,0 +1,100 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Programmatic authorization of the messages routed by a zenoh node.
+//!
+//! An authorization callback registered with [`OpenBuilder::authorization`](crate::OpenBuilder::authorization)
+//! is asked for a [`Permission`] on the publications, deletions, queries and declarations of
+//! subscribers, queryables, liveliness tokens or interests exchanged with the remote nodes, along
+//! with the identity of these nodes. It complements the `access_control` configuration, whose rules
+//! must be known in advance.
+//!
+//! The callback is asked once per remote node, key expression and [`Action`]: its decision
+//! is reused for the following messages until the node disconnects, the least recently used
+//! decisions being forgotten past 1024 of them per node. The messages are held back while the
+//! decision is pending, without blocking the routing of the others, and are denied if the
+//! callback takes more than a second to decide. Deletions are authorized as [`Action::Put`],
+//! liveliness tokens as [`Action::DeclareSubscriber`] and interests as [`Action::Get`].
+//!
+//! # Examples
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() {
+//! use zenoh::authorization::{Action, Permission};
+//! use zenoh::prelude::r#async::*;
+//!
+//! let session = zenoh::open(config::peer())
+//!     .authorization(|request| async move {
+//!         // Each tenant only accesses the keys prefixed with its username
+//!         match request.subject.username.as_deref() {
+//!             Some(tenant) if request.key_expr.starts_with(&format!("{tenant}/")) => {
+//!                 Permission::Allow
+//!             }
+//!             _ => Permission::Deny,
+//!         }
+//!     })
+//!     .res()
+//!     .await
+//!     .unwrap();
+//! # }
+//! ```
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use zenoh_protocol::core::{WhatAmI, ZenohId};
+
+pub use zenoh_config::{Action, InterceptorFlow, Permission};
+
+/// The identity of a remote node, as established by its transport.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct AuthorizationSubject {
+    pub zid: ZenohId,
+    pub whatami: WhatAmI,
+    /// The username the node authenticated with, if any.
+    pub username: Option<String>,
+    /// The claims of the token the node authenticated with, if any.
+    pub token_claims: Option<serde_json::Map<String, serde_json::Value>>,
+    /// The common names of the TLS certificates presented by the node.
+    pub cert_common_names: Vec<String>,
+    /// The subject alternative names of the TLS certificates presented by the node.
+    pub cert_subject_alt_names: Vec<String>,
+    /// The network interfaces the node is connected through.
+    pub interfaces: Vec<String>,
+}
+
+/// A message to authorize.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    /// The remote node the message is received from or sent to.
+    pub subject: Arc<AuthorizationSubject>,
+    /// Whether the message is received from ([`InterceptorFlow::Ingress`]) or sent to
+    /// ([`InterceptorFlow::Egress`]) the subject.
+    pub flow: InterceptorFlow,
+    pub action: Action,
+    pub key_expr: String,
+}
+
+pub(crate) type Authorizer = Arc<
+    dyn Fn(AuthorizationRequest) -> Pin<Box<dyn Future<Output = Permission> + Send>> + Send + Sync,
+>;
+
+pub(crate) fn authorizer<F, Fut>(callback: F) -> Authorizer
+where
+    F: Fn(AuthorizationRequest) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Permission> + Send + 'static,
+{
+    Arc::new(move |request| Box::pin(callback(request)))
+}
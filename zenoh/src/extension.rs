@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! User-defined extensions of the zenoh protocol.
+//!
+//! An [`Extension`] is carried by the publications it is attached to, next to the
+//! official extensions of the protocol, and is forwarded as-is by the routers. Its ID must be in
+//! [`USER_IDS`], a range never assigned to official extensions.
+//!
+//! A mandatory extension must be understood by its receivers: a session drops the samples and
+//! queries carrying a mandatory extension unless it [accepts](crate::Session::accept_extension)
+//! its ID. Optional extensions are always delivered and can be ignored.
+//!
+//! # Examples
+//! ```
+//! # #[tokio::main]
+//! # async fn main() {
+//! use zenoh::extension::{Extension, ExtensionBody};
+//! use zenoh::prelude::r#async::*;
+//!
+//! let session = zenoh::open(config::peer()).res().await.unwrap();
+//! session.accept_extension(0x0c).unwrap();
+//! let subscriber = session
+//!     .declare_subscriber("key/expression")
+//!     .callback(|sample| {
+//!         for extension in &sample.extensions {
+//!             println!("Extension {}: {:?}", extension.id(), extension.body());
+//!         }
+//!     })
+//!     .res()
+//!     .await
+//!     .unwrap();
+//! session
+//!     .put("key/expression", "value")
+//!     .with_extension(Extension::new(0x0c, true, ExtensionBody::Z64(42)).unwrap())
+//!     .res()
+//!     .await
+//!     .unwrap();
+//! # }
+//! ```
+use std::ops::RangeInclusive;
+use zenoh_protocol::common::{iext, ZExtUnknown};
+use zenoh_result::{bail, ZResult};
+
+pub use zenoh_protocol::common::ZExtBody as ExtensionBody;
+
+/// The IDs available to user-defined extensions.
+pub const USER_IDS: RangeInclusive<u8> = iext::USER_ID_MIN..=iext::ID_MASK;
+
+/// A user-defined extension of a zenoh message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extension(pub(crate) ZExtUnknown);
+
+impl Extension {
+    /// Creates an extension, failing if `id` is not in [`USER_IDS`].
+    pub fn new(id: u8, mandatory: bool, body: ExtensionBody) -> ZResult<Self> {
+        if !USER_IDS.contains(&id) {
+            bail!(
+                "Invalid extension ID {:#x}: user-defined extensions must use IDs {:#x} to {:#x}",
+                id,
+                USER_IDS.start(),
+                USER_IDS.end()
+            );
+        }
+        Ok(Extension(ZExtUnknown::new(id, mandatory, body)))
+    }
+
+    /// Gets the ID of this extension.
+    #[inline]
+    pub fn id(&self) -> u8 {
+        iext::mid(self.0.id)
+    }
+
+    /// Returns `true` if the receivers of this extension must understand it.
+    #[inline]
+    pub fn is_mandatory(&self) -> bool {
+        self.0.is_mandatory()
+    }
+
+    /// Gets the body of this extension.
+    #[inline]
+    pub fn body(&self) -> &ExtensionBody {
+        &self.0.body
+    }
+}
+
+/// Extracts the user-defined extensions of the unknown extensions of a received message.
+pub(crate) fn from_unknown(ext_unknown: Vec<ZExtUnknown>) -> Vec<Extension> {
+    ext_unknown
+        .into_iter()
+        .filter(|e| iext::is_user(e.id))
+        .map(Extension)
+        .collect()
+}
+
+pub(crate) fn to_unknown(extensions: &[Extension]) -> Vec<ZExtUnknown> {
+    extensions.iter().map(|e| e.0.clone()).collect()
+}
+
+/// Returns `true` if all the mandatory `extensions` are in the `accepted` bitmask of IDs.
+pub(crate) fn accepted(extensions: &[Extension], accepted: u16) -> bool {
+    extensions
+        .iter()
+        .all(|e| !e.is_mandatory() || accepted & (1 << e.id()) != 0)
+}
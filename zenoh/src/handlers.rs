@@ -29,6 +29,12 @@ pub type Callback<'a, T> = Dyn<dyn Fn(T) + Send + Sync + 'a>;
 pub trait IntoCallbackReceiverPair<'a, T> {
     type Receiver;
     fn into_cb_receiver_pair(self) -> (Callback<'a, T>, Self::Receiver);
+
+    /// The number of items the receiver can queue, accounted in the memory budget of the session.
+    /// It is `0` for the handlers without a bounded queue.
+    fn queue_capacity(&self) -> usize {
+        0
+    }
 }
 impl<'a, T, F> IntoCallbackReceiverPair<'a, T> for F
 where
@@ -55,6 +61,10 @@ impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T>
             receiver,
         )
     }
+
+    fn queue_capacity(&self) -> usize {
+        self.0.capacity().unwrap_or(0)
+    }
 }
 pub struct DefaultHandler;
 impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T> for DefaultHandler {
@@ -62,6 +72,10 @@ impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T> for DefaultHandler
     fn into_cb_receiver_pair(self) -> (Callback<'static, T>, Self::Receiver) {
         flume::bounded(*API_DATA_RECEPTION_CHANNEL_SIZE).into_cb_receiver_pair()
     }
+
+    fn queue_capacity(&self) -> usize {
+        *API_DATA_RECEPTION_CHANNEL_SIZE
+    }
 }
 impl<T: Send + Sync + 'static> IntoCallbackReceiverPair<'static, T>
     for (std::sync::mpsc::SyncSender<T>, std::sync::mpsc::Receiver<T>)
@@ -13,10 +13,47 @@
 //
 
 //! Tools to access information about the current zenoh [`Session`](crate::Session).
+//!
+//! # Built-in keys
+//! Every [`Session`](crate::Session) answers queries on the following keys, where `<zid>`
+//! is its [`ZenohId`]. They can be queried locally or remotely, without any queryable
+//! being declared by the application.
+//!
+//! | Key                 | Value                                                                  |
+//! |---------------------|------------------------------------------------------------------------|
+//! | `@/<zid>/time/now`  | The current time of the session, also set as the reply's timestamp     |
+//! | `@/<zid>/version`   | The zenoh version of the session                                       |
+//! | `@/<zid>/uptime`    | The number of seconds elapsed since the zenoh runtime was started      |
+//!
+//! The time is given by the session's HLC when `timestamping` is enabled in its configuration,
+//! by its system clock otherwise.
+//!
+//! # Examples
+//! ```
+//! # #[tokio::main]
+//! # async fn main() {
+//! use zenoh::prelude::r#async::*;
+//!
+//! let session = zenoh::open(config::peer()).res().await.unwrap();
+//! let zid = session.zid();
+//! let reply = session
+//!     .get(format!("@/{zid}/uptime"))
+//!     .res()
+//!     .await
+//!     .unwrap()
+//!     .recv_async()
+//!     .await
+//!     .unwrap();
+//! println!("Uptime: {}", reply.sample.unwrap().value);
+//! # }
+//! ```
 use crate::SessionRef;
 use std::future::Ready;
+use std::time::SystemTime;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
-use zenoh_protocol::core::{WhatAmI, ZenohId};
+use zenoh_link::Link;
+use zenoh_protocol::core::{Locator, WhatAmI, ZenohId};
+use zenoh_transport::unicast::TransportUnicast;
 
 /// A builder retuned by [`SessionInfo::zid()`](SessionInfo::zid) that allows
 /// to access the [`ZenohId`] of the current zenoh [`Session`](crate::Session).
@@ -153,6 +190,117 @@ impl<'a> AsyncResolve for PeersZidBuilder<'a> {
     }
 }
 
+/// A link of a connection to a remote zenoh node.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkInfo {
+    /// The local locator of the link.
+    pub src: Locator,
+    /// The remote locator of the link.
+    pub dst: Locator,
+    /// The maximum size of the batches sent on the link.
+    pub mtu: u16,
+    pub is_reliable: bool,
+    pub is_streamed: bool,
+}
+
+impl LinkInfo {
+    /// The protocol of the link (e.g. `tcp`, `udp`, `quic`).
+    pub fn protocol(&self) -> &str {
+        self.dst.protocol().as_str()
+    }
+}
+
+impl From<Link> for LinkInfo {
+    fn from(link: Link) -> Self {
+        LinkInfo {
+            src: link.src,
+            dst: link.dst,
+            mtu: link.mtu,
+            is_reliable: link.is_reliable,
+            is_streamed: link.is_streamed,
+        }
+    }
+}
+
+/// A connection of the current zenoh [`Session`](crate::Session) to a remote zenoh node.
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct ConnectionInfo {
+    /// The [`ZenohId`] of the remote node.
+    pub zid: ZenohId,
+    /// The kind of the remote node.
+    pub whatami: WhatAmI,
+    /// The protocol version negotiated with the remote node.
+    pub version: u8,
+    /// The links used by the connection.
+    pub links: Vec<LinkInfo>,
+    /// The time the connection was established.
+    pub established: SystemTime,
+}
+
+impl ConnectionInfo {
+    fn new(transport: &TransportUnicast) -> Option<Self> {
+        Some(ConnectionInfo {
+            zid: transport.get_zid().ok()?,
+            whatami: transport.get_whatami().ok()?,
+            version: transport.get_version().ok()?,
+            links: transport
+                .get_links()
+                .ok()?
+                .into_iter()
+                .map(LinkInfo::from)
+                .collect(),
+            established: transport.get_established().ok()?,
+        })
+    }
+}
+
+/// A builder returned by [`SessionInfo::connections()`](SessionInfo::connections) that allows
+/// to access the [`ConnectionInfo`] of the zenoh routers and peers this process is currently
+/// connected to.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// for connection in session.info().connections().res().await {
+///     println!("{} ({}): {:?}", connection.zid, connection.whatami, connection.links);
+/// }
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[derive(Debug)]
+pub struct ConnectionsBuilder<'a> {
+    pub(crate) session: SessionRef<'a>,
+}
+
+impl<'a> Resolvable for ConnectionsBuilder<'a> {
+    type To = Box<dyn Iterator<Item = ConnectionInfo> + Send + Sync>;
+}
+
+impl<'a> SyncResolve for ConnectionsBuilder<'a> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        Box::new(
+            zenoh_runtime::ZRuntime::Application
+                .block_in_place(self.session.runtime.manager().get_transports_unicast())
+                .into_iter()
+                .filter_map(|s| ConnectionInfo::new(&s)),
+        )
+    }
+}
+
+impl<'a> AsyncResolve for ConnectionsBuilder<'a> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
 /// Struct returned by [`Session::info()`](crate::SessionDeclarations::info) which allows
 /// to access informations about the current zenoh [`Session`](crate::Session).
 ///
@@ -228,4 +376,24 @@ impl SessionInfo<'_> {
             session: self.session.clone(),
         }
     }
+
+    /// Return the [`ConnectionInfo`] of the zenoh routers and peers this process is currently
+    /// connected to: their [`ZenohId`], kind, links and connection time.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let mut connections = session.info().connections().res().await;
+    /// while let Some(connection) = connections.next() {}
+    /// # }
+    /// ```
+    pub fn connections(&self) -> ConnectionsBuilder<'_> {
+        ConnectionsBuilder {
+            session: self.session.clone(),
+        }
+    }
 }
@@ -0,0 +1,214 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Session-level interceptors.
+//!
+//! A [`SessionInterceptor`] declared on a [`Session`](crate::Session) sees every publication
+//! issued by the session before it is sent, and every sample and query received by the session
+//! before it is delivered to its subscribers and queryables. It can observe, modify or drop them,
+//! e.g. to add attachments, enforce naming conventions or collect metrics.
+use crate::prelude::Sample;
+use crate::queryable::Query;
+use crate::{Id, SessionRef, Undeclarable};
+use std::fmt;
+use std::future::Ready;
+use std::sync::Arc;
+use zenoh_core::{AsyncResolve, Resolvable, Resolve, SyncResolve};
+use zenoh_result::ZResult;
+
+/// Hooks called by a [`Session`](crate::Session) on the messages it sends and receives.
+///
+/// All the hooks have a default implementation letting the messages through unchanged.
+/// They are called on the network threads, so they should not block.
+///
+/// # Examples
+/// ```
+/// use zenoh::interceptor::SessionInterceptor;
+/// use zenoh::prelude::*;
+///
+/// struct Tagger;
+///
+/// impl SessionInterceptor for Tagger {
+///     fn egress_sample(&self, mut sample: Sample) -> Option<Sample> {
+///         let mut attachment = sample.attachment.take().unwrap_or_default();
+///         attachment.insert("app", "tagger");
+///         sample.attachment = Some(attachment);
+///         Some(sample)
+///     }
+/// }
+/// ```
+pub trait SessionInterceptor: Send + Sync {
+    /// Called on each publication (put or delete) of the session before it is sent.
+    ///
+    /// The returned sample is the one sent, its key expression, value, kind, timestamp and
+    /// attachment can be changed. Returning `None` drops the publication.
+    fn egress_sample(&self, sample: Sample) -> Option<Sample> {
+        Some(sample)
+    }
+
+    /// Called on each sample received by the session, once before it is delivered to the
+    /// matching subscribers.
+    ///
+    /// The returned sample is the one delivered; changes to its key expression are ignored.
+    /// Returning `None` drops the sample.
+    fn ingress_sample(&self, sample: Sample) -> Option<Sample> {
+        Some(sample)
+    }
+
+    /// Called on each query received by the session before it is delivered to the matching
+    /// queryables.
+    ///
+    /// Returning `false` drops the query, which then resolves without replies from this session.
+    fn ingress_query(&self, query: &Query) -> bool {
+        let _ = query;
+        true
+    }
+}
+
+pub(crate) type InterceptorsChain = Vec<Arc<dyn SessionInterceptor>>;
+
+pub(crate) fn intercept_egress(chain: &InterceptorsChain, mut sample: Sample) -> Option<Sample> {
+    for interceptor in chain {
+        sample = interceptor.egress_sample(sample)?;
+    }
+    Some(sample)
+}
+
+pub(crate) fn intercept_ingress(chain: &InterceptorsChain, mut sample: Sample) -> Option<Sample> {
+    for interceptor in chain {
+        sample = interceptor.ingress_sample(sample)?;
+    }
+    Some(sample)
+}
+
+pub(crate) fn intercept_query(chain: &InterceptorsChain, query: &Query) -> bool {
+    chain
+        .iter()
+        .all(|interceptor| interceptor.ingress_query(query))
+}
+
+/// A builder for declaring an [`Interceptor`].
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::interceptor::SessionInterceptor;
+/// use zenoh::prelude::r#async::*;
+///
+/// struct Nop;
+/// impl SessionInterceptor for Nop {}
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let interceptor = session.declare_interceptor(Nop).res().await.unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct InterceptorBuilder<'a> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) interceptor: Arc<dyn SessionInterceptor>,
+}
+
+impl<'a> Resolvable for InterceptorBuilder<'a> {
+    type To = ZResult<Interceptor<'a>>;
+}
+
+impl SyncResolve for InterceptorBuilder<'_> {
+    #[inline]
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let id = self.session.declare_interceptor_inner(self.interceptor);
+        Ok(Interceptor {
+            session: self.session,
+            id,
+            alive: true,
+        })
+    }
+}
+
+impl AsyncResolve for InterceptorBuilder<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A [`SessionInterceptor`] declared on a [`Session`](crate::Session).
+///
+/// Interceptors are called in their declaration order and are automatically undeclared
+/// when dropped.
+pub struct Interceptor<'a> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) id: Id,
+    pub(crate) alive: bool,
+}
+
+impl<'a> Interceptor<'a> {
+    /// Undeclare an [`Interceptor`].
+    ///
+    /// Interceptors are automatically undeclared when dropped,
+    /// but you may want to use this function to handle errors or
+    /// undeclare the Interceptor asynchronously.
+    #[inline]
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
+        Undeclarable::undeclare_inner(self, ())
+    }
+}
+
+impl fmt::Debug for Interceptor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interceptor").field("id", &self.id).finish()
+    }
+}
+
+impl<'a> Undeclarable<(), InterceptorUndeclaration<'a>> for Interceptor<'a> {
+    fn undeclare_inner(self, _: ()) -> InterceptorUndeclaration<'a> {
+        InterceptorUndeclaration { interceptor: self }
+    }
+}
+
+/// A [`Resolvable`] returned when undeclaring an [`Interceptor`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct InterceptorUndeclaration<'a> {
+    interceptor: Interceptor<'a>,
+}
+
+impl Resolvable for InterceptorUndeclaration<'_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for InterceptorUndeclaration<'_> {
+    fn res_sync(mut self) -> <Self as Resolvable>::To {
+        self.interceptor.alive = false;
+        self.interceptor
+            .session
+            .undeclare_interceptor(self.interceptor.id)
+    }
+}
+
+impl AsyncResolve for InterceptorUndeclaration<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+impl Drop for Interceptor<'_> {
+    fn drop(&mut self) {
+        if self.alive {
+            let _ = self.session.undeclare_interceptor(self.id);
+        }
+    }
+}
@@ -123,12 +123,22 @@ pub const FEATURES: &str = concat_enabled_features!(
         "transport_unixsock-stream",
         "transport_ws",
         "transport_vsock",
+        "transport_ble",
+        "transport_webtransport",
         "unstable",
         "default"
     ]
 );
 
 mod admin;
+#[cfg(feature = "unstable")]
+pub mod adminspace;
+#[cfg(feature = "unstable")]
+pub mod authorization;
+#[cfg(feature = "unstable")]
+pub mod extension;
+#[cfg(feature = "unstable")]
+pub mod trace;
 #[macro_use]
 mod session;
 pub use session::*;
@@ -142,6 +152,8 @@ pub use zenoh_config as config;
 pub mod handlers;
 pub mod info;
 #[cfg(feature = "unstable")]
+pub mod interceptor;
+#[cfg(feature = "unstable")]
 pub mod liveliness;
 #[cfg(all(feature = "unstable", feature = "plugins"))]
 pub mod plugins;
@@ -151,6 +163,8 @@ pub mod query;
 pub mod queryable;
 pub mod sample;
 pub mod subscriber;
+#[cfg(feature = "serde")]
+pub mod typed;
 pub mod value;
 #[cfg(feature = "shared-memory")]
 pub use zenoh_shm as shm;
@@ -174,6 +188,47 @@ pub mod time {
         let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
         Timestamp::new(now.into(), TimestampId::try_from([1]).unwrap())
     }
+
+    /// Generates a [`Timestamp`] with the current system time and the given zenoh id as id,
+    /// for the data that must be timestamped by a zenoh instance without HLC.
+    #[zenoh_macros::unstable]
+    pub(crate) fn new_timestamp(zid: zenoh_protocol::core::ZenohId) -> Timestamp {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        Timestamp::new(now.into(), (&zid).into())
+    }
+
+    /// Checks a user provided [`Timestamp`] as the routers check the timestamps of the
+    /// received data: the given HLC is updated with it and, if it exceeds the HLC's physical
+    /// time by more than its configured delta, it is either rejected if `drop_future_timestamp`
+    /// is set, or replaced by a new timestamp of the HLC.
+    /// Any timestamp is accepted when no HLC is available.
+    pub(crate) fn check_timestamp(
+        hlc: Option<&uhlc::HLC>,
+        drop_future_timestamp: bool,
+        timestamp: Timestamp,
+    ) -> crate::Result<Timestamp> {
+        let Some(hlc) = hlc else {
+            return Ok(timestamp);
+        };
+        match hlc.update_with_timestamp(&timestamp) {
+            Ok(()) => Ok(timestamp),
+            Err(e) if drop_future_timestamp => {
+                Err(zenoh_result::zerror!("Invalid timestamp {}: {}", timestamp, e).into())
+            }
+            Err(e) => {
+                let replacement = hlc.new_timestamp();
+                tracing::error!(
+                    "Invalid timestamp {} ({}). Replace timestamp: {}",
+                    timestamp,
+                    e,
+                    replacement
+                );
+                Ok(replacement)
+            }
+        }
+    }
 }
 
 /// A map of key/value (String,String) properties.
@@ -236,6 +291,7 @@ where
         what: what.into(),
         config: config.try_into().map_err(|e| e.into()),
         handler: DefaultHandler,
+        deduplicate: false,
     }
 }
 
@@ -273,7 +329,11 @@ where
     TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
     <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
 {
-    OpenBuilder { config }
+    OpenBuilder {
+        config,
+        #[cfg(feature = "unstable")]
+        authorizer: None,
+    }
 }
 
 /// A builder returned by [`open`] used to open a zenoh [`Session`].
@@ -294,6 +354,30 @@ where
     <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
 {
     config: TryIntoConfig,
+    #[cfg(feature = "unstable")]
+    authorizer: Option<authorization::Authorizer>,
+}
+
+impl<TryIntoConfig> OpenBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    /// Authorize the messages exchanged with the remote nodes with the given callback,
+    /// see [`authorization`].
+    ///
+    /// The callback runs apart from the network threads, and may thus use zenoh, but the
+    /// first messages on a key expression wait for its decision: they are denied if it takes
+    /// more than a second.
+    #[zenoh_macros::unstable]
+    pub fn authorization<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(authorization::AuthorizationRequest) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = authorization::Permission> + Send + 'static,
+    {
+        self.authorizer = Some(authorization::authorizer(callback));
+        self
+    }
 }
 
 impl<TryIntoConfig> Resolvable for OpenBuilder<TryIntoConfig>
@@ -314,7 +398,12 @@ where
             .config
             .try_into()
             .map_err(|e| zerror!("Invalid Zenoh configuration {:?}", &e))?;
-        Session::new(config).res_sync()
+        Session::new(
+            config,
+            #[cfg(feature = "unstable")]
+            self.authorizer,
+        )
+        .res_sync()
     }
 }
 
@@ -330,9 +419,122 @@ where
     }
 }
 
-/// Initialize a Session with an existing Runtime.
-/// This operation is used by the plugins to share the same Runtime as the router.
-#[doc(hidden)]
+/// Open and start a zenoh [`Runtime`](runtime::Runtime), to be shared by several sessions.
+///
+/// The runtime owns the transports, the scouting and the admin space configured by `config`.
+/// Sessions are created on it with [`init`] and share its sockets, threads and [`ZenohId`](prelude::ZenohId).
+/// Closing these sessions doesn't close the runtime: it is closed with
+/// [`Runtime::close`](runtime::Runtime::close).
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let runtime = zenoh::open_runtime(config::peer()).res().await.unwrap();
+/// let session1 = zenoh::init(runtime.clone()).res().await.unwrap();
+/// let session2 = zenoh::init(runtime.clone()).res().await.unwrap();
+/// assert_eq!(session1.zid(), session2.zid());
+///
+/// session1.close().res().await.unwrap();
+/// session2.close().res().await.unwrap();
+/// runtime.close().await.unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub fn open_runtime<TryIntoConfig>(config: TryIntoConfig) -> OpenRuntimeBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    OpenRuntimeBuilder {
+        config,
+        authorizer: None,
+    }
+}
+
+/// A builder returned by [`open_runtime`] used to open a zenoh [`Runtime`](runtime::Runtime).
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[zenoh_macros::unstable]
+pub struct OpenRuntimeBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    config: TryIntoConfig,
+    authorizer: Option<authorization::Authorizer>,
+}
+
+#[zenoh_macros::unstable]
+impl<TryIntoConfig> OpenRuntimeBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    /// Authorize the messages exchanged with the remote nodes with the given callback,
+    /// see [`OpenBuilder::authorization`].
+    pub fn authorization<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(authorization::AuthorizationRequest) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = authorization::Permission> + Send + 'static,
+    {
+        self.authorizer = Some(authorization::authorizer(callback));
+        self
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<TryIntoConfig> Resolvable for OpenRuntimeBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    type To = ZResult<Runtime>;
+}
+
+#[zenoh_macros::unstable]
+impl<TryIntoConfig> SyncResolve for OpenRuntimeBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let config: crate::config::Config = self
+            .config
+            .try_into()
+            .map_err(|e| zerror!("Invalid Zenoh configuration {:?}", &e))?;
+        zenoh_runtime::ZRuntime::Application.block_in_place(async move {
+            let mut runtime = runtime::RuntimeBuilder::new(config)
+                .authorizer(self.authorizer)
+                .build()
+                .await?;
+            runtime.start().await?;
+            Ok(runtime)
+        })
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<TryIntoConfig> AsyncResolve for OpenRuntimeBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// Initialize a [`Session`] with an existing [`Runtime`](runtime::Runtime).
+///
+/// Several sessions can be initialized with the same runtime: they share its transports,
+/// scouting and threads instead of each opening its own. This operation is also used by
+/// the plugins to share the same runtime as the router.
+///
+/// Closing the session doesn't close the runtime, see [`open_runtime`].
 #[zenoh_macros::unstable]
 pub fn init(runtime: Runtime) -> InitBuilder {
     InitBuilder {
@@ -344,7 +546,6 @@ pub fn init(runtime: Runtime) -> InitBuilder {
 
 /// A builder returned by [`init`] and used to initialize a Session with an existing Runtime.
 #[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
-#[doc(hidden)]
 #[zenoh_macros::unstable]
 pub struct InitBuilder {
     runtime: Runtime,
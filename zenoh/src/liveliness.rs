@@ -29,9 +29,10 @@ use {
     },
     std::convert::TryInto,
     std::future::Ready,
-    std::sync::Arc,
+    std::sync::{Arc, Mutex},
     std::time::Duration,
     zenoh_config::unwrap_or_default,
+    zenoh_core::zlock,
     zenoh_core::AsyncResolve,
     zenoh_core::Resolvable,
     zenoh_core::Result as ZResult,
@@ -155,6 +156,7 @@ impl<'a> Liveliness<'a> {
             session: self.session.clone(),
             key_expr: TryIntoKeyExpr::try_into(key_expr).map_err(Into::into),
             handler: DefaultHandler,
+            history: false,
         }
     }
 
@@ -421,6 +423,7 @@ pub struct LivelinessSubscriberBuilder<'a, 'b, Handler> {
     pub session: SessionRef<'a>,
     pub key_expr: ZResult<KeyExpr<'b>>,
     pub handler: Handler,
+    pub history: bool,
 }
 
 #[zenoh_macros::unstable]
@@ -455,11 +458,13 @@ impl<'a, 'b> LivelinessSubscriberBuilder<'a, 'b, DefaultHandler> {
             session,
             key_expr,
             handler: _,
+            history,
         } = self;
         LivelinessSubscriberBuilder {
             session,
             key_expr,
             handler: callback,
+            history,
         }
     }
 
@@ -526,15 +531,32 @@ impl<'a, 'b> LivelinessSubscriberBuilder<'a, 'b, DefaultHandler> {
             session,
             key_expr,
             handler: _,
+            history,
         } = self;
         LivelinessSubscriberBuilder {
             session,
             key_expr,
             handler,
+            history,
         }
     }
 }
 
+#[zenoh_macros::unstable]
+impl<'a, 'b, Handler> LivelinessSubscriberBuilder<'a, 'b, Handler> {
+    /// Receive the currently alive matching tokens upon declaration.
+    ///
+    /// When enabled, the subscriber first receives a [`SampleKind::Put`] for each matching token
+    /// that is already alive, then the liveliness changes. The changes that happen while the alive
+    /// tokens are being queried are delivered right after them, so a token may be received twice.
+    #[inline]
+    #[zenoh_macros::unstable]
+    pub fn history(mut self, history: bool) -> Self {
+        self.history = history;
+        self
+    }
+}
+
 #[zenoh_macros::unstable]
 impl<'a, Handler> Resolvable for LivelinessSubscriberBuilder<'a, '_, Handler>
 where
@@ -555,22 +577,91 @@ where
         let key_expr = self.key_expr?;
         let session = self.session;
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
-        session
-            .declare_subscriber_inner(
-                &key_expr,
-                &Some(KeyExpr::from(*KE_PREFIX_LIVELINESS)),
-                Locality::default(),
-                callback,
-                &SubscriberInfo::default(),
-            )
-            .map(|sub_state| Subscriber {
-                subscriber: SubscriberInner {
-                    session,
-                    state: sub_state,
-                    alive: true,
-                },
-                receiver,
+        if !self.history {
+            return session
+                .declare_subscriber_inner(
+                    &key_expr,
+                    &Some(KeyExpr::from(*KE_PREFIX_LIVELINESS)),
+                    Locality::default(),
+                    callback,
+                    &SubscriberInfo::default(),
+                )
+                .map(|sub_state| Subscriber {
+                    subscriber: SubscriberInner {
+                        session,
+                        state: sub_state,
+                        alive: true,
+                    },
+                    receiver,
+                });
+        }
+
+        // Hold back the liveliness changes until the alive tokens were delivered.
+        let pending: Arc<Mutex<Option<Vec<Sample>>>> = Arc::new(Mutex::new(Some(vec![])));
+        let live_callback = {
+            let pending = pending.clone();
+            let callback = callback.clone();
+            Arc::new(move |sample| {
+                let mut pending = zlock!(pending);
+                match &mut *pending {
+                    Some(samples) => samples.push(sample),
+                    None => {
+                        drop(pending);
+                        callback(sample)
+                    }
+                }
             })
+        };
+        let sub_state = session.declare_subscriber_inner(
+            &key_expr,
+            &Some(KeyExpr::from(*KE_PREFIX_LIVELINESS)),
+            Locality::default(),
+            live_callback,
+            &SubscriberInfo::default(),
+        )?;
+        let subscriber = Subscriber {
+            subscriber: SubscriberInner {
+                session,
+                state: sub_state,
+                alive: true,
+            },
+            receiver,
+        };
+
+        let timeout = {
+            let conf = subscriber.subscriber.session.runtime.config().lock();
+            Duration::from_millis(unwrap_or_default!(conf.queries_default_timeout()))
+        };
+        let reply_callback = {
+            let callback = callback.clone();
+            Arc::new(move |reply: Reply| {
+                if let Ok(sample) = reply.sample {
+                    callback(sample)
+                }
+            })
+        };
+        let on_final = Box::new(move |_| {
+            let mut pending = zlock!(pending);
+            for sample in pending.take().unwrap_or_default() {
+                callback(sample)
+            }
+        });
+        subscriber.subscriber.session.query(
+            &key_expr.into(),
+            &Some(KeyExpr::from(*KE_PREFIX_LIVELINESS)),
+            QueryTarget::default(),
+            None,
+            QueryConsolidation::default(),
+            Locality::default(),
+            timeout,
+            None,
+            None,
+            None,
+            reply_callback,
+            Some(on_final),
+            None,
+        )?;
+        Ok(subscriber)
     }
 }
 
@@ -764,13 +855,18 @@ where
                 &self.key_expr?.into(),
                 &Some(KeyExpr::from(*KE_PREFIX_LIVELINESS)),
                 QueryTarget::default(),
+                None,
                 QueryConsolidation::default(),
                 Locality::default(),
                 self.timeout,
                 None,
                 #[cfg(feature = "unstable")]
                 None,
+                #[cfg(feature = "unstable")]
+                None,
                 callback,
+                None,
+                None,
             )
             .map(|_| receiver)
     }
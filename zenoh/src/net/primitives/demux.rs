@@ -11,7 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::Primitives;
+use super::{drops::read_drop_notification, Primitives};
 use crate::net::routing::{
     dispatcher::face::Face,
     interceptor::{InterceptorTrait, InterceptorsChain},
@@ -19,7 +19,10 @@ use crate::net::routing::{
 };
 use std::{any::Any, sync::Arc};
 use zenoh_link::Link;
-use zenoh_protocol::network::{NetworkBody, NetworkMessage};
+use zenoh_protocol::{
+    core::WireExpr,
+    network::{oam::id::OAM_DROP_NOTIFICATION, NetworkBody, NetworkMessage},
+};
 use zenoh_result::ZResult;
 use zenoh_transport::unicast::TransportUnicast;
 use zenoh_transport::TransportPeerEventHandler;
@@ -48,6 +51,10 @@ impl TransportPeerEventHandler for DeMux {
     #[inline]
     fn handle_message(&self, mut msg: NetworkMessage) -> ZResult<()> {
         if !self.interceptor.interceptors.is_empty() {
+            let dropped = match &msg.body {
+                NetworkBody::Push(push) => Some((push.wire_expr.clone(), push.ext_nodeid.node_id)),
+                _ => None,
+            };
             let ctx = RoutingContext::new_in(msg, self.face.clone());
             let prefix = ctx
                 .wire_expr()
@@ -57,9 +64,14 @@ impl TransportPeerEventHandler for DeMux {
             let cache = prefix
                 .as_ref()
                 .and_then(|p| p.get_ingress_cache(&self.face));
-            let ctx = match self.interceptor.intercept(ctx, cache) {
-                Some(ctx) => ctx,
-                None => return Ok(()),
+            let ctx = match self.interceptor.intercept_or_drop(ctx, cache) {
+                Ok(ctx) => ctx,
+                Err(reason) => {
+                    if let (Some((wire_expr, node_id)), Some(reason)) = (dropped, reason) {
+                        self.face.notify_drop(&wire_expr, reason, 1, node_id);
+                    }
+                    return Ok(());
+                }
             };
             msg = ctx.msg;
         }
@@ -70,6 +82,14 @@ impl TransportPeerEventHandler for DeMux {
             NetworkBody::Request(m) => self.face.send_request(m),
             NetworkBody::Response(m) => self.face.send_response(m),
             NetworkBody::ResponseFinal(m) => self.face.send_response_final(m),
+            NetworkBody::RequestCancel(m) => self.face.send_request_cancel(m),
+            NetworkBody::OAM(m) if m.id == OAM_DROP_NOTIFICATION => {
+                // Forward the notification along the route of the dropped samples
+                if let Some((key_expr, reason, count, node_id)) = read_drop_notification(&m) {
+                    let wire_expr = WireExpr::from(key_expr.as_str());
+                    self.face.notify_drop(&wire_expr, reason, count, node_id);
+                }
+            }
             NetworkBody::OAM(m) => {
                 if let Some(transport) = self.transport.as_ref() {
                     let ctrl_lock = zlock!(self.face.tables.ctrl_lock);
@@ -0,0 +1,157 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::net::routing::{dispatcher::tables::NodeId, interceptor::DropReason};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh_buffers::{
+    reader::HasReader,
+    writer::{DidntWrite, HasWriter},
+    ZBuf,
+};
+use zenoh_codec::{RCodec, WCodec, Zenoh080};
+use zenoh_core::zlock;
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+use zenoh_protocol::{
+    common::ZExtBody,
+    network::{
+        oam::{self, id::OAM_DROP_NOTIFICATION},
+        NetworkBody, NetworkMessage, Oam,
+    },
+};
+use zenoh_runtime::ZRuntime;
+
+// The time in milliseconds the drop notifications are gathered before being sent
+const DROP_NOTIFICATION_DELAY: u64 = 100;
+// The maximum number of key expressions notified per reason in a single delay,
+// the drops on other key expressions being left out
+const MAX_PENDING_KEY_EXPRS: usize = 64;
+
+/// The drop notifications waiting to be sent to a remote node, gathered for a short delay
+/// so that a burst of drops is notified with a few messages.
+#[derive(Default)]
+#[allow(clippy::type_complexity)]
+pub(crate) struct PendingDrops(Mutex<HashMap<(DropReason, NodeId), HashMap<OwnedKeyExpr, usize>>>);
+
+impl PendingDrops {
+    /// Records `count` samples published on `key_expr` dropped for the given `reason`,
+    /// that would have been sent with the given routing context, and sends them with `send`
+    /// once the delay is elapsed.
+    pub(crate) fn add<F>(
+        self: &Arc<Self>,
+        key_expr: &keyexpr,
+        reason: DropReason,
+        count: usize,
+        context: NodeId,
+        send: F,
+    ) where
+        F: Fn(NetworkMessage) + Send + 'static,
+    {
+        let mut pending = zlock!(self.0);
+        let schedule = pending.is_empty();
+        let key_exprs = pending.entry((reason, context)).or_default();
+        match key_exprs.get_mut(key_expr) {
+            Some(c) => *c += count,
+            None if key_exprs.len() < MAX_PENDING_KEY_EXPRS => {
+                key_exprs.insert(key_expr.to_owned(), count);
+            }
+            None => tracing::trace!("Too many drop notifications pending, skipping {key_expr}"),
+        }
+        drop(pending);
+
+        if schedule {
+            let pending = self.clone();
+            ZRuntime::Net.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(DROP_NOTIFICATION_DELAY)).await;
+                let drops = std::mem::take(&mut *zlock!(pending.0));
+                for ((reason, context), key_exprs) in drops {
+                    for (key_expr, count) in key_exprs {
+                        match make_msg(&key_expr, reason, count, context) {
+                            Ok(msg) => send(msg),
+                            Err(_) => tracing::debug!("Error encoding a drop notification"),
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+fn make_msg(
+    key_expr: &keyexpr,
+    reason: DropReason,
+    count: usize,
+    context: NodeId,
+) -> Result<NetworkMessage, DidntWrite> {
+    let codec = Zenoh080::new();
+    let mut buf = ZBuf::empty();
+    let mut writer = buf.writer();
+    codec.write(&mut writer, reason.id())?;
+    codec.write(&mut writer, count as u64)?;
+    codec.write(&mut writer, context as u64)?;
+    codec.write(&mut writer, key_expr.as_str())?;
+    Ok(NetworkBody::OAM(Oam {
+        id: OAM_DROP_NOTIFICATION,
+        body: ZExtBody::ZBuf(buf),
+        ext_qos: oam::ext::QoSType::oam_default(),
+        ext_tstamp: None,
+    })
+    .into())
+}
+
+/// Reads the key expression, the reason, the number and the routing context of the drops
+/// notified by `oam`.
+pub(crate) fn read_drop_notification(
+    oam: &Oam,
+) -> Option<(OwnedKeyExpr, DropReason, usize, NodeId)> {
+    let ZExtBody::ZBuf(buf) = &oam.body else {
+        return None;
+    };
+    let codec = Zenoh080::new();
+    let mut reader = buf.reader();
+    let reason: u8 = codec.read(&mut reader).ok()?;
+    let count: u64 = codec.read(&mut reader).ok()?;
+    let context: u64 = codec.read(&mut reader).ok()?;
+    let key_expr: String = codec.read(&mut reader).ok()?;
+    Some((
+        OwnedKeyExpr::new(key_expr).ok()?,
+        DropReason::from_id(reason)?,
+        usize::try_from(count).ok()?,
+        NodeId::try_from(context).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_notification_codec() {
+        let key_expr = keyexpr::new("test/drops").unwrap();
+        let msg = make_msg(key_expr, DropReason::AccessControl, 42, 3).unwrap();
+        let NetworkBody::OAM(oam) = msg.body else {
+            panic!("Drop notifications are OAM messages");
+        };
+        assert_eq!(oam.id, OAM_DROP_NOTIFICATION);
+        assert_eq!(
+            read_drop_notification(&oam),
+            Some((key_expr.to_owned(), DropReason::AccessControl, 42, 3))
+        );
+
+        // Malformed notifications are ignored
+        let mut oam = oam;
+        oam.body = ZExtBody::Unit;
+        assert_eq!(read_drop_notification(&oam), None);
+    }
+}
@@ -12,14 +12,18 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 mod demux;
+mod drops;
 mod mux;
 
 use std::any::Any;
 
 pub use demux::*;
 pub use mux::*;
-use zenoh_protocol::network::{Declare, Push, Request, Response, ResponseFinal};
+use zenoh_keyexpr::keyexpr;
+use zenoh_protocol::network::{Declare, Push, Request, RequestCancel, Response, ResponseFinal};
 
+use super::routing::dispatcher::tables::NodeId;
+use super::routing::interceptor::DropReason;
 use super::routing::RoutingContext;
 
 pub trait Primitives: Send + Sync {
@@ -33,6 +37,8 @@ pub trait Primitives: Send + Sync {
 
     fn send_response_final(&self, msg: ResponseFinal);
 
+    fn send_request_cancel(&self, msg: RequestCancel);
+
     fn send_close(&self);
 }
 
@@ -48,6 +54,19 @@ pub(crate) trait EPrimitives: Send + Sync {
     fn send_response(&self, ctx: RoutingContext<Response>);
 
     fn send_response_final(&self, ctx: RoutingContext<ResponseFinal>);
+
+    fn send_request_cancel(&self, ctx: RoutingContext<RequestCancel>);
+
+    /// Notify that `count` samples published on `key_expr` were dropped by the infrastructure,
+    /// `context` being the routing context they would have been sent with.
+    fn send_drop_notification(
+        &self,
+        _key_expr: &keyexpr,
+        _reason: DropReason,
+        _count: usize,
+        _context: NodeId,
+    ) {
+    }
 }
 
 #[derive(Default)]
@@ -64,6 +83,8 @@ impl Primitives for DummyPrimitives {
 
     fn send_response_final(&self, _msg: ResponseFinal) {}
 
+    fn send_request_cancel(&self, _msg: RequestCancel) {}
+
     fn send_close(&self) {}
 }
 
@@ -78,6 +99,8 @@ impl EPrimitives for DummyPrimitives {
 
     fn send_response_final(&self, _ctx: RoutingContext<ResponseFinal>) {}
 
+    fn send_request_cancel(&self, _ctx: RoutingContext<RequestCancel>) {}
+
     fn as_any(&self) -> &dyn Any {
         self
     }
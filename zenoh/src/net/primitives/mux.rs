@@ -11,15 +11,22 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::{EPrimitives, Primitives};
+use super::{drops::PendingDrops, EPrimitives, Primitives};
 use crate::net::routing::{
-    dispatcher::face::{Face, WeakFace},
-    interceptor::{InterceptorTrait, InterceptorsChain},
+    dispatcher::{
+        face::{Face, WeakFace},
+        tables::NodeId,
+    },
+    interceptor::{DropReason, InterceptorTrait, InterceptorsChain},
     RoutingContext,
 };
-use std::sync::OnceLock;
-use zenoh_protocol::network::{
-    Declare, NetworkBody, NetworkMessage, Push, Request, Response, ResponseFinal,
+use std::sync::{Arc, OnceLock};
+use zenoh_keyexpr::keyexpr;
+use zenoh_protocol::{
+    core::WireExpr,
+    network::{
+        Declare, NetworkBody, NetworkMessage, Push, Request, RequestCancel, Response, ResponseFinal,
+    },
 };
 use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
 
@@ -27,6 +34,7 @@ pub struct Mux {
     pub handler: TransportUnicast,
     pub(crate) face: OnceLock<WeakFace>,
     pub(crate) interceptor: InterceptorsChain,
+    pub(crate) drops: Arc<PendingDrops>,
 }
 
 impl Mux {
@@ -35,6 +43,25 @@ impl Mux {
             handler,
             face: OnceLock::new(),
             interceptor,
+            drops: Arc::default(),
+        }
+    }
+
+    /// Notify the remote node that a sample it would have received on `wire_expr`
+    /// was dropped by an egress interceptor.
+    fn notify_egress_drop(
+        &self,
+        face: &Face,
+        wire_expr: &WireExpr,
+        reason: DropReason,
+        context: NodeId,
+    ) {
+        let expr = zread!(face.tables.tables)
+            .get_sent_mapping(&face.state, &wire_expr.scope, wire_expr.mapping)
+            .map(|prefix| prefix.expr() + wire_expr.suffix.as_ref());
+        match expr.as_deref().map(keyexpr::new) {
+            Some(Ok(key_expr)) => self.send_drop_notification(key_expr, reason, 1, context),
+            _ => tracing::trace!("Dropped sample with unknown key expression {}", wire_expr),
         }
     }
 }
@@ -160,6 +187,30 @@ impl Primitives for Mux {
         }
     }
 
+    fn send_request_cancel(&self, msg: RequestCancel) {
+        let msg = NetworkMessage {
+            body: NetworkBody::RequestCancel(msg),
+            #[cfg(feature = "stats")]
+            size: None,
+        };
+        if self.interceptor.interceptors.is_empty() {
+            let _ = self.handler.schedule(msg);
+        } else if let Some(face) = self.face.get().and_then(|f| f.upgrade()) {
+            let ctx = RoutingContext::new_out(msg, face.clone());
+            let prefix = ctx
+                .wire_expr()
+                .and_then(|we| (!we.has_suffix()).then(|| ctx.prefix()))
+                .flatten()
+                .cloned();
+            let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(&face));
+            if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
+                let _ = self.handler.schedule(ctx.msg);
+            }
+        } else {
+            tracing::error!("Uninitialized multiplexer!");
+        }
+    }
+
     fn send_close(&self) {
         // self.handler.closing().await;
     }
@@ -192,6 +243,8 @@ impl EPrimitives for Mux {
     }
 
     fn send_push(&self, msg: Push) {
+        let dropped = (!self.interceptor.interceptors.is_empty())
+            .then(|| (msg.wire_expr.clone(), msg.ext_nodeid.node_id));
         let msg = NetworkMessage {
             body: NetworkBody::Push(msg),
             #[cfg(feature = "stats")]
@@ -207,8 +260,15 @@ impl EPrimitives for Mux {
                 .flatten()
                 .cloned();
             let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(&face));
-            if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-                let _ = self.handler.schedule(ctx.msg);
+            match self.interceptor.intercept_or_drop(ctx, cache) {
+                Ok(ctx) => {
+                    let _ = self.handler.schedule(ctx.msg);
+                }
+                Err(reason) => {
+                    if let (Some((wire_expr, node_id)), Some(reason)) = (dropped, reason) {
+                        self.notify_egress_drop(&face, &wire_expr, reason, node_id);
+                    }
+                }
             }
         } else {
             tracing::error!("Uninitialized multiplexer!");
@@ -290,6 +350,45 @@ impl EPrimitives for Mux {
         }
     }
 
+    fn send_request_cancel(&self, ctx: RoutingContext<RequestCancel>) {
+        let ctx = RoutingContext {
+            msg: NetworkMessage {
+                body: NetworkBody::RequestCancel(ctx.msg),
+                #[cfg(feature = "stats")]
+                size: None,
+            },
+            inface: ctx.inface,
+            outface: ctx.outface,
+            prefix: ctx.prefix,
+            full_expr: ctx.full_expr,
+        };
+        let prefix = ctx
+            .wire_expr()
+            .and_then(|we| (!we.has_suffix()).then(|| ctx.prefix()))
+            .flatten()
+            .cloned();
+        let cache = prefix
+            .as_ref()
+            .and_then(|p| p.get_egress_cache(ctx.outface.get().unwrap()));
+        if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
+            let _ = self.handler.schedule(ctx.msg);
+        }
+    }
+
+    fn send_drop_notification(
+        &self,
+        key_expr: &keyexpr,
+        reason: DropReason,
+        count: usize,
+        context: NodeId,
+    ) {
+        let handler = self.handler.clone();
+        self.drops
+            .add(key_expr, reason, count, context, move |msg| {
+                let _ = handler.schedule(msg);
+            });
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -299,6 +398,7 @@ pub struct McastMux {
     pub handler: TransportMulticast,
     pub(crate) face: OnceLock<Face>,
     pub(crate) interceptor: InterceptorsChain,
+    pub(crate) drops: Arc<PendingDrops>,
 }
 
 impl McastMux {
@@ -307,6 +407,25 @@ impl McastMux {
             handler,
             face: OnceLock::new(),
             interceptor,
+            drops: Arc::default(),
+        }
+    }
+
+    /// Notify the remote node that a sample it would have received on `wire_expr`
+    /// was dropped by an egress interceptor.
+    fn notify_egress_drop(
+        &self,
+        face: &Face,
+        wire_expr: &WireExpr,
+        reason: DropReason,
+        context: NodeId,
+    ) {
+        let expr = zread!(face.tables.tables)
+            .get_sent_mapping(&face.state, &wire_expr.scope, wire_expr.mapping)
+            .map(|prefix| prefix.expr() + wire_expr.suffix.as_ref());
+        match expr.as_deref().map(keyexpr::new) {
+            Some(Ok(key_expr)) => self.send_drop_notification(key_expr, reason, 1, context),
+            _ => tracing::trace!("Dropped sample with unknown key expression {}", wire_expr),
         }
     }
 }
@@ -432,6 +551,30 @@ impl Primitives for McastMux {
         }
     }
 
+    fn send_request_cancel(&self, msg: RequestCancel) {
+        let msg = NetworkMessage {
+            body: NetworkBody::RequestCancel(msg),
+            #[cfg(feature = "stats")]
+            size: None,
+        };
+        if self.interceptor.interceptors.is_empty() {
+            let _ = self.handler.schedule(msg);
+        } else if let Some(face) = self.face.get() {
+            let ctx = RoutingContext::new_out(msg, face.clone());
+            let prefix = ctx
+                .wire_expr()
+                .and_then(|we| (!we.has_suffix()).then(|| ctx.prefix()))
+                .flatten()
+                .cloned();
+            let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(face));
+            if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
+                let _ = self.handler.schedule(ctx.msg);
+            }
+        } else {
+            tracing::error!("Uninitialized multiplexer!");
+        }
+    }
+
     fn send_close(&self) {
         // self.handler.closing().await;
     }
@@ -464,6 +607,8 @@ impl EPrimitives for McastMux {
     }
 
     fn send_push(&self, msg: Push) {
+        let dropped = (!self.interceptor.interceptors.is_empty())
+            .then(|| (msg.wire_expr.clone(), msg.ext_nodeid.node_id));
         let msg = NetworkMessage {
             body: NetworkBody::Push(msg),
             #[cfg(feature = "stats")]
@@ -479,8 +624,15 @@ impl EPrimitives for McastMux {
                 .flatten()
                 .cloned();
             let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(face));
-            if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-                let _ = self.handler.schedule(ctx.msg);
+            match self.interceptor.intercept_or_drop(ctx, cache) {
+                Ok(ctx) => {
+                    let _ = self.handler.schedule(ctx.msg);
+                }
+                Err(reason) => {
+                    if let (Some((wire_expr, node_id)), Some(reason)) = (dropped, reason) {
+                        self.notify_egress_drop(face, &wire_expr, reason, node_id);
+                    }
+                }
             }
         } else {
             tracing::error!("Uninitialized multiplexer!");
@@ -562,6 +714,45 @@ impl EPrimitives for McastMux {
         }
     }
 
+    fn send_request_cancel(&self, ctx: RoutingContext<RequestCancel>) {
+        let ctx = RoutingContext {
+            msg: NetworkMessage {
+                body: NetworkBody::RequestCancel(ctx.msg),
+                #[cfg(feature = "stats")]
+                size: None,
+            },
+            inface: ctx.inface,
+            outface: ctx.outface,
+            prefix: ctx.prefix,
+            full_expr: ctx.full_expr,
+        };
+        let prefix = ctx
+            .wire_expr()
+            .and_then(|we| (!we.has_suffix()).then(|| ctx.prefix()))
+            .flatten()
+            .cloned();
+        let cache = prefix
+            .as_ref()
+            .and_then(|p| p.get_egress_cache(ctx.outface.get().unwrap()));
+        if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
+            let _ = self.handler.schedule(ctx.msg);
+        }
+    }
+
+    fn send_drop_notification(
+        &self,
+        key_expr: &keyexpr,
+        reason: DropReason,
+        count: usize,
+        context: NodeId,
+    ) {
+        let handler = self.handler.clone();
+        self.drops
+            .add(key_expr, reason, count, context, move |msg| {
+                let _ = handler.schedule(msg);
+            });
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -12,10 +12,11 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::super::router::*;
+use super::pubsub::route_drop_notification;
 use super::tables::TablesLock;
 use super::{resource::*, tables};
 use crate::net::primitives::{McastMux, Mux, Primitives};
-use crate::net::routing::interceptor::{InterceptorTrait, InterceptorsChain};
+use crate::net::routing::interceptor::{DropReason, InterceptorTrait, InterceptorsChain};
 use crate::KeyExpr;
 use std::any::Any;
 use std::collections::HashMap;
@@ -24,8 +25,8 @@ use std::sync::{Arc, Weak};
 use tokio_util::sync::CancellationToken;
 use zenoh_protocol::zenoh::RequestBody;
 use zenoh_protocol::{
-    core::{ExprId, WhatAmI, ZenohId},
-    network::{Mapping, Push, Request, RequestId, Response, ResponseFinal},
+    core::{ExprId, WhatAmI, WireExpr, ZenohId},
+    network::{Mapping, Push, Request, RequestCancel, RequestId, Response, ResponseFinal},
 };
 use zenoh_sync::get_mut_unchecked;
 use zenoh_task::TaskController;
@@ -182,6 +183,25 @@ impl Face {
             state: Arc::downgrade(&self.state),
         }
     }
+
+    /// Notify the faces a sample received on this face for the given `wire_expr` would have
+    /// been routed to that `count` such samples were dropped for the given `reason`.
+    pub(crate) fn notify_drop(
+        &self,
+        wire_expr: &WireExpr,
+        reason: DropReason,
+        count: usize,
+        routing_context: NodeId,
+    ) {
+        route_drop_notification(
+            &self.tables,
+            &self.state,
+            wire_expr,
+            reason,
+            count,
+            routing_context,
+        );
+    }
 }
 
 impl Primitives for Face {
@@ -234,9 +254,32 @@ impl Primitives for Face {
             }
             zenoh_protocol::network::DeclareBody::DeclareToken(_m) => todo!(),
             zenoh_protocol::network::DeclareBody::UndeclareToken(_m) => todo!(),
-            zenoh_protocol::network::DeclareBody::DeclareInterest(_m) => todo!(),
-            zenoh_protocol::network::DeclareBody::FinalInterest(_m) => todo!(),
-            zenoh_protocol::network::DeclareBody::UndeclareInterest(_m) => todo!(),
+            zenoh_protocol::network::DeclareBody::DeclareInterest(m) => {
+                declare_interest(
+                    ctrl_lock.as_ref(),
+                    &self.tables,
+                    &mut self.state.clone(),
+                    m.id,
+                    &m.wire_expr,
+                    m.interest,
+                );
+            }
+            zenoh_protocol::network::DeclareBody::FinalInterest(m) => {
+                declare_final_interest(
+                    ctrl_lock.as_ref(),
+                    &self.tables,
+                    &mut self.state.clone(),
+                    m.id,
+                );
+            }
+            zenoh_protocol::network::DeclareBody::UndeclareInterest(m) => {
+                undeclare_interest(
+                    ctrl_lock.as_ref(),
+                    &self.tables,
+                    &mut self.state.clone(),
+                    m.id,
+                );
+            }
         }
         drop(ctrl_lock);
     }
@@ -265,6 +308,7 @@ impl Primitives for Face {
                     msg.ext_target,
                     msg.ext_budget,
                     msg.ext_timeout,
+                    msg.ext_selection,
                     msg.payload,
                     msg.ext_nodeid.node_id,
                 );
@@ -283,6 +327,7 @@ impl Primitives for Face {
             &self.tables,
             &mut self.state.clone(),
             msg.rid,
+            msg.ext_qos,
             msg.ext_respid,
             msg.wire_expr,
             msg.payload,
@@ -293,6 +338,10 @@ impl Primitives for Face {
         route_send_response_final(&self.tables, &mut self.state.clone(), msg.rid);
     }
 
+    fn send_request_cancel(&self, msg: RequestCancel) {
+        route_send_request_cancel(&self.tables, &self.state, msg.rid);
+    }
+
     fn send_close(&self) {
         tables::close_face(&self.tables, &Arc::downgrade(&self.state));
     }
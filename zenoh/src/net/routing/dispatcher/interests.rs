@@ -0,0 +1,69 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::face::FaceState;
+use super::tables::TablesLock;
+use crate::net::routing::hat::HatTrait;
+use std::sync::Arc;
+use zenoh_core::zread;
+use zenoh_protocol::core::key_expr::OwnedKeyExpr;
+use zenoh_protocol::core::WireExpr;
+use zenoh_protocol::network::declare::{Interest, InterestId};
+
+pub(crate) fn declare_interest(
+    hat_code: &(dyn HatTrait + Send + Sync),
+    tables: &TablesLock,
+    face: &mut Arc<FaceState>,
+    id: InterestId,
+    expr: &WireExpr,
+    interest: Interest,
+) {
+    tracing::debug!("Declare interest {} for {}", id, face);
+    let rtables = zread!(tables.tables);
+    match rtables.get_mapping(face, &expr.scope, expr.mapping) {
+        Some(prefix) => {
+            let expr = prefix.expr() + expr.suffix.as_ref();
+            drop(rtables);
+            match OwnedKeyExpr::try_from(expr) {
+                Ok(key_expr) => {
+                    let mut wtables = zwrite!(tables.tables);
+                    hat_code.declare_interest(&mut wtables, face, id, key_expr, interest);
+                }
+                Err(e) => tracing::error!("Declare interest for invalid key expression: {}", e),
+            }
+        }
+        None => tracing::error!("Declare interest for unknown scope {}!", expr.scope),
+    }
+}
+
+pub(crate) fn declare_final_interest(
+    hat_code: &(dyn HatTrait + Send + Sync),
+    tables: &TablesLock,
+    face: &mut Arc<FaceState>,
+    id: InterestId,
+) {
+    tracing::trace!("Final interest {} from {}", id, face);
+    let mut wtables = zwrite!(tables.tables);
+    hat_code.declare_final_interest(&mut wtables, face, id);
+}
+
+pub(crate) fn undeclare_interest(
+    hat_code: &(dyn HatTrait + Send + Sync),
+    tables: &TablesLock,
+    face: &mut Arc<FaceState>,
+    id: InterestId,
+) {
+    tracing::debug!("Undeclare interest {} for {}", id, face);
+    let mut wtables = zwrite!(tables.tables);
+    hat_code.undeclare_interest(&mut wtables, face, id);
+}
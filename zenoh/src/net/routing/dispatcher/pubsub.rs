@@ -15,6 +15,7 @@ use super::face::FaceState;
 use super::resource::{DataRoutes, Direction, PullCaches, Resource};
 use super::tables::{NodeId, Route, RoutingExpr, Tables, TablesLock};
 use crate::net::routing::hat::HatTrait;
+use crate::net::routing::interceptor::DropReason;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -26,7 +27,7 @@ use zenoh_protocol::network::declare::Mode;
 use zenoh_protocol::{
     core::{WhatAmI, WireExpr},
     network::{declare::ext, Push},
-    zenoh::PushBody,
+    zenoh::{Del, PushBody, Put},
 };
 use zenoh_sync::get_mut_unchecked;
 
@@ -441,6 +442,29 @@ pub fn full_reentrant_route_data(
                 prefix.expr(),
                 expr.suffix.as_ref()
             );
+            match &payload {
+                PushBody::Put(Put {
+                    ext_trace: Some(trace_id),
+                    ..
+                }) => tracing::debug!(
+                    "Route put from {} for res {}{} with trace ID {}",
+                    face,
+                    prefix.expr(),
+                    expr.suffix.as_ref(),
+                    trace_id
+                ),
+                PushBody::Del(Del {
+                    ext_trace: Some(trace_id),
+                    ..
+                }) => tracing::debug!(
+                    "Route delete from {} for res {}{} with trace ID {}",
+                    face,
+                    prefix.expr(),
+                    expr.suffix.as_ref(),
+                    trace_id
+                ),
+                _ => (),
+            }
             let mut expr = RoutingExpr::new(&prefix, expr.suffix.as_ref());
 
             #[cfg(feature = "stats")]
@@ -558,6 +582,50 @@ pub fn full_reentrant_route_data(
     }
 }
 
+/// Notify the faces a sample received on `face` for `expr` would have been routed to
+/// that `count` such samples were dropped for the given `reason`.
+pub(crate) fn route_drop_notification(
+    tables_ref: &Arc<TablesLock>,
+    face: &FaceState,
+    expr: &WireExpr,
+    reason: DropReason,
+    count: usize,
+    routing_context: NodeId,
+) {
+    let tables = zread!(tables_ref.tables);
+    let Some(prefix) = tables.get_mapping(face, &expr.scope, expr.mapping).cloned() else {
+        tracing::trace!("Dropped sample with unknown scope {}", expr.scope);
+        return;
+    };
+    let mut expr = RoutingExpr::new(&prefix, expr.suffix.as_ref());
+    if !tables.hat_code.ingress_filter(&tables, face, &mut expr) {
+        return;
+    }
+    let res = Resource::get_resource(&prefix, expr.suffix);
+    let route = get_data_route(&tables, face, &res, &mut expr, routing_context)
+        .values()
+        .filter(|(outface, _key_expr, _context)| {
+            outface.id != face.id
+                && tables
+                    .hat_code
+                    .egress_filter(&tables, face, outface, &mut expr)
+        })
+        .cloned()
+        .collect::<Vec<Direction>>();
+    let key_expr = expr.full_expr().to_string();
+    drop(tables);
+    match keyexpr::new(key_expr.as_str()) {
+        Ok(key_expr) => {
+            for (outface, _key_expr, context) in route {
+                outface
+                    .primitives
+                    .send_drop_notification(key_expr, reason, count, context);
+            }
+        }
+        Err(e) => tracing::trace!("Dropped sample with invalid key expression: {}", e),
+    }
+}
+
 pub fn pull_data(tables_ref: &RwLock<Tables>, face: &Arc<FaceState>, expr: WireExpr) {
     let tables = zread!(tables_ref);
     match tables.get_mapping(face, &expr.scope, expr.mapping) {
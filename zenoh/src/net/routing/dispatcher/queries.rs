@@ -12,20 +12,21 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::face::FaceState;
-use super::resource::{QueryRoute, QueryRoutes, QueryTargetQablSet, Resource};
+use super::resource::{QueryRoute, QueryRoutes, QueryTargetQabl, QueryTargetQablSet, Resource};
 use super::tables::NodeId;
 use super::tables::{RoutingExpr, Tables, TablesLock};
 use crate::net::routing::hat::HatTrait;
 use crate::net::routing::RoutingContext;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 use zenoh_buffers::ZBuf;
-use zenoh_config::WhatAmI;
+use zenoh_config::{unwrap_or_default, Config, QueryableSelectionConf, WhatAmI};
 use zenoh_protocol::core::key_expr::keyexpr;
-use zenoh_protocol::core::KnownEncoding;
+use zenoh_protocol::core::{KnownEncoding, ZenohId};
 use zenoh_protocol::network::declare::queryable::ext::QueryableInfo;
 use zenoh_protocol::zenoh;
 use zenoh_protocol::zenoh::ext::ValueType;
@@ -34,8 +35,8 @@ use zenoh_protocol::{
     network::{
         declare::ext,
         request::{
-            ext::{BudgetType, TargetType, TimeoutType},
-            Request, RequestId,
+            ext::{BudgetType, SelectionType, TargetType, TimeoutType},
+            Request, RequestCancel, RequestId,
         },
         response::{self, ext::ResponderIdType, Response, ResponseFinal},
     },
@@ -49,6 +50,55 @@ pub(crate) struct Query {
     src_qid: RequestId,
 }
 
+/// The selection of the queryable receiving a query that targets the best matching queryable,
+/// the configured one applying to the queries that don't carry their own.
+pub(crate) struct QueryableSelector {
+    default: SelectionType,
+    next: AtomicUsize,
+    ranking: Vec<ZenohId>,
+}
+
+impl QueryableSelector {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        let default = match unwrap_or_default!(config.routing().query().selection()) {
+            QueryableSelectionConf::Nearest => SelectionType::Nearest,
+            QueryableSelectionConf::RoundRobin => SelectionType::RoundRobin,
+            QueryableSelectionConf::Ranked => SelectionType::Ranked,
+        };
+        QueryableSelector {
+            default,
+            next: AtomicUsize::new(0),
+            ranking: config.routing().query().ranking().clone(),
+        }
+    }
+
+    /// Select one of the `candidates`, sorted by distance, with the `selection` of the query
+    /// or else the configured one.
+    fn select<'a>(
+        &self,
+        selection: Option<SelectionType>,
+        mut candidates: impl Iterator<Item = &'a QueryTargetQabl>,
+    ) -> Option<&'a QueryTargetQabl> {
+        match selection.unwrap_or(self.default) {
+            SelectionType::Nearest => candidates.next(),
+            SelectionType::RoundRobin => {
+                let candidates: Vec<_> = candidates.collect();
+                if candidates.is_empty() {
+                    return None;
+                }
+                let idx = self.next.fetch_add(1, Ordering::Relaxed) % candidates.len();
+                Some(candidates[idx])
+            }
+            SelectionType::Ranked => candidates.min_by_key(|qabl| {
+                self.ranking
+                    .iter()
+                    .position(|zid| *zid == qabl.direction.0.zid)
+                    .unwrap_or(self.ranking.len())
+            }),
+        }
+    }
+}
+
 pub(crate) fn declare_queryable(
     hat_code: &(dyn HatTrait + Send + Sync),
     tables: &TablesLock,
@@ -259,6 +309,7 @@ fn compute_final_route(
     src_face: &Arc<FaceState>,
     expr: &mut RoutingExpr,
     target: &TargetType,
+    selection: Option<SelectionType>,
     query: Arc<Query>,
 ) -> QueryRoute {
     match target {
@@ -342,10 +393,12 @@ fn compute_final_route(
             route
         }
         TargetType::BestMatching => {
-            if let Some(qabl) = qabls
-                .iter()
-                .find(|qabl| qabl.direction.0.id != src_face.id && qabl.complete > 0)
-            {
+            if let Some(qabl) = tables.query_selection.select(
+                selection,
+                qabls
+                    .iter()
+                    .filter(|qabl| qabl.direction.0.id != src_face.id && qabl.complete > 0),
+            ) {
                 let mut route = HashMap::new();
                 #[cfg(feature = "complete_n")]
                 {
@@ -361,7 +414,15 @@ fn compute_final_route(
                 }
                 route
             } else {
-                compute_final_route(tables, qabls, src_face, expr, &TargetType::All, query)
+                compute_final_route(
+                    tables,
+                    qabls,
+                    src_face,
+                    expr,
+                    &TargetType::All,
+                    selection,
+                    query,
+                )
             }
         }
     }
@@ -413,12 +474,14 @@ impl Timed for QueryCleanup {
                 &self.tables,
                 &mut face,
                 self.qid,
+                response::ext::QoSType::response_default(),
                 ext_respid,
                 WireExpr::empty(),
                 ResponseBody::Err(zenoh::Err {
                     timestamp: None,
                     is_infrastructure: false,
                     ext_sinfo: None,
+                    ext_trace: None,
                     ext_unknown: vec![],
                     ext_body: Some(ValueType {
                         #[cfg(feature = "shared-memory")]
@@ -426,7 +489,7 @@ impl Timed for QueryCleanup {
                         payload: ZBuf::from("Timeout".as_bytes().to_vec()),
                         encoding: KnownEncoding::TextPlain.into(),
                     }),
-                    code: 0, // TODO
+                    code: zenoh::err::code::TIMEOUT,
                 }),
             );
             let queries_lock = zwrite!(self.tables.queries_lock);
@@ -443,6 +506,16 @@ impl Timed for QueryCleanup {
                     self.timeout,
                 );
                 finalize_pending_query(query);
+                // Let the queryable know it can stop answering.
+                face.primitives
+                    .send_request_cancel(RoutingContext::with_expr(
+                        RequestCancel {
+                            rid: self.qid,
+                            ext_qos: ext::QoSType::request_default(),
+                            ext_tstamp: None,
+                        },
+                        "".to_string(),
+                    ));
             }
         }
     }
@@ -556,6 +629,7 @@ pub fn route_query(
     ext_target: TargetType,
     ext_budget: Option<BudgetType>,
     ext_timeout: Option<TimeoutType>,
+    ext_selection: Option<SelectionType>,
     body: RequestBody,
     routing_context: NodeId,
 ) {
@@ -569,6 +643,13 @@ pub fn route_query(
                 prefix.expr(),
                 expr.suffix.as_ref(),
             );
+            if let RequestBody::Query(zenoh::Query {
+                ext_trace: Some(trace_id),
+                ..
+            }) = &body
+            {
+                tracing::debug!("Route query {}:{} with trace ID {}", face, qid, trace_id);
+            }
             let prefix = prefix.clone();
             let mut expr = RoutingExpr::new(&prefix, expr.suffix.as_ref());
 
@@ -592,8 +673,15 @@ pub fn route_query(
                 });
 
                 let queries_lock = zwrite!(tables_ref.queries_lock);
-                let route =
-                    compute_final_route(&rtables, &route, face, &mut expr, &ext_target, query);
+                let route = compute_final_route(
+                    &rtables,
+                    &route,
+                    face,
+                    &mut expr,
+                    &ext_target,
+                    ext_selection,
+                    query,
+                );
                 let local_replies =
                     rtables
                         .hat_code
@@ -614,6 +702,8 @@ pub fn route_query(
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment: None, // @TODO: expose it in the API
+                        ext_delete: None,
+                        ext_trace: None,
                         ext_unknown: vec![],
                         payload,
                     });
@@ -683,6 +773,7 @@ pub fn route_query(
                                     ext_target: *t,
                                     ext_budget,
                                     ext_timeout,
+                                    ext_selection,
                                     payload: body.clone(),
                                 },
                                 expr.full_expr().to_string(),
@@ -714,6 +805,7 @@ pub fn route_query(
                                     ext_target,
                                     ext_budget,
                                     ext_timeout,
+                                    ext_selection,
                                     payload: body.clone(),
                                 },
                                 expr.full_expr().to_string(),
@@ -760,6 +852,7 @@ pub(crate) fn route_send_response(
     tables_ref: &Arc<TablesLock>,
     face: &mut Arc<FaceState>,
     qid: RequestId,
+    ext_qos: response::ext::QoSType,
     ext_respid: Option<ResponderIdType>,
     key_expr: WireExpr,
     body: ResponseBody,
@@ -778,6 +871,30 @@ pub(crate) fn route_send_response(
         Some((query, _)) => {
             drop(queries_lock);
 
+            match &body {
+                ResponseBody::Reply(Reply {
+                    ext_trace: Some(trace_id),
+                    ..
+                }) => tracing::debug!(
+                    "Route reply {}:{} from {} with trace ID {}",
+                    query.src_face,
+                    query.src_qid,
+                    face,
+                    trace_id
+                ),
+                ResponseBody::Err(zenoh::Err {
+                    ext_trace: Some(trace_id),
+                    ..
+                }) => tracing::debug!(
+                    "Route error reply {}:{} from {} with trace ID {}",
+                    query.src_face,
+                    query.src_qid,
+                    face,
+                    trace_id
+                ),
+                _ => (),
+            }
+
             #[cfg(feature = "stats")]
             if !admin {
                 inc_res_stats!(query.src_face, tx, user, body)
@@ -794,7 +911,7 @@ pub(crate) fn route_send_response(
                         rid: query.src_qid,
                         wire_expr: key_expr.to_owned(),
                         payload: body,
-                        ext_qos: response::ext::QoSType::response_default(),
+                        ext_qos,
                         ext_tstamp: None,
                         ext_respid,
                     },
@@ -836,6 +953,53 @@ pub(crate) fn route_send_response_final(
     }
 }
 
+pub(crate) fn route_send_request_cancel(
+    tables_ref: &Arc<TablesLock>,
+    face: &Arc<FaceState>,
+    qid: RequestId,
+) {
+    let faces = zread!(tables_ref.tables)
+        .faces
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    let queries_lock = zwrite!(tables_ref.queries_lock);
+    let mut cancelled = vec![];
+    for mut outface in faces {
+        let qids = outface
+            .pending_queries
+            .iter()
+            .filter(|(_, (query, _))| query.src_face.id == face.id && query.src_qid == qid)
+            .map(|(qid, _)| *qid)
+            .collect::<Vec<_>>();
+        for qid in qids {
+            if let Some(query) = get_mut_unchecked(&mut outface).pending_queries.remove(&qid) {
+                cancelled.push((outface.clone(), qid, query));
+            }
+        }
+    }
+    drop(queries_lock);
+
+    if cancelled.is_empty() {
+        tracing::debug!("Route cancel {}:{}: Query not found!", face, qid);
+    }
+    for (outface, out_qid, (_, cancellation_token)) in cancelled {
+        // The querier already gave up on the query: cancel it without sending it a final reply.
+        cancellation_token.cancel();
+        tracing::debug!("Propagate cancel {}:{} to {}", face, qid, outface);
+        outface
+            .primitives
+            .send_request_cancel(RoutingContext::with_expr(
+                RequestCancel {
+                    rid: out_qid,
+                    ext_qos: ext::QoSType::request_default(),
+                    ext_tstamp: None,
+                },
+                "".to_string(),
+            ));
+    }
+}
+
 pub(crate) fn finalize_pending_queries(tables_ref: &TablesLock, face: &mut Arc<FaceState>) {
     let queries_lock = zwrite!(tables_ref.queries_lock);
     for (_, query) in get_mut_unchecked(face).pending_queries.drain() {
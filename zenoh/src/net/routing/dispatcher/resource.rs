@@ -36,6 +36,7 @@ use zenoh_protocol::{
     },
 };
 use zenoh_sync::get_mut_unchecked;
+use zenoh_util::{MemoryReservation, MemorySubsystem};
 
 pub(crate) type NodeId = u16;
 
@@ -176,6 +177,7 @@ pub struct Resource {
     pub(crate) childs: HashMap<String, Arc<Resource>>,
     pub(crate) context: Option<ResourceContext>,
     pub(crate) session_ctxs: HashMap<usize, Arc<SessionContext>>,
+    pub(crate) memory: Option<MemoryReservation>,
 }
 
 impl PartialEq for Resource {
@@ -192,7 +194,12 @@ impl Hash for Resource {
 }
 
 impl Resource {
-    fn new(parent: &Arc<Resource>, suffix: &str, context: Option<ResourceContext>) -> Resource {
+    fn new(
+        tables: &Tables,
+        parent: &Arc<Resource>,
+        suffix: &str,
+        context: Option<ResourceContext>,
+    ) -> Resource {
         let nonwild_prefix = match &parent.nonwild_prefix {
             None => {
                 if suffix.contains('*') {
@@ -211,6 +218,11 @@ impl Resource {
             childs: HashMap::new(),
             context,
             session_ctxs: HashMap::new(),
+            // The suffix is stored by the resource and as its key in the childs of its parent
+            memory: Some(tables.memory_budget.reserve(
+                MemorySubsystem::RoutingTables,
+                std::mem::size_of::<Resource>() + 2 * suffix.len(),
+            )),
         }
     }
 
@@ -285,6 +297,7 @@ impl Resource {
             childs: HashMap::new(),
             context: None,
             session_ctxs: HashMap::new(),
+            memory: None,
         })
     }
 
@@ -354,7 +367,7 @@ impl Resource {
             match get_mut_unchecked(from).childs.get_mut(chunk) {
                 Some(res) => Resource::make_resource(tables, res, rest),
                 None => {
-                    let mut new = Arc::new(Resource::new(from, chunk, None));
+                    let mut new = Arc::new(Resource::new(tables, from, chunk, None));
                     if tracing::enabled!(tracing::Level::DEBUG) && rest.is_empty() {
                         tracing::debug!("Register resource {}", new.expr());
                     }
@@ -379,7 +392,7 @@ impl Resource {
                     match get_mut_unchecked(from).childs.get_mut(chunk) {
                         Some(res) => Resource::make_resource(tables, res, rest),
                         None => {
-                            let mut new = Arc::new(Resource::new(from, chunk, None));
+                            let mut new = Arc::new(Resource::new(tables, from, chunk, None));
                             if tracing::enabled!(tracing::Level::DEBUG) && rest.is_empty() {
                                 tracing::debug!("Register resource {}", new.expr());
                             }
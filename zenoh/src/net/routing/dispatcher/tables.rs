@@ -31,6 +31,7 @@ use zenoh_protocol::core::{ExprId, WhatAmI, ZenohId};
 use zenoh_protocol::network::Mapping;
 use zenoh_result::ZResult;
 use zenoh_sync::get_mut_unchecked;
+use zenoh_util::MemoryBudget;
 
 pub(crate) struct RoutingExpr<'a> {
     pub(crate) prefix: &'a Arc<Resource>,
@@ -65,12 +66,14 @@ pub struct Tables {
     pub(crate) hlc: Option<Arc<HLC>>,
     pub(crate) drop_future_timestamp: bool,
     pub(crate) queries_default_timeout: Duration,
+    pub(crate) query_selection: QueryableSelector,
     pub(crate) root_res: Arc<Resource>,
     pub(crate) faces: HashMap<usize, Arc<FaceState>>,
     pub(crate) mcast_groups: Vec<Arc<FaceState>>,
     pub(crate) mcast_faces: Vec<Arc<FaceState>>,
     pub(crate) interceptors: Vec<InterceptorFactory>,
     pub(crate) pull_caches_lock: Mutex<()>,
+    pub(crate) memory_budget: Arc<MemoryBudget>,
     pub(crate) hat: Box<dyn Any + Send + Sync>,
     pub(crate) hat_code: Arc<dyn HatTrait + Send + Sync>, // @TODO make this a Box
 }
@@ -96,12 +99,14 @@ impl Tables {
             hlc,
             drop_future_timestamp,
             queries_default_timeout,
+            query_selection: QueryableSelector::from_config(config),
             root_res: Resource::root(),
             faces: HashMap::new(),
             mcast_groups: vec![],
             mcast_faces: vec![],
             interceptors: interceptor_factories(config)?,
             pull_caches_lock: Mutex::new(()),
+            memory_budget: Arc::new(MemoryBudget::unlimited()),
             hat: hat_code.new_tables(router_peers_failover_brokering),
             hat_code: hat_code.into(),
         })
@@ -0,0 +1,183 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::{face_hat, face_hat_mut, hat, hat_mut, ForwardedInterest, HatCode, HatFace, HatTables};
+use crate::net::routing::dispatcher::face::FaceState;
+use crate::net::routing::dispatcher::tables::Tables;
+use crate::net::routing::hat::HatInterestTrait;
+use crate::net::routing::router::{update_data_routes_from, update_query_routes_from};
+use crate::net::routing::RoutingContext;
+use std::sync::Arc;
+use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
+use zenoh_protocol::{
+    core::WhatAmI,
+    network::declare::{
+        common::ext::WireExprType, ext, Declare, DeclareBody, DeclareInterest, Interest,
+        InterestId, UndeclareInterest,
+    },
+};
+use zenoh_sync::get_mut_unchecked;
+
+#[inline]
+fn send_interest(dst_face: &Arc<FaceState>, interest: &ForwardedInterest) {
+    dst_face.primitives.send_declare(RoutingContext::with_expr(
+        Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body: DeclareBody::DeclareInterest(DeclareInterest {
+                id: interest.id,
+                wire_expr: interest.key_expr.to_string().into(),
+                interest: interest.interest,
+            }),
+        },
+        interest.key_expr.to_string(),
+    ));
+}
+
+#[inline]
+fn send_forget_interest(dst_face: &Arc<FaceState>, interest: &ForwardedInterest) {
+    dst_face.primitives.send_declare(RoutingContext::with_expr(
+        Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body: DeclareBody::UndeclareInterest(UndeclareInterest {
+                id: interest.id,
+                ext_wire_expr: WireExprType {
+                    wire_expr: interest.key_expr.to_string().into(),
+                },
+            }),
+        },
+        interest.key_expr.to_string(),
+    ));
+}
+
+/// The faces the interests of the local sessions are forwarded to.
+fn upstream_faces(tables: &Tables) -> Vec<Arc<FaceState>> {
+    tables
+        .faces
+        .values()
+        .filter(|face| face.whatami != WhatAmI::Client)
+        .cloned()
+        .collect()
+}
+
+/// Recompute all the routes, which depend on the key expressions covered by the interests.
+fn update_routes(tables: &mut Tables) {
+    let mut root = tables.root_res.clone();
+    update_data_routes_from(tables, &mut root);
+    update_query_routes_from(tables, &mut root);
+}
+
+/// Returns `true` if the declarations selected by `kind` matching `key_expr` are all known,
+/// i.e. if `key_expr` is included in a complete interest in them. The data and queries for
+/// the key expressions which are not covered are sent to the routers.
+pub(super) fn covered(tables: &Tables, key_expr: &keyexpr, kind: fn(&Interest) -> bool) -> bool {
+    !hat!(tables).interests
+        || tables.faces.values().any(|face| {
+            face_hat!(face).remote_interests.values().any(|interest| {
+                interest.complete
+                    && kind(&interest.interest)
+                    && interest.key_expr.includes(key_expr)
+            })
+        })
+}
+
+pub(super) fn interests_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
+    if face.whatami == WhatAmI::Client {
+        return;
+    }
+    let mut faces = tables.faces.values().cloned().collect::<Vec<_>>();
+    let mut forwarded = false;
+    for src_face in &mut faces {
+        for interest in face_hat_mut!(src_face).remote_interests.values_mut() {
+            // The declarations matching the interest are redeclared by the new face
+            interest.complete = false;
+            send_interest(face, interest);
+            forwarded = true;
+        }
+    }
+    if forwarded {
+        update_routes(tables);
+    }
+}
+
+pub(super) fn interests_remove_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
+    let interests = std::mem::take(&mut face_hat_mut!(face).remote_interests);
+    if !interests.is_empty() {
+        for dst_face in upstream_faces(tables) {
+            for interest in interests.values() {
+                send_forget_interest(&dst_face, interest);
+            }
+        }
+        update_routes(tables);
+    }
+}
+
+impl HatInterestTrait for HatCode {
+    fn declare_interest(
+        &self,
+        tables: &mut Tables,
+        face: &mut Arc<FaceState>,
+        id: InterestId,
+        key_expr: OwnedKeyExpr,
+        interest: Interest,
+    ) {
+        if !hat!(tables).interests || face.whatami != WhatAmI::Client {
+            return;
+        }
+        let forwarded = ForwardedInterest {
+            id: hat_mut!(tables).next_interest_id(),
+            key_expr,
+            interest,
+            complete: false,
+        };
+        for dst_face in upstream_faces(tables) {
+            send_interest(&dst_face, &forwarded);
+        }
+        if interest.future() {
+            face_hat_mut!(face).remote_interests.insert(id, forwarded);
+        }
+    }
+
+    fn declare_final_interest(
+        &self,
+        tables: &mut Tables,
+        _face: &mut Arc<FaceState>,
+        id: InterestId,
+    ) {
+        let mut faces = tables.faces.values().cloned().collect::<Vec<_>>();
+        let mut completed = false;
+        for src_face in &mut faces {
+            for interest in face_hat_mut!(src_face).remote_interests.values_mut() {
+                if interest.id == id && !interest.complete {
+                    interest.complete = true;
+                    completed = true;
+                }
+            }
+        }
+        if completed {
+            update_routes(tables);
+        }
+    }
+
+    fn undeclare_interest(&self, tables: &mut Tables, face: &mut Arc<FaceState>, id: InterestId) {
+        if let Some(interest) = face_hat_mut!(face).remote_interests.remove(&id) {
+            for dst_face in upstream_faces(tables) {
+                send_forget_interest(&dst_face, &interest);
+            }
+            update_routes(tables);
+        }
+    }
+}
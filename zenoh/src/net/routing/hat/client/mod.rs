@@ -28,6 +28,7 @@ use crate::{
 };
 
 use self::{
+    interests::{interests_new_face, interests_remove_face},
     pubsub::{pubsub_new_face, undeclare_client_subscription},
     queries::{queries_new_face, undeclare_client_queryable},
 };
@@ -43,16 +44,32 @@ use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
 };
-use zenoh_config::WhatAmI;
-use zenoh_protocol::network::declare::queryable::ext::QueryableInfo;
+use zenoh_config::{unwrap_or_default, WhatAmI};
+use zenoh_protocol::core::key_expr::OwnedKeyExpr;
+use zenoh_protocol::network::declare::{queryable::ext::QueryableInfo, Interest, InterestId};
 use zenoh_protocol::network::Oam;
 use zenoh_result::ZResult;
 use zenoh_sync::get_mut_unchecked;
 use zenoh_transport::unicast::TransportUnicast;
 
+mod interests;
 mod pubsub;
 mod queries;
 
+macro_rules! hat {
+    ($t:expr) => {
+        $t.hat.downcast_ref::<HatTables>().unwrap()
+    };
+}
+use hat;
+
+macro_rules! hat_mut {
+    ($t:expr) => {
+        $t.hat.downcast_mut::<HatTables>().unwrap()
+    };
+}
+use hat_mut;
+
 macro_rules! face_hat {
     ($f:expr) => {
         $f.hat.downcast_ref::<HatFace>().unwrap()
@@ -67,18 +84,34 @@ macro_rules! face_hat_mut {
 }
 use face_hat_mut;
 
-struct HatTables {}
+struct HatTables {
+    /// Whether the local sessions declare interests to the routers
+    /// instead of receiving all their declarations.
+    interests: bool,
+    next_interest_id: InterestId,
+}
 
 impl HatTables {
     fn new() -> Self {
-        Self {}
+        Self {
+            interests: false,
+            next_interest_id: 0,
+        }
+    }
+
+    fn next_interest_id(&mut self) -> InterestId {
+        self.next_interest_id = self.next_interest_id.wrapping_add(1);
+        self.next_interest_id
     }
 }
 
 pub(crate) struct HatCode {}
 
 impl HatBaseTrait for HatCode {
-    fn init(&self, _tables: &mut Tables, _runtime: Runtime) {}
+    fn init(&self, tables: &mut Tables, runtime: Runtime) {
+        let config = runtime.config().lock();
+        hat_mut!(tables).interests = unwrap_or_default!(config.routing().interests().enabled());
+    }
 
     fn new_tables(&self, _router_peers_failover_brokering: bool) -> Box<dyn Any + Send + Sync> {
         Box::new(HatTables::new())
@@ -112,11 +145,13 @@ impl HatBaseTrait for HatCode {
     ) -> ZResult<()> {
         pubsub_new_face(tables, &mut face.state);
         queries_new_face(tables, &mut face.state);
+        interests_new_face(tables, &mut face.state);
         Ok(())
     }
 
     fn close_face(&self, tables: &TablesLock, face: &mut Arc<FaceState>) {
         let mut wtables = zwrite!(tables.tables);
+        interests_remove_face(&mut wtables, face);
         let mut face_clone = face.clone();
         let face = get_mut_unchecked(face);
         for res in face.remote_mappings.values_mut() {
@@ -285,11 +320,22 @@ impl HatContext {
     }
 }
 
+/// An interest declared by a local session, forwarded to the routers.
+struct ForwardedInterest {
+    /// The ID of the interest declared to the routers.
+    id: InterestId,
+    key_expr: OwnedKeyExpr,
+    interest: Interest,
+    /// Whether the routers declared all the current declarations matching the interest.
+    complete: bool,
+}
+
 struct HatFace {
     local_subs: HashSet<Arc<Resource>>,
     remote_subs: HashSet<Arc<Resource>>,
     local_qabls: HashMap<Arc<Resource>, QueryableInfo>,
     remote_qabls: HashSet<Arc<Resource>>,
+    remote_interests: HashMap<InterestId, ForwardedInterest>,
 }
 
 impl HatFace {
@@ -299,6 +345,7 @@ impl HatFace {
             remote_subs: HashSet::new(),
             local_qabls: HashMap::new(),
             remote_qabls: HashSet::new(),
+            remote_interests: HashMap::new(),
         }
     }
 }
@@ -11,6 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use super::interests::covered;
 use super::{face_hat, face_hat_mut, get_routes_entries};
 use super::{HatCode, HatFace};
 use crate::net::routing::dispatcher::face::FaceState;
@@ -28,7 +29,7 @@ use zenoh_protocol::{
     core::{Reliability, WhatAmI},
     network::declare::{
         common::ext::WireExprType, ext, subscriber::ext::SubscriberInfo, Declare, DeclareBody,
-        DeclareSubscriber, Mode, UndeclareSubscriber,
+        DeclareSubscriber, Interest, Mode, UndeclareSubscriber,
     },
 };
 use zenoh_sync::get_mut_unchecked;
@@ -345,6 +346,16 @@ impl HatPubSubTrait for HatCode {
                 }
             }
         }
+        if source_type == WhatAmI::Client && !covered(tables, &key_expr, Interest::subscribers) {
+            for face in tables.faces.values() {
+                if face.whatami != WhatAmI::Client {
+                    route.entry(face.id).or_insert_with(|| {
+                        let key_expr = Resource::get_best_key(expr.prefix, expr.suffix, face.id);
+                        (face.clone(), key_expr.to_owned(), NodeId::default())
+                    });
+                }
+            }
+        }
         for mcast_group in &tables.mcast_groups {
             route.insert(
                 mcast_group.id,
@@ -11,6 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use super::interests::covered;
 use super::{face_hat, face_hat_mut, get_routes_entries};
 use super::{HatCode, HatFace};
 use crate::net::routing::dispatcher::face::FaceState;
@@ -31,7 +32,7 @@ use zenoh_protocol::{
     core::{WhatAmI, WireExpr},
     network::declare::{
         common::ext::WireExprType, ext, queryable::ext::QueryableInfo, Declare, DeclareBody,
-        DeclareQueryable, UndeclareQueryable,
+        DeclareQueryable, Interest, UndeclareQueryable,
     },
 };
 use zenoh_sync::get_mut_unchecked;
@@ -340,6 +341,20 @@ impl HatQueriesTrait for HatCode {
                 }
             }
         }
+        if source_type == WhatAmI::Client && !covered(tables, &key_expr, Interest::queryables) {
+            for face in tables.faces.values() {
+                if face.whatami != WhatAmI::Client
+                    && !route.iter().any(|qabl| qabl.direction.0.id == face.id)
+                {
+                    let key_expr = Resource::get_best_key(expr.prefix, expr.suffix, face.id);
+                    route.push(QueryTargetQabl {
+                        direction: (face.clone(), key_expr.to_owned(), NodeId::default()),
+                        complete: 0,
+                        distance: 0.5,
+                    });
+                }
+            }
+        }
         route.sort_by_key(|qabl| OrderedFloat(qabl.distance));
         Arc::new(route)
     }
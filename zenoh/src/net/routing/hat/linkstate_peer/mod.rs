@@ -27,7 +27,7 @@ use super::{
         face::FaceState,
         tables::{NodeId, Resource, RoutingExpr, Tables, TablesLock},
     },
-    HatBaseTrait, HatTrait,
+    HatBaseTrait, HatInterestTrait, HatTrait,
 };
 use crate::{
     net::{
@@ -526,6 +526,8 @@ fn get_peer(tables: &Tables, face: &Arc<FaceState>, nodeid: NodeId) -> Option<Ze
     }
 }
 
+impl HatInterestTrait for HatCode {}
+
 impl HatTrait for HatCode {}
 
 #[inline]
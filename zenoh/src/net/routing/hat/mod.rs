@@ -29,9 +29,11 @@ use std::{any::Any, sync::Arc};
 use zenoh_buffers::ZBuf;
 use zenoh_config::{unwrap_or_default, Config, WhatAmI, ZenohId};
 use zenoh_protocol::{
-    core::WireExpr,
+    core::{key_expr::OwnedKeyExpr, WireExpr},
     network::{
-        declare::{queryable::ext::QueryableInfo, subscriber::ext::SubscriberInfo},
+        declare::{
+            queryable::ext::QueryableInfo, subscriber::ext::SubscriberInfo, Interest, InterestId,
+        },
         Oam,
     },
 };
@@ -64,7 +66,10 @@ impl Sources {
     }
 }
 
-pub(crate) trait HatTrait: HatBaseTrait + HatPubSubTrait + HatQueriesTrait {}
+pub(crate) trait HatTrait:
+    HatBaseTrait + HatPubSubTrait + HatQueriesTrait + HatInterestTrait
+{
+}
 
 pub(crate) trait HatBaseTrait {
     fn init(&self, tables: &mut Tables, runtime: Runtime);
@@ -117,6 +122,21 @@ pub(crate) trait HatBaseTrait {
 
     fn info(&self, tables: &Tables, kind: WhatAmI) -> String;
 
+    /// The status of the routers cluster this node is part of, if any.
+    fn cluster_info(&self, _tables: &Tables) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// The members of the routers cluster this node is part of that are currently reachable.
+    fn cluster_reachable(&self, _tables: &Tables) -> Vec<ZenohId> {
+        vec![]
+    }
+
+    /// Whether the routers cluster this node is part of, if any, reaches its quorum.
+    fn cluster_quorum_reached(&self, _tables: &Tables) -> bool {
+        true
+    }
+
     fn closing(
         &self,
         tables: &mut Tables,
@@ -195,6 +215,37 @@ pub(crate) trait HatQueriesTrait {
     ) -> Vec<(WireExpr<'static>, ZBuf)>;
 }
 
+pub(crate) trait HatInterestTrait {
+    /// Handles an interest declared by `face`. The hats that propagate all their
+    /// declarations to `face` regardless of its interests ignore it.
+    fn declare_interest(
+        &self,
+        _tables: &mut Tables,
+        _face: &mut Arc<FaceState>,
+        _id: InterestId,
+        _key_expr: OwnedKeyExpr,
+        _interest: Interest,
+    ) {
+    }
+
+    /// Handles the end of the declarations sent by `face` for an interest declared to it.
+    fn declare_final_interest(
+        &self,
+        _tables: &mut Tables,
+        _face: &mut Arc<FaceState>,
+        _id: InterestId,
+    ) {
+    }
+
+    fn undeclare_interest(
+        &self,
+        _tables: &mut Tables,
+        _face: &mut Arc<FaceState>,
+        _id: InterestId,
+    ) {
+    }
+}
+
 pub(crate) fn new_hat(whatami: WhatAmI, config: &Config) -> Box<dyn HatTrait + Send + Sync> {
     match whatami {
         WhatAmI::Client => Box::new(client::HatCode {}),
@@ -41,7 +41,7 @@ use super::{
         face::FaceState,
         tables::{NodeId, Resource, RoutingExpr, Tables, TablesLock},
     },
-    HatBaseTrait, HatTrait,
+    HatBaseTrait, HatInterestTrait, HatTrait,
 };
 use std::{
     any::Any,
@@ -376,6 +376,8 @@ impl HatFace {
     }
 }
 
+impl HatInterestTrait for HatCode {}
+
 impl HatTrait for HatCode {}
 
 #[inline]
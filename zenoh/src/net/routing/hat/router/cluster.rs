@@ -0,0 +1,204 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashSet;
+use zenoh_protocol::core::ZenohId;
+
+/// The health of a routers cluster as seen from the local router.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum ClusterStatus {
+    /// All the members of the cluster are reachable.
+    Healthy,
+    /// Some members are unreachable but the quorum is still reached.
+    Degraded,
+    /// The quorum is not reached: the local router is isolated in a minority partition.
+    SplitBrain,
+}
+
+/// Tracks which members of a routers cluster are reachable through the routers
+/// linkstate network and detects split-brain situations.
+pub(super) struct Cluster {
+    name: Option<String>,
+    members: HashSet<ZenohId>,
+    quorum: usize,
+    reachable: HashSet<ZenohId>,
+    status: ClusterStatus,
+}
+
+impl Cluster {
+    pub(super) fn new(
+        zid: ZenohId,
+        name: Option<String>,
+        members: Vec<ZenohId>,
+        quorum: Option<usize>,
+    ) -> Self {
+        let mut members: HashSet<ZenohId> = members.into_iter().collect();
+        if members.insert(zid) {
+            tracing::warn!(
+                "Router {} is not listed in its own cluster members: adding it",
+                zid
+            );
+        }
+        let majority = members.len() / 2 + 1;
+        let quorum = match quorum {
+            Some(quorum) if quorum == 0 || quorum > members.len() => {
+                tracing::warn!(
+                    "Invalid cluster quorum {} for {} members: using {} instead",
+                    quorum,
+                    members.len(),
+                    majority
+                );
+                majority
+            }
+            Some(quorum) => {
+                if quorum < majority {
+                    tracing::warn!(
+                        "Cluster quorum {} is lower than the majority of members ({}): split-brain situations may go undetected",
+                        quorum,
+                        majority
+                    );
+                }
+                quorum
+            }
+            None => majority,
+        };
+        let mut cluster = Cluster {
+            name,
+            members,
+            quorum,
+            reachable: HashSet::from([zid]),
+            status: ClusterStatus::Healthy,
+        };
+        cluster.status = cluster.compute_status();
+        cluster
+    }
+
+    fn compute_status(&self) -> ClusterStatus {
+        if self.reachable.len() == self.members.len() {
+            ClusterStatus::Healthy
+        } else if self.reachable.len() >= self.quorum {
+            ClusterStatus::Degraded
+        } else {
+            ClusterStatus::SplitBrain
+        }
+    }
+
+    /// Update the set of reachable members from the set of routers currently present
+    /// in the routers linkstate network. Routers that are not members of the cluster are ignored.
+    pub(super) fn update<I: IntoIterator<Item = ZenohId>>(&mut self, routers: I) -> ClusterStatus {
+        self.reachable = routers
+            .into_iter()
+            .filter(|zid| self.members.contains(zid))
+            .collect();
+        let status = self.compute_status();
+        if status != self.status {
+            match status {
+                ClusterStatus::Healthy => tracing::info!(
+                    "Cluster {}: all {} members reachable",
+                    self.name.as_deref().unwrap_or_default(),
+                    self.members.len()
+                ),
+                ClusterStatus::Degraded => tracing::warn!(
+                    "Cluster {}: {}/{} members reachable",
+                    self.name.as_deref().unwrap_or_default(),
+                    self.reachable.len(),
+                    self.members.len()
+                ),
+                ClusterStatus::SplitBrain => tracing::error!(
+                    "Cluster {}: split-brain detected! Only {}/{} members reachable (quorum: {})",
+                    self.name.as_deref().unwrap_or_default(),
+                    self.reachable.len(),
+                    self.members.len(),
+                    self.quorum
+                ),
+            }
+            self.status = status;
+        }
+        status
+    }
+
+    pub(super) fn status(&self) -> ClusterStatus {
+        self.status
+    }
+
+    pub(super) fn reachable(&self) -> impl Iterator<Item = &ZenohId> {
+        self.reachable.iter()
+    }
+
+    pub(super) fn to_json(&self) -> serde_json::Value {
+        let mut unreachable = self
+            .members
+            .difference(&self.reachable)
+            .map(|zid| zid.to_string())
+            .collect::<Vec<_>>();
+        unreachable.sort();
+        let mut reachable = self
+            .reachable
+            .iter()
+            .map(|zid| zid.to_string())
+            .collect::<Vec<_>>();
+        reachable.sort();
+        serde_json::json!({
+            "name": self.name,
+            "status": self.status(),
+            "quorum": self.quorum,
+            "reachable": reachable,
+            "unreachable": unreachable,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn zid(id: u8) -> ZenohId {
+        ZenohId::try_from([id]).unwrap()
+    }
+
+    #[test]
+    fn cluster_status() {
+        let mut cluster = Cluster::new(zid(1), None, vec![zid(1), zid(2), zid(3)], None);
+        assert_eq!(cluster.status(), ClusterStatus::SplitBrain);
+
+        assert_eq!(
+            cluster.update(vec![zid(1), zid(2), zid(3)]),
+            ClusterStatus::Healthy
+        );
+        assert_eq!(
+            cluster.update(vec![zid(1), zid(3)]),
+            ClusterStatus::Degraded
+        );
+        // Routers that are not members do not count in the quorum.
+        assert_eq!(
+            cluster.update(vec![zid(1), zid(4), zid(5)]),
+            ClusterStatus::SplitBrain
+        );
+    }
+
+    #[test]
+    fn cluster_quorum() {
+        let mut cluster = Cluster::new(zid(1), None, vec![zid(2), zid(3), zid(4)], Some(1));
+        assert_eq!(cluster.status(), ClusterStatus::Degraded);
+        assert_eq!(
+            cluster.update(vec![zid(1), zid(2), zid(3), zid(4)]),
+            ClusterStatus::Healthy
+        );
+
+        // An invalid quorum falls back to the majority of the members.
+        let cluster = Cluster::new(zid(1), None, vec![zid(2), zid(3)], Some(4));
+        assert_eq!(cluster.quorum, 2);
+    }
+}
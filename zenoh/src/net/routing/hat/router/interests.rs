@@ -0,0 +1,98 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::pubsub::{declare_current_subscriptions, undeclare_uninteresting_subscriptions};
+use super::queries::{declare_current_queryables, undeclare_uninteresting_queryables};
+use super::{face_hat, face_hat_mut, hat, HatCode, HatFace, HatTables};
+use crate::net::routing::dispatcher::face::FaceState;
+use crate::net::routing::dispatcher::resource::Resource;
+use crate::net::routing::dispatcher::tables::Tables;
+use crate::net::routing::hat::HatInterestTrait;
+use crate::net::routing::{RoutingContext, PREFIX_LIVELINESS};
+use std::sync::Arc;
+use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
+use zenoh_protocol::{
+    core::WhatAmI,
+    network::declare::{ext, Declare, DeclareBody, FinalInterest, Interest, InterestId},
+};
+use zenoh_sync::get_mut_unchecked;
+
+/// Returns `true` if the declarations of `res` selected by `kind` must be propagated to `face`,
+/// i.e. unless `face` is a client which declares interests but none in them. The clients which
+/// never declared any interest, e.g. with interests disabled, receive all the declarations.
+pub(super) fn interested(
+    tables: &Tables,
+    face: &FaceState,
+    res: &Arc<Resource>,
+    kind: fn(&Interest) -> bool,
+) -> bool {
+    if !hat!(tables).client_interests
+        || face.whatami != WhatAmI::Client
+        || !face_hat!(face).declares_interests
+    {
+        return true;
+    }
+    let expr = res.expr();
+    // Liveliness tokens are looked up by the clients without declaring any interest
+    expr.starts_with(PREFIX_LIVELINESS)
+        || keyexpr::new(expr.as_str()).is_ok_and(|res_expr| {
+            face_hat!(face)
+                .remote_interests
+                .values()
+                .any(|(key_expr, interest)| {
+                    interest.future() && kind(interest) && key_expr.intersects(res_expr)
+                })
+        })
+}
+
+impl HatInterestTrait for HatCode {
+    fn declare_interest(
+        &self,
+        tables: &mut Tables,
+        face: &mut Arc<FaceState>,
+        id: InterestId,
+        key_expr: OwnedKeyExpr,
+        interest: Interest,
+    ) {
+        if interest.future() {
+            face_hat_mut!(face)
+                .remote_interests
+                .insert(id, (key_expr.clone(), interest));
+        }
+        if !face_hat!(face).declares_interests {
+            // The face received all the declarations until its first interest: the ones it is
+            // not interested in are withdrawn
+            face_hat_mut!(face).declares_interests = true;
+            undeclare_uninteresting_subscriptions(tables, face);
+            undeclare_uninteresting_queryables(tables, face);
+        }
+        if interest.current() {
+            if interest.subscribers() {
+                declare_current_subscriptions(tables, face, &key_expr);
+            }
+            if interest.queryables() {
+                declare_current_queryables(tables, face, &key_expr);
+            }
+            face.primitives.send_declare(RoutingContext::new(Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::FinalInterest(FinalInterest { id }),
+            }));
+        }
+    }
+
+    fn undeclare_interest(&self, _tables: &mut Tables, face: &mut Arc<FaceState>, id: InterestId) {
+        face_hat_mut!(face).remote_interests.remove(&id);
+    }
+}
@@ -18,6 +18,7 @@
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 use self::{
+    cluster::{Cluster, ClusterStatus},
     network::{shared_nodes, Network},
     pubsub::{
         pubsub_linkstate_change, pubsub_new_face, pubsub_remove_node, undeclare_client_subscription,
@@ -57,13 +58,20 @@ use std::{
 use zenoh_config::{unwrap_or_default, ModeDependent, WhatAmI, WhatAmIMatcher, ZenohId};
 use zenoh_protocol::{
     common::ZExtBody,
-    network::{declare::queryable::ext::QueryableInfo, oam::id::OAM_LINKSTATE, Oam},
+    core::key_expr::OwnedKeyExpr,
+    network::{
+        declare::{queryable::ext::QueryableInfo, Interest, InterestId},
+        oam::id::OAM_LINKSTATE,
+        Oam,
+    },
 };
 use zenoh_result::ZResult;
 use zenoh_sync::get_mut_unchecked;
 use zenoh_task::TerminatableTask;
 use zenoh_transport::unicast::TransportUnicast;
 
+mod cluster;
+mod interests;
 mod network;
 mod pubsub;
 mod queries;
@@ -125,6 +133,10 @@ struct HatTables {
     routers_trees_task: Option<TerminatableTask>,
     peers_trees_task: Option<TerminatableTask>,
     router_peers_failover_brokering: bool,
+    cluster: Option<Cluster>,
+    /// Whether the declarations are only propagated to the clients
+    /// for the key expressions they declared an interest in.
+    client_interests: bool,
 }
 
 impl Drop for HatTables {
@@ -153,6 +165,14 @@ impl HatTables {
             routers_trees_task: None,
             peers_trees_task: None,
             router_peers_failover_brokering,
+            cluster: None,
+            client_interests: false,
+        }
+    }
+
+    fn update_cluster(&mut self) {
+        if let (Some(cluster), Some(net)) = (self.cluster.as_mut(), self.routers_net.as_ref()) {
+            cluster.update(net.graph.node_weights().map(|node| node.zid));
         }
     }
 
@@ -280,6 +300,10 @@ impl HatTables {
                     pubsub::pubsub_tree_change(&mut tables, &new_childs, net_type);
                     queries::queries_tree_change(&mut tables, &new_childs, net_type);
 
+                    if net_type == WhatAmI::Router {
+                        hat_mut!(tables).update_cluster();
+                    }
+
                     tracing::trace!("Computations completed");
                     match net_type {
                         WhatAmI::Router => hat_mut!(tables).routers_trees_task = None,
@@ -315,7 +339,21 @@ impl HatBaseTrait for HatCode {
             && unwrap_or_default!(config.routing().peer().mode()) == *"linkstate";
         let router_peers_failover_brokering =
             unwrap_or_default!(config.routing().router().peers_failover_brokering());
+        let cluster = (whatami == WhatAmI::Router
+            && unwrap_or_default!(config.routing().router().cluster().enabled()))
+        .then(|| {
+            let cluster = config.routing().router().cluster();
+            Cluster::new(
+                tables.zid,
+                cluster.name().clone(),
+                cluster.members().clone(),
+                *cluster.quorum(),
+            )
+        });
+        let client_interests = unwrap_or_default!(config.routing().interests().enabled());
         drop(config);
+        hat_mut!(tables).cluster = cluster;
+        hat_mut!(tables).client_interests = client_interests;
 
         if router_full_linkstate | gossip {
             hat_mut!(tables).routers_net = Some(Network::new(
@@ -750,6 +788,27 @@ impl HatBaseTrait for HatCode {
         false
     }
 
+    fn cluster_info(&self, tables: &Tables) -> Option<serde_json::Value> {
+        hat!(tables)
+            .cluster
+            .as_ref()
+            .map(|cluster| cluster.to_json())
+    }
+
+    fn cluster_reachable(&self, tables: &Tables) -> Vec<ZenohId> {
+        hat!(tables)
+            .cluster
+            .as_ref()
+            .map(|cluster| cluster.reachable().copied().collect())
+            .unwrap_or_default()
+    }
+
+    fn cluster_quorum_reached(&self, tables: &Tables) -> bool {
+        hat!(tables).cluster.as_ref().map_or(true, |cluster| {
+            cluster.status() != ClusterStatus::SplitBrain
+        })
+    }
+
     fn info(&self, tables: &Tables, kind: WhatAmI) -> String {
         match kind {
             WhatAmI::Router => hat!(tables)
@@ -791,6 +850,10 @@ struct HatFace {
     remote_subs: HashSet<Arc<Resource>>,
     local_qabls: HashMap<Arc<Resource>, QueryableInfo>,
     remote_qabls: HashSet<Arc<Resource>>,
+    remote_interests: HashMap<InterestId, (OwnedKeyExpr, Interest)>,
+    /// Whether the face declared interests, thus only receives the declarations it is
+    /// interested in.
+    declares_interests: bool,
 }
 
 impl HatFace {
@@ -801,6 +864,8 @@ impl HatFace {
             remote_subs: HashSet::new(),
             local_qabls: HashMap::new(),
             remote_qabls: HashSet::new(),
+            remote_interests: HashMap::new(),
+            declares_interests: false,
         }
     }
 }
@@ -11,6 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use super::interests::interested;
 use super::network::Network;
 use super::{face_hat, face_hat_mut, get_routes_entries, hat, hat_mut, res_hat, res_hat_mut};
 use super::{get_peer, get_router, HatCode, HatContext, HatFace, HatTables};
@@ -26,12 +27,12 @@ use petgraph::graph::NodeIndex;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use zenoh_protocol::core::key_expr::OwnedKeyExpr;
+use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
 use zenoh_protocol::{
     core::{Reliability, WhatAmI, ZenohId},
     network::declare::{
         common::ext::WireExprType, ext, subscriber::ext::SubscriberInfo, Declare, DeclareBody,
-        DeclareSubscriber, Mode, UndeclareSubscriber,
+        DeclareSubscriber, Interest, Mode, UndeclareSubscriber,
     },
 };
 use zenoh_sync::get_mut_unchecked;
@@ -98,6 +99,7 @@ fn propagate_simple_subscription_to(
                     || dst_face.whatami != WhatAmI::Peer
                     || hat!(tables).failover_brokering(src_face.zid, dst_face.zid))
         }
+        && interested(tables, dst_face, res, Interest::subscribers)
     {
         face_hat_mut!(dst_face).local_subs.insert(res.clone());
         let key_expr = Resource::decl_key(res, dst_face);
@@ -622,6 +624,9 @@ pub(super) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
 
     if face.whatami == WhatAmI::Client {
         for sub in &hat!(tables).router_subs {
+            if !interested(tables, face, sub, Interest::subscribers) {
+                continue;
+            }
             face_hat_mut!(face).local_subs.insert(sub.clone());
             let key_expr = Resource::decl_key(sub, face);
             face.primitives.send_declare(RoutingContext::with_expr(
@@ -669,6 +674,71 @@ pub(super) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
     }
 }
 
+/// Declare to `face` the known subscriptions matching `key_expr` it was not declared yet.
+pub(super) fn declare_current_subscriptions(
+    tables: &mut Tables,
+    face: &mut Arc<FaceState>,
+    key_expr: &keyexpr,
+) {
+    let sub_info = SubscriberInfo {
+        reliability: Reliability::Reliable, // @TODO compute proper reliability to propagate from reliability of known subscribers
+        mode: Mode::Push,
+    };
+    for sub in &hat!(tables).router_subs {
+        if !face_hat!(face).local_subs.contains(sub)
+            && keyexpr::new(sub.expr().as_str()).is_ok_and(|expr| expr.intersects(key_expr))
+            && (remote_router_subs(tables, sub)
+                || remote_peer_subs(tables, sub)
+                || client_subs(sub).iter().any(|client| client.id != face.id))
+        {
+            face_hat_mut!(face).local_subs.insert(sub.clone());
+            let key_expr = Resource::decl_key(sub, face);
+            face.primitives.send_declare(RoutingContext::with_expr(
+                Declare {
+                    ext_qos: ext::QoSType::declare_default(),
+                    ext_tstamp: None,
+                    ext_nodeid: ext::NodeIdType::default(),
+                    body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                        id: 0, // @TODO use proper SubscriberId (#703)
+                        wire_expr: key_expr,
+                        ext_info: sub_info,
+                    }),
+                },
+                sub.expr(),
+            ));
+        }
+    }
+}
+
+/// Undeclare to `face` the subscriptions it was declared but is not interested in.
+pub(super) fn undeclare_uninteresting_subscriptions(
+    tables: &mut Tables,
+    face: &mut Arc<FaceState>,
+) {
+    let subs: Vec<Arc<Resource>> = face_hat!(face)
+        .local_subs
+        .iter()
+        .filter(|sub| !interested(tables, face, sub, Interest::subscribers))
+        .cloned()
+        .collect();
+    for sub in subs {
+        let wire_expr = Resource::get_best_key(&sub, "", face.id);
+        face.primitives.send_declare(RoutingContext::with_expr(
+            Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::UndeclareSubscriber(UndeclareSubscriber {
+                    id: 0, // @TODO use proper SubscriberId (#703)
+                    ext_wire_expr: WireExprType { wire_expr },
+                }),
+            },
+            sub.expr(),
+        ));
+        face_hat_mut!(face).local_subs.remove(&sub);
+    }
+}
+
 pub(super) fn pubsub_remove_node(tables: &mut Tables, node: &ZenohId, net_type: WhatAmI) {
     match net_type {
         WhatAmI::Router => {
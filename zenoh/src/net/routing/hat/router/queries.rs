@@ -11,6 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use super::interests::interested;
 use super::network::Network;
 use super::{face_hat, face_hat_mut, get_routes_entries, hat, hat_mut, res_hat, res_hat_mut};
 use super::{get_peer, get_router, HatCode, HatContext, HatFace, HatTables};
@@ -29,12 +30,12 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use zenoh_buffers::ZBuf;
 use zenoh_protocol::core::key_expr::include::{Includer, DEFAULT_INCLUDER};
-use zenoh_protocol::core::key_expr::OwnedKeyExpr;
+use zenoh_protocol::core::key_expr::{keyexpr, OwnedKeyExpr};
 use zenoh_protocol::{
     core::{WhatAmI, WireExpr, ZenohId},
     network::declare::{
         common::ext::WireExprType, ext, queryable::ext::QueryableInfo, Declare, DeclareBody,
-        DeclareQueryable, UndeclareQueryable,
+        DeclareQueryable, Interest, UndeclareQueryable,
     },
 };
 use zenoh_sync::get_mut_unchecked;
@@ -251,6 +252,7 @@ fn propagate_simple_queryable(
                         || hat!(tables)
                             .failover_brokering(src_face.as_ref().unwrap().zid, dst_face.zid))
             }
+            && interested(tables, &dst_face, res, Interest::queryables)
         {
             face_hat_mut!(&mut dst_face)
                 .local_qabls
@@ -752,7 +754,7 @@ fn forget_client_queryable(
 pub(super) fn queries_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
     if face.whatami == WhatAmI::Client {
         for qabl in hat!(tables).router_qabls.iter() {
-            if qabl.context.is_some() {
+            if qabl.context.is_some() && interested(tables, face, qabl, Interest::queryables) {
                 let info = local_qabl_info(tables, qabl, face);
                 face_hat_mut!(face).local_qabls.insert(qabl.clone(), info);
                 let key_expr = Resource::decl_key(qabl, face);
@@ -803,6 +805,66 @@ pub(super) fn queries_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
     }
 }
 
+/// Declare to `face` the known queryables matching `key_expr` it was not declared yet.
+pub(super) fn declare_current_queryables(
+    tables: &mut Tables,
+    face: &mut Arc<FaceState>,
+    key_expr: &keyexpr,
+) {
+    for qabl in hat!(tables).router_qabls.iter() {
+        if qabl.context.is_some()
+            && !face_hat!(face).local_qabls.contains_key(qabl)
+            && keyexpr::new(qabl.expr().as_str()).is_ok_and(|expr| expr.intersects(key_expr))
+            && (remote_router_qabls(tables, qabl)
+                || remote_peer_qabls(tables, qabl)
+                || client_qabls(qabl).iter().any(|client| client.id != face.id))
+        {
+            let info = local_qabl_info(tables, qabl, face);
+            face_hat_mut!(face).local_qabls.insert(qabl.clone(), info);
+            let key_expr = Resource::decl_key(qabl, face);
+            face.primitives.send_declare(RoutingContext::with_expr(
+                Declare {
+                    ext_qos: ext::QoSType::declare_default(),
+                    ext_tstamp: None,
+                    ext_nodeid: ext::NodeIdType::default(),
+                    body: DeclareBody::DeclareQueryable(DeclareQueryable {
+                        id: 0, // @TODO use proper QueryableId (#703)
+                        wire_expr: key_expr,
+                        ext_info: info,
+                    }),
+                },
+                qabl.expr(),
+            ));
+        }
+    }
+}
+
+/// Undeclare to `face` the queryables it was declared but is not interested in.
+pub(super) fn undeclare_uninteresting_queryables(tables: &mut Tables, face: &mut Arc<FaceState>) {
+    let qabls: Vec<Arc<Resource>> = face_hat!(face)
+        .local_qabls
+        .keys()
+        .filter(|qabl| !interested(tables, face, qabl, Interest::queryables))
+        .cloned()
+        .collect();
+    for qabl in qabls {
+        let wire_expr = Resource::get_best_key(&qabl, "", face.id);
+        face.primitives.send_declare(RoutingContext::with_expr(
+            Declare {
+                ext_qos: ext::QoSType::declare_default(),
+                ext_tstamp: None,
+                ext_nodeid: ext::NodeIdType::default(),
+                body: DeclareBody::UndeclareQueryable(UndeclareQueryable {
+                    id: 0, // @TODO use proper QueryableId (#703)
+                    ext_wire_expr: WireExprType { wire_expr },
+                }),
+            },
+            qabl.expr(),
+        ));
+        face_hat_mut!(face).local_qabls.remove(&qabl);
+    }
+}
+
 pub(super) fn queries_remove_node(tables: &mut Tables, node: &ZenohId, net_type: WhatAmI) {
     match net_type {
         WhatAmI::Router => {
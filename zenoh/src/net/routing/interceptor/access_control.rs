@@ -19,8 +19,8 @@
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 
 use super::{
-    authorization::PolicyEnforcer, EgressInterceptor, IngressInterceptor, InterceptorFactory,
-    InterceptorFactoryTrait, InterceptorTrait,
+    authorization::PolicyEnforcer, DropReason, EgressInterceptor, IngressInterceptor,
+    InterceptorFactory, InterceptorFactoryTrait, InterceptorTrait,
 };
 use crate::net::routing::RoutingContext;
 use crate::KeyExpr;
@@ -37,18 +37,18 @@ pub struct AclEnforcer {
     enforcer: Arc<PolicyEnforcer>,
 }
 #[derive(Clone, Debug)]
-pub struct Interface {
+pub struct AuthSubject {
     id: usize,
     name: String,
 }
 struct EgressAclEnforcer {
     policy_enforcer: Arc<PolicyEnforcer>,
-    interface_list: Vec<Interface>,
+    subject_list: Vec<AuthSubject>,
     zid: ZenohId,
 }
 struct IngressAclEnforcer {
     policy_enforcer: Arc<PolicyEnforcer>,
-    interface_list: Vec<Interface>,
+    subject_list: Vec<AuthSubject>,
     zid: ZenohId,
 }
 
@@ -75,6 +75,22 @@ pub(crate) fn acl_interceptor_factories(
     Ok(res)
 }
 
+/// The `<claim>=<value>` subjects of a token claim, one per element if the claim is an array.
+#[cfg(feature = "auth_token")]
+fn token_claim_subjects(name: &str, value: &serde_json::Value) -> Vec<Subject> {
+    use serde_json::Value;
+
+    let subject = |value: &Value| match value {
+        Value::String(s) => Some(Subject::TokenClaim(format!("{name}={s}"))),
+        Value::Number(_) | Value::Bool(_) => Some(Subject::TokenClaim(format!("{name}={value}"))),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    };
+    match value {
+        Value::Array(values) => values.iter().filter_map(subject).collect(),
+        value => subject(value).into_iter().collect(),
+    }
+}
+
 impl InterceptorFactoryTrait for AclEnforcer {
     fn new_transport_unicast(
         &self,
@@ -82,19 +98,18 @@ impl InterceptorFactoryTrait for AclEnforcer {
     ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
         match transport.get_zid() {
             Ok(zid) => {
-                let mut interface_list: Vec<Interface> = Vec::new();
+                let mut subjects: Vec<Subject> = Vec::new();
                 match transport.get_links() {
                     Ok(links) => {
                         for link in links {
-                            let enforcer = self.enforcer.clone();
                             for face in link.interfaces {
-                                let subject = &Subject::Interface(face.clone());
-                                if let Some(val) = enforcer.subject_map.get(subject) {
-                                    interface_list.push(Interface {
-                                        id: *val,
-                                        name: face,
-                                    });
-                                }
+                                subjects.push(Subject::Interface(face));
+                            }
+                            if let Some(cert_common_name) = link.cert_common_name {
+                                subjects.push(Subject::CertCommonName(cert_common_name));
+                            }
+                            for name in link.cert_subject_alt_names {
+                                subjects.push(Subject::CertSubjectAltName(name));
                             }
                         }
                     }
@@ -103,14 +118,55 @@ impl InterceptorFactoryTrait for AclEnforcer {
                         return (None, None);
                     }
                 }
+                match transport.get_username() {
+                    Ok(Some(username)) => subjects.push(Subject::Username(username)),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::error!("Couldn't get username with error: {}", e);
+                        return (None, None);
+                    }
+                }
+                #[cfg(feature = "auth_token")]
+                match transport.get_token_claims() {
+                    Ok(claims) => subjects.extend(
+                        claims
+                            .iter()
+                            .flatten()
+                            .flat_map(|(name, value)| token_claim_subjects(name, value)),
+                    ),
+                    Err(e) => {
+                        tracing::error!("Couldn't get token claims with error: {}", e);
+                        return (None, None);
+                    }
+                }
+                subjects.push(Subject::Zid(zid));
+
+                let mut subject_list: Vec<AuthSubject> = Vec::new();
+                for subject in subjects {
+                    if let Some(val) = self.enforcer.subject_map.get(&subject) {
+                        if subject_list.iter().all(|s| s.id != *val) {
+                            subject_list.push(AuthSubject {
+                                id: *val,
+                                name: match subject {
+                                    Subject::Interface(name)
+                                    | Subject::CertCommonName(name)
+                                    | Subject::CertSubjectAltName(name)
+                                    | Subject::Username(name)
+                                    | Subject::TokenClaim(name) => name,
+                                    Subject::Zid(zid) => zid.to_string(),
+                                },
+                            });
+                        }
+                    }
+                }
                 let ingress_interceptor = Box::new(IngressAclEnforcer {
                     policy_enforcer: self.enforcer.clone(),
-                    interface_list: interface_list.clone(),
+                    subject_list: subject_list.clone(),
                     zid,
                 });
                 let egress_interceptor = Box::new(EgressAclEnforcer {
                     policy_enforcer: self.enforcer.clone(),
-                    interface_list: interface_list.clone(),
+                    subject_list: subject_list.clone(),
                     zid,
                 });
                 match (
@@ -211,6 +267,10 @@ impl InterceptorTrait for IngressAclEnforcer {
         }
         Some(ctx)
     }
+
+    fn drop_reason(&self) -> Option<DropReason> {
+        Some(DropReason::AccessControl)
+    }
 }
 
 impl InterceptorTrait for EgressAclEnforcer {
@@ -279,56 +339,59 @@ impl InterceptorTrait for EgressAclEnforcer {
         }
         Some(ctx)
     }
+
+    fn drop_reason(&self) -> Option<DropReason> {
+        Some(DropReason::AccessControl)
+    }
 }
 pub trait AclActionMethods {
     fn policy_enforcer(&self) -> Arc<PolicyEnforcer>;
-    fn interface_list(&self) -> Vec<Interface>;
+    fn subject_list(&self) -> Vec<AuthSubject>;
     fn zid(&self) -> ZenohId;
     fn flow(&self) -> InterceptorFlow;
     fn action(&self, action: Action, log_msg: &str, key_expr: &str) -> Permission {
         let policy_enforcer = self.policy_enforcer();
-        let interface_list = self.interface_list();
+        let subject_list = self.subject_list();
         let zid = self.zid();
-        let mut decision = policy_enforcer.default_permission;
-        for subject in &interface_list {
-            match policy_enforcer.policy_decision_point(subject.id, self.flow(), action, key_expr) {
-                Ok(Permission::Allow) => {
-                    tracing::trace!(
-                        "{} on {} is authorized to {} on {}",
-                        zid,
-                        subject.name,
-                        log_msg,
-                        key_expr
-                    );
-                    decision = Permission::Allow;
-                    break;
-                }
-                Ok(Permission::Deny) => {
-                    tracing::debug!(
-                        "{} on {} is unauthorized to {} on {}",
-                        zid,
-                        subject.name,
-                        log_msg,
-                        key_expr
-                    );
-
-                    decision = Permission::Deny;
-                    continue;
-                }
-                Err(e) => {
-                    tracing::debug!(
-                        "{} on {} has an authorization error to {} on {}: {}",
-                        zid,
-                        subject.name,
-                        log_msg,
-                        key_expr,
-                        e
-                    );
-                    return Permission::Deny;
-                }
+        let subjects = subject_list.iter().map(|s| s.id).collect::<Vec<_>>();
+        let names = subject_list
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match policy_enforcer.policy_decision_point(&subjects, self.flow(), action, key_expr) {
+            Ok(Permission::Allow) => {
+                tracing::trace!(
+                    "{} as [{}] is authorized to {} on {}",
+                    zid,
+                    names,
+                    log_msg,
+                    key_expr
+                );
+                Permission::Allow
+            }
+            Ok(Permission::Deny) => {
+                tracing::debug!(
+                    "{} as [{}] is unauthorized to {} on {}",
+                    zid,
+                    names,
+                    log_msg,
+                    key_expr
+                );
+                Permission::Deny
+            }
+            Err(e) => {
+                tracing::debug!(
+                    "{} as [{}] has an authorization error to {} on {}: {}",
+                    zid,
+                    names,
+                    log_msg,
+                    key_expr,
+                    e
+                );
+                Permission::Deny
             }
         }
-        decision
     }
 }
 
@@ -337,8 +400,8 @@ impl AclActionMethods for EgressAclEnforcer {
         self.policy_enforcer.clone()
     }
 
-    fn interface_list(&self) -> Vec<Interface> {
-        self.interface_list.clone()
+    fn subject_list(&self) -> Vec<AuthSubject> {
+        self.subject_list.clone()
     }
 
     fn zid(&self) -> ZenohId {
@@ -354,8 +417,8 @@ impl AclActionMethods for IngressAclEnforcer {
         self.policy_enforcer.clone()
     }
 
-    fn interface_list(&self) -> Vec<Interface> {
-        self.interface_list.clone()
+    fn subject_list(&self) -> Vec<AuthSubject> {
+        self.subject_list.clone()
     }
 
     fn zid(&self) -> ZenohId {
@@ -111,6 +111,7 @@ pub struct InterfaceEnabled {
 pub struct PolicyEnforcer {
     pub(crate) acl_enabled: bool,
     pub(crate) default_permission: Permission,
+    pub(crate) deny_overrides: bool,
     pub(crate) subject_map: SubjectMap,
     pub(crate) policy_map: PolicyMap,
     pub(crate) interface_enabled: InterfaceEnabled,
@@ -127,6 +128,7 @@ impl PolicyEnforcer {
         PolicyEnforcer {
             acl_enabled: true,
             default_permission: Permission::Deny,
+            deny_overrides: false,
             subject_map: SubjectMap::default(),
             policy_map: PolicyMap::default(),
             interface_enabled: InterfaceEnabled::default(),
@@ -140,6 +142,7 @@ impl PolicyEnforcer {
         let mut_acl_config = acl_config.clone();
         self.acl_enabled = mut_acl_config.enabled;
         self.default_permission = mut_acl_config.default_permission;
+        self.deny_overrides = mut_acl_config.deny_overrides;
         if self.acl_enabled {
             if let Some(mut rules) = mut_acl_config.rules {
                 if rules.is_empty() {
@@ -155,15 +158,18 @@ impl PolicyEnforcer {
                 } else {
                     // check for undefined values in rules and initialize them to defaults
                     for (rule_offset, rule) in rules.iter_mut().enumerate() {
-                        match rule.interfaces {
-                            Some(_) => (),
-                            None => {
-                                tracing::warn!("ACL config interfaces list is empty. Applying rule #{} to all network interfaces", rule_offset);
-                                if let Ok(all_interfaces) =
-                                    get_interface_names_by_addr(Ipv4Addr::UNSPECIFIED.into())
-                                {
-                                    rule.interfaces = Some(all_interfaces);
-                                }
+                        if rule.interfaces.is_none()
+                            && rule.cert_common_names.is_none()
+                            && rule.cert_subject_alt_names.is_none()
+                            && rule.usernames.is_none()
+                            && rule.token_claims.is_none()
+                            && rule.zids.is_none()
+                        {
+                            tracing::warn!("ACL config subjects lists are empty. Applying rule #{} to all network interfaces", rule_offset);
+                            if let Ok(all_interfaces) =
+                                get_interface_names_by_addr(Ipv4Addr::UNSPECIFIED.into())
+                            {
+                                rule.interfaces = Some(all_interfaces);
                             }
                         }
                         match rule.flows {
@@ -227,9 +233,48 @@ impl PolicyEnforcer {
         for config_rule in config_rule_set {
             // config validation
             let mut validation_err = String::new();
-            if config_rule.interfaces.as_ref().unwrap().is_empty() {
+            if config_rule
+                .interfaces
+                .as_ref()
+                .is_some_and(|interfaces| interfaces.is_empty())
+            {
                 validation_err.push_str("ACL config interfaces list is empty. ");
             }
+            if config_rule
+                .cert_common_names
+                .as_ref()
+                .is_some_and(|cert_common_names| cert_common_names.is_empty())
+            {
+                validation_err.push_str("ACL config cert_common_names list is empty. ");
+            }
+            if config_rule
+                .cert_subject_alt_names
+                .as_ref()
+                .is_some_and(|cert_subject_alt_names| cert_subject_alt_names.is_empty())
+            {
+                validation_err.push_str("ACL config cert_subject_alt_names list is empty. ");
+            }
+            if config_rule
+                .usernames
+                .as_ref()
+                .is_some_and(|usernames| usernames.is_empty())
+            {
+                validation_err.push_str("ACL config usernames list is empty. ");
+            }
+            if config_rule
+                .token_claims
+                .as_ref()
+                .is_some_and(|token_claims| token_claims.is_empty())
+            {
+                validation_err.push_str("ACL config token_claims list is empty. ");
+            }
+            if config_rule
+                .zids
+                .as_ref()
+                .is_some_and(|zids| zids.is_empty())
+            {
+                validation_err.push_str("ACL config zids list is empty. ");
+            }
             if config_rule.actions.is_empty() {
                 validation_err.push_str("ACL config actions list is empty. ");
             }
@@ -242,10 +287,45 @@ impl PolicyEnforcer {
             if !validation_err.is_empty() {
                 bail!("{}", validation_err);
             }
-            for subject in config_rule.interfaces.as_ref().unwrap() {
-                if subject.trim().is_empty() {
+            let mut subjects: Vec<Subject> = Vec::new();
+            for interface in config_rule.interfaces.iter().flatten() {
+                if interface.trim().is_empty() {
                     bail!("found an empty interface value in interfaces list");
                 }
+                subjects.push(Subject::Interface(interface.clone()));
+            }
+            for cert_common_name in config_rule.cert_common_names.iter().flatten() {
+                if cert_common_name.trim().is_empty() {
+                    bail!("found an empty value in cert_common_names list");
+                }
+                subjects.push(Subject::CertCommonName(cert_common_name.clone()));
+            }
+            for cert_subject_alt_name in config_rule.cert_subject_alt_names.iter().flatten() {
+                if cert_subject_alt_name.trim().is_empty() {
+                    bail!("found an empty value in cert_subject_alt_names list");
+                }
+                subjects.push(Subject::CertSubjectAltName(cert_subject_alt_name.clone()));
+            }
+            for username in config_rule.usernames.iter().flatten() {
+                if username.trim().is_empty() {
+                    bail!("found an empty value in usernames list");
+                }
+                subjects.push(Subject::Username(username.clone()));
+            }
+            for token_claim in config_rule.token_claims.iter().flatten() {
+                match token_claim.split_once('=') {
+                    Some((name, value)) if !name.trim().is_empty() && !value.trim().is_empty() => {
+                        subjects.push(Subject::TokenClaim(token_claim.clone()))
+                    }
+                    _ => bail!(
+                        "found an invalid value in token_claims list, expected `<claim>=<value>`"
+                    ),
+                }
+            }
+            for zid in config_rule.zids.iter().flatten() {
+                subjects.push(Subject::Zid(*zid));
+            }
+            for subject in subjects {
                 for flow in config_rule.flows.as_ref().unwrap() {
                     for action in &config_rule.actions {
                         for key_expr in &config_rule.key_exprs {
@@ -253,7 +333,7 @@ impl PolicyEnforcer {
                                 bail!("found an empty key-expression value in key_exprs list");
                             }
                             policy_rules.push(PolicyRule {
-                                subject: Subject::Interface(subject.clone()),
+                                subject: subject.clone(),
                                 key_expr: key_expr.clone(),
                                 action: *action,
                                 permission: config_rule.permission,
@@ -281,45 +361,47 @@ impl PolicyEnforcer {
 
     /*
        checks each msg against the ACL ruleset for allow/deny
+       the msg is allowed as soon as one of the subjects allows it, unless deny_overrides is set
+       in which case a deny from any of the subjects prevails over an allow from the others
     */
 
     pub fn policy_decision_point(
         &self,
-        subject: usize,
+        subjects: &[usize],
         flow: InterceptorFlow,
         action: Action,
         key_expr: &str,
     ) -> ZResult<Permission> {
-        let policy_map = &self.policy_map;
-        match policy_map.get(&subject) {
-            Some(single_policy) => {
-                let deny_result = single_policy
-                    .flow(flow)
-                    .action(action)
-                    .deny
-                    .nodes_including(keyexpr::new(&key_expr)?)
-                    .count();
-                if deny_result != 0 {
+        let key_expr = keyexpr::new(key_expr)?;
+        let policies = subjects
+            .iter()
+            .filter_map(|subject| self.policy_map.get(subject))
+            .map(|single_policy| single_policy.flow(flow).action(action));
+        if self.deny_overrides {
+            let mut allowed = false;
+            for policy in policies {
+                if policy.deny.nodes_including(key_expr).count() != 0 {
                     return Ok(Permission::Deny);
                 }
-                if self.default_permission == Permission::Allow {
-                    Ok(Permission::Allow)
-                } else {
-                    let allow_result = single_policy
-                        .flow(flow)
-                        .action(action)
-                        .allow
-                        .nodes_including(keyexpr::new(&key_expr)?)
-                        .count();
-
-                    if allow_result != 0 {
-                        Ok(Permission::Allow)
-                    } else {
-                        Ok(Permission::Deny)
-                    }
+                allowed = allowed || policy.allow.nodes_including(key_expr).count() != 0;
+            }
+            if allowed {
+                Ok(Permission::Allow)
+            } else {
+                Ok(self.default_permission)
+            }
+        } else {
+            let mut decision = self.default_permission;
+            for policy in policies {
+                if policy.deny.nodes_including(key_expr).count() == 0
+                    && (self.default_permission == Permission::Allow
+                        || policy.allow.nodes_including(key_expr).count() != 0)
+                {
+                    return Ok(Permission::Allow);
                 }
+                decision = Permission::Deny;
             }
-            None => Ok(self.default_permission),
+            Ok(decision)
         }
     }
 }
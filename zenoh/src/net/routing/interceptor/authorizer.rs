@@ -0,0 +1,392 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use super::{
+    DropReason, EgressInterceptor, IngressInterceptor, InterceptorFactory, InterceptorFactoryTrait,
+    InterceptorTrait,
+};
+use crate::authorization::{
+    Action, AuthorizationRequest, AuthorizationSubject, Authorizer, InterceptorFlow, Permission,
+};
+use crate::net::primitives::Primitives;
+use crate::net::routing::RoutingContext;
+use crate::KeyExpr;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh_core::zlock;
+use zenoh_protocol::{
+    network::{Declare, DeclareBody, NetworkBody, NetworkMessage, Push, Request},
+    zenoh::{PushBody, RequestBody},
+};
+use zenoh_result::ZResult;
+use zenoh_runtime::ZRuntime;
+use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
+
+pub(crate) fn authorizer_interceptor_factory(authorizer: Authorizer) -> InterceptorFactory {
+    Box::new(AuthorizerFactory { authorizer })
+}
+
+struct AuthorizerFactory {
+    authorizer: Authorizer,
+}
+
+fn subject(transport: &TransportUnicast) -> ZResult<AuthorizationSubject> {
+    let mut subject = AuthorizationSubject {
+        zid: transport.get_zid()?,
+        whatami: transport.get_whatami()?,
+        username: transport.get_username()?,
+        token_claims: None,
+        cert_common_names: vec![],
+        cert_subject_alt_names: vec![],
+        interfaces: vec![],
+    };
+    #[cfg(feature = "auth_token")]
+    {
+        subject.token_claims = transport.get_token_claims()?;
+    }
+    for link in transport.get_links()? {
+        subject.interfaces.extend(link.interfaces);
+        subject.cert_common_names.extend(link.cert_common_name);
+        subject
+            .cert_subject_alt_names
+            .extend(link.cert_subject_alt_names);
+    }
+    Ok(subject)
+}
+
+impl InterceptorFactoryTrait for AuthorizerFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        match subject(transport) {
+            Ok(subject) => {
+                let subject = Arc::new(subject);
+                (
+                    Some(Box::new(AuthorizerInterceptor::new(
+                        self.authorizer.clone(),
+                        subject.clone(),
+                        InterceptorFlow::Ingress,
+                        Resume::Ingress,
+                    ))),
+                    Some(Box::new(AuthorizerInterceptor::new(
+                        self.authorizer.clone(),
+                        subject,
+                        InterceptorFlow::Egress,
+                        Resume::Egress(transport.clone()),
+                    ))),
+                )
+            }
+            Err(e) => {
+                tracing::error!("Failed to get the identity of the transport: {}", e);
+                (None, None)
+            }
+        }
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        tracing::debug!("Transport Multicast is disabled in interceptor");
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        tracing::debug!("Peer Multicast is disabled in interceptor");
+        None
+    }
+}
+
+/// The time after which a decision of the callback still pending is taken as a denial.
+const DECISION_TIMEOUT: Duration = Duration::from_secs(1);
+/// The maximum number of decisions kept per remote node and flow, the least recently used one
+/// being evicted, and asked again to the callback when needed.
+const MAX_DECISIONS: usize = 1_024;
+/// The maximum number of messages held back while a decision is pending, the following ones
+/// being denied.
+const MAX_PENDING_MESSAGES: usize = 64;
+
+/// The state of a decision of the callback.
+enum DecisionState {
+    /// The decision is pending, holding back the messages waiting for it.
+    Pending(VecDeque<RoutingContext<NetworkMessage>>),
+    Taken(Permission),
+}
+
+/// Where the messages held back by a pending decision are routed once it is taken.
+#[derive(Clone)]
+enum Resume {
+    /// The messages received from a remote node are routed through the face they came from.
+    Ingress,
+    /// The messages to send to a remote node are scheduled on its transport, the authorizer
+    /// being the last of the egress interceptors.
+    Egress(TransportUnicast),
+}
+
+impl Resume {
+    fn route(&self, ctx: RoutingContext<NetworkMessage>) {
+        match self {
+            Resume::Ingress => {
+                let Some(face) = ctx.inface().cloned() else {
+                    return;
+                };
+                match ctx.msg.body {
+                    NetworkBody::Push(m) => face.send_push(m),
+                    NetworkBody::Declare(m) => face.send_declare(m),
+                    NetworkBody::Request(m) => face.send_request(m),
+                    // The other messages are not authorized, hence never held back
+                    _ => {}
+                }
+            }
+            Resume::Egress(transport) => {
+                let _ = transport.schedule(ctx.msg);
+            }
+        }
+    }
+}
+
+/// A decision of the callback, asked once per key expression and action.
+struct Decision(Mutex<DecisionState>);
+
+impl Decision {
+    fn ask(authorizer: &Authorizer, request: AuthorizationRequest, resume: Resume) -> Arc<Self> {
+        let decision = Arc::new(Decision(Mutex::new(
+            DecisionState::Pending(VecDeque::new()),
+        )));
+        let permission = authorizer(request.clone());
+        // The callback runs apart from the routing so that it may use zenoh itself,
+        // the messages are held back meanwhile instead of blocking the routing
+        let d = decision.clone();
+        ZRuntime::Net.spawn(async move {
+            let permission = match tokio::time::timeout(DECISION_TIMEOUT, permission).await {
+                Ok(permission) => permission,
+                Err(_) => {
+                    tracing::warn!(
+                        "Authorization of {} to {:?} ({:?}) on {:?} timed out",
+                        request.subject.zid,
+                        request.action,
+                        request.flow,
+                        request.key_expr
+                    );
+                    Permission::Deny
+                }
+            };
+            d.take(permission, &resume);
+        });
+        decision
+    }
+
+    /// Takes the decision, routing or dropping the messages held back in order.
+    fn take(&self, permission: Permission, resume: &Resume) {
+        loop {
+            let mut state = zlock!(self.0);
+            let pending = match &mut *state {
+                DecisionState::Pending(pending) => std::mem::take(pending),
+                DecisionState::Taken(_) => VecDeque::new(),
+            };
+            // The messages held back while routing the previous ones are routed as well
+            if pending.is_empty() {
+                *state = DecisionState::Taken(permission);
+                return;
+            }
+            drop(state);
+            match permission {
+                Permission::Allow => pending.into_iter().for_each(|ctx| resume.route(ctx)),
+                Permission::Deny => {
+                    tracing::debug!("Dropping {} unauthorized messages", pending.len())
+                }
+            }
+        }
+    }
+}
+
+/// The decisions of the callback, the least recently used one being evicted when full.
+#[derive(Default)]
+struct Decisions {
+    decisions: HashMap<(String, Action), (Arc<Decision>, u64)>,
+    clock: u64,
+}
+
+struct AuthorizerInterceptor {
+    authorizer: Authorizer,
+    subject: Arc<AuthorizationSubject>,
+    flow: InterceptorFlow,
+    resume: Resume,
+    decisions: Mutex<Decisions>,
+}
+
+impl AuthorizerInterceptor {
+    fn new(
+        authorizer: Authorizer,
+        subject: Arc<AuthorizationSubject>,
+        flow: InterceptorFlow,
+        resume: Resume,
+    ) -> Self {
+        AuthorizerInterceptor {
+            authorizer,
+            subject,
+            flow,
+            resume,
+            decisions: Mutex::new(Decisions::default()),
+        }
+    }
+
+    fn decision(&self, key_expr: &str, action: Action) -> Arc<Decision> {
+        let mut guard = zlock!(self.decisions);
+        let decisions = &mut *guard;
+        decisions.clock += 1;
+        let clock = decisions.clock;
+        if let Some((decision, used)) = decisions.decisions.get_mut(&(key_expr.to_string(), action))
+        {
+            *used = clock;
+            return decision.clone();
+        }
+
+        if decisions.decisions.len() >= MAX_DECISIONS {
+            let lru = decisions
+                .decisions
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                decisions.decisions.remove(&lru);
+            }
+        }
+        let decision = Decision::ask(
+            &self.authorizer,
+            AuthorizationRequest {
+                subject: self.subject.clone(),
+                flow: self.flow,
+                action,
+                key_expr: key_expr.to_string(),
+            },
+            self.resume.clone(),
+        );
+        decisions
+            .decisions
+            .insert((key_expr.to_string(), action), (decision.clone(), clock));
+        decision
+    }
+}
+
+impl InterceptorTrait for AuthorizerInterceptor {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        Some(Box::new(key_expr.to_string()))
+    }
+
+    fn intercept(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        self.intercept_or_drop(ctx, cache).ok()
+    }
+
+    fn drop_reason(&self) -> Option<DropReason> {
+        Some(DropReason::AccessControl)
+    }
+
+    fn intercept_or_drop(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Result<RoutingContext<NetworkMessage>, Option<DropReason>> {
+        let action = match &ctx.msg.body {
+            // A deletion is authorized as a publication on its key expression
+            NetworkBody::Push(Push {
+                payload: PushBody::Put(_) | PushBody::Del(_),
+                ..
+            })
+            | NetworkBody::Request(Request {
+                payload: RequestBody::Put(_) | RequestBody::Del(_),
+                ..
+            }) => Action::Put,
+            NetworkBody::Request(Request {
+                payload: RequestBody::Query(_),
+                ..
+            }) => Action::Get,
+            NetworkBody::Declare(Declare { body, .. }) => match body {
+                // Liveliness tokens are subscriptions to their key expression
+                DeclareBody::DeclareSubscriber(_) | DeclareBody::DeclareToken(_) => {
+                    Action::DeclareSubscriber
+                }
+                DeclareBody::DeclareQueryable(_) => Action::DeclareQueryable,
+                // An interest queries the declarations on its key expression
+                DeclareBody::DeclareInterest(_) => Action::Get,
+                // These only withdraw declarations or refer to key expressions
+                DeclareBody::DeclareKeyExpr(_)
+                | DeclareBody::UndeclareKeyExpr(_)
+                | DeclareBody::UndeclareSubscriber(_)
+                | DeclareBody::UndeclareQueryable(_)
+                | DeclareBody::UndeclareToken(_)
+                | DeclareBody::FinalInterest(_)
+                | DeclareBody::UndeclareInterest(_) => return Ok(ctx),
+            },
+            // A pull refers to a subscriber already declared
+            NetworkBody::Request(Request {
+                payload: RequestBody::Pull(_),
+                ..
+            }) => return Ok(ctx),
+            NetworkBody::Response(_)
+            | NetworkBody::ResponseFinal(_)
+            | NetworkBody::RequestCancel(_)
+            | NetworkBody::OAM(_) => return Ok(ctx),
+        };
+        let Some(key_expr) = cache
+            .and_then(|c| c.downcast_ref::<String>().cloned())
+            .or_else(|| ctx.full_expr().map(String::from))
+        else {
+            return Err(self.drop_reason());
+        };
+
+        let decision = self.decision(&key_expr, action);
+        let mut state = zlock!(decision.0);
+        match &mut *state {
+            DecisionState::Taken(Permission::Allow) => Ok(ctx),
+            DecisionState::Taken(Permission::Deny) => {
+                tracing::debug!(
+                    "{} is unauthorized to {:?} ({:?}) on {:?}",
+                    self.subject.zid,
+                    action,
+                    self.flow,
+                    key_expr
+                );
+                Err(self.drop_reason())
+            }
+            DecisionState::Pending(pending) if pending.len() < MAX_PENDING_MESSAGES => {
+                pending.push_back(ctx);
+                Err(None)
+            }
+            DecisionState::Pending(_) => {
+                tracing::debug!(
+                    "Too many messages waiting for the authorization of {} to {:?} ({:?}) on {:?}",
+                    self.subject.zid,
+                    action,
+                    self.flow,
+                    key_expr
+                );
+                Err(self.drop_reason())
+            }
+        }
+    }
+}
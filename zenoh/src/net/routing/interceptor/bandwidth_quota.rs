@@ -0,0 +1,196 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use crate::net::routing::interceptor::*;
+use std::sync::Mutex;
+use std::time::Instant;
+use zenoh_buffers::buffer::Buffer;
+use zenoh_config::{BandwidthQuotaItemConf, BandwidthQuotaRuleConf};
+use zenoh_core::zlock;
+use zenoh_keyexpr::OwnedKeyExpr;
+use zenoh_protocol::network::{NetworkBody, Push};
+use zenoh_protocol::zenoh::PushBody;
+use zenoh_result::{bail, ZResult};
+
+pub(crate) fn bandwidth_quota_interceptor_factories(
+    config: &Vec<BandwidthQuotaItemConf>,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    for bq in config {
+        for rule in &bq.rules {
+            if rule.bytes_per_sec == 0 {
+                bail!(
+                    "Invalid bandwidth quota for {}: bytes_per_sec must be positive",
+                    rule.key_expr
+                );
+            }
+        }
+        res.push(Box::new(BandwidthQuotaInterceptorFactory::new(bq.clone())));
+    }
+
+    Ok(res)
+}
+
+pub struct BandwidthQuotaInterceptorFactory {
+    interfaces: Option<Vec<String>>,
+    rules: Vec<BandwidthQuotaRuleConf>,
+}
+
+impl BandwidthQuotaInterceptorFactory {
+    pub fn new(conf: BandwidthQuotaItemConf) -> Self {
+        Self {
+            interfaces: conf.interfaces,
+            rules: conf.rules,
+        }
+    }
+
+    fn interceptor(&self) -> EgressInterceptor {
+        Box::new(ComputeOnMiss::new(BandwidthQuotaInterceptor::new(
+            &self.rules,
+        )))
+    }
+}
+
+impl InterceptorFactoryTrait for BandwidthQuotaInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        tracing::debug!("New bandwidth quota transport unicast {:?}", transport);
+        if let Some(interfaces) = &self.interfaces {
+            if let Ok(links) = transport.get_links() {
+                for link in links {
+                    tracing::debug!(
+                        "New bandwidth quota transport unicast link interfaces: {:?}",
+                        link.interfaces
+                    );
+                    if !link.interfaces.iter().any(|x| interfaces.contains(x)) {
+                        return (None, None);
+                    }
+                }
+            }
+        };
+        (None, Some(self.interceptor()))
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        // Multicast links are not bound to a known interface
+        self.interfaces.is_none().then(|| self.interceptor())
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        None
+    }
+}
+
+/// A token bucket holding up to one second of the quota.
+///
+/// A publication is sent as long as the bucket is not empty, possibly leaving it in debt,
+/// so that publications larger than the quota are still sent at the quota's average rate.
+struct TokenBucket {
+    bytes_per_sec: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_sec: u64) -> Self {
+        let bytes_per_sec = bytes_per_sec as f64;
+        Self {
+            bytes_per_sec,
+            tokens: bytes_per_sec,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn consume(&mut self, bytes: usize) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.bytes_per_sec).min(self.bytes_per_sec);
+        if self.tokens <= 0.0 {
+            return false;
+        }
+        self.tokens -= bytes as f64;
+        true
+    }
+}
+
+pub(crate) struct BandwidthQuotaInterceptor {
+    rules: Vec<OwnedKeyExpr>,
+    buckets: Mutex<Vec<TokenBucket>>,
+}
+
+impl BandwidthQuotaInterceptor {
+    pub fn new(rules: &[BandwidthQuotaRuleConf]) -> Self {
+        Self {
+            rules: rules.iter().map(|rule| rule.key_expr.clone()).collect(),
+            buckets: Mutex::new(
+                rules
+                    .iter()
+                    .map(|rule| TokenBucket::new(rule.bytes_per_sec))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl InterceptorTrait for BandwidthQuotaInterceptor {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        let id = self.rules.iter().position(|rule| rule.includes(key_expr));
+        Some(Box::new(id))
+    }
+
+    fn intercept(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        let NetworkBody::Push(Push {
+            payload: PushBody::Put(put),
+            ..
+        }) = &ctx.msg.body
+        else {
+            return Some(ctx);
+        };
+        let Some(id) = cache.and_then(|c| c.downcast_ref::<Option<usize>>().copied().flatten())
+        else {
+            return Some(ctx);
+        };
+        let bytes = put.payload.len();
+        if zlock!(self.buckets)[id].consume(bytes) {
+            return Some(ctx);
+        }
+        tracing::trace!(
+            "Dropping {} bytes exceeding the bandwidth quota of {}",
+            bytes,
+            self.rules[id]
+        );
+        #[cfg(feature = "stats")]
+        if let Some(stats) = ctx.outface().and_then(|face| face.state.stats.as_ref()) {
+            stats.inc_tx_z_put_throttled_bytes(bytes);
+        }
+        None
+    }
+}
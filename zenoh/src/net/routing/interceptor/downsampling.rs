@@ -160,6 +160,10 @@ impl InterceptorTrait for DownsamplingInterceptor {
 
         Some(ctx)
     }
+
+    fn drop_reason(&self) -> Option<DropReason> {
+        Some(DropReason::Downsampling)
+    }
 }
 
 const NANOS_PER_SEC: f64 = 1_000_000_000.0;
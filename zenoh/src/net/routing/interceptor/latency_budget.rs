@@ -0,0 +1,104 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use crate::net::routing::interceptor::*;
+use std::time::SystemTime;
+use zenoh_config::LatencyBudgetConf;
+use zenoh_protocol::core::NTP64;
+use zenoh_protocol::network::{NetworkBody, Push};
+use zenoh_protocol::zenoh::PushBody;
+use zenoh_result::ZResult;
+
+pub(crate) fn latency_budget_interceptor_factories(
+    config: &LatencyBudgetConf,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+    if config.enabled().unwrap_or(false) {
+        res.push(Box::new(LatencyBudgetInterceptorFactory {}));
+    }
+    Ok(res)
+}
+
+pub struct LatencyBudgetInterceptorFactory {}
+
+impl InterceptorFactoryTrait for LatencyBudgetInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        tracing::debug!("New latency budget transport unicast {:?}", transport);
+        (Some(Box::new(LatencyBudgetInterceptor {})), None)
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        Some(Box::new(LatencyBudgetInterceptor {}))
+    }
+}
+
+pub(crate) struct LatencyBudgetInterceptor {}
+
+impl InterceptorTrait for LatencyBudgetInterceptor {
+    fn compute_keyexpr_cache(&self, _key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        None
+    }
+
+    fn intercept(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        _cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        let NetworkBody::Push(Push {
+            payload: PushBody::Put(put),
+            ..
+        }) = &ctx.msg.body
+        else {
+            return Some(ctx);
+        };
+        // The budget of data without timestamp can't be checked
+        let (Some(timestamp), Some(budget)) = (put.timestamp.as_ref(), put.ext_latency_budget)
+        else {
+            return Some(ctx);
+        };
+        let deadline = *timestamp.get_time() + NTP64::from(budget);
+        if deadline.to_system_time() >= SystemTime::now() {
+            return Some(ctx);
+        }
+        tracing::trace!(
+            "Dropping message exceeding its latency budget of {:?}",
+            budget
+        );
+        #[cfg(feature = "stats")]
+        if let Some(stats) = ctx.inface().and_then(|face| face.state.stats.as_ref()) {
+            stats.inc_rx_z_put_late(1);
+        }
+        None
+    }
+
+    fn drop_reason(&self) -> Option<DropReason> {
+        Some(DropReason::LatencyBudget)
+    }
+}
@@ -22,8 +22,13 @@ mod access_control;
 use access_control::acl_interceptor_factories;
 
 mod authorization;
+
+#[cfg(feature = "unstable")]
+mod authorizer;
 use super::RoutingContext;
 use crate::KeyExpr;
+#[cfg(feature = "unstable")]
+pub(crate) use authorizer::authorizer_interceptor_factory;
 use std::any::Any;
 
 use zenoh_config::Config;
@@ -34,6 +39,67 @@ use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
 pub mod downsampling;
 use crate::net::routing::interceptor::downsampling::downsampling_interceptor_factories;
 
+pub mod priority_remapping;
+use crate::net::routing::interceptor::priority_remapping::priority_remapping_interceptor_factories;
+
+pub mod latency_budget;
+use crate::net::routing::interceptor::latency_budget::latency_budget_interceptor_factories;
+
+pub mod bandwidth_quota;
+use crate::net::routing::interceptor::bandwidth_quota::bandwidth_quota_interceptor_factories;
+
+pub mod qos_overwrite;
+use crate::net::routing::interceptor::qos_overwrite::qos_overwrite_interceptor_factories;
+
+/// The reason why samples were dropped by the infrastructure.
+///
+/// The drops decided by the ingress and egress interceptors of any zenoh instance on the route
+/// of the samples are reported, remote instances notifying the next hops of the route. Samples
+/// dropped by the congestion control of a link are not: use
+/// [`sequence_numbers`](crate::publication::PublisherBuilder::sequence_numbers) and
+/// [`sample_miss_callback`](crate::subscriber::SubscriberBuilder::sample_miss_callback)
+/// to detect those.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DropReason {
+    /// The samples were dropped by a downsampling rule.
+    Downsampling,
+    /// The samples were dropped by an access control rule.
+    AccessControl,
+    /// The samples were dropped because their latency budget elapsed.
+    LatencyBudget,
+}
+
+impl DropReason {
+    /// The identifier of the reason in the drop notifications sent to the other nodes.
+    pub(crate) fn id(&self) -> u8 {
+        match self {
+            DropReason::Downsampling => 0,
+            DropReason::AccessControl => 1,
+            DropReason::LatencyBudget => 2,
+        }
+    }
+
+    pub(crate) fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(DropReason::Downsampling),
+            1 => Some(DropReason::AccessControl),
+            2 => Some(DropReason::LatencyBudget),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DropReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DropReason::Downsampling => write!(f, "downsampling"),
+            DropReason::AccessControl => write!(f, "access_control"),
+            DropReason::LatencyBudget => write!(f, "latency_budget"),
+        }
+    }
+}
+
 pub(crate) trait InterceptorTrait {
     fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>>;
 
@@ -42,6 +108,22 @@ pub(crate) trait InterceptorTrait {
         ctx: RoutingContext<NetworkMessage>,
         cache: Option<&Box<dyn Any + Send + Sync>>,
     ) -> Option<RoutingContext<NetworkMessage>>;
+
+    /// The reason reported to the subscribers when this interceptor drops a message.
+    fn drop_reason(&self) -> Option<DropReason> {
+        None
+    }
+
+    /// Same as [`intercept`](Self::intercept) but returns the [`DropReason`] of the message
+    /// if it is dropped. A message held back by the interceptor, to be routed later, is not
+    /// reported as dropped.
+    fn intercept_or_drop(
+        &self,
+        ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Result<RoutingContext<NetworkMessage>, Option<DropReason>> {
+        self.intercept(ctx, cache).ok_or_else(|| self.drop_reason())
+    }
 }
 
 pub(crate) type Interceptor = Box<dyn InterceptorTrait + Send + Sync>;
@@ -63,7 +145,17 @@ pub(crate) fn interceptor_factories(config: &Config) -> ZResult<Vec<InterceptorF
     let mut res: Vec<InterceptorFactory> = vec![];
     // Uncomment to log the interceptors initialisation
     // res.push(Box::new(LoggerInterceptor {}));
+    res.extend(priority_remapping_interceptor_factories(
+        config.priority_remapping(),
+    )?);
+    res.extend(qos_overwrite_interceptor_factories(config.qos_overwrite())?);
+    res.extend(latency_budget_interceptor_factories(
+        config.latency_budget(),
+    )?);
     res.extend(downsampling_interceptor_factories(config.downsampling())?);
+    res.extend(bandwidth_quota_interceptor_factories(
+        config.bandwidth_quota(),
+    )?);
     res.extend(acl_interceptor_factories(config.access_control())?);
     Ok(res)
 }
@@ -99,24 +191,33 @@ impl InterceptorTrait for InterceptorsChain {
 
     fn intercept<'a>(
         &self,
-        mut ctx: RoutingContext<NetworkMessage>,
+        ctx: RoutingContext<NetworkMessage>,
         caches: Option<&Box<dyn Any + Send + Sync>>,
     ) -> Option<RoutingContext<NetworkMessage>> {
+        self.intercept_or_drop(ctx, caches).ok()
+    }
+
+    /// Returns the [`DropReason`] of the interceptor that dropped the message, if any.
+    fn intercept_or_drop(
+        &self,
+        mut ctx: RoutingContext<NetworkMessage>,
+        caches: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Result<RoutingContext<NetworkMessage>, Option<DropReason>> {
         let caches =
             caches.and_then(|i| i.downcast_ref::<Vec<Option<Box<dyn Any + Send + Sync>>>>());
         for (idx, interceptor) in self.interceptors.iter().enumerate() {
             let cache = caches
                 .and_then(|caches| caches.get(idx).map(|k| k.as_ref()))
                 .flatten();
-            match interceptor.intercept(ctx, cache) {
-                Some(newctx) => ctx = newctx,
-                None => {
+            match interceptor.intercept_or_drop(ctx, cache) {
+                Ok(newctx) => ctx = newctx,
+                Err(reason) => {
                     tracing::trace!("Msg intercepted!");
-                    return None;
+                    return Err(reason);
                 }
             }
         }
-        Some(ctx)
+        Ok(ctx)
     }
 }
 
@@ -156,6 +257,11 @@ impl<T: InterceptorTrait> InterceptorTrait for ComputeOnMiss<T> {
             self.interceptor.intercept(ctx, cache)
         }
     }
+
+    #[inline]
+    fn drop_reason(&self) -> Option<DropReason> {
+        self.interceptor.drop_reason()
+    }
 }
 
 pub(crate) struct IngressMsgLogger {}
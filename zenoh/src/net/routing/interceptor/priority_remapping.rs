@@ -0,0 +1,141 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use crate::net::routing::interceptor::*;
+use zenoh_config::PriorityRemappingItemConf;
+use zenoh_protocol::core::Priority;
+use zenoh_protocol::network::NetworkBody;
+use zenoh_result::ZResult;
+
+pub(crate) fn priority_remapping_interceptor_factories(
+    config: &Vec<PriorityRemappingItemConf>,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    for pr in config {
+        res.push(Box::new(PriorityRemappingInterceptorFactory::new(
+            pr.clone(),
+        )));
+    }
+
+    Ok(res)
+}
+
+pub struct PriorityRemappingInterceptorFactory {
+    interfaces: Option<Vec<String>>,
+    mapping: [Priority; Priority::NUM],
+}
+
+impl PriorityRemappingInterceptorFactory {
+    pub fn new(conf: PriorityRemappingItemConf) -> Self {
+        let mut mapping = [Priority::default(); Priority::NUM];
+        for (p, mapped) in mapping.iter_mut().enumerate() {
+            let priority = Priority::try_from(p as u8).unwrap();
+            *mapped = conf
+                .rules
+                .iter()
+                .find(|rule| Priority::from(rule.from) == priority)
+                .map_or(priority, |rule| rule.to.into());
+            if let Some(max) = conf.max.map(Priority::from) {
+                if (*mapped as u8) < (max as u8) {
+                    *mapped = max;
+                }
+            }
+        }
+        Self {
+            interfaces: conf.interfaces,
+            mapping,
+        }
+    }
+
+    fn interceptor(&self) -> IngressInterceptor {
+        Box::new(PriorityRemappingInterceptor {
+            mapping: self.mapping,
+        })
+    }
+}
+
+impl InterceptorFactoryTrait for PriorityRemappingInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        tracing::debug!("New priority remapper transport unicast {:?}", transport);
+        if let Some(interfaces) = &self.interfaces {
+            if let Ok(links) = transport.get_links() {
+                for link in links {
+                    tracing::debug!(
+                        "New priority remapper transport unicast link interfaces: {:?}",
+                        link.interfaces
+                    );
+                    if !link.interfaces.iter().any(|x| interfaces.contains(x)) {
+                        return (None, None);
+                    }
+                }
+            }
+        };
+        (Some(self.interceptor()), None)
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        // Multicast links are not bound to a known interface
+        self.interfaces.is_none().then(|| self.interceptor())
+    }
+}
+
+pub(crate) struct PriorityRemappingInterceptor {
+    mapping: [Priority; Priority::NUM],
+}
+
+impl InterceptorTrait for PriorityRemappingInterceptor {
+    fn compute_keyexpr_cache(&self, _key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        None
+    }
+
+    fn intercept(
+        &self,
+        mut ctx: RoutingContext<NetworkMessage>,
+        _cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        // Declarations and OAM messages keep their priority
+        let ext_qos = match &mut ctx.msg.body {
+            NetworkBody::Push(msg) => &mut msg.ext_qos,
+            NetworkBody::Request(msg) => &mut msg.ext_qos,
+            NetworkBody::Response(msg) => &mut msg.ext_qos,
+            NetworkBody::ResponseFinal(msg) => &mut msg.ext_qos,
+            NetworkBody::RequestCancel(msg) => &mut msg.ext_qos,
+            NetworkBody::Declare(_) | NetworkBody::OAM(_) => return Some(ctx),
+        };
+        let priority = ext_qos.get_priority();
+        let mapped = self.mapping[priority as usize];
+        if mapped != priority {
+            tracing::trace!("Remapping priority {:?} to {:?}", priority, mapped);
+            ext_qos.set_priority(mapped);
+        }
+        Some(ctx)
+    }
+}
@@ -0,0 +1,145 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+
+use crate::net::routing::interceptor::*;
+use std::sync::Arc;
+use zenoh_config::{InterceptorFlow, QosOverwriteConf, QosOverwriteItemConf};
+use zenoh_keyexpr::OwnedKeyExpr;
+use zenoh_protocol::network::NetworkBody;
+use zenoh_result::ZResult;
+
+pub(crate) fn qos_overwrite_interceptor_factories(
+    config: &Vec<QosOverwriteItemConf>,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    for qo in config {
+        res.push(Box::new(QosOverwriteInterceptorFactory::new(qo.clone())));
+    }
+
+    Ok(res)
+}
+
+pub struct QosOverwriteInterceptorFactory {
+    interfaces: Option<Vec<String>>,
+    key_exprs: Arc<Vec<OwnedKeyExpr>>,
+    overwrite: QosOverwriteConf,
+    flow: InterceptorFlow,
+}
+
+impl QosOverwriteInterceptorFactory {
+    pub fn new(conf: QosOverwriteItemConf) -> Self {
+        Self {
+            interfaces: conf.interfaces,
+            key_exprs: Arc::new(conf.key_exprs),
+            overwrite: conf.overwrite,
+            flow: conf.flow,
+        }
+    }
+
+    fn interceptor(&self) -> Interceptor {
+        Box::new(ComputeOnMiss::new(QosOverwriteInterceptor {
+            key_exprs: self.key_exprs.clone(),
+            overwrite: self.overwrite,
+        }))
+    }
+}
+
+impl InterceptorFactoryTrait for QosOverwriteInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        tracing::debug!("New QoS overwriter transport unicast {:?}", transport);
+        if let Some(interfaces) = &self.interfaces {
+            if let Ok(links) = transport.get_links() {
+                for link in links {
+                    tracing::debug!(
+                        "New QoS overwriter transport unicast link interfaces: {:?}",
+                        link.interfaces
+                    );
+                    if !link.interfaces.iter().any(|x| interfaces.contains(x)) {
+                        return (None, None);
+                    }
+                }
+            }
+        };
+        match self.flow {
+            InterceptorFlow::Ingress => (Some(self.interceptor()), None),
+            InterceptorFlow::Egress => (None, Some(self.interceptor())),
+        }
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        // Multicast links are not bound to a known interface
+        (self.interfaces.is_none() && matches!(self.flow, InterceptorFlow::Egress))
+            .then(|| self.interceptor())
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        (self.interfaces.is_none() && matches!(self.flow, InterceptorFlow::Ingress))
+            .then(|| self.interceptor())
+    }
+}
+
+pub(crate) struct QosOverwriteInterceptor {
+    key_exprs: Arc<Vec<OwnedKeyExpr>>,
+    overwrite: QosOverwriteConf,
+}
+
+impl InterceptorTrait for QosOverwriteInterceptor {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        let matches = self.key_exprs.iter().any(|ke| ke.includes(key_expr));
+        Some(Box::new(matches))
+    }
+
+    fn intercept(
+        &self,
+        mut ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        if !cache.is_some_and(|c| c.downcast_ref::<bool>() == Some(&true)) {
+            return Some(ctx);
+        }
+        let ext_qos = match &mut ctx.msg.body {
+            NetworkBody::Push(msg) => &mut msg.ext_qos,
+            NetworkBody::Request(msg) => &mut msg.ext_qos,
+            NetworkBody::Response(msg) => &mut msg.ext_qos,
+            NetworkBody::ResponseFinal(_)
+            | NetworkBody::RequestCancel(_)
+            | NetworkBody::Declare(_)
+            | NetworkBody::OAM(_) => return Some(ctx),
+        };
+        if let Some(priority) = self.overwrite.priority {
+            ext_qos.set_priority(priority.into());
+        }
+        if let Some(congestion_control) = self.overwrite.congestion_control {
+            ext_qos.set_congestion_control(congestion_control.into());
+        }
+        if let Some(express) = self.overwrite.express {
+            ext_qos.set_is_express(express);
+        }
+        tracing::trace!("Overwriting QoS with {:?}", self.overwrite);
+        Some(ctx)
+    }
+}
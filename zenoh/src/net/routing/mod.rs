@@ -106,7 +106,7 @@ impl RoutingContext<NetworkMessage> {
             NetworkBody::Push(m) => Some(&m.wire_expr),
             NetworkBody::Request(m) => Some(&m.wire_expr),
             NetworkBody::Response(m) => Some(&m.wire_expr),
-            NetworkBody::ResponseFinal(_) => None,
+            NetworkBody::ResponseFinal(_) | NetworkBody::RequestCancel(_) => None,
             NetworkBody::Declare(m) => match &m.body {
                 DeclareBody::DeclareKeyExpr(m) => Some(&m.wire_expr),
                 DeclareBody::UndeclareKeyExpr(_) => None,
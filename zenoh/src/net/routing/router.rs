@@ -12,6 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::dispatcher::face::{Face, FaceState};
+pub use super::dispatcher::interests::*;
 pub use super::dispatcher::pubsub::*;
 pub use super::dispatcher::queries::*;
 pub use super::dispatcher::resource::*;
@@ -20,15 +20,26 @@ use crate::prelude::sync::{Sample, SyncResolve};
 use crate::queryable::Query;
 use crate::queryable::QueryInner;
 use crate::value::Value;
+use base64::{engine::general_purpose::STANDARD as b64_std_engine, Engine};
+use ring::signature::{UnparsedPublicKey, ED25519};
 use serde_json::json;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{error, trace};
-use zenoh_buffers::buffer::SplitBuffer;
-use zenoh_config::{unwrap_or_default, ConfigValidator, ValidatedMap, WhatAmI};
+use zenoh_buffers::{
+    buffer::SplitBuffer,
+    reader::{HasReader, Reader},
+    ZBuf,
+};
+use zenoh_codec::{RCodec, Zenoh080};
+use zenoh_config::{
+    unwrap_or_default, AdminSpaceOperatorConf, ConfigValidator, ValidatedMap, WhatAmI,
+};
 #[cfg(all(feature = "unstable", feature = "plugins"))]
 use zenoh_plugin_trait::{PluginControl, PluginStatus};
 #[cfg(all(feature = "unstable", feature = "plugins"))]
@@ -37,8 +48,8 @@ use zenoh_protocol::{
     core::{key_expr::OwnedKeyExpr, ExprId, KnownEncoding, WireExpr, ZenohId, EMPTY_EXPR_ID},
     network::{
         declare::{queryable::ext::QueryableInfo, subscriber::ext::SubscriberInfo},
-        ext, Declare, DeclareBody, DeclareQueryable, DeclareSubscriber, Push, Request, Response,
-        ResponseFinal,
+        ext, Declare, DeclareBody, DeclareQueryable, DeclareSubscriber, Push, Request,
+        RequestCancel, Response, ResponseFinal,
     },
     zenoh::{PushBody, RequestBody},
 };
@@ -53,12 +64,52 @@ pub struct AdminContext {
 
 type Handler = Arc<dyn Fn(&AdminContext, Query) + Send + Sync>;
 
+// The attachment entries of the admin space writes signed by an operator
+pub(crate) const ATTACHMENT_OPERATOR: &str = "zenoh.admin.operator";
+pub(crate) const ATTACHMENT_TIMESTAMP: &str = "zenoh.admin.timestamp";
+pub(crate) const ATTACHMENT_SIGNATURE: &str = "zenoh.admin.signature";
+// How long a signed write is accepted, i.e. the clock skew tolerated between the operator and the node
+const SIGNATURE_VALIDITY: Duration = Duration::from_secs(30);
+
+/// The message an operator signs to put `payload` on `key_expr`, or to delete it if `None`.
+pub(crate) fn operator_message(key_expr: &str, payload: Option<&[u8]>, timestamp: u64) -> Vec<u8> {
+    let mut message = b"zenoh-admin-v1".to_vec();
+    message.push(0);
+    message.extend_from_slice(key_expr.as_bytes());
+    message.push(0);
+    message.extend_from_slice(&timestamp.to_le_bytes());
+    match payload {
+        Some(payload) => {
+            message.push(b'P');
+            message.extend_from_slice(payload);
+        }
+        None => message.push(b'D'),
+    }
+    message
+}
+
+fn attachment_get(attachment: &ZBuf, key: &str) -> Option<Vec<u8>> {
+    let codec = Zenoh080::new();
+    let mut reader = attachment.reader();
+    while reader.can_read() {
+        let k: Vec<u8> = codec.read(&mut reader).ok()?;
+        let v: Vec<u8> = codec.read(&mut reader).ok()?;
+        if k == key.as_bytes() {
+            return Some(v);
+        }
+    }
+    None
+}
+
 pub struct AdminSpace {
     zid: ZenohId,
     primitives: Mutex<Option<Arc<Face>>>,
     mappings: Mutex<HashMap<ExprId, String>>,
     handlers: HashMap<OwnedKeyExpr, Handler>,
     context: Arc<AdminContext>,
+    // The signatures of the recently accepted writes with their timestamp, to reject replays
+    signatures: Mutex<HashMap<Vec<u8>, u64>>,
+    decl_id_counter: AtomicU32,
 }
 
 #[cfg(all(feature = "unstable", feature = "plugins"))]
@@ -161,6 +212,14 @@ impl AdminSpace {
                     .unwrap(),
                 Arc::new(routers_linkstate_data),
             );
+            if unwrap_or_default!(config.routing().router().cluster().enabled()) {
+                handlers.insert(
+                    format!("@/{whatami_str}/{zid_str}/cluster")
+                        .try_into()
+                        .unwrap(),
+                    Arc::new(cluster_data),
+                );
+            }
         }
         if runtime.state.whatami != WhatAmI::Client
             && unwrap_or_default!(config.routing().peer().mode()) == *"linkstate"
@@ -219,6 +278,8 @@ impl AdminSpace {
             mappings: Mutex::new(HashMap::new()),
             handlers,
             context,
+            signatures: Mutex::new(HashMap::new()),
+            decl_id_counter: AtomicU32::new(0),
         });
 
         config.set_plugin_validator(Arc::downgrade(&admin));
@@ -303,7 +364,7 @@ impl AdminSpace {
             ext_tstamp: None,
             ext_nodeid: ext::NodeIdType::default(),
             body: DeclareBody::DeclareQueryable(DeclareQueryable {
-                id: 0, // @TODO use proper QueryableId (#703)
+                id: admin.decl_id_counter.fetch_add(1, Ordering::SeqCst),
                 wire_expr: [&root_key, "/**"].concat().into(),
                 ext_info: QueryableInfo {
                     complete: 0,
@@ -317,11 +378,115 @@ impl AdminSpace {
             ext_tstamp: None,
             ext_nodeid: ext::NodeIdType::default(),
             body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
-                id: 0, // @TODO use proper SubscriberId (#703)
+                id: admin.decl_id_counter.fetch_add(1, Ordering::SeqCst),
                 wire_expr: [&root_key, "/config/**"].concat().into(),
                 ext_info: SubscriberInfo::default(),
             }),
         });
+
+        #[cfg(all(feature = "unstable", feature = "plugins"))]
+        primitives.send_declare(Declare {
+            ext_qos: ext::QoSType::declare_default(),
+            ext_tstamp: None,
+            ext_nodeid: ext::NodeIdType::default(),
+            body: DeclareBody::DeclareSubscriber(DeclareSubscriber {
+                id: admin.decl_id_counter.fetch_add(1, Ordering::SeqCst),
+                wire_expr: [&root_key, "/plugins/*/cmd"].concat().into(),
+                ext_info: SubscriberInfo::default(),
+            }),
+        });
+    }
+
+    /// Runs a lifecycle command received on `@/<whatami>/<zid>/plugins/<id>/cmd` on plugin `id`.
+    #[cfg(all(feature = "unstable", feature = "plugins"))]
+    fn plugin_command(&self, id: &str, cmd: &str) -> ZResult<()> {
+        let runtime = &self.context.runtime;
+        let mut plugins_mgr = runtime.plugins_manager();
+        match cmd {
+            "start" => {
+                let Some(loaded) = plugins_mgr.loaded_plugin_mut(id) else {
+                    bail!("Plugin `{}` is not loaded", id)
+                };
+                loaded.start(runtime)?;
+            }
+            "stop" | "restart" | "pause" | "resume" | "reconfigure" => {
+                let Some(started) = plugins_mgr.started_plugin_mut(id) else {
+                    bail!("Plugin `{}` is not started", id)
+                };
+                match cmd {
+                    "stop" => started.stop(),
+                    // The plugin library is not reloaded: the same instance is stopped and started
+                    "restart" => {
+                        started.stop();
+                        plugins_mgr.loaded_plugin_mut(id).unwrap().start(runtime)?;
+                    }
+                    "pause" => started.pause()?,
+                    "resume" => started.resume()?,
+                    _ => started.reconfigure()?,
+                }
+            }
+            _ => bail!(
+                "Unknown plugin command `{}` (expected start, stop, restart, pause, resume or reconfigure)",
+                cmd
+            ),
+        }
+        tracing::info!("Plugin `{}`: `{}` command succeeded", id, cmd);
+        Ok(())
+    }
+
+    /// Checks that a write is signed by one of the `operators`, returning the operator id.
+    fn check_operator(&self, msg: &Push, operators: &[AdminSpaceOperatorConf]) -> ZResult<String> {
+        let key_expr = self.key_expr_to_string(&msg.wire_expr)?;
+        let (attachment, payload) = match &msg.payload {
+            PushBody::Put(put) => (
+                put.ext_attachment.as_ref().map(|a| &a.buffer),
+                Some(put.payload.contiguous()),
+            ),
+            PushBody::Del(del) => (del.ext_attachment.as_ref().map(|a| &a.buffer), None),
+        };
+        let Some(attachment) = attachment else {
+            bail!("missing operator signature")
+        };
+        let (Some(id), Some(timestamp), Some(signature)) = (
+            attachment_get(attachment, ATTACHMENT_OPERATOR),
+            attachment_get(attachment, ATTACHMENT_TIMESTAMP),
+            attachment_get(attachment, ATTACHMENT_SIGNATURE),
+        ) else {
+            bail!("missing operator signature")
+        };
+        let id = String::from_utf8(id).map_err(|_| zerror!("invalid operator id"))?;
+        let operator = operators
+            .iter()
+            .find(|o| o.id == id)
+            .ok_or_else(|| zerror!("unknown operator `{}`", id))?;
+        let public_key = b64_std_engine
+            .decode(&operator.public_key)
+            .map_err(|e| zerror!("invalid public key of operator `{}`: {}", id, e))?;
+        let timestamp = u64::from_le_bytes(
+            timestamp
+                .try_into()
+                .map_err(|_| zerror!("invalid signature timestamp"))?,
+        );
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if now.abs_diff(timestamp) > SIGNATURE_VALIDITY.as_secs() {
+            bail!("expired signature of operator `{}`", id);
+        }
+        let message = operator_message(key_expr.as_str(), payload.as_deref(), timestamp);
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&message, &signature)
+            .map_err(|_| zerror!("invalid signature of operator `{}`", id))?;
+
+        // A valid signature is only accepted once
+        let mut signatures = zlock!(self.signatures);
+        signatures.retain(|_, t| now.abs_diff(*t) <= SIGNATURE_VALIDITY.as_secs());
+        if signatures.insert(signature, timestamp).is_some() {
+            bail!("replayed signature of operator `{}`", id);
+        }
+        Ok(id)
     }
 
     pub fn key_expr_to_string<'a>(&self, key_expr: &'a WireExpr) -> ZResult<KeyExpr<'a>> {
@@ -356,7 +521,7 @@ impl Primitives for AdminSpace {
 
     fn send_push(&self, msg: Push) {
         trace!("recv Push {:?}", msg);
-        {
+        let operators = {
             let conf = self.context.runtime.state.config.lock();
             if !conf.adminspace.permissions().write {
                 tracing::error!(
@@ -365,6 +530,43 @@ impl Primitives for AdminSpace {
                 );
                 return;
             }
+            conf.adminspace.permissions().operators.clone()
+        };
+        if let Some(operators) = operators {
+            match self.check_operator(&msg, &operators) {
+                Ok(id) => tracing::info!(
+                    "Received PUT on '{}' signed by operator `{}`",
+                    msg.wire_expr,
+                    id
+                ),
+                Err(e) => {
+                    tracing::error!("Rejected PUT on '{}': {}", msg.wire_expr, e);
+                    return;
+                }
+            }
+        }
+
+        #[cfg(all(feature = "unstable", feature = "plugins"))]
+        if let Some(id) = msg
+            .wire_expr
+            .as_str()
+            .strip_prefix(&format!(
+                "@/{}/{}/plugins/",
+                self.context.runtime.state.whatami, self.context.runtime.state.zid
+            ))
+            .and_then(|key| key.strip_suffix("/cmd"))
+        {
+            if let PushBody::Put(put) = &msg.payload {
+                match std::str::from_utf8(&put.payload.contiguous()) {
+                    Ok(cmd) => {
+                        if let Err(e) = self.plugin_command(id, cmd.trim()) {
+                            error!("Error running command `{}` on plugin `{}`: {}", cmd, id, e);
+                        }
+                    }
+                    Err(e) => error!("Received non utf8 command for plugin `{}`: {}", id, e),
+                }
+            }
+            return;
         }
 
         if let Some(key) = msg.wire_expr.as_str().strip_prefix(&format!(
@@ -447,19 +649,24 @@ impl Primitives for AdminSpace {
             };
 
             let zid = self.zid;
-            let parameters = query.parameters.to_owned();
             let query = Query {
                 inner: Arc::new(QueryInner {
                     key_expr: key_expr.clone(),
-                    parameters,
+                    parameters: query.parameters,
                     value: query
                         .ext_body
                         .map(|b| Value::from(b.payload).encoding(b.encoding)),
                     qid: msg.id,
                     zid,
                     primitives,
+                    hlc: self.context.runtime.shared_hlc(),
+                    drop_future_timestamp: self.context.runtime.drop_future_timestamp(),
                     #[cfg(feature = "unstable")]
                     attachment: query.ext_attachment.map(Into::into),
+                    #[cfg(feature = "unstable")]
+                    trace_id: query.ext_trace.map(Into::into),
+                    cancellation: Default::default(),
+                    received_queries: None,
                 }),
             };
 
@@ -479,6 +686,10 @@ impl Primitives for AdminSpace {
         trace!("recv ResponseFinal {:?}", msg);
     }
 
+    fn send_request_cancel(&self, msg: RequestCancel) {
+        trace!("recv RequestCancel {:?}", msg);
+    }
+
     fn send_close(&self) {
         trace!("recv Close");
     }
@@ -510,6 +721,10 @@ impl crate::net::primitives::EPrimitives for AdminSpace {
         (self as &dyn Primitives).send_response_final(ctx.msg)
     }
 
+    fn send_request_cancel(&self, ctx: crate::net::routing::RoutingContext<RequestCancel>) {
+        (self as &dyn Primitives).send_request_cancel(ctx.msg)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -546,13 +761,50 @@ fn local_data(context: &AdminContext, query: Query) {
 
     // transports info
     let transport_to_json = |transport: &TransportUnicast| {
+        // The identity the remote authenticated with, as seen by the access control
+        let links = transport.get_links().unwrap_or_default();
+        let mut cert_common_names: Vec<&String> = Vec::new();
+        let mut cert_subject_alt_names: Vec<&String> = Vec::new();
+        for link in links.iter() {
+            cert_common_names.extend(link.cert_common_name.iter());
+            cert_subject_alt_names.extend(link.cert_subject_alt_names.iter());
+        }
+        cert_common_names.sort();
+        cert_common_names.dedup();
+        cert_subject_alt_names.sort();
+        cert_subject_alt_names.dedup();
         #[allow(unused_mut)]
         let mut json = json!({
             "peer": transport.get_zid().map_or_else(|_| "unknown".to_string(), |p| p.to_string()),
             "whatami": transport.get_whatami().map_or_else(|_| "unknown".to_string(), |p| p.to_string()),
-            "links": transport.get_links().map_or_else(
+            "links": links.iter().map(|link| link.dst.to_string()).collect::<Vec<_>>(),
+            "version": transport.get_version().ok(),
+            "sn_resolution": transport.get_sn_resolution().ok().map(|r| r.to_string()),
+            "batch_size": transport.get_batch_size().ok().flatten(),
+            "identity": {
+                "username": transport.get_username().ok().flatten(),
+                "cert_common_names": cert_common_names,
+                "cert_subject_alt_names": cert_subject_alt_names,
+            },
+            "link_metrics": transport.get_link_metrics().map_or_else(
                 |_| Vec::new(),
-                |links| links.iter().map(|link| link.dst.to_string()).collect()
+                |metrics| metrics.iter().map(|m| json!({
+                    "link": m.link.dst.to_string(),
+                    "tx_msgs": m.tx_msgs,
+                    "tx_dropped": m.tx_dropped,
+                    "tx_bytes": m.tx_bytes,
+                    "rx_msgs": m.rx_msgs,
+                    "rx_bytes": m.rx_bytes,
+                    "rx_corrupted": m.rx_corrupted,
+                    "latency_us": m.latency.map(|l| l.as_micros() as u64),
+                    "rtt_us": m.rtt.map(|r| r.as_micros() as u64),
+                    "os_rtt_us": m.os_rtt.map(|r| r.as_micros() as u64),
+                    "loss": m.loss,
+                    "retransmissions": m.retransmissions,
+                    "degraded": m.degraded,
+                    "batch_fill_ratio": m.batch_fill_ratio,
+                    "compression_ratio": m.compression_ratio,
+                })).collect()
             ),
         });
         #[cfg(feature = "stats")]
@@ -637,6 +889,10 @@ zenoh_build{{version="{}"}} 1
             .openmetrics_text(),
     );
 
+    let transports = zenoh_runtime::ZRuntime::Net
+        .block_in_place(context.runtime.manager().get_transports_unicast());
+    metrics.push_str(&link_metrics_openmetrics_text(&transports));
+
     if let Err(e) = query
         .reply(Ok(Sample::new(
             reply_key,
@@ -648,6 +904,81 @@ zenoh_build{{version="{}"}} 1
     }
 }
 
+// The metrics of the links of the unicast transports, labelled by peer and link
+fn link_metrics_openmetrics_text(transports: &[TransportUnicast]) -> String {
+    let links: Vec<(ZenohId, zenoh_transport::unicast::TransportLinkMetrics)> = transports
+        .iter()
+        .filter_map(|t| Some((t.get_zid().ok()?, t.get_link_metrics().ok()?)))
+        .flat_map(|(zid, metrics)| metrics.into_iter().map(move |m| (zid, m)))
+        .collect();
+
+    let mut text = String::new();
+    let mut push =
+        |name: &str,
+         help: &str,
+         kind: &str,
+         value: &dyn Fn(&zenoh_transport::unicast::TransportLinkMetrics) -> Option<f64>| {
+            text.push_str(&format!("# HELP {name} {help}\n# TYPE {name} {kind}\n"));
+            for (zid, m) in links.iter() {
+                if let Some(v) = value(m) {
+                    text.push_str(&format!(
+                        "{name}{{peer=\"{zid}\",link=\"{}\"}} {v}\n",
+                        m.link.dst
+                    ));
+                }
+            }
+        };
+    push(
+        "zenoh_link_tx_msgs",
+        "Counter of network messages sent on a link.",
+        "counter",
+        &|m| Some(m.tx_msgs as f64),
+    );
+    push(
+        "zenoh_link_tx_dropped",
+        "Counter of network messages dropped by a link because of congestion.",
+        "counter",
+        &|m| Some(m.tx_dropped as f64),
+    );
+    push(
+        "zenoh_link_tx_bytes",
+        "Counter of bytes sent on a link.",
+        "counter",
+        &|m| Some(m.tx_bytes as f64),
+    );
+    push(
+        "zenoh_link_rx_msgs",
+        "Counter of network messages received on a link.",
+        "counter",
+        &|m| Some(m.rx_msgs as f64),
+    );
+    push(
+        "zenoh_link_rx_bytes",
+        "Counter of bytes received on a link.",
+        "counter",
+        &|m| Some(m.rx_bytes as f64),
+    );
+    push(
+        "zenoh_link_retransmissions",
+        "Counter of segments retransmitted by the OS on a link.",
+        "counter",
+        &|m| m.retransmissions.map(|r| r as f64),
+    );
+    push(
+        "zenoh_link_rtt_seconds",
+        "Round trip time estimate of a link, from its probes or else from the OS.",
+        "gauge",
+        &|m| m.rtt.or(m.os_rtt).map(|r| r.as_secs_f64()),
+    );
+    push(
+        "zenoh_link_batch_fill_ratio",
+        "Average ratio between the size of the batches sent on a link and their capacity.",
+        "gauge",
+        &|m| m.batch_fill_ratio,
+    );
+    text
+}
+
 fn routers_linkstate_data(context: &AdminContext, query: Query) {
     let reply_key: OwnedKeyExpr = format!(
         "@/{}/{}/linkstate/routers",
@@ -676,6 +1007,30 @@ fn routers_linkstate_data(context: &AdminContext, query: Query) {
     }
 }
 
+fn cluster_data(context: &AdminContext, query: Query) {
+    let reply_key: OwnedKeyExpr = format!(
+        "@/{}/{}/cluster",
+        context.runtime.state.whatami, context.runtime.state.zid
+    )
+    .try_into()
+    .unwrap();
+
+    let tables = zread!(context.runtime.state.router.tables.tables);
+
+    if let Some(cluster) = tables.hat_code.cluster_info(&tables) {
+        if let Err(e) = query
+            .reply(Ok(Sample::new(
+                reply_key,
+                Value::from(cluster.to_string().as_bytes().to_vec())
+                    .encoding(KnownEncoding::AppJson.into()),
+            )))
+            .res()
+        {
+            tracing::error!("Error sending AdminSpace reply: {:?}", e);
+        }
+    }
+}
+
 fn peers_linkstate_data(context: &AdminContext, query: Query) {
     let reply_key: OwnedKeyExpr = format!(
         "@/{}/{}/linkstate/peers",
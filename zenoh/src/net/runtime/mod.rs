@@ -17,12 +17,17 @@
 //! This module is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
-mod adminspace;
+pub(crate) mod adminspace;
 pub mod orchestrator;
+mod retained;
 
 use super::primitives::DeMux;
 use super::routing;
+#[cfg(feature = "unstable")]
+use super::routing::interceptor::authorizer_interceptor_factory;
 use super::routing::router::Router;
+#[cfg(feature = "unstable")]
+use crate::authorization::Authorizer;
 use crate::config::{unwrap_or_default, Config, ModeDependent, Notifier};
 #[cfg(all(feature = "unstable", feature = "plugins"))]
 use crate::plugins::sealed::PluginsManager;
@@ -30,19 +35,24 @@ use crate::{GIT_VERSION, LONG_VERSION};
 pub use adminspace::AdminSpace;
 use futures::stream::StreamExt;
 use futures::Future;
+pub use orchestrator::{EndpointKind, EndpointStatus};
 use std::any::Any;
 use std::sync::{Arc, Weak};
 #[cfg(all(feature = "unstable", feature = "plugins"))]
 use std::sync::{Mutex, MutexGuard};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use uhlc::{HLCBuilder, HLC};
+#[cfg(feature = "unstable")]
+use zenoh_core::zwrite;
 use zenoh_link::{EndPoint, Link};
 use zenoh_plugin_trait::{PluginStartArgs, StructVersion};
 use zenoh_protocol::core::{Locator, WhatAmI, ZenohId};
 use zenoh_protocol::network::NetworkMessage;
 use zenoh_result::{bail, ZResult};
+#[cfg(feature = "shared-memory")]
+use zenoh_shm::ShmProvider;
 use zenoh_sync::get_mut_unchecked;
 use zenoh_task::TaskController;
 use zenoh_transport::{
@@ -59,7 +69,11 @@ pub(crate) struct RuntimeState {
     manager: TransportManager,
     transport_handlers: std::sync::RwLock<Vec<Arc<dyn TransportEventHandler>>>,
     locators: std::sync::RwLock<Vec<Locator>>,
+    listeners: std::sync::Mutex<Vec<(EndPoint, Locator)>>,
+    endpoints_update: tokio::sync::Mutex<()>,
     hlc: Option<Arc<HLC>>,
+    drop_future_timestamp: bool,
+    start_time: Instant,
     task_controller: TaskController,
     #[cfg(all(feature = "unstable", feature = "plugins"))]
     plugins_manager: Mutex<PluginsManager>,
@@ -79,6 +93,10 @@ pub struct RuntimeBuilder {
     config: Config,
     #[cfg(all(feature = "unstable", feature = "plugins"))]
     plugins_manager: Option<PluginsManager>,
+    #[cfg(feature = "unstable")]
+    authorizer: Option<Authorizer>,
+    #[cfg(feature = "shared-memory")]
+    shm_provider: Option<Arc<dyn ShmProvider>>,
 }
 
 impl RuntimeBuilder {
@@ -87,9 +105,26 @@ impl RuntimeBuilder {
             config,
             #[cfg(all(feature = "unstable", feature = "plugins"))]
             plugins_manager: None,
+            #[cfg(feature = "unstable")]
+            authorizer: None,
+            #[cfg(feature = "shared-memory")]
+            shm_provider: None,
         }
     }
 
+    /// Sets the [`ShmProvider`] the shared memory segments are created and opened with.
+    #[cfg(feature = "shared-memory")]
+    pub fn shm_provider(mut self, shm_provider: Arc<dyn ShmProvider>) -> Self {
+        self.shm_provider = Some(shm_provider);
+        self
+    }
+
+    #[cfg(feature = "unstable")]
+    pub(crate) fn authorizer(mut self, authorizer: Option<Authorizer>) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
     #[cfg(all(feature = "unstable", feature = "plugins"))]
     pub fn plugins_manager<T: Into<Option<PluginsManager>>>(mut self, plugins_manager: T) -> Self {
         self.plugins_manager = plugins_manager.into();
@@ -101,6 +136,10 @@ impl RuntimeBuilder {
             config,
             #[cfg(all(feature = "unstable", feature = "plugins"))]
             mut plugins_manager,
+            #[cfg(feature = "unstable")]
+            authorizer,
+            #[cfg(feature = "shared-memory")]
+            shm_provider,
         } = self;
 
         tracing::debug!("Zenoh Rust API {}", GIT_VERSION);
@@ -111,8 +150,16 @@ impl RuntimeBuilder {
         let metadata = config.metadata().clone();
         let hlc = (*unwrap_or_default!(config.timestamping().enabled().get(whatami)))
             .then(|| Arc::new(HLCBuilder::new().with_id(uhlc::ID::from(&zid)).build()));
+        let drop_future_timestamp =
+            unwrap_or_default!(config.timestamping().drop_future_timestamp());
 
         let router = Arc::new(Router::new(zid, whatami, hlc.clone(), &config)?);
+        #[cfg(feature = "unstable")]
+        if let Some(authorizer) = authorizer {
+            zwrite!(router.tables.tables)
+                .interceptors
+                .push(authorizer_interceptor_factory(authorizer));
+        }
 
         let handler = Arc::new(RuntimeTransportEventHandler {
             runtime: std::sync::RwLock::new(WeakRuntime { state: Weak::new() }),
@@ -122,8 +169,15 @@ impl RuntimeBuilder {
             .from_config(&config)
             .await?
             .whatami(whatami)
-            .zid(zid)
-            .build(handler.clone())?;
+            .zid(zid);
+        #[cfg(feature = "shared-memory")]
+        let transport_manager = match shm_provider {
+            Some(shm_provider) => transport_manager.shm_provider(shm_provider),
+            None => transport_manager,
+        };
+        let transport_manager = transport_manager.build(handler.clone())?;
+        zwrite!(router.tables.tables).memory_budget =
+            transport_manager.config.memory_budget.clone();
 
         // Plugins manager
         #[cfg(all(feature = "unstable", feature = "plugins"))]
@@ -132,6 +186,11 @@ impl RuntimeBuilder {
             .unwrap_or_else(|| crate::plugins::loader::load_plugins(&config));
         // Admin space creation flag
         let start_admin_space = *config.adminspace.enabled();
+        // Key expressions retained by the routers cluster
+        let cluster_retained = (whatami == WhatAmI::Router
+            && unwrap_or_default!(config.routing().router().cluster().enabled()))
+        .then(|| config.routing().router().cluster().retained().clone())
+        .filter(|retained| !retained.is_empty());
 
         let config = Notifier::new(config);
         let runtime = Runtime {
@@ -144,7 +203,11 @@ impl RuntimeBuilder {
                 manager: transport_manager,
                 transport_handlers: std::sync::RwLock::new(vec![]),
                 locators: std::sync::RwLock::new(vec![]),
+                listeners: std::sync::Mutex::new(vec![]),
+                endpoints_update: tokio::sync::Mutex::new(()),
                 hlc,
+                drop_future_timestamp,
+                start_time: Instant::now(),
                 task_controller: TaskController::default(),
                 #[cfg(all(feature = "unstable", feature = "plugins"))]
                 plugins_manager: Mutex::new(plugins_manager),
@@ -158,6 +221,11 @@ impl RuntimeBuilder {
             AdminSpace::start(&runtime, LONG_VERSION.clone()).await;
         }
 
+        // Retained publications of the routers cluster
+        if let Some(key_exprs) = cluster_retained {
+            retained::start(&runtime, key_exprs);
+        }
+
         // Start plugins
         #[cfg(all(feature = "unstable", feature = "plugins"))]
         crate::plugins::loader::start_plugins(&runtime);
@@ -174,9 +242,19 @@ impl RuntimeBuilder {
                         res = stream.next() => {
                             match res {
                                 Some(event) => {
-                                    if &*event == "connect/endpoints" {
-                                        if let Err(e) = runtime2.update_peers().await {
-                                            tracing::error!("Error updating peers: {}", e);
+                                    let kind = match &*event {
+                                        "listen/endpoints" => EndpointKind::Listen,
+                                        "connect/endpoints" => EndpointKind::Connect,
+                                        _ => continue,
+                                    };
+                                    for status in runtime2.update_endpoints(kind).await {
+                                        if let Err(e) = status.result {
+                                            tracing::error!(
+                                                "Error updating {:?} endpoint {}: {}",
+                                                status.kind,
+                                                status.endpoint,
+                                                e
+                                            );
                                         }
                                     }
                                 },
@@ -225,6 +303,11 @@ impl Runtime {
         zwrite!(self.state.transport_handlers).push(handler);
     }
 
+    pub(crate) fn remove_handler(&self, handler: &Arc<dyn TransportEventHandler>) {
+        zwrite!(self.state.transport_handlers)
+            .retain(|h| Arc::as_ptr(h) as *const () != Arc::as_ptr(handler) as *const ());
+    }
+
     pub async fn close(&self) -> ZResult<()> {
         tracing::trace!("Runtime::close())");
         // TODO: Plugins should be stopped
@@ -254,6 +337,11 @@ impl Runtime {
         self.state.hlc.as_ref().map(|hlc| hlc.new_timestamp())
     }
 
+    /// The time elapsed since this runtime was built.
+    pub fn uptime(&self) -> Duration {
+        self.state.start_time.elapsed()
+    }
+
     pub fn get_locators(&self) -> Vec<Locator> {
         self.state.locators.read().unwrap().clone()
     }
@@ -294,6 +382,14 @@ impl Runtime {
         self.state.hlc.as_ref().map(Arc::as_ref)
     }
 
+    pub(crate) fn shared_hlc(&self) -> Option<Arc<HLC>> {
+        self.state.hlc.clone()
+    }
+
+    pub(crate) fn drop_future_timestamp(&self) -> bool {
+        self.state.drop_future_timestamp
+    }
+
     pub fn zid(&self) -> ZenohId {
         self.state.zid
     }
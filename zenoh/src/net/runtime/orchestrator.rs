@@ -26,9 +26,10 @@ use zenoh_config::{
 use zenoh_link::{Locator, LocatorInspector};
 use zenoh_protocol::{
     core::{whatami::WhatAmIMatcher, EndPoint, WhatAmI, ZenohId},
-    scouting::{Hello, Scout, ScoutingBody, ScoutingMessage},
+    scouting::{hello, Hello, Scout, ScoutingBody, ScoutingMessage},
 };
 use zenoh_result::{bail, zerror, ZResult};
+use zenoh_transport::unicast::TransportUnicast;
 
 const RCV_BUF_SIZE: usize = u16::MAX as usize;
 const SCOUT_INITIAL_PERIOD: Duration = Duration::from_millis(1_000);
@@ -42,6 +43,29 @@ pub enum Loop {
     Break,
 }
 
+/// The kind of an endpoint of a [`Runtime`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndpointKind {
+    /// An endpoint the runtime listens on (`listen/endpoints`).
+    Listen,
+    /// An endpoint the runtime connects to (`connect/endpoints`).
+    Connect,
+}
+
+/// The outcome of adding or removing an endpoint of a running [`Runtime`].
+#[derive(Debug)]
+pub struct EndpointStatus {
+    pub kind: EndpointKind,
+    pub endpoint: EndPoint,
+    /// `true` if the endpoint was added, `false` if it was removed.
+    pub added: bool,
+    /// The error raised when opening or closing the endpoint, if any.
+    ///
+    /// The connections to the added [`EndpointKind::Connect`] endpoints are established in the
+    /// background according to their retry configuration: only their scheduling is reported here.
+    pub result: ZResult<()>,
+}
+
 impl Runtime {
     pub async fn start(&mut self) -> ZResult<()> {
         match self.whatami() {
@@ -67,7 +91,7 @@ impl Runtime {
             0 => {
                 if scouting {
                     tracing::info!("Scouting for router ...");
-                    let ifaces = Runtime::get_interfaces(&ifaces);
+                    let ifaces = Runtime::get_group_interfaces(&ifaces, &addr);
                     if ifaces.is_empty() {
                         bail!("Unable to find multicast interface!")
                     } else {
@@ -188,7 +212,7 @@ impl Runtime {
             let guard = self.state.config.lock();
             unwrap_or_default!(guard.scouting().multicast().ttl())
         };
-        let ifaces = Runtime::get_interfaces(&ifaces);
+        let ifaces = Runtime::get_group_interfaces(&ifaces, &addr);
         let mcast_socket = Runtime::bind_mcast_port(&addr, &ifaces, multicast_ttl).await?;
         if !ifaces.is_empty() {
             let sockets: Vec<UdpSocket> = ifaces
@@ -228,12 +252,10 @@ impl Runtime {
         if timeout.is_zero() {
             self.connect_peers_impl(peers, single_link).await
         } else {
-            let res = tokio::time::timeout(timeout, async {
-                self.connect_peers_impl(peers, single_link).await.ok()
-            })
-            .await;
+            let res =
+                tokio::time::timeout(timeout, self.connect_peers_impl(peers, single_link)).await;
             match res {
-                Ok(_) => Ok(()),
+                Ok(res) => res,
                 Err(_) => {
                     let e = zerror!(
                         "{:?} Unable to connect to any of {:?}! ",
@@ -276,8 +298,9 @@ impl Runtime {
                 }
             } else {
                 // try to connect with retry waiting
-                self.peer_connector_retry(endpoint).await;
-                return Ok(());
+                if self.peer_connector_retry(endpoint).await.is_ok() {
+                    return Ok(());
+                }
             }
         }
         let e = zerror!(
@@ -308,7 +331,7 @@ impl Runtime {
                 }
             } else if retry_config.exit_on_failure {
                 // try to connect with retry waiting
-                self.peer_connector_retry(endpoint).await;
+                self.peer_connector_retry(endpoint).await?;
             } else {
                 // try to connect in background
                 self.spawn_peer_connector(endpoint).await?
@@ -321,7 +344,10 @@ impl Runtime {
         match tokio::time::timeout(timeout, self.manager().open_transport_unicast(peer.clone()))
             .await
         {
-            Ok(Ok(_)) => Ok(()),
+            Ok(Ok(transport)) => {
+                Self::set_transport_endpoint(&transport, peer);
+                Ok(())
+            }
             Ok(Err(e)) => {
                 tracing::warn!("Unable to connect to {}! {}", peer, e);
                 Err(e)
@@ -333,53 +359,184 @@ impl Runtime {
         }
     }
 
-    pub(crate) async fn update_peers(&self) -> ZResult<()> {
+    /// Add `endpoint` to the `listen` or `connect` configuration of this runtime and open it.
+    ///
+    /// Returns the status of the endpoint, or nothing if it was already configured.
+    pub async fn add_endpoint(
+        &self,
+        kind: EndpointKind,
+        endpoint: EndPoint,
+    ) -> Vec<EndpointStatus> {
+        {
+            let mut guard = self.state.config.lock();
+            let endpoints = match kind {
+                EndpointKind::Listen => {
+                    if guard.listen.endpoints.is_empty() {
+                        // Keep the default listeners
+                        guard.listen.endpoints = zlock!(self.state.listeners)
+                            .iter()
+                            .map(|(e, _)| e.clone())
+                            .collect();
+                    }
+                    &mut guard.listen.endpoints
+                }
+                EndpointKind::Connect => &mut guard.connect.endpoints,
+            };
+            if endpoints.contains(&endpoint) {
+                return vec![];
+            }
+            endpoints.push(endpoint);
+        }
+        self.update_endpoints(kind).await
+    }
+
+    /// Remove `endpoint` from the `listen` or `connect` configuration of this runtime and close it.
+    ///
+    /// Returns the status of the endpoint, or nothing if it was not configured.
+    pub async fn remove_endpoint(
+        &self,
+        kind: EndpointKind,
+        endpoint: &EndPoint,
+    ) -> Vec<EndpointStatus> {
+        {
+            let mut guard = self.state.config.lock();
+            let endpoints = match kind {
+                EndpointKind::Listen => &mut guard.listen.endpoints,
+                EndpointKind::Connect => &mut guard.connect.endpoints,
+            };
+            let len = endpoints.len();
+            endpoints.retain(|e| e != endpoint);
+            if endpoints.len() == len {
+                return vec![];
+            }
+        }
+        self.update_endpoints(kind).await
+    }
+
+    /// Apply the current `listen` or `connect` configuration of this runtime.
+    pub async fn update_endpoints(&self, kind: EndpointKind) -> Vec<EndpointStatus> {
+        match kind {
+            EndpointKind::Listen => self.update_listeners().await,
+            EndpointKind::Connect => self.update_peers().await,
+        }
+    }
+
+    /// Open the listeners added to the `listen` configuration and close the ones removed from it.
+    pub async fn update_listeners(&self) -> Vec<EndpointStatus> {
+        let _guard = self.state.endpoints_update.lock().await;
+        let endpoints = { self.state.config.lock().listen().endpoints().clone() };
+        let removed = {
+            let mut listeners = zlock!(self.state.listeners);
+            let (removed, kept): (Vec<_>, Vec<_>) = listeners
+                .drain(..)
+                .partition(|(e, _)| !endpoints.contains(e));
+            *listeners = kept;
+            removed
+        };
+        let mut statuses = vec![];
+        for (endpoint, locator) in removed {
+            let result = self.manager().del_listener(&locator.into()).await;
+            match &result {
+                Ok(()) => tracing::debug!("Listener removed: {}", endpoint),
+                Err(e) => tracing::warn!("Unable to remove listener {}: {}", endpoint, e),
+            }
+            statuses.push(EndpointStatus {
+                kind: EndpointKind::Listen,
+                endpoint,
+                added: false,
+                result,
+            });
+        }
+        let added = {
+            let listeners = zlock!(self.state.listeners);
+            endpoints
+                .into_iter()
+                .filter(|e| !listeners.iter().any(|(l, _)| l == e))
+                .collect::<Vec<_>>()
+        };
+        for endpoint in added {
+            let result = self.add_listener(endpoint.clone()).await;
+            statuses.push(EndpointStatus {
+                kind: EndpointKind::Listen,
+                endpoint,
+                added: true,
+                result,
+            });
+        }
+        if !statuses.is_empty() {
+            self.print_locators();
+        }
+        statuses
+    }
+
+    /// Connect to the endpoints added to the `connect` configuration and close the transports
+    /// established with the ones removed from it.
+    pub async fn update_peers(&self) -> Vec<EndpointStatus> {
+        let _guard = self.state.endpoints_update.lock().await;
         let peers = { self.state.config.lock().connect().endpoints().clone() };
         let tranports = self.manager().get_transports_unicast().await;
+        let mut statuses = vec![];
 
-        if self.state.whatami == WhatAmI::Client {
-            for transport in tranports {
-                let should_close = if let Ok(Some(orch_transport)) = transport.get_callback() {
-                    if let Some(orch_transport) = orch_transport
-                        .as_any()
-                        .downcast_ref::<super::RuntimeSession>()
+        let mut connected = vec![];
+        for transport in tranports {
+            let endpoint = Self::get_transport_endpoint(&transport);
+            match endpoint {
+                Some(endpoint) if !peers.contains(&endpoint) => {
+                    let result = transport.close().await;
+                    statuses.push(EndpointStatus {
+                        kind: EndpointKind::Connect,
+                        endpoint,
+                        added: false,
+                        result,
+                    });
+                }
+                Some(endpoint) => connected.push(endpoint),
+                None => {
+                    // Clients only keep the connections to their configured endpoints
+                    if self.state.whatami == WhatAmI::Client
+                        && transport
+                            .get_callback()
+                            .ok()
+                            .flatten()
+                            .is_some_and(|cb| cb.as_any().is::<super::RuntimeSession>())
                     {
-                        if let Some(endpoint) = &*zread!(orch_transport.endpoint) {
-                            !peers.contains(endpoint)
-                        } else {
-                            true
-                        }
-                    } else {
-                        false
+                        let _ = transport.close().await;
                     }
-                } else {
-                    false
-                };
-                if should_close {
-                    transport.close().await?;
                 }
             }
-        } else {
+        }
+
+        // Clients reconnect to their configured endpoints when their connection is closed
+        if self.state.whatami != WhatAmI::Client {
             for peer in peers {
-                if !tranports.iter().any(|transport| {
-                    if let Ok(Some(orch_transport)) = transport.get_callback() {
-                        if let Some(orch_transport) = orch_transport
-                            .as_any()
-                            .downcast_ref::<super::RuntimeSession>()
-                        {
-                            if let Some(endpoint) = &*zread!(orch_transport.endpoint) {
-                                return *endpoint == peer;
-                            }
-                        }
-                    }
-                    false
-                }) {
-                    self.spawn_peer_connector(peer).await?;
+                if !connected.contains(&peer) {
+                    let result = self.spawn_peer_connector(peer.clone()).await;
+                    statuses.push(EndpointStatus {
+                        kind: EndpointKind::Connect,
+                        endpoint: peer,
+                        added: true,
+                        result,
+                    });
                 }
             }
         }
 
-        Ok(())
+        statuses
+    }
+
+    fn get_transport_endpoint(transport: &TransportUnicast) -> Option<EndPoint> {
+        let orch_transport = transport.get_callback().ok()??;
+        let orch_transport = orch_transport.as_any().downcast_ref::<RuntimeSession>()?;
+        let endpoint = zread!(orch_transport.endpoint).clone();
+        endpoint
+    }
+
+    fn set_transport_endpoint(transport: &TransportUnicast, endpoint: EndPoint) {
+        if let Ok(Some(orch_transport)) = transport.get_callback() {
+            if let Some(orch_transport) = orch_transport.as_any().downcast_ref::<RuntimeSession>() {
+                *zwrite!(orch_transport.endpoint) = Some(endpoint);
+            }
+        }
     }
 
     fn get_listen_retry_config(&self, endpoint: &EndPoint) -> zenoh_config::ConnectionRetryConf {
@@ -412,12 +569,9 @@ impl Runtime {
         if timeout.is_zero() {
             self.bind_listeners_impl(listeners).await
         } else {
-            let res = tokio::time::timeout(timeout, async {
-                self.bind_listeners_impl(listeners).await.ok()
-            })
-            .await;
+            let res = tokio::time::timeout(timeout, self.bind_listeners_impl(listeners)).await;
             match res {
-                Ok(_) => Ok(()),
+                Ok(res) => res,
                 Err(e) => {
                     tracing::error!("Unable to open listeners: {}", e);
                     Err(Box::new(e))
@@ -440,7 +594,7 @@ impl Runtime {
                 };
             } else if retry_config.exit_on_failure {
                 // try to add listener with retry waiting
-                self.add_listener_retry(endpoint, retry_config).await
+                self.add_listener_retry(endpoint, retry_config).await?
             } else {
                 // try to add listener in background
                 self.spawn_add_listener(endpoint, retry_config).await
@@ -457,8 +611,13 @@ impl Runtime {
     ) {
         let this = self.clone();
         self.spawn(async move {
-            this.add_listener_retry(listener, retry_config).await;
-            this.print_locators();
+            if this
+                .add_listener_retry(listener, retry_config)
+                .await
+                .is_ok()
+            {
+                this.print_locators();
+            }
         });
     }
 
@@ -466,20 +625,35 @@ impl Runtime {
         &self,
         listener: EndPoint,
         retry_config: zenoh_config::ConnectionRetryConf,
-    ) {
+    ) -> ZResult<()> {
         let mut period = retry_config.period();
         loop {
             if self.add_listener(listener.clone()).await.is_ok() {
-                break;
+                return Ok(());
+            }
+            if period.is_exhausted() {
+                let e = zerror!(
+                    "Unable to add listener {} after {} attempts",
+                    listener,
+                    retry_config.max_attempts
+                );
+                tracing::error!("{}", e);
+                return Err(e.into());
             }
             tokio::time::sleep(period.next_duration()).await;
         }
     }
 
     async fn add_listener(&self, listener: EndPoint) -> ZResult<()> {
+        #[cfg(feature = "transport_udp")]
+        let endpoint = self.nat_traversal_listener(listener.clone())?;
+        #[cfg(not(feature = "transport_udp"))]
         let endpoint = listener.clone();
         match self.manager().add_listener(endpoint).await {
-            Ok(listener) => tracing::debug!("Listener added: {}", listener),
+            Ok(locator) => {
+                tracing::debug!("Listener added: {}", locator);
+                zlock!(self.state.listeners).push((listener, locator));
+            }
             Err(err) => {
                 tracing::warn!("Unable to open listener {}: {}", listener, err);
                 return Err(err);
@@ -530,12 +704,68 @@ impl Runtime {
         }
     }
 
+    /// Get the addresses of the interfaces to scout on for the given multicast group, given as
+    /// in [`get_interfaces`](Self::get_interfaces). The interfaces given by name are resolved
+    /// to one of their addresses of the address family of the group.
+    pub fn get_group_interfaces(names: &str, group: &SocketAddr) -> Vec<IpAddr> {
+        if group.is_ipv4() {
+            return Self::get_interfaces(names);
+        }
+        if names == "auto" {
+            let ifaces = zenoh_util::net::get_ipv6_multicast_interfaces();
+            if ifaces.is_empty() {
+                tracing::warn!(
+                    "Unable to find active, non-loopback IPv6 multicast interface. Will use [::]."
+                );
+                vec![Ipv6Addr::UNSPECIFIED.into()]
+            } else {
+                ifaces
+            }
+        } else {
+            names
+                .split(',')
+                .filter_map(|name| match name.trim().parse::<IpAddr>() {
+                    Ok(addr) => Some(addr),
+                    Err(_) => {
+                        match zenoh_util::net::get_unicast_addresses_of_interface(name.trim()) {
+                            Ok(addrs) => {
+                                let addr = addrs.into_iter().find(|addr| addr.is_ipv6());
+                                if addr.is_none() {
+                                    tracing::error!(
+                                        "Unable to find an IPv6 address of interface {}",
+                                        name
+                                    );
+                                }
+                                addr
+                            }
+                            Err(err) => {
+                                tracing::error!("Unable to find interface {}: {}", name, err);
+                                None
+                            }
+                        }
+                    }
+                })
+                .collect()
+        }
+    }
+
+    // The index of the interface with the given address, 0 letting the system select one
+    fn get_interface_index(addr: &IpAddr) -> u32 {
+        if addr.is_unspecified() {
+            return 0;
+        }
+        zenoh_util::net::get_index_of_interface(*addr).unwrap_or_else(|err| {
+            tracing::warn!("Unable to find the index of interface {}: {}", addr, err);
+            0
+        })
+    }
+
     pub async fn bind_mcast_port(
         sockaddr: &SocketAddr,
         ifaces: &[IpAddr],
         multicast_ttl: u32,
     ) -> ZResult<UdpSocket> {
-        let socket = match Socket::new(Domain::IPV4, Type::DGRAM, None) {
+        let socket = match Socket::new(Domain::for_address(*sockaddr), Type::DGRAM, None) {
             Ok(socket) => socket,
             Err(err) => {
                 tracing::error!("Unable to create datagram socket: {}", err);
@@ -546,15 +776,20 @@ impl Runtime {
             tracing::error!("Unable to set SO_REUSEADDR option: {}", err);
             bail!(err => "Unable to set SO_REUSEADDR option");
         }
-        let addr: IpAddr = {
+        if sockaddr.is_ipv6() {
+            if let Err(err) = socket.set_only_v6(true) {
+                tracing::error!("Unable to set IPV6_V6ONLY option: {}", err);
+                bail!(err => "Unable to set IPV6_V6ONLY option");
+            }
+        }
+        let addr: IpAddr = match sockaddr {
             #[cfg(unix)]
-            {
-                sockaddr.ip()
-            } // See UNIX Network Programmping p.212
+            SocketAddr::V4(_) => sockaddr.ip(), // See UNIX Network Programmping p.212
             #[cfg(windows)]
-            {
-                std::net::Ipv4Addr::UNSPECIFIED.into()
-            }
+            SocketAddr::V4(_) => std::net::Ipv4Addr::UNSPECIFIED.into(),
+            // The IPv6 groups of a scope narrower than global can not be bound to without their
+            // scope, which the groups joined on several interfaces do not have
+            SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
         };
         match socket.bind(&SocketAddr::new(addr, sockaddr.port()).into()) {
             Ok(()) => tracing::debug!("UDP port bound to {}", sockaddr),
@@ -565,22 +800,48 @@ impl Runtime {
         }
 
         match sockaddr.ip() {
-            IpAddr::V6(addr) => match socket.join_multicast_v6(&addr, 0) {
-                Ok(()) => {
-                    tracing::debug!("Joined multicast group {} on interface 0", sockaddr.ip())
+            IpAddr::V6(addr) => {
+                let mut joined = false;
+                for iface in ifaces {
+                    if iface.is_ipv4() {
+                        tracing::warn!(
+                            "Cannot join IpV6 multicast group {} on IpV4 iface {}",
+                            sockaddr.ip(),
+                            iface
+                        );
+                        continue;
+                    }
+                    let index = Self::get_interface_index(iface);
+                    match socket.join_multicast_v6(&addr, index) {
+                        Ok(()) => {
+                            tracing::debug!(
+                                "Joined multicast group {} on interface {} (index {})",
+                                sockaddr.ip(),
+                                iface,
+                                index
+                            );
+                            joined = true;
+                        }
+                        Err(err) => tracing::warn!(
+                            "Unable to join multicast group {} on interface {} (index {}): {}",
+                            sockaddr.ip(),
+                            iface,
+                            index,
+                            err
+                        ),
+                    }
                 }
-                Err(err) => {
+                if !joined {
                     tracing::error!(
-                        "Unable to join multicast group {} on interface 0: {}",
-                        sockaddr.ip(),
-                        err
+                        "Unable to join multicast group {} on any interface",
+                        sockaddr.ip()
                     );
-                    bail!(err =>
-                        "Unable to join multicast group {} on interface 0",
+                    bail!(
+                        "Unable to join multicast group {} on any interface",
                         sockaddr.ip()
                     )
                 }
-            },
+            }
             IpAddr::V4(addr) => {
                 for iface in ifaces {
                     if let IpAddr::V4(iface_addr) = iface {
@@ -612,10 +873,9 @@ impl Runtime {
         // Must set to nonblocking according to the doc of tokio
         // https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#notes
         socket.set_nonblocking(true)?;
-        socket.set_multicast_ttl_v4(multicast_ttl)?;
-
-        if sockaddr.is_ipv6() && multicast_ttl > 1 {
-            tracing::warn!("UDP Multicast TTL has been set to a value greater than 1 on a socket bound to an IPv6 address. This might not have the desired effect");
+        match sockaddr {
+            SocketAddr::V4(_) => socket.set_multicast_ttl_v4(multicast_ttl)?,
+            SocketAddr::V6(_) => socket.set_multicast_hops_v6(multicast_ttl)?,
         }
 
         // UdpSocket::from_std requires a runtime even though it's a sync function
@@ -625,33 +885,49 @@ impl Runtime {
     }
 
     pub fn bind_ucast_port(addr: IpAddr, multicast_ttl: u32) -> ZResult<UdpSocket> {
-        let socket = match Socket::new(Domain::IPV4, Type::DGRAM, None) {
+        let socket = match Socket::new(
+            Domain::for_address(SocketAddr::new(addr, 0)),
+            Type::DGRAM,
+            None,
+        ) {
             Ok(socket) => socket,
             Err(err) => {
                 tracing::warn!("Unable to create datagram socket: {}", err);
                 bail!(err=> "Unable to create datagram socket");
             }
         };
-        match socket.bind(&SocketAddr::new(addr, 0).into()) {
+        // The IPv6 link-local addresses can not be bound to without their scope, the interface
+        // is selected by its index instead
+        let bind_addr = match addr {
+            IpAddr::V6(v6) if v6.segments()[0] & 0xffc0 == 0xfe80 => Ipv6Addr::UNSPECIFIED.into(),
+            _ => addr,
+        };
+        match socket.bind(&SocketAddr::new(bind_addr, 0).into()) {
             Ok(()) => {
                 #[allow(clippy::or_fun_call)]
                 let local_addr = socket
                     .local_addr()
-                    .unwrap_or(SocketAddr::new(addr, 0).into())
+                    .unwrap_or(SocketAddr::new(bind_addr, 0).into())
                     .as_socket()
-                    .unwrap_or(SocketAddr::new(addr, 0));
+                    .unwrap_or(SocketAddr::new(bind_addr, 0));
                 tracing::debug!("UDP port bound to {}", local_addr);
             }
             Err(err) => {
-                tracing::warn!("Unable to bind udp port {}:0: {}", addr, err);
-                bail!(err => "Unable to bind udp port {}:0", addr);
+                tracing::warn!("Unable to bind udp port {}:0: {}", bind_addr, err);
+                bail!(err => "Unable to bind udp port {}:0", bind_addr);
             }
         }
 
         // Must set to nonblocking according to the doc of tokio
         // https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#notes
         socket.set_nonblocking(true)?;
-        socket.set_multicast_ttl_v4(multicast_ttl)?;
+        match addr {
+            IpAddr::V4(_) => socket.set_multicast_ttl_v4(multicast_ttl)?,
+            IpAddr::V6(_) => {
+                socket.set_multicast_if_v6(Self::get_interface_index(&addr))?;
+                socket.set_multicast_hops_v6(multicast_ttl)?;
+            }
+        }
 
         // UdpSocket::from_std requires a runtime even though it's a sync function
         let udp_socket = zenoh_runtime::ZRuntime::Net
@@ -665,18 +941,31 @@ impl Runtime {
             .await?
         {
             let this = self.clone();
-            self.spawn(async move { this.peer_connector_retry(peer).await });
+            self.spawn(async move {
+                let _ = this.peer_connector_retry(peer).await;
+            });
             Ok(())
         } else {
             bail!("Forbidden multicast endpoint in connect list!")
         }
     }
 
-    async fn peer_connector_retry(&self, peer: EndPoint) {
+    async fn peer_connector_retry(&self, peer: EndPoint) -> ZResult<()> {
         let retry_config = self.get_connect_retry_config(&peer);
         let mut period = retry_config.period();
         let cancellation_token = self.get_cancellation_token();
         loop {
+            if !self
+                .state
+                .config
+                .lock()
+                .connect()
+                .endpoints()
+                .contains(&peer)
+            {
+                tracing::debug!("Stop connecting to {}: not configured anymore", peer);
+                break;
+            }
             tracing::trace!("Trying to connect to configured peer {}", peer);
             let endpoint = peer.clone();
             tokio::select! {
@@ -684,14 +973,7 @@ impl Runtime {
                     match res {
                         Ok(Ok(transport)) => {
                             tracing::debug!("Successfully connected to configured peer {}", peer);
-                            if let Ok(Some(orch_transport)) = transport.get_callback() {
-                                if let Some(orch_transport) = orch_transport
-                                    .as_any()
-                                    .downcast_ref::<super::RuntimeSession>()
-                                {
-                                    *zwrite!(orch_transport.endpoint) = Some(peer);
-                                }
-                            }
+                            Self::set_transport_endpoint(&transport, peer);
                             break;
                         }
                         Ok(Err(e)) => {
@@ -714,8 +996,18 @@ impl Runtime {
                 }
                 _ = cancellation_token.cancelled() => { break; }
             }
+            if period.is_exhausted() {
+                let e = zerror!(
+                    "Unable to connect to configured peer {} after {} attempts",
+                    peer,
+                    retry_config.max_attempts
+                );
+                tracing::warn!("{}", e);
+                return Err(e.into());
+            }
             tokio::time::sleep(period.next_duration()).await;
         }
+        Ok(())
     }
 
     pub async fn scout<Fut, F>(
@@ -868,6 +1160,11 @@ impl Runtime {
             }
         }
 
+        #[cfg(feature = "transport_udp")]
+        if self.connect_through_nat(zid).await {
+            return true;
+        }
+
         tracing::warn!(
             "Unable to connect to any locator of scouted peer {}: {:?}",
             zid,
@@ -876,6 +1173,109 @@ impl Runtime {
         false
     }
 
+    /// Enables the NAT traversal on a UDP listener: routers act as rendezvous on their
+    /// listeners, peers register their listeners with their rendezvous.
+    #[cfg(feature = "transport_udp")]
+    fn nat_traversal_listener(&self, mut endpoint: EndPoint) -> ZResult<EndPoint> {
+        use zenoh_link::udp::{config::*, UDP_LOCATOR_PREFIX};
+
+        if endpoint.protocol().as_str() != UDP_LOCATOR_PREFIX {
+            return Ok(endpoint);
+        }
+        let (enabled, rendezvous, secret) = {
+            let guard = &self.state.config.lock();
+            (
+                unwrap_or_default!(guard.scouting().nat_traversal().enabled()),
+                guard.scouting().nat_traversal().rendezvous().clone(),
+                guard.scouting().nat_traversal().secret().clone(),
+            )
+        };
+        if !enabled {
+            return Ok(endpoint);
+        }
+        let secret = secret.ok_or_else(|| {
+            zerror!("scouting/nat_traversal/secret must be set when NAT traversal is enabled")
+        })?;
+        match (self.whatami(), rendezvous) {
+            (WhatAmI::Router, _) => {
+                let mut config = endpoint.config_mut();
+                config.insert(UDP_NAT_SERVER, "true")?;
+                config.insert(UDP_NAT_SECRET, &secret)?;
+            }
+            (WhatAmI::Peer, Some(rendezvous)) => {
+                let mut config = endpoint.config_mut();
+                config.insert(UDP_NAT_RENDEZVOUS, rendezvous.address().as_str())?;
+                config.insert(UDP_NAT_ID, &self.zid().to_string())?;
+                config.insert(UDP_NAT_SECRET, &secret)?;
+            }
+            _ => {}
+        }
+        Ok(endpoint)
+    }
+
+    /// Opens a UDP link to a peer through its NAT, with the help of the rendezvous both peers are
+    /// registered with. The peers keep communicating through the routers if it fails.
+    #[cfg(feature = "transport_udp")]
+    async fn connect_through_nat(&self, zid: &ZenohId) -> bool {
+        use zenoh_link::udp::config::UDP_NAT_TARGET;
+
+        let rendezvous = {
+            let guard = &self.state.config.lock();
+            if !unwrap_or_default!(guard.scouting().nat_traversal().enabled()) {
+                return false;
+            }
+            guard.scouting().nat_traversal().rendezvous().clone()
+        };
+        let Some(rendezvous) = rendezvous.filter(|_| self.whatami() == WhatAmI::Peer) else {
+            return false;
+        };
+
+        let mut endpoint: EndPoint = rendezvous.to_locator().into();
+        if let Err(e) = endpoint
+            .config_mut()
+            .insert(UDP_NAT_TARGET, &zid.to_string())
+        {
+            tracing::warn!("Invalid NAT traversal rendezvous {}: {}", rendezvous, e);
+            return false;
+        }
+        tracing::debug!(
+            "Try to connect to peer {} through its NAT with rendezvous {}",
+            zid,
+            rendezvous
+        );
+        let retry_config = self.get_connect_retry_config(&endpoint);
+        match tokio::time::timeout(
+            retry_config.timeout(),
+            self.manager().open_transport_unicast(endpoint),
+        )
+        .await
+        {
+            Ok(Ok(transport)) => {
+                tracing::debug!(
+                    "Successfully connected to newly scouted peer through its NAT: {:?}",
+                    transport
+                );
+                true
+            }
+            Ok(Err(e)) => {
+                tracing::debug!(
+                    "Unable to connect to peer {} through its NAT, communicating through the routers: {}",
+                    zid,
+                    e
+                );
+                false
+            }
+            Err(e) => {
+                tracing::debug!(
+                    "Unable to connect to peer {} through its NAT, communicating through the routers: {}",
+                    zid,
+                    e
+                );
+                false
+            }
+        }
+    }
+
     pub async fn connect_peer(&self, zid: &ZenohId, locators: &[Locator]) {
         let manager = self.manager();
         if zid != &manager.zid() {
@@ -950,6 +1350,17 @@ impl Runtime {
         .await
     }
 
+    /// The metadata advertised in the Hello messages, if any.
+    fn hello_metadata(&self) -> Option<hello::ext::Metadata> {
+        match &self.state.metadata {
+            serde_json::Value::Null => None,
+            serde_json::Value::Object(o) if o.is_empty() => None,
+            metadata => serde_json::to_vec(metadata)
+                .ok()
+                .map(|m| hello::ext::Metadata::new(m.into())),
+        }
+    }
+
     async fn responder(&self, mcast_socket: &UdpSocket, ucast_sockets: &[UdpSocket]) {
         fn get_best_match<'a>(addr: &IpAddr, sockets: &'a [UdpSocket]) -> Option<&'a UdpSocket> {
             fn octets(addr: &IpAddr) -> Vec<u8> {
@@ -1004,6 +1415,7 @@ impl Runtime {
                             whatami: self.whatami(),
                             zid,
                             locators: self.get_locators(),
+                            ext_metadata: self.hello_metadata(),
                         }
                         .into();
                         let socket = get_best_match(&peer.ip(), ucast_sockets).unwrap();
@@ -1041,6 +1453,13 @@ impl Runtime {
                     let retry_config = runtime.get_global_connect_retry_config();
                     let mut period = retry_config.period();
                     while runtime.start_client().await.is_err() {
+                        if period.is_exhausted() {
+                            tracing::error!(
+                                "Unable to reconnect to a router after {} attempts",
+                                retry_config.max_attempts
+                            );
+                            break;
+                        }
                         tokio::select! {
                             _ = tokio::time::sleep(period.next_duration()) => {}
                             _ = cancellation_token.cancelled() => { break; }
@@ -1063,9 +1482,9 @@ impl Runtime {
                     if peers.contains(endpoint) {
                         let endpoint = endpoint.clone();
                         let runtime = session.runtime.clone();
-                        session
-                            .runtime
-                            .spawn(async move { runtime.peer_connector_retry(endpoint).await });
+                        session.runtime.spawn(async move {
+                            let _ = runtime.peer_connector_retry(endpoint).await;
+                        });
                     }
                 }
             }
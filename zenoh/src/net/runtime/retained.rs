@@ -0,0 +1,218 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The publications retained by the routers of a cluster.
+//!
+//! Each member subscribes to the retained key expressions, so that the publications reach all
+//! of them through the routing, and keeps the last one of each key to reply to the queries.
+//! A member that joins the cluster, or that is reachable again after a split, retrieves the
+//! publications retained by the others.
+//!
+//! The retained publications are not replied to the queries while the member is not part of the
+//! partition of the cluster reaching the quorum, as they may be outdated by the other partition.
+use super::Runtime;
+use crate::prelude::r#async::*;
+use crate::prelude::sync::SyncResolve;
+use crate::queryable::Query;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh_core::{zlock, zread};
+use zenoh_result::ZResult;
+
+/// The interval the reachable members of the cluster are checked at.
+const ALIGNMENT_INTERVAL: Duration = Duration::from_secs(1);
+/// The parameter of the queries of the members retrieving the publications of the others.
+const ALIGNMENT_PARAMETER: &str = "_cluster_alignment";
+/// How long the deletions are retained.
+const TOMBSTONE_LIFETIME: Duration = Duration::from_secs(3600);
+
+#[derive(Default)]
+struct Retained {
+    // The deletions are retained as well, for an older publication not to be retrieved again
+    // from a member which missed them, until they are older than TOMBSTONE_LIFETIME
+    samples: Mutex<HashMap<OwnedKeyExpr, (Sample, Instant)>>,
+    quorum: AtomicBool,
+}
+
+impl Retained {
+    fn expire_tombstones(&self, now: Instant) {
+        zlock!(self.samples).retain(|_, (sample, since)| {
+            sample.kind == SampleKind::Put || now.duration_since(*since) < TOMBSTONE_LIFETIME
+        });
+    }
+}
+
+pub(crate) fn start(runtime: &Runtime, key_exprs: Vec<OwnedKeyExpr>) {
+    let runtime2 = runtime.clone();
+    runtime.spawn_abortable(async move {
+        if let Err(e) = run(runtime2, key_exprs).await {
+            tracing::error!("Unable to retain the publications of the cluster: {}", e);
+        }
+    });
+}
+
+async fn run(runtime: Runtime, key_exprs: Vec<OwnedKeyExpr>) -> ZResult<()> {
+    let session = Session::init(runtime.clone(), vec![], vec![])
+        .res_async()
+        .await;
+    let retained = Arc::new(Retained::default());
+    let mut subscribers = vec![];
+    let mut queryables = vec![];
+    for key_expr in &key_exprs {
+        let r = retained.clone();
+        subscribers.push(
+            session
+                .declare_subscriber(key_expr)
+                .callback(move |sample| retain(&r, sample))
+                .res_async()
+                .await?,
+        );
+        let r = retained.clone();
+        queryables.push(
+            session
+                .declare_queryable(key_expr)
+                .complete(true)
+                .callback(move |query| reply(&r, query))
+                .res_async()
+                .await?,
+        );
+    }
+
+    let mut aligned: HashSet<ZenohId> = HashSet::new();
+    loop {
+        let (reachable, quorum): (HashSet<ZenohId>, bool) = {
+            let tables = zread!(runtime.state.router.tables.tables);
+            let reachable = tables
+                .hat_code
+                .cluster_reachable(&tables)
+                .into_iter()
+                .filter(|zid| *zid != runtime.zid())
+                .collect();
+            (reachable, tables.hat_code.cluster_quorum_reached(&tables))
+        };
+        if retained.quorum.swap(quorum, Ordering::Relaxed) != quorum {
+            if quorum {
+                tracing::info!("Serving the retained publications of the cluster");
+            } else {
+                tracing::warn!(
+                    "Quorum not reached: not serving the retained publications of the cluster"
+                );
+            }
+        }
+        if reachable.difference(&aligned).next().is_some() {
+            // Leave time for the declarations of the members to be routed
+            tokio::time::sleep(ALIGNMENT_INTERVAL).await;
+            for key_expr in &key_exprs {
+                align(&session, key_expr, &retained).await;
+            }
+        }
+        aligned = reachable;
+        retained.expire_tombstones(Instant::now());
+        tokio::time::sleep(ALIGNMENT_INTERVAL).await;
+    }
+}
+
+fn retain(retained: &Retained, sample: Sample) {
+    let key_expr = OwnedKeyExpr::from(sample.key_expr.clone());
+    let mut samples = zlock!(retained.samples);
+    // A sample without timestamp is considered as the last one
+    let is_older = match (&sample.timestamp, samples.get(&key_expr)) {
+        (Some(timestamp), Some((last, _))) => last
+            .timestamp
+            .as_ref()
+            .is_some_and(|last| timestamp <= last),
+        _ => false,
+    };
+    if !is_older {
+        samples.insert(key_expr, (sample, Instant::now()));
+    }
+}
+
+fn reply(retained: &Retained, query: Query) {
+    let alignment = query.parameters() == ALIGNMENT_PARAMETER;
+    // The members keep aligning with each other whatever the quorum
+    if !alignment && !retained.quorum.load(Ordering::Relaxed) {
+        return;
+    }
+    let samples = zlock!(retained.samples)
+        .iter()
+        .filter(|(key_expr, (sample, _))| {
+            (alignment || sample.kind == SampleKind::Put) && query.key_expr().intersects(key_expr)
+        })
+        .map(|(_, (sample, _))| sample.clone())
+        .collect::<Vec<_>>();
+    for sample in samples {
+        if let Err(e) = query.reply(Ok(sample)).res_sync() {
+            tracing::error!("Error replying a retained publication: {}", e);
+        }
+    }
+}
+
+async fn align(session: &Session, key_expr: &OwnedKeyExpr, retained: &Retained) {
+    let selector = Selector::from(key_expr).with_parameters(ALIGNMENT_PARAMETER);
+    let replies = match session
+        .get(selector)
+        .target(QueryTarget::All)
+        .consolidation(ConsolidationMode::None)
+        .res_async()
+        .await
+    {
+        Ok(replies) => replies,
+        Err(e) => {
+            tracing::warn!("Unable to retrieve the publications of the cluster: {}", e);
+            return;
+        }
+    };
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.sample {
+            retain(retained, sample);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delete(key_expr: &'static str) -> Sample {
+        let mut sample = Sample::new(KeyExpr::try_from(key_expr).unwrap(), "");
+        sample.kind = SampleKind::Delete;
+        sample
+    }
+
+    #[test]
+    fn tombstones_expiration() {
+        let retained = Retained::default();
+        retain(&retained, delete("test/a"));
+        retain(
+            &retained,
+            Sample::new(KeyExpr::try_from("test/b").unwrap(), "b"),
+        );
+        let later = Instant::now() + TOMBSTONE_LIFETIME;
+        retained.expire_tombstones(later - Duration::from_secs(1));
+        assert_eq!(zlock!(retained.samples).len(), 2);
+
+        // Only the deletions retained for longer than their lifetime are forgotten
+        retain(&retained, delete("test/c"));
+        retained.expire_tombstones(later);
+        let mut keys: Vec<String> = zlock!(retained.samples)
+            .keys()
+            .map(|k| k.to_string())
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["test/b", "test/c"]);
+    }
+}
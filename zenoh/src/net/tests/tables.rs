@@ -443,6 +443,8 @@ impl Primitives for ClientPrimitives {
 
     fn send_response_final(&self, _msg: zenoh_protocol::network::ResponseFinal) {}
 
+    fn send_request_cancel(&self, _msg: zenoh_protocol::network::RequestCancel) {}
+
     fn send_close(&self) {}
 }
 
@@ -470,6 +472,8 @@ impl EPrimitives for ClientPrimitives {
 
     fn send_response_final(&self, _ctx: RoutingContext<zenoh_protocol::network::ResponseFinal>) {}
 
+    fn send_request_cancel(&self, _ctx: RoutingContext<zenoh_protocol::network::RequestCancel>) {}
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -629,8 +633,12 @@ fn client_test() {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
@@ -663,8 +671,12 @@ fn client_test() {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
@@ -697,8 +709,12 @@ fn client_test() {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
@@ -731,8 +747,12 @@ fn client_test() {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
@@ -765,8 +785,12 @@ fn client_test() {
             timestamp: None,
             encoding: Encoding::default(),
             ext_sinfo: None,
+            ext_coherence: None,
+            ext_ttl: None,
+            ext_latency_budget: None,
             #[cfg(feature = "shared-memory")]
             ext_shm: None,
+            ext_trace: None,
             ext_unknown: vec![],
             payload: ZBuf::empty(),
             ext_attachment: None,
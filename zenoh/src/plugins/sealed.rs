@@ -34,7 +34,7 @@ pub trait ZenohPlugin: Plugin<StartArgs = Runtime, Instance = RunningPlugin> {}
 
 impl StructVersion for RunningPlugin {
     fn struct_version() -> u64 {
-        1
+        2
     }
     fn struct_features() -> &'static str {
         crate::FEATURES
@@ -49,6 +49,18 @@ impl PluginControl for RunningPlugin {
     fn plugins_status(&self, names: &keyexpr) -> Vec<PluginStatusRec> {
         self.as_ref().plugins_status(names)
     }
+
+    fn pause(&mut self) -> ZResult<()> {
+        self.as_mut().pause()
+    }
+
+    fn resume(&mut self) -> ZResult<()> {
+        self.as_mut().resume()
+    }
+
+    fn reconfigure(&mut self) -> ZResult<()> {
+        self.as_mut().reconfigure()
+    }
 }
 
 impl PluginInstance for RunningPlugin {}
@@ -14,27 +14,43 @@
 
 //! Publishing primitives.
 #[zenoh_macros::unstable]
+use crate::extension::Extension;
+#[zenoh_macros::unstable]
 use crate::handlers::Callback;
 #[zenoh_macros::unstable]
 use crate::handlers::DefaultHandler;
 use crate::net::primitives::Primitives;
+use crate::net::routing::dispatcher::face::Face;
 use crate::prelude::*;
-#[zenoh_macros::unstable]
-use crate::sample::Attachment;
 use crate::sample::DataInfo;
 use crate::sample::QoS;
+#[zenoh_macros::unstable]
+use crate::sample::{Attachment, Coherence};
+use crate::sample::{EntityId, SourceSn};
+use crate::time::Timestamp;
+#[zenoh_macros::unstable]
+use crate::trace::TraceId;
 use crate::Encoding;
+use crate::Session;
 use crate::SessionRef;
 use crate::Undeclarable;
 use std::future::Ready;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+#[zenoh_macros::unstable]
+use std::time::Duration;
+use std::time::Instant;
 use zenoh_core::{zread, AsyncResolve, Resolvable, Resolve, SyncResolve};
 use zenoh_protocol::network::push::ext;
 use zenoh_protocol::network::Mapping;
 use zenoh_protocol::network::Push;
+use zenoh_protocol::zenoh::ext::TraceIdType;
 use zenoh_protocol::zenoh::Del;
 use zenoh_protocol::zenoh::PushBody;
 use zenoh_protocol::zenoh::Put;
 use zenoh_result::ZResult;
+#[zenoh_macros::unstable]
+use zenoh_transport::common::congestion::CongestionState;
 
 /// The kind of congestion control.
 pub use zenoh_protocol::core::CongestionControl;
@@ -83,8 +99,17 @@ pub struct PutBuilder<'a, 'b> {
     pub(crate) publisher: PublisherBuilder<'a, 'b>,
     pub(crate) value: Value,
     pub(crate) kind: SampleKind,
+    pub(crate) timestamp: Option<Timestamp>,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")]
+    pub(crate) ttl: Option<Duration>,
+    #[cfg(feature = "unstable")]
+    pub(crate) latency_budget: Option<Duration>,
+    #[cfg(feature = "unstable")]
+    pub(crate) extensions: Vec<Extension>,
+    #[cfg(feature = "unstable")]
+    pub(crate) trace_id: Option<TraceId>,
 }
 
 impl PutBuilder<'_, '_> {
@@ -111,6 +136,16 @@ impl PutBuilder<'_, '_> {
         self
     }
 
+    /// Change the express policy to apply when routing the data.
+    ///
+    /// When express is set to `true`, the data is not batched with other messages
+    /// but sent as soon as possible, trading throughput for latency.
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.publisher = self.publisher.express(is_express);
+        self
+    }
+
     /// Restrict the matching subscribers that will receive the published data
     /// to the ones that have the given [`Locality`](crate::prelude::Locality).
     #[zenoh_macros::unstable]
@@ -120,16 +155,113 @@ impl PutBuilder<'_, '_> {
         self
     }
 
+    /// Limit the publications on the key expression to at most `max_rate` per second
+    /// (see [`PublisherBuilder::max_rate`]).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn max_rate(mut self, max_rate: f64) -> Self {
+        self.publisher = self.publisher.max_rate(max_rate);
+        self
+    }
+
     pub fn kind(mut self, kind: SampleKind) -> Self {
         self.kind = kind;
         self
     }
 
+    /// Use the given [`Timestamp`] instead of one generated by the session's HLC.
+    ///
+    /// As for the data received by the routers, if the timestamp is ahead of the session's HLC
+    /// by more than its configured delta, resolving the operation fails when
+    /// `timestamping/drop_future_timestamp` is set, otherwise the timestamp is replaced
+    /// by one generated by the session's HLC.
+    #[inline]
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
     #[zenoh_macros::unstable]
     pub fn with_attachment(mut self, attachment: Attachment) -> Self {
         self.attachment = Some(attachment);
         self
     }
+
+    /// Set for how long the published data remains valid after its timestamp.
+    ///
+    /// Storages and publication caches stop serving the data once it expired, and subscribers
+    /// may [ignore](crate::subscriber::SubscriberBuilder::ignore_expired) it.
+    /// This has no effect on deletes.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Set the maximum delay after its timestamp for the published data to be delivered.
+    ///
+    /// Routers and subscribers enabling the `latency_budget` configuration drop the data
+    /// once its budget is exceeded, notifying the subscribers declared with
+    /// [`drop_notifications`](crate::subscriber::SubscriberBuilder::drop_notifications).
+    /// The data is timestamped with the current time if the session has no HLC,
+    /// so the clocks of the nodes should be synchronized.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session
+    ///     .put("robot/joint/1", "0.42")
+    ///     .latency_budget(Duration::from_millis(5))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn latency_budget(mut self, latency_budget: Duration) -> Self {
+        self.latency_budget = Some(latency_budget);
+        self
+    }
+
+    /// Attach a user-defined protocol [`Extension`] to the written data.
+    ///
+    /// The sessions receiving a mandatory extension drop the data unless they
+    /// [accept](crate::Session::accept_extension) its ID.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn with_extension(mut self, extension: Extension) -> Self {
+        self.extensions.push(extension);
+        self
+    }
+
+    /// Attach a [`TraceId`] to the written data, delivered along with it.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn trace_id(mut self, trace_id: TraceId) -> Self {
+        self.trace_id = Some(trace_id);
+        self
+    }
+
+    /// Change the value of the written data.
+    ///
+    /// Deletes carry their value along with them, e.g. as tombstone metadata
+    /// such as the reason of the deletion or its author.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn value<IntoValue>(mut self, value: IntoValue) -> Self
+    where
+        IntoValue: Into<Value>,
+    {
+        self.value = value.into();
+        self
+    }
 }
 
 impl Resolvable for PutBuilder<'_, '_> {
@@ -144,23 +276,46 @@ impl SyncResolve for PutBuilder<'_, '_> {
             key_expr,
             congestion_control,
             priority,
+            is_express,
             destination,
+            max_rate,
+            sequence_numbers: _,
+            #[cfg(feature = "unstable")]
+                congestion_callback: _,
         } = self.publisher;
+        check_max_rate(max_rate)?;
 
         let publisher = Publisher {
             session,
             key_expr: key_expr?,
             congestion_control,
             priority,
+            is_express,
             destination,
+            sequence: None,
+            max_rate,
+            is_declared: false,
+            #[cfg(feature = "unstable")]
+            congestion_listener: None,
         };
 
         resolve_put(
             &publisher,
             self.value,
             self.kind,
+            self.timestamp,
+            publisher.priority,
+            publisher.is_express,
             #[cfg(feature = "unstable")]
             self.attachment,
+            #[cfg(feature = "unstable")]
+            self.ttl,
+            #[cfg(feature = "unstable")]
+            self.latency_budget,
+            #[cfg(feature = "unstable")]
+            self.extensions,
+            #[cfg(feature = "unstable")]
+            self.trace_id,
         )
     }
 }
@@ -246,7 +401,93 @@ pub struct Publisher<'a> {
     pub(crate) key_expr: KeyExpr<'a>,
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
+    pub(crate) is_express: bool,
     pub(crate) destination: Locality,
+    pub(crate) sequence: Option<Arc<PublisherSequence>>,
+    pub(crate) max_rate: Option<f64>,
+    // Only the publishers made by declare_publisher hold a publication interest, not the
+    // short-lived ones built for a single put
+    pub(crate) is_declared: bool,
+    #[cfg(feature = "unstable")]
+    #[allow(dead_code)] // Unregisters the congestion callback when dropped
+    pub(crate) congestion_listener: Option<Arc<CongestionListener>>,
+}
+
+/// The [`EntityId`] of a declared [`Publisher`] and the sequence number of its next publication.
+#[derive(Debug)]
+pub(crate) struct PublisherSequence {
+    pub(crate) eid: EntityId,
+    pub(crate) next_sn: AtomicU32,
+}
+
+impl PublisherSequence {
+    fn next(&self) -> (EntityId, u32) {
+        (self.eid, self.next_sn.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A change of the congestion state of the transmission queues used by a [`Publisher`]
+/// with [`CongestionControl::Adaptive`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CongestionEvent {
+    /// The publications are dropped because of a sustained congestion.
+    Dropping,
+    /// The congestion is over and the publications are blocking again.
+    Blocking,
+}
+
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub(crate) struct CongestionCallback(Callback<'static, CongestionEvent>);
+
+#[zenoh_macros::unstable]
+impl std::fmt::Debug for CongestionCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CongestionCallback").finish_non_exhaustive()
+    }
+}
+
+/// The registration of a [`CongestionCallback`] on the transport manager,
+/// removed when the last clone of its [`Publisher`] is dropped.
+#[zenoh_macros::unstable]
+pub(crate) struct CongestionListener {
+    state: Arc<CongestionState>,
+    id: usize,
+}
+
+#[zenoh_macros::unstable]
+impl CongestionListener {
+    fn new(session: &Session, priority: Priority, callback: CongestionCallback) -> Self {
+        let state = session.runtime.manager().config.congestion.clone();
+        let priority: ProtocolPriority = priority.into();
+        let id = state.add_listener(Arc::new(move |p, dropping| {
+            if p == priority {
+                (callback.0)(if dropping {
+                    CongestionEvent::Dropping
+                } else {
+                    CongestionEvent::Blocking
+                });
+            }
+        }));
+        Self { state, id }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Drop for CongestionListener {
+    fn drop(&mut self) {
+        self.state.remove_listener(self.id);
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::fmt::Debug for CongestionListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CongestionListener")
+            .field("id", &self.id)
+            .finish()
+    }
 }
 
 impl<'a> Publisher<'a> {
@@ -254,6 +495,24 @@ impl<'a> Publisher<'a> {
         &self.key_expr
     }
 
+    /// Get the `congestion_control` applied when routing the data.
+    #[inline]
+    pub fn get_congestion_control(&self) -> CongestionControl {
+        self.congestion_control
+    }
+
+    /// Get the priority of the written data.
+    #[inline]
+    pub fn get_priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Get the express policy applied when routing the data.
+    #[inline]
+    pub fn is_express(&self) -> bool {
+        self.is_express
+    }
+
     /// Change the `congestion_control` to apply when routing the data.
     #[inline]
     pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
@@ -268,6 +527,16 @@ impl<'a> Publisher<'a> {
         self
     }
 
+    /// Change the express policy to apply when routing the data.
+    ///
+    /// When express is set to `true`, the data is not batched with other messages
+    /// but sent as soon as possible, trading throughput for latency.
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.is_express = is_express;
+        self
+    }
+
     /// Restrict the matching subscribers that will receive the published data
     /// to the ones that have the given [`Locality`](crate::prelude::Locality).
     #[zenoh_macros::unstable]
@@ -277,6 +546,19 @@ impl<'a> Publisher<'a> {
         self
     }
 
+    /// Stamp the publications with the id of the publisher and a sequence number,
+    /// exposed as the [`source_info`](Sample::source_info) of the received samples.
+    ///
+    /// This lets the subscribers detect the samples they missed (see
+    /// [`sample_miss_callback`](crate::subscriber::SubscriberBuilder::sample_miss_callback))
+    /// at the cost of a source info extension on every publication.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn sequence_numbers(mut self, enabled: bool) -> Self {
+        self.sequence_numbers = enabled;
+        self
+    }
+
     /// Consumes the given `Publisher`, returning a thread-safe reference-counting
     /// pointer to it (`Arc<Publisher>`). This is equivalent to `Arc::new(Publisher)`.
     ///
@@ -318,8 +600,19 @@ impl<'a> Publisher<'a> {
             publisher: self,
             value,
             kind,
+            timestamp: None,
+            priority: None,
+            is_express: None,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            ttl: None,
+            #[cfg(feature = "unstable")]
+            latency_budget: None,
+            #[cfg(feature = "unstable")]
+            extensions: vec![],
+            #[cfg(feature = "unstable")]
+            trace_id: None,
         }
     }
 
@@ -361,6 +654,56 @@ impl<'a> Publisher<'a> {
         self._write(SampleKind::Delete, Value::empty())
     }
 
+    /// Put a batch of values, each one on the key expression of this publisher
+    /// joined with the given suffix. An empty suffix designates the key expression
+    /// of the publisher itself.
+    ///
+    /// All the key expressions are validated before anything is sent. The values are then
+    /// serialized back to back as puts that are not express, the key expression of each one
+    /// being sent as its suffix relative to the declared key expression of the publisher, and
+    /// flushed together once the whole batch is serialized: only the transport batches that are
+    /// full are sent before. The batch is not atomic though: a failure may leave it partially
+    /// sent, and the puts held back by the `max_rate` of the publisher are sent later on.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let publisher = session.declare_publisher("sensors").res().await.unwrap();
+    /// publisher
+    ///     .write_batch([("temperature", "21.5"), ("humidity", "40")])
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn write_batch<I, S, IntoValue>(&self, batch: I) -> PublicationBatch
+    where
+        I: IntoIterator<Item = (S, IntoValue)>,
+        S: AsRef<str>,
+        IntoValue: Into<Value>,
+    {
+        let samples = batch
+            .into_iter()
+            .map(|(suffix, value)| {
+                let suffix = suffix.as_ref();
+                let key_expr = if suffix.is_empty() {
+                    self.key_expr.clone().into_owned()
+                } else {
+                    self.key_expr.join(suffix)?
+                };
+                Ok((key_expr, value.into()))
+            })
+            .collect();
+        PublicationBatch {
+            publisher: self,
+            samples,
+        }
+    }
+
     /// Return the [`MatchingStatus`] of the publisher.
     ///
     /// [`MatchingStatus::matching_subscribers`] will return true if there exist Subscribers
@@ -596,71 +939,396 @@ impl Resolvable for PublisherUndeclaration<'_> {
 impl SyncResolve for PublisherUndeclaration<'_> {
     fn res_sync(mut self) -> <Self as Resolvable>::To {
         let Publisher {
-            session, key_expr, ..
+            session,
+            key_expr,
+            is_declared,
+            ..
         } = &self.publisher;
-        session
-            .undeclare_publication_intent(key_expr.clone())
-            .res_sync()?;
+        if *is_declared {
+            session
+                .undeclare_publication_intent(key_expr.clone())
+                .res_sync()?;
+        }
         self.publisher.key_expr = unsafe { keyexpr::from_str_unchecked("") }.into();
         Ok(())
     }
-}
-
-impl AsyncResolve for PublisherUndeclaration<'_> {
-    type Future = Ready<Self::To>;
+}
+
+impl AsyncResolve for PublisherUndeclaration<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+impl Drop for Publisher<'_> {
+    fn drop(&mut self) {
+        if self.is_declared && !self.key_expr.is_empty() {
+            let _ = self
+                .session
+                .undeclare_publication_intent(self.key_expr.clone())
+                .res_sync();
+        }
+    }
+}
+
+/// A [`Resolvable`] returned by [`Publisher::put()`](Publisher::put),
+/// [`Publisher::delete()`](Publisher::delete) and [`Publisher::write()`](Publisher::write).
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct Publication<'a> {
+    publisher: &'a Publisher<'a>,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    priority: Option<Priority>,
+    is_express: Option<bool>,
+    #[cfg(feature = "unstable")]
+    pub(crate) attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")]
+    pub(crate) ttl: Option<Duration>,
+    #[cfg(feature = "unstable")]
+    pub(crate) latency_budget: Option<Duration>,
+    #[cfg(feature = "unstable")]
+    pub(crate) extensions: Vec<Extension>,
+    #[cfg(feature = "unstable")]
+    pub(crate) trace_id: Option<TraceId>,
+}
+
+impl<'a> Publication<'a> {
+    /// Use the given [`Timestamp`] instead of one generated by the session's HLC.
+    ///
+    /// As for the data received by the routers, if the timestamp is ahead of the session's HLC
+    /// by more than its configured delta, resolving the publication fails when
+    /// `timestamping/drop_future_timestamp` is set, otherwise the timestamp is replaced
+    /// by one generated by the session's HLC.
+    #[inline]
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Send this publication with the given priority instead of the priority
+    /// of the [`Publisher`].
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::publication::Priority;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// publisher.put("routine").res().await.unwrap();
+    /// publisher
+    ///     .put("urgent")
+    ///     .priority(Priority::RealTime)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Send this publication with the given express policy instead of the express policy
+    /// of the [`Publisher`].
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// publisher.put("batched").res().await.unwrap();
+    /// publisher.put("express").express(true).res().await.unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.is_express = Some(is_express);
+        self
+    }
+
+    #[zenoh_macros::unstable]
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachment = Some(attachment);
+        self
+    }
+
+    /// Set for how long the published data remains valid after its timestamp
+    /// (see [`PutBuilder::ttl`]).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Set the maximum delay after its timestamp for the published data to be delivered
+    /// (see [`PutBuilder::latency_budget`]).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn latency_budget(mut self, latency_budget: Duration) -> Self {
+        self.latency_budget = Some(latency_budget);
+        self
+    }
+
+    /// Attach a user-defined protocol [`Extension`] to the written data
+    /// (see [`PutBuilder::with_extension`]).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn with_extension(mut self, extension: Extension) -> Self {
+        self.extensions.push(extension);
+        self
+    }
+
+    /// Attach a [`TraceId`] to the written data, delivered along with it.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn trace_id(mut self, trace_id: TraceId) -> Self {
+        self.trace_id = Some(trace_id);
+        self
+    }
+
+    /// Change the value of the written data (see [`PutBuilder::value`]).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn value<IntoValue>(mut self, value: IntoValue) -> Self
+    where
+        IntoValue: Into<Value>,
+    {
+        self.value = value.into();
+        self
+    }
+}
+
+impl Resolvable for Publication<'_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for Publication<'_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        resolve_put(
+            self.publisher,
+            self.value,
+            self.kind,
+            self.timestamp,
+            self.priority.unwrap_or(self.publisher.priority),
+            self.is_express.unwrap_or(self.publisher.is_express),
+            #[cfg(feature = "unstable")]
+            self.attachment,
+            #[cfg(feature = "unstable")]
+            self.ttl,
+            #[cfg(feature = "unstable")]
+            self.latency_budget,
+            #[cfg(feature = "unstable")]
+            self.extensions,
+            #[cfg(feature = "unstable")]
+            self.trace_id,
+        )
+    }
+}
+
+impl AsyncResolve for Publication<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A [`Resolvable`] returned by [`Publisher::write_batch()`](Publisher::write_batch).
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct PublicationBatch<'a> {
+    publisher: &'a Publisher<'a>,
+    samples: ZResult<Vec<(KeyExpr<'static>, Value)>>,
+}
+
+impl Resolvable for PublicationBatch<'_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for PublicationBatch<'_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        resolve_put_batch(self.publisher, self.samples?)
+    }
+}
+
+impl AsyncResolve for PublicationBatch<'_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A builder for publishing a coherent set of samples, returned by
+/// [`Session::coherent_set()`](crate::Session::coherent_set).
+///
+/// All the samples of the set share a set id and are tagged with their index in the set,
+/// the last one being flagged as such. Subscribers declared with
+/// [`coherent_sets()`](crate::subscriber::SubscriberBuilder::coherent_sets) only deliver the samples
+/// of a set once all of them have been received.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// session
+///     .coherent_set()
+///     .put("robot/pose/x", "1.0")
+///     .put("robot/pose/y", "2.0")
+///     .delete("robot/pose/z")
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[derive(Debug)]
+pub struct CoherentSetBuilder<'a> {
+    pub(crate) session: &'a Session,
+    pub(crate) samples: ZResult<Vec<(KeyExpr<'static>, Value, SampleKind)>>,
+    pub(crate) congestion_control: CongestionControl,
+    pub(crate) priority: Priority,
+}
+
+#[zenoh_macros::unstable]
+impl<'a> CoherentSetBuilder<'a> {
+    fn push<'b, TryIntoKeyExpr>(
+        mut self,
+        key_expr: TryIntoKeyExpr,
+        value: Value,
+        kind: SampleKind,
+    ) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        if let Ok(samples) = self.samples.as_mut() {
+            match key_expr.try_into() {
+                Ok(key_expr) => samples.push((key_expr.into_owned(), value, kind)),
+                Err(e) => self.samples = Err(e.into()),
+            }
+        }
+        self
+    }
+
+    /// Add a put of `value` on `key_expr` to the set.
+    #[inline]
+    pub fn put<'b, TryIntoKeyExpr, IntoValue>(
+        self,
+        key_expr: TryIntoKeyExpr,
+        value: IntoValue,
+    ) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+        IntoValue: Into<Value>,
+    {
+        self.push(key_expr, value.into(), SampleKind::Put)
+    }
 
-    fn res_async(self) -> Self::Future {
-        std::future::ready(self.res_sync())
+    /// Add a delete of `key_expr` to the set.
+    #[inline]
+    pub fn delete<'b, TryIntoKeyExpr>(self, key_expr: TryIntoKeyExpr) -> Self
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        self.push(key_expr, Value::empty(), SampleKind::Delete)
     }
-}
 
-impl Drop for Publisher<'_> {
-    fn drop(&mut self) {
-        if !self.key_expr.is_empty() {
-            let _ = self
-                .session
-                .undeclare_publication_intent(self.key_expr.clone())
-                .res_sync();
-        }
+    /// Change the `congestion_control` to apply when routing the samples of the set.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
     }
-}
-
-/// A [`Resolvable`] returned by [`Publisher::put()`](Publisher::put),
-/// [`Publisher::delete()`](Publisher::delete) and [`Publisher::write()`](Publisher::write).
-#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
-pub struct Publication<'a> {
-    publisher: &'a Publisher<'a>,
-    value: Value,
-    kind: SampleKind,
-    #[cfg(feature = "unstable")]
-    pub(crate) attachment: Option<Attachment>,
-}
 
-impl<'a> Publication<'a> {
-    #[zenoh_macros::unstable]
-    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
-        self.attachment = Some(attachment);
+    /// Change the priority of the samples of the set.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
         self
     }
 }
 
-impl Resolvable for Publication<'_> {
+#[zenoh_macros::unstable]
+impl Resolvable for CoherentSetBuilder<'_> {
     type To = ZResult<()>;
 }
 
-impl SyncResolve for Publication<'_> {
+#[zenoh_macros::unstable]
+impl SyncResolve for CoherentSetBuilder<'_> {
     fn res_sync(self) -> <Self as Resolvable>::To {
-        resolve_put(
-            self.publisher,
-            self.value,
-            self.kind,
+        let samples = self.samples?;
+        let Some((first, _, _)) = samples.first() else {
+            return Ok(());
+        };
+        let publisher = Publisher {
+            session: SessionRef::Borrow(self.session),
+            key_expr: first.clone(),
+            congestion_control: self.congestion_control,
+            priority: self.priority,
+            is_express: false,
+            destination: Locality::default(),
+            sequence: None,
+            max_rate: None,
+            is_declared: false,
             #[cfg(feature = "unstable")]
-            self.attachment,
-        )
+            congestion_listener: None,
+        };
+        let (set_id, primitives) = {
+            let state = zread!(self.session.state);
+            (
+                state.decl_id_counter.fetch_add(1, Ordering::SeqCst) as u32,
+                state.primitives.as_ref().unwrap().clone(),
+            )
+        };
+        tracing::trace!("coherent_set({}, [{} samples])", set_id, samples.len());
+        let zid = self.session.runtime.zid();
+        let last = samples.len() - 1;
+        for (index, (key_expr, value, kind)) in samples.into_iter().enumerate() {
+            send_put(
+                &publisher,
+                &primitives,
+                &key_expr,
+                value,
+                kind,
+                self.session.runtime.new_timestamp(),
+                publisher.priority,
+                publisher.is_express,
+                None,
+                Some(Coherence {
+                    zid,
+                    set_id,
+                    index: index as u32,
+                    last: index == last,
+                }),
+                None,
+                None,
+                vec![],
+                None,
+            );
+        }
+        Ok(())
     }
 }
 
-impl AsyncResolve for Publication<'_> {
+#[zenoh_macros::unstable]
+impl AsyncResolve for CoherentSetBuilder<'_> {
     type Future = Ready<Self::To>;
 
     fn res_async(self) -> Self::Future {
@@ -720,7 +1388,12 @@ pub struct PublisherBuilder<'a, 'b: 'a> {
     pub(crate) key_expr: ZResult<KeyExpr<'b>>,
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
+    pub(crate) is_express: bool,
     pub(crate) destination: Locality,
+    pub(crate) max_rate: Option<f64>,
+    pub(crate) sequence_numbers: bool,
+    #[cfg(feature = "unstable")]
+    pub(crate) congestion_callback: Option<CongestionCallback>,
 }
 
 impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
@@ -733,7 +1406,12 @@ impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
             },
             congestion_control: self.congestion_control,
             priority: self.priority,
+            is_express: self.is_express,
             destination: self.destination,
+            max_rate: self.max_rate,
+            sequence_numbers: self.sequence_numbers,
+            #[cfg(feature = "unstable")]
+            congestion_callback: self.congestion_callback.clone(),
         }
     }
 }
@@ -753,6 +1431,16 @@ impl<'a, 'b> PublisherBuilder<'a, 'b> {
         self
     }
 
+    /// Change the express policy to apply when routing the data.
+    ///
+    /// When express is set to `true`, the data is not batched with other messages
+    /// but sent as soon as possible, trading throughput for latency.
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.is_express = is_express;
+        self
+    }
+
     /// Restrict the matching subscribers that will receive the published data
     /// to the ones that have the given [`Locality`](crate::prelude::Locality).
     #[zenoh_macros::unstable]
@@ -761,6 +1449,74 @@ impl<'a, 'b> PublisherBuilder<'a, 'b> {
         self.destination = destination;
         self
     }
+
+    /// Limit the publications to at most `max_rate` per second for each key expression.
+    ///
+    /// The publications exceeding the rate are held back by the session, keeping only the
+    /// latest one for each key expression, which is sent as soon as the rate allows it.
+    /// This applies to the publications of all the publishers and puts on the same
+    /// key expression with a max rate. Resolving fails if `max_rate` is not positive or
+    /// so small that the period between two publications overflows a [`Duration`].
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session
+    ///     .declare_publisher("sensors/imu")
+    ///     .max_rate(100.0)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// for i in 0..1000 {
+    ///     publisher.put(format!("{i}")).res().await.unwrap();
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn max_rate(mut self, max_rate: f64) -> Self {
+        self.max_rate = Some(max_rate);
+        self
+    }
+
+    /// Register a callback notified when the transmission queues of the publisher's priority
+    /// start or stop dropping the messages published with [`CongestionControl::Adaptive`].
+    ///
+    /// The callback is invoked from the network tasks and should not block.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::publication::{CongestionControl, CongestionEvent};
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session
+    ///     .declare_publisher("key/expression")
+    ///     .congestion_control(CongestionControl::Adaptive)
+    ///     .congestion_callback(|event| match event {
+    ///         CongestionEvent::Dropping => println!("Dropping publications"),
+    ///         CongestionEvent::Blocking => println!("Blocking publications"),
+    ///     })
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn congestion_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(CongestionEvent) + Send + Sync + 'static,
+    {
+        self.congestion_callback = Some(CongestionCallback(Arc::new(callback)));
+        self
+    }
 }
 
 impl<'a, 'b> Resolvable for PublisherBuilder<'a, 'b> {
@@ -770,6 +1526,7 @@ impl<'a, 'b> Resolvable for PublisherBuilder<'a, 'b> {
 impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
     fn res_sync(self) -> <Self as Resolvable>::To {
         let mut key_expr = self.key_expr?;
+        check_max_rate(self.max_rate)?;
         if !key_expr.is_fully_optimized(&self.session) {
             let session_id = self.session.id;
             let expr_id = self.session.declare_prefix(key_expr.as_str()).res_sync();
@@ -803,12 +1560,35 @@ impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
         self.session
             .declare_publication_intent(key_expr.clone())
             .res_sync()?;
+        let sequence = self.sequence_numbers.then(|| {
+            let eid = zread!(self.session.state)
+                .decl_id_counter
+                .fetch_add(1, Ordering::SeqCst) as EntityId;
+            Arc::new(PublisherSequence {
+                eid,
+                next_sn: AtomicU32::new(0),
+            })
+        });
+        #[cfg(feature = "unstable")]
+        let congestion_listener = self.congestion_callback.map(|callback| {
+            Arc::new(CongestionListener::new(
+                &self.session,
+                self.priority,
+                callback,
+            ))
+        });
         let publisher = Publisher {
             session: self.session,
             key_expr,
             congestion_control: self.congestion_control,
             priority: self.priority,
+            is_express: self.is_express,
             destination: self.destination,
+            sequence,
+            max_rate: self.max_rate,
+            is_declared: true,
+            #[cfg(feature = "unstable")]
+            congestion_listener,
         };
         tracing::trace!("publish({:?})", publisher.key_expr);
         Ok(publisher)
@@ -823,28 +1603,325 @@ impl<'a, 'b> AsyncResolve for PublisherBuilder<'a, 'b> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn resolve_put(
     publisher: &Publisher<'_>,
     value: Value,
     kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    priority: Priority,
+    is_express: bool,
     #[cfg(feature = "unstable")] attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")] ttl: Option<Duration>,
+    #[cfg(feature = "unstable")] latency_budget: Option<Duration>,
+    #[cfg(feature = "unstable")] extensions: Vec<Extension>,
+    #[cfg(feature = "unstable")] trace_id: Option<TraceId>,
 ) -> ZResult<()> {
     tracing::trace!("write({:?}, [...])", &publisher.key_expr);
+    let timestamp = match timestamp {
+        Some(timestamp) => Some(crate::time::check_timestamp(
+            publisher.session.runtime.hlc(),
+            publisher.session.runtime.drop_future_timestamp(),
+            timestamp,
+        )?),
+        None => publisher.session.runtime.new_timestamp(),
+    };
+    // The latency budget is relative to the timestamp of the data
+    #[cfg(feature = "unstable")]
+    let timestamp = match timestamp {
+        None if latency_budget.is_some() => {
+            Some(crate::time::new_timestamp(publisher.session.runtime.zid()))
+        }
+        timestamp => timestamp,
+    };
+    if let Some(max_rate) = publisher.max_rate {
+        send_put_throttled(
+            publisher,
+            max_rate,
+            publisher.key_expr.clone().into_owned(),
+            value,
+            kind,
+            timestamp,
+            priority,
+            is_express,
+            #[cfg(feature = "unstable")]
+            attachment,
+            #[cfg(feature = "unstable")]
+            ttl,
+            #[cfg(feature = "unstable")]
+            latency_budget,
+            #[cfg(feature = "unstable")]
+            extensions,
+            #[cfg(feature = "unstable")]
+            trace_id,
+        );
+        return Ok(());
+    }
+    let primitives = zread!(publisher.session.state)
+        .primitives
+        .as_ref()
+        .unwrap()
+        .clone();
+    send_put(
+        publisher,
+        &primitives,
+        &publisher.key_expr,
+        value,
+        kind,
+        timestamp,
+        priority,
+        is_express,
+        #[cfg(feature = "unstable")]
+        attachment,
+        #[cfg(feature = "unstable")]
+        None,
+        #[cfg(feature = "unstable")]
+        ttl,
+        #[cfg(feature = "unstable")]
+        latency_budget,
+        #[cfg(feature = "unstable")]
+        extensions,
+        #[cfg(feature = "unstable")]
+        trace_id,
+    );
+    Ok(())
+}
+
+fn check_max_rate(max_rate: Option<f64>) -> ZResult<()> {
+    match max_rate {
+        Some(max_rate) if max_rate.is_nan() || max_rate <= 0.0 => {
+            bail!("Invalid max rate {}: it must be positive", max_rate)
+        }
+        // The period between two publications must fit in a Duration
+        Some(max_rate) if Duration::try_from_secs_f64(1.0 / max_rate).is_err() => {
+            bail!("Invalid max rate {}: it is too small", max_rate)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A publication held back by the rate limiting of the session, sent on the given session.
+pub(crate) type PendingPublication = Box<dyn FnOnce(&Session) + Send + Sync>;
+
+/// The rate limiting state of the publications on a key expression.
+pub(crate) struct PublicationRate {
+    /// The earliest time at which the next publication can be sent.
+    pub(crate) next: Instant,
+    /// The latest publication held back, sent at `next`.
+    pub(crate) pending: Option<PendingPublication>,
+}
+
+/// Send a publication now if the `max_rate` of `key_expr` allows it, or hold it back
+/// in place of the publication previously held back on `key_expr`, if any.
+#[allow(clippy::too_many_arguments)]
+fn send_put_throttled(
+    publisher: &Publisher<'_>,
+    max_rate: f64,
+    key_expr: KeyExpr<'static>,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    priority: Priority,
+    is_express: bool,
+    #[cfg(feature = "unstable")] attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")] ttl: Option<Duration>,
+    #[cfg(feature = "unstable")] latency_budget: Option<Duration>,
+    #[cfg(feature = "unstable")] extensions: Vec<Extension>,
+    #[cfg(feature = "unstable")] trace_id: Option<TraceId>,
+) {
+    let congestion_control = publisher.congestion_control;
+    let destination = publisher.destination;
+    let sequence = publisher.sequence.clone();
+    let throttled_key_expr = key_expr.as_keyexpr().to_owned();
+    let publication: PendingPublication = Box::new(move |session: &Session| {
+        let Some(primitives) = zread!(session.state).primitives.clone() else {
+            return;
+        };
+        let publisher = Publisher {
+            session: SessionRef::Borrow(session),
+            key_expr,
+            congestion_control,
+            priority,
+            is_express,
+            destination,
+            sequence,
+            max_rate: None,
+            is_declared: false,
+            #[cfg(feature = "unstable")]
+            congestion_listener: None,
+        };
+        send_put(
+            &publisher,
+            &primitives,
+            &publisher.key_expr,
+            value,
+            kind,
+            timestamp,
+            priority,
+            is_express,
+            #[cfg(feature = "unstable")]
+            attachment,
+            #[cfg(feature = "unstable")]
+            None,
+            #[cfg(feature = "unstable")]
+            ttl,
+            #[cfg(feature = "unstable")]
+            latency_budget,
+            #[cfg(feature = "unstable")]
+            extensions,
+            #[cfg(feature = "unstable")]
+            trace_id,
+        );
+    });
+    if let Some(publication) =
+        publisher
+            .session
+            .throttle_publication(throttled_key_expr, max_rate, publication)
+    {
+        publication(&publisher.session);
+    }
+}
+
+fn resolve_put_batch(
+    publisher: &Publisher<'_>,
+    samples: Vec<(KeyExpr<'static>, Value)>,
+) -> ZResult<()> {
+    tracing::trace!(
+        "write_batch({:?}, [{} samples])",
+        &publisher.key_expr,
+        samples.len()
+    );
     let primitives = zread!(publisher.session.state)
         .primitives
         .as_ref()
         .unwrap()
         .clone();
-    let timestamp = publisher.session.runtime.new_timestamp();
+    // The puts are not express so that they share the transport batches
+    let publisher = Publisher {
+        session: publisher.session.clone(),
+        key_expr: publisher.key_expr.clone(),
+        congestion_control: publisher.congestion_control,
+        priority: publisher.priority,
+        is_express: false,
+        destination: publisher.destination,
+        sequence: publisher.sequence.clone(),
+        max_rate: publisher.max_rate,
+        is_declared: false,
+        #[cfg(feature = "unstable")]
+        congestion_listener: None,
+    };
+    zenoh_transport::batch_scope(|| {
+        for (key_expr, value) in samples {
+            if let Some(max_rate) = publisher.max_rate {
+                send_put_throttled(
+                    &publisher,
+                    max_rate,
+                    key_expr,
+                    value,
+                    SampleKind::Put,
+                    publisher.session.runtime.new_timestamp(),
+                    publisher.priority,
+                    publisher.is_express,
+                    #[cfg(feature = "unstable")]
+                    None,
+                    #[cfg(feature = "unstable")]
+                    None,
+                    #[cfg(feature = "unstable")]
+                    None,
+                    #[cfg(feature = "unstable")]
+                    vec![],
+                    #[cfg(feature = "unstable")]
+                    None,
+                );
+                continue;
+            }
+            send_put(
+                &publisher,
+                &primitives,
+                &key_expr,
+                value,
+                SampleKind::Put,
+                publisher.session.runtime.new_timestamp(),
+                publisher.priority,
+                publisher.is_express,
+                #[cfg(feature = "unstable")]
+                None,
+                #[cfg(feature = "unstable")]
+                None,
+                #[cfg(feature = "unstable")]
+                None,
+                #[cfg(feature = "unstable")]
+                None,
+                #[cfg(feature = "unstable")]
+                vec![],
+                #[cfg(feature = "unstable")]
+                None,
+            );
+        }
+    });
+    Ok(())
+}
 
+#[allow(clippy::too_many_arguments)]
+fn send_put(
+    publisher: &Publisher<'_>,
+    primitives: &Face,
+    key_expr: &KeyExpr<'_>,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    priority: Priority,
+    is_express: bool,
+    #[cfg(feature = "unstable")] attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")] coherence: Option<Coherence>,
+    #[cfg(feature = "unstable")] ttl: Option<Duration>,
+    #[cfg(feature = "unstable")] latency_budget: Option<Duration>,
+    #[cfg(feature = "unstable")] extensions: Vec<Extension>,
+    #[cfg(feature = "unstable")] trace_id: Option<TraceId>,
+) {
+    #[cfg(feature = "unstable")]
+    let intercepted: KeyExpr<'static>;
+    #[cfg(feature = "unstable")]
+    let (key_expr, value, kind, timestamp, attachment) = {
+        let interceptors = publisher.session.interceptors();
+        if interceptors.is_empty() {
+            (key_expr, value, kind, timestamp, attachment)
+        } else {
+            let mut sample = Sample::new(key_expr.clone().into_owned(), value);
+            sample.kind = kind;
+            sample.timestamp = timestamp;
+            sample.attachment = attachment;
+            let Some(sample) = crate::interceptor::intercept_egress(&interceptors, sample) else {
+                return;
+            };
+            intercepted = sample.key_expr;
+            (
+                &intercepted,
+                sample.value,
+                sample.kind,
+                sample.timestamp,
+                sample.attachment,
+            )
+        }
+    };
+    let source = publisher.sequence.as_ref().map(|sequence| sequence.next());
+    let ext_sinfo = source.map(|(eid, sn)| zenoh_protocol::zenoh::ext::SourceInfoType {
+        zid: publisher.session.runtime.zid(),
+        eid,
+        sn,
+    });
+    #[cfg(feature = "unstable")]
+    let ext_unknown = crate::extension::to_unknown(&extensions);
+    #[cfg(not(feature = "unstable"))]
+    let ext_unknown = vec![];
+    #[cfg(feature = "unstable")]
+    let trace = trace_id.map(|trace_id| trace_id.to_bytes());
+    #[cfg(not(feature = "unstable"))]
+    let trace: Option<[u8; 16]> = None;
     if publisher.destination != Locality::SessionLocal {
         primitives.send_push(Push {
-            wire_expr: publisher.key_expr.to_wire(&publisher.session).to_owned(),
-            ext_qos: ext::QoSType::new(
-                publisher.priority.into(),
-                publisher.congestion_control,
-                false,
-            ),
+            wire_expr: key_expr.to_wire(&publisher.session).to_owned(),
+            ext_qos: ext::QoSType::new(priority.into(), publisher.congestion_control, is_express),
             ext_tstamp: None,
             ext_nodeid: ext::NodeIdType::default(),
             payload: match kind {
@@ -857,14 +1934,26 @@ fn resolve_put(
                             ext_attachment = Some(attachment.into());
                         }
                     }
+                    #[cfg(feature = "unstable")]
+                    let ext_coherence = coherence.as_ref().map(coherence_ext);
+                    #[cfg(not(feature = "unstable"))]
+                    let ext_coherence = None;
+                    #[cfg(not(feature = "unstable"))]
+                    let ttl = None;
+                    #[cfg(not(feature = "unstable"))]
+                    let latency_budget = None;
                     PushBody::Put(Put {
                         timestamp,
                         encoding: value.encoding.clone(),
-                        ext_sinfo: None,
+                        ext_sinfo: ext_sinfo.clone(),
+                        ext_coherence,
+                        ext_ttl: ttl,
+                        ext_latency_budget: latency_budget,
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment,
-                        ext_unknown: vec![],
+                        ext_trace: trace.map(TraceIdType::new),
+                        ext_unknown: ext_unknown.clone(),
                         payload: value.payload.clone(),
                     })
                 }
@@ -877,11 +1966,26 @@ fn resolve_put(
                             ext_attachment = Some(attachment.into());
                         }
                     }
+                    #[cfg(feature = "unstable")]
+                    let ext_coherence = coherence.as_ref().map(coherence_ext);
+                    #[cfg(not(feature = "unstable"))]
+                    let ext_coherence = None;
+                    let ext_body = (!value.payload.is_empty()
+                        || value.encoding != Encoding::default())
+                    .then(|| zenoh_protocol::zenoh::del::ext::DelBodyType {
+                        #[cfg(feature = "shared-memory")]
+                        ext_shm: None,
+                        encoding: value.encoding.clone(),
+                        payload: value.payload.clone(),
+                    });
                     PushBody::Del(Del {
                         timestamp,
-                        ext_sinfo: None,
+                        ext_sinfo,
+                        ext_coherence,
                         ext_attachment,
-                        ext_unknown: vec![],
+                        ext_body,
+                        ext_trace: trace.map(TraceIdType::new),
+                        ext_unknown,
                     })
                 }
             },
@@ -892,38 +1996,70 @@ fn resolve_put(
             kind,
             encoding: Some(value.encoding),
             timestamp,
-            source_id: None,
-            source_sn: None,
+            source_id: source.map(|_| publisher.session.runtime.zid()),
+            source_sn: source.map(|(_, sn)| sn as SourceSn),
+            source_eid: source.map(|(eid, _)| eid),
+            #[cfg(feature = "unstable")]
+            coherence,
+            #[cfg(feature = "unstable")]
+            ttl: ttl.filter(|_| kind == SampleKind::Put),
+            #[cfg(feature = "unstable")]
+            latency_budget: latency_budget.filter(|_| kind == SampleKind::Put),
+            #[cfg(feature = "unstable")]
+            extensions,
+            #[cfg(feature = "unstable")]
+            trace_id,
             qos: QoS::from(ext::QoSType::new(
-                publisher.priority.into(),
+                priority.into(),
                 publisher.congestion_control,
-                false,
+                is_express,
             )),
         };
 
         publisher.session.handle_data(
             true,
-            &publisher.key_expr.to_wire(&publisher.session),
+            &key_expr.to_wire(&publisher.session),
             Some(data_info),
             value.payload,
             #[cfg(feature = "unstable")]
             attachment,
         );
     }
-    Ok(())
+}
+
+#[zenoh_macros::unstable]
+fn coherence_ext<const ID: u8>(
+    coherence: &Coherence,
+) -> zenoh_protocol::zenoh::ext::CoherenceType<{ ID }> {
+    zenoh_protocol::zenoh::ext::CoherenceType {
+        zid: coherence.zid,
+        set_id: coherence.set_id,
+        index: coherence.index,
+        last: coherence.last,
+    }
 }
 
 /// The Priority of zenoh messages.
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+///
+/// Messages with a higher priority are transmitted before messages with a lower priority
+/// on every hop of the network, provided QoS is enabled on the transports.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum Priority {
+    /// Highest priority, for time-critical messages.
     RealTime = 1,
+    /// High priority interactive traffic, e.g. user commands.
     InteractiveHigh = 2,
+    /// Low priority interactive traffic.
     InteractiveLow = 3,
+    /// High priority data traffic.
     DataHigh = 4,
+    /// Default priority for data traffic.
     #[default]
     Data = 5,
+    /// Low priority data traffic.
     DataLow = 6,
+    /// Lowest priority, for bulk transfers that should not interfere with other traffic.
     Background = 7,
 }
 
@@ -966,6 +2102,42 @@ impl TryFrom<u8> for Priority {
     }
 }
 
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Priority::RealTime => "real_time",
+            Priority::InteractiveHigh => "interactive_high",
+            Priority::InteractiveLow => "interactive_low",
+            Priority::DataHigh => "data_high",
+            Priority::Data => "data",
+            Priority::DataLow => "data_low",
+            Priority::Background => "background",
+        })
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = zenoh_result::Error;
+
+    /// A Priority may be parsed either from its name (e.g. `real_time`, `data_low`)
+    /// or from its numeric value (1-7).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "real_time" => Ok(Priority::RealTime),
+            "interactive_high" => Ok(Priority::InteractiveHigh),
+            "interactive_low" => Ok(Priority::InteractiveLow),
+            "data_high" => Ok(Priority::DataHigh),
+            "data" => Ok(Priority::Data),
+            "data_low" => Ok(Priority::DataLow),
+            "background" => Ok(Priority::Background),
+            other => match other.parse::<u8>() {
+                Ok(value) => Priority::try_from(value),
+                Err(_) => bail!("{} is not a valid priority value.", other),
+            },
+        }
+    }
+}
+
 type ProtocolPriority = zenoh_protocol::core::Priority;
 impl From<Priority> for ProtocolPriority {
     fn from(prio: Priority) -> Self {
@@ -1390,6 +2562,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn priority_from_str() {
+        use super::Priority;
+
+        for i in Priority::MAX as u8..=Priority::MIN as u8 {
+            let p: Priority = i.try_into().unwrap();
+            assert_eq!(p.to_string().parse::<Priority>().unwrap(), p);
+            assert_eq!(i.to_string().parse::<Priority>().unwrap(), p);
+        }
+        assert!("0".parse::<Priority>().is_err());
+        assert!("control".parse::<Priority>().is_err());
+    }
+
+    #[test]
+    fn qos_in_sample() {
+        use crate::{open, prelude::sync::*};
+
+        const KEY_EXPR: &str = "test/qos/sample";
+
+        let session = open(Config::default()).res().unwrap();
+        let sub = session.declare_subscriber(KEY_EXPR).res().unwrap();
+        session
+            .put(KEY_EXPR, "zenoh")
+            .priority(Priority::DataHigh)
+            .congestion_control(CongestionControl::Block)
+            .res()
+            .unwrap();
+        let sample = sub.recv().unwrap();
+
+        assert_eq!(sample.priority(), Priority::DataHigh);
+        assert_eq!(sample.congestion_control(), CongestionControl::Block);
+    }
+
+    #[test]
+    fn write_batch() {
+        use crate::{open, prelude::sync::*};
+
+        const KEY_EXPR: &str = "test/write_batch";
+
+        let session = open(Config::default()).res().unwrap();
+        let sub = session
+            .declare_subscriber(format!("{KEY_EXPR}/**"))
+            .res()
+            .unwrap();
+        let pub_ = session.declare_publisher(KEY_EXPR).res().unwrap();
+        pub_.write_batch([("a", "1"), ("b/c", "2"), ("", "3")])
+            .res()
+            .unwrap();
+        for (key_expr, value) in [
+            ("test/write_batch/a", "1"),
+            ("test/write_batch/b/c", "2"),
+            ("test/write_batch", "3"),
+        ] {
+            let sample = sub.recv().unwrap();
+            assert_eq!(sample.key_expr.as_str(), key_expr);
+            assert_eq!(sample.value.to_string(), value);
+        }
+
+        // The key expressions are sent relative to the declared key expression of the publisher
+        let batch = pub_.write_batch([("a", "1")]);
+        let (key_expr, _) = &batch.samples.as_ref().unwrap()[0];
+        let wire_expr = key_expr.to_wire(&session);
+        assert_eq!(wire_expr.scope, pub_.key_expr.to_wire(&session).scope);
+        assert_ne!(wire_expr.scope, 0);
+        assert_eq!(wire_expr.suffix, "/a");
+
+        // Nothing is sent if one of the key expressions is invalid.
+        assert!(pub_.write_batch([("d", "4"), ("e?", "5")]).res().is_err());
+        assert!(sub.try_recv().is_err());
+    }
+
+    #[test]
+    fn publication_interest_outlives_put() {
+        use crate::{open, prelude::sync::*};
+        use zenoh_core::zread;
+
+        const KEY_EXPR: &str = "test/publication_interest";
+
+        let config = |mode, endpoint: &str| {
+            let mut config = Config::default();
+            config.set_mode(Some(mode)).unwrap();
+            config.scouting.multicast.set_enabled(Some(false)).unwrap();
+            config
+                .insert_json5("routing/interests/enabled", "true")
+                .unwrap();
+            match mode {
+                WhatAmI::Router => config.listen.endpoints = vec![endpoint.parse().unwrap()],
+                _ => config.connect.endpoints = vec![endpoint.parse().unwrap()],
+            }
+            config
+        };
+        let endpoint = "tcp/127.0.0.1:38473";
+        let _router = open(config(WhatAmI::Router, endpoint)).res().unwrap();
+        let session = open(config(WhatAmI::Client, endpoint)).res().unwrap();
+        let has_interest = || {
+            zread!(session.state)
+                .publication_interests
+                .contains_key(keyexpr::new(KEY_EXPR).unwrap())
+        };
+
+        let pub_ = session.declare_publisher(KEY_EXPR).res().unwrap();
+        assert!(has_interest());
+
+        // The publishers built for a single put do not release the interest of the declared one
+        session.put(KEY_EXPR, "put").res().unwrap();
+        session.delete(KEY_EXPR).res().unwrap();
+        assert!(has_interest());
+
+        pub_.undeclare().res().unwrap();
+        assert!(!has_interest());
+    }
+
     #[test]
     fn sample_kind_integrity_in_publication() {
         use crate::publication::HasWriteWithSampleKind;
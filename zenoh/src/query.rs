@@ -18,15 +18,21 @@ use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::prelude::*;
 #[zenoh_macros::unstable]
 use crate::sample::Attachment;
+#[zenoh_macros::unstable]
+use crate::trace::TraceId;
 use crate::Session;
 use std::collections::HashMap;
+use std::fmt;
 use std::future::Ready;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use zenoh_core::{AsyncResolve, Resolvable, Resolve, ResolveClosure, ResolveFuture, SyncResolve};
+use zenoh_protocol::zenoh::err::code;
 use zenoh_result::ZResult;
+use zenoh_util::MemoryReservation;
 
 /// The [`Queryable`](crate::queryable::Queryable)s that should be target of a [`get`](Session::get).
-pub use zenoh_protocol::core::QueryTarget;
+pub use zenoh_protocol::core::{QueryTarget, QueryableSelection};
 
 /// The kind of consolidation.
 pub use zenoh_protocol::core::ConsolidationMode;
@@ -76,6 +82,79 @@ impl Default for QueryConsolidation {
     }
 }
 
+/// The code of an error [`Reply`], set with [`Query::reply_err`](crate::queryable::Query::reply_err).
+///
+/// The codes without a dedicated variant are application-defined and surfaced as [`ErrorCode::Other`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// The replier didn't specify a code.
+    Unspecified,
+    /// The requested resource doesn't exist.
+    NotFound,
+    /// The querier isn't allowed to perform the query.
+    Unauthorized,
+    /// The query is malformed (e.g. invalid parameters or value).
+    InvalidRequest,
+    /// The query timed out.
+    Timeout,
+    /// The replier failed to process the query.
+    Internal,
+    /// An application-defined code.
+    Other(u16),
+}
+
+impl From<u16> for ErrorCode {
+    fn from(value: u16) -> Self {
+        match value {
+            code::UNSPECIFIED => ErrorCode::Unspecified,
+            code::NOT_FOUND => ErrorCode::NotFound,
+            code::UNAUTHORIZED => ErrorCode::Unauthorized,
+            code::INVALID_REQUEST => ErrorCode::InvalidRequest,
+            code::TIMEOUT => ErrorCode::Timeout,
+            code::INTERNAL => ErrorCode::Internal,
+            other => ErrorCode::Other(other),
+        }
+    }
+}
+
+impl From<ErrorCode> for u16 {
+    fn from(value: ErrorCode) -> Self {
+        match value {
+            ErrorCode::Unspecified => code::UNSPECIFIED,
+            ErrorCode::NotFound => code::NOT_FOUND,
+            ErrorCode::Unauthorized => code::UNAUTHORIZED,
+            ErrorCode::InvalidRequest => code::INVALID_REQUEST,
+            ErrorCode::Timeout => code::TIMEOUT,
+            ErrorCode::Internal => code::INTERNAL,
+            ErrorCode::Other(other) => other,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCode::Other(other) => write!(f, "{other}"),
+            known => f.write_str(zenoh_protocol::zenoh::err::code_to_str((*known).into())),
+        }
+    }
+}
+
+/// The error carried by an error [`Reply`]: its [`ErrorCode`] and its payload.
+#[derive(Clone, Debug)]
+pub struct ReplyError {
+    pub code: ErrorCode,
+    pub value: Value,
+}
+
+impl fmt::Display for ReplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.value)
+    }
+}
+
+impl std::error::Error for ReplyError {}
+
 /// Structs returned by a [`get`](Session::get).
 #[non_exhaustive]
 #[derive(Clone, Debug)]
@@ -84,6 +163,162 @@ pub struct Reply {
     pub sample: Result<Sample, Value>,
     /// The id of the zenoh instance that answered this Reply.
     pub replier_id: ZenohId,
+    pub(crate) code: ErrorCode,
+}
+
+impl Reply {
+    /// The [`ErrorCode`] of this Reply, `None` if it isn't an error.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        self.sample.is_err().then_some(self.code)
+    }
+
+    /// Converts this Reply into its result, with the errors as a structured [`ReplyError`].
+    pub fn into_result(self) -> Result<Sample, ReplyError> {
+        let code = self.code;
+        self.sample.map_err(|value| ReplyError { code, value })
+    }
+}
+
+/// The way a [`get`](Session::get) ended, delivered as the last item of a [`ReplyStream`]
+/// or to the callback set with [`GetBuilder::on_final`].
+///
+/// The status is the one of the query as a whole, not of each queryable: the routers gather
+/// the final responses of the queryables they forwarded the query to into a single one, so the
+/// querier can't tell which queryables didn't answer a query that timed out or was cancelled.
+/// The [`replier_id`](Reply::replier_id) of the replies tells which ones did answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryStatus {
+    /// All the matching queryables answered the query.
+    Complete,
+    /// The query timed out before all the matching queryables answered it.
+    Timeout,
+    /// The query was cancelled with its [`CancellationHandle`] before all the matching
+    /// queryables answered it.
+    Cancelled,
+}
+
+/// A handle to cancel in-flight queries, set with [`GetBuilder::cancellation`].
+///
+/// Cancelling a query ends it as if it timed out: the replies held back for consolidation are
+/// delivered, no more replies are received and the queryables are notified that they can stop
+/// answering it (see [`Query::is_cancelled`](crate::queryable::Query::is_cancelled)).
+/// The same handle may be shared by several queries.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationHandle(pub(crate) tokio_util::sync::CancellationToken);
+
+impl CancellationHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the queries using this handle.
+    pub fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called on this handle.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+/// An item of a [`ReplyStream`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum ReplyEvent {
+    /// A reply to the query.
+    Reply(Reply),
+    /// The query ended: no more replies will follow.
+    Done(QueryStatus),
+}
+
+/// A [`Stream`](futures::Stream) of the replies to a [`get`](Session::get), returned by
+/// [`GetBuilder::stream`].
+///
+/// Replies are delivered as soon as they are received (or, with [`ConsolidationMode::Latest`],
+/// once all the queryables answered). The last item is always a [`ReplyEvent::Done`] carrying
+/// the [`QueryStatus`], unless the session is closed while the query is still pending.
+#[derive(Debug)]
+pub struct ReplyStream {
+    receiver: flume::r#async::RecvStream<'static, ReplyEvent>,
+}
+
+impl futures::Stream for ReplyStream {
+    type Item = ReplyEvent;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        std::pin::Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+pub(crate) type OnFinal = Box<dyn FnOnce(QueryStatus) + Send + Sync>;
+
+/// A callback called with the [`QueryStatus`] of a query, set with [`GetBuilder::on_final`].
+pub(crate) struct FinalCallback(pub(crate) OnFinal);
+
+impl fmt::Debug for FinalCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FinalCallback")
+    }
+}
+
+/// A user-provided consolidation strategy, set with [`GetBuilder::consolidate_with`].
+#[derive(Clone)]
+pub(crate) struct Consolidator(Arc<dyn Fn(Vec<Reply>) -> Vec<Reply> + Send + Sync>);
+
+impl Consolidator {
+    /// Buffer the replies received through the returned callback and, once the query ends,
+    /// pass the consolidated ones to `callback` before calling `on_final`.
+    fn wrap(
+        self,
+        callback: Callback<'static, Reply>,
+        on_final: Option<OnFinal>,
+    ) -> (Callback<'static, Reply>, OnFinal) {
+        let replies = Arc::new(Mutex::new(Vec::new()));
+        let buffer = {
+            let replies = replies.clone();
+            Arc::new(move |reply| zlock!(replies).push(reply))
+        };
+        let flush: OnFinal = Box::new(move |status| {
+            let replies = std::mem::take(&mut *zlock!(replies));
+            for reply in (self.0)(replies) {
+                callback(reply);
+            }
+            if let Some(on_final) = on_final {
+                on_final(status);
+            }
+        });
+        (buffer, flush)
+    }
+}
+
+/// Apply the custom consolidation of a query, if any, to its callbacks.
+fn consolidate(
+    consolidator: Option<Consolidator>,
+    consolidation: QueryConsolidation,
+    callback: Callback<'static, Reply>,
+    on_final: Option<OnFinal>,
+) -> (
+    QueryConsolidation,
+    Callback<'static, Reply>,
+    Option<OnFinal>,
+) {
+    match consolidator {
+        Some(consolidator) => {
+            let (callback, on_final) = consolidator.wrap(callback, on_final);
+            (ConsolidationMode::None.into(), callback, Some(on_final))
+        }
+        None => (consolidation, callback, on_final),
+    }
+}
+
+impl fmt::Debug for Consolidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Consolidator")
+    }
 }
 
 pub(crate) struct QueryState {
@@ -93,6 +328,42 @@ pub(crate) struct QueryState {
     pub(crate) reception_mode: ConsolidationMode,
     pub(crate) replies: Option<HashMap<OwnedKeyExpr, Reply>>,
     pub(crate) callback: Callback<'static, Reply>,
+    pub(crate) on_final: Option<OnFinal>,
+    pub(crate) memory: MemoryReservation,
+}
+
+impl QueryState {
+    /// Keeps a reply for a key that has no reply yet, for its consolidation.
+    /// The reply is not kept if it doesn't fit in the memory budget of the pending queries:
+    /// an error reply telling so is returned instead, to be delivered in its place.
+    pub(crate) fn keep_new_reply(&mut self, reply: Reply) -> Result<(), Reply> {
+        let Ok(sample) = &reply.sample else {
+            return Ok(());
+        };
+        let size =
+            std::mem::size_of::<Reply>() + sample.key_expr.len() + sample.value.payload.len();
+        if !self.memory.try_grow(size) {
+            tracing::warn!(
+                "Memory budget exceeded by the replies of query {}: not keeping reply for {}",
+                self.selector,
+                sample.key_expr
+            );
+            return Err(Reply {
+                sample: Err(format!(
+                    "Memory budget exceeded by the replies of the query: dropped the reply for {}",
+                    sample.key_expr
+                )
+                .into()),
+                replier_id: reply.replier_id,
+                code: ErrorCode::Internal,
+            });
+        }
+        let key_expr = sample.key_expr.clone().into();
+        if let Some(replies) = self.replies.as_mut() {
+            replies.insert(key_expr, reply);
+        }
+        Ok(())
+    }
 }
 
 /// A builder for initializing a `query`.
@@ -124,13 +395,19 @@ pub struct GetBuilder<'a, 'b, Handler> {
     pub(crate) selector: ZResult<Selector<'b>>,
     pub(crate) scope: ZResult<Option<KeyExpr<'b>>>,
     pub(crate) target: QueryTarget,
+    pub(crate) selection: Option<QueryableSelection>,
     pub(crate) consolidation: QueryConsolidation,
     pub(crate) destination: Locality,
     pub(crate) timeout: Duration,
     pub(crate) handler: Handler,
     pub(crate) value: Option<Value>,
+    pub(crate) consolidator: Option<Consolidator>,
+    pub(crate) cancellation: Option<CancellationHandle>,
+    pub(crate) on_final: Option<FinalCallback>,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")]
+    pub(crate) trace_id: Option<TraceId>,
 }
 
 impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
@@ -161,12 +438,18 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             selector,
             scope,
             target,
+            selection,
             consolidation,
             destination,
             timeout,
             value,
+            consolidator,
+            cancellation,
+            on_final,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            trace_id,
             handler: _,
         } = self;
         GetBuilder {
@@ -174,12 +457,18 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             selector,
             scope,
             target,
+            selection,
             consolidation,
             destination,
             timeout,
             value,
+            consolidator,
+            cancellation,
+            on_final,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            trace_id,
             handler: callback,
         }
     }
@@ -246,12 +535,18 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             selector,
             scope,
             target,
+            selection,
             consolidation,
             destination,
             timeout,
             value,
+            consolidator,
+            cancellation,
+            on_final,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            trace_id,
             handler: _,
         } = self;
         GetBuilder {
@@ -259,12 +554,249 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             selector,
             scope,
             target,
+            selection,
+            consolidation,
+            destination,
+            timeout,
+            value,
+            consolidator,
+            cancellation,
+            on_final,
+            #[cfg(feature = "unstable")]
+            attachment,
+            #[cfg(feature = "unstable")]
+            trace_id,
+            handler,
+        }
+    }
+
+    /// Resolve the query and fold all its replies into a single value.
+    ///
+    /// The returned value is available once the query has been answered by all the
+    /// matching queryables or has timed out.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let errors = session
+    ///     .get("key/expression")
+    ///     .fold(0, |errors, reply| errors + reply.sample.is_err() as usize)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn fold<T, F>(self, init: T, mut f: F) -> impl Resolve<ZResult<T>> + 'a
+    where
+        T: Send + 'a,
+        F: FnMut(T, Reply) -> T + Send + 'a,
+    {
+        let builder = self.into_owned();
+        ResolveFuture::new(async move {
+            let replies = builder.res_async().await?;
+            let mut acc = init;
+            while let Ok(reply) = replies.recv_async().await {
+                acc = f(acc, reply);
+            }
+            Ok(acc)
+        })
+    }
+
+    /// Resolve the query and gather the values of its successful replies by key expression.
+    ///
+    /// When several replies are received for the same key expression, only the one with the
+    /// latest timestamp is kept. Key expressions whose latest reply is a deletion are left out
+    /// of the map. Error replies are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let values = session.get("key/**").collect_map().res().await.unwrap();
+    /// for (key_expr, value) in values {
+    ///     println!("{key_expr}: {value}");
+    /// }
+    /// # }
+    /// ```
+    pub fn collect_map(self) -> impl Resolve<ZResult<HashMap<OwnedKeyExpr, Value>>> + 'a {
+        let builder = self.into_owned();
+        ResolveFuture::new(async move {
+            let replies = builder.res_async().await?;
+            let mut samples: HashMap<OwnedKeyExpr, Sample> = HashMap::new();
+            while let Ok(reply) = replies.recv_async().await {
+                let Ok(sample) = reply.sample else {
+                    continue;
+                };
+                match samples.get(sample.key_expr.as_keyexpr()) {
+                    Some(latest) if latest.timestamp >= sample.timestamp => {}
+                    _ => {
+                        samples.insert(sample.key_expr.clone().into(), sample);
+                    }
+                }
+            }
+            Ok(samples
+                .into_iter()
+                .filter(|(_, sample)| sample.kind == SampleKind::Put)
+                .map(|(key_expr, sample)| (key_expr, sample.value))
+                .collect())
+        })
+    }
+
+    /// Resolve the query and return its first reply, or `None` if the query was not answered
+    /// before it completed or timed out. Subsequent replies are discarded.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// if let Some(reply) = session.get("key/expression").first().res().await.unwrap() {
+    ///     println!("Received {:?}", reply.sample);
+    /// }
+    /// # }
+    /// ```
+    pub fn first(self) -> impl Resolve<ZResult<Option<Reply>>> + 'a {
+        let (sender, receiver) = flume::bounded(1);
+        let builder = self.into_owned().callback(move |reply| {
+            let _ = sender.try_send(reply);
+        });
+        ResolveFuture::new(async move {
+            builder.res_async().await?;
+            Ok(receiver.recv_async().await.ok())
+        })
+    }
+
+    /// Resolve the query into a [`ReplyStream`], which yields the replies as they arrive and
+    /// ends with a [`ReplyEvent::Done`] telling whether the query completed or timed out.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use futures::prelude::*;
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::query::{QueryStatus, ReplyEvent};
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let mut replies = session.get("key/expression").stream().res().await.unwrap();
+    /// while let Some(event) = replies.next().await {
+    ///     match event {
+    ///         ReplyEvent::Reply(reply) => println!("Received {:?}", reply.sample),
+    ///         ReplyEvent::Done(QueryStatus::Complete) => println!("All queryables answered"),
+    ///         ReplyEvent::Done(status) => println!("Query ended: {status:?}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn stream(self) -> impl Resolve<ZResult<ReplyStream>> + 'a {
+        let GetBuilder {
+            session,
+            selector,
+            scope,
+            target,
+            selection,
             consolidation,
             destination,
             timeout,
             value,
+            consolidator,
+            cancellation,
+            on_final: user_on_final,
             #[cfg(feature = "unstable")]
             attachment,
+            #[cfg(feature = "unstable")]
+            trace_id,
+            handler: _,
+        } = self.into_owned();
+        let (sender, receiver) = flume::unbounded();
+        let on_final: OnFinal = {
+            let sender = sender.clone();
+            Box::new(move |status| {
+                if let Some(FinalCallback(on_final)) = user_on_final {
+                    on_final(status);
+                }
+                let _ = sender.send(ReplyEvent::Done(status));
+            })
+        };
+        let callback = move |reply| {
+            let _ = sender.send(ReplyEvent::Reply(reply));
+        };
+        let (consolidation, callback, on_final) = consolidate(
+            consolidator,
+            consolidation,
+            Arc::new(callback),
+            Some(on_final),
+        );
+        ResolveClosure::new(move || {
+            session.query(
+                &selector?,
+                &scope?,
+                target,
+                selection,
+                consolidation,
+                destination,
+                timeout,
+                value,
+                #[cfg(feature = "unstable")]
+                attachment,
+                #[cfg(feature = "unstable")]
+                trace_id,
+                callback,
+                on_final,
+                cancellation,
+            )?;
+            Ok(ReplyStream {
+                receiver: receiver.into_stream(),
+            })
+        })
+    }
+
+    fn into_owned(self) -> GetBuilder<'a, 'static, DefaultHandler> {
+        let GetBuilder {
+            session,
+            selector,
+            scope,
+            target,
+            selection,
+            consolidation,
+            destination,
+            timeout,
+            value,
+            consolidator,
+            cancellation,
+            on_final,
+            #[cfg(feature = "unstable")]
+            attachment,
+            #[cfg(feature = "unstable")]
+            trace_id,
+            handler,
+        } = self;
+        GetBuilder {
+            session,
+            selector: selector.map(Selector::into_owned),
+            scope: scope.map(|scope| scope.map(KeyExpr::into_owned)),
+            target,
+            selection,
+            consolidation,
+            destination,
+            timeout,
+            value,
+            consolidator,
+            cancellation,
+            on_final,
+            #[cfg(feature = "unstable")]
+            attachment,
+            #[cfg(feature = "unstable")]
+            trace_id,
             handler,
         }
     }
@@ -277,7 +809,22 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
         self
     }
 
+    /// Change how the routers select the queryable receiving the query when it targets the
+    /// [`BestMatching`](QueryTarget::BestMatching) one, instead of their configured
+    /// `routing.query.selection`. The [`Ranked`](QueryableSelection::Ranked) selection follows
+    /// the `routing.query.ranking` of the routers.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn selection(mut self, selection: QueryableSelection) -> Self {
+        self.selection = Some(selection);
+        self
+    }
+
     /// Change the consolidation mode of the query.
+    ///
+    /// Replies of kind [`SampleKind::Delete`] take part in the consolidation like the others:
+    /// a deletion suppresses the older replies for the same key expression, and is itself
+    /// suppressed by the newer ones.
     #[inline]
     pub fn consolidation<QC: Into<QueryConsolidation>>(mut self, consolidation: QC) -> Self {
         self.consolidation = consolidation.into();
@@ -293,6 +840,80 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
         self
     }
 
+    /// Allow to cancel the query with the given [`CancellationHandle`].
+    #[inline]
+    pub fn cancellation(mut self, handle: CancellationHandle) -> Self {
+        self.cancellation = Some(handle);
+        self
+    }
+
+    /// Call `on_final` with the [`QueryStatus`] of the query once it ended, after all its
+    /// replies were delivered.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session
+    ///     .get("key/expression")
+    ///     .callback(|reply| println!("Received {:?}", reply.sample))
+    ///     .on_final(|status| println!("Query ended: {status:?}"))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn on_final<F>(mut self, on_final: F) -> Self
+    where
+        F: FnOnce(QueryStatus) + Send + Sync + 'static,
+    {
+        self.on_final = Some(FinalCallback(Box::new(on_final)));
+        self
+    }
+
+    /// Consolidate the replies of the query with a user-provided function.
+    ///
+    /// When set, the query is sent without consolidation and all its replies are buffered by
+    /// the querying session. Once all the matching queryables answered (or the query timed out),
+    /// the buffered replies are passed to `consolidate` and only the replies it returns are
+    /// delivered. This replaces any [`consolidation`](GetBuilder::consolidation) mode.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// // Only keep the reply with the largest payload.
+    /// let replies = session
+    ///     .get("key/expression")
+    ///     .consolidate_with(|replies| {
+    ///         replies
+    ///             .into_iter()
+    ///             .filter(|reply| reply.sample.is_ok())
+    ///             .max_by_key(|reply| reply.sample.as_ref().unwrap().value.payload.len())
+    ///             .into_iter()
+    ///             .collect()
+    ///     })
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn consolidate_with<F>(mut self, consolidate: F) -> Self
+    where
+        F: Fn(Vec<Reply>) -> Vec<Reply> + Send + Sync + 'static,
+    {
+        self.consolidator = Some(Consolidator(Arc::new(consolidate)));
+        self
+    }
+
     /// Set query timeout.
     #[inline]
     pub fn timeout(mut self, timeout: Duration) -> Self {
@@ -301,6 +922,10 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
     }
 
     /// Set query value.
+    ///
+    /// The value is the body of the query: its payload and encoding are delivered as is to the
+    /// queryables through [`Query::value`](crate::queryable::Query::value), unlike the selector
+    /// parameters which must be valid UTF-8.
     #[inline]
     pub fn with_value<IntoValue>(mut self, value: IntoValue) -> Self
     where
@@ -316,6 +941,13 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
         self
     }
 
+    /// Attach a [`TraceId`] to the query, delivered to the queryables and carried by the replies.
+    #[zenoh_macros::unstable]
+    pub fn trace_id(mut self, trace_id: TraceId) -> Self {
+        self.trace_id = Some(trace_id);
+        self
+    }
+
     /// By default, `get` guarantees that it will only receive replies whose key expressions intersect
     /// with the queried key expression.
     ///
@@ -328,11 +960,16 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             selector,
             scope,
             target,
+            selection,
             consolidation,
             destination,
             timeout,
             value,
+            consolidator,
+            cancellation,
+            on_final,
             attachment,
+            trace_id,
             handler,
         } = self;
         Self {
@@ -340,11 +977,16 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             selector: selector.and_then(|s| s.accept_any_keyexpr(accept == ReplyKeyExpr::Any)),
             scope,
             target,
+            selection,
             consolidation,
             destination,
             timeout,
             value,
+            consolidator,
+            cancellation,
+            on_final,
             attachment,
+            trace_id,
             handler,
         }
     }
@@ -376,20 +1018,35 @@ where
     Handler::Receiver: Send,
 {
     fn res_sync(self) -> <Self as Resolvable>::To {
+        let queue_capacity = self.handler.queue_capacity();
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let callback = self
+            .session
+            .account_handler_queue(queue_capacity, callback)?;
+        let (consolidation, callback, on_final) = consolidate(
+            self.consolidator,
+            self.consolidation,
+            callback,
+            self.on_final.map(|FinalCallback(on_final)| on_final),
+        );
 
         self.session
             .query(
                 &self.selector?,
                 &self.scope?,
                 self.target,
-                self.consolidation,
+                self.selection,
+                consolidation,
                 self.destination,
                 self.timeout,
                 self.value,
                 #[cfg(feature = "unstable")]
                 self.attachment,
+                #[cfg(feature = "unstable")]
+                self.trace_id,
                 callback,
+                on_final,
+                self.cancellation,
             )
             .map(|_| receiver)
     }
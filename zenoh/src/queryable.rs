@@ -17,43 +17,68 @@
 use crate::handlers::{locked, DefaultHandler};
 use crate::net::primitives::Primitives;
 use crate::prelude::*;
+use crate::query::ErrorCode;
 #[zenoh_macros::unstable]
 use crate::query::ReplyKeyExpr;
 #[zenoh_macros::unstable]
 use crate::sample::Attachment;
 use crate::sample::DataInfo;
+use crate::sample::SampleBuilder;
+#[zenoh_macros::unstable]
+use crate::selector::SampleFilter;
+use crate::time::Timestamp;
+#[zenoh_macros::unstable]
+use crate::trace::TraceId;
 use crate::SessionRef;
 use crate::Undeclarable;
 
+use std::collections::HashMap;
 use std::fmt;
-use std::future::Ready;
+use std::future::{Future, Ready};
 use std::ops::Deref;
-use std::sync::Arc;
-use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+use uhlc::HLC;
+use zenoh_buffers::ZStr;
+use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
 use zenoh_protocol::core::WireExpr;
 use zenoh_protocol::network::{response, Mapping, RequestId, Response, ResponseFinal};
-use zenoh_protocol::zenoh::ext::ValueType;
+use zenoh_protocol::zenoh::ext::{TraceIdType, ValueType};
 use zenoh_protocol::zenoh::reply::ext::ConsolidationType;
 use zenoh_protocol::zenoh::{self, ResponseBody};
 use zenoh_result::ZResult;
 
+/// The queries being answered by a session, indexed by locality and request id,
+/// so that they can be cancelled by their queriers.
+pub(crate) type ReceivedQueries = Arc<Mutex<HashMap<(bool, RequestId), CancellationToken>>>;
+
 pub(crate) struct QueryInner {
     /// The key expression of this Query.
     pub(crate) key_expr: KeyExpr<'static>,
     /// This Query's selector parameters.
-    pub(crate) parameters: String,
+    pub(crate) parameters: ZStr,
     /// This Query's body.
     pub(crate) value: Option<Value>,
 
     pub(crate) qid: RequestId,
     pub(crate) zid: ZenohId,
     pub(crate) primitives: Arc<dyn Primitives>,
+    pub(crate) hlc: Option<Arc<HLC>>,
+    pub(crate) drop_future_timestamp: bool,
     #[cfg(feature = "unstable")]
     pub(crate) attachment: Option<Attachment>,
+    #[cfg(feature = "unstable")]
+    pub(crate) trace_id: Option<TraceId>,
+    pub(crate) cancellation: CancellationToken,
+    /// The registry this query is cancellable through, with the locality of the query.
+    pub(crate) received_queries: Option<(ReceivedQueries, bool)>,
 }
 
 impl Drop for QueryInner {
     fn drop(&mut self) {
+        if let Some((received_queries, local)) = &self.received_queries {
+            zlock!(received_queries).remove(&(*local, self.qid));
+        }
         self.primitives.send_response_final(ResponseFinal {
             rid: self.qid,
             ext_qos: response::ext::QoSType::response_final_default(),
@@ -74,7 +99,7 @@ impl Query {
     pub fn selector(&self) -> Selector<'_> {
         Selector {
             key_expr: self.inner.key_expr.clone(),
-            parameters: (&self.inner.parameters).into(),
+            parameters: self.inner.parameters.as_str().into(),
         }
     }
 
@@ -90,7 +115,18 @@ impl Query {
         &self.inner.parameters
     }
 
-    /// This Query's value.
+    /// The standardized `_time` and `_filter` filters of this Query's selector parameters.
+    ///
+    /// Queryables should only reply with the samples passing this filter.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn sample_filter(&self) -> ZResult<SampleFilter> {
+        use crate::selector::Parameters;
+        self.inner.parameters.as_str().sample_filter()
+    }
+
+    /// This Query's value, i.e. the body set by the querier with
+    /// [`GetBuilder::with_value`](crate::query::GetBuilder::with_value).
     #[inline(always)]
     pub fn value(&self) -> Option<&Value> {
         self.inner.value.as_ref()
@@ -101,6 +137,38 @@ impl Query {
         self.inner.attachment.as_ref()
     }
 
+    /// The [`TraceId`] set by the querier with
+    /// [`GetBuilder::trace_id`](crate::query::GetBuilder::trace_id).
+    ///
+    /// The replies to this Query carry the same trace ID.
+    #[zenoh_macros::unstable]
+    pub fn trace_id(&self) -> Option<TraceId> {
+        self.inner.trace_id
+    }
+
+    fn ext_trace<const ID: u8>(&self) -> Option<TraceIdType<ID>> {
+        #[cfg(feature = "unstable")]
+        return self.inner.trace_id.map(Into::into);
+        #[cfg(not(feature = "unstable"))]
+        None
+    }
+
+    /// Whether the querier cancelled this Query, either explicitly or because it timed out.
+    ///
+    /// Replies to a cancelled Query are dropped.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancellation.is_cancelled()
+    }
+
+    /// Waits for the querier to cancel this Query.
+    ///
+    /// Long-running queryables can use it to stop answering as soon as the querier gave up.
+    #[inline]
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + '_ {
+        self.inner.cancellation.cancelled()
+    }
+
     /// Sends a reply to this Query.
     ///
     /// By default, queries only accept replies whose key expression intersects with the query's.
@@ -111,9 +179,46 @@ impl Query {
         ReplyBuilder {
             query: self,
             result,
+            code: ErrorCode::Unspecified,
+            priority: Priority::default(),
+            congestion_control: CongestionControl::Block,
+            is_express: false,
+            timestamp: None,
+        }
+    }
+
+    /// Sends an error reply to this Query, with an [`ErrorCode`] the querier can match on.
+    ///
+    /// The querier gets the code back with [`Reply::error_code`](crate::query::Reply::error_code)
+    /// or [`Reply::into_result`](crate::query::Reply::into_result).
+    #[inline]
+    pub fn reply_err<IntoValue>(&self, code: ErrorCode, value: IntoValue) -> ReplyBuilder<'_>
+    where
+        IntoValue: Into<Value>,
+    {
+        ReplyBuilder {
+            query: self,
+            result: Err(value.into()),
+            code,
+            priority: Priority::default(),
+            congestion_control: CongestionControl::Block,
+            is_express: false,
+            timestamp: None,
         }
     }
 
+    /// Sends a reply to this Query signaling the deletion of `key_expr`.
+    ///
+    /// The querier receives it as a [`Sample`] of kind [`SampleKind::Delete`].
+    /// The same key expression restrictions as [`Query::reply`] apply.
+    #[inline]
+    pub fn reply_del<IntoKeyExpr>(&self, key_expr: IntoKeyExpr) -> ReplyBuilder<'_>
+    where
+        IntoKeyExpr: Into<KeyExpr<'static>>,
+    {
+        self.reply(Ok(SampleBuilder::delete(key_expr).build()))
+    }
+
     /// Queries may or may not accept replies on key expressions that do not intersect with their own key expression.
     /// This getter allows you to check whether or not a specific query does.
     #[zenoh_macros::unstable]
@@ -159,6 +264,11 @@ impl fmt::Display for Query {
 pub struct ReplyBuilder<'a> {
     query: &'a Query,
     result: Result<Sample, Value>,
+    code: ErrorCode,
+    priority: Priority,
+    congestion_control: CongestionControl,
+    is_express: bool,
+    timestamp: Option<Timestamp>,
 }
 
 impl<'a> ReplyBuilder<'a> {
@@ -173,6 +283,46 @@ impl<'a> ReplyBuilder<'a> {
             Err(_) => Err((self, attachment)),
         }
     }
+
+    /// Use the given [`Timestamp`] for the reply instead of the one of its [`Sample`].
+    ///
+    /// As for the data received by the routers, if the timestamp is ahead of the session's HLC
+    /// by more than its configured delta, resolving the reply fails when
+    /// `timestamping/drop_future_timestamp` is set, otherwise the timestamp is replaced
+    /// by one generated by the session's HLC. Error replies are not timestamped, so this
+    /// has no effect on them.
+    #[inline]
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Change the `congestion_control` to apply when routing the reply.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
+    }
+
+    /// Change the priority of the reply.
+    ///
+    /// Routers forward the reply to the querier with this priority, so that large bulk
+    /// replies don't delay the replies to more urgent queries.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Change the express policy to apply when routing the reply.
+    ///
+    /// When express is set to `true`, the reply is not batched with other messages
+    /// but sent as soon as possible, trading throughput for latency.
+    #[inline]
+    pub fn express(mut self, is_express: bool) -> Self {
+        self.is_express = is_express;
+        self
+    }
 }
 
 impl<'a> Resolvable for ReplyBuilder<'a> {
@@ -181,6 +331,10 @@ impl<'a> Resolvable for ReplyBuilder<'a> {
 
 impl SyncResolve for ReplyBuilder<'_> {
     fn res_sync(self) -> <Self as Resolvable>::To {
+        if self.query.is_cancelled() {
+            tracing::trace!("Drop reply to cancelled query {}", self.query.inner.qid);
+            return Ok(());
+        }
         match self.result {
             Ok(sample) => {
                 if !self.query._accepts_any_replies().unwrap_or(false)
@@ -198,7 +352,18 @@ impl SyncResolve for ReplyBuilder<'_> {
                     source_info,
                     #[cfg(feature = "unstable")]
                     attachment,
+                    ..
                 } = sample;
+                // Only the overridden timestamps are checked, the ones of the samples
+                // being left untouched as for the other replies
+                let timestamp = match self.timestamp {
+                    Some(timestamp) => Some(crate::time::check_timestamp(
+                        self.query.inner.hlc.as_deref(),
+                        self.query.inner.drop_future_timestamp,
+                        timestamp,
+                    )?),
+                    None => timestamp,
+                };
                 #[allow(unused_mut)]
                 let mut data_info = DataInfo {
                     kind,
@@ -207,6 +372,17 @@ impl SyncResolve for ReplyBuilder<'_> {
                     qos,
                     source_id: None,
                     source_sn: None,
+                    source_eid: None,
+                    #[cfg(feature = "unstable")]
+                    coherence: None,
+                    #[cfg(feature = "unstable")]
+                    ttl: None,
+                    #[cfg(feature = "unstable")]
+                    latency_budget: None,
+                    #[cfg(feature = "unstable")]
+                    extensions: vec![],
+                    #[cfg(feature = "unstable")]
+                    trace_id: None,
                 };
                 #[allow(unused_mut)]
                 let mut ext_attachment = None;
@@ -214,6 +390,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                 {
                     data_info.source_id = source_info.source_id;
                     data_info.source_sn = source_info.source_sn;
+                    data_info.source_eid = Some(source_info.source_eid);
                     if let Some(attachment) = attachment {
                         ext_attachment = Some(attachment.into());
                     }
@@ -232,7 +409,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                         {
                             Some(zenoh::reply::ext::SourceInfoType {
                                 zid: data_info.source_id.unwrap_or_default(),
-                                eid: 0, // @TODO use proper EntityId (#703)
+                                eid: data_info.source_eid.unwrap_or_default(),
                                 sn: data_info.source_sn.unwrap_or_default() as u32,
                             })
                         } else {
@@ -242,10 +419,17 @@ impl SyncResolve for ReplyBuilder<'_> {
                         #[cfg(feature = "shared-memory")]
                         ext_shm: None,
                         ext_attachment,
+                        ext_delete: (data_info.kind == SampleKind::Delete)
+                            .then_some(zenoh::reply::ext::Delete::new()),
+                        ext_trace: self.query.ext_trace(),
                         ext_unknown: vec![],
                         payload,
                     }),
-                    ext_qos: response::ext::QoSType::response_default(),
+                    ext_qos: response::ext::QoSType::new(
+                        self.priority.into(),
+                        self.congestion_control,
+                        self.is_express,
+                    ),
                     ext_tstamp: None,
                     ext_respid: Some(response::ext::ResponderIdType {
                         zid: self.query.inner.zid,
@@ -266,6 +450,7 @@ impl SyncResolve for ReplyBuilder<'_> {
                         timestamp: None,
                         is_infrastructure: false,
                         ext_sinfo: None,
+                        ext_trace: self.query.ext_trace(),
                         ext_unknown: vec![],
                         ext_body: Some(ValueType {
                             #[cfg(feature = "shared-memory")]
@@ -273,9 +458,13 @@ impl SyncResolve for ReplyBuilder<'_> {
                             payload: payload.payload,
                             encoding: payload.encoding,
                         }),
-                        code: 0, // TODO
+                        code: self.code.into(),
                     }),
-                    ext_qos: response::ext::QoSType::response_default(),
+                    ext_qos: response::ext::QoSType::new(
+                        self.priority.into(),
+                        self.congestion_control,
+                        self.is_express,
+                    ),
                     ext_tstamp: None,
                     ext_respid: Some(response::ext::ResponderIdType {
                         zid: self.query.inner.zid,
@@ -628,7 +817,9 @@ where
 {
     fn res_sync(self) -> <Self as Resolvable>::To {
         let session = self.session;
+        let queue_capacity = self.handler.queue_capacity();
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let callback = session.account_handler_queue(queue_capacity, callback)?;
         session
             .declare_queryable_inner(
                 &self.key_expr?.to_wire(&session),
@@ -14,19 +14,31 @@
 
 //! Sample primitives
 use crate::buffers::ZBuf;
+#[zenoh_macros::unstable]
+use crate::extension::Extension;
 use crate::prelude::ZenohId;
 use crate::prelude::{KeyExpr, SampleKind, Value};
 use crate::query::Reply;
+#[zenoh_macros::unstable]
+use crate::time::NTP64;
 use crate::time::{new_reception_timestamp, Timestamp};
+#[zenoh_macros::unstable]
+use crate::trace::TraceId;
 use crate::Priority;
 #[zenoh_macros::unstable]
 use serde::Serialize;
 use std::convert::{TryFrom, TryInto};
+#[zenoh_macros::unstable]
+use std::time::{Duration, SystemTime};
 use zenoh_protocol::core::{CongestionControl, Encoding};
 use zenoh_protocol::network::push::ext::QoSType;
 
 pub type SourceSn = u64;
 
+/// The id of an entity, e.g. a [`Publisher`](crate::publication::Publisher),
+/// within its zenoh instance.
+pub type EntityId = u32;
+
 /// The locality of samples to be received by subscribers or targeted by publishers.
 #[zenoh_macros::unstable]
 #[derive(Clone, Copy, Debug, Default, Serialize, PartialEq, Eq)]
@@ -52,6 +64,17 @@ pub(crate) struct DataInfo {
     pub timestamp: Option<Timestamp>,
     pub source_id: Option<ZenohId>,
     pub source_sn: Option<SourceSn>,
+    pub source_eid: Option<EntityId>,
+    #[cfg(feature = "unstable")]
+    pub coherence: Option<Coherence>,
+    #[cfg(feature = "unstable")]
+    pub ttl: Option<Duration>,
+    #[cfg(feature = "unstable")]
+    pub latency_budget: Option<Duration>,
+    #[cfg(feature = "unstable")]
+    pub extensions: Vec<Extension>,
+    #[cfg(feature = "unstable")]
+    pub trace_id: Option<TraceId>,
     pub qos: QoS,
 }
 
@@ -63,6 +86,9 @@ pub struct SourceInfo {
     pub source_id: Option<ZenohId>,
     /// The sequence number of the [`Sample`] from the source.
     pub source_sn: Option<SourceSn>,
+    /// The [`EntityId`] of the publisher within the source, only meaningful if
+    /// [`source_id`](SourceInfo::source_id) is set.
+    pub source_eid: EntityId,
 }
 
 #[test]
@@ -80,8 +106,27 @@ impl SourceInfo {
         SourceInfo {
             source_id: None,
             source_sn: None,
+            source_eid: 0,
+        }
+    }
+
+    /// Creates the source info of a [`Sample`] published by the entity `source_eid`
+    /// of the zenoh instance `source_id`, with sequence number `source_sn`.
+    pub fn new(source_id: ZenohId, source_eid: EntityId, source_sn: SourceSn) -> Self {
+        SourceInfo {
+            source_id: Some(source_id),
+            source_sn: Some(source_sn),
+            source_eid,
         }
     }
+
+    /// The globally unique id of the entity that published the concerned [`Sample`], if known.
+    pub fn source(&self) -> Option<EntityGlobalId> {
+        self.source_id.map(|zid| EntityGlobalId {
+            zid,
+            eid: self.source_eid,
+        })
+    }
 }
 
 #[zenoh_macros::unstable]
@@ -90,6 +135,7 @@ impl From<DataInfo> for SourceInfo {
         SourceInfo {
             source_id: data_info.source_id,
             source_sn: data_info.source_sn,
+            source_eid: data_info.source_eid.unwrap_or_default(),
         }
     }
 }
@@ -104,6 +150,54 @@ impl From<Option<DataInfo>> for SourceInfo {
     }
 }
 
+/// The globally unique id of an entity, i.e. its [`EntityId`] within the zenoh instance
+/// identified by its [`ZenohId`].
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntityGlobalId {
+    pub zid: ZenohId,
+    pub eid: EntityId,
+}
+
+/// Notification that samples published by a source were not received by a subscriber,
+/// as detected from the gaps in the sequence numbers of the samples received from it.
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleMissed {
+    /// The source whose samples were missed.
+    pub source: EntityGlobalId,
+    /// The number of missed samples.
+    pub count: u32,
+}
+
+/// The position of a [`Sample`] in a coherent set of changes published with
+/// [`Session::coherent_set`](crate::Session::coherent_set).
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Coherence {
+    /// The [`ZenohId`] of the zenoh instance that published the set.
+    pub zid: ZenohId,
+    /// The id of the set, unique within the zenoh instance that published it.
+    pub set_id: u32,
+    /// The index of the [`Sample`] in the set.
+    pub index: u32,
+    /// Whether the [`Sample`] is the last one of the set.
+    pub last: bool,
+}
+
+#[zenoh_macros::unstable]
+pub use crate::net::routing::interceptor::DropReason;
+
+/// Informations on samples dropped by the infrastructure before reaching a subscriber.
+#[zenoh_macros::unstable]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleGap {
+    /// The number of dropped samples.
+    pub count: usize,
+    /// The reason why the samples were dropped.
+    pub reason: DropReason,
+}
+
 mod attachment {
     #[zenoh_macros::unstable]
     use zenoh_buffers::{
@@ -327,6 +421,9 @@ pub struct Sample {
     /// The key expression on which this Sample was published.
     pub key_expr: KeyExpr<'static>,
     /// The value of this Sample.
+    ///
+    /// The value of a [`Delete`](SampleKind::Delete) sample is empty, unless it was published with one
+    /// (see [`PutBuilder::value`](crate::publication::PutBuilder::value)).
     pub value: Value,
     /// The kind of this Sample.
     pub kind: SampleKind,
@@ -354,6 +451,75 @@ pub struct Sample {
     ///
     /// A map of key-value pairs, where each key and value are byte-slices.
     pub attachment: Option<Attachment>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// Set on the synthetic samples notifying a subscriber that samples were dropped by the
+    /// infrastructure (see [`SubscriberBuilder::drop_notifications`](crate::subscriber::SubscriberBuilder::drop_notifications)).
+    /// The key expression of such a sample covers the keys of the dropped samples and its value is empty.
+    pub gap: Option<SampleGap>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// The position of this Sample in a coherent set of changes, if it was published as part of one
+    /// (see [`Session::coherent_set`](crate::Session::coherent_set)).
+    pub coherence: Option<Coherence>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// For how long this Sample is valid after its [`timestamp`](Sample::timestamp), if it was
+    /// published with a time-to-live (see [`PutBuilder::ttl`](crate::publication::PutBuilder::ttl)).
+    pub ttl: Option<Duration>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// The maximum delay after its [`timestamp`](Sample::timestamp) for this Sample to be delivered,
+    /// if it was published with a latency budget
+    /// (see [`PutBuilder::latency_budget`](crate::publication::PutBuilder::latency_budget)).
+    pub latency_budget: Option<Duration>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// The user-defined protocol extensions this Sample was published with
+    /// (see [`PutBuilder::with_extension`](crate::publication::PutBuilder::with_extension)).
+    pub extensions: Vec<Extension>,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// The [`TraceId`] this Sample was published with
+    /// (see [`PutBuilder::trace_id`](crate::publication::PutBuilder::trace_id)),
+    /// or the one of the query it replies to.
+    pub trace_id: Option<TraceId>,
 }
 
 impl Sample {
@@ -374,6 +540,18 @@ impl Sample {
             source_info: SourceInfo::empty(),
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            gap: None,
+            #[cfg(feature = "unstable")]
+            coherence: None,
+            #[cfg(feature = "unstable")]
+            ttl: None,
+            #[cfg(feature = "unstable")]
+            latency_budget: None,
+            #[cfg(feature = "unstable")]
+            extensions: vec![],
+            #[cfg(feature = "unstable")]
+            trace_id: None,
         }
     }
     /// Creates a new Sample.
@@ -397,6 +575,18 @@ impl Sample {
             source_info: SourceInfo::empty(),
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            gap: None,
+            #[cfg(feature = "unstable")]
+            coherence: None,
+            #[cfg(feature = "unstable")]
+            ttl: None,
+            #[cfg(feature = "unstable")]
+            latency_budget: None,
+            #[cfg(feature = "unstable")]
+            extensions: vec![],
+            #[cfg(feature = "unstable")]
+            trace_id: None,
         })
     }
 
@@ -419,9 +609,21 @@ impl Sample {
                 timestamp: data_info.timestamp,
                 qos: data_info.qos,
                 #[cfg(feature = "unstable")]
+                coherence: data_info.coherence,
+                #[cfg(feature = "unstable")]
+                ttl: data_info.ttl,
+                #[cfg(feature = "unstable")]
+                latency_budget: data_info.latency_budget,
+                #[cfg(feature = "unstable")]
+                extensions: data_info.extensions.clone(),
+                #[cfg(feature = "unstable")]
+                trace_id: data_info.trace_id,
+                #[cfg(feature = "unstable")]
                 source_info: data_info.into(),
                 #[cfg(feature = "unstable")]
                 attachment: None,
+                #[cfg(feature = "unstable")]
+                gap: None,
             }
         } else {
             Sample {
@@ -434,6 +636,18 @@ impl Sample {
                 source_info: SourceInfo::empty(),
                 #[cfg(feature = "unstable")]
                 attachment: None,
+                #[cfg(feature = "unstable")]
+                gap: None,
+                #[cfg(feature = "unstable")]
+                coherence: None,
+                #[cfg(feature = "unstable")]
+                ttl: None,
+                #[cfg(feature = "unstable")]
+                latency_budget: None,
+                #[cfg(feature = "unstable")]
+                extensions: vec![],
+                #[cfg(feature = "unstable")]
+                trace_id: None,
             }
         }
     }
@@ -473,6 +687,78 @@ impl Sample {
         }
     }
 
+    /// Gets the time at which this Sample expires, if it was published with a
+    /// [`ttl`](Sample::ttl) and is timestamped.
+    #[zenoh_macros::unstable]
+    pub fn expiration(&self) -> Option<Timestamp> {
+        let (timestamp, ttl) = (self.timestamp.as_ref()?, self.ttl?);
+        Some(Timestamp::new(
+            *timestamp.get_time() + NTP64::from(ttl),
+            *timestamp.get_id(),
+        ))
+    }
+
+    /// Returns `true` if this Sample outlived its [`ttl`](Sample::ttl).
+    ///
+    /// Samples without [`timestamp`](Sample::timestamp) never expire.
+    #[zenoh_macros::unstable]
+    pub fn is_expired(&self) -> bool {
+        self.expiration()
+            .is_some_and(|e| e.get_time().to_system_time() < SystemTime::now())
+    }
+
+    /// Gets the time by which this Sample should have been delivered, if it was published with a
+    /// [`latency_budget`](Sample::latency_budget) and is timestamped.
+    #[zenoh_macros::unstable]
+    pub fn deadline(&self) -> Option<Timestamp> {
+        let (timestamp, budget) = (self.timestamp.as_ref()?, self.latency_budget?);
+        Some(Timestamp::new(
+            *timestamp.get_time() + NTP64::from(budget),
+            *timestamp.get_id(),
+        ))
+    }
+
+    /// Returns `true` if this Sample exceeded its [`latency_budget`](Sample::latency_budget).
+    ///
+    /// Samples without [`timestamp`](Sample::timestamp) are never late.
+    #[zenoh_macros::unstable]
+    pub fn is_late(&self) -> bool {
+        self.deadline()
+            .is_some_and(|d| d.get_time().to_system_time() < SystemTime::now())
+    }
+
+    /// Gets the priority this Sample was sent with.
+    #[inline]
+    pub fn priority(&self) -> Priority {
+        self.qos.priority()
+    }
+
+    /// Gets the congestion control this Sample was sent with.
+    #[inline]
+    pub fn congestion_control(&self) -> CongestionControl {
+        self.qos.congestion_control()
+    }
+
+    /// Gets the express flag this Sample was sent with.
+    #[inline]
+    pub fn express(&self) -> bool {
+        self.qos.express()
+    }
+
+    /// Gets the [`ZenohId`] of the zenoh instance that published this Sample, if known.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn source_id(&self) -> Option<ZenohId> {
+        self.source_info.source_id
+    }
+
+    /// Gets the sequence number of this Sample from its source, if known.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn source_sn(&self) -> Option<SourceSn> {
+        self.source_info.source_sn
+    }
+
     #[zenoh_macros::unstable]
     pub fn attachment(&self) -> Option<&Attachment> {
         self.attachment.as_ref()
@@ -491,6 +777,146 @@ impl Sample {
     }
 }
 
+/// A builder for [`Sample`]s.
+///
+/// Useful to fully construct samples outside of zenoh, e.g. in storage backends, replicators or tests.
+///
+/// # Examples
+/// ```
+/// use zenoh::prelude::sync::*;
+/// use zenoh::sample::{QoS, SampleBuilder};
+///
+/// let key_expr = KeyExpr::try_from("key/expression").unwrap();
+/// let sample = SampleBuilder::put(key_expr.clone(), "value")
+///     .qos(QoS::default().with_priority(Priority::DataHigh))
+///     .build();
+/// assert_eq!(sample.kind, SampleKind::Put);
+/// assert_eq!(sample.priority(), Priority::DataHigh);
+///
+/// let sample = SampleBuilder::delete(key_expr).build();
+/// assert_eq!(sample.kind, SampleKind::Delete);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SampleBuilder(Sample);
+
+impl SampleBuilder {
+    /// Creates a builder for a [`Put`](SampleKind::Put) sample.
+    #[inline]
+    pub fn put<IntoKeyExpr, IntoValue>(key_expr: IntoKeyExpr, value: IntoValue) -> Self
+    where
+        IntoKeyExpr: Into<KeyExpr<'static>>,
+        IntoValue: Into<Value>,
+    {
+        SampleBuilder(Sample::new(key_expr, value))
+    }
+
+    /// Creates a builder for a [`Delete`](SampleKind::Delete) sample.
+    #[inline]
+    pub fn delete<IntoKeyExpr>(key_expr: IntoKeyExpr) -> Self
+    where
+        IntoKeyExpr: Into<KeyExpr<'static>>,
+    {
+        SampleBuilder::put(key_expr, Value::empty()).kind(SampleKind::Delete)
+    }
+
+    /// Sets the key expression of the Sample.
+    #[inline]
+    pub fn key_expr<IntoKeyExpr>(mut self, key_expr: IntoKeyExpr) -> Self
+    where
+        IntoKeyExpr: Into<KeyExpr<'static>>,
+    {
+        self.0.key_expr = key_expr.into();
+        self
+    }
+
+    /// Sets the value of the Sample.
+    #[inline]
+    pub fn value<IntoValue>(mut self, value: IntoValue) -> Self
+    where
+        IntoValue: Into<Value>,
+    {
+        self.0.value = value.into();
+        self
+    }
+
+    /// Sets the kind of the Sample.
+    #[inline]
+    pub fn kind(mut self, kind: SampleKind) -> Self {
+        self.0.kind = kind;
+        self
+    }
+
+    /// Sets the timestamp of the Sample.
+    #[inline]
+    pub fn timestamp<IntoTimestamp>(mut self, timestamp: IntoTimestamp) -> Self
+    where
+        IntoTimestamp: Into<Option<Timestamp>>,
+    {
+        self.0.timestamp = timestamp.into();
+        self
+    }
+
+    /// Sets the quality of service settings of the Sample.
+    #[inline]
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.0.qos = qos;
+        self
+    }
+
+    /// Sets the source info of the Sample.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn source_info(mut self, source_info: SourceInfo) -> Self {
+        self.0.source_info = source_info;
+        self
+    }
+
+    /// Sets the attachment of the Sample.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn attachment<IntoAttachment>(mut self, attachment: IntoAttachment) -> Self
+    where
+        IntoAttachment: Into<Option<Attachment>>,
+    {
+        self.0.attachment = attachment.into();
+        self
+    }
+
+    /// Sets the time-to-live of the Sample.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ttl<IntoDuration>(mut self, ttl: IntoDuration) -> Self
+    where
+        IntoDuration: Into<Option<Duration>>,
+    {
+        self.0.ttl = ttl.into();
+        self
+    }
+
+    /// Sets the latency budget of the Sample.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn latency_budget<IntoDuration>(mut self, latency_budget: IntoDuration) -> Self
+    where
+        IntoDuration: Into<Option<Duration>>,
+    {
+        self.0.latency_budget = latency_budget.into();
+        self
+    }
+
+    /// Builds the Sample.
+    #[inline]
+    pub fn build(self) -> Sample {
+        self.0
+    }
+}
+
+impl From<SampleBuilder> for Sample {
+    fn from(builder: SampleBuilder) -> Self {
+        builder.build()
+    }
+}
+
 impl std::ops::Deref for Sample {
     type Target = Value;
 
@@ -14,10 +14,13 @@
 use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::net::runtime::{orchestrator::Loop, Runtime};
 
+use crate::prelude::{Locator, SplitBuffer, ZenohId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{fmt, future::Ready, net::SocketAddr, ops::Deref};
 use tokio::net::UdpSocket;
-use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
 use zenoh_protocol::core::WhatAmIMatcher;
 use zenoh_result::ZResult;
 use zenoh_task::TerminatableTask;
@@ -25,8 +28,48 @@ use zenoh_task::TerminatableTask;
 /// Constants and helpers for zenoh `whatami` flags.
 pub use zenoh_protocol::core::WhatAmI;
 
-/// A zenoh Hello message.
-pub use zenoh_protocol::scouting::Hello;
+/// A zenoh Hello message, advertising a zenoh node found by a [`Scout`].
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hello {
+    /// The [`ZenohId`] of the node.
+    pub zid: ZenohId,
+    /// The role of the node.
+    pub whatami: WhatAmI,
+    /// The locators the node is reachable at.
+    pub locators: Vec<Locator>,
+    /// The `metadata` of the node's configuration, if the node advertises it.
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl From<zenoh_protocol::scouting::Hello> for Hello {
+    fn from(hello: zenoh_protocol::scouting::Hello) -> Self {
+        let metadata = hello.ext_metadata.and_then(|m| {
+            serde_json::from_slice(&m.value.contiguous())
+                .map_err(|e| tracing::debug!("Invalid metadata in Hello from {}: {}", hello.zid, e))
+                .ok()
+        });
+        Hello {
+            zid: hello.zid,
+            whatami: hello.whatami,
+            locators: hello.locators,
+            metadata,
+        }
+    }
+}
+
+impl fmt::Display for Hello {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("Hello");
+        s.field("zid", &self.zid)
+            .field("whatami", &self.whatami)
+            .field("locators", &self.locators);
+        if let Some(metadata) = &self.metadata {
+            s.field("metadata", &format_args!("{metadata}"));
+        }
+        s.finish()
+    }
+}
 
 /// A builder for initializing a [`Scout`].
 ///
@@ -52,6 +95,7 @@ pub struct ScoutBuilder<Handler> {
     pub(crate) what: WhatAmIMatcher,
     pub(crate) config: ZResult<crate::config::Config>,
     pub(crate) handler: Handler,
+    pub(crate) deduplicate: bool,
 }
 
 impl ScoutBuilder<DefaultHandler> {
@@ -80,11 +124,13 @@ impl ScoutBuilder<DefaultHandler> {
             what,
             config,
             handler: _,
+            deduplicate,
         } = self;
         ScoutBuilder {
             what,
             config,
             handler: callback,
+            deduplicate,
         }
     }
 
@@ -147,15 +193,29 @@ impl ScoutBuilder<DefaultHandler> {
             what,
             config,
             handler: _,
+            deduplicate,
         } = self;
         ScoutBuilder {
             what,
             config,
             handler,
+            deduplicate,
         }
     }
 }
 
+impl<Handler> ScoutBuilder<Handler> {
+    /// Only report each node once, or again when its [`Hello`] changes.
+    ///
+    /// Scouting goes on until the [`Scout`] is stopped and nodes keep answering to it,
+    /// so without deduplication the same nodes are reported over and over.
+    #[inline]
+    pub fn deduplicate(mut self, deduplicate: bool) -> Self {
+        self.deduplicate = deduplicate;
+        self
+    }
+}
+
 impl<Handler> Resolvable for ScoutBuilder<Handler>
 where
     Handler: crate::prelude::IntoCallbackReceiverPair<'static, Hello> + Send,
@@ -171,6 +231,19 @@ where
 {
     fn res_sync(self) -> <Self as Resolvable>::To {
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let callback = if self.deduplicate {
+            let seen: Mutex<HashMap<ZenohId, Hello>> = Mutex::new(HashMap::new());
+            Arc::new(move |hello: Hello| {
+                let mut seen = zlock!(seen);
+                if seen.get(&hello.zid) != Some(&hello) {
+                    seen.insert(hello.zid, hello.clone());
+                    drop(seen);
+                    callback(hello);
+                }
+            })
+        } else {
+            callback
+        };
         scout(self.what, self.config?, callback).map(|scout| Scout { scout, receiver })
     }
 }
@@ -322,7 +395,7 @@ fn scout(
         zenoh_config::defaults::scouting::multicast::interface,
         |s| s.as_ref(),
     );
-    let ifaces = Runtime::get_interfaces(ifaces);
+    let ifaces = Runtime::get_group_interfaces(ifaces, &addr);
     if !ifaces.is_empty() {
         let sockets: Vec<UdpSocket> = ifaces
             .into_iter()
@@ -337,7 +410,7 @@ fn scout(
                     let scout = Runtime::scout(&sockets, what, &addr, move |hello| {
                         let callback = callback.clone();
                         async move {
-                            callback(hello);
+                            callback(hello.into());
                             Loop::Continue
                         }
                     });
@@ -27,6 +27,15 @@ use std::{
     hash::Hash,
     str::FromStr,
 };
+#[cfg(feature = "unstable")]
+use {
+    crate::{
+        prelude::{KnownEncoding, SplitBuffer},
+        sample::Sample,
+        value::Value,
+    },
+    std::time::SystemTime,
+};
 
 /// A selector is the combination of a [Key Expression](crate::prelude::KeyExpr), which defines the
 /// set of keys that are relevant to an operation, and a set of parameters
@@ -61,6 +70,8 @@ use std::{
 ///   this parameter must be readable by the [Zenoh Time DSL](zenoh_util::time_range::TimeRange) for the value to be considered valid.
 /// - **`[unstable]`** `_anyke`: used in queries to express interest in replies coming from any key expression. By default, only replies
 ///   whose key expression match query's key expression are accepted. `_anyke` disables the query-reply key expression matching check.
+/// - **`[unstable]`** `_filter`: used to express interest in only values satisfying numeric comparisons, see [`Condition`]
+///   for the syntax. Queryables can evaluate it together with `_time` through [`SampleFilter`].
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq)]
 pub struct Selector<'a> {
@@ -71,6 +82,8 @@ pub struct Selector<'a> {
 }
 
 pub const TIME_RANGE_KEY: &str = "_time";
+#[zenoh_macros::unstable]
+pub const FILTER_KEY: &str = "_filter";
 impl<'a> Selector<'a> {
     /// Gets the parameters as a raw string.
     pub fn parameters(&self) -> &str {
@@ -281,6 +294,53 @@ fn selector_accessors() {
         assert_eq!(selector.to_string(), without_any + "&other");
     }
 }
+#[cfg(feature = "unstable")]
+#[test]
+fn selector_sample_filter() {
+    use crate::time::{new_reception_timestamp, Timestamp, TimestampId};
+    use std::time::Duration;
+
+    let selector = Selector::try_from("a/b?_time=[now(-1h)..]&_filter=temp>30;x.y<=2").unwrap();
+    let filter = selector.sample_filter().unwrap();
+    assert_eq!(
+        filter
+            .conditions()
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>(),
+        ["temp>30", "x.y<=2"]
+    );
+    let key_expr = KeyExpr::try_from("a/b").unwrap();
+    let json = |v: serde_json::Value| Sample::new(key_expr.clone(), v);
+    assert!(filter.matches(&json(serde_json::json!({"temp": 31, "x": {"y": 2.0}}))));
+    assert!(!filter.matches(&json(serde_json::json!({"temp": 30, "x": {"y": 2.0}}))));
+    assert!(!filter.matches(&json(serde_json::json!({"temp": 31}))));
+    assert!(!filter.matches(&Sample::new(key_expr.clone(), 31)));
+
+    // Samples out of the time range are filtered out, samples without timestamp are not.
+    let sample = json(serde_json::json!({"temp": 31, "x": {"y": 0}}));
+    let old = Duration::from_secs(60);
+    let id = TimestampId::try_from([1]).unwrap();
+    assert!(filter.matches(&sample.clone().with_timestamp(new_reception_timestamp())));
+    assert!(!filter.matches(
+        &sample
+            .clone()
+            .with_timestamp(Timestamp::new(old.into(), id))
+    ));
+    assert!(filter.matches(&sample));
+
+    // Without field, the payload itself is compared.
+    let filter = "_filter=!=3".sample_filter().unwrap();
+    assert!(filter.matches(&Sample::new(key_expr.clone(), 4)));
+    assert!(filter.matches(&Sample::new(key_expr.clone(), "2.5")));
+    assert!(!filter.matches(&Sample::new(key_expr.clone(), 3.0)));
+    assert!(!filter.matches(&Sample::new(key_expr, "three")));
+
+    assert!("a/b".sample_filter().unwrap().is_empty());
+    assert!("_filter=temp".sample_filter().is_err());
+    assert!("_filter=temp>hot".sample_filter().is_err());
+}
+
 pub trait Parameter: Sized {
     type Name: AsRef<str> + Sized;
     type Value: AsRef<str> + Sized;
@@ -405,6 +465,33 @@ pub trait Parameters<'a> {
             None => None,
         })
     }
+
+    /// Extracts the standardized `_time` and `_filter` arguments from the selector parameters
+    /// into a [`SampleFilter`], resolving the time range at the current time.
+    #[zenoh_macros::unstable]
+    fn sample_filter(&'a self) -> ZResult<SampleFilter>
+    where
+        <Self::Decoder as Iterator>::Item: Parameter,
+    {
+        let [time_range, conditions] = self.get_parameters([TIME_RANGE_KEY, FILTER_KEY])?;
+        let time_range = match time_range {
+            Some(s) => Some(s.as_ref().parse::<TimeRange>()?.resolve()),
+            None => None,
+        };
+        let conditions = match conditions {
+            Some(s) => s
+                .as_ref()
+                .split(';')
+                .filter(|c| !c.is_empty())
+                .map(str::parse)
+                .collect::<ZResult<_>>()?,
+            None => vec![],
+        };
+        Ok(SampleFilter {
+            time_range,
+            conditions,
+        })
+    }
 }
 impl<'a> Parameters<'a> for Selector<'a> {
     type Decoder = <str as Parameters<'a>>::Decoder;
@@ -436,6 +523,177 @@ impl<'a, K: Borrow<str> + Hash + Eq + 'a, V: Borrow<str> + 'a> Parameters<'a> fo
     }
 }
 
+/// A comparison operator of a [`Condition`].
+#[zenoh_macros::unstable]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[zenoh_macros::unstable]
+impl Comparison {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        }
+    }
+
+    fn eval(&self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+        }
+    }
+}
+
+/// A numeric comparison of the `_filter` selector parameter.
+///
+/// In string form, a condition is `[<field>]<op><number>` where `<op>` is one of `==`, `!=`, `<`,
+/// `<=`, `>` or `>=`. Without field, the payload itself must be a number (`application/integer`,
+/// `application/float`, `text/plain` or a JSON number). With a field, the payload must be a JSON
+/// object, and `.` separates the names of nested fields. Several conditions are separated by `;`
+/// and must all hold, e.g. `_filter=temp>30;temp<=50`.
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    pub field: Option<String>,
+    pub op: Comparison,
+    pub value: f64,
+}
+
+#[zenoh_macros::unstable]
+impl Condition {
+    /// Returns `true` if `value` satisfies this condition.
+    ///
+    /// Values that aren't numbers, or don't have the field, never satisfy a condition.
+    pub fn matches(&self, value: &Value) -> bool {
+        self.extract(value)
+            .is_some_and(|lhs| self.op.eval(lhs, self.value))
+    }
+
+    fn extract(&self, value: &Value) -> Option<f64> {
+        let json = match value.encoding.prefix() {
+            KnownEncoding::AppJson | KnownEncoding::TextJson => {
+                serde_json::Value::try_from(value).ok()?
+            }
+            KnownEncoding::AppInteger | KnownEncoding::AppFloat | KnownEncoding::TextPlain
+                if self.field.is_none() =>
+            {
+                let payload = value.payload.contiguous();
+                return std::str::from_utf8(&payload).ok()?.trim().parse().ok();
+            }
+            _ => return None,
+        };
+        let json = match &self.field {
+            Some(field) => field
+                .split('.')
+                .try_fold(&json, |json, name| json.get(name))?,
+            None => &json,
+        };
+        json.as_f64()
+    }
+}
+
+#[zenoh_macros::unstable]
+impl FromStr for Condition {
+    type Err = zenoh_result::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Two-character operators first, so that `<=` isn't read as `<`.
+        const OPS: [(&str, Comparison); 6] = [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ];
+        let Some((index, op)) = OPS
+            .iter()
+            .filter_map(|(token, op)| s.find(token).map(|i| (i, (*token, *op))))
+            .min_by_key(|(i, (token, _))| (*i, std::cmp::Reverse(token.len())))
+        else {
+            bail!(
+                "Invalid filter condition (missing comparison operator): {}",
+                s
+            )
+        };
+        let field = s[..index].trim();
+        let value = s[index + op.0.len()..].trim();
+        Ok(Condition {
+            field: (!field.is_empty()).then(|| field.to_owned()),
+            op: op.1,
+            value: value
+                .parse()
+                .map_err(|e| zerror!("Invalid filter condition value `{}`: {}", value, e))?,
+        })
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::fmt::Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(field) = &self.field {
+            write!(f, "{field}")?;
+        }
+        write!(f, "{}{}", self.op.as_str(), self.value)
+    }
+}
+
+/// The standardized filters of a selector: the `_time` range and the `_filter` conditions.
+///
+/// Queryables and storages evaluate it on the samples they could reply with, so that they all
+/// share the same semantics for these parameters. An empty filter matches every sample.
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SampleFilter {
+    time_range: Option<TimeRange<SystemTime>>,
+    conditions: Vec<Condition>,
+}
+
+#[zenoh_macros::unstable]
+impl SampleFilter {
+    /// The resolved `_time` range, if any.
+    pub fn time_range(&self) -> Option<&TimeRange<SystemTime>> {
+        self.time_range.as_ref()
+    }
+
+    /// The `_filter` conditions.
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    /// Returns `true` if the filter has neither time range nor conditions.
+    pub fn is_empty(&self) -> bool {
+        self.time_range.is_none() && self.conditions.is_empty()
+    }
+
+    /// Returns `true` if `sample` passes the filter.
+    ///
+    /// Samples without timestamp are not filtered out by the time range.
+    pub fn matches(&self, sample: &Sample) -> bool {
+        if let (Some(time_range), Some(timestamp)) = (&self.time_range, &sample.timestamp) {
+            if !time_range.contains(timestamp.get_time().to_system_time()) {
+                return false;
+            }
+        }
+        self.conditions.iter().all(|c| c.matches(&sample.value))
+    }
+}
+
 impl std::fmt::Debug for Selector<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "sel\"{self}\"")
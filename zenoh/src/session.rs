@@ -13,16 +13,23 @@
 //
 
 use crate::admin;
+#[zenoh_macros::unstable]
+use crate::adminspace::Admin;
 use crate::config::Config;
 use crate::config::Notifier;
 use crate::handlers::{Callback, DefaultHandler};
 use crate::info::*;
+#[zenoh_macros::unstable]
+use crate::interceptor::{InterceptorBuilder, InterceptorsChain, SessionInterceptor};
 use crate::key_expr::KeyExprInner;
 #[zenoh_macros::unstable]
 use crate::liveliness::{Liveliness, LivelinessTokenState};
 use crate::net::primitives::Primitives;
 use crate::net::routing::dispatcher::face::Face;
+use crate::net::routing::interceptor::DropReason;
 use crate::net::runtime::Runtime;
+#[zenoh_macros::unstable]
+use crate::net::runtime::{EndpointKind, EndpointStatus};
 use crate::prelude::Locality;
 use crate::prelude::{KeyExpr, Parameters};
 use crate::publication::*;
@@ -31,10 +38,16 @@ use crate::queryable::*;
 use crate::runtime::RuntimeBuilder;
 #[cfg(feature = "unstable")]
 use crate::sample::Attachment;
+#[cfg(feature = "unstable")]
+use crate::sample::Coherence;
 use crate::sample::DataInfo;
 use crate::sample::QoS;
 use crate::selector::TIME_RANGE_KEY;
 use crate::subscriber::*;
+#[cfg(feature = "unstable")]
+use crate::trace::TraceId;
+#[cfg(feature = "serde")]
+use crate::typed::{JsonCodec, TypedPublisherBuilder, TypedSubscriberBuilder};
 use crate::Id;
 use crate::Priority;
 use crate::Sample;
@@ -45,32 +58,40 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt;
+use std::future::Future;
 use std::ops::Deref;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::RwLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, trace, warn};
 use uhlc::HLC;
-use zenoh_buffers::ZBuf;
+use zenoh_buffers::{ZBuf, ZStr};
 use zenoh_collections::SingleOrVec;
 use zenoh_config::unwrap_or_default;
-use zenoh_core::{zconfigurable, zread, Resolve, ResolveClosure, ResolveFuture, SyncResolve};
+use zenoh_core::{
+    zconfigurable, zlock, zread, Resolvable, Resolve, ResolveClosure, ResolveFuture, SyncResolve,
+};
+#[zenoh_macros::unstable]
+use zenoh_protocol::core::EndPoint;
 use zenoh_protocol::network::AtomicRequestId;
 use zenoh_protocol::network::RequestId;
 use zenoh_protocol::{
     core::{
         key_expr::{keyexpr, OwnedKeyExpr},
-        AtomicExprId, CongestionControl, ExprId, WireExpr, ZenohId, EMPTY_EXPR_ID,
+        AtomicExprId, CongestionControl, ExprId, WhatAmI, WireExpr, ZenohId, EMPTY_EXPR_ID,
     },
     network::{
         declare::{
             self, common::ext::WireExprType, queryable::ext::QueryableInfo,
-            subscriber::ext::SubscriberInfo, Declare, DeclareBody, DeclareKeyExpr,
-            DeclareQueryable, DeclareSubscriber, UndeclareQueryable, UndeclareSubscriber,
+            subscriber::ext::SubscriberInfo, Declare, DeclareBody, DeclareInterest, DeclareKeyExpr,
+            DeclareQueryable, DeclareSubscriber, Interest, InterestId, UndeclareInterest,
+            UndeclareQueryable, UndeclareSubscriber,
         },
         ext,
-        request::{self, ext::TargetType, Request},
+        request::{self, ext::TargetType, Request, RequestCancel},
         Mapping, Push, Response, ResponseFinal,
     },
     zenoh::{
@@ -83,7 +104,9 @@ use zenoh_protocol::{
 };
 use zenoh_result::ZResult;
 use zenoh_task::TaskController;
+use zenoh_transport::TransportEventHandler;
 use zenoh_util::core::AsyncResolve;
+use zenoh_util::MemorySubsystem;
 
 zconfigurable! {
     pub(crate) static ref API_DATA_RECEPTION_CHANNEL_SIZE: usize = 256;
@@ -91,8 +114,11 @@ zconfigurable! {
     pub(crate) static ref API_REPLY_EMISSION_CHANNEL_SIZE: usize = 256;
     pub(crate) static ref API_REPLY_RECEPTION_CHANNEL_SIZE: usize = 256;
     pub(crate) static ref API_OPEN_SESSION_DELAY: u64 = 500;
+    pub(crate) static ref API_DROP_NOTIFICATION_DELAY: u64 = 100;
 }
 
+const DEFAULT_CLOSE_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub(crate) struct SessionState {
     pub(crate) primitives: Option<Arc<Face>>, // @TODO replace with MaybeUninit ??
     pub(crate) expr_id_counter: AtomicExprId, // @TODO: manage rollover and uniqueness
@@ -107,7 +133,20 @@ pub(crate) struct SessionState {
     pub(crate) tokens: HashMap<Id, Arc<LivelinessTokenState>>,
     #[cfg(feature = "unstable")]
     pub(crate) matching_listeners: HashMap<Id, Arc<MatchingListenerState>>,
+    #[cfg(feature = "unstable")]
+    pub(crate) drop_listeners: HashMap<Id, DropListenerState>,
+    #[cfg(feature = "unstable")]
+    pub(crate) interceptors: Vec<(Id, Arc<dyn SessionInterceptor>)>,
+    /// The bitmask of the IDs of the mandatory user-defined extensions accepted by the session.
+    #[cfg(feature = "unstable")]
+    pub(crate) accepted_extensions: u16,
     pub(crate) queries: HashMap<RequestId, QueryState>,
+    pub(crate) received_queries: ReceivedQueries,
+    pub(crate) transport_handler: Option<Arc<dyn TransportEventHandler>>,
+    pub(crate) publication_rates: HashMap<OwnedKeyExpr, PublicationRate>,
+    /// The interests declared for the key expressions of the publishers,
+    /// with the number of publishers on each of them.
+    pub(crate) publication_interests: HashMap<OwnedKeyExpr, (InterestId, usize)>,
     pub(crate) aggregated_subscribers: Vec<OwnedKeyExpr>,
     //pub(crate) aggregated_publishers: Vec<OwnedKeyExpr>,
 }
@@ -131,7 +170,17 @@ impl SessionState {
             tokens: HashMap::new(),
             #[cfg(feature = "unstable")]
             matching_listeners: HashMap::new(),
+            #[cfg(feature = "unstable")]
+            drop_listeners: HashMap::new(),
+            #[cfg(feature = "unstable")]
+            interceptors: Vec::new(),
+            #[cfg(feature = "unstable")]
+            accepted_extensions: 0,
             queries: HashMap::new(),
+            received_queries: ReceivedQueries::default(),
+            transport_handler: None,
+            publication_rates: HashMap::new(),
+            publication_interests: HashMap::new(),
             aggregated_subscribers,
             //aggregated_publishers,
         }
@@ -301,6 +350,8 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             reliability: Reliability::default(),
             mode: PushMode,
             origin: Locality::default(),
+            #[cfg(feature = "unstable")]
+            drop_notifications: false,
             handler: DefaultHandler,
         }
     }
@@ -333,7 +384,12 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             key_expr: key_expr.try_into().map_err(Into::into),
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
+            is_express: false,
             destination: Locality::default(),
+            max_rate: None,
+            sequence_numbers: false,
+            #[cfg(feature = "unstable")]
+            congestion_callback: None,
         }
     }
     #[zenoh_macros::unstable]
@@ -342,6 +398,12 @@ impl<'s, 'a> SessionDeclarations<'s, 'a> for SessionRef<'a> {
             session: self.clone(),
         }
     }
+    #[zenoh_macros::unstable]
+    fn admin(&'s self) -> Admin<'a> {
+        Admin {
+            session: self.clone(),
+        }
+    }
     fn info(&'s self) -> SessionInfo<'a> {
         SessionInfo {
             session: self.clone(),
@@ -420,10 +482,15 @@ impl Session {
                 task_controller: TaskController::default(),
             };
 
-            runtime.new_handler(Arc::new(admin::Handler::new(session.clone())));
+            let handler: Arc<dyn TransportEventHandler> =
+                Arc::new(admin::Handler::new(session.clone()));
+            runtime.new_handler(handler.clone());
 
             let primitives = Some(router.new_primitives(Arc::new(session.clone())));
-            zwrite!(state).primitives = primitives;
+            let mut guard = zwrite!(state);
+            guard.primitives = primitives;
+            guard.transport_handler = Some(handler);
+            drop(guard);
 
             admin::init(&session);
 
@@ -511,6 +578,9 @@ impl Session {
     /// Sessions are automatically closed when dropped, but you may want to use this function to handle errors or
     /// close the Session asynchronously.
     ///
+    /// Closing a session undeclares all its entities, then waits for the pending messages to be
+    /// written on the links before tearing them down, for at most the [`CloseBuilder::timeout`].
+    ///
     /// # Examples
     /// ```
     /// # #[tokio::main]
@@ -521,22 +591,61 @@ impl Session {
     /// session.close().res().await.unwrap();
     /// # }
     /// ```
-    pub fn close(mut self) -> impl Resolve<ZResult<()>> {
-        ResolveFuture::new(async move {
-            trace!("close()");
-            self.task_controller.terminate_all(Duration::from_secs(10));
-            if self.owns_runtime {
-                self.runtime.close().await?;
-            }
-            let mut state = zwrite!(self.state);
-            // clean up to break cyclic references from self.state to itself
-            let primitives = state.primitives.take();
-            state.queryables.clear();
-            drop(state);
-            primitives.as_ref().unwrap().send_close();
-            self.alive = false;
-            Ok(())
-        })
+    pub fn close(self) -> CloseBuilder {
+        CloseBuilder {
+            session: self,
+            timeout: DEFAULT_CLOSE_TIMEOUT,
+        }
+    }
+
+    async fn close_inner(mut self, timeout: Duration) -> ZResult<()> {
+        trace!("close()");
+        let deadline = tokio::time::Instant::now() + timeout;
+        self.undeclare_all();
+        if !self.runtime.manager().flush_unicast(timeout).await {
+            warn!(
+                "Pending messages could not be sent within {} ms before closing the session",
+                timeout.as_millis()
+            );
+        }
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        self.task_controller.terminate_all(remaining);
+        if self.owns_runtime {
+            self.runtime.close().await?;
+        }
+        let mut state = zwrite!(self.state);
+        // clean up to break cyclic references from self.state to itself
+        let primitives = state.primitives.take();
+        state.queryables.clear();
+        let handler = state.transport_handler.take();
+        drop(state);
+        // the runtime may be shared with other sessions and outlive this one
+        if let Some(handler) = handler {
+            self.runtime.remove_handler(&handler);
+        }
+        primitives.as_ref().unwrap().send_close();
+        self.alive = false;
+        Ok(())
+    }
+
+    /// Undeclare all the subscribers, queryables and liveliness tokens of this session.
+    fn undeclare_all(&self) {
+        let state = zread!(self.state);
+        let subscribers: Vec<Id> = state.subscribers.keys().copied().collect();
+        let queryables: Vec<Id> = state.queryables.keys().copied().collect();
+        #[cfg(feature = "unstable")]
+        let tokens: Vec<Id> = state.tokens.keys().copied().collect();
+        drop(state);
+        for id in subscribers {
+            let _ = self.unsubscribe(id);
+        }
+        for id in queryables {
+            let _ = self.close_queryable(id);
+        }
+        #[cfg(feature = "unstable")]
+        for id in tokens {
+            let _ = self.undeclare_liveliness(id);
+        }
     }
 
     pub fn undeclare<'a, T, O>(&'a self, decl: T) -> O
@@ -579,6 +688,120 @@ impl Session {
     pub fn config(&self) -> &Notifier<Config> {
         self.runtime.config()
     }
+
+    /// Add `endpoint` to the `listen` or `connect` endpoints of this zenoh [`Session`](Session),
+    /// without restarting it.
+    ///
+    /// Resolves to the status of the endpoint, or to nothing if it was already configured.
+    /// The same change can be made remotely by putting the endpoints on the
+    /// `@/<whatami>/<zid>/config/listen/endpoints` or `@/<whatami>/<zid>/config/connect/endpoints`
+    /// key of the admin space.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::runtime::EndpointKind;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// for status in session
+    ///     .add_endpoint(EndpointKind::Listen, "tcp/127.0.0.1:0".parse().unwrap())
+    ///     .res()
+    ///     .await
+    /// {
+    ///     println!("{}: {:?}", status.endpoint, status.result);
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn add_endpoint(
+        &self,
+        kind: EndpointKind,
+        endpoint: EndPoint,
+    ) -> impl Resolve<Vec<EndpointStatus>> {
+        let runtime = self.runtime.clone();
+        ResolveFuture::new(async move { runtime.add_endpoint(kind, endpoint).await })
+    }
+
+    /// Remove `endpoint` from the `listen` or `connect` endpoints of this zenoh [`Session`](Session),
+    /// without restarting it.
+    ///
+    /// Resolves to the status of the endpoint, or to nothing if it was not configured.
+    /// See [`Session::add_endpoint`].
+    #[zenoh_macros::unstable]
+    pub fn remove_endpoint(
+        &self,
+        kind: EndpointKind,
+        endpoint: EndPoint,
+    ) -> impl Resolve<Vec<EndpointStatus>> {
+        let runtime = self.runtime.clone();
+        ResolveFuture::new(async move { runtime.remove_endpoint(kind, &endpoint).await })
+    }
+
+    /// Declare a [`SessionInterceptor`](crate::interceptor::SessionInterceptor) observing,
+    /// modifying or dropping the publications sent and the samples and queries received
+    /// by this [`Session`](Session).
+    ///
+    /// The interceptor is active until the returned
+    /// [`Interceptor`](crate::interceptor::Interceptor) is undeclared or dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::interceptor::SessionInterceptor;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// struct NoPrivate;
+    /// impl SessionInterceptor for NoPrivate {
+    ///     fn egress_sample(&self, sample: Sample) -> Option<Sample> {
+    ///         (!sample.key_expr.starts_with("private/")).then_some(sample)
+    ///     }
+    /// }
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let interceptor = session.declare_interceptor(NoPrivate).res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn declare_interceptor<I>(&self, interceptor: I) -> InterceptorBuilder<'_>
+    where
+        I: SessionInterceptor + 'static,
+    {
+        InterceptorBuilder {
+            session: SessionRef::Borrow(self),
+            interceptor: Arc::new(interceptor),
+        }
+    }
+
+    /// Accept the mandatory user-defined [`Extension`](crate::extension::Extension) with the
+    /// given ID, failing if `id` is not in [`USER_IDS`](crate::extension::USER_IDS).
+    ///
+    /// The samples carrying a mandatory extension which is not accepted are dropped
+    /// by this [`Session`](Session).
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session.accept_extension(0x0c).unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn accept_extension(&self, id: u8) -> ZResult<()> {
+        if !crate::extension::USER_IDS.contains(&id) {
+            bail!(
+                "Invalid extension ID {:#x}: not a user-defined extension",
+                id
+            );
+        }
+        zwrite!(self.state).accepted_extensions |= 1 << id;
+        Ok(())
+    }
 }
 
 impl<'a> SessionDeclarations<'a, 'a> for Session {
@@ -619,6 +842,10 @@ impl<'a> SessionDeclarations<'a, 'a> for Session {
     fn liveliness(&'a self) -> Liveliness {
         SessionRef::Borrow(self).liveliness()
     }
+    #[zenoh_macros::unstable]
+    fn admin(&'a self) -> Admin {
+        SessionRef::Borrow(self).admin()
+    }
 }
 
 impl Session {
@@ -719,8 +946,17 @@ impl Session {
             publisher: self.declare_publisher(key_expr),
             value: value.into(),
             kind: SampleKind::Put,
+            timestamp: None,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            ttl: None,
+            #[cfg(feature = "unstable")]
+            latency_budget: None,
+            #[cfg(feature = "unstable")]
+            extensions: vec![],
+            #[cfg(feature = "unstable")]
+            trace_id: None,
         }
     }
 
@@ -753,8 +989,50 @@ impl Session {
             publisher: self.declare_publisher(key_expr),
             value: Value::empty(),
             kind: SampleKind::Delete,
+            timestamp: None,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            ttl: None,
+            #[cfg(feature = "unstable")]
+            latency_budget: None,
+            #[cfg(feature = "unstable")]
+            extensions: vec![],
+            #[cfg(feature = "unstable")]
+            trace_id: None,
+        }
+    }
+
+    /// Publish a coherent set of samples.
+    ///
+    /// The samples added to the returned [`CoherentSetBuilder`] are tagged as members of the same
+    /// set, allowing subscribers declared with
+    /// [`coherent_sets()`](crate::subscriber::SubscriberBuilder::coherent_sets) to deliver them
+    /// all at once, or not at all.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session
+    ///     .coherent_set()
+    ///     .put("key/a", "value")
+    ///     .delete("key/b")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn coherent_set(&self) -> CoherentSetBuilder<'_> {
+        CoherentSetBuilder {
+            session: self,
+            samples: Ok(vec![]),
+            congestion_control: CongestionControl::default(),
+            priority: Priority::default(),
         }
     }
     /// Query data from the matching queryables in the system.
@@ -797,12 +1075,18 @@ impl Session {
             selector,
             scope: Ok(None),
             target: QueryTarget::default(),
+            selection: None,
             consolidation: QueryConsolidation::default(),
             destination: Locality::default(),
             timeout,
             value: None,
+            consolidator: None,
+            cancellation: None,
+            on_final: None,
             #[cfg(feature = "unstable")]
             attachment: None,
+            #[cfg(feature = "unstable")]
+            trace_id: None,
             handler: DefaultHandler,
         }
     }
@@ -821,12 +1105,18 @@ impl Session {
     }
 
     #[allow(clippy::new_ret_no_self)]
-    pub(super) fn new(config: Config) -> impl Resolve<ZResult<Session>> {
+    pub(super) fn new(
+        config: Config,
+        #[cfg(feature = "unstable")] authorizer: Option<crate::authorization::Authorizer>,
+    ) -> impl Resolve<ZResult<Session>> {
         ResolveFuture::new(async move {
             tracing::debug!("Config: {:?}", &config);
             let aggregated_subscribers = config.aggregation().subscribers().clone();
             let aggregated_publishers = config.aggregation().publishers().clone();
-            let mut runtime = RuntimeBuilder::new(config).build().await?;
+            let builder = RuntimeBuilder::new(config);
+            #[cfg(feature = "unstable")]
+            let builder = builder.authorizer(authorizer);
+            let mut runtime = builder.build().await?;
 
             let mut session = Self::init(
                 runtime.clone(),
@@ -900,9 +1190,36 @@ impl Session {
     /// * `key_expr` - The key expression to publish
     pub(crate) fn declare_publication_intent<'a>(
         &'a self,
-        _key_expr: KeyExpr<'a>,
+        key_expr: KeyExpr<'a>,
     ) -> impl Resolve<Result<(), std::convert::Infallible>> + 'a {
         ResolveClosure::new(move || {
+            // Clients declaring interests only learn the subscribers matching their publishers
+            if self.runtime.whatami() == WhatAmI::Client
+                && unwrap_or_default!(self.runtime.config().lock().routing().interests().enabled())
+            {
+                let mut state = zwrite!(self.state);
+                let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst) as InterestId;
+                let interests = state
+                    .publication_interests
+                    .entry(key_expr.as_keyexpr().to_owned())
+                    .or_insert((id, 0));
+                interests.1 += 1;
+                if interests.1 == 1 {
+                    trace!("declare_interest({:?})", key_expr);
+                    let primitives = state.primitives.as_ref().unwrap().clone();
+                    drop(state);
+                    primitives.send_declare(Declare {
+                        ext_qos: declare::ext::QoSType::declare_default(),
+                        ext_tstamp: None,
+                        ext_nodeid: declare::ext::NodeIdType::default(),
+                        body: DeclareBody::DeclareInterest(DeclareInterest {
+                            id,
+                            wire_expr: key_expr.to_wire(self).to_owned(),
+                            interest: Interest::SUBSCRIBERS | Interest::CURRENT | Interest::FUTURE,
+                        }),
+                    });
+                }
+            }
             // tracing::trace!("declare_publication({:?})", key_expr);
             // let mut state = zwrite!(self.state);
             // if !state.publications.iter().any(|p| **p == **key_expr) {
@@ -936,9 +1253,31 @@ impl Session {
     /// * `key_expr` - The key expression of the publication to undeclarte
     pub(crate) fn undeclare_publication_intent<'a>(
         &'a self,
-        _key_expr: KeyExpr<'a>,
+        key_expr: KeyExpr<'a>,
     ) -> impl Resolve<ZResult<()>> + 'a {
         ResolveClosure::new(move || {
+            let mut state = zwrite!(self.state);
+            if let Some((id, count)) = state.publication_interests.get_mut(key_expr.as_keyexpr()) {
+                *count -= 1;
+                if *count == 0 {
+                    let id = *id;
+                    state.publication_interests.remove(key_expr.as_keyexpr());
+                    trace!("undeclare_interest({:?})", key_expr);
+                    let primitives = state.primitives.as_ref().unwrap().clone();
+                    drop(state);
+                    primitives.send_declare(Declare {
+                        ext_qos: declare::ext::QoSType::declare_default(),
+                        ext_tstamp: None,
+                        ext_nodeid: declare::ext::NodeIdType::default(),
+                        body: DeclareBody::UndeclareInterest(UndeclareInterest {
+                            id,
+                            ext_wire_expr: WireExprType {
+                                wire_expr: key_expr.to_wire(self).to_owned(),
+                            },
+                        }),
+                    });
+                }
+            }
             // let mut state = zwrite!(self.state);
             // if let Some(idx) = state.publications.iter().position(|p| **p == *key_expr) {
             //     trace!("undeclare_publication({:?})", key_expr);
@@ -970,6 +1309,97 @@ impl Session {
         })
     }
 
+    /// Returns the given publication on `key_expr` if it can be sent now without exceeding
+    /// `max_rate` publications per second, otherwise holds it back until it can.
+    pub(crate) fn throttle_publication(
+        &self,
+        key_expr: OwnedKeyExpr,
+        max_rate: f64,
+        publication: PendingPublication,
+    ) -> Option<PendingPublication> {
+        let period = Duration::from_secs_f64(1.0 / max_rate);
+        let now = Instant::now();
+        let mut state = zwrite!(self.state);
+        if !state.publication_rates.contains_key(&key_expr) {
+            // The idle entries hold nothing a new entry would not, keep them from piling up
+            // with the key expressions that are no longer published
+            state
+                .publication_rates
+                .retain(|_, rate| rate.pending.is_some() || rate.next > now);
+        }
+        let rate = state
+            .publication_rates
+            .entry(key_expr.clone())
+            .or_insert_with(|| PublicationRate {
+                next: now,
+                pending: None,
+            });
+        if now >= rate.next {
+            // A publication held back but not flushed yet is superseded by this one
+            rate.next = now + period;
+            rate.pending = None;
+            return Some(publication);
+        }
+        if rate.pending.replace(publication).is_none() {
+            let next = rate.next;
+            drop(state);
+            self.task_controller
+                .spawn_with_rt(zenoh_runtime::ZRuntime::Net, {
+                    let session = self.clone();
+                    async move {
+                        tokio::time::sleep_until(next.into()).await;
+                        session.flush_publication(&key_expr, period);
+                    }
+                });
+        }
+        None
+    }
+
+    fn flush_publication(&self, key_expr: &keyexpr, period: Duration) {
+        let mut state = zwrite!(self.state);
+        let now = Instant::now();
+        // A late task may find a publication held back after the one it was scheduled for,
+        // which is flushed by its own task
+        let Some(rate) = state
+            .publication_rates
+            .get_mut(key_expr)
+            .filter(|rate| now >= rate.next)
+        else {
+            return;
+        };
+        if let Some(publication) = rate.pending.take() {
+            rate.next = now + period;
+            drop(state);
+            publication(self);
+        }
+    }
+
+    /// Accounts the queue of a handler in the memory budget for as long as its callback lives.
+    /// Only the slots of the queue are accounted: the payloads of the queued items are not.
+    pub(crate) fn account_handler_queue<T: 'static>(
+        &self,
+        capacity: usize,
+        callback: Callback<'static, T>,
+    ) -> ZResult<Callback<'static, T>> {
+        if capacity == 0 {
+            return Ok(callback);
+        }
+        let memory = self
+            .runtime
+            .manager()
+            .config
+            .memory_budget
+            .try_reserve(
+                MemorySubsystem::HandlerQueues,
+                capacity.saturating_mul(std::mem::size_of::<T>()),
+            )
+            .ok_or_else(|| zerror!("Memory budget exceeded by handler queues"))?;
+        Ok(Arc::new(move |t| {
+            let _ = &memory;
+            callback(t)
+        }))
+    }
+
     pub(crate) fn declare_subscriber_inner(
         &self,
         key_expr: &KeyExpr,
@@ -1097,6 +1527,8 @@ impl Session {
         let mut state = zwrite!(self.state);
         if let Some(sub_state) = state.subscribers.remove(&sid) {
             trace!("unsubscribe({:?})", sub_state);
+            #[cfg(feature = "unstable")]
+            state.drop_listeners.remove(&sid);
             for res in state
                 .local_resources
                 .values_mut()
@@ -1424,6 +1856,89 @@ impl Session {
         }
     }
 
+    #[zenoh_macros::unstable]
+    pub(crate) fn declare_interceptor_inner(&self, interceptor: Arc<dyn SessionInterceptor>) -> Id {
+        let mut state = zwrite!(self.state);
+        let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst);
+        trace!("declare_interceptor({:?})", id);
+        state.interceptors.push((id, interceptor));
+        id
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn undeclare_interceptor(&self, id: Id) -> ZResult<()> {
+        let mut state = zwrite!(self.state);
+        match state.interceptors.iter().position(|(i, _)| *i == id) {
+            Some(idx) => {
+                trace!("undeclare_interceptor({:?})", id);
+                state.interceptors.remove(idx);
+                Ok(())
+            }
+            None => Err(zerror!("Unable to find interceptor").into()),
+        }
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn interceptors(&self) -> InterceptorsChain {
+        zread!(self.state)
+            .interceptors
+            .iter()
+            .map(|(_, i)| i.clone())
+            .collect()
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn declare_drop_listener(&self, sub_state: &SubscriberState) {
+        trace!("drop_listener({:?})", sub_state);
+        zwrite!(self.state).drop_listeners.insert(
+            sub_state.id,
+            DropListenerState {
+                key_expr: sub_state.key_expr.clone(),
+                callback: sub_state.callback.clone(),
+                pending: HashMap::new(),
+            },
+        );
+    }
+
+    #[zenoh_macros::unstable]
+    pub(crate) fn handle_drop(&self, key_expr: &keyexpr, reason: DropReason, count: usize) {
+        let mut state = zwrite!(self.state);
+        let mut schedule = false;
+        for listener in state.drop_listeners.values_mut() {
+            if listener.key_expr.intersects(key_expr) {
+                schedule |= listener.add(key_expr, reason, count);
+            }
+        }
+        drop(state);
+        if schedule {
+            self.task_controller
+                .spawn_with_rt(zenoh_runtime::ZRuntime::Net, {
+                    let session = self.clone();
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(*API_DROP_NOTIFICATION_DELAY))
+                            .await;
+                        session.flush_drop_notifications();
+                    }
+                });
+        }
+    }
+
+    #[zenoh_macros::unstable]
+    fn flush_drop_notifications(&self) {
+        let mut state = zwrite!(self.state);
+        let gaps = state
+            .drop_listeners
+            .values_mut()
+            .map(|listener| (listener.callback.clone(), listener.take_gaps()))
+            .collect::<Vec<_>>();
+        drop(state);
+        for (callback, samples) in gaps {
+            for sample in samples {
+                callback(sample);
+            }
+        }
+    }
+
     #[zenoh_macros::unstable]
     pub(crate) fn declare_matches_listener_inner(
         &self,
@@ -1597,6 +2112,22 @@ impl Session {
     ) {
         let mut callbacks = SingleOrVec::default();
         let state = zread!(self.state);
+        #[cfg(feature = "unstable")]
+        if let Some(info) = info
+            .as_ref()
+            .filter(|info| !crate::extension::accepted(&info.extensions, state.accepted_extensions))
+        {
+            tracing::debug!(
+                "Dropping Data for `{:?}` with unknown mandatory extensions {:?}",
+                key_expr,
+                info.extensions
+            );
+            return;
+        }
+        #[cfg(feature = "unstable")]
+        let intercept = !state.interceptors.is_empty();
+        #[cfg(feature = "unstable")]
+        let mut full_key_expr: Option<KeyExpr<'static>> = None;
         if key_expr.suffix.is_empty() {
             match state.get_res(&key_expr.scope, key_expr.mapping, local) {
                 Some(Resource::Node(res)) => {
@@ -1634,6 +2165,10 @@ impl Session {
                             };
                         }
                     }
+                    #[cfg(feature = "unstable")]
+                    if intercept {
+                        full_key_expr = Some(res.key_expr.clone().into());
+                    }
                 }
                 Some(Resource::Prefix { prefix }) => {
                     tracing::error!(
@@ -1684,6 +2219,10 @@ impl Session {
                             };
                         }
                     }
+                    #[cfg(feature = "unstable")]
+                    if intercept {
+                        full_key_expr = Some(key_expr.into_owned());
+                    }
                 }
                 Err(err) => {
                     tracing::error!("Received Data for unkown key_expr: {}", err);
@@ -1692,6 +2231,21 @@ impl Session {
             }
         };
         drop(state);
+        #[cfg(feature = "unstable")]
+        if let Some(key_expr) = full_key_expr.filter(|_| !callbacks.is_empty()) {
+            let mut sample = Sample::with_info(key_expr, payload, info);
+            sample.attachment = attachment;
+            let Some(sample) = crate::interceptor::intercept_ingress(&self.interceptors(), sample)
+            else {
+                return;
+            };
+            for (cb, key_expr) in callbacks {
+                let mut sample = sample.clone();
+                sample.key_expr = key_expr;
+                cb(sample);
+            }
+            return;
+        }
         let zenoh_collections::single_or_vec::IntoIter { drain, last } = callbacks.into_iter();
         for (cb, key_expr) in drain {
             #[allow(unused_mut)]
@@ -1728,6 +2282,7 @@ impl Session {
                 ext_target: request::ext::TargetType::default(),
                 ext_budget: None,
                 ext_timeout: None,
+                ext_selection: None,
                 payload: RequestBody::Pull(Pull {
                     ext_unknown: vec![],
                 }),
@@ -1742,12 +2297,16 @@ impl Session {
         selector: &Selector<'_>,
         scope: &Option<KeyExpr<'_>>,
         target: QueryTarget,
+        selection: Option<QueryableSelection>,
         consolidation: QueryConsolidation,
         destination: Locality,
         timeout: Duration,
         value: Option<Value>,
         #[cfg(feature = "unstable")] attachment: Option<Attachment>,
+        #[cfg(feature = "unstable")] trace_id: Option<TraceId>,
         callback: Callback<'static, Reply>,
+        on_final: Option<OnFinal>,
+        cancellation: Option<CancellationHandle>,
     ) -> ZResult<()> {
         tracing::trace!("get({}, {:?}, {:?})", selector, target, consolidation);
         let mut state = zwrite!(self.state);
@@ -1761,6 +2320,18 @@ impl Session {
             }
             Mode::Manual(mode) => mode,
         };
+        let memory = self
+            .runtime
+            .manager()
+            .config
+            .memory_budget
+            .try_reserve(
+                MemorySubsystem::PendingQueries,
+                std::mem::size_of::<QueryState>()
+                    + selector.key_expr.len()
+                    + selector.parameters().len(),
+            )
+            .ok_or_else(|| zerror!("Memory budget exceeded by pending queries"))?;
         let qid = state.qid_counter.fetch_add(1, Ordering::SeqCst);
         let nb_final = match destination {
             Locality::Any => 2,
@@ -1772,23 +2343,14 @@ impl Session {
             .spawn_with_rt(zenoh_runtime::ZRuntime::Net, {
                 let state = self.state.clone();
                 let zid = self.runtime.zid();
+                let cancellation = cancellation.map(|c| c.0).unwrap_or_default();
                 async move {
                     tokio::select! {
                         _ = tokio::time::sleep(timeout) => {
-                            let mut state = zwrite!(state);
-                            if let Some(query) = state.queries.remove(&qid) {
-                                std::mem::drop(state);
-                                tracing::debug!("Timeout on query {}! Send error and close.", qid);
-                                if query.reception_mode == ConsolidationMode::Latest {
-                                    for (_, reply) in query.replies.unwrap().into_iter() {
-                                        (query.callback)(reply);
-                                    }
-                                }
-                                (query.callback)(Reply {
-                                    sample: Err("Timeout".into()),
-                                    replier_id: zid,
-                                });
-                            }
+                            abort_query(&state, qid, zid, QueryStatus::Timeout);
+                        }
+                        _ = cancellation.cancelled() => {
+                            abort_query(&state, qid, zid, QueryStatus::Cancelled);
                         }
                         _ = token.cancelled() => {}
                     }
@@ -1814,6 +2376,8 @@ impl Session {
                 reception_mode: consolidation,
                 replies: (consolidation != ConsolidationMode::None).then(HashMap::new),
                 callback,
+                on_final,
+                memory,
             },
         );
 
@@ -1829,6 +2393,10 @@ impl Session {
                     ext_attachment = Some(attachment.into());
                 }
             }
+            #[cfg(feature = "unstable")]
+            let ext_trace = trace_id.map(Into::into);
+            #[cfg(not(feature = "unstable"))]
+            let ext_trace = None;
             primitives.send_request(Request {
                 id: qid,
                 wire_expr: wexpr.clone(),
@@ -1838,8 +2406,9 @@ impl Session {
                 ext_target: target,
                 ext_budget: None,
                 ext_timeout: Some(timeout),
+                ext_selection: selection,
                 payload: RequestBody::Query(zenoh_protocol::zenoh::Query {
-                    parameters: selector.parameters().to_string(),
+                    parameters: selector.parameters().into(),
                     ext_sinfo: None,
                     ext_consolidation: consolidation.into(),
                     ext_body: value.as_ref().map(|v| query::ext::QueryBodyType {
@@ -1849,6 +2418,7 @@ impl Session {
                         payload: v.payload.clone(),
                     }),
                     ext_attachment,
+                    ext_trace,
                     ext_unknown: vec![],
                 }),
             });
@@ -1857,7 +2427,7 @@ impl Session {
             self.handle_query(
                 true,
                 &wexpr,
-                selector.parameters(),
+                selector.parameters().into(),
                 qid,
                 target,
                 consolidation.into(),
@@ -1869,6 +2439,8 @@ impl Session {
                 }),
                 #[cfg(feature = "unstable")]
                 attachment,
+                #[cfg(feature = "unstable")]
+                trace_id,
             );
         }
         Ok(())
@@ -1879,14 +2451,15 @@ impl Session {
         &self,
         local: bool,
         key_expr: &WireExpr,
-        parameters: &str,
+        parameters: ZStr,
         qid: RequestId,
         _target: TargetType,
         _consolidation: ConsolidationType,
         body: Option<QueryBodyType>,
         #[cfg(feature = "unstable")] attachment: Option<Attachment>,
+        #[cfg(feature = "unstable")] trace_id: Option<TraceId>,
     ) {
-        let (primitives, key_expr, callbacks) = {
+        let (primitives, received_queries, key_expr, callbacks) = {
             let state = zread!(self.state);
             match state.wireexpr_to_keyexpr(key_expr, local) {
                 Ok(key_expr) => {
@@ -1915,6 +2488,7 @@ impl Session {
                         .collect::<Vec<Arc<dyn Fn(Query) + Send + Sync>>>();
                     (
                         state.primitives.as_ref().unwrap().clone(),
+                        state.received_queries.clone(),
                         key_expr.into_owned(),
                         callbacks,
                     )
@@ -1926,9 +2500,9 @@ impl Session {
             }
         };
 
-        let parameters = parameters.to_owned();
-
         let zid = self.runtime.zid(); // @TODO build/use prebuilt specific zid
+        let cancellation = CancellationToken::new();
+        zlock!(received_queries).insert((local, qid), cancellation.clone());
 
         let query = Query {
             inner: Arc::new(QueryInner {
@@ -1940,6 +2514,8 @@ impl Session {
                 }),
                 qid,
                 zid,
+                hlc: self.runtime.shared_hlc(),
+                drop_future_timestamp: self.runtime.drop_future_timestamp(),
                 primitives: if local {
                     Arc::new(self.clone())
                 } else {
@@ -1947,8 +2523,18 @@ impl Session {
                 },
                 #[cfg(feature = "unstable")]
                 attachment,
+                #[cfg(feature = "unstable")]
+                trace_id,
+                cancellation,
+                received_queries: Some((received_queries, local)),
             }),
         };
+        #[cfg(feature = "unstable")]
+        if !callbacks.is_empty()
+            && !crate::interceptor::intercept_query(&self.interceptors(), &query)
+        {
+            return;
+        }
         for callback in callbacks.iter() {
             callback(query.clone());
         }
@@ -1994,6 +2580,8 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             reliability: Reliability::default(),
             mode: PushMode,
             origin: Locality::default(),
+            #[cfg(feature = "unstable")]
+            drop_notifications: false,
             handler: DefaultHandler,
         }
     }
@@ -2076,7 +2664,12 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
             key_expr: key_expr.try_into().map_err(Into::into),
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
+            is_express: false,
             destination: Locality::default(),
+            max_rate: None,
+            sequence_numbers: false,
+            #[cfg(feature = "unstable")]
+            congestion_callback: None,
         }
     }
 
@@ -2104,6 +2697,13 @@ impl<'s> SessionDeclarations<'s, 'static> for Arc<Session> {
         }
     }
 
+    #[zenoh_macros::unstable]
+    fn admin(&'s self) -> Admin<'static> {
+        Admin {
+            session: SessionRef::Shared(self.clone()),
+        }
+    }
+
     fn info(&'s self) -> SessionInfo<'static> {
         SessionInfo {
             session: SessionRef::Shared(self.clone()),
@@ -2219,9 +2819,15 @@ impl Primitives for Session {
             }
             DeclareBody::DeclareToken(_) => todo!(),
             DeclareBody::UndeclareToken(_) => todo!(),
-            DeclareBody::DeclareInterest(_) => todo!(),
-            DeclareBody::FinalInterest(_) => todo!(),
-            DeclareBody::UndeclareInterest(_) => todo!(),
+            DeclareBody::DeclareInterest(m) => {
+                trace!("recv DeclareInterest {} {:?}", m.id, m.wire_expr);
+            }
+            DeclareBody::FinalInterest(m) => {
+                trace!("recv FinalInterest {}", m.id);
+            }
+            DeclareBody::UndeclareInterest(m) => {
+                trace!("recv UndeclareInterest {}", m.id);
+            }
         }
     }
 
@@ -2236,6 +2842,22 @@ impl Primitives for Session {
                     qos: QoS::from(msg.ext_qos),
                     source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
                     source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
+                    source_eid: m.ext_sinfo.as_ref().map(|i| i.eid),
+                    #[cfg(feature = "unstable")]
+                    coherence: m.ext_coherence.as_ref().map(|c| Coherence {
+                        zid: c.zid,
+                        set_id: c.set_id,
+                        index: c.index,
+                        last: c.last,
+                    }),
+                    #[cfg(feature = "unstable")]
+                    ttl: m.ext_ttl,
+                    #[cfg(feature = "unstable")]
+                    latency_budget: m.ext_latency_budget,
+                    #[cfg(feature = "unstable")]
+                    extensions: crate::extension::from_unknown(m.ext_unknown),
+                    #[cfg(feature = "unstable")]
+                    trace_id: m.ext_trace.map(Into::into),
                 };
                 self.handle_data(
                     false,
@@ -2247,19 +2869,39 @@ impl Primitives for Session {
                 )
             }
             PushBody::Del(m) => {
+                let (encoding, payload) = match m.ext_body {
+                    Some(body) => (Some(body.encoding), body.payload),
+                    None => (None, ZBuf::empty()),
+                };
                 let info = DataInfo {
                     kind: SampleKind::Delete,
-                    encoding: None,
+                    encoding,
                     timestamp: m.timestamp,
                     qos: QoS::from(msg.ext_qos),
                     source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
                     source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
+                    source_eid: m.ext_sinfo.as_ref().map(|i| i.eid),
+                    #[cfg(feature = "unstable")]
+                    coherence: m.ext_coherence.as_ref().map(|c| Coherence {
+                        zid: c.zid,
+                        set_id: c.set_id,
+                        index: c.index,
+                        last: c.last,
+                    }),
+                    #[cfg(feature = "unstable")]
+                    ttl: None,
+                    #[cfg(feature = "unstable")]
+                    latency_budget: None,
+                    #[cfg(feature = "unstable")]
+                    extensions: crate::extension::from_unknown(m.ext_unknown),
+                    #[cfg(feature = "unstable")]
+                    trace_id: m.ext_trace.map(Into::into),
                 };
                 self.handle_data(
                     false,
                     &msg.wire_expr,
                     Some(info),
-                    ZBuf::empty(),
+                    payload,
                     #[cfg(feature = "unstable")]
                     m.ext_attachment.map(Into::into),
                 )
@@ -2273,13 +2915,15 @@ impl Primitives for Session {
             RequestBody::Query(m) => self.handle_query(
                 false,
                 &msg.wire_expr,
-                &m.parameters,
+                m.parameters,
                 msg.id,
                 msg.ext_target,
                 m.ext_consolidation,
                 m.ext_body,
                 #[cfg(feature = "unstable")]
                 m.ext_attachment.map(Into::into),
+                #[cfg(feature = "unstable")]
+                m.ext_trace.map(Into::into),
             ),
             RequestBody::Put(_) => (),
             RequestBody::Del(_) => (),
@@ -2323,6 +2967,7 @@ impl Primitives for Session {
                         let new_reply = Reply {
                             replier_id,
                             sample: Err(value),
+                            code: e.code.into(),
                         };
                         callback(new_reply);
                     }
@@ -2385,12 +3030,27 @@ impl Primitives for Session {
                             None => key_expr,
                         };
                         let info = DataInfo {
-                            kind: SampleKind::Put,
+                            kind: if m.ext_delete.is_some() {
+                                SampleKind::Delete
+                            } else {
+                                SampleKind::Put
+                            },
                             encoding: Some(m.encoding),
                             timestamp: m.timestamp,
                             qos: QoS::from(msg.ext_qos),
                             source_id: m.ext_sinfo.as_ref().map(|i| i.zid),
                             source_sn: m.ext_sinfo.as_ref().map(|i| i.sn as u64),
+                            source_eid: m.ext_sinfo.as_ref().map(|i| i.eid),
+                            #[cfg(feature = "unstable")]
+                            coherence: None,
+                            #[cfg(feature = "unstable")]
+                            ttl: None,
+                            #[cfg(feature = "unstable")]
+                            latency_budget: None,
+                            #[cfg(feature = "unstable")]
+                            extensions: vec![],
+                            #[cfg(feature = "unstable")]
+                            trace_id: m.ext_trace.map(Into::into),
                         };
                         #[allow(unused_mut)]
                         let mut sample =
@@ -2402,6 +3062,7 @@ impl Primitives for Session {
                         let new_reply = Reply {
                             sample: Ok(sample),
                             replier_id: ZenohId::rand(), // TODO
+                            code: ErrorCode::Unspecified,
                         };
                         let callback =
                             match query.reception_mode {
@@ -2432,16 +3093,8 @@ impl Primitives for Session {
                                             }
                                         }
                                         None => {
-                                            query.replies.as_mut().unwrap().insert(
-                                                new_reply
-                                                    .sample
-                                                    .as_ref()
-                                                    .unwrap()
-                                                    .key_expr
-                                                    .clone()
-                                                    .into(),
-                                                new_reply.clone(),
-                                            );
+                                            // The reply is delivered even if it can't be kept
+                                            let _ = query.keep_new_reply(new_reply.clone());
                                             Some((query.callback.clone(), new_reply))
                                         }
                                     }
@@ -2465,21 +3118,14 @@ impl Primitives for Session {
                                                     new_reply,
                                                 );
                                             }
+                                            None
                                         }
-                                        None => {
-                                            query.replies.as_mut().unwrap().insert(
-                                                new_reply
-                                                    .sample
-                                                    .as_ref()
-                                                    .unwrap()
-                                                    .key_expr
-                                                    .clone()
-                                                    .into(),
-                                                new_reply,
-                                            );
-                                        }
-                                    };
-                                    None
+                                        // Deliver an error in place of a reply that can't be kept
+                                        None => query
+                                            .keep_new_reply(new_reply)
+                                            .err()
+                                            .map(|error| (query.callback.clone(), error)),
+                                    }
                                 }
                             };
                         std::mem::drop(state);
@@ -2509,6 +3155,9 @@ impl Primitives for Session {
                             (query.callback)(reply);
                         }
                     }
+                    if let Some(on_final) = query.on_final {
+                        on_final(QueryStatus::Complete);
+                    }
                     trace!("Close query {}", msg.rid);
                 }
             }
@@ -2518,11 +3167,111 @@ impl Primitives for Session {
         }
     }
 
+    fn send_request_cancel(&self, msg: RequestCancel) {
+        trace!("recv RequestCancel {:?}", msg);
+        let received_queries = zread!(self.state).received_queries.clone();
+        let cancellation = zlock!(received_queries).get(&(false, msg.rid)).cloned();
+        if let Some(cancellation) = cancellation {
+            cancellation.cancel();
+        }
+    }
+
     fn send_close(&self) {
         trace!("recv Close");
     }
 }
 
+/// Ends the pending query `qid` before all the queryables answered it: delivers the replies
+/// held back for consolidation and notifies the queryables that they can stop answering it.
+fn abort_query(state: &RwLock<SessionState>, qid: RequestId, zid: ZenohId, status: QueryStatus) {
+    let mut state = zwrite!(state);
+    if let Some(query) = state.queries.remove(&qid) {
+        let primitives = state.primitives.clone();
+        let received_queries = state.received_queries.clone();
+        std::mem::drop(state);
+        tracing::debug!("Query {} ended with {:?}! Cancel and close.", qid, status);
+        if let Some(primitives) = primitives {
+            primitives.send_request_cancel(RequestCancel {
+                rid: qid,
+                ext_qos: request::ext::QoSType::request_default(),
+                ext_tstamp: None,
+            });
+        }
+        let cancellation = zlock!(received_queries).get(&(true, qid)).cloned();
+        if let Some(cancellation) = cancellation {
+            cancellation.cancel();
+        }
+        if query.reception_mode == ConsolidationMode::Latest {
+            for (_, reply) in query.replies.unwrap().into_iter() {
+                (query.callback)(reply);
+            }
+        }
+        if status == QueryStatus::Timeout {
+            (query.callback)(Reply {
+                sample: Err("Timeout".into()),
+                replier_id: zid,
+                code: ErrorCode::Timeout,
+            });
+        }
+        if let Some(on_final) = query.on_final {
+            on_final(status);
+        }
+    }
+}
+
+/// A builder returned by [`Session::close()`](Session::close) that closes the session.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// session.put("key/expression", "value").res().await.unwrap();
+/// session
+///     .close()
+///     .timeout(Duration::from_secs(1))
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct CloseBuilder {
+    session: Session,
+    timeout: Duration,
+}
+
+impl CloseBuilder {
+    /// Set the maximum time to wait for the pending messages to be sent
+    /// before the session is torn down. Defaults to 10 seconds.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Resolvable for CloseBuilder {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for CloseBuilder {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        zenoh_runtime::ZRuntime::Application.block_in_place(self.session.close_inner(self.timeout))
+    }
+}
+
+impl AsyncResolve for CloseBuilder {
+    type Future = Pin<Box<dyn Future<Output = <Self as Resolvable>::To> + Send>>;
+
+    fn res_async(self) -> Self::Future {
+        Box::pin(self.session.close_inner(self.timeout))
+    }
+}
+
 impl Drop for Session {
     fn drop(&mut self) {
         if self.alive {
@@ -2661,6 +3410,88 @@ pub trait SessionDeclarations<'s, 'a> {
         TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
         <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>;
 
+    /// Create a [`TypedPublisher`](crate::typed::TypedPublisher) for the given key expression.
+    ///
+    /// The published values are serialized with a [`Codec`](crate::typed::Codec)
+    /// ([`JsonCodec`](crate::typed::JsonCodec) by default) that also sets their encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_expr` - The key expression matching resources to write
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let publisher = session.declare_publisher_typed::<Vec<u32>, _>("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// publisher.put(&vec![1, 2, 3]).res().await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    fn declare_publisher_typed<'b, T, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> TypedPublisherBuilder<'a, 'b, T>
+    where
+        T: serde::Serialize + ?Sized,
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        TypedPublisherBuilder {
+            builder: self.declare_publisher(key_expr),
+            codec: JsonCodec,
+            _type: std::marker::PhantomData,
+        }
+    }
+
+    /// Create a [`TypedSubscriber`](crate::typed::TypedSubscriber) for the given key expression.
+    ///
+    /// The received values are deserialized with a [`Codec`](crate::typed::Codec)
+    /// ([`JsonCodec`](crate::typed::JsonCodec) by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `key_expr` - The key expression to subscribe to
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let subscriber = session.declare_subscriber_typed::<Vec<u32>, _>("key/expression")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received: {:?}", sample.value);
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    fn declare_subscriber_typed<'b, T, TryIntoKeyExpr>(
+        &'s self,
+        key_expr: TryIntoKeyExpr,
+    ) -> TypedSubscriberBuilder<'a, 'b, T>
+    where
+        T: serde::de::DeserializeOwned,
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_result::Error>,
+    {
+        TypedSubscriberBuilder {
+            builder: self.declare_subscriber(key_expr),
+            codec: JsonCodec,
+            _type: std::marker::PhantomData,
+        }
+    }
+
     /// Obtain a [`Liveliness`] struct tied to this Zenoh [`Session`].
     ///
     /// # Examples
@@ -2680,6 +3511,21 @@ pub trait SessionDeclarations<'s, 'a> {
     /// ```
     #[zenoh_macros::unstable]
     fn liveliness(&'s self) -> Liveliness<'a>;
+    /// Obtain an [`Admin`] struct to query the admin space of the zenoh nodes
+    /// reachable from this [`Session`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let routers = session.admin().routers().res().await.unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    fn admin(&'s self) -> Admin<'a>;
     /// Get informations about the zenoh [`Session`](Session).
     ///
     /// # Examples
@@ -2721,6 +3567,24 @@ impl crate::net::primitives::EPrimitives for Session {
         (self as &dyn Primitives).send_response_final(ctx.msg)
     }
 
+    #[inline]
+    fn send_request_cancel(&self, ctx: crate::net::routing::RoutingContext<RequestCancel>) {
+        (self as &dyn Primitives).send_request_cancel(ctx.msg)
+    }
+
+    fn send_drop_notification(
+        &self,
+        key_expr: &keyexpr,
+        reason: DropReason,
+        count: usize,
+        _context: crate::net::routing::dispatcher::tables::NodeId,
+    ) {
+        #[cfg(feature = "unstable")]
+        self.handle_drop(key_expr, reason, count);
+        #[cfg(not(feature = "unstable"))]
+        let _ = (key_expr, reason, count);
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
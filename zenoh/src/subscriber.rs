@@ -15,13 +15,23 @@
 //! Subscribing primitives.
 use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::prelude::Locality;
+#[zenoh_macros::unstable]
+use crate::prelude::{keyexpr, Value, ZenohId};
 use crate::prelude::{Id, IntoCallbackReceiverPair, KeyExpr, Sample};
+#[zenoh_macros::unstable]
+use crate::sample::{DropReason, EntityGlobalId, SampleGap, SampleMissed};
 use crate::Undeclarable;
 use crate::{Result as ZResult, SessionRef};
+#[zenoh_macros::unstable]
+use std::collections::{hash_map::Entry, HashMap};
 use std::fmt;
 use std::future::Ready;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
+#[zenoh_macros::unstable]
+use std::sync::{Mutex, Weak};
+#[zenoh_macros::unstable]
+use std::time::{Duration, Instant};
 use zenoh_core::{AsyncResolve, Resolvable, Resolve, SyncResolve};
 use zenoh_protocol::network::declare::{subscriber::ext::SubscriberInfo, Mode};
 
@@ -48,6 +58,341 @@ impl fmt::Debug for SubscriberState {
     }
 }
 
+/// A [`Handler`](IntoCallbackReceiverPair) detecting the samples missed by a [`Subscriber`],
+/// returned by [`SubscriberBuilder::sample_miss_callback`].
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct SampleMissDetection<Handler, OnMiss> {
+    handler: Handler,
+    on_miss: OnMiss,
+}
+
+#[zenoh_macros::unstable]
+impl<Handler, OnMiss> IntoCallbackReceiverPair<'static, Sample>
+    for SampleMissDetection<Handler, OnMiss>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+    OnMiss: Fn(SampleMissed) + Send + Sync + 'static,
+{
+    type Receiver = Handler::Receiver;
+
+    fn queue_capacity(&self) -> usize {
+        self.handler.queue_capacity()
+    }
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let on_miss = self.on_miss;
+        let last_sns: Mutex<HashMap<EntityGlobalId, u32>> = Mutex::new(HashMap::new());
+        let callback = move |sample: Sample| {
+            if let (Some(source), Some(sn)) =
+                (sample.source_info.source(), sample.source_info.source_sn)
+            {
+                // Sequence numbers are 32 bits on the wire and wrap around.
+                let sn = sn as u32;
+                let missed = match zlock!(last_sns).entry(source) {
+                    Entry::Occupied(mut last) => {
+                        let delta = sn.wrapping_sub(*last.get());
+                        // Duplicated or out of order samples don't move the sequence forward.
+                        if delta != 0 && delta <= u32::MAX / 2 {
+                            last.insert(sn);
+                            delta - 1
+                        } else {
+                            0
+                        }
+                    }
+                    Entry::Vacant(last) => {
+                        last.insert(sn);
+                        0
+                    }
+                };
+                if missed > 0 {
+                    on_miss(SampleMissed {
+                        source,
+                        count: missed,
+                    });
+                }
+            }
+            callback(sample)
+        };
+        (Arc::new(callback), receiver)
+    }
+}
+
+/// The time after which the samples received of an incomplete coherent set are delivered
+/// by a subscriber declared with [`SubscriberBuilder::coherent_sets`].
+#[zenoh_macros::unstable]
+pub const DEFAULT_COHERENT_SET_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The number of samples of a coherent set above which the samples received of it are
+/// delivered by a subscriber declared with [`SubscriberBuilder::coherent_sets`].
+#[zenoh_macros::unstable]
+pub const DEFAULT_COHERENT_SET_MAX_SAMPLES: usize = 1024;
+
+/// A [`Handler`](IntoCallbackReceiverPair) delivering the samples of coherent sets all at once,
+/// returned by [`SubscriberBuilder::coherent_sets`].
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct CoherentSetDelivery<Handler> {
+    handler: Handler,
+    timeout: Duration,
+    max_samples: usize,
+}
+
+/// The coherent sets being received from each source by a subscriber.
+#[zenoh_macros::unstable]
+struct CoherentSets {
+    // The set being received from each source, with its id and the time its first sample
+    // was received.
+    pending: Mutex<HashMap<ZenohId, PendingSet>>,
+    callback: Callback<'static, Sample>,
+    timeout: Duration,
+    max_samples: usize,
+}
+
+#[zenoh_macros::unstable]
+struct PendingSet {
+    set_id: u32,
+    since: Instant,
+    samples: Vec<Sample>,
+}
+
+#[zenoh_macros::unstable]
+impl CoherentSets {
+    fn handle(&self, sample: Sample) {
+        let Some(coherence) = sample.coherence else {
+            return (self.callback)(sample);
+        };
+        let mut pending = zlock!(self.pending);
+        let mut complete = vec![];
+        let set = pending.entry(coherence.zid).or_insert_with(|| PendingSet {
+            set_id: coherence.set_id,
+            since: Instant::now(),
+            samples: vec![],
+        });
+        if set.set_id != coherence.set_id {
+            // The last sample of the previous set didn't match this subscriber,
+            // or was lost: deliver what was received of it.
+            complete.push(std::mem::take(&mut set.samples));
+            set.set_id = coherence.set_id;
+            set.since = Instant::now();
+        }
+        set.samples.push(sample);
+        if coherence.last || set.samples.len() >= self.max_samples {
+            complete.push(pending.remove(&coherence.zid).unwrap().samples);
+        }
+        // Deliver while holding the lock so that sets are never interleaved.
+        self.deliver(complete);
+    }
+
+    // Delivers what was received of the sets that are still incomplete after the timeout,
+    // e.g. because their last sample was lost or filtered out on the way.
+    fn expire(&self) {
+        let mut pending = zlock!(self.pending);
+        let now = Instant::now();
+        let mut expired = vec![];
+        pending.retain(|_, set| {
+            if now.duration_since(set.since) < self.timeout {
+                return true;
+            }
+            expired.push(std::mem::take(&mut set.samples));
+            false
+        });
+        self.deliver(expired);
+    }
+
+    fn deliver(&self, sets: Vec<Vec<Sample>>) {
+        for mut samples in sets {
+            samples.sort_by_key(|s| s.coherence.map(|c| c.index));
+            for sample in samples {
+                (self.callback)(sample);
+            }
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<Handler> IntoCallbackReceiverPair<'static, Sample> for CoherentSetDelivery<Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+{
+    type Receiver = Handler::Receiver;
+
+    fn queue_capacity(&self) -> usize {
+        self.handler.queue_capacity() + self.max_samples
+    }
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let sets = Arc::new(CoherentSets {
+            pending: Mutex::new(HashMap::new()),
+            callback,
+            timeout: self.timeout,
+            max_samples: self.max_samples.max(1),
+        });
+        // The expiration task stops once the subscriber is dropped
+        let weak: Weak<CoherentSets> = Arc::downgrade(&sets);
+        let period = (self.timeout / 2).max(Duration::from_millis(1));
+        zenoh_runtime::ZRuntime::Net.spawn(async move {
+            loop {
+                tokio::time::sleep(period).await;
+                match weak.upgrade() {
+                    Some(sets) => sets.expire(),
+                    None => break,
+                }
+            }
+        });
+        (Arc::new(move |sample| sets.handle(sample)), receiver)
+    }
+}
+
+/// A [`Handler`](IntoCallbackReceiverPair) discarding the expired samples,
+/// returned by [`SubscriberBuilder::ignore_expired`].
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct ExpiredSampleFilter<Handler> {
+    handler: Handler,
+}
+
+#[zenoh_macros::unstable]
+impl<Handler> IntoCallbackReceiverPair<'static, Sample> for ExpiredSampleFilter<Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+{
+    type Receiver = Handler::Receiver;
+
+    fn queue_capacity(&self) -> usize {
+        self.handler.queue_capacity()
+    }
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let callback = move |sample: Sample| {
+            if sample.is_expired() {
+                tracing::trace!("Ignoring expired sample on {}", sample.key_expr);
+            } else {
+                callback(sample)
+            }
+        };
+        (Arc::new(callback), receiver)
+    }
+}
+
+/// A [`Handler`](IntoCallbackReceiverPair) delivering the samples of each source in order,
+/// returned by [`SubscriberBuilder::ordered`].
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub struct OrderedDelivery<Handler> {
+    handler: Handler,
+    window: usize,
+}
+
+/// The samples received out of order from a source, and the sequence number
+/// of the next sample to deliver.
+#[zenoh_macros::unstable]
+struct ReorderBuffer {
+    next_sn: u32,
+    held: HashMap<u32, Sample>,
+}
+
+#[zenoh_macros::unstable]
+impl<Handler> IntoCallbackReceiverPair<'static, Sample> for OrderedDelivery<Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+{
+    type Receiver = Handler::Receiver;
+
+    fn queue_capacity(&self) -> usize {
+        self.handler.queue_capacity() + self.window
+    }
+
+    fn into_cb_receiver_pair(self) -> (Callback<'static, Sample>, Self::Receiver) {
+        let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let window = self.window;
+        let buffers: Mutex<HashMap<EntityGlobalId, ReorderBuffer>> = Mutex::new(HashMap::new());
+        let callback = move |sample: Sample| {
+            let (Some(source), Some(sn)) =
+                (sample.source_info.source(), sample.source_info.source_sn)
+            else {
+                return callback(sample);
+            };
+            // Sequence numbers are 32 bits on the wire and wrap around.
+            let sn = sn as u32;
+            let mut buffers = zlock!(buffers);
+            let buffer = buffers.entry(source).or_insert_with(|| ReorderBuffer {
+                next_sn: sn,
+                held: HashMap::new(),
+            });
+            let delta = sn.wrapping_sub(buffer.next_sn);
+            if delta > u32::MAX / 2 || buffer.held.contains_key(&sn) {
+                tracing::trace!("Ignoring duplicated or late sample on {}", sample.key_expr);
+                return;
+            }
+            buffer.held.insert(sn, sample);
+            if delta != 0 && buffer.held.len() <= window {
+                return;
+            }
+            if delta != 0 {
+                // The window is full: give up on the missing samples.
+                buffer.next_sn = buffer
+                    .held
+                    .keys()
+                    .min_by_key(|sn| sn.wrapping_sub(buffer.next_sn))
+                    .copied()
+                    .unwrap_or(sn);
+            }
+            // Deliver while holding the lock so that samples are never interleaved.
+            while let Some(sample) = buffer.held.remove(&buffer.next_sn) {
+                buffer.next_sn = buffer.next_sn.wrapping_add(1);
+                callback(sample);
+            }
+        };
+        (Arc::new(callback), receiver)
+    }
+}
+
+/// Accumulates the samples dropped by the infrastructure for a subscriber
+/// that enabled [`drop_notifications`](SubscriberBuilder::drop_notifications).
+#[zenoh_macros::unstable]
+pub(crate) struct DropListenerState {
+    pub(crate) key_expr: KeyExpr<'static>,
+    pub(crate) callback: Callback<'static, Sample>,
+    pub(crate) pending: HashMap<DropReason, (usize, KeyExpr<'static>)>,
+}
+
+#[zenoh_macros::unstable]
+impl DropListenerState {
+    /// Records `count` dropped samples. Returns `true` if nothing was pending before.
+    pub(crate) fn add(&mut self, key_expr: &keyexpr, reason: DropReason, count: usize) -> bool {
+        let was_empty = self.pending.is_empty();
+        match self.pending.get_mut(&reason) {
+            Some((c, ke)) => {
+                *c += count;
+                if ke.as_keyexpr() != key_expr {
+                    *ke = self.key_expr.clone();
+                }
+            }
+            None => {
+                self.pending
+                    .insert(reason, (count, KeyExpr::from(key_expr.to_owned())));
+            }
+        }
+        was_empty
+    }
+
+    /// Drains the pending drops into gap notification samples.
+    pub(crate) fn take_gaps(&mut self) -> Vec<Sample> {
+        self.pending
+            .drain()
+            .map(|(reason, (count, key_expr))| {
+                let mut sample = Sample::new(key_expr, Value::empty());
+                sample.gap = Some(SampleGap { count, reason });
+                sample
+            })
+            .collect()
+    }
+}
+
 /// A subscriber that provides data through a callback.
 ///
 /// CallbackSubscribers can be created from a zenoh [`Session`](crate::Session)
@@ -337,6 +682,9 @@ pub struct SubscriberBuilder<'a, 'b, Mode, Handler> {
     pub handler: Handler,
     #[cfg(not(feature = "unstable"))]
     pub(crate) handler: Handler,
+
+    #[cfg(feature = "unstable")]
+    pub drop_notifications: bool,
 }
 
 impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
@@ -368,6 +716,8 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
             reliability,
             mode,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler: _,
         } = self;
         SubscriberBuilder {
@@ -376,6 +726,8 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
             reliability,
             mode,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler: callback,
         }
     }
@@ -443,6 +795,8 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
             reliability,
             mode,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler: _,
         } = self;
         SubscriberBuilder {
@@ -451,6 +805,8 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
             reliability,
             mode,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler,
         }
     }
@@ -486,6 +842,261 @@ impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
         self
     }
 
+    /// Enable or disable drop notifications for this [`Subscriber`].
+    ///
+    /// When enabled, the subscriber receives synthetic samples with their
+    /// [`gap`](crate::sample::Sample::gap) set whenever samples matching its key expression
+    /// are dropped by the interceptors of a zenoh instance on their route (downsampling, access
+    /// control or latency budget rules), the remote instances notifying the drops to the next
+    /// hops of the route. Drops happening within a short period are reported in a single
+    /// notification per [`DropReason`](crate::sample::DropReason).
+    ///
+    /// Samples dropped by the congestion control of a link are not reported, as they are
+    /// dropped on the sending side of the link: see
+    /// [`sample_miss_callback`](SubscriberBuilder::sample_miss_callback) to detect those.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn drop_notifications(mut self, enabled: bool) -> Self {
+        self.drop_notifications = enabled;
+        self
+    }
+
+    /// Detect the samples missed by this [`Subscriber`], e.g. lost on a best effort route.
+    ///
+    /// Gaps in the sequence numbers of the samples received from each
+    /// [`Publisher`](crate::publication::Publisher) declared with
+    /// [`sequence_numbers`](crate::publication::PublisherBuilder::sequence_numbers)
+    /// are reported to the given callback as [`SampleMissed`] events, before the sample
+    /// that revealed them is delivered. Samples without [`source_info`](Sample::source_info),
+    /// e.g. those published with [`Session::put`](crate::Session::put), are not tracked.
+    ///
+    /// This must be called after the handler of the subscriber has been set.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .best_effort()
+    ///     .sample_miss_callback(|missed| {
+    ///         println!("Missed {} samples from {:?}", missed.count, missed.source)
+    ///     })
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn sample_miss_callback<OnMiss>(
+        self,
+        on_miss: OnMiss,
+    ) -> SubscriberBuilder<'a, 'b, Mode, SampleMissDetection<Handler, OnMiss>>
+    where
+        OnMiss: Fn(SampleMissed) + Send + Sync + 'static,
+    {
+        let SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler,
+        } = self;
+        SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler: SampleMissDetection { handler, on_miss },
+        }
+    }
+
+    /// Deliver the samples of coherent sets, published with
+    /// [`Session::coherent_set`](crate::Session::coherent_set), all at once and in order.
+    ///
+    /// The samples of a set are held back until its last sample is received.
+    /// A subscriber doesn't know which samples of a set match its key expression: if the last
+    /// sample of a set doesn't match, the set is delivered when a sample of the next set
+    /// from the same session is received. Samples that don't belong to a set are delivered
+    /// immediately.
+    ///
+    /// Sets are only reassembled by the subscribers: routers forward their samples one by one
+    /// like any other sample, and storages store them one by one as they are received, so that
+    /// a storage may hold, and reply with, part of a set. A set whose last sample is lost,
+    /// e.g. on a best effort route, or filtered out would then be held back forever: what
+    /// was received of a set is delivered anyway after [`DEFAULT_COHERENT_SET_TIMEOUT`], or
+    /// once [`DEFAULT_COHERENT_SET_MAX_SAMPLES`] samples of it were received
+    /// (see [`coherent_sets_bounded`](SubscriberBuilder::coherent_sets_bounded)).
+    ///
+    /// This must be called after the handler of the subscriber has been set.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("robot/pose/**")
+    ///     .coherent_sets()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn coherent_sets(self) -> SubscriberBuilder<'a, 'b, Mode, CoherentSetDelivery<Handler>> {
+        self.coherent_sets_bounded(
+            DEFAULT_COHERENT_SET_TIMEOUT,
+            DEFAULT_COHERENT_SET_MAX_SAMPLES,
+        )
+    }
+
+    /// Deliver the samples of coherent sets all at once and in order, as
+    /// [`coherent_sets`](SubscriberBuilder::coherent_sets) does, delivering what was received
+    /// of a set anyway once `timeout` elapsed since its first sample was received, or once
+    /// `max_samples` samples of it were received.
+    ///
+    /// This must be called after the handler of the subscriber has been set.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn coherent_sets_bounded(
+        self,
+        timeout: Duration,
+        max_samples: usize,
+    ) -> SubscriberBuilder<'a, 'b, Mode, CoherentSetDelivery<Handler>> {
+        let SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler,
+        } = self;
+        SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler: CoherentSetDelivery {
+                handler,
+                timeout,
+                max_samples,
+            },
+        }
+    }
+
+    /// Discard the samples received after their [`ttl`](Sample::ttl) elapsed.
+    ///
+    /// Expiration is computed from the [`timestamp`](Sample::timestamp) of the samples:
+    /// samples without timestamp are always delivered.
+    ///
+    /// This must be called after the handler of the subscriber has been set.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("presence/**")
+    ///     .ignore_expired()
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ignore_expired(self) -> SubscriberBuilder<'a, 'b, Mode, ExpiredSampleFilter<Handler>> {
+        let SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler,
+        } = self;
+        SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler: ExpiredSampleFilter { handler },
+        }
+    }
+
+    /// Deliver the samples of each [`Publisher`](crate::publication::Publisher) in the order
+    /// they were published, as given by their sequence numbers.
+    ///
+    /// Samples received out of order, e.g. over multiple links or after a reconnection, are
+    /// held back until the samples published before them are received, but at most `window`
+    /// samples are held back for each publisher: when more are, the missing samples are
+    /// considered lost. Duplicated samples and samples received after their successors
+    /// were delivered are discarded. Samples without [`source_info`](Sample::source_info),
+    /// e.g. those published with [`Session::put`](crate::Session::put), are delivered immediately.
+    ///
+    /// This must be called after the handler of the subscriber has been set.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("robot/commands/**")
+    ///     .ordered(16)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ordered(
+        self,
+        window: usize,
+    ) -> SubscriberBuilder<'a, 'b, Mode, OrderedDelivery<Handler>> {
+        let SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler,
+        } = self;
+        SubscriberBuilder {
+            session,
+            key_expr,
+            reliability,
+            mode,
+            origin,
+            drop_notifications,
+            handler: OrderedDelivery { handler, window },
+        }
+    }
+
     /// Change the subscription mode to Pull.
     #[inline]
     pub fn pull_mode(self) -> SubscriberBuilder<'a, 'b, PullMode, Handler> {
@@ -495,6 +1106,8 @@ impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
             reliability,
             mode: _,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler,
         } = self;
         SubscriberBuilder {
@@ -503,6 +1116,8 @@ impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
             reliability,
             mode: PullMode,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler,
         }
     }
@@ -516,6 +1131,8 @@ impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
             reliability,
             mode: _,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler,
         } = self;
         SubscriberBuilder {
@@ -524,6 +1141,8 @@ impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
             reliability,
             mode: PushMode,
             origin,
+            #[cfg(feature = "unstable")]
+            drop_notifications,
             handler,
         }
     }
@@ -546,7 +1165,9 @@ where
     fn res_sync(self) -> <Self as Resolvable>::To {
         let key_expr = self.key_expr?;
         let session = self.session;
+        let queue_capacity = self.handler.queue_capacity();
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let callback = session.account_handler_queue(queue_capacity, callback)?;
         session
             .declare_subscriber_inner(
                 &key_expr,
@@ -558,13 +1179,19 @@ where
                     mode: self.mode.into(),
                 },
             )
-            .map(|sub_state| Subscriber {
-                subscriber: SubscriberInner {
-                    session,
-                    state: sub_state,
-                    alive: true,
-                },
-                receiver,
+            .map(|sub_state| {
+                #[cfg(feature = "unstable")]
+                if self.drop_notifications {
+                    session.declare_drop_listener(&sub_state);
+                }
+                Subscriber {
+                    subscriber: SubscriberInner {
+                        session,
+                        state: sub_state,
+                        alive: true,
+                    },
+                    receiver,
+                }
             })
     }
 }
@@ -598,7 +1225,9 @@ where
     fn res_sync(self) -> <Self as Resolvable>::To {
         let key_expr = self.key_expr?;
         let session = self.session;
+        let queue_capacity = self.handler.queue_capacity();
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let callback = session.account_handler_queue(queue_capacity, callback)?;
         session
             .declare_subscriber_inner(
                 &key_expr,
@@ -610,15 +1239,21 @@ where
                     mode: self.mode.into(),
                 },
             )
-            .map(|sub_state| PullSubscriber {
-                subscriber: PullSubscriberInner {
-                    inner: SubscriberInner {
-                        session,
-                        state: sub_state,
-                        alive: true,
+            .map(|sub_state| {
+                #[cfg(feature = "unstable")]
+                if self.drop_notifications {
+                    session.declare_drop_listener(&sub_state);
+                }
+                PullSubscriber {
+                    subscriber: PullSubscriberInner {
+                        inner: SubscriberInner {
+                            session,
+                            state: sub_state,
+                            alive: true,
+                        },
                     },
-                },
-                receiver,
+                    receiver,
+                }
             })
     }
 }
@@ -821,3 +1456,63 @@ impl<Receiver> DerefMut for Subscriber<'_, Receiver> {
 
 /// A [`Subscriber`] that provides data through a `flume` channel.
 pub type FlumeSubscriber<'a> = Subscriber<'a, flume::Receiver<Sample>>;
+
+#[cfg(all(test, feature = "unstable"))]
+mod tests {
+    use super::*;
+    use crate::sample::SourceInfo;
+
+    fn sample(zid: ZenohId, sn: u64) -> Sample {
+        let mut sample = Sample::new(KeyExpr::try_from("test/ordered").unwrap(), sn.to_string());
+        sample.source_info = SourceInfo::new(zid, 0, sn);
+        sample
+    }
+
+    #[test]
+    fn ordered_delivery() {
+        let (callback, receiver) = OrderedDelivery {
+            handler: DefaultHandler,
+            window: 2,
+        }
+        .into_cb_receiver_pair();
+        let zid = ZenohId::rand();
+        let received = || {
+            receiver
+                .try_iter()
+                .map(|s| s.value.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Out of order samples are held back until the missing ones are received
+        callback(sample(zid, 0));
+        callback(sample(zid, 2));
+        callback(sample(zid, 3));
+        assert_eq!(received(), ["0"]);
+        callback(sample(zid, 1));
+        assert_eq!(received(), ["1", "2", "3"]);
+
+        // Duplicated and late samples are discarded
+        callback(sample(zid, 2));
+        callback(sample(zid, 4));
+        callback(sample(zid, 4));
+        assert_eq!(received(), ["4"]);
+
+        // Missing samples are given up on once the window is full
+        callback(sample(zid, 7));
+        callback(sample(zid, 6));
+        assert!(received().is_empty());
+        callback(sample(zid, 9));
+        assert_eq!(received(), ["6", "7"]);
+        callback(sample(zid, 5));
+        callback(sample(zid, 8));
+        assert_eq!(received(), ["8", "9"]);
+
+        // Other sources and samples without source info are not held back
+        callback(sample(ZenohId::rand(), 42));
+        callback(Sample::new(
+            KeyExpr::try_from("test/ordered").unwrap(),
+            "put",
+        ));
+        assert_eq!(received(), ["42", "put"]);
+    }
+}
@@ -0,0 +1,146 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Correlation of the messages of a same exchange across the hops of the infrastructure.
+//!
+//! A [`TraceId`] attached to a publication is delivered along with its samples, and a
+//! [`TraceId`] attached to a query is carried by its replies. The routers forward it untouched
+//! and log it at the `debug` level, which allows to follow a message from hop to hop.
+//!
+//! # Examples
+//! ```
+//! # #[tokio::main]
+//! # async fn main() {
+//! use zenoh::prelude::r#async::*;
+//! use zenoh::trace::TraceId;
+//!
+//! let session = zenoh::open(config::peer()).res().await.unwrap();
+//! let subscriber = session
+//!     .declare_subscriber("key/expression")
+//!     .callback(|sample| println!("Received {} with {:?}", sample.key_expr, sample.trace_id))
+//!     .res()
+//!     .await
+//!     .unwrap();
+//! session
+//!     .put("key/expression", "value")
+//!     .trace_id(TraceId::rand())
+//!     .res()
+//!     .await
+//!     .unwrap();
+//! # }
+//! ```
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use zenoh_protocol::zenoh::ext::TraceIdType;
+use zenoh_result::{bail, Error as ZError};
+
+/// The identifier correlating the messages of a same exchange, e.g. a
+/// [W3C trace context](https://www.w3.org/TR/trace-context/) trace-id.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct TraceId([u8; 16]);
+
+impl TraceId {
+    pub const fn new(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Generates a random trace ID.
+    pub fn rand() -> Self {
+        Self(rand::random())
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 16] {
+        self.0
+    }
+}
+
+impl From<[u8; 16]> for TraceId {
+    fn from(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl<const ID: u8> From<TraceIdType<{ ID }>> for TraceId {
+    fn from(ext: TraceIdType<{ ID }>) -> Self {
+        Self(ext.id)
+    }
+}
+
+impl<const ID: u8> From<TraceId> for TraceIdType<{ ID }> {
+    fn from(trace_id: TraceId) -> Self {
+        TraceIdType::new(trace_id.0)
+    }
+}
+
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TraceId({self})")
+    }
+}
+
+impl FromStr for TraceId {
+    type Err = ZError;
+
+    /// Parses the 32 hexadecimal digits of a trace ID.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 32 || !s.is_ascii() {
+            bail!("Invalid trace ID {}: expected 32 hexadecimal digits", s);
+        }
+        let mut bytes = [0u8; 16];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)
+                .map_err(|e| zenoh_result::zerror!("Invalid trace ID {}: {}", s, e))?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl From<TraceId> for String {
+    fn from(trace_id: TraceId) -> Self {
+        trace_id.to_string()
+    }
+}
+
+impl TryFrom<String> for TraceId {
+    type Error = ZError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[test]
+fn trace_id_string() {
+    let trace_id = TraceId::rand();
+    assert_eq!(trace_id.to_string().parse::<TraceId>().unwrap(), trace_id);
+    assert!("4bf92f3577b34da6a3ce929d0e0e4736"
+        .parse::<TraceId>()
+        .is_ok());
+    assert!("4bf92f3577b34da6a3ce929d0e0e473"
+        .parse::<TraceId>()
+        .is_err());
+    assert!("4bf92f3577b34da6a3ce929d0e0e47zz"
+        .parse::<TraceId>()
+        .is_err());
+}
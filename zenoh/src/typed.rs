@@ -0,0 +1,375 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Typed publishers and subscribers.
+//!
+//! Typed publishers and subscribers serialize and deserialize the published data
+//! with a [`Codec`] and automatically set the corresponding [`Encoding`].
+//!
+//! see [`declare_publisher_typed`](crate::SessionDeclarations::declare_publisher_typed)
+//! and [`declare_subscriber_typed`](crate::SessionDeclarations::declare_subscriber_typed)
+use crate::handlers::DefaultHandler;
+use crate::prelude::*;
+use crate::publication::{Publisher, PublisherBuilder};
+use crate::subscriber::{FlumeSubscriber, PushMode, SubscriberBuilder};
+use crate::Encoding;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Ready;
+use std::marker::PhantomData;
+use zenoh_core::{AsyncResolve, Resolvable, Resolve, SyncResolve};
+use zenoh_result::{zerror, ZResult};
+
+/// A serialization format used by typed publishers and subscribers.
+pub trait Codec: Clone + Send + Sync + 'static {
+    /// Serialize the given value into a [`Value`] with the appropriate [`Encoding`].
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> ZResult<Value>;
+
+    /// Deserialize a value from the given [`Value`].
+    fn decode<T: DeserializeOwned>(&self, value: &Value) -> ZResult<T>;
+}
+
+/// A [`Codec`] serializing data as JSON with the [`Encoding::APP_JSON`] encoding.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<T: Serialize + ?Sized>(&self, value: &T) -> ZResult<Value> {
+        let bytes = serde_json::to_vec(value).map_err(|e| zerror!("{}", e))?;
+        Ok(Value::from(bytes).encoding(Encoding::APP_JSON))
+    }
+
+    fn decode<T: DeserializeOwned>(&self, value: &Value) -> ZResult<T> {
+        match value.encoding.prefix() {
+            KnownEncoding::AppJson | KnownEncoding::TextJson => {
+                Ok(serde_json::from_slice(&value.payload.contiguous())
+                    .map_err(|e| zerror!("{}", e))?)
+            }
+            unexpected => Err(zerror!("{:?} can not be decoded as JSON", unexpected).into()),
+        }
+    }
+}
+
+/// A builder for initializing a [`TypedPublisher`].
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::publication::CongestionControl;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let publisher = session
+///     .declare_publisher_typed::<Vec<u32>, _>("key/expression")
+///     .congestion_control(CongestionControl::Block)
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+#[derive(Debug)]
+pub struct TypedPublisherBuilder<'a, 'b: 'a, T: ?Sized, C = JsonCodec> {
+    pub(crate) builder: PublisherBuilder<'a, 'b>,
+    pub(crate) codec: C,
+    pub(crate) _type: PhantomData<fn(&T)>,
+}
+
+impl<'a, 'b, T: ?Sized, C> TypedPublisherBuilder<'a, 'b, T, C> {
+    /// Change the `congestion_control` to apply when routing the data.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.builder = self.builder.congestion_control(congestion_control);
+        self
+    }
+
+    /// Change the priority of the written data.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.builder = self.builder.priority(priority);
+        self
+    }
+
+    /// Restrict the matching subscribers that will receive the published data
+    /// to the ones that have the given [`Locality`](crate::prelude::Locality).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn allowed_destination(mut self, destination: Locality) -> Self {
+        self.builder = self.builder.allowed_destination(destination);
+        self
+    }
+
+    /// Change the [`Codec`] used to serialize the published data.
+    #[inline]
+    pub fn codec<Codec2>(self, codec: Codec2) -> TypedPublisherBuilder<'a, 'b, T, Codec2> {
+        TypedPublisherBuilder {
+            builder: self.builder,
+            codec,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, T: ?Sized, C: Codec> Resolvable for TypedPublisherBuilder<'a, 'b, T, C> {
+    type To = ZResult<TypedPublisher<'a, T, C>>;
+}
+
+impl<'a, 'b, T: ?Sized, C: Codec> SyncResolve for TypedPublisherBuilder<'a, 'b, T, C> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        Ok(TypedPublisher {
+            publisher: self.builder.res_sync()?,
+            codec: self.codec,
+            _type: PhantomData,
+        })
+    }
+}
+
+impl<'a, 'b, T: ?Sized, C: Codec> AsyncResolve for TypedPublisherBuilder<'a, 'b, T, C> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A publisher that serializes the values of type `T` it publishes with a [`Codec`].
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let publisher = session
+///     .declare_publisher_typed::<Vec<u32>, _>("key/expression")
+///     .res()
+///     .await
+///     .unwrap();
+/// publisher.put(&vec![1, 2, 3]).res().await.unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TypedPublisher<'a, T: ?Sized, C = JsonCodec> {
+    publisher: Publisher<'a>,
+    codec: C,
+    _type: PhantomData<fn(&T)>,
+}
+
+impl<'a, T: Serialize + ?Sized, C: Codec> TypedPublisher<'a, T, C> {
+    /// Serialize and publish the given value.
+    pub fn put(&self, value: &T) -> impl Resolve<ZResult<()>> + '_ {
+        let value = self.codec.encode(value);
+        zenoh_core::ResolveClosure::new(move || self.publisher.put(value?).res_sync())
+    }
+
+    /// Publish a delete for the key expression of this publisher.
+    pub fn delete(&self) -> impl Resolve<ZResult<()>> + '_ {
+        self.publisher.delete()
+    }
+
+    /// Returns the underlying [`Publisher`].
+    pub fn publisher(&self) -> &Publisher<'a> {
+        &self.publisher
+    }
+
+    /// Returns the [`Codec`] used by this publisher.
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Undeclares the [`TypedPublisher`].
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
+        self.publisher.undeclare()
+    }
+}
+
+/// A builder for initializing a [`TypedSubscriber`].
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_subscriber_typed::<Vec<u32>, _>("key/expression")
+///     .reliable()
+///     .res()
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct TypedSubscriberBuilder<'a, 'b, T, C = JsonCodec> {
+    pub(crate) builder: SubscriberBuilder<'a, 'b, PushMode, DefaultHandler>,
+    pub(crate) codec: C,
+    pub(crate) _type: PhantomData<fn() -> T>,
+}
+
+impl<'a, 'b, T, C> TypedSubscriberBuilder<'a, 'b, T, C> {
+    /// Change the subscription reliability.
+    #[inline]
+    pub fn reliability(mut self, reliability: Reliability) -> Self {
+        self.builder = self.builder.reliability(reliability);
+        self
+    }
+
+    /// Change the subscription reliability to `Reliable`.
+    #[inline]
+    pub fn reliable(mut self) -> Self {
+        self.builder = self.builder.reliable();
+        self
+    }
+
+    /// Change the subscription reliability to `BestEffort`.
+    #[inline]
+    pub fn best_effort(mut self) -> Self {
+        self.builder = self.builder.best_effort();
+        self
+    }
+
+    /// Restrict the matching publications that will be receive by this [`TypedSubscriber`]
+    /// to the ones that have the given [`Locality`](crate::prelude::Locality).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn allowed_origin(mut self, origin: Locality) -> Self {
+        self.builder = self.builder.allowed_origin(origin);
+        self
+    }
+
+    /// Change the [`Codec`] used to deserialize the received data.
+    #[inline]
+    pub fn codec<Codec2>(self, codec: Codec2) -> TypedSubscriberBuilder<'a, 'b, T, Codec2> {
+        TypedSubscriberBuilder {
+            builder: self.builder,
+            codec,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, T, C: Codec> Resolvable for TypedSubscriberBuilder<'a, 'b, T, C> {
+    type To = ZResult<TypedSubscriber<'a, T, C>>;
+}
+
+impl<'a, 'b, T, C: Codec> SyncResolve for TypedSubscriberBuilder<'a, 'b, T, C> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        Ok(TypedSubscriber {
+            subscriber: self.builder.res_sync()?,
+            codec: self.codec,
+            _type: PhantomData,
+        })
+    }
+}
+
+impl<'a, 'b, T, C: Codec> AsyncResolve for TypedSubscriberBuilder<'a, 'b, T, C> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A deserialized value received by a [`TypedSubscriber`], along with its [`Sample`].
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct TypedSample<T> {
+    /// The deserialized value.
+    pub value: T,
+    /// The received sample.
+    pub sample: Sample,
+}
+
+/// A subscriber that deserializes the values of type `T` it receives with a [`Codec`].
+///
+/// Samples that can not be deserialized are reported as errors by the receiving functions.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let subscriber = session
+///     .declare_subscriber_typed::<Vec<u32>, _>("key/expression")
+///     .res()
+///     .await
+///     .unwrap();
+/// while let Ok(sample) = subscriber.recv_async().await {
+///     println!("Received: {} {:?}", sample.sample.key_expr, sample.value);
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TypedSubscriber<'a, T, C = JsonCodec> {
+    subscriber: FlumeSubscriber<'a>,
+    codec: C,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: DeserializeOwned, C: Codec> TypedSubscriber<'a, T, C> {
+    fn decode(&self, sample: Sample) -> ZResult<TypedSample<T>> {
+        let value = self.codec.decode(&sample.value).map_err(|e| {
+            zerror!(
+                "Unable to decode sample received on {}: {}",
+                sample.key_expr,
+                e
+            )
+        })?;
+        Ok(TypedSample { value, sample })
+    }
+
+    /// Wait for the next sample and deserialize it.
+    pub fn recv(&self) -> ZResult<TypedSample<T>> {
+        let sample = self.subscriber.recv().map_err(|e| zerror!("{}", e))?;
+        self.decode(sample)
+    }
+
+    /// Asynchronously wait for the next sample and deserialize it.
+    pub async fn recv_async(&self) -> ZResult<TypedSample<T>> {
+        let sample = self
+            .subscriber
+            .recv_async()
+            .await
+            .map_err(|e| zerror!("{}", e))?;
+        self.decode(sample)
+    }
+
+    /// Deserialize the next sample if one is immediately available.
+    pub fn try_recv(&self) -> ZResult<Option<TypedSample<T>>> {
+        match self.subscriber.try_recv() {
+            Ok(sample) => self.decode(sample).map(Some),
+            Err(flume::TryRecvError::Empty) => Ok(None),
+            Err(e) => Err(zerror!("{}", e).into()),
+        }
+    }
+
+    /// Returns the key expression of this subscriber.
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+
+    /// Returns the [`Codec`] used by this subscriber.
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Undeclares the [`TypedSubscriber`].
+    pub fn undeclare(self) -> impl Resolve<ZResult<()>> + 'a {
+        self.subscriber.undeclare()
+    }
+}
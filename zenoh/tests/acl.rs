@@ -35,6 +35,9 @@ mod test {
         test_get_qbl_allow().await;
         test_get_qbl_allow_then_deny().await;
         test_get_qbl_deny_then_allow().await;
+        test_pub_sub_zid_allow().await;
+        test_pub_sub_zid_deny_and_interface_allow(false).await;
+        test_pub_sub_zid_deny_and_interface_allow(true).await;
     }
     async fn get_basic_router_config() -> Config {
         let mut config = config::default();
@@ -58,6 +61,17 @@ mod test {
         (s01, s02)
     }
 
+    async fn get_client_sessions_with_zids(zid01: &str, zid02: &str) -> (Session, Session) {
+        println!("Opening client sessions with zids {zid01} and {zid02}");
+        let mut config = config::client(["tcp/127.0.0.1:7447".parse::<EndPoint>().unwrap()]);
+        config.set_id(zid01.parse().unwrap()).unwrap();
+        let s01 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+        let mut config = config::client(["tcp/127.0.0.1:7447".parse::<EndPoint>().unwrap()]);
+        config.set_id(zid02.parse().unwrap()).unwrap();
+        let s02 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+        (s01, s02)
+    }
+
     async fn close_sessions(s01: Session, s02: Session) {
         println!("Closing client sessions");
         ztimeout!(s01.close().res_async()).unwrap();
@@ -532,4 +546,145 @@ mod test {
         close_sessions(get_session, qbl_session).await;
         close_router_session(session).await;
     }
+
+    async fn test_pub_sub_zid_allow() {
+        println!("test_pub_sub_zid_allow");
+
+        let mut config_router = get_basic_router_config().await;
+        config_router
+            .insert_json5(
+                "access_control",
+                r#"
+        {"enabled": true,
+          "default_permission": "deny",
+          "rules":
+          [
+            {
+              "permission": "allow",
+              "flows": ["egress","ingress"],
+              "actions": [
+                "put",
+                "declare_subscriber"
+              ],
+              "key_exprs": [
+                "test/demo"
+              ],
+              "zids": [
+                "a1", "a2"
+              ]
+            },
+          ]
+    }
+    "#,
+            )
+            .unwrap();
+        println!("Opening router session");
+
+        let session = ztimeout!(zenoh::open(config_router).res_async()).unwrap();
+        let (sub_session, pub_session) = get_client_sessions_with_zids("a1", "a2").await;
+        {
+            let publisher = ztimeout!(pub_session.declare_publisher(KEY_EXPR).res_async()).unwrap();
+            let received_value = Arc::new(Mutex::new(String::new()));
+            let temp_recv_value = received_value.clone();
+            let subscriber = ztimeout!(sub_session
+                .declare_subscriber(KEY_EXPR)
+                .callback(move |sample| {
+                    let mut temp_value = zlock!(temp_recv_value);
+                    *temp_value = sample.value.to_string();
+                })
+                .res_async())
+            .unwrap();
+
+            tokio::time::sleep(SLEEP).await;
+
+            ztimeout!(publisher.put(VALUE).res_async()).unwrap();
+            tokio::time::sleep(SLEEP).await;
+
+            assert_eq!(*zlock!(received_value), VALUE);
+            ztimeout!(subscriber.undeclare().res_async()).unwrap();
+        }
+        close_sessions(sub_session, pub_session).await;
+        close_router_session(session).await;
+    }
+
+    async fn test_pub_sub_zid_deny_and_interface_allow(deny_overrides: bool) {
+        println!("test_pub_sub_zid_deny_and_interface_allow (deny_overrides: {deny_overrides})");
+
+        let mut config_router = get_basic_router_config().await;
+        config_router
+            .insert_json5(
+                "access_control",
+                r#"
+        {"enabled": true,
+          "default_permission": "deny",
+          "rules":
+          [
+            {
+              "permission": "allow",
+              "flows": ["egress","ingress"],
+              "actions": [
+                "put",
+                "declare_subscriber"
+              ],
+              "key_exprs": [
+                "test/demo"
+              ],
+              "interfaces": [
+                "lo","lo0"
+              ]
+            },
+            {
+              "permission": "deny",
+              "flows": ["ingress"],
+              "actions": [
+                "put"
+              ],
+              "key_exprs": [
+                "test/demo"
+              ],
+              "zids": [
+                "b2"
+              ]
+            },
+          ]
+    }
+    "#,
+            )
+            .unwrap();
+        config_router
+            .insert_json5("access_control/deny_overrides", &deny_overrides.to_string())
+            .unwrap();
+        println!("Opening router session");
+
+        let session = ztimeout!(zenoh::open(config_router).res_async()).unwrap();
+        let (sub_session, pub_session) = get_client_sessions_with_zids("b1", "b2").await;
+        {
+            let publisher = ztimeout!(pub_session.declare_publisher(KEY_EXPR).res_async()).unwrap();
+            let received_value = Arc::new(Mutex::new(String::new()));
+            let temp_recv_value = received_value.clone();
+            let subscriber = ztimeout!(sub_session
+                .declare_subscriber(KEY_EXPR)
+                .callback(move |sample| {
+                    let mut temp_value = zlock!(temp_recv_value);
+                    *temp_value = sample.value.to_string();
+                })
+                .res_async())
+            .unwrap();
+
+            tokio::time::sleep(SLEEP).await;
+
+            ztimeout!(publisher.put(VALUE).res_async()).unwrap();
+            tokio::time::sleep(SLEEP).await;
+
+            // The interface allows the put unless the zid deny overrides it
+            if deny_overrides {
+                assert_ne!(*zlock!(received_value), VALUE);
+            } else {
+                assert_eq!(*zlock!(received_value), VALUE);
+            }
+            ztimeout!(subscriber.undeclare().res_async()).unwrap();
+        }
+        close_sessions(sub_session, pub_session).await;
+        close_router_session(session).await;
+    }
 }
@@ -0,0 +1,291 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+
+const SLEEP: Duration = Duration::from_secs(1);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn adminspace_client() {
+    zenoh_util::try_init_log_from_env();
+
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:38462".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.adminspace.set_enabled(true).unwrap();
+    let router = zenoh::open(config).res().await.unwrap();
+
+    let mut config = config::client(["tcp/127.0.0.1:38462".parse::<EndPoint>().unwrap()]);
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let client = zenoh::open(config).res().await.unwrap();
+    let client_zid = client.zid();
+
+    let _sub = client
+        .declare_subscriber("test/adminspace/sub")
+        .res()
+        .await
+        .unwrap();
+    let _qabl = client
+        .declare_queryable("test/adminspace/qabl")
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let admin = client.admin();
+
+    let routers = admin.routers().res().await.unwrap();
+    assert_eq!(routers.len(), 1);
+    assert_eq!(routers[0].zid, router.zid());
+    assert_eq!(routers[0].whatami, WhatAmI::Router);
+    assert!(routers[0]
+        .locators
+        .iter()
+        .any(|l| l.as_str() == "tcp/127.0.0.1:38462"));
+    assert!(routers[0]
+        .sessions
+        .iter()
+        .any(|s| s.peer == client_zid && s.whatami == WhatAmI::Client));
+
+    let links = admin.links().res().await.unwrap();
+    assert!(links
+        .iter()
+        .any(|l| l.node == router.zid() && l.peer == client_zid));
+
+    let subs = admin.subscriptions().res().await.unwrap();
+    let sub = subs
+        .iter()
+        .find(|s| s.key_expr.as_str() == "test/adminspace/sub")
+        .unwrap();
+    assert_eq!(sub.node, router.zid());
+    assert!(sub.clients.contains(&client_zid));
+
+    let qabls = admin.queryables().res().await.unwrap();
+    assert!(qabls
+        .iter()
+        .any(|q| q.key_expr.as_str() == "test/adminspace/qabl" && q.clients.contains(&client_zid)));
+
+    // The client doesn't expose its admin space, and the router has no plugins
+    assert_eq!(admin.nodes().res().await.unwrap().len(), 1);
+    assert!(admin.plugins().res().await.unwrap().is_empty());
+
+    drop((_sub, _qabl));
+    client.close().res().await.unwrap();
+    router.close().res().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn adminspace_operators() {
+    use zenoh::adminspace::AdminOperator;
+    use zenoh::config::AdminSpaceOperatorConf;
+
+    zenoh_util::try_init_log_from_env();
+
+    let alice =
+        AdminOperator::from_pkcs8("alice", &AdminOperator::generate_pkcs8().unwrap()).unwrap();
+    let mallory =
+        AdminOperator::from_pkcs8("alice", &AdminOperator::generate_pkcs8().unwrap()).unwrap();
+
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:38463".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.adminspace.set_enabled(true).unwrap();
+    config.adminspace.permissions.write = true;
+    config.adminspace.permissions.operators = Some(vec![AdminSpaceOperatorConf {
+        id: alice.id().to_string(),
+        public_key: alice.public_key(),
+    }]);
+    let router = zenoh::open(config).res().await.unwrap();
+
+    let mut config = config::client(["tcp/127.0.0.1:38463".parse::<EndPoint>().unwrap()]);
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let client = zenoh::open(config).res().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let key_expr =
+        KeyExpr::try_from(format!("@/router/{}/config/scouting/delay", router.zid())).unwrap();
+    let delay = || *router.config().lock().scouting.delay();
+
+    // Unsigned writes are rejected
+    client.put(&key_expr, "500").res().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_ne!(delay(), Some(500));
+
+    // Writes signed by an unknown key are rejected
+    client
+        .put(&key_expr, "500")
+        .with_attachment(mallory.sign_put(&key_expr, b"500"))
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_ne!(delay(), Some(500));
+
+    // Writes signed by an operator are accepted
+    let signed = alice.sign_put(&key_expr, b"500");
+    client
+        .put(&key_expr, "500")
+        .with_attachment(signed.clone())
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(delay(), Some(500));
+
+    client
+        .put(&key_expr, "600")
+        .with_attachment(alice.sign_put(&key_expr, b"600"))
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(delay(), Some(600));
+
+    // Signed writes can't be replayed
+    client
+        .put(&key_expr, "500")
+        .with_attachment(signed)
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(delay(), Some(600));
+
+    client.close().res().await.unwrap();
+    router.close().res().await.unwrap();
+}
+
+#[cfg(feature = "plugins")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn adminspace_plugin_commands() {
+    use zenoh::adminspace::AdminOperator;
+    use zenoh::config::AdminSpaceOperatorConf;
+    use zenoh::plugins::{PluginsManager, RunningPlugin, RunningPluginTrait};
+    use zenoh::runtime::{Runtime, RuntimeBuilder};
+    use zenoh_plugin_trait::{Plugin, PluginControl, PluginState};
+
+    struct TestPlugin;
+
+    impl Plugin for TestPlugin {
+        type StartArgs = Runtime;
+        type Instance = RunningPlugin;
+        const DEFAULT_NAME: &'static str = "test";
+        const PLUGIN_VERSION: &'static str = "0.0.0";
+        const PLUGIN_LONG_VERSION: &'static str = "v0.0.0";
+
+        fn start(_name: &str, _runtime: &Runtime) -> zenoh::Result<RunningPlugin> {
+            Ok(Box::new(TestPlugin))
+        }
+    }
+
+    impl PluginControl for TestPlugin {}
+    impl RunningPluginTrait for TestPlugin {}
+
+    async fn plugin_state(session: &Session) -> PluginState {
+        session
+            .admin()
+            .plugins()
+            .res()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|p| p.status.id == "test")
+            .unwrap()
+            .status
+            .state
+    }
+
+    zenoh_util::try_init_log_from_env();
+
+    let alice =
+        AdminOperator::from_pkcs8("alice", &AdminOperator::generate_pkcs8().unwrap()).unwrap();
+
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:38482".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.adminspace.set_enabled(true).unwrap();
+    config.adminspace.permissions.write = true;
+    config.adminspace.permissions.operators = Some(vec![AdminSpaceOperatorConf {
+        id: alice.id().to_string(),
+        public_key: alice.public_key(),
+    }]);
+    let runtime = RuntimeBuilder::new(config)
+        .plugins_manager(
+            PluginsManager::static_plugins_only()
+                .declare_static_plugin::<TestPlugin, _>("test", false),
+        )
+        .build()
+        .await
+        .unwrap();
+    let router = zenoh::init(runtime).res().await.unwrap();
+
+    let mut config = config::client(["tcp/127.0.0.1:38482".parse::<EndPoint>().unwrap()]);
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let client = zenoh::open(config).res().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let key_expr =
+        KeyExpr::try_from(format!("@/router/{}/plugins/test/cmd", router.zid())).unwrap();
+    assert_eq!(plugin_state(&router).await, PluginState::Started);
+
+    // Unsigned commands are rejected
+    client.put(&key_expr, "stop").res().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(plugin_state(&router).await, PluginState::Started);
+
+    // Commands signed by an operator are run
+    client
+        .put(&key_expr, "stop")
+        .with_attachment(alice.sign_put(&key_expr, b"stop"))
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(plugin_state(&router).await, PluginState::Loaded);
+
+    client
+        .put(&key_expr, "start")
+        .with_attachment(alice.sign_put(&key_expr, b"start"))
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(plugin_state(&router).await, PluginState::Started);
+
+    client
+        .put(&key_expr, "restart")
+        .with_attachment(alice.sign_put(&key_expr, b"restart"))
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(plugin_state(&router).await, PluginState::Started);
+
+    // Commands not supported by the plugin leave it untouched
+    client
+        .put(&key_expr, "pause")
+        .with_attachment(alice.sign_put(&key_expr, b"pause"))
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(plugin_state(&router).await, PluginState::Started);
+
+    client.close().res().await.unwrap();
+    router.close().res().await.unwrap();
+}
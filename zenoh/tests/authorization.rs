@@ -0,0 +1,171 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::authorization::{Action, InterceptorFlow, Permission};
+use zenoh::prelude::r#async::*;
+use zenoh_core::{zlock, ztimeout};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn authorization_callback() {
+    zenoh_util::try_init_log_from_env();
+
+    let requests = Arc::new(Mutex::new(vec![]));
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:38467".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let router = ztimeout!(zenoh::open(config)
+        .authorization({
+            let requests = requests.clone();
+            move |request| {
+                let requests = requests.clone();
+                async move {
+                    let permission = if request.key_expr.starts_with("test/authorization/denied") {
+                        Permission::Deny
+                    } else {
+                        Permission::Allow
+                    };
+                    zlock!(requests).push(request);
+                    permission
+                }
+            }
+        })
+        .res_async())
+    .unwrap();
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let subscriber = ztimeout!(router
+        .declare_subscriber("test/authorization/**")
+        .callback({
+            let received = received.clone();
+            move |sample| zlock!(received).push(sample.key_expr.to_string())
+        })
+        .res_async())
+    .unwrap();
+
+    let client = ztimeout!(zenoh::open(config::client(["tcp/127.0.0.1:38467"
+        .parse::<EndPoint>()
+        .unwrap()]))
+    .res_async())
+    .unwrap();
+    let client_subscriber = ztimeout!(client
+        .declare_subscriber("test/authorization/client")
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(client.put("test/authorization/allowed", "1").res_async()).unwrap();
+    ztimeout!(client.put("test/authorization/allowed", "2").res_async()).unwrap();
+    ztimeout!(client.put("test/authorization/denied", "3").res_async()).unwrap();
+    ztimeout!(client.delete("test/authorization/denied").res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // Only the authorized publications are routed, deletions included
+    assert_eq!(
+        *zlock!(received),
+        vec![
+            "test/authorization/allowed".to_string(),
+            "test/authorization/allowed".to_string()
+        ]
+    );
+
+    let requests = zlock!(requests).clone();
+    let client_requests = requests
+        .iter()
+        .filter(|r| r.subject.zid == client.zid() && matches!(r.flow, InterceptorFlow::Ingress))
+        .map(|r| (r.action, r.key_expr.as_str()))
+        .collect::<Vec<_>>();
+    assert!(client_requests.contains(&(Action::DeclareSubscriber, "test/authorization/client")));
+    // The decisions are asked once per key expression and action
+    assert_eq!(
+        client_requests
+            .iter()
+            .filter(|r| **r == (Action::Put, "test/authorization/allowed"))
+            .count(),
+        1
+    );
+    assert_eq!(
+        client_requests
+            .iter()
+            .filter(|r| **r == (Action::Put, "test/authorization/denied"))
+            .count(),
+        1
+    );
+    assert!(requests
+        .iter()
+        .all(|r| r.subject.whatami == WhatAmI::Client));
+
+    ztimeout!(client_subscriber.undeclare().res_async()).unwrap();
+    ztimeout!(subscriber.undeclare().res_async()).unwrap();
+    ztimeout!(client.close().res_async()).unwrap();
+    ztimeout!(router.close().res_async()).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn authorization_callback_pending() {
+    zenoh_util::try_init_log_from_env();
+
+    let mut config = config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:38468".parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let router = ztimeout!(zenoh::open(config)
+        .authorization(|request| async move {
+            // A slow decision holds back its messages, a decision too slow denies them
+            if request.key_expr.starts_with("test/authorization/slow") {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            } else if request.key_expr.starts_with("test/authorization/timeout") {
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            }
+            Permission::Allow
+        })
+        .res_async())
+    .unwrap();
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let subscriber = ztimeout!(router
+        .declare_subscriber("test/authorization/**")
+        .callback({
+            let received = received.clone();
+            move |sample| zlock!(received).push(sample.value.to_string())
+        })
+        .res_async())
+    .unwrap();
+
+    let client = ztimeout!(zenoh::open(config::client(["tcp/127.0.0.1:38468"
+        .parse::<EndPoint>()
+        .unwrap()]))
+    .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(client.put("test/authorization/timeout", "0").res_async()).unwrap();
+    ztimeout!(client.put("test/authorization/slow", "1").res_async()).unwrap();
+    ztimeout!(client.put("test/authorization/slow", "2").res_async()).unwrap();
+    ztimeout!(client.put("test/authorization/fast", "3").res_async()).unwrap();
+    tokio::time::sleep(4 * SLEEP).await;
+
+    // The pending decisions don't block the routing of the other messages, the messages
+    // held back are routed in order once authorized and denied once timed out
+    assert_eq!(*zlock!(received), vec!["3", "1", "2"]);
+
+    ztimeout!(subscriber.undeclare().res_async()).unwrap();
+    ztimeout!(client.close().res_async()).unwrap();
+    ztimeout!(router.close().res_async()).unwrap();
+}
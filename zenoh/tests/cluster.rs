@@ -0,0 +1,121 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+const SLEEP: Duration = Duration::from_millis(500);
+const ALIGNMENT: Duration = Duration::from_secs(3);
+
+fn open_router(zid: &str, listen: &str, connect: Option<&str>) -> Session {
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.set_id(zid.parse().unwrap()).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![listen.parse().unwrap()];
+    if let Some(connect) = connect {
+        config.connect.endpoints = vec![connect.parse().unwrap()];
+    }
+    config
+        .insert_json5(
+            "routing/router/cluster",
+            r#"{enabled: true, members: ["a1", "a2"], retained: ["test/cluster/retained/**"]}"#,
+        )
+        .unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+fn open_client(locator: &str) -> Session {
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Client)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.connect.endpoints = vec![locator.parse().unwrap()];
+    zenoh::open(config).res().unwrap()
+}
+
+fn get_retained(session: &Session) -> Vec<(String, String)> {
+    let replies = session
+        .get("test/cluster/retained/**")
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap();
+    let mut values = vec![];
+    while let Ok(reply) = replies.recv() {
+        let sample = reply.sample.unwrap();
+        values.push((
+            sample.key_expr.to_string(),
+            String::try_from(&sample.value).unwrap(),
+        ));
+    }
+    values.sort();
+    values
+}
+
+#[test]
+fn cluster_two_routers() {
+    zenoh_util::try_init_log_from_env();
+
+    let locator_a = "tcp/127.0.0.1:38480";
+    let locator_b = "tcp/127.0.0.1:38481";
+
+    // A publication is retained by the only member of the cluster, but not served below the quorum...
+    let router_a = open_router("a1", locator_a, None);
+    let client_a = open_client(locator_a);
+    client_a.put("test/cluster/retained/a", "1").res().unwrap();
+    std::thread::sleep(SLEEP);
+    assert!(get_retained(&client_a).is_empty());
+
+    // ...and retrieved by the member joining the cluster afterwards.
+    let router_b = open_router("a2", locator_b, Some(locator_a));
+    std::thread::sleep(ALIGNMENT);
+    let client_b = open_client(locator_b);
+    std::thread::sleep(SLEEP);
+    assert_eq!(
+        get_retained(&client_a),
+        vec![("test/cluster/retained/a".to_string(), "1".to_string())]
+    );
+    assert_eq!(
+        get_retained(&client_b),
+        vec![("test/cluster/retained/a".to_string(), "1".to_string())]
+    );
+
+    // The declarations are shared by the members.
+    let subscriber = client_b
+        .declare_subscriber("test/cluster/data")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+    client_a.put("test/cluster/data", "data").res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(String::try_from(&sample.value).unwrap(), "data");
+
+    // The publications and deletions are retained by all the members.
+    client_a.put("test/cluster/retained/b", "2").res().unwrap();
+    client_a.delete("test/cluster/retained/a").res().unwrap();
+    std::thread::sleep(SLEEP);
+    assert_eq!(
+        get_retained(&client_b),
+        vec![("test/cluster/retained/b".to_string(), "2".to_string())]
+    );
+
+    // The member left alone reports the split of the cluster, and stops serving its publications.
+    drop(router_a);
+    std::thread::sleep(ALIGNMENT);
+    let replies = router_b.get("@/router/a2/cluster").res().unwrap();
+    let reply = replies.recv_timeout(TIMEOUT).unwrap();
+    let info: serde_json::Value =
+        serde_json::from_str(&String::try_from(&reply.sample.unwrap().value).unwrap()).unwrap();
+    assert_eq!(info["status"], "split_brain");
+    assert!(get_retained(&client_b).is_empty());
+}
@@ -0,0 +1,148 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+mod common;
+
+use common::{open_sessions, SLEEP, TIMEOUT};
+use zenoh::prelude::sync::*;
+
+#[test]
+fn coherent_set_delivery() {
+    zenoh_util::try_init_log_from_env();
+
+    let (pub_session, sub_session) = open_sessions("tcp/127.0.0.1:38450");
+    let subscriber = sub_session
+        .declare_subscriber("test/coherence/sub/**")
+        .coherent_sets()
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    // A complete set is delivered in order, with its coherence information.
+    pub_session
+        .coherent_set()
+        .put("test/coherence/sub/a", "a")
+        .put("test/coherence/sub/b", "b")
+        .delete("test/coherence/sub/c")
+        .res()
+        .unwrap();
+    let samples: Vec<Sample> = (0..3)
+        .map(|_| subscriber.recv_timeout(TIMEOUT).unwrap())
+        .collect();
+    let set_id = samples[0].coherence.unwrap().set_id;
+    for (index, sample) in samples.iter().enumerate() {
+        let coherence = sample.coherence.unwrap();
+        assert_eq!(coherence.zid, pub_session.zid());
+        assert_eq!(coherence.set_id, set_id);
+        assert_eq!(coherence.index, index as u32);
+        assert_eq!(coherence.last, index == 2);
+    }
+    assert_eq!(samples[0].key_expr.as_str(), "test/coherence/sub/a");
+    assert_eq!(samples[2].kind, SampleKind::Delete);
+
+    // Samples outside of a set are delivered immediately.
+    pub_session.put("test/coherence/sub/d", "d").res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert!(sample.coherence.is_none());
+
+    // A set whose last sample doesn't match is held back until the next set starts.
+    pub_session
+        .coherent_set()
+        .put("test/coherence/sub/a", "a")
+        .put("test/coherence/sub/b", "b")
+        .put("test/coherence/other", "other")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+    assert!(subscriber.try_recv().is_err());
+
+    pub_session
+        .coherent_set()
+        .put("test/coherence/sub/e", "e")
+        .res()
+        .unwrap();
+    let keys: Vec<String> = (0..3)
+        .map(|_| {
+            subscriber
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .key_expr
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            "test/coherence/sub/a",
+            "test/coherence/sub/b",
+            "test/coherence/sub/e"
+        ]
+    );
+
+    // An empty set sends nothing.
+    pub_session.coherent_set().res().unwrap();
+    std::thread::sleep(SLEEP);
+    assert!(subscriber.try_recv().is_err());
+}
+
+#[test]
+fn coherent_set_bounds() {
+    zenoh_util::try_init_log_from_env();
+
+    let (pub_session, sub_session) = open_sessions("tcp/127.0.0.1:38474");
+    let subscriber = sub_session
+        .declare_subscriber("test/coherence/bounds/**")
+        .coherent_sets_bounded(SLEEP, 3)
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    // A set whose last sample doesn't match is delivered once the timeout elapsed,
+    // even if no other set is published.
+    pub_session
+        .coherent_set()
+        .put("test/coherence/bounds/a", "a")
+        .put("test/coherence/other", "other")
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(SLEEP + TIMEOUT).unwrap();
+    assert_eq!(sample.key_expr.as_str(), "test/coherence/bounds/a");
+    assert!(!sample.coherence.unwrap().last);
+
+    // A set is delivered in parts once it exceeds the maximum number of samples.
+    let mut set = pub_session.coherent_set();
+    for i in 0..4 {
+        set = set.put(format!("test/coherence/bounds/{i}"), i.to_string());
+    }
+    set.put("test/coherence/other", "other").res().unwrap();
+    let keys: Vec<String> = (0..3)
+        .map(|_| {
+            subscriber
+                .recv_timeout(TIMEOUT)
+                .unwrap()
+                .key_expr
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            "test/coherence/bounds/0",
+            "test/coherence/bounds/1",
+            "test/coherence/bounds/2"
+        ]
+    );
+    let sample = subscriber.recv_timeout(SLEEP + TIMEOUT).unwrap();
+    assert_eq!(sample.key_expr.as_str(), "test/coherence/bounds/3");
+}
@@ -0,0 +1,50 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+pub const TIMEOUT: Duration = Duration::from_secs(1);
+pub const SLEEP: Duration = Duration::from_millis(500);
+
+/// Opens a publisher session connected to a subscriber session listening on `locator`.
+pub fn open_sessions(locator: &str) -> (Session, Session) {
+    open_sessions_with(locator, |_| ())
+}
+
+/// Same as [`open_sessions`], with the configuration of the publisher session amended by `f`.
+pub fn open_sessions_with(locator: &str, f: impl FnOnce(&mut Config)) -> (Session, Session) {
+    let mut pub_config = Config::default();
+    pub_config
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    pub_config.connect.endpoints = vec![locator.parse().unwrap()];
+    f(&mut pub_config);
+
+    let mut sub_config = Config::default();
+    sub_config
+        .scouting
+        .multicast
+        .set_enabled(Some(false))
+        .unwrap();
+    sub_config.listen.endpoints = vec![locator.parse().unwrap()];
+
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    (pub_session, sub_session)
+}
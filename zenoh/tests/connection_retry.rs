@@ -53,6 +53,8 @@ fn retry_config_overriding() {
             period_init_ms: 3000,
             period_max_ms: 6000,
             period_increase_factor: 1.5,
+            period_jitter: 0.,
+            max_attempts: -1,
             exit_on_failure: false,
         },
         // override one key
@@ -60,6 +62,8 @@ fn retry_config_overriding() {
             period_init_ms: 30000,
             period_max_ms: 6000,
             period_increase_factor: 1.5,
+            period_jitter: 0.,
+            max_attempts: -1,
             exit_on_failure: false,
         },
         // override all keys
@@ -67,6 +71,8 @@ fn retry_config_overriding() {
             period_init_ms: 30000,
             period_max_ms: 60000,
             period_increase_factor: 15.,
+            period_jitter: 0.,
+            max_attempts: -1,
             exit_on_failure: true,
         },
     ];
@@ -156,6 +162,78 @@ fn retry_config_infinit_period() {
     assert_eq!(period.next_duration(), std::time::Duration::MAX);
 }
 
+#[test]
+fn retry_config_jitter() {
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "listen/retry",
+            r#"
+            {
+                period_init_ms: 1000,
+                period_max_ms: 4000,
+                period_increase_factor: 2,
+                period_jitter: 0.5,
+            }
+            "#,
+        )
+        .unwrap();
+
+    let endpoint: EndPoint = "tcp/[::]:0".parse().unwrap();
+    let retry_config = zenoh_config::get_retry_config(&config, Some(&endpoint), true);
+
+    let mut period = retry_config.period();
+    let expected = vec![1000, 2000, 4000, 4000, 4000];
+
+    for v in expected {
+        let d = period.next_duration();
+        assert!(d >= std::time::Duration::from_millis(v / 2));
+        assert!(d <= std::time::Duration::from_millis(v + v / 2));
+    }
+}
+
+#[test]
+fn retry_config_max_attempts() {
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "listen/retry",
+            r#"
+            {
+                period_init_ms: 1000,
+                max_attempts: 5,
+            }
+            "#,
+        )
+        .unwrap();
+
+    let endpoint: EndPoint = "tcp/[::]:0#retry_max_attempts=3".parse().unwrap();
+    let retry_config = zenoh_config::get_retry_config(&config, Some(&endpoint), true);
+    assert_eq!(retry_config.max_attempts, 3);
+
+    let mut period = retry_config.period();
+    assert!(!period.is_exhausted());
+    period.next_duration();
+    assert!(!period.is_exhausted());
+    period.next_duration();
+    assert!(period.is_exhausted());
+}
+
+#[test]
+#[should_panic(expected = "Unable to add listener")]
+fn listen_max_attempts() {
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "listen/endpoints",
+            r#"["tcp/8.8.8.8:8#retry_period_init_ms=100;retry_max_attempts=3"]"#,
+        )
+        .unwrap();
+
+    config.insert_json5("listen/timeout_ms", "-1").unwrap();
+    zenoh::open(config).res().unwrap();
+}
+
 #[test]
 #[should_panic(expected = "Can not create a new TCP listener")]
 fn listen_no_retry() {
@@ -0,0 +1,90 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::net::TcpStream;
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+use zenoh::runtime::EndpointKind;
+
+const SLEEP: Duration = Duration::from_secs(1);
+
+fn open(listen: &[&str]) -> Session {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = listen.iter().map(|e| e.parse().unwrap()).collect();
+    zenoh::open(config).res().unwrap()
+}
+
+#[test]
+fn endpoints_add_remove() {
+    zenoh_util::try_init_log_from_env();
+
+    let initial = "tcp/127.0.0.1:38459";
+    let added = "tcp/127.0.0.1:38460";
+    let session1 = open(&[initial]);
+    let session2 = open(&["tcp/127.0.0.1:38461"]);
+
+    // Listen on a new endpoint
+    let statuses = session1
+        .add_endpoint(EndpointKind::Listen, added.parse().unwrap())
+        .res();
+    assert_eq!(statuses.len(), 1);
+    assert!(statuses[0].added);
+    assert!(statuses[0].result.is_ok());
+    assert!(session1
+        .add_endpoint(EndpointKind::Listen, added.parse().unwrap())
+        .res()
+        .is_empty());
+    assert!(TcpStream::connect("127.0.0.1:38460").is_ok());
+
+    // A failing endpoint is reported and the others are kept
+    let statuses = session2
+        .add_endpoint(EndpointKind::Listen, initial.parse().unwrap())
+        .res();
+    assert_eq!(statuses.len(), 1);
+    assert!(statuses[0].result.is_err());
+
+    // Connect to it
+    let statuses = session2
+        .add_endpoint(EndpointKind::Connect, added.parse().unwrap())
+        .res();
+    assert_eq!(statuses.len(), 1);
+    assert!(statuses[0].result.is_ok());
+    std::thread::sleep(SLEEP);
+    let peers: Vec<ZenohId> = session2.info().peers_zid().res().collect();
+    assert_eq!(peers, [session1.zid()]);
+
+    // Disconnect from it
+    let statuses = session2
+        .remove_endpoint(EndpointKind::Connect, added.parse().unwrap())
+        .res();
+    assert_eq!(statuses.len(), 1);
+    assert!(!statuses[0].added);
+    assert!(statuses[0].result.is_ok());
+    std::thread::sleep(SLEEP);
+    assert_eq!(session2.info().peers_zid().res().count(), 0);
+
+    // Stop listening on it
+    let statuses = session1
+        .remove_endpoint(EndpointKind::Listen, added.parse().unwrap())
+        .res();
+    assert_eq!(statuses.len(), 1);
+    assert!(statuses[0].result.is_ok());
+    assert!(TcpStream::connect("127.0.0.1:38460").is_err());
+    assert!(TcpStream::connect("127.0.0.1:38459").is_ok());
+    assert_eq!(
+        session1.config().lock().listen.endpoints,
+        vec![initial.parse().unwrap()]
+    );
+}
@@ -0,0 +1,77 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+mod common;
+
+use common::{open_sessions, SLEEP, TIMEOUT};
+use zenoh::extension::{Extension, ExtensionBody, USER_IDS};
+use zenoh::prelude::sync::*;
+
+#[test]
+fn extension_ids() {
+    assert!(Extension::new(*USER_IDS.start() - 1, false, ExtensionBody::Unit).is_err());
+    assert!(Extension::new(*USER_IDS.end() + 1, false, ExtensionBody::Unit).is_err());
+    let extension = Extension::new(*USER_IDS.end(), true, ExtensionBody::Z64(42)).unwrap();
+    assert_eq!(extension.id(), *USER_IDS.end());
+    assert!(extension.is_mandatory());
+    assert_eq!(extension.body(), &ExtensionBody::Z64(42));
+
+    let session = zenoh::open(Config::default()).res().unwrap();
+    assert!(session.accept_extension(*USER_IDS.start() - 1).is_err());
+    assert!(session.accept_extension(*USER_IDS.start()).is_ok());
+}
+
+#[test]
+fn put_extensions() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke = "test/extensions/put";
+    let (pub_session, sub_session) = open_sessions("tcp/127.0.0.1:38468");
+    let subscriber = sub_session.declare_subscriber(ke).res().unwrap();
+    std::thread::sleep(SLEEP);
+
+    let optional = Extension::new(0x0c, false, ExtensionBody::Z64(42)).unwrap();
+    let mandatory = Extension::new(0x0d, true, ExtensionBody::Unit).unwrap();
+
+    // Optional extensions are always delivered.
+    pub_session
+        .put(ke, "optional")
+        .with_extension(optional.clone())
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.extensions, vec![optional.clone()]);
+
+    // Mandatory extensions are dropped unless accepted.
+    pub_session
+        .put(ke, "rejected")
+        .with_extension(mandatory.clone())
+        .res()
+        .unwrap();
+    pub_session.put(ke, "plain").res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.value.to_string(), "plain");
+    assert!(sample.extensions.is_empty());
+
+    sub_session.accept_extension(mandatory.id()).unwrap();
+    pub_session
+        .delete(ke)
+        .with_extension(optional.clone())
+        .with_extension(mandatory.clone())
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.extensions, vec![optional, mandatory]);
+}
@@ -0,0 +1,612 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use futures::StreamExt;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::r#async::*;
+use zenoh::query::{CancellationHandle, ErrorCode, QueryStatus, Reply, ReplyEvent};
+use zenoh::queryable::Query;
+use zenoh::time::{Timestamp, TimestampId};
+
+async fn open_session() -> Session {
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().await.unwrap()
+}
+
+fn timestamp(secs: u64) -> Timestamp {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap() - Duration::from_secs(secs);
+    Timestamp::new(time.into(), TimestampId::try_from([1]).unwrap())
+}
+
+fn reply(query: &Query, result: Result<Sample, Value>) {
+    zenoh_core::SyncResolve::res_sync(query.reply(result)).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_accumulation() {
+    let session = open_session().await;
+
+    // Two queryables answer on overlapping keys with different timestamps.
+    let _old = session
+        .declare_queryable("test/get/**")
+        .callback(|query| {
+            for (key, value) in [("test/get/a", "old"), ("test/get/b", "old")] {
+                let sample = Sample::new(KeyExpr::try_from(key).unwrap(), value)
+                    .with_timestamp(timestamp(20));
+                reply(&query, Ok(sample));
+            }
+            reply(&query, Err("error".into()));
+        })
+        .res()
+        .await
+        .unwrap();
+    let _new = session
+        .declare_queryable("test/get/**")
+        .callback(|query| {
+            let sample = Sample::new(KeyExpr::try_from("test/get/a").unwrap(), "new")
+                .with_timestamp(timestamp(10));
+            reply(&query, Ok(sample));
+        })
+        .res()
+        .await
+        .unwrap();
+
+    let values = session
+        .get("test/get/**")
+        .consolidation(ConsolidationMode::None)
+        .collect_map()
+        .res()
+        .await
+        .unwrap();
+    assert_eq!(values.len(), 2);
+    let value = values.get(keyexpr::new("test/get/a").unwrap()).unwrap();
+    assert_eq!(value.to_string(), "new");
+    let value = values.get(keyexpr::new("test/get/b").unwrap()).unwrap();
+    assert_eq!(value.to_string(), "old");
+
+    let count = session
+        .get("test/get/**")
+        .consolidation(ConsolidationMode::None)
+        .fold(0, |count, _| count + 1)
+        .res()
+        .await
+        .unwrap();
+    assert_eq!(count, 4);
+
+    let first = session.get("test/get/**").first().res().await.unwrap();
+    assert!(first.is_some());
+
+    let none = session
+        .get("test/none/**")
+        .timeout(Duration::from_millis(500))
+        .first()
+        .res()
+        .await
+        .unwrap();
+    assert!(none.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_stream() {
+    let session = open_session().await;
+
+    let _queryable = session
+        .declare_queryable("test/stream/**")
+        .callback(|query| {
+            for key in ["test/stream/a", "test/stream/b"] {
+                reply(
+                    &query,
+                    Ok(Sample::new(KeyExpr::try_from(key).unwrap(), "value")),
+                );
+            }
+        })
+        .res()
+        .await
+        .unwrap();
+    let mut replies = session
+        .get("test/stream/**")
+        .consolidation(ConsolidationMode::None)
+        .stream()
+        .res()
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        match replies.next().await {
+            Some(ReplyEvent::Reply(reply)) => assert!(reply.sample.is_ok()),
+            event => panic!("Unexpected event: {event:?}"),
+        }
+    }
+    match replies.next().await {
+        Some(ReplyEvent::Done(status)) => assert_eq!(status, QueryStatus::Complete),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    assert!(replies.next().await.is_none());
+
+    // A queryable holding on to its queries never answers them.
+    let pending = Arc::new(Mutex::new(vec![]));
+    let _silent = session
+        .declare_queryable("test/silent")
+        .callback({
+            let pending = pending.clone();
+            move |query| pending.lock().unwrap().push(query)
+        })
+        .res()
+        .await
+        .unwrap();
+    let events: Vec<ReplyEvent> = session
+        .get("test/silent")
+        .timeout(Duration::from_millis(500))
+        .stream()
+        .res()
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(matches!(
+        events.last(),
+        Some(ReplyEvent::Done(QueryStatus::Timeout))
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_on_final() {
+    let session = open_session().await;
+
+    let _queryable = session
+        .declare_queryable("test/on_final")
+        .callback(|query| {
+            let sample = Sample::new(KeyExpr::try_from("test/on_final").unwrap(), "value");
+            reply(&query, Ok(sample));
+        })
+        .res()
+        .await
+        .unwrap();
+    let (events_tx, events_rx) = flume::unbounded();
+    session
+        .get("test/on_final")
+        .callback({
+            let events_tx = events_tx.clone();
+            move |reply| events_tx.send(ReplyEvent::Reply(reply)).unwrap()
+        })
+        .on_final(move |status| events_tx.send(ReplyEvent::Done(status)).unwrap())
+        .res()
+        .await
+        .unwrap();
+    match events_rx.recv_async().await {
+        Ok(ReplyEvent::Reply(reply)) => assert!(reply.sample.is_ok()),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    match events_rx.recv_async().await {
+        Ok(ReplyEvent::Done(status)) => assert_eq!(status, QueryStatus::Complete),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+
+    // The status is also reported with the default handler.
+    let (status_tx, status_rx) = flume::bounded(1);
+    let replies = session
+        .get("test/on_final/none")
+        .on_final(move |status| status_tx.send(status).unwrap())
+        .res()
+        .await
+        .unwrap();
+    assert!(replies.recv_async().await.is_err());
+    assert_eq!(status_rx.recv_async().await, Ok(QueryStatus::Complete));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_custom_consolidation() {
+    let session = open_session().await;
+
+    let _queryables: Vec<_> = futures::future::join_all([1i64, 3, 2].map(|n| {
+        session
+            .declare_queryable("test/consolidate")
+            .callback(move |query| {
+                let sample = Sample::new(KeyExpr::try_from("test/consolidate").unwrap(), n);
+                reply(&query, Ok(sample));
+            })
+            .res()
+    }))
+    .await;
+
+    // Keep the reply with the largest value.
+    let replies: Vec<Reply> = session
+        .get("test/consolidate")
+        .consolidate_with(|replies| {
+            let max = replies
+                .into_iter()
+                .max_by_key(|reply| i64::try_from(&reply.sample.as_ref().unwrap().value).unwrap());
+            max.into_iter().collect()
+        })
+        .fold(vec![], |mut replies, reply| {
+            replies.push(reply);
+            replies
+        })
+        .res()
+        .await
+        .unwrap();
+    assert_eq!(replies.len(), 1);
+    let value = &replies[0].sample.as_ref().unwrap().value;
+    assert_eq!(i64::try_from(value).unwrap(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_reply_del() {
+    let session = open_session().await;
+
+    let _queryable = session
+        .declare_queryable("test/reply_del")
+        .callback(|query| {
+            zenoh_core::SyncResolve::res_sync(
+                query.reply_del(KeyExpr::try_from("test/reply_del").unwrap()),
+            )
+            .unwrap();
+        })
+        .res()
+        .await
+        .unwrap();
+
+    let replies = session.get("test/reply_del").res().await.unwrap();
+    let sample = replies.recv_async().await.unwrap().sample.unwrap();
+    assert_eq!(sample.key_expr.as_str(), "test/reply_del");
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert!(sample.value.payload.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_consolidation_del() {
+    let session = open_session().await;
+
+    // `a` was deleted after being put, `b` was put again after being deleted.
+    let key_expr = |key| KeyExpr::try_from(format!("test/consolidation_del/{key}")).unwrap();
+    let _puts = session
+        .declare_queryable("test/consolidation_del/**")
+        .callback(move |query| {
+            for (key, age) in [("a", 20), ("b", 10)] {
+                if !query.key_expr().intersects(&key_expr(key)) {
+                    continue;
+                }
+                let sample = Sample::new(key_expr(key), "value").with_timestamp(timestamp(age));
+                reply(&query, Ok(sample));
+            }
+        })
+        .res()
+        .await
+        .unwrap();
+    let _dels = session
+        .declare_queryable("test/consolidation_del/**")
+        .callback(move |query| {
+            for (key, age) in [("a", 10), ("b", 20)] {
+                if !query.key_expr().intersects(&key_expr(key)) {
+                    continue;
+                }
+                let builder = query.reply_del(key_expr(key)).timestamp(timestamp(age));
+                zenoh_core::SyncResolve::res_sync(builder).unwrap();
+            }
+        })
+        .res()
+        .await
+        .unwrap();
+
+    let replies = session
+        .get("test/consolidation_del/a")
+        .consolidation(ConsolidationMode::Latest)
+        .res()
+        .await
+        .unwrap();
+    let sample = replies.recv_async().await.unwrap().sample.unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert!(replies.recv_async().await.is_err());
+
+    let values = session
+        .get("test/consolidation_del/**")
+        .consolidation(ConsolidationMode::None)
+        .collect_map()
+        .res()
+        .await
+        .unwrap();
+    assert_eq!(values.len(), 1);
+    let value = values.get(keyexpr::new("test/consolidation_del/b").unwrap());
+    assert_eq!(value.unwrap().to_string(), "value");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_reply_err() {
+    let session = open_session().await;
+
+    let _queryables: Vec<_> =
+        futures::future::join_all([ErrorCode::NotFound, ErrorCode::Other(42)].map(|code| {
+            session
+                .declare_queryable("test/reply_err")
+                .callback(move |query| {
+                    zenoh_core::SyncResolve::res_sync(query.reply_err(code, "error")).unwrap();
+                })
+                .res()
+        }))
+        .await;
+
+    let mut codes = vec![];
+    let replies = session.get("test/reply_err").res().await.unwrap();
+    while let Ok(reply) = replies.recv_async().await {
+        let code = reply.error_code().unwrap();
+        let error = reply.into_result().unwrap_err();
+        assert_eq!(error.code, code);
+        assert_eq!(String::try_from(&error.value).unwrap(), "error");
+        codes.push(code);
+    }
+    codes.sort_by_key(|code| u16::from(*code));
+    assert_eq!(codes, [ErrorCode::NotFound, ErrorCode::Other(42)]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_with_value() {
+    let session = open_session().await;
+    let body: Vec<u8> = (0..=255).cycle().take(64 * 1024).collect();
+
+    // Echo the body of the query back.
+    let _queryable = session
+        .declare_queryable("test/body")
+        .callback(|query| {
+            let value = query.value().cloned().unwrap_or_else(Value::empty);
+            let sample = Sample::new(query.key_expr().clone(), value);
+            reply(&query, Ok(sample));
+        })
+        .res()
+        .await
+        .unwrap();
+
+    let replies = session
+        .get("test/body")
+        .with_value(Value::from(body.clone()).encoding(KnownEncoding::AppOctetStream.into()))
+        .res()
+        .await
+        .unwrap();
+    let sample = replies.recv_async().await.unwrap().sample.unwrap();
+    assert_eq!(sample.value.encoding, KnownEncoding::AppOctetStream.into());
+    assert_eq!(sample.value.payload.contiguous().as_ref(), body.as_slice());
+}
+
+/// Declare a queryable that replies once, then holds on to the query until it is cancelled.
+async fn declare_slow_queryable<'a>(
+    session: &'a Session,
+    key_expr: &'static str,
+    cancelled: flume::Sender<()>,
+) -> zenoh::queryable::Queryable<'a, ()> {
+    session
+        .declare_queryable(key_expr)
+        .callback(move |query| {
+            let sample = Sample::new(query.key_expr().clone(), "partial");
+            reply(&query, Ok(sample));
+            let cancelled = cancelled.clone();
+            tokio::spawn(async move {
+                query.cancelled().await;
+                assert!(query.is_cancelled());
+                // Replies to a cancelled query are dropped.
+                reply(&query, Ok(Sample::new(query.key_expr().clone(), "late")));
+                cancelled.send(()).unwrap();
+            });
+        })
+        .res()
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_cancel() {
+    let session = open_session().await;
+    let (cancelled_tx, cancelled_rx) = flume::unbounded();
+    let _queryable = declare_slow_queryable(&session, "test/cancel", cancelled_tx).await;
+
+    let handle = CancellationHandle::new();
+    let mut replies = session
+        .get("test/cancel")
+        .consolidation(ConsolidationMode::None)
+        .timeout(Duration::from_secs(60))
+        .cancellation(handle.clone())
+        .stream()
+        .res()
+        .await
+        .unwrap();
+    match replies.next().await {
+        Some(ReplyEvent::Reply(reply)) => assert!(reply.sample.is_ok()),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    handle.cancel();
+    match replies.next().await {
+        Some(ReplyEvent::Done(status)) => assert_eq!(status, QueryStatus::Cancelled),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    assert!(replies.next().await.is_none());
+    tokio::time::timeout(Duration::from_secs(5), cancelled_rx.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_timeout_remote() {
+    let locator = "tcp/127.0.0.1:38456";
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![locator.parse().unwrap()];
+    let session1 = zenoh::open(config).res().await.unwrap();
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.connect.endpoints = vec![locator.parse().unwrap()];
+    let session2 = zenoh::open(config).res().await.unwrap();
+
+    let (cancelled_tx, cancelled_rx) = flume::unbounded();
+    let _queryable = declare_slow_queryable(&session1, "test/timeout", cancelled_tx).await;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // The partial replies are delivered, followed by the timeout, and the remote
+    // queryable is notified that the query was cancelled. The timeout is either
+    // reported by the session or by its router, depending on which fires first.
+    let mut replies = session2
+        .get("test/timeout")
+        .consolidation(ConsolidationMode::None)
+        .timeout(Duration::from_millis(500))
+        .stream()
+        .res()
+        .await
+        .unwrap();
+    let mut events = vec![];
+    while let Some(event) = replies.next().await {
+        events.push(event);
+    }
+    assert_eq!(events.len(), 3, "{events:?}");
+    match &events[0] {
+        ReplyEvent::Reply(reply) => {
+            let value = &reply.sample.as_ref().unwrap().value;
+            assert_eq!(String::try_from(value).unwrap(), "partial");
+        }
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    match &events[1] {
+        ReplyEvent::Reply(reply) => assert_eq!(reply.error_code(), Some(ErrorCode::Timeout)),
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    match &events[2] {
+        ReplyEvent::Done(QueryStatus::Timeout | QueryStatus::Complete) => {}
+        event => panic!("Unexpected event: {event:?}"),
+    }
+    tokio::time::timeout(Duration::from_secs(5), cancelled_rx.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_memory_budget() {
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.insert_json5("memory/budget", "1048576").unwrap();
+    config
+        .insert_json5("memory/limits/pending_queries", "64")
+        .unwrap();
+    let session = zenoh::open(config).res().await.unwrap();
+
+    // The state of a pending query doesn't fit in the limit of the pending queries.
+    assert!(session.get("test/budget").res().await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_memory_budget_handler_queues() {
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.insert_json5("memory/budget", "1048576").unwrap();
+    config
+        .insert_json5("memory/limits/handler_queues", "1024")
+        .unwrap();
+    let session = zenoh::open(config).res().await.unwrap();
+
+    // The queue of the default handler doesn't fit in the limit of the handler queues.
+    assert!(session
+        .declare_subscriber("test/budget")
+        .res()
+        .await
+        .is_err());
+    assert!(session
+        .declare_queryable("test/budget")
+        .res()
+        .await
+        .is_err());
+
+    // A callback queues nothing.
+    let _subscriber = session
+        .declare_subscriber("test/budget")
+        .callback(|_| {})
+        .res()
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_reply_qos() {
+    let locator = "tcp/127.0.0.1:38457";
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![locator.parse().unwrap()];
+    let session1 = zenoh::open(config).res().await.unwrap();
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.connect.endpoints = vec![locator.parse().unwrap()];
+    let session2 = zenoh::open(config).res().await.unwrap();
+
+    let _queryable = session1
+        .declare_queryable("test/qos")
+        .callback(|query| {
+            let sample = Sample::new(query.key_expr().clone(), "bulk");
+            zenoh_core::SyncResolve::res_sync(
+                query
+                    .reply(Ok(sample))
+                    .priority(Priority::DataLow)
+                    .congestion_control(CongestionControl::Drop)
+                    .express(true),
+            )
+            .unwrap();
+        })
+        .res()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // The QoS of the reply is kept along the reply path.
+    let replies = session2.get("test/qos").res().await.unwrap();
+    let sample = replies.recv_async().await.unwrap().sample.unwrap();
+    assert_eq!(sample.qos.priority(), Priority::DataLow);
+    assert_eq!(sample.qos.congestion_control(), CongestionControl::Drop);
+    assert!(sample.qos.express());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn get_memory_budget_consolidation() {
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.insert_json5("memory/budget", "1048576").unwrap();
+    config
+        .insert_json5("memory/limits/pending_queries", "4096")
+        .unwrap();
+    let session = zenoh::open(config).res().await.unwrap();
+
+    let _queryable = session
+        .declare_queryable("test/budget/**")
+        .callback(|query| {
+            for i in 0..10 {
+                let key = KeyExpr::try_from(format!("test/budget/{i}")).unwrap();
+                reply(&query, Ok(Sample::new(key, vec![0u8; 1024])));
+            }
+        })
+        .res()
+        .await
+        .unwrap();
+
+    // The replies kept for consolidation are accounted with the pending query:
+    // the ones that don't fit in the limit are replaced by errors.
+    let replies: Vec<Reply> = session
+        .get("test/budget/**")
+        .consolidation(ConsolidationMode::Latest)
+        .fold(vec![], |mut replies, reply| {
+            replies.push(reply);
+            replies
+        })
+        .res()
+        .await
+        .unwrap();
+    let (samples, errors): (Vec<_>, Vec<_>) = replies.iter().partition(|r| r.sample.is_ok());
+    assert!(!samples.is_empty());
+    assert!(!errors.is_empty());
+    assert_eq!(samples.len() + errors.len(), 10);
+}
@@ -0,0 +1,111 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::{Duration, SystemTime};
+use zenoh::prelude::sync::*;
+
+const SLEEP: Duration = Duration::from_millis(500);
+
+fn get(session: &Session, selector: &str) -> Vec<Sample> {
+    session
+        .get(selector)
+        .res()
+        .unwrap()
+        .into_iter()
+        .map(|reply| reply.sample.unwrap())
+        .collect()
+}
+
+#[test]
+fn builtin_info_keys() {
+    zenoh_util::try_init_log_from_env();
+
+    let locator = "tcp/127.0.0.1:38453";
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![locator.parse().unwrap()];
+    let session1 = zenoh::open(config).res().unwrap();
+
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.connect.endpoints = vec![locator.parse().unwrap()];
+    let session2 = zenoh::open(config).res().unwrap();
+    std::thread::sleep(SLEEP);
+
+    let zid = session1.zid();
+    for session in [&session1, &session2] {
+        let samples = get(session, &format!("@/{zid}/time/now"));
+        assert_eq!(samples.len(), 1);
+        let timestamp = samples[0].timestamp.unwrap();
+        assert_eq!(
+            String::try_from(&samples[0].value).unwrap(),
+            timestamp.to_string()
+        );
+
+        let samples = get(session, &format!("@/{zid}/version"));
+        assert_eq!(samples.len(), 1);
+        assert!(!String::try_from(&samples[0].value).unwrap().is_empty());
+
+        let samples = get(session, &format!("@/{zid}/uptime"));
+        assert_eq!(samples.len(), 1);
+        assert!(f64::try_from(&samples[0].value).unwrap() > 0.0);
+
+        let mut keys: Vec<String> = get(session, &format!("@/{zid}/**"))
+            .into_iter()
+            .map(|sample| sample.key_expr.to_string())
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                format!("@/{zid}/time/now"),
+                format!("@/{zid}/uptime"),
+                format!("@/{zid}/version")
+            ]
+        );
+    }
+}
+
+#[test]
+fn session_info_connections() {
+    zenoh_util::try_init_log_from_env();
+
+    let locator = "tcp/127.0.0.1:38458";
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![locator.parse().unwrap()];
+    let session1 = zenoh::open(config).res().unwrap();
+
+    let before = SystemTime::now();
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.connect.endpoints = vec![locator.parse().unwrap()];
+    let session2 = zenoh::open(config).res().unwrap();
+    std::thread::sleep(SLEEP);
+
+    let connections: Vec<_> = session2.info().connections().res().collect();
+    assert_eq!(connections.len(), 1);
+    let connection = &connections[0];
+    assert_eq!(connection.zid, session1.zid());
+    assert_eq!(connection.whatami, WhatAmI::Peer);
+    assert!(connection.established >= before);
+    assert!(connection.established <= SystemTime::now());
+    assert_eq!(connection.links.len(), 1);
+    assert_eq!(connection.links[0].protocol(), "tcp");
+    assert_eq!(connection.links[0].dst.to_string(), locator);
+
+    let connections: Vec<_> = session1.info().connections().res().collect();
+    assert_eq!(connections.len(), 1);
+    assert_eq!(connections[0].zid, session2.zid());
+    assert_eq!(connections[0].links[0].src.to_string(), locator);
+}
@@ -234,6 +234,141 @@ fn downsampling_by_interface() {
     downsampling_by_interface_impl(InterceptorFlow::Egress);
 }
 
+#[cfg(feature = "unstable")]
+#[test]
+fn downsampling_drop_notifications() {
+    use zenoh::sample::DropReason;
+
+    zenoh_util::try_init_log_from_env();
+
+    let ke_prefix = "test/downsamples_drop_notifications";
+    let locator = "tcp/127.0.0.1:38448";
+    let ke_10hz: KeyExpr = format!("{ke_prefix}/10hz").try_into().unwrap();
+
+    let ds_config = DownsamplingItemConf {
+        flow: InterceptorFlow::Ingress,
+        interfaces: None,
+        rules: vec![DownsamplingRuleConf {
+            key_expr: ke_10hz.clone().into(),
+            freq: 10.0,
+        }],
+    };
+    let (pub_config, sub_config) = build_config(locator, vec![ds_config], InterceptorFlow::Ingress);
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let _sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/*"))
+        .drop_notifications(true)
+        .callback({
+            let received = received.clone();
+            let dropped = dropped.clone();
+            move |sample| match sample.gap {
+                Some(gap) => {
+                    assert_eq!(gap.reason, DropReason::Downsampling);
+                    assert!(gap.count > 0);
+                    dropped.fetch_add(gap.count, Ordering::SeqCst);
+                }
+                None => {
+                    received.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        })
+        .res()
+        .unwrap();
+
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    let publisher = pub_session.declare_publisher(ke_10hz).res().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    let interval = std::time::Duration::from_millis(MINIMAL_SLEEP_INTERVAL_MS);
+    let mut published = 0;
+    let start = std::time::Instant::now();
+    while start.elapsed() < std::time::Duration::from_secs(1) {
+        publisher.put("message").res().unwrap();
+        published += 1;
+        std::thread::sleep(interval);
+    }
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    let received = received.load(Ordering::SeqCst);
+    let dropped = dropped.load(Ordering::SeqCst);
+    tracing::info!("published: {published}, received: {received}, dropped: {dropped}");
+    assert!(received > 0 && received <= 10 + 1);
+    assert!(dropped > 0);
+    assert_eq!(received + dropped, published);
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn downsampling_sample_miss_detection() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke_prefix = "test/downsamples_sample_miss";
+    let locator = "tcp/127.0.0.1:38449";
+    let ke_10hz: KeyExpr = format!("{ke_prefix}/10hz").try_into().unwrap();
+
+    let ds_config = DownsamplingItemConf {
+        flow: InterceptorFlow::Ingress,
+        interfaces: None,
+        rules: vec![DownsamplingRuleConf {
+            key_expr: ke_10hz.clone().into(),
+            freq: 10.0,
+        }],
+    };
+    let (pub_config, sub_config) = build_config(locator, vec![ds_config], InterceptorFlow::Ingress);
+
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    let pub_zid = pub_session.zid();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let missed = Arc::new(AtomicUsize::new(0));
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let _sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/*"))
+        .callback({
+            let received = received.clone();
+            move |sample| {
+                assert_eq!(sample.source_info.source_id, Some(pub_zid));
+                received.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .sample_miss_callback({
+            let missed = missed.clone();
+            move |event| {
+                assert_eq!(event.source.zid, pub_zid);
+                missed.fetch_add(event.count as usize, Ordering::SeqCst);
+            }
+        })
+        .res()
+        .unwrap();
+
+    let publisher = pub_session
+        .declare_publisher(ke_10hz)
+        .sequence_numbers(true)
+        .res()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    let interval = std::time::Duration::from_millis(MINIMAL_SLEEP_INTERVAL_MS);
+    let mut published = 0;
+    let start = std::time::Instant::now();
+    while start.elapsed() < std::time::Duration::from_secs(1) {
+        publisher.put("message").res().unwrap();
+        published += 1;
+        std::thread::sleep(interval);
+    }
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    let received = received.load(Ordering::SeqCst);
+    let missed = missed.load(Ordering::SeqCst);
+    tracing::info!("published: {published}, received: {received}, missed: {missed}");
+    assert!(received > 1 && received <= 10 + 1);
+    assert!(missed > 0);
+    assert!(received + missed <= published);
+}
+
 #[test]
 #[should_panic(expected = "unknown variant `down`")]
 fn downsampling_config_error_wrong_strategy() {
@@ -261,3 +396,381 @@ fn downsampling_config_error_wrong_strategy() {
 
     zenoh::open(config).res().unwrap();
 }
+
+#[test]
+fn priority_remapping() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke_prefix = "test/priority_remapping";
+    let locator = "tcp/127.0.0.1:38452";
+    let (pub_config, mut sub_config) = build_config(locator, vec![], InterceptorFlow::Ingress);
+    sub_config
+        .insert_json5(
+            "priority_remapping",
+            r#"
+              [
+                {
+                  rules: [
+                    { from: "data_high", to: "data_low" },
+                    { from: "data_low", to: "data_high" },
+                  ],
+                  max: "interactive_low",
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/*"))
+        .res()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    let timeout = std::time::Duration::from_secs(1);
+    for (priority, expected) in [
+        (Priority::RealTime, Priority::InteractiveLow),
+        (Priority::InteractiveLow, Priority::InteractiveLow),
+        (Priority::DataHigh, Priority::DataLow),
+        (Priority::Data, Priority::Data),
+        (Priority::DataLow, Priority::DataHigh),
+        (Priority::Background, Priority::Background),
+    ] {
+        pub_session
+            .put(format!("{ke_prefix}/{priority}"), "message")
+            .priority(priority)
+            .res()
+            .unwrap();
+        let sample = sub.recv_timeout(timeout).unwrap();
+        assert_eq!(sample.priority(), expected);
+    }
+}
+
+#[test]
+#[should_panic(expected = "unknown variant `urgent`")]
+fn priority_remapping_config_error_wrong_priority() {
+    zenoh_util::try_init_log_from_env();
+
+    let mut config = Config::default();
+    config
+        .insert_json5("priority_remapping", r#"[ { max: "urgent" } ]"#)
+        .unwrap();
+
+    zenoh::open(config).res().unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn session_interceptors() {
+    use zenoh::interceptor::SessionInterceptor;
+    use zenoh::queryable::Query;
+
+    struct Egress;
+    impl SessionInterceptor for Egress {
+        fn egress_sample(&self, mut sample: Sample) -> Option<Sample> {
+            if sample.key_expr.ends_with("/private") {
+                return None;
+            }
+            let mut attachment = sample.attachment.take().unwrap_or_default();
+            attachment.insert("tag", "egress");
+            sample.attachment = Some(attachment);
+            Some(sample)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Ingress {
+        samples: Arc<AtomicUsize>,
+        queries: Arc<AtomicUsize>,
+    }
+    impl SessionInterceptor for Ingress {
+        fn ingress_sample(&self, sample: Sample) -> Option<Sample> {
+            self.samples.fetch_add(1, Ordering::SeqCst);
+            (sample.value.to_string() != "skip").then_some(sample)
+        }
+        fn ingress_query(&self, query: &Query) -> bool {
+            self.queries.fetch_add(1, Ordering::SeqCst);
+            query.parameters() != "deny"
+        }
+    }
+
+    zenoh_util::try_init_log_from_env();
+
+    let ke_prefix = "test/session_interceptors";
+    let locator = "tcp/127.0.0.1:38463";
+    let (pub_config, sub_config) = build_config(locator, vec![], InterceptorFlow::Egress);
+    let timeout = std::time::Duration::from_secs(1);
+
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let ingress = Ingress::default();
+    let _ingress = sub_session
+        .declare_interceptor(ingress.clone())
+        .res()
+        .unwrap();
+    let sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/*"))
+        .res()
+        .unwrap();
+    let _qabl = sub_session
+        .declare_queryable(format!("{ke_prefix}/qabl"))
+        .callback(move |query| {
+            let key_expr = query.key_expr().clone();
+            query
+                .reply(Ok(Sample::new(key_expr, "reply")))
+                .res()
+                .unwrap();
+        })
+        .res()
+        .unwrap();
+
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    let egress = pub_session.declare_interceptor(Egress).res().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    // Egress: tagged, or dropped
+    pub_session
+        .put(format!("{ke_prefix}/private"), "secret")
+        .res()
+        .unwrap();
+    pub_session
+        .put(format!("{ke_prefix}/public"), "data")
+        .res()
+        .unwrap();
+    let sample = sub.recv_timeout(timeout).unwrap();
+    assert_eq!(sample.key_expr.as_str(), format!("{ke_prefix}/public"));
+    assert_eq!(
+        sample.attachment.unwrap().get(&"tag").unwrap().as_slice(),
+        b"egress"
+    );
+
+    // Ingress: dropped after being observed
+    pub_session
+        .put(format!("{ke_prefix}/public"), "skip")
+        .res()
+        .unwrap();
+    assert!(sub.recv_timeout(timeout).is_err());
+    assert_eq!(ingress.samples.load(Ordering::SeqCst), 2);
+
+    // Ingress queries
+    let replies = pub_session
+        .get(format!("{ke_prefix}/qabl?deny"))
+        .res()
+        .unwrap();
+    assert!(replies.recv_timeout(timeout).is_err());
+    let replies = pub_session.get(format!("{ke_prefix}/qabl")).res().unwrap();
+    assert!(replies.recv_timeout(timeout).unwrap().sample.is_ok());
+    assert_eq!(ingress.queries.load(Ordering::SeqCst), 2);
+
+    // Undeclared interceptors are not called anymore
+    egress.undeclare().res().unwrap();
+    pub_session
+        .put(format!("{ke_prefix}/private"), "data")
+        .res()
+        .unwrap();
+    let sample = sub.recv_timeout(timeout).unwrap();
+    assert!(sample.attachment.is_none());
+}
+
+#[test]
+fn qos_overwrite() {
+    use zenoh::publication::CongestionControl;
+
+    zenoh_util::try_init_log_from_env();
+
+    let ke_prefix = "test/qos_overwrite";
+    let locator = "tcp/127.0.0.1:38466";
+    let (mut pub_config, sub_config) = build_config(locator, vec![], InterceptorFlow::Egress);
+    pub_config
+        .insert_json5(
+            "qos_overwrite",
+            r#"
+              [
+                {
+                  flow: "egress",
+                  key_exprs: [ "test/qos_overwrite/backup/**" ],
+                  overwrite: {
+                    priority: "background",
+                    congestion_control: "drop",
+                    express: true,
+                  },
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/**"))
+        .res()
+        .unwrap();
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    let timeout = std::time::Duration::from_secs(1);
+    for (key, priority, congestion_control, express) in [
+        (
+            "backup/db",
+            Priority::Background,
+            CongestionControl::Drop,
+            true,
+        ),
+        (
+            "live/db",
+            Priority::DataHigh,
+            CongestionControl::Block,
+            false,
+        ),
+    ] {
+        pub_session
+            .put(format!("{ke_prefix}/{key}"), "message")
+            .priority(Priority::DataHigh)
+            .congestion_control(CongestionControl::Block)
+            .res()
+            .unwrap();
+        let sample = sub.recv_timeout(timeout).unwrap();
+        assert_eq!(sample.key_expr.as_str(), format!("{ke_prefix}/{key}"));
+        assert_eq!(sample.priority(), priority);
+        assert_eq!(sample.congestion_control(), congestion_control);
+        assert_eq!(sample.express(), express);
+    }
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn latency_budget() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use zenoh::sample::DropReason;
+    use zenoh::time::{Timestamp, TimestampId};
+
+    zenoh_util::try_init_log_from_env();
+
+    let ke_prefix = "test/latency_budget";
+    let locator = "tcp/127.0.0.1:38464";
+    let (pub_config, mut sub_config) = build_config(locator, vec![], InterceptorFlow::Ingress);
+    sub_config.latency_budget.set_enabled(Some(true)).unwrap();
+
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let (gaps_tx, gaps) = flume::unbounded();
+    let (samples_tx, samples) = flume::unbounded();
+    let _sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/*"))
+        .drop_notifications(true)
+        .callback(move |sample| match sample.gap {
+            Some(gap) => gaps_tx.send(gap).unwrap(),
+            None => samples_tx.send(sample).unwrap(),
+        })
+        .res()
+        .unwrap();
+
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    std::thread::sleep(Duration::from_millis(WARMUP_MS));
+
+    let past = SystemTime::now().duration_since(UNIX_EPOCH).unwrap() - Duration::from_secs(1);
+    let past = Timestamp::new(past.into(), TimestampId::try_from([1]).unwrap());
+    pub_session
+        .put(format!("{ke_prefix}/late"), "late")
+        .timestamp(past)
+        .latency_budget(Duration::from_millis(100))
+        .res()
+        .unwrap();
+    pub_session
+        .put(format!("{ke_prefix}/on_time"), "on_time")
+        .latency_budget(Duration::from_secs(10))
+        .res()
+        .unwrap();
+    // Data without latency budget is never dropped
+    pub_session
+        .put(format!("{ke_prefix}/no_budget"), "no_budget")
+        .timestamp(past)
+        .res()
+        .unwrap();
+
+    let timeout = Duration::from_secs(1);
+    let sample = samples.recv_timeout(timeout).unwrap();
+    assert_eq!(sample.key_expr.as_str(), format!("{ke_prefix}/on_time"));
+    assert_eq!(sample.latency_budget, Some(Duration::from_secs(10)));
+    assert!(sample.timestamp.is_some());
+    assert!(!sample.is_late());
+    let sample = samples.recv_timeout(timeout).unwrap();
+    assert_eq!(sample.key_expr.as_str(), format!("{ke_prefix}/no_budget"));
+    assert!(samples.recv_timeout(timeout).is_err());
+
+    let gap = gaps.recv_timeout(timeout).unwrap();
+    assert_eq!(gap.reason, DropReason::LatencyBudget);
+    assert_eq!(gap.count, 1);
+}
+
+#[test]
+fn bandwidth_quota() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke_prefix = "test/bandwidth_quota";
+    let locator = "tcp/127.0.0.1:38465";
+    let (mut pub_config, sub_config) = build_config(locator, vec![], InterceptorFlow::Egress);
+    pub_config
+        .insert_json5(
+            "bandwidth_quota",
+            r#"
+              [
+                {
+                  rules: [
+                    { key_expr: "test/bandwidth_quota/limited/**", bytes_per_sec: 1000 },
+                  ],
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+
+    let sub_session = zenoh::open(sub_config).res().unwrap();
+    let (tx, rx) = flume::unbounded();
+    let _sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/**"))
+        .callback(move |sample| tx.send(sample.key_expr.to_string()).unwrap())
+        .res()
+        .unwrap();
+    let pub_session = zenoh::open(pub_config).res().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    // The quota allows a burst of one second worth of bytes
+    let payload = vec![0u8; 200];
+    for _ in 0..20 {
+        for key in ["limited/a", "unlimited"] {
+            pub_session
+                .put(format!("{ke_prefix}/{key}"), payload.clone())
+                .res()
+                .unwrap();
+        }
+    }
+
+    let mut limited = 0;
+    let mut unlimited = 0;
+    while let Ok(key) = rx.recv_timeout(std::time::Duration::from_millis(500)) {
+        if key == format!("{ke_prefix}/limited/a") {
+            limited += 1;
+        } else {
+            unlimited += 1;
+        }
+    }
+    assert!((5..=6).contains(&limited), "{limited} limited samples");
+    assert_eq!(unlimited, 20);
+}
+
+#[test]
+#[should_panic(expected = "bytes_per_sec must be positive")]
+fn bandwidth_quota_config_error_zero_quota() {
+    zenoh_util::try_init_log_from_env();
+
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "bandwidth_quota",
+            r#"[ { rules: [ { key_expr: "test/**", bytes_per_sec: 0 } ] } ]"#,
+        )
+        .unwrap();
+
+    zenoh::open(config).res().unwrap();
+}
@@ -0,0 +1,133 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+const SLEEP: Duration = Duration::from_millis(500);
+
+fn open_session(mode: WhatAmI, locator: &str) -> Session {
+    open_session_with_interests(mode, locator, true)
+}
+
+fn open_session_with_interests(mode: WhatAmI, locator: &str, interests: bool) -> Session {
+    let mut config = Config::default();
+    config.set_mode(Some(mode)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5("routing/interests/enabled", &interests.to_string())
+        .unwrap();
+    match mode {
+        WhatAmI::Router => config.listen.endpoints = vec![locator.parse().unwrap()],
+        _ => config.connect.endpoints = vec![locator.parse().unwrap()],
+    }
+    zenoh::open(config).res().unwrap()
+}
+
+#[test]
+fn interests_pub_sub() {
+    zenoh_util::try_init_log_from_env();
+
+    let locator = "tcp/127.0.0.1:38469";
+    let _router = open_session(WhatAmI::Router, locator);
+    let sub_session = open_session(WhatAmI::Client, locator);
+    let pub_session = open_session(WhatAmI::Client, locator);
+    let subscriber = sub_session
+        .declare_subscriber("test/interests/**")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    // The subscribers matching a publisher are declared to its session...
+    let publisher = pub_session
+        .declare_publisher("test/interests/declared")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+    publisher.put("declared").res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(String::try_from(&sample.value).unwrap(), "declared");
+
+    // ...while the data for the other key expressions is sent to the router.
+    pub_session
+        .put("test/interests/undeclared", "undeclared")
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(String::try_from(&sample.value).unwrap(), "undeclared");
+
+    // The subscribers declared after the publisher are declared to its session too.
+    let late_subscriber = sub_session
+        .declare_subscriber("test/interests/declared")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+    publisher.put("late").res().unwrap();
+    let sample = late_subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(String::try_from(&sample.value).unwrap(), "late");
+}
+
+#[test]
+fn interests_get() {
+    zenoh_util::try_init_log_from_env();
+
+    let locator = "tcp/127.0.0.1:38470";
+    let _router = open_session(WhatAmI::Router, locator);
+    let qbl_session = open_session(WhatAmI::Client, locator);
+    let get_session = open_session(WhatAmI::Client, locator);
+    let _queryable = qbl_session
+        .declare_queryable("test/interests/queryable")
+        .callback(|query| {
+            let sample = Sample::new(query.key_expr().clone(), "reply");
+            query.reply(Ok(sample)).res().unwrap();
+        })
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    // The queries without interest in the queryables are sent to the router.
+    let reply = get_session
+        .get("test/interests/queryable")
+        .res()
+        .unwrap()
+        .recv_timeout(TIMEOUT)
+        .unwrap();
+    assert_eq!(
+        String::try_from(&reply.sample.unwrap().value).unwrap(),
+        "reply"
+    );
+}
+
+#[test]
+fn interests_legacy_client() {
+    zenoh_util::try_init_log_from_env();
+
+    let locator = "tcp/127.0.0.1:38473";
+    let _router = open_session(WhatAmI::Router, locator);
+    let sub_session = open_session(WhatAmI::Client, locator);
+    // A client with interests disabled never declares any, thus receives all the declarations.
+    let legacy_session = open_session_with_interests(WhatAmI::Client, locator, false);
+    let subscriber = sub_session
+        .declare_subscriber("test/interests/legacy")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    legacy_session
+        .put("test/interests/legacy", "legacy")
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(String::try_from(&sample.value).unwrap(), "legacy");
+}
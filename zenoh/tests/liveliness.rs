@@ -65,6 +65,10 @@ async fn zenoh_liveliness() {
 
     drop(token);
 
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Delete);
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_test");
+
     tokio::time::sleep(SLEEP).await;
 
     let replies = ztimeout!(session2
@@ -76,3 +80,172 @@ async fn zenoh_liveliness() {
 
     assert!(replies.try_recv().is_err());
 }
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_liveliness_wildcard() {
+    let mut c1 = config::peer();
+    c1.listen
+        .set_endpoints(vec!["tcp/localhost:47449".parse().unwrap()])
+        .unwrap();
+    c1.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session1 = ztimeout!(zenoh::open(c1).res_async()).unwrap();
+    let mut c2 = config::peer();
+    c2.connect
+        .set_endpoints(vec!["tcp/localhost:47449".parse().unwrap()])
+        .unwrap();
+    c2.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session2 = ztimeout!(zenoh::open(c2).res_async()).unwrap();
+
+    let sub = ztimeout!(session2
+        .liveliness()
+        .declare_subscriber("zenoh_liveliness_wildcard_test/**")
+        .res_async())
+    .unwrap();
+
+    let token_a = ztimeout!(session1
+        .liveliness()
+        .declare_token("zenoh_liveliness_wildcard_test/a")
+        .res_async())
+    .unwrap();
+    let _token_b = ztimeout!(session1
+        .liveliness()
+        .declare_token("zenoh_liveliness_wildcard_test/b")
+        .res_async())
+    .unwrap();
+
+    let mut appeared = vec![];
+    for _ in 0..2 {
+        let sample = ztimeout!(sub.recv_async()).unwrap();
+        assert!(sample.kind == SampleKind::Put);
+        appeared.push(sample.key_expr.as_str().to_string());
+    }
+    appeared.sort();
+    assert_eq!(
+        appeared,
+        [
+            "zenoh_liveliness_wildcard_test/a",
+            "zenoh_liveliness_wildcard_test/b"
+        ]
+    );
+
+    tokio::time::sleep(SLEEP).await;
+
+    let replies = ztimeout!(session2
+        .liveliness()
+        .get("zenoh_liveliness_wildcard_test/**")
+        .res_async())
+    .unwrap();
+    let mut alive = vec![];
+    while let Ok(reply) = ztimeout!(replies.recv_async()) {
+        alive.push(reply.sample.unwrap().key_expr.as_str().to_string());
+    }
+    alive.sort();
+    assert_eq!(alive, appeared);
+
+    drop(token_a);
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Delete);
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_wildcard_test/a");
+
+    tokio::time::sleep(SLEEP).await;
+
+    let replies = ztimeout!(session2
+        .liveliness()
+        .get("zenoh_liveliness_wildcard_test/**")
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(replies.recv_async()).unwrap().sample.unwrap();
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_wildcard_test/b");
+    assert!(ztimeout!(replies.recv_async()).is_err());
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_liveliness_session_close() {
+    let mut c1 = config::peer();
+    c1.listen
+        .set_endpoints(vec!["tcp/localhost:47451".parse().unwrap()])
+        .unwrap();
+    c1.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session1 = ztimeout!(zenoh::open(c1).res_async()).unwrap();
+    let mut c2 = config::peer();
+    c2.connect
+        .set_endpoints(vec!["tcp/localhost:47451".parse().unwrap()])
+        .unwrap();
+    c2.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session2 = ztimeout!(zenoh::open(c2).res_async()).unwrap();
+
+    let sub = ztimeout!(session2
+        .liveliness()
+        .declare_subscriber("zenoh_liveliness_session_close_test")
+        .res_async())
+    .unwrap();
+
+    let _token = ztimeout!(session1
+        .liveliness()
+        .declare_token("zenoh_liveliness_session_close_test")
+        .res_async())
+    .unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Put);
+
+    // The tokens of a closed session disappear without being undeclared.
+    ztimeout!(session1.close().res_async()).unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Delete);
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_session_close_test");
+
+    let replies = ztimeout!(session2
+        .liveliness()
+        .get("zenoh_liveliness_session_close_test")
+        .res_async())
+    .unwrap();
+    assert!(ztimeout!(replies.recv_async()).is_err());
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_liveliness_history() {
+    let mut c1 = config::peer();
+    c1.listen
+        .set_endpoints(vec!["tcp/localhost:47448".parse().unwrap()])
+        .unwrap();
+    c1.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session1 = ztimeout!(zenoh::open(c1).res_async()).unwrap();
+    let mut c2 = config::peer();
+    c2.connect
+        .set_endpoints(vec!["tcp/localhost:47448".parse().unwrap()])
+        .unwrap();
+    c2.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let session2 = ztimeout!(zenoh::open(c2).res_async()).unwrap();
+
+    let token = ztimeout!(session1
+        .liveliness()
+        .declare_token("zenoh_liveliness_history_test")
+        .res_async())
+    .unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+
+    // A late subscriber receives the token that is already alive.
+    let sub = ztimeout!(session2
+        .liveliness()
+        .declare_subscriber("zenoh_liveliness_history_test")
+        .history(true)
+        .res_async())
+    .unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Put);
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_history_test");
+
+    drop(token);
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.kind == SampleKind::Delete);
+    assert!(sample.key_expr.as_str() == "zenoh_liveliness_history_test");
+}
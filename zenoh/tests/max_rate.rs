@@ -0,0 +1,108 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+fn open_session() -> Session {
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+#[test]
+fn publisher_max_rate() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke = "test/max_rate/publisher";
+    let session = open_session();
+    let subscriber = session.declare_subscriber(ke).res().unwrap();
+    let publisher = session.declare_publisher(ke).max_rate(5.0).res().unwrap();
+
+    // The first publication is sent right away, only the latest of the next ones is sent
+    // once the rate allows it
+    for i in 0..50 {
+        publisher.put(i.to_string()).res().unwrap();
+    }
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.value.to_string(), "0");
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.value.to_string(), "49");
+    assert!(subscriber.recv_timeout(Duration::from_millis(500)).is_err());
+
+    // Once the rate allows it, publications are sent right away again
+    publisher.put("50").res().unwrap();
+    let sample = subscriber.try_recv().unwrap();
+    assert_eq!(sample.value.to_string(), "50");
+}
+
+#[test]
+fn put_max_rate_per_key() {
+    zenoh_util::try_init_log_from_env();
+
+    let session = open_session();
+    let subscriber = session
+        .declare_subscriber("test/max_rate/put/*")
+        .res()
+        .unwrap();
+
+    for i in 0..10 {
+        for key in ["a", "b"] {
+            session
+                .put(format!("test/max_rate/put/{key}"), i.to_string())
+                .max_rate(5.0)
+                .res()
+                .unwrap();
+        }
+    }
+    let mut received = vec![];
+    while let Ok(sample) = subscriber.recv_timeout(Duration::from_millis(500)) {
+        received.push(format!("{}={}", sample.key_expr, sample.value));
+    }
+    received.sort();
+    assert_eq!(
+        received,
+        [
+            "test/max_rate/put/a=0",
+            "test/max_rate/put/a=9",
+            "test/max_rate/put/b=0",
+            "test/max_rate/put/b=9",
+        ]
+    );
+}
+
+#[test]
+fn invalid_max_rate() {
+    zenoh_util::try_init_log_from_env();
+
+    let session = open_session();
+    assert!(session
+        .declare_publisher("test/max_rate/invalid")
+        .max_rate(0.0)
+        .res()
+        .is_err());
+    assert!(session
+        .put("test/max_rate/invalid", "value")
+        .max_rate(-1.0)
+        .res()
+        .is_err());
+    // The period between two publications would overflow
+    assert!(session
+        .declare_publisher("test/max_rate/invalid")
+        .max_rate(f64::MIN_POSITIVE)
+        .res()
+        .is_err());
+}
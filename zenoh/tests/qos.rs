@@ -52,4 +52,39 @@ async fn pubsub() {
 
     assert_eq!(qos.priority(), Priority::DataLow);
     assert_eq!(qos.congestion_control(), CongestionControl::Block);
+
+    // Per-message priority override
+    ztimeout!(publisher2
+        .put("qos")
+        .priority(Priority::RealTime)
+        .res_async())
+    .unwrap();
+    let qos = ztimeout!(subscriber.recv_async()).unwrap().qos;
+
+    assert_eq!(qos.priority(), Priority::RealTime);
+    assert_eq!(qos.congestion_control(), CongestionControl::Block);
+
+    ztimeout!(publisher2.delete().priority(Priority::DataHigh).res_async()).unwrap();
+    let sample = ztimeout!(subscriber.recv_async()).unwrap();
+
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.qos.priority(), Priority::DataHigh);
+
+    ztimeout!(publisher2.put("qos").res_async()).unwrap();
+    let qos = ztimeout!(subscriber.recv_async()).unwrap().qos;
+
+    assert_eq!(qos.priority(), Priority::DataLow);
+    assert!(!qos.express());
+
+    // Per-message express override
+    ztimeout!(publisher2.put("qos").express(true).res_async()).unwrap();
+    let qos = ztimeout!(subscriber.recv_async()).unwrap().qos;
+
+    assert_eq!(qos.priority(), Priority::DataLow);
+    assert!(qos.express());
+
+    ztimeout!(publisher2.put("qos").res_async()).unwrap();
+    let qos = ztimeout!(subscriber.recv_async()).unwrap().qos;
+
+    assert!(!qos.express());
 }
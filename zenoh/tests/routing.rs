@@ -734,3 +734,109 @@ async fn two_node_combination() -> Result<()> {
     println!("Two-node combination test passed.");
     Result::Ok(())
 }
+
+// A router balancing the queries targeting the best matching queryable between two identical clients.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn query_selection_round_robin() -> Result<()> {
+    zenoh_util::try_init_log_from_env();
+    let locator = "tcp/127.0.0.1:38455";
+    let ke = "testKeyExprQuerySelection";
+
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![locator.parse().unwrap()];
+    config
+        .insert_json5("routing/query/selection", r#""round_robin""#)
+        .unwrap();
+    let router = ztimeout!(zenoh::open(config).res_async())?;
+
+    let mut sessions = vec![];
+    for _ in 0..2 {
+        let config = zenoh::config::client([locator.parse::<EndPoint>().unwrap()]);
+        sessions.push(ztimeout!(zenoh::open(config).res_async())?);
+    }
+    let mut queryables = vec![];
+    for (session, name) in sessions.iter().zip(["a", "b"]) {
+        queryables.push(ztimeout!(session
+            .declare_queryable(ke)
+            .complete(true)
+            .callback(move |query| {
+                let sample = Sample::new(query.key_expr().clone(), name);
+                zenoh_core::SyncResolve::res_sync(query.reply(Ok(sample))).unwrap();
+            })
+            .res_async())?);
+    }
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let mut repliers = vec![];
+    for _ in 0..4 {
+        let replies = ztimeout!(router.get(ke).target(QueryTarget::BestMatching).res_async())?;
+        let mut values = vec![];
+        while let Ok(reply) = ztimeout!(replies.recv_async()) {
+            values.push(String::try_from(&reply.sample.unwrap().value).unwrap());
+        }
+        assert_eq!(values.len(), 1);
+        repliers.push(values.remove(0));
+    }
+    assert_ne!(repliers[0], repliers[1]);
+    assert_eq!(repliers[0], repliers[2]);
+    assert_eq!(repliers[1], repliers[3]);
+    Ok(())
+}
+
+// A query overriding the selection configured on the router it is routed by.
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn query_selection_override() -> Result<()> {
+    use zenoh::query::QueryableSelection;
+
+    zenoh_util::try_init_log_from_env();
+    let locator = "tcp/127.0.0.1:38456";
+    let ke = "testKeyExprQuerySelectionOverride";
+
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.listen.endpoints = vec![locator.parse().unwrap()];
+    let _router = ztimeout!(zenoh::open(config).res_async())?;
+
+    let mut sessions = vec![];
+    for _ in 0..3 {
+        let config = zenoh::config::client([locator.parse::<EndPoint>().unwrap()]);
+        sessions.push(ztimeout!(zenoh::open(config).res_async())?);
+    }
+    let mut queryables = vec![];
+    for (session, name) in sessions.iter().zip(["a", "b"]) {
+        queryables.push(ztimeout!(session
+            .declare_queryable(ke)
+            .complete(true)
+            .callback(move |query| {
+                let sample = Sample::new(query.key_expr().clone(), name);
+                zenoh_core::SyncResolve::res_sync(query.reply(Ok(sample))).unwrap();
+            })
+            .res_async())?);
+    }
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // The router selects the nearest queryable by default, each one in turn when the
+    // queries ask for it
+    let mut repliers = vec![];
+    for _ in 0..4 {
+        let replies = ztimeout!(sessions[2]
+            .get(ke)
+            .target(QueryTarget::BestMatching)
+            .selection(QueryableSelection::RoundRobin)
+            .res_async())?;
+        let mut values = vec![];
+        while let Ok(reply) = ztimeout!(replies.recv_async()) {
+            values.push(String::try_from(&reply.sample.unwrap().value).unwrap());
+        }
+        assert_eq!(values.len(), 1);
+        repliers.push(values.remove(0));
+    }
+    assert_ne!(repliers[0], repliers[1]);
+    assert_eq!(repliers[0], repliers[2]);
+    assert_eq!(repliers[1], repliers[3]);
+    Ok(())
+}
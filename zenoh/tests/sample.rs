@@ -0,0 +1,112 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+mod common;
+
+use common::{SLEEP, TIMEOUT};
+use zenoh::prelude::sync::*;
+use zenoh::sample::{QoS, SampleBuilder, SourceInfo};
+use zenoh::time::new_reception_timestamp;
+
+#[test]
+fn sample_builder() {
+    let zid = ZenohId::rand();
+    let timestamp = new_reception_timestamp();
+    let key_expr = KeyExpr::try_from("test/sample").unwrap();
+    let sample = SampleBuilder::put(key_expr.clone(), "value")
+        .timestamp(timestamp)
+        .qos(QoS::default().with_express(true))
+        .source_info(SourceInfo::new(zid, 1, 42))
+        .build();
+    assert_eq!(sample.key_expr.as_str(), "test/sample");
+    assert_eq!(sample.kind, SampleKind::Put);
+    assert_eq!(sample.payload.contiguous().as_ref(), b"value");
+    assert_eq!(sample.timestamp, Some(timestamp));
+    assert!(sample.express());
+    assert_eq!(sample.source_id(), Some(zid));
+    assert_eq!(sample.source_sn(), Some(42));
+    assert_eq!(sample.source_info.source_eid, 1);
+
+    let sample: Sample = SampleBuilder::delete(key_expr).into();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert!(sample.payload.is_empty());
+    assert!(sample.timestamp.is_none());
+    assert!(sample.source_id().is_none());
+}
+
+#[test]
+fn sample_source_info() {
+    let session = zenoh::open(Config::default()).res().unwrap();
+    let subscriber = session.declare_subscriber("test/sample").res().unwrap();
+    let publisher = session
+        .declare_publisher("test/sample")
+        .sequence_numbers(true)
+        .res()
+        .unwrap();
+
+    publisher.put("a").res().unwrap();
+    publisher.put("b").res().unwrap();
+    let first = subscriber.recv_timeout(TIMEOUT).unwrap();
+    let second = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(first.source_id(), Some(session.zid()));
+    assert_eq!(second.source_id(), Some(session.zid()));
+    assert_eq!(second.source_sn(), first.source_sn().map(|sn| sn + 1));
+
+    // Publishers don't stamp their publications unless asked to
+    let unstamped = session.declare_publisher("test/sample").res().unwrap();
+    unstamped.put("unstamped").res().unwrap();
+    assert!(subscriber
+        .recv_timeout(TIMEOUT)
+        .unwrap()
+        .source_id()
+        .is_none());
+}
+
+#[test]
+fn delete_with_value() {
+    let (pub_session, sub_session) = common::open_sessions("tcp/127.0.0.1:38454");
+
+    let remote = sub_session
+        .declare_subscriber("test/sample/delete")
+        .res()
+        .unwrap();
+    let local = pub_session
+        .declare_subscriber("test/sample/delete")
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    pub_session
+        .delete("test/sample/delete")
+        .value("expired")
+        .res()
+        .unwrap();
+    for subscriber in [&remote, &local] {
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(sample.kind, SampleKind::Delete);
+        assert_eq!(sample.payload.contiguous().as_ref(), b"expired");
+        assert_eq!(sample.encoding, KnownEncoding::TextPlain.into());
+    }
+
+    let publisher = pub_session
+        .declare_publisher("test/sample/delete")
+        .res()
+        .unwrap();
+    publisher.delete().res().unwrap();
+    for subscriber in [&remote, &local] {
+        let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(sample.kind, SampleKind::Delete);
+        assert!(sample.payload.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::scouting::WhatAmI;
+use zenoh_core::ztimeout;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn scouting_deduplicate() {
+    let mut config = config::peer();
+    config
+        .insert_json5("scouting/multicast/address", r#""224.0.0.224:47450""#)
+        .unwrap();
+    config
+        .insert_json5("scouting/multicast/autoconnect", r#""""#)
+        .unwrap();
+    config.listen.endpoints = vec!["tcp/127.0.0.1:47450".parse().unwrap()];
+    config
+        .insert_json5("metadata", r#"{ name: "scouting_test" }"#)
+        .unwrap();
+    let session = ztimeout!(zenoh::open(config.clone()).res_async()).unwrap();
+
+    let scout = ztimeout!(zenoh::scout(WhatAmI::Peer, config)
+        .deduplicate(true)
+        .res_async())
+    .unwrap();
+    let hello = loop {
+        let hello = ztimeout!(scout.recv_async()).unwrap();
+        if hello.zid == session.zid() {
+            break hello;
+        }
+    };
+    assert_eq!(hello.whatami, WhatAmI::Peer);
+    assert!(hello
+        .locators
+        .contains(&"tcp/127.0.0.1:47450".parse().unwrap()));
+    assert_eq!(
+        hello.metadata,
+        Some(serde_json::json!({ "name": "scouting_test" }))
+    );
+
+    // The node keeps answering the scout but isn't reported again.
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert!(scout.try_iter().all(|hello| hello.zid != session.zid()));
+    scout.stop();
+}
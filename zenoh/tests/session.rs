@@ -243,3 +243,147 @@ async fn zenoh_2sessions_1runtime_init() {
     println!("[  ][02e] Closing r2 runtime");
     ztimeout!(r2.close()).unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_session_close_drain() {
+    zenoh_util::try_init_log_from_env();
+    let (peer01, peer02) = open_session_unicast(&["tcp/127.0.0.1:17445"]).await;
+    let key_expr = "test/session/close";
+
+    let msgs = Arc::new(AtomicUsize::new(0));
+    let c_msgs = msgs.clone();
+    let sub = ztimeout!(peer01
+        .declare_subscriber(key_expr)
+        .callback(move |_| {
+            c_msgs.fetch_add(1, Ordering::Relaxed);
+        })
+        .res_async())
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // Close the publisher right after the last put: nothing should be lost
+    println!("[CD][01b] Putting on peer02 session. {MSG_COUNT} msgs.");
+    for _ in 0..MSG_COUNT {
+        ztimeout!(peer02
+            .put(key_expr, vec![0u8; MSG_SIZE[0]])
+            .congestion_control(CongestionControl::Block)
+            .res_async())
+        .unwrap();
+    }
+    println!("[CD][02b] Closing peer02 session");
+    ztimeout!(peer02.close().timeout(TIMEOUT).res_async()).unwrap();
+
+    ztimeout!(async {
+        while msgs.load(Ordering::Relaxed) < MSG_COUNT {
+            tokio::time::sleep(SLEEP).await;
+        }
+    });
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(msgs.load(Ordering::Relaxed), MSG_COUNT);
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    ztimeout!(peer01.close().res_async()).unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_2sessions_shared_runtime() {
+    zenoh_util::try_init_log_from_env();
+    let endpoint = "tcp/127.0.0.1:17444";
+    let key_expr = "test/session/shared";
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    println!("[SR][01a] Opening the shared runtime: {endpoint}");
+    let runtime = ztimeout!(zenoh::open_runtime(config).res_async()).unwrap();
+    let peer01 = ztimeout!(zenoh::init(runtime.clone()).res_async()).unwrap();
+    let peer02 = ztimeout!(zenoh::init(runtime.clone()).res_async()).unwrap();
+    assert_eq!(peer01.zid(), peer02.zid());
+
+    // The sessions of a runtime see each other
+    let sub = ztimeout!(peer02.declare_subscriber(key_expr).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    println!("[SR][02a] Putting on peer01 session");
+    ztimeout!(peer01.put(key_expr, "peer01").res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.value.to_string(), "peer01");
+
+    // Closing a session doesn't close the runtime
+    println!("[SR][03a] Closing peer01 session");
+    ztimeout!(peer01.close().res_async()).unwrap();
+    assert!(runtime
+        .get_locators()
+        .iter()
+        .any(|l| l.as_str() == endpoint));
+    let peer03 = ztimeout!(zenoh::init(runtime.clone()).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(peer03.put(key_expr, "peer03").res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.value.to_string(), "peer03");
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    ztimeout!(peer02.close().res_async()).unwrap();
+    ztimeout!(peer03.close().res_async()).unwrap();
+    println!("[SR][04a] Closing the shared runtime");
+    ztimeout!(runtime.close()).unwrap();
+}
+
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_session_close_shared_runtime() {
+    zenoh_util::try_init_log_from_env();
+    let endpoint = "tcp/127.0.0.1:17443";
+    let key_expr = "test/session/close/shared";
+
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    println!("[CS][01a] Opening the remote session: {endpoint}");
+    let remote = ztimeout!(zenoh::open(config).res_async()).unwrap();
+    let sub = ztimeout!(remote
+        .declare_subscriber(key_expr)
+        .callback(|_| {})
+        .res_async())
+    .unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    println!("[CS][02a] Opening the shared runtime: {endpoint}");
+    let runtime = ztimeout!(zenoh::open_runtime(config).res_async()).unwrap();
+    let peer01 = ztimeout!(zenoh::init(runtime.clone()).res_async())
+        .unwrap()
+        .into_arc();
+    let peer02 = ztimeout!(zenoh::init(runtime.clone()).res_async()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // peer01 keeps the link busy while peer02 is closed
+    let c_peer01 = peer01.clone();
+    let traffic = tokio::spawn(async move {
+        loop {
+            c_peer01
+                .put(key_expr, vec![0u8; MSG_SIZE[1]])
+                .congestion_control(CongestionControl::Block)
+                .res_async()
+                .await
+                .unwrap();
+        }
+    });
+    tokio::time::sleep(SLEEP).await;
+
+    // Closing peer02 only waits for its own messages, not for peer01's traffic
+    println!("[CS][03a] Closing peer02 session");
+    let close_timeout = Duration::from_secs(5);
+    let start = std::time::Instant::now();
+    ztimeout!(peer02.close().timeout(close_timeout).res_async()).unwrap();
+    assert!(start.elapsed() < close_timeout);
+
+    traffic.abort();
+    let _ = traffic.await;
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    ztimeout!(remote.close().res_async()).unwrap();
+    drop(peer01);
+    println!("[CS][04a] Closing the shared runtime");
+    ztimeout!(runtime.close()).unwrap();
+}
@@ -0,0 +1,125 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::sync::*;
+use zenoh::time::{Timestamp, TimestampId};
+
+fn open_session(drop_future_timestamp: bool) -> Session {
+    let mut config = Config::default();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5(
+            "timestamping",
+            &format!(
+                r#"{{ enabled: {{ router: true, peer: true, client: true }}, drop_future_timestamp: {drop_future_timestamp} }}"#
+            ),
+        )
+        .unwrap();
+    zenoh::open(config).res().unwrap()
+}
+
+fn timestamp_at(offset: Duration, future: bool) -> Timestamp {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let time = if future { now + offset } else { now - offset };
+    Timestamp::new(time.into(), TimestampId::try_from([7]).unwrap())
+}
+
+#[test]
+fn put_delete_timestamp_override() {
+    let ke = "test/timestamp/pubsub";
+    let session = open_session(true);
+    let subscriber = session.declare_subscriber(ke).res().unwrap();
+
+    let timestamp = timestamp_at(Duration::from_secs(60), false);
+    session.put(ke, "put").timestamp(timestamp).res().unwrap();
+    let sample = subscriber.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(sample.timestamp, Some(timestamp));
+
+    session.delete(ke).timestamp(timestamp).res().unwrap();
+    let sample = subscriber.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.timestamp, Some(timestamp));
+
+    let publisher = session.declare_publisher(ke).res().unwrap();
+    publisher
+        .put("publisher")
+        .timestamp(timestamp)
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(sample.timestamp, Some(timestamp));
+
+    // Without an override, the session's HLC is used and is now ahead of the given timestamp.
+    publisher.put("publisher").res().unwrap();
+    let sample = subscriber.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert!(sample.timestamp.unwrap() > timestamp);
+
+    // Timestamps too far ahead of the session's HLC are rejected.
+    let future = timestamp_at(Duration::from_secs(3600), true);
+    assert!(session.put(ke, "put").timestamp(future).res().is_err());
+    assert!(publisher.delete().timestamp(future).res().is_err());
+    assert!(subscriber.try_recv().is_err());
+}
+
+#[test]
+fn future_timestamp_replaced() {
+    let ke = "test/timestamp/future";
+    let session = open_session(false);
+    let subscriber = session.declare_subscriber(ke).res().unwrap();
+
+    // Without drop_future_timestamp, timestamps too far ahead of the session's HLC are
+    // replaced by one of the HLC, as the routers do for the data they receive.
+    let future = timestamp_at(Duration::from_secs(3600), true);
+    session.put(ke, "put").timestamp(future).res().unwrap();
+    let sample = subscriber.recv_timeout(Duration::from_secs(1)).unwrap();
+    let timestamp = sample.timestamp.unwrap();
+    assert!(timestamp < future);
+    assert_eq!(timestamp.get_id(), session.hlc().unwrap().get_id());
+}
+
+#[test]
+fn reply_timestamp_override() {
+    let ke = "test/timestamp/reply";
+    let session = open_session(true);
+    let timestamp = timestamp_at(Duration::from_secs(60), false);
+    let _queryable = session
+        .declare_queryable(ke)
+        .callback(move |query| {
+            let sample = Sample::new(query.key_expr().clone(), "reply");
+            query.reply(Ok(sample)).timestamp(timestamp).res().unwrap();
+            let future = timestamp_at(Duration::from_secs(3600), true);
+            let sample = Sample::new(query.key_expr().clone(), "future");
+            assert!(query.reply(Ok(sample)).timestamp(future).res().is_err());
+            // The timestamps of the samples are only checked when overridden
+            let sample = Sample::new(query.key_expr().clone(), "sample").with_timestamp(future);
+            query.reply(Ok(sample)).res().unwrap();
+        })
+        .res()
+        .unwrap();
+
+    let replies: Vec<_> = session
+        .get(ke)
+        .consolidation(ConsolidationMode::None)
+        .res()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(replies.len(), 2);
+    let sample = replies[0].sample.as_ref().unwrap();
+    assert_eq!(sample.timestamp, Some(timestamp));
+    let sample = replies[1].sample.as_ref().unwrap();
+    assert_eq!(sample.value.payload.contiguous().as_ref(), b"sample");
+    assert!(sample.timestamp.unwrap() > timestamp);
+}
@@ -0,0 +1,90 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+mod common;
+
+use common::{open_sessions, SLEEP, TIMEOUT};
+use zenoh::prelude::sync::*;
+use zenoh::trace::TraceId;
+
+#[test]
+fn put_trace_id() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke = "test/trace/put";
+    let (pub_session, sub_session) = open_sessions("tcp/127.0.0.1:38471");
+    let subscriber = sub_session.declare_subscriber(ke).res().unwrap();
+    std::thread::sleep(SLEEP);
+
+    let trace_id = TraceId::rand();
+    pub_session
+        .put(ke, "traced")
+        .trace_id(trace_id)
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.trace_id, Some(trace_id));
+
+    pub_session.put(ke, "untraced").res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.trace_id, None);
+
+    let trace_id = TraceId::rand();
+    pub_session.delete(ke).trace_id(trace_id).res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.trace_id, Some(trace_id));
+}
+
+#[test]
+fn get_trace_id() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke = "test/trace/get";
+    let (get_session, qbl_session) = open_sessions("tcp/127.0.0.1:38472");
+    let queryable = qbl_session
+        .declare_queryable(ke)
+        .callback(move |query| {
+            let value = query
+                .trace_id()
+                .map(|trace_id| trace_id.to_string())
+                .unwrap_or_default();
+            query
+                .reply(Ok(Sample::new(query.key_expr().clone(), value)))
+                .res()
+                .unwrap();
+        })
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    let trace_id = TraceId::rand();
+    let replies = get_session
+        .get(ke)
+        .trace_id(trace_id)
+        .timeout(TIMEOUT)
+        .res()
+        .unwrap();
+    let sample = replies.recv().unwrap().sample.unwrap();
+    // The queryable received the trace ID and the reply carries it back.
+    assert_eq!(sample.value.to_string(), trace_id.to_string());
+    assert_eq!(sample.trace_id, Some(trace_id));
+
+    let replies = get_session.get(ke).timeout(TIMEOUT).res().unwrap();
+    let sample = replies.recv().unwrap().sample.unwrap();
+    assert_eq!(sample.value.to_string(), "");
+    assert_eq!(sample.trace_id, None);
+
+    queryable.undeclare().res().unwrap();
+}
@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+mod common;
+
+use common::{open_sessions_with, SLEEP, TIMEOUT};
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh::prelude::sync::*;
+use zenoh::time::{Timestamp, TimestampId};
+
+fn open_sessions_timestamped(locator: &str) -> (Session, Session) {
+    open_sessions_with(locator, |config| {
+        config
+            .insert_json5(
+                "timestamping",
+                r#"{ enabled: { router: true, peer: true, client: true } }"#,
+            )
+            .unwrap();
+    })
+}
+
+#[test]
+fn put_ttl() {
+    zenoh_util::try_init_log_from_env();
+
+    let ke = "test/ttl/put";
+    let (pub_session, sub_session) = open_sessions_timestamped("tcp/127.0.0.1:38451");
+    let subscriber = sub_session.declare_subscriber(ke).res().unwrap();
+    let filtered = sub_session
+        .declare_subscriber(ke)
+        .ignore_expired()
+        .res()
+        .unwrap();
+    std::thread::sleep(SLEEP);
+
+    // The ttl is carried along with the sample.
+    let ttl = Duration::from_secs(60);
+    pub_session.put(ke, "fresh").ttl(ttl).res().unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.ttl, Some(ttl));
+    assert!(!sample.is_expired());
+    let timestamp = sample.timestamp.unwrap();
+    assert_eq!(
+        sample.expiration().unwrap().get_time().to_duration(),
+        timestamp.get_time().to_duration() + ttl
+    );
+    let sample = filtered.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.value.to_string(), "fresh");
+
+    // Samples published too long ago are expired on reception.
+    let past = SystemTime::now().duration_since(UNIX_EPOCH).unwrap() - Duration::from_secs(10);
+    let past = Timestamp::new(past.into(), TimestampId::try_from([7]).unwrap());
+    pub_session
+        .put(ke, "stale")
+        .timestamp(past)
+        .ttl(Duration::from_secs(1))
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert!(sample.is_expired());
+    std::thread::sleep(SLEEP);
+    assert!(filtered.try_recv().is_err());
+
+    // Samples without ttl never expire.
+    pub_session
+        .put(ke, "forever")
+        .timestamp(past)
+        .res()
+        .unwrap();
+    let sample = subscriber.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.ttl, None);
+    assert!(!sample.is_expired());
+    let sample = filtered.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(sample.value.to_string(), "forever");
+}
@@ -0,0 +1,58 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "serde")]
+use std::time::Duration;
+use zenoh::prelude::sync::*;
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Position {
+    x: f64,
+    y: f64,
+    label: String,
+}
+
+#[test]
+fn typed_pubsub() {
+    let session = zenoh::open(Config::default()).res().unwrap();
+    let subscriber = session
+        .declare_subscriber_typed::<Position, _>("test/typed")
+        .res()
+        .unwrap();
+    let publisher = session
+        .declare_publisher_typed::<Position, _>("test/typed")
+        .res()
+        .unwrap();
+    std::thread::sleep(Duration::from_secs(1));
+
+    let position = Position {
+        x: 1.0,
+        y: -2.5,
+        label: "origin".to_string(),
+    };
+    publisher.put(&position).res().unwrap();
+    let sample = subscriber.recv().unwrap();
+    assert_eq!(sample.value, position);
+    assert_eq!(sample.sample.encoding, Encoding::APP_JSON);
+
+    // Values that do not match the expected type are reported as errors.
+    session.put("test/typed", "not json").res().unwrap();
+    assert!(subscriber.recv().is_err());
+
+    session
+        .put("test/typed", serde_json::json!({ "x": 1 }))
+        .res()
+        .unwrap();
+    assert!(subscriber.recv().is_err());
+    assert!(subscriber.try_recv().unwrap().is_none());
+}
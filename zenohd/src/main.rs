@@ -351,6 +351,8 @@ fn test_default_features() {
             " zenoh/transport_unixsock-stream",
             " zenoh/transport_ws",
             // " zenoh/transport_vsock",
+            // " zenoh/transport_ble",
+            // " zenoh/transport_webtransport",
             " zenoh/unstable",
             " zenoh/default",
         )
@@ -378,6 +380,8 @@ fn test_no_default_features() {
             // " zenoh/transport_unixsock-stream",
             // " zenoh/transport_ws",
             // " zenoh/transport_vsock",
+            // " zenoh/transport_ble",
+            // " zenoh/transport_webtransport",
             " zenoh/unstable",
             // " zenoh/default",
         )